@@ -0,0 +1,129 @@
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+/* SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com> */
+
+/* Generated by cbindgen from bindings/c; do not edit. */
+
+#ifndef SYSAND_H
+#define SYSAND_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Version of the C ABI. Incremented on every incompatible change to the
+// functions or types in `sysand.h`.
+#define SYSAND_ABI_VERSION 1
+
+// Result of a sysand C API call. On anything other than `SYSAND_OK`, a
+// description of the error is available via `sysand_last_error_message`.
+typedef enum SysandStatus {
+  SYSAND_STATUS_OK = 0,
+  // A required pointer argument was null or a string was not valid UTF-8
+  SYSAND_STATUS_INVALID_ARGUMENT = 1,
+  // Filesystem error
+  SYSAND_STATUS_IO = 2,
+  // The project is missing or its metadata is invalid
+  SYSAND_STATUS_PROJECT = 3,
+  // The IRI could not be resolved
+  SYSAND_STATUS_RESOLUTION = 4,
+  // Failure while building a KPAR
+  SYSAND_STATUS_BUILD = 5,
+  // Failure while reading or writing the environment
+  SYSAND_STATUS_ENVIRONMENT = 6,
+  // A Rust panic was caught at the API boundary
+  SYSAND_STATUS_PANIC = 7,
+} SysandStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Version of the sysand library as a static NUL-terminated string.
+const char *sysand_version(void);
+
+// Message describing the error of the last failed call on this thread, or
+// null if the last call succeeded. The string is owned by the library and
+// valid until the next sysand call on the same thread.
+const char *sysand_last_error_message(void);
+
+// Release a string returned by the library. Null is ignored.
+//
+// # Safety
+//
+// `s` must be null or a string returned through an out-pointer of this
+// library that has not been freed yet.
+void sysand_string_free(char *s);
+
+// Create a new project in the existing directory `path`.
+// `publisher` and `license` may be null.
+//
+// # Safety
+//
+// All non-null string arguments must be valid NUL-terminated strings.
+enum SysandStatus sysand_init_project(const char *path,
+                                      const char *name,
+                                      const char *publisher,
+                                      const char *version,
+                                      const char *license);
+
+// Create an empty environment at `path`, which must not exist.
+//
+// # Safety
+//
+// `path` must be a valid NUL-terminated string.
+enum SysandStatus sysand_env_create(const char *path);
+
+// Read the information and metadata of the project at `path`. On success,
+// `*out_info` and `*out_meta` receive JSON strings to be freed with
+// `sysand_string_free`.
+//
+// # Safety
+//
+// `path` must be a valid NUL-terminated string; `out_info` and `out_meta`
+// must be valid for writes.
+enum SysandStatus sysand_info_path(const char *path, char **out_info, char **out_meta);
+
+// Resolve `iri` and read the information and metadata of its newest
+// version. `file:` IRIs are relative to `relative_file_root` (may be null).
+// `index_urls` is an array of `index_urls_len` sysand index URLs; if null,
+// no index is consulted. Outputs as in `sysand_info_path`.
+//
+// # Safety
+//
+// All non-null string arguments must be valid NUL-terminated strings;
+// `index_urls` must be null or point to `index_urls_len` such strings;
+// `out_info` and `out_meta` must be valid for writes.
+enum SysandStatus sysand_info_resolve(const char *iri,
+                                      const char *relative_file_root,
+                                      const char *const *index_urls,
+                                      size_t index_urls_len,
+                                      char **out_info,
+                                      char **out_meta);
+
+// Build the project at `project_path` into a KPAR at `output_path`.
+// `compression` may be null to use the default compression method.
+//
+// # Safety
+//
+// All non-null string arguments must be valid NUL-terminated strings.
+enum SysandStatus sysand_build_kpar(const char *project_path,
+                                    const char *output_path,
+                                    const char *compression);
+
+// Install the project at `location` (a project directory or a KPAR file)
+// into the environment at `env_path` as `iri`.
+//
+// # Safety
+//
+// All arguments must be valid NUL-terminated strings.
+enum SysandStatus sysand_env_install_path(const char *env_path,
+                                          const char *iri,
+                                          const char *location);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SYSAND_H */
@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::{
+    cell::RefCell,
+    ffi::{CStr, CString, c_char},
+    panic::{AssertUnwindSafe, catch_unwind},
+};
+
+use sysand_core::utils::format_err;
+
+/// Result of a sysand C API call. On anything other than `SYSAND_OK`, a
+/// description of the error is available via `sysand_last_error_message`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SysandStatus {
+    Ok = 0,
+    /// A required pointer argument was null or a string was not valid UTF-8
+    InvalidArgument = 1,
+    /// Filesystem error
+    Io = 2,
+    /// The project is missing or its metadata is invalid
+    Project = 3,
+    /// The IRI could not be resolved
+    Resolution = 4,
+    /// Failure while building a KPAR
+    Build = 5,
+    /// Failure while reading or writing the environment
+    Environment = 6,
+    /// A Rust panic was caught at the API boundary
+    Panic = 7,
+}
+
+/// Error carried to the C boundary: a status and a message.
+#[derive(Debug)]
+pub(crate) struct CError {
+    pub status: SysandStatus,
+    pub message: String,
+}
+
+impl CError {
+    pub fn new(status: SysandStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn from_err<E: std::error::Error>(status: SysandStatus, err: E) -> Self {
+        Self::new(status, format_err(err))
+    }
+}
+
+pub(crate) trait ResultExt<T> {
+    fn status(self, status: SysandStatus) -> Result<T, CError>;
+}
+
+impl<T, E: std::error::Error> ResultExt<T> for Result<T, E> {
+    fn status(self, status: SysandStatus) -> Result<T, CError> {
+        self.map_err(|e| CError::from_err(status, e))
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: &str) {
+    // Interior NULs would truncate the message on the C side anyway
+    let message = CString::new(message.replace('\0', "\\0")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+/// Run `f`, recording its error (or panic) as the last error of this thread.
+pub(crate) fn ffi_call<F: FnOnce() -> Result<(), CError>>(f: F) -> SysandStatus {
+    LAST_ERROR.with(|e| *e.borrow_mut() = None);
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => SysandStatus::Ok,
+        Ok(Err(err)) => {
+            set_last_error(&err.message);
+            err.status
+        }
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("unknown panic");
+            set_last_error(&format!("sysand panicked: {message}"));
+            SysandStatus::Panic
+        }
+    }
+}
+
+/// Borrow a required C string argument.
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL-terminated string that outlives the
+/// returned reference.
+pub(crate) unsafe fn required_str<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, CError> {
+    if ptr.is_null() {
+        return Err(CError::new(
+            SysandStatus::InvalidArgument,
+            format!("`{name}` must not be null"),
+        ));
+    }
+    unsafe { optional_str(ptr, name) }.map(Option::unwrap)
+}
+
+/// Borrow an optional (nullable) C string argument.
+///
+/// # Safety
+///
+/// Same as [`required_str`].
+pub(crate) unsafe fn optional_str<'a>(
+    ptr: *const c_char,
+    name: &str,
+) -> Result<Option<&'a str>, CError> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map(Some)
+        .map_err(|e| {
+            CError::new(
+                SysandStatus::InvalidArgument,
+                format!("`{name}` is not valid UTF-8: {e}"),
+            )
+        })
+}
+
+/// Hand `value` over to the caller through the out-pointer `out`. The caller
+/// frees it with `sysand_string_free`.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+pub(crate) unsafe fn write_out_string(
+    out: *mut *mut c_char,
+    value: String,
+    name: &str,
+) -> Result<(), CError> {
+    if out.is_null() {
+        return Err(CError::new(
+            SysandStatus::InvalidArgument,
+            format!("`{name}` must not be null"),
+        ));
+    }
+    let value = CString::new(value).map_err(|e| {
+        CError::new(
+            SysandStatus::InvalidArgument,
+            format!("`{name}` would contain a NUL byte: {e}"),
+        )
+    })?;
+    unsafe { *out = value.into_raw() };
+    Ok(())
+}
+
+pub(crate) fn last_error_ptr() -> *const c_char {
+    LAST_ERROR.with(|e| {
+        e.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
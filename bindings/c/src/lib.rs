@@ -0,0 +1,342 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! C ABI for sysand.
+//!
+//! All functions return a [`SysandStatus`]; on failure, a message describing
+//! the error can be retrieved with [`sysand_last_error_message`]. Strings
+//! returned through out-pointers are owned by the caller and must be released
+//! with [`sysand_string_free`]. Project information and metadata are
+//! exchanged as `.project.json`/`.meta.json` formatted JSON strings.
+
+use std::{
+    ffi::{CString, c_char},
+    sync::Arc,
+};
+
+use camino::Utf8PathBuf;
+use sysand_core::{
+    auth::Unauthenticated,
+    build::{KparCompressionMethod, do_build_kpar},
+    commands::{env::do_env_local_dir, init::do_init_local_file},
+    config::Config,
+    env::{
+        OverwritePolicy, WriteEnvironment as _, local_directory::LocalDirectoryEnvironment,
+        utils::clone_project,
+    },
+    info::{do_info, do_info_project},
+    init::InitError,
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
+    project::{
+        ProjectRead,
+        local_kpar::{KparInnerPath, LocalKParProject},
+        local_src::LocalSrcProject,
+        utils::wrapfs,
+    },
+    resolve::{net_utils::HttpClientFactory, standard::standard_resolver},
+    session::shared_runtime,
+};
+
+mod error;
+
+pub use error::SysandStatus;
+use error::{
+    CError, ResultExt as _, ffi_call, last_error_ptr, optional_str, required_str, write_out_string,
+};
+
+/// Version of the C ABI. Incremented on every incompatible change to the
+/// functions or types in `sysand.h`.
+pub const SYSAND_ABI_VERSION: u32 = 1;
+
+fn local_src_project(path: impl Into<Utf8PathBuf>) -> LocalSrcProject {
+    LocalSrcProject {
+        nominal_path: None,
+        project_path: path.into(),
+        expected_checksum: None,
+    }
+}
+
+/// Serialize `info` and `meta` to JSON into the out-pointers.
+///
+/// # Safety
+///
+/// `out_info` and `out_meta` must be null or valid for writes.
+unsafe fn write_info_meta(
+    info: &InterchangeProjectInfoRaw,
+    meta: &InterchangeProjectMetadataRaw,
+    out_info: *mut *mut c_char,
+    out_meta: *mut *mut c_char,
+) -> Result<(), CError> {
+    let info = serde_json::to_string(info).status(SysandStatus::Project)?;
+    let meta = serde_json::to_string(meta).status(SysandStatus::Project)?;
+    unsafe {
+        write_out_string(out_info, info, "out_info")?;
+        if let Err(e) = write_out_string(out_meta, meta, "out_meta") {
+            sysand_string_free(*out_info);
+            *out_info = std::ptr::null_mut();
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// Version of the sysand library as a static NUL-terminated string.
+#[unsafe(no_mangle)]
+pub extern "C" fn sysand_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Message describing the error of the last failed call on this thread, or
+/// null if the last call succeeded. The string is owned by the library and
+/// valid until the next sysand call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn sysand_last_error_message() -> *const c_char {
+    last_error_ptr()
+}
+
+/// Release a string returned by the library. Null is ignored.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned through an out-pointer of this
+/// library that has not been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sysand_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Create a new project in the existing directory `path`.
+/// `publisher` and `license` may be null.
+///
+/// # Safety
+///
+/// All non-null string arguments must be valid NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sysand_init_project(
+    path: *const c_char,
+    name: *const c_char,
+    publisher: *const c_char,
+    version: *const c_char,
+    license: *const c_char,
+) -> SysandStatus {
+    ffi_call(|| {
+        let path = unsafe { required_str(path, "path") }?;
+        let name = unsafe { required_str(name, "name") }?;
+        let publisher = unsafe { optional_str(publisher, "publisher") }?;
+        let version = unsafe { required_str(version, "version") }?;
+        let license = unsafe { optional_str(license, "license") }?;
+
+        do_init_local_file(
+            name.to_owned(),
+            publisher.map(str::to_owned),
+            version.to_owned(),
+            license.map(str::to_owned),
+            Utf8PathBuf::from(path),
+        )
+        .map(|_| ())
+        .map_err(|err| {
+            let status = match err {
+                InitError::SemVerParse(..) | InitError::SPDXLicenseParse(..) => {
+                    SysandStatus::InvalidArgument
+                }
+                InitError::Project(_) => SysandStatus::Project,
+            };
+            CError::from_err(status, err)
+        })
+    })
+}
+
+/// Create an empty environment at `path`, which must not exist.
+///
+/// # Safety
+///
+/// `path` must be a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sysand_env_create(path: *const c_char) -> SysandStatus {
+    ffi_call(|| {
+        let path = unsafe { required_str(path, "path") }?;
+        do_env_local_dir(path)
+            .map(|_| ())
+            .status(SysandStatus::Environment)
+    })
+}
+
+/// Read the information and metadata of the project at `path`. On success,
+/// `*out_info` and `*out_meta` receive JSON strings to be freed with
+/// `sysand_string_free`.
+///
+/// # Safety
+///
+/// `path` must be a valid NUL-terminated string; `out_info` and `out_meta`
+/// must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sysand_info_path(
+    path: *const c_char,
+    out_info: *mut *mut c_char,
+    out_meta: *mut *mut c_char,
+) -> SysandStatus {
+    ffi_call(|| {
+        let path = unsafe { required_str(path, "path") }?;
+        let (info, meta) =
+            do_info_project(&local_src_project(path)).status(SysandStatus::Project)?;
+        unsafe { write_info_meta(&info, &meta, out_info, out_meta) }
+    })
+}
+
+/// Resolve `iri` and read the information and metadata of its newest
+/// version. `file:` IRIs are relative to `relative_file_root` (may be null).
+/// `index_urls` is an array of `index_urls_len` sysand index URLs; if null,
+/// no index is consulted. Outputs as in `sysand_info_path`.
+///
+/// # Safety
+///
+/// All non-null string arguments must be valid NUL-terminated strings;
+/// `index_urls` must be null or point to `index_urls_len` such strings;
+/// `out_info` and `out_meta` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sysand_info_resolve(
+    iri: *const c_char,
+    relative_file_root: *const c_char,
+    index_urls: *const *const c_char,
+    index_urls_len: usize,
+    out_info: *mut *mut c_char,
+    out_meta: *mut *mut c_char,
+) -> SysandStatus {
+    ffi_call(|| {
+        let iri = unsafe { required_str(iri, "iri") }?;
+        let relative_file_root = unsafe { optional_str(relative_file_root, "relative_file_root") }?;
+        let index_urls = if index_urls.is_null() {
+            None
+        } else {
+            let urls = unsafe { std::slice::from_raw_parts(index_urls, index_urls_len) };
+            Some(
+                urls.iter()
+                    .map(|&url| {
+                        let url = unsafe { required_str(url, "index_urls") }?;
+                        url::Url::parse(url).status(SysandStatus::InvalidArgument)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+        };
+
+        // Shared by all calls, so that connections are reused across them
+        let client = HttpClientFactory::shared(&Config::default())
+            .client()
+            .status(SysandStatus::Resolution)?;
+        let runtime = shared_runtime();
+        let resolver = standard_resolver(
+            relative_file_root.map(Utf8PathBuf::from),
+            None,
+            Some(client),
+            index_urls,
+            vec![],
+            runtime,
+            // TODO: Add C support for authentication
+            Arc::new(Unauthenticated {}),
+        )
+        .status(SysandStatus::Resolution)?;
+
+        let (info, meta) = do_info(iri, &resolver).status(SysandStatus::Resolution)?;
+        unsafe { write_info_meta(&info, &meta, out_info, out_meta) }
+    })
+}
+
+/// Build the project at `project_path` into a KPAR at `output_path`.
+/// `compression` may be null to use the default compression method.
+///
+/// # Safety
+///
+/// All non-null string arguments must be valid NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sysand_build_kpar(
+    project_path: *const c_char,
+    output_path: *const c_char,
+    compression: *const c_char,
+) -> SysandStatus {
+    ffi_call(|| {
+        let project_path = unsafe { required_str(project_path, "project_path") }?;
+        let output_path = unsafe { required_str(output_path, "output_path") }?;
+        let compression = match unsafe { optional_str(compression, "compression") }? {
+            Some(compression) => KparCompressionMethod::try_from(compression.to_owned())
+                .status(SysandStatus::InvalidArgument)?,
+            None => KparCompressionMethod::default(),
+        };
+
+        do_build_kpar(
+            &local_src_project(project_path),
+            output_path,
+            compression,
+            true,
+            true,
+        )
+        .map(|_| ())
+        .status(SysandStatus::Build)
+    })
+}
+
+/// Install the project at `location` (a project directory or a KPAR file)
+/// into the environment at `env_path` as `iri`.
+///
+/// # Safety
+///
+/// All arguments must be valid NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sysand_env_install_path(
+    env_path: *const c_char,
+    iri: *const c_char,
+    location: *const c_char,
+) -> SysandStatus {
+    fn install<P: ProjectRead>(
+        env: &mut LocalDirectoryEnvironment,
+        iri: &str,
+        project: &P,
+        location: &str,
+    ) -> Result<(), CError> {
+        let Some(version) = project.version().status(SysandStatus::Project)? else {
+            return Err(CError::new(
+                SysandStatus::Project,
+                format!("project at `{location}` lacks project information"),
+            ));
+        };
+        let checksum = project
+            .checksum_canonical_variant()
+            .status(SysandStatus::Project)?;
+
+        env.put_project(
+            iri,
+            version,
+            Some(checksum),
+            OverwritePolicy::Replace,
+            |to| clone_project(project, to, true).map(|_| ()),
+        )
+        .map(|_| ())
+        .status(SysandStatus::Environment)
+    }
+
+    ffi_call(|| {
+        let env_path = unsafe { required_str(env_path, "env_path") }?;
+        let iri = unsafe { required_str(iri, "iri") }?;
+        let location = unsafe { required_str(location, "location") }?;
+
+        let mut env =
+            LocalDirectoryEnvironment::read(env_path).status(SysandStatus::Environment)?;
+        let metadata = wrapfs::metadata(location).status(SysandStatus::Io)?;
+        if metadata.is_file() {
+            let project = LocalKParProject::new(location, KparInnerPath::Guess, None, None);
+            install(&mut env, iri, &project, location)
+        } else if metadata.is_dir() {
+            install(&mut env, iri, &local_src_project(location), location)
+        } else {
+            Err(CError::new(
+                SysandStatus::Project,
+                format!("unable to find project at `{location}`"),
+            ))
+        }
+    })
+}
+
+#[cfg(test)]
+#[path = "./lib_tests.rs"]
+mod tests;
@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::ffi::{CStr, CString, c_char};
+
+use camino_tempfile::tempdir;
+
+use super::*;
+
+fn c(s: &str) -> CString {
+    CString::new(s).unwrap()
+}
+
+fn last_error() -> String {
+    let ptr = sysand_last_error_message();
+    assert!(!ptr.is_null());
+    unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_owned()
+}
+
+/// Take ownership of a string returned by the library.
+fn take(ptr: *mut c_char) -> String {
+    assert!(!ptr.is_null());
+    let s = unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_owned();
+    unsafe { sysand_string_free(ptr) };
+    s
+}
+
+#[test]
+fn init_info_build_install() {
+    let cwd = tempdir().unwrap();
+    let project = cwd.path().join("project");
+    let env = cwd.path().join("env");
+    let kpar = cwd.path().join("project.kpar");
+    std::fs::create_dir(&project).unwrap();
+
+    let status = unsafe {
+        sysand_init_project(
+            c(project.as_str()).as_ptr(),
+            c("init_info_build").as_ptr(),
+            std::ptr::null(),
+            c("1.2.3").as_ptr(),
+            c("MIT").as_ptr(),
+        )
+    };
+    assert_eq!(status, SysandStatus::Ok);
+    assert!(sysand_last_error_message().is_null());
+
+    let mut info = std::ptr::null_mut();
+    let mut meta = std::ptr::null_mut();
+    let status = unsafe { sysand_info_path(c(project.as_str()).as_ptr(), &mut info, &mut meta) };
+    assert_eq!(status, SysandStatus::Ok);
+    let info: serde_json::Value = serde_json::from_str(&take(info)).unwrap();
+    assert_eq!(info["name"], "init_info_build");
+    assert_eq!(info["version"], "1.2.3");
+    let meta: serde_json::Value = serde_json::from_str(&take(meta)).unwrap();
+    assert!(meta["created"].is_string());
+
+    let status = unsafe {
+        sysand_build_kpar(
+            c(project.as_str()).as_ptr(),
+            c(kpar.as_str()).as_ptr(),
+            std::ptr::null(),
+        )
+    };
+    assert_eq!(status, SysandStatus::Ok);
+    assert!(kpar.is_file());
+
+    assert_eq!(
+        unsafe { sysand_env_create(c(env.as_str()).as_ptr()) },
+        SysandStatus::Ok
+    );
+    let status = unsafe {
+        sysand_env_install_path(
+            c(env.as_str()).as_ptr(),
+            c("urn:kpar:init_info_build").as_ptr(),
+            c(kpar.as_str()).as_ptr(),
+        )
+    };
+    assert_eq!(status, SysandStatus::Ok, "{}", last_error());
+}
+
+#[test]
+fn errors_are_reported() {
+    let status = unsafe {
+        sysand_init_project(
+            std::ptr::null(),
+            c("x").as_ptr(),
+            std::ptr::null(),
+            c("1.0.0").as_ptr(),
+            std::ptr::null(),
+        )
+    };
+    assert_eq!(status, SysandStatus::InvalidArgument);
+    assert!(last_error().contains("`path` must not be null"));
+
+    let cwd = tempdir().unwrap();
+    let status = unsafe {
+        sysand_init_project(
+            c(cwd.path().as_str()).as_ptr(),
+            c("x").as_ptr(),
+            std::ptr::null(),
+            c("not a version").as_ptr(),
+            std::ptr::null(),
+        )
+    };
+    assert_eq!(status, SysandStatus::InvalidArgument);
+
+    let mut info = std::ptr::null_mut();
+    let mut meta = std::ptr::null_mut();
+    let status = unsafe {
+        sysand_info_path(
+            c(cwd.path().join("missing").as_str()).as_ptr(),
+            &mut info,
+            &mut meta,
+        )
+    };
+    assert_eq!(status, SysandStatus::Project);
+    assert!(info.is_null() && meta.is_null());
+}
+
+#[test]
+fn version_is_static() {
+    let version = unsafe { CStr::from_ptr(sysand_version()) };
+    assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+}
@@ -0,0 +1,59 @@
+/* SPDX-License-Identifier: MIT OR Apache-2.0 */
+/* SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com> */
+
+/* Links against the sysand C library and exercises the happy path of the
+ * API: create a project, read it back and build a KPAR.
+ *
+ * Usage: smoke <empty directory> */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "sysand.h"
+
+static int check(SysandStatus status, const char *what) {
+  if (status != SYSAND_STATUS_OK) {
+    fprintf(stderr, "%s failed (%d): %s\n", what, (int)status,
+            sysand_last_error_message());
+    return 1;
+  }
+  return 0;
+}
+
+int main(int argc, char **argv) {
+  char kpar[4096];
+  char *info = NULL;
+  char *meta = NULL;
+
+  if (argc != 2) {
+    fprintf(stderr, "usage: %s <empty directory>\n", argv[0]);
+    return 2;
+  }
+  snprintf(kpar, sizeof kpar, "%s/smoke.kpar", argv[1]);
+
+  printf("sysand %s (ABI %d)\n", sysand_version(), SYSAND_ABI_VERSION);
+
+  if (check(sysand_init_project(argv[1], "smoke", NULL, "0.1.0", "MIT"),
+            "sysand_init_project") ||
+      check(sysand_info_path(argv[1], &info, &meta), "sysand_info_path") ||
+      check(sysand_build_kpar(argv[1], kpar, NULL), "sysand_build_kpar")) {
+    return 1;
+  }
+
+  printf("%s\n%s\n", info, meta);
+  if (strstr(info, "\"name\":\"smoke\"") == NULL) {
+    fprintf(stderr, "unexpected project information\n");
+    return 1;
+  }
+  sysand_string_free(info);
+  sysand_string_free(meta);
+
+  if (sysand_init_project(NULL, "smoke", NULL, "0.1.0", NULL) !=
+          SYSAND_STATUS_INVALID_ARGUMENT ||
+      sysand_last_error_message() == NULL) {
+    fprintf(stderr, "expected an invalid argument error\n");
+    return 1;
+  }
+
+  return 0;
+}
@@ -127,6 +127,28 @@ public class BasicTest {
         }
     }
 
+    @Test
+    public void testProjectBuildProgress() {
+        try {
+            java.nio.file.Path tempDir = java.nio.file.Files.createTempDirectory("sysand-test-build-progress");
+            com.sensmetry.sysand.Sysand.init("test_build_progress", "a", "1.2.3", "MIT", tempDir);
+            Files.writeString(tempDir.resolve("a.sysml"), "package P;");
+            java.util.LinkedHashMap<String, String> index = new java.util.LinkedHashMap<>();
+            index.put("P", "a.sysml");
+            com.sensmetry.sysand.Sysand.setProjectIndex(tempDir, index);
+
+            java.util.List<String> events = new java.util.ArrayList<>();
+            com.sensmetry.sysand.Sysand.buildProject(tempDir.resolve("sysand-test-build.kpar"), tempDir,
+                    CompressionMethod.DEFLATED,
+                    (phase, current, total, message) -> events.add(phase + " " + current + "/" + total + " " + message));
+            assertEquals(java.util.List.of("build 1/1 a.sysml"), events);
+        } catch (java.io.IOException e) {
+            fail("Failed during temporary directory operations: " + e.getMessage());
+        } catch (com.sensmetry.sysand.exceptions.SysandException e) {
+            fail("Failed to build project: " + e.getMessage());
+        }
+    }
+
     @Test
     public void testHttpInfo() {
         // TODO: Find a good mock server so that we can test this.
@@ -213,6 +235,37 @@ public class BasicTest {
         }
     }
 
+    @Test
+    public void testWorkspaceProjects() {
+        try {
+            java.nio.file.Path tempDir = java.nio.file.Files.createTempDirectory("sysand-test-workspace-projects");
+
+            java.nio.file.Path projA = tempDir.resolve("projA");
+            java.nio.file.Path projB = tempDir.resolve("projB");
+            Files.createDirectories(projA);
+            Files.createDirectories(projB);
+            com.sensmetry.sysand.Sysand.init("projA", "a", "1.0.0", null, projA);
+            com.sensmetry.sysand.Sysand.init("projB", "a", "1.0.0", null, projB);
+
+            writeWorkspaceJson(tempDir, "projA", "projB");
+
+            com.sensmetry.sysand.model.WorkspaceProject[] projects =
+                com.sensmetry.sysand.Sysand.workspaceProjects(tempDir);
+            assertEquals(2, projects.length);
+
+            java.util.Arrays.sort(projects, java.util.Comparator.comparing(
+                com.sensmetry.sysand.model.WorkspaceProject::getPath));
+            assertTrue(projects[0].getPath().endsWith("projA"), "First path should end with projA: " + projects[0].getPath());
+            assertTrue(java.nio.file.Paths.get(projects[0].getPath()).isAbsolute(), "Paths should be absolute");
+            assertArrayEquals(new String[] {"urn:test:projA"}, projects[0].getIris());
+            assertArrayEquals(new String[] {"urn:test:projB"}, projects[1].getIris());
+        } catch (java.io.IOException e) {
+            fail("Failed: " + e.getMessage());
+        } catch (com.sensmetry.sysand.exceptions.SysandException e) {
+            fail("Failed: " + e.getMessage());
+        }
+    }
+
     @Test
     public void testSetWorkspaceProjectIndexes() {
         try {
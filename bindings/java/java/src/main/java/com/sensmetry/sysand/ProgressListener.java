@@ -0,0 +1,18 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+package com.sensmetry.sysand;
+
+/**
+ * Receives progress of long-running operations.
+ */
+@FunctionalInterface
+public interface ProgressListener {
+    /**
+     * @param phase   One of {@code build}, {@code install} and {@code download}.
+     * @param current Units done so far: files, sync actions or bytes.
+     * @param total   Total units, or -1 if not known.
+     * @param message What is being worked on, e.g. a file path.
+     */
+    void onProgress(String phase, long current, long total, String message);
+}
@@ -166,6 +166,26 @@ public class Sysand {
         return workspaceProjectPaths(workspacePath.toString());
     }
 
+    /**
+     * Get all projects in a workspace together with their IRIs.
+     *
+     * @param workspacePath The path to the workspace directory containing {@code .workspace.json}.
+     * @return An array of workspace projects with absolute paths.
+     */
+    private static native com.sensmetry.sysand.model.WorkspaceProject[] workspaceProjects(String workspacePath)
+            throws com.sensmetry.sysand.exceptions.SysandException;
+
+    /**
+     * Get all projects in a workspace together with their IRIs.
+     *
+     * @param workspacePath The path to the workspace directory containing {@code .workspace.json}.
+     * @return An array of workspace projects with absolute paths.
+     */
+    public static com.sensmetry.sysand.model.WorkspaceProject[] workspaceProjects(java.nio.file.Path workspacePath)
+            throws com.sensmetry.sysand.exceptions.SysandException {
+        return workspaceProjects(workspacePath.toString());
+    }
+
     /**
      * Set the index field in a project's {@code .meta.json} file,
      * overwriting any existing index contents.
@@ -239,7 +259,8 @@ public class Sysand {
      * @param outputPath  The path to the output file.
      * @param projectPath The path to the project.
      */
-    private static native void buildProject(String outputPath, String projectPath, String compression)
+    private static native void buildProject(String outputPath, String projectPath, String compression,
+            ProgressListener progress)
             throws com.sensmetry.sysand.exceptions.SysandException;
 
     /**
@@ -251,7 +272,22 @@ public class Sysand {
      */
     public static void buildProject(java.nio.file.Path outputPath, java.nio.file.Path projectPath, com.sensmetry.sysand.model.CompressionMethod compression)
             throws com.sensmetry.sysand.exceptions.SysandException {
-        buildProject(outputPath.toString(), projectPath.toString(), compression.toString());
+        buildProject(outputPath.toString(), projectPath.toString(), compression.toString(), null);
+    }
+
+    /**
+     * Build Model Project Interchange file (.kpar) from the project at the given
+     * path, reporting each file added to {@code progress}.
+     *
+     * @param outputPath  The path to the output file.
+     * @param projectPath The path to the project.
+     * @param progress    Called on the building thread. An exception it throws
+     *                    is rethrown once the build stops.
+     */
+    public static void buildProject(java.nio.file.Path outputPath, java.nio.file.Path projectPath,
+            com.sensmetry.sysand.model.CompressionMethod compression, ProgressListener progress)
+            throws com.sensmetry.sysand.exceptions.SysandException {
+        buildProject(outputPath.toString(), projectPath.toString(), compression.toString(), progress);
     }
 
     /**
@@ -6,4 +6,16 @@ package com.sensmetry.sysand.model;
 // TODO(Java 17+): use sealed classes and interfaces 
 // This is meant to be a sealed interface and Sysand only supports
 // usage classes from this package.
-public interface InterchangeProjectUsage {}
+public interface InterchangeProjectUsage {
+
+    /**
+     * @return IRI of the used project.
+     */
+    String getResource();
+
+    /**
+     * @return Version constraint of the usage, or {@code null} if any
+     *         version is accepted.
+     */
+    String getVersionConstraint();
+}
@@ -7,12 +7,26 @@ public class InterchangeProjectUsageResource implements InterchangeProjectUsage
 
     private String resource;
     private String versionConstraint;
+    private String when;
+    private String hash;
 
     public InterchangeProjectUsageResource(String resource, String versionConstraint) {
+        this(resource, versionConstraint, null);
+    }
+
+    public InterchangeProjectUsageResource(String resource, String versionConstraint, String when) {
+        this(resource, versionConstraint, when, null);
+    }
+
+    public InterchangeProjectUsageResource(
+            String resource, String versionConstraint, String when, String hash) {
         this.resource = resource;
         this.versionConstraint = versionConstraint;
+        this.when = when;
+        this.hash = hash;
     }
 
+    @Override
     public String getResource() {
         return resource;
     }
@@ -21,6 +35,7 @@ public class InterchangeProjectUsageResource implements InterchangeProjectUsage
         this.resource = resource;
     }
 
+    @Override
     public String getVersionConstraint() {
         return versionConstraint;
     }
@@ -28,4 +43,20 @@ public class InterchangeProjectUsageResource implements InterchangeProjectUsage
     public void setVersionConstraint(String versionConstraint) {
         this.versionConstraint = versionConstraint;
     }
+
+    public String getWhen() {
+        return when;
+    }
+
+    public void setWhen(String when) {
+        this.when = when;
+    }
+
+    public String getHash() {
+        return hash;
+    }
+
+    public void setHash(String hash) {
+        this.hash = hash;
+    }
 }
@@ -0,0 +1,39 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+package com.sensmetry.sysand.model;
+
+public class WorkspaceProject {
+
+    private String path;
+    private String[] iris;
+
+    public WorkspaceProject(String path, String[] iris) {
+        this.path = path;
+        this.iris = iris;
+    }
+
+    /**
+     * @return Absolute path to the project directory.
+     */
+    public String getPath() {
+        return path;
+    }
+
+    public void setPath(String path) {
+        this.path = path;
+    }
+
+    /**
+     * @return IRIs under which the project is known in the workspace.
+     */
+    public String[] getIris() {
+        // We need to clone the array to prevent the caller from modifying the
+        // internal state.
+        return iris.clone();
+    }
+
+    public void setIris(String[] iris) {
+        this.iris = iris.clone();
+    }
+}
@@ -203,9 +203,13 @@ fn get_usage_array_field<'local>(
                 let resource = get_string_field(env, &elem, "resource")?;
                 let version_constraint =
                     get_nullable_string_field(env, &elem, "versionConstraint")?;
+                let when = get_nullable_string_field(env, &elem, "when")?;
+                let hash = get_nullable_string_field(env, &elem, "hash")?;
                 result.push(InterchangeProjectUsageRaw::Resource {
                     resource,
                     version_constraint,
+                    when,
+                    hash,
                 });
             }
             Ok(false) => {
@@ -350,6 +354,7 @@ pub(crate) fn java_metadata_to_raw<'local>(
         includes_derived,
         includes_implied,
         checksum,
+        variants: IndexMap::new(),
     })
 }
 
@@ -369,6 +374,9 @@ pub(crate) const INTERCHANGE_PROJECT_CHECKSUM_CLASS: &str =
     "com/sensmetry/sysand/model/InterchangeProjectChecksum";
 pub(crate) const INTERCHANGE_PROJECT_CHECKSUM_CLASS_CONSTRUCTOR: &str =
     "(Ljava/lang/String;Ljava/lang/String;)V";
+pub(crate) const WORKSPACE_PROJECT_CLASS: &str = "com/sensmetry/sysand/model/WorkspaceProject";
+pub(crate) const WORKSPACE_PROJECT_CLASS_CONSTRUCTOR: &str =
+    "(Ljava/lang/String;[Ljava/lang/String;)V";
 
 pub(crate) trait ToJObject {
     /// `None` return = exception thrown. Parent must return
@@ -514,7 +522,9 @@ impl ToJObject for InterchangeProjectChecksum {
         ) {
             Ok(o) => Some(o),
             Err(e) => {
-                env.throw_runtime_exception(format!("Failed to create LinkedHashMap: {e}"));
+                env.throw_runtime_exception(format!(
+                    "Failed to create InterchangeProjectChecksum: {e}"
+                ));
                 None
             }
         }
@@ -547,17 +557,28 @@ impl ToJObject for InterchangeProjectUsageRaw {
             InterchangeProjectUsageRaw::Resource {
                 resource,
                 version_constraint,
+                when,
+                hash,
             } => {
                 let resource = resource.to_jobject(env)?;
                 let version_constraint = version_constraint.to_jobject(env)?;
+                let when = when.to_jobject(env)?;
+                let hash = hash.to_jobject(env)?;
                 match env.new_object(
                     INTERCHANGE_PROJECT_USAGE_RESOURCE_CLASS,
-                    "(Ljava/lang/String;Ljava/lang/String;)V",
-                    &[JValue::from(&resource), JValue::from(&version_constraint)],
+                    "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;)V",
+                    &[
+                        JValue::from(&resource),
+                        JValue::from(&version_constraint),
+                        JValue::from(&when),
+                        JValue::from(&hash),
+                    ],
                 ) {
                     Ok(o) => Some(o),
                     Err(e) => {
-                        env.throw_runtime_exception(format!("Failed to create LinkedHashMap: {e}"));
+                        env.throw_runtime_exception(format!(
+                            "Failed to create InterchangeProjectUsageResource: {e}"
+                        ));
                         None
                     }
                 }
@@ -674,6 +695,62 @@ impl ToJObject for InterchangeProjectMetadataRaw {
     }
 }
 
+/// Workspace project with its path resolved against the workspace root
+pub(crate) struct WorkspaceProject {
+    pub path: String,
+    pub iris: Vec<String>,
+}
+
+impl ToJObject for WorkspaceProject {
+    fn to_jobject<'local>(&self, env: &mut JNIEnv<'local>) -> Option<JObject<'local>> {
+        let path = self.path.to_jobject(env)?;
+        let iris = self.iris.to_jobject(env)?;
+        match env.new_object(
+            WORKSPACE_PROJECT_CLASS,
+            WORKSPACE_PROJECT_CLASS_CONSTRUCTOR,
+            &[JValue::from(&path), JValue::from(&iris)],
+        ) {
+            Ok(o) => Some(o),
+            Err(e) => {
+                env.throw_runtime_exception(format!("Failed to create WorkspaceProject: {e}"));
+                None
+            }
+        }
+    }
+}
+
+impl ToJObjectArray for [WorkspaceProject] {
+    fn to_jobject_array<'local>(&self, env: &mut JNIEnv<'local>) -> Option<JObjectArray<'local>> {
+        let mut array = match env.new_object_array(
+            self.len()
+                .try_into()
+                .expect("Failed to convert length to i32"),
+            WORKSPACE_PROJECT_CLASS,
+            JObject::null(),
+        ) {
+            Ok(a) => a,
+            Err(e) => {
+                env.throw_runtime_exception(format!("Failed to create WorkspaceProject[]: {e}"));
+                return None;
+            }
+        };
+        for (i, value) in self.iter().enumerate() {
+            let index: i32 = i.try_into().expect("Failed to convert index to i32");
+            let value_object = value.to_jobject(env)?;
+            match env.set_object_array_element(&mut array, index, value_object) {
+                Ok(_) => (),
+                Err(e) => {
+                    env.throw_runtime_exception(format!(
+                        "Failed to set WorkspaceProject[] element: {e}"
+                    ));
+                    return None;
+                }
+            };
+        }
+        Some(array)
+    }
+}
+
 pub(crate) fn java_map_to_index_map<'local>(
     env: &mut JNIEnv<'local>,
     map_obj: &JObject<'local>,
@@ -1,34 +1,34 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
-use std::sync::Arc;
-
 use camino::Utf8PathBuf;
 use jni::{
     JNIEnv,
     errors::Error,
-    objects::{JClass, JObject, JObjectArray, JString},
+    objects::{JClass, JObject, JObjectArray, JString, JValue},
 };
 use sysand_core::{
-    auth::Unauthenticated,
     build::{KParBuildError, KparCompressionMethod},
     commands,
+    config::Config,
+    context::ProjectContext,
     env::{DEFAULT_ENV_NAME, local_directory::LocalWriteError},
-    info::InfoError,
     init::InitError,
+    progress::{self, ProgressEvent},
     project::{
         ProjectMut,
         local_src::{LocalSrcError, LocalSrcProject},
         utils::wrapfs,
     },
-    resolve::{net_utils::create_reqwest_client, standard::standard_resolver},
+    session::Session,
     utils::format_err,
     workspace::Workspace,
 };
 
 use crate::{
     conversion::{
-        ToJObject, ToJObjectArray, java_info_to_raw, java_map_to_index_map, java_metadata_to_raw,
+        ToJObject, ToJObjectArray, WorkspaceProject, java_info_to_raw, java_map_to_index_map,
+        java_metadata_to_raw,
     },
     exceptions::{ExceptionKind, JniExt, StdlibExceptionKind},
 };
@@ -172,6 +172,11 @@ pub extern "system" fn Java_com_sensmetry_sysand_Sysand_env<'local>(
                     LocalWriteError::MissingMeta | LocalWriteError::MissingInfoMeta => {
                         env.throw_exception(ExceptionKind::SysandException, e)
                     }
+                    LocalWriteError::Archive(_) => env.throw_exception(ExceptionKind::IOError, e),
+                    LocalWriteError::Encryption(_) => {
+                        env.throw_exception(ExceptionKind::IOError, e)
+                    }
+                    LocalWriteError::ReadOnly(_) => env.throw_exception(ExceptionKind::IOError, e),
                 },
             }
         }
@@ -214,31 +219,6 @@ pub extern "system" fn Java_com_sensmetry_sysand_Sysand_info<'local>(
     let Some(uri) = env.get_str(&uri, "uri") else {
         return JObject::default();
     };
-    let client = match create_reqwest_client() {
-        Ok(c) => c,
-        Err(e) => {
-            env.throw_exception(ExceptionKind::SysandException, format_err(e));
-            return JObject::default();
-        }
-    };
-
-    let runtime = {
-        let r = match tokio::runtime::Builder::new_current_thread()
-            .enable_all()
-            .build()
-        {
-            Ok(r) => r,
-            Err(e) => {
-                env.throw_exception(
-                    ExceptionKind::IOError,
-                    format!("Failed to build tokio runtime: {e}"),
-                );
-                return JObject::default();
-            }
-        };
-        Arc::new(r)
-    };
-
     let Some(relative_file_root) = env.get_str(&relative_file_root, "relativeFileRoot") else {
         return JObject::default();
     };
@@ -261,33 +241,23 @@ pub extern "system" fn Java_com_sensmetry_sysand_Sysand_info<'local>(
         }
     };
 
-    let combined_resolver = match standard_resolver(
-        Some(Utf8PathBuf::from(relative_file_root)),
-        None,
-        Some(client),
-        index_base_url.map(|x| vec![x]),
-        runtime,
-        // FIXME: Add Java support for authentication
-        Arc::new(Unauthenticated {}),
+    let session = match Session::new(
+        Config::default(),
+        ProjectContext {
+            current_directory: Utf8PathBuf::from(relative_file_root),
+            ..Default::default()
+        },
     ) {
-        Ok(resolver) => resolver,
+        Ok(session) => session.with_index_urls(index_base_url.map(|x| vec![x])),
         Err(error) => {
-            env.throw_exception(
-                ExceptionKind::ResolutionError,
-                format!("Failed to discover index endpoints: {}", format_err(error)),
-            );
+            env.throw_exception(ExceptionKind::SysandException, format_err(error));
             return JObject::default();
         }
     };
 
-    let info_meta = match commands::info::do_info(&uri, &combined_resolver) {
+    let info_meta = match session.info(&uri) {
         Ok(info_meta) => info_meta,
-        Err(
-            e @ (InfoError::NoSemanticVersionsFound(_)
-            | InfoError::NoResolve(..)
-            | InfoError::UnsupportedIri(..)
-            | InfoError::Resolution(_)),
-        ) => {
+        Err(e) => {
             env.throw_exception(ExceptionKind::ResolutionError, format_err(e));
             return JObject::default();
         }
@@ -320,6 +290,33 @@ pub extern "system" fn Java_com_sensmetry_sysand_Sysand_workspaceProjectPaths<'l
     paths.to_jobject_array(&mut env).unwrap_or_default()
 }
 
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_sensmetry_sysand_Sysand_workspaceProjects<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    workspace_path: JString<'local>,
+) -> JObjectArray<'local> {
+    let Some(workspace_path) = env.get_str(&workspace_path, "workspacePath") else {
+        return JObjectArray::default();
+    };
+    let workspace = match Workspace::new(workspace_path.into()) {
+        Ok(w) => w,
+        Err(e) => {
+            env.throw_exception(ExceptionKind::InvalidWorkspace, format_err(e));
+            return JObjectArray::default();
+        }
+    };
+    let projects: Vec<WorkspaceProject> = workspace
+        .projects()
+        .iter()
+        .map(|p| WorkspaceProject {
+            path: workspace.root_path().join(&p.path).into_string(),
+            iris: p.iris.iter().map(|iri| iri.to_string()).collect(),
+        })
+        .collect();
+    projects.to_jobject_array(&mut env).unwrap_or_default()
+}
+
 #[unsafe(no_mangle)]
 pub extern "system" fn Java_com_sensmetry_sysand_Sysand_setProjectIndex<'local>(
     mut env: JNIEnv<'local>,
@@ -469,6 +466,17 @@ fn handle_build_error(env: &mut JNIEnv<'_>, error: KParBuildError<LocalSrcError>
         KParBuildError::MissingIndexSymbol(_, _) => {
             env.throw_exception(ExceptionKind::InvalidValue, e)
         }
+        KParBuildError::PostProcess { .. } => {
+            env.throw_exception(ExceptionKind::SysandException, e);
+        }
+        KParBuildError::InvalidGlob(..) | KParBuildError::MissingVariantPath { .. } => {
+            env.throw_exception(ExceptionKind::InvalidValue, e)
+        }
+        KParBuildError::IncludeWithoutRoot
+        | KParBuildError::Dirty { .. }
+        | KParBuildError::NotReproducible { .. } => {
+            env.throw_exception(ExceptionKind::SysandException, e);
+        }
     }
 }
 
@@ -485,6 +493,55 @@ fn compression_from_java_string(
     }
 }
 
+/// Run `f`, calling `ProgressListener.onProgress` of `listener` for each
+/// progress event, unless `listener` is null. Returns `None` if a Java
+/// exception is pending afterwards, e.g. one thrown by the listener
+fn with_progress_listener<R>(
+    env: &mut JNIEnv<'_>,
+    listener: &JObject<'_>,
+    f: impl FnOnce() -> R,
+) -> Option<R> {
+    if listener.is_null() {
+        return Some(f());
+    }
+    let (vm, listener) = match (env.get_java_vm(), env.new_global_ref(listener)) {
+        (Ok(vm), Ok(listener)) => (vm, listener),
+        (Err(e), _) | (_, Err(e)) => {
+            env.throw_runtime_exception(format!("Failed to set up progress listener: {e}"));
+            return None;
+        }
+    };
+    let result = progress::with_listener(
+        move |e: &ProgressEvent| {
+            let Ok(mut env) = vm.attach_current_thread() else {
+                return;
+            };
+            // Stop calling the listener once it has thrown
+            if env.exception_check().unwrap_or(true) {
+                return;
+            }
+            let (Ok(phase), Ok(message)) =
+                (env.new_string(e.phase.as_str()), env.new_string(&e.message))
+            else {
+                return;
+            };
+            let _ = env.call_method(
+                &listener,
+                "onProgress",
+                "(Ljava/lang/String;JJLjava/lang/String;)V",
+                &[
+                    JValue::Object(&phase),
+                    JValue::Long(e.current as i64),
+                    JValue::Long(e.total.map_or(-1, |t| t as i64)),
+                    JValue::Object(&message),
+                ],
+            );
+        },
+        f,
+    );
+    (!env.exception_check().unwrap_or(true)).then_some(result)
+}
+
 #[unsafe(no_mangle)]
 pub extern "system" fn Java_com_sensmetry_sysand_Sysand_buildProject<'local>(
     mut env: JNIEnv<'local>,
@@ -492,6 +549,7 @@ pub extern "system" fn Java_com_sensmetry_sysand_Sysand_buildProject<'local>(
     output_path: JString<'local>,
     project_path: JString<'local>,
     compression: JString<'local>,
+    progress: JObject<'local>,
 ) {
     let Some(output_path) = env.get_str(&output_path, "outputPath") else {
         return;
@@ -510,16 +568,20 @@ pub extern "system" fn Java_com_sensmetry_sysand_Sysand_buildProject<'local>(
     let Some(compression) = compression_from_java_string(&mut env, compression) else {
         return;
     };
-    let command_result = sysand_core::commands::build::do_build_kpar(
-        &project,
-        &output_path,
-        compression,
-        // Currently keeping index updating disabled, since users can set their own index,
-        // and flipping this to true would overwrite that potentially custom index.
-        // TODO: add this as argument
-        false,
-        true,
-    );
+    let Some(command_result) = with_progress_listener(&mut env, &progress, || {
+        sysand_core::commands::build::do_build_kpar(
+            &project,
+            &output_path,
+            compression,
+            // Currently keeping index updating disabled, since users can set their own index,
+            // and flipping this to true would overwrite that potentially custom index.
+            // TODO: add this as argument
+            false,
+            true,
+        )
+    }) else {
+        return;
+    };
     match command_result {
         Ok(_) => {}
         Err(error) => handle_build_error(&mut env, error),
@@ -564,12 +626,13 @@ pub extern "system" fn Java_com_sensmetry_sysand_Sysand_buildWorkspace<'local>(
     let command_result = sysand_core::commands::build::do_build_workspace_kpars(
         &workspace,
         &output_path,
-        compression,
+        compression.into(),
         // Currently keeping index updating disabled, since users can set their own index,
         // and flipping this to true would overwrite that potentially custom index.
         // TODO: add this as argument
         false,
         true,
+        &sysand_core::commands::build::KparManifest::default(),
     );
     match command_result {
         Ok(_) => {}
@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+import { IndexStub } from "../../testing/index_stub.js";
+
+let sysand;
+let index;
+beforeAll(async function () {
+  if (!sysand) {
+    sysand = await import("sysand");
+  }
+  sysand.init_logger();
+  sysand.ensure_debug_hook();
+});
+
+beforeEach(async function () {
+  sysand.clear_local_storage("sysand_stub_storage/");
+  index = new IndexStub(sysand.index_project_path, {
+    passthrough: false,
+  }).install(window);
+});
+
+afterEach(async function () {
+  index.uninstall();
+});
+
+it("resolves project info from the index stub", async function () {
+  index
+    .addProject("pkg:sysand/acme/lib", "1.0.0", { info: { name: "lib" } })
+    .addProject("pkg:sysand/acme/lib", "1.1.0", { info: { name: "lib" } })
+    .addProject("pkg:sysand/acme/lib", "2.0.0", { status: "yanked" });
+
+  const { info } = await sysand.do_info_js("pkg:sysand/acme/lib", [index.url]);
+
+  expect(info.name).toBe("lib");
+  expect(info.version).toBe("1.1.0");
+  expect(index.requests).toContain(
+    index.url + "acme/lib/1.1.0/.project.json",
+  );
+});
+
+it("follows the index root of the index stub", async function () {
+  index.setIndexRoot("root/").addProject("urn:kpar:lib", "1.0.0");
+
+  const { info } = await sysand.do_info_js("urn:kpar:lib", [
+    "https://index.sysand.test/",
+  ]);
+
+  expect(info.version).toBe("1.0.0");
+});
+
+it("locks against the index stub", async function () {
+  index
+    .addProject("pkg:sysand/acme/lib", "1.0.0", {
+      info: {
+        usage: [{ resource: "pkg:sysand/acme/dep", versionConstraint: "^1" }],
+      },
+    })
+    .addProject("pkg:sysand/acme/dep", "1.2.0");
+  sysand.do_init_js_local_storage(
+    "app",
+    undefined,
+    "0.1.0",
+    "sysand_stub_storage",
+    "/",
+  );
+  const project = JSON.parse(
+    window.localStorage.getItem("sysand_stub_storage/.project.json"),
+  );
+  project.usage = [{ resource: "pkg:sysand/acme/lib" }];
+  window.localStorage.setItem(
+    "sysand_stub_storage/.project.json",
+    JSON.stringify(project),
+  );
+
+  const lock = await sysand.do_lock_js("sysand_stub_storage", "/", [
+    index.url,
+  ]);
+
+  expect(lock).toContain(
+    `index_kpar = "${index.url}acme/dep/1.2.0/project.kpar"`,
+  );
+});
+
+it("rejects unexpected requests without passthrough", async function () {
+  await expectAsync(
+    sysand.do_info_js("https://example.com/lib", []),
+  ).toBeRejected();
+});
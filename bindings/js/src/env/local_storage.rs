@@ -2,7 +2,9 @@
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
 use sysand_core::{
-    env::{ProjectChecksumResult, PutProjectError, ReadEnvironment, WriteEnvironment},
+    env::{
+        OverwritePolicy, ProjectChecksumResult, PutProjectError, ReadEnvironment, WriteEnvironment,
+    },
     project::ProjectChecksum,
     utils::sha256_lowercase_hex,
 };
@@ -143,6 +145,7 @@ impl WriteEnvironment for LocalBrowserStorageEnvironment {
         uri: S,
         version: T,
         _checksum: Option<ProjectChecksum>,
+        overwrite: OverwritePolicy,
         write_project: F,
     ) -> Result<Self::InterchangeProjectMut, sysand_core::env::PutProjectError<Self::WriteError, E>>
     where
@@ -154,22 +157,28 @@ impl WriteEnvironment for LocalBrowserStorageEnvironment {
             expected_checksum: None,
         };
 
-        // TODO: For production JS-version this should be made more robust
-        write_project(&mut project).map_err(sysand_core::env::PutProjectError::Callback)?;
-
         let mut current_versions = self
             .vfs
             .read_string(self.versions_path(&uri))
             .map_err(Error::LocalStorage)
             .map_err(PutProjectError::Write)?;
 
-        let mut found = false;
-        for current_version in current_versions.lines() {
-            if current_version == version.as_ref() {
-                found = true;
-            }
+        let found = current_versions
+            .lines()
+            .any(|current_version| current_version == version.as_ref());
+        // Checksums are not stored, so installed versions are never identical
+        let installed = if found {
+            ProjectChecksumResult::ChecksumNotPresent
+        } else {
+            ProjectChecksumResult::VersionNotFound
+        };
+        if !overwrite.should_write(uri.as_ref(), version.as_ref(), installed)? {
+            return Ok(project);
         }
 
+        // TODO: For production JS-version this should be made more robust
+        write_project(&mut project).map_err(sysand_core::env::PutProjectError::Callback)?;
+
         if !found {
             current_versions.push_str(&format!("\n{}", version.as_ref()));
         }
@@ -219,4 +219,23 @@ impl ProjectMut for ProjectLocalBrowserStorage {
 
         Ok(())
     }
+
+    fn move_source<P: AsRef<Utf8UnixPath>, Q: AsRef<Utf8UnixPath>>(
+        &mut self,
+        from: P,
+        to: Q,
+    ) -> Result<(), Self::Error> {
+        let from_path = self.root_path.join(&from);
+        let to_path = self.root_path.join(&to);
+
+        if self.vfs.exists(&to_path)? {
+            return Err(Error::AlreadyExists(to_path.as_str().into()));
+        }
+
+        let contents = self.vfs.read_string(&from_path)?;
+        self.vfs.write_string(&to_path, contents)?;
+        self.vfs.delete(&from_path)?;
+
+        Ok(())
+    }
 }
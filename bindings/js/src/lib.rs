@@ -8,7 +8,10 @@ use sysand_core::{commands::init::do_init, utils::format_err};
 
 pub mod env;
 pub mod io;
+#[cfg(feature = "nodejs")]
+pub mod nodejs;
 mod panic_hook;
+pub mod resolve;
 
 #[cfg(feature = "browser")]
 mod local_storage_utils;
@@ -23,6 +26,17 @@ pub fn ensure_debug_hook() {
     panic_hook::set_once();
 }
 
+#[wasm_bindgen(js_name = normalize_iri)]
+pub fn normalize_iri(iri: &str) -> Result<String, JsValue> {
+    sysand_core::iri_normalize::normalize_iri(iri)
+        .map_err(|e| JsValue::from_str(&sysand_core::utils::format_err(e)))
+}
+
+#[wasm_bindgen(js_name = iris_equivalent)]
+pub fn iris_equivalent(a: &str, b: &str) -> bool {
+    sysand_core::iri_normalize::iris_equivalent(a, b)
+}
+
 #[cfg(feature = "browser")]
 #[wasm_bindgen(js_name = clear_local_storage)]
 pub fn clear_local_storage(prefix: &str) -> Result<(), JsValue> {
@@ -70,3 +84,126 @@ pub fn do_env_js_local_storage(prefix: &str, root_path: &str) -> Result<(), JsVa
 
     Ok(())
 }
+
+#[cfg(feature = "browser")]
+fn to_js_object<T: serde::Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    let json = serde_json::to_string(value).map_err(|e| JsValue::from_str(&format_err(e)))?;
+    js_sys::JSON::parse(&json)
+}
+
+/// Directory of `iri` in a sysand index, relative to the index root. Used
+/// by the index stub in `testing/index_stub.js`.
+#[cfg(feature = "browser")]
+#[wasm_bindgen(js_name = index_project_path)]
+pub fn index_project_path(iri: &str) -> Result<String, JsValue> {
+    sysand_core::index::project_path(iri).map_err(|e| JsValue::from_str(&format_err(e)))
+}
+
+/// Resolve `iri` against the sysand indexes at `index_urls` and return the
+/// `.project.json` and `.meta.json` of its newest version as
+/// `{ info, meta }`.
+#[cfg(feature = "browser")]
+#[wasm_bindgen(js_name = do_info_js)]
+pub async fn do_info_js(iri: String, index_urls: Vec<String>) -> Result<JsValue, JsValue> {
+    use sysand_core::{commands::info::do_info, stdlib::known_std_libs};
+
+    let resolver = resolve::fetch::FetchResolver::new(index_urls, known_std_libs())
+        .map_err(|e| JsValue::from_str(&format_err(e)))?
+        .prefetch([iri.clone()], false)
+        .await
+        .map_err(|e| JsValue::from_str(&format_err(e)))?;
+    let (info, meta) = do_info(&iri, &resolver).map_err(|e| JsValue::from_str(&format_err(e)))?;
+
+    to_js_object(&serde_json::json!({ "info": info, "meta": meta }))
+}
+
+/// Resolve `iri` against the sysand indexes at `index_urls` and return the
+/// source file paths of its newest version, relative to the project root.
+#[cfg(feature = "browser")]
+#[wasm_bindgen(js_name = do_sources_js)]
+pub async fn do_sources_js(
+    iri: String,
+    index_urls: Vec<String>,
+    include_index: bool,
+) -> Result<Vec<String>, JsValue> {
+    use sysand_core::{
+        commands::{info::do_info, sources::do_sources_project_no_deps},
+        project::memory::InMemoryProject,
+        stdlib::known_std_libs,
+    };
+
+    let resolver = resolve::fetch::FetchResolver::new(index_urls, known_std_libs())
+        .map_err(|e| JsValue::from_str(&format_err(e)))?
+        .prefetch([iri.clone()], false)
+        .await
+        .map_err(|e| JsValue::from_str(&format_err(e)))?;
+    let (info, meta) = do_info(&iri, &resolver).map_err(|e| JsValue::from_str(&format_err(e)))?;
+
+    Ok(
+        do_sources_project_no_deps(&InMemoryProject::from_info_meta(info, meta), include_index)
+            .map_err(|e| JsValue::from_str(&format_err(e)))?
+            .into_iter()
+            .map(|p| p.into_string())
+            .collect(),
+    )
+}
+
+/// Solve the usages of the project stored in local storage at
+/// `prefix`/`root_path` against the sysand indexes at `index_urls`, write
+/// the resulting lockfile next to the project and return its contents.
+/// Standard library usages are not resolved.
+#[cfg(feature = "browser")]
+#[wasm_bindgen(js_name = do_lock_js)]
+pub async fn do_lock_js(
+    prefix: &str,
+    root_path: &str,
+    index_urls: Vec<String>,
+) -> Result<String, JsValue> {
+    use sysand_core::{
+        commands::lock::{DEFAULT_LOCKFILE_NAME, do_lock_projects},
+        context::ProjectContext,
+        model::InterchangeProjectUsageRaw,
+        project::ProjectRead,
+        stdlib::known_std_libs,
+    };
+    use typed_path::Utf8UnixPath;
+
+    let project = io::local_storage::open_project_local_storage(prefix, root_path)
+        .map_err(|e| JsValue::from_str(&format_err(e)))?;
+    let usages = project
+        .get_info()
+        .map_err(|e| JsValue::from_str(&format_err(e)))?
+        .map(|info| info.usage)
+        .unwrap_or_default();
+
+    let provided_iris = known_std_libs();
+    let resolver = resolve::fetch::FetchResolver::new(index_urls, provided_iris.clone())
+        .map_err(|e| JsValue::from_str(&format_err(e)))?
+        .prefetch(
+            usages
+                .into_iter()
+                .map(|InterchangeProjectUsageRaw::Resource { resource, .. }| resource),
+            true,
+        )
+        .await
+        .map_err(|e| JsValue::from_str(&format_err(e)))?;
+
+    let outcome = do_lock_projects(
+        [(None, &project)],
+        resolver,
+        &provided_iris,
+        &ProjectContext::default(),
+    )
+    .map_err(|e| JsValue::from_str(&format_err(e)))?;
+    let lock = outcome.lock.canonicalize().to_string();
+
+    project
+        .vfs
+        .write_string(
+            Utf8UnixPath::new(root_path).join(DEFAULT_LOCKFILE_NAME),
+            &lock,
+        )
+        .map_err(|e| JsValue::from_str(&format_err(e)))?;
+
+    Ok(lock)
+}
@@ -0,0 +1,379 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Filesystem-backed commands for Node.js hosts (e.g. VS Code extensions).
+//!
+//! These use `std::fs` directly, so the module must be built for a target
+//! with filesystem access, i.e. `wasm32-wasip1` run under `node:wasi` with
+//! the workspace directory preopened. Networking is not available, so
+//! `do_sync_js` only installs projects with local sources.
+
+use std::{
+    cell::RefCell, collections::HashMap, convert::Infallible, num::NonZeroU64, str::FromStr,
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use semver::{Version, VersionReq};
+use sysand_core::{
+    build::{KparCompressionMethod, do_build_kpar},
+    commands::{
+        env::do_env_local_dir,
+        init::do_init_local_file,
+        lock::DEFAULT_LOCKFILE_NAME,
+        sources::{do_sources_local_src_project_no_deps, find_project_dependencies},
+        sync::do_sync,
+    },
+    env::{
+        OverwritePolicy, ReadEnvironment as _, WriteEnvironment as _,
+        local_directory::{LocalDirectoryEnvironment, metadata::EnvMetadataError},
+        utils::clone_project,
+    },
+    lock::{Lock, LockFormat},
+    progress::{self, ProgressEvent},
+    project::{
+        KparMeta, ProjectRead,
+        local_kpar::{KparInnerPath, LocalKParProject},
+        local_src::LocalSrcProject,
+        memory::InMemoryProject,
+        utils::wrapfs,
+    },
+    stdlib::known_std_libs,
+    utils::format_err,
+};
+use typed_path::Utf8UnixPathBuf;
+use wasm_bindgen::prelude::*;
+
+fn to_js_err<E: std::error::Error>(err: E) -> JsValue {
+    JsValue::from_str(&format_err(err))
+}
+
+fn env_read_to_js_err(err: EnvMetadataError) -> JsValue {
+    JsValue::from_str(&format!(
+        "failed to read environment metadata: {}",
+        format_err(err)
+    ))
+}
+
+thread_local! {
+    /// Progress callback of the running command and the first error it
+    /// threw. JS functions are not `Send`, so the listener looks the
+    /// callback up here; commands run on the single wasm thread
+    static PROGRESS_CALLBACK: RefCell<Option<(js_sys::Function, Option<JsValue>)>> =
+        const { RefCell::new(None) };
+}
+
+/// Run `f`, calling `callback` with `(phase, current, total, message)` for
+/// each progress event. The first error thrown by `callback` is returned
+/// after `f` finishes
+fn with_progress_callback<T>(
+    callback: Option<js_sys::Function>,
+    f: impl FnOnce() -> Result<T, JsValue>,
+) -> Result<T, JsValue> {
+    let Some(callback) = callback else {
+        return f();
+    };
+    let previous = PROGRESS_CALLBACK.replace(Some((callback, None)));
+    let result = progress::with_listener(
+        |e: &ProgressEvent| {
+            PROGRESS_CALLBACK.with_borrow_mut(|state| {
+                let Some((callback, error)) = state else {
+                    return;
+                };
+                let args = js_sys::Array::new();
+                args.push(&JsValue::from_str(e.phase.as_str()));
+                args.push(&JsValue::from_f64(e.current as f64));
+                args.push(
+                    &e.total
+                        .map_or(JsValue::UNDEFINED, |t| JsValue::from_f64(t as f64)),
+                );
+                args.push(&JsValue::from_str(&e.message));
+                if let Err(err) = callback.apply(&JsValue::NULL, &args) {
+                    error.get_or_insert(err);
+                }
+            })
+        },
+        f,
+    );
+    match PROGRESS_CALLBACK.replace(previous) {
+        Some((_, Some(err))) => Err(err),
+        _ => result,
+    }
+}
+
+fn local_src_project(path: impl Into<Utf8PathBuf>) -> LocalSrcProject {
+    LocalSrcProject {
+        nominal_path: None,
+        project_path: path.into(),
+        expected_checksum: None,
+    }
+}
+
+fn provided_iris(include_std: bool) -> HashMap<String, Vec<InMemoryProject>> {
+    if include_std {
+        HashMap::default()
+    } else {
+        known_std_libs()
+    }
+}
+
+/// Source file paths of `project` and, if `env` is given, of all its
+/// (transitive) usages installed there.
+fn collect_sources(
+    project: &LocalSrcProject,
+    env: Option<LocalDirectoryEnvironment>,
+    include_std: bool,
+) -> Result<Vec<String>, JsValue> {
+    let mut result: Vec<String> = do_sources_local_src_project_no_deps(project, true)
+        .map_err(to_js_err)?
+        .into_iter()
+        .map(|p| p.into_string())
+        .collect();
+
+    let Some(env) = env else {
+        return Ok(result);
+    };
+
+    let Some(info) = project.get_info().map_err(to_js_err)? else {
+        return Err(JsValue::from_str("project is missing project information"));
+    };
+
+    for dep in find_project_dependencies(
+        info.validate().map_err(to_js_err)?.usage,
+        env,
+        &provided_iris(include_std),
+    )
+    .map_err(to_js_err)?
+    {
+        result.extend(
+            do_sources_local_src_project_no_deps(&dep, true)
+                .map_err(to_js_err)?
+                .into_iter()
+                .map(|p| p.into_string()),
+        );
+    }
+
+    Ok(result)
+}
+
+#[wasm_bindgen(js_name = do_init_js_local_file)]
+pub fn do_init_js_local_file(
+    name: String,
+    publisher: Option<String>,
+    version: String,
+    path: String,
+    license: Option<String>,
+) -> Result<(), JsValue> {
+    do_init_local_file(name, publisher, version, license, Utf8PathBuf::from(path))
+        .map_err(to_js_err)?;
+
+    Ok(())
+}
+
+#[wasm_bindgen(js_name = do_env_js_local_dir)]
+pub fn do_env_js_local_dir(path: String) -> Result<(), JsValue> {
+    do_env_local_dir(Utf8Path::new(&path)).map_err(to_js_err)?;
+
+    Ok(())
+}
+
+/// Install the project at `location` (a project directory or a KPAR file)
+/// into the environment at `env_path` as `iri`.
+#[wasm_bindgen(js_name = do_env_install_path_js)]
+pub fn do_env_install_path_js(
+    env_path: String,
+    iri: String,
+    location: String,
+) -> Result<(), JsValue> {
+    fn install<P: ProjectRead>(
+        env: &mut LocalDirectoryEnvironment,
+        iri: String,
+        project: &P,
+        location: &Utf8Path,
+    ) -> Result<(), JsValue> {
+        let Some(version) = project.version().map_err(to_js_err)? else {
+            return Err(JsValue::from_str(&format!(
+                "project at `{location}` lacks project information"
+            )));
+        };
+        let checksum = project.checksum_canonical_variant().map_err(to_js_err)?;
+
+        env.put_project(
+            iri,
+            version,
+            Some(checksum),
+            OverwritePolicy::Replace,
+            |to| clone_project(project, to, true).map(|_| ()),
+        )
+        .map_err(to_js_err)?;
+
+        Ok(())
+    }
+
+    let location = Utf8PathBuf::from(location);
+    let mut env = LocalDirectoryEnvironment::read(env_path).map_err(env_read_to_js_err)?;
+
+    let metadata = wrapfs::metadata(&location).map_err(to_js_err)?;
+    if metadata.is_file() {
+        let project = LocalKParProject::new(&location, KparInnerPath::Guess, None, None);
+        install(&mut env, iri, &project, &location)
+    } else if metadata.is_dir() {
+        install(&mut env, iri, &local_src_project(&location), &location)
+    } else {
+        Err(JsValue::from_str(&format!(
+            "unable to find project at `{location}`"
+        )))
+    }
+}
+
+/// Install the projects in the lockfile of the project at `project_path`
+/// into the environment at `env_path`. Only projects with local sources
+/// (`editable`, `src_path` or `kpar_path`) can be installed; standard
+/// library projects are skipped.
+///
+/// `progress` is called with `(phase, current, total, message)` before each
+/// project is installed.
+#[wasm_bindgen(js_name = do_sync_js)]
+pub fn do_sync_js(
+    project_path: String,
+    env_path: String,
+    progress: Option<js_sys::Function>,
+) -> Result<(), JsValue> {
+    let project_root = Utf8PathBuf::from(project_path);
+    // Lockfiles can be in either format
+    let lockfile = [LockFormat::Json, LockFormat::Toml]
+        .into_iter()
+        .map(|f| project_root.join(f.file_name()))
+        .find(|p| p.is_file())
+        .unwrap_or_else(|| project_root.join(DEFAULT_LOCKFILE_NAME));
+    let lock =
+        Lock::from_str(&wrapfs::read_to_string(lockfile).map_err(to_js_err)?).map_err(to_js_err)?;
+    let mut env = LocalDirectoryEnvironment::read(env_path).map_err(env_read_to_js_err)?;
+
+    with_progress_callback(progress, || {
+        do_sync(
+            &lock,
+            &mut env,
+            Some(
+                |src_path: Utf8UnixPathBuf, checksum: String| -> LocalSrcProject {
+                    LocalSrcProject {
+                        project_path: project_root.join(src_path.as_str()),
+                        nominal_path: Some(src_path),
+                        expected_checksum: Some(checksum),
+                    }
+                },
+            ),
+            None::<fn(String, String) -> Result<InMemoryProject, Infallible>>,
+            Some(
+                |kpar_path: String,
+                 kpar_size: NonZeroU64,
+                 kpar_digest: String|
+                 -> LocalKParProject {
+                    LocalKParProject::new(
+                        project_root.join(&kpar_path),
+                        KparInnerPath::Guess,
+                        Some(kpar_path.into()),
+                        Some(KparMeta {
+                            size_bytes: kpar_size,
+                            sha256_hex: kpar_digest,
+                        }),
+                    )
+                },
+            ),
+            None::<fn(String, NonZeroU64, String) -> Result<InMemoryProject, Infallible>>,
+            None::<fn(String, NonZeroU64, String) -> Result<InMemoryProject, Infallible>>,
+            None::<fn(String, Option<String>) -> Result<InMemoryProject, Infallible>>,
+            &known_std_libs(),
+        )
+        .map_err(to_js_err)
+    })
+}
+
+/// Build the project at `project_path` into a KPAR at `output_path`.
+/// `progress` is called with `(phase, current, total, message)` for each
+/// file added.
+#[wasm_bindgen(js_name = do_build_js)]
+pub fn do_build_js(
+    output_path: String,
+    project_path: String,
+    compression: Option<String>,
+    progress: Option<js_sys::Function>,
+) -> Result<(), JsValue> {
+    let compression = match compression {
+        Some(compression) => KparCompressionMethod::try_from(compression).map_err(to_js_err)?,
+        None => KparCompressionMethod::default(),
+    };
+
+    with_progress_callback(progress, || {
+        do_build_kpar(
+            &local_src_project(project_path),
+            &output_path,
+            compression,
+            true,
+            true,
+        )
+        .map(|_| ())
+        .map_err(to_js_err)
+    })
+}
+
+/// Source file paths of the project at `path`. With `include_deps`, also
+/// those of its usages installed in the environment at `env_path`.
+#[wasm_bindgen(js_name = do_sources_project_js)]
+pub fn do_sources_project_js(
+    path: String,
+    include_deps: bool,
+    env_path: Option<String>,
+    include_std: bool,
+) -> Result<Vec<String>, JsValue> {
+    let env = if include_deps {
+        let Some(env_path) = env_path else {
+            return Err(JsValue::from_str("unable to identify local environment"));
+        };
+        Some(LocalDirectoryEnvironment::read(env_path).map_err(env_read_to_js_err)?)
+    } else {
+        None
+    };
+
+    collect_sources(&local_src_project(path), env, include_std)
+}
+
+/// Source file paths of the project `iri` installed in the environment at
+/// `env_path`, optionally restricted to versions matching `version`.
+#[wasm_bindgen(js_name = do_sources_env_js)]
+pub fn do_sources_env_js(
+    env_path: String,
+    iri: String,
+    version: Option<String>,
+    include_deps: bool,
+    include_std: bool,
+) -> Result<Vec<String>, JsValue> {
+    let version = version
+        .map(|v| VersionReq::parse(&v))
+        .transpose()
+        .map_err(to_js_err)?;
+    let env = LocalDirectoryEnvironment::read(env_path).map_err(env_read_to_js_err)?;
+
+    let mut project = None;
+    for candidate in env.candidate_projects(&iri).map_err(to_js_err)? {
+        let matches = match &version {
+            None => true,
+            Some(vr) => candidate
+                .version()
+                .map_err(to_js_err)?
+                .and_then(|v| Version::parse(&v).ok())
+                .is_some_and(|v| vr.matches(&v)),
+        };
+        if matches {
+            project = Some(candidate);
+            break;
+        }
+    }
+    let Some(project) = project else {
+        return Err(JsValue::from_str(&match version {
+            Some(vr) => format!("unable to find project `{iri}` ({vr}) in local environment"),
+            None => format!("unable to find project `{iri}` in local environment"),
+        }));
+    };
+
+    collect_sources(&project, include_deps.then_some(env), include_std)
+}
@@ -0,0 +1,322 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Resolution of project IRIs over HTTP using the browser `fetch` API.
+//!
+//! The resolvers in `sysand-core` are synchronous (or need a tokio runtime),
+//! neither of which is available in the browser. Instead, [`FetchResolver`]
+//! asynchronously collects the `.project.json`/`.meta.json` of every
+//! candidate version into a [`MemoryResolver`], on which the core commands
+//! then run unchanged. Only metadata is fetched; KPARs and source files are
+//! never downloaded.
+//!
+//! IRIs are looked up in the configured sysand indexes (see
+//! `design/index-protocol.md`) in order. `http(s)` IRIs not found in any
+//! index are tried as remote source projects, i.e. with `.project.json` and
+//! `.meta.json` directly under the IRI.
+
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroU64,
+};
+
+use fluent_uri::Iri;
+use serde::{Deserialize, de::DeserializeOwned};
+use sysand_core::{
+    index::{ParseIriError, legacy_project_path, project_path},
+    lock::Source,
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw, InterchangeProjectUsageRaw},
+    project::{ProjectRead, memory::InMemoryProject},
+    resolve::memory::{AcceptAll, MemoryResolver},
+};
+use thiserror::Error;
+use url::Url;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Request, RequestInit, Response};
+
+const INDEX_CONFIG_PATH: &str = "sysand-index-config.json";
+const VERSIONS_PATH: &str = "versions.json";
+const KPAR_FILE: &str = "project.kpar";
+const PROJECT_JSON_FILE: &str = ".project.json";
+const META_JSON_FILE: &str = ".meta.json";
+
+#[derive(Error, Debug)]
+pub enum FetchError {
+    #[error("failed to get `window` object")]
+    NoWindow,
+    /// String since JsValue is not Send/Sync
+    #[error("HTTP request to `{0}` failed: {1}")]
+    Request(Box<str>, String),
+    #[error("HTTP request to `{url}` returned status {status}")]
+    BadHttpStatus { url: Box<str>, status: u16 },
+    #[error("failed to parse JSON from `{0}`: {1}")]
+    JsonParse(Box<str>, serde_json::Error),
+    #[error("invalid URL `{0}`: {1}")]
+    Url(Box<str>, url::ParseError),
+    #[error(transparent)]
+    Iri(#[from] ParseIriError),
+}
+
+/// Subset of the sysand index `versions.json` needed to locate the
+/// per-version files and record the KPAR in a lockfile.
+#[derive(Debug, Deserialize)]
+struct VersionsJson {
+    versions: Vec<VersionEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionEntry {
+    version: String,
+    kpar_size: NonZeroU64,
+    kpar_digest: String,
+    #[serde(default)]
+    status: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct IndexConfig {
+    #[serde(default)]
+    index_root: Option<String>,
+}
+
+/// GET `url` and parse the body as JSON. A 404 returns `Ok(None)`.
+async fn fetch_json<T: DeserializeOwned>(url: &Url) -> Result<Option<T>, FetchError> {
+    let request_error = |e: JsValue| FetchError::Request(url.as_str().into(), format!("{e:?}"));
+
+    let window = web_sys::window().ok_or(FetchError::NoWindow)?;
+    let init = RequestInit::new();
+    init.set_method("GET");
+    let request = Request::new_with_str_and_init(url.as_str(), &init).map_err(request_error)?;
+    request
+        .headers()
+        .set("Accept", "application/json")
+        .map_err(request_error)?;
+
+    let response: Response = JsFuture::from(window.fetch_with_request(&request))
+        .await
+        .and_then(|r| r.dyn_into())
+        .map_err(request_error)?;
+
+    if response.status() == 404 {
+        return Ok(None);
+    }
+    if !response.ok() {
+        return Err(FetchError::BadHttpStatus {
+            url: url.as_str().into(),
+            status: response.status(),
+        });
+    }
+
+    let body = JsFuture::from(response.text().map_err(request_error)?)
+        .await
+        .map_err(request_error)?
+        .as_string()
+        .unwrap_or_default();
+
+    serde_json::from_str(&body)
+        .map(Some)
+        .map_err(|e| FetchError::JsonParse(url.as_str().into(), e))
+}
+
+fn url_join(url: &Url, path: &str) -> Result<Url, FetchError> {
+    url.join(path)
+        .map_err(|e| FetchError::Url(format!("{url}{path}").into(), e))
+}
+
+fn with_trailing_slash(mut url: Url) -> Url {
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+    url
+}
+
+/// Collects candidate projects for IRIs from sysand indexes and remote
+/// source projects over HTTP.
+#[derive(Debug)]
+pub struct FetchResolver {
+    /// Discovery roots of the indexes, in priority order
+    index_urls: Vec<Url>,
+    /// Projects that are never fetched, e.g. the standard library
+    provided_iris: HashMap<String, Vec<InMemoryProject>>,
+}
+
+impl FetchResolver {
+    pub fn new<I: IntoIterator<Item = S>, S: AsRef<str>>(
+        index_urls: I,
+        provided_iris: HashMap<String, Vec<InMemoryProject>>,
+    ) -> Result<Self, FetchError> {
+        let index_urls = index_urls
+            .into_iter()
+            .map(|u| {
+                Url::parse(u.as_ref())
+                    .map(with_trailing_slash)
+                    .map_err(|e| FetchError::Url(u.as_ref().into(), e))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            index_urls,
+            provided_iris,
+        })
+    }
+
+    /// Follow the optional `sysand-index-config.json` of an index to its
+    /// `index_root`.
+    async fn index_root(discovery_root: &Url) -> Result<Url, FetchError> {
+        let config_url = url_join(discovery_root, INDEX_CONFIG_PATH)?;
+        let config: IndexConfig = fetch_json(&config_url).await?.unwrap_or_default();
+        match config.index_root {
+            Some(root) => url_join(discovery_root, &root).map(with_trailing_slash),
+            None => Ok(discovery_root.clone()),
+        }
+    }
+
+    /// Fetch `.project.json` and `.meta.json` from the directory `base`.
+    /// Returns `None` if either is missing.
+    async fn fetch_info_meta(
+        base: &Url,
+    ) -> Result<Option<(InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw)>, FetchError>
+    {
+        let info = fetch_json(&url_join(base, PROJECT_JSON_FILE)?).await?;
+        let meta = fetch_json(&url_join(base, META_JSON_FILE)?).await?;
+        Ok(info.zip(meta))
+    }
+
+    async fn fetch_from_index(
+        index_root: &Url,
+        iri: &str,
+    ) -> Result<Vec<InMemoryProject>, FetchError> {
+        let mut project_url = url_join(index_root, &format!("{}/", project_path(iri)?))?;
+        let mut versions =
+            fetch_json::<VersionsJson>(&url_join(&project_url, VERSIONS_PATH)?).await?;
+        // Indexes that predate the `_kpar` layout keep `urn:kpar` projects
+        // in the `_iri` bucket
+        if versions.is_none()
+            && let Some(legacy_path) = legacy_project_path(iri)?
+        {
+            project_url = url_join(index_root, &format!("{legacy_path}/"))?;
+            versions = fetch_json::<VersionsJson>(&url_join(&project_url, VERSIONS_PATH)?).await?;
+        }
+        let Some(versions) = versions else {
+            return Ok(vec![]);
+        };
+
+        let mut candidates = vec![];
+        for entry in versions.versions {
+            if matches!(entry.status.as_deref(), Some("yanked" | "removed")) {
+                continue;
+            }
+            let version_url = url_join(&project_url, &format!("{}/", entry.version))?;
+            let Some((info, meta)) = Self::fetch_info_meta(&version_url).await? else {
+                log::warn!(
+                    "skipping version `{}` of `{iri}`: missing metadata",
+                    entry.version
+                );
+                continue;
+            };
+            let mut project = InMemoryProject::from_info_meta(info, meta);
+            project.nominal_sources = vec![Source::IndexKpar {
+                index_kpar: url_join(&version_url, KPAR_FILE)?.to_string(),
+                kpar_size: entry.kpar_size,
+                kpar_digest: entry
+                    .kpar_digest
+                    .strip_prefix("sha256:")
+                    .unwrap_or(&entry.kpar_digest)
+                    .to_string(),
+            }];
+            candidates.push(project);
+        }
+        Ok(candidates)
+    }
+
+    async fn fetch_remote_src(url: Url) -> Result<Option<InMemoryProject>, FetchError> {
+        let url = with_trailing_slash(url);
+        let Some((info, meta)) = Self::fetch_info_meta(&url).await? else {
+            return Ok(None);
+        };
+        let mut project = InMemoryProject::from_info_meta(info, meta);
+        let checksum = match project.checksum_canonical_hex() {
+            Ok(Some(checksum)) => checksum,
+            // Checksums cannot be canonicalized without downloading sources
+            _ => {
+                log::warn!("skipping remote project `{url}`: `.meta.json` lacks SHA256 checksums");
+                return Ok(None);
+            }
+        };
+        project.nominal_sources = vec![Source::RemoteSrc {
+            remote_src: url.to_string(),
+            checksum,
+        }];
+        Ok(Some(project))
+    }
+
+    /// Candidate projects (one per available version) for `iri`, taken from
+    /// the first index that has the project. Empty if the IRI cannot be
+    /// resolved.
+    async fn fetch_candidates(
+        &self,
+        index_roots: &[Url],
+        iri: &str,
+    ) -> Result<Vec<InMemoryProject>, FetchError> {
+        if let Some(provided) = self.provided_iris.get(iri) {
+            return Ok(provided.clone());
+        }
+
+        for index_root in index_roots {
+            let candidates = Self::fetch_from_index(index_root, iri).await?;
+            if !candidates.is_empty() {
+                return Ok(candidates);
+            }
+        }
+
+        match Url::parse(iri) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {
+                Ok(Self::fetch_remote_src(url).await?.into_iter().collect())
+            }
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// Fetch candidates for `iris` and, if `transitive`, for everything they
+    /// (or any of their versions) use.
+    pub async fn prefetch<I: IntoIterator<Item = String>>(
+        &self,
+        iris: I,
+        transitive: bool,
+    ) -> Result<MemoryResolver<AcceptAll, InMemoryProject>, FetchError> {
+        let mut pending: Vec<String> = iris.into_iter().collect();
+        let mut seen: HashSet<String> = pending.iter().cloned().collect();
+        let mut projects = vec![];
+
+        let mut index_roots = Vec::with_capacity(self.index_urls.len());
+        for discovery_root in &self.index_urls {
+            index_roots.push(Self::index_root(discovery_root).await?);
+        }
+
+        while let Some(iri) = pending.pop() {
+            let candidates = self.fetch_candidates(&index_roots, &iri).await?;
+            if transitive {
+                for usage in candidates
+                    .iter()
+                    .flat_map(|p| p.info.iter())
+                    .flat_map(|i| &i.usage)
+                {
+                    let InterchangeProjectUsageRaw::Resource { resource, .. } = usage;
+                    if seen.insert(resource.clone()) {
+                        pending.push(resource.clone());
+                    }
+                }
+            }
+            if candidates.is_empty() {
+                continue;
+            }
+            match Iri::parse(iri) {
+                Ok(parsed) => projects.push((parsed, candidates)),
+                Err((e, iri)) => log::warn!("skipping invalid IRI `{iri}`: {e}"),
+            }
+        }
+
+        Ok(MemoryResolver::from(projects))
+    }
+}
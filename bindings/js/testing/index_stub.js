@@ -0,0 +1,175 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+// In-process stub of a sysand index (see `design/index-protocol.md`) for
+// testing code that uses the fetch-based resolver of the browser bindings,
+// i.e. `do_info_js`, `do_sources_js` and `do_lock_js`.
+//
+// The stub replaces `fetch` of the given global object (`globalThis`, i.e.
+// `window` in browsers, by default) and answers requests to its index and to the registered remote
+// source projects from memory. Requests to other URLs are passed to the
+// original `fetch`, unless `passthrough` is disabled, in which case they
+// fail. Every request handled by the stub is recorded in `requests`.
+//
+// ```js
+// import * as sysand from "sysand";
+// import { IndexStub } from "./testing/index_stub.js";
+//
+// const index = new IndexStub(sysand.index_project_path).install();
+// index.addProject("pkg:sysand/acme/lib", "1.0.0");
+// const { info } = await sysand.do_info_js("pkg:sysand/acme/lib", [index.url]);
+// index.uninstall();
+// ```
+
+export const DEFAULT_INDEX_URL = "https://index.sysand.test/";
+
+const EMPTY_DIGEST = "sha256:" + "0".repeat(64);
+
+function withTrailingSlash(url) {
+  return url.endsWith("/") ? url : url + "/";
+}
+
+function jsonResponse(value) {
+  return new Response(JSON.stringify(value), {
+    status: 200,
+    headers: { "Content-Type": "application/json" },
+  });
+}
+
+export class IndexStub {
+  // `projectPath` maps an IRI to its directory in the index, relative to
+  // the index root. Pass `index_project_path` of the sysand module, which
+  // normalizes IRIs the same way as the resolver.
+  constructor(
+    projectPath,
+    { url = DEFAULT_INDEX_URL, passthrough = true } = {},
+  ) {
+    this.projectPath = projectPath;
+    this.url = withTrailingSlash(url);
+    this.passthrough = passthrough;
+    this.requests = [];
+    // URL -> function returning the response
+    this.routes = new Map();
+    // Project directory URL -> { iri, versions }
+    this.projects = new Map();
+    this.target = null;
+    this.originalFetch = null;
+  }
+
+  // Add version `version` of project `iri` to the index. `info` and `meta`
+  // default to a minimal `.project.json` (named after the last segment of
+  // `iri`) and `.meta.json`; `info.version` is always `version`. `status`
+  // can be set to `"yanked"` or `"removed"`.
+  addProject(
+    iri,
+    version,
+    {
+      info = {},
+      meta = {},
+      kparSize = 1,
+      kparDigest = EMPTY_DIGEST,
+      status,
+    } = {},
+  ) {
+    const projectUrl = this.url + this.projectPath(iri) + "/";
+    let project = this.projects.get(projectUrl);
+    if (!project) {
+      project = { iri, versions: [] };
+      this.projects.set(projectUrl, project);
+      this.routes.set(projectUrl + "versions.json", () =>
+        jsonResponse({ versions: project.versions }),
+      );
+    }
+    info = { name: iri.split(/[/:]/).pop(), ...info, version };
+    project.versions = project.versions.filter((v) => v.version !== version);
+    project.versions.push({
+      version,
+      usage: info.usage ?? [],
+      kpar_size: kparSize,
+      kpar_digest: kparDigest,
+      ...(status ? { status } : {}),
+    });
+    this.addFiles(projectUrl + version + "/", info, meta);
+    return this;
+  }
+
+  // Serve a remote source project with `info` and `meta` at `url`. Its
+  // `.meta.json` needs SHA256 checksums for the resolver to accept it.
+  addRemoteSource(url, info, meta) {
+    this.addFiles(withTrailingSlash(url), info, meta);
+    return this;
+  }
+
+  // Serve `sysand-index-config.json` pointing to `indexRoot`, relative to
+  // the index URL. Projects added afterwards are served from there.
+  setIndexRoot(indexRoot) {
+    this.routes.set(this.url + "sysand-index-config.json", () =>
+      jsonResponse({ index_root: indexRoot }),
+    );
+    this.url = withTrailingSlash(new URL(indexRoot, this.url).href);
+    return this;
+  }
+
+  addFiles(baseUrl, info, meta) {
+    const fullMeta = { index: {}, created: "2026-01-01T00:00:00Z", ...meta };
+    this.routes.set(baseUrl + ".project.json", () => jsonResponse(info));
+    this.routes.set(baseUrl + ".meta.json", () => jsonResponse(fullMeta));
+  }
+
+  handles(url) {
+    return (
+      url.startsWith(this.url) ||
+      [...this.routes.keys()].some(
+        (route) => new URL(route).origin === new URL(url).origin,
+      )
+    );
+  }
+
+  // Response to a GET of `url`, `null` if the stub does not serve `url`
+  respond(url) {
+    if (url === this.url + "index.json") {
+      return jsonResponse({
+        projects: [...this.projects.values()].map(({ iri }) => ({ iri })),
+      });
+    }
+    const route = this.routes.get(url);
+    if (route) {
+      return route();
+    }
+    return this.handles(url) ? new Response(null, { status: 404 }) : null;
+  }
+
+  async fetch(input, init) {
+    const url =
+      typeof input === "string" ? input : (input.url ?? String(input));
+    const response = this.respond(url);
+    if (response) {
+      this.requests.push(url);
+      return response;
+    }
+    if (this.passthrough && this.originalFetch) {
+      return this.originalFetch.call(this.target, input, init);
+    }
+    throw new TypeError(`IndexStub: unexpected request to ${url}`);
+  }
+
+  // Replace `fetch` of `target` with the stub
+  install(target = globalThis) {
+    if (this.target) {
+      throw new Error("IndexStub is already installed");
+    }
+    this.target = target;
+    this.originalFetch = target.fetch;
+    target.fetch = (input, init) => this.fetch(input, init);
+    return this;
+  }
+
+  // Restore the original `fetch`
+  uninstall() {
+    if (this.target) {
+      this.target.fetch = this.originalFetch;
+      this.target = null;
+      this.originalFetch = null;
+    }
+  }
+}
@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+#[cfg(feature = "nodejs")]
+mod nodejs_tests {
+    use std::error::Error;
+
+    use camino_tempfile::tempdir;
+    use sysand_core::{
+        commands::lock::DEFAULT_LOCKFILE_NAME,
+        env::{DEFAULT_ENV_NAME, ReadEnvironment, local_directory::LocalDirectoryEnvironment},
+        lock::CURRENT_LOCK_VERSION,
+        project::{ProjectRead, local_src::LocalSrcProject},
+    };
+    use sysand_js::nodejs::{
+        do_build_js, do_env_install_path_js, do_env_js_local_dir, do_init_js_local_file,
+        do_sources_env_js, do_sources_project_js, do_sync_js,
+    };
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn init_env_install_sources() -> Result<(), Box<dyn Error>> {
+        let cwd = tempdir()?;
+        let dep_path = cwd.path().join("dep");
+        let env_path = cwd.path().join(DEFAULT_ENV_NAME);
+        std::fs::create_dir_all(&dep_path)?;
+
+        do_init_js_local_file(
+            "dep".to_string(),
+            None,
+            "1.0.0".to_string(),
+            dep_path.to_string(),
+            None,
+        )
+        .unwrap();
+        std::fs::write(dep_path.join("Dep.sysml"), "package Dep;")?;
+        std::fs::write(
+            dep_path.join(".meta.json"),
+            r#"{"index":{"Dep":"Dep.sysml"},"created":"2026-01-01T00:00:00.000000000Z"}"#,
+        )?;
+
+        do_env_js_local_dir(env_path.to_string()).unwrap();
+        do_env_install_path_js(
+            env_path.to_string(),
+            "urn:kpar:dep".to_string(),
+            dep_path.to_string(),
+        )
+        .unwrap();
+
+        let env = LocalDirectoryEnvironment::read(&env_path)?;
+        assert!(env.has("urn:kpar:dep")?);
+
+        assert_eq!(
+            do_sources_env_js(
+                env_path.to_string(),
+                "urn:kpar:dep".to_string(),
+                Some("^1".to_string()),
+                false,
+                false,
+            )
+            .unwrap()
+            .len(),
+            1
+        );
+        assert_eq!(
+            do_sources_project_js(dep_path.to_string(), false, None, false).unwrap(),
+            vec![dep_path.join("Dep.sysml").to_string()]
+        );
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn build_and_sync_local_src() -> Result<(), Box<dyn Error>> {
+        let cwd = tempdir()?;
+        let dep_path = cwd.path().join("lib").join("dep");
+        let env_path = cwd.path().join(DEFAULT_ENV_NAME);
+        std::fs::create_dir_all(&dep_path)?;
+
+        do_init_js_local_file(
+            "dep".to_string(),
+            None,
+            "1.0.0".to_string(),
+            dep_path.to_string(),
+            None,
+        )
+        .unwrap();
+        do_build_js(
+            cwd.path().join("dep.kpar").to_string(),
+            dep_path.to_string(),
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(cwd.path().join("dep.kpar").is_file());
+
+        let checksum = LocalSrcProject {
+            nominal_path: None,
+            project_path: dep_path,
+            expected_checksum: None,
+        }
+        .checksum_canonical_hex()?
+        .unwrap();
+        std::fs::write(
+            cwd.path().join(DEFAULT_LOCKFILE_NAME),
+            format!(
+                r#"lock_version = "{CURRENT_LOCK_VERSION}"
+
+[[project]]
+name = "dep"
+version = "1.0.0"
+identifiers = ["urn:kpar:dep"]
+sources = [
+    {{ src_path = "lib/dep", checksum = "{checksum}" }},
+]
+"#
+            ),
+        )?;
+
+        do_env_js_local_dir(env_path.to_string()).unwrap();
+        do_sync_js(cwd.path().to_string(), env_path.to_string(), None).unwrap();
+
+        let env = LocalDirectoryEnvironment::read(&env_path)?;
+        assert!(env.has("urn:kpar:dep")?);
+
+        Ok(())
+    }
+}
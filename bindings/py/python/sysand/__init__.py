@@ -7,6 +7,7 @@ from ._model import (
     InterchangeProjectChecksum,
     InterchangeProjectMetadata,
     CompressionMethod,
+    ProgressEvent,
 )
 
 from ._info import info_path, info
@@ -41,12 +42,26 @@ from ._sources import (
 
 from ._build import build
 
+from ._discover import (
+    WalkResult,
+    WalkStop,
+    WalkedProject,
+    WorkspaceProject,
+    discover_project,
+    discover_workspace,
+    walk_projects,
+    workspace_projects,
+)
+
+from ._iri import iris_equivalent, normalize_iri
+
 __all__ = [
     "InterchangeProjectUsage",
     "InterchangeProjectInfo",
     "InterchangeProjectChecksum",
     "InterchangeProjectMetadata",
     "CompressionMethod",
+    "ProgressEvent",
     ## Add
     "add",
     ## Remove
@@ -66,4 +81,16 @@ __all__ = [
     "exclude",
     ## Sources
     "sources",
+    ## Discover
+    "WalkResult",
+    "WalkStop",
+    "WalkedProject",
+    "WorkspaceProject",
+    "discover_project",
+    "discover_workspace",
+    "walk_projects",
+    "workspace_projects",
+    ## IRI
+    "normalize_iri",
+    "iris_equivalent",
 ]
@@ -3,23 +3,28 @@
 
 from __future__ import annotations
 
-from sysand._model import CompressionMethod
+from sysand._model import CompressionMethod, ProgressEvent
 import sysand._sysand_core as sysand_rs  # type: ignore
 
 from pathlib import Path
+import typing
 
 
 def build(
     output_path: str | Path,
     project_path: str | Path | None = None,
     compression: CompressionMethod | None = None,
+    progress: typing.Callable[[ProgressEvent], None] | None = None,
 ) -> None:
     if project_path is not None:
         project_path = str(project_path)
 
     # comp = None if compression is None else _convert_compression(compression)
     comp = None if compression is None else compression.name
-    sysand_rs.do_build_py(str(output_path), project_path, comp)
+    callback = None
+    if progress is not None:
+        callback = lambda *event: progress(ProgressEvent(*event))  # noqa: E731
+    sysand_rs.do_build_py(str(output_path), project_path, comp, callback)
 
 
 __all__ = [
@@ -0,0 +1,95 @@
+# SPDX-License-Identifier: MIT OR Apache-2.0
+# SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+from __future__ import annotations
+
+import typing
+from pathlib import Path
+
+import sysand._sysand_core as sysand_rs  # type: ignore
+
+
+class WorkspaceProject(typing.NamedTuple):
+    path: Path
+    """Path of the project, relative to the workspace root"""
+    iris: typing.List[str]
+    """IRIs by which the project is known in the workspace"""
+
+
+class WalkedProject(typing.NamedTuple):
+    path: Path
+    """Path of the project, relative to the walked directory"""
+    parent: Path | None
+    """Path of the innermost project containing this one, if it is nested"""
+
+
+class WalkStop(typing.NamedTuple):
+    path: Path
+    """Path of the directory that was not searched"""
+    reason: str
+
+
+class WalkResult(typing.NamedTuple):
+    projects: typing.List[WalkedProject]
+    workspaces: typing.List[Path]
+    stops: typing.List[WalkStop]
+
+
+def discover_project(
+    path: str | Path = ".",
+    *,
+    max_depth: int | None = None,
+) -> Path | None:
+    """Find the root of the project containing `path`, searching at most
+    `max_depth` parent directories (all of them if `None`)"""
+    root = sysand_rs.discover_project_py(str(path), max_depth)  # type: ignore
+    return None if root is None else Path(root)
+
+
+def discover_workspace(path: str | Path = ".") -> Path | None:
+    """Find the root of the workspace containing `path`"""
+    root = sysand_rs.discover_workspace_py(str(path))  # type: ignore
+    return None if root is None else Path(root)
+
+
+def walk_projects(
+    path: str | Path = ".",
+    *,
+    max_depth: int | None = None,
+    nested: bool = True,
+    hidden: bool = False,
+) -> WalkResult:
+    """Find all projects in `path` and its subdirectories, searching at most
+    `max_depth` levels of subdirectories (all of them if `None`).
+    Subdirectories that cannot be read are reported in `stops`"""
+    projects, workspaces, stops = sysand_rs.walk_projects_py(  # type: ignore
+        str(path), max_depth, nested, hidden
+    )
+    return WalkResult(
+        [
+            WalkedProject(Path(p), None if parent is None else Path(parent))
+            for p, parent in projects
+        ],
+        [Path(p) for p in workspaces],
+        [WalkStop(Path(p), reason) for p, reason in stops],
+    )
+
+
+def workspace_projects(path: str | Path = ".") -> typing.List[WorkspaceProject]:
+    """List the projects of the workspace rooted at `path`"""
+    return [
+        WorkspaceProject(Path(project_path), iris)
+        for project_path, iris in sysand_rs.workspace_projects_py(str(path))  # type: ignore
+    ]
+
+
+__all__ = [
+    "WalkResult",
+    "WalkStop",
+    "WalkedProject",
+    "WorkspaceProject",
+    "discover_project",
+    "discover_workspace",
+    "walk_projects",
+    "workspace_projects",
+]
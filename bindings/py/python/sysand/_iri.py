@@ -0,0 +1,17 @@
+# SPDX-License-Identifier: MIT OR Apache-2.0
+# SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+from __future__ import annotations
+
+import sysand._sysand_core as sysand_rs  # type: ignore
+
+
+def normalize_iri(iri: str) -> str:
+    return sysand_rs.normalize_iri_py(iri)
+
+
+def iris_equivalent(a: str, b: str) -> bool:
+    return sysand_rs.iris_equivalent_py(a, b)
+
+
+__all__ = ["normalize_iri", "iris_equivalent"]
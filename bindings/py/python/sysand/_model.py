@@ -9,6 +9,8 @@ import datetime
 class InterchangeProjectUsage(typing.TypedDict):
     resource: str
     version_constraint: typing.Optional[str]
+    when: typing.Optional[str]
+    hash: typing.Optional[str]
 
 
 class InterchangeProjectInfo(typing.TypedDict):
@@ -51,10 +53,21 @@ class CompressionMethod(Enum):
     """Compress the files using PPMd. Only available when sysand is compiled with feature kpar-ppmd"""
 
 
+class ProgressEvent(typing.NamedTuple):
+    phase: str
+    """One of `build`, `install` and `download`"""
+    current: int
+    """Units done so far: files, sync actions or bytes"""
+    total: typing.Optional[int]
+    message: str
+    """What is being worked on, e.g. a file path"""
+
+
 __all__ = [
     "InterchangeProjectUsage",
     "InterchangeProjectInfo",
     "InterchangeProjectChecksum",
     "InterchangeProjectMetadata",
     "CompressionMethod",
+    "ProgressEvent",
 ]
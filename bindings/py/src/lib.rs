@@ -1,24 +1,34 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
-use std::{collections::HashMap, iter, process::ExitCode, sync::Arc};
+use std::{
+    collections::HashMap,
+    iter,
+    process::ExitCode,
+    sync::{Arc, Mutex},
+};
 
 use camino::{Utf8Path, Utf8PathBuf};
 use pyo3::{
-    exceptions::{PyFileExistsError, PyFileNotFoundError, PyIOError, PyRuntimeError, PyValueError},
+    exceptions::{
+        PyFileExistsError, PyFileNotFoundError, PyIOError, PyPermissionError, PyRuntimeError,
+        PyValueError,
+    },
     prelude::*,
 };
 use semver::{Version, VersionReq};
 use sysand_core::{
     add::do_add_guess,
-    auth::Unauthenticated,
     build::{KParBuildError, KparCompressionMethod, do_build_kpar},
     commands::{
         env::{EnvError, do_env_local_dir},
         init::do_init_local_file,
     },
+    config::local_fs::load_configs,
+    context::ProjectContext,
+    discover::{WalkOptions, discover_project_within, discover_workspace, walk_projects},
     env::{
-        DEFAULT_ENV_NAME, ReadEnvironment as _, WriteEnvironment,
+        DEFAULT_ENV_NAME, OverwritePolicy, ReadEnvironment as _, WriteEnvironment,
         local_directory::{
             LocalDirectoryEnvironment, LocalReadError, LocalWriteError, metadata::EnvMetadataError,
         },
@@ -26,9 +36,11 @@ use sysand_core::{
     },
     exclude::do_exclude,
     include::do_include,
-    info::{InfoError, InfoProjectError, do_info, do_info_project},
+    info::{InfoProjectError, do_info_project},
     init::InitError,
-    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
+    iri_normalize::{iris_equivalent, normalize_iri},
+    model::{HashAlg, InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
+    progress::{self, ProgressEvent},
     project::{
         ProjectRead as _,
         local_kpar::{KparInnerPath, LocalKParProject},
@@ -36,11 +48,12 @@ use sysand_core::{
         utils::wrapfs,
     },
     remove::do_remove_guess,
-    resolve::{net_utils::create_reqwest_client, standard::standard_resolver},
+    session::Session,
     sources::{do_sources_local_src_project_no_deps, find_project_dependencies},
     stdlib::known_std_libs,
     symbols::Language,
     utils::format_err,
+    workspace::{Workspace, WorkspaceReadError},
 };
 use typed_path::Utf8UnixPathBuf;
 
@@ -114,6 +127,9 @@ fn do_env_py_local_dir(path: String) -> PyResult<()> {
                 LocalWriteError::MissingMeta | LocalWriteError::MissingInfoMeta => {
                     PyFileNotFoundError::new_err(e)
                 }
+                LocalWriteError::ReadOnly(_) => PyPermissionError::new_err(e),
+                LocalWriteError::Archive(_) => PyIOError::new_err(e),
+                LocalWriteError::Encryption(_) => PyIOError::new_err(e),
             },
         }
     })?;
@@ -160,13 +176,9 @@ fn do_info_py(
     let _ = pyo3_log::try_init();
 
     py.detach(|| {
-        let client = create_reqwest_client().map_err(|e| PyRuntimeError::new_err(format_err(e)))?;
-
-        let runtime = Arc::new(
-            tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()?,
-        );
+        // Proxy, CA and client certificates are configured like for the CLI
+        let config = load_configs(&relative_file_root)
+            .map_err(|e| PyRuntimeError::new_err(format_err(e)))?;
 
         let index_url = index_urls
             .map(|url_strs| {
@@ -178,37 +190,58 @@ fn do_info_py(
             .transpose()
             .map_err(|err| PyValueError::new_err(format_err(err)))?;
 
-        let combined_resolver = standard_resolver(
-            Some(relative_file_root.into()),
-            None,
-            Some(client),
-            index_url,
-            runtime,
-            // FIXME: Add Python support for authentication
-            Arc::new(Unauthenticated {}),
+        // FIXME: Add Python support for authentication
+        let session = Session::new(
+            config,
+            ProjectContext {
+                current_directory: relative_file_root.into(),
+                ..Default::default()
+            },
         )
-        .map_err(|err| PyValueError::new_err(format_err(err)))?;
-
-        match do_info(&uri, &combined_resolver) {
-            Ok(info_meta) => Ok(info_meta),
-            Err(
-                e @ (InfoError::NoSemanticVersionsFound(_)
-                | InfoError::NoResolve(..)
-                | InfoError::UnsupportedIri(..)
-                | InfoError::Resolution(_)),
-            ) => Err(PyRuntimeError::new_err(format_err(e))),
-        }
+        .map_err(|err| PyValueError::new_err(format_err(err)))?
+        .with_index_urls(index_url);
+
+        session
+            .info(&uri)
+            .map_err(|e| PyRuntimeError::new_err(format_err(e)))
     })
 }
 
+/// Run `f`, calling `callback` with `(phase, current, total, message)` for
+/// each progress event. The first exception raised by `callback` is
+/// returned after `f` finishes
+fn with_progress_callback<R>(callback: Option<Py<PyAny>>, f: impl FnOnce() -> R) -> PyResult<R> {
+    let Some(callback) = callback else {
+        return Ok(f());
+    };
+    let error: Arc<Mutex<Option<PyErr>>> = Arc::default();
+    let listener_error = error.clone();
+    let listener = move |e: &ProgressEvent| {
+        Python::attach(|py| {
+            if let Err(err) = callback.call1(
+                py,
+                (e.phase.as_str(), e.current, e.total, e.message.as_str()),
+            ) {
+                listener_error.lock().unwrap().get_or_insert(err);
+            }
+        })
+    };
+    let result = progress::with_listener(listener, f);
+    match error.lock().unwrap().take() {
+        Some(err) => Err(err),
+        None => Ok(result),
+    }
+}
+
 #[pyfunction(name = "do_build_py")]
 #[pyo3(
-    signature = (output_path, project_path, compression),
+    signature = (output_path, project_path, compression, progress=None),
 )]
 fn do_build_py(
     output_path: String,
     project_path: Option<String>,
     compression: Option<String>,
+    progress: Option<Py<PyAny>>,
 ) -> PyResult<()> {
     let _ = pyo3_log::try_init();
 
@@ -229,27 +262,35 @@ fn do_build_py(
         None => KparCompressionMethod::default(),
     };
 
-    do_build_kpar(&project, &output_path, compression, true, true)
-        .map(|_| ())
-        .map_err(|err| {
-            let e = format_err(&err);
-            match err {
-                KParBuildError::ProjectRead(_) => PyRuntimeError::new_err(e),
-                KParBuildError::Io(_) => PyIOError::new_err(e),
-                KParBuildError::Validation { .. } => PyValueError::new_err(e),
-                KParBuildError::Extract(_) => PyValueError::new_err(e),
-                KParBuildError::UnknownFormat(_) => PyValueError::new_err(e),
-                KParBuildError::MissingInfo => PyValueError::new_err(e),
-                KParBuildError::MissingMeta => PyValueError::new_err(e),
-                KParBuildError::MissingInfoMeta => PyValueError::new_err(e),
-                KParBuildError::Zip(_) => PyIOError::new_err(e),
-                KParBuildError::Serialize(..) => PyValueError::new_err(e),
-                KParBuildError::WorkspaceRead(_) => PyRuntimeError::new_err(e),
-                KParBuildError::PathUsage(_) => PyValueError::new_err(e),
-                KParBuildError::WorkspaceMetamodelConflict { .. } => PyValueError::new_err(e),
-                KParBuildError::MissingIndexSymbol(_, _) => PyValueError::new_err(e),
-            }
-        })
+    with_progress_callback(progress, || {
+        do_build_kpar(&project, &output_path, compression, true, true)
+    })?
+    .map(|_| ())
+    .map_err(|err| {
+        let e = format_err(&err);
+        match err {
+            KParBuildError::ProjectRead(_) => PyRuntimeError::new_err(e),
+            KParBuildError::Io(_) => PyIOError::new_err(e),
+            KParBuildError::Validation { .. } => PyValueError::new_err(e),
+            KParBuildError::Extract(_) => PyValueError::new_err(e),
+            KParBuildError::UnknownFormat(_) => PyValueError::new_err(e),
+            KParBuildError::MissingInfo => PyValueError::new_err(e),
+            KParBuildError::MissingMeta => PyValueError::new_err(e),
+            KParBuildError::MissingInfoMeta => PyValueError::new_err(e),
+            KParBuildError::Zip(_) => PyIOError::new_err(e),
+            KParBuildError::Serialize(..) => PyValueError::new_err(e),
+            KParBuildError::WorkspaceRead(_) => PyRuntimeError::new_err(e),
+            KParBuildError::PathUsage(_) => PyValueError::new_err(e),
+            KParBuildError::WorkspaceMetamodelConflict { .. } => PyValueError::new_err(e),
+            KParBuildError::MissingIndexSymbol(_, _) => PyValueError::new_err(e),
+            KParBuildError::PostProcess { .. } => PyRuntimeError::new_err(e),
+            KParBuildError::InvalidGlob(..) => PyValueError::new_err(e),
+            KParBuildError::IncludeWithoutRoot => PyValueError::new_err(e),
+            KParBuildError::Dirty { .. } => PyValueError::new_err(e),
+            KParBuildError::NotReproducible { .. } => PyRuntimeError::new_err(e),
+            KParBuildError::MissingVariantPath { .. } => PyValueError::new_err(e),
+        }
+    })
 }
 
 #[pyfunction(name = "do_sources_env_py")]
@@ -509,7 +550,7 @@ fn do_include_py(
     do_include(
         &mut project,
         iter::once(Utf8UnixPathBuf::from(src_path)),
-        compute_checksum,
+        compute_checksum.then_some(HashAlg::Sha256),
         index_symbols,
         force_format,
     )
@@ -566,9 +607,13 @@ fn do_env_install_path_py(env_path: String, iri: String, location: String) -> Py
         let checksum = project
             .checksum_canonical_variant()
             .map_err(|e| PyRuntimeError::new_err(format_err(e)))?;
-        env.put_project(iri, version, Some(checksum), |to| {
-            clone_project(&project, to, true).map(|_| ())
-        })
+        env.put_project(
+            iri,
+            version,
+            Some(checksum),
+            OverwritePolicy::Replace,
+            |to| clone_project(&project, to, true).map(|_| ()),
+        )
         .map_err(|e| PyRuntimeError::new_err(format_err(e)))?;
     } else if metadata.is_dir() {
         let project = LocalSrcProject {
@@ -590,9 +635,13 @@ fn do_env_install_path_py(env_path: String, iri: String, location: String) -> Py
             .checksum_canonical_variant()
             .map_err(|e| PyRuntimeError::new_err(format_err(e)))?;
 
-        env.put_project(iri, version, Some(checksum), |to| {
-            clone_project(&project, to, true).map(|_| ())
-        })
+        env.put_project(
+            iri,
+            version,
+            Some(checksum),
+            OverwritePolicy::Replace,
+            |to| clone_project(&project, to, true).map(|_| ()),
+        )
         .map_err(|e| PyRuntimeError::new_err(format_err(e)))?;
     } else {
         return Err(PyRuntimeError::new_err(format!(
@@ -603,6 +652,122 @@ fn do_env_install_path_py(env_path: String, iri: String, location: String) -> Py
     Ok(())
 }
 
+/// Root of the project containing `path`, searching at most `max_depth`
+/// parent directories (all of them if `None`)
+#[pyfunction(name = "discover_project_py")]
+#[pyo3(
+    signature = (path, max_depth),
+)]
+fn discover_project_py(path: String, max_depth: Option<usize>) -> PyResult<Option<String>> {
+    let _ = pyo3_log::try_init();
+
+    let project = discover_project_within(Utf8PathBuf::from(path), max_depth)
+        .map_err(|e| PyIOError::new_err(format_err(e)))?;
+
+    Ok(project.map(|p| p.project_path.into_string()))
+}
+
+/// Root of the workspace containing `path`
+#[pyfunction(name = "discover_workspace_py")]
+#[pyo3(
+    signature = (path),
+)]
+fn discover_workspace_py(path: String) -> PyResult<Option<String>> {
+    let _ = pyo3_log::try_init();
+
+    let workspace = discover_workspace(Utf8PathBuf::from(path)).map_err(workspace_read_to_pyerr)?;
+
+    Ok(workspace.map(|w| w.root_path().to_string()))
+}
+
+/// Projects in `path` and its subdirectories, as `(path, parent)` pairs,
+/// together with the workspaces found and the `(path, reason)` pairs of
+/// directories that were not searched. Paths are relative to `path`
+#[pyfunction(name = "walk_projects_py")]
+#[pyo3(
+    signature = (path, max_depth, nested, hidden),
+)]
+#[expect(clippy::type_complexity)]
+fn walk_projects_py(
+    path: String,
+    max_depth: Option<usize>,
+    nested: bool,
+    hidden: bool,
+) -> PyResult<(
+    Vec<(String, Option<String>)>,
+    Vec<String>,
+    Vec<(String, String)>,
+)> {
+    let _ = pyo3_log::try_init();
+
+    let options = WalkOptions {
+        max_depth,
+        nested,
+        hidden,
+    };
+    let report = walk_projects(Utf8PathBuf::from(path), &options)
+        .map_err(|e| PyIOError::new_err(format_err(e)))?;
+
+    Ok((
+        report
+            .projects
+            .into_iter()
+            .map(|p| (p.path.into_string(), p.parent.map(|p| p.into_string())))
+            .collect(),
+        report
+            .workspaces
+            .into_iter()
+            .map(|p| p.into_string())
+            .collect(),
+        report
+            .stops
+            .into_iter()
+            .map(|s| (s.path.into_string(), s.reason.to_string()))
+            .collect(),
+    ))
+}
+
+/// Projects of the workspace rooted at `path`, as `(path, IRIs)` pairs.
+/// Paths are relative to the workspace root
+#[pyfunction(name = "workspace_projects_py")]
+#[pyo3(
+    signature = (path),
+)]
+fn workspace_projects_py(path: String) -> PyResult<Vec<(String, Vec<String>)>> {
+    let _ = pyo3_log::try_init();
+
+    let workspace = Workspace::new(path.into()).map_err(workspace_read_to_pyerr)?;
+
+    Ok(workspace
+        .projects()
+        .iter()
+        .map(|project| {
+            (
+                project.path.clone(),
+                project.iris.iter().map(|iri| iri.to_string()).collect(),
+            )
+        })
+        .collect())
+}
+
+/// Canonical form of `iri`, see [`normalize_iri`]
+#[pyfunction(name = "normalize_iri_py")]
+#[pyo3(
+    signature = (iri),
+)]
+fn normalize_iri_py(iri: String) -> PyResult<String> {
+    normalize_iri(&iri).map_err(|err| PyValueError::new_err(format_err(&err)))
+}
+
+/// Whether `a` and `b` identify the same project, see [`iris_equivalent`]
+#[pyfunction(name = "iris_equivalent_py")]
+#[pyo3(
+    signature = (a, b),
+)]
+fn iris_equivalent_py(a: String, b: String) -> bool {
+    iris_equivalent(&a, &b)
+}
+
 #[pymodule(name = "_sysand_core")]
 pub fn sysand_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(run_cli, m)?)?;
@@ -618,6 +783,12 @@ pub fn sysand_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(do_include_py, m)?)?;
     m.add_function(wrap_pyfunction!(do_exclude_py, m)?)?;
     m.add_function(wrap_pyfunction!(do_env_install_path_py, m)?)?;
+    m.add_function(wrap_pyfunction!(discover_project_py, m)?)?;
+    m.add_function(wrap_pyfunction!(discover_workspace_py, m)?)?;
+    m.add_function(wrap_pyfunction!(walk_projects_py, m)?)?;
+    m.add_function(wrap_pyfunction!(workspace_projects_py, m)?)?;
+    m.add_function(wrap_pyfunction!(normalize_iri_py, m)?)?;
+    m.add_function(wrap_pyfunction!(iris_equivalent_py, m)?)?;
     // Currently this interop is done with strings instead
     // m.add_class::<KparCompressionMethod>()?;
 
@@ -631,3 +802,12 @@ fn env_read_to_pyerr(err: EnvMetadataError) -> PyErr {
         format_err(err)
     ))
 }
+
+fn workspace_read_to_pyerr(err: WorkspaceReadError) -> PyErr {
+    let e = format_err(&err);
+    match err {
+        WorkspaceReadError::Io(_) => PyIOError::new_err(e),
+        WorkspaceReadError::Deserialize(_) => PyValueError::new_err(e),
+        WorkspaceReadError::Validation(..) => PyValueError::new_err(e),
+    }
+}
@@ -161,7 +161,7 @@ def test_index_info(caplog: pytest.LogCaptureFixture, httpserver: HTTPServer) ->
     httpserver.expect_request("/sysand-index-config.json").respond_with_data(
         "", status=404
     )
-    iri_dir = "/_iri/19148b59a7f258e6eab15189ebcc5b6f884e02690a3b27f3f43e4c6e15dd9536"
+    iri_dir = "/_kpar/test_index_info"
     httpserver.expect_request(f"{iri_dir}/versions.json").respond_with_json(
         {
             "versions": [
@@ -295,3 +295,67 @@ def test_build(compression: Union[sysand.CompressionMethod, None]) -> None:
             project_path=tmp_main,
             compression=compression,
         )
+
+
+def test_build_progress() -> None:
+    with tempfile.TemporaryDirectory() as tmp_main:
+        tmp_main = Path(tmp_main).resolve()
+        sysand.init("test_build_progress", "a", "1.2.3", tmp_main)
+        for name in ["a.sysml", "b.sysml"]:
+            with open(tmp_main / name, "w") as f:
+                f.write("package P;")
+            sysand.include(tmp_main, name)
+
+        events: List[sysand.ProgressEvent] = []
+        sysand.build(
+            output_path=tmp_main / "test_build.kpar",
+            project_path=tmp_main,
+            progress=events.append,
+        )
+
+        assert events == [
+            sysand.ProgressEvent("build", 1, 2, "a.sysml"),
+            sysand.ProgressEvent("build", 2, 2, "b.sysml"),
+        ]
+
+
+def test_discover() -> None:
+    with tempfile.TemporaryDirectory() as tmp_main:
+        tmp_main = Path(tmp_main).resolve()
+        with open(tmp_main / ".workspace.json", "w") as f:
+            f.write(
+                '{"projects": [{"path": "project1", "iris": ["urn:kpar:project1"]}, {"path": "group/project2", "iris": []}]}'
+            )
+        (tmp_main / "project1" / "nested").mkdir(parents=True)
+        sysand.init("project1", "a", "1.2.3", tmp_main / "project1")
+
+        assert sysand.discover_project(tmp_main / "project1" / "nested") == (
+            tmp_main / "project1"
+        )
+        assert (
+            sysand.discover_project(tmp_main / "project1" / "nested", max_depth=0)
+            is None
+        )
+        assert sysand.discover_project(tmp_main) is None
+        assert (
+            sysand.discover_workspace(tmp_main / "project1" / "nested") == tmp_main
+        )
+        assert sysand.workspace_projects(tmp_main) == [
+            sysand.WorkspaceProject(Path("project1"), ["urn:kpar:project1"]),
+            sysand.WorkspaceProject(Path("group/project2"), []),
+        ]
+        assert sysand.walk_projects(tmp_main) == sysand.WalkResult(
+            [sysand.WalkedProject(Path("project1"), None)], [Path(".")], []
+        )
+
+
+def test_iri_normalization() -> None:
+    assert (
+        sysand.normalize_iri("HTTP://Example.com:80/a/./b") == "http://example.com/a/b"
+    )
+    assert sysand.iris_equivalent("http://example.com/x", "https://EXAMPLE.com/x/")
+    assert not sysand.iris_equivalent(
+        "http://example.com/x", "http://example.com/y"
+    )
+    with pytest.raises(ValueError):
+        sysand.normalize_iri("not an iri")
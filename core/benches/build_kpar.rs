@@ -0,0 +1,96 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Builds a KPAR from a project with large synthetic source files and reports
+//! the throughput and peak memory use.
+//!
+//! Run with `cargo bench -p sysand-core --features filesystem --bench build_kpar`.
+//! The total size of the sources in MiB can be set with `SYSAND_BENCH_MIB`
+//! (default 256).
+
+use std::{
+    io::{BufWriter, Write},
+    time::Instant,
+};
+
+use camino::Utf8Path;
+use camino_tempfile::tempdir;
+use sysand_core::{
+    build::{KparCompressionMethod, do_build_kpar},
+    include::do_include,
+    init::do_init,
+    model::HashAlg,
+    project::local_src::LocalSrcProject,
+};
+
+const FILES: usize = 4;
+
+fn write_source(path: &Utf8Path, index: usize, size: usize) {
+    let mut file = BufWriter::new(std::fs::File::create(path).unwrap());
+    writeln!(file, "package Large{index} {{").unwrap();
+    let mut written = 0;
+    let mut i = 0;
+    while written < size {
+        let line =
+            format!("    part def Part{i} {{ doc /* generated */ attribute value = \"{i}\"; }}\n");
+        file.write_all(line.as_bytes()).unwrap();
+        written += line.len();
+        i += 1;
+    }
+    writeln!(file, "}}").unwrap();
+}
+
+/// Peak resident set size of this process, where it is known
+fn peak_memory() -> Option<String> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|l| l.strip_prefix("VmHWM:"))
+        .map(|v| v.trim().to_owned())
+}
+
+fn main() {
+    let mib: usize = std::env::var("SYSAND_BENCH_MIB")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(256);
+    let tmp = tempdir().unwrap();
+    let root = tmp.path().join("project");
+    std::fs::create_dir(&root).unwrap();
+    let mut project = LocalSrcProject {
+        nominal_path: None,
+        project_path: root.clone(),
+        expected_checksum: None,
+    };
+    do_init("large".into(), None, "1.0.0".into(), None, &mut project).unwrap();
+    let names: Vec<String> = (0..FILES).map(|i| format!("large{i}.sysml")).collect();
+    for (i, name) in names.iter().enumerate() {
+        write_source(&root.join(name), i, mib * 1024 * 1024 / FILES);
+    }
+    do_include(
+        &mut project,
+        names.iter().map(|n| n.as_str().into()),
+        Some(HashAlg::Sha256),
+        true,
+        None,
+    )
+    .unwrap();
+
+    for compression in [
+        KparCompressionMethod::Stored,
+        KparCompressionMethod::Deflated,
+    ] {
+        let kpar_path = tmp.path().join("large.kpar");
+        let start = Instant::now();
+        do_build_kpar(&project, &kpar_path, compression, true, false).unwrap();
+        let elapsed = start.elapsed();
+        println!(
+            "build_kpar/{compression:?}: {mib} MiB in {:.2?} ({:.1} MiB/s)",
+            elapsed,
+            mib as f64 / elapsed.as_secs_f64()
+        );
+    }
+    if let Some(peak) = peak_memory() {
+        println!("peak memory: {peak}");
+    }
+}
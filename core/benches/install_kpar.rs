@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Copies the project in a KPAR with large synthetic source files into a
+//! directory, once through `clone_project` (extracting each file to a
+//! temporary file first) and once streaming with `extract_into`, and reports
+//! the throughput of both.
+//!
+//! Run with `cargo bench -p sysand-core --features filesystem --bench install_kpar`.
+//! The total size of the sources in MiB can be set with `SYSAND_BENCH_MIB`
+//! (default 256).
+
+use std::{
+    io::{BufWriter, Write},
+    time::Instant,
+};
+
+use camino::Utf8Path;
+use camino_tempfile::tempdir;
+use sysand_core::{
+    build::{KparCompressionMethod, do_build_kpar},
+    env::utils::clone_project,
+    include::do_include,
+    init::do_init,
+    model::HashAlg,
+    project::{
+        local_kpar::{KparInnerPath, LocalKParProject},
+        local_src::LocalSrcProject,
+    },
+};
+
+const FILES: usize = 4;
+
+fn write_source(path: &Utf8Path, index: usize, size: usize) {
+    let mut file = BufWriter::new(std::fs::File::create(path).unwrap());
+    writeln!(file, "package Large{index} {{").unwrap();
+    let mut written = 0;
+    let mut i = 0;
+    while written < size {
+        let line =
+            format!("    part def Part{i} {{ doc /* generated */ attribute value = \"{i}\"; }}\n");
+        file.write_all(line.as_bytes()).unwrap();
+        written += line.len();
+        i += 1;
+    }
+    writeln!(file, "}}").unwrap();
+}
+
+fn target(path: &Utf8Path) -> LocalSrcProject {
+    std::fs::create_dir(path).unwrap();
+    LocalSrcProject {
+        nominal_path: None,
+        project_path: path.to_owned(),
+        expected_checksum: None,
+    }
+}
+
+fn main() {
+    let mib: usize = std::env::var("SYSAND_BENCH_MIB")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(256);
+    let tmp = tempdir().unwrap();
+    let root = tmp.path().join("project");
+    let mut project = target(&root);
+    do_init("large".into(), None, "1.0.0".into(), None, &mut project).unwrap();
+    let names: Vec<String> = (0..FILES).map(|i| format!("large{i}.sysml")).collect();
+    for (i, name) in names.iter().enumerate() {
+        write_source(&root.join(name), i, mib * 1024 * 1024 / FILES);
+    }
+    do_include(
+        &mut project,
+        names.iter().map(|n| n.as_str().into()),
+        Some(HashAlg::Sha256),
+        true,
+        None,
+    )
+    .unwrap();
+    let kpar_path = tmp.path().join("large.kpar");
+    do_build_kpar(
+        &project,
+        &kpar_path,
+        KparCompressionMethod::Deflated,
+        true,
+        false,
+    )
+    .unwrap();
+
+    let report = |name: &str, start: Instant| {
+        let elapsed = start.elapsed();
+        println!(
+            "install_kpar/{name}: {mib} MiB in {:.2?} ({:.1} MiB/s)",
+            elapsed,
+            mib as f64 / elapsed.as_secs_f64()
+        );
+    };
+
+    let kpar = LocalKParProject::new(&kpar_path, KparInnerPath::Guess, None, None);
+    let mut to = target(&tmp.path().join("cloned"));
+    let start = Instant::now();
+    clone_project(&kpar, &mut to, true).unwrap();
+    report("clone_project", start);
+
+    let kpar = LocalKParProject::new(&kpar_path, KparInnerPath::Guess, None, None);
+    let mut to = target(&tmp.path().join("extracted"));
+    let start = Instant::now();
+    kpar.extract_into(&mut to, true).unwrap();
+    report("extract_into", start);
+}
@@ -0,0 +1,88 @@
+#!/bin/bash
+
+# SPDX-License-Identifier: MIT OR Apache-2.0
+# SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+# Regenerate or check the public API snapshot of sysand-core in
+# `core/public-api.txt`. Requires `cargo-public-api` and a nightly toolchain.
+#
+# Usage:
+#   public_api.sh                   regenerate the snapshot
+#   public_api.sh --check           fail if the snapshot is out of date
+#   public_api.sh --check BASE_REF  also fail if the API changed relative to
+#                                   BASE_REF without a sufficient API_VERSION
+#                                   bump (removals are breaking, additions are
+#                                   compatible)
+
+set -eu
+
+# Compute the root directory based on the location of this script.
+SCRIPT_DIR=$(dirname "$(realpath "$0")")
+PACKAGE_DIR=$(dirname "$SCRIPT_DIR")
+
+cd "$PACKAGE_DIR"
+
+SNAPSHOT=public-api.txt
+# Features that are part of the published API; binding support is excluded
+FEATURES=filesystem,networking,encryption,json-schema
+
+api_version() {
+    sed -n 's/^pub const API_VERSION: &str = "\(.*\)";$/\1/p' src/lib.rs
+}
+
+generate() {
+    echo "# sysand-core API_VERSION $(api_version)"
+    cargo public-api --package sysand-core --features "$FEATURES" -ss
+}
+
+if [ "${1:-}" != "--check" ]; then
+    generate >"$SNAPSHOT"
+    exit 0
+fi
+
+current=$(mktemp)
+trap 'rm -f "$current"' EXIT
+generate >"$current"
+if ! diff -u "$SNAPSHOT" "$current"; then
+    echo "error: $SNAPSHOT is out of date, run core/scripts/public_api.sh" >&2
+    exit 1
+fi
+
+BASE_REF=${2:-}
+if [ -z "$BASE_REF" ]; then
+    exit 0
+fi
+if ! base_snapshot=$(git show "$BASE_REF:core/$SNAPSHOT" 2>/dev/null); then
+    echo "no $SNAPSHOT at $BASE_REF, skipping version check"
+    exit 0
+fi
+
+old_version=$(head -n 1 <<<"$base_snapshot" | awk '{ print $4 }')
+new_version=$(api_version)
+api_diff=$(diff <(tail -n +2 <<<"$base_snapshot") <(tail -n +2 "$SNAPSHOT") || true)
+
+IFS=. read -r major minor patch <<<"$old_version"
+if grep -q '^<' <<<"$api_diff"; then
+    kind=breaking
+    if [ "$major" -eq 0 ]; then
+        required="0.$((minor + 1)).0"
+    else
+        required="$((major + 1)).0.0"
+    fi
+elif grep -q '^>' <<<"$api_diff"; then
+    kind=compatible
+    if [ "$major" -eq 0 ]; then
+        required="0.$minor.$((patch + 1))"
+    else
+        required="$major.$((minor + 1)).0"
+    fi
+else
+    exit 0
+fi
+
+lowest=$(printf '%s\n%s\n' "$required" "$new_version" | sort -V | head -n 1)
+if [ "$lowest" != "$required" ]; then
+    echo "$api_diff"
+    echo "error: $kind public API change requires API_VERSION >= $required (was $old_version, is $new_version)" >&2
+    exit 1
+fi
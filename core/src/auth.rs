@@ -3,9 +3,18 @@
 
 //! This module includes utilities for creating and using authentication policies for requests.
 
+use std::{
+    fmt,
+    io::Write,
+    process::{Command, ExitStatus, Stdio},
+    sync::Arc,
+};
+
 use globset::{GlobBuilder, GlobSetBuilder};
-use reqwest::{Response, header};
+use reqwest::{Response, StatusCode, header};
 use reqwest_middleware::{ClientWithMiddleware, RequestBuilder};
+use serde::Deserialize;
+use tokio::sync::OnceCell;
 
 pub trait HTTPAuthentication: std::fmt::Debug + 'static {
     /// Tries to execute a request with some authentication policy. The request might be retried
@@ -97,11 +106,29 @@ impl HTTPAuthentication for ForceHTTPBasicAuth {
 
 /// Authentication policy that *always* includes a bearer token
 #[derive(Debug, Clone)]
-pub struct ForceBearerAuth(Box<str>);
+pub struct ForceBearerAuth(BearerToken);
+
+#[derive(Debug, Clone)]
+enum BearerToken {
+    Static(Box<str>),
+    Helper(CredentialHelper),
+}
 
 impl ForceBearerAuth {
     pub fn new<S: AsRef<str>>(token: S) -> ForceBearerAuth {
-        Self(token.as_ref().into())
+        Self(BearerToken::Static(token.as_ref().into()))
+    }
+
+    /// Bearer token obtained from `helper` when it is first needed
+    pub fn from_helper(helper: CredentialHelper) -> ForceBearerAuth {
+        Self(BearerToken::Helper(helper))
+    }
+
+    pub async fn token(&self) -> Result<Box<str>, CredentialHelperError> {
+        match &self.0 {
+            BearerToken::Static(token) => Ok(token.clone()),
+            BearerToken::Helper(helper) => helper.token().await,
+        }
     }
 }
 
@@ -114,7 +141,11 @@ impl HTTPAuthentication for ForceBearerAuth {
     where
         F: Fn(&ClientWithMiddleware) -> RequestBuilder + 'static,
     {
-        let (client, req) = request.bearer_auth(&self.0).build_split();
+        let token = self
+            .token()
+            .await
+            .map_err(reqwest_middleware::Error::middleware)?;
+        let (client, req) = request.bearer_auth(token).build_split();
         let req = req?;
         log::debug!("{} (bearer auth) `{}`", req.method(), req.url());
 
@@ -129,6 +160,125 @@ impl HTTPAuthentication for ForceBearerAuth {
     }
 }
 
+/// External program, run by the shell, that prints a short-lived bearer
+/// token, e.g. `gcloud auth print-access-token`. The URL of the index is
+/// written to its standard input, so Docker credential helpers, which print
+/// a JSON object with the token in `Secret`, can be used too.
+///
+/// The program is run when a token is first needed and the token is reused
+/// by all clones of the helper, i.e. for the rest of the command
+#[derive(Debug, Clone)]
+pub struct CredentialHelper {
+    command: Box<str>,
+    url: Box<str>,
+    token: Arc<OnceCell<Box<str>>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CredentialHelperError {
+    #[error("failed to run credential helper `{command}`: {source}")]
+    Run {
+        command: Box<str>,
+        source: std::io::Error,
+    },
+    #[error("credential helper `{command}` failed with {status}")]
+    Failed {
+        command: Box<str>,
+        status: ExitStatus,
+    },
+    #[error("credential helper `{command}` printed no token")]
+    NoToken { command: Box<str> },
+}
+
+impl CredentialHelper {
+    pub fn new<S: AsRef<str>, T: AsRef<str>>(command: S, url: T) -> CredentialHelper {
+        Self {
+            command: command.as_ref().into(),
+            url: url.as_ref().into(),
+            token: Arc::default(),
+        }
+    }
+
+    /// Token printed by the helper, running it if no token was obtained yet.
+    /// Concurrent callers wait for a single run of the helper, which happens
+    /// on the blocking thread pool of the runtime
+    pub async fn token(&self) -> Result<Box<str>, CredentialHelperError> {
+        self.token
+            .get_or_try_init(|| {
+                let helper = self.clone();
+                async move {
+                    tokio::task::spawn_blocking(move || helper.run())
+                        .await
+                        .map_err(|err| CredentialHelperError::Run {
+                            command: self.command.clone(),
+                            source: std::io::Error::other(err),
+                        })?
+                }
+            })
+            .await
+            .cloned()
+    }
+
+    fn run(&self) -> Result<Box<str>, CredentialHelperError> {
+        log::debug!(
+            "auth: running credential helper `{}` for `{}`",
+            self.command,
+            self.url
+        );
+        let run_error = |source| CredentialHelperError::Run {
+            command: self.command.clone(),
+            source,
+        };
+        let mut command = if cfg!(windows) {
+            let mut command = Command::new("cmd");
+            command.args(["/C", &self.command]);
+            command
+        } else {
+            let mut command = Command::new("sh");
+            command.args(["-c", &self.command]);
+            command
+        };
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(run_error)?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // Helpers that do not need the URL may exit without reading it
+            let _ = writeln!(stdin, "{}", self.url);
+        }
+        let output = child.wait_with_output().map_err(run_error)?;
+        if !output.status.success() {
+            return Err(CredentialHelperError::Failed {
+                command: self.command.clone(),
+                status: output.status,
+            });
+        }
+        parse_helper_output(&String::from_utf8_lossy(&output.stdout)).ok_or_else(|| {
+            CredentialHelperError::NoToken {
+                command: self.command.clone(),
+            }
+        })
+    }
+}
+
+/// Token in the output of a credential helper, either the whole output or
+/// the `Secret` of a Docker credential helper
+fn parse_helper_output(stdout: &str) -> Option<Box<str>> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct DockerCredential {
+        secret: String,
+    }
+
+    let token = match serde_json::from_str::<DockerCredential>(stdout) {
+        Ok(credential) => credential.secret,
+        Err(_) => stdout.trim().to_string(),
+    };
+    (!token.is_empty()).then(|| token.into())
+}
+
 /// First tries `Higher` priority authentication and then the
 /// `Lower` priority one in case the first request results in
 /// a response in the 4xx range.
@@ -483,11 +633,123 @@ impl StandardHTTPAuthenticationBuilder {
         );
     }
 
+    /// Authenticate requests to `globstr` with bearer tokens obtained from
+    /// `helper`
+    pub fn add_credential_helper<S: AsRef<str>>(&mut self, globstr: S, helper: CredentialHelper) {
+        self.partial.add(
+            globstr,
+            SequenceAuthentication {
+                higher: Unauthenticated {},
+                lower: StandardInnerAuthentication::BearerAuth(ForceBearerAuth::from_helper(
+                    helper,
+                )),
+            },
+        );
+    }
+
     // TODO: For other authentication schemes
     // pub fn add_..._auth<S: AsRef<str>, ...>(&self, globstr: S, ...)
 }
 
 // pub struct GlobsetAuth
+
+/// Permission that an index server can grant to credentials
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Permission {
+    Read,
+    Publish,
+    Yank,
+    Admin,
+    /// Permission not known to this version of sysand
+    Other(Box<str>),
+}
+
+impl From<&str> for Permission {
+    fn from(value: &str) -> Self {
+        match value {
+            "read" => Self::Read,
+            "publish" => Self::Publish,
+            "yank" => Self::Yank,
+            "admin" => Self::Admin,
+            other => Self::Other(other.into()),
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Read => "read",
+            Self::Publish => "publish",
+            Self::Yank => "yank",
+            Self::Admin => "admin",
+            Self::Other(p) => p,
+        })
+    }
+}
+
+/// Server-declared reason for rejecting a request, parsed from a 403 response
+/// body of the form `{"error": "...", "permission": "publish", "scope": "urn:corp:*"}`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionDenied {
+    /// Permission the credentials lack
+    pub permission: Permission,
+    /// Resources the permission is required for, as reported by the server
+    pub scope: Option<String>,
+    /// Human-readable message from the server
+    pub message: Option<String>,
+}
+
+impl PermissionDenied {
+    /// Parse a structured 403 response body. Returns `None` if the body does
+    /// not declare the missing permission.
+    pub fn from_response_body(body: &[u8]) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct Body {
+            permission: String,
+            scope: Option<String>,
+            error: Option<String>,
+        }
+
+        let body: Body = serde_json::from_slice(body).ok()?;
+        let non_empty = |s: Option<String>| s.filter(|s| !s.trim().is_empty());
+        Some(Self {
+            permission: body.permission.as_str().into(),
+            scope: non_empty(body.scope),
+            message: non_empty(body.error),
+        })
+    }
+}
+
+impl fmt::Display for PermissionDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "your credentials lack `{}` permission", self.permission)?;
+        if let Some(scope) = &self.scope {
+            write!(f, " on `{scope}`")?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, ": {message}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for PermissionDenied {}
+
+/// Suggestion to append to errors for HTTP statuses caused by missing or
+/// insufficient credentials. Empty for other statuses.
+pub fn auth_status_hint(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::UNAUTHORIZED => {
+            " (the server requires authentication; check that credentials are configured for this URL)"
+        }
+        StatusCode::FORBIDDEN => {
+            " (the configured credentials are not permitted to access this URL)"
+        }
+        _ => "",
+    }
+}
+
 #[cfg(test)]
 #[path = "./auth_tests.rs"]
 mod tests;
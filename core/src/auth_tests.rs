@@ -1,7 +1,12 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
 
-use crate::auth::{GlobMapBuilder, GlobMapResultMut};
+use reqwest::StatusCode;
+
+use crate::auth::{
+    CredentialHelper, GlobMapBuilder, GlobMapResultMut, Permission, PermissionDenied,
+    auth_status_hint, parse_helper_output,
+};
 
 #[test]
 fn basic_globmap_lookup() -> Result<(), Box<dyn std::error::Error>> {
@@ -59,3 +64,98 @@ fn basic_globmap_lookup() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn permission_denied_from_structured_body() {
+    let denied = PermissionDenied::from_response_body(
+        br#"{"error": "read-only token", "permission": "publish", "scope": "urn:corp:*"}"#,
+    )
+    .unwrap();
+    assert_eq!(denied.permission, Permission::Publish);
+    assert_eq!(denied.scope.as_deref(), Some("urn:corp:*"));
+    assert_eq!(
+        denied.to_string(),
+        "your credentials lack `publish` permission on `urn:corp:*`: read-only token"
+    );
+}
+
+#[test]
+fn permission_denied_keeps_unknown_permission() {
+    let denied =
+        PermissionDenied::from_response_body(br#"{"permission": "mirror", "scope": ""}"#).unwrap();
+    assert_eq!(denied.permission, Permission::Other("mirror".into()));
+    assert_eq!(denied.scope, None);
+    assert_eq!(
+        denied.to_string(),
+        "your credentials lack `mirror` permission"
+    );
+}
+
+#[test]
+fn permission_denied_requires_permission_field() {
+    assert_eq!(
+        PermissionDenied::from_response_body(br#"{"error": "forbidden"}"#),
+        None
+    );
+    assert_eq!(PermissionDenied::from_response_body(b"forbidden"), None);
+}
+
+#[test]
+fn auth_status_hint_only_for_auth_statuses() {
+    assert!(!auth_status_hint(StatusCode::UNAUTHORIZED).is_empty());
+    assert!(!auth_status_hint(StatusCode::FORBIDDEN).is_empty());
+    assert!(auth_status_hint(StatusCode::NOT_FOUND).is_empty());
+}
+
+#[test]
+fn credential_helper_output() {
+    assert_eq!(parse_helper_output("token\n").as_deref(), Some("token"));
+    assert_eq!(
+        parse_helper_output(
+            r#"{"ServerURL":"https://index.corp","Username":"_token","Secret":"secret"}"#
+        )
+        .as_deref(),
+        Some("secret")
+    );
+    assert_eq!(parse_helper_output(" \n"), None);
+}
+
+#[cfg(unix)]
+#[test]
+fn credential_helper_runs_once() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = camino_tempfile::tempdir()?;
+    let runs = dir.path().join("runs");
+    let helper = CredentialHelper::new(
+        format!("read url; echo \"$url\" >> '{runs}'; echo token"),
+        "https://index.corp",
+    );
+    let rt = tokio::runtime::Builder::new_current_thread().build()?;
+    let (first, second) = rt.block_on(futures::future::join(
+        helper.clone().token(),
+        helper.token(),
+    ));
+    assert_eq!(&*first?, "token");
+    assert_eq!(&*second?, "token");
+    assert_eq!(&*rt.block_on(helper.token())?, "token");
+    assert_eq!(std::fs::read_to_string(&runs)?, "https://index.corp\n");
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn credential_helper_failure_is_not_cached() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = camino_tempfile::tempdir()?;
+    let runs = dir.path().join("runs");
+    let rt = tokio::runtime::Builder::new_current_thread().build()?;
+    let helper = CredentialHelper::new(
+        format!("read url; echo \"$url\" >> '{runs}'; exit 1"),
+        "https://index.corp",
+    );
+    assert!(rt.block_on(helper.token()).is_err());
+    assert!(rt.block_on(helper.token()).is_err());
+    assert_eq!(
+        std::fs::read_to_string(&runs)?,
+        "https://index.corp\nhttps://index.corp\n"
+    );
+    Ok(())
+}
@@ -3,6 +3,8 @@
 use thiserror::Error;
 
 use crate::{
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    iri_normalize::{iris_equivalent, normalize_iri_tolerant},
     model::{
         InterchangeProjectUsageG, InterchangeProjectUsageRaw, InterchangeProjectValidationError,
     },
@@ -22,6 +24,16 @@ pub enum AddError<ProjectError> {
     MissingInfo(&'static str),
 }
 
+impl<ProjectError> HasErrorCode for AddError<ProjectError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            AddError::Project(_) => ErrorCode::new(201, ErrorClass::Project),
+            AddError::Validation(_) => ErrorCode::new(202, ErrorClass::Project),
+            AddError::MissingInfo(_) => ErrorCode::new(203, ErrorClass::Project),
+        }
+    }
+}
+
 /// If `resource` is of shape `publisher/name`, and both satisfy Sysand PURL
 /// rules, return `Ok(Some(pkg:sysand/publisher/name))`. Otherwise, if it's of shape
 /// `string1/string2` and does not contain `:`, return error. If none of these,
@@ -66,6 +78,8 @@ pub fn do_add_guess<P: ProjectMut>(
             }
         },
         version_constraint,
+        when: None,
+        hash: None,
     };
     do_add(project, &usage_raw)
 }
@@ -88,14 +102,40 @@ pub fn do_add<P: ProjectMut>(
             InterchangeProjectUsageRaw::Resource {
                 resource: new_resource,
                 version_constraint: new_vc,
+                when: new_when,
+                hash: new_hash,
             } => {
                 for u in info.usage.iter_mut() {
                     match u {
                         InterchangeProjectUsageRaw::Resource {
                             resource,
                             version_constraint,
-                        } if resource == new_resource => {
+                            when,
+                            hash,
+                        } if iris_equivalent(resource, new_resource) => {
+                            if new_when.is_some() && new_when != when {
+                                log::warn!(
+                                    "usage `{new_resource}` is already present; its condition\n\
+                                     {SP:>8} will be set to `{}`",
+                                    new_when.as_deref().unwrap_or_default(),
+                                );
+                                when.clone_from(new_when);
+                                found = true;
+                            }
+                            if new_hash.is_some() && new_hash != hash {
+                                log::warn!(
+                                    "usage `{new_resource}` is already present; its hash pin\n\
+                                     {SP:>8} will be set to `{}`",
+                                    new_hash.as_deref().unwrap_or_default(),
+                                );
+                                hash.clone_from(new_hash);
+                                found = true;
+                            }
                             match (&new_vc, version_constraint) {
+                                // Only the condition or the hash pin changed
+                                (None, _) if found => (),
+                                (Some(vc_new), Some(vc_current))
+                                    if found && vc_new == vc_current => {}
                                 (None, None) => {
                                     log::warn!(
                                         "ignoring usage `{new_resource}`,\n\
@@ -149,7 +189,19 @@ pub fn do_add<P: ProjectMut>(
             }
         }
         if !found {
-            info.usage.push(usage);
+            info.usage.push(match usage {
+                InterchangeProjectUsageRaw::Resource {
+                    resource,
+                    version_constraint,
+                    when,
+                    hash,
+                } => InterchangeProjectUsageRaw::Resource {
+                    resource: normalize_iri_tolerant(&resource),
+                    version_constraint,
+                    when,
+                    hash,
+                },
+            });
         }
         project.put_info(info, true).map_err(AddError::Project)?;
         Ok(true)
@@ -2,8 +2,10 @@
 // SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
 
 use crate::{
-    add::{do_add_guess, expand_sysand_purl_shorthand},
-    model::{InterchangeProjectInfoRaw, InterchangeProjectUsageRaw},
+    add::{AddError, do_add, do_add_guess, expand_sysand_purl_shorthand},
+    model::{
+        InterchangeProjectInfoRaw, InterchangeProjectUsageRaw, InterchangeProjectValidationError,
+    },
     project::memory::InMemoryProject,
     utils::format_err,
 };
@@ -59,7 +61,9 @@ fn add_accepts_normalized_sysand_shorthand() {
         info.usage[0],
         InterchangeProjectUsageRaw::Resource {
             resource: "pkg:sysand/acme-labs/my.project".to_string(),
-            version_constraint: Some("^1.2.3".to_string())
+            version_constraint: Some("^1.2.3".to_string()),
+            when: None,
+            hash: None,
         }
     );
 }
@@ -81,7 +85,9 @@ fn add_keeps_iri_resource() {
         info.usage[0],
         InterchangeProjectUsageRaw::Resource {
             resource: "https://example.com/acme-labs/my.project".to_string(),
-            version_constraint: None
+            version_constraint: None,
+            when: None,
+            hash: None,
         }
     );
 }
@@ -97,3 +103,47 @@ fn add_rejects_non_normalized_sysand_shorthand() {
     assert!(err.contains("`pkg:sysand/acme-labs/my.project`"), "{err}");
     assert!(project.info.unwrap().usage.is_empty());
 }
+
+#[test]
+fn add_skips_equivalent_iri() {
+    let mut project = project();
+
+    do_add_guess(&mut project, "HTTP://Example.com:80/lib".to_owned(), None).unwrap();
+    assert!(!do_add_guess(&mut project, "https://example.com/lib/".to_owned(), None).unwrap());
+
+    let info = project.info.unwrap();
+    assert_eq!(info.usage.len(), 1);
+    assert_eq!(
+        info.usage[0],
+        InterchangeProjectUsageRaw::Resource {
+            resource: "http://example.com/lib".to_string(),
+            version_constraint: None,
+            when: None,
+            hash: None,
+        }
+    );
+}
+
+#[test]
+fn add_pins_hash_of_present_usage() {
+    let mut project = project();
+    let usage = |hash: Option<&str>| InterchangeProjectUsageRaw::Resource {
+        resource: "urn:kpar:lib".to_owned(),
+        version_constraint: Some("^1.0.0".to_owned()),
+        when: None,
+        hash: hash.map(str::to_owned),
+    };
+    let pin = format!("sha256:{}", "ab".repeat(32));
+
+    assert!(do_add(&mut project, &usage(None)).unwrap());
+    assert!(do_add(&mut project, &usage(Some(&pin))).unwrap());
+    assert!(!do_add(&mut project, &usage(Some(&pin))).unwrap());
+    assert_eq!(project.info.unwrap().usage, vec![usage(Some(&pin))]);
+
+    assert_matches!(
+        do_add(&mut self::project(), &usage(Some("sha256:AB"))),
+        Err(AddError::Validation(
+            InterchangeProjectValidationError::InvalidUsageHash { .. }
+        ))
+    );
+}
@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use thiserror::Error;
+
+use crate::{
+    auth::HTTPAuthentication,
+    env::index::{IndexEnvironmentAsync, IndexEnvironmentError},
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    lock::{Lock, Source},
+};
+
+/// Locked project that an index declares deprecated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeprecatedProject {
+    pub name: String,
+    pub version: String,
+    /// Identifier of the project that the index knows it by
+    pub iri: String,
+    /// IRI of the project suggested in its place, if any
+    pub replaced_by: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum AuditError {
+    #[error("failed to check `{iri}` in an index")]
+    Index {
+        iri: Box<str>,
+        #[source]
+        source: IndexEnvironmentError,
+    },
+}
+
+impl HasErrorCode for AuditError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            AuditError::Index { .. } => ErrorCode::new(3001, ErrorClass::Network),
+        }
+    }
+}
+
+/// Projects of `lock` installed from an index that any of `indexes`
+/// declares deprecated. Indexes are consulted in order, and the first one
+/// declaring a project deprecated provides its replacement
+pub async fn do_audit_async<Policy: HTTPAuthentication>(
+    lock: &Lock,
+    indexes: &[IndexEnvironmentAsync<Policy>],
+) -> Result<Vec<DeprecatedProject>, AuditError> {
+    let mut deprecated = vec![];
+    for project in &lock.projects {
+        if !project
+            .sources
+            .iter()
+            .any(|s| matches!(s, Source::IndexKpar { .. }))
+        {
+            continue;
+        }
+        'project: for iri in &project.identifiers {
+            for index in indexes {
+                let deprecation =
+                    index
+                        .deprecation_async(iri)
+                        .await
+                        .map_err(|source| AuditError::Index {
+                            iri: iri.as_str().into(),
+                            source,
+                        })?;
+                if let Some(deprecation) = deprecation {
+                    deprecated.push(DeprecatedProject {
+                        name: project.name.clone(),
+                        version: project.version.clone(),
+                        iri: iri.clone(),
+                        replaced_by: deprecation.replaced_by,
+                    });
+                    break 'project;
+                }
+            }
+        }
+    }
+    Ok(deprecated)
+}
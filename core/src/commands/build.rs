@@ -2,25 +2,40 @@
 // SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
 
 use camino::{Utf8Path, Utf8PathBuf};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use indexmap::IndexMap;
 use thiserror::Error;
+use typed_path::Utf8UnixPath;
 
-use std::{collections::HashSet, io::Write as _};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    io::{self, Cursor, Read as _, Seek, Write},
+    ops::RangeInclusive,
+    process::Command,
+};
 
 use crate::{
+    config::{BuildConfig, PostBuildHook},
     env::utils::ErrorBound,
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
     include::{IncludeError, extract_symbols, read_project_file_to_string},
     model::{
-        InterchangeProjectChecksumRaw, InterchangeProjectUsageRaw,
-        InterchangeProjectValidationError, KerMlChecksumAlg,
+        HashAlg, InterchangeProjectChecksumRaw, InterchangeProjectMetadataRaw,
+        InterchangeProjectUsageRaw, InterchangeProjectValidationError, KerMlChecksumAlg,
+        variant_path_contains,
     },
+    parallel::{Jobs, parallel_map},
+    progress::{self, ProgressPhase},
     project::{
-        ProjectRead,
-        local_kpar::LocalKParProjectRaw,
+        ProjectRead, hash_reader_hex,
+        local_kpar::{LocalKParError, LocalKParProjectRaw},
         local_src::{LocalSrcError, LocalSrcProject},
         utils::{FsIoError, ZipArchiveError, wrapfs},
     },
-    utils::{format_err, license_file_stems, sha256_lowercase_hex},
+    symbols::top_level::TopLevelText,
+    timings::{self, Phase},
+    utils::{StreamHasher, format_err, license_file_stems, sha256_lowercase_hex},
+    walk::{self, OUTPUT_DIR},
     workspace::{Workspace, WorkspaceReadError},
 };
 
@@ -48,6 +63,64 @@ pub enum KparCompressionMethod {
     Ppmd,
 }
 
+impl KparCompressionMethod {
+    /// Range of compression levels supported by the method, `None` if it
+    /// does not support levels
+    pub fn level_range(&self) -> Option<RangeInclusive<i64>> {
+        match self {
+            KparCompressionMethod::Stored => None,
+            KparCompressionMethod::Deflated => Some(1..=9),
+            #[cfg(feature = "kpar-bzip2")]
+            KparCompressionMethod::Bzip2 => Some(1..=9),
+            #[cfg(feature = "kpar-zstd")]
+            KparCompressionMethod::Zstd => Some(1..=22),
+            #[cfg(feature = "kpar-xz")]
+            KparCompressionMethod::Xz => Some(0..=9),
+            #[cfg(feature = "kpar-ppmd")]
+            KparCompressionMethod::Ppmd => Some(1..=9),
+        }
+    }
+}
+
+/// Compression method and level of the files in a KPAR
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct KparCompression {
+    pub method: KparCompressionMethod,
+    /// Compression level, `None` for the default level of `method`
+    pub level: Option<i64>,
+}
+
+impl KparCompression {
+    /// Check that `method` supports `level`
+    pub fn new(
+        method: KparCompressionMethod,
+        level: Option<i64>,
+    ) -> Result<Self, CompressionMethodParseError> {
+        if let Some(level) = level {
+            match method.level_range() {
+                Some(range) if range.contains(&level) => {}
+                range => {
+                    return Err(CompressionMethodParseError::InvalidLevel {
+                        method,
+                        level,
+                        range,
+                    });
+                }
+            }
+        }
+        Ok(Self { method, level })
+    }
+}
+
+impl From<KparCompressionMethod> for KparCompression {
+    fn from(method: KparCompressionMethod) -> Self {
+        Self {
+            method,
+            level: None,
+        }
+    }
+}
+
 impl From<KparCompressionMethod> for zip::CompressionMethod {
     fn from(value: KparCompressionMethod) -> Self {
         match value {
@@ -74,6 +147,42 @@ pub enum CompressionMethodParseError {
     },
     #[error("{0}")]
     Invalid(String),
+    #[error("{}", invalid_level_message(*.method, *.level, .range))]
+    InvalidLevel {
+        method: KparCompressionMethod,
+        level: i64,
+        range: Option<RangeInclusive<i64>>,
+    },
+}
+
+fn invalid_level_message(
+    method: KparCompressionMethod,
+    level: i64,
+    range: &Option<RangeInclusive<i64>>,
+) -> String {
+    match range {
+        Some(range) => format!(
+            "compression level {level} is invalid for {method:?} compression; \
+            expected a level from {} to {}",
+            range.start(),
+            range.end()
+        ),
+        None => format!("{method:?} compression does not support compression levels"),
+    }
+}
+
+impl HasErrorCode for CompressionMethodParseError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            CompressionMethodParseError::SuggestFeature { .. } => {
+                ErrorCode::new(1090, ErrorClass::Usage)
+            }
+            CompressionMethodParseError::Invalid(_) => ErrorCode::new(1091, ErrorClass::Usage),
+            CompressionMethodParseError::InvalidLevel { .. } => {
+                ErrorCode::new(1092, ErrorClass::Usage)
+            }
+        }
+    }
 }
 
 impl TryFrom<String> for KparCompressionMethod {
@@ -171,6 +280,75 @@ pub enum KParBuildError<ProjectReadError: ErrorBound> {
     },
     #[error("file `{0}` is missing symbol `{1}` found in index")]
     MissingIndexSymbol(Box<str>, String),
+    #[error("post-processor `{name}` failed: {message}")]
+    PostProcess { name: String, message: String },
+    #[error("invalid glob `{0}` in `[build]`: {1}")]
+    InvalidGlob(String, globset::Error),
+    #[error("`[build] include` can only be used when building a project with a local root")]
+    IncludeWithoutRoot,
+    #[error(
+        "rebuilding `{path}` produced a different archive:\n\
+        built:   sha256 {built}\n\
+        rebuilt: sha256 {rebuilt}"
+    )]
+    NotReproducible {
+        path: String,
+        built: String,
+        rebuilt: String,
+    },
+    #[error("path `{path}` of variant `{variant}` does not match any packaged file")]
+    MissingVariantPath { variant: String, path: String },
+    #[error("{}", format_dirty(missing, unindexed))]
+    Dirty {
+        /// Files in the project metadata not selected by `[build] include`
+        missing: Vec<String>,
+        /// Files selected by `[build] include` not in the project metadata
+        unindexed: Vec<String>,
+    },
+}
+
+fn format_dirty(missing: &[String], unindexed: &[String]) -> String {
+    let mut message = "files selected by `[build] include` differ from project metadata".to_owned();
+    for p in missing {
+        message.push_str(&format!(
+            "\n  included in metadata, but not selected: `{p}`"
+        ));
+    }
+    for p in unindexed {
+        message.push_str(&format!(
+            "\n  selected, but not included in metadata: `{p}`"
+        ));
+    }
+    message
+}
+
+impl<ProjectReadError: ErrorBound> HasErrorCode for KParBuildError<ProjectReadError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            KParBuildError::ProjectRead(_) => ErrorCode::new(1001, ErrorClass::Project),
+            KParBuildError::WorkspaceRead(_) => ErrorCode::new(1002, ErrorClass::Project),
+            KParBuildError::Io(_) => ErrorCode::new(1003, ErrorClass::Io),
+            KParBuildError::Validation { .. } => ErrorCode::new(1004, ErrorClass::Project),
+            KParBuildError::Extract(_) => ErrorCode::new(1005, ErrorClass::Build),
+            KParBuildError::UnknownFormat(_) => ErrorCode::new(1006, ErrorClass::Build),
+            KParBuildError::MissingInfoMeta => ErrorCode::new(1007, ErrorClass::Project),
+            KParBuildError::MissingInfo => ErrorCode::new(1008, ErrorClass::Project),
+            KParBuildError::MissingMeta => ErrorCode::new(1009, ErrorClass::Project),
+            KParBuildError::Zip(_) => ErrorCode::new(1010, ErrorClass::Build),
+            KParBuildError::Serialize(..) => ErrorCode::new(1011, ErrorClass::Build),
+            KParBuildError::PathUsage(_) => ErrorCode::new(1012, ErrorClass::Build),
+            KParBuildError::WorkspaceMetamodelConflict { .. } => {
+                ErrorCode::new(1013, ErrorClass::Project)
+            }
+            KParBuildError::MissingIndexSymbol(..) => ErrorCode::new(1014, ErrorClass::Build),
+            KParBuildError::PostProcess { .. } => ErrorCode::new(1015, ErrorClass::Build),
+            KParBuildError::InvalidGlob(..) => ErrorCode::new(1016, ErrorClass::Usage),
+            KParBuildError::IncludeWithoutRoot => ErrorCode::new(1017, ErrorClass::Build),
+            KParBuildError::Dirty { .. } => ErrorCode::new(1018, ErrorClass::Build),
+            KParBuildError::NotReproducible { .. } => ErrorCode::new(1019, ErrorClass::Build),
+            KParBuildError::MissingVariantPath { .. } => ErrorCode::new(1020, ErrorClass::Build),
+        }
+    }
 }
 
 impl<ProjectReadError: ErrorBound> From<FsIoError> for KParBuildError<ProjectReadError> {
@@ -200,7 +378,7 @@ pub fn default_kpar_path<Pr: ProjectRead>(
     let mut path = workspace
         .map(Workspace::root_path)
         .unwrap_or(project_path)
-        .join("output");
+        .join(OUTPUT_DIR);
     path.push(default_kpar_file_name(project)?);
     Ok(path)
 }
@@ -222,6 +400,71 @@ pub fn default_kpar_file_name<Pr: ProjectRead>(
     ))
 }
 
+/// Environment variable with a Unix timestamp to use as the modification
+/// time of all files in built KPARs, see
+/// <https://reproducible-builds.org/specs/source-date-epoch/>
+pub const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
+
+/// Permissions of all files in built KPARs, regardless of the permissions
+/// of the source files
+const KPAR_FILE_PERMISSIONS: u32 = 0o644;
+
+/// Modification time of files in built KPARs. Archives only depend on the
+/// project contents, so the time is fixed: it is `SOURCE_DATE_EPOCH` if set,
+/// or else the earliest time a ZIP archive can represent (1980-01-01)
+fn kpar_timestamp() -> zip::DateTime {
+    match std::env::var(SOURCE_DATE_EPOCH) {
+        Ok(epoch) => parse_source_date_epoch(&epoch).unwrap_or_else(|| {
+            log::warn!(
+                "ignoring `{SOURCE_DATE_EPOCH}={epoch}`: it must be a Unix timestamp \
+                between 1980 and 2107"
+            );
+            zip::DateTime::DEFAULT
+        }),
+        Err(_) => zip::DateTime::DEFAULT,
+    }
+}
+
+fn parse_source_date_epoch(epoch: &str) -> Option<zip::DateTime> {
+    use chrono::{Datelike, Timelike};
+
+    let time = chrono::DateTime::from_timestamp(epoch.trim().parse().ok()?, 0)?;
+    zip::DateTime::from_date_and_time(
+        time.year().try_into().ok()?,
+        time.month() as u8,
+        time.day() as u8,
+        time.hour() as u8,
+        time.minute() as u8,
+        time.second() as u8,
+    )
+    .ok()
+}
+
+/// Which source files are packaged into a KPAR. By default these are the
+/// files in the project metadata (its index and checksums).
+#[derive(Debug, Default, Clone)]
+pub struct KparManifest {
+    /// Globs of files to package, relative to the project root. If not
+    /// empty, the matching files must be exactly the files in the project
+    /// metadata, unless `allow_dirty` is set
+    pub include: Vec<String>,
+    /// Globs of files not to package
+    pub exclude: Vec<String>,
+    /// Package the files matching `include` even if they differ from the
+    /// files in the project metadata
+    pub allow_dirty: bool,
+}
+
+impl KparManifest {
+    pub fn new(config: &BuildConfig, allow_dirty: bool) -> Self {
+        Self {
+            include: config.include.clone(),
+            exclude: config.exclude.clone(),
+            allow_dirty,
+        }
+    }
+}
+
 /// `update_index` controls whether to parse symbols from current
 /// file to update index
 pub fn do_build_kpar<P: AsRef<Utf8Path>, Pr: ProjectRead>(
@@ -230,6 +473,26 @@ pub fn do_build_kpar<P: AsRef<Utf8Path>, Pr: ProjectRead>(
     compression: KparCompressionMethod,
     update_index: bool,
     allow_path_usage: bool,
+) -> Result<LocalKParProjectRaw, KParBuildError<Pr::Error>> {
+    do_build_kpar_with_manifest(
+        project,
+        path,
+        compression.into(),
+        update_index,
+        allow_path_usage,
+        &KparManifest::default(),
+    )
+}
+
+/// Like [`do_build_kpar`], but packages the source files selected by
+/// `manifest`
+pub fn do_build_kpar_with_manifest<P: AsRef<Utf8Path>, Pr: ProjectRead>(
+    project: &Pr,
+    path: P,
+    compression: KparCompression,
+    update_index: bool,
+    allow_path_usage: bool,
+    manifest: &KparManifest,
 ) -> Result<LocalKParProjectRaw, KParBuildError<Pr::Error>> {
     let path = path.as_ref();
     match do_build_kpar_inner(
@@ -239,6 +502,7 @@ pub fn do_build_kpar<P: AsRef<Utf8Path>, Pr: ProjectRead>(
         update_index,
         allow_path_usage,
         None,
+        manifest,
     ) {
         Ok(p) => Ok(p),
         Err(e) => {
@@ -250,18 +514,117 @@ pub fn do_build_kpar<P: AsRef<Utf8Path>, Pr: ProjectRead>(
     }
 }
 
+/// Build `project` into a KPAR archive written to `writer`, e.g. a
+/// `Cursor<Vec<u8>>` to store the archive in a
+/// [`Vfs`](crate::vfs::Vfs). Returns `writer` after the archive is finished.
+/// `label` is used to refer to the archive in logs and errors.
+pub fn do_build_kpar_to_writer<W: Write + Seek, Pr: ProjectRead>(
+    project: &Pr,
+    writer: W,
+    label: &str,
+    compression: KparCompressionMethod,
+    update_index: bool,
+    allow_path_usage: bool,
+) -> Result<W, KParBuildError<Pr::Error>> {
+    let (writer, size) = write_kpar(
+        project,
+        writer,
+        label,
+        compression.into(),
+        update_index,
+        allow_path_usage,
+        None,
+        &KparManifest::default(),
+    )?;
+    size.log(label);
+    Ok(writer)
+}
+
+/// Build `project` again in memory with the same options it was built with
+/// into the KPAR at `path`, and check that both archives are identical.
+/// `workspace` is the workspace the project was built in, if any. Returns
+/// the SHA-256 digest of the archive.
+pub fn do_check_reproducible_kpar<P: AsRef<Utf8Path>, Pr: ProjectRead>(
+    project: &Pr,
+    path: P,
+    compression: KparCompression,
+    update_index: bool,
+    allow_path_usage: bool,
+    manifest: &KparManifest,
+    workspace: Option<&Workspace>,
+) -> Result<String, KParBuildError<Pr::Error>> {
+    let path = path.as_ref();
+    let built = sha256_lowercase_hex(wrapfs::read(path)?);
+    let rebuilt = write_kpar(
+        project,
+        Cursor::new(vec![]),
+        path.as_str(),
+        compression,
+        update_index,
+        allow_path_usage,
+        workspace
+            .and_then(Workspace::metamodel)
+            .map(|iri| iri.as_str()),
+        manifest,
+    )?
+    .0
+    .into_inner();
+    let rebuilt = sha256_lowercase_hex(rebuilt);
+    if built != rebuilt {
+        return Err(KParBuildError::NotReproducible {
+            path: path.to_string(),
+            built,
+            rebuilt,
+        });
+    }
+    let checked = "Checked";
+    let header = crate::style::get_style_config().header;
+    log::info!("{header}{checked:>12}{header:#} kpar `{path}` is reproducible (sha256 {built})");
+    Ok(built)
+}
+
 /// Caller must delete the created archive on error
 fn do_build_kpar_inner<P: AsRef<Utf8Path>, Pr: ProjectRead>(
     project: &Pr,
     path: P,
-    compression: KparCompressionMethod,
+    compression: KparCompression,
     update_index: bool,
     allow_path_usage: bool,
     workspace_metamodel: Option<&str>,
+    manifest: &KparManifest,
 ) -> Result<LocalKParProjectRaw, KParBuildError<Pr::Error>> {
+    let path = path.as_ref();
+    let archive_file = wrapfs::File::create(path)?;
+    let (_, size) = write_kpar(
+        project,
+        archive_file,
+        path.as_str(),
+        compression,
+        update_index,
+        allow_path_usage,
+        workspace_metamodel,
+        manifest,
+    )?;
+    size.log(path.as_str());
+
+    Ok(LocalKParProjectRaw::new_project_at_root(path)?)
+}
+
+#[expect(clippy::too_many_arguments)]
+fn write_kpar<W: Write + Seek, Pr: ProjectRead>(
+    project: &Pr,
+    writer: W,
+    path: &str,
+    compression: KparCompression,
+    update_index: bool,
+    allow_path_usage: bool,
+    workspace_metamodel: Option<&str>,
+    manifest: &KparManifest,
+) -> Result<(W, KparSize), KParBuildError<Pr::Error>> {
     let building = "Building";
     let header = crate::style::get_style_config().header;
-    log::info!("{header}{building:>12}{header:#} kpar `{}`", path.as_ref());
+    log::info!("{header}{building:>12}{header:#} kpar `{path}`");
+    let _timer = timings::time(Phase::Zip);
 
     let (info, mut meta) = match project.get_project() {
         Ok(im) => match im {
@@ -327,7 +690,7 @@ fn do_build_kpar_inner<P: AsRef<Utf8Path>, Pr: ProjectRead>(
                 return Err(KParBuildError::WorkspaceMetamodelConflict {
                     workspace_metamodel: ws_metamodel.to_string(),
                     project_metamodel: proj_metamodel.into(),
-                    project_path: path.as_ref().to_string(),
+                    project_path: path.to_string(),
                 });
             }
         } else {
@@ -335,36 +698,35 @@ fn do_build_kpar_inner<P: AsRef<Utf8Path>, Pr: ProjectRead>(
         }
     }
 
-    let archive_file = wrapfs::File::create(&path)?;
-    let mut zip = zip::ZipWriter::new(archive_file);
-
-    let options = zip::write::SimpleFileOptions::default()
-        .compression_method(compression.into())
-        .system(zip::System::Unix)
-        .last_modified_time(zip::DateTime::DEFAULT);
+    let mut archive = KparWriter::new(writer, path, compression);
 
-    let source_paths = meta.source_paths(true);
-    let mut checksums = if let Some(mut checksum) = meta.checksum.take() {
-        checksum.clear();
-        checksum
-    } else {
-        IndexMap::new()
-    };
-    let len = source_paths.len();
+    let source_paths = select_source_files(project, &meta, manifest)?;
+    for (variant, paths) in &meta.variants {
+        if let Some(path) = paths
+            .iter()
+            .find(|v| !source_paths.iter().any(|p| variant_path_contains(v, p)))
+        {
+            return Err(KParBuildError::MissingVariantPath {
+                variant: variant.clone(),
+                path: path.clone(),
+            });
+        }
+    }
+    let mut checksums = meta.checksum.take().unwrap_or_default();
+    // Files keep the checksum algorithm they were included with
+    let algorithms: HashMap<String, HashAlg> = checksums
+        .drain(..)
+        .map(|(p, c)| (p, file_hash_alg(&c.algorithm)))
+        .collect();
+    let len = source_paths.len() as u64;
     if update_index {
         meta.index.clear();
         for (i, p) in source_paths.into_iter().enumerate() {
-            // `log` always appends a newline, so `\r` does not work with it
-            eprint!("\rupdating file metadata ({}/{len})", i + 1);
-
-            let source = read_project_file_to_string(project, &p)?;
-            let checksum = sha256_lowercase_hex(&source);
-            let symbols = extract_symbols(&p, &source, None)?;
+            progress::report(ProgressPhase::Build, i as u64 + 1, Some(len), || p.as_str());
 
-            zip.start_file(&p, options)
-                .map_err(|e| ZipArchiveError::Write(Utf8Path::new(&p).into(), e))?;
-            zip.write_all(source.as_bytes())
-                .map_err(|e| FsIoError::WriteFile(path.as_ref().into(), e))?;
+            let algorithm = algorithms.get(&p).copied().unwrap_or_default();
+            let streamed = archive.add_source(project, &p, algorithm)?;
+            let symbols = source_symbols(project, &p, streamed.top_level)?;
 
             for s in symbols {
                 meta.index.insert(s, p.clone());
@@ -372,18 +734,20 @@ fn do_build_kpar_inner<P: AsRef<Utf8Path>, Pr: ProjectRead>(
             checksums.insert(
                 p,
                 InterchangeProjectChecksumRaw {
-                    value: checksum,
-                    algorithm: KerMlChecksumAlg::Sha256.into(),
+                    value: streamed.checksum,
+                    algorithm: algorithm.to_string(),
                 },
             );
         }
     } else {
+        meta.index
+            .retain(|_, p| source_paths.binary_search(p).is_ok());
         for (i, p) in source_paths.into_iter().enumerate() {
-            eprint!("\rupdating file checksums ({}/{len})", i + 1);
+            progress::report(ProgressPhase::Build, i as u64 + 1, Some(len), || p.as_str());
 
-            let source = read_project_file_to_string(project, &p)?;
-            let checksum = sha256_lowercase_hex(&source);
-            let new_symbols = extract_symbols(&p, &source, None)?;
+            let algorithm = algorithms.get(&p).copied().unwrap_or_default();
+            let streamed = archive.add_source(project, &p, algorithm)?;
+            let new_symbols = source_symbols(project, &p, streamed.top_level)?;
 
             let new_symbols: HashSet<String> = new_symbols.into_iter().collect();
             let old_symbols = meta.file_index_symbols(&p);
@@ -402,21 +766,15 @@ fn do_build_kpar_inner<P: AsRef<Utf8Path>, Pr: ProjectRead>(
                 );
             }
 
-            zip.start_file(&p, options)
-                .map_err(|e| ZipArchiveError::Write(Utf8Path::new(&p).into(), e))?;
-            zip.write_all(source.as_bytes())
-                .map_err(|e| FsIoError::WriteFile(path.as_ref().into(), e))?;
-
             checksums.insert(
                 p,
                 InterchangeProjectChecksumRaw {
-                    value: checksum,
-                    algorithm: KerMlChecksumAlg::Sha256.into(),
+                    value: streamed.checksum,
+                    algorithm: algorithm.to_string(),
                 },
             );
         }
     }
-    eprintln!();
     meta.checksum = Some(checksums);
 
     let project_root = project.project_root();
@@ -440,10 +798,7 @@ fn do_build_kpar_inner<P: AsRef<Utf8Path>, Pr: ProjectRead>(
         }
     }
     for (archive_path, content) in extra_files {
-        zip.start_file(&archive_path, options)
-            .map_err(|e| ZipArchiveError::Write(Utf8Path::new(&archive_path).into(), e))?;
-        zip.write_all(content.as_bytes())
-            .map_err(|e| FsIoError::WriteFile(path.as_ref().into(), e))?;
+        archive.add(&archive_path, content.as_bytes())?;
     }
 
     // KerML Clause 10.3: “In addition, the archive shall contain, at its
@@ -454,20 +809,272 @@ fn do_build_kpar_inner<P: AsRef<Utf8Path>, Pr: ProjectRead>(
         serde_json::to_string(&info).expect("BUG: failed to serialize .project.json");
     let meta_content = serde_json::to_string(&meta).expect("BUG: failed to serialize .meta.json");
 
-    zip.start_file(".project.json", options)
-        .map_err(|e| ZipArchiveError::Write(Utf8Path::new(".project.json").into(), e))?;
-    zip.write_all(info_content.as_bytes())
-        .map_err(|e| FsIoError::WriteFile(path.as_ref().into(), e))?;
+    archive.add(".project.json", info_content.as_bytes())?;
+    archive.add(".meta.json", meta_content.as_bytes())?;
+
+    archive.finish()
+}
+
+/// KPAR being written, keeping track of the size of its contents
+struct KparWriter<'a, W: Write + Seek> {
+    zip: zip::ZipWriter<W>,
+    options: zip::write::SimpleFileOptions,
+    /// Archive path, used in errors
+    path: &'a str,
+    uncompressed_size: u64,
+}
+
+impl<'a, W: Write + Seek> KparWriter<'a, W> {
+    fn new(writer: W, path: &'a str, compression: KparCompression) -> Self {
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(compression.method.into())
+            .compression_level(compression.level)
+            .system(zip::System::Unix)
+            .last_modified_time(kpar_timestamp())
+            .unix_permissions(KPAR_FILE_PERMISSIONS);
+        Self {
+            zip: zip::ZipWriter::new(writer),
+            options,
+            path,
+            uncompressed_size: 0,
+        }
+    }
+
+    fn add<E: ErrorBound>(&mut self, name: &str, content: &[u8]) -> Result<(), KParBuildError<E>> {
+        self.zip
+            .start_file(name, self.options)
+            .map_err(|e| ZipArchiveError::Write(Utf8Path::new(name).into(), e))?;
+        self.zip
+            .write_all(content)
+            .map_err(|e| FsIoError::WriteFile(self.path.into(), e))?;
+        self.uncompressed_size += content.len() as u64;
+        Ok(())
+    }
+
+    /// Stream the source file `path` of `project` into the archive through a
+    /// bounded buffer, checksumming it with `algorithm` and reducing it to its
+    /// top-level text on the way, so that the file is never read into memory
+    /// as a whole
+    fn add_source<Pr: ProjectRead>(
+        &mut self,
+        project: &Pr,
+        path: &str,
+        algorithm: HashAlg,
+    ) -> Result<StreamedSource, KParBuildError<Pr::Error>> {
+        let mut reader = project
+            .read_source(path)
+            .map_err(KParBuildError::ProjectRead)?;
+        // Entries over 4 GiB need Zip64 headers, which have to be
+        // chosen before the entry is written
+        let large_file = project
+            .project_root()
+            .and_then(|root| std::fs::metadata(root.join(path)).ok())
+            .is_some_and(|m| m.len() > ZIP64_THRESHOLD);
+        self.zip
+            .start_file(path, self.options.large_file(large_file))
+            .map_err(|e| ZipArchiveError::Write(Utf8Path::new(path).into(), e))?;
+        let mut written = 0;
+
+        let mut hasher = StreamHasher::new(algorithm);
+        let mut top_level = TopLevelText::default();
+        let mut buffer = vec![0; STREAM_BUFFER_SIZE];
+        loop {
+            let length = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(length) => length,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(FsIoError::ReadFile(path.into(), e).into()),
+            };
+            let chunk = &buffer[..length];
+            hasher.update(chunk);
+            top_level.feed(chunk);
+            written += length as u64;
+            if !large_file && written > ZIP64_THRESHOLD {
+                return Err(ZipArchiveError::SizeUnknown(path.into()).into());
+            }
+            self.zip
+                .write_all(chunk)
+                .map_err(|e| FsIoError::WriteFile(self.path.into(), e))?;
+            self.uncompressed_size += length as u64;
+        }
+        Ok(StreamedSource {
+            checksum: hasher.finalize_hex(),
+            top_level: top_level.finish(),
+        })
+    }
+
+    fn finish<E: ErrorBound>(self) -> Result<(W, KparSize), KParBuildError<E>> {
+        let mut writer = self
+            .zip
+            .finish()
+            .map_err(|e| ZipArchiveError::Finish(Utf8Path::new(self.path).into(), e))?;
+        let compressed = writer
+            .stream_position()
+            .map_err(|e| FsIoError::WriteFile(self.path.into(), e))?;
+        Ok((
+            writer,
+            KparSize {
+                compressed,
+                uncompressed: self.uncompressed_size,
+            },
+        ))
+    }
+}
+
+/// Size of the buffer source files are streamed into a KPAR through
+const STREAM_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Largest entry size that fits without Zip64 headers
+const ZIP64_THRESHOLD: u64 = u32::MAX as u64;
+
+/// Source file streamed into a KPAR
+struct StreamedSource {
+    /// Checksum of the file
+    checksum: String,
+    /// Text of the file that can contain its top-level symbols, if the file
+    /// could be reduced to it
+    top_level: Option<String>,
+}
+
+/// Top-level symbols of the source file `path`, extracted from its
+/// `top_level` text. The whole file is only read if it could not be reduced
+/// to its top-level text, or to report extraction errors with their
+/// location in the file
+fn source_symbols<Pr: ProjectRead>(
+    project: &Pr,
+    path: &str,
+    top_level: Option<String>,
+) -> Result<Vec<String>, KParBuildError<Pr::Error>> {
+    let path = Utf8UnixPath::new(path);
+    if let Some(text) = top_level {
+        match extract_symbols(&path, text, None) {
+            Ok(symbols) => return Ok(symbols),
+            Err(e @ IncludeError::UnknownFormat(_)) => return Err(e.into()),
+            Err(_) => (),
+        }
+    }
+    let source = read_project_file_to_string(project, &path)?;
+    Ok(extract_symbols(&path, &source, None)?)
+}
+
+/// Size of a built KPAR and of the files in it
+#[derive(Clone, Copy, Debug)]
+struct KparSize {
+    compressed: u64,
+    uncompressed: u64,
+}
+
+impl KparSize {
+    fn log(&self, path: &str) {
+        let built = "Built";
+        let header = crate::style::get_style_config().header;
+        let ratio = if self.uncompressed == 0 {
+            100.0
+        } else {
+            self.compressed as f64 * 100.0 / self.uncompressed as f64
+        };
+        log::info!(
+            "{header}{built:>12}{header:#} kpar `{path}`: {} ({ratio:.0}% of {} uncompressed)",
+            format_size(self.compressed),
+            format_size(self.uncompressed),
+        );
+    }
+}
+
+/// Human readable size of `bytes`, e.g. `1.5 MiB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Algorithm to checksum a file with in the archive, given the algorithm
+/// it is checksummed with in the project. `NONE` and algorithms that cannot
+/// be computed are replaced by SHA256
+fn file_hash_alg(algorithm: &str) -> HashAlg {
+    KerMlChecksumAlg::try_from(algorithm)
+        .ok()
+        .and_then(|a| HashAlg::try_from(a).ok())
+        .unwrap_or_default()
+}
+
+/// Source files of `project` to package, sorted so that archives are
+/// reproducible
+fn select_source_files<Pr: ProjectRead>(
+    project: &Pr,
+    meta: &InterchangeProjectMetadataRaw,
+    manifest: &KparManifest,
+) -> Result<Vec<String>, KParBuildError<Pr::Error>> {
+    let exclude = build_glob_set(&manifest.exclude)?;
+    let indexed: BTreeSet<String> = meta
+        .source_paths(true)
+        .into_iter()
+        .filter(|p| !exclude.is_match(p))
+        .collect();
+    if manifest.include.is_empty() {
+        return Ok(indexed.into_iter().collect());
+    }
 
-    zip.start_file(".meta.json", options)
-        .map_err(|e| ZipArchiveError::Write(Utf8Path::new(".meta.json").into(), e))?;
-    zip.write_all(meta_content.as_bytes())
-        .map_err(|e| FsIoError::WriteFile(path.as_ref().into(), e))?;
+    let include = build_glob_set(&manifest.include)?;
+    let root = project
+        .project_root()
+        .ok_or(KParBuildError::IncludeWithoutRoot)?;
+    let mut selected = BTreeSet::new();
+    // Hidden entries, such as `.git`, and ignored files are never packaged
+    let walker = walk::walker(root, |e| !e.file_name().to_string_lossy().starts_with('.'));
+    for entry in walker {
+        let entry = entry.map_err(|e| walk::walk_error(root, e))?;
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let Some(relative) = walk::relative_path(root, entry.path()) else {
+            continue;
+        };
+        if include.is_match(&relative) && !exclude.is_match(&relative) {
+            selected.insert(relative);
+        }
+    }
 
-    zip.finish()
-        .map_err(|e| ZipArchiveError::Finish(path.as_ref().into(), e))?;
+    let missing: Vec<String> = indexed.difference(&selected).cloned().collect();
+    let unindexed: Vec<String> = selected.difference(&indexed).cloned().collect();
+    if !missing.is_empty() || !unindexed.is_empty() {
+        if !manifest.allow_dirty {
+            return Err(KParBuildError::Dirty { missing, unindexed });
+        }
+        for p in &missing {
+            log::warn!("not packaging file `{p}` included in metadata, but not selected");
+        }
+        for p in &unindexed {
+            log::warn!("packaging file `{p}` not included in metadata");
+        }
+    }
+    Ok(selected.into_iter().collect())
+}
 
-    Ok(LocalKParProjectRaw::new_project_at_root(&path)?)
+fn build_glob_set<E: ErrorBound>(globs: &[String]) -> Result<GlobSet, KParBuildError<E>> {
+    glob_set(globs).map_err(|(glob, e)| KParBuildError::InvalidGlob(glob, e))
+}
+
+/// Set of `[build]` globs. On error, returns the invalid glob
+pub(crate) fn glob_set(globs: &[String]) -> Result<GlobSet, (String, globset::Error)> {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(
+            GlobBuilder::new(glob)
+                .literal_separator(true)
+                .build()
+                .map_err(|e| (glob.clone(), e))?,
+        );
+    }
+    builder.build().map_err(|e| (globs.join(", "), e))
 }
 
 fn read_optional_project_file(
@@ -490,12 +1097,185 @@ fn read_optional_project_file(
     }
 }
 
+/// A step run on a KPAR after it has been built. Post-processors may
+/// rewrite the archive in place, e.g. to add or transform files.
+pub trait KparPostProcessor {
+    /// Name used in log and error messages
+    fn name(&self) -> &str;
+
+    /// `project_root` is the local root of the project the KPAR was built from,
+    /// if known.
+    fn process(&self, kpar_path: &Utf8Path, project_root: Option<&Utf8Path>) -> Result<(), String>;
+}
+
+/// Post-processor running an external program configured in `sysand.toml`
+/// under `[[post_build]]`. The program is run in the project root (if known)
+/// with the path to the KPAR appended to its arguments.
+#[derive(Debug, Clone)]
+pub struct CommandPostProcessor {
+    pub name: String,
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+impl From<&PostBuildHook> for CommandPostProcessor {
+    fn from(value: &PostBuildHook) -> Self {
+        Self {
+            name: value.name.clone().unwrap_or_else(|| value.command.clone()),
+            command: value.command.clone(),
+            args: value.args.clone(),
+        }
+    }
+}
+
+impl KparPostProcessor for CommandPostProcessor {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn process(&self, kpar_path: &Utf8Path, project_root: Option<&Utf8Path>) -> Result<(), String> {
+        let mut command = Command::new(&self.command);
+        command.args(&self.args).arg(kpar_path);
+        if let Some(root) = project_root {
+            command.current_dir(root);
+        }
+        let status = command
+            .status()
+            .map_err(|e| format!("failed to run `{}`: {e}", self.command))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("`{}` exited with {status}", self.command))
+        }
+    }
+}
+
+/// Run `processors` in order on the built `kpar`. Afterwards source file
+/// checksums in the archived `.meta.json` are recomputed, so that the project
+/// digest reflects the post-processed contents, on up to `jobs` threads. If
+/// no processors are given, the archive is returned as is.
+pub fn post_process_kpar(
+    kpar: LocalKParProjectRaw,
+    processors: &[&dyn KparPostProcessor],
+    project_root: Option<&Utf8Path>,
+    jobs: Jobs,
+) -> Result<LocalKParProjectRaw, KParBuildError<LocalKParError>> {
+    if processors.is_empty() {
+        return Ok(kpar);
+    }
+    let path = kpar.archive_path().to_owned();
+    let header = crate::style::get_style_config().header;
+    for processor in processors {
+        let processing = "Processing";
+        log::info!(
+            "{header}{processing:>12}{header:#} kpar `{path}` with `{}`",
+            processor.name()
+        );
+        processor
+            .process(&path, project_root)
+            .map_err(|message| KParBuildError::PostProcess {
+                name: processor.name().to_owned(),
+                message,
+            })?;
+    }
+
+    let kpar = LocalKParProjectRaw::new_project_at_root(&path)?;
+    let Some(mut meta) = kpar.get_meta().map_err(KParBuildError::ProjectRead)? else {
+        return Err(KParBuildError::MissingMeta);
+    };
+    let previous_algorithms: HashMap<&String, HashAlg> = meta
+        .checksum
+        .iter()
+        .flatten()
+        .map(|(p, c)| (p, file_hash_alg(&c.algorithm)))
+        .collect();
+    let source_paths: Vec<String> = meta.source_paths(true).into_iter().collect();
+    let values = parallel_map(&source_paths, jobs, |p| {
+        let algorithm = previous_algorithms.get(p).copied().unwrap_or_default();
+        let mut source = kpar.read_source(p).map_err(KParBuildError::ProjectRead)?;
+        let value = hash_reader_hex(algorithm, &mut source)
+            .map_err(|e| FsIoError::ReadFile(Utf8PathBuf::from(p), e))?;
+        Ok::<_, KParBuildError<LocalKParError>>(InterchangeProjectChecksumRaw {
+            value,
+            algorithm: algorithm.to_string(),
+        })
+    });
+    let mut checksums = IndexMap::new();
+    for (p, checksum) in source_paths.into_iter().zip(values) {
+        checksums.insert(p, checksum?);
+    }
+    checksums.sort_keys();
+    let mut previous = meta.checksum.take().unwrap_or_default();
+    previous.sort_keys();
+    if previous != checksums {
+        log::debug!("updating source file checksums of post-processed kpar `{path}`");
+        meta.checksum = Some(checksums);
+        replace_archive_meta(&kpar, &meta)?;
+    }
+
+    if let Some(digest) = kpar
+        .checksum_canonical_hex()
+        .map_err(|e| KParBuildError::Extract(format_err(e)))?
+    {
+        log::debug!("post-processed kpar `{path}` has project digest `{digest}`");
+    }
+
+    Ok(kpar)
+}
+
+/// Rewrite the archive of `kpar` with `.meta.json` replaced by `meta`.
+/// All other entries are copied without recompressing them.
+fn replace_archive_meta(
+    kpar: &LocalKParProjectRaw,
+    meta: &crate::model::InterchangeProjectMetadataRaw,
+) -> Result<(), KParBuildError<LocalKParError>> {
+    let path = kpar.archive_path();
+    let mut archive = kpar.open_archive().map_err(KParBuildError::ProjectRead)?;
+    let temp_path = path.with_extension("kpar.tmp");
+    let mut zip = zip::ZipWriter::new(wrapfs::File::create(&temp_path)?);
+    let mut options = zip::write::SimpleFileOptions::default()
+        .system(zip::System::Unix)
+        .last_modified_time(kpar_timestamp())
+        .unix_permissions(KPAR_FILE_PERMISSIONS);
+
+    let result = (|| {
+        for i in 0..archive.len() {
+            let file = archive.by_index_raw(i).map_err(ZipArchiveError::FileMeta)?;
+            if file.name() == ".meta.json" {
+                options = options.compression_method(file.compression());
+                continue;
+            }
+            let name = file.name().to_owned();
+            zip.raw_copy_file(file)
+                .map_err(|e| ZipArchiveError::Write(Utf8Path::new(&name).into(), e))?;
+        }
+        let meta_content =
+            serde_json::to_string(meta).expect("BUG: failed to serialize .meta.json");
+        zip.start_file(".meta.json", options)
+            .map_err(|e| ZipArchiveError::Write(Utf8Path::new(".meta.json").into(), e))?;
+        zip.write_all(meta_content.as_bytes())
+            .map_err(|e| FsIoError::WriteFile(temp_path.clone(), e))?;
+        zip.finish()
+            .map_err(|e| ZipArchiveError::Finish(temp_path.as_path().into(), e))?;
+        std::fs::rename(&temp_path, path)
+            .map_err(|e| FsIoError::Move(temp_path.clone(), path.to_owned(), e))?;
+        Ok(())
+    })();
+    if result.is_err()
+        && let Err(e) = wrapfs::remove_file(&temp_path)
+    {
+        log::debug!("cleanup: failed to remove temporary archive `{temp_path}`: {e}");
+    }
+    result
+}
+
 pub fn do_build_workspace_kpars<P: AsRef<Utf8Path>>(
     workspace: &Workspace,
     path: P,
-    compression: KparCompressionMethod,
+    compression: KparCompression,
     update_index: bool,
     allow_path_usage: bool,
+    manifest: &KparManifest,
 ) -> Result<Vec<LocalKParProjectRaw>, KParBuildError<LocalSrcError>> {
     let ws_metamodel = workspace.metamodel().map(|iri| iri.as_str());
 
@@ -516,6 +1296,7 @@ pub fn do_build_workspace_kpars<P: AsRef<Utf8Path>>(
             update_index,
             allow_path_usage,
             ws_metamodel,
+            manifest,
         ) {
             Ok(p) => p,
             Err(e) => {
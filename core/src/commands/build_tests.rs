@@ -1,10 +1,32 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
 
+use std::io::{Read as _, Write as _};
+
+use camino::Utf8Path;
 use camino_tempfile::tempdir;
 
-use super::{license_file_stems, read_optional_project_file};
-use crate::project::utils::FsIoError;
+use super::{
+    CompressionMethodParseError, KParBuildError, KparCompression, KparCompressionMethod,
+    KparManifest, KparPostProcessor, do_build_kpar, do_build_kpar_to_writer,
+    do_build_kpar_with_manifest, do_check_reproducible_kpar, format_size, license_file_stems,
+    parse_source_date_epoch, post_process_kpar, read_optional_project_file,
+};
+use crate::{
+    include::do_include,
+    init::do_init,
+    model::HashAlg,
+    parallel::Jobs,
+    project::{
+        ProjectRead, hash_reader_hex,
+        local_kpar::{KparInnerPath, LocalKParProject},
+        local_src::LocalSrcProject,
+        utils::FsIoError,
+        vfs::VfsProject,
+    },
+    utils::{hash_lowercase_hex, sha256_lowercase_hex},
+    vfs::{MemoryFs, StdFs, Vfs},
+};
 
 #[test]
 fn returns_none_when_project_root_is_none() {
@@ -99,3 +121,645 @@ fn license_stems_or_later_strips_plus() {
 fn license_stems_deduplicates() {
     assert_eq!(stems("MIT AND MIT"), vec!["MIT".to_string()]);
 }
+
+/// Replaces the contents of `test.sysml` in the archive.
+struct ReplaceSource(&'static str);
+
+impl KparPostProcessor for ReplaceSource {
+    fn name(&self) -> &str {
+        "replace-source"
+    }
+
+    fn process(
+        &self,
+        kpar_path: &Utf8Path,
+        _project_root: Option<&Utf8Path>,
+    ) -> Result<(), String> {
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(kpar_path).unwrap()).unwrap();
+        let mut entries = Vec::new();
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i).unwrap();
+            let mut content = Vec::new();
+            file.read_to_end(&mut content).unwrap();
+            if file.name() == "test.sysml" {
+                content = self.0.as_bytes().to_vec();
+            }
+            entries.push((file.name().to_owned(), content));
+        }
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(kpar_path).unwrap());
+        for (name, content) in entries {
+            zip.start_file(name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(&content).unwrap();
+        }
+        zip.finish().unwrap();
+        Ok(())
+    }
+}
+
+struct Fail;
+
+impl KparPostProcessor for Fail {
+    fn name(&self) -> &str {
+        "fail"
+    }
+
+    fn process(&self, _: &Utf8Path, _: Option<&Utf8Path>) -> Result<(), String> {
+        Err("refused".to_owned())
+    }
+}
+
+fn build_test_project(root: &Utf8Path) -> LocalSrcProject {
+    let mut project = LocalSrcProject {
+        nominal_path: None,
+        project_path: root.to_owned(),
+        expected_checksum: None,
+    };
+    do_init("test".into(), None, "1.0.0".into(), None, &mut project).unwrap();
+    std::fs::write(root.join("test.sysml"), "package P;\n").unwrap();
+    do_include(
+        &mut project,
+        ["test.sysml".into()].into_iter(),
+        Some(HashAlg::Sha256),
+        true,
+        None,
+    )
+    .unwrap();
+    project
+}
+
+#[test]
+fn post_process_updates_checksums() {
+    let tmp = tempdir().unwrap();
+    let project = build_test_project(tmp.path());
+    let kpar_path = tmp.path().join("test.kpar");
+    let kpar = do_build_kpar(
+        &project,
+        &kpar_path,
+        KparCompressionMethod::default(),
+        true,
+        false,
+    )
+    .unwrap();
+    let digest_before = kpar.checksum_canonical_hex().unwrap().unwrap();
+
+    let new_source = "package P;\n// watermark\n";
+    let kpar = post_process_kpar(
+        kpar,
+        &[&ReplaceSource(new_source)],
+        Some(tmp.path()),
+        Jobs::default(),
+    )
+    .unwrap();
+
+    let meta = kpar.get_meta().unwrap().unwrap();
+    let checksum = &meta.checksum.unwrap()["test.sysml"];
+    assert_eq!(checksum.value, sha256_lowercase_hex(new_source.as_bytes()));
+    assert_ne!(
+        kpar.checksum_canonical_hex().unwrap().unwrap(),
+        digest_before
+    );
+}
+
+#[test]
+fn build_keeps_included_checksum_algorithm() {
+    let tmp = tempdir().unwrap();
+    let mut project = build_test_project(tmp.path());
+    std::fs::write(tmp.path().join("b.sysml"), "package B;\n").unwrap();
+    do_include(
+        &mut project,
+        ["b.sysml".into()].into_iter(),
+        Some(HashAlg::Blake3),
+        true,
+        None,
+    )
+    .unwrap();
+
+    let kpar = do_build_kpar(
+        &project,
+        tmp.path().join("test.kpar"),
+        KparCompressionMethod::default(),
+        true,
+        false,
+    )
+    .unwrap();
+
+    let checksums = kpar.get_meta().unwrap().unwrap().checksum.unwrap();
+    assert_eq!(checksums["test.sysml"].algorithm, "SHA256");
+    assert_eq!(checksums["b.sysml"].algorithm, "BLAKE3");
+    assert_eq!(
+        checksums["b.sysml"].value,
+        hash_lowercase_hex(HashAlg::Blake3, "package B;\n")
+    );
+}
+
+#[test]
+fn post_process_reports_failure() {
+    let tmp = tempdir().unwrap();
+    let project = build_test_project(tmp.path());
+    let kpar = do_build_kpar(
+        &project,
+        tmp.path().join("test.kpar"),
+        KparCompressionMethod::default(),
+        true,
+        false,
+    )
+    .unwrap();
+
+    let err = post_process_kpar(kpar, &[&Fail], None, Jobs::ONE).unwrap_err();
+    match err {
+        KParBuildError::PostProcess { name, message } => {
+            assert_eq!(name, "fail");
+            assert_eq!(message, "refused");
+        }
+        other => panic!("expected KParBuildError::PostProcess, got {other:?}"),
+    }
+}
+
+#[test]
+fn build_from_memory_fs() {
+    let fs = MemoryFs::new();
+    fs.write("project/A.sysml", "package A;").unwrap();
+    let mut project = VfsProject::new(fs.clone(), "project");
+    do_init(
+        "a".to_string(),
+        None,
+        "1.0.0".to_string(),
+        None,
+        &mut project,
+    )
+    .unwrap();
+    do_include(
+        &mut project,
+        ["A.sysml".into()].into_iter(),
+        Some(HashAlg::Sha256),
+        true,
+        None,
+    )
+    .unwrap();
+
+    let archive = do_build_kpar_to_writer(
+        &project,
+        std::io::Cursor::new(vec![]),
+        "a.kpar",
+        KparCompressionMethod::default(),
+        true,
+        false,
+    )
+    .unwrap()
+    .into_inner();
+    fs.write("output/a.kpar", &archive).unwrap();
+
+    // Check the archive by reading it back from disk
+    let tmp = tempdir().unwrap();
+    let disk = StdFs::new(tmp.path());
+    disk.write("a.kpar", fs.read("output/a.kpar").unwrap().unwrap())
+        .unwrap();
+    let kpar = LocalKParProject::new(tmp.path().join("a.kpar"), KparInnerPath::Guess, None, None);
+    let meta = kpar.get_meta().unwrap().unwrap();
+    assert_eq!(meta.index.get("A").map(String::as_str), Some("A.sysml"));
+    assert_eq!(
+        kpar.checksum_canonical_hex().unwrap(),
+        project.checksum_canonical_hex().unwrap()
+    );
+}
+
+fn build_with_manifest(
+    project: &LocalSrcProject,
+    kpar_path: &Utf8Path,
+    manifest: &KparManifest,
+) -> Result<LocalKParProject, KParBuildError<crate::project::local_src::LocalSrcError>> {
+    do_build_kpar_with_manifest(
+        project,
+        kpar_path,
+        KparCompression::default(),
+        true,
+        false,
+        manifest,
+    )?;
+    Ok(LocalKParProject::new(
+        kpar_path,
+        KparInnerPath::Guess,
+        None,
+        None,
+    ))
+}
+
+fn archive_entries(kpar_path: &Utf8Path) -> Vec<String> {
+    let archive = zip::ZipArchive::new(std::fs::File::open(kpar_path).unwrap()).unwrap();
+    archive.file_names().map(str::to_owned).collect()
+}
+
+#[test]
+fn build_packages_files_in_sorted_order() {
+    let tmp = tempdir().unwrap();
+    let mut project = build_test_project(tmp.path());
+    for name in ["b.sysml", "a.sysml", "c.sysml"] {
+        std::fs::write(tmp.path().join(name), "package Q;\n").unwrap();
+    }
+    do_include(
+        &mut project,
+        ["c.sysml".into(), "b.sysml".into(), "a.sysml".into()].into_iter(),
+        Some(HashAlg::Sha256),
+        false,
+        None,
+    )
+    .unwrap();
+
+    let kpar_path = tmp.path().join("test.kpar");
+    build_with_manifest(&project, &kpar_path, &KparManifest::default()).unwrap();
+    let entries = archive_entries(&kpar_path);
+    assert_eq!(
+        &entries[..4],
+        ["a.sysml", "b.sysml", "c.sysml", "test.sysml"]
+    );
+}
+
+#[test]
+fn build_excludes_files() {
+    let tmp = tempdir().unwrap();
+    let mut project = build_test_project(tmp.path());
+    std::fs::create_dir(tmp.path().join("drafts")).unwrap();
+    std::fs::write(tmp.path().join("drafts/d.sysml"), "package D;\n").unwrap();
+    do_include(
+        &mut project,
+        ["drafts/d.sysml".into()].into_iter(),
+        Some(HashAlg::Sha256),
+        true,
+        None,
+    )
+    .unwrap();
+
+    let kpar_path = tmp.path().join("test.kpar");
+    let manifest = KparManifest {
+        exclude: vec!["drafts/**".into()],
+        ..Default::default()
+    };
+    let kpar = build_with_manifest(&project, &kpar_path, &manifest).unwrap();
+    assert!(!archive_entries(&kpar_path).contains(&"drafts/d.sysml".to_owned()));
+    let meta = kpar.get_meta().unwrap().unwrap();
+    assert!(!meta.checksum.unwrap().contains_key("drafts/d.sysml"));
+    assert!(!meta.index.contains_key("D"));
+}
+
+#[test]
+fn build_include_matching_metadata() {
+    let tmp = tempdir().unwrap();
+    let project = build_test_project(tmp.path());
+    // Hidden directories are never packaged
+    std::fs::create_dir(tmp.path().join(".sysand")).unwrap();
+    std::fs::write(tmp.path().join(".sysand/x.sysml"), "package X;\n").unwrap();
+
+    let kpar_path = tmp.path().join("test.kpar");
+    let manifest = KparManifest {
+        include: vec!["**/*.sysml".into()],
+        ..Default::default()
+    };
+    build_with_manifest(&project, &kpar_path, &manifest).unwrap();
+    assert!(archive_entries(&kpar_path).contains(&"test.sysml".to_owned()));
+}
+
+#[test]
+fn build_include_rejects_dirty_project() {
+    let tmp = tempdir().unwrap();
+    let project = build_test_project(tmp.path());
+    std::fs::create_dir(tmp.path().join("extra")).unwrap();
+    std::fs::write(tmp.path().join("extra/new.sysml"), "package N;\n").unwrap();
+
+    let kpar_path = tmp.path().join("test.kpar");
+    let manifest = KparManifest {
+        include: vec!["extra/*.sysml".into()],
+        ..Default::default()
+    };
+    match build_with_manifest(&project, &kpar_path, &manifest).unwrap_err() {
+        KParBuildError::Dirty { missing, unindexed } => {
+            assert_eq!(missing, ["test.sysml"]);
+            assert_eq!(unindexed, ["extra/new.sysml"]);
+        }
+        other => panic!("expected KParBuildError::Dirty, got {other:?}"),
+    }
+    assert!(!kpar_path.exists());
+
+    let manifest = KparManifest {
+        allow_dirty: true,
+        ..manifest
+    };
+    let kpar = build_with_manifest(&project, &kpar_path, &manifest).unwrap();
+    let meta = kpar.get_meta().unwrap().unwrap();
+    assert_eq!(
+        meta.index.get("N").map(String::as_str),
+        Some("extra/new.sysml")
+    );
+    assert!(!meta.index.contains_key("P"));
+    assert!(!archive_entries(&kpar_path).contains(&"test.sysml".to_owned()));
+}
+
+#[test]
+fn build_rejects_invalid_glob() {
+    let tmp = tempdir().unwrap();
+    let project = build_test_project(tmp.path());
+    let manifest = KparManifest {
+        exclude: vec!["[".into()],
+        ..Default::default()
+    };
+    let err = build_with_manifest(&project, &tmp.path().join("test.kpar"), &manifest).unwrap_err();
+    assert!(matches!(err, KParBuildError::InvalidGlob(glob, _) if glob == "["));
+}
+
+#[test]
+fn source_date_epoch() {
+    let time = parse_source_date_epoch("1700000000").unwrap();
+    assert_eq!((time.year(), time.month(), time.day()), (2023, 11, 14));
+    assert_eq!((time.hour(), time.minute(), time.second()), (22, 13, 20));
+    assert_eq!(
+        parse_source_date_epoch(" 315532800\n"),
+        Some(zip::DateTime::DEFAULT)
+    );
+    // Before 1980
+    assert_eq!(parse_source_date_epoch("0"), None);
+    assert_eq!(parse_source_date_epoch("yesterday"), None);
+}
+
+#[test]
+fn rebuild_is_bit_identical() {
+    let tmp = tempdir().unwrap();
+    let project = build_test_project(tmp.path());
+    let first = tmp.path().join("first.kpar");
+    let second = tmp.path().join("second.kpar");
+    do_build_kpar(
+        &project,
+        &first,
+        KparCompressionMethod::default(),
+        true,
+        false,
+    )
+    .unwrap();
+
+    // Neither file times nor permissions of sources end up in the archive
+    let source = tmp.path().join("test.sysml");
+    std::fs::File::options()
+        .write(true)
+        .open(&source)
+        .unwrap()
+        .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(3600))
+        .unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        std::fs::set_permissions(&source, std::fs::Permissions::from_mode(0o600)).unwrap();
+    }
+    do_build_kpar(
+        &project,
+        &second,
+        KparCompressionMethod::default(),
+        true,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(
+        std::fs::read(&first).unwrap(),
+        std::fs::read(&second).unwrap()
+    );
+    let digest = do_check_reproducible_kpar(
+        &project,
+        &first,
+        KparCompression::default(),
+        true,
+        false,
+        &KparManifest::default(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(digest, sha256_lowercase_hex(std::fs::read(&first).unwrap()));
+
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(&first).unwrap()).unwrap();
+    for i in 0..archive.len() {
+        let file = archive.by_index(i).unwrap();
+        assert_eq!(file.unix_mode(), Some(0o100644), "{}", file.name());
+        assert_eq!(file.last_modified(), Some(zip::DateTime::DEFAULT));
+    }
+}
+
+#[test]
+fn check_reproducible_detects_changes() {
+    let tmp = tempdir().unwrap();
+    let project = build_test_project(tmp.path());
+    let kpar_path = tmp.path().join("test.kpar");
+    do_build_kpar(
+        &project,
+        &kpar_path,
+        KparCompressionMethod::default(),
+        true,
+        false,
+    )
+    .unwrap();
+    std::fs::write(tmp.path().join("test.sysml"), "package P;\n// changed\n").unwrap();
+
+    let err = do_check_reproducible_kpar(
+        &project,
+        &kpar_path,
+        KparCompression::default(),
+        true,
+        false,
+        &KparManifest::default(),
+        None,
+    )
+    .unwrap_err();
+    assert!(
+        matches!(err, KParBuildError::NotReproducible { built, rebuilt, .. } if built != rebuilt)
+    );
+}
+
+#[test]
+fn compression_level_is_validated() {
+    assert_eq!(
+        KparCompression::new(KparCompressionMethod::Deflated, Some(9)).unwrap(),
+        KparCompression {
+            method: KparCompressionMethod::Deflated,
+            level: Some(9),
+        }
+    );
+    assert!(matches!(
+        KparCompression::new(KparCompressionMethod::Deflated, Some(10)),
+        Err(CompressionMethodParseError::InvalidLevel {
+            level: 10,
+            range: Some(_),
+            ..
+        })
+    ));
+    assert!(matches!(
+        KparCompression::new(KparCompressionMethod::Stored, Some(1)),
+        Err(CompressionMethodParseError::InvalidLevel { range: None, .. })
+    ));
+    assert!(KparCompression::new(KparCompressionMethod::Stored, None).is_ok());
+}
+
+#[test]
+fn compression_level_is_used() {
+    let tmp = tempdir().unwrap();
+    let project = build_test_project(tmp.path());
+    std::fs::write(tmp.path().join("test.sysml"), "package P;\n".repeat(1000)).unwrap();
+    let build = |name: &str, compression: KparCompression| {
+        let path = tmp.path().join(name);
+        do_build_kpar_with_manifest(
+            &project,
+            &path,
+            compression,
+            true,
+            false,
+            &KparManifest::default(),
+        )
+        .unwrap();
+        std::fs::metadata(&path).unwrap().len()
+    };
+
+    let stored = build("stored.kpar", KparCompressionMethod::Stored.into());
+    let fast = build(
+        "fast.kpar",
+        KparCompression::new(KparCompressionMethod::Deflated, Some(1)).unwrap(),
+    );
+    let best = build(
+        "best.kpar",
+        KparCompression::new(KparCompressionMethod::Deflated, Some(9)).unwrap(),
+    );
+    assert!(fast < stored);
+    assert!(best <= fast);
+}
+
+#[test]
+fn sizes_are_human_readable() {
+    assert_eq!(format_size(0), "0 B");
+    assert_eq!(format_size(1023), "1023 B");
+    assert_eq!(format_size(1536), "1.5 KiB");
+    assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+}
+
+/// Write a source file of at least `size` bytes with top-level packages `A`
+/// and `B`, the first of which has a large body
+fn write_large_source(path: &Utf8Path, size: usize) {
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path).unwrap());
+    writeln!(file, "package A {{").unwrap();
+    let mut written = 0;
+    let mut i = 0;
+    while written < size {
+        let line =
+            format!("    part def 'P{i} }}' {{ doc /* {{ */ attribute a = \"{{{i}\"; }} // }}\n");
+        file.write_all(line.as_bytes()).unwrap();
+        written += line.len();
+        i += 1;
+    }
+    writeln!(file, "}}\npackage B;").unwrap();
+}
+
+#[test]
+fn build_streams_large_sources() {
+    let tmp = tempdir().unwrap();
+    let project = build_test_project(tmp.path());
+    let source_path = tmp.path().join("test.sysml");
+    write_large_source(&source_path, 16 * 1024 * 1024);
+
+    let kpar_path = tmp.path().join("test.kpar");
+    let kpar = do_build_kpar(
+        &project,
+        &kpar_path,
+        KparCompressionMethod::Stored,
+        true,
+        false,
+    )
+    .unwrap();
+
+    let meta = kpar.get_meta().unwrap().unwrap();
+    assert_eq!(meta.index["A"], "test.sysml");
+    assert_eq!(meta.index["B"], "test.sysml");
+    assert_eq!(meta.index.len(), 2);
+    let expected = hash_reader_hex(
+        HashAlg::Sha256,
+        &mut std::fs::File::open(&source_path).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(meta.checksum.unwrap()["test.sysml"].value, expected);
+
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(&kpar_path).unwrap()).unwrap();
+    let mut entry = archive.by_name("test.sysml").unwrap();
+    assert_eq!(entry.size(), std::fs::metadata(&source_path).unwrap().len());
+    assert_eq!(
+        hash_reader_hex(HashAlg::Sha256, &mut entry).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn build_reports_symbol_errors_in_whole_file() {
+    let tmp = tempdir().unwrap();
+    let project = build_test_project(tmp.path());
+    std::fs::write(
+        tmp.path().join("test.sysml"),
+        "package A {\n    part def X;\n}\npackage P, Q;\n",
+    )
+    .unwrap();
+
+    let err = do_build_kpar(
+        &project,
+        tmp.path().join("test.kpar"),
+        KparCompressionMethod::default(),
+        true,
+        false,
+    )
+    .unwrap_err();
+    let KParBuildError::Extract(message) = err else {
+        panic!("expected an extraction error, got {err:?}");
+    };
+    assert!(message.contains("line 4"), "{message}");
+}
+
+#[test]
+fn build_rejects_invalid_utf8_sources() {
+    let tmp = tempdir().unwrap();
+    let project = build_test_project(tmp.path());
+    std::fs::write(tmp.path().join("test.sysml"), b"package A { \xff }\n").unwrap();
+
+    let err = do_build_kpar(
+        &project,
+        tmp.path().join("test.kpar"),
+        KparCompressionMethod::default(),
+        true,
+        false,
+    )
+    .unwrap_err();
+    assert!(matches!(err, KParBuildError::Io(_)), "{err:?}");
+}
+
+#[cfg(feature = "large-kpar-tests")]
+#[test]
+fn build_source_over_4_gib_uses_zip64() {
+    let tmp = tempdir().unwrap();
+    let project = build_test_project(tmp.path());
+    // Padding goes inside the package body, so it is not part of the
+    // top-level text kept for symbol extraction
+    let mut file = std::fs::File::create(tmp.path().join("test.sysml")).unwrap();
+    file.write_all(b"package P {\n").unwrap();
+    let padding = vec![b' '; 1 << 20];
+    for _ in 0..4097 {
+        file.write_all(&padding).unwrap();
+    }
+    file.write_all(b"}\n").unwrap();
+    let size = file.metadata().unwrap().len();
+    assert!(size > u32::MAX as u64);
+    drop(file);
+
+    let kpar_path = tmp.path().join("test.kpar");
+    do_build_kpar(
+        &project,
+        &kpar_path,
+        KparCompressionMethod::Deflated,
+        true,
+        false,
+    )
+    .unwrap();
+
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(&kpar_path).unwrap()).unwrap();
+    assert_eq!(archive.by_name("test.sysml").unwrap().size(), size);
+    let kpar = LocalKParProject::new(&kpar_path, KparInnerPath::Guess, None, None);
+    assert_eq!(kpar.get_info().unwrap().unwrap().name, "test");
+}
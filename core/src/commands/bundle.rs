@@ -0,0 +1,385 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Bundles of a project and its entire resolved dependency closure, as
+//! KPARs next to a `bundle.toml` manifest, for installing into an
+//! environment offline. A bundle is either a zip archive or a directory
+//! with the same layout:
+//!
+//! ```text
+//! bundle.toml
+//! kpars/
+//!     Vehicle-1.0.0.kpar
+//!     Units-2.1.0.kpar
+//! ```
+
+use std::io::{self, Seek, Write};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    build::{KparCompressionMethod, default_kpar_file_name, do_build_kpar},
+    commands::env::do_env_install_project,
+    env::{OverwritePolicy, ReadEnvironment, local_directory::LocalDirectoryEnvironment},
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    lock::{Lock, Project, Source},
+    model::HashAlg,
+    project::{
+        ProjectChecksum, ProjectRead, hash_reader_hex,
+        local_kpar::LocalKParProjectRaw,
+        local_src::LocalSrcProject,
+        utils::{FsIoError, ZipArchiveError, wrapfs},
+    },
+    utils::format_err,
+};
+
+pub const BUNDLE_MANIFEST: &str = "bundle.toml";
+pub const BUNDLE_KPAR_DIR: &str = "kpars";
+pub const CURRENT_BUNDLE_VERSION: &str = "0.1";
+
+/// Layout of a bundle
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BundleFormat {
+    /// Single zip archive
+    #[default]
+    Zip,
+    /// Directory tree
+    Directory,
+}
+
+/// Project in a bundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundledProject {
+    pub name: String,
+    pub version: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub identifiers: Vec<String>,
+    /// Path of the KPAR inside the bundle
+    pub kpar: String,
+    /// SHA256 hex digest of the KPAR
+    pub kpar_digest: String,
+}
+
+/// Contents of `bundle.toml`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub bundle_version: String,
+    #[serde(rename = "project", skip_serializing_if = "Vec::is_empty", default)]
+    pub projects: Vec<BundledProject>,
+}
+
+impl Default for BundleManifest {
+    fn default() -> Self {
+        BundleManifest {
+            bundle_version: CURRENT_BUNDLE_VERSION.to_owned(),
+            projects: vec![],
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum BundleError {
+    #[error(
+        "project `{name}` {version} is not installed in the environment;\n\
+        run `sysand sync` before bundling"
+    )]
+    MissingFromEnv { name: String, version: String },
+    #[error("project `{0}` has no identifiers, so it cannot be installed from a bundle")]
+    MissingIdentifiers(String),
+    #[error("failed to build KPAR of project `{project}`: {message}")]
+    Build { project: String, message: String },
+    #[error("failed to install project `{project}` from the bundle: {message}")]
+    Install { project: String, message: String },
+    #[error("KPAR `{kpar}` in the bundle has SHA256 digest `{actual}`, expected `{expected}`")]
+    DigestMismatch {
+        kpar: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("KPAR path `{0}` points outside the bundle")]
+    InvalidKparPath(String),
+    #[error("invalid bundle manifest `{path}`:\n{message}")]
+    Manifest { path: Box<str>, message: String },
+    #[error("unsupported bundle version `{0}`, expected `{CURRENT_BUNDLE_VERSION}`")]
+    UnsupportedVersion(String),
+    #[error(transparent)]
+    Zip(#[from] ZipArchiveError),
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+}
+
+impl From<FsIoError> for BundleError {
+    fn from(v: FsIoError) -> Self {
+        Self::Io(Box::new(v))
+    }
+}
+
+impl HasErrorCode for BundleError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            BundleError::MissingFromEnv { .. } => ErrorCode::new(2601, ErrorClass::Environment),
+            BundleError::MissingIdentifiers(_) => ErrorCode::new(2602, ErrorClass::Project),
+            BundleError::Build { .. } => ErrorCode::new(2603, ErrorClass::Build),
+            BundleError::Install { .. } => ErrorCode::new(2604, ErrorClass::Environment),
+            BundleError::DigestMismatch { .. } => ErrorCode::new(2605, ErrorClass::Project),
+            BundleError::InvalidKparPath(_) => ErrorCode::new(2606, ErrorClass::Project),
+            BundleError::Manifest { .. } => ErrorCode::new(2607, ErrorClass::Project),
+            BundleError::UnsupportedVersion(_) => ErrorCode::new(2608, ErrorClass::Project),
+            BundleError::Zip(_) => ErrorCode::new(2609, ErrorClass::Io),
+            BundleError::Io(e) => e.error_code(),
+        }
+    }
+}
+
+/// Bundle every project in `lock` into `output`. Editable projects are
+/// read from their directories, relative to `root` (the directory of the
+/// lockfile), the others from `env`, so the environment must be in sync
+/// with the lockfile. Returns the manifest of the bundle
+pub fn do_bundle<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+    lock: &Lock,
+    root: P,
+    env: &LocalDirectoryEnvironment,
+    output: Q,
+    format: BundleFormat,
+) -> Result<BundleManifest, BundleError> {
+    let root = root.as_ref();
+    let output = output.as_ref();
+    match format {
+        BundleFormat::Directory => {
+            wrapfs::create_dir_all(output)?;
+            write_bundle(lock, root, env, output)
+        }
+        BundleFormat::Zip => {
+            let staging = camino_tempfile::tempdir().map_err(FsIoError::MkTempDir)?;
+            let manifest = write_bundle(lock, root, env, staging.path())?;
+            let file = wrapfs::File::create(output)?;
+            write_zip(staging.path(), &manifest, file, output)?;
+            Ok(manifest)
+        }
+    }
+}
+
+/// Install every project of the bundle at `path` (a zip archive or a
+/// directory) that is not yet installed in `env`. Returns the projects that
+/// were installed
+pub fn do_bundle_install<P: AsRef<Utf8Path>>(
+    path: P,
+    env: &mut LocalDirectoryEnvironment,
+) -> Result<Vec<BundledProject>, BundleError> {
+    let path = path.as_ref();
+    if wrapfs::is_dir(path)? {
+        return install_bundle_dir(path, env);
+    }
+
+    let extracted = camino_tempfile::tempdir().map_err(FsIoError::MkTempDir)?;
+    let mut zip = zip::ZipArchive::new(wrapfs::File::open(path)?)
+        .map_err(|e| ZipArchiveError::read_archive(path.into(), e))?;
+    zip.extract(extracted.path())
+        .map_err(|e| ZipArchiveError::read_archive(path.into(), e))?;
+    install_bundle_dir(extracted.path(), env)
+}
+
+/// Read `bundle.toml` of the bundle directory `dir`
+pub fn read_bundle_manifest<P: AsRef<Utf8Path>>(dir: P) -> Result<BundleManifest, BundleError> {
+    let path = dir.as_ref().join(BUNDLE_MANIFEST);
+    let manifest: BundleManifest =
+        toml::from_str(&wrapfs::read_to_string(&path)?).map_err(|e| BundleError::Manifest {
+            path: path.as_str().into(),
+            message: e.to_string(),
+        })?;
+    if manifest.bundle_version != CURRENT_BUNDLE_VERSION {
+        return Err(BundleError::UnsupportedVersion(manifest.bundle_version));
+    }
+    Ok(manifest)
+}
+
+fn write_bundle(
+    lock: &Lock,
+    root: &Utf8Path,
+    env: &LocalDirectoryEnvironment,
+    dir: &Utf8Path,
+) -> Result<BundleManifest, BundleError> {
+    let kpar_dir = dir.join(BUNDLE_KPAR_DIR);
+    wrapfs::create_dir_all(&kpar_dir)?;
+
+    let mut manifest = BundleManifest::default();
+    for project in &lock.projects {
+        let editable = project.sources.iter().find_map(|s| match s {
+            Source::Editable { editable } => Some(editable),
+            _ => None,
+        });
+        let file_name = match editable {
+            Some(editable) => {
+                let storage = LocalSrcProject {
+                    nominal_path: None,
+                    project_path: root.join(editable.as_str()),
+                    expected_checksum: None,
+                };
+                build_kpar(project, &storage, &kpar_dir)?
+            }
+            None => {
+                let iri = project
+                    .identifiers
+                    .first()
+                    .ok_or_else(|| BundleError::MissingIdentifiers(project.name.clone()))?;
+                let missing = || BundleError::MissingFromEnv {
+                    name: project.name.clone(),
+                    version: project.version.clone(),
+                };
+                if !env
+                    .has_version(iri, &project.version)
+                    .map_err(|_| missing())?
+                {
+                    return Err(missing());
+                }
+                let storage = env
+                    .get_project(iri, &project.version)
+                    .map_err(|_| missing())?;
+                build_kpar(project, &storage, &kpar_dir)?
+            }
+        };
+
+        let kpar_path = kpar_dir.join(&file_name);
+        let kpar_digest = hash_reader_hex(HashAlg::Sha256, &mut wrapfs::File::open(&kpar_path)?)
+            .map_err(|e| FsIoError::ReadFile(kpar_path.clone(), e))?;
+        manifest.projects.push(BundledProject {
+            name: project.name.clone(),
+            version: project.version.clone(),
+            identifiers: project.identifiers.clone(),
+            kpar: format!("{BUNDLE_KPAR_DIR}/{file_name}"),
+            kpar_digest,
+        });
+    }
+
+    let contents = toml::to_string_pretty(&manifest).map_err(|e| BundleError::Manifest {
+        path: BUNDLE_MANIFEST.into(),
+        message: e.to_string(),
+    })?;
+    wrapfs::write(dir.join(BUNDLE_MANIFEST), contents)?;
+    Ok(manifest)
+}
+
+/// Build `storage` into `kpar_dir`, returning the file name of the KPAR.
+/// The name is made unique if projects share a name and version
+fn build_kpar<Pr: ProjectRead>(
+    project: &Project,
+    storage: &Pr,
+    kpar_dir: &Utf8Path,
+) -> Result<String, BundleError> {
+    let build_error = |message: String| BundleError::Build {
+        project: project.name.clone(),
+        message,
+    };
+    let default_name = default_kpar_file_name(storage).map_err(|e| build_error(format_err(e)))?;
+    let stem = default_name.trim_end_matches(".kpar");
+    let mut file_name = default_name.clone();
+    let mut n = 1;
+    while wrapfs::is_file(kpar_dir.join(&file_name))? {
+        n += 1;
+        file_name = format!("{stem}-{n}.kpar");
+    }
+    do_build_kpar(
+        storage,
+        kpar_dir.join(&file_name),
+        KparCompressionMethod::default(),
+        false,
+        true,
+    )
+    .map_err(|e| build_error(format_err(e)))?;
+    Ok(file_name)
+}
+
+/// Store the bundle directory `dir` in a zip archive. KPARs are already
+/// compressed, so entries are stored as-is
+fn write_zip<W: Write + Seek>(
+    dir: &Utf8Path,
+    manifest: &BundleManifest,
+    writer: W,
+    output: &Utf8Path,
+) -> Result<(), BundleError> {
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Stored)
+        .system(zip::System::Unix);
+    let mut zip = zip::ZipWriter::new(writer);
+    let entries =
+        std::iter::once(BUNDLE_MANIFEST).chain(manifest.projects.iter().map(|p| p.kpar.as_str()));
+    for name in entries {
+        zip.start_file(name, options)
+            .map_err(|e| ZipArchiveError::Write(Utf8Path::new(name).into(), e))?;
+        let source = dir.join(name);
+        io::copy(&mut wrapfs::File::open(&source)?, &mut zip)
+            .map_err(|e| FsIoError::WriteFile(output.to_owned(), e))?;
+    }
+    zip.finish()
+        .map_err(|e| ZipArchiveError::Finish(output.into(), e))?;
+    Ok(())
+}
+
+fn install_bundle_dir(
+    dir: &Utf8Path,
+    env: &mut LocalDirectoryEnvironment,
+) -> Result<Vec<BundledProject>, BundleError> {
+    let manifest = read_bundle_manifest(dir)?;
+    let mut installed = vec![];
+    for project in manifest.projects {
+        let Some(iri) = project.identifiers.first() else {
+            log::warn!(
+                "skipping project `{}` {}: it has no identifiers",
+                project.name,
+                project.version
+            );
+            continue;
+        };
+        let install_error = |message: String| BundleError::Install {
+            project: project.name.clone(),
+            message,
+        };
+        if env
+            .has_version(iri, &project.version)
+            .map_err(|e| install_error(format_err(e)))?
+        {
+            log::debug!("`{iri}` {} is already installed", project.version);
+            continue;
+        }
+
+        if Utf8Path::new(&project.kpar)
+            .components()
+            .any(|c| !matches!(c, camino::Utf8Component::Normal(_)))
+        {
+            return Err(BundleError::InvalidKparPath(project.kpar));
+        }
+        let kpar_path: Utf8PathBuf = dir.join(&project.kpar);
+        let actual = hash_reader_hex(HashAlg::Sha256, &mut wrapfs::File::open(&kpar_path)?)
+            .map_err(|e| FsIoError::ReadFile(kpar_path.clone(), e))?;
+        if actual != project.kpar_digest {
+            return Err(BundleError::DigestMismatch {
+                kpar: project.kpar,
+                expected: project.kpar_digest,
+                actual,
+            });
+        }
+
+        let storage = LocalKParProjectRaw::new_guess_root(&kpar_path)
+            .map_err(|e| install_error(format_err(e)))?;
+        do_env_install_project(
+            iri,
+            &project.version,
+            &storage,
+            Some(ProjectChecksum::Kpar(actual)),
+            env,
+            OverwritePolicy::Error,
+            true,
+            None,
+            None,
+        )
+        .map_err(|e| install_error(format_err(e)))?;
+        installed.push(project);
+    }
+    Ok(installed)
+}
+
+#[cfg(test)]
+#[path = "./bundle_tests.rs"]
+mod tests;
@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use camino::Utf8Path;
+use camino_tempfile::{Utf8TempDir, tempdir};
+use typed_path::Utf8UnixPathBuf;
+
+use crate::{
+    commands::{
+        bundle::{
+            BUNDLE_MANIFEST, BundleError, BundleFormat, do_bundle, do_bundle_install,
+            read_bundle_manifest,
+        },
+        env::do_env_install_project,
+    },
+    env::{
+        DEFAULT_ENV_NAME, OverwritePolicy, ReadEnvironment,
+        local_directory::LocalDirectoryEnvironment,
+    },
+    init::{do_init, do_init_memory},
+    lock::{Lock, Project, Source},
+    project::local_src::LocalSrcProject,
+};
+
+fn lock_project(name: &str, version: &str, sources: Vec<Source>) -> Project {
+    Project {
+        publisher: None,
+        name: name.into(),
+        version: version.into(),
+        exports: vec![],
+        identifiers: if sources.is_empty() {
+            vec![format!("urn:kpar:{name}")]
+        } else {
+            vec![]
+        },
+        usages: vec![],
+        sources,
+        variant: None,
+    }
+}
+
+fn make_env(root: &Utf8Path, projects: &[(&str, &str)]) -> LocalDirectoryEnvironment {
+    let env_dir = root.join(DEFAULT_ENV_NAME);
+    std::fs::create_dir_all(&env_dir).unwrap();
+    let mut env = LocalDirectoryEnvironment::create(&env_dir).unwrap();
+    for (name, version) in projects {
+        let project = do_init_memory(*name, None::<&str>, *version, None).unwrap();
+        do_env_install_project(
+            format!("urn:kpar:{name}"),
+            version,
+            &project,
+            None,
+            &mut env,
+            OverwritePolicy::Error,
+            false,
+            None,
+            None,
+        )
+        .unwrap();
+    }
+    env
+}
+
+/// Project `main` with dependencies `a` and `b` installed in its env
+fn bundle_project() -> (Utf8TempDir, Lock, LocalDirectoryEnvironment) {
+    let cwd = tempdir().unwrap();
+    let mut project = LocalSrcProject {
+        nominal_path: None,
+        project_path: cwd.path().to_owned(),
+        expected_checksum: None,
+    };
+    do_init("main".into(), None, "0.1.0".into(), None, &mut project).unwrap();
+    let env = make_env(cwd.path(), &[("a", "1.0.0"), ("b", "2.0.0")]);
+    let lock = Lock {
+        projects: vec![
+            lock_project(
+                "main",
+                "0.1.0",
+                vec![Source::Editable {
+                    editable: Utf8UnixPathBuf::from("."),
+                }],
+            ),
+            lock_project("a", "1.0.0", vec![]),
+            lock_project("b", "2.0.0", vec![]),
+        ],
+        ..Default::default()
+    };
+    (cwd, lock, env)
+}
+
+#[test]
+fn bundle_roundtrip() {
+    for format in [BundleFormat::Zip, BundleFormat::Directory] {
+        let (cwd, lock, env) = bundle_project();
+        let output = cwd.path().join("bundle");
+        let manifest = do_bundle(&lock, cwd.path(), &env, &output, format).unwrap();
+        let kpars: Vec<_> = manifest.projects.iter().map(|p| p.kpar.as_str()).collect();
+        assert_eq!(
+            kpars,
+            [
+                "kpars/main-0.1.0.kpar",
+                "kpars/a-1.0.0.kpar",
+                "kpars/b-2.0.0.kpar"
+            ]
+        );
+        if format == BundleFormat::Directory {
+            assert_eq!(read_bundle_manifest(&output).unwrap(), manifest);
+        } else {
+            assert!(output.is_file());
+        }
+
+        let target = tempdir().unwrap();
+        let mut target_env = make_env(target.path(), &[("a", "1.0.0")]);
+        let installed = do_bundle_install(&output, &mut target_env).unwrap();
+        // `main` has no identifiers and `a` is already installed
+        let names: Vec<_> = installed.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["b"], "{format:?}");
+        assert!(target_env.has_version("urn:kpar:b", "2.0.0").unwrap());
+    }
+}
+
+#[test]
+fn bundle_requires_synced_env() {
+    let (cwd, mut lock, env) = bundle_project();
+    lock.projects.push(lock_project("c", "3.0.0", vec![]));
+    let err = do_bundle(
+        &lock,
+        cwd.path(),
+        &env,
+        cwd.path().join("bundle"),
+        BundleFormat::Directory,
+    )
+    .unwrap_err();
+    assert!(
+        matches!(&err, BundleError::MissingFromEnv { name, .. } if name == "c"),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn bundle_install_checks_digests() {
+    let (cwd, lock, env) = bundle_project();
+    let output = cwd.path().join("bundle");
+    do_bundle(&lock, cwd.path(), &env, &output, BundleFormat::Directory).unwrap();
+    std::fs::write(output.join("kpars/b-2.0.0.kpar"), b"tampered").unwrap();
+
+    let target = tempdir().unwrap();
+    let mut target_env = make_env(target.path(), &[]);
+    let err = do_bundle_install(&output, &mut target_env).unwrap_err();
+    assert!(
+        matches!(&err, BundleError::DigestMismatch { kpar, .. } if kpar == "kpars/b-2.0.0.kpar"),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn bundle_install_rejects_unknown_version() {
+    let dir = tempdir().unwrap();
+    std::fs::write(
+        dir.path().join(BUNDLE_MANIFEST),
+        "bundle_version = \"9.0\"\n",
+    )
+    .unwrap();
+    let mut env = make_env(dir.path(), &[]);
+    let err = do_bundle_install(dir.path(), &mut env).unwrap_err();
+    assert!(
+        matches!(&err, BundleError::UnsupportedVersion(v) if v == "9.0"),
+        "{err:?}"
+    );
+}
@@ -0,0 +1,203 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::fmt::Display;
+
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use typed_path::Utf8UnixPathBuf;
+
+use crate::{
+    env::local_directory::{
+        LocalDirectoryEnvironment,
+        metadata::{EnvProject, EnvProjectChecksum},
+    },
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    lock::{CURRENT_LOCK_VERSION, Lock, Project, Source},
+};
+
+const CURRENT_EXPORT_VERSION: &str = "0.1";
+
+/// Installed projects of an environment, as written by `sysand env export`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct EnvExport {
+    pub version: String,
+    #[serde(default)]
+    pub projects: Vec<EnvExportProject>,
+}
+
+/// Installed project together with the sources it can be installed from
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct EnvExportProject {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub publisher: Option<String>,
+    pub name: String,
+    pub version: String,
+    pub identifiers: Vec<String>,
+    /// Checksum recorded in the environment when the project was installed
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<EnvProjectChecksum>,
+    pub sources: Vec<Source>,
+}
+
+#[derive(Error, Debug)]
+pub enum EnvExportError {
+    #[error("unable to find sources of installed project `{iri}` version `{version}`: {message}")]
+    Sources {
+        iri: String,
+        version: String,
+        message: String,
+    },
+    #[error("no sources found for installed project `{iri}` version `{version}`")]
+    MissingSources { iri: String, version: String },
+    #[error("environment export version `{0}` is not supported")]
+    UnsupportedVersion(String),
+    #[error(
+        "project `{iri}` version `{version}` was exported with checksum `{expected}`,\n\
+        but installed with checksum `{actual}`"
+    )]
+    ChecksumMismatch {
+        iri: String,
+        version: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl HasErrorCode for EnvExportError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            EnvExportError::Sources { .. } => ErrorCode::new(1170, ErrorClass::Resolution),
+            EnvExportError::MissingSources { .. } => ErrorCode::new(1171, ErrorClass::Resolution),
+            EnvExportError::UnsupportedVersion(_) => ErrorCode::new(1172, ErrorClass::Environment),
+            EnvExportError::ChecksumMismatch { .. } => {
+                ErrorCode::new(1173, ErrorClass::Environment)
+            }
+        }
+    }
+}
+
+/// Export all projects installed in `env`, skipping editable ones. Sources
+/// are those recorded in the environment, otherwise those returned by
+/// `find_sources`. Relative paths of local sources are made absolute with
+/// `root`, so that the export can be imported elsewhere
+pub fn do_env_export<E: Display, F: FnMut(&EnvProject) -> Result<Vec<Source>, E>>(
+    env: &LocalDirectoryEnvironment,
+    root: &Utf8Path,
+    mut find_sources: F,
+) -> Result<EnvExport, EnvExportError> {
+    let mut projects = vec![];
+    for project in env.projects().iter().filter(|p| !p.editable) {
+        let iri = project.identifiers.first().cloned().unwrap_or_default();
+        let mut sources = if project.sources.is_empty() {
+            find_sources(project).map_err(|e| EnvExportError::Sources {
+                iri: iri.clone(),
+                version: project.version.clone(),
+                message: e.to_string(),
+            })?
+        } else {
+            project.sources.clone()
+        };
+        sources.retain(|s| !matches!(s, Source::Editable { .. }));
+        if sources.is_empty() {
+            return Err(EnvExportError::MissingSources {
+                iri,
+                version: project.version.clone(),
+            });
+        }
+        for source in &mut sources {
+            if let Source::LocalSrc { src_path: path, .. }
+            | Source::LocalKpar {
+                kpar_path: path, ..
+            } = source
+                && path.is_relative()
+            {
+                *path = Utf8UnixPathBuf::from(root.as_str()).join(&*path);
+            }
+        }
+        projects.push(EnvExportProject {
+            publisher: project.publisher.clone(),
+            name: project.name.clone(),
+            version: project.version.clone(),
+            identifiers: project.identifiers.clone(),
+            checksum: project.checksum.clone(),
+            sources,
+        });
+    }
+    Ok(EnvExport {
+        version: CURRENT_EXPORT_VERSION.to_owned(),
+        projects,
+    })
+}
+
+impl EnvExport {
+    /// Lock with exactly the exported projects, for installing them
+    pub fn to_lock(&self) -> Result<Lock, EnvExportError> {
+        if self.version != CURRENT_EXPORT_VERSION {
+            return Err(EnvExportError::UnsupportedVersion(self.version.clone()));
+        }
+        let mut projects = vec![];
+        for project in &self.projects {
+            if project.sources.is_empty() {
+                return Err(EnvExportError::MissingSources {
+                    iri: project.identifiers.first().cloned().unwrap_or_default(),
+                    version: project.version.clone(),
+                });
+            }
+            projects.push(Project {
+                publisher: project.publisher.clone(),
+                name: project.name.clone(),
+                version: project.version.clone(),
+                exports: vec![],
+                identifiers: project.identifiers.clone(),
+                usages: vec![],
+                sources: project.sources.clone(),
+                variant: None,
+            });
+        }
+        Ok(Lock {
+            lock_version: CURRENT_LOCK_VERSION.to_owned(),
+            projects,
+            ..Default::default()
+        })
+    }
+
+    /// Check that the projects installed in `env` have the exported checksums
+    pub fn verify(&self, env: &LocalDirectoryEnvironment) -> Result<(), EnvExportError> {
+        for project in &self.projects {
+            let Some(expected) = &project.checksum else {
+                continue;
+            };
+            let installed = env.projects().iter().find(|p| {
+                !p.editable
+                    && p.version == project.version
+                    && p.identifiers
+                        .iter()
+                        .any(|iri| project.identifiers.contains(iri))
+            });
+            if let Some(actual) = installed.and_then(|p| p.checksum.as_ref())
+                && actual != expected
+            {
+                return Err(EnvExportError::ChecksumMismatch {
+                    iri: project.identifiers.first().cloned().unwrap_or_default(),
+                    version: project.version.clone(),
+                    expected: checksum_str(expected).to_owned(),
+                    actual: checksum_str(actual).to_owned(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn checksum_str(checksum: &EnvProjectChecksum) -> &str {
+    match checksum {
+        EnvProjectChecksum::Kpar { kpar_cksum } => kpar_cksum,
+        EnvProjectChecksum::Project { src_cksum } => src_cksum,
+    }
+}
+
+#[cfg(test)]
+#[path = "./export_tests.rs"]
+mod tests;
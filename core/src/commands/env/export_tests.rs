@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::{assert_matches, convert::Infallible};
+
+use camino_tempfile::tempdir;
+use indexmap::IndexMap;
+
+use super::{EnvExportError, do_env_export};
+use crate::{
+    commands::env::do_env_install_project,
+    env::{DEFAULT_ENV_NAME, OverwritePolicy, local_directory::LocalDirectoryEnvironment},
+    lock::Source,
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw, format_created_now},
+    project::{ProjectChecksum, ProjectMut, memory::InMemoryProject},
+};
+
+fn project(name: &str) -> InMemoryProject {
+    let info = InterchangeProjectInfoRaw {
+        name: name.to_string(),
+        publisher: None,
+        description: None,
+        version: "1.0.0".to_string(),
+        license: None,
+        maintainer: vec![],
+        website: None,
+        topic: vec![],
+        usage: vec![],
+    };
+    let meta = InterchangeProjectMetadataRaw {
+        index: IndexMap::new(),
+        created: format_created_now(),
+        metamodel: None,
+        includes_derived: None,
+        includes_implied: None,
+        checksum: None,
+        variants: IndexMap::new(),
+    };
+    let mut project = InMemoryProject::default();
+    project.put_project(&info, &meta, true).unwrap();
+    project
+}
+
+#[test]
+fn export_and_lock() {
+    let cwd = tempdir().unwrap();
+    let env_dir = cwd.path().join(DEFAULT_ENV_NAME);
+    std::fs::create_dir(&env_dir).unwrap();
+    let mut env = LocalDirectoryEnvironment::create(&env_dir).unwrap();
+    let remote = vec![Source::RemoteSrc {
+        remote_src: "https://example.com/a".to_string(),
+        checksum: "0".repeat(64),
+    }];
+    do_env_install_project(
+        "urn:kpar:a",
+        "1.0.0",
+        &project("a"),
+        None,
+        &mut env,
+        OverwritePolicy::Error,
+        false,
+        Some(&remote),
+        None,
+    )
+    .unwrap();
+    do_env_install_project(
+        "urn:kpar:b",
+        "1.0.0",
+        &project("b"),
+        Some(ProjectChecksum::Project("1".repeat(64))),
+        &mut env,
+        OverwritePolicy::Error,
+        false,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let export = do_env_export(&env, cwd.path(), |p| {
+        assert_eq!(p.name, "b");
+        Ok::<_, Infallible>(vec![Source::LocalSrc {
+            src_path: "deps/b".into(),
+            checksum: "1".repeat(64),
+        }])
+    })
+    .unwrap();
+
+    assert_eq!(export.projects.len(), 2);
+    assert_eq!(export.projects[0].sources, remote);
+    assert_eq!(
+        export.projects[1].sources,
+        [Source::LocalSrc {
+            src_path: format!("{}/deps/b", cwd.path()).into(),
+            checksum: "1".repeat(64),
+        }]
+    );
+    let json = serde_json::to_string(&export).unwrap();
+    assert!(json.contains(&format!(r#""src_cksum":"{}""#, "1".repeat(64))));
+    assert_eq!(
+        serde_json::from_str::<super::EnvExport>(&json).unwrap(),
+        export
+    );
+
+    let lock = export.to_lock().unwrap();
+    assert_eq!(lock.projects.len(), 2);
+    assert_eq!(lock.projects[1].identifiers, ["urn:kpar:b"]);
+    export.verify(&env).unwrap();
+
+    let mut changed = export.clone();
+    changed.projects[1].checksum = Some(super::EnvProjectChecksum::Project {
+        src_cksum: "2".repeat(64),
+    });
+    assert_matches!(
+        changed.verify(&env),
+        Err(EnvExportError::ChecksumMismatch { iri, .. }) if iri == "urn:kpar:b"
+    );
+}
+
+#[test]
+fn export_without_sources() {
+    let cwd = tempdir().unwrap();
+    let env_dir = cwd.path().join(DEFAULT_ENV_NAME);
+    std::fs::create_dir(&env_dir).unwrap();
+    let mut env = LocalDirectoryEnvironment::create(&env_dir).unwrap();
+    do_env_install_project(
+        "urn:kpar:a",
+        "1.0.0",
+        &project("a"),
+        None,
+        &mut env,
+        OverwritePolicy::Error,
+        false,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let err = do_env_export(&env, cwd.path(), |_| Ok::<_, Infallible>(vec![])).unwrap_err();
+    assert_matches!(err, EnvExportError::MissingSources { iri, .. } if iri == "urn:kpar:a");
+}
@@ -3,63 +3,45 @@
 
 use thiserror::Error;
 
+#[cfg(feature = "filesystem")]
+use crate::project::local_kpar::{LocalKParError, LocalKParProject};
 use crate::{
     env::{
-        PutProjectError, ReadEnvironment, WriteEnvironment,
-        utils::{CloneError, clone_project},
+        OverwritePolicy, PutProjectError, ReadEnvironment, WriteEnvironment,
+        utils::{CloneError, ErrorBound, clone_project, clone_project_metadata},
     },
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    lock::Source,
     project::{ProjectChecksum, ProjectRead},
+    timings::{self, Phase},
 };
 
 #[derive(Error, Debug)]
 enum CheckInstallError<EnvReadError> {
     #[error("project with IRI `{0}` is already installed")]
     AlreadyInstalled(Box<str>),
-    #[error("project with IRI `{0}` already has version `{1}` installed")]
-    AlreadyInstalledVersion(Box<str>, String),
-    #[error("unknown version of project with IRI `{0}` is already installed")]
-    AlreadyInstalledUnknownVersion(Box<str>),
     #[error("environment read error: {0}")]
     EnvRead(EnvReadError),
 }
 
+/// Installing another version of an installed project requires
+/// `allow_multiple`. Reinstalling the same version is decided by the
+/// overwrite policy passed to `put_project`
 fn check_install<S: AsRef<str>, E: ReadEnvironment>(
     uri: S,
     version: &str,
     env: &E,
-    allow_overwrite: bool,
     allow_multiple: bool,
 ) -> Result<(), CheckInstallError<E::ReadError>> {
-    if allow_overwrite && allow_multiple {
+    if allow_multiple || !env.has(&uri).map_err(CheckInstallError::EnvRead)? {
         return Ok(());
     }
-    let project_present = env.has(&uri).map_err(CheckInstallError::EnvRead)?;
-
-    if !allow_overwrite && !allow_multiple {
-        if project_present {
-            return Err(CheckInstallError::AlreadyInstalled(uri.as_ref().into()));
-        }
-        return Ok(());
+    let version_present = env
+        .has_version(&uri, version)
+        .map_err(CheckInstallError::EnvRead)?;
+    if !version_present {
+        return Err(CheckInstallError::AlreadyInstalled(uri.as_ref().into()));
     }
-
-    if project_present {
-        let version_present = env
-            .has_version(&uri, version)
-            .map_err(CheckInstallError::EnvRead)?;
-
-        if !allow_overwrite && version_present {
-            return Err(CheckInstallError::AlreadyInstalledVersion(
-                uri.as_ref().into(),
-                version.to_owned(),
-            ));
-        }
-        if !allow_multiple && !version_present {
-            return Err(CheckInstallError::AlreadyInstalledUnknownVersion(
-                uri.as_ref().into(),
-            ));
-        }
-    }
-
     Ok(())
 }
 
@@ -81,6 +63,26 @@ pub enum EnvInstallError<EnvReadError, ProjectReadError, InstallationError> {
     Installation(InstallationError),
 }
 
+impl<EnvReadError, ProjectReadError, InstallationError> HasErrorCode
+    for EnvInstallError<EnvReadError, ProjectReadError, InstallationError>
+{
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            EnvInstallError::AlreadyInstalled(_) => ErrorCode::new(1150, ErrorClass::Environment),
+            EnvInstallError::AlreadyInstalledVersion(..) => {
+                ErrorCode::new(1151, ErrorClass::Environment)
+            }
+            EnvInstallError::AlreadyInstalledUnknownVersion(_) => {
+                ErrorCode::new(1152, ErrorClass::Environment)
+            }
+            EnvInstallError::EnvRead(_) => ErrorCode::new(1153, ErrorClass::Environment),
+            EnvInstallError::ProjectRead(_) => ErrorCode::new(1154, ErrorClass::Project),
+            EnvInstallError::MissingSpec => ErrorCode::new(1155, ErrorClass::Project),
+            EnvInstallError::Installation(_) => ErrorCode::new(1156, ErrorClass::Environment),
+        }
+    }
+}
+
 type InstallationError<EnvWriteError, ProjectReadError, ProjectWriteError> =
     PutProjectError<EnvWriteError, CloneError<ProjectReadError, ProjectWriteError>>;
 
@@ -91,17 +93,17 @@ impl<EnvReadError, ProjectReadError, I> From<CheckInstallError<EnvReadError>>
         match value {
             CheckInstallError::AlreadyInstalled(s) => Self::AlreadyInstalled(s),
             CheckInstallError::EnvRead(e) => Self::EnvRead(e),
-            CheckInstallError::AlreadyInstalledVersion(iri, version) => {
-                Self::AlreadyInstalledVersion(iri, version)
-            }
-            CheckInstallError::AlreadyInstalledUnknownVersion(iri) => {
-                Self::AlreadyInstalledUnknownVersion(iri)
-            }
         }
     }
 }
 
-#[allow(clippy::type_complexity)]
+/// Install `storage` in `env` as version `version` of `uri`. If
+/// `metadata_only` is given and `env` supports it, only `.project.json` and
+/// `.meta.json` are installed, and the remaining files are fetched from the
+/// given sources when first read. If the version is already installed,
+/// `overwrite` decides whether it is replaced. `source` is recorded by
+/// environments that keep the provenance of installed projects
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub fn do_env_install_project<
     S: AsRef<str>,
     P: ProjectRead,
@@ -112,8 +114,10 @@ pub fn do_env_install_project<
     storage: &P,
     checksum: Option<ProjectChecksum>,
     env: &mut E,
-    allow_overwrite: bool,
+    overwrite: OverwritePolicy,
     allow_multiple: bool,
+    metadata_only: Option<&[Source]>,
+    source: Option<&Source>,
 ) -> Result<
     (),
     EnvInstallError<
@@ -126,19 +130,136 @@ pub fn do_env_install_project<
         >,
     >,
 > {
-    check_install(&uri, version, env, allow_overwrite, allow_multiple)?;
+    install_with(
+        uri,
+        version,
+        checksum,
+        env,
+        overwrite,
+        allow_multiple,
+        metadata_only,
+        source,
+        |p, metadata_only| {
+            if metadata_only {
+                clone_project_metadata(storage, p, true).map(|_| ())
+            } else {
+                clone_project(storage, p, true).map(|_| ())
+            }
+        },
+    )
+}
+
+/// Install the project in the KPAR `kpar` in `env` as version `version` of
+/// `uri`. Like [`do_env_install_project`], but each file is decompressed
+/// straight into the environment, see [`LocalKParProject::extract_into`]
+#[cfg(feature = "filesystem")]
+#[allow(clippy::type_complexity)]
+pub fn do_env_install_kpar<S: AsRef<str>, E: WriteEnvironment + ReadEnvironment>(
+    uri: S,
+    version: &str,
+    kpar: &LocalKParProject,
+    env: &mut E,
+    overwrite: OverwritePolicy,
+    allow_multiple: bool,
+    source: Option<&Source>,
+) -> Result<
+    (),
+    EnvInstallError<
+        E::ReadError,
+        LocalKParError,
+        InstallationError<
+            E::WriteError,
+            LocalKParError,
+            <E::InterchangeProjectMut as ProjectRead>::Error,
+        >,
+    >,
+> {
+    let checksum = kpar
+        .checksum_canonical_variant()
+        .map_err(EnvInstallError::ProjectRead)?;
+    install_with(
+        uri,
+        version,
+        Some(checksum),
+        env,
+        overwrite,
+        allow_multiple,
+        None,
+        source,
+        |p, _| kpar.extract_into(p, true).map(|_| ()),
+    )
+}
+
+/// Install a project whose files are written by `write`, which is told
+/// whether to write only the metadata
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn install_with<S, PE, E, F>(
+    uri: S,
+    version: &str,
+    checksum: Option<ProjectChecksum>,
+    env: &mut E,
+    overwrite: OverwritePolicy,
+    allow_multiple: bool,
+    metadata_only: Option<&[Source]>,
+    source: Option<&Source>,
+    write: F,
+) -> Result<
+    (),
+    EnvInstallError<
+        E::ReadError,
+        PE,
+        InstallationError<E::WriteError, PE, <E::InterchangeProjectMut as ProjectRead>::Error>,
+    >,
+>
+where
+    S: AsRef<str>,
+    PE: ErrorBound,
+    E: WriteEnvironment + ReadEnvironment,
+    F: FnOnce(
+        &mut E::InterchangeProjectMut,
+        bool,
+    ) -> Result<(), CloneError<PE, <E::InterchangeProjectMut as ProjectRead>::Error>>,
+{
+    check_install(&uri, version, env, allow_multiple)?;
 
+    let _timer = timings::time(Phase::Install);
+    let metadata_only = metadata_only.filter(|_| env.supports_metadata_only(&uri));
     let installing = "Installing";
     let header = crate::style::get_style_config().header;
     log::info!(
-        "{header}{installing:>12}{header:#} `{}` {version}",
+        "{header}{installing:>12}{header:#} `{}` {version}{}",
         uri.as_ref(),
+        if metadata_only.is_some() {
+            " (metadata only)"
+        } else {
+            ""
+        },
     );
 
-    env.put_project(uri, version, checksum, |p| {
-        clone_project(storage, p, true).map(|_| ())
-    })
-    .map_err(EnvInstallError::Installation)?;
+    let installation_error = |e| match e {
+        PutProjectError::AlreadyInstalled { uri, version } => {
+            EnvInstallError::AlreadyInstalledVersion(uri.into(), version)
+        }
+        e => EnvInstallError::Installation(e),
+    };
+    match metadata_only {
+        Some(sources) => {
+            env.put_project(&uri, version, checksum.clone(), overwrite, |p| {
+                write(p, true)
+            })
+            .map_err(installation_error)?;
+            env.set_metadata_only(&uri, version, sources.to_vec())
+                .map_err(|e| EnvInstallError::Installation(PutProjectError::Write(e)))?;
+        }
+        None => {
+            env.put_project(&uri, version, checksum.clone(), overwrite, |p| {
+                write(p, false)
+            })
+            .map_err(installation_error)?;
+        }
+    }
+    env.record_install(&uri, version, source, checksum.as_ref())
+        .map_err(|e| EnvInstallError::Installation(PutProjectError::Write(e)))?;
 
     Ok(())
 }
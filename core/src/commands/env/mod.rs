@@ -11,6 +11,7 @@ use crate::{
         memory::{MemoryStorageEnvironment, MemoryWriteError},
         utils::ErrorBound,
     },
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
     project::memory::InMemoryProject,
 };
 
@@ -20,6 +21,8 @@ use camino::Utf8PathBuf;
 use thiserror::Error;
 
 mod install;
+#[cfg(feature = "filesystem")]
+pub use install::do_env_install_kpar;
 pub use install::do_env_install_project;
 
 mod uninstall;
@@ -28,6 +31,11 @@ pub use uninstall::do_env_uninstall;
 mod list;
 pub use list::do_env_list;
 
+#[cfg(feature = "filesystem")]
+mod export;
+#[cfg(feature = "filesystem")]
+pub use export::{EnvExport, EnvExportError, EnvExportProject, do_env_export};
+
 #[derive(Error, Debug)]
 pub enum EnvError<WriteError: ErrorBound> {
     #[error("refusing to overwrite `{0}`")]
@@ -36,6 +44,15 @@ pub enum EnvError<WriteError: ErrorBound> {
     Write(#[from] WriteError),
 }
 
+impl<WriteError: ErrorBound> HasErrorCode for EnvError<WriteError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            EnvError::AlreadyExists(_) => ErrorCode::new(1101, ErrorClass::Environment),
+            EnvError::Write(_) => ErrorCode::new(1102, ErrorClass::Environment),
+        }
+    }
+}
+
 pub fn do_env_memory()
 -> Result<MemoryStorageEnvironment<InMemoryProject>, EnvError<MemoryWriteError>> {
     Ok(MemoryStorageEnvironment::default())
@@ -5,6 +5,7 @@ use thiserror::Error;
 use typed_path::Utf8UnixPath;
 
 use crate::{
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
     model::InterchangeProjectChecksumRaw,
     project::{ProjectMut, ProjectOrIOError, utils::FsIoError},
 };
@@ -21,6 +22,17 @@ pub enum ExcludeError<ProjectError> {
     MissingMeta,
 }
 
+impl<ProjectError> HasErrorCode for ExcludeError<ProjectError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            ExcludeError::Project(_) => ErrorCode::new(501, ErrorClass::Project),
+            ExcludeError::Io(_) => ErrorCode::new(502, ErrorClass::Io),
+            ExcludeError::SourceNotFound(_) => ErrorCode::new(503, ErrorClass::Project),
+            ExcludeError::MissingMeta => ErrorCode::new(504, ErrorClass::Project),
+        }
+    }
+}
+
 impl<ProjectError> From<ProjectOrIOError<ProjectError>> for ExcludeError<ProjectError> {
     fn from(value: ProjectOrIOError<ProjectError>) -> Self {
         match value {
@@ -0,0 +1,327 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::{collections::HashMap, fmt::Write};
+
+use thiserror::Error;
+
+use crate::{
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    lock::{Lock, Project, Source},
+};
+
+/// Formats the lockfile can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Starlark file defining `SYSAND_DEPENDENCIES`, loadable from Bazel
+    /// and Buck2
+    Bazel,
+    /// Nix expression with a fixed-output derivation for every dependency
+    /// and a derivation building `sysand_env` from them
+    Nix,
+}
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error(
+        "project `{0}` can only be fetched from a remote source or git repository,\n\
+        which cannot be exported; only KPAR archives and local paths are supported"
+    )]
+    UnsupportedSource(String),
+    #[error("project `{0}` has no identifiers, so it cannot be installed from the export")]
+    MissingIdentifier(String),
+}
+
+impl HasErrorCode for ExportError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            ExportError::UnsupportedSource(_) => ErrorCode::new(1701, ErrorClass::Project),
+            ExportError::MissingIdentifier(_) => ErrorCode::new(1702, ErrorClass::Project),
+        }
+    }
+}
+
+/// Where an exported dependency is fetched from
+enum Location<'a> {
+    Url { url: &'a str, sha256: &'a str },
+    LocalKpar { path: &'a str, sha256: &'a str },
+    LocalSrc { path: &'a str },
+}
+
+struct Dependency<'a> {
+    project: &'a Project,
+    repository: String,
+    location: Location<'a>,
+}
+
+/// Export the dependencies recorded in `lock` in the given `format`.
+///
+/// Projects with only editable sources are the ones being developed and are
+/// not exported. Other projects are exported with their first source that
+/// is a KPAR (remote or index KPARs preferred) or a local directory.
+pub fn do_export(lock: &Lock, format: ExportFormat) -> Result<String, ExportError> {
+    let dependencies = dependencies(lock)?;
+    match format {
+        ExportFormat::Bazel => Ok(to_starlark(&dependencies)),
+        ExportFormat::Nix => to_nix(&dependencies),
+    }
+}
+
+fn dependencies(lock: &Lock) -> Result<Vec<Dependency<'_>>, ExportError> {
+    let mut dependencies: Vec<Dependency> = vec![];
+    for project in &lock.projects {
+        if project
+            .sources
+            .iter()
+            .all(|s| matches!(s, Source::Editable { .. }))
+        {
+            continue;
+        }
+        let location = location(&project.sources)
+            .ok_or_else(|| ExportError::UnsupportedSource(project.name.clone()))?;
+
+        let mut repository = repository_name(project.publisher.as_deref(), &project.name, None);
+        if dependencies.iter().any(|d| d.repository == repository) {
+            repository = repository_name(
+                project.publisher.as_deref(),
+                &project.name,
+                Some(&project.version),
+            );
+        }
+        dependencies.push(Dependency {
+            project,
+            repository,
+            location,
+        });
+    }
+    Ok(dependencies)
+}
+
+fn location(sources: &[Source]) -> Option<Location<'_>> {
+    let remote = sources.iter().find_map(|s| match s {
+        Source::IndexKpar {
+            index_kpar: url,
+            kpar_digest,
+            ..
+        }
+        | Source::RemoteKpar {
+            remote_kpar: url,
+            kpar_digest,
+            ..
+        } => Some(Location::Url {
+            url,
+            sha256: kpar_digest,
+        }),
+        _ => None,
+    });
+    remote.or_else(|| {
+        sources.iter().find_map(|s| match s {
+            Source::LocalKpar {
+                kpar_path,
+                kpar_digest,
+                ..
+            } => Some(Location::LocalKpar {
+                path: kpar_path.as_str(),
+                sha256: kpar_digest,
+            }),
+            Source::LocalSrc { src_path, .. } => Some(Location::LocalSrc {
+                path: src_path.as_str(),
+            }),
+            _ => None,
+        })
+    })
+}
+
+/// Name usable as a Bazel repository name and Nix attribute name, e.g. `sysand_acme_lib_a`
+fn repository_name(publisher: Option<&str>, name: &str, version: Option<&str>) -> String {
+    let mut repository = String::from("sysand");
+    for part in [publisher, Some(name), version].into_iter().flatten() {
+        repository.push('_');
+        repository.extend(part.chars().map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        }));
+    }
+    repository
+}
+
+fn starlark_str(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn starlark_list<'a, I: IntoIterator<Item = &'a str>>(items: I) -> String {
+    let items: Vec<_> = items.into_iter().map(starlark_str).collect();
+    format!("[{}]", items.join(", "))
+}
+
+fn to_starlark(dependencies: &[Dependency]) -> String {
+    // Identifier -> repository of the project providing it
+    let providers: HashMap<&str, &str> = dependencies
+        .iter()
+        .flat_map(|d| {
+            d.project
+                .identifiers
+                .iter()
+                .map(|i| (i.as_str(), d.repository.as_str()))
+        })
+        .collect();
+
+    let mut out = String::from(
+        "# Generated by `sysand export --format bazel` from the sysand lockfile.\n\
+        # Do not edit, regenerate after updating the lockfile instead.\n\
+        \n\
+        SYSAND_DEPENDENCIES = [\n",
+    );
+    for dependency in dependencies {
+        let project = dependency.project;
+        out.push_str("    {\n");
+        let mut field = |key: &str, value: String| {
+            writeln!(out, "        {}: {value},", starlark_str(key)).unwrap();
+        };
+        field("repository", starlark_str(&dependency.repository));
+        field("name", starlark_str(&project.name));
+        if let Some(publisher) = &project.publisher {
+            field("publisher", starlark_str(publisher));
+        }
+        field("version", starlark_str(&project.version));
+        field(
+            "identifiers",
+            starlark_list(project.identifiers.iter().map(String::as_str)),
+        );
+        match dependency.location {
+            Location::Url { url, sha256 } => {
+                field("url", starlark_str(url));
+                field("sha256", starlark_str(sha256));
+            }
+            Location::LocalKpar { path, sha256 } => {
+                field("path", starlark_str(path));
+                field("sha256", starlark_str(sha256));
+            }
+            Location::LocalSrc { path } => field("path", starlark_str(path)),
+        }
+        let mut deps: Vec<_> = project
+            .usages
+            .iter()
+            .filter_map(|u| providers.get(&**u).copied())
+            .collect();
+        deps.sort_unstable();
+        deps.dedup();
+        field("deps", starlark_list(deps));
+        out.push_str("    },\n");
+    }
+    out.push_str("]\n");
+    out
+}
+
+fn nix_str(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            // Prevent interpolation
+            '$' if chars.peek() == Some(&'{') => quoted.push_str("\\$"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Nix path expression for a lockfile path, relative paths being relative
+/// to the generated file
+fn nix_path(path: &str) -> String {
+    match path.strip_prefix('/') {
+        Some(absolute) => format!("/. + {}", nix_str(&format!("/{absolute}"))),
+        None => format!("./. + {}", nix_str(&format!("/{path}"))),
+    }
+}
+
+fn to_nix(dependencies: &[Dependency]) -> Result<String, ExportError> {
+    let mut out = String::from(
+        "# Generated by `sysand export --format nix` from the sysand lockfile.\n\
+        # Do not edit, regenerate after updating the lockfile instead.\n\
+        #\n\
+        # Local paths are relative to this file, so it must be placed in the\n\
+        # project directory.\n\
+        {\n\
+        \x20 pkgs ? import <nixpkgs> { },\n\
+        \x20 sysand ? pkgs.sysand,\n\
+        }:\n\
+        \n\
+        rec {\n\
+        \x20 dependencies = {\n",
+    );
+    for dependency in dependencies {
+        let project = dependency.project;
+        let identifier = project
+            .identifiers
+            .first()
+            .ok_or_else(|| ExportError::MissingIdentifier(project.name.clone()))?;
+        writeln!(out, "    {} = {{", nix_str(&dependency.repository)).unwrap();
+        writeln!(out, "      name = {};", nix_str(&project.name)).unwrap();
+        writeln!(out, "      version = {};", nix_str(&project.version)).unwrap();
+        writeln!(out, "      identifier = {};", nix_str(identifier)).unwrap();
+        match dependency.location {
+            Location::Url { url, sha256 } => {
+                out.push_str("      src = pkgs.fetchurl {\n");
+                writeln!(
+                    out,
+                    "        name = {};",
+                    nix_str(&format!("{}.kpar", dependency.repository))
+                )
+                .unwrap();
+                writeln!(out, "        url = {};", nix_str(url)).unwrap();
+                writeln!(out, "        sha256 = {};", nix_str(sha256)).unwrap();
+                out.push_str("      };\n");
+            }
+            Location::LocalKpar { path, .. } | Location::LocalSrc { path } => {
+                writeln!(out, "      src = {};", nix_path(path)).unwrap();
+            }
+        }
+        out.push_str("    };\n");
+    }
+    // Dependencies are installed one by one without resolution, so that
+    // building the environment needs no network access
+    out.push_str(
+        "  };\n\
+        \n\
+        \x20 sysand_env = pkgs.runCommand \"sysand-env\" { nativeBuildInputs = [ sysand ]; } ''\n\
+        \x20   sysand env --no-config --no-cache\n\
+        \x20   ${pkgs.lib.concatMapStrings (dependency: ''\n\
+        \x20     sysand env install --no-config --no-cache --no-deps --no-index --allow-multiple \\\n\
+        \x20       ${pkgs.lib.escapeShellArg dependency.identifier} \\\n\
+        \x20       ${pkgs.lib.escapeShellArg dependency.version} \\\n\
+        \x20       --path ${dependency.src}\n\
+        \x20   '') (builtins.attrValues dependencies)}\n\
+        \x20   mv .sysand \"$out\"\n\
+        \x20 '';\n\
+        }\n",
+    );
+    Ok(out)
+}
+
+#[cfg(test)]
+#[path = "./export_tests.rs"]
+mod tests;
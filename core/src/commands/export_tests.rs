@@ -0,0 +1,213 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::str::FromStr;
+
+use super::*;
+use crate::lock::CURRENT_LOCK_VERSION;
+
+const DIGEST: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+fn lock(projects: &str) -> Lock {
+    Lock::from_str(&format!(
+        "lock_version = \"{CURRENT_LOCK_VERSION}\"\n\n{projects}"
+    ))
+    .unwrap()
+}
+
+#[test]
+fn bazel_manifest() {
+    let lock = lock(&format!(
+        r#"[[project]]
+name = "app"
+version = "0.1.0"
+usages = ["urn:kpar:lib-a"]
+sources = [{{ editable = "." }}]
+
+[[project]]
+publisher = "Acme Corp"
+name = "Lib A"
+version = "1.0.0"
+identifiers = ["urn:kpar:lib-a"]
+usages = ["urn:kpar:lib-b"]
+sources = [
+    {{ remote_src = "https://example.com/lib-a", checksum = "{DIGEST}" }},
+    {{ index_kpar = "https://example.com/lib-a.kpar", kpar_size = 10, kpar_digest = "{DIGEST}" }},
+]
+
+[[project]]
+name = "lib-b"
+version = "2.0.0"
+identifiers = ["urn:kpar:lib-b"]
+sources = [{{ src_path = "vendor/lib-b", checksum = "{DIGEST}" }}]
+"#
+    ));
+
+    let manifest = do_export(&lock, ExportFormat::Bazel).unwrap();
+
+    assert_eq!(
+        manifest,
+        format!(
+            r#"# Generated by `sysand export --format bazel` from the sysand lockfile.
+# Do not edit, regenerate after updating the lockfile instead.
+
+SYSAND_DEPENDENCIES = [
+    {{
+        "repository": "sysand_acme_corp_lib_a",
+        "name": "Lib A",
+        "publisher": "Acme Corp",
+        "version": "1.0.0",
+        "identifiers": ["urn:kpar:lib-a"],
+        "url": "https://example.com/lib-a.kpar",
+        "sha256": "{DIGEST}",
+        "deps": ["sysand_lib_b"],
+    }},
+    {{
+        "repository": "sysand_lib_b",
+        "name": "lib-b",
+        "version": "2.0.0",
+        "identifiers": ["urn:kpar:lib-b"],
+        "path": "vendor/lib-b",
+        "deps": [],
+    }},
+]
+"#
+        )
+    );
+}
+
+#[test]
+fn duplicate_names_get_versioned_repositories() {
+    let lock = lock(&format!(
+        r#"[[project]]
+name = "lib"
+version = "1.0.0"
+identifiers = ["urn:kpar:lib-1"]
+sources = [{{ remote_kpar = "https://example.com/lib-1.kpar", kpar_size = 10, kpar_digest = "{DIGEST}" }}]
+
+[[project]]
+name = "lib"
+version = "2.0.0"
+identifiers = ["urn:kpar:lib-2"]
+sources = [{{ remote_kpar = "https://example.com/lib-2.kpar", kpar_size = 10, kpar_digest = "{DIGEST}" }}]
+"#
+    ));
+
+    let manifest = do_export(&lock, ExportFormat::Bazel).unwrap();
+
+    assert!(manifest.contains(r#""repository": "sysand_lib","#));
+    assert!(manifest.contains(r#""repository": "sysand_lib_2.0.0","#));
+}
+
+#[test]
+fn git_source_is_unsupported() {
+    let lock = lock(
+        r#"[[project]]
+name = "lib"
+version = "1.0.0"
+identifiers = ["urn:kpar:lib"]
+sources = [{ remote_git = "https://example.com/lib.git" }]
+"#,
+    );
+
+    let err = do_export(&lock, ExportFormat::Bazel).unwrap_err();
+
+    assert!(matches!(err, ExportError::UnsupportedSource(name) if name == "lib"));
+}
+
+#[test]
+fn nix_expression() {
+    let lock = lock(&format!(
+        r#"[[project]]
+name = "app"
+version = "0.1.0"
+usages = ["urn:kpar:lib-a"]
+sources = [{{ editable = "." }}]
+
+[[project]]
+publisher = "Acme Corp"
+name = "Lib A"
+version = "1.0.0"
+identifiers = ["urn:kpar:lib-a"]
+usages = ["urn:kpar:lib-b"]
+sources = [{{ remote_kpar = "https://example.com/lib-a.kpar", kpar_size = 10, kpar_digest = "{DIGEST}" }}]
+
+[[project]]
+name = "lib-b"
+version = "2.0.0"
+identifiers = ["urn:kpar:lib-b"]
+sources = [{{ src_path = "vendor/lib-b", checksum = "{DIGEST}" }}]
+"#
+    ));
+
+    let expression = do_export(&lock, ExportFormat::Nix).unwrap();
+
+    assert_eq!(
+        expression,
+        format!(
+            r#"# Generated by `sysand export --format nix` from the sysand lockfile.
+# Do not edit, regenerate after updating the lockfile instead.
+#
+# Local paths are relative to this file, so it must be placed in the
+# project directory.
+{{
+  pkgs ? import <nixpkgs> {{ }},
+  sysand ? pkgs.sysand,
+}}:
+
+rec {{
+  dependencies = {{
+    "sysand_acme_corp_lib_a" = {{
+      name = "Lib A";
+      version = "1.0.0";
+      identifier = "urn:kpar:lib-a";
+      src = pkgs.fetchurl {{
+        name = "sysand_acme_corp_lib_a.kpar";
+        url = "https://example.com/lib-a.kpar";
+        sha256 = "{DIGEST}";
+      }};
+    }};
+    "sysand_lib_b" = {{
+      name = "lib-b";
+      version = "2.0.0";
+      identifier = "urn:kpar:lib-b";
+      src = ./. + "/vendor/lib-b";
+    }};
+  }};
+
+  sysand_env = pkgs.runCommand "sysand-env" {{ nativeBuildInputs = [ sysand ]; }} ''
+    sysand env --no-config --no-cache
+    ${{pkgs.lib.concatMapStrings (dependency: ''
+      sysand env install --no-config --no-cache --no-deps --no-index --allow-multiple \
+        ${{pkgs.lib.escapeShellArg dependency.identifier}} \
+        ${{pkgs.lib.escapeShellArg dependency.version}} \
+        --path ${{dependency.src}}
+    '') (builtins.attrValues dependencies)}}
+    mv .sysand "$out"
+  '';
+}}
+"#
+        )
+    );
+}
+
+#[test]
+fn nix_strings_are_escaped() {
+    assert_eq!(nix_str(r#"a "b" \c ${d} $e"#), r#""a \"b\" \\c \${d} $e""#);
+    assert_eq!(nix_path("/opt/lib"), r#"/. + "/opt/lib""#);
+}
+
+#[test]
+fn nix_requires_identifiers() {
+    let lock = lock(&format!(
+        r#"[[project]]
+name = "lib"
+version = "1.0.0"
+sources = [{{ remote_kpar = "https://example.com/lib.kpar", kpar_size = 10, kpar_digest = "{DIGEST}" }}]
+"#
+    ));
+
+    let err = do_export(&lock, ExportFormat::Nix).unwrap_err();
+
+    assert!(matches!(err, ExportError::MissingIdentifier(name) if name == "lib"));
+}
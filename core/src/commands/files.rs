@@ -0,0 +1,325 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::{collections::BTreeSet, fmt};
+
+use globset::GlobSet;
+use thiserror::Error;
+
+use crate::{
+    commands::build::{KparManifest, glob_set},
+    env::utils::ErrorBound,
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    project::{
+        ProjectRead,
+        utils::{FsIoError, wrapfs},
+    },
+    utils::license_file_stems,
+    walk,
+};
+
+/// How a file of the project is treated when building a KPAR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FileClass {
+    /// Packaged as a model interchange source file
+    Source,
+    /// Packaged alongside the sources: project information, metadata,
+    /// readme, changelog and licenses
+    Metadata,
+    /// Not packaged
+    Ignored,
+    /// Selection of the file is inconsistent: building either fails or
+    /// needs `--allow-dirty`
+    Unknown,
+}
+
+impl fmt::Display for FileClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FileClass::Source => "source",
+            FileClass::Metadata => "metadata",
+            FileClass::Ignored => "ignored",
+            FileClass::Unknown => "unknown",
+        })
+    }
+}
+
+/// Why a file was given its [`FileClass`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileReason {
+    /// `.project.json` or `.meta.json`
+    ProjectFile,
+    Readme,
+    Changelog,
+    /// License text of the given SPDX identifier in the project license
+    License(String),
+    /// Listed in the index or checksums of `.meta.json`
+    InMetadata,
+    /// Matches the given include glob and is in `.meta.json`
+    IncludeGlob(String),
+    /// Matches the given include glob, but is not in `.meta.json`
+    IncludeGlobNotInMetadata(String),
+    /// In `.meta.json`, but matches no include glob
+    InMetadataNotIncluded,
+    /// Matches the given exclude glob
+    ExcludeGlob(String),
+    /// Hidden files and directories are never packaged
+    Hidden,
+    /// Interchange file not in `.meta.json`
+    NotInMetadata,
+    /// Matches no include glob
+    NotIncluded,
+    /// Only SysML v2 and KerML files are model interchange files
+    NotInterchange,
+    /// In `.meta.json`, but skipped by `.gitignore` or `.sysandignore`
+    IgnoreFile,
+    /// In `.meta.json`, but does not exist
+    Missing,
+}
+
+impl fmt::Display for FileReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileReason::ProjectFile => f.write_str("project information/metadata"),
+            FileReason::Readme => f.write_str("project readme"),
+            FileReason::Changelog => f.write_str("project changelog"),
+            FileReason::License(id) => write!(f, "text of license `{id}`"),
+            FileReason::InMetadata => f.write_str("listed in `.meta.json`"),
+            FileReason::IncludeGlob(glob) => write!(f, "matches include glob `{glob}`"),
+            FileReason::IncludeGlobNotInMetadata(glob) => write!(
+                f,
+                "matches include glob `{glob}`, but is not listed in `.meta.json`"
+            ),
+            FileReason::InMetadataNotIncluded => {
+                f.write_str("listed in `.meta.json`, but matches no include glob")
+            }
+            FileReason::ExcludeGlob(glob) => write!(f, "matches exclude glob `{glob}`"),
+            FileReason::Hidden => f.write_str("hidden"),
+            FileReason::NotInMetadata => f.write_str("not listed in `.meta.json`"),
+            FileReason::NotIncluded => f.write_str("matches no include glob"),
+            FileReason::NotInterchange => {
+                f.write_str("not a SysML v2 (`.sysml`) or KerML (`.kerml`) file")
+            }
+            FileReason::IgnoreFile => f.write_str(
+                "listed in `.meta.json`, but ignored by `.gitignore` or `.sysandignore`",
+            ),
+            FileReason::Missing => f.write_str("listed in `.meta.json`, but does not exist"),
+        }
+    }
+}
+
+/// A file or hidden directory (path ending with `/`) of a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectFile {
+    /// Path relative to the project root, with `/` separators
+    pub path: String,
+    pub class: FileClass,
+    pub reason: FileReason,
+}
+
+#[derive(Error, Debug)]
+pub enum FilesError<ProjectError: ErrorBound> {
+    #[error(transparent)]
+    Project(ProjectError),
+    #[error("project is missing project metadata `.meta.json`")]
+    MissingMeta,
+    #[error("project has no root directory to list files from")]
+    NoRoot,
+    #[error("invalid glob `{0}` in `[build]` config: {1}")]
+    InvalidGlob(String, globset::Error),
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+}
+
+impl<ProjectError: ErrorBound> From<FsIoError> for FilesError<ProjectError> {
+    fn from(v: FsIoError) -> Self {
+        Self::Io(Box::new(v))
+    }
+}
+
+impl<ProjectError: ErrorBound> HasErrorCode for FilesError<ProjectError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            FilesError::Project(_) => ErrorCode::new(2401, ErrorClass::Project),
+            FilesError::MissingMeta => ErrorCode::new(2402, ErrorClass::Project),
+            FilesError::NoRoot => ErrorCode::new(2403, ErrorClass::Usage),
+            FilesError::InvalidGlob(..) => ErrorCode::new(2404, ErrorClass::Usage),
+            FilesError::Io(e) => e.error_code(),
+        }
+    }
+}
+
+/// List every file under the root of `project`, sorted by path, classified
+/// by whether and why building a KPAR with `manifest` would package it.
+/// Hidden directories are listed once, without their contents. Files skipped
+/// by [`walk::walker`], such as those matched by `.gitignore` or
+/// `.sysandignore`, are only listed if they are in `.meta.json`. Files listed
+/// in `.meta.json` that do not exist are included as
+/// [`FileReason::Missing`]
+pub fn do_files<Pr: ProjectRead>(
+    project: &Pr,
+    manifest: &KparManifest,
+) -> Result<Vec<ProjectFile>, FilesError<Pr::Error>> {
+    let root = project.project_root().ok_or(FilesError::NoRoot)?;
+    let meta = project
+        .get_meta()
+        .map_err(FilesError::Project)?
+        .ok_or(FilesError::MissingMeta)?;
+    let licenses: BTreeSet<String> = project
+        .get_info()
+        .map_err(FilesError::Project)?
+        .and_then(|info| info.license)
+        .and_then(|l| spdx::Expression::parse(&l).ok())
+        .map(|expression| license_file_stems(&expression).into_iter().collect())
+        .unwrap_or_default();
+    let include = glob_set(&manifest.include).map_err(|(g, e)| FilesError::InvalidGlob(g, e))?;
+    let exclude = glob_set(&manifest.exclude).map_err(|(g, e)| FilesError::InvalidGlob(g, e))?;
+    let classifier = Classifier {
+        manifest,
+        include,
+        exclude,
+        indexed: meta.source_paths(true).into_iter().collect(),
+        licenses,
+    };
+
+    let mut files = Vec::new();
+    // Hidden directories, such as `.git`, are never packaged, so their
+    // contents are not interesting
+    let walker = walk::walker(root, |e| {
+        e.depth() < 2
+            || !e
+                .path()
+                .parent()
+                .and_then(|p| p.file_name())
+                .is_some_and(|n| n.to_string_lossy().starts_with('.'))
+    });
+    for entry in walker {
+        let entry = entry.map_err(|e| walk::walk_error(root, e))?;
+        if entry.depth() == 0 {
+            continue;
+        }
+        let Some(relative) = walk::relative_path(root, entry.path()) else {
+            continue;
+        };
+        if entry.file_type().is_some_and(|t| t.is_dir()) {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                files.push(ProjectFile {
+                    path: format!("{relative}/"),
+                    class: FileClass::Ignored,
+                    reason: FileReason::Hidden,
+                });
+            }
+            continue;
+        }
+        let hidden = relative.split('/').any(|c| c.starts_with('.'));
+        let (class, reason) = classifier.classify(&relative, hidden);
+        files.push(ProjectFile {
+            path: relative,
+            class,
+            reason,
+        });
+    }
+
+    let present: BTreeSet<&str> = files.iter().map(|f| f.path.as_str()).collect();
+    let unlisted: Vec<String> = classifier
+        .indexed
+        .iter()
+        .filter(|p| !present.contains(p.as_str()) && !classifier.exclude.is_match(p))
+        .cloned()
+        .collect();
+    for path in unlisted {
+        // Ignore files only affect selection by include globs
+        let (class, reason) = if !wrapfs::is_file(root.join(&path))? {
+            (FileClass::Unknown, FileReason::Missing)
+        } else if classifier.manifest.include.is_empty() {
+            classifier.classify(&path, path.split('/').any(|c| c.starts_with('.')))
+        } else {
+            (FileClass::Unknown, FileReason::IgnoreFile)
+        };
+        files.push(ProjectFile {
+            path,
+            class,
+            reason,
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+struct Classifier<'a> {
+    manifest: &'a KparManifest,
+    include: GlobSet,
+    exclude: GlobSet,
+    indexed: BTreeSet<String>,
+    licenses: BTreeSet<String>,
+}
+
+impl Classifier<'_> {
+    /// Mirrors the selection of files in `build`
+    fn classify(&self, path: &str, hidden: bool) -> (FileClass, FileReason) {
+        match path {
+            ".project.json" | ".meta.json" => {
+                return (FileClass::Metadata, FileReason::ProjectFile);
+            }
+            "README.md" => return (FileClass::Metadata, FileReason::Readme),
+            "CHANGELOG.md" => return (FileClass::Metadata, FileReason::Changelog),
+            _ => {}
+        }
+        if let Some(id) = path
+            .strip_prefix("LICENSES/")
+            .and_then(|p| p.strip_suffix(".txt"))
+            .filter(|id| self.licenses.contains(*id))
+        {
+            return (FileClass::Metadata, FileReason::License(id.to_owned()));
+        }
+        if hidden {
+            return (FileClass::Ignored, FileReason::Hidden);
+        }
+        if let Some(&i) = self.exclude.matches(path).first() {
+            let glob = self.manifest.exclude[i].clone();
+            return (FileClass::Ignored, FileReason::ExcludeGlob(glob));
+        }
+
+        let indexed = self.indexed.contains(path);
+        if self.manifest.include.is_empty() {
+            return if indexed {
+                (FileClass::Source, FileReason::InMetadata)
+            } else if is_interchange(path) {
+                (FileClass::Unknown, FileReason::NotInMetadata)
+            } else {
+                (FileClass::Ignored, FileReason::NotInterchange)
+            };
+        }
+        match self.include.matches(path).first() {
+            Some(&i) => {
+                let glob = self.manifest.include[i].clone();
+                if indexed {
+                    (FileClass::Source, FileReason::IncludeGlob(glob))
+                } else if self.manifest.allow_dirty {
+                    (
+                        FileClass::Source,
+                        FileReason::IncludeGlobNotInMetadata(glob),
+                    )
+                } else {
+                    (
+                        FileClass::Unknown,
+                        FileReason::IncludeGlobNotInMetadata(glob),
+                    )
+                }
+            }
+            None if indexed && self.manifest.allow_dirty => {
+                (FileClass::Ignored, FileReason::InMetadataNotIncluded)
+            }
+            None if indexed => (FileClass::Unknown, FileReason::InMetadataNotIncluded),
+            None => (FileClass::Ignored, FileReason::NotIncluded),
+        }
+    }
+}
+
+fn is_interchange(path: &str) -> bool {
+    path.ends_with(".sysml") || path.ends_with(".kerml")
+}
+
+#[cfg(test)]
+#[path = "./files_tests.rs"]
+mod tests;
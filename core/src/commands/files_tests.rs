@@ -0,0 +1,208 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use camino::Utf8Path;
+use camino_tempfile::tempdir;
+
+use crate::{
+    commands::{
+        build::KparManifest,
+        files::{FileClass, FileReason, FilesError, do_files},
+    },
+    include::do_include,
+    init::do_init,
+    model::HashAlg,
+    project::local_src::LocalSrcProject,
+};
+
+fn files_project(root: &Utf8Path) -> LocalSrcProject {
+    let mut project = LocalSrcProject {
+        nominal_path: None,
+        project_path: root.to_owned(),
+        expected_checksum: None,
+    };
+    do_init(
+        "test".into(),
+        None,
+        "1.0.0".into(),
+        Some("MIT".into()),
+        &mut project,
+    )
+    .unwrap();
+    std::fs::create_dir_all(root.join("drafts")).unwrap();
+    std::fs::create_dir_all(root.join("LICENSES")).unwrap();
+    std::fs::create_dir_all(root.join(".sysand/lib")).unwrap();
+    std::fs::create_dir_all(root.join(".cache")).unwrap();
+    for (path, content) in [
+        ("a.sysml", "package A;\n"),
+        ("drafts/d.sysml", "package D;\n"),
+        ("new.kerml", "package N;\n"),
+        ("notes.txt", "notes\n"),
+        ("README.md", "# Test\n"),
+        ("LICENSES/MIT.txt", "MIT\n"),
+        (".sysand/lib/x.sysml", "package X;\n"),
+        (".cache/c.sysml", "package C;\n"),
+    ] {
+        std::fs::write(root.join(path), content).unwrap();
+    }
+    do_include(
+        &mut project,
+        ["a.sysml".into(), "drafts/d.sysml".into()].into_iter(),
+        Some(HashAlg::Sha256),
+        true,
+        None,
+    )
+    .unwrap();
+    project
+}
+
+fn classes(
+    project: &LocalSrcProject,
+    manifest: &KparManifest,
+) -> Vec<(String, FileClass, FileReason)> {
+    do_files(project, manifest)
+        .unwrap()
+        .into_iter()
+        .map(|f| (f.path, f.class, f.reason))
+        .collect()
+}
+
+#[test]
+fn files_from_metadata() {
+    let tmp = tempdir().unwrap();
+    let project = files_project(tmp.path());
+    std::fs::remove_file(tmp.path().join("a.sysml")).unwrap();
+    let manifest = KparManifest {
+        exclude: vec!["drafts/**".into()],
+        ..Default::default()
+    };
+
+    assert_eq!(
+        classes(&project, &manifest),
+        [
+            (".cache/".into(), FileClass::Ignored, FileReason::Hidden),
+            (
+                ".meta.json".into(),
+                FileClass::Metadata,
+                FileReason::ProjectFile
+            ),
+            (
+                ".project.json".into(),
+                FileClass::Metadata,
+                FileReason::ProjectFile
+            ),
+            (
+                "LICENSES/MIT.txt".into(),
+                FileClass::Metadata,
+                FileReason::License("MIT".into())
+            ),
+            ("README.md".into(), FileClass::Metadata, FileReason::Readme),
+            ("a.sysml".into(), FileClass::Unknown, FileReason::Missing),
+            (
+                "drafts/d.sysml".into(),
+                FileClass::Ignored,
+                FileReason::ExcludeGlob("drafts/**".into())
+            ),
+            (
+                "new.kerml".into(),
+                FileClass::Unknown,
+                FileReason::NotInMetadata
+            ),
+            (
+                "notes.txt".into(),
+                FileClass::Ignored,
+                FileReason::NotInterchange
+            ),
+        ]
+    );
+}
+
+#[test]
+fn files_from_include_globs() {
+    let tmp = tempdir().unwrap();
+    let project = files_project(tmp.path());
+    let mut manifest = KparManifest {
+        include: vec!["*.sysml".into(), "*.kerml".into()],
+        ..Default::default()
+    };
+
+    let sources = |files: Vec<(String, FileClass, FileReason)>| {
+        files
+            .into_iter()
+            .filter(|(p, ..)| p.ends_with(".sysml") || p.ends_with(".kerml"))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        sources(classes(&project, &manifest)),
+        [
+            (
+                "a.sysml".into(),
+                FileClass::Source,
+                FileReason::IncludeGlob("*.sysml".into())
+            ),
+            (
+                "drafts/d.sysml".into(),
+                FileClass::Unknown,
+                FileReason::InMetadataNotIncluded
+            ),
+            (
+                "new.kerml".into(),
+                FileClass::Unknown,
+                FileReason::IncludeGlobNotInMetadata("*.kerml".into())
+            ),
+        ]
+    );
+
+    manifest.allow_dirty = true;
+    let files = sources(classes(&project, &manifest));
+    assert_eq!(files[1].1, FileClass::Ignored);
+    assert_eq!(files[2].1, FileClass::Source);
+}
+
+#[test]
+fn files_respects_ignore_files() {
+    let tmp = tempdir().unwrap();
+    let project = files_project(tmp.path());
+    std::fs::write(tmp.path().join(".sysandignore"), "drafts/\nnew.kerml\n").unwrap();
+
+    let listed = |manifest: &KparManifest| {
+        classes(&project, manifest)
+            .into_iter()
+            .filter(|(p, ..)| p.starts_with("drafts/") || p == "new.kerml")
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        listed(&KparManifest::default()),
+        [(
+            "drafts/d.sysml".into(),
+            FileClass::Source,
+            FileReason::InMetadata
+        )]
+    );
+    let manifest = KparManifest {
+        include: vec!["**/*.sysml".into()],
+        ..Default::default()
+    };
+    assert_eq!(
+        listed(&manifest),
+        [(
+            "drafts/d.sysml".into(),
+            FileClass::Unknown,
+            FileReason::IgnoreFile
+        )]
+    );
+}
+
+#[test]
+fn files_invalid_glob() {
+    let tmp = tempdir().unwrap();
+    let project = files_project(tmp.path());
+    let manifest = KparManifest {
+        include: vec!["a[".into()],
+        ..Default::default()
+    };
+    assert!(matches!(
+        do_files(&project, &manifest),
+        Err(FilesError::InvalidGlob(glob, _)) if glob == "a["
+    ));
+}
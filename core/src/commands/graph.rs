@@ -0,0 +1,282 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Write as _},
+};
+
+use crate::{
+    lock::{Lock, Project, Source},
+    model::InterchangeProjectUsageRaw,
+};
+
+/// Syntax of an exported dependency graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphFormat {
+    /// Graphviz DOT
+    #[default]
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+/// Project in a dependency graph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphNode {
+    /// First identifier of the project, or its name if it has none
+    pub iri: String,
+    /// Locked version. `None` if the project is used but not locked
+    pub version: Option<String>,
+    /// Path of the project if it is editable, relative to the lockfile
+    pub editable: Option<String>,
+    /// Index of the cluster in [`DependencyGraph::clusters`]
+    pub cluster: Option<usize>,
+}
+
+impl GraphNode {
+    pub fn label(&self) -> String {
+        match &self.version {
+            Some(version) => format!("{}@{version}", self.iri),
+            None => self.iri.clone(),
+        }
+    }
+}
+
+/// Usage of project `to` by project `from`, as indices in
+/// [`DependencyGraph::nodes`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphEdge {
+    pub from: usize,
+    pub to: usize,
+    pub version_constraint: Option<String>,
+}
+
+/// Dependency graph of the projects in a lockfile
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DependencyGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// Labels of node clusters
+    pub clusters: Vec<String>,
+}
+
+impl DependencyGraph {
+    /// Graph with a node for each project in `lock` and an edge for each
+    /// of their usages. Lockfiles do not record version constraints, so
+    /// `declared_usages` is called to get the usages declared by a project,
+    /// e.g. in its installed `.project.json`. It may return no usages if
+    /// these are not available, in which case the edges of the project
+    /// have no constraint.
+    pub fn from_lock<F>(lock: &Lock, mut declared_usages: F) -> Self
+    where
+        F: FnMut(&Project) -> Vec<InterchangeProjectUsageRaw>,
+    {
+        let mut graph = DependencyGraph::default();
+        let mut by_iri = HashMap::new();
+        for (i, project) in lock.projects.iter().enumerate() {
+            graph.nodes.push(GraphNode {
+                iri: project.identifiers.first().unwrap_or(&project.name).clone(),
+                version: Some(project.version.clone()),
+                editable: project.sources.iter().find_map(|s| match s {
+                    Source::Editable { editable } => Some(editable.to_string()),
+                    _ => None,
+                }),
+                cluster: None,
+            });
+            for iri in &project.identifiers {
+                by_iri.entry(iri.as_str()).or_insert(i);
+            }
+        }
+
+        let mut unlocked = HashMap::new();
+        for (from, project) in lock.projects.iter().enumerate() {
+            let declared = declared_usages(project);
+            for usage in &project.usages {
+                let iri: &str = usage;
+                let to = match by_iri.get(iri) {
+                    Some(&to) => to,
+                    None => *unlocked.entry(iri.to_owned()).or_insert_with(|| {
+                        graph.nodes.push(GraphNode {
+                            iri: iri.to_owned(),
+                            version: None,
+                            editable: None,
+                            cluster: None,
+                        });
+                        graph.nodes.len() - 1
+                    }),
+                };
+                let version_constraint = declared.iter().find_map(|u| match u {
+                    InterchangeProjectUsageRaw::Resource {
+                        resource,
+                        version_constraint,
+                        ..
+                    } if resource == iri => version_constraint.clone(),
+                    _ => None,
+                });
+                let edge = GraphEdge {
+                    from,
+                    to,
+                    version_constraint,
+                };
+                if !graph.edges.contains(&edge) {
+                    graph.edges.push(edge);
+                }
+            }
+        }
+        graph
+    }
+
+    /// Put each editable project at one of `paths` in its own cluster,
+    /// together with the projects used only by it, directly or
+    /// transitively. Projects used by several of them are not clustered.
+    pub fn cluster_editable<S: AsRef<str>>(&mut self, paths: &[S]) {
+        let roots: Vec<usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| {
+                n.editable
+                    .as_deref()
+                    .is_some_and(|e| paths.iter().any(|p| same_path(p.as_ref(), e)))
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        let mut reached_by: Vec<Vec<usize>> = vec![vec![]; self.nodes.len()];
+        for (cluster, &root) in roots.iter().enumerate() {
+            let mut seen = HashSet::from([root]);
+            let mut stack = vec![root];
+            while let Some(node) = stack.pop() {
+                reached_by[node].push(cluster);
+                for edge in self.edges.iter().filter(|e| e.from == node) {
+                    if !roots.contains(&edge.to) && seen.insert(edge.to) {
+                        stack.push(edge.to);
+                    }
+                }
+            }
+        }
+
+        self.clusters = roots.iter().map(|&r| self.nodes[r].label()).collect();
+        for (node, clusters) in self.nodes.iter_mut().zip(reached_by) {
+            node.cluster = match clusters.as_slice() {
+                [cluster] => Some(*cluster),
+                _ => None,
+            };
+        }
+    }
+
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    /// Graphviz DOT representation of the graph
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph dependencies {\n    rankdir=LR;\n");
+        let node = |out: &mut String, indent: &str, i: usize| {
+            writeln!(
+                out,
+                "{indent}n{i} [label={}];",
+                dot_quote(&self.nodes[i].label())
+            )
+            .unwrap();
+        };
+        for (cluster, label) in self.clusters.iter().enumerate() {
+            writeln!(out, "    subgraph cluster_{cluster} {{").unwrap();
+            writeln!(out, "        label={};", dot_quote(label)).unwrap();
+            for i in self.cluster_nodes(Some(cluster)) {
+                node(&mut out, "        ", i);
+            }
+            out.push_str("    }\n");
+        }
+        for i in self.cluster_nodes(None) {
+            node(&mut out, "    ", i);
+        }
+        for edge in &self.edges {
+            write!(out, "    n{} -> n{}", edge.from, edge.to).unwrap();
+            if let Some(constraint) = &edge.version_constraint {
+                write!(out, " [label={}]", dot_quote(constraint)).unwrap();
+            }
+            out.push_str(";\n");
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Mermaid flowchart representation of the graph
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart LR\n");
+        let node = |out: &mut String, indent: &str, i: usize| {
+            writeln!(
+                out,
+                "{indent}n{i}[{}]",
+                mermaid_quote(&self.nodes[i].label())
+            )
+            .unwrap();
+        };
+        for (cluster, label) in self.clusters.iter().enumerate() {
+            writeln!(out, "    subgraph c{cluster} [{}]", mermaid_quote(label)).unwrap();
+            for i in self.cluster_nodes(Some(cluster)) {
+                node(&mut out, "        ", i);
+            }
+            out.push_str("    end\n");
+        }
+        for i in self.cluster_nodes(None) {
+            node(&mut out, "    ", i);
+        }
+        for edge in &self.edges {
+            match &edge.version_constraint {
+                Some(constraint) => writeln!(
+                    out,
+                    "    n{} -->|{}| n{}",
+                    edge.from,
+                    mermaid_quote(constraint),
+                    edge.to
+                ),
+                None => writeln!(out, "    n{} --> n{}", edge.from, edge.to),
+            }
+            .unwrap();
+        }
+        out
+    }
+
+    fn cluster_nodes(&self, cluster: Option<usize>) -> impl Iterator<Item = usize> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(move |(_, n)| n.cluster == cluster)
+            .map(|(i, _)| i)
+    }
+}
+
+impl fmt::Display for GraphFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            GraphFormat::Dot => "dot",
+            GraphFormat::Mermaid => "mermaid",
+        })
+    }
+}
+
+/// Compare relative Unix paths, ignoring `./` prefixes and trailing `/`
+fn same_path(a: &str, b: &str) -> bool {
+    fn segments(p: &str) -> impl Iterator<Item = &str> {
+        p.split('/').filter(|s| !s.is_empty() && *s != ".")
+    }
+    segments(a).eq(segments(b))
+}
+
+fn dot_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn mermaid_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "#quot;"))
+}
+
+#[cfg(test)]
+#[path = "./graph_tests.rs"]
+mod tests;
@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use crate::{
+    commands::graph::{DependencyGraph, GraphFormat},
+    lock::{CURRENT_LOCK_VERSION, Lock, Project, Source},
+    model::InterchangeProjectUsageRaw,
+    solve::markers::Markers,
+};
+
+fn locked(name: &str, iri: Option<&str>, source: Source, usages: &[&str]) -> Project {
+    Project {
+        name: name.into(),
+        publisher: None,
+        version: "1.0.0".into(),
+        exports: vec![],
+        identifiers: iri.into_iter().map(Into::into).collect(),
+        sources: vec![source],
+        usages: usages.iter().map(|u| u.to_string().into()).collect(),
+        variant: None,
+    }
+}
+
+fn editable(path: &str) -> Source {
+    Source::Editable {
+        editable: path.into(),
+    }
+}
+
+fn git() -> Source {
+    Source::RemoteGit {
+        remote_git: "https://example.com/lib.git".into(),
+        rev: None,
+    }
+}
+
+/// Workspace members `a` and `b`, both using `urn:kpar:shared`, and `a`
+/// also using `urn:kpar:only-a`
+fn workspace_lock() -> Lock {
+    Lock {
+        lock_version: CURRENT_LOCK_VERSION.into(),
+        markers: Markers::new(),
+        projects: vec![
+            locked(
+                "a",
+                Some("urn:kpar:a"),
+                editable("a"),
+                &["urn:kpar:shared", "urn:kpar:only-a"],
+            ),
+            locked("b", None, editable("b"), &["urn:kpar:shared"]),
+            locked("shared", Some("urn:kpar:shared"), git(), &[]),
+            locked(
+                "only-a",
+                Some("urn:kpar:only-a"),
+                git(),
+                &["urn:kpar:unlocked"],
+            ),
+        ],
+    }
+}
+
+fn declared(project: &Project) -> Vec<InterchangeProjectUsageRaw> {
+    if project.name == "a" {
+        vec![InterchangeProjectUsageRaw::Resource {
+            resource: "urn:kpar:shared".into(),
+            version_constraint: Some("^1.0".into()),
+            when: None,
+            hash: None,
+        }]
+    } else {
+        vec![]
+    }
+}
+
+#[test]
+fn graph_from_lock_to_dot() {
+    let graph = DependencyGraph::from_lock(&workspace_lock(), declared);
+
+    assert_eq!(graph.nodes.len(), 5);
+    assert_eq!(graph.nodes[1].label(), "b@1.0.0");
+    assert_eq!(graph.nodes[4].label(), "urn:kpar:unlocked");
+    assert_eq!(
+        graph.render(GraphFormat::Dot),
+        r#"digraph dependencies {
+    rankdir=LR;
+    n0 [label="urn:kpar:a@1.0.0"];
+    n1 [label="b@1.0.0"];
+    n2 [label="urn:kpar:shared@1.0.0"];
+    n3 [label="urn:kpar:only-a@1.0.0"];
+    n4 [label="urn:kpar:unlocked"];
+    n0 -> n2 [label="^1.0"];
+    n0 -> n3;
+    n1 -> n2;
+    n3 -> n4;
+}
+"#
+    );
+}
+
+#[test]
+fn clustered_graph_to_mermaid() {
+    let mut graph = DependencyGraph::from_lock(&workspace_lock(), declared);
+    graph.cluster_editable(&["./a", "b/"]);
+
+    assert_eq!(graph.clusters, ["urn:kpar:a@1.0.0", "b@1.0.0"]);
+    assert_eq!(
+        graph.render(GraphFormat::Mermaid),
+        r#"flowchart LR
+    subgraph c0 ["urn:kpar:a@1.0.0"]
+        n0["urn:kpar:a@1.0.0"]
+        n3["urn:kpar:only-a@1.0.0"]
+        n4["urn:kpar:unlocked"]
+    end
+    subgraph c1 ["b@1.0.0"]
+        n1["b@1.0.0"]
+    end
+    n2["urn:kpar:shared@1.0.0"]
+    n0 -->|"^1.0"| n2
+    n0 --> n3
+    n1 --> n2
+    n3 --> n4
+"#
+    );
+}
@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Lifecycle hooks configured under `[hooks]` in `sysand.toml`.
+
+use std::{fmt, process::Command};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use thiserror::Error;
+
+use crate::{
+    config::Hook,
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+};
+
+/// Name of the stage the hook is run for, e.g. `pre_build`
+pub const HOOK_ENV: &str = "SYSAND_HOOK";
+/// Root directory of the project, also the working directory of the hook
+pub const PROJECT_ROOT_ENV: &str = "SYSAND_PROJECT_ROOT";
+/// Source files of the project and its dependencies, separated like `PATH`
+pub const SOURCES_ENV: &str = "SYSAND_SOURCES";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    PreBuild,
+    PostSync,
+}
+
+impl HookStage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HookStage::PreBuild => "pre_build",
+            HookStage::PostSync => "post_sync",
+        }
+    }
+}
+
+impl fmt::Display for HookStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum HookError {
+    #[error("failed to run `{stage}` hook `{name}`: {source}")]
+    Spawn {
+        stage: HookStage,
+        name: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("`{stage}` hook `{name}` exited with {status}")]
+    Failed {
+        stage: HookStage,
+        name: String,
+        status: std::process::ExitStatus,
+    },
+    #[error("source paths cannot be passed to hooks in `{SOURCES_ENV}`: {0}")]
+    Sources(#[from] std::env::JoinPathsError),
+}
+
+impl HasErrorCode for HookError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            HookError::Spawn { .. } => ErrorCode::new(2801, ErrorClass::Other),
+            HookError::Failed { .. } => ErrorCode::new(2802, ErrorClass::Other),
+            HookError::Sources(_) => ErrorCode::new(2803, ErrorClass::Other),
+        }
+    }
+}
+
+/// Run `hooks` of `stage` in order in `project_root`, stopping at the first
+/// one that fails. `sources` are passed in [`SOURCES_ENV`]
+pub fn run_hooks(
+    stage: HookStage,
+    hooks: &[Hook],
+    project_root: &Utf8Path,
+    sources: &[Utf8PathBuf],
+) -> Result<(), HookError> {
+    if hooks.is_empty() {
+        return Ok(());
+    }
+    log::warn!(
+        "running `{stage}` hooks configured in `sysand.toml`; they are not\n\
+        sandboxed and run with your permissions, use `--no-hooks` to skip them"
+    );
+    let sources = std::env::join_paths(sources)?;
+    let header = crate::style::get_style_config().header;
+    for hook in hooks {
+        let name = hook.name.as_deref().unwrap_or(&hook.command);
+        let running = "Running";
+        log::info!(
+            "{header}{running:>12}{header:#} `{stage}` hook `{name}`: `{}`",
+            std::iter::once(&hook.command)
+                .chain(&hook.args)
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+        let status = Command::new(&hook.command)
+            .args(&hook.args)
+            .current_dir(project_root)
+            .env(HOOK_ENV, stage.as_str())
+            .env(PROJECT_ROOT_ENV, project_root)
+            .env(SOURCES_ENV, &sources)
+            .status()
+            .map_err(|source| HookError::Spawn {
+                stage,
+                name: name.to_owned(),
+                source,
+            })?;
+        if !status.success() {
+            return Err(HookError::Failed {
+                stage,
+                name: name.to_owned(),
+                status,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "./hooks_tests.rs"]
+mod tests;
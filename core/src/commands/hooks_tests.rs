@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::assert_matches;
+
+use camino::Utf8PathBuf;
+use camino_tempfile::tempdir;
+
+use super::{HookError, HookStage, run_hooks};
+use crate::config::Hook;
+
+fn sh(name: &str, script: &str) -> Hook {
+    Hook {
+        name: Some(name.to_owned()),
+        command: "sh".to_owned(),
+        args: vec!["-c".to_owned(), script.to_owned()],
+    }
+}
+
+#[test]
+fn no_hooks() {
+    run_hooks(HookStage::PreBuild, &[], "does/not/exist".into(), &[]).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn hooks_get_stage_and_sources() {
+    let dir = tempdir().unwrap();
+    let sources = [Utf8PathBuf::from("a.sysml"), Utf8PathBuf::from("b.sysml")];
+    let hooks = [sh("env", "echo \"$SYSAND_HOOK $SYSAND_SOURCES\" > out.txt")];
+
+    run_hooks(HookStage::PostSync, &hooks, dir.path(), &sources).unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("out.txt")).unwrap(),
+        "post_sync a.sysml:b.sysml\n"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn failing_hook_stops_the_rest() {
+    let dir = tempdir().unwrap();
+    let hooks = [sh("fails", "exit 3"), sh("touch", "touch out.txt")];
+
+    let err = run_hooks(HookStage::PreBuild, &hooks, dir.path(), &[]).unwrap_err();
+
+    assert_matches!(err, HookError::Failed { name, .. } if name == "fails");
+    assert!(!dir.path().join("out.txt").exists());
+}
@@ -1,16 +1,19 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
-use std::io::Read;
+use std::{collections::BTreeSet, io::Read};
 
 use thiserror::Error;
 use typed_path::{Utf8UnixPath, Utf8UnixPathBuf};
 
 use crate::{
-    model::KerMlChecksumAlg,
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    model::{HashAlg, InterchangeProjectMetadataRaw, InterchangeProjectUsageRaw, KerMlChecksumAlg},
+    parallel::{Jobs, parallel_map},
     project::{ProjectMut, ProjectOrIOError, ProjectRead, utils::FsIoError},
-    symbols::{ExtractError, Language},
-    utils::sha256_lowercase_hex,
+    stdlib::std_usages_for_names,
+    symbols::{ExtractError, Language, referenced_names},
+    utils::hash_lowercase_hex,
 };
 
 #[derive(Error, Debug)]
@@ -27,6 +30,17 @@ pub enum IncludeError<ProjectError> {
     UnknownFormat(Box<str>),
 }
 
+impl<ProjectError> HasErrorCode for IncludeError<ProjectError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            IncludeError::Project(_) => ErrorCode::new(401, ErrorClass::Project),
+            IncludeError::Io(_) => ErrorCode::new(402, ErrorClass::Io),
+            IncludeError::Extract(..) => ErrorCode::new(403, ErrorClass::Project),
+            IncludeError::UnknownFormat(_) => ErrorCode::new(404, ErrorClass::Usage),
+        }
+    }
+}
+
 impl<ProjectError> From<FsIoError> for IncludeError<ProjectError> {
     fn from(v: FsIoError) -> Self {
         Self::Io(Box::new(v))
@@ -44,10 +58,12 @@ impl<ProjectError> From<ProjectOrIOError<ProjectError>> for IncludeError<Project
 
 // TODO: Add a CLI option to make the file format explicit (useful in cases
 // of non-standard file extensions)
+/// Add `paths` to the metadata of `project`. Their checksums are computed
+/// with `checksum_algorithm`, or set to `NONE` if it is `None`
 pub fn do_include<Pr: ProjectMut, I: Iterator<Item = Utf8UnixPathBuf>>(
     project: &mut Pr,
     paths: I,
-    compute_checksum: bool,
+    checksum_algorithm: Option<HashAlg>,
     index_symbols: bool,
     force_format: Option<Language>,
 ) -> Result<(), IncludeError<Pr::Error>> {
@@ -57,33 +73,52 @@ pub fn do_include<Pr: ProjectMut, I: Iterator<Item = Utf8UnixPathBuf>>(
         .map_err(IncludeError::Project)?
         .unwrap_or_default();
     for path in paths {
-        let including = "Including";
-        let header = crate::style::get_style_config().header;
-        log::info!("{header}{including:>12}{header:#} file `{path}`");
-
-        let source = read_project_file_to_string(&project, &path)?;
-        if compute_checksum {
-            let checksum = sha256_lowercase_hex(&source);
-            meta.add_checksum(&path, KerMlChecksumAlg::Sha256, checksum, true);
-        } else {
-            meta.add_checksum(&path, KerMlChecksumAlg::None, "", true);
-        }
+        log_including(&path);
+        let included = read_included_file(
+            project,
+            &path,
+            checksum_algorithm,
+            index_symbols,
+            force_format,
+        )?;
+        add_included_file(&mut meta, &path, included);
+    }
+    project
+        .put_meta(&meta, true)
+        .map_err(IncludeError::Project)?;
+    Ok(())
+}
 
-        if index_symbols {
-            // Remove if present any existing symbols from the same file
-            meta.index.retain(|s, v| {
-                if *v == path {
-                    log::debug!("meta.index: removing obsolete symbol `{s}` (file `{v}`)");
-                    false
-                } else {
-                    true
-                }
-            });
-
-            for s in extract_symbols(&path, &source, force_format)? {
-                meta.index.insert(s, path.to_string());
-            }
-        }
+/// Same as [`do_include`], but reads, checksums and extracts symbols from
+/// the files on up to `jobs` threads. The metadata is updated in the order
+/// of `paths`, and if several files fail, the error of the first one is
+/// returned
+pub fn do_include_parallel<Pr: ProjectMut + Sync, I: Iterator<Item = Utf8UnixPathBuf>>(
+    project: &mut Pr,
+    paths: I,
+    checksum_algorithm: Option<HashAlg>,
+    index_symbols: bool,
+    force_format: Option<Language>,
+    jobs: Jobs,
+) -> Result<(), IncludeError<Pr::Error>> {
+    let mut meta = project
+        .get_meta()
+        .map_err(IncludeError::Project)?
+        .unwrap_or_default();
+    let paths: Vec<Utf8UnixPathBuf> = paths.collect();
+    let shared: &Pr = project;
+    let included = parallel_map(&paths, jobs, |path| {
+        read_included_file(
+            shared,
+            path,
+            checksum_algorithm,
+            index_symbols,
+            force_format,
+        )
+    });
+    for (path, included) in paths.iter().zip(included) {
+        log_including(path);
+        add_included_file(&mut meta, path, included?);
     }
     project
         .put_meta(&meta, true)
@@ -91,6 +126,98 @@ pub fn do_include<Pr: ProjectMut, I: Iterator<Item = Utf8UnixPathBuf>>(
     Ok(())
 }
 
+/// Checksum and symbols of a file being included
+struct IncludedFile {
+    checksum: Option<(HashAlg, String)>,
+    symbols: Option<Vec<String>>,
+}
+
+fn log_including(path: &Utf8UnixPath) {
+    let including = "Including";
+    let header = crate::style::get_style_config().header;
+    log::info!("{header}{including:>12}{header:#} file `{path}`");
+}
+
+fn read_included_file<Pr: ProjectRead>(
+    project: &Pr,
+    path: &Utf8UnixPath,
+    checksum_algorithm: Option<HashAlg>,
+    index_symbols: bool,
+    force_format: Option<Language>,
+) -> Result<IncludedFile, IncludeError<Pr::Error>> {
+    let source = read_project_file_to_string(project, &path)?;
+    let checksum = checksum_algorithm.map(|a| (a, hash_lowercase_hex(a, &source)));
+    let symbols = if index_symbols {
+        Some(extract_symbols(&path, &source, force_format)?)
+    } else {
+        None
+    };
+    Ok(IncludedFile { checksum, symbols })
+}
+
+fn add_included_file(
+    meta: &mut InterchangeProjectMetadataRaw,
+    path: &Utf8UnixPath,
+    included: IncludedFile,
+) {
+    match included.checksum {
+        Some((algorithm, checksum)) => meta.add_checksum(path, algorithm.into(), checksum, true),
+        None => meta.add_checksum(path, KerMlChecksumAlg::None, "", true),
+    };
+
+    if let Some(symbols) = included.symbols {
+        // Remove if present any existing symbols from the same file
+        meta.index.retain(|s, v| {
+            if *v == path.as_str() {
+                log::debug!("meta.index: removing obsolete symbol `{s}` (file `{v}`)");
+                false
+            } else {
+                true
+            }
+        });
+
+        for s in symbols {
+            meta.index.insert(s, path.to_string());
+        }
+    }
+}
+
+/// Usages of standard libraries that the files at `paths` refer to, but
+/// `project` does not declare. Names defined by the project itself are not
+/// attributed to standard libraries
+pub fn find_missing_std_usages<Pr: ProjectRead, P: AsRef<Utf8UnixPath>>(
+    project: &Pr,
+    paths: &[P],
+) -> Result<Vec<InterchangeProjectUsageRaw>, IncludeError<Pr::Error>> {
+    let mut names = BTreeSet::new();
+    for path in paths {
+        let source = read_project_file_to_string(project, path)?;
+        names.extend(
+            referenced_names(&source)
+                .map_err(|e| IncludeError::Extract(path.as_ref().as_str().into(), e))?,
+        );
+    }
+    if let Some(meta) = project.get_meta().map_err(IncludeError::Project)? {
+        for symbol in meta.index.keys() {
+            names.remove(symbol);
+        }
+    }
+    let declared = project
+        .get_info()
+        .map_err(IncludeError::Project)?
+        .map(|info| info.usage)
+        .unwrap_or_default();
+    Ok(std_usages_for_names(names.iter().map(String::as_str))
+        .into_iter()
+        .filter(|usage| {
+            let InterchangeProjectUsageRaw::Resource { resource, .. } = usage;
+            !declared.iter().any(|d| match d {
+                InterchangeProjectUsageRaw::Resource { resource: r, .. } => r == resource,
+            })
+        })
+        .collect())
+}
+
 /// Extract top level symbols from `source`, using `path` for diagnostics
 /// only
 pub fn extract_symbols<P: AsRef<Utf8UnixPath>, S: AsRef<str>, T>(
@@ -118,3 +245,7 @@ pub fn read_project_file_to_string<Pr: ProjectRead, P: AsRef<Utf8UnixPath>>(
     })?;
     Ok(source)
 }
+
+#[cfg(test)]
+#[path = "./include_tests.rs"]
+mod tests;
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::io::Cursor;
+
+use typed_path::Utf8UnixPath;
+
+use crate::{
+    add::do_add,
+    include::{do_include, find_missing_std_usages},
+    init::do_init_memory,
+    model::InterchangeProjectUsageRaw,
+    project::ProjectMut,
+};
+
+const QUANTITIES: &str =
+    "https://www.omg.org/spec/SysML/20250201/Quantities-and-Units-Domain-Library.kpar";
+
+fn usage(resource: &str, version: &str) -> InterchangeProjectUsageRaw {
+    InterchangeProjectUsageRaw::Resource {
+        resource: resource.into(),
+        version_constraint: Some(version.into()),
+        when: None,
+        hash: None,
+    }
+}
+
+#[test]
+fn missing_std_usages_of_included_files() {
+    let mut project = do_init_memory("test", None::<&str>, "1.0.0", None).unwrap();
+    project
+        .write_source(
+            Utf8UnixPath::new("a.sysml"),
+            &mut Cursor::new(
+                "package A {\n    private import ISQ::*;\n    attribute m : SI::kg;\n    import B::*;\n}\n",
+            ),
+            true,
+        )
+        .unwrap();
+    project
+        .write_source(
+            Utf8UnixPath::new("b.sysml"),
+            &mut Cursor::new("package ScalarValues;\npackage B;\n"),
+            true,
+        )
+        .unwrap();
+    do_include(
+        &mut project,
+        ["a.sysml".into(), "b.sysml".into()].into_iter(),
+        None,
+        true,
+        None,
+    )
+    .unwrap();
+
+    // `ScalarValues` is defined by the project itself
+    assert_eq!(
+        find_missing_std_usages(&project, &["a.sysml", "b.sysml"]).unwrap(),
+        [usage(QUANTITIES, "2.0.0")]
+    );
+
+    do_add(&mut project, &usage(QUANTITIES, "2.0.0")).unwrap();
+    assert_eq!(find_missing_std_usages(&project, &["a.sysml"]).unwrap(), []);
+}
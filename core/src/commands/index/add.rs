@@ -13,13 +13,14 @@ use super::{
 };
 
 use crate::{
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
     index::{
         iri::{ParseIriError, ParsedIri, parse_iri},
         model::{
             IndexJson, IndexProject, ProjectStatus, VersionEntry, VersionStatus, VersionsJson,
         },
     },
-    model::InterchangeProjectValidationError,
+    model::{InterchangeProjectInfoRaw, InterchangeProjectValidationError},
     project::{
         CanonicalizationError, ProjectRead as _,
         local_kpar::{LocalKParError, LocalKParProjectRaw},
@@ -144,6 +145,70 @@ pub enum IndexAddError {
         kpar_path: Box<Utf8Path>,
         root_in_kpar: Box<Utf8Path>,
     },
+    #[error(
+        "{iri} differs only in case from {existing}, which is already in the index; \
+         `urn:kpar` names must be unique ignoring case"
+    )]
+    KparNameCollision { iri: Box<str>, existing: Box<str> },
+    #[error(
+        "{iri} is already used by a project of {}, but this project is of {}",
+        publisher_or_none(.existing_publisher),
+        publisher_or_none(.publisher)
+    )]
+    KparPublisherCollision {
+        iri: Box<str>,
+        existing_publisher: Option<String>,
+        publisher: Option<String>,
+    },
+}
+
+fn publisher_or_none(publisher: &Option<String>) -> String {
+    match publisher {
+        Some(publisher) => format!("publisher `{publisher}`"),
+        None => "no publisher".to_owned(),
+    }
+}
+
+impl HasErrorCode for IndexAddError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            IndexAddError::IndexRootNotFound(..) => ErrorCode::new(1401, ErrorClass::Index),
+            IndexAddError::NotAnIndex(..) => ErrorCode::new(1402, ErrorClass::Index),
+            IndexAddError::Io(..) => ErrorCode::new(1403, ErrorClass::Io),
+            IndexAddError::IoWithCleanupSuccess { .. } => ErrorCode::new(1404, ErrorClass::Io),
+            IndexAddError::IoWithCleanupFailure { .. } => ErrorCode::new(1405, ErrorClass::Io),
+            IndexAddError::InvalidJsonFile { .. } => ErrorCode::new(1406, ErrorClass::Index),
+            IndexAddError::MissingInfo(..) => ErrorCode::new(1407, ErrorClass::Project),
+            IndexAddError::MissingMeta(..) => ErrorCode::new(1408, ErrorClass::Project),
+            IndexAddError::ProjectDigest(..) => ErrorCode::new(1409, ErrorClass::Project),
+            IndexAddError::ProjectRead(..) => ErrorCode::new(1410, ErrorClass::Project),
+            IndexAddError::InvalidProject { .. } => ErrorCode::new(1411, ErrorClass::Project),
+            IndexAddError::VersionHasBuildMetadata { .. } => {
+                ErrorCode::new(1412, ErrorClass::Project)
+            }
+            IndexAddError::InvalidIri(..) => ErrorCode::new(1413, ErrorClass::Usage),
+            IndexAddError::InvalidPublisherInProject { .. } => {
+                ErrorCode::new(1414, ErrorClass::Project)
+            }
+            IndexAddError::InvalidNameInProject { .. } => ErrorCode::new(1415, ErrorClass::Project),
+            IndexAddError::InconsistentName { .. } => ErrorCode::new(1416, ErrorClass::Index),
+            IndexAddError::MissingPublisherSpecifiedInIri { .. } => {
+                ErrorCode::new(1417, ErrorClass::Index)
+            }
+            IndexAddError::InconsistentPublisher { .. } => ErrorCode::new(1418, ErrorClass::Index),
+            IndexAddError::MissingPublisherAndIri => ErrorCode::new(1419, ErrorClass::Index),
+            IndexAddError::ProjectRemoved { .. } => ErrorCode::new(1420, ErrorClass::Index),
+            IndexAddError::DuplicateProject { .. } => ErrorCode::new(1421, ErrorClass::Index),
+            IndexAddError::InvalidExistingVersion { .. } => ErrorCode::new(1422, ErrorClass::Index),
+            IndexAddError::DuplicateVersion { .. } => ErrorCode::new(1423, ErrorClass::Index),
+            IndexAddError::VersionAlreadyExists { .. } => ErrorCode::new(1424, ErrorClass::Index),
+            IndexAddError::VersionYanked { .. } => ErrorCode::new(1425, ErrorClass::Index),
+            IndexAddError::VersionRemoved { .. } => ErrorCode::new(1426, ErrorClass::Index),
+            IndexAddError::ProjectNotAtRoot { .. } => ErrorCode::new(1427, ErrorClass::Project),
+            IndexAddError::KparNameCollision { .. } => ErrorCode::new(1428, ErrorClass::Index),
+            IndexAddError::KparPublisherCollision { .. } => ErrorCode::new(1429, ErrorClass::Index),
+        }
+    }
 }
 
 pub fn do_index_add<I: AsRef<str>, P: AsRef<Utf8Path>, R: AsRef<Utf8Path>>(
@@ -242,6 +307,20 @@ pub fn do_index_add<I: AsRef<str>, P: AsRef<Utf8Path>, R: AsRef<Utf8Path>>(
 
     let iri = parsed_iri.get_iri();
     let project_path = index_root.join(parsed_iri.get_path());
+    let is_kpar_urn = matches!(parsed_iri, ParsedIri::Kpar { .. });
+    if is_kpar_urn
+        && let Some(existing) = index_value
+            .projects
+            .iter()
+            .find(|p| p.iri != iri && p.iri.eq_ignore_ascii_case(&iri))
+    {
+        // Index paths must not differ only in case, as they may be
+        // served from case-insensitive filesystems
+        return Err(IndexAddError::KparNameCollision {
+            iri: iri.into(),
+            existing: existing.iri.as_str().into(),
+        });
+    }
 
     let project_entries: Vec<_> = index_value
         .projects
@@ -302,6 +381,28 @@ pub fn do_index_add<I: AsRef<str>, P: AsRef<Utf8Path>, R: AsRef<Utf8Path>>(
         }
     }
 
+    // `urn:kpar` names are not tied to a publisher, so make sure that a
+    // name is not taken over by a project of another publisher
+    if is_kpar_urn && let Some(newest) = versions_value.versions.first() {
+        let newest_info_path = project_path.join(&newest.version).join(INFO_FILE_NAME);
+        let newest_info: InterchangeProjectInfoRaw =
+            serde_json::from_str(&wrapfs::read_to_string(&newest_info_path)?).map_err(|e| {
+                IndexAddError::InvalidJsonFile {
+                    path: newest_info_path.as_str().into(),
+                    source: e,
+                }
+            })?;
+        let existing_publisher = newest_info.publisher.as_deref().map(normalize_field);
+        let publisher = info.publisher.as_deref().map(normalize_field);
+        if existing_publisher != publisher {
+            return Err(IndexAddError::KparPublisherCollision {
+                iri: iri.into(),
+                existing_publisher,
+                publisher,
+            });
+        }
+    }
+
     let insert_ind = match versions_value
         .versions
         .binary_search_by_key(&&Reverse(semver.clone()), version_key)
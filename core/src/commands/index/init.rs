@@ -12,6 +12,7 @@ use thiserror::Error;
 use super::{INDEX_FILE_NAME, to_json_string};
 
 use crate::{
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
     index::model::IndexJson,
     project::utils::{FsIoError, wrapfs},
 };
@@ -24,6 +25,15 @@ pub enum IndexInitError {
     WriteError(#[from] Box<FsIoError>),
 }
 
+impl HasErrorCode for IndexInitError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            IndexInitError::AlreadyExists => ErrorCode::new(1301, ErrorClass::Index),
+            IndexInitError::WriteError(..) => ErrorCode::new(1302, ErrorClass::Io),
+        }
+    }
+}
+
 pub fn do_index_init<R: AsRef<Utf8Path>>(index_root: R) -> Result<(), IndexInitError> {
     let creating = "Creating";
     let header = crate::style::get_style_config().header;
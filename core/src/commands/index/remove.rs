@@ -12,6 +12,7 @@ use super::{
 };
 
 use crate::{
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
     index::{
         iri::{ParseIriError, parse_iri},
         model::{IndexJson, ProjectStatus, VersionEntry, VersionStatus, VersionsJson},
@@ -47,6 +48,20 @@ pub enum IndexRemoveError {
     VersionNotFound { iri: Box<str>, version: Box<str> },
 }
 
+impl HasErrorCode for IndexRemoveError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            IndexRemoveError::IndexRootNotFound(..) => ErrorCode::new(1601, ErrorClass::Index),
+            IndexRemoveError::NotAnIndex { .. } => ErrorCode::new(1602, ErrorClass::Index),
+            IndexRemoveError::ProjectNotFound { .. } => ErrorCode::new(1603, ErrorClass::Index),
+            IndexRemoveError::Io(..) => ErrorCode::new(1604, ErrorClass::Io),
+            IndexRemoveError::InvalidJsonFile { .. } => ErrorCode::new(1605, ErrorClass::Index),
+            IndexRemoveError::InvalidIri(..) => ErrorCode::new(1606, ErrorClass::Usage),
+            IndexRemoveError::VersionNotFound { .. } => ErrorCode::new(1607, ErrorClass::Index),
+        }
+    }
+}
+
 pub enum RemoveTarget {
     // It's String and not semver::Version because it's good to allow removing a non-semantic
     // version
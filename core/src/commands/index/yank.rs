@@ -10,6 +10,7 @@ use super::{
 };
 
 use crate::{
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
     index::{
         iri::{ParseIriError, parse_iri},
         model::{IndexJson, VersionStatus, VersionsJson},
@@ -49,6 +50,21 @@ pub enum IndexYankError {
     VersionNotFound { iri: Box<str>, version: Box<str> },
 }
 
+impl HasErrorCode for IndexYankError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            IndexYankError::IndexRootNotFound(..) => ErrorCode::new(1501, ErrorClass::Index),
+            IndexYankError::NotAnIndex { .. } => ErrorCode::new(1502, ErrorClass::Index),
+            IndexYankError::ProjectNotFound { .. } => ErrorCode::new(1503, ErrorClass::Index),
+            IndexYankError::Io(..) => ErrorCode::new(1504, ErrorClass::Io),
+            IndexYankError::InvalidJsonFile { .. } => ErrorCode::new(1505, ErrorClass::Index),
+            IndexYankError::InvalidIri(..) => ErrorCode::new(1506, ErrorClass::Usage),
+            IndexYankError::VersionRemoved { .. } => ErrorCode::new(1507, ErrorClass::Index),
+            IndexYankError::VersionNotFound { .. } => ErrorCode::new(1508, ErrorClass::Index),
+        }
+    }
+}
+
 pub fn do_index_yank<R: AsRef<Utf8Path>, I: AsRef<str>, V: AsRef<str>>(
     iri: I,
     // It's String and not semver::Version because it's good to allow yanking a non-semantic
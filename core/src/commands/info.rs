@@ -2,10 +2,12 @@
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
 use semver::Version;
+use serde::Serialize;
 use thiserror::Error;
 
 use crate::{
-    env::utils::ErrorBound,
+    env::{ReadEnvironment, utils::ErrorBound},
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
     model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
     project::ProjectRead,
     resolve::{ResolutionOutcome, ResolveRead},
@@ -25,6 +27,17 @@ pub enum InfoProjectError<Error: ErrorBound> {
     InvalidProject(#[from] Error),
 }
 
+impl<Error: ErrorBound> HasErrorCode for InfoProjectError<Error> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            InfoProjectError::MissingProject => ErrorCode::new(601, ErrorClass::Project),
+            InfoProjectError::MissingInfo => ErrorCode::new(602, ErrorClass::Project),
+            InfoProjectError::MissingMeta => ErrorCode::new(603, ErrorClass::Project),
+            InfoProjectError::InvalidProject(_) => ErrorCode::new(604, ErrorClass::Project),
+        }
+    }
+}
+
 pub fn do_info_project<P: ProjectRead>(
     project: &P,
 ) -> Result<(InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw), InfoProjectError<P::Error>>
@@ -50,6 +63,17 @@ pub enum InfoError<Error: ErrorBound> {
     Resolution(#[from] Error),
 }
 
+impl<Error: ErrorBound> HasErrorCode for InfoError<Error> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            InfoError::NoSemanticVersionsFound(_) => ErrorCode::new(650, ErrorClass::Resolution),
+            InfoError::NoResolve(..) => ErrorCode::new(651, ErrorClass::Resolution),
+            InfoError::UnsupportedIri(..) => ErrorCode::new(652, ErrorClass::Usage),
+            InfoError::Resolution(_) => ErrorCode::new(653, ErrorClass::Resolution),
+        }
+    }
+}
+
 pub fn do_info<S: AsRef<str>, R: ResolveRead>(
     uri: S,
     resolver: &R,
@@ -116,3 +140,87 @@ pub fn do_info<S: AsRef<str>, R: ResolveRead>(
         ResolutionOutcome::Unresolvable(e) => Err(InfoError::NoResolve(uri.as_ref().into(), e)),
     }
 }
+
+/// Info of a project version in an environment, one line of the output of
+/// `sysand info --all-installed`
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct EnvInfoEntry {
+    pub iri: String,
+    pub version: String,
+    pub info: InterchangeProjectInfoRaw,
+    pub meta: InterchangeProjectMetadataRaw,
+}
+
+#[derive(Error, Debug)]
+pub enum EnvInfoError<Error: ErrorBound> {
+    #[error("failed to list projects: {0}")]
+    List(Error),
+    #[error("failed to read `{iri}` {version}: {cause}")]
+    Project {
+        iri: String,
+        version: String,
+        cause: String,
+    },
+}
+
+impl<Error: ErrorBound> HasErrorCode for EnvInfoError<Error> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            EnvInfoError::List(_) => ErrorCode::new(654, ErrorClass::Resolution),
+            EnvInfoError::Project { .. } => ErrorCode::new(655, ErrorClass::Project),
+        }
+    }
+}
+
+pub type EnvInfoResult<Error> = Result<EnvInfoEntry, EnvInfoError<Error>>;
+
+/// Info of every version of every project in `env`, read lazily so that
+/// the entries of large environments such as indexes can be streamed.
+/// A project that cannot be read is reported without ending the iteration
+pub fn do_info_env<Env: ReadEnvironment>(
+    env: &Env,
+) -> Result<impl Iterator<Item = EnvInfoResult<Env::ReadError>> + '_, EnvInfoError<Env::ReadError>>
+{
+    let uris = env.uris().map_err(EnvInfoError::List)?;
+    Ok(uris.into_iter().flat_map(move |iri| {
+        let entries: Box<dyn Iterator<Item = _>> = match iri {
+            Ok(iri) => match env.versions(&iri) {
+                Ok(versions) => Box::new(
+                    versions
+                        .into_iter()
+                        .map(move |version| info_entry(env, &iri, version)),
+                ),
+                Err(e) => Box::new(std::iter::once(Err(EnvInfoError::List(e)))),
+            },
+            Err(e) => Box::new(std::iter::once(Err(EnvInfoError::List(e)))),
+        };
+        entries
+    }))
+}
+
+fn info_entry<Env: ReadEnvironment>(
+    env: &Env,
+    iri: &str,
+    version: Result<String, Env::ReadError>,
+) -> EnvInfoResult<Env::ReadError> {
+    let version = version.map_err(EnvInfoError::List)?;
+    let project_error = |cause| EnvInfoError::Project {
+        iri: iri.to_owned(),
+        version: version.clone(),
+        cause,
+    };
+    let project = env
+        .get_project(iri, &version)
+        .map_err(|e| project_error(format_err(e)))?;
+    let (info, meta) = do_info_project(&project).map_err(|e| project_error(format_err(e)))?;
+    Ok(EnvInfoEntry {
+        iri: iri.to_owned(),
+        version,
+        info,
+        meta,
+    })
+}
+
+#[cfg(test)]
+#[path = "./info_tests.rs"]
+mod tests;
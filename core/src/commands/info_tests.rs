@@ -0,0 +1,46 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use super::do_info_env;
+use crate::{
+    env::{
+        OverwritePolicy, WriteEnvironment, memory::MemoryStorageEnvironment, utils::clone_project,
+    },
+    init::do_init_memory,
+    project::{ProjectRead, memory::InMemoryProject},
+};
+
+#[test]
+fn info_env_lists_every_version() {
+    let mut env = MemoryStorageEnvironment::<InMemoryProject>::new();
+    for (iri, name, version) in [
+        ("urn:kpar:a", "A", "1.0.0"),
+        ("urn:kpar:a", "A", "2.0.0"),
+        ("urn:kpar:b", "B", "0.1.0"),
+    ] {
+        let project = do_init_memory(name, None::<&str>, version, None).unwrap();
+        let checksum = project.checksum_canonical_variant().unwrap();
+        env.put_project(iri, version, Some(checksum), OverwritePolicy::Error, |p| {
+            clone_project(&project, p, true).map(|_| ())
+        })
+        .unwrap();
+    }
+
+    let mut entries: Vec<_> = do_info_env(&env)
+        .unwrap()
+        .map(|entry| {
+            let entry = entry.unwrap();
+            (entry.iri, entry.version, entry.info.name)
+        })
+        .collect();
+    entries.sort();
+
+    assert_eq!(
+        entries,
+        [
+            ("urn:kpar:a".into(), "1.0.0".into(), "A".into()),
+            ("urn:kpar:a".into(), "2.0.0".into(), "A".into()),
+            ("urn:kpar:b".into(), "0.1.0".into(), "B".into()),
+        ]
+    );
+}
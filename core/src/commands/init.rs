@@ -8,8 +8,10 @@ use spdx;
 
 use crate::{
     env::utils::ErrorBound,
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
     model::{InterchangeProjectInfoRaw, InterchangeProjectMetadata},
     project::{ProjectMut, memory::InMemoryProject},
+    purl::{SysandPurlError, parse_sysand_purl, sysand_purl},
 };
 
 #[cfg(feature = "filesystem")]
@@ -19,6 +21,19 @@ use thiserror::Error;
 
 const DEFAULT_PUBLISHER: &str = "untitled";
 
+/// Schemes of IRIs that the standard resolvers can fetch projects from
+const RESOLVABLE_SCHEMES: &[&str] = &[
+    "pkg",
+    "file",
+    "http",
+    "https",
+    "ssh",
+    "git+file",
+    "git+http",
+    "git+https",
+    "git+ssh",
+];
+
 #[derive(Error, Debug)]
 pub enum InitError<ProjectError: ErrorBound> {
     #[error("failed to parse `{0}` as a Semantic Version: {1}")]
@@ -29,6 +44,61 @@ pub enum InitError<ProjectError: ErrorBound> {
     SPDXLicenseParse(Box<str>, spdx::error::ParseError),
 }
 
+impl<ProjectError: ErrorBound> HasErrorCode for InitError<ProjectError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            InitError::SemVerParse(..) => ErrorCode::new(101, ErrorClass::Usage),
+            InitError::Project(_) => ErrorCode::new(102, ErrorClass::Project),
+            InitError::SPDXLicenseParse(..) => ErrorCode::new(103, ErrorClass::Usage),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ProjectIriError {
+    #[error("`{0}` is not a valid IRI: {1}")]
+    Parse(Box<str>, fluent_uri::ParseError),
+    #[error(transparent)]
+    InvalidPurl(#[from] SysandPurlError),
+}
+
+impl HasErrorCode for ProjectIriError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            ProjectIriError::Parse(..) => ErrorCode::new(104, ErrorClass::Usage),
+            ProjectIriError::InvalidPurl(_) => ErrorCode::new(105, ErrorClass::Usage),
+        }
+    }
+}
+
+/// IRI suggested for a new project, i.e. its `pkg:sysand` IRI, if the
+/// publisher and name are valid for one
+pub fn suggest_project_iri(name: &str, publisher: Option<&str>) -> Option<String> {
+    sysand_purl(publisher.unwrap_or(DEFAULT_PUBLISHER), name)
+}
+
+/// Validate `iri` as the canonical IRI of a project. Warns if no standard
+/// resolver can fetch projects identified by it
+pub fn validate_project_iri(iri: &str) -> Result<fluent_uri::Iri<String>, ProjectIriError> {
+    let parsed = fluent_uri::Iri::parse(iri.to_owned())
+        .map_err(|(e, _)| ProjectIriError::Parse(iri.into(), e))?;
+    let scheme = parsed.scheme().as_str();
+    if scheme.eq_ignore_ascii_case("pkg") {
+        if parse_sysand_purl(iri)?.is_none() {
+            log::warn!(
+                "`{iri}` is not a `pkg:sysand` IRI, projects identified by it\n\
+                cannot be resolved from an index"
+            );
+        }
+    } else if !RESOLVABLE_SCHEMES
+        .iter()
+        .any(|s| scheme.eq_ignore_ascii_case(s))
+    {
+        log::warn!("IRI scheme `{scheme}` of `{iri}` is not supported by any resolver");
+    }
+    Ok(parsed)
+}
+
 pub fn do_init_ext<P: ProjectMut>(
     name: String,
     publisher: Option<String>,
@@ -75,6 +145,7 @@ pub fn do_init_ext<P: ProjectMut>(
             includes_derived: None,
             includes_implied: None,
             checksum: None,
+            variants: indexmap::IndexMap::new(),
         }
         .into(),
         false,
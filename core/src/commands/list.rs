@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::{
+    env::{ReadEnvironment, utils::ErrorBound},
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    lock::{Lock, Source},
+    model::InterchangeProjectUsageRaw,
+};
+
+/// Kind of source a locked project is fetched from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    /// Project under development, used in place
+    Editable,
+    /// Local directory or KPAR archive
+    Path,
+    /// Sysand index
+    Index,
+    /// Git repository
+    Git,
+    /// Remote directory or KPAR archive
+    Url,
+}
+
+impl SourceKind {
+    pub fn of(source: &Source) -> Self {
+        match source {
+            Source::Editable { .. } => SourceKind::Editable,
+            Source::LocalSrc { .. } | Source::LocalKpar { .. } => SourceKind::Path,
+            Source::IndexKpar { .. } => SourceKind::Index,
+            Source::RemoteGit { .. } => SourceKind::Git,
+            Source::RemoteKpar { .. } | Source::RemoteSrc { .. } => SourceKind::Url,
+        }
+    }
+}
+
+impl fmt::Display for SourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SourceKind::Editable => "editable",
+            SourceKind::Path => "path",
+            SourceKind::Index => "index",
+            SourceKind::Git => "git",
+            SourceKind::Url => "url",
+        })
+    }
+}
+
+/// Status of a usage of the current project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageStatus {
+    pub iri: String,
+    pub version_constraint: Option<String>,
+    /// Version in the lockfile, if the usage is locked
+    pub locked_version: Option<String>,
+    /// Whether the locked version is installed in the environment
+    pub installed: bool,
+    /// Kind of the first source of the locked project
+    pub source: Option<SourceKind>,
+}
+
+#[derive(Error, Debug)]
+pub enum ListError<EnvError: ErrorBound> {
+    #[error("failed to read environment: {0}")]
+    Env(EnvError),
+}
+
+impl<EnvError: ErrorBound> HasErrorCode for ListError<EnvError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            ListError::Env(_) => ErrorCode::new(2101, ErrorClass::Environment),
+        }
+    }
+}
+
+/// Status of each of `usages` according to `lock` and `env`. A usage is
+/// locked if a project in `lock` has its IRI as an identifier.
+pub fn do_list<Env: ReadEnvironment>(
+    usages: &[InterchangeProjectUsageRaw],
+    lock: Option<&Lock>,
+    env: Option<&Env>,
+) -> Result<Vec<UsageStatus>, ListError<Env::ReadError>> {
+    let mut statuses = Vec::with_capacity(usages.len());
+    for usage in usages {
+        let InterchangeProjectUsageRaw::Resource {
+            resource,
+            version_constraint,
+            ..
+        } = usage;
+        let locked = lock.and_then(|lock| {
+            lock.projects
+                .iter()
+                .find(|p| p.identifiers.iter().any(|i| i == resource))
+        });
+        let installed = match (locked, env) {
+            // Some environments fail to list versions of missing projects
+            (Some(locked), Some(env)) => {
+                env.has(resource).map_err(ListError::Env)?
+                    && env
+                        .has_version(resource, &locked.version)
+                        .map_err(ListError::Env)?
+            }
+            _ => false,
+        };
+        statuses.push(UsageStatus {
+            iri: resource.clone(),
+            version_constraint: version_constraint.clone(),
+            locked_version: locked.map(|p| p.version.clone()),
+            installed,
+            source: locked.and_then(|p| p.sources.first()).map(SourceKind::of),
+        });
+    }
+    Ok(statuses)
+}
+
+#[cfg(test)]
+#[path = "./list_tests.rs"]
+mod tests;
@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use crate::{
+    commands::{
+        init::do_init_memory,
+        list::{SourceKind, UsageStatus, do_list},
+    },
+    env::memory::MemoryStorageEnvironment,
+    lock::{CURRENT_LOCK_VERSION, Lock, Project, Source},
+    model::InterchangeProjectUsageRaw,
+    project::memory::InMemoryProject,
+    solve::markers::Markers,
+};
+
+fn locked(iri: &str, version: &str, source: Source) -> Project {
+    Project {
+        name: iri.into(),
+        publisher: None,
+        version: version.into(),
+        exports: vec![],
+        identifiers: vec![iri.into()],
+        sources: vec![source],
+        usages: vec![],
+        variant: None,
+    }
+}
+
+fn usage(iri: &str, version_constraint: Option<&str>) -> InterchangeProjectUsageRaw {
+    InterchangeProjectUsageRaw::Resource {
+        resource: iri.into(),
+        version_constraint: version_constraint.map(Into::into),
+        when: None,
+        hash: None,
+    }
+}
+
+#[test]
+fn list_usage_status() {
+    let lock = Lock {
+        lock_version: CURRENT_LOCK_VERSION.into(),
+        markers: Markers::new(),
+        projects: vec![
+            locked(
+                "urn:kpar:installed",
+                "1.0.0",
+                Source::RemoteGit {
+                    remote_git: "https://example.com/installed.git".into(),
+                    rev: None,
+                },
+            ),
+            locked(
+                "urn:kpar:missing",
+                "2.0.0",
+                Source::LocalSrc {
+                    src_path: "missing".into(),
+                    checksum: String::new(),
+                },
+            ),
+        ],
+    };
+    let env = MemoryStorageEnvironment::<InMemoryProject>::try_from([(
+        "urn:kpar:installed".into(),
+        do_init_memory("installed", None::<&str>, "1.0.0", None).unwrap(),
+    )])
+    .unwrap();
+    let usages = [
+        usage("urn:kpar:installed", Some("^1")),
+        usage("urn:kpar:missing", None),
+        usage("urn:kpar:unlocked", Some("3")),
+    ];
+
+    let statuses = do_list(&usages, Some(&lock), Some(&env)).unwrap();
+
+    assert_eq!(
+        statuses,
+        [
+            UsageStatus {
+                iri: "urn:kpar:installed".into(),
+                version_constraint: Some("^1".into()),
+                locked_version: Some("1.0.0".into()),
+                installed: true,
+                source: Some(SourceKind::Git),
+            },
+            UsageStatus {
+                iri: "urn:kpar:missing".into(),
+                version_constraint: None,
+                locked_version: Some("2.0.0".into()),
+                installed: false,
+                source: Some(SourceKind::Path),
+            },
+            UsageStatus {
+                iri: "urn:kpar:unlocked".into(),
+                version_constraint: Some("3".into()),
+                locked_version: None,
+                installed: false,
+                source: None,
+            },
+        ]
+    );
+
+    let statuses =
+        do_list::<MemoryStorageEnvironment<InMemoryProject>>(&usages, None, None).unwrap();
+    assert!(
+        statuses
+            .iter()
+            .all(|s| s.locked_version.is_none() && !s.installed && s.source.is_none())
+    );
+}
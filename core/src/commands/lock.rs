@@ -2,6 +2,7 @@
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
 use fluent_uri::Iri;
+use indexmap::IndexMap;
 use std::{
     collections::{HashMap, HashSet, hash_map::Entry},
     fmt::{self, Debug},
@@ -14,18 +15,31 @@ use camino::Utf8Path;
 use thiserror::Error;
 
 pub const DEFAULT_LOCKFILE_NAME: &str = "sysand-lock.toml";
+/// Name of lockfiles in [`LockFormat::Json`](crate::lock::LockFormat::Json)
+pub const JSON_LOCKFILE_NAME: &str = "sysand-lock.json";
 
-#[cfg(feature = "filesystem")]
-use crate::project::{editable::EditableProject, local_src::LocalSrcProject, utils::wrapfs};
 use crate::{
     context::ProjectContext,
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    iri_normalize::iris_equivalent,
     lock::{Lock, Project, Usage, hash_str},
     model::{
-        InterchangeProjectUsage, InterchangeProjectUsageRaw, InterchangeProjectValidationError,
+        HASH_PIN_PREFIX, InterchangeProjectUsage, InterchangeProjectUsageRaw,
+        InterchangeProjectValidationError,
     },
     project::{CanonicalizationError, ProjectRead, memory::InMemoryProject, utils::FsIoError},
     resolve::ResolveRead,
-    solve::pubgrub::{SolverError, solve},
+    solve::pubgrub::{SolverError, solve_with_strategy},
+    timings::{self, Phase},
+};
+#[cfg(feature = "filesystem")]
+use crate::{
+    project::{editable::EditableProject, local_src::LocalSrcProject, utils::wrapfs},
+    resolve::{
+        memory::{AcceptAll, MemoryResolver},
+        priority::{PriorityProject, PriorityResolver},
+    },
+    workspace::Workspace,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -55,6 +69,20 @@ pub enum LockProjectError<PI: ProjectRead, PD: ProjectRead, R: ResolveRead + Deb
     LockError(#[from] LockError<PD, R>),
 }
 
+impl<PI: ProjectRead, PD: ProjectRead, R: ResolveRead + Debug + 'static> HasErrorCode
+    for LockProjectError<PI, PD, R>
+{
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            LockProjectError::InputProjectError(_) => ErrorCode::new(801, ErrorClass::Project),
+            LockProjectError::InputProjectCanonicalizationError(_) => {
+                ErrorCode::new(802, ErrorClass::Project)
+            }
+            LockProjectError::LockError(e) => e.error_code(),
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 #[error(
         "symbol name `{}` is exported more than once in lockfile:\nproject 1:\n{:#}\nproject 2:\n{:#}", .symbol, .pr1.to_toml(), .pr2.to_toml()
@@ -107,6 +135,33 @@ pub enum LockError<PD: ProjectRead, R: ResolveRead + Debug + 'static> {
     NameCollision(Box<NameCollisionError>),
     #[error(transparent)]
     SelfNameCollision(Box<SelfNameCollisionError>),
+    #[error(
+        "project `{iri}` has hash `{HASH_PIN_PREFIX}{actual}`,\n\
+        but its usage is pinned to `{HASH_PIN_PREFIX}{expected}`"
+    )]
+    HashMismatch {
+        iri: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl<PD: ProjectRead, R: ResolveRead + Debug + 'static> HasErrorCode for LockError<PD, R> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            LockError::DependencyProject(_) => ErrorCode::new(850, ErrorClass::Resolution),
+            LockError::DependencyProjectCanonicalization(_) => {
+                ErrorCode::new(851, ErrorClass::Resolution)
+            }
+            LockError::Io(_) => ErrorCode::new(852, ErrorClass::Io),
+            LockError::IncompleteProject { .. } => ErrorCode::new(853, ErrorClass::Resolution),
+            LockError::InvalidUsage { .. } => ErrorCode::new(854, ErrorClass::Resolution),
+            LockError::Solver(_) => ErrorCode::new(855, ErrorClass::Resolution),
+            LockError::NameCollision(_) => ErrorCode::new(856, ErrorClass::Resolution),
+            LockError::SelfNameCollision(_) => ErrorCode::new(857, ErrorClass::Resolution),
+            LockError::HashMismatch { .. } => ErrorCode::new(858, ErrorClass::Resolution),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -115,6 +170,69 @@ pub struct LockOutcome<PD: Debug> {
     pub dependencies: Vec<(fluent_uri::Iri<String>, PD)>,
 }
 
+#[derive(Error, Debug)]
+pub enum VariantError<E> {
+    #[error(transparent)]
+    DependencyProject(E),
+    #[error(
+        "project `{iri}` has no variant `{variant}`{}",
+        if .available.is_empty() {
+            String::new()
+        } else {
+            format!(", available variants: `{}`", .available.join("`, `"))
+        }
+    )]
+    UnknownVariant {
+        iri: String,
+        variant: String,
+        available: Vec<String>,
+    },
+}
+
+impl<E> HasErrorCode for VariantError<E> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            VariantError::DependencyProject(_) => ErrorCode::new(860, ErrorClass::Resolution),
+            VariantError::UnknownVariant { .. } => ErrorCode::new(861, ErrorClass::Resolution),
+        }
+    }
+}
+
+impl<PD: ProjectRead + Debug> LockOutcome<PD> {
+    /// Lock dependencies with the variants in `variants`, by IRI, as set in
+    /// `sysand.toml`. Each dependency must declare its selected variant
+    pub fn select_variants(
+        &mut self,
+        variants: &IndexMap<String, String>,
+    ) -> Result<(), VariantError<PD::Error>> {
+        for (iri, variant) in variants {
+            let Some((_, dependency)) = self.dependencies.iter().find(|(i, _)| i.as_str() == iri)
+            else {
+                log::warn!("variant `{variant}` is set for `{iri}`, which is not a dependency");
+                continue;
+            };
+            let declared = dependency
+                .get_meta()
+                .map_err(VariantError::DependencyProject)?
+                .map(|meta| meta.variants.into_keys().collect::<Vec<_>>())
+                .unwrap_or_default();
+            if !declared.contains(variant) {
+                return Err(VariantError::UnknownVariant {
+                    iri: iri.clone(),
+                    variant: variant.clone(),
+                    available: declared,
+                });
+            }
+            for project in &mut self.lock.projects {
+                if project.identifiers.contains(iri) {
+                    project.variant = Some(variant.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Generates a lockfile by solving for a (compatible) set of interchange projects
 /// to satisfy interchange project usages in `info`.
 ///
@@ -199,12 +317,14 @@ pub fn do_lock_projects<
             usages: info
                 .usage
                 .iter()
+                .filter(|u| u.applies(&ctx.markers))
                 .map(|u| match u {
                     InterchangeProjectUsageRaw::Resource { resource, .. } => {
                         Usage::from(resource.to_owned())
                     }
                 })
                 .collect(),
+            variant: None,
         });
 
         all_deps.extend(validated_info.usage);
@@ -236,9 +356,20 @@ pub fn do_lock_extend<
     provided_iris: &HashMap<String, Vec<InMemoryProject>>,
     ctx: &ProjectContext,
 ) -> Result<LockOutcome<PD>, LockError<PD, R>> {
+    let _timer = timings::time(Phase::Resolution);
     let inputs: Vec<_> = usages.into_iter().collect();
+    let mut pins: Vec<_> = inputs
+        .iter()
+        .filter(|u| u.applies(&ctx.markers))
+        .filter_map(|u| {
+            let InterchangeProjectUsage::Resource { resource, .. } = u;
+            Some((resource.to_string(), u.hash_pin()?.to_owned()))
+        })
+        .collect();
     let mut dependencies = vec![];
-    let solution = solve(inputs, resolver).map_err(LockError::Solver)?;
+    let solution = solve_with_strategy(inputs, resolver, ctx.markers.clone(), ctx.version_strategy)
+        .map_err(LockError::Solver)?;
+    lock.markers.clone_from(&ctx.markers);
     let mut lock_projects = HashSet::new();
     let mut lock_symbols = HashMap::new();
     for (i, p) in lock.projects.iter().enumerate() {
@@ -290,6 +421,16 @@ pub fn do_lock_extend<
             Vec::new()
         };
 
+        pins.extend(
+            info.usage
+                .iter()
+                .filter(|u| u.applies(&ctx.markers))
+                .filter_map(|u| {
+                    let InterchangeProjectUsageRaw::Resource { resource, .. } = u;
+                    Some((resource.to_owned(), u.hash_pin()?.to_owned()))
+                }),
+        );
+
         let lock_project = Project {
             name: info.name,
             publisher: info.publisher,
@@ -300,10 +441,12 @@ pub fn do_lock_extend<
             usages: info
                 .usage
                 .into_iter()
+                .filter(|u| u.applies(&ctx.markers))
                 .map(|u| match u {
                     InterchangeProjectUsageRaw::Resource { resource, .. } => Usage::from(resource),
                 })
                 .collect(),
+            variant: None,
         };
         if lock_projects.contains(iri.as_str()) {
             log::debug!(
@@ -345,10 +488,42 @@ pub fn do_lock_extend<
 
         dependencies.push((iri, project));
     }
+    verify_hash_pins(&pins, &dependencies)?;
 
     Ok(LockOutcome { lock, dependencies })
 }
 
+/// Check that the projects in `dependencies` have the canonical hashes
+/// that `pins`, `(IRI, hex digest)` pairs, require of them
+fn verify_hash_pins<PD: ProjectRead + Debug, R: ResolveRead + Debug + 'static>(
+    pins: &[(String, String)],
+    dependencies: &[(Iri<String>, PD)],
+) -> Result<(), LockError<PD, R>> {
+    for (iri, expected) in pins {
+        let Some((_, project)) = dependencies
+            .iter()
+            .find(|(dependency, _)| iris_equivalent(dependency.as_str(), iri))
+        else {
+            continue;
+        };
+        let actual = project
+            .checksum_canonical_hex()
+            .map_err(LockError::DependencyProjectCanonicalization)?
+            .ok_or_else(|| LockError::IncompleteProject {
+                project_label: iri.to_owned(),
+                field: IncompleteField::CanonicalDigest,
+            })?;
+        if &actual != expected {
+            return Err(LockError::HashMismatch {
+                iri: iri.to_owned(),
+                expected: expected.to_owned(),
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
 #[cfg(feature = "filesystem")]
 pub type EditableLocalSrcProject = EditableProject<LocalSrcProject>;
 
@@ -380,6 +555,179 @@ pub fn do_lock_local_editable<
     do_lock_projects([(identifiers, &project)], resolver, provided_iris, ctx)
 }
 
+/// Resolver used to lock a workspace: usages of workspace projects are
+/// resolved to the projects themselves, everything else by `R`
+#[cfg(feature = "filesystem")]
+pub type WorkspaceResolver<R> =
+    PriorityResolver<MemoryResolver<AcceptAll, EditableLocalSrcProject>, R>;
+
+#[cfg(feature = "filesystem")]
+pub type WorkspaceDependency<PD> = PriorityProject<EditableLocalSrcProject, PD>;
+
+/// Usage of the same project by several workspace projects with different
+/// version constraints
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageConflict {
+    pub iri: String,
+    /// `(project, version constraint)` pairs, where the project is given
+    /// by its path in the workspace
+    pub constraints: Vec<(String, Option<String>)>,
+}
+
+impl fmt::Display for UsageConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is used with different versions:", self.iri)?;
+        for (project, constraint) in &self.constraints {
+            write!(
+                f,
+                "\n    {} by `{project}`",
+                constraint.as_deref().unwrap_or("any version")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "filesystem")]
+#[derive(Error, Debug)]
+pub enum LockWorkspaceError<PD: ProjectRead, R: ResolveRead + Debug + 'static> {
+    #[error(transparent)]
+    Lock(#[from] LockProjectError<EditableLocalSrcProject, PD, R>),
+    #[error(
+        "{source}\nno versions satisfy all workspace projects:\n{}",
+        .conflicts.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n")
+    )]
+    Conflict {
+        conflicts: Vec<UsageConflict>,
+        source: Box<LockProjectError<EditableLocalSrcProject, PD, R>>,
+    },
+}
+
+#[cfg(feature = "filesystem")]
+impl<PD: ProjectRead, R: ResolveRead + Debug + 'static> HasErrorCode for LockWorkspaceError<PD, R> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            LockWorkspaceError::Lock(e) => e.error_code(),
+            LockWorkspaceError::Conflict { source, .. } => source.error_code(),
+        }
+    }
+}
+
+#[cfg(feature = "filesystem")]
+pub type LockWorkspaceResult<PD, R> = Result<
+    LockOutcome<WorkspaceDependency<PD>>,
+    LockWorkspaceError<WorkspaceDependency<PD>, WorkspaceResolver<R>>,
+>;
+
+/// Usages of the same IRI with different version constraints in `projects`,
+/// given as `(label, project)` pairs
+pub fn usage_conflicts<'a, P: ProjectRead + 'a, I: IntoIterator<Item = (String, &'a P)>>(
+    projects: I,
+) -> Vec<UsageConflict> {
+    let mut usages: Vec<UsageConflict> = vec![];
+    for (label, project) in projects {
+        let Ok(Some(info)) = project.get_info() else {
+            continue;
+        };
+        for usage in info.usage {
+            let InterchangeProjectUsageRaw::Resource {
+                resource,
+                version_constraint,
+                ..
+            } = usage;
+            let constraint = (label.clone(), version_constraint);
+            match usages.iter_mut().find(|u| u.iri == resource) {
+                Some(usage) => usage.constraints.push(constraint),
+                None => usages.push(UsageConflict {
+                    iri: resource,
+                    constraints: vec![constraint],
+                }),
+            }
+        }
+    }
+    usages.retain(|usage| {
+        usage
+            .constraints
+            .iter()
+            .any(|(_, constraint)| *constraint != usage.constraints[0].1)
+    });
+    usages
+}
+
+/// Solves for the dependencies of all projects in `workspace` together, so
+/// that every dependency is locked at a single version shared by all of
+/// them. Workspace projects are locked as editable projects at their paths
+/// relative to the workspace root, so the lockfile belongs in the workspace
+/// root. Usages of workspace projects are resolved to the projects
+/// themselves.
+///
+/// If no versions satisfy all workspace projects, the usages that differ
+/// between them are reported.
+#[cfg(feature = "filesystem")]
+pub fn do_lock_workspace<PD: ProjectRead + Debug, R: ResolveRead<ProjectStorage = PD> + Debug>(
+    workspace: &Workspace,
+    provided_iris: &HashMap<String, Vec<InMemoryProject>>,
+    resolver: R,
+    ctx: &ProjectContext,
+) -> LockWorkspaceResult<PD, R> {
+    let mut members = Vec::with_capacity(workspace.projects().len());
+    for project in workspace.projects() {
+        let editable = EditableProject::new(
+            project.path.as_str().into(),
+            LocalSrcProject {
+                nominal_path: None,
+                project_path: wrapfs::canonicalize(workspace.root_path().join(&project.path))
+                    .map_err(|e| LockProjectError::LockError(LockError::Io(e)))?,
+                expected_checksum: None,
+            },
+        );
+        members.push((project, editable));
+    }
+
+    let mut member_resolver = MemoryResolver {
+        iri_predicate: AcceptAll {},
+        projects: HashMap::new(),
+    };
+    for (project, editable) in &members {
+        for iri in &project.iris {
+            member_resolver
+                .projects
+                .entry(iri.clone())
+                .or_insert_with(Vec::new)
+                .push(editable.clone());
+        }
+    }
+
+    let inputs = members.iter().map(|(project, editable)| {
+        let identifiers = (!project.iris.is_empty()).then(|| project.iris.clone());
+        (identifiers, editable)
+    });
+    match do_lock_projects(
+        inputs,
+        PriorityResolver::new(member_resolver, resolver),
+        provided_iris,
+        ctx,
+    ) {
+        Ok(outcome) => Ok(outcome),
+        Err(source @ LockProjectError::LockError(LockError::Solver(_))) => {
+            let conflicts = usage_conflicts(
+                members
+                    .iter()
+                    .map(|(project, editable)| (project.path.clone(), editable)),
+            );
+            if conflicts.is_empty() {
+                Err(source.into())
+            } else {
+                Err(LockWorkspaceError::Conflict {
+                    conflicts,
+                    source: Box::new(source),
+                })
+            }
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
 #[cfg(test)]
 #[path = "./lock_tests.rs"]
 mod tests;
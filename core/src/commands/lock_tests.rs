@@ -4,12 +4,15 @@
 use std::assert_matches;
 use std::collections::HashMap;
 
+use fluent_uri::Iri;
+
 use crate::{
-    commands::lock::{LockError, do_lock_extend, do_lock_projects},
+    commands::lock::{LockError, UsageConflict, do_lock_extend, do_lock_projects, usage_conflicts},
     lock::{Lock, Project, Source},
-    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
-    project::memory::InMemoryProject,
-    resolve::null::NullResolver,
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw, InterchangeProjectUsageRaw},
+    project::{ProjectRead, memory::InMemoryProject},
+    resolve::{memory::MemoryResolver, null::NullResolver},
+    solve::markers::Markers,
 };
 
 #[test]
@@ -18,6 +21,7 @@ fn lock_export_conflict() {
 
     let lock = Lock {
         lock_version: String::new(),
+        markers: Markers::new(),
         projects: vec![
             Project {
                 name: "test1".into(),
@@ -27,6 +31,7 @@ fn lock_export_conflict() {
                 identifiers: vec!["test1".into()],
                 sources: vec![],
                 usages: vec![],
+                variant: None,
             },
             Project {
                 name: "test2".into(),
@@ -36,6 +41,7 @@ fn lock_export_conflict() {
                 identifiers: vec!["test2".into()],
                 sources: vec![],
                 usages: vec![],
+                variant: None,
             },
         ],
     };
@@ -71,6 +77,7 @@ fn lock_preserves_project_publisher() {
             includes_derived: None,
             includes_implied: None,
             checksum: None,
+            variants: Default::default(),
         },
     );
     project.nominal_sources = vec![Source::Editable {
@@ -88,3 +95,97 @@ fn lock_preserves_project_publisher() {
 
     assert_eq!(lock.projects[0].publisher.as_deref(), Some("Acme Labs"));
 }
+
+fn project_using(usage: &[(&str, Option<&str>)]) -> InMemoryProject {
+    InMemoryProject::from_info_meta(
+        InterchangeProjectInfoRaw {
+            name: "member".into(),
+            publisher: None,
+            version: "1.0.0".into(),
+            description: None,
+            license: None,
+            maintainer: vec![],
+            website: None,
+            topic: vec![],
+            usage: usage
+                .iter()
+                .map(|(iri, constraint)| InterchangeProjectUsageRaw::Resource {
+                    resource: iri.to_string(),
+                    version_constraint: constraint.map(str::to_string),
+                    when: None,
+                    hash: None,
+                })
+                .collect(),
+        },
+        InterchangeProjectMetadataRaw {
+            index: Default::default(),
+            created: "2026-01-01T00:00:00Z".into(),
+            metamodel: None,
+            includes_derived: None,
+            includes_implied: None,
+            checksum: None,
+            variants: Default::default(),
+        },
+    )
+}
+
+#[test]
+fn usage_conflicts_between_workspace_projects() {
+    let a = project_using(&[("urn:kpar:lib", Some("^1.0")), ("urn:kpar:shared", None)]);
+    let b = project_using(&[("urn:kpar:lib", Some("^2.0")), ("urn:kpar:shared", None)]);
+    let c = project_using(&[("urn:kpar:other", Some("1"))]);
+
+    let conflicts = usage_conflicts([("a".into(), &a), ("b".into(), &b), ("c".into(), &c)]);
+
+    assert_eq!(
+        conflicts,
+        vec![UsageConflict {
+            iri: "urn:kpar:lib".into(),
+            constraints: vec![
+                ("a".into(), Some("^1.0".into())),
+                ("b".into(), Some("^2.0".into())),
+            ],
+        }]
+    );
+    assert_eq!(
+        conflicts[0].to_string(),
+        "`urn:kpar:lib` is used with different versions:\n    ^1.0 by `a`\n    ^2.0 by `b`"
+    );
+}
+
+#[test]
+fn lock_checks_hash_pins() {
+    let mut dependency = project_using(&[]);
+    dependency.nominal_sources = vec![Source::Editable {
+        editable: "dep".into(),
+    }];
+    let hash = dependency.checksum_canonical_hex().unwrap().unwrap();
+    let lock_pinned = |pin: String| {
+        let usage = InterchangeProjectUsageRaw::Resource {
+            resource: "urn:kpar:dep".into(),
+            version_constraint: Some("1.0.0".into()),
+            when: None,
+            hash: Some(pin),
+        };
+        do_lock_extend(
+            Lock::default(),
+            [usage.validate().unwrap()],
+            MemoryResolver::from_iter([(
+                Iri::parse("urn:kpar:dep".to_owned()).unwrap(),
+                vec![dependency.clone()],
+            )]),
+            &HashMap::new(),
+            &Default::default(),
+        )
+    };
+
+    let outcome = lock_pinned(format!("sha256:{hash}")).unwrap();
+    assert_eq!(outcome.lock.projects.len(), 1);
+
+    let other = "0".repeat(64);
+    assert_matches!(
+        lock_pinned(format!("sha256:{other}")),
+        Err(LockError::HashMismatch { iri, expected, actual })
+            if iri == "urn:kpar:dep" && expected == other && actual == hash
+    );
+}
@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::time::SystemTime;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use thiserror::Error;
+
+use crate::{
+    config::ProjectManifest,
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadata},
+    project::{
+        ProjectMut, ProjectRead,
+        local_src::{LocalSrcError, LocalSrcProject},
+        utils::FsIoError,
+    },
+};
+
+#[derive(Error, Debug)]
+pub enum ManifestError {
+    #[error(
+        "`{}` was edited after the `[manifest]` it is rendered from; its `{}` differ(s).\n\
+        Move the changes to `[manifest]` in `sysand.toml`, or remove it to render it again",
+        .0,
+        .1.join("`, `")
+    )]
+    Drift(Utf8PathBuf, Vec<String>),
+    #[error(transparent)]
+    Project(#[from] LocalSrcError),
+}
+
+impl From<Box<FsIoError>> for ManifestError {
+    fn from(value: Box<FsIoError>) -> Self {
+        Self::Project(LocalSrcError::from(value))
+    }
+}
+
+impl HasErrorCode for ManifestError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            ManifestError::Drift(..) => ErrorCode::new(1180, ErrorClass::Project),
+            ManifestError::Project(_) => ErrorCode::new(1181, ErrorClass::Project),
+        }
+    }
+}
+
+/// Render `manifest`, declared in the configuration file `config_path`,
+/// into `.project.json` and `.meta.json` of `project`. Returns whether
+/// anything changed, or would change if `check` is set, in which case
+/// nothing is written.
+///
+/// If `.project.json` differs from the manifest and was modified after
+/// `config_path`, it was edited by hand, and it is left alone to not lose
+/// the edits
+pub fn do_render_manifest(
+    manifest: &ProjectManifest,
+    config_path: &Utf8Path,
+    project: &mut LocalSrcProject,
+    check: bool,
+) -> Result<bool, ManifestError> {
+    let info = manifest.to_info();
+    let current_info = project.get_info()?;
+    let current_meta = project.get_meta()?;
+
+    let mut meta = current_meta.clone().unwrap_or_else(|| {
+        InterchangeProjectMetadata {
+            index: indexmap::IndexMap::new(),
+            created: chrono::Utc::now(),
+            metamodel: None,
+            includes_derived: None,
+            includes_implied: None,
+            checksum: None,
+            variants: indexmap::IndexMap::new(),
+        }
+        .into()
+    });
+    if manifest.metamodel.is_some() {
+        meta.metamodel = manifest.metamodel.clone();
+    }
+
+    let info_changed = current_info.as_ref() != Some(&info);
+    if !info_changed && current_meta.as_ref() == Some(&meta) {
+        return Ok(false);
+    }
+    if let Some(current_info) = &current_info
+        && info_changed
+        && let (Some(info_modified), Some(config_modified)) =
+            (modified(&project.info_path())?, modified(config_path)?)
+        && info_modified > config_modified
+    {
+        return Err(ManifestError::Drift(
+            project.info_path(),
+            changed_fields(current_info, &info),
+        ));
+    }
+
+    if check {
+        return Ok(true);
+    }
+    let header = crate::style::get_style_config().header;
+    log::info!(
+        "{header}{:>12}{header:#} `.project.json` and `.meta.json` from `{config_path}`",
+        "Rendering"
+    );
+    project.put_project(&info, &meta, true)?;
+    Ok(true)
+}
+
+/// Modification time of `path`, `None` if it does not exist
+fn modified(path: &Utf8Path) -> Result<Option<SystemTime>, Box<FsIoError>> {
+    match path.metadata() {
+        Ok(metadata) => Ok(metadata.modified().ok()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(Box::new(FsIoError::Metadata(path.to_owned(), e))),
+    }
+}
+
+/// Top-level fields of `.project.json` that differ between `a` and `b`
+fn changed_fields(a: &InterchangeProjectInfoRaw, b: &InterchangeProjectInfoRaw) -> Vec<String> {
+    let to_map = |info| match serde_json::to_value(info) {
+        Ok(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    let (a, b) = (to_map(a), to_map(b));
+    let mut fields: Vec<String> = a
+        .keys()
+        .chain(b.keys().filter(|k| !a.contains_key(*k)))
+        .filter(|k| a.get(*k) != b.get(*k))
+        .cloned()
+        .collect();
+    fields.sort();
+    fields
+}
+
+#[cfg(test)]
+#[path = "./manifest_tests.rs"]
+mod tests;
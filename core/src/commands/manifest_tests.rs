@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::time::{Duration, SystemTime};
+
+use camino_tempfile::tempdir;
+
+use super::*;
+use crate::config::ManifestUsage;
+
+fn manifest() -> ProjectManifest {
+    ProjectManifest {
+        name: "lib".into(),
+        publisher: Some("acme".into()),
+        version: "1.0.0".into(),
+        usage: vec![ManifestUsage {
+            resource: "urn:kpar:dep".into(),
+            version_constraint: Some("^2".into()),
+            when: None,
+            hash: None,
+        }],
+        metamodel: Some("https://www.omg.org/spec/SysML/20250201".into()),
+        ..Default::default()
+    }
+}
+
+fn set_modified(path: &Utf8Path, time: SystemTime) {
+    std::fs::File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(time)
+        .unwrap();
+}
+
+#[test]
+fn renders_and_keeps_in_sync() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("sysand.toml");
+    std::fs::write(&config_path, "").unwrap();
+    let mut project = LocalSrcProject {
+        nominal_path: None,
+        project_path: dir.path().to_owned(),
+        expected_checksum: None,
+    };
+
+    assert!(do_render_manifest(&manifest(), &config_path, &mut project, false).unwrap());
+    let info = project.get_info().unwrap().unwrap();
+    assert_eq!(info, manifest().to_info());
+    let meta = project.get_meta().unwrap().unwrap();
+    assert_eq!(
+        meta.metamodel.as_deref(),
+        Some("https://www.omg.org/spec/SysML/20250201")
+    );
+    assert!(!do_render_manifest(&manifest(), &config_path, &mut project, false).unwrap());
+
+    // Manifest edited after `.project.json`
+    let now = SystemTime::now();
+    set_modified(&project.info_path(), now - Duration::from_secs(10));
+    let mut edited = manifest();
+    edited.version = "1.1.0".into();
+    assert!(do_render_manifest(&edited, &config_path, &mut project, true).unwrap());
+    assert_eq!(project.get_info().unwrap().unwrap().version, "1.0.0");
+    assert!(do_render_manifest(&edited, &config_path, &mut project, false).unwrap());
+    assert_eq!(project.get_info().unwrap().unwrap().version, "1.1.0");
+}
+
+#[test]
+fn detects_edited_project_json() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("sysand.toml");
+    std::fs::write(&config_path, "").unwrap();
+    let mut project = LocalSrcProject {
+        nominal_path: None,
+        project_path: dir.path().to_owned(),
+        expected_checksum: None,
+    };
+    do_render_manifest(&manifest(), &config_path, &mut project, false).unwrap();
+
+    let mut info = manifest().to_info();
+    info.description = Some("edited by hand".into());
+    info.version = "2.0.0".into();
+    project.put_info(&info, true).unwrap();
+    set_modified(&config_path, SystemTime::now() - Duration::from_secs(10));
+
+    let err = do_render_manifest(&manifest(), &config_path, &mut project, false).unwrap_err();
+    let ManifestError::Drift(_, fields) = err else {
+        panic!("expected drift, got {err:?}");
+    };
+    assert_eq!(fields, ["description", "version"]);
+    assert_eq!(project.get_info().unwrap().unwrap().version, "2.0.0");
+}
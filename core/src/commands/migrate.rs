@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use camino::{Utf8Path, Utf8PathBuf};
+use thiserror::Error;
+
+use crate::{
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    migration::{Format, MigrationError},
+    project::utils::{FsIoError, wrapfs},
+};
+
+#[derive(Error, Debug)]
+pub enum MigrateError {
+    #[error("failed to migrate `{0}`:\n{1}")]
+    Migration(Utf8PathBuf, MigrationError),
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+}
+
+impl HasErrorCode for MigrateError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            MigrateError::Migration(_, err) => err.error_code(),
+            MigrateError::Io(_) => ErrorCode::new(2010, ErrorClass::Io),
+        }
+    }
+}
+
+/// Outcome of migrating a file
+#[derive(Debug)]
+pub struct MigrateOutcome {
+    /// Version of the file before migrating
+    pub from: String,
+    /// Copy of the file before migrating, `None` on dry run
+    pub backup: Option<Utf8PathBuf>,
+}
+
+/// Migrate the file at `path` in `format` to the current version of
+/// `format`. The original file is kept next to it as
+/// `<file name>.<version>.bak`. With `dry_run`, nothing is written.
+///
+/// Returns `None` if the file is already in the current version.
+pub fn do_migrate_file<P: AsRef<Utf8Path>>(
+    path: P,
+    format: &Format,
+    dry_run: bool,
+) -> Result<Option<MigrateOutcome>, MigrateError> {
+    let path = path.as_ref();
+    let contents = wrapfs::read_to_string(path)?;
+    let Some((migrated, new_contents)) = format
+        .migrate_str(&contents)
+        .map_err(|e| MigrateError::Migration(path.to_owned(), e))?
+    else {
+        return Ok(None);
+    };
+
+    let header = crate::style::get_style_config().header;
+    let migrating = if dry_run {
+        "Would migrate"
+    } else {
+        "Migrating"
+    };
+    log::info!(
+        "{header}{migrating:>12}{header:#} `{path}` from version {} to {}",
+        migrated.from,
+        format.current
+    );
+    for migration in &migrated.applied {
+        log::info!(
+            "{:>12} {} -> {}: {}",
+            ' ',
+            migration.from,
+            migration.to,
+            migration.description
+        );
+    }
+    if dry_run {
+        return Ok(Some(MigrateOutcome {
+            from: migrated.from,
+            backup: None,
+        }));
+    }
+
+    let backup = Utf8PathBuf::from(format!("{path}.{}.bak", migrated.from));
+    wrapfs::write(&backup, &contents)?;
+    wrapfs::write(path, new_contents)?;
+    log::info!("{:>12} original kept in `{backup}`", ' ');
+
+    Ok(Some(MigrateOutcome {
+        from: migrated.from,
+        backup: Some(backup),
+    }))
+}
+
+#[cfg(test)]
+#[path = "./migrate_tests.rs"]
+mod tests;
@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::ops::Deref;
+
+use thiserror::Error;
+
+use crate::{
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    lock::Lock,
+    model::InterchangeProjectUsageRaw,
+    project::ProjectMut,
+};
+
+#[derive(Error, Debug)]
+pub enum MigrateIriError<ProjectError> {
+    #[error(transparent)]
+    Project(ProjectError),
+    #[error("could not find project information")]
+    MissingInfo,
+    #[error("cannot migrate `{0}` to itself")]
+    SameIri(Box<str>),
+}
+
+impl<ProjectError> HasErrorCode for MigrateIriError<ProjectError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            MigrateIriError::Project(_) => ErrorCode::new(1801, ErrorClass::Project),
+            MigrateIriError::MissingInfo => ErrorCode::new(1802, ErrorClass::Project),
+            MigrateIriError::SameIri(_) => ErrorCode::new(1803, ErrorClass::Usage),
+        }
+    }
+}
+
+/// Replace usages of `old` with usages of `new` in `project`, keeping their
+/// version constraints. If `project` already uses `new`, usages of `old`
+/// are removed instead. With `dry_run`, `project` is not modified.
+///
+/// Returns the number of usages replaced.
+pub fn do_migrate_iri_project<P: ProjectMut>(
+    project: &mut P,
+    old: &str,
+    new: &str,
+    dry_run: bool,
+) -> Result<usize, MigrateIriError<P::Error>> {
+    if old == new {
+        return Err(MigrateIriError::SameIri(old.into()));
+    }
+    let mut info = project
+        .get_info()
+        .map_err(MigrateIriError::Project)?
+        .ok_or(MigrateIriError::MissingInfo)?;
+
+    let uses_new = info
+        .usage
+        .iter()
+        .any(|InterchangeProjectUsageRaw::Resource { resource, .. }| resource == new);
+    let mut replaced = 0;
+    info.usage
+        .retain_mut(|InterchangeProjectUsageRaw::Resource { resource, .. }| {
+            if resource != old {
+                return true;
+            }
+            replaced += 1;
+            if uses_new {
+                log::warn!("`{new}` is already used, removing usage of `{old}`");
+                false
+            } else {
+                *resource = new.to_owned();
+                true
+            }
+        });
+
+    if replaced > 0 && !dry_run {
+        project
+            .put_info(&info, true)
+            .map_err(MigrateIriError::Project)?;
+    }
+    Ok(replaced)
+}
+
+/// Replace `old` with `new` in the identifiers and usages of the projects
+/// in `lock`. Sources are kept, so the project previously identified by
+/// `old` is still fetched from the same location until `lock` is
+/// regenerated.
+///
+/// Returns the number of identifiers and usages replaced.
+pub fn migrate_iri_lock(lock: &mut Lock, old: &str, new: &str) -> usize {
+    lock.projects
+        .iter_mut()
+        .map(|project| {
+            replace(&mut project.identifiers, old, new) + replace(&mut project.usages, old, new)
+        })
+        .sum()
+}
+
+/// Replace `old` with `new` in `items`, removing it instead if `items`
+/// already contains `new`
+fn replace<T: Deref<Target = str> + From<String>>(
+    items: &mut Vec<T>,
+    old: &str,
+    new: &str,
+) -> usize {
+    let has_new = items.iter().any(|item| &**item == new);
+    let mut replaced = 0;
+    items.retain_mut(|item| {
+        if &**item != old {
+            return true;
+        }
+        replaced += 1;
+        if !has_new {
+            *item = new.to_owned().into();
+        }
+        !has_new
+    });
+    replaced
+}
+
+#[cfg(test)]
+#[path = "./migrate_iri_tests.rs"]
+mod tests;
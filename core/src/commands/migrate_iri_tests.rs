@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::str::FromStr;
+
+use super::*;
+use crate::{
+    lock::CURRENT_LOCK_VERSION,
+    model::InterchangeProjectInfoRaw,
+    project::{ProjectRead, memory::InMemoryProject},
+};
+
+const OLD: &str = "pkg:sysand/acme/old";
+const NEW: &str = "pkg:sysand/acme/new";
+
+fn usage(resource: &str, version_constraint: Option<&str>) -> InterchangeProjectUsageRaw {
+    InterchangeProjectUsageRaw::Resource {
+        resource: resource.to_owned(),
+        version_constraint: version_constraint.map(str::to_owned),
+        when: None,
+        hash: None,
+    }
+}
+
+fn project_with_usages(usage: Vec<InterchangeProjectUsageRaw>) -> InMemoryProject {
+    InMemoryProject {
+        info: Some(InterchangeProjectInfoRaw {
+            name: "main".to_owned(),
+            publisher: None,
+            description: None,
+            version: "1.2.3".to_owned(),
+            license: None,
+            maintainer: vec![],
+            topic: vec![],
+            usage,
+            website: None,
+        }),
+        ..InMemoryProject::default()
+    }
+}
+
+#[test]
+fn usages_are_replaced() {
+    let mut project = project_with_usages(vec![
+        usage(OLD, Some("^1.0")),
+        usage("urn:kpar:other", None),
+    ]);
+
+    let replaced = do_migrate_iri_project(&mut project, OLD, NEW, false).unwrap();
+
+    assert_eq!(replaced, 1);
+    assert_eq!(
+        project.get_info().unwrap().unwrap().usage,
+        vec![usage(NEW, Some("^1.0")), usage("urn:kpar:other", None)]
+    );
+}
+
+#[test]
+fn dry_run_keeps_usages() {
+    let mut project = project_with_usages(vec![usage(OLD, None)]);
+
+    let replaced = do_migrate_iri_project(&mut project, OLD, NEW, true).unwrap();
+
+    assert_eq!(replaced, 1);
+    assert_eq!(
+        project.get_info().unwrap().unwrap().usage,
+        vec![usage(OLD, None)]
+    );
+}
+
+#[test]
+fn existing_usage_of_new_is_kept() {
+    let mut project = project_with_usages(vec![usage(OLD, None), usage(NEW, Some("^2.0"))]);
+
+    let replaced = do_migrate_iri_project(&mut project, OLD, NEW, false).unwrap();
+
+    assert_eq!(replaced, 1);
+    assert_eq!(
+        project.get_info().unwrap().unwrap().usage,
+        vec![usage(NEW, Some("^2.0"))]
+    );
+}
+
+#[test]
+fn same_iri_is_rejected() {
+    let mut project = project_with_usages(vec![usage(OLD, None)]);
+
+    let err = do_migrate_iri_project(&mut project, OLD, OLD, false).unwrap_err();
+
+    assert!(matches!(err, MigrateIriError::SameIri(iri) if &*iri == OLD));
+}
+
+#[test]
+fn lock_identifiers_and_usages_are_replaced() {
+    let mut lock = Lock::from_str(&format!(
+        r#"lock_version = "{CURRENT_LOCK_VERSION}"
+
+[[project]]
+name = "main"
+version = "0.1.0"
+usages = ["{OLD}"]
+sources = [{{ editable = "." }}]
+
+[[project]]
+name = "old"
+version = "1.0.0"
+identifiers = ["{OLD}"]
+sources = [{{ remote_git = "https://example.com/old.git" }}]
+"#
+    ))
+    .unwrap();
+
+    let replaced = migrate_iri_lock(&mut lock, OLD, NEW);
+
+    assert_eq!(replaced, 2);
+    assert_eq!(&*lock.projects[0].usages[0], NEW);
+    assert_eq!(lock.projects[1].identifiers, [NEW]);
+}
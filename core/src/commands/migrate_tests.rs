@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use super::*;
+use crate::env::local_directory::metadata::ENV_FORMAT;
+
+const UNVERSIONED_ENV: &str = r#"[[project]]
+name = "Example"
+version = "1.0.0"
+path = "lib/example"
+"#;
+
+#[test]
+fn file_is_migrated_with_backup() {
+    let dir = camino_tempfile::tempdir().unwrap();
+    let path = dir.path().join("env.toml");
+    std::fs::write(&path, UNVERSIONED_ENV).unwrap();
+
+    let outcome = do_migrate_file(&path, &ENV_FORMAT, false).unwrap().unwrap();
+
+    assert_eq!(outcome.from, "0.0");
+    let backup = outcome.backup.unwrap();
+    assert_eq!(backup, dir.path().join("env.toml.0.0.bak"));
+    assert_eq!(std::fs::read_to_string(backup).unwrap(), UNVERSIONED_ENV);
+    let migrated = std::fs::read_to_string(&path).unwrap();
+    assert!(migrated.starts_with("version = \"0.1\"\n"));
+    assert!(migrated.contains("[[project]]\nname = \"Example\""));
+
+    assert!(
+        do_migrate_file(&path, &ENV_FORMAT, false)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn dry_run_writes_nothing() {
+    let dir = camino_tempfile::tempdir().unwrap();
+    let path = dir.path().join("env.toml");
+    std::fs::write(&path, UNVERSIONED_ENV).unwrap();
+
+    let outcome = do_migrate_file(&path, &ENV_FORMAT, true).unwrap().unwrap();
+
+    assert_eq!(outcome.from, "0.0");
+    assert!(outcome.backup.is_none());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), UNVERSIONED_ENV);
+    assert!(!dir.path().join("env.toml.0.0.bak").exists());
+}
@@ -2,20 +2,46 @@
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
 pub mod add;
+#[cfg(all(feature = "filesystem", feature = "networking"))]
+pub mod audit;
 #[cfg(feature = "filesystem")]
 pub mod build;
+#[cfg(feature = "filesystem")]
+pub mod bundle;
 pub mod env;
 pub mod exclude;
+pub mod export;
+#[cfg(feature = "filesystem")]
+pub mod files;
+pub mod graph;
+#[cfg(feature = "filesystem")]
+pub mod hooks;
 pub mod include;
 #[cfg(feature = "filesystem")]
 pub mod index;
 pub mod info;
 pub mod init;
+pub mod list;
 pub mod lock;
+#[cfg(feature = "filesystem")]
+pub mod manifest;
+#[cfg(feature = "filesystem")]
+pub mod migrate;
+pub mod migrate_iri;
+pub mod mv;
+#[cfg(feature = "filesystem")]
+pub mod normalize;
+#[cfg(all(feature = "filesystem", feature = "networking"))]
+pub mod owner;
+#[cfg(feature = "filesystem")]
+pub mod policy;
 #[cfg(all(feature = "filesystem", feature = "networking"))]
 pub mod publish;
 pub mod remove;
 #[cfg(feature = "filesystem")]
 pub mod root;
+pub mod search;
 pub mod sources;
 pub mod sync;
+pub mod upgrade_check;
+pub mod verify;
@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use thiserror::Error;
+use typed_path::Utf8UnixPath;
+
+use crate::{
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    project::ProjectMut,
+};
+
+#[derive(Error, Debug)]
+pub enum MvError<ProjectError> {
+    #[error(transparent)]
+    Project(ProjectError),
+    #[error("could not find file `{0}` in project metadata")]
+    SourceNotFound(Box<str>),
+    #[error("file `{0}` is already in project metadata")]
+    TargetExists(Box<str>),
+    #[error("project is missing metadata file `.meta.json`")]
+    MissingMeta,
+}
+
+impl<ProjectError> HasErrorCode for MvError<ProjectError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            MvError::Project(_) => ErrorCode::new(3200, ErrorClass::Project),
+            MvError::SourceNotFound(_) => ErrorCode::new(3201, ErrorClass::Usage),
+            MvError::TargetExists(_) => ErrorCode::new(3202, ErrorClass::Usage),
+            MvError::MissingMeta => ErrorCode::new(3203, ErrorClass::Project),
+        }
+    }
+}
+
+/// Move the source file at `from` to `to` in `project`, and refer to it as
+/// `to` in the checksums, the index and the variants of its metadata. If the
+/// metadata cannot be written, the file is moved back.
+///
+/// Moving the file and writing the metadata are not atomic. If a previous
+/// move was interrupted in between, i.e. `from` is still in the metadata but
+/// the file is only found at `to`, the move is completed by writing the
+/// metadata
+pub fn do_mv<Pr: ProjectMut, P: AsRef<Utf8UnixPath>, Q: AsRef<Utf8UnixPath>>(
+    project: &mut Pr,
+    from: P,
+    to: Q,
+) -> Result<(), MvError<Pr::Error>> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    let mut meta = project
+        .get_meta()
+        .map_err(MvError::Project)?
+        .ok_or(MvError::MissingMeta)?;
+
+    let moving = "Moving";
+    let header = crate::style::get_style_config().header;
+    log::info!("{header}{moving:>12}{header:#} file: `{from}` to `{to}`");
+
+    if meta.source_paths(true).contains(to.as_str()) {
+        return Err(MvError::TargetExists(to.as_str().into()));
+    }
+    if !meta.rename_source(from, to) {
+        return Err(MvError::SourceNotFound(from.as_str().into()));
+    }
+
+    let moved = project.read_source(from).is_err() && project.read_source(to).is_ok();
+    if moved {
+        log::info!("file `{from}` was already moved to `{to}`, updating project metadata");
+    } else {
+        project.move_source(from, to).map_err(MvError::Project)?;
+    }
+    if let Err(e) = project.put_meta(&meta, true) {
+        if !moved && let Err(e) = project.move_source(to, from) {
+            log::warn!("failed to move `{to}` back to `{from}`: {e}");
+        }
+        return Err(MvError::Project(e));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "./mv_tests.rs"]
+mod tests;
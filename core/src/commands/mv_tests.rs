@@ -0,0 +1,86 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::io::Cursor;
+
+use typed_path::Utf8UnixPath;
+
+use crate::{
+    include::do_include,
+    init::do_init_memory,
+    model::HashAlg,
+    mv::{MvError, do_mv},
+    project::{ProjectMut, ProjectRead, memory::InMemoryProject},
+};
+
+fn project_with(files: &[(&str, &str)]) -> InMemoryProject {
+    let mut project = do_init_memory("test", None::<&str>, "1.0.0", None).unwrap();
+    for (path, contents) in files {
+        project
+            .write_source(Utf8UnixPath::new(path), &mut Cursor::new(*contents), true)
+            .unwrap();
+    }
+    do_include(
+        &mut project,
+        files.iter().map(|(path, _)| (*path).into()),
+        Some(HashAlg::Sha256),
+        true,
+        None,
+    )
+    .unwrap();
+    project
+}
+
+#[test]
+fn mv_renames_file_and_metadata() {
+    let mut project = project_with(&[("a.sysml", "package A;\n"), ("b.sysml", "package B;\n")]);
+    let mut meta = project.get_meta().unwrap().unwrap();
+    meta.variants.insert("core".into(), vec!["a.sysml".into()]);
+    project.put_meta(&meta, true).unwrap();
+    let checksum = meta.checksum.as_ref().unwrap()["a.sysml"].clone();
+
+    do_mv(&mut project, "a.sysml", "models/a.sysml").unwrap();
+
+    assert!(!project.files.contains_key(Utf8UnixPath::new("a.sysml")));
+    assert_eq!(
+        project.files[Utf8UnixPath::new("models/a.sysml")],
+        "package A;\n"
+    );
+    let meta = project.get_meta().unwrap().unwrap();
+    let checksum_paths: Vec<_> = meta.checksum.as_ref().unwrap().keys().collect();
+    assert_eq!(checksum_paths, ["models/a.sysml", "b.sysml"]);
+    assert_eq!(meta.checksum.as_ref().unwrap()["models/a.sysml"], checksum);
+    assert_eq!(meta.index["A"], "models/a.sysml");
+    assert_eq!(meta.index["B"], "b.sysml");
+    assert_eq!(meta.variants["core"], ["models/a.sysml"]);
+}
+
+#[test]
+fn mv_rejects_unknown_and_existing_files() {
+    let mut project = project_with(&[("a.sysml", "package A;\n"), ("b.sysml", "package B;\n")]);
+    let before = project.clone();
+
+    assert!(matches!(
+        do_mv(&mut project, "c.sysml", "d.sysml"),
+        Err(MvError::SourceNotFound(path)) if &*path == "c.sysml"
+    ));
+    assert!(matches!(
+        do_mv(&mut project, "a.sysml", "b.sysml"),
+        Err(MvError::TargetExists(path)) if &*path == "b.sysml"
+    ));
+    assert_eq!(project, before);
+}
+
+#[test]
+fn mv_completes_interrupted_move() {
+    let mut project = project_with(&[("a.sysml", "package A;\n")]);
+    let mut expected = project.clone();
+    do_mv(&mut expected, "a.sysml", "models/a.sysml").unwrap();
+
+    // Interrupted after moving the file, before writing the metadata
+    project.move_source("a.sysml", "models/a.sysml").unwrap();
+    assert_eq!(project.get_meta().unwrap().unwrap().index["A"], "a.sysml");
+
+    do_mv(&mut project, "a.sysml", "models/a.sysml").unwrap();
+    assert_eq!(project, expected);
+}
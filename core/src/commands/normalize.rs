@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use camino::Utf8PathBuf;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    model::{InterchangeProjectMetadataRaw, format_created},
+    project::{
+        CanonicalizationError, ProjectRead,
+        local_src::{LocalSrcError, LocalSrcProject},
+        utils::{FsIoError, ProjectSerializationError, wrapfs},
+    },
+};
+
+#[derive(Error, Debug)]
+pub enum NormalizeError {
+    #[error(transparent)]
+    Project(#[from] LocalSrcError),
+    #[error(transparent)]
+    Canonicalization(#[from] CanonicalizationError<LocalSrcError>),
+    #[error(
+        "{} file(s) are not normalized: `{}`; run `sysand normalize`",
+        .0.len(),
+        .0.iter().map(|p| p.as_str()).collect::<Vec<_>>().join("`, `")
+    )]
+    NotNormalized(Vec<Utf8PathBuf>),
+}
+
+impl From<Box<FsIoError>> for NormalizeError {
+    fn from(value: Box<FsIoError>) -> Self {
+        Self::Project(LocalSrcError::from(value))
+    }
+}
+
+impl HasErrorCode for NormalizeError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            NormalizeError::Project(_) => ErrorCode::new(2901, ErrorClass::Project),
+            NormalizeError::Canonicalization(_) => ErrorCode::new(2902, ErrorClass::Project),
+            NormalizeError::NotNormalized(_) => ErrorCode::new(2903, ErrorClass::Project),
+        }
+    }
+}
+
+/// `meta` in canonical form: `index` and `checksum` sorted by key, and
+/// `created` in the format written by sysand, if it is a valid timestamp.
+/// Field order is fixed by serialization, and the order of lists such as
+/// `usage` in `.project.json` is kept, as it may be meaningful. Checksums are expected to be canonicalized already, see
+/// [`ProjectRead::canonical_meta`]
+pub fn normalize_meta(mut meta: InterchangeProjectMetadataRaw) -> InterchangeProjectMetadataRaw {
+    meta.index.sort_keys();
+    if let Some(checksum) = &mut meta.checksum {
+        checksum.sort_keys();
+    }
+    if let Ok(created) = chrono::DateTime::parse_from_rfc3339(&meta.created) {
+        meta.created = format_created(&created.to_utc());
+    }
+    meta
+}
+
+/// Files of `project` that are not in canonical form, together with their
+/// canonical contents
+pub fn normalized_files(
+    project: &LocalSrcProject,
+) -> Result<Vec<(Utf8PathBuf, String)>, NormalizeError> {
+    let info = project.get_info()?.ok_or(LocalSrcError::MissingInfoMeta)?;
+    let meta = project
+        .canonical_meta()?
+        .ok_or(LocalSrcError::MissingMeta)?;
+
+    let mut files = vec![];
+    for (path, contents) in [
+        (project.info_path(), to_json(&info, "project info")?),
+        (
+            project.meta_path(),
+            to_json(&normalize_meta(meta), "project metadata")?,
+        ),
+    ] {
+        if wrapfs::read_to_string(&path)? != contents {
+            files.push((path, contents));
+        }
+    }
+    Ok(files)
+}
+
+/// Rewrite `.project.json` and `.meta.json` of `project` in canonical form.
+/// With `check`, nothing is written and files not in canonical form are an
+/// error. Returns the files that were rewritten
+pub fn do_normalize(
+    project: &LocalSrcProject,
+    check: bool,
+) -> Result<Vec<Utf8PathBuf>, NormalizeError> {
+    let files = normalized_files(project)?;
+    if check {
+        return if files.is_empty() {
+            Ok(vec![])
+        } else {
+            Err(NormalizeError::NotNormalized(
+                files.into_iter().map(|(path, _)| path).collect(),
+            ))
+        };
+    }
+    for (path, contents) in &files {
+        wrapfs::write(path, contents)?;
+    }
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+/// Serialization of `value` as written by `LocalSrcProject`
+fn to_json<T: Serialize>(value: &T, what: &str) -> Result<String, NormalizeError> {
+    let mut json = serde_json::to_string_pretty(value).map_err(|e| {
+        LocalSrcError::from(ProjectSerializationError::new(
+            format!("failed to serialize {what}"),
+            e,
+        ))
+    })?;
+    json.push('\n');
+    Ok(json)
+}
+
+#[cfg(test)]
+#[path = "./normalize_tests.rs"]
+mod tests;
@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::assert_matches;
+
+use camino_tempfile::tempdir;
+
+use super::{NormalizeError, do_normalize};
+use crate::project::{local_src::LocalSrcProject, utils::wrapfs};
+
+const INFO: &str = r#"{"version":"1.0.0","name":"norm"}"#;
+const META: &str = r#"{
+    "created": "2025-01-01T00:00:00.000+00:00",
+    "index": {"B": "b.sysml", "A": "a.sysml"},
+    "checksum": {
+        "b.sysml": {"value": "ABC", "algorithm": "SHA256"},
+        "a.sysml": {"value": "def", "algorithm": "SHA256"}
+    }
+}"#;
+
+#[test]
+fn normalize_and_check() {
+    let dir = tempdir().unwrap();
+    let project = LocalSrcProject {
+        nominal_path: None,
+        project_path: dir.path().to_owned(),
+        expected_checksum: None,
+    };
+    wrapfs::write(project.info_path(), INFO).unwrap();
+    wrapfs::write(project.meta_path(), META).unwrap();
+
+    let err = do_normalize(&project, true).unwrap_err();
+    assert_matches!(err, NormalizeError::NotNormalized(files) if files.len() == 2);
+    assert_eq!(wrapfs::read_to_string(project.meta_path()).unwrap(), META);
+
+    let rewritten = do_normalize(&project, false).unwrap();
+    assert_eq!(rewritten.len(), 2);
+    assert_eq!(
+        wrapfs::read_to_string(project.info_path()).unwrap(),
+        "{\n  \"name\": \"norm\",\n  \"version\": \"1.0.0\"\n}\n"
+    );
+    assert_eq!(
+        wrapfs::read_to_string(project.meta_path()).unwrap(),
+        r#"{
+  "index": {
+    "A": "a.sysml",
+    "B": "b.sysml"
+  },
+  "created": "2025-01-01T00:00:00Z",
+  "checksum": {
+    "a.sysml": {
+      "value": "def",
+      "algorithm": "SHA256"
+    },
+    "b.sysml": {
+      "value": "abc",
+      "algorithm": "SHA256"
+    }
+  }
+}
+"#
+    );
+
+    assert!(do_normalize(&project, true).unwrap().is_empty());
+    assert!(do_normalize(&project, false).unwrap().is_empty());
+}
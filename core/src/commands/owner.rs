@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use thiserror::Error;
+use url::Url;
+
+use crate::{
+    auth::{ForceBearerAuth, GlobMap, GlobMapResult},
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    index::api::{IndexApiClient, IndexApiError},
+    purl::{SysandPurlError, parse_sysand_purl},
+};
+
+#[derive(Error, Debug)]
+pub enum OwnerError {
+    #[error(
+        "`{0}` is not a `pkg:sysand/<publisher>/<name>` IRI; only projects\n\
+        published to an index have owners"
+    )]
+    NotSysandIri(Box<str>),
+    #[error("invalid Sysand project identifier `{iri}`")]
+    InvalidPurl {
+        iri: Box<str>,
+        #[source]
+        source: SysandPurlError,
+    },
+    #[error(
+        "no bearer token credentials configured for `{url}`; set\n\
+        SYSAND_CRED_<X> and SYSAND_CRED_<X>_BEARER_TOKEN environment variables"
+    )]
+    NoBearer { url: Box<str> },
+    #[error(
+        "{candidates} bearer token credentials match `{url}`;\n\
+        configure exactly one"
+    )]
+    AmbiguousBearer { url: Box<str>, candidates: usize },
+    #[error(transparent)]
+    Api(#[from] IndexApiError),
+}
+
+impl HasErrorCode for OwnerError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            OwnerError::NotSysandIri(_) => ErrorCode::new(2701, ErrorClass::Usage),
+            OwnerError::InvalidPurl { .. } => ErrorCode::new(2702, ErrorClass::Usage),
+            OwnerError::NoBearer { .. } => ErrorCode::new(2703, ErrorClass::Publish),
+            OwnerError::AmbiguousBearer { .. } => ErrorCode::new(2704, ErrorClass::Publish),
+            OwnerError::Api(e) => match e {
+                IndexApiError::Http(_) => ErrorCode::new(2710, ErrorClass::Network),
+                IndexApiError::ResponseBody(_) => ErrorCode::new(2711, ErrorClass::Network),
+                IndexApiError::MalformedResponse(..) => ErrorCode::new(2712, ErrorClass::Network),
+                IndexApiError::ServerError { .. } => ErrorCode::new(2713, ErrorClass::Network),
+                IndexApiError::Unauthorized(_) => ErrorCode::new(2714, ErrorClass::Publish),
+                IndexApiError::PermissionDenied(_) => ErrorCode::new(2715, ErrorClass::Publish),
+                IndexApiError::NotOwner(_) => ErrorCode::new(2716, ErrorClass::Publish),
+                IndexApiError::Conflict(_) => ErrorCode::new(2717, ErrorClass::Publish),
+                IndexApiError::BadRequest(_) => ErrorCode::new(2718, ErrorClass::Publish),
+                IndexApiError::NotFound(..) => ErrorCode::new(2719, ErrorClass::Publish),
+            },
+        }
+    }
+}
+
+/// Check that `iri` identifies a project that can be owned, i.e. is a
+/// `pkg:sysand/` IRI
+pub fn check_owned_iri(iri: &str) -> Result<(), OwnerError> {
+    match parse_sysand_purl(iri) {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(OwnerError::NotSysandIri(iri.into())),
+        Err(source) => Err(OwnerError::InvalidPurl {
+            iri: iri.into(),
+            source,
+        }),
+    }
+}
+
+/// Bearer token of `bearer_map` to authenticate to the API at `api_root` with
+pub fn owner_bearer(
+    bearer_map: &GlobMap<ForceBearerAuth>,
+    api_root: &Url,
+) -> Result<ForceBearerAuth, OwnerError> {
+    match bearer_map.lookup(api_root.as_str()) {
+        GlobMapResult::Found(_, token) => Ok(token.clone()),
+        GlobMapResult::Ambiguous(candidates) => Err(OwnerError::AmbiguousBearer {
+            url: api_root.as_str().into(),
+            candidates: candidates.len(),
+        }),
+        GlobMapResult::NotFound => Err(OwnerError::NoBearer {
+            url: api_root.as_str().into(),
+        }),
+    }
+}
+
+/// Owners of the project `iri` in the index of `api`, sorted
+pub fn do_owner_list(api: &IndexApiClient, iri: &str) -> Result<Vec<String>, OwnerError> {
+    check_owned_iri(iri)?;
+    let mut owners = api.list_owners(iri)?;
+    owners.sort();
+    Ok(owners)
+}
+
+/// Make `owner` an owner of the project `iri` in the index of `api`
+pub fn do_owner_add(api: &IndexApiClient, iri: &str, owner: &str) -> Result<(), OwnerError> {
+    check_owned_iri(iri)?;
+    Ok(api.add_owner(iri, owner)?)
+}
+
+/// Make `owner` no longer an owner of the project `iri` in the index of
+/// `api`. The index may refuse to remove the last owner
+pub fn do_owner_remove(api: &IndexApiClient, iri: &str, owner: &str) -> Result<(), OwnerError> {
+    check_owned_iri(iri)?;
+    Ok(api.remove_owner(iri, owner)?)
+}
@@ -0,0 +1,385 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Configurable checks a project must pass before it is published, e.g.
+//! that it has a license or a README. The checks to run are taken from the
+//! `[publish]` table of the configuration, see [`PublishPolicy::from_config`].
+
+use std::fmt;
+
+use camino::Utf8Path;
+use regex::Regex;
+use thiserror::Error;
+
+use crate::{
+    config::{MetadataCheck, MetadataField, PublishConfig},
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    model::{
+        InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw, InterchangeProjectUsageRaw,
+        KerMlChecksumAlg,
+    },
+    project::{
+        ProjectRead,
+        local_kpar::{LocalKParError, LocalKParProjectRaw},
+        utils::{FsIoError, wrapfs},
+    },
+};
+
+/// Project being checked by a [`PolicyCheck`]
+#[derive(Debug, Clone)]
+pub struct PolicySubject {
+    pub info: InterchangeProjectInfoRaw,
+    pub meta: InterchangeProjectMetadataRaw,
+    /// Paths of all files of the project, relative to its root
+    pub files: Vec<String>,
+    /// Size of the KPAR in bytes
+    pub archive_size: u64,
+}
+
+impl PolicySubject {
+    /// Read the project in the KPAR at `path`
+    pub fn from_kpar<P: AsRef<Utf8Path>>(path: P) -> Result<Self, PolicyError> {
+        let path = path.as_ref();
+        let read_err = |e| PolicyError::KparRead(path.as_str().into(), e);
+        let archive_size = wrapfs::metadata(path)?.len();
+        let kpar = LocalKParProjectRaw::new_guess_root(path).map_err(read_err)?;
+        let (info, meta) = kpar.get_project().map_err(read_err)?;
+        let info = info.ok_or_else(|| PolicyError::MissingInfo(path.as_str().into()))?;
+        let meta = meta.ok_or_else(|| PolicyError::MissingMeta(path.as_str().into()))?;
+
+        let root = kpar
+            .project_root_in_archive()
+            .map(|p| format!("{}/", p.as_str().trim_end_matches('/')))
+            .unwrap_or_default();
+        let archive = kpar.open_archive().map_err(read_err)?;
+        let mut files: Vec<String> = archive
+            .file_names()
+            .filter(|name| !name.ends_with('/'))
+            .filter_map(|name| name.strip_prefix(root.as_str()))
+            .map(str::to_owned)
+            .collect();
+        files.sort();
+
+        Ok(Self {
+            info,
+            meta,
+            files,
+            archive_size,
+        })
+    }
+}
+
+/// A single requirement on projects to be published
+pub trait PolicyCheck: fmt::Debug + Send + Sync {
+    /// Short name of the check, shown with its violations
+    fn name(&self) -> &str;
+
+    /// Reasons why `subject` violates the policy, empty if it does not
+    fn check(&self, subject: &PolicySubject) -> Vec<String>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyViolation {
+    pub check: String,
+    pub message: String,
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.check, self.message)
+    }
+}
+
+/// The project must have a license
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequireLicense;
+
+impl PolicyCheck for RequireLicense {
+    fn name(&self) -> &str {
+        "require_license"
+    }
+
+    fn check(&self, subject: &PolicySubject) -> Vec<String> {
+        match subject.info.license.as_deref() {
+            Some(license) if !license.trim().is_empty() => vec![],
+            _ => vec!["project has no license".to_owned()],
+        }
+    }
+}
+
+/// The project must contain `README.md`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequireReadme;
+
+impl PolicyCheck for RequireReadme {
+    fn name(&self) -> &str {
+        "require_readme"
+    }
+
+    fn check(&self, subject: &PolicySubject) -> Vec<String> {
+        if subject.files.iter().any(|f| f == "README.md") {
+            vec![]
+        } else {
+            vec!["project does not contain `README.md`".to_owned()]
+        }
+    }
+}
+
+/// Every source file must have a checksum other than `NONE`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequireChecksums;
+
+impl PolicyCheck for RequireChecksums {
+    fn name(&self) -> &str {
+        "require_checksums"
+    }
+
+    fn check(&self, subject: &PolicySubject) -> Vec<String> {
+        let mut paths: Vec<_> = subject.meta.source_paths(true).into_iter().collect();
+        paths.sort();
+        paths
+            .into_iter()
+            .filter(|path| {
+                !subject
+                    .meta
+                    .checksum
+                    .as_ref()
+                    .and_then(|c| c.get(path))
+                    .is_some_and(|c| {
+                        !matches!(
+                            KerMlChecksumAlg::try_from(c.algorithm.as_str()),
+                            Ok(KerMlChecksumAlg::None)
+                        )
+                    })
+            })
+            .map(|path| format!("`{path}` has no checksum"))
+            .collect()
+    }
+}
+
+/// Usages must not refer to `file:` or `git+` IRIs, which only resolve on
+/// the machine or with the access of the publisher
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ForbidLocalUsages;
+
+impl PolicyCheck for ForbidLocalUsages {
+    fn name(&self) -> &str {
+        "forbid_local_usages"
+    }
+
+    fn check(&self, subject: &PolicySubject) -> Vec<String> {
+        subject
+            .info
+            .usage
+            .iter()
+            .filter_map(|usage| {
+                let InterchangeProjectUsageRaw::Resource { resource, .. } = usage;
+                let scheme = resource.split_once(':')?.0.to_ascii_lowercase();
+                (scheme == "file" || scheme.starts_with("git+"))
+                    .then(|| format!("usage `{resource}` refers to a local path or git repository"))
+            })
+            .collect()
+    }
+}
+
+/// The KPAR must not be larger than the given number of bytes
+#[derive(Debug, Clone, Copy)]
+pub struct MaxArchiveSize(pub u64);
+
+impl PolicyCheck for MaxArchiveSize {
+    fn name(&self) -> &str {
+        "max_archive_size"
+    }
+
+    fn check(&self, subject: &PolicySubject) -> Vec<String> {
+        if subject.archive_size > self.0 {
+            vec![format!(
+                "KPAR is {} bytes, more than the maximum of {} bytes",
+                subject.archive_size, self.0
+            )]
+        } else {
+            vec![]
+        }
+    }
+}
+
+/// A field of `.project.json` must match a regular expression
+#[derive(Debug, Clone)]
+pub struct MetadataPattern {
+    field: MetadataField,
+    regex: Regex,
+    message: Option<String>,
+}
+
+impl MetadataPattern {
+    pub fn new(check: &MetadataCheck) -> Result<Self, PolicyError> {
+        // Anchored so that the whole value has to match
+        let regex = Regex::new(&format!("^(?:{})$", check.pattern))
+            .map_err(|e| PolicyError::InvalidPattern(check.pattern.as_str().into(), e))?;
+        Ok(Self {
+            field: check.field,
+            regex,
+            message: check.message.clone(),
+        })
+    }
+}
+
+fn field_name(field: MetadataField) -> &'static str {
+    match field {
+        MetadataField::Name => "name",
+        MetadataField::Publisher => "publisher",
+        MetadataField::Description => "description",
+        MetadataField::Version => "version",
+        MetadataField::License => "license",
+        MetadataField::Maintainer => "maintainer",
+        MetadataField::Website => "website",
+        MetadataField::Topic => "topic",
+    }
+}
+
+fn field_values(info: &InterchangeProjectInfoRaw, field: MetadataField) -> Vec<&str> {
+    match field {
+        MetadataField::Name => vec![info.name.as_str()],
+        MetadataField::Version => vec![info.version.as_str()],
+        MetadataField::Publisher => info.publisher.as_deref().into_iter().collect(),
+        MetadataField::Description => info.description.as_deref().into_iter().collect(),
+        MetadataField::License => info.license.as_deref().into_iter().collect(),
+        MetadataField::Website => info.website.as_deref().into_iter().collect(),
+        MetadataField::Maintainer => info.maintainer.iter().map(String::as_str).collect(),
+        MetadataField::Topic => info.topic.iter().map(String::as_str).collect(),
+    }
+}
+
+impl PolicyCheck for MetadataPattern {
+    fn name(&self) -> &str {
+        "metadata_check"
+    }
+
+    fn check(&self, subject: &PolicySubject) -> Vec<String> {
+        let name = field_name(self.field);
+        let explain = |msg: String| match &self.message {
+            Some(message) => format!("{msg} ({message})"),
+            None => msg,
+        };
+        let values = field_values(&subject.info, self.field);
+        if values.is_empty() {
+            return vec![explain(format!("`{name}` is not set"))];
+        }
+        values
+            .into_iter()
+            .filter(|value| !self.regex.is_match(value))
+            .map(|value| {
+                explain(format!(
+                    "`{name}` value `{value}` does not match `{}`",
+                    self.regex.as_str()
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Checks run on a project before it is published
+#[derive(Debug, Default)]
+pub struct PublishPolicy {
+    checks: Vec<Box<dyn PolicyCheck>>,
+}
+
+impl PublishPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Policy with the checks enabled in `config`
+    pub fn from_config(config: &PublishConfig) -> Result<Self, PolicyError> {
+        let mut policy = Self::new();
+        if config.require_license == Some(true) {
+            policy.add_check(RequireLicense);
+        }
+        if config.require_readme == Some(true) {
+            policy.add_check(RequireReadme);
+        }
+        if config.require_checksums == Some(true) {
+            policy.add_check(RequireChecksums);
+        }
+        if config.forbid_local_usages == Some(true) {
+            policy.add_check(ForbidLocalUsages);
+        }
+        if let Some(max) = config.max_archive_size {
+            policy.add_check(MaxArchiveSize(max));
+        }
+        for check in &config.metadata_checks {
+            policy.add_check(MetadataPattern::new(check)?);
+        }
+        Ok(policy)
+    }
+
+    pub fn add_check<C: PolicyCheck + 'static>(&mut self, check: C) {
+        self.checks.push(Box::new(check));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+
+    /// All violations of the policy by `subject`, in the order of the checks
+    pub fn check(&self, subject: &PolicySubject) -> Vec<PolicyViolation> {
+        self.checks
+            .iter()
+            .flat_map(|check| {
+                check
+                    .check(subject)
+                    .into_iter()
+                    .map(|message| PolicyViolation {
+                        check: check.name().to_owned(),
+                        message,
+                    })
+            })
+            .collect()
+    }
+
+    /// Fail if `subject` violates the policy
+    pub fn enforce(&self, subject: &PolicySubject) -> Result<(), PolicyError> {
+        let violations = self.check(subject);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(PolicyError::Violations(violations))
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum PolicyError {
+    #[error(
+        "project violates the publish policy:\n{}",
+        .0.iter().map(|v| format!("  {v}")).collect::<Vec<_>>().join("\n")
+    )]
+    Violations(Vec<PolicyViolation>),
+    #[error("invalid `pattern` `{0}` of `[[publish.metadata_check]]`")]
+    InvalidPattern(Box<str>, #[source] regex::Error),
+    #[error("failed to read KPAR `{0}`")]
+    KparRead(Box<str>, #[source] LocalKParError),
+    #[error("KPAR `{0}` is missing project information `.project.json`")]
+    MissingInfo(Box<str>),
+    #[error("KPAR `{0}` is missing project metadata `.meta.json`")]
+    MissingMeta(Box<str>),
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+}
+
+impl HasErrorCode for PolicyError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            PolicyError::Violations(_) => ErrorCode::new(1265, ErrorClass::Publish),
+            PolicyError::InvalidPattern(..) => ErrorCode::new(1266, ErrorClass::Usage),
+            PolicyError::KparRead(..) => ErrorCode::new(1267, ErrorClass::Publish),
+            PolicyError::MissingInfo(_) => ErrorCode::new(1268, ErrorClass::Publish),
+            PolicyError::MissingMeta(_) => ErrorCode::new(1269, ErrorClass::Publish),
+            PolicyError::Io(e) => e.error_code(),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./policy_tests.rs"]
+mod tests;
@@ -0,0 +1,177 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use indexmap::IndexMap;
+
+use crate::{
+    commands::policy::{PolicyError, PolicySubject, PublishPolicy},
+    config::{MetadataCheck, MetadataField, PublishConfig},
+    model::{
+        InterchangeProjectChecksumRaw, InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw,
+        InterchangeProjectUsageRaw,
+    },
+};
+
+fn subject() -> PolicySubject {
+    PolicySubject {
+        info: InterchangeProjectInfoRaw {
+            name: "mycorp-lib".to_string(),
+            publisher: Some("MyCorp".to_string()),
+            description: None,
+            version: "1.0.0".to_string(),
+            license: Some("MIT".to_string()),
+            maintainer: vec![],
+            website: None,
+            topic: vec![],
+            usage: vec![InterchangeProjectUsageRaw::Resource {
+                resource: "pkg:sysand/mycorp/base".to_string(),
+                version_constraint: None,
+                when: None,
+                hash: None,
+            }],
+        },
+        meta: InterchangeProjectMetadataRaw {
+            checksum: Some(IndexMap::from([(
+                "lib.sysml".to_string(),
+                InterchangeProjectChecksumRaw {
+                    value: "00".to_string(),
+                    algorithm: "SHA256".to_string(),
+                },
+            )])),
+            ..Default::default()
+        },
+        files: vec!["README.md".to_string(), "lib.sysml".to_string()],
+        archive_size: 1000,
+    }
+}
+
+fn all_builtin() -> PublishConfig {
+    PublishConfig {
+        require_license: Some(true),
+        require_readme: Some(true),
+        require_checksums: Some(true),
+        forbid_local_usages: Some(true),
+        max_archive_size: Some(1000),
+        metadata_checks: vec![],
+    }
+}
+
+#[test]
+fn empty_config_checks_nothing() {
+    let policy = PublishPolicy::from_config(&PublishConfig::default()).unwrap();
+    assert!(policy.is_empty());
+
+    let mut subject = subject();
+    subject.info.license = None;
+    subject.files.clear();
+    assert!(policy.check(&subject).is_empty());
+}
+
+#[test]
+fn builtin_checks_pass() {
+    let policy = PublishPolicy::from_config(&all_builtin()).unwrap();
+    policy.enforce(&subject()).unwrap();
+}
+
+#[test]
+fn builtin_checks_fail() {
+    let policy = PublishPolicy::from_config(&all_builtin()).unwrap();
+
+    let mut subject = subject();
+    subject.info.license = None;
+    subject.files.retain(|f| f != "README.md");
+    subject.meta.checksum.as_mut().unwrap()["lib.sysml"].algorithm = "NONE".to_string();
+    subject
+        .info
+        .usage
+        .push(InterchangeProjectUsageRaw::Resource {
+            resource: "git+https://example.com/dep.git".to_string(),
+            version_constraint: None,
+            when: None,
+            hash: None,
+        });
+    subject
+        .info
+        .usage
+        .push(InterchangeProjectUsageRaw::Resource {
+            resource: "file:///home/me/dep".to_string(),
+            version_constraint: None,
+            when: None,
+            hash: None,
+        });
+    subject.archive_size = 1001;
+
+    let checks: Vec<_> = policy
+        .check(&subject)
+        .into_iter()
+        .map(|v| v.check)
+        .collect();
+    assert_eq!(
+        checks,
+        [
+            "require_license",
+            "require_readme",
+            "require_checksums",
+            "forbid_local_usages",
+            "forbid_local_usages",
+            "max_archive_size",
+        ]
+    );
+    assert!(matches!(
+        policy.enforce(&subject),
+        Err(PolicyError::Violations(v)) if v.len() == 6
+    ));
+}
+
+#[test]
+fn metadata_checks() {
+    let config = PublishConfig {
+        metadata_checks: vec![
+            MetadataCheck {
+                field: MetadataField::Name,
+                pattern: "mycorp-.*".to_string(),
+                message: None,
+            },
+            MetadataCheck {
+                field: MetadataField::Website,
+                pattern: "https://.*".to_string(),
+                message: Some("set a website".to_string()),
+            },
+        ],
+        ..Default::default()
+    };
+    let policy = PublishPolicy::from_config(&config).unwrap();
+
+    let violations = policy.check(&subject());
+    assert_eq!(violations.len(), 1);
+    assert_eq!(
+        violations[0].message,
+        "`website` is not set (set a website)"
+    );
+
+    let mut subject = subject();
+    subject.info.name = "lib-mycorp-x".to_string();
+    subject.info.website = Some("https://example.com".to_string());
+    let violations = policy.check(&subject);
+    assert_eq!(violations.len(), 1);
+    assert_eq!(
+        violations[0].message,
+        "`name` value `lib-mycorp-x` does not match `^(?:mycorp-.*)$`"
+    );
+}
+
+#[test]
+fn invalid_pattern() {
+    let config = PublishConfig {
+        metadata_checks: vec![MetadataCheck {
+            field: MetadataField::Name,
+            pattern: "(".to_string(),
+            message: None,
+        }],
+        ..Default::default()
+    };
+    assert!(matches!(
+        PublishPolicy::from_config(&config),
+        Err(PolicyError::InvalidPattern(..))
+    ));
+}
@@ -11,19 +11,23 @@ use std::{
 use bytes::Bytes;
 use camino::Utf8Path;
 use reqwest::header;
-use serde::Deserialize;
 use serde_json::Value;
 use thiserror::Error;
 use url::Url;
 use zip::result::ZipError;
 
 use crate::{
-    auth::{ForceBearerAuth, GlobMap, GlobMapResult, HTTPAuthentication},
-    env::discovery::{HttpBaseUrlShapeError, validate_http_base_url_shape},
+    auth::{ForceBearerAuth, GlobMap, GlobMapResult},
+    env::{
+        discovery::{HttpBaseUrlShapeError, validate_http_base_url_shape},
+        index::Deprecation,
+    },
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
     include::{IncludeError, extract_symbols},
+    index::api::{ApiResponse, IndexApiClient, IndexApiError, NotOwner, error_body_to_string},
     model::{
-        InterchangeProjectUsageRaw, InterchangeProjectValidationError, KERML_METAMODEL_PREFIX,
-        KerMlChecksumAlg, SYSML_METAMODEL_PREFIX,
+        HashAlg, InterchangeProjectUsageRaw, InterchangeProjectValidationError,
+        KERML_METAMODEL_PREFIX, KerMlChecksumAlg, SYSML_METAMODEL_PREFIX,
     },
     project::{
         ProjectRead,
@@ -31,13 +35,13 @@ use crate::{
         utils::{FsIoError, wrapfs},
     },
     purl::{
-        SysandPurlError, is_valid_unnormalized_name, is_valid_unnormalized_publisher,
-        normalize_field, parse_sysand_purl,
+        PKG_SYSAND_PREFIX, SysandPurlError, is_valid_unnormalized_name,
+        is_valid_unnormalized_publisher, normalize_field, parse_sysand_purl,
     },
     symbols::Language,
     utils::{
-        RelativePathKind, RelativeUnixPathError, license_file_stems, parse_relative_unix_path,
-        sha256_lowercase_hex,
+        RelativePathKind, RelativeUnixPathError, hash_lowercase_hex, license_file_stems,
+        parse_relative_unix_path, sha256_lowercase_hex,
     },
 };
 
@@ -229,9 +233,10 @@ pub fn do_publish(
     );
 
     // Stash the URL as a string for post-request logging; the `Url` itself
-    // is moved into the closure since `with_authentication` may call it
-    // multiple times and each `post` consumes the URL.
+    // is moved into the closure since the request may be built multiple
+    // times and each `post` consumes the URL.
     let upload_url_for_log = upload_url.to_string();
+    let api = IndexApiClient::new(client, api_root, auth, runtime);
 
     let build_request = move |c: &reqwest_middleware::ClientWithMiddleware| {
         let metadata_part = reqwest::multipart::Part::text(metadata.clone())
@@ -252,14 +257,16 @@ pub fn do_publish(
         c.post(upload_url.clone()).multipart(form)
     };
 
-    let response =
-        runtime.block_on(async { auth.with_authentication(&client, &build_request).await })?;
-
-    let status = response.status().as_u16();
-    let response_url = response.url().to_string();
-    let body_bytes = runtime
-        .block_on(response.bytes())
-        .map_err(PublishError::ResponseBody)?;
+    let ApiResponse {
+        status,
+        url: response_url,
+        body: body_bytes,
+    } = api.send(build_request).map_err(|e| match e {
+        IndexApiError::Http(e) => PublishError::Http(e),
+        IndexApiError::ResponseBody(e) => PublishError::ResponseBody(e),
+        // Sending fails only to make the request or read the response
+        e => unreachable!("unexpected error sending request: {e}"),
+    })?;
     log::debug!(
         "publish response: request URL `{}`, final URL `{}`, status {}",
         upload_url_for_log,
@@ -270,6 +277,21 @@ pub fn do_publish(
     map_publish_response(status, &body_bytes, &upload_url_for_log, &response_url)
 }
 
+/// Mark the project of `prepared` deprecated in the index of `api`.
+/// Nothing is uploaded
+pub fn do_deprecate(
+    prepared: &PublishPreparation,
+    deprecation: &Deprecation,
+    api: &IndexApiClient,
+) -> Result<(), PublishError> {
+    let iri = prepared.iri();
+    api.deprecate(&iri, deprecation.replaced_by.as_deref())
+        .map_err(|source| PublishError::Deprecate {
+            iri: iri.into(),
+            source,
+        })
+}
+
 /// Which root is being validated — selects the error variant so the
 /// message names the spec concept the URL came from.
 #[derive(Debug, Clone, Copy)]
@@ -580,7 +602,7 @@ pub enum PublishError {
     },
     #[error(
         "unsupported checksum algorithm `{alg}` for file `{path}`\n\
-        only SHA256 is currently supported"
+        supported algorithms are SHA224, SHA256, SHA-384, SHA-512 and BLAKE3"
     )]
     UnsupportedFileChecksumType {
         path: Box<str>,
@@ -838,6 +860,12 @@ pub enum PublishError {
     #[error("authentication failed: {0}")]
     AuthError(String),
 
+    #[error("publish not permitted: {0}")]
+    PermissionDenied(crate::auth::PermissionDenied),
+
+    #[error("publish not permitted: {0}")]
+    NotOwner(NotOwner),
+
     #[error("conflict: package version already exists: {0}")]
     Conflict(String),
 
@@ -851,6 +879,122 @@ pub enum PublishError {
         "KPAR file is unexpectedly large ({size} bytes, limit is {limit} bytes); verify you are publishing the correct file"
     )]
     KparTooLarge { size: u64, limit: u64 },
+
+    #[error(
+        "project IRI `{configured}` (`iri` in `sysand.toml`) does not match\n\
+        `{published}`, the IRI the project is published as"
+    )]
+    IriMismatch {
+        configured: Box<str>,
+        published: Box<str>,
+    },
+
+    #[error("failed to deprecate `{iri}`: {source}")]
+    Deprecate {
+        iri: Box<str>,
+        #[source]
+        source: IndexApiError,
+    },
+}
+
+impl HasErrorCode for PublishError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            PublishError::ExecInArchive { .. } => ErrorCode::new(1201, ErrorClass::Publish),
+            PublishError::OverlappingFiles => ErrorCode::new(1202, ErrorClass::Publish),
+            PublishError::CompressedDirEntry { .. } => ErrorCode::new(1203, ErrorClass::Publish),
+            PublishError::InvalidPathInArchive(..) => ErrorCode::new(1204, ErrorClass::Publish),
+            PublishError::UnsupportedCompression { .. } => {
+                ErrorCode::new(1205, ErrorClass::Publish)
+            }
+            PublishError::Encrypted { .. } => ErrorCode::new(1206, ErrorClass::Publish),
+            PublishError::Symlink { .. } => ErrorCode::new(1207, ErrorClass::Publish),
+            PublishError::NonexistentSymbolExported { .. } => {
+                ErrorCode::new(1208, ErrorClass::Publish)
+            }
+            PublishError::IncorrectFileChecksum { .. } => ErrorCode::new(1209, ErrorClass::Publish),
+            PublishError::UnsupportedFileChecksumType { .. } => {
+                ErrorCode::new(1210, ErrorClass::Publish)
+            }
+            PublishError::UnexpectedFile { .. } => ErrorCode::new(1211, ErrorClass::Publish),
+            PublishError::MissingChecksum => ErrorCode::new(1212, ErrorClass::Publish),
+            PublishError::EmptyChecksum => ErrorCode::new(1213, ErrorClass::Publish),
+            PublishError::IndexFail { .. } => ErrorCode::new(1214, ErrorClass::Publish),
+            PublishError::MissingFile { .. } => ErrorCode::new(1215, ErrorClass::Publish),
+            PublishError::MissingChecksumForFile { .. } => {
+                ErrorCode::new(1216, ErrorClass::Publish)
+            }
+            PublishError::IncorrectFileFormat { .. } => ErrorCode::new(1217, ErrorClass::Publish),
+            PublishError::MissingLicenseFile { .. } => ErrorCode::new(1218, ErrorClass::Publish),
+            PublishError::UnsupportedMetamodel { .. } => ErrorCode::new(1219, ErrorClass::Publish),
+            PublishError::MissingMetamodel => ErrorCode::new(1220, ErrorClass::Publish),
+            PublishError::InvalidMetamodelVersion { .. } => {
+                ErrorCode::new(1221, ErrorClass::Publish)
+            }
+            PublishError::UnknownStdLib { .. } => ErrorCode::new(1222, ErrorClass::Publish),
+            PublishError::InvalidStdLibVersion { .. } => ErrorCode::new(1223, ErrorClass::Publish),
+            PublishError::StdWithVersionConstraint { .. } => {
+                ErrorCode::new(1224, ErrorClass::Publish)
+            }
+            PublishError::InvalidPurl { .. } => ErrorCode::new(1225, ErrorClass::Publish),
+            PublishError::DisallowedUsage { .. } => ErrorCode::new(1226, ErrorClass::Publish),
+            PublishError::InfoMetaValidation { .. } => ErrorCode::new(1227, ErrorClass::Publish),
+            PublishError::Io(..) => ErrorCode::new(1228, ErrorClass::Io),
+            PublishError::KparRead(..) => ErrorCode::new(1229, ErrorClass::Publish),
+            PublishError::KparReadZip(..) => ErrorCode::new(1230, ErrorClass::Publish),
+            PublishError::KparFileRead { .. } => ErrorCode::new(1231, ErrorClass::Publish),
+            PublishError::KparFileReadIo { .. } => ErrorCode::new(1232, ErrorClass::Publish),
+            PublishError::ProjectNotAtRoot { .. } => ErrorCode::new(1233, ErrorClass::Publish),
+            PublishError::MissingInfo => ErrorCode::new(1234, ErrorClass::Publish),
+            PublishError::MissingMeta => ErrorCode::new(1235, ErrorClass::Publish),
+            PublishError::MissingPublisher => ErrorCode::new(1236, ErrorClass::Publish),
+            PublishError::InvalidPublisher(..) => ErrorCode::new(1237, ErrorClass::Publish),
+            PublishError::InvalidName(..) => ErrorCode::new(1238, ErrorClass::Publish),
+            PublishError::VersionBuildMetadata { .. } => ErrorCode::new(1239, ErrorClass::Publish),
+            PublishError::MissingLicense => ErrorCode::new(1240, ErrorClass::Publish),
+            PublishError::InvalidLicense { .. } => ErrorCode::new(1241, ErrorClass::Publish),
+            PublishError::InvalidDiscoveryRoot { .. } => ErrorCode::new(1242, ErrorClass::Usage),
+            PublishError::InvalidApiRoot { .. } => ErrorCode::new(1243, ErrorClass::Usage),
+            PublishError::NoPublishBearer { .. } => ErrorCode::new(1244, ErrorClass::Publish),
+            PublishError::AmbiguousPublishBearer { .. } => {
+                ErrorCode::new(1245, ErrorClass::Publish)
+            }
+            PublishError::TrustedPublishingUnavailable => ErrorCode::new(1246, ErrorClass::Publish),
+            PublishError::MissingTrustedPublishingEnvironment { .. } => {
+                ErrorCode::new(1247, ErrorClass::Publish)
+            }
+            PublishError::MultipleTrustedPublishingProviders => {
+                ErrorCode::new(1248, ErrorClass::Publish)
+            }
+            PublishError::InvalidGithubOidcRequestUrl { .. } => {
+                ErrorCode::new(1249, ErrorClass::Publish)
+            }
+            PublishError::TrustedPublishingHttp { .. } => ErrorCode::new(1250, ErrorClass::Network),
+            PublishError::TrustedPublishingProviderHttpStatus { .. } => {
+                ErrorCode::new(1251, ErrorClass::Network)
+            }
+            PublishError::TrustedPublishingExchangeHttpStatus { .. } => {
+                ErrorCode::new(1252, ErrorClass::Network)
+            }
+            PublishError::TrustedPublishingResponseBody { .. } => {
+                ErrorCode::new(1253, ErrorClass::Network)
+            }
+            PublishError::MalformedJsonResponse { .. } => ErrorCode::new(1254, ErrorClass::Network),
+            PublishError::MissingJsonField { .. } => ErrorCode::new(1255, ErrorClass::Network),
+            PublishError::Http(..) => ErrorCode::new(1256, ErrorClass::Network),
+            PublishError::ResponseBody(..) => ErrorCode::new(1257, ErrorClass::Network),
+            PublishError::ServerError { .. } => ErrorCode::new(1258, ErrorClass::Network),
+            PublishError::AuthError(..) => ErrorCode::new(1259, ErrorClass::Publish),
+            PublishError::PermissionDenied(..) => ErrorCode::new(1260, ErrorClass::Publish),
+            PublishError::Conflict(..) => ErrorCode::new(1261, ErrorClass::Publish),
+            PublishError::BadRequest(..) => ErrorCode::new(1262, ErrorClass::Publish),
+            PublishError::NotFound(..) => ErrorCode::new(1263, ErrorClass::Publish),
+            PublishError::KparTooLarge { .. } => ErrorCode::new(1264, ErrorClass::Publish),
+            PublishError::NotOwner(..) => ErrorCode::new(1270, ErrorClass::Publish),
+            PublishError::IriMismatch { .. } => ErrorCode::new(1271, ErrorClass::Publish),
+            PublishError::Deprecate { .. } => ErrorCode::new(1272, ErrorClass::Publish),
+        }
+    }
 }
 
 // --- Preparation helpers ---
@@ -866,6 +1010,37 @@ pub struct PublishPreparation {
     metadata: String,
 }
 
+impl PublishPreparation {
+    /// `pkg:sysand` IRI that the project is published as
+    pub fn iri(&self) -> String {
+        format!(
+            "{PKG_SYSAND_PREFIX}{}/{}",
+            self.norm_publisher, self.norm_name
+        )
+    }
+
+    /// Check that the canonical `iri` configured for the project is the IRI
+    /// it is published as. IRIs other than `pkg:sysand` ones only get a
+    /// warning, as the project may be known by several IRIs
+    pub fn check_project_iri(&self, iri: &str) -> Result<(), PublishError> {
+        let published = self.iri();
+        match parse_sysand_purl(iri) {
+            Ok(None) => {
+                log::warn!(
+                    "project IRI `{iri}` is not a `pkg:sysand` IRI; the project\n\
+                    is published as `{published}`"
+                );
+                Ok(())
+            }
+            _ if iri == published => Ok(()),
+            _ => Err(PublishError::IriMismatch {
+                configured: iri.into(),
+                published: published.into(),
+            }),
+        }
+    }
+}
+
 // TODO:
 // - warn if unknown fields present in .project.json/.meta.json - this will require
 //   a large refactoring to keep track of consistently
@@ -1081,12 +1256,12 @@ pub fn prepare_publish_payload(path: &Utf8Path) -> Result<PublishPreparation, Pu
             });
         }
 
-        if file_checksum.algorithm != KerMlChecksumAlg::Sha256 {
+        let Ok(algorithm) = HashAlg::try_from(file_checksum.algorithm) else {
             return Err(PublishError::UnsupportedFileChecksumType {
                 path: src_file.as_str().into(),
                 alg: file_checksum.algorithm,
             });
-        }
+        };
 
         match kpar_files.get_mut(src_file.as_str()) {
             Some(v) => {
@@ -1105,7 +1280,7 @@ pub fn prepare_publish_payload(path: &Utf8Path) -> Result<PublishPreparation, Pu
                         path: src_file.as_str().into(),
                         source: e,
                     })?;
-                let actual_checksum = sha256_lowercase_hex(&source);
+                let actual_checksum = hash_lowercase_hex(algorithm, &source);
                 if !actual_checksum.eq_ignore_ascii_case(&file_checksum.value) {
                     return Err(PublishError::IncorrectFileChecksum {
                         path: src_file.as_str().into(),
@@ -1234,7 +1409,18 @@ fn map_publish_response(
             is_new_project: true,
         }),
         400 => Err(PublishError::BadRequest(error_body_to_string(body_bytes))),
-        401 | 403 => Err(PublishError::AuthError(error_body_to_string(body_bytes))),
+        403 => Err(
+            if let Some(not_owner) = NotOwner::from_response_body(body_bytes) {
+                PublishError::NotOwner(not_owner)
+            } else if let Some(denied) =
+                crate::auth::PermissionDenied::from_response_body(body_bytes)
+            {
+                PublishError::PermissionDenied(denied)
+            } else {
+                PublishError::AuthError(error_body_to_string(body_bytes))
+            },
+        ),
+        401 => Err(PublishError::AuthError(error_body_to_string(body_bytes))),
         404 => Err(PublishError::NotFound(error_body_to_string(body_bytes))),
         409 => Err(PublishError::Conflict(error_body_to_string(body_bytes))),
         _ => {
@@ -1252,24 +1438,6 @@ fn map_publish_response(
     }
 }
 
-#[derive(Deserialize)]
-struct ErrorResponse {
-    error: String,
-}
-
-fn error_body_to_string(body_bytes: &[u8]) -> String {
-    let text = String::from_utf8_lossy(body_bytes);
-    let trimmed = text.trim();
-
-    if trimmed.is_empty() {
-        return "no error details provided".to_string();
-    }
-
-    serde_json::from_str::<ErrorResponse>(trimmed)
-        .map(|error| error.error)
-        .unwrap_or_else(|_| trimmed.to_string())
-}
-
 const KERML_STD_LIB_SUFFIXES: [&str; 3] = [
     "/Semantic-Library.kpar",
     "/Data-Type-Library.kpar",
@@ -1329,6 +1497,7 @@ fn check_std_libs(
         InterchangeProjectUsageRaw::Resource {
             resource,
             version_constraint,
+            ..
         } => {
             if let Some(stripped) = resource.strip_prefix(prefix) {
                 for s in lib_names {
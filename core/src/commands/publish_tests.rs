@@ -502,6 +502,8 @@ fn usage(resource: &str) -> InterchangeProjectUsageRaw {
     InterchangeProjectUsageRaw::Resource {
         resource: resource.to_string(),
         version_constraint: None,
+        when: None,
+        hash: None,
     }
 }
 
@@ -509,6 +511,8 @@ fn usage_with_vc(resource: &str, vc: &str) -> InterchangeProjectUsageRaw {
     InterchangeProjectUsageRaw::Resource {
         resource: resource.to_string(),
         version_constraint: Some(vc.to_string()),
+        when: None,
+        hash: None,
     }
 }
 
@@ -593,6 +597,51 @@ fn map_publish_response_400_maps_to_bad_request() {
     assert_matches!(err, PublishError::BadRequest(_));
 }
 
+#[test]
+fn map_publish_response_403_with_permission_maps_to_permission_denied() {
+    let err = map_publish_response(
+        403,
+        br#"{"error":"token is read-only","permission":"publish","scope":"urn:corp:*"}"#,
+        "http://example.org/v1/upload",
+        "http://example.org/v1/upload",
+    )
+    .unwrap_err();
+    assert_matches!(err, PublishError::PermissionDenied(_));
+    assert_eq!(
+        err.to_string(),
+        "publish not permitted: your credentials lack `publish` permission on `urn:corp:*`: token is read-only"
+    );
+}
+
+#[test]
+fn map_publish_response_403_with_owners_maps_to_not_owner() {
+    let err = map_publish_response(
+        403,
+        br#"{"error":"not an owner","iri":"pkg:sysand/acme/lib","owners":["alice","bob"]}"#,
+        "http://example.org/v1/upload",
+        "http://example.org/v1/upload",
+    )
+    .unwrap_err();
+    assert_matches!(err, PublishError::NotOwner(_));
+    assert_eq!(
+        err.to_string(),
+        "publish not permitted: your credentials do not own `pkg:sysand/acme/lib`; \
+         ask one of its owners (`alice`, `bob`) to add you with `sysand owner add`"
+    );
+}
+
+#[test]
+fn map_publish_response_403_without_permission_maps_to_auth_error() {
+    let err = map_publish_response(
+        403,
+        br#"{"error":"forbidden"}"#,
+        "http://example.org/v1/upload",
+        "http://example.org/v1/upload",
+    )
+    .unwrap_err();
+    assert_matches!(err, PublishError::AuthError(_));
+}
+
 #[test]
 fn map_publish_response_200_is_ok_not_new_project() {
     let resp = map_publish_response(
@@ -1009,7 +1058,7 @@ mod prepare_publish {
 
     #[test]
     fn unsupported_file_checksum_type() {
-        // SHA1 is a valid algorithm but not SHA256.
+        // SHA1 is a valid algorithm, but cannot be verified.
         let sha1_val = "a".repeat(40); // 40 hex chars = valid SHA1 length
         let meta = format!(
             r#"{{"index":{{"Sym":"f.sysml"}},"created":"2025-01-01T00:00:00Z","metamodel":"https://www.omg.org/spec/SysML/20250201","checksum":{{"f.sysml":{{"value":"{sha1_val}","algorithm":"SHA1"}}}}}}"#
@@ -1023,6 +1072,25 @@ mod prepare_publish {
         assert_matches!(err, PublishError::UnsupportedFileChecksumType { .. });
     }
 
+    #[test]
+    fn file_checksum_verified_with_declared_algorithm() {
+        let sha512_val = "a".repeat(128);
+        let meta = format!(
+            r#"{{"index":{{"Sym":"f.sysml"}},"created":"2025-01-01T00:00:00Z","metamodel":"https://www.omg.org/spec/SysML/20250201","checksum":{{"f.sysml":{{"value":"{sha512_val}","algorithm":"SHA-512"}}}}}}"#
+        );
+        let (_tmp, path) = write_zip(&[
+            (".project.json", base_project().as_slice(), deflate()),
+            (".meta.json", meta.as_bytes(), deflate()),
+            ("LICENSES/MIT.txt", b"MIT License", deflate()),
+            ("f.sysml", b"package Sym;", deflate()),
+        ]);
+        let err = prepare_publish_payload(&path).expect_err("expected Err");
+        assert_matches!(
+            err,
+            PublishError::IncorrectFileChecksum { actual, .. } if actual.len() == 128
+        );
+    }
+
     #[test]
     fn missing_file() {
         // checksum mentions test.sysml but the archive does not contain it.
@@ -1242,4 +1310,29 @@ mod prepare_publish {
         ]);
         prepare_publish_payload(&path).expect("fully valid kpar should succeed");
     }
+
+    #[test]
+    fn project_iri_must_match_published_iri() {
+        let content = b"package Test;";
+        let (proj, meta) = valid_entries(content);
+        let (_tmp, path) = write_zip(&[
+            (".project.json", proj.as_slice(), deflate()),
+            (".meta.json", meta.as_slice(), deflate()),
+            ("LICENSES/MIT.txt", b"MIT License", deflate()),
+            ("test.sysml", content, deflate()),
+        ]);
+        let prepared = prepare_publish_payload(&path).unwrap();
+        assert_eq!(prepared.iri(), "pkg:sysand/test-pub/test-pkg");
+
+        prepared
+            .check_project_iri("pkg:sysand/test-pub/test-pkg")
+            .unwrap();
+        prepared
+            .check_project_iri("https://example.com/test-pkg")
+            .unwrap();
+        let err = prepared
+            .check_project_iri("pkg:sysand/other/test-pkg")
+            .unwrap_err();
+        assert_matches!(err, PublishError::IriMismatch { .. });
+    }
 }
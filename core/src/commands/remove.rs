@@ -5,6 +5,8 @@ use thiserror::Error;
 
 use crate::{
     add::expand_sysand_purl_shorthand,
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    iri_normalize::iris_equivalent,
     model::{InterchangeProjectUsageRaw, InterchangeProjectValidationError},
     project::ProjectMut,
 };
@@ -21,6 +23,17 @@ pub enum RemoveError<ProjectError> {
     MissingInfo(Box<str>),
 }
 
+impl<ProjectError> HasErrorCode for RemoveError<ProjectError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            RemoveError::Project(_) => ErrorCode::new(301, ErrorClass::Project),
+            RemoveError::Validation(_) => ErrorCode::new(302, ErrorClass::Project),
+            RemoveError::UsageNotFound(_) => ErrorCode::new(303, ErrorClass::Project),
+            RemoveError::MissingInfo(_) => ErrorCode::new(304, ErrorClass::Project),
+        }
+    }
+}
+
 /// Like `do_remove`, but try to guess how `resource` should be interpreted.
 /// Currently it can be either an IRI or `publisher/name` PURL shorthand
 pub fn do_remove_guess<P: ProjectMut>(
@@ -51,7 +64,15 @@ pub fn do_remove<P: ProjectMut>(
     log::info!("{header}{removing:>12}{header:#} `{}` from usages", iri);
 
     if let Some(mut info) = project.get_info().map_err(RemoveError::Project)? {
-        let popped = info.pop_usage(&iri);
+        let popped: Vec<_> = info
+            .usage
+            .extract_if(
+                ..,
+                |InterchangeProjectUsageRaw::Resource { resource, .. }| {
+                    iris_equivalent(resource, &iri)
+                },
+            )
+            .collect();
 
         if popped.is_empty() {
             Err(RemoveError::UsageNotFound(iri.into_boxed_str()))
@@ -21,6 +21,8 @@ fn project_with_usage(resource: &str) -> InMemoryProject {
             usage: vec![InterchangeProjectUsageRaw::Resource {
                 resource: resource.to_owned(),
                 version_constraint: None,
+                when: None,
+                hash: None,
             }],
             website: None,
         }),
@@ -43,7 +45,9 @@ fn remove_accepts_normalized_sysand_shorthand() {
         removed[0],
         InterchangeProjectUsageRaw::Resource {
             resource: "pkg:sysand/acme-labs/my.project".to_string(),
-            version_constraint: None
+            version_constraint: None,
+            when: None,
+            hash: None,
         }
     );
     assert!(project.info.unwrap().usage.is_empty());
@@ -64,7 +68,9 @@ fn remove_keeps_iri_resource() {
         removed[0],
         InterchangeProjectUsageRaw::Resource {
             resource: "https://example.com/acme-labs/my.project".to_string(),
-            version_constraint: None
+            version_constraint: None,
+            when: None,
+            hash: None,
         }
     );
     assert!(project.info.unwrap().usage.is_empty());
@@ -81,3 +87,13 @@ fn remove_rejects_non_normalized_sysand_shorthand() {
     assert!(err.contains("`pkg:sysand/acme-labs/my.project`"), "{err}");
     assert_eq!(project.info.unwrap().usage.len(), 1);
 }
+
+#[test]
+fn remove_equivalent_iri() {
+    let mut project = project_with_usage("http://example.com/lib");
+
+    let removed = do_remove_guess(&mut project, "https://EXAMPLE.com/lib/".to_owned()).unwrap();
+
+    assert_eq!(removed.len(), 1);
+    assert!(project.info.unwrap().usage.is_empty());
+}
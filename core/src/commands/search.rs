@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use thiserror::Error;
+
+use crate::{
+    env::{ReadEnvironment, utils::ErrorBound},
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+};
+
+#[derive(Error, Debug)]
+pub enum SearchError<EnvError: ErrorBound> {
+    #[error("failed to list projects: {0}")]
+    Env(EnvError),
+}
+
+impl<EnvError: ErrorBound> HasErrorCode for SearchError<EnvError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            SearchError::Env(_) => ErrorCode::new(250, ErrorClass::Resolution),
+        }
+    }
+}
+
+/// How well `iri` matches `query`, lower is better, or `None` if it does
+/// not match. The query is compared case-insensitively to the name, which
+/// is the last segment of the IRI, and then to the whole IRI:
+///
+/// 0. name equals the query
+/// 1. name starts with the query
+/// 2. name contains the query
+/// 3. IRI contains the query
+/// 4. query characters appear in the name in order
+pub fn match_score(query: &str, iri: &str) -> Option<u8> {
+    let query = query.to_lowercase();
+    let iri = iri.to_lowercase();
+    let name = iri
+        .trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or_default();
+
+    if query.is_empty() {
+        None
+    } else if name == query {
+        Some(0)
+    } else if name.starts_with(&query) {
+        Some(1)
+    } else if name.contains(&query) {
+        Some(2)
+    } else if iri.contains(&query) {
+        Some(3)
+    } else {
+        let mut name_chars = name.chars();
+        query
+            .chars()
+            .all(|q| name_chars.any(|n| n == q))
+            .then_some(4)
+    }
+}
+
+/// IRIs of the projects in `envs` matching `query`, best matches first.
+/// See [`match_score`] for how matches are ranked
+pub fn do_search<Env: ReadEnvironment>(
+    query: &str,
+    envs: &[Env],
+) -> Result<Vec<String>, SearchError<Env::ReadError>> {
+    let mut matches = vec![];
+    for env in envs {
+        for iri in env.uris().map_err(SearchError::Env)? {
+            let iri = iri.map_err(SearchError::Env)?;
+            if let Some(score) = match_score(query, &iri) {
+                matches.push((score, iri));
+            }
+        }
+    }
+    matches.sort();
+    matches.dedup_by(|(_, a), (_, b)| a == b);
+    Ok(matches.into_iter().map(|(_, iri)| iri).collect())
+}
+
+#[cfg(test)]
+#[path = "./search_tests.rs"]
+mod tests;
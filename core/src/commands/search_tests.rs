@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use crate::{
+    commands::{
+        init::do_init_memory,
+        search::{do_search, match_score},
+    },
+    env::memory::MemoryStorageEnvironment,
+};
+
+#[test]
+fn match_score_ranks_names() {
+    assert_eq!(match_score("vehicle", "pkg:sysand/acme/vehicle"), Some(0));
+    assert_eq!(match_score("Vehicle", "urn:kpar:vehicle"), Some(0));
+    assert_eq!(
+        match_score("vehicle", "pkg:sysand/acme/vehicle-lib"),
+        Some(1)
+    );
+    assert_eq!(
+        match_score("vehicle", "pkg:sysand/acme/my-vehicle"),
+        Some(2)
+    );
+    assert_eq!(match_score("acme", "pkg:sysand/acme/vehicle"), Some(3));
+    assert_eq!(match_score("vhcl", "pkg:sysand/acme/vehicle"), Some(4));
+    assert_eq!(match_score("lorry", "pkg:sysand/acme/vehicle"), None);
+    assert_eq!(match_score("", "pkg:sysand/acme/vehicle"), None);
+}
+
+#[test]
+fn search_multiple_envs() {
+    let project = do_init_memory("project", None::<&str>, "1.0.0", None).unwrap();
+    let env = |iris: &[&str]| {
+        MemoryStorageEnvironment::try_from_iter(
+            iris.iter().map(|iri| (iri.to_string(), project.clone())),
+        )
+        .unwrap()
+    };
+    let envs = [
+        env(&["pkg:sysand/acme/my-vehicle", "pkg:sysand/acme/engine"]),
+        env(&["pkg:sysand/acme/vehicle", "pkg:sysand/acme/my-vehicle"]),
+    ];
+
+    assert_eq!(
+        do_search("vehicle", &envs).unwrap(),
+        ["pkg:sysand/acme/vehicle", "pkg:sysand/acme/my-vehicle"]
+    );
+    assert!(do_search("lorry", &envs).unwrap().is_empty());
+}
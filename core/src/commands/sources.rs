@@ -12,6 +12,7 @@ use typed_path::Utf8UnixPathBuf;
 use crate::project::local_src::{LocalSrcError, LocalSrcProject, PathError};
 use crate::{
     env::ReadEnvironment,
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
     model::{InterchangeProjectUsage, InterchangeProjectValidationError},
     project::{ProjectRead, memory::InMemoryProject},
     resolve::{
@@ -34,6 +35,15 @@ pub enum SourcesError<ProjectError> {
     },
 }
 
+impl<ProjectError> HasErrorCode for SourcesError<ProjectError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            SourcesError::Project(_) => ErrorCode::new(701, ErrorClass::Project),
+            SourcesError::Validation { .. } => ErrorCode::new(702, ErrorClass::Project),
+        }
+    }
+}
+
 /// Enumerates source files in a project (as relative Unix-paths under the project root).
 /// Combine with `find_project_dependencies` or `enumerate_projects_lock` to get source files
 /// of project usages (dependencies).
@@ -70,6 +80,17 @@ pub enum LocalSourcesError {
     Path(#[from] PathError),
 }
 
+#[cfg(feature = "filesystem")]
+impl HasErrorCode for LocalSourcesError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            LocalSourcesError::Project(_) => ErrorCode::new(750, ErrorClass::Project),
+            LocalSourcesError::Validation { .. } => ErrorCode::new(751, ErrorClass::Project),
+            LocalSourcesError::Path(_) => ErrorCode::new(752, ErrorClass::Project),
+        }
+    }
+}
+
 #[cfg(feature = "filesystem")]
 impl From<SourcesError<LocalSrcError>> for LocalSourcesError {
     fn from(value: SourcesError<LocalSrcError>) -> Self {
@@ -1,17 +1,28 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
-use std::{collections::HashMap, num::NonZeroU64};
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroU64,
+};
 
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use typed_path::Utf8UnixPathBuf;
 
 use crate::{
-    commands::env::do_env_install_project,
-    env::{ProjectChecksumResult, ReadEnvironment, WriteEnvironment, utils::ErrorBound},
-    iri_normalize::canonicalize_iri_tolerant,
-    lock::{Lock, Source},
-    project::{ProjectChecksum, ProjectRead, memory::InMemoryProject},
+    commands::env::{do_env_install_project, do_env_uninstall},
+    env::{
+        OverwritePolicy, ProjectChecksumResult, ReadEnvironment, WriteEnvironment,
+        utils::ErrorBound,
+    },
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    iri_normalize::{canonicalize_iri_tolerant, iris_equivalent},
+    lock::{Lock, Project, Source},
+    model::{HASH_PIN_PREFIX, InterchangeProjectUsageRaw},
+    progress::{self, ProgressPhase},
+    project::{ProjectChecksum, ProjectRead, memory::InMemoryProject, variant::VariantProject},
     utils::format_err,
 };
 
@@ -73,6 +84,8 @@ pub enum SyncError<UrlParseError: ErrorBound, GitError: ErrorBound> {
     UnsupportedSources(String),
     #[error("failed to install project `{uri}`:\n{cause}")]
     InstallFail { uri: Box<str>, cause: String },
+    #[error("failed to uninstall project `{uri}`:\n{cause}")]
+    UninstallFail { uri: Box<str>, cause: String },
     #[error(
         "tried to install a non-provided version {version} of `{iri}`, which is\n\
         an IRI marked as being provided by your tooling; provided versions are:\n\
@@ -86,8 +99,229 @@ pub enum SyncError<UrlParseError: ErrorBound, GitError: ErrorBound> {
     // TODO: preserve error type
     #[error("project read error: {0}")]
     ProjectRead(String),
+    #[error("no project with IRI `{0}` in lockfile")]
+    UnknownFilterIri(String),
+    #[error("project `{iri}` has no variant `{variant}`")]
+    UnknownVariant { iri: String, variant: String },
+    #[error(
+        "installed project `{iri}` has hash `{HASH_PIN_PREFIX}{actual}`,\n\
+        but its usage is pinned to `{HASH_PIN_PREFIX}{expected}`"
+    )]
+    HashMismatch {
+        iri: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl<UrlParseError: ErrorBound, GitError: ErrorBound> HasErrorCode
+    for SyncError<UrlParseError, GitError>
+{
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            SyncError::BadChecksum { .. } => ErrorCode::new(901, ErrorClass::Environment),
+            SyncError::BadProject(_) => ErrorCode::new(902, ErrorClass::Environment),
+            SyncError::MissingSource(_) => ErrorCode::new(903, ErrorClass::Project),
+            SyncError::MissingIriSrcPath(_) => ErrorCode::new(904, ErrorClass::Project),
+            SyncError::MissingIriRemoteSrc(_) => ErrorCode::new(905, ErrorClass::Project),
+            SyncError::MissingIriLocalKparPath(_) => ErrorCode::new(906, ErrorClass::Project),
+            SyncError::MissingIriRemoteKparPath(_) => ErrorCode::new(907, ErrorClass::Project),
+            SyncError::MissingIriIndexKparUrl(_) => ErrorCode::new(908, ErrorClass::Project),
+            SyncError::MissingIriRemoteGitUrl(_) => ErrorCode::new(909, ErrorClass::Project),
+            SyncError::MissingSrcPathStorage(_) => ErrorCode::new(910, ErrorClass::Environment),
+            SyncError::MissingRemoteSrcStorage(_) => ErrorCode::new(911, ErrorClass::Environment),
+            SyncError::MissingLocalKparStorage(_) => ErrorCode::new(912, ErrorClass::Environment),
+            SyncError::MissingRemoteKparStorage(_) => ErrorCode::new(913, ErrorClass::Environment),
+            SyncError::MissingIndexKparStorage(_) => ErrorCode::new(914, ErrorClass::Environment),
+            SyncError::MissingRemoteGitStorage(_) => ErrorCode::new(915, ErrorClass::Environment),
+            SyncError::GitDownload(..) => ErrorCode::new(916, ErrorClass::Network),
+            SyncError::InvalidRemoteSource(..) => ErrorCode::new(917, ErrorClass::Project),
+            SyncError::UnsupportedSources(_) => ErrorCode::new(918, ErrorClass::Environment),
+            SyncError::InstallFail { .. } => ErrorCode::new(919, ErrorClass::Environment),
+            SyncError::InvalidProvidedVersion { .. } => ErrorCode::new(920, ErrorClass::Resolution),
+            SyncError::ProjectRead(_) => ErrorCode::new(921, ErrorClass::Environment),
+            SyncError::UninstallFail { .. } => ErrorCode::new(922, ErrorClass::Environment),
+            SyncError::UnknownFilterIri(_) => ErrorCode::new(923, ErrorClass::Usage),
+            SyncError::UnknownVariant { .. } => ErrorCode::new(924, ErrorClass::Resolution),
+            SyncError::HashMismatch { .. } => ErrorCode::new(925, ErrorClass::Environment),
+        }
+    }
+}
+
+/// Action that brings an environment in line with a lockfile
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum SyncAction {
+    /// Install `project`, which is not in the environment
+    Install { project: Project },
+    /// Install `project` over its current installation, then remove
+    /// versions `replaces` of it, which are not in the lockfile
+    Update {
+        project: Project,
+        #[serde(skip_serializing_if = "Vec::is_empty", default)]
+        replaces: Vec<String>,
+    },
+    /// Remove version `version` of `iri`, which is in the lockfile only
+    /// with other versions
+    Remove { iri: String, version: String },
+}
+
+/// Actions needed to sync an environment to a lockfile, computed by
+/// [`do_sync_plan`] and carried out by [`do_sync_apply`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SyncPlan {
+    /// [`Lock::canonical_hash`] of the lock the plan was computed from
+    pub lock_digest: String,
+    pub actions: Vec<SyncAction>,
+    /// Install only `.project.json` and `.meta.json` of the projects,
+    /// if the environment supports it. Their remaining files are fetched
+    /// from their sources when first read
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub metadata_only: bool,
+}
+
+impl SyncPlan {
+    /// Whether the plan was computed from `lock`
+    pub fn is_for(&self, lock: &Lock) -> bool {
+        self.lock_digest == lock.canonical_hash()
+    }
+
+    /// Drop installing the projects that `fallback` already has, e.g. a
+    /// read-only system environment, so that they are used from there.
+    /// Versions they would have replaced are removed instead
+    pub fn use_installed<E: ReadEnvironment, U: ErrorBound, G: ErrorBound>(
+        &mut self,
+        fallback: &E,
+    ) -> Result<(), SyncError<U, G>> {
+        let mut actions = Vec::with_capacity(self.actions.len());
+        for action in self.actions.drain(..) {
+            let (project, replaces) = match &action {
+                SyncAction::Install { project } => (project, &vec![]),
+                SyncAction::Update { project, replaces } => (project, replaces),
+                SyncAction::Remove { .. } => {
+                    actions.push(action);
+                    continue;
+                }
+            };
+            if !is_installed(project, fallback)? {
+                actions.push(action);
+                continue;
+            }
+            let iri = project.identifiers.first().cloned().unwrap_or_default();
+            log::debug!(
+                "`{iri}` {} is used from a fallback environment",
+                project.version
+            );
+            actions.extend(replaces.iter().map(|version| SyncAction::Remove {
+                iri: iri.clone(),
+                version: version.clone(),
+            }));
+        }
+        self.actions = actions;
+        Ok(())
+    }
 }
 
+/// Restricts syncing to a subset of the projects of a lockfile
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SyncFilter {
+    /// IRIs of projects to sync together with their dependencies. All
+    /// projects are synced if empty
+    pub only: Vec<String>,
+    /// IRIs of projects not to sync, together with the dependencies
+    /// used only by them
+    pub skip: Vec<String>,
+    /// Sync the projects of `only` without their dependencies
+    pub no_deps: bool,
+}
+
+impl SyncFilter {
+    pub fn is_empty(&self) -> bool {
+        self.only.is_empty() && self.skip.is_empty()
+    }
+
+    /// Indices of the projects of `lock` to sync. Without `only`, these
+    /// are found from the projects not used by any other project
+    pub fn selected<U: ErrorBound, G: ErrorBound>(
+        &self,
+        lock: &Lock,
+    ) -> Result<HashSet<usize>, SyncError<U, G>> {
+        let has_iri = |p: &Project, iris: &[String]| p.identifiers.iter().any(|i| iris.contains(i));
+        for iri in self.only.iter().chain(&self.skip) {
+            if !lock.projects.iter().any(|p| p.identifiers.contains(iri)) {
+                return Err(SyncError::UnknownFilterIri(iri.clone()));
+            }
+        }
+
+        let mut stack: Vec<usize> = if self.only.is_empty() {
+            let used = |p: &Project| {
+                lock.projects.iter().any(|q| {
+                    q.usages
+                        .iter()
+                        .any(|u| p.identifiers.iter().any(|i| i == u.inner()))
+                })
+            };
+            let top: Vec<usize> = (0..lock.projects.len())
+                .filter(|&i| !used(&lock.projects[i]))
+                .collect();
+            // Every project is used by another one only if all are in cycles
+            if top.is_empty() {
+                (0..lock.projects.len()).collect()
+            } else {
+                top
+            }
+        } else {
+            (0..lock.projects.len())
+                .filter(|&i| has_iri(&lock.projects[i], &self.only))
+                .collect()
+        };
+
+        let mut selected = HashSet::new();
+        while let Some(i) = stack.pop() {
+            let project = &lock.projects[i];
+            if has_iri(project, &self.skip) || !selected.insert(i) || self.no_deps {
+                continue;
+            }
+            for usage in &project.usages {
+                stack.extend(
+                    (0..lock.projects.len())
+                        .filter(|&j| lock.projects[j].identifiers.contains(usage.inner())),
+                );
+            }
+        }
+        Ok(selected)
+    }
+
+    /// Remove the actions of `plan` that concern projects of `lock` not
+    /// selected by the filter. `plan` must have been computed from `lock`
+    pub fn apply<U: ErrorBound, G: ErrorBound>(
+        &self,
+        plan: &mut SyncPlan,
+        lock: &Lock,
+    ) -> Result<(), SyncError<U, G>> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let selected = self.selected(lock)?;
+        let is_selected = |iri: &str, version: Option<&str>| {
+            selected.iter().any(|&i| {
+                let p = &lock.projects[i];
+                version.is_none_or(|v| v == p.version) && p.identifiers.iter().any(|x| x == iri)
+            })
+        };
+        plan.actions.retain(|action| match action {
+            SyncAction::Install { project } | SyncAction::Update { project, .. } => project
+                .identifiers
+                .iter()
+                .any(|iri| is_selected(iri, Some(&project.version))),
+            SyncAction::Remove { iri, .. } => is_selected(iri, None),
+        });
+        Ok(())
+    }
+}
+
+/// Sync `env` to `lockfile`. Equivalent to applying the plan computed by
+/// [`do_sync_plan`] with [`do_sync_apply`]
 // TODO: take `lock` by value
 // TODO: Use AnyProject::try_from_source to avoid having so many arguments
 #[allow(clippy::too_many_arguments)]
@@ -132,20 +366,59 @@ where
     CreateIndexKParStorage:
         Fn(String, NonZeroU64, String) -> Result<IndexKParStorage, UrlParseError>,
     IndexKParStorage: ProjectRead,
-    CreateRemoteGitStorage: Fn(String) -> Result<RemoteGitStorage, GitError>,
+    CreateRemoteGitStorage: Fn(String, Option<String>) -> Result<RemoteGitStorage, GitError>,
     RemoteGitStorage: ProjectRead,
 {
-    let syncing = "Syncing";
-    let header = crate::style::get_style_config().header;
-    log::info!("{header}{syncing:>12}{header:#} env");
+    let plan = do_sync_plan(lockfile, env, provided_iris)?;
+    do_sync_apply(
+        &plan,
+        env,
+        src_path_storage,
+        remote_src_storage,
+        kpar_path_storage,
+        remote_kpar_storage,
+        index_kpar_storage,
+        remote_git_storage,
+    )
+}
 
-    // Do `continue 'main_loop` if it becomes clear that no env changes will be made
-    // for the current iteration `project`
-    let mut updated = false;
-    'main_loop: for project in lockfile.projects.iter() {
-        // TODO: We need a proper way to treat multiple IRIs here
-        let main_uri = project.identifiers.first();
+/// Compute the actions needed to sync `env` to `lockfile`, without
+/// modifying `env`. Projects in `provided_iris` are never installed, but
+/// their versions in `lockfile` must be provided
+pub fn do_sync_plan<
+    Environment: ReadEnvironment,
+    UrlParseError: ErrorBound,
+    GitError: ErrorBound,
+>(
+    lockfile: &Lock,
+    env: &Environment,
+    provided_iris: &HashMap<String, Vec<InMemoryProject>>,
+) -> Result<SyncPlan, SyncError<UrlParseError, GitError>> {
+    let read_err = |e| SyncError::ProjectRead(format_err(e));
+    // Installed versions of each IRI in `env`
+    let mut installed: IndexMap<String, Vec<String>> = IndexMap::new();
+    for uri in env.uris().map_err(read_err)? {
+        let uri = uri.map_err(read_err)?;
+        if installed.contains_key(&uri) {
+            continue;
+        }
+        let versions = env
+            .versions(&uri)
+            .map_err(read_err)?
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .map_err(read_err)?;
+        installed.insert(uri, versions);
+    }
+    let is_locked = |iri: &str, version: &str| {
+        lockfile
+            .projects
+            .iter()
+            .any(|p| p.version == version && p.identifiers.iter().any(|i| i == iri))
+    };
 
+    let mut actions = vec![];
+    'main_loop: for project in lockfile.projects.iter() {
         for iri in &project.identifiers {
             let excluded_versions = if let Ok(parsed_iri) = fluent_uri::Iri::parse(iri.clone()) {
                 // TODO: maybe canonicalize on lock read, or don't canonicalize at all?
@@ -181,189 +454,436 @@ where
                 project.identifiers.as_slice().into(),
             ));
         }
+        if let Some(Source::Editable { editable }) = project.sources.first() {
+            // Nothing to install for editable
+            log::debug!("skipping installation of editable project from `{editable}`");
+            continue;
+        }
+        if is_installed(project, env)? {
+            continue;
+        }
 
-        for iri in &project.identifiers {
-            // TODO: move functionality to check if any of a set of IRIs is installed to env trait
-            for source in &project.sources {
-                if let Some(checksum) = source.to_checksum()
-                    && env
-                        .has_version_verified(iri, &project.version, &checksum)
-                        .map_err(|e| SyncError::ProjectRead(format_err(e)))?
-                        == ProjectChecksumResult::Match
-                {
-                    log::debug!("`{iri}` found in .sysand");
-                    continue 'main_loop;
-                }
+        if project
+            .identifiers
+            .iter()
+            .any(|iri| installed.contains_key(iri))
+        {
+            let replaces = project
+                .identifiers
+                .first()
+                .and_then(|iri| installed.get(iri).map(|versions| (iri, versions)))
+                .map(|(iri, versions)| {
+                    versions
+                        .iter()
+                        .filter(|version| !is_locked(iri, version))
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default();
+            actions.push(SyncAction::Update {
+                project: project.clone(),
+                replaces,
+            });
+        } else {
+            actions.push(SyncAction::Install {
+                project: project.clone(),
+            });
+        }
+    }
+
+    // Projects not in `lockfile` at all, e.g. installed with `sysand env install`,
+    // are kept. Only versions of locked projects that are no longer locked are removed
+    for (iri, versions) in &installed {
+        if !lockfile
+            .projects
+            .iter()
+            .any(|p| p.identifiers.contains(iri))
+        {
+            continue;
+        }
+        for version in versions {
+            let replaced = actions.iter().any(|action| {
+                matches!(action, SyncAction::Update { project, replaces }
+                    if project.identifiers.first() == Some(iri) && replaces.contains(version))
+            });
+            if !is_locked(iri, version) && !replaced {
+                actions.push(SyncAction::Remove {
+                    iri: iri.to_owned(),
+                    version: version.to_owned(),
+                });
             }
         }
+    }
 
-        let mut no_supported = true;
-        // TODO: does it make sense to install the same project from all the sources?
-        for source in &project.sources {
-            let supported = true;
-            match source {
-                Source::Editable { editable } => {
-                    // Nothing to install for editable
-                    log::debug!("skipping installation of editable project from `{editable}`");
-                    continue 'main_loop;
-                }
-                Source::LocalSrc { src_path, checksum } => {
-                    let uri = main_uri
-                        .ok_or_else(|| SyncError::MissingIriSrcPath(src_path.as_str().into()))?;
-                    let src_path_storage = src_path_storage
-                        .as_ref()
-                        .ok_or_else(|| SyncError::MissingSrcPathStorage(uri.as_str().into()))?;
-                    let storage = src_path_storage(src_path.clone(), checksum.clone());
-                    log::debug!("trying to install `{uri}` from src_path `{src_path}`");
-                    try_install(
-                        uri,
-                        &project.version,
-                        // TODO: avoid clone
-                        &ProjectChecksum::Project(checksum.to_owned()),
-                        storage,
-                        env,
-                    )?;
-                }
-                Source::RemoteSrc {
-                    remote_src,
-                    checksum,
-                } => {
-                    let uri = main_uri.as_ref().ok_or_else(|| {
-                        SyncError::MissingIriRemoteSrc(remote_src.as_str().into())
-                    })?;
-                    let remote_src_storage = remote_src_storage
-                        .as_ref()
-                        .ok_or_else(|| SyncError::MissingRemoteSrcStorage(uri.as_str().into()))?;
-                    let storage = remote_src_storage(remote_src.clone(), checksum.clone())
-                        .map_err(|e| {
-                            SyncError::InvalidRemoteSource(remote_src.as_str().into(), e)
+    Ok(SyncPlan {
+        lock_digest: lockfile.canonical_hash(),
+        actions,
+        metadata_only: false,
+    })
+}
+
+/// Carry out the actions of `plan` in `env`. Projects that are already
+/// installed with the expected checksum are not installed again, so a
+/// plan that failed part way through can be applied again to finish it
+#[allow(clippy::too_many_arguments)]
+pub fn do_sync_apply<
+    Environment,
+    CreateSrcPathStorage,
+    SrcPathStorage,
+    CreateRemoteSrcStorage,
+    RemoteSrcStorage,
+    CreateKParPathStorage,
+    KParPathStorage,
+    CreateRemoteKParStorage,
+    RemoteKParStorage,
+    CreateIndexKParStorage,
+    IndexKParStorage,
+    UrlParseError: ErrorBound,
+    CreateRemoteGitStorage,
+    RemoteGitStorage,
+    GitError: ErrorBound,
+>(
+    plan: &SyncPlan,
+    env: &mut Environment,
+    src_path_storage: Option<CreateSrcPathStorage>,
+    remote_src_storage: Option<CreateRemoteSrcStorage>,
+    kpar_path_storage: Option<CreateKParPathStorage>,
+    remote_kpar_storage: Option<CreateRemoteKParStorage>,
+    index_kpar_storage: Option<CreateIndexKParStorage>,
+    remote_git_storage: Option<CreateRemoteGitStorage>,
+) -> Result<(), SyncError<UrlParseError, GitError>>
+where
+    Environment: ReadEnvironment + WriteEnvironment,
+    CreateSrcPathStorage: Fn(Utf8UnixPathBuf, String) -> SrcPathStorage,
+    SrcPathStorage: ProjectRead,
+    CreateRemoteSrcStorage: Fn(String, String) -> Result<RemoteSrcStorage, UrlParseError>,
+    RemoteSrcStorage: ProjectRead,
+    CreateKParPathStorage: Fn(String, NonZeroU64, String) -> KParPathStorage,
+    KParPathStorage: ProjectRead,
+    CreateRemoteKParStorage:
+        Fn(String, NonZeroU64, String) -> Result<RemoteKParStorage, UrlParseError>,
+    RemoteKParStorage: ProjectRead,
+    CreateIndexKParStorage:
+        Fn(String, NonZeroU64, String) -> Result<IndexKParStorage, UrlParseError>,
+    IndexKParStorage: ProjectRead,
+    CreateRemoteGitStorage: Fn(String, Option<String>) -> Result<RemoteGitStorage, GitError>,
+    RemoteGitStorage: ProjectRead,
+{
+    let syncing = "Syncing";
+    let header = crate::style::get_style_config().header;
+    log::info!("{header}{syncing:>12}{header:#} env");
+
+    if plan.actions.is_empty() {
+        log::info!("{:>12} nothing to do: env is already up to date", ' ');
+    }
+    let total = plan.actions.len() as u64;
+    for (i, action) in plan.actions.iter().enumerate() {
+        progress::report(
+            ProgressPhase::Install,
+            i as u64 + 1,
+            Some(total),
+            || match action {
+                SyncAction::Install { project } | SyncAction::Update { project, .. } => format!(
+                    "{} {}",
+                    project.identifiers.first().map_or("", String::as_str),
+                    project.version
+                ),
+                SyncAction::Remove { iri, version } => format!("{iri} {version}"),
+            },
+        );
+        let (project, replaces) = match action {
+            SyncAction::Install { project } => (project, &[][..]),
+            SyncAction::Update { project, replaces } => (project, replaces.as_slice()),
+            SyncAction::Remove { iri, version } => {
+                uninstall(iri, version, env)?;
+                continue;
+            }
+        };
+        // TODO: We need a proper way to treat multiple IRIs here
+        let main_uri = project.identifiers.first();
+        let metadata_only = plan.metadata_only.then_some(project.sources.as_slice());
+        let variant = project.variant.as_deref();
+
+        'install: {
+            if is_installed(project, env)? {
+                break 'install;
+            }
+            let mut no_supported = true;
+            // TODO: does it make sense to install the same project from all the sources?
+            for source in &project.sources {
+                let supported = true;
+                match source {
+                    Source::Editable { editable } => {
+                        // Nothing to install for editable
+                        log::debug!("skipping installation of editable project from `{editable}`");
+                        break 'install;
+                    }
+                    Source::LocalSrc { src_path, checksum } => {
+                        let uri = main_uri.ok_or_else(|| {
+                            SyncError::MissingIriSrcPath(src_path.as_str().into())
                         })?;
-                    log::debug!("trying to install `{uri}` from remote_src: {remote_src}");
-                    try_install(
-                        uri,
-                        &project.version,
-                        &ProjectChecksum::Project(checksum.to_owned()),
-                        storage,
-                        env,
-                    )?;
-                }
-                Source::LocalKpar {
-                    kpar_path,
-                    kpar_size,
-                    kpar_digest,
-                } => {
-                    let uri = main_uri.ok_or_else(|| {
-                        SyncError::MissingIriLocalKparPath(kpar_path.as_str().into())
-                    })?;
-                    let kpar_path_storage = kpar_path_storage.as_ref().ok_or_else(|| {
-                        SyncError::MissingLocalKparStorage(kpar_path.as_str().into())
-                    })?;
-                    let storage = kpar_path_storage(
-                        kpar_path.as_str().to_owned(),
-                        *kpar_size,
-                        kpar_digest.to_owned(),
-                    );
-                    log::debug!("trying to install `{uri}` from kpar_path: {kpar_path}");
-                    try_install(
-                        uri,
-                        &project.version,
-                        &ProjectChecksum::Kpar(kpar_digest.to_owned()),
-                        storage,
-                        env,
-                    )?;
-                }
-                Source::RemoteKpar {
-                    remote_kpar,
-                    kpar_size,
-                    kpar_digest,
-                } => {
-                    let uri = main_uri.ok_or_else(|| {
-                        SyncError::MissingIriRemoteKparPath(remote_kpar.as_str().into())
-                    })?;
-                    let remote_kpar_storage = remote_kpar_storage.as_ref().ok_or_else(|| {
-                        SyncError::MissingRemoteKparStorage(remote_kpar.as_str().into())
-                    })?;
-                    let storage = remote_kpar_storage(
-                        remote_kpar.to_owned(),
-                        *kpar_size,
-                        kpar_digest.to_owned(),
-                    )
-                    .map_err(|e| SyncError::InvalidRemoteSource(remote_kpar.as_str().into(), e))?;
-                    log::debug!("trying to install `{uri}` from remote_kpar: {remote_kpar}");
-                    try_install(
-                        uri,
-                        &project.version,
-                        &ProjectChecksum::Kpar(kpar_digest.to_owned()),
-                        storage,
-                        env,
-                    )?;
-                }
-                Source::IndexKpar {
-                    index_kpar,
-                    kpar_size,
-                    kpar_digest,
-                } => {
-                    let uri = main_uri.ok_or_else(|| {
-                        SyncError::MissingIriIndexKparUrl(index_kpar.as_str().into())
-                    })?;
-                    let index_kpar_storage = index_kpar_storage
-                        .as_ref()
-                        .ok_or_else(|| SyncError::MissingIndexKparStorage(uri.as_str().into()))?;
-                    let storage =
-                        index_kpar_storage(index_kpar.to_owned(), *kpar_size, kpar_digest.clone())
+                        let src_path_storage = src_path_storage
+                            .as_ref()
+                            .ok_or_else(|| SyncError::MissingSrcPathStorage(uri.as_str().into()))?;
+                        let storage = src_path_storage(src_path.clone(), checksum.clone());
+                        log::debug!("trying to install `{uri}` from src_path `{src_path}`");
+                        try_install(
+                            uri,
+                            &project.version,
+                            // TODO: avoid clone
+                            &ProjectChecksum::Project(checksum.to_owned()),
+                            storage,
+                            env,
+                            metadata_only,
+                            variant,
+                            Some(source),
+                        )?;
+                    }
+                    Source::RemoteSrc {
+                        remote_src,
+                        checksum,
+                    } => {
+                        let uri = main_uri.as_ref().ok_or_else(|| {
+                            SyncError::MissingIriRemoteSrc(remote_src.as_str().into())
+                        })?;
+                        let remote_src_storage = remote_src_storage.as_ref().ok_or_else(|| {
+                            SyncError::MissingRemoteSrcStorage(uri.as_str().into())
+                        })?;
+                        let storage = remote_src_storage(remote_src.clone(), checksum.clone())
                             .map_err(|e| {
-                                SyncError::InvalidRemoteSource(index_kpar.as_str().into(), e)
+                                SyncError::InvalidRemoteSource(remote_src.as_str().into(), e)
                             })?;
-                    log::debug!("trying to install `{uri}` from index_kpar: {index_kpar}");
-                    try_install(
-                        uri,
-                        &project.version,
-                        &ProjectChecksum::Kpar(kpar_digest.to_owned()),
-                        storage,
-                        env,
-                    )?;
-                }
-                // TODO: git is for now assumed to be editable; in particular we should probably set
-                // editable=true in lockfile/env.toml or some other indicator that the project is expected to change and no
-                // integrity checking will be done
-                // To avoid having remote URLs for editable projects in env.toml, for now on sync unconditionally
-                // install the project
-                Source::RemoteGit { remote_git } => {
-                    let uri = main_uri.ok_or_else(|| {
-                        SyncError::MissingIriRemoteGitUrl(remote_git.as_str().into())
-                    })?;
-                    let remote_git_storage = remote_git_storage.as_ref().ok_or_else(|| {
-                        SyncError::MissingRemoteGitStorage(remote_git.as_str().into())
-                    })?;
-                    let storage = remote_git_storage(remote_git.clone())
-                        .map_err(|e| SyncError::GitDownload(remote_git.as_str().into(), e))?;
-                    log::debug!("trying to install `{uri}` from remote_git: {remote_git}");
-                    do_env_install_project(uri, &project.version, &storage, None, env, true, true)
-                        .map_err(|e| SyncError::InstallFail {
-                            uri: uri.as_str().into(),
-                            cause: format_err(e),
+                        log::debug!("trying to install `{uri}` from remote_src: {remote_src}");
+                        try_install(
+                            uri,
+                            &project.version,
+                            &ProjectChecksum::Project(checksum.to_owned()),
+                            storage,
+                            env,
+                            metadata_only,
+                            variant,
+                            Some(source),
+                        )?;
+                    }
+                    Source::LocalKpar {
+                        kpar_path,
+                        kpar_size,
+                        kpar_digest,
+                    } => {
+                        let uri = main_uri.ok_or_else(|| {
+                            SyncError::MissingIriLocalKparPath(kpar_path.as_str().into())
+                        })?;
+                        let kpar_path_storage = kpar_path_storage.as_ref().ok_or_else(|| {
+                            SyncError::MissingLocalKparStorage(kpar_path.as_str().into())
+                        })?;
+                        let storage = kpar_path_storage(
+                            kpar_path.as_str().to_owned(),
+                            *kpar_size,
+                            kpar_digest.to_owned(),
+                        );
+                        log::debug!("trying to install `{uri}` from kpar_path: {kpar_path}");
+                        try_install(
+                            uri,
+                            &project.version,
+                            &ProjectChecksum::Kpar(kpar_digest.to_owned()),
+                            storage,
+                            env,
+                            metadata_only,
+                            variant,
+                            Some(source),
+                        )?;
+                    }
+                    Source::RemoteKpar {
+                        remote_kpar,
+                        kpar_size,
+                        kpar_digest,
+                    } => {
+                        let uri = main_uri.ok_or_else(|| {
+                            SyncError::MissingIriRemoteKparPath(remote_kpar.as_str().into())
+                        })?;
+                        let remote_kpar_storage =
+                            remote_kpar_storage.as_ref().ok_or_else(|| {
+                                SyncError::MissingRemoteKparStorage(remote_kpar.as_str().into())
+                            })?;
+                        let storage = remote_kpar_storage(
+                            remote_kpar.to_owned(),
+                            *kpar_size,
+                            kpar_digest.to_owned(),
+                        )
+                        .map_err(|e| {
+                            SyncError::InvalidRemoteSource(remote_kpar.as_str().into(), e)
                         })?;
+                        log::debug!("trying to install `{uri}` from remote_kpar: {remote_kpar}");
+                        try_install(
+                            uri,
+                            &project.version,
+                            &ProjectChecksum::Kpar(kpar_digest.to_owned()),
+                            storage,
+                            env,
+                            metadata_only,
+                            variant,
+                            Some(source),
+                        )?;
+                    }
+                    Source::IndexKpar {
+                        index_kpar,
+                        kpar_size,
+                        kpar_digest,
+                    } => {
+                        let uri = main_uri.ok_or_else(|| {
+                            SyncError::MissingIriIndexKparUrl(index_kpar.as_str().into())
+                        })?;
+                        let index_kpar_storage = index_kpar_storage.as_ref().ok_or_else(|| {
+                            SyncError::MissingIndexKparStorage(uri.as_str().into())
+                        })?;
+                        let storage = index_kpar_storage(
+                            index_kpar.to_owned(),
+                            *kpar_size,
+                            kpar_digest.clone(),
+                        )
+                        .map_err(|e| {
+                            SyncError::InvalidRemoteSource(index_kpar.as_str().into(), e)
+                        })?;
+                        log::debug!("trying to install `{uri}` from index_kpar: {index_kpar}");
+                        try_install(
+                            uri,
+                            &project.version,
+                            &ProjectChecksum::Kpar(kpar_digest.to_owned()),
+                            storage,
+                            env,
+                            metadata_only,
+                            variant,
+                            Some(source),
+                        )?;
+                    }
+                    // TODO: git is for now assumed to be editable; in particular we should probably set
+                    // editable=true in lockfile/env.toml or some other indicator that the project is expected to change and no
+                    // integrity checking will be done
+                    // To avoid having remote URLs for editable projects in env.toml, for now on sync unconditionally
+                    // install the project
+                    Source::RemoteGit { remote_git, rev } => {
+                        let uri = main_uri.ok_or_else(|| {
+                            SyncError::MissingIriRemoteGitUrl(remote_git.as_str().into())
+                        })?;
+                        let remote_git_storage = remote_git_storage.as_ref().ok_or_else(|| {
+                            SyncError::MissingRemoteGitStorage(remote_git.as_str().into())
+                        })?;
+                        let storage = remote_git_storage(remote_git.clone(), rev.clone())
+                            .map_err(|e| SyncError::GitDownload(remote_git.as_str().into(), e))?;
+                        log::debug!("trying to install `{uri}` from remote_git: {remote_git}");
+                        install(
+                            uri,
+                            &project.version,
+                            &storage,
+                            None,
+                            env,
+                            metadata_only,
+                            variant,
+                            Some(source),
+                        )?;
+                    }
+                }
+                if supported {
+                    no_supported = false;
                 }
             }
-            if supported {
-                no_supported = false;
+            if no_supported {
+                return Err(SyncError::UnsupportedSources(
+                    main_uri
+                        .cloned()
+                        .unwrap_or_else(|| "project without IRI".to_string()),
+                ));
             }
         }
-        if no_supported {
-            return Err(SyncError::UnsupportedSources(
-                main_uri
-                    .cloned()
-                    .unwrap_or_else(|| "project without IRI".to_string()),
-            ));
+
+        if let Some(uri) = main_uri {
+            for version in replaces {
+                uninstall(uri, version, env)?;
+            }
         }
-        updated = true;
     }
-    if !updated {
-        log::info!("{:>12} nothing to do: env is already up to date", ' ');
+    Ok(())
+}
+
+/// Check the hash pins of `usages` against the projects of `lock` that
+/// are installed in `env`. Pins of projects that are not locked or not
+/// installed in `env` are not checked
+pub fn do_sync_verify_pins<E: ReadEnvironment, U: ErrorBound, G: ErrorBound>(
+    usages: &[InterchangeProjectUsageRaw],
+    lock: &Lock,
+    env: &E,
+) -> Result<(), SyncError<U, G>> {
+    let read_err = |e| SyncError::ProjectRead(format_err(e));
+    for usage in usages {
+        let InterchangeProjectUsageRaw::Resource { resource, .. } = usage;
+        let Some(expected) = usage.hash_pin() else {
+            continue;
+        };
+        let locked = lock.projects.iter().find_map(|project| {
+            let iri = project
+                .identifiers
+                .iter()
+                .find(|iri| iris_equivalent(iri, resource))?;
+            Some((iri, &project.version))
+        });
+        let Some((iri, version)) = locked else {
+            continue;
+        };
+        if !env.has_version(iri, version).map_err(read_err)? {
+            continue;
+        }
+        let actual = env
+            .get_project(iri, version)
+            .map_err(read_err)?
+            .checksum_canonical_hex()
+            .map_err(|e| SyncError::ProjectRead(format_err(e)))?
+            .ok_or_else(|| SyncError::BadProject(iri.to_owned()))?;
+        if actual != expected {
+            return Err(SyncError::HashMismatch {
+                iri: iri.to_owned(),
+                expected: expected.to_owned(),
+                actual,
+            });
+        }
     }
     Ok(())
 }
 
+/// Whether `project` is installed in `env` with the checksum of one of its
+/// sources
+fn is_installed<E: ReadEnvironment, U: ErrorBound, G: ErrorBound>(
+    project: &Project,
+    env: &E,
+) -> Result<bool, SyncError<U, G>> {
+    for iri in &project.identifiers {
+        // TODO: move functionality to check if any of a set of IRIs is installed to env trait
+        for source in &project.sources {
+            if let Some(checksum) = source.to_checksum()
+                && env
+                    .has_version_verified(iri, &project.version, &checksum)
+                    .map_err(|e| SyncError::ProjectRead(format_err(e)))?
+                    == ProjectChecksumResult::Match
+            {
+                log::debug!("`{iri}` found in .sysand");
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+fn uninstall<E: WriteEnvironment, U: ErrorBound, G: ErrorBound>(
+    uri: &str,
+    version: &str,
+    env: &mut E,
+) -> Result<(), SyncError<U, G>> {
+    do_env_uninstall(uri, Some(version), env).map_err(|e| SyncError::UninstallFail {
+        uri: uri.into(),
+        cause: format_err(e),
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
 fn try_install<
     E: ReadEnvironment + WriteEnvironment,
     P: ProjectRead,
@@ -376,6 +896,9 @@ fn try_install<
     expected_checksum: &ProjectChecksum,
     storage: P,
     env: &mut E,
+    metadata_only: Option<&[Source]>,
+    variant: Option<&str>,
+    source: Option<&Source>,
 ) -> Result<(), SyncError<U, G>> {
     let uri = uri.as_ref();
     let actual_checksum = storage
@@ -383,19 +906,16 @@ fn try_install<
         .map_err(|e| SyncError::ProjectRead(format_err(e)))?;
     if expected_checksum == &actual_checksum {
         // TODO: Need to decide how to handle existing installations and possible flags to modify behavior
-        do_env_install_project(
+        install(
             uri,
             version,
             &storage,
             Some(actual_checksum),
             env,
-            true,
-            true,
-        )
-        .map_err(|e| SyncError::InstallFail {
-            uri: uri.into(),
-            cause: format_err(e),
-        })?;
+            metadata_only,
+            variant,
+            source,
+        )?;
     } else {
         return Err(SyncError::BadChecksum {
             iri: uri.into(),
@@ -406,6 +926,60 @@ fn try_install<
     Ok(())
 }
 
+/// Install `storage`, or only its `variant` if given
+#[allow(clippy::too_many_arguments)]
+fn install<E: ReadEnvironment + WriteEnvironment, P: ProjectRead, U: ErrorBound, G: ErrorBound>(
+    uri: &str,
+    version: &str,
+    storage: &P,
+    checksum: Option<ProjectChecksum>,
+    env: &mut E,
+    metadata_only: Option<&[Source]>,
+    variant: Option<&str>,
+    source: Option<&Source>,
+) -> Result<(), SyncError<U, G>> {
+    let result = match variant {
+        Some(variant) => {
+            let declared = storage
+                .get_meta()
+                .map_err(|e| SyncError::ProjectRead(format_err(e)))?
+                .is_some_and(|meta| meta.variants.contains_key(variant));
+            if !declared {
+                return Err(SyncError::UnknownVariant {
+                    iri: uri.into(),
+                    variant: variant.into(),
+                });
+            }
+            do_env_install_project(
+                uri,
+                version,
+                &VariantProject::new(storage, variant),
+                checksum,
+                env,
+                OverwritePolicy::Replace,
+                true,
+                metadata_only,
+                source,
+            )
+        }
+        None => do_env_install_project(
+            uri,
+            version,
+            storage,
+            checksum,
+            env,
+            OverwritePolicy::Replace,
+            true,
+            metadata_only,
+            source,
+        ),
+    };
+    result.map_err(|e| SyncError::InstallFail {
+        uri: uri.into(),
+        cause: format_err(e),
+    })
+}
+
 #[cfg(test)]
 #[path = "./sync_tests.rs"]
 mod tests;
@@ -1,7 +1,12 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
 
-use std::convert::Infallible;
+use std::{
+    assert_matches,
+    collections::{HashMap, HashSet},
+    convert::Infallible,
+    str::FromStr,
+};
 
 use chrono::DateTime;
 use indexmap::IndexMap;
@@ -9,12 +14,13 @@ use semver::Version;
 
 use crate::{
     env::{
-        ProjectChecksumResult, ReadEnvironment, WriteEnvironment, memory::MemoryStorageEnvironment,
-        utils::clone_project,
+        OverwritePolicy, ProjectChecksumResult, ReadEnvironment, WriteEnvironment,
+        memory::MemoryStorageEnvironment, utils::clone_project,
     },
+    lock::{CURRENT_LOCK_VERSION, Lock},
     model::{InterchangeProjectInfo, InterchangeProjectMetadata},
     project::{ProjectChecksum, ProjectMut, ProjectRead, memory::InMemoryProject},
-    sync::{SyncError, try_install},
+    sync::{SyncAction, SyncError, SyncFilter, SyncPlan, do_sync_plan, try_install},
 };
 
 fn new_env() -> MemoryStorageEnvironment<InMemoryProject> {
@@ -45,6 +51,7 @@ fn storage_example() -> InMemoryProject {
                 includes_derived: None,
                 includes_implied: None,
                 checksum: None,
+                variants: IndexMap::new(),
             }
             .into(),
             true,
@@ -70,9 +77,13 @@ fn installed_projects_are_found() {
     let version = "1.2.3";
     let checksum = storage.checksum_canonical_variant().unwrap();
     let mut env = new_env();
-    env.put_project(uri, version, Some(checksum.clone()), |p| {
-        clone_project(&storage, p, true).map(|_| ())
-    })
+    env.put_project(
+        uri,
+        version,
+        Some(checksum.clone()),
+        OverwritePolicy::Replace,
+        |p| clone_project(&storage, p, true).map(|_| ()),
+    )
     .unwrap();
 
     assert_eq!(
@@ -106,7 +117,7 @@ fn try_install_installs_project() {
     let mut env = new_env();
 
     try_install::<_, InMemoryProject, Infallible, Infallible, _>(
-        uri, "1.2.3", &checksum, storage, &mut env,
+        uri, "1.2.3", &checksum, storage, &mut env, None, None, None,
     )
     .unwrap();
 
@@ -134,7 +145,7 @@ fn try_install_fails_to_install_wrong_checksum() {
         expected,
         actual,
     } = try_install::<_, _, Infallible, Infallible, _>(
-        &uri, "1.2.3", &checksum, &storage, &mut env,
+        &uri, "1.2.3", &checksum, &storage, &mut env, None, None, None,
     )
     .unwrap_err()
     else {
@@ -167,9 +178,13 @@ fn has_version_verified_different_checksum_kinds() {
         ProjectChecksum::Kpar(c) => ProjectChecksum::Project(c.clone()),
     };
     let mut env = new_env();
-    env.put_project(uri, version, Some(project_checksum), |p| {
-        clone_project(&storage, p, true).map(|_| ())
-    })
+    env.put_project(
+        uri,
+        version,
+        Some(project_checksum),
+        OverwritePolicy::Replace,
+        |p| clone_project(&storage, p, true).map(|_| ()),
+    )
     .unwrap();
 
     assert_eq!(
@@ -186,9 +201,13 @@ fn has_version_verified_version_not_found_for_known_uri() {
     let version = "1.2.3";
     let checksum = storage.checksum_canonical_variant().unwrap();
     let mut env = new_env();
-    env.put_project(uri, version, Some(checksum.clone()), |p| {
-        clone_project(&storage, p, true).map(|_| ())
-    })
+    env.put_project(
+        uri,
+        version,
+        Some(checksum.clone()),
+        OverwritePolicy::Replace,
+        |p| clone_project(&storage, p, true).map(|_| ()),
+    )
     .unwrap();
 
     assert_eq!(
@@ -196,3 +215,246 @@ fn has_version_verified_version_not_found_for_known_uri() {
         ProjectChecksumResult::VersionNotFound
     );
 }
+
+fn env_with_versions(uri: &str, versions: &[&str]) -> MemoryStorageEnvironment<InMemoryProject> {
+    let storage = storage_example();
+    let checksum = storage.checksum_canonical_variant().unwrap();
+    let mut env = new_env();
+    for version in versions {
+        env.put_project(
+            uri,
+            version,
+            Some(checksum.clone()),
+            OverwritePolicy::Replace,
+            |p| clone_project(&storage, p, true).map(|_| ()),
+        )
+        .unwrap();
+    }
+    env
+}
+
+fn lock_with_version(uri: &str, version: &str) -> Lock {
+    let checksum = match storage_example().checksum_canonical_variant().unwrap() {
+        ProjectChecksum::Project(checksum) | ProjectChecksum::Kpar(checksum) => checksum,
+    };
+    Lock::from_str(&format!(
+        r#"lock_version = "{CURRENT_LOCK_VERSION}"
+
+[[project]]
+name = "install_test"
+version = "{version}"
+identifiers = ["{uri}"]
+sources = [{{ src_path = "lib/install_test", checksum = "{checksum}" }}]
+"#
+    ))
+    .unwrap()
+}
+
+fn plan(lock: &Lock, env: &MemoryStorageEnvironment<InMemoryProject>) -> SyncPlan {
+    do_sync_plan::<_, Infallible, Infallible>(lock, env, &HashMap::new()).unwrap()
+}
+
+#[test]
+fn plan_installs_missing_projects() {
+    let uri = "urn:kpar:install_test";
+    let lock = lock_with_version(uri, "1.2.3");
+
+    let plan = plan(&lock, &new_env());
+
+    assert!(plan.is_for(&lock));
+    assert_eq!(
+        plan.actions,
+        [SyncAction::Install {
+            project: lock.projects[0].clone()
+        }]
+    );
+}
+
+#[test]
+fn plan_skips_installed_projects() {
+    let uri = "urn:kpar:install_test";
+    let lock = lock_with_version(uri, "1.2.3");
+
+    let plan = plan(&lock, &env_with_versions(uri, &["1.2.3"]));
+
+    assert_eq!(plan.actions, []);
+}
+
+#[test]
+fn plan_updates_projects_and_removes_stale_versions() {
+    let uri = "urn:kpar:install_test";
+    let lock = lock_with_version(uri, "1.2.3");
+
+    let plan_update = plan(&lock, &env_with_versions(uri, &["1.0.0"]));
+    let plan_remove = plan(&lock, &env_with_versions(uri, &["1.2.3", "1.0.0"]));
+
+    assert_eq!(
+        plan_update.actions,
+        [SyncAction::Update {
+            project: lock.projects[0].clone(),
+            replaces: vec!["1.0.0".to_owned()],
+        }]
+    );
+    assert_eq!(
+        plan_remove.actions,
+        [SyncAction::Remove {
+            iri: uri.to_owned(),
+            version: "1.0.0".to_owned(),
+        }]
+    );
+}
+
+#[test]
+fn plan_keeps_projects_not_in_lock() {
+    let lock = lock_with_version("urn:kpar:install_test", "1.2.3");
+    let env = env_with_versions("urn:kpar:other", &["1.0.0"]);
+
+    let plan = plan(&lock, &env);
+
+    assert_eq!(
+        plan.actions,
+        [SyncAction::Install {
+            project: lock.projects[0].clone()
+        }]
+    );
+}
+
+#[test]
+fn plan_uses_projects_installed_in_fallback() {
+    let uri = "urn:kpar:install_test";
+    let lock = lock_with_version(uri, "1.2.3");
+    let fallback = env_with_versions(uri, &["1.2.3"]);
+
+    let mut plan_install = plan(&lock, &new_env());
+    let mut plan_update = plan(&lock, &env_with_versions(uri, &["1.0.0"]));
+    plan_install
+        .use_installed::<_, Infallible, Infallible>(&fallback)
+        .unwrap();
+    plan_update
+        .use_installed::<_, Infallible, Infallible>(&fallback)
+        .unwrap();
+
+    assert_eq!(plan_install.actions, []);
+    assert_eq!(
+        plan_update.actions,
+        [SyncAction::Remove {
+            iri: uri.to_owned(),
+            version: "1.0.0".to_owned(),
+        }]
+    );
+
+    let mut plan_missing = plan(&lock, &new_env());
+    plan_missing
+        .use_installed::<_, Infallible, Infallible>(&env_with_versions(uri, &["1.0.0"]))
+        .unwrap();
+    assert_eq!(plan_missing.actions.len(), 1);
+}
+
+#[test]
+fn plan_roundtrips_through_json() {
+    let lock = lock_with_version("urn:kpar:install_test", "1.2.3");
+    let plan = plan(
+        &lock,
+        &env_with_versions("urn:kpar:install_test", &["1.0.0"]),
+    );
+
+    let json = serde_json::to_string(&plan).unwrap();
+
+    assert_eq!(serde_json::from_str::<SyncPlan>(&json).unwrap(), plan);
+    assert!(!plan.is_for(&lock_with_version("urn:kpar:install_test", "1.2.4")));
+}
+
+/// `app` uses `a` and `c`, which both use `b`; `c` also uses `d`
+fn dependency_lock() -> Lock {
+    let project = |name: &str, usages: &[&str]| {
+        format!(
+            "[[project]]\nname = \"{name}\"\nversion = \"1.0.0\"\nidentifiers = [\"urn:kpar:{name}\"]\n\
+            usages = [{}]\nsources = [{{ editable = \"{name}\" }}]\n",
+            usages
+                .iter()
+                .map(|u| format!("\"urn:kpar:{u}\""))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    };
+    Lock::from_str(&format!(
+        "lock_version = \"{CURRENT_LOCK_VERSION}\"\n\n{}{}{}{}{}",
+        project("app", &["a", "c"]),
+        project("a", &["b"]),
+        project("b", &[]),
+        project("c", &["b", "d"]),
+        project("d", &[]),
+    ))
+    .unwrap()
+}
+
+fn selected_names(lock: &Lock, only: &[&str], skip: &[&str]) -> Vec<String> {
+    let filter = SyncFilter {
+        only: only.iter().map(|n| format!("urn:kpar:{n}")).collect(),
+        skip: skip.iter().map(|n| format!("urn:kpar:{n}")).collect(),
+        no_deps: false,
+    };
+    let selected: HashSet<usize> = filter.selected::<Infallible, Infallible>(lock).unwrap();
+    let mut names: Vec<_> = selected
+        .into_iter()
+        .map(|i| lock.projects[i].name.clone())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn filter_only_and_skip() {
+    let lock = dependency_lock();
+
+    assert_eq!(selected_names(&lock, &[], &[]).len(), 5);
+    assert_eq!(selected_names(&lock, &["a"], &[]), ["a", "b"]);
+    assert_eq!(selected_names(&lock, &["c"], &["b"]), ["c", "d"]);
+    // `b` is still used by `a`
+    assert_eq!(selected_names(&lock, &[], &["c"]), ["a", "app", "b"]);
+
+    let without_deps = SyncFilter {
+        only: vec!["urn:kpar:a".to_string(), "urn:kpar:c".to_string()],
+        skip: vec![],
+        no_deps: true,
+    }
+    .selected::<Infallible, Infallible>(&lock)
+    .unwrap();
+    let mut names: Vec<_> = without_deps
+        .into_iter()
+        .map(|i| lock.projects[i].name.as_str())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["a", "c"]);
+
+    let err = SyncFilter {
+        only: vec!["urn:kpar:missing".to_string()],
+        skip: vec![],
+        no_deps: false,
+    }
+    .selected::<Infallible, Infallible>(&lock)
+    .unwrap_err();
+    assert_matches!(err, SyncError::UnknownFilterIri(iri) if iri == "urn:kpar:missing");
+}
+
+#[test]
+fn filter_restricts_plan() {
+    let lock = lock_with_version("urn:kpar:install_test", "1.0.0");
+    let env = new_env();
+    let mut sync_plan = plan(&lock, &env);
+    assert_eq!(sync_plan.actions.len(), 1);
+
+    SyncFilter::default()
+        .apply::<Infallible, Infallible>(&mut sync_plan, &lock)
+        .unwrap();
+    assert_eq!(sync_plan.actions.len(), 1);
+
+    SyncFilter {
+        only: vec![],
+        skip: vec!["urn:kpar:install_test".to_string()],
+        no_deps: false,
+    }
+    .apply::<Infallible, Infallible>(&mut sync_plan, &lock)
+    .unwrap();
+    assert!(sync_plan.actions.is_empty());
+}
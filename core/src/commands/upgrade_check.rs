@@ -0,0 +1,278 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Read as _,
+};
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    env::utils::ErrorBound,
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw, InterchangeProjectUsageRaw},
+    project::{ProjectRead, utils::FsIoError},
+    symbols::{Language, referenced_names},
+};
+
+/// Top-level symbol of the old version of a dependency that the new version
+/// does not have
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RemovedSymbol {
+    pub symbol: String,
+    /// File of the old version declaring the symbol
+    pub file: String,
+    /// Symbol that only the new version declares in the same file, if it is
+    /// the only one added to and this the only one removed from the file.
+    /// This is likely the new name of the symbol
+    pub renamed_to: Option<String>,
+    /// Source files of the current project referring to the symbol
+    pub used_in: Vec<String>,
+}
+
+/// Change of a usage of a dependency between its versions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum UsageChange {
+    Added {
+        iri: String,
+        version_constraint: Option<String>,
+    },
+    Removed {
+        iri: String,
+        version_constraint: Option<String>,
+    },
+    Constraint {
+        iri: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+}
+
+/// Differences between two versions of a dependency that may break the
+/// current project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UpgradeReport {
+    pub iri: String,
+    pub old_version: String,
+    pub new_version: String,
+    pub removed_symbols: Vec<RemovedSymbol>,
+    /// Top-level symbols only the new version declares
+    pub added_symbols: Vec<String>,
+    pub usage_changes: Vec<UsageChange>,
+}
+
+impl UpgradeReport {
+    /// Removed symbols that the current project refers to
+    pub fn breaking(&self) -> impl Iterator<Item = &RemovedSymbol> {
+        self.removed_symbols
+            .iter()
+            .filter(|s| !s.used_in.is_empty())
+    }
+
+    pub fn is_breaking(&self) -> bool {
+        self.breaking().next().is_some()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum UpgradeCheckError<ProjectError: ErrorBound, DependencyError: ErrorBound> {
+    #[error(transparent)]
+    Project(ProjectError),
+    #[error("project is missing project metadata `.meta.json`")]
+    MissingMeta,
+    #[error(transparent)]
+    Dependency(DependencyError),
+    #[error("`{iri}` {version} is missing `.project.json` or `.meta.json`")]
+    IncompleteDependency { iri: String, version: String },
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+}
+
+impl<ProjectError: ErrorBound, DependencyError: ErrorBound> From<FsIoError>
+    for UpgradeCheckError<ProjectError, DependencyError>
+{
+    fn from(v: FsIoError) -> Self {
+        Self::Io(Box::new(v))
+    }
+}
+
+impl<ProjectError: ErrorBound, DependencyError: ErrorBound> HasErrorCode
+    for UpgradeCheckError<ProjectError, DependencyError>
+{
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            UpgradeCheckError::Project(_) => ErrorCode::new(2201, ErrorClass::Project),
+            UpgradeCheckError::MissingMeta => ErrorCode::new(2202, ErrorClass::Project),
+            UpgradeCheckError::Dependency(_) => ErrorCode::new(2203, ErrorClass::Resolution),
+            UpgradeCheckError::IncompleteDependency { .. } => {
+                ErrorCode::new(2204, ErrorClass::Resolution)
+            }
+            UpgradeCheckError::Io(e) => e.error_code(),
+        }
+    }
+}
+
+/// Compare the top-level symbols and usages of versions `old` and `new` of
+/// dependency `iri`, and find the source files of `project` referring to
+/// the symbols that `new` no longer declares. Symbols are compared by the
+/// `index` of `.meta.json`, and references are found by looking for the
+/// first segments of qualified names and for imported names.
+pub fn do_upgrade_check<Pr: ProjectRead, Dep: ProjectRead>(
+    project: &Pr,
+    iri: &str,
+    old: &Dep,
+    new: &Dep,
+) -> Result<UpgradeReport, UpgradeCheckError<Pr::Error, Dep::Error>> {
+    let (old_info, old_meta) = dependency_project(iri, old)?;
+    let (new_info, new_meta) = dependency_project(iri, new)?;
+
+    let references = project_references(project)?;
+    let added_symbols: Vec<String> = new_meta
+        .index
+        .keys()
+        .filter(|s| !old_meta.index.contains_key(*s))
+        .cloned()
+        .collect();
+    let removed: Vec<(&String, &String)> = old_meta
+        .index
+        .iter()
+        .filter(|(s, _)| !new_meta.index.contains_key(*s))
+        .collect();
+    let removed_symbols = removed
+        .iter()
+        .map(|&(symbol, file)| {
+            // A symbol is likely renamed if it is the only one removed from
+            // a file, and another is the only one added to it
+            let removed_in_file = removed.iter().filter(|(_, f)| *f == file).count();
+            let mut added_in_file = added_symbols
+                .iter()
+                .filter(|s| new_meta.index.get(*s) == Some(file));
+            let renamed_to = match (added_in_file.next(), added_in_file.next()) {
+                (Some(s), None) if removed_in_file == 1 => Some(s.clone()),
+                _ => None,
+            };
+            RemovedSymbol {
+                symbol: symbol.clone(),
+                file: file.clone(),
+                renamed_to,
+                used_in: references
+                    .get(symbol)
+                    .map(|files| files.iter().cloned().collect())
+                    .unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    Ok(UpgradeReport {
+        iri: iri.to_owned(),
+        old_version: old_info.version.clone(),
+        new_version: new_info.version.clone(),
+        removed_symbols,
+        added_symbols,
+        usage_changes: usage_changes(&old_info.usage, &new_info.usage),
+    })
+}
+
+fn dependency_project<ProjectError: ErrorBound, Dep: ProjectRead>(
+    iri: &str,
+    dependency: &Dep,
+) -> Result<
+    (InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw),
+    UpgradeCheckError<ProjectError, Dep::Error>,
+> {
+    match dependency
+        .get_project()
+        .map_err(UpgradeCheckError::Dependency)?
+    {
+        (Some(info), Some(meta)) => Ok((info, meta)),
+        (info, _) => Err(UpgradeCheckError::IncompleteDependency {
+            iri: iri.to_owned(),
+            version: info.map(|i| i.version).unwrap_or_else(|| "?".into()),
+        }),
+    }
+}
+
+/// Source files of `project` referring to each name
+fn project_references<Pr: ProjectRead, DepError: ErrorBound>(
+    project: &Pr,
+) -> Result<BTreeMap<String, BTreeSet<String>>, UpgradeCheckError<Pr::Error, DepError>> {
+    let meta = project
+        .get_meta()
+        .map_err(UpgradeCheckError::Project)?
+        .ok_or(UpgradeCheckError::MissingMeta)?;
+    let mut references: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for path in meta.source_paths(true) {
+        if Language::guess_from_path(typed_path::Utf8UnixPath::new(&path)).is_none() {
+            continue;
+        }
+        let mut source = String::new();
+        project
+            .read_source(&path)
+            .map_err(UpgradeCheckError::Project)?
+            .read_to_string(&mut source)
+            .map_err(|e| FsIoError::ReadFile(path.as_str().into(), e))?;
+        match referenced_names(&source) {
+            Ok(names) => {
+                for name in names {
+                    references.entry(name).or_default().insert(path.clone());
+                }
+            }
+            Err(e) => log::warn!("failed to find the names referred to in `{path}`: {e}"),
+        }
+    }
+    Ok(references)
+}
+
+fn usage_changes(
+    old: &[InterchangeProjectUsageRaw],
+    new: &[InterchangeProjectUsageRaw],
+) -> Vec<UsageChange> {
+    let constraints = |usages: &[InterchangeProjectUsageRaw]| -> BTreeMap<String, Option<String>> {
+        usages
+            .iter()
+            .map(
+                |InterchangeProjectUsageRaw::Resource {
+                     resource,
+                     version_constraint,
+                     ..
+                 }| { (resource.clone(), version_constraint.clone()) },
+            )
+            .collect()
+    };
+    let old = constraints(old);
+    let new = constraints(new);
+
+    let mut changes = vec![];
+    for (iri, old_constraint) in &old {
+        match new.get(iri) {
+            None => changes.push(UsageChange::Removed {
+                iri: iri.clone(),
+                version_constraint: old_constraint.clone(),
+            }),
+            Some(new_constraint) if new_constraint != old_constraint => {
+                changes.push(UsageChange::Constraint {
+                    iri: iri.clone(),
+                    old: old_constraint.clone(),
+                    new: new_constraint.clone(),
+                })
+            }
+            Some(_) => (),
+        }
+    }
+    for (iri, new_constraint) in &new {
+        if !old.contains_key(iri) {
+            changes.push(UsageChange::Added {
+                iri: iri.clone(),
+                version_constraint: new_constraint.clone(),
+            });
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+#[path = "./upgrade_check_tests.rs"]
+mod tests;
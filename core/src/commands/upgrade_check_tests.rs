@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::io::Cursor;
+
+use indexmap::IndexMap;
+use typed_path::Utf8UnixPath;
+
+use crate::{
+    commands::upgrade_check::{RemovedSymbol, UpgradeCheckError, UsageChange, do_upgrade_check},
+    model::{
+        InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw, InterchangeProjectUsageRaw,
+        format_created_now,
+    },
+    project::{ProjectMut, memory::InMemoryProject},
+};
+
+const IRI: &str = "urn:kpar:vehicles";
+
+fn usage(iri: &str, version_constraint: Option<&str>) -> InterchangeProjectUsageRaw {
+    InterchangeProjectUsageRaw::Resource {
+        resource: iri.into(),
+        version_constraint: version_constraint.map(Into::into),
+        when: None,
+        hash: None,
+    }
+}
+
+/// Project with the given `index`, `usage` and source files
+fn project(
+    version: &str,
+    index: &[(&str, &str)],
+    usage: Vec<InterchangeProjectUsageRaw>,
+    sources: &[(&str, &str)],
+) -> InMemoryProject {
+    let info = InterchangeProjectInfoRaw {
+        name: "vehicles".to_string(),
+        publisher: None,
+        description: None,
+        version: version.to_string(),
+        license: None,
+        maintainer: vec![],
+        website: None,
+        topic: vec![],
+        usage,
+    };
+    let meta = InterchangeProjectMetadataRaw {
+        index: index
+            .iter()
+            .map(|(s, f)| (s.to_string(), f.to_string()))
+            .collect::<IndexMap<_, _>>(),
+        created: format_created_now(),
+        metamodel: None,
+        includes_derived: None,
+        includes_implied: None,
+        checksum: None,
+        variants: IndexMap::new(),
+    };
+    let mut project = InMemoryProject::default();
+    project.put_project(&info, &meta, true).unwrap();
+    for (path, content) in sources {
+        project
+            .write_source(Utf8UnixPath::new(path), &mut Cursor::new(content), true)
+            .unwrap();
+    }
+    project
+}
+
+fn current() -> InMemoryProject {
+    project(
+        "0.1.0",
+        &[("Main", "main.sysml"), ("Other", "other.sysml")],
+        vec![usage(IRI, Some("^1.0.0"))],
+        &[
+            (
+                "main.sysml",
+                "package Main {\n    private import Vehicles::*;\n    part car : Parts::Wheel;\n}",
+            ),
+            ("other.sysml", "package Other { import Parts; }"),
+        ],
+    )
+}
+
+#[test]
+fn upgrade_check_reports_removed_symbols() {
+    let old = project(
+        "1.0.0",
+        &[
+            ("Vehicles", "vehicles.sysml"),
+            ("Parts", "parts.sysml"),
+            ("Unused", "unused.sysml"),
+        ],
+        vec![
+            usage("urn:kpar:units", Some("^1.0.0")),
+            usage("urn:kpar:old", None),
+        ],
+        &[],
+    );
+    let new = project(
+        "2.0.0",
+        &[
+            ("Vehicles", "vehicles.sysml"),
+            ("Components", "parts.sysml"),
+            ("Extra", "extra.sysml"),
+        ],
+        vec![
+            usage("urn:kpar:units", Some("^2.0.0")),
+            usage("urn:kpar:new", None),
+        ],
+        &[],
+    );
+
+    let report = do_upgrade_check(&current(), IRI, &old, &new).unwrap();
+
+    assert_eq!(report.old_version, "1.0.0");
+    assert_eq!(report.new_version, "2.0.0");
+    assert_eq!(
+        report.removed_symbols,
+        [
+            RemovedSymbol {
+                symbol: "Parts".into(),
+                file: "parts.sysml".into(),
+                renamed_to: Some("Components".into()),
+                used_in: vec!["main.sysml".into(), "other.sysml".into()],
+            },
+            RemovedSymbol {
+                symbol: "Unused".into(),
+                file: "unused.sysml".into(),
+                renamed_to: None,
+                used_in: vec![],
+            },
+        ]
+    );
+    assert_eq!(report.added_symbols, ["Components", "Extra"]);
+    assert!(report.is_breaking());
+    assert_eq!(
+        report
+            .breaking()
+            .map(|s| s.symbol.as_str())
+            .collect::<Vec<_>>(),
+        ["Parts"]
+    );
+    assert_eq!(
+        report.usage_changes,
+        [
+            UsageChange::Removed {
+                iri: "urn:kpar:old".into(),
+                version_constraint: None,
+            },
+            UsageChange::Constraint {
+                iri: "urn:kpar:units".into(),
+                old: Some("^1.0.0".into()),
+                new: Some("^2.0.0".into()),
+            },
+            UsageChange::Added {
+                iri: "urn:kpar:new".into(),
+                version_constraint: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn upgrade_check_compatible_versions() {
+    let old = project("1.0.0", &[("Vehicles", "vehicles.sysml")], vec![], &[]);
+    let new = project(
+        "1.1.0",
+        &[("Vehicles", "vehicles.sysml"), ("Trucks", "trucks.sysml")],
+        vec![],
+        &[],
+    );
+
+    let report = do_upgrade_check(&current(), IRI, &old, &new).unwrap();
+
+    assert!(!report.is_breaking());
+    assert!(report.removed_symbols.is_empty());
+    assert_eq!(report.added_symbols, ["Trucks"]);
+    assert!(report.usage_changes.is_empty());
+}
+
+#[test]
+fn upgrade_check_incomplete_dependency() {
+    let old = project("1.0.0", &[("Vehicles", "vehicles.sysml")], vec![], &[]);
+    let new = InMemoryProject::default();
+
+    let err = do_upgrade_check(&current(), IRI, &old, &new).unwrap_err();
+
+    assert!(
+        matches!(&err, UpgradeCheckError::IncompleteDependency { iri, .. } if iri == IRI),
+        "{err}"
+    );
+}
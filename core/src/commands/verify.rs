@@ -0,0 +1,155 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use thiserror::Error;
+
+use crate::{
+    env::utils::ErrorBound,
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    model::{HashAlg, KerMlChecksumAlg},
+    parallel::{Jobs, parallel_map},
+    project::{ProjectRead, hash_reader_hex, utils::FsIoError},
+};
+
+/// Result of checking a source file against its checksum in `.meta.json`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileStatus {
+    Match,
+    Mismatch {
+        expected: String,
+        actual: String,
+    },
+    /// File has no checksum, or its algorithm is `NONE`
+    NoChecksum,
+    /// Checksum algorithm of the file cannot be computed by sysand
+    UnsupportedAlgorithm(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedFile {
+    pub path: String,
+    pub status: FileStatus,
+}
+
+/// Source files of a project, sorted by path, with the results of checking
+/// their checksums
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyReport {
+    pub files: Vec<VerifiedFile>,
+}
+
+impl VerifyReport {
+    /// Files whose contents do not match their checksums
+    pub fn mismatches(&self) -> impl Iterator<Item = &VerifiedFile> {
+        self.files
+            .iter()
+            .filter(|f| matches!(f.status, FileStatus::Mismatch { .. }))
+    }
+
+    /// Files that could not be checked
+    pub fn unchecked(&self) -> impl Iterator<Item = &VerifiedFile> {
+        self.files.iter().filter(|f| {
+            matches!(
+                f.status,
+                FileStatus::NoChecksum | FileStatus::UnsupportedAlgorithm(_)
+            )
+        })
+    }
+
+    /// Fail if any file does not match its checksum
+    pub fn check<ProjectError: ErrorBound>(&self) -> Result<(), VerifyError<ProjectError>> {
+        let mismatches: Vec<String> = self.mismatches().map(|f| f.path.clone()).collect();
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(VerifyError::Mismatch(mismatches))
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum VerifyError<ProjectError: ErrorBound> {
+    #[error(transparent)]
+    Project(ProjectError),
+    #[error("project is missing project metadata `.meta.json`")]
+    MissingMeta,
+    #[error(
+        "contents of {} file(s) do not match their checksums in `.meta.json`: `{}`",
+        .0.len(),
+        .0.join("`, `")
+    )]
+    Mismatch(Vec<String>),
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+}
+
+impl<ProjectError: ErrorBound> From<FsIoError> for VerifyError<ProjectError> {
+    fn from(v: FsIoError) -> Self {
+        Self::Io(Box::new(v))
+    }
+}
+
+impl<ProjectError: ErrorBound> HasErrorCode for VerifyError<ProjectError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            VerifyError::Project(_) => ErrorCode::new(2301, ErrorClass::Project),
+            VerifyError::MissingMeta => ErrorCode::new(2302, ErrorClass::Project),
+            VerifyError::Mismatch(_) => ErrorCode::new(2303, ErrorClass::Project),
+            VerifyError::Io(e) => e.error_code(),
+        }
+    }
+}
+
+/// Check the source files of `project` against the checksums in its
+/// `.meta.json`, reading and checksumming up to `jobs` files in parallel.
+/// Files that cannot be read are an error; if several cannot, the error of
+/// the first one by path is returned
+pub fn do_verify<Pr: ProjectRead + Sync>(
+    project: &Pr,
+    jobs: Jobs,
+) -> Result<VerifyReport, VerifyError<Pr::Error>> {
+    let meta = project
+        .get_meta()
+        .map_err(VerifyError::Project)?
+        .ok_or(VerifyError::MissingMeta)?;
+    let mut paths: Vec<String> = meta.source_paths(true).into_iter().collect();
+    paths.sort();
+
+    let statuses = parallel_map(&paths, jobs, |path| -> Result<_, VerifyError<Pr::Error>> {
+        let Some(checksum) = meta.checksum.as_ref().and_then(|c| c.get(path)) else {
+            return Ok(FileStatus::NoChecksum);
+        };
+        let algorithm = match KerMlChecksumAlg::try_from(checksum.algorithm.as_str()) {
+            Ok(KerMlChecksumAlg::None) => return Ok(FileStatus::NoChecksum),
+            Ok(algorithm) => HashAlg::try_from(algorithm).ok(),
+            Err(_) => None,
+        };
+        let Some(algorithm) = algorithm else {
+            return Ok(FileStatus::UnsupportedAlgorithm(checksum.algorithm.clone()));
+        };
+        let mut source = project.read_source(path).map_err(VerifyError::Project)?;
+        let actual = hash_reader_hex(algorithm, &mut source)
+            .map_err(|e| FsIoError::ReadFile(path.into(), e))?;
+        if actual.eq_ignore_ascii_case(&checksum.value) {
+            Ok(FileStatus::Match)
+        } else {
+            Ok(FileStatus::Mismatch {
+                expected: checksum.value.clone(),
+                actual,
+            })
+        }
+    });
+
+    let mut files = Vec::with_capacity(paths.len());
+    for (path, status) in paths.into_iter().zip(statuses) {
+        files.push(VerifiedFile {
+            path,
+            status: status?,
+        });
+    }
+    Ok(VerifyReport { files })
+}
+
+#[cfg(test)]
+#[path = "./verify_tests.rs"]
+mod tests;
@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::{io::Cursor, num::NonZeroUsize};
+
+use typed_path::{Utf8UnixPath, Utf8UnixPathBuf};
+
+use crate::{
+    commands::verify::{FileStatus, VerifyError, do_verify},
+    include::{do_include, do_include_parallel},
+    model::{HashAlg, InterchangeProjectMetadataRaw, KerMlChecksumAlg},
+    parallel::Jobs,
+    project::{ProjectMut, ProjectRead, memory::InMemoryProject},
+};
+
+fn write(project: &mut InMemoryProject, path: &str, content: &str) {
+    project
+        .write_source(Utf8UnixPath::new(path), &mut Cursor::new(content), true)
+        .unwrap();
+}
+
+fn project(files: usize) -> InMemoryProject {
+    let mut project = InMemoryProject::default();
+    project
+        .put_meta(&InterchangeProjectMetadataRaw::default(), true)
+        .unwrap();
+    for i in 0..files {
+        write(
+            &mut project,
+            &format!("p{i}.sysml"),
+            &format!("package P{i};"),
+        );
+    }
+    project
+}
+
+fn paths(files: usize) -> impl Iterator<Item = Utf8UnixPathBuf> {
+    (0..files).map(|i| format!("p{i}.sysml").into())
+}
+
+#[test]
+fn include_parallel_matches_sequential() {
+    let mut sequential = project(50);
+    do_include(
+        &mut sequential,
+        paths(50),
+        Some(HashAlg::Sha256),
+        true,
+        None,
+    )
+    .unwrap();
+    for jobs in [1, 4, 64] {
+        let mut parallel = project(50);
+        do_include_parallel(
+            &mut parallel,
+            paths(50),
+            Some(HashAlg::Sha256),
+            true,
+            None,
+            Jobs::new(NonZeroUsize::new(jobs).unwrap()),
+        )
+        .unwrap();
+        let parallel = parallel.get_meta().unwrap().unwrap();
+        let sequential = sequential.get_meta().unwrap().unwrap();
+        assert_eq!(parallel.index, sequential.index, "jobs: {jobs}");
+        assert_eq!(parallel.checksum, sequential.checksum, "jobs: {jobs}");
+    }
+}
+
+#[test]
+fn include_parallel_reports_first_error() {
+    let mut project = project(10);
+    write(&mut project, "p3.sysml", "package P, Q;");
+    write(&mut project, "p7.sysml", "package P, Q;");
+    let err = do_include_parallel(
+        &mut project,
+        paths(10),
+        None,
+        true,
+        None,
+        Jobs::new(NonZeroUsize::new(4).unwrap()),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("`p3.sysml`"), "{err}");
+}
+
+#[test]
+fn verify_reports_mismatches() {
+    let mut project = project(3);
+    do_include(&mut project, paths(2), Some(HashAlg::Sha256), false, None).unwrap();
+    do_include(&mut project, paths(3).skip(2), None, true, None).unwrap();
+    write(&mut project, "p1.sysml", "package Changed;");
+
+    let report = do_verify(&project, Jobs::default()).unwrap();
+
+    let statuses: Vec<_> = report
+        .files
+        .iter()
+        .map(|f| (f.path.as_str(), &f.status))
+        .collect();
+    assert!(
+        matches!(
+            statuses[..],
+            [
+                ("p0.sysml", FileStatus::Match),
+                ("p1.sysml", FileStatus::Mismatch { .. }),
+                ("p2.sysml", FileStatus::NoChecksum),
+            ]
+        ),
+        "{statuses:?}"
+    );
+    assert_eq!(report.unchecked().count(), 1);
+    let err = report.check::<std::io::Error>().unwrap_err();
+    assert!(
+        matches!(&err, VerifyError::Mismatch(files) if files == &["p1.sysml"]),
+        "{err}"
+    );
+}
+
+#[test]
+fn verify_unsupported_algorithm() {
+    let mut project = project(1);
+    let mut meta = project.get_meta().unwrap().unwrap();
+    meta.add_checksum("p0.sysml", KerMlChecksumAlg::Md5, "00", true);
+    project.put_meta(&meta, true).unwrap();
+
+    let report = do_verify(&project, Jobs::ONE).unwrap();
+
+    assert_eq!(
+        report.files[0].status,
+        FileStatus::UnsupportedAlgorithm("MD5".into())
+    );
+    assert!(report.check::<std::io::Error>().is_ok());
+}
+
+#[test]
+fn verify_missing_meta() {
+    let project = InMemoryProject::default();
+    let err = do_verify(&project, Jobs::ONE).unwrap_err();
+    assert!(matches!(err, VerifyError::MissingMeta), "{err}");
+}
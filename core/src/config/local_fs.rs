@@ -1,15 +1,17 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
-use std::{fs, io::ErrorKind, str::FromStr};
+use std::{io::ErrorKind, str::FromStr};
 
 use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
 use thiserror::Error;
-use toml_edit::{ArrayOfTables, DocumentMut, Item, Table, Value};
+use toml_edit::{Array, ArrayOfTables, Document, DocumentMut, Item, Table, TableLike, Value};
 
 use super::Config;
 use crate::{
     config::OverrideSource,
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
     project::utils::{FsIoError, wrapfs},
     utils::multiline_array,
 };
@@ -21,6 +23,8 @@ pub const CONFIG_FILE: &str = "sysand.toml";
 pub enum ConfigReadError {
     #[error("failed to deserialize TOML file `{0}`: {1}")]
     Toml(Box<Utf8Path>, toml::de::Error),
+    #[error("invalid settings in `{SYSAND_SETTINGS}` environment variable: {0}")]
+    Env(toml::de::Error),
     #[error(transparent)]
     Io(#[from] Box<FsIoError>),
 }
@@ -31,29 +35,320 @@ impl From<FsIoError> for ConfigReadError {
     }
 }
 
+/// Environment variable with settings in TOML that take precedence over
+/// all configuration files, e.g. `network.proxy = "http://proxy:3128"`
+pub const SYSAND_SETTINGS: &str = "SYSAND_SETTINGS";
+
+/// Environment variable with the path of the system configuration file,
+/// replacing the platform default of [`system_config_path`]
+pub const SYSAND_SYSTEM_CONFIG: &str = "SYSAND_SYSTEM_CONFIG";
+
 pub fn get_config<P: AsRef<Utf8Path>>(path: P) -> Result<Config, ConfigReadError> {
-    if wrapfs::is_file(path.as_ref())? {
-        let contents = {
-            fs::read_to_string(path.as_ref())
-                .map_err(|e| Box::new(FsIoError::ReadFile(path.as_ref().to_owned(), e)))
-        }?;
-        Ok(toml::from_str(&contents)
-            .map_err(|e| ConfigReadError::Toml(path.as_ref().to_owned().into(), e))?)
+    let path = path.as_ref();
+    if wrapfs::is_file(path)? {
+        let (config, unknown_keys) = check_config(path)?;
+        warn_unknown_keys(path.as_str(), &unknown_keys);
+        Ok(config)
     } else {
         Ok(Config::default())
     }
 }
 
+/// Warn that `unknown_keys` of the configuration at `location` are ignored
+pub fn warn_unknown_keys(location: &str, unknown_keys: &[UnknownKey]) {
+    for unknown in unknown_keys {
+        log::warn!(
+            "unknown key `{}` in `{location}{}` is ignored",
+            unknown.key,
+            unknown.line.map_or(String::new(), |l| format!(":{l}")),
+        );
+    }
+}
+
+/// Key of a configuration file that is not a setting, e.g. a misspelled one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnknownKey {
+    /// Dotted path of the key, with array indices in brackets
+    pub key: String,
+    /// 1-based line of the key, if it could be found
+    pub line: Option<usize>,
+}
+
+/// Read the configuration file at `path`, also returning the keys in it
+/// that are not settings
+pub fn check_config<P: AsRef<Utf8Path>>(
+    path: P,
+) -> Result<(Config, Vec<UnknownKey>), ConfigReadError> {
+    let path = path.as_ref();
+    let contents = wrapfs::read_to_string(path)?;
+    parse_config(&contents).map_err(|e| ConfigReadError::Toml(path.to_owned().into(), e))
+}
+
+/// Parse the configuration in `contents`, also returning the keys in it that
+/// are not settings
+pub fn parse_config(contents: &str) -> Result<(Config, Vec<UnknownKey>), toml::de::Error> {
+    let mut ignored = vec![];
+    let config = serde_ignored::deserialize(toml::Deserializer::parse(contents)?, |path| {
+        let mut segments = vec![];
+        key_segments(&path, &mut segments);
+        ignored.push(segments);
+    })?;
+    // Spans are only kept by the read-only document
+    let document = Document::parse(contents).ok();
+    let unknown_keys = ignored
+        .into_iter()
+        .map(|segments| UnknownKey {
+            key: ConfigKey(segments.clone()).to_string(),
+            line: document
+                .as_ref()
+                .and_then(|d| key_span_start(d.as_item(), &segments))
+                .map(|start| contents[..start].matches('\n').count() + 1),
+        })
+        .collect();
+    Ok((config, unknown_keys))
+}
+
+/// Segment of a [`ConfigKey`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Path of a setting, e.g. `cache.ttl` or `index[0].url`. Keys other than
+/// letters, digits, `-` and `_` are quoted, e.g. `variants."urn:kpar:a.b"`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigKey(pub Vec<KeySegment>);
+
+#[derive(Error, Debug)]
+#[error("invalid configuration key `{0}`")]
+pub struct ConfigKeyParseError(String);
+
+impl FromStr for ConfigKey {
+    type Err = ConfigKeyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ConfigKeyParseError(s.to_string());
+        let mut segments = vec![];
+        let mut rest = s;
+        loop {
+            let key;
+            if let Some(quoted) = rest.strip_prefix('"') {
+                let end = quoted.find('"').ok_or_else(invalid)?;
+                key = &quoted[..end];
+                rest = &quoted[end + 1..];
+            } else {
+                let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                key = &rest[..end];
+                if key.is_empty() {
+                    return Err(invalid());
+                }
+                rest = &rest[end..];
+            }
+            segments.push(KeySegment::Key(key.to_string()));
+            while let Some(index) = rest.strip_prefix('[') {
+                let end = index.find(']').ok_or_else(invalid)?;
+                let index_value = index[..end].parse().map_err(|_| invalid())?;
+                segments.push(KeySegment::Index(index_value));
+                rest = &index[end + 1..];
+            }
+            match rest.strip_prefix('.') {
+                Some(next) => rest = next,
+                None if rest.is_empty() => break,
+                None => return Err(invalid()),
+            }
+        }
+        Ok(Self(segments))
+    }
+}
+
+impl std::fmt::Display for ConfigKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            match segment {
+                KeySegment::Index(index) => write!(f, "[{index}]")?,
+                KeySegment::Key(key) => {
+                    if i > 0 {
+                        f.write_str(".")?;
+                    }
+                    let bare = !key.is_empty()
+                        && key
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+                    if bare {
+                        f.write_str(key)?;
+                    } else {
+                        write!(f, "\"{key}\"")?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ConfigKey {
+    /// Value of the setting in `table`, a configuration as TOML
+    pub fn get<'a>(&self, table: &'a toml::Table) -> Option<&'a toml::Value> {
+        let (KeySegment::Key(first), rest) = self.0.split_first()? else {
+            return None;
+        };
+        let mut value = table.get(first)?;
+        for segment in rest {
+            value = match segment {
+                KeySegment::Key(key) => value.as_table()?.get(key)?,
+                KeySegment::Index(index) => value.as_array()?.get(*index)?,
+            };
+        }
+        Some(value)
+    }
+}
+
+fn key_segments(path: &serde_ignored::Path<'_>, segments: &mut Vec<KeySegment>) {
+    use serde_ignored::Path;
+    match path {
+        Path::Root => {}
+        Path::Seq { parent, index } => {
+            key_segments(parent, segments);
+            segments.push(KeySegment::Index(*index));
+        }
+        Path::Map { parent, key } => {
+            key_segments(parent, segments);
+            segments.push(KeySegment::Key(key.clone()));
+        }
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => key_segments(parent, segments),
+    }
+}
+
+/// Byte offset of the last key of `segments` in the document containing `item`
+fn key_span_start(item: &Item, segments: &[KeySegment]) -> Option<usize> {
+    let (segment, rest) = segments.split_first()?;
+    let (span, next) = match (segment, item) {
+        (KeySegment::Key(_), Item::Table(table)) => {
+            return key_span_start_in_table(table, segments);
+        }
+        (KeySegment::Key(k), Item::Value(Value::InlineTable(table))) => {
+            let (key, item) = table.get_key_value(k)?;
+            (key.span(), item)
+        }
+        (KeySegment::Index(i), Item::ArrayOfTables(tables)) => {
+            let table = tables.get(*i)?;
+            if rest.is_empty() {
+                return table.span().map(|s| s.start);
+            }
+            return key_span_start_in_table(table, rest);
+        }
+        (KeySegment::Index(i), Item::Value(Value::Array(array))) => {
+            let value = array.get(*i)?;
+            if rest.is_empty() {
+                return value.span().map(|s| s.start);
+            }
+            return key_span_start(&Item::Value(value.clone()), rest);
+        }
+        _ => return None,
+    };
+    if rest.is_empty() {
+        span.map(|s| s.start)
+    } else {
+        key_span_start(next, rest)
+    }
+}
+
+fn key_span_start_in_table(table: &Table, segments: &[KeySegment]) -> Option<usize> {
+    let (KeySegment::Key(k), rest) = segments.split_first()? else {
+        return None;
+    };
+    let (key, item) = table.get_key_value(k)?;
+    if rest.is_empty() {
+        key.span().map(|s| s.start)
+    } else {
+        key_span_start(item, rest)
+    }
+}
+
+/// Path of the user configuration file in the platform configuration
+/// directory, if there is one
+pub fn user_config_path() -> Option<Utf8PathBuf> {
+    let mut path = Utf8PathBuf::from_path_buf(dirs::config_dir()?).ok()?;
+    path.push(CONFIG_DIR);
+    path.push(CONFIG_FILE);
+    Some(path)
+}
+
+/// Read the user configuration file from the platform configuration
+/// directory, if any.
+pub fn load_user_config() -> Result<Config, ConfigReadError> {
+    user_config_path().map_or_else(|| Ok(Config::default()), get_config)
+}
+
+/// Path of the system configuration file, shared by all users: the
+/// [`SYSAND_SYSTEM_CONFIG`] environment variable if set, or else
+/// `/etc/sysand/sysand.toml`, `/Library/Application Support/sysand/sysand.toml`
+/// on macOS and `%ProgramData%\sysand\sysand.toml` on Windows
+pub fn system_config_path() -> Option<Utf8PathBuf> {
+    if let Some(path) = std::env::var(SYSAND_SYSTEM_CONFIG)
+        .ok()
+        .filter(|p| !p.is_empty())
+    {
+        return Some(path.into());
+    }
+    let mut path = if cfg!(windows) {
+        Utf8PathBuf::from(std::env::var("ProgramData").ok()?)
+    } else if cfg!(target_os = "macos") {
+        Utf8PathBuf::from("/Library/Application Support")
+    } else {
+        Utf8PathBuf::from("/etc")
+    };
+    path.push(CONFIG_DIR);
+    path.push(CONFIG_FILE);
+    Some(path)
+}
+
+/// Read the system configuration file, if any
+pub fn load_system_config() -> Result<Config, ConfigReadError> {
+    system_config_path().map_or_else(|| Ok(Config::default()), get_config)
+}
+
+/// Parse the settings in the [`SYSAND_SETTINGS`] environment variable, if
+/// set, also returning the keys in them that are not settings
+pub fn check_env_settings() -> Result<Option<(Config, Vec<UnknownKey>)>, ConfigReadError> {
+    match std::env::var(SYSAND_SETTINGS) {
+        Ok(settings) => parse_config(&settings)
+            .map(Some)
+            .map_err(ConfigReadError::Env),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Read the settings in the [`SYSAND_SETTINGS`] environment variable, if set
+pub fn load_env_settings() -> Result<Config, ConfigReadError> {
+    Ok(match check_env_settings()? {
+        Some((config, unknown_keys)) => {
+            warn_unknown_keys(SYSAND_SETTINGS, &unknown_keys);
+            config
+        }
+        None => Config::default(),
+    })
+}
+
+/// `sysand` directory in the platform cache directory, if there is one
+pub fn user_cache_dir() -> Option<Utf8PathBuf> {
+    let mut path = Utf8PathBuf::from_path_buf(dirs::cache_dir()?).ok()?;
+    path.push(CONFIG_DIR);
+    Some(path)
+}
+
+/// Read the configuration of the project in `working_dir`. Settings are
+/// taken from, in order of precedence: the [`SYSAND_SETTINGS`] environment
+/// variable, the user configuration file, `sysand.toml` in `working_dir` and
+/// the system configuration file. Lists, such as `[[index]]`, are merged
 pub fn load_configs<P: AsRef<Utf8Path>>(working_dir: P) -> Result<Config, ConfigReadError> {
-    let mut config = dirs::config_dir().map_or_else(
-        || Ok(Config::default()),
-        |mut path| {
-            path.push(CONFIG_DIR);
-            path.push(CONFIG_FILE);
-            get_config(Utf8PathBuf::from_path_buf(path).unwrap())
-        },
-    )?;
+    let mut config = load_env_settings()?;
+    config.merge(load_user_config()?);
     config.merge(get_config(working_dir.as_ref().join(CONFIG_FILE))?);
+    config.merge(load_system_config()?);
 
     Ok(config)
 }
@@ -202,6 +497,358 @@ pub fn remove_project_source_from_config<P: AsRef<Utf8Path>, S: AsRef<str>>(
     Ok(false)
 }
 
+/// Record `iri` as the canonical IRI of the project in the configuration
+/// file at `config_path`, creating the file if needed
+pub fn set_project_iri_in_config<P: AsRef<Utf8Path>, S: AsRef<str>>(
+    config_path: P,
+    iri: S,
+) -> Result<(), ConfigProjectSourceError> {
+    let config_path = config_path.as_ref();
+    let contents = match wrapfs::metadata(config_path) {
+        Ok(metadata) if metadata.is_file() => wrapfs::read_to_string(config_path)?,
+        Ok(_) => {
+            return Err(ConfigProjectSourceError::NotAFile(config_path.to_string()));
+        }
+        Err(err) if matches!(err.as_ref(), FsIoError::Metadata(_, e) if e.kind() == ErrorKind::NotFound) => {
+            String::new()
+        }
+        Err(err) => return Err(ConfigProjectSourceError::Io(err)),
+    };
+    let mut config = DocumentMut::from_str(&contents)
+        .map_err(|err| ConfigProjectSourceError::TomlEdit(config_path.to_owned(), err))?;
+    config["iri"] = toml_edit::value(iri.as_ref());
+
+    let setting = "Setting";
+    let header = crate::style::get_style_config().header;
+    log::info!(
+        "{header}{setting:>12}{header:#} project IRI `{}` in configuration file at `{}`",
+        iri.as_ref(),
+        config_path,
+    );
+
+    wrapfs::write(config_path, config.to_string())?;
+
+    Ok(())
+}
+
+/// Set the variant of dependency `iri` in the `[variants]` table of the
+/// configuration file at `config_path`, creating the file if needed
+pub fn set_project_variant_in_config<P: AsRef<Utf8Path>, S: AsRef<str>, V: AsRef<str>>(
+    config_path: P,
+    iri: S,
+    variant: V,
+) -> Result<(), ConfigProjectSourceError> {
+    let config_path = config_path.as_ref();
+    let contents = match wrapfs::metadata(config_path) {
+        Ok(metadata) if metadata.is_file() => wrapfs::read_to_string(config_path)?,
+        Ok(_) => {
+            return Err(ConfigProjectSourceError::NotAFile(config_path.to_string()));
+        }
+        Err(err) if matches!(err.as_ref(), FsIoError::Metadata(_, e) if e.kind() == ErrorKind::NotFound) => {
+            String::new()
+        }
+        Err(err) => return Err(ConfigProjectSourceError::Io(err)),
+    };
+    let mut config = DocumentMut::from_str(&contents)
+        .map_err(|err| ConfigProjectSourceError::TomlEdit(config_path.to_owned(), err))?;
+    let variants = config
+        .entry("variants")
+        .or_insert_with(|| toml_edit::Item::Table(toml_edit::Table::new()));
+    variants[iri.as_ref()] = toml_edit::value(variant.as_ref());
+
+    let setting = "Setting";
+    let header = crate::style::get_style_config().header;
+    log::info!(
+        "{header}{setting:>12}{header:#} variant `{}` of project `{}` in configuration file at `{}`",
+        variant.as_ref(),
+        iri.as_ref(),
+        config_path,
+    );
+
+    wrapfs::write(config_path, config.to_string())?;
+
+    Ok(())
+}
+
+/// Set why the usage `iri` is needed in the `[reasons]` table of the
+/// configuration file at `config_path`, creating the file if needed
+pub fn set_usage_reason_in_config<P: AsRef<Utf8Path>, S: AsRef<str>, R: AsRef<str>>(
+    config_path: P,
+    iri: S,
+    reason: R,
+) -> Result<(), ConfigProjectSourceError> {
+    let config_path = config_path.as_ref();
+    let contents = match wrapfs::metadata(config_path) {
+        Ok(metadata) if metadata.is_file() => wrapfs::read_to_string(config_path)?,
+        Ok(_) => {
+            return Err(ConfigProjectSourceError::NotAFile(config_path.to_string()));
+        }
+        Err(err) if matches!(err.as_ref(), FsIoError::Metadata(_, e) if e.kind() == ErrorKind::NotFound) => {
+            String::new()
+        }
+        Err(err) => return Err(ConfigProjectSourceError::Io(err)),
+    };
+    let mut config = DocumentMut::from_str(&contents)
+        .map_err(|err| ConfigProjectSourceError::TomlEdit(config_path.to_owned(), err))?;
+    let reasons = config
+        .entry("reasons")
+        .or_insert_with(|| toml_edit::Item::Table(toml_edit::Table::new()));
+    reasons[iri.as_ref()] = toml_edit::value(reason.as_ref());
+
+    let setting = "Setting";
+    let header = crate::style::get_style_config().header;
+    log::info!(
+        "{header}{setting:>12}{header:#} reason for usage `{}` in configuration file at `{}`",
+        iri.as_ref(),
+        config_path,
+    );
+
+    wrapfs::write(config_path, config.to_string())?;
+
+    Ok(())
+}
+
+/// Set `shared_lock` in the `[workspace]` table of the configuration file
+/// at `config_path`, creating the file if needed
+pub fn set_workspace_shared_lock_in_config<P: AsRef<Utf8Path>>(
+    config_path: P,
+    shared_lock: bool,
+) -> Result<(), ConfigProjectSourceError> {
+    let config_path = config_path.as_ref();
+    let contents = match wrapfs::metadata(config_path) {
+        Ok(metadata) if metadata.is_file() => wrapfs::read_to_string(config_path)?,
+        Ok(_) => {
+            return Err(ConfigProjectSourceError::NotAFile(config_path.to_string()));
+        }
+        Err(err) if matches!(err.as_ref(), FsIoError::Metadata(_, e) if e.kind() == ErrorKind::NotFound) => {
+            String::new()
+        }
+        Err(err) => return Err(ConfigProjectSourceError::Io(err)),
+    };
+    let mut config = DocumentMut::from_str(&contents)
+        .map_err(|err| ConfigProjectSourceError::TomlEdit(config_path.to_owned(), err))?;
+    let workspace = config
+        .entry("workspace")
+        .or_insert_with(|| toml_edit::Item::Table(toml_edit::Table::new()));
+    workspace["shared_lock"] = toml_edit::value(shared_lock);
+
+    wrapfs::write(config_path, config.to_string())?;
+
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+pub enum ConfigEditError {
+    #[error(transparent)]
+    Document(#[from] ConfigProjectSourceError),
+    #[error(transparent)]
+    Read(#[from] ConfigReadError),
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+    #[error("`{0}` is not a setting")]
+    UnknownKey(ConfigKey),
+    #[error("cannot set `{0}`: {1}")]
+    InvalidKey(ConfigKey, &'static str),
+    #[error("invalid value for `{0}`: {1}")]
+    InvalidValue(ConfigKey, toml::de::Error),
+}
+
+impl HasErrorCode for ConfigEditError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            ConfigEditError::Document(ConfigProjectSourceError::Io(e)) | ConfigEditError::Io(e) => {
+                e.error_code()
+            }
+            ConfigEditError::Document(_) => ErrorCode::new(3100, ErrorClass::Usage),
+            ConfigEditError::Read(_) => ErrorCode::new(3101, ErrorClass::Usage),
+            ConfigEditError::UnknownKey(_) => ErrorCode::new(3102, ErrorClass::Usage),
+            ConfigEditError::InvalidKey(..) => ErrorCode::new(3103, ErrorClass::Usage),
+            ConfigEditError::InvalidValue(..) => ErrorCode::new(3104, ErrorClass::Usage),
+        }
+    }
+}
+
+/// Configuration file at `config_path` for editing, empty if there is none
+fn read_config_document(config_path: &Utf8Path) -> Result<DocumentMut, ConfigProjectSourceError> {
+    let contents = match wrapfs::metadata(config_path) {
+        Ok(metadata) if metadata.is_file() => wrapfs::read_to_string(config_path)?,
+        Ok(_) => {
+            return Err(ConfigProjectSourceError::NotAFile(config_path.to_string()));
+        }
+        Err(err) if matches!(err.as_ref(), FsIoError::Metadata(_, e) if e.kind() == ErrorKind::NotFound) => {
+            String::new()
+        }
+        Err(err) => return Err(ConfigProjectSourceError::Io(err)),
+    };
+    DocumentMut::from_str(&contents)
+        .map_err(|err| ConfigProjectSourceError::TomlEdit(config_path.to_owned(), err))
+}
+
+/// Set `key` to `value` in the configuration file at `config_path`,
+/// creating the file if needed. `value` is parsed as a TOML value, or taken
+/// as a string if it is not one or the setting is a string. Entries of
+/// lists of tables, e.g. `index[1].url`, are added by setting a key of the
+/// entry one past the last one
+pub fn set_config_value<P: AsRef<Utf8Path>>(
+    config_path: P,
+    key: &ConfigKey,
+    value: &str,
+) -> Result<(), ConfigEditError> {
+    let config_path = config_path.as_ref();
+    let document = read_config_document(config_path)?;
+    let (_, unknown_before) = parse_config(&document.to_string())
+        .map_err(|e| ConfigReadError::Toml(config_path.to_owned().into(), e))?;
+
+    let typed = value.parse::<Value>().ok();
+    // Values that are not valid TOML, e.g. unquoted IRIs, are strings
+    let fallback = (!typed.as_ref().is_some_and(Value::is_str)).then(|| Value::from(value));
+    let mut error = None;
+    for mut candidate in typed.into_iter().chain(fallback) {
+        candidate.decor_mut().clear();
+        let mut edited = document.clone();
+        set_item(edited.as_table_mut(), &key.0, candidate)
+            .map_err(|reason| ConfigEditError::InvalidKey(key.clone(), reason))?;
+        let contents = edited.to_string();
+        match parse_config(&contents) {
+            Ok((_, unknown)) => {
+                if unknown
+                    .iter()
+                    .any(|u| !unknown_before.iter().any(|b| b.key == u.key))
+                {
+                    return Err(ConfigEditError::UnknownKey(key.clone()));
+                }
+                let setting = "Setting";
+                let header = crate::style::get_style_config().header;
+                log::info!(
+                    "{header}{setting:>12}{header:#} `{key}` to `{value}` in configuration file at `{config_path}`",
+                );
+                wrapfs::write(config_path, contents)?;
+                return Ok(());
+            }
+            Err(err) => error = Some(err),
+        }
+    }
+    Err(ConfigEditError::InvalidValue(
+        key.clone(),
+        error.expect("at least one value is tried"),
+    ))
+}
+
+/// Remove `key` from the configuration file at `config_path`, along with
+/// tables and lists left empty. Returns whether `key` was set
+pub fn unset_config_value<P: AsRef<Utf8Path>>(
+    config_path: P,
+    key: &ConfigKey,
+) -> Result<bool, ConfigEditError> {
+    let config_path = config_path.as_ref();
+    if !wrapfs::is_file(config_path)? {
+        return Ok(false);
+    }
+    let mut document = read_config_document(config_path)?;
+    if !unset_item(document.as_table_mut(), &key.0) {
+        return Ok(false);
+    }
+
+    let removing = "Removing";
+    let header = crate::style::get_style_config().header;
+    log::info!(
+        "{header}{removing:>12}{header:#} `{key}` from configuration file at `{config_path}`",
+    );
+    wrapfs::write(config_path, document.to_string())?;
+
+    Ok(true)
+}
+
+fn set_item(
+    table: &mut dyn TableLike,
+    segments: &[KeySegment],
+    value: Value,
+) -> Result<(), &'static str> {
+    let Some((KeySegment::Key(key), rest)) = segments.split_first() else {
+        return Err("tables cannot be set as a whole");
+    };
+    match rest.split_first() {
+        None => {
+            table.insert(key, Item::Value(value));
+        }
+        Some((KeySegment::Key(_), _)) => {
+            let child = table.entry(key).or_insert(Item::Table(Table::new()));
+            let child = child.as_table_like_mut().ok_or("it is not in a table")?;
+            set_item(child, rest, value)?;
+        }
+        Some((KeySegment::Index(index), rest)) => {
+            let child = table.entry(key).or_insert_with(|| {
+                if rest.is_empty() {
+                    Item::Value(Value::Array(Array::new()))
+                } else {
+                    Item::ArrayOfTables(ArrayOfTables::new())
+                }
+            });
+            match child {
+                Item::ArrayOfTables(tables) => {
+                    if *index == tables.len() {
+                        tables.push(Table::new());
+                    }
+                    let entry = tables
+                        .get_mut(*index)
+                        .ok_or("entries can only be added at the end of a list")?;
+                    set_item(entry, rest, value)?;
+                }
+                Item::Value(Value::Array(array)) if rest.is_empty() => {
+                    if *index == array.len() {
+                        array.push_formatted(value);
+                    } else if *index < array.len() {
+                        array.replace_formatted(*index, value);
+                    } else {
+                        return Err("entries can only be added at the end of a list");
+                    }
+                }
+                _ => return Err("it is not in a list"),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn unset_item(table: &mut dyn TableLike, segments: &[KeySegment]) -> bool {
+    let Some((KeySegment::Key(key), rest)) = segments.split_first() else {
+        return false;
+    };
+    let removed = match (rest.split_first(), table.get_mut(key)) {
+        (None, _) => return table.remove(key).is_some(),
+        (Some((KeySegment::Key(_), _)), Some(child)) => child
+            .as_table_like_mut()
+            .is_some_and(|child| unset_item(child, rest)),
+        (Some((KeySegment::Index(index), rest)), Some(Item::ArrayOfTables(tables))) => {
+            if rest.is_empty() && *index < tables.len() {
+                tables.remove(*index);
+                true
+            } else {
+                tables
+                    .get_mut(*index)
+                    .is_some_and(|entry| unset_item(entry, rest))
+            }
+        }
+        (Some((KeySegment::Index(index), [])), Some(Item::Value(Value::Array(array))))
+            if *index < array.len() =>
+        {
+            array.remove(*index);
+            true
+        }
+        _ => false,
+    };
+    // Do not leave empty tables and lists behind
+    let empty = table.get(key).is_some_and(|item| match item {
+        Item::ArrayOfTables(tables) => tables.is_empty(),
+        Item::Value(Value::Array(array)) => array.is_empty(),
+        item => item.as_table_like().is_some_and(TableLike::is_empty),
+    });
+    if removed && empty {
+        table.remove(key);
+    }
+    removed
+}
+
 #[cfg(test)]
 #[path = "./local_fs_tests.rs"]
 mod tests;
@@ -4,6 +4,7 @@
 use std::{error::Error, io::Write};
 
 use camino_tempfile::tempdir;
+use indexmap::IndexMap;
 
 use crate::{
     config::{Config, ConfigProject, Index, OverrideSource, local_fs},
@@ -21,7 +22,7 @@ fn load_configs() -> Result<(), Box<dyn Error>> {
             ..Default::default()
         }],
         projects: vec![],
-        // auth: None,
+        ..Default::default()
     };
     config_file.write_all(toml::to_string_pretty(&config)?.as_bytes())?;
 
@@ -49,6 +50,7 @@ fn add_project_source_to_config() -> Result<(), Box<dyn Error>> {
             identifiers: vec![iri.to_string()],
             sources: vec![source],
         }],
+        ..Default::default()
     };
 
     assert_eq!(
@@ -74,6 +76,7 @@ fn remove_project_source_from_config() -> Result<(), Box<dyn Error>> {
             identifiers: vec![iri.to_string()],
             sources: vec![source],
         }],
+        ..Default::default()
     };
     config_file.write_all(toml::to_string_pretty(&config)?.as_bytes())?;
 
@@ -83,3 +86,190 @@ fn remove_project_source_from_config() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn set_project_iri_in_config() -> Result<(), Box<dyn Error>> {
+    let dir = tempdir()?;
+    let config_path = dir.path().join(local_fs::CONFIG_FILE);
+    let source = OverrideSource::LocalSrc {
+        src_path: "local/test".into(),
+    };
+    local_fs::add_project_source_to_config(&config_path, "urn:kpar:test", &source)?;
+
+    local_fs::set_project_iri_in_config(&config_path, "pkg:sysand/acme/lib")?;
+
+    let config: Config = toml::from_str(wrapfs::read_to_string(&config_path)?.as_str())?;
+    assert_eq!(config.iri.as_deref(), Some("pkg:sysand/acme/lib"));
+    assert_eq!(config.projects.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn set_project_variant_in_config() -> Result<(), Box<dyn Error>> {
+    let dir = tempdir()?;
+    let config_path = dir.path().join(local_fs::CONFIG_FILE);
+    local_fs::set_project_iri_in_config(&config_path, "pkg:sysand/acme/lib")?;
+
+    local_fs::set_project_variant_in_config(&config_path, "urn:kpar:test", "minimal")?;
+    local_fs::set_project_variant_in_config(&config_path, "urn:kpar:other", "full")?;
+    local_fs::set_project_variant_in_config(&config_path, "urn:kpar:test", "full")?;
+
+    let config: Config = toml::from_str(wrapfs::read_to_string(&config_path)?.as_str())?;
+    assert_eq!(config.iri.as_deref(), Some("pkg:sysand/acme/lib"));
+    assert_eq!(
+        config.variants,
+        IndexMap::from([
+            ("urn:kpar:test".to_string(), "full".to_string()),
+            ("urn:kpar:other".to_string(), "full".to_string()),
+        ])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_config_unknown_keys() -> Result<(), Box<dyn Error>> {
+    let contents = r#"iri = "pkg:sysand/acme/lib"
+indexes = ["https://example.com"]
+
+[[index]]
+url = "https://example.com"
+defualt = true
+
+[build]
+include = ["*.sysml"]
+compresion = "deflated"
+
+[cache]
+ttl = 60
+"#;
+
+    let (config, unknown_keys) = local_fs::parse_config(contents)?;
+
+    assert_eq!(config.iri.as_deref(), Some("pkg:sysand/acme/lib"));
+    assert_eq!(config.indexes.len(), 1);
+    assert_eq!(
+        unknown_keys,
+        vec![
+            local_fs::UnknownKey {
+                key: "indexes".to_string(),
+                line: Some(2),
+            },
+            local_fs::UnknownKey {
+                key: "index[0].defualt".to_string(),
+                line: Some(6),
+            },
+            local_fs::UnknownKey {
+                key: "build.compresion".to_string(),
+                line: Some(10),
+            },
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_config_error_points_at_line() {
+    let err = local_fs::parse_config("[cache]\nttl = \"soon\"\n").unwrap_err();
+
+    assert!(err.to_string().contains("line 2"), "{err}");
+}
+
+#[test]
+fn config_key_parse() -> Result<(), Box<dyn Error>> {
+    use local_fs::{ConfigKey, KeySegment};
+
+    let key: ConfigKey = "index[0].url".parse()?;
+    assert_eq!(
+        key.0,
+        vec![
+            KeySegment::Key("index".to_string()),
+            KeySegment::Index(0),
+            KeySegment::Key("url".to_string()),
+        ]
+    );
+    assert_eq!(key.to_string(), "index[0].url");
+
+    let key: ConfigKey = r#"variants."urn:kpar:a.b""#.parse()?;
+    assert_eq!(key.0[1], KeySegment::Key("urn:kpar:a.b".to_string()));
+    assert_eq!(key.to_string(), r#"variants."urn:kpar:a.b""#);
+
+    for invalid in ["", "cache.", "index[x]", "index[0", "a..b", r#""open"#] {
+        assert!(invalid.parse::<ConfigKey>().is_err(), "{invalid}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn set_and_unset_config_value() -> Result<(), Box<dyn Error>> {
+    let dir = tempdir()?;
+    let config_path = dir.path().join(local_fs::CONFIG_FILE);
+    wrapfs::write(&config_path, "# Shared settings\n[cache]\nttl = 60\n")?;
+
+    local_fs::set_config_value(&config_path, &"cache.ttl".parse()?, "120")?;
+    local_fs::set_config_value(&config_path, &"iri".parse()?, "pkg:sysand/acme/lib")?;
+    local_fs::set_config_value(&config_path, &"index[0].url".parse()?, "https://a.com")?;
+    local_fs::set_config_value(&config_path, &"index[1].url".parse()?, "https://b.com")?;
+    local_fs::set_config_value(&config_path, &"index[1].default".parse()?, "true")?;
+    local_fs::set_config_value(&config_path, &"source_roots[0]".parse()?, "vendor")?;
+
+    let contents = wrapfs::read_to_string(&config_path)?;
+    assert!(
+        contents.starts_with("iri = \"pkg:sysand/acme/lib\"\n"),
+        "{contents}"
+    );
+    assert!(
+        contents.contains("# Shared settings\n[cache]\nttl = 120\n"),
+        "{contents}"
+    );
+    let config: Config = toml::from_str(&contents)?;
+    assert_eq!(config.cache.ttl, Some(120));
+    assert_eq!(config.indexes.len(), 2);
+    assert_eq!(config.indexes[1].url, "https://b.com");
+    assert_eq!(config.indexes[1].default, Some(true));
+    assert_eq!(config.source_roots, vec!["vendor".to_string()]);
+
+    assert!(matches!(
+        local_fs::set_config_value(&config_path, &"cache.tll".parse()?, "1"),
+        Err(local_fs::ConfigEditError::UnknownKey(_))
+    ));
+    assert!(matches!(
+        local_fs::set_config_value(&config_path, &"cache.ttl".parse()?, "soon"),
+        Err(local_fs::ConfigEditError::InvalidValue(..))
+    ));
+    assert!(matches!(
+        local_fs::set_config_value(&config_path, &"index[3].url".parse()?, "https://c.com"),
+        Err(local_fs::ConfigEditError::InvalidKey(..))
+    ));
+    assert_eq!(wrapfs::read_to_string(&config_path)?, contents);
+
+    assert!(local_fs::unset_config_value(
+        &config_path,
+        &"index[0]".parse()?
+    )?);
+    assert!(local_fs::unset_config_value(
+        &config_path,
+        &"cache.ttl".parse()?
+    )?);
+    assert!(local_fs::unset_config_value(
+        &config_path,
+        &"source_roots[0]".parse()?
+    )?);
+    assert!(!local_fs::unset_config_value(
+        &config_path,
+        &"cache.ttl".parse()?
+    )?);
+
+    let config: Config = toml::from_str(&wrapfs::read_to_string(&config_path)?)?;
+    assert_eq!(config.indexes.len(), 1);
+    assert_eq!(config.indexes[0].url, "https://b.com");
+    assert_eq!(config.cache.ttl, None);
+    assert!(config.source_roots.is_empty());
+    let contents = wrapfs::read_to_string(&config_path)?;
+    assert!(!contents.contains("[cache]"), "{contents}");
+
+    Ok(())
+}
@@ -1,27 +1,464 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
+use camino::{Utf8Path, Utf8PathBuf};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use toml_edit::{InlineTable, Value};
 use typed_path::Utf8UnixPathBuf;
 use url::Url;
 
-use crate::project::utils::{deserialize_unix_path, serialize_unix_path};
+#[cfg(feature = "filesystem")]
+use crate::build::{CompressionMethodParseError, KparCompressionMethod};
+use crate::{
+    lock::LockFormat,
+    model::{InterchangeProjectInfoRaw, InterchangeProjectUsageRaw},
+    project::utils::{deserialize_unix_path, serialize_unix_path},
+    solve::markers::Markers,
+    stdlib::IncludeStd,
+};
 
 #[cfg(feature = "filesystem")]
 pub mod local_fs;
 
 // TODO: validate IRIs and paths
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Config {
+    /// Canonical IRI of the project in the directory of this file, set by
+    /// `sysand init --iri`. Publishing checks that it matches the
+    /// `pkg:sysand` IRI of the published project
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub iri: Option<String>,
     #[serde(rename = "index", skip_serializing_if = "Vec::is_empty", default)]
     pub indexes: Vec<Index>,
     #[serde(rename = "project", skip_serializing_if = "Vec::is_empty", default)]
     pub projects: Vec<ConfigProject>,
+    /// Variant of a dependency to use, by IRI, set by `sysand add
+    /// --variant`. Variants are declared in the `variants` field of the
+    /// `.meta.json` of the dependency
+    #[serde(skip_serializing_if = "IndexMap::is_empty", default)]
+    pub variants: IndexMap<String, String>,
+    /// Why each usage of the project is needed, by IRI, set by `sysand add
+    /// --reason`. Shown by `sysand list` and `sysand why`
+    #[serde(skip_serializing_if = "IndexMap::is_empty", default)]
+    pub reasons: IndexMap<String, String>,
+    /// Default for `--include-std` in `lock`, `sync`, `sources` and `info`:
+    /// `true`, `false` or `"auto"` to process only the standard libraries
+    /// of the metamodel declared in `.meta.json`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub include_std: Option<IncludeStd>,
+    #[serde(rename = "post_build", skip_serializing_if = "Vec::is_empty", default)]
+    pub post_build: Vec<PostBuildHook>,
+    #[serde(skip_serializing_if = "BuildConfig::is_empty", default)]
+    pub build: BuildConfig,
+    #[serde(
+        rename = "install_location",
+        skip_serializing_if = "Vec::is_empty",
+        default
+    )]
+    pub install_locations: Vec<InstallLocation>,
+    #[serde(skip_serializing_if = "DiscoveryConfig::is_empty", default)]
+    pub discovery: DiscoveryConfig,
+    /// Directories whose projects are used as dependencies without being
+    /// installed, e.g. checked out by a build system. Relative paths are
+    /// relative to the project root. See
+    /// `discover::discover_source_root` for how projects are found
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub source_roots: Vec<String>,
+    /// Read-only environment directories, e.g. distributed with a tool
+    /// installation or managed by an admin. Locked projects installed in
+    /// them are used from there by `sysand sync` and `sysand sources`
+    /// instead of being installed in the local environment. Relative
+    /// paths are relative to the project root
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub system_envs: Vec<String>,
+    #[serde(skip_serializing_if = "CacheConfig::is_empty", default)]
+    pub cache: CacheConfig,
+    #[serde(skip_serializing_if = "EnvConfig::is_empty", default)]
+    pub env: EnvConfig,
+    #[serde(skip_serializing_if = "WorkspaceConfig::is_empty", default)]
+    pub workspace: WorkspaceConfig,
+    #[serde(skip_serializing_if = "LockConfig::is_empty", default)]
+    pub lock: LockConfig,
+    #[serde(skip_serializing_if = "NetworkConfig::is_empty", default)]
+    pub network: NetworkConfig,
+    #[serde(skip_serializing_if = "PublishConfig::is_empty", default)]
+    pub publish: PublishConfig,
+    #[serde(skip_serializing_if = "HooksConfig::is_empty", default)]
+    pub hooks: HooksConfig,
+    /// Information and metadata of the project in the directory of this
+    /// file, rendered into its `.project.json` and `.meta.json`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub manifest: Option<ProjectManifest>,
     // pub auth: Option<Vec<AuthSource>>,
 }
 
+/// Project information declared in the `[manifest]` table of
+/// `sysand.toml`, from which `.project.json` and the metamodel in
+/// `.meta.json` are rendered by `build`, `lock` and `normalize`. It is not
+/// called `[project]`, as `[[project]]` declares project sources.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct ProjectManifest {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub publisher: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub description: Option<String>,
+    pub version: String,
+    /// SPDX license expression
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub license: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub maintainer: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub website: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub topic: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub usage: Vec<ManifestUsage>,
+    /// Metamodel IRI. If not set, the one in `.meta.json` is kept
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub metamodel: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct ManifestUsage {
+    pub resource: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub version_constraint: Option<String>,
+    /// Condition on the markers that the usage applies under
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub when: Option<String>,
+    /// Canonical project hash the used project must have, as `sha256:<hex>`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub hash: Option<String>,
+}
+
+impl ProjectManifest {
+    /// Project information declared by the manifest
+    pub fn to_info(&self) -> InterchangeProjectInfoRaw {
+        InterchangeProjectInfoRaw {
+            name: self.name.clone(),
+            publisher: self.publisher.clone(),
+            description: self.description.clone(),
+            version: self.version.clone(),
+            license: self.license.clone(),
+            maintainer: self.maintainer.clone(),
+            website: self.website.clone(),
+            topic: self.topic.clone(),
+            usage: self
+                .usage
+                .iter()
+                .map(|usage| InterchangeProjectUsageRaw::Resource {
+                    resource: usage.resource.clone(),
+                    version_constraint: usage.version_constraint.clone(),
+                    when: usage.when.clone(),
+                    hash: usage.hash.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Which files are packaged when building a KPAR.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct BuildConfig {
+    /// Globs of source files to package, relative to the project root.
+    /// If set, the files matching them must be exactly the files in the
+    /// project metadata, unless building with `--allow-dirty`
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub include: Vec<String>,
+    /// Globs of source files not to package, even if they are in the
+    /// project metadata or match `include`
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub exclude: Vec<String>,
+    /// Method to compress the files in the KPAR, e.g. `deflated`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub compression: Option<String>,
+    /// Compression level, the range of which depends on the method
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub compression_level: Option<i64>,
+}
+
+impl BuildConfig {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Compression method set in `compression`, if any
+    #[cfg(feature = "filesystem")]
+    pub fn compression_method(
+        &self,
+    ) -> Result<Option<KparCompressionMethod>, CompressionMethodParseError> {
+        self.compression
+            .as_deref()
+            .map(|method| KparCompressionMethod::try_from(method.to_uppercase()))
+            .transpose()
+    }
+}
+
+/// How the current project is found when running commands.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct DiscoveryConfig {
+    /// How many parent directories of the current directory to search for
+    /// a project. `0` only considers the current directory. Unlimited if
+    /// not set
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max_depth: Option<usize>,
+    /// What commands that need a project do when none is found
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub when_missing: Option<WhenMissing>,
+}
+
+impl DiscoveryConfig {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Cache of project metadata fetched over HTTP.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct CacheConfig {
+    /// For how many seconds cached metadata is used without checking
+    /// whether it has changed. `0` always checks
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub ttl: Option<u64>,
+    /// URLs of environment directories shared over HTTP, e.g. by a team.
+    /// Projects installed in them are read from there instead of being
+    /// downloaded from the indexes
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub shared_envs: Vec<String>,
+}
+
+impl CacheConfig {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// How the local environment stores installed projects.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct EnvConfig {
+    /// Storage of newly installed projects, `directory` (default), `kpar`
+    /// or `encrypted`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub storage: Option<EnvStorage>,
+    /// File holding the key of projects stored encrypted, as 64
+    /// hexadecimal characters. Relative paths are relative to the current
+    /// directory. The `SYSAND_ENV_KEY` environment variable takes
+    /// precedence
+    #[cfg(feature = "encryption")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub key_file: Option<String>,
+}
+
+impl EnvConfig {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// How a project installed in the local environment is stored
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum EnvStorage {
+    /// All files of the project are extracted into its directory
+    #[default]
+    Directory,
+    /// The files of the project are kept in a compressed KPAR in its
+    /// directory, with only `.project.json` and `.meta.json` extracted.
+    /// Saves disk space and inodes; tools that need the files on disk
+    /// can extract them with `sysand sources --materialize`
+    Kpar,
+    /// The source files of the project are encrypted with the key set by
+    /// `key_file`, so that other users of the machine cannot read them.
+    /// `.project.json` and `.meta.json` are kept in plain text
+    #[cfg(feature = "encryption")]
+    Encrypted,
+}
+
+/// How the projects of a workspace are locked and installed.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct WorkspaceConfig {
+    /// Lock all workspace projects together in a single lockfile in the
+    /// workspace root, so that they share dependency versions and the
+    /// workspace environment
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub shared_lock: Option<bool>,
+}
+
+impl WorkspaceConfig {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// How lockfiles are written.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct LockConfig {
+    /// Format of written lockfiles, `toml` (default) or `json`. Lockfiles
+    /// in either format are always read
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub format: Option<LockFormat>,
+    /// Markers that conditional usages are evaluated against, e.g.
+    /// `{ tool = "syside" }`. Overridden by `--marker`
+    #[serde(skip_serializing_if = "Markers::is_empty", default)]
+    pub markers: Markers,
+}
+
+impl LockConfig {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// How sysand connects to indexes and other remote sources.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct NetworkConfig {
+    /// URL of the proxy used for all HTTP(S) requests, e.g.
+    /// `http://proxy.example.com:8080`. If not set, the proxies in the
+    /// `HTTPS_PROXY`, `HTTP_PROXY` and `ALL_PROXY` environment variables
+    /// are used
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub proxy: Option<String>,
+    /// Hosts, domains and IP ranges connected to directly instead of
+    /// through the proxy, in addition to the ones in `NO_PROXY`
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub no_proxy: Vec<String>,
+    /// PEM file of CA certificates trusted in addition to the system trust
+    /// store, e.g. of a private CA. Relative paths are relative to the
+    /// current directory
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub ca_bundle: Option<String>,
+    /// Maximum number of requests sent to the same host at once. Defaults
+    /// to 8
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max_concurrent_requests: Option<usize>,
+    /// Maximum number of requests per second sent to the same host. Not
+    /// limited by default
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub requests_per_second: Option<f64>,
+    /// Longest wait, in seconds, before retrying a request that a host
+    /// answered with 429 Too Many Requests, as asked for by its
+    /// `Retry-After` header. Requests asked to wait longer fail. Defaults
+    /// to 60
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max_retry_after: Option<u64>,
+    /// Limits for requests to specific hosts, e.g. `index.example.com`,
+    /// taking precedence over the ones above
+    #[serde(skip_serializing_if = "IndexMap::is_empty", default)]
+    pub hosts: IndexMap<String, HostLimits>,
+}
+
+impl NetworkConfig {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Limits for requests to a single host, see [`NetworkConfig`]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct HostLimits {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max_concurrent_requests: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub requests_per_second: Option<f64>,
+}
+
+/// Policy checks a KPAR must pass before `sysand publish` uploads it, in
+/// addition to the checks required by the index. See
+/// `commands::policy::PublishPolicy`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct PublishConfig {
+    /// Require the project to have a license
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub require_license: Option<bool>,
+    /// Require the KPAR to contain `README.md`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub require_readme: Option<bool>,
+    /// Require every source file to have a checksum other than `NONE`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub require_checksums: Option<bool>,
+    /// Forbid usages of `file:` and `git+` IRIs, which cannot be resolved
+    /// on other machines
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub forbid_local_usages: Option<bool>,
+    /// Maximum size of the KPAR in bytes
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max_archive_size: Option<u64>,
+    /// Regular expressions project metadata fields must match
+    #[serde(
+        rename = "metadata_check",
+        skip_serializing_if = "Vec::is_empty",
+        default
+    )]
+    pub metadata_checks: Vec<MetadataCheck>,
+}
+
+impl PublishConfig {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Requirement that a field of `.project.json` matches a regular
+/// expression. Fields with several values, e.g. `topic`, must match with
+/// each value. A missing field does not match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct MetadataCheck {
+    pub field: MetadataField,
+    /// Regular expression, matched against the whole value
+    pub pattern: String,
+    /// Explanation shown when the field does not match
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub message: Option<String>,
+}
+
+/// Field of `.project.json` checked by a [`MetadataCheck`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum MetadataField {
+    Name,
+    Publisher,
+    Description,
+    Version,
+    License,
+    Maintainer,
+    Website,
+    Topic,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum WhenMissing {
+    /// Fail
+    #[default]
+    Error,
+    /// Create a minimal project in the current directory
+    Here,
+    /// Ask whether to create a minimal project in the current directory if
+    /// running interactively, otherwise fail
+    Prompt,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct ConfigProject {
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub identifiers: Vec<String>,
@@ -30,6 +467,7 @@ pub struct ConfigProject {
 }
 
 #[derive(Clone, Eq, Debug, Deserialize, Ord, PartialEq, PartialOrd, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum OverrideSource {
     // Path must be a Unix path relative to workspace root
@@ -38,6 +476,7 @@ pub enum OverrideSource {
             deserialize_with = "deserialize_unix_path",
             serialize_with = "serialize_unix_path"
         )]
+        #[cfg_attr(feature = "json-schema", schemars(with = "String"))]
         editable: Utf8UnixPathBuf,
     },
     // Path must be a Unix path relative to workspace root
@@ -46,6 +485,7 @@ pub enum OverrideSource {
             deserialize_with = "deserialize_unix_path",
             serialize_with = "serialize_unix_path"
         )]
+        #[cfg_attr(feature = "json-schema", schemars(with = "String"))]
         src_path: Utf8UnixPathBuf,
     },
     // Path must be a Unix path relative to workspace root
@@ -54,6 +494,7 @@ pub enum OverrideSource {
             deserialize_with = "deserialize_unix_path",
             serialize_with = "serialize_unix_path"
         )]
+        #[cfg_attr(feature = "json-schema", schemars(with = "String"))]
         kpar_path: Utf8UnixPathBuf,
     },
     RemoteKpar {
@@ -106,17 +547,128 @@ impl OverrideSource {
 impl Config {
     pub fn merge(&mut self, config: Config) {
         let Config {
+            iri,
             mut indexes,
             mut projects,
+            variants,
+            reasons,
+            include_std,
+            mut post_build,
+            mut build,
+            mut install_locations,
+            discovery,
+            mut source_roots,
+            mut system_envs,
+            mut cache,
+            env,
+            workspace,
+            lock,
+            mut network,
+            publish,
+            mut hooks,
+            manifest,
         } = config;
         self.indexes.append(&mut indexes);
         self.projects.append(&mut projects);
+        self.post_build.append(&mut post_build);
+        self.build.include.append(&mut build.include);
+        self.build.exclude.append(&mut build.exclude);
+        self.install_locations.append(&mut install_locations);
+        self.hooks.pre_build.append(&mut hooks.pre_build);
+        self.hooks.post_sync.append(&mut hooks.post_sync);
+        self.source_roots.append(&mut source_roots);
+        self.system_envs.append(&mut system_envs);
+        self.cache.shared_envs.append(&mut cache.shared_envs);
+        // Settings already present take precedence
+        self.iri = self.iri.take().or(iri);
+        self.manifest = self.manifest.take().or(manifest);
+        for (iri, variant) in variants {
+            self.variants.entry(iri).or_insert(variant);
+        }
+        for (iri, reason) in reasons {
+            self.reasons.entry(iri).or_insert(reason);
+        }
+        self.include_std = self.include_std.or(include_std);
+        self.discovery.max_depth = self.discovery.max_depth.or(discovery.max_depth);
+        self.discovery.when_missing = self.discovery.when_missing.or(discovery.when_missing);
+        self.cache.ttl = self.cache.ttl.or(cache.ttl);
+        self.env.storage = self.env.storage.or(env.storage);
+        #[cfg(feature = "encryption")]
+        {
+            self.env.key_file = self.env.key_file.take().or(env.key_file);
+        }
+        self.workspace.shared_lock = self.workspace.shared_lock.or(workspace.shared_lock);
+        self.lock.format = self.lock.format.or(lock.format);
+        for (attribute, value) in lock.markers {
+            self.lock.markers.entry(attribute).or_insert(value);
+        }
+        self.network.proxy = self.network.proxy.take().or(network.proxy);
+        self.network.no_proxy.append(&mut network.no_proxy);
+        self.network.ca_bundle = self.network.ca_bundle.take().or(network.ca_bundle);
+        self.network.max_concurrent_requests = self
+            .network
+            .max_concurrent_requests
+            .or(network.max_concurrent_requests);
+        self.network.requests_per_second = self
+            .network
+            .requests_per_second
+            .or(network.requests_per_second);
+        self.network.max_retry_after = self.network.max_retry_after.or(network.max_retry_after);
+        for (host, limits) in network.hosts {
+            self.network.hosts.entry(host).or_insert(limits);
+        }
+        self.build.compression = self.build.compression.take().or(build.compression);
+        self.build.compression_level = self.build.compression_level.or(build.compression_level);
+        let PublishConfig {
+            require_license,
+            require_readme,
+            require_checksums,
+            forbid_local_usages,
+            max_archive_size,
+            mut metadata_checks,
+        } = publish;
+        self.publish.require_license = self.publish.require_license.or(require_license);
+        self.publish.require_readme = self.publish.require_readme.or(require_readme);
+        self.publish.require_checksums = self.publish.require_checksums.or(require_checksums);
+        self.publish.forbid_local_usages = self.publish.forbid_local_usages.or(forbid_local_usages);
+        self.publish.max_archive_size = self.publish.max_archive_size.or(max_archive_size);
+        self.publish.metadata_checks.append(&mut metadata_checks);
 
         // if let Some(auth) = config.auth {
         //     self.auth = Some(auth.clone());
         // }
     }
 
+    /// Fixed install locations by IRI, with relative paths resolved
+    /// against `root`. If an IRI has several locations, the first one is used
+    pub fn install_locations<P: AsRef<Utf8Path>>(&self, root: P) -> IndexMap<String, Utf8PathBuf> {
+        let mut locations = IndexMap::new();
+        for location in &self.install_locations {
+            locations
+                .entry(location.iri.clone())
+                .or_insert_with(|| root.as_ref().join(&location.path));
+        }
+        locations
+    }
+
+    /// Directories of the system environments in `system_envs`, with
+    /// relative paths resolved against `root`
+    pub fn system_env_paths<P: AsRef<Utf8Path>>(&self, root: P) -> Vec<Utf8PathBuf> {
+        self.system_envs
+            .iter()
+            .map(|path| root.as_ref().join(path))
+            .collect()
+    }
+
+    /// URLs of the shared environments in `cache.shared_envs`
+    pub fn shared_env_urls(&self) -> Result<Vec<Url>, url::ParseError> {
+        self.cache
+            .shared_envs
+            .iter()
+            .map(|url| Url::parse(url))
+            .collect()
+    }
+
     pub fn index_urls(
         &self,
         index_urls: Vec<String>,
@@ -176,11 +728,84 @@ impl Config {
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Index {
     pub name: Option<String>,
     pub url: String,
     // pub explicit: Option<bool>,
     pub default: Option<bool>,
+    /// PEM file with the client certificate presented to the index when it
+    /// requires mutual TLS. May also contain the private key. Relative
+    /// paths are relative to the current directory
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub client_cert: Option<String>,
+    /// PEM file with the private key of `client_cert`, if it is not in the
+    /// same file
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub client_key: Option<String>,
+    /// Command run by the shell to obtain a short-lived bearer token for the
+    /// index, e.g. `gcloud auth print-access-token`. It is run once per
+    /// command, when the index first needs authentication
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub credential_helper: Option<String>,
+    /// IRI patterns, e.g. `urn:mycorp:*`, resolved only against this index
+    /// and never against other indexes. `*` matches any characters
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub scope: Vec<String>,
+}
+
+/// External program run on a freshly built KPAR. The path to the
+/// archive is passed as the last argument; the program may rewrite
+/// the archive in place.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct PostBuildHook {
+    pub name: Option<String>,
+    pub command: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub args: Vec<String>,
+}
+
+/// Commands run at points of the project lifecycle, in order. Each is run
+/// in the project root, see `commands::hooks::run_hooks`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct HooksConfig {
+    /// Run by `sysand build` before building, e.g. to generate source files
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub pre_build: Vec<Hook>,
+    /// Run by `sysand sync` after installing the dependencies, e.g. to
+    /// validate the model
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub post_sync: Vec<Hook>,
+}
+
+impl HooksConfig {
+    pub fn is_empty(&self) -> bool {
+        self.pre_build.is_empty() && self.post_sync.is_empty()
+    }
+}
+
+/// External program run by a lifecycle hook
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct Hook {
+    pub name: Option<String>,
+    pub command: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub args: Vec<String>,
+}
+
+/// Directory in which a dependency is installed in addition to the local
+/// environment, e.g. because another tool expects it there
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+pub struct InstallLocation {
+    pub iri: String,
+    /// Relative paths are relative to the project root. The directory is
+    /// replaced on every install, so it must be either empty or contain only
+    /// the installed project
+    pub path: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
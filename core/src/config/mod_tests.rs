@@ -3,7 +3,16 @@
 
 use url::Url;
 
-use crate::config::{Config, ConfigProject, Index, OverrideSource};
+#[cfg(feature = "filesystem")]
+use crate::build::KparCompressionMethod;
+use crate::{
+    config::{
+        BuildConfig, CacheConfig, Config, ConfigProject, DiscoveryConfig, HostLimits, Index,
+        InstallLocation, LockConfig, NetworkConfig, OverrideSource, WhenMissing, WorkspaceConfig,
+    },
+    lock::LockFormat,
+    stdlib::IncludeStd,
+};
 
 #[test]
 fn default_config() {
@@ -23,6 +32,27 @@ fn default_index() {
     assert_eq!(index.default, None);
 }
 
+#[test]
+fn parse_index_client_cert() {
+    let config: Config = toml::from_str(
+        "[[index]]\nurl = \"https://index.corp\"\nclient_cert = \"client.pem\"\nclient_key = \"client.key\"\n",
+    )
+    .unwrap();
+    assert_eq!(
+        config.indexes,
+        vec![Index {
+            url: "https://index.corp".into(),
+            client_cert: Some("client.pem".into()),
+            client_key: Some("client.key".into()),
+            ..Default::default()
+        }]
+    );
+    assert_eq!(
+        toml::from_str::<Config>(&toml::to_string(&config).unwrap()).unwrap(),
+        config
+    );
+}
+
 #[test]
 fn merge() {
     let mut defaults = Config::default();
@@ -37,13 +67,291 @@ fn merge() {
                 src_path: "./path/to project".into(),
             }],
         }],
-        // auth: None,
+        ..Default::default()
     };
     defaults.merge(config.clone());
 
     assert_eq!(defaults, config);
 }
 
+#[test]
+fn merge_discovery_keeps_existing() {
+    let mut config = Config {
+        discovery: DiscoveryConfig {
+            max_depth: Some(0),
+            when_missing: None,
+        },
+        ..Default::default()
+    };
+    config.merge(Config {
+        discovery: DiscoveryConfig {
+            max_depth: Some(3),
+            when_missing: Some(WhenMissing::Prompt),
+        },
+        ..Default::default()
+    });
+
+    assert_eq!(
+        config.discovery,
+        DiscoveryConfig {
+            max_depth: Some(0),
+            when_missing: Some(WhenMissing::Prompt),
+        }
+    );
+}
+
+#[test]
+fn parse_and_merge_cache() {
+    let mut config: Config =
+        toml::from_str("[cache]\nttl = 60\nshared_envs = [\"http://a.lan/.sysand\"]\n").unwrap();
+    assert_eq!(
+        config.cache,
+        CacheConfig {
+            ttl: Some(60),
+            shared_envs: vec!["http://a.lan/.sysand".into()],
+        }
+    );
+
+    config.merge(Config {
+        cache: CacheConfig {
+            ttl: Some(0),
+            shared_envs: vec!["http://b.lan/.sysand".into()],
+        },
+        ..Default::default()
+    });
+    assert_eq!(config.cache.ttl, Some(60));
+    assert_eq!(
+        config.shared_env_urls().unwrap(),
+        [
+            url::Url::parse("http://a.lan/.sysand").unwrap(),
+            url::Url::parse("http://b.lan/.sysand").unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn parse_and_merge_workspace() {
+    let mut config: Config = toml::from_str("[workspace]\nshared_lock = true\n").unwrap();
+    assert_eq!(
+        config.workspace,
+        WorkspaceConfig {
+            shared_lock: Some(true)
+        }
+    );
+
+    config.merge(Config {
+        workspace: WorkspaceConfig {
+            shared_lock: Some(false),
+        },
+        ..Default::default()
+    });
+    assert_eq!(config.workspace.shared_lock, Some(true));
+
+    let mut config = Config::default();
+    config.merge(toml::from_str("[workspace]\nshared_lock = false\n").unwrap());
+    assert_eq!(config.workspace.shared_lock, Some(false));
+}
+
+#[test]
+fn parse_and_merge_lock() {
+    let mut config: Config = toml::from_str("[lock]\nformat = \"json\"\n").unwrap();
+    assert_eq!(
+        config.lock,
+        LockConfig {
+            format: Some(LockFormat::Json),
+            ..Default::default()
+        }
+    );
+
+    config.merge(toml::from_str("[lock]\nformat = \"toml\"\n").unwrap());
+    assert_eq!(config.lock.format, Some(LockFormat::Json));
+
+    config.merge(
+        toml::from_str("[lock]\nmarkers = { tool = \"syside\", variant = \"full\" }\n").unwrap(),
+    );
+    config.merge(toml::from_str("[lock]\nmarkers = { tool = \"other\" }\n").unwrap());
+    assert_eq!(
+        config.lock.markers,
+        [("tool", "syside"), ("variant", "full")]
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .into()
+    );
+
+    assert!(toml::from_str::<Config>("[lock]\nformat = \"yaml\"\n").is_err());
+}
+
+#[test]
+fn parse_and_merge_include_std() {
+    for (value, include) in [
+        ("true", IncludeStd::Always),
+        ("false", IncludeStd::Never),
+        ("\"auto\"", IncludeStd::Auto),
+    ] {
+        let config: Config = toml::from_str(&format!("include_std = {value}\n")).unwrap();
+        assert_eq!(config.include_std, Some(include));
+        assert_eq!(
+            toml::to_string(&config).unwrap(),
+            format!("include_std = {value}\n")
+        );
+    }
+
+    let mut config: Config = toml::from_str("include_std = \"auto\"\n").unwrap();
+    config.merge(toml::from_str("include_std = true\n").unwrap());
+    assert_eq!(config.include_std, Some(IncludeStd::Auto));
+
+    assert!(toml::from_str::<Config>("include_std = \"sometimes\"\n").is_err());
+}
+
+#[test]
+fn parse_and_merge_network() {
+    let mut config: Config = toml::from_str(
+        "[network]\nproxy = \"http://proxy.lan:3128\"\nno_proxy = [\"index.lan\"]\n",
+    )
+    .unwrap();
+    assert_eq!(
+        config.network,
+        NetworkConfig {
+            proxy: Some("http://proxy.lan:3128".into()),
+            no_proxy: vec!["index.lan".into()],
+            ..Default::default()
+        }
+    );
+
+    config.merge(
+        toml::from_str(
+            "[network]\nproxy = \"http://other.lan:3128\"\nno_proxy = [\".corp\"]\nca_bundle = \"ca.pem\"\n",
+        )
+        .unwrap(),
+    );
+    assert_eq!(
+        config.network,
+        NetworkConfig {
+            proxy: Some("http://proxy.lan:3128".into()),
+            no_proxy: vec!["index.lan".into(), ".corp".into()],
+            ca_bundle: Some("ca.pem".into()),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn parse_and_merge_network_limits() {
+    let mut config: Config = toml::from_str(
+        "[network]\nrequests_per_second = 2.5\n[network.hosts.\"index.lan\"]\nmax_concurrent_requests = 2\n",
+    )
+    .unwrap();
+    config.merge(
+        toml::from_str(
+            "[network]\nrequests_per_second = 10\nmax_retry_after = 5\n[network.hosts.\"index.lan\"]\nrequests_per_second = 1\n[network.hosts.\"other.lan\"]\nrequests_per_second = 1\n",
+        )
+        .unwrap(),
+    );
+    assert_eq!(config.network.requests_per_second, Some(2.5));
+    assert_eq!(config.network.max_retry_after, Some(5));
+    assert_eq!(
+        config.network.hosts.get("index.lan"),
+        Some(&HostLimits {
+            max_concurrent_requests: Some(2),
+            requests_per_second: None,
+        })
+    );
+    assert!(config.network.hosts.contains_key("other.lan"));
+}
+
+#[test]
+fn parse_and_merge_build() {
+    let mut config: Config =
+        toml::from_str("[build]\ninclude = [\"**/*.sysml\"]\nexclude = [\"drafts/**\"]\n").unwrap();
+    assert_eq!(
+        config.build,
+        BuildConfig {
+            include: vec!["**/*.sysml".to_string()],
+            exclude: vec!["drafts/**".to_string()],
+            ..Default::default()
+        }
+    );
+
+    config.merge(Config {
+        build: BuildConfig {
+            include: vec!["**/*.kerml".to_string()],
+            exclude: vec![],
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    assert_eq!(config.build.include, ["**/*.sysml", "**/*.kerml"]);
+    assert_eq!(config.build.exclude, ["drafts/**"]);
+}
+
+#[cfg(feature = "filesystem")]
+#[test]
+fn build_compression() {
+    let mut config: Config = toml::from_str("[build]\ncompression = \"stored\"\n").unwrap();
+    assert_eq!(
+        config.build.compression_method().unwrap(),
+        Some(KparCompressionMethod::Stored)
+    );
+
+    config.merge(Config {
+        build: BuildConfig {
+            compression: Some("deflated".to_string()),
+            compression_level: Some(9),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    assert_eq!(config.build.compression.as_deref(), Some("stored"));
+    assert_eq!(config.build.compression_level, Some(9));
+
+    let config: Config = toml::from_str("[build]\ncompression = \"lzma\"\n").unwrap();
+    assert!(config.build.compression_method().is_err());
+}
+
+#[test]
+fn install_locations_first_wins() {
+    let mut config: Config = toml::from_str(
+        r#"
+[[install_location]]
+iri = "urn:kpar:a"
+path = "tool/libs/a"
+"#,
+    )
+    .unwrap();
+    config.merge(Config {
+        install_locations: vec![
+            InstallLocation {
+                iri: "urn:kpar:a".to_string(),
+                path: "elsewhere".to_string(),
+            },
+            InstallLocation {
+                iri: "urn:kpar:b".to_string(),
+                path: "/opt/tool/b".to_string(),
+            },
+        ],
+        ..Default::default()
+    });
+
+    let locations = config.install_locations("/project");
+    assert_eq!(locations.len(), 2);
+    assert_eq!(locations["urn:kpar:a"], "/project/tool/libs/a");
+    assert_eq!(locations["urn:kpar:b"], "/opt/tool/b");
+}
+
+#[test]
+fn parse_discovery() {
+    let config: Config = toml::from_str(
+        r#"
+[discovery]
+max_depth = 2
+when_missing = "here"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(config.discovery.max_depth, Some(2));
+    assert_eq!(config.discovery.when_missing, Some(WhenMissing::Here));
+}
+
 #[test]
 fn index_urls_without_default() {
     let config = Config {
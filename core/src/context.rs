@@ -10,6 +10,8 @@ use crate::{
     workspace::Workspace,
 };
 
+use crate::solve::{markers::Markers, pubgrub::VersionStrategy};
+
 #[derive(Debug, Default)]
 pub struct ProjectContext {
     /// Current workspace if found
@@ -26,4 +28,8 @@ pub struct ProjectContext {
     /// the environment metadata file exists.
     #[cfg(feature = "filesystem")]
     pub env: Option<LocalDirectoryEnvironment>,
+    /// Markers that conditional usages are evaluated against when locking
+    pub markers: Markers,
+    /// Versions preferred when locking
+    pub version_strategy: VersionStrategy,
 }
@@ -1,14 +1,22 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
+use std::{fmt, io};
+
 use camino::{Utf8Path, Utf8PathBuf};
+use fluent_uri::Iri;
+use thiserror::Error;
 use typed_path::Utf8UnixPathBuf;
 
 use crate::{
     project::{
-        local_src::LocalSrcProject,
+        ProjectRead,
+        local_kpar::{KparInnerPath, LocalKParError, LocalKParProject},
+        local_src::{LocalSrcError, LocalSrcProject},
         utils::{FsIoError, ToPathBuf, wrapfs},
     },
+    purl::sysand_purl,
+    walk,
     workspace::{Workspace, WorkspaceReadError},
 };
 
@@ -18,16 +26,26 @@ fn is_project_file(path: &Utf8Path) -> Result<bool, Box<FsIoError>> {
 
 pub fn discover_project<P: AsRef<Utf8Path>>(
     working_directory: P,
+) -> Result<Option<LocalSrcProject>, Box<FsIoError>> {
+    discover_project_within(working_directory, None)
+}
+
+/// Like [`discover_project`], but searches at most `max_depth` parent
+/// directories of `working_directory` (all of them if `None`).
+pub fn discover_project_within<P: AsRef<Utf8Path>>(
+    working_directory: P,
+    max_depth: Option<usize>,
 ) -> Result<Option<LocalSrcProject>, Box<FsIoError>> {
     log::debug!(
         "trying to discover project in `{}`",
         working_directory.as_ref()
     );
-    let project = discover(working_directory, is_project_file)?.map(|path| LocalSrcProject {
-        nominal_path: Some(Utf8UnixPathBuf::from(".")),
-        project_path: path,
-        expected_checksum: None,
-    });
+    let project =
+        discover(working_directory, max_depth, is_project_file)?.map(|path| LocalSrcProject {
+            nominal_path: Some(Utf8UnixPathBuf::from(".")),
+            project_path: path,
+            expected_checksum: None,
+        });
     Ok(project)
 }
 
@@ -40,7 +58,7 @@ pub fn discover_workspace<P: AsRef<Utf8Path>>(
         "trying to discover workspace in `{}`",
         working_directory.as_ref()
     );
-    let path = match discover(working_directory, |path| {
+    let path = match discover(working_directory, None, |path| {
         wrapfs::is_file(path.join(".workspace.json"))
     })? {
         Some(p) => p,
@@ -50,14 +68,30 @@ pub fn discover_workspace<P: AsRef<Utf8Path>>(
 }
 
 /// Discover a directory that satisfies `predicate`. Tries
-/// `working_directory` and all its ancestors.
+/// `working_directory` and its ancestors, at most `max_depth` levels up.
 fn discover<P: AsRef<Utf8Path>, F: Fn(&Utf8Path) -> Result<bool, Box<FsIoError>>>(
     working_directory: P,
+    max_depth: Option<usize>,
     predicate: F,
 ) -> Result<Option<Utf8PathBuf>, Box<FsIoError>> {
+    Ok(discover_traced(working_directory, max_depth, predicate)?.ok())
+}
+
+/// Like [`discover`], but reports why nothing was found
+fn discover_traced<P: AsRef<Utf8Path>, F: Fn(&Utf8Path) -> Result<bool, Box<FsIoError>>>(
+    working_directory: P,
+    max_depth: Option<usize>,
+    predicate: F,
+) -> Result<Result<Utf8PathBuf, StopReason>, Box<FsIoError>> {
     let mut current = working_directory.to_path_buf();
+    let mut depth = 0;
 
     while !predicate(&current)? {
+        if max_depth.is_some_and(|max| depth >= max) {
+            log::debug!("discover: not found within {depth} parent directories");
+            return Ok(Err(StopReason::MaxDepth));
+        }
+        depth += 1;
         match current.parent() {
             Some(parent) if parent.as_str().is_empty() => {
                 log::debug!("discover: hit empty relative path, trying to canonicalize");
@@ -68,11 +102,12 @@ fn discover<P: AsRef<Utf8Path>, F: Fn(&Utf8Path) -> Result<bool, Box<FsIoError>>
                             log::debug!(
                                 "discover: canonicalized path `{current_canonical}` has no parent either"
                             );
-                            return Ok(None);
+                            return Ok(Err(StopReason::FilesystemRoot));
                         }
                     },
                     Err(e) => {
                         log::debug!("discover: unable to canonicalize path `{current}`: {e}");
+                        return Ok(Err(StopReason::from_io(&e)));
                     }
                 }
             }
@@ -82,11 +117,345 @@ fn discover<P: AsRef<Utf8Path>, F: Fn(&Utf8Path) -> Result<bool, Box<FsIoError>>
             }
             None => {
                 log::debug!("discover: not found");
-                return Ok(None);
+                return Ok(Err(StopReason::FilesystemRoot));
             }
         }
     }
     log::debug!("discover: found in `{current}`");
 
-    Ok(Some(current))
+    Ok(Ok(current))
+}
+
+/// Why discovery did not search further
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    /// Reached the root of the filesystem
+    FilesystemRoot,
+    /// Reached the depth limit
+    MaxDepth,
+    PermissionDenied,
+    /// Any other I/O error, with its message
+    Io(String),
+}
+
+impl StopReason {
+    fn from_io(err: &io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+            _ => Self::Io(err.to_string()),
+        }
+    }
+
+    fn from_fs(err: &FsIoError) -> Self {
+        match err {
+            FsIoError::IsFile(_, e)
+            | FsIoError::IsDir(_, e)
+            | FsIoError::Metadata(_, e)
+            | FsIoError::ReadDir(_, e) => Self::from_io(e),
+            _ => Self::Io(err.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopReason::FilesystemRoot => f.write_str("reached the filesystem root"),
+            StopReason::MaxDepth => f.write_str("reached the depth limit"),
+            StopReason::PermissionDenied => f.write_str("permission denied"),
+            StopReason::Io(msg) => f.write_str(msg),
+        }
+    }
+}
+
+/// Result of [`trace_project`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Discovery {
+    Found(Utf8PathBuf),
+    NotFound(StopReason),
+}
+
+/// Like [`discover_project_within`], but returns the project root, or why
+/// the search stopped without finding one. Directories that cannot be read
+/// stop the search instead of failing it
+pub fn trace_project<P: AsRef<Utf8Path>>(
+    working_directory: P,
+    max_depth: Option<usize>,
+) -> Discovery {
+    match discover_traced(working_directory, max_depth, is_project_file) {
+        Ok(Ok(root)) => Discovery::Found(root),
+        Ok(Err(reason)) => Discovery::NotFound(reason),
+        Err(err) => Discovery::NotFound(StopReason::from_fs(&err)),
+    }
+}
+
+/// Options of [`walk_projects`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WalkOptions {
+    /// How many levels of subdirectories of the root to search, all of
+    /// them if `None`
+    pub max_depth: Option<usize>,
+    /// Search inside projects for nested projects
+    pub nested: bool,
+    /// Search directories whose name starts with `.`
+    pub hidden: bool,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            nested: true,
+            hidden: false,
+        }
+    }
+}
+
+/// Project found by [`walk_projects`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WalkedProject {
+    /// Path of the project directory, relative to the root
+    pub path: Utf8UnixPathBuf,
+    /// Path of the innermost project containing this one, if it is nested
+    pub parent: Option<Utf8UnixPathBuf>,
+}
+
+/// Directory that [`walk_projects`] did not search
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WalkStop {
+    /// Path of the directory, relative to the root
+    pub path: Utf8UnixPathBuf,
+    pub reason: StopReason,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WalkReport {
+    /// Projects in the order they were found, parents before nested ones
+    pub projects: Vec<WalkedProject>,
+    /// Directories containing `.workspace.json`
+    pub workspaces: Vec<Utf8UnixPathBuf>,
+    pub stops: Vec<WalkStop>,
 }
+
+/// Find all projects in `root` and its subdirectories, sorted by path.
+/// Symbolic links, environment directories and directories skipped by
+/// [`walk::walker`] are not followed. Subdirectories that cannot be read
+/// are reported in [`WalkReport::stops`], only failing to read `root` is an
+/// error
+pub fn walk_projects<P: AsRef<Utf8Path>>(
+    root: P,
+    options: &WalkOptions,
+) -> Result<WalkReport, Box<FsIoError>> {
+    let root = root.as_ref();
+    log::debug!("walking projects in `{root}`");
+    let hidden = options.hidden;
+    let walker = walk::walk_builder(root, move |e| {
+        e.file_type().is_some_and(|t| t.is_dir())
+            && (hidden || !e.file_name().to_string_lossy().starts_with('.'))
+    })
+    .max_depth(options.max_depth.map(|max| max + 1))
+    .build();
+    let mut report = WalkReport::default();
+    // Projects containing the current directory, innermost last
+    let mut ancestors: Vec<(usize, Utf8UnixPathBuf)> = vec![];
+    // Depth of the directory whose subdirectories are not searched
+    let mut pruned: Option<usize> = None;
+    let stop = |report: &mut WalkReport, path: Utf8UnixPathBuf, reason: StopReason| {
+        log::debug!("walk: not searching `{path}`: {reason}");
+        report.stops.push(WalkStop { path, reason });
+    };
+    let relative = |path: &Utf8Path| match walk::relative_path(root, path.as_std_path()) {
+        Some(relative) if relative.is_empty() => Some(Utf8UnixPathBuf::from(".")),
+        relative => relative.map(Utf8UnixPathBuf::from),
+    };
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) if err.depth().is_none_or(|d| d == 0) => {
+                return Err(walk::walk_error(root, err));
+            }
+            Err(err) => {
+                let (path, err) = walk::split_error(root, err);
+                if let Some(path) = relative(&path) {
+                    stop(&mut report, path, StopReason::from_io(&err));
+                }
+                continue;
+            }
+        };
+        let depth = entry.depth();
+        if pruned.is_some_and(|d| depth > d) {
+            continue;
+        }
+        pruned = None;
+        let Some(dir) = Utf8Path::from_path(entry.path()) else {
+            continue;
+        };
+        let Some(path) = relative(dir) else {
+            continue;
+        };
+        if options.max_depth.is_some_and(|max| depth > max) {
+            // Subdirectory beyond the limit: its parent was not searched
+            // completely
+            let parent = path
+                .parent()
+                .filter(|p| !p.as_str().is_empty())
+                .map_or_else(|| Utf8UnixPathBuf::from("."), |p| p.to_owned());
+            stop(&mut report, parent, StopReason::MaxDepth);
+            pruned = Some(depth - 1);
+            continue;
+        }
+        ancestors.retain(|(d, _)| *d < depth);
+        let checked = is_project_file(dir)
+            .and_then(|is_project| Ok((is_project, wrapfs::is_file(dir.join(".workspace.json"))?)));
+        let (is_project, is_workspace) = match checked {
+            Ok(checked) => checked,
+            Err(err) if depth == 0 => return Err(err),
+            Err(err) => {
+                stop(&mut report, path, StopReason::from_fs(&err));
+                pruned = Some(depth);
+                continue;
+            }
+        };
+        if is_project {
+            report.projects.push(WalkedProject {
+                path: path.clone(),
+                parent: ancestors.last().map(|(_, p)| p.clone()),
+            });
+        }
+        if is_workspace {
+            report.workspaces.push(path.clone());
+        }
+        if is_project {
+            if !options.nested {
+                pruned = Some(depth);
+            }
+            ancestors.push((depth, path));
+        }
+    }
+    Ok(report)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SourceRootProjectKind {
+    /// Project directory
+    Src,
+    /// KPAR archive
+    Kpar,
+}
+
+/// Project found by [`discover_source_root`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceRootProject {
+    /// IRIs the project is provided as
+    pub iris: Vec<Iri<String>>,
+    /// Path of the project directory or archive, relative to the source root
+    pub path: Utf8UnixPathBuf,
+    pub kind: SourceRootProjectKind,
+}
+
+#[derive(Error, Debug)]
+pub enum SourceRootError {
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceReadError),
+    #[error("failed to read project `{0}` in source root:\n{1}")]
+    Src(Utf8PathBuf, LocalSrcError),
+    #[error("failed to read project archive `{0}` in source root:\n{1}")]
+    Kpar(Utf8PathBuf, LocalKParError),
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+}
+
+impl From<FsIoError> for SourceRootError {
+    fn from(v: FsIoError) -> Self {
+        Self::Io(Box::new(v))
+    }
+}
+
+/// Find the projects provided by the source root `root`, i.e. a directory
+/// of projects managed outside of sysand (e.g. checked out by a build
+/// system) that can be used as dependencies.
+///
+/// If `root` is a workspace, its projects are provided as the IRIs listed
+/// in `.workspace.json`. Otherwise, `root` itself if it is a project, or
+/// else its immediate subdirectories that are projects and its `.kpar`
+/// files are used. Every project is also provided as the `pkg:sysand` IRI
+/// derived from its publisher and name, if valid. Projects that end up with
+/// no IRI are skipped.
+pub fn discover_source_root<P: AsRef<Utf8Path>>(
+    root: P,
+) -> Result<Vec<SourceRootProject>, SourceRootError> {
+    let root = root.as_ref();
+    log::debug!("discovering projects in source root `{root}`");
+
+    let mut candidates = vec![];
+    if wrapfs::is_file(root.join(".workspace.json"))? {
+        let workspace = Workspace::new(root.to_owned())?;
+        for project in workspace.projects() {
+            candidates.push((
+                Utf8UnixPathBuf::from(project.path.as_str()),
+                SourceRootProjectKind::Src,
+                project.iris.clone(),
+            ));
+        }
+    } else if is_project_file(root)? {
+        candidates.push((".".into(), SourceRootProjectKind::Src, vec![]));
+    } else {
+        let mut entries = vec![];
+        for entry in wrapfs::read_dir(root)? {
+            let entry = entry.map_err(|e| FsIoError::ReadDir(root.to_owned(), e))?;
+            entries.push(entry.file_name().to_owned());
+        }
+        entries.sort();
+        for name in entries {
+            let path = root.join(&name);
+            let kind = if wrapfs::is_dir(&path)? && is_project_file(&path)? {
+                SourceRootProjectKind::Src
+            } else if path.extension() == Some("kpar") && wrapfs::is_file(&path)? {
+                SourceRootProjectKind::Kpar
+            } else {
+                continue;
+            };
+            candidates.push((name.into(), kind, vec![]));
+        }
+    }
+
+    let mut projects = vec![];
+    for (path, kind, mut iris) in candidates {
+        let native_path = root.join(path.as_str());
+        let info = match kind {
+            SourceRootProjectKind::Src => LocalSrcProject {
+                nominal_path: None,
+                project_path: native_path.clone(),
+                expected_checksum: None,
+            }
+            .get_info()
+            .map_err(|e| SourceRootError::Src(native_path.clone(), e))?,
+            SourceRootProjectKind::Kpar => {
+                LocalKParProject::new(&native_path, KparInnerPath::Guess, None, None)
+                    .get_info()
+                    .map_err(|e| SourceRootError::Kpar(native_path.clone(), e))?
+            }
+        };
+        let purl = info.as_ref().and_then(|info| {
+            sysand_purl(info.publisher.as_deref()?, &info.name)
+                .and_then(|purl| Iri::parse(purl).ok())
+        });
+        if let Some(purl) = purl
+            && !iris.contains(&purl)
+        {
+            iris.push(purl);
+        }
+
+        if iris.is_empty() {
+            log::debug!("source root project `{native_path}` has no IRI, skipping");
+            continue;
+        }
+        projects.push(SourceRootProject { iris, path, kind });
+    }
+
+    Ok(projects)
+}
+
+#[cfg(test)]
+#[path = "./discover_tests.rs"]
+mod tests;
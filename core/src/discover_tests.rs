@@ -0,0 +1,195 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use camino_tempfile::tempdir;
+
+use super::*;
+use crate::build::{KparCompressionMethod, do_build_kpar};
+
+fn write_project(path: &Utf8Path, name: &str, publisher: Option<&str>) {
+    std::fs::create_dir_all(path).unwrap();
+    let publisher = publisher
+        .map(|p| format!(r#""publisher": "{p}", "#))
+        .unwrap_or_default();
+    std::fs::write(
+        path.join(".project.json"),
+        format!(r#"{{"name": "{name}", {publisher}"version": "1.0.0", "usage": []}}"#),
+    )
+    .unwrap();
+    std::fs::write(
+        path.join(".meta.json"),
+        r#"{"index": {}, "created": "2025-01-01T00:00:00.000000000Z"}"#,
+    )
+    .unwrap();
+}
+
+fn iris(project: &SourceRootProject) -> Vec<&str> {
+    project.iris.iter().map(|iri| iri.as_str()).collect()
+}
+
+#[test]
+fn source_root_subdirectories() {
+    let root = tempdir().unwrap();
+    write_project(&root.path().join("lib-a"), "Lib A", Some("Acme Corp"));
+    // No publisher, so no IRI
+    write_project(&root.path().join("lib-b"), "lib-b", None);
+    write_project(&root.path().join("lib-c-src"), "lib-c", Some("acme-corp"));
+    do_build_kpar(
+        &LocalSrcProject {
+            nominal_path: None,
+            project_path: root.path().join("lib-c-src"),
+            expected_checksum: None,
+        },
+        root.path().join("lib-c.kpar"),
+        KparCompressionMethod::default(),
+        false,
+        false,
+    )
+    .unwrap();
+    std::fs::remove_dir_all(root.path().join("lib-c-src")).unwrap();
+    std::fs::write(root.path().join("notes.txt"), "not a project").unwrap();
+
+    let projects = discover_source_root(root.path()).unwrap();
+
+    assert_eq!(projects.len(), 2);
+    assert_eq!(projects[0].path, "lib-a");
+    assert_eq!(projects[0].kind, SourceRootProjectKind::Src);
+    assert_eq!(iris(&projects[0]), ["pkg:sysand/acme-corp/lib-a"]);
+    assert_eq!(projects[1].path, "lib-c.kpar");
+    assert_eq!(projects[1].kind, SourceRootProjectKind::Kpar);
+    assert_eq!(iris(&projects[1]), ["pkg:sysand/acme-corp/lib-c"]);
+}
+
+#[test]
+fn source_root_is_project() {
+    let root = tempdir().unwrap();
+    write_project(root.path(), "lib", Some("acme"));
+    write_project(&root.path().join("nested"), "nested", Some("acme"));
+
+    let projects = discover_source_root(root.path()).unwrap();
+
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].path, ".");
+    assert_eq!(iris(&projects[0]), ["pkg:sysand/acme/lib"]);
+}
+
+#[test]
+fn source_root_workspace() {
+    let root = tempdir().unwrap();
+    write_project(&root.path().join("first"), "first", Some("acme"));
+    write_project(&root.path().join("second"), "second", None);
+    write_project(&root.path().join("unlisted"), "unlisted", Some("acme"));
+    std::fs::write(
+        root.path().join(".workspace.json"),
+        r#"{"projects": [
+            {"path": "first", "iris": ["urn:kpar:first"]},
+            {"path": "second", "iris": ["urn:kpar:second"]}
+        ]}"#,
+    )
+    .unwrap();
+
+    let projects = discover_source_root(root.path()).unwrap();
+
+    assert_eq!(projects.len(), 2);
+    assert_eq!(projects[0].path, "first");
+    assert_eq!(
+        iris(&projects[0]),
+        ["urn:kpar:first", "pkg:sysand/acme/first"]
+    );
+    assert_eq!(projects[1].path, "second");
+    assert_eq!(iris(&projects[1]), ["urn:kpar:second"]);
+}
+
+#[test]
+fn source_root_missing() {
+    let root = tempdir().unwrap();
+    assert!(matches!(
+        discover_source_root(root.path().join("missing")),
+        Err(SourceRootError::Io(_))
+    ));
+}
+
+fn walked(report: &WalkReport) -> Vec<(&str, Option<&str>)> {
+    report
+        .projects
+        .iter()
+        .map(|p| (p.path.as_str(), p.parent.as_ref().map(|p| p.as_str())))
+        .collect()
+}
+
+#[test]
+fn walk_nested_projects() {
+    let root = tempdir().unwrap();
+    std::fs::write(root.path().join(".workspace.json"), r#"{"projects": []}"#).unwrap();
+    write_project(&root.path().join("a"), "a", None);
+    write_project(&root.path().join("a/models/inner"), "inner", None);
+    write_project(&root.path().join("b/deep/c"), "c", None);
+    write_project(&root.path().join(".hidden"), "hidden", None);
+    write_project(&root.path().join("a/.sysand/dep"), "dep", None);
+
+    let report = walk_projects(root.path(), &WalkOptions::default()).unwrap();
+    assert_eq!(
+        walked(&report),
+        [
+            ("a", None),
+            ("a/models/inner", Some("a")),
+            ("b/deep/c", None)
+        ]
+    );
+    assert_eq!(report.workspaces, [Utf8UnixPathBuf::from(".")]);
+    assert!(report.stops.is_empty());
+
+    let options = WalkOptions {
+        nested: false,
+        hidden: true,
+        ..Default::default()
+    };
+    let report = walk_projects(root.path(), &options).unwrap();
+    assert_eq!(
+        walked(&report),
+        [(".hidden", None), ("a", None), ("b/deep/c", None)]
+    );
+}
+
+#[test]
+fn walk_max_depth() {
+    let root = tempdir().unwrap();
+    write_project(&root.path().join("a"), "a", None);
+    write_project(&root.path().join("b/deep"), "deep", None);
+
+    let options = WalkOptions {
+        max_depth: Some(1),
+        ..Default::default()
+    };
+    let report = walk_projects(root.path(), &options).unwrap();
+    assert_eq!(walked(&report), [("a", None)]);
+    assert_eq!(
+        report.stops,
+        [WalkStop {
+            path: "b".into(),
+            reason: StopReason::MaxDepth
+        }]
+    );
+
+    assert!(walk_projects(root.path().join("missing"), &options).is_err());
+}
+
+#[test]
+fn trace_project_reasons() {
+    let root = tempdir().unwrap();
+    write_project(&root.path().join("a"), "a", None);
+    std::fs::create_dir_all(root.path().join("a/b/c")).unwrap();
+
+    assert_eq!(
+        trace_project(root.path().join("a/b/c"), None),
+        Discovery::Found(root.path().join("a"))
+    );
+    assert_eq!(
+        trace_project(root.path().join("a/b/c"), Some(1)),
+        Discovery::NotFound(StopReason::MaxDepth)
+    );
+    assert_eq!(
+        trace_project(root.path(), None),
+        Discovery::NotFound(StopReason::FilesystemRoot)
+    );
+}
@@ -0,0 +1,631 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Environment storing the source files of installed projects by content.
+//! Each file is stored once, named by its SHA256, and each installed
+//! project version has a manifest mapping its source paths to these
+//! files. Files shared by several projects or versions, such as those of
+//! the standard library, are thus only stored once:
+//!
+//! ```text
+//! <root>
+//!  ├──manifests
+//!  │  └──<package_ID>_<version>.json
+//!  └──objects
+//!     └──<first 2 hex digits>
+//!        └──<remaining 62 hex digits>
+//! ```
+//!
+//! Project information and metadata are kept in the manifest. Editable
+//! projects are not supported, as their files are not owned by the
+//! environment. An existing local environment can be migrated with
+//! [`ContentAddressedEnvironment::import`].
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    io::{Cursor, Read, Write},
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use fluent_uri::Iri;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use typed_path::Utf8UnixPath;
+
+use crate::{
+    context::ProjectContext,
+    env::{
+        OverwritePolicy, ProjectChecksumResult, PutProjectError, ReadEnvironment, WriteEnvironment,
+        local_directory::{LocalDirectoryEnvironment, metadata::EnvProjectChecksum},
+        utils::clone_project,
+    },
+    iri_normalize::IriVersionFilename,
+    lock::Source,
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
+    project::{
+        CanonicalizationError, ProjectChecksum, ProjectMut, ProjectRead,
+        utils::{FsIoError, wrapfs},
+    },
+    utils::{format_err, sha256_lowercase_hex},
+};
+
+pub const MANIFESTS_PATH: &str = "manifests";
+pub const OBJECTS_PATH: &str = "objects";
+const CURRENT_MANIFEST_VERSION: &str = "0.1";
+
+#[derive(Error, Debug)]
+pub enum ContentAddressedError {
+    #[error("project `{0}` is not present in environment")]
+    ProjectNotFound(Box<str>),
+    #[error("source file `{0}` is not present in project")]
+    SourceNotFound(Box<str>),
+    #[error("stored file for `{0}` does not match its checksum `{1}`")]
+    CorruptObject(Box<str>, Box<str>),
+    #[error("failed to parse manifest `{0}`: {1}")]
+    ParseManifest(Utf8PathBuf, serde_json::Error),
+    #[error("failed to serialize manifest: {0}")]
+    SerializeManifest(serde_json::Error),
+    #[error("manifest `{0}` has unsupported version `{1}`")]
+    UnsupportedManifestVersion(Utf8PathBuf, String),
+    #[error("{0}")]
+    AlreadyExists(String),
+    #[error("project is missing `.project.json` and/or `.meta.json` files")]
+    MissingInfoMeta,
+    #[error(
+        "projects in a content-addressed environment have no source that can be \
+        used in a lockfile"
+    )]
+    NoSources,
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+}
+
+impl From<FsIoError> for ContentAddressedError {
+    fn from(v: FsIoError) -> Self {
+        Self::Io(Box::new(v))
+    }
+}
+
+/// Checksum of the source a project was installed from, as in `env.toml`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ManifestChecksum {
+    Kpar { kpar_cksum: String },
+    Project { src_cksum: String },
+}
+
+impl From<ProjectChecksum> for ManifestChecksum {
+    fn from(value: ProjectChecksum) -> Self {
+        match value {
+            ProjectChecksum::Project(c) => Self::Project { src_cksum: c },
+            ProjectChecksum::Kpar(c) => Self::Kpar { kpar_cksum: c },
+        }
+    }
+}
+
+/// Installed project version, with its source files given by the SHA256
+/// of their contents
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub manifest_version: String,
+    pub identifiers: Vec<String>,
+    pub version: String,
+    #[serde(flatten, skip_serializing_if = "Option::is_none", default)]
+    pub checksum: Option<ManifestChecksum>,
+    pub info: Option<InterchangeProjectInfoRaw>,
+    pub meta: Option<InterchangeProjectMetadataRaw>,
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    fn new(identifiers: Vec<String>, version: String, checksum: Option<ProjectChecksum>) -> Self {
+        Self {
+            manifest_version: CURRENT_MANIFEST_VERSION.to_owned(),
+            identifiers,
+            version,
+            checksum: checksum.map(Into::into),
+            info: None,
+            meta: None,
+            files: BTreeMap::new(),
+        }
+    }
+
+    fn is<S: AsRef<str>, V: AsRef<str>>(&self, uri: S, version: V) -> bool {
+        self.version == version.as_ref() && self.identifiers.iter().any(|i| i == uri.as_ref())
+    }
+}
+
+/// Path of the stored file with the given SHA256 in `objects`
+fn object_path(objects: &Utf8Path, hash: &str) -> Utf8PathBuf {
+    let (prefix, rest) = hash.split_at(2.min(hash.len()));
+    objects.join(prefix).join(rest)
+}
+
+/// Write `contents` to `path` through a temporary file, so that readers
+/// never see a partially written file
+fn write_atomically(path: &Utf8Path, contents: &[u8]) -> Result<(), ContentAddressedError> {
+    let dir = path.parent().expect("stored files have a parent directory");
+    wrapfs::create_dir_all(dir)?;
+    let mut file =
+        camino_tempfile::NamedUtf8TempFile::new_in(dir).map_err(FsIoError::CreateTempFile)?;
+    file.write_all(contents)
+        .map_err(|e| FsIoError::WriteFile(file.path().to_owned(), e))?;
+    file.persist(path)
+        .map_err(|e| FsIoError::Move(e.file.path().to_owned(), path.to_owned(), e.error))?;
+    Ok(())
+}
+
+/// Project stored in a [`ContentAddressedEnvironment`]
+#[derive(Clone, Debug)]
+pub struct ContentAddressedProject {
+    objects: Utf8PathBuf,
+    pub manifest: Manifest,
+}
+
+impl ProjectRead for ContentAddressedProject {
+    type Error = ContentAddressedError;
+
+    fn get_project(
+        &self,
+    ) -> Result<
+        (
+            Option<InterchangeProjectInfoRaw>,
+            Option<InterchangeProjectMetadataRaw>,
+        ),
+        Self::Error,
+    > {
+        Ok((self.manifest.info.clone(), self.manifest.meta.clone()))
+    }
+
+    type SourceReader<'a> = Cursor<Vec<u8>>;
+
+    fn read_source<P: AsRef<Utf8UnixPath>>(
+        &self,
+        path: P,
+    ) -> Result<Self::SourceReader<'_>, Self::Error> {
+        let path = path.as_ref().as_str();
+        let hash = self
+            .manifest
+            .files
+            .get(path)
+            .ok_or_else(|| ContentAddressedError::SourceNotFound(path.into()))?;
+        let contents = wrapfs::read(object_path(&self.objects, hash))?;
+        if &sha256_lowercase_hex(&contents) != hash {
+            return Err(ContentAddressedError::CorruptObject(
+                path.into(),
+                hash.as_str().into(),
+            ));
+        }
+        Ok(Cursor::new(contents))
+    }
+
+    fn sources(&self, _ctx: &ProjectContext) -> Result<Vec<Source>, Self::Error> {
+        Err(ContentAddressedError::NoSources)
+    }
+
+    fn checksum_canonical_variant(&self) -> Result<ProjectChecksum, Self::Error> {
+        match self.checksum_canonical_hex() {
+            Ok(Some(c)) => Ok(ProjectChecksum::Project(c)),
+            Ok(None) => Err(ContentAddressedError::MissingInfoMeta),
+            Err(CanonicalizationError::ProjectRead(e)) => Err(e),
+            Err(CanonicalizationError::FileRead(path, error)) => {
+                Err(FsIoError::ReadFile(String::from(path).into(), error).into())
+            }
+        }
+    }
+}
+
+impl ProjectMut for ContentAddressedProject {
+    fn put_info(
+        &mut self,
+        info: &InterchangeProjectInfoRaw,
+        overwrite: bool,
+    ) -> Result<(), Self::Error> {
+        if !overwrite && self.manifest.info.is_some() {
+            return Err(ContentAddressedError::AlreadyExists(
+                "project already has an information file".into(),
+            ));
+        }
+        self.manifest.info = Some(info.clone());
+        Ok(())
+    }
+
+    fn put_meta(
+        &mut self,
+        meta: &InterchangeProjectMetadataRaw,
+        overwrite: bool,
+    ) -> Result<(), Self::Error> {
+        if !overwrite && self.manifest.meta.is_some() {
+            return Err(ContentAddressedError::AlreadyExists(
+                "project already has a meta manifest".into(),
+            ));
+        }
+        self.manifest.meta = Some(meta.clone());
+        Ok(())
+    }
+
+    fn write_source<P: AsRef<Utf8UnixPath>, R: Read>(
+        &mut self,
+        path: P,
+        source: &mut R,
+        overwrite: bool,
+    ) -> Result<(), Self::Error> {
+        let path = path.as_ref().as_str();
+        if !overwrite && self.manifest.files.contains_key(path) {
+            return Err(ContentAddressedError::AlreadyExists(format!(
+                "source file `{path}` already exists"
+            )));
+        }
+        let mut contents = vec![];
+        source
+            .read_to_end(&mut contents)
+            .map_err(FsIoError::ReadFileHandle)?;
+        let hash = sha256_lowercase_hex(&contents);
+        let object = object_path(&self.objects, &hash);
+        if !wrapfs::is_file(&object)? {
+            write_atomically(&object, &contents)?;
+        }
+        self.manifest.files.insert(path.to_owned(), hash);
+        Ok(())
+    }
+
+    fn move_source<P: AsRef<Utf8UnixPath>, Q: AsRef<Utf8UnixPath>>(
+        &mut self,
+        from: P,
+        to: Q,
+    ) -> Result<(), Self::Error> {
+        let (from, to) = (from.as_ref().as_str(), to.as_ref().as_str());
+        if self.manifest.files.contains_key(to) {
+            return Err(ContentAddressedError::AlreadyExists(format!(
+                "source file `{to}` already exists"
+            )));
+        }
+        let hash = self
+            .manifest
+            .files
+            .remove(from)
+            .ok_or_else(|| ContentAddressedError::SourceNotFound(from.into()))?;
+        self.manifest.files.insert(to.to_owned(), hash);
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error("failed to import `{0}` {1}: {2}")]
+    Project(String, String, String),
+}
+
+/// Environment storing source files by content, see the module
+/// documentation for the layout
+#[derive(Debug, Clone)]
+pub struct ContentAddressedEnvironment {
+    root_dir: Utf8PathBuf,
+    /// Manifests by file name
+    manifests: BTreeMap<String, Manifest>,
+}
+
+impl ContentAddressedEnvironment {
+    /// Open the environment at `root_dir`, creating it if it does not exist
+    pub fn open<P: AsRef<Utf8Path>>(root_dir: P) -> Result<Self, ContentAddressedError> {
+        let root_dir = root_dir.as_ref().to_owned();
+        let manifests_dir = root_dir.join(MANIFESTS_PATH);
+        wrapfs::create_dir_all(&manifests_dir)?;
+        wrapfs::create_dir_all(root_dir.join(OBJECTS_PATH))?;
+
+        let mut manifests = BTreeMap::new();
+        for entry in wrapfs::read_dir(&manifests_dir)? {
+            let entry = entry.map_err(|e| FsIoError::ReadDir(manifests_dir.clone(), e))?;
+            let path = entry.path();
+            let Some(name) = path
+                .file_name()
+                .filter(|_| path.extension() == Some("json"))
+            else {
+                continue;
+            };
+            let manifest: Manifest = serde_json::from_str(&wrapfs::read_to_string(path)?)
+                .map_err(|e| ContentAddressedError::ParseManifest(path.to_owned(), e))?;
+            if manifest.manifest_version != CURRENT_MANIFEST_VERSION {
+                return Err(ContentAddressedError::UnsupportedManifestVersion(
+                    path.to_owned(),
+                    manifest.manifest_version,
+                ));
+            }
+            manifests.insert(name.to_owned(), manifest);
+        }
+
+        Ok(Self {
+            root_dir,
+            manifests,
+        })
+    }
+
+    pub fn root_dir(&self) -> &Utf8Path {
+        &self.root_dir
+    }
+
+    pub fn manifests(&self) -> impl Iterator<Item = &Manifest> {
+        self.manifests.values()
+    }
+
+    fn objects_dir(&self) -> Utf8PathBuf {
+        self.root_dir.join(OBJECTS_PATH)
+    }
+
+    fn find<S: AsRef<str>, V: AsRef<str>>(&self, uri: S, version: V) -> Option<(&str, &Manifest)> {
+        self.manifests
+            .iter()
+            .find(|(_, m)| m.is(&uri, &version))
+            .map(|(name, m)| (name.as_str(), m))
+    }
+
+    /// Name of the manifest file of a newly installed project
+    fn new_manifest_name(&self, iri: Iri<&str>, version: &str) -> String {
+        let mut names = IriVersionFilename::new(iri, version);
+        loop {
+            let name = format!("{}.json", names.next_candidate());
+            if !self.manifests.contains_key(&name) {
+                return name;
+            }
+        }
+    }
+
+    fn write_manifest(&self, name: &str, manifest: &Manifest) -> Result<(), ContentAddressedError> {
+        let contents = serde_json::to_string_pretty(manifest)
+            .map_err(ContentAddressedError::SerializeManifest)?;
+        write_atomically(
+            &self.root_dir.join(MANIFESTS_PATH).join(name),
+            contents.as_bytes(),
+        )
+    }
+
+    /// Remove stored files that no manifest refers to, returning how many
+    /// were removed
+    pub fn remove_unreferenced_objects(&self) -> Result<usize, ContentAddressedError> {
+        let referenced: HashSet<&str> = self
+            .manifests
+            .values()
+            .flat_map(|m| m.files.values().map(String::as_str))
+            .collect();
+        let objects = self.objects_dir();
+        let mut removed = 0;
+        for prefix in wrapfs::read_dir(&objects)? {
+            let prefix = prefix.map_err(|e| FsIoError::ReadDir(objects.clone(), e))?;
+            if !prefix
+                .file_type()
+                .map_err(|e| FsIoError::Metadata(prefix.path().to_owned(), e))?
+                .is_dir()
+            {
+                continue;
+            }
+            for object in wrapfs::read_dir(prefix.path())? {
+                let object = object.map_err(|e| FsIoError::ReadDir(prefix.path().to_owned(), e))?;
+                let hash = format!("{}{}", prefix.file_name(), object.file_name());
+                if !referenced.contains(hash.as_str()) {
+                    wrapfs::remove_file(object.path())?;
+                    removed += 1;
+                }
+            }
+            // Only succeeds if the directory is now empty
+            let _ = fs::remove_dir(prefix.path());
+        }
+        Ok(removed)
+    }
+
+    /// Copy the projects installed in `local` into this environment,
+    /// returning the number of copied projects. Editable projects are
+    /// skipped, as they are not stored in the environment
+    pub fn import(&mut self, local: &LocalDirectoryEnvironment) -> Result<usize, ImportError> {
+        let mut imported = 0;
+        for project in local.projects() {
+            let Some(iri) = project.identifiers.first() else {
+                continue;
+            };
+            if project.editable {
+                log::debug!("not importing editable project `{iri}`");
+                continue;
+            }
+            let import_err = |e| ImportError::Project(iri.clone(), project.version.clone(), e);
+            let source = local
+                .get_project(iri, &project.version)
+                .map_err(|e| import_err(format_err(e)))?;
+            let checksum = project.checksum.as_ref().map(|c| match c {
+                EnvProjectChecksum::Kpar { kpar_cksum } => {
+                    ProjectChecksum::Kpar(kpar_cksum.clone())
+                }
+                EnvProjectChecksum::Project { src_cksum } => {
+                    ProjectChecksum::Project(src_cksum.clone())
+                }
+            });
+            self.put_project(
+                iri,
+                &project.version,
+                checksum,
+                OverwritePolicy::Replace,
+                |p| clone_project(&source, p, true).map(|_| ()),
+            )
+            .map_err(|e| import_err(format_err(e)))?;
+            let (name, _) = self
+                .find(iri, &project.version)
+                .expect("project was just installed");
+            let name = name.to_owned();
+            let manifest = self.manifests.get_mut(&name).expect("manifest exists");
+            for alias in &project.identifiers[1..] {
+                if !manifest.identifiers.contains(alias) {
+                    manifest.identifiers.push(alias.clone());
+                }
+            }
+            let manifest = manifest.clone();
+            self.write_manifest(&name, &manifest)
+                .map_err(|e| import_err(format_err(e)))?;
+            imported += 1;
+        }
+        Ok(imported)
+    }
+}
+
+impl ReadEnvironment for ContentAddressedEnvironment {
+    type ReadError = ContentAddressedError;
+
+    type UriIter = Vec<Result<String, ContentAddressedError>>;
+
+    fn uris(&self) -> Result<Self::UriIter, Self::ReadError> {
+        let mut uris: Vec<&String> = self
+            .manifests
+            .values()
+            .flat_map(|m| m.identifiers.iter())
+            .collect();
+        uris.sort();
+        uris.dedup();
+        Ok(uris.into_iter().cloned().map(Ok).collect())
+    }
+
+    type VersionIter = Vec<Result<String, ContentAddressedError>>;
+
+    fn versions<S: AsRef<str>>(&self, uri: S) -> Result<Self::VersionIter, Self::ReadError> {
+        Ok(self
+            .manifests
+            .values()
+            .filter(|m| m.identifiers.iter().any(|i| i == uri.as_ref()))
+            .map(|m| Ok(m.version.clone()))
+            .collect())
+    }
+
+    type InterchangeProjectRead = ContentAddressedProject;
+
+    fn get_project<S: AsRef<str>, T: AsRef<str>>(
+        &self,
+        uri: S,
+        version: T,
+    ) -> Result<Self::InterchangeProjectRead, Self::ReadError> {
+        let (_, manifest) = self
+            .find(&uri, version)
+            .ok_or_else(|| ContentAddressedError::ProjectNotFound(uri.as_ref().into()))?;
+        Ok(ContentAddressedProject {
+            objects: self.objects_dir(),
+            manifest: manifest.clone(),
+        })
+    }
+
+    fn has_version_verified<S: AsRef<str>, V: AsRef<str>>(
+        &self,
+        uri: S,
+        version: V,
+        checksum: &ProjectChecksum,
+    ) -> Result<ProjectChecksumResult, Self::ReadError> {
+        let Some((_, manifest)) = self.find(uri, version) else {
+            return Ok(ProjectChecksumResult::VersionNotFound);
+        };
+        Ok(match (checksum, &manifest.checksum) {
+            (_, None) => ProjectChecksumResult::ChecksumNotPresent,
+            (ProjectChecksum::Project(c), Some(ManifestChecksum::Project { src_cksum }))
+            | (
+                ProjectChecksum::Kpar(c),
+                Some(ManifestChecksum::Kpar {
+                    kpar_cksum: src_cksum,
+                }),
+            ) => {
+                if c == src_cksum {
+                    ProjectChecksumResult::Match
+                } else {
+                    ProjectChecksumResult::Mismatch
+                }
+            }
+            _ => ProjectChecksumResult::DifferentChecksumKinds,
+        })
+    }
+}
+
+impl WriteEnvironment for ContentAddressedEnvironment {
+    type WriteError = ContentAddressedError;
+
+    type InterchangeProjectMut = ContentAddressedProject;
+
+    fn put_project<S: AsRef<str>, T: AsRef<str>, F, CE>(
+        &mut self,
+        uri: S,
+        version: T,
+        checksum: Option<ProjectChecksum>,
+        overwrite: OverwritePolicy,
+        write_project: F,
+    ) -> Result<Self::InterchangeProjectMut, PutProjectError<Self::WriteError, CE>>
+    where
+        F: FnOnce(&mut Self::InterchangeProjectMut) -> Result<(), CE>,
+    {
+        let uri = uri.as_ref();
+        let version = version.as_ref();
+        let iri = Iri::parse(uri).map_err(|e| PutProjectError::IriParse(uri.to_owned(), e))?;
+
+        let installed = match (&checksum, self.find(uri, version)) {
+            (_, None) => ProjectChecksumResult::VersionNotFound,
+            (Some(checksum), Some(_)) => self.has_version_verified(uri, version, checksum)?,
+            (None, Some(_)) => ProjectChecksumResult::ChecksumNotPresent,
+        };
+        if !overwrite.should_write(uri, version, installed)? {
+            let (_, existing) = self.find(uri, version).expect("BUG: version is installed");
+            return Ok(ContentAddressedProject {
+                objects: self.objects_dir(),
+                manifest: existing.clone(),
+            });
+        }
+
+        let (name, identifiers) = match self.find(uri, version) {
+            Some((name, existing)) => (name.to_owned(), existing.identifiers.clone()),
+            None => (self.new_manifest_name(iri, version), vec![uri.to_owned()]),
+        };
+        let mut project = ContentAddressedProject {
+            objects: self.objects_dir(),
+            manifest: Manifest::new(identifiers, version.to_owned(), checksum),
+        };
+        write_project(&mut project).map_err(PutProjectError::Callback)?;
+        if project.manifest.info.is_none() || project.manifest.meta.is_none() {
+            return Err(PutProjectError::Write(
+                ContentAddressedError::MissingInfoMeta,
+            ));
+        }
+
+        self.write_manifest(&name, &project.manifest)?;
+        let replaced = self.manifests.insert(name, project.manifest.clone());
+        if replaced.is_some() {
+            self.remove_unreferenced_objects()?;
+        }
+        Ok(project)
+    }
+
+    fn del_project_version<S: AsRef<str>, T: AsRef<str>>(
+        &mut self,
+        uri: S,
+        version: T,
+    ) -> Result<(), Self::WriteError> {
+        if let Some((name, _)) = self.find(uri, version) {
+            let name = name.to_owned();
+            wrapfs::remove_file(self.root_dir.join(MANIFESTS_PATH).join(&name))?;
+            self.manifests.remove(&name);
+            self.remove_unreferenced_objects()?;
+        }
+        Ok(())
+    }
+
+    fn del_uri<S: AsRef<str>>(&mut self, uri: S) -> Result<(), Self::WriteError> {
+        let names: Vec<String> = self
+            .manifests
+            .iter()
+            .filter(|(_, m)| m.identifiers.iter().any(|i| i == uri.as_ref()))
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in &names {
+            wrapfs::remove_file(self.root_dir.join(MANIFESTS_PATH).join(name))?;
+            self.manifests.remove(name);
+        }
+        if !names.is_empty() {
+            self.remove_unreferenced_objects()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "./content_addressed_tests.rs"]
+mod tests;
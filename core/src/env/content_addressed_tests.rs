@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::io::{Cursor, Read as _};
+
+use camino::Utf8Path;
+use camino_tempfile::tempdir;
+use indexmap::IndexMap;
+use typed_path::Utf8UnixPath;
+
+use super::{ContentAddressedEnvironment, ContentAddressedError, OBJECTS_PATH};
+use crate::{
+    env::{
+        DEFAULT_ENV_NAME, OverwritePolicy, ProjectChecksumResult, ReadEnvironment,
+        WriteEnvironment, local_directory::LocalDirectoryEnvironment, utils::clone_project,
+    },
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw, format_created_now},
+    project::{ProjectChecksum, ProjectMut, ProjectRead, memory::InMemoryProject},
+};
+
+const SHARED: &str = "package Shared;";
+
+fn source_project(version: &str, own: &str) -> InMemoryProject {
+    let info = InterchangeProjectInfoRaw {
+        name: "lib".to_string(),
+        publisher: None,
+        description: None,
+        version: version.to_string(),
+        license: None,
+        maintainer: vec![],
+        website: None,
+        topic: vec![],
+        usage: vec![],
+    };
+    let meta = InterchangeProjectMetadataRaw {
+        index: IndexMap::from([
+            ("Shared".to_string(), "Shared.sysml".to_string()),
+            ("Own".to_string(), "Own.sysml".to_string()),
+        ]),
+        created: format_created_now(),
+        metamodel: None,
+        includes_derived: None,
+        includes_implied: None,
+        checksum: None,
+        variants: IndexMap::new(),
+    };
+    let mut project = InMemoryProject::default();
+    project.put_project(&info, &meta, true).unwrap();
+    project
+        .write_source(
+            Utf8UnixPath::new("Shared.sysml"),
+            &mut Cursor::new(SHARED),
+            true,
+        )
+        .unwrap();
+    project
+        .write_source(Utf8UnixPath::new("Own.sysml"), &mut Cursor::new(own), true)
+        .unwrap();
+    project
+}
+
+fn install(env: &mut ContentAddressedEnvironment, uri: &str, version: &str, own: &str) {
+    let source = source_project(version, own);
+    env.put_project(uri, version, None, OverwritePolicy::Replace, |p| {
+        clone_project(&source, p, true).map(|_| ())
+    })
+    .unwrap();
+}
+
+fn count_objects(root: &Utf8Path) -> usize {
+    walkdir::WalkDir::new(root.join(OBJECTS_PATH))
+        .into_iter()
+        .filter(|e| e.as_ref().unwrap().file_type().is_file())
+        .count()
+}
+
+fn read_to_string<P: ProjectRead>(project: &P, path: &str) -> String {
+    let mut content = String::new();
+    project
+        .read_source(path)
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    content
+}
+
+#[test]
+fn identical_files_are_stored_once() {
+    let dir = tempdir().unwrap();
+    let mut env = ContentAddressedEnvironment::open(dir.path()).unwrap();
+
+    install(&mut env, "urn:kpar:a", "1.0.0", "package A1;");
+    install(&mut env, "urn:kpar:a", "2.0.0", "package A2;");
+    install(&mut env, "urn:kpar:b", "1.0.0", "package A1;");
+
+    // `Shared.sysml` and `package A1;` are each stored once
+    assert_eq!(count_objects(dir.path()), 3);
+
+    let reopened = ContentAddressedEnvironment::open(dir.path()).unwrap();
+    assert_eq!(reopened.manifests().count(), 3);
+    let project = reopened.get_project("urn:kpar:a", "2.0.0").unwrap();
+    assert_eq!(read_to_string(&project, "Shared.sysml"), SHARED);
+    assert_eq!(read_to_string(&project, "Own.sysml"), "package A2;");
+    assert_eq!(project.get_info().unwrap().unwrap().version, "2.0.0");
+    assert_eq!(
+        project.checksum_canonical_hex().unwrap(),
+        source_project("2.0.0", "package A2;")
+            .checksum_canonical_hex()
+            .unwrap()
+    );
+}
+
+#[test]
+fn removing_projects_removes_unreferenced_files() {
+    let dir = tempdir().unwrap();
+    let mut env = ContentAddressedEnvironment::open(dir.path()).unwrap();
+
+    install(&mut env, "urn:kpar:a", "1.0.0", "package A1;");
+    install(&mut env, "urn:kpar:b", "1.0.0", "package B1;");
+    assert_eq!(count_objects(dir.path()), 3);
+
+    env.del_uri("urn:kpar:a").unwrap();
+    assert_eq!(count_objects(dir.path()), 2);
+    assert!(!env.has("urn:kpar:a").unwrap());
+
+    env.del_project_version("urn:kpar:b", "1.0.0").unwrap();
+    assert_eq!(count_objects(dir.path()), 0);
+    assert_eq!(env.uris().unwrap().len(), 0);
+}
+
+#[test]
+fn modified_object_is_detected() {
+    let dir = tempdir().unwrap();
+    let mut env = ContentAddressedEnvironment::open(dir.path()).unwrap();
+    install(&mut env, "urn:kpar:a", "1.0.0", "package A1;");
+
+    let project = env.get_project("urn:kpar:a", "1.0.0").unwrap();
+    let hash = &project.manifest.files["Own.sysml"];
+    std::fs::write(
+        super::object_path(&dir.path().join(OBJECTS_PATH), hash),
+        "package Modified;",
+    )
+    .unwrap();
+
+    assert!(matches!(
+        project.read_source("Own.sysml"),
+        Err(ContentAddressedError::CorruptObject(..))
+    ));
+}
+
+#[test]
+fn import_from_local_env() {
+    let cwd = tempdir().unwrap();
+    let env_dir = cwd.path().join(DEFAULT_ENV_NAME);
+    std::fs::create_dir(&env_dir).unwrap();
+    let mut local = LocalDirectoryEnvironment::create(&env_dir).unwrap();
+    for version in ["1.0.0", "2.0.0"] {
+        let source = source_project(version, "package Same;");
+        local
+            .put_project(
+                "urn:kpar:a",
+                version,
+                Some(ProjectChecksum::Kpar(format!("cksum{version}"))),
+                OverwritePolicy::Replace,
+                |p| clone_project(&source, p, true).map(|_| ()),
+            )
+            .unwrap();
+    }
+
+    let store = cwd.path().join("store");
+    let mut env = ContentAddressedEnvironment::open(&store).unwrap();
+    assert_eq!(env.import(&local).unwrap(), 2);
+
+    assert_eq!(count_objects(&store), 2);
+    assert_eq!(
+        env.versions("urn:kpar:a").unwrap().len(),
+        local.versions("urn:kpar:a").unwrap().len()
+    );
+    assert_eq!(
+        env.has_version_verified(
+            "urn:kpar:a",
+            "2.0.0",
+            &ProjectChecksum::Kpar("cksum2.0.0".into())
+        )
+        .unwrap(),
+        ProjectChecksumResult::Match
+    );
+    let project = env.get_project("urn:kpar:a", "1.0.0").unwrap();
+    assert_eq!(read_to_string(&project, "Own.sysml"), "package Same;");
+}
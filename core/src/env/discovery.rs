@@ -65,40 +65,42 @@ impl ResolvedEndpoints {
         Self::url_join(&self.index_root, &format!("{path}/"))
     }
 
-    /// Per-version directory URL ending with a trailing slash, so that
-    /// `Url::join` treats it as a directory when composing leaf URLs
-    /// (`project.kpar`, `.project.json`, `.meta.json`).
-    pub(crate) fn version_dir_url<S: AsRef<str>, T: AsRef<str>>(
-        &self,
-        iri: S,
-        version: T,
+    /// Per-version directory URL of the project whose directory is
+    /// `project_url`, ending with a trailing slash, so that `Url::join`
+    /// treats it as a directory when composing leaf URLs (`project.kpar`,
+    /// `.project.json`, `.meta.json`).
+    fn version_dir_url(
+        project_url: &url::Url,
+        version: &str,
     ) -> Result<url::Url, IndexEnvironmentError> {
-        let base = self.project_url(iri)?;
-        Self::url_join(&base, &format!("{}/", version.as_ref()))
+        Self::url_join(project_url, &format!("{version}/"))
     }
 
-    pub(crate) fn kpar_url<S: AsRef<str>, T: AsRef<str>>(
-        &self,
-        iri: S,
-        version: T,
+    pub(crate) fn kpar_url(
+        project_url: &url::Url,
+        version: &str,
     ) -> Result<url::Url, IndexEnvironmentError> {
-        Self::url_join(&self.version_dir_url(iri, version)?, KPAR_FILE)
+        Self::url_join(&Self::version_dir_url(project_url, version)?, KPAR_FILE)
     }
 
-    pub(crate) fn project_json_url<S: AsRef<str>, T: AsRef<str>>(
-        &self,
-        iri: S,
-        version: T,
+    pub(crate) fn project_json_url(
+        project_url: &url::Url,
+        version: &str,
     ) -> Result<url::Url, IndexEnvironmentError> {
-        Self::url_join(&self.version_dir_url(iri, version)?, PROJECT_JSON_FILE)
+        Self::url_join(
+            &Self::version_dir_url(project_url, version)?,
+            PROJECT_JSON_FILE,
+        )
     }
 
-    pub(crate) fn meta_json_url<S: AsRef<str>, T: AsRef<str>>(
-        &self,
-        iri: S,
-        version: T,
+    pub(crate) fn meta_json_url(
+        project_url: &url::Url,
+        version: &str,
     ) -> Result<url::Url, IndexEnvironmentError> {
-        Self::url_join(&self.version_dir_url(iri, version)?, META_JSON_FILE)
+        Self::url_join(
+            &Self::version_dir_url(project_url, version)?,
+            META_JSON_FILE,
+        )
     }
 
     pub(crate) fn versions_url<S: AsRef<str>>(
@@ -108,6 +110,18 @@ impl ResolvedEndpoints {
         let base = self.project_url(iri)?;
         Self::url_join(&base, VERSIONS_PATH)
     }
+
+    /// `versions.json` URL of `iri` in indexes that predate the `_kpar`
+    /// layout, if it differs from [`Self::versions_url`] (§5)
+    pub(crate) fn legacy_versions_url<S: AsRef<str>>(
+        &self,
+        iri: S,
+    ) -> Result<Option<url::Url>, IndexEnvironmentError> {
+        let Some(path) = parse_iri(iri.as_ref())?.legacy_path() else {
+            return Ok(None);
+        };
+        Self::url_join(&self.index_root, &format!("{path}/{VERSIONS_PATH}")).map(Some)
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Read-only view of several environments, e.g. the local environment of a
+//! project followed by system-wide environments distributed with a tool
+//! installation. Each project version is read from the first environment
+//! that has it.
+
+use indexmap::IndexSet;
+
+use crate::{
+    env::{ProjectChecksumResult, ReadEnvironment},
+    project::ProjectChecksum,
+};
+
+#[derive(Debug, Clone)]
+pub struct FallbackEnvironment<Env> {
+    pub layers: Vec<Env>,
+}
+
+impl<Env> FallbackEnvironment<Env> {
+    /// `layers` must not be empty
+    pub fn new(layers: Vec<Env>) -> Self {
+        Self { layers }
+    }
+}
+
+impl<Env: ReadEnvironment> ReadEnvironment for FallbackEnvironment<Env> {
+    type ReadError = Env::ReadError;
+
+    type UriIter = Vec<Result<String, Self::ReadError>>;
+
+    fn uris(&self) -> Result<Self::UriIter, Self::ReadError> {
+        let mut uris = IndexSet::new();
+        for layer in &self.layers {
+            for uri in layer.uris()? {
+                uris.insert(uri?);
+            }
+        }
+        Ok(uris.into_iter().map(Ok).collect())
+    }
+
+    type VersionIter = Vec<Result<String, Self::ReadError>>;
+
+    fn versions<S: AsRef<str>>(&self, uri: S) -> Result<Self::VersionIter, Self::ReadError> {
+        let mut versions = IndexSet::new();
+        for layer in &self.layers {
+            for version in layer.versions(&uri)? {
+                versions.insert(version?);
+            }
+        }
+        Ok(versions.into_iter().map(Ok).collect())
+    }
+
+    type InterchangeProjectRead = Env::InterchangeProjectRead;
+
+    /// Fails with the error of the last layer if no layer has the version
+    fn get_project<S: AsRef<str>, T: AsRef<str>>(
+        &self,
+        uri: S,
+        version: T,
+    ) -> Result<Self::InterchangeProjectRead, Self::ReadError> {
+        for layer in &self.layers {
+            if layer.has_version(&uri, &version)? {
+                return layer.get_project(uri, version);
+            }
+        }
+        self.layers
+            .last()
+            .expect("BUG: fallback environment has no layers")
+            .get_project(uri, version)
+    }
+
+    /// `Match` if any layer has a matching version, otherwise the result
+    /// of the first layer that has the version at all
+    fn has_version_verified<S: AsRef<str>, V: AsRef<str>>(
+        &self,
+        uri: S,
+        version: V,
+        checksum: &ProjectChecksum,
+    ) -> Result<ProjectChecksumResult, Self::ReadError> {
+        let mut result = ProjectChecksumResult::VersionNotFound;
+        for layer in &self.layers {
+            match layer.has_version_verified(&uri, &version, checksum)? {
+                ProjectChecksumResult::Match => return Ok(ProjectChecksumResult::Match),
+                ProjectChecksumResult::VersionNotFound => {}
+                other if result == ProjectChecksumResult::VersionNotFound => result = other,
+                _ => {}
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+#[path = "./fallback_tests.rs"]
+mod tests;
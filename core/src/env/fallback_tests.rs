@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use crate::{
+    env::{
+        OverwritePolicy, ProjectChecksumResult, ReadEnvironment, WriteEnvironment,
+        fallback::FallbackEnvironment, memory::MemoryStorageEnvironment, utils::clone_project,
+    },
+    init::do_init_memory,
+    project::{ProjectChecksum, ProjectRead, memory::InMemoryProject},
+};
+
+type Env = MemoryStorageEnvironment<InMemoryProject>;
+
+fn env_with(projects: &[(&str, &str)]) -> Env {
+    let mut env = Env::new();
+    for (name, version) in projects {
+        let project = do_init_memory(*name, None::<&str>, *version, None).unwrap();
+        let checksum = project.checksum_canonical_variant().unwrap();
+        env.put_project(
+            format!("urn:kpar:{}", name.to_lowercase()),
+            version,
+            Some(checksum),
+            OverwritePolicy::Error,
+            |p| clone_project(&project, p, true).map(|_| ()),
+        )
+        .unwrap();
+    }
+    env
+}
+
+fn checksum(name: &str, version: &str) -> ProjectChecksum {
+    do_init_memory(name, None::<&str>, version, None)
+        .unwrap()
+        .checksum_canonical_variant()
+        .unwrap()
+}
+
+#[test]
+fn reads_from_first_layer_with_version() {
+    let env = FallbackEnvironment::new(vec![
+        env_with(&[("Lib", "1.0.0")]),
+        env_with(&[("Lib", "1.0.0"), ("Lib", "2.0.0"), ("Other", "1.0.0")]),
+    ]);
+
+    let uris: Vec<_> = env
+        .uris()
+        .unwrap()
+        .into_iter()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(uris, ["urn:kpar:lib", "urn:kpar:other"]);
+    let versions: Vec<_> = env
+        .versions("urn:kpar:lib")
+        .unwrap()
+        .into_iter()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(versions, ["1.0.0", "2.0.0"]);
+
+    let project = env.get_project("urn:kpar:lib", "2.0.0").unwrap();
+    assert_eq!(project.get_info().unwrap().unwrap().version, "2.0.0");
+    assert!(env.get_project("urn:kpar:lib", "3.0.0").is_err());
+}
+
+#[test]
+fn any_matching_layer_counts() {
+    let env = FallbackEnvironment::new(vec![
+        env_with(&[("Lib", "1.0.0")]),
+        env_with(&[("Other", "1.0.0")]),
+    ]);
+
+    assert_eq!(
+        env.has_version_verified("urn:kpar:other", "1.0.0", &checksum("Other", "1.0.0"))
+            .unwrap(),
+        ProjectChecksumResult::Match
+    );
+    assert_eq!(
+        env.has_version_verified("urn:kpar:lib", "1.0.0", &checksum("Other", "1.0.0"))
+            .unwrap(),
+        ProjectChecksumResult::Mismatch
+    );
+    assert_eq!(
+        env.has_version_verified("urn:kpar:lib", "2.0.0", &checksum("Lib", "2.0.0"))
+            .unwrap(),
+        ProjectChecksumResult::VersionNotFound
+    );
+}
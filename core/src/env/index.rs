@@ -14,15 +14,17 @@
 //!   `index.json` and per-version 404s remain hard errors.
 //! - Retired versions are filtered at candidate enumeration, and `removed`
 //!   entries are rejected before per-version files are fetched.
+//! - Deprecated projects stay resolvable; selecting one logs a warning
+//!   once per env.
 //! - The wire contract is kept in `design/index-protocol.md`; the code
 //!   does not use `#[serde(deny_unknown_fields)]` so new optional fields are
 //!   ignored by default.
 
 use std::{
-    collections::{HashMap, hash_map::Entry},
+    collections::{HashMap, HashSet, hash_map::Entry},
     num::NonZeroU64,
     rc::Rc,
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
 use semver::Version;
@@ -90,6 +92,8 @@ pub struct IndexEnvironmentAsync<Policy> {
     // This is a Mutex to enable caching in &self methods of ReadEnvironment
     // trait.
     versions_cache: tokio::sync::Mutex<HashMap<String, VersionsCacheEntry>>,
+    /// IRIs of deprecated projects already warned about
+    deprecation_warned: Mutex<HashSet<String>>,
 }
 
 impl<Policy> IndexEnvironmentAsync<Policy> {
@@ -112,6 +116,7 @@ impl<Policy> IndexEnvironmentAsync<Policy> {
             discovery_root: None,
             endpoints: endpoints_cell,
             versions_cache: Default::default(),
+            deprecation_warned: Default::default(),
         }
     }
 
@@ -130,16 +135,49 @@ impl<Policy> IndexEnvironmentAsync<Policy> {
             discovery_root: Some(discovery_root),
             endpoints: tokio::sync::OnceCell::new(),
             versions_cache: Default::default(),
+            deprecation_warned: Default::default(),
         }
     }
+
+    /// User-configured discovery root, if the env was created from one
+    pub(crate) fn discovery_root(&self) -> Option<&url::Url> {
+        self.discovery_root.as_ref()
+    }
 }
 
-/// Per-IRI cache slot: validated `versions.json` entries, shared across
+/// Per-IRI cache slot: validated `versions.json` document, shared across
 /// callers by `Rc`. The slot is only populated on a successful fetch
 /// (see `fetch_versions_json`); validation errors propagate, and the
 /// "project not in this index" 404 outcome (§8) is not currently
 /// cached.
-pub(crate) type VersionsCacheEntry = Rc<[AdvertisedVersion]>;
+pub(crate) type VersionsCacheEntry = Rc<AdvertisedProject>;
+
+/// Deprecation of a project, as declared by `versions.json` (§8)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deprecation {
+    /// IRI of the project suggested in its place, if any
+    pub replaced_by: Option<String>,
+}
+
+impl std::fmt::Display for Deprecation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.replaced_by {
+            Some(replacement) => write!(f, "deprecated; use `{replacement}` instead"),
+            None => write!(f, "deprecated"),
+        }
+    }
+}
+
+/// A validated `versions.json` document
+#[derive(Debug)]
+pub(crate) struct AdvertisedProject {
+    /// Directory of the project in the index, where `versions.json` was
+    /// found
+    pub(crate) project_url: url::Url,
+    /// Entries in newest-first order
+    pub(crate) versions: Vec<AdvertisedVersion>,
+    pub(crate) deprecation: Option<Deprecation>,
+}
 
 /// A validated sha256 hex digest — 64 lowercase hex characters, with the
 /// `"sha256:"` prefix already stripped. Constructed via `TryFrom<&str>`
@@ -259,11 +297,19 @@ pub enum HttpFetchError {
         #[source]
         source: reqwest_middleware::Error,
     },
-    #[error("HTTP request to `{url}` returned status {status}")]
+    #[error(
+        "HTTP request to `{url}` returned status {status}{}",
+        crate::auth::auth_status_hint(*status)
+    )]
     BadHttpStatus {
         url: Box<str>,
         status: reqwest::StatusCode,
     },
+    #[error("HTTP request to `{url}` was denied: {denied}")]
+    PermissionDenied {
+        url: Box<str>,
+        denied: crate::auth::PermissionDenied,
+    },
     #[error("failed to read HTTP response body from `{url}`: {source}")]
     Body {
         url: Box<str>,
@@ -315,6 +361,21 @@ pub(crate) async fn fetch_json<T: DeserializeOwned, P: HTTPAuthentication>(
     }
 
     if !status.is_success() {
+        if status == reqwest::StatusCode::FORBIDDEN {
+            // A structured 403 body names the missing permission; anything
+            // else (including an unreadable body) stays a plain status error
+            if let Some(denied) = response
+                .bytes()
+                .await
+                .ok()
+                .and_then(|body| crate::auth::PermissionDenied::from_response_body(&body))
+            {
+                return Err(HttpFetchError::PermissionDenied {
+                    url: url.as_str().into(),
+                    denied,
+                });
+            }
+        }
         return Err(HttpFetchError::BadHttpStatus {
             url: url.as_str().into(),
             status,
@@ -383,20 +444,35 @@ impl<Policy: HTTPAuthentication> IndexEnvironmentAsync<Policy> {
     async fn fetch_versions_json<S: AsRef<str>>(
         &self,
         iri: S,
-    ) -> Result<Option<Rc<[AdvertisedVersion]>>, IndexEnvironmentError> {
+    ) -> Result<Option<VersionsCacheEntry>, IndexEnvironmentError> {
         let iri_key = iri.as_ref();
         if let Some(cached) = self.versions_cache.lock().await.get(iri_key).cloned() {
             return Ok(Some(cached));
         }
 
-        let url = self.endpoints().await?.versions_url(iri_key)?;
-        let fetched = fetch_json::<VersionsJson, _>(
+        let endpoints = self.endpoints().await?;
+        let mut url = endpoints.versions_url(iri_key)?;
+        let mut fetched = fetch_json::<VersionsJson, _>(
             &self.client,
             &*self.auth_policy,
             &url,
             MissingPolicy::AllowNotFound,
         )
         .await?;
+        // Indexes that predate the `_kpar` layout keep `urn:kpar` projects
+        // in the `_iri` bucket (§5)
+        if fetched.is_none()
+            && let Some(legacy_url) = endpoints.legacy_versions_url(iri_key)?
+        {
+            fetched = fetch_json::<VersionsJson, _>(
+                &self.client,
+                &*self.auth_policy,
+                &legacy_url,
+                MissingPolicy::AllowNotFound,
+            )
+            .await?;
+            url = legacy_url;
+        }
         let Some(parsed) = fetched else {
             return Ok(None);
         };
@@ -405,10 +481,37 @@ impl<Policy: HTTPAuthentication> IndexEnvironmentAsync<Policy> {
         let mut cache = self.versions_cache.lock().await;
         let val = match cache.entry(iri_key.to_owned()) {
             Entry::Occupied(occupied) => Rc::clone(occupied.get()),
-            Entry::Vacant(vacant) => Rc::clone(vacant.insert_entry(validated).get()),
+            Entry::Vacant(vacant) => Rc::clone(vacant.insert_entry(Rc::new(validated)).get()),
         };
         Ok(Some(val))
     }
+
+    /// Deprecation of the project `iri` declared by this index. `None` if
+    /// the project is not deprecated or not in this index
+    pub async fn deprecation_async<S: AsRef<str>>(
+        &self,
+        iri: S,
+    ) -> Result<Option<Deprecation>, IndexEnvironmentError> {
+        Ok(self
+            .fetch_versions_json(iri)
+            .await?
+            .and_then(|project| project.deprecation.clone()))
+    }
+
+    /// Warn, once per IRI, about selecting the deprecated project `iri`
+    fn warn_if_deprecated(&self, iri: &str, project: &AdvertisedProject) {
+        let Some(deprecation) = &project.deprecation else {
+            return;
+        };
+        if self
+            .deprecation_warned
+            .lock()
+            .expect("deprecation warning set is not poisoned")
+            .insert(iri.to_owned())
+        {
+            log::warn!("project `{iri}` is {deprecation}");
+        }
+    }
 }
 
 /// Validate every entry's required fields (semver parse, no build
@@ -427,8 +530,8 @@ impl<Policy: HTTPAuthentication> IndexEnvironmentAsync<Policy> {
 fn validate_versions(
     url: &url::Url,
     vs: VersionsJson,
-) -> Result<Rc<[AdvertisedVersion]>, IndexEnvironmentError> {
-    let validated: Rc<[AdvertisedVersion]> = vs
+) -> Result<AdvertisedProject, IndexEnvironmentError> {
+    let validated: Vec<AdvertisedVersion> = vs
         .versions
         .into_iter()
         .map(|entry| {
@@ -489,7 +592,15 @@ fn validate_versions(
             });
         }
     }
-    Ok(validated)
+    Ok(AdvertisedProject {
+        project_url: url
+            .join(".")
+            .map_err(|e| IndexEnvironmentError::JoinURL(url.as_str().into(), ".".into(), e))?,
+        versions: validated,
+        deprecation: vs.deprecated.then_some(Deprecation {
+            replaced_by: vs.replaced_by,
+        }),
+    })
 }
 
 type ResultStream<T> = futures::stream::Iter<std::vec::IntoIter<Result<T, IndexEnvironmentError>>>;
@@ -536,7 +647,9 @@ impl<Policy: HTTPAuthentication> ReadEnvironmentAsync for IndexEnvironmentAsync<
         // `get_project_async` directly, which allows `yanked` (files
         // still served) and hard-fails `removed` — see §13 and the §9
         // file-presence rule.
+        self.warn_if_deprecated(uri.as_ref(), &vs);
         let versions: Vec<Result<String, IndexEnvironmentError>> = vs
+            .versions
             .iter()
             .filter(|e| {
                 if e.status == VersionStatus::Available {
@@ -591,7 +704,9 @@ impl<Policy: HTTPAuthentication> ReadEnvironmentAsync for IndexEnvironmentAsync<
                 version: version.as_ref().to_string(),
             }
         })?;
+        self.warn_if_deprecated(uri.as_ref(), &versions);
         let advertised = versions
+            .versions
             .iter()
             .find(|e| e.version == requested)
             .cloned()
@@ -614,9 +729,13 @@ impl<Policy: HTTPAuthentication> ReadEnvironmentAsync for IndexEnvironmentAsync<
         // Build leaf URLs from the validated version (i.e. the `Display` of
         // the parsed `semver::Version`), not the caller-supplied string.
         let advertised_version = advertised.version.to_string();
-        let kpar_url = endpoints.kpar_url(&uri, &advertised_version)?;
-        let project_json_url = endpoints.project_json_url(&uri, &advertised_version)?;
-        let meta_json_url = endpoints.meta_json_url(&uri, &advertised_version)?;
+        // Relative to where `versions.json` was found, which is not
+        // `endpoints.project_url` for indexes that predate `_kpar/`
+        let project_url = &versions.project_url;
+        let kpar_url = ResolvedEndpoints::kpar_url(project_url, &advertised_version)?;
+        let project_json_url =
+            ResolvedEndpoints::project_json_url(project_url, &advertised_version)?;
+        let meta_json_url = ResolvedEndpoints::meta_json_url(project_url, &advertised_version)?;
 
         let project = IndexEntryProject::new(
             kpar_url,
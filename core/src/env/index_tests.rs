@@ -251,6 +251,15 @@ mod uris {
 
     use super::*;
 
+    /// `project.kpar` URL of `version` of `iri` in the `_kpar` layout
+    fn kpar_url(
+        endpoints: &ResolvedEndpoints,
+        iri: impl AsRef<str>,
+        version: &str,
+    ) -> Result<url::Url, super::IndexEnvironmentError> {
+        ResolvedEndpoints::kpar_url(&endpoints.project_url(iri)?, version)
+    }
+
     #[test]
     fn uri_examples() -> Result<(), Box<dyn std::error::Error>> {
         let env = index_env_async("https://www.example.com/index/")?;
@@ -263,24 +272,30 @@ mod uris {
 
         // pkg:sysand/<publisher>/<name> routes under publisher/name/
         assert_eq!(
-            endpoints
-                .kpar_url(purl("admin/proj0"), "0.3.0")?
-                .to_string(),
+            kpar_url(endpoints, purl("admin/proj0"), "0.3.0")?.to_string(),
             "https://www.example.com/index/admin/proj0/0.3.0/project.kpar"
         );
 
-        // Non-pkg:sysand IRIs go under _iri/<sha256(normalized_iri)>/
+        // urn:kpar:<name> routes under _kpar/<name>/
         assert_eq!(
-            endpoints.kpar_url("urn:kpar:b", "1.0.0")?.to_string(),
-            "https://www.example.com/index/_iri/621a5fdf587a3ecc878a98c8be2240dd5bbe561860d11f4da1ece4a4fe2fb8b5/1.0.0/project.kpar"
+            kpar_url(endpoints, "urn:kpar:b", "1.0.0")?.to_string(),
+            "https://www.example.com/index/_kpar/b/1.0.0/project.kpar"
+        );
+
+        // Other IRIs go under _iri/<sha256(normalized_iri)>/
+        assert_eq!(
+            kpar_url(endpoints, "urn:example:b", "1.0.0")?.to_string(),
+            "https://www.example.com/index/_iri/b02753fd9753cbdfd9a866105296ae83b0522416e37f3e9fd4e1c15e86020e5b/1.0.0/project.kpar"
         );
 
         // Per-version `.project.json` lives in the same version directory; this
         // also exercises the `version_dir_url` trailing-slash invariant.
         assert_eq!(
-            endpoints
-                .project_json_url(purl("admin/proj0"), "0.3.0")?
-                .to_string(),
+            ResolvedEndpoints::project_json_url(
+                &endpoints.project_url(purl("admin/proj0"))?,
+                "0.3.0"
+            )?
+            .to_string(),
             "https://www.example.com/index/admin/proj0/0.3.0/.project.json"
         );
 
@@ -321,11 +336,9 @@ mod uris {
             &purl("a/"),
             &purl(""),
         ] {
-            let err = resolved_endpoints(&env)
-                .kpar_url(iri, "1.0.0")
-                .expect_err(&format!(
-                    "expected `{iri}` to be rejected as malformed pkg:sysand"
-                ));
+            let err = kpar_url(resolved_endpoints(&env), iri, "1.0.0").expect_err(&format!(
+                "expected `{iri}` to be rejected as malformed pkg:sysand"
+            ));
             assert_matches!(
                 err,
                 super::IndexEnvironmentError::MalformedIri(
@@ -338,6 +351,35 @@ mod uris {
         Ok(())
     }
 
+    #[test]
+    fn malformed_kpar_urn_rejected_loudly() -> Result<(), Box<dyn std::error::Error>> {
+        let env = index_env_async("https://www.example.com/index/")?;
+
+        for iri in [
+            "urn:kpar:",
+            "urn:kpar:../b",
+            "urn:kpar:b/c",
+            "urn:kpar:-b",
+            "urn:kpar:b%20c",
+        ] {
+            let err = kpar_url(resolved_endpoints(&env), iri, "1.0.0")
+                .expect_err(&format!("expected `{iri}` to be rejected as malformed"));
+            assert_matches!(
+                err,
+                super::IndexEnvironmentError::MalformedIri(ParseIriError::MalformedKparUrn { .. }),
+                "expected MalformedKparUrn for `{iri}`, got {err:?}"
+            );
+            // Only well-formed names fall back to the legacy layout
+            assert!(resolved_endpoints(&env).legacy_versions_url(iri).is_err());
+        }
+        assert_eq!(
+            kpar_url(resolved_endpoints(&env), "URN:KPAR:b", "1.0.0")?.to_string(),
+            "https://www.example.com/index/_kpar/b/1.0.0/project.kpar"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn non_normalized_sysand_purl_error_suggests_normalized_form()
     -> Result<(), Box<dyn std::error::Error>> {
@@ -345,9 +387,12 @@ mod uris {
         // the suggested normalized IRI — that's what makes the error actionable
         // (otherwise the user just sees "rejected" with no path forward).
         let env = index_env_async("https://www.example.com/index/")?;
-        let err = resolved_endpoints(&env)
-            .kpar_url(purl("Acme Labs/My.Project"), "1.0.0")
-            .expect_err("non-normalized pkg:sysand must be rejected");
+        let err = kpar_url(
+            resolved_endpoints(&env),
+            purl("Acme Labs/My.Project"),
+            "1.0.0",
+        )
+        .expect_err("non-normalized pkg:sysand must be rejected");
         let err_msg = format_err(&err);
         let proper_purl = &purl("acme-labs/my.project");
         assert!(
@@ -367,9 +412,7 @@ mod uris {
             "https://www.example.com/index/index.json"
         );
         assert_eq!(
-            endpoints
-                .kpar_url(purl("admin/proj0"), "0.3.0")?
-                .to_string(),
+            kpar_url(endpoints, purl("admin/proj0"), "0.3.0")?.to_string(),
             "https://www.example.com/index/admin/proj0/0.3.0/project.kpar"
         );
 
@@ -605,7 +648,7 @@ mod versions {
 
         let iri_versions_mock = mock_json_get(
             &mut server,
-            "/_iri/621a5fdf587a3ecc878a98c8be2240dd5bbe561860d11f4da1ece4a4fe2fb8b5/versions.json",
+            "/_iri/b02753fd9753cbdfd9a866105296ae83b0522416e37f3e9fd4e1c15e86020e5b/versions.json",
             versions_json_body([("1.0.0", "[]")]),
         );
 
@@ -616,7 +659,7 @@ mod versions {
         assert!(pkg_versions.contains(&"0.3.0".to_string()));
         assert!(pkg_versions.contains(&"0.2.0".to_string()));
 
-        let iri_versions: Result<Vec<_>, _> = env.versions("urn:kpar:b")?.collect();
+        let iri_versions: Result<Vec<_>, _> = env.versions("urn:example:b")?.collect();
         assert_eq!(iri_versions?, vec!["1.0.0"]);
 
         pkg_versions_mock.assert();
@@ -663,6 +706,61 @@ mod versions {
         Ok(())
     }
 
+    #[test]
+    fn deprecation_from_versions_json() -> Result<(), Box<dyn std::error::Error>> {
+        // §8 `deprecated` / `replaced_by`: deprecation applies to the
+        // whole project and does not filter any version.
+        let mut server = mockito::Server::new();
+        let runtime = make_runtime()?;
+        let env = index_env_async(&server.url())?;
+
+        let deprecated = format!(
+            r#"{{"versions":[{{"version":"1.0.0","usage":[],"kpar_size":42,"kpar_digest":"{FILLER_DIGEST}"}}],
+                "deprecated":true,"replaced_by":"{}"}}"#,
+            purl("admin/new")
+        );
+        let old_mock = mock_json_get(&mut server, "/admin/old/versions.json", deprecated);
+        let plain_mock = mock_json_get(
+            &mut server,
+            "/admin/plain/versions.json",
+            versions_json_body([("1.0.0", "[]")]),
+        );
+        let missing_mock = server
+            .mock("GET", "/admin/missing/versions.json")
+            .with_status(404)
+            .create();
+
+        let deprecation = runtime.block_on(env.deprecation_async(purl("admin/old")))?;
+        assert_eq!(
+            deprecation,
+            Some(crate::env::index::Deprecation {
+                replaced_by: Some(purl("admin/new"))
+            })
+        );
+        // Cached, so enumerating versions does not fetch again
+        let versions: Vec<_> = runtime.block_on(async {
+            futures::StreamExt::collect::<Vec<_>>(env.versions_async(purl("admin/old")).await?)
+                .await
+                .into_iter()
+                .collect::<Result<_, _>>()
+        })?;
+        assert_eq!(versions, vec!["1.0.0"]);
+        assert_eq!(
+            runtime.block_on(env.deprecation_async(purl("admin/plain")))?,
+            None
+        );
+        assert_eq!(
+            runtime.block_on(env.deprecation_async(purl("admin/missing")))?,
+            None
+        );
+
+        old_mock.assert();
+        plain_mock.assert();
+        missing_mock.assert();
+
+        Ok(())
+    }
+
     #[test]
     fn versions_json_preserves_server_order() -> Result<(), Box<dyn std::error::Error>> {
         // Semver-tricky fixture makes pass-through visible: a lexicographic-
@@ -894,6 +992,39 @@ mod versions {
         Ok(())
     }
 
+    #[test]
+    fn versions_json_structured_forbidden_names_permission()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let mut server = mockito::Server::new();
+        let env = index_env_sync(&server)?;
+
+        let versions_mock = server
+            .mock("GET", "/admin/proj0/versions.json")
+            .with_status(403)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"error":"no access","permission":"read","scope":"admin/*"}"#)
+            .expect(1)
+            .create();
+
+        let err = env
+            .versions(purl("admin/proj0"))
+            .expect_err("403 on root versions.json must be a hard error");
+        match err {
+            super::IndexEnvironmentError::Fetch(super::HttpFetchError::PermissionDenied {
+                denied,
+                ..
+            }) => {
+                assert_eq!(denied.permission, crate::auth::Permission::Read);
+                assert_eq!(denied.scope.as_deref(), Some("admin/*"));
+            }
+            other => panic!("expected PermissionDenied, got {other:?}"),
+        }
+
+        versions_mock.assert();
+
+        Ok(())
+    }
+
     #[test]
     fn missing_required_field_errors() -> Result<(), Box<dyn std::error::Error>> {
         // An entry omitting any required field (here `kpar_digest`)
@@ -1270,6 +1401,53 @@ mod get_project {
         Ok(())
     }
 
+    #[test]
+    fn get_project_kpar_urn_from_legacy_layout() -> Result<(), Box<dyn std::error::Error>> {
+        // Indexes that predate `_kpar/` serve `urn:kpar:b` from
+        // `_iri/<sha256("urn:kpar:b")>/`
+        let mut server = mockito::Server::new();
+        let env = index_env_sync(&server)?;
+        let legacy = "/_iri/621a5fdf587a3ecc878a98c8be2240dd5bbe561860d11f4da1ece4a4fe2fb8b5";
+
+        let kpar_mock = server
+            .mock("GET", "/_kpar/b/versions.json")
+            .with_status(404)
+            .expect(1)
+            .create();
+        let versions_mock = mock_json_get(
+            &mut server,
+            &format!("{legacy}/versions.json"),
+            versions_json_body([("1.0.0", "[]")]),
+        );
+        let project_json_mock = mock_json_get(
+            &mut server,
+            &format!("{legacy}/1.0.0/.project.json"),
+            project_json_body("b", None, "1.0.0", "[]"),
+        );
+        let meta_json_mock = mock_json_get(
+            &mut server,
+            &format!("{legacy}/1.0.0/.meta.json"),
+            meta_json_body(),
+        );
+
+        let versions: Result<Vec<_>, _> = env.versions("urn:kpar:b")?.collect();
+        assert_eq!(versions?, vec!["1.0.0"]);
+        let project = env.get_project("urn:kpar:b", "1.0.0")?;
+        assert_eq!(
+            project.inner.archive.url().as_str(),
+            format!("{}{legacy}/1.0.0/project.kpar", server.url())
+        );
+        let (info, _) = project.get_project()?;
+        assert_eq!(info.expect("info should be prefetched").name, "b");
+
+        kpar_mock.assert();
+        versions_mock.assert();
+        project_json_mock.assert();
+        meta_json_mock.assert();
+
+        Ok(())
+    }
+
     #[test]
     fn get_project_iri_hash_route() -> Result<(), Box<dyn std::error::Error>> {
         let mut server = mockito::Server::new();
@@ -1281,29 +1459,29 @@ mod get_project {
 
         let versions_mock = mock_json_get(
             &mut server,
-            "/_iri/621a5fdf587a3ecc878a98c8be2240dd5bbe561860d11f4da1ece4a4fe2fb8b5/versions.json",
+            "/_iri/b02753fd9753cbdfd9a866105296ae83b0522416e37f3e9fd4e1c15e86020e5b/versions.json",
             versions_json_body([("1.0.0", "[]")]),
         );
 
         let project_json_mock = mock_json_get(
             &mut server,
-            "/_iri/621a5fdf587a3ecc878a98c8be2240dd5bbe561860d11f4da1ece4a4fe2fb8b5/1.0.0/.project.json",
+            "/_iri/b02753fd9753cbdfd9a866105296ae83b0522416e37f3e9fd4e1c15e86020e5b/1.0.0/.project.json",
             info_json,
         );
 
         let meta_json_mock = mock_json_get(
             &mut server,
-            "/_iri/621a5fdf587a3ecc878a98c8be2240dd5bbe561860d11f4da1ece4a4fe2fb8b5/1.0.0/.meta.json",
+            "/_iri/b02753fd9753cbdfd9a866105296ae83b0522416e37f3e9fd4e1c15e86020e5b/1.0.0/.meta.json",
             meta_json,
         );
 
-        let project = env.get_project("urn:kpar:b", "1.0.0")?;
+        let project = env.get_project("urn:example:b", "1.0.0")?;
 
         let inner = &project.inner;
         assert_eq!(
             inner.archive.url().as_str(),
             format!(
-                "{}/_iri/621a5fdf587a3ecc878a98c8be2240dd5bbe561860d11f4da1ece4a4fe2fb8b5/1.0.0/project.kpar",
+                "{}/_iri/b02753fd9753cbdfd9a866105296ae83b0522416e37f3e9fd4e1c15e86020e5b/1.0.0/project.kpar",
                 server.url()
             )
         );
@@ -1358,14 +1536,18 @@ mod get_project {
             info.usage[0],
             InterchangeProjectUsageRaw::Resource {
                 resource: purl("admin/dep"),
-                version_constraint: Some("<2".to_string())
+                version_constraint: Some("<2".to_string()),
+                when: None,
+                hash: None,
             }
         );
         assert_eq!(
             info.usage[1],
             InterchangeProjectUsageRaw::Resource {
                 resource: purl("admin/other"),
-                version_constraint: None
+                version_constraint: None,
+                when: None,
+                hash: None,
             }
         );
         versions_mock.assert();
@@ -1471,7 +1653,9 @@ mod get_project {
             info.usage[0],
             InterchangeProjectUsageRaw::Resource {
                 resource: purl("x/y"),
-                version_constraint: Some(">=1".to_string())
+                version_constraint: Some(">=1".to_string()),
+                when: None,
+                hash: None,
             }
         );
         assert_eq!(
@@ -0,0 +1,245 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Local environment with a second layer of fixed install locations.
+//! Projects are installed in the environment as usual, and projects with
+//! a fixed location are additionally installed there, e.g. where another
+//! tool expects to find its libraries. The environment stays the source of
+//! truth: a project counts as installed only if it is present in both
+//! layers, and removing it from the environment also removes it from its
+//! fixed location.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use indexmap::IndexMap;
+use thiserror::Error;
+
+use crate::{
+    env::{
+        OverwritePolicy, ProjectChecksumResult, PutProjectError, ReadEnvironment, WriteEnvironment,
+        local_directory::{
+            LocalDirectoryEnvironment, LocalReadError, LocalWriteError, lazy::LazySrcProject,
+        },
+        utils::clone_project,
+    },
+    lock::Source,
+    project::{
+        ProjectChecksum,
+        local_src::LocalSrcProject,
+        utils::{FsIoError, wrapfs},
+    },
+    utils::format_err,
+};
+
+#[derive(Error, Debug)]
+pub enum LayeredWriteError {
+    #[error(transparent)]
+    Local(#[from] LocalWriteError),
+    #[error(
+        "install location `{0}` is not empty and does not contain a project;\n\
+        refusing to overwrite it"
+    )]
+    LocationOccupied(Utf8PathBuf),
+    #[error("failed to install project at `{0}`: {1}")]
+    Install(Utf8PathBuf, String),
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+}
+
+/// `LocalDirectoryEnvironment` that also installs the projects in
+/// `locations` (by IRI) at the given directories
+#[derive(Debug, Clone)]
+pub struct LayeredEnvironment {
+    pub inner: LocalDirectoryEnvironment,
+    locations: IndexMap<String, Utf8PathBuf>,
+}
+
+impl LayeredEnvironment {
+    pub fn new(inner: LocalDirectoryEnvironment, locations: IndexMap<String, Utf8PathBuf>) -> Self {
+        Self { inner, locations }
+    }
+
+    pub fn into_inner(self) -> LocalDirectoryEnvironment {
+        self.inner
+    }
+
+    /// Fixed install location of `uri`, if any
+    pub fn location<S: AsRef<str>>(&self, uri: S) -> Option<&Utf8Path> {
+        self.locations.get(uri.as_ref()).map(Utf8PathBuf::as_path)
+    }
+}
+
+/// Replace the contents of `location` with a copy of `project`
+fn install_at(project: &LocalSrcProject, location: &Utf8Path) -> Result<(), LayeredWriteError> {
+    log::info!("{:>12} also at `{location}`", ' ');
+    remove_location(location)?;
+    wrapfs::create_dir_all(location)?;
+    let mut target = LocalSrcProject {
+        nominal_path: None,
+        project_path: location.to_owned(),
+        expected_checksum: None,
+    };
+    clone_project(project, &mut target, true)
+        .map_err(|e| LayeredWriteError::Install(location.to_owned(), format_err(e)))?;
+    Ok(())
+}
+
+/// Version of the project at `location`, `None` if there is none
+fn installed_version(location: &Utf8Path) -> Option<String> {
+    let project = LocalSrcProject {
+        nominal_path: None,
+        project_path: location.to_owned(),
+        expected_checksum: None,
+    };
+    match project.get_info() {
+        Ok(info) => info.map(|info| info.version),
+        Err(e) => {
+            log::debug!("failed to read project at install location `{location}`: {e}");
+            None
+        }
+    }
+}
+
+/// Remove the project at `location`. Directories that are not projects
+/// are only removed if they are empty, to avoid deleting unrelated files
+fn remove_location(location: &Utf8Path) -> Result<(), LayeredWriteError> {
+    if !wrapfs::is_dir(location)? {
+        return Ok(());
+    }
+    if wrapfs::is_file(location.join(".project.json"))? {
+        wrapfs::remove_dir_all(location)?;
+    } else if wrapfs::read_dir(location)?.next().is_some() {
+        return Err(LayeredWriteError::LocationOccupied(location.to_owned()));
+    }
+    Ok(())
+}
+
+impl ReadEnvironment for LayeredEnvironment {
+    type ReadError = LocalReadError;
+
+    type UriIter = <LocalDirectoryEnvironment as ReadEnvironment>::UriIter;
+
+    fn uris(&self) -> Result<Self::UriIter, Self::ReadError> {
+        self.inner.uris()
+    }
+
+    type VersionIter = <LocalDirectoryEnvironment as ReadEnvironment>::VersionIter;
+
+    fn versions<S: AsRef<str>>(&self, uri: S) -> Result<Self::VersionIter, Self::ReadError> {
+        self.inner.versions(uri)
+    }
+
+    type InterchangeProjectRead = LazySrcProject;
+
+    fn get_project<S: AsRef<str>, T: AsRef<str>>(
+        &self,
+        uri: S,
+        version: T,
+    ) -> Result<Self::InterchangeProjectRead, Self::ReadError> {
+        self.inner.get_project(uri, version)
+    }
+
+    fn has_version_verified<S: AsRef<str>, V: AsRef<str>>(
+        &self,
+        uri: S,
+        version: V,
+        checksum: &ProjectChecksum,
+    ) -> Result<ProjectChecksumResult, Self::ReadError> {
+        let result = self.inner.has_version_verified(&uri, &version, checksum)?;
+        if result == ProjectChecksumResult::Match
+            && let Some(location) = self.location(&uri)
+            && installed_version(location).as_deref() != Some(version.as_ref())
+        {
+            // Installed again to restore the copy at `location`
+            return Ok(ProjectChecksumResult::VersionNotFound);
+        }
+        Ok(result)
+    }
+}
+
+impl WriteEnvironment for LayeredEnvironment {
+    type WriteError = LayeredWriteError;
+
+    type InterchangeProjectMut = LocalSrcProject;
+
+    fn put_project<S: AsRef<str>, T: AsRef<str>, F, CE>(
+        &mut self,
+        uri: S,
+        version: T,
+        checksum: Option<ProjectChecksum>,
+        overwrite: OverwritePolicy,
+        write_project: F,
+    ) -> Result<Self::InterchangeProjectMut, PutProjectError<Self::WriteError, CE>>
+    where
+        F: FnOnce(&mut Self::InterchangeProjectMut) -> Result<(), CE>,
+    {
+        let project = self
+            .inner
+            .put_project(&uri, version, checksum, overwrite, write_project)
+            .map_err(|e| match e {
+                PutProjectError::Write(e) => PutProjectError::Write(e.into()),
+                PutProjectError::Callback(e) => PutProjectError::Callback(e),
+                PutProjectError::IriParse(iri, e) => PutProjectError::IriParse(iri, e),
+                PutProjectError::AlreadyInstalled { uri, version } => {
+                    PutProjectError::AlreadyInstalled { uri, version }
+                }
+            })?;
+        if let Some(location) = self.location(&uri) {
+            install_at(&project, location)?;
+        }
+        Ok(project)
+    }
+
+    fn del_project_version<S: AsRef<str>, T: AsRef<str>>(
+        &mut self,
+        uri: S,
+        version: T,
+    ) -> Result<(), Self::WriteError> {
+        self.inner.ensure_writable()?;
+        if let Some(location) = self.location(&uri)
+            && installed_version(location).as_deref() == Some(version.as_ref())
+        {
+            remove_location(location)?;
+        }
+        Ok(self.inner.del_project_version(uri, version)?)
+    }
+
+    fn del_uri<S: AsRef<str>>(&mut self, uri: S) -> Result<(), Self::WriteError> {
+        self.inner.ensure_writable()?;
+        if let Some(location) = self.location(&uri) {
+            remove_location(location)?;
+        }
+        Ok(self.inner.del_uri(uri)?)
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.inner.is_read_only()
+    }
+
+    /// Projects with a fixed install location are always installed in full
+    fn supports_metadata_only<S: AsRef<str>>(&self, uri: S) -> bool {
+        self.location(&uri).is_none() && self.inner.supports_metadata_only(uri)
+    }
+
+    fn set_metadata_only<S: AsRef<str>, T: AsRef<str>>(
+        &mut self,
+        uri: S,
+        version: T,
+        sources: Vec<Source>,
+    ) -> Result<(), Self::WriteError> {
+        Ok(self.inner.set_metadata_only(uri, version, sources)?)
+    }
+
+    fn record_install<S: AsRef<str>, T: AsRef<str>>(
+        &mut self,
+        uri: S,
+        version: T,
+        source: Option<&Source>,
+        checksum: Option<&ProjectChecksum>,
+    ) -> Result<(), Self::WriteError> {
+        Ok(self.inner.record_install(uri, version, source, checksum)?)
+    }
+}
+
+#[cfg(test)]
+#[path = "./layered_tests.rs"]
+mod tests;
@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::io::Cursor;
+
+use camino::Utf8PathBuf;
+use camino_tempfile::{Utf8TempDir, tempdir};
+use indexmap::IndexMap;
+use typed_path::Utf8UnixPath;
+
+use super::{LayeredEnvironment, LayeredWriteError};
+use crate::{
+    env::{
+        DEFAULT_ENV_NAME, OverwritePolicy, ProjectChecksumResult, PutProjectError, ReadEnvironment,
+        WriteEnvironment, local_directory::LocalDirectoryEnvironment, utils::clone_project,
+    },
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw, format_created_now},
+    project::{ProjectChecksum, ProjectMut, memory::InMemoryProject},
+};
+
+const URI: &str = "urn:sysand_test:tool_lib";
+
+fn source_project(version: &str) -> InMemoryProject {
+    let info = InterchangeProjectInfoRaw {
+        name: "tool_lib".to_string(),
+        publisher: None,
+        description: None,
+        version: version.to_string(),
+        license: None,
+        maintainer: vec![],
+        website: None,
+        topic: vec![],
+        usage: vec![],
+    };
+    let meta = InterchangeProjectMetadataRaw {
+        index: IndexMap::from([("Lib".to_string(), "Lib.sysml".to_string())]),
+        created: format_created_now(),
+        metamodel: None,
+        includes_derived: None,
+        includes_implied: None,
+        checksum: None,
+        variants: IndexMap::new(),
+    };
+    let mut project = InMemoryProject::default();
+    project.put_project(&info, &meta, true).unwrap();
+    project
+        .write_source(
+            Utf8UnixPath::new("Lib.sysml"),
+            &mut Cursor::new("package Lib;"),
+            true,
+        )
+        .unwrap();
+    project
+}
+
+fn make_env() -> (Utf8TempDir, Utf8PathBuf, LayeredEnvironment) {
+    let cwd = tempdir().unwrap();
+    let env_dir = cwd.path().join(DEFAULT_ENV_NAME);
+    std::fs::create_dir(&env_dir).unwrap();
+    let env = LocalDirectoryEnvironment::create(&env_dir).unwrap();
+    let location = cwd.path().join("tool/libs/tool_lib");
+    let locations = IndexMap::from([(URI.to_string(), location.clone())]);
+    (cwd, location, LayeredEnvironment::new(env, locations))
+}
+
+fn checksum() -> ProjectChecksum {
+    ProjectChecksum::Project("abc".to_string())
+}
+
+fn install(
+    env: &mut LayeredEnvironment,
+    version: &str,
+) -> Result<(), PutProjectError<LayeredWriteError, String>> {
+    let source = source_project(version);
+    env.put_project(
+        URI,
+        version,
+        Some(checksum()),
+        OverwritePolicy::Replace,
+        |p| {
+            clone_project(&source, p, true)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        },
+    )
+    .map(|_| ())
+}
+
+#[test]
+fn installs_at_location() {
+    let (_cwd, location, mut env) = make_env();
+    install(&mut env, "1.0.0").unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(location.join("Lib.sysml")).unwrap(),
+        "package Lib;"
+    );
+    assert!(location.join(".project.json").is_file());
+    assert_eq!(
+        env.has_version_verified(URI, "1.0.0", &checksum()).unwrap(),
+        ProjectChecksumResult::Match
+    );
+    // Still installed in the environment itself
+    let project = env.get_project(URI, "1.0.0").unwrap();
+    assert!(project.project_path.starts_with(env.inner.root_path()));
+}
+
+#[test]
+fn missing_copy_is_not_installed() {
+    let (_cwd, location, mut env) = make_env();
+    install(&mut env, "1.0.0").unwrap();
+    std::fs::remove_dir_all(&location).unwrap();
+
+    assert_eq!(
+        env.inner
+            .has_version_verified(URI, "1.0.0", &checksum())
+            .unwrap(),
+        ProjectChecksumResult::Match
+    );
+    assert_eq!(
+        env.has_version_verified(URI, "1.0.0", &checksum()).unwrap(),
+        ProjectChecksumResult::VersionNotFound
+    );
+
+    install(&mut env, "1.0.0").unwrap();
+    assert!(location.join("Lib.sysml").is_file());
+}
+
+#[test]
+fn replaces_and_removes_copy() {
+    let (_cwd, location, mut env) = make_env();
+    install(&mut env, "1.0.0").unwrap();
+    install(&mut env, "2.0.0").unwrap();
+    assert!(
+        std::fs::read_to_string(location.join(".project.json"))
+            .unwrap()
+            .contains("2.0.0")
+    );
+
+    // Removing a version that is not at the location keeps the copy
+    env.del_project_version(URI, "1.0.0").unwrap();
+    assert!(location.join(".project.json").is_file());
+
+    env.del_project_version(URI, "2.0.0").unwrap();
+    assert!(!location.exists());
+    assert!(!env.has(URI).unwrap());
+}
+
+#[test]
+fn does_not_overwrite_unrelated_files() {
+    let (_cwd, location, mut env) = make_env();
+    std::fs::create_dir_all(&location).unwrap();
+    std::fs::write(location.join("notes.txt"), "keep me").unwrap();
+
+    let err = install(&mut env, "1.0.0").unwrap_err();
+    assert!(matches!(
+        err,
+        PutProjectError::Write(LayeredWriteError::LocationOccupied(path)) if path == location
+    ));
+    assert_eq!(
+        std::fs::read_to_string(location.join("notes.txt")).unwrap(),
+        "keep me"
+    );
+}
@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Installed projects stored as compressed KPARs, see
+//! [`EnvStorage::Kpar`](crate::config::EnvStorage::Kpar). All files of such
+//! a project are kept in `project.kpar` in its directory, and only
+//! `.project.json` and `.meta.json` are extracted next to it.
+
+use std::io;
+
+use camino::Utf8Path;
+use walkdir::WalkDir;
+use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+use crate::project::{
+    local_kpar::LocalKParError,
+    utils::{FsIoError, ZipArchiveError, wrapfs},
+};
+
+pub const ARCHIVE_PATH: &str = "project.kpar";
+
+/// Files kept extracted next to the archive
+pub(super) const EXTRACTED: [&str; 2] = [".project.json", ".meta.json"];
+
+/// Pack all files of the project in `dir` into [`ARCHIVE_PATH`], and remove
+/// them except `.project.json` and `.meta.json`. Returns whether the archive
+/// was written, which it is not if the project has no other files, e.g. if
+/// only its metadata is installed
+pub(super) fn pack_project(dir: &Utf8Path) -> Result<bool, LocalKParError> {
+    let mut files = vec![];
+    for entry in WalkDir::new(dir).min_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|e| {
+            let path = e.path().unwrap_or(dir.as_std_path()).to_string_lossy();
+            FsIoError::ReadDir(path.as_ref().into(), e.into())
+        })?;
+        if entry.file_type().is_file() {
+            let path = Utf8Path::from_path(entry.path())
+                .expect("BUG: path in project directory is not UTF-8")
+                .to_owned();
+            files.push(path);
+        }
+    }
+    let relative = |path: &Utf8Path| {
+        path.strip_prefix(dir)
+            .expect("BUG: file is not in project directory")
+            .components()
+            .map(|c| c.as_str())
+            .collect::<Vec<_>>()
+            .join("/")
+    };
+    if files
+        .iter()
+        .all(|path| EXTRACTED.contains(&relative(path).as_str()))
+    {
+        return Ok(false);
+    }
+
+    let archive_path = dir.join(ARCHIVE_PATH);
+    let mut zip = ZipWriter::new(wrapfs::File::create(&archive_path)?);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for path in &files {
+        let name = relative(path);
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| ZipArchiveError::Write(path.as_path().into(), e))?;
+        let mut file = wrapfs::File::open(path)?;
+        io::copy(&mut file, &mut zip).map_err(|e| FsIoError::ReadFile(path.clone(), e))?;
+    }
+    zip.finish()
+        .map_err(|e| ZipArchiveError::Finish(archive_path.as_path().into(), e))?;
+
+    for path in &files {
+        if !EXTRACTED.contains(&relative(path).as_str()) {
+            wrapfs::remove_file(path)?;
+        }
+    }
+    // Directories left empty
+    for entry in WalkDir::new(dir).min_depth(1).contents_first(true) {
+        if let Ok(entry) = entry
+            && entry.file_type().is_dir()
+        {
+            let _ = std::fs::remove_dir(entry.path());
+        }
+    }
+    Ok(true)
+}
+
+/// Extract the files in the archive of the project in `dir` next to it
+pub(super) fn unpack_project(dir: &Utf8Path) -> Result<(), LocalKParError> {
+    let archive_path = dir.join(ARCHIVE_PATH);
+    let mut zip = ZipArchive::new(wrapfs::File::open(&archive_path)?)
+        .map_err(|e| ZipArchiveError::read_archive(archive_path.as_path().into(), e))?;
+    zip.extract(dir)
+        .map_err(|e| ZipArchiveError::read_archive(archive_path.as_path().into(), e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "./archive_tests.rs"]
+mod tests;
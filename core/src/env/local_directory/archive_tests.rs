@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::io::{Cursor, Read};
+
+use camino_tempfile::tempdir;
+use indexmap::IndexMap;
+use typed_path::Utf8UnixPath;
+
+use super::ARCHIVE_PATH;
+use crate::{
+    commands::env::do_env_install_project,
+    config::EnvStorage,
+    env::{
+        DEFAULT_ENV_NAME, OverwritePolicy, ReadEnvironment,
+        local_directory::{LocalDirectoryEnvironment, provenance::InstallStatus},
+    },
+    init::do_init_memory,
+    project::{ProjectMut, ProjectRead},
+};
+
+const URI: &str = "urn:kpar:lib";
+
+#[test]
+fn kpar_storage_keeps_files_in_archive() {
+    let mut project = do_init_memory("Lib", None::<&str>, "1.0.0", None).unwrap();
+    let mut meta = project.get_meta().unwrap().unwrap();
+    meta.index = IndexMap::from([("Lib".to_string(), "src/Lib.sysml".to_string())]);
+    let info = project.get_info().unwrap().unwrap();
+    project.put_project(&info, &meta, true).unwrap();
+    project
+        .write_source(
+            Utf8UnixPath::new("src/Lib.sysml"),
+            &mut Cursor::new("package Lib;"),
+            true,
+        )
+        .unwrap();
+
+    let cwd = tempdir().unwrap();
+    let env_dir = cwd.path().join(DEFAULT_ENV_NAME);
+    std::fs::create_dir(&env_dir).unwrap();
+    let mut env = LocalDirectoryEnvironment::create(&env_dir)
+        .unwrap()
+        .with_storage(EnvStorage::Kpar);
+    do_env_install_project(
+        URI,
+        "1.0.0",
+        &project,
+        None,
+        &mut env,
+        OverwritePolicy::Error,
+        false,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let installed = env.projects()[0].clone();
+    assert!(installed.archived);
+    let dir = env_dir.join(installed.path.as_str());
+    assert!(dir.join(ARCHIVE_PATH).is_file());
+    assert!(dir.join(".project.json").is_file());
+    assert!(!dir.join("src").exists());
+    let reread = LocalDirectoryEnvironment::read(&env_dir).unwrap();
+    assert!(reread.projects()[0].archived);
+    assert_eq!(
+        env.install_status(&installed).unwrap(),
+        InstallStatus::Unchanged
+    );
+
+    let stored = env.get_project(URI, "1.0.0").unwrap();
+    assert!(!stored.is_extracted().unwrap());
+    let mut src = String::new();
+    stored
+        .read_source("src/Lib.sysml")
+        .unwrap()
+        .read_to_string(&mut src)
+        .unwrap();
+    assert_eq!(src, "package Lib;");
+
+    stored.materialize().unwrap();
+    assert!(stored.is_extracted().unwrap());
+    assert_eq!(
+        std::fs::read_to_string(dir.join("src/Lib.sysml")).unwrap(),
+        "package Lib;"
+    );
+}
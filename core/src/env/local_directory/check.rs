@@ -0,0 +1,231 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Consistency checks of a [`LocalDirectoryEnvironment`], to find and
+//! repair what interrupted installs and removals leave behind, and
+//! installed projects that were changed in place.
+
+use std::{collections::HashSet, fmt};
+
+use camino::Utf8PathBuf;
+
+use crate::{
+    env::local_directory::{
+        LocalDirectoryEnvironment, PROJECT_PATH_PREFIX, provenance::InstallStatus,
+    },
+    project::utils::{FsIoError, wrapfs},
+};
+
+/// Inconsistency between `env.toml` and the projects installed in `lib/`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvProblem {
+    /// Entry in `env.toml` whose project directory does not exist
+    MissingDir {
+        iri: String,
+        version: String,
+        path: String,
+    },
+    /// Entry in `env.toml` whose project directory does not contain a
+    /// valid project of the recorded version
+    BrokenProject {
+        iri: String,
+        version: String,
+        path: String,
+        reason: String,
+    },
+    /// Installed project whose contents changed after it was installed,
+    /// according to its `.sysand-install.json`
+    Modified {
+        iri: String,
+        version: String,
+        path: String,
+    },
+    /// Entry with the same IRI and version as an earlier entry
+    DuplicateEntry {
+        iri: String,
+        version: String,
+        path: String,
+    },
+    /// File or directory in `lib/` that no entry in `env.toml` refers to
+    Orphaned { path: String },
+}
+
+impl fmt::Display for EnvProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvProblem::MissingDir { iri, version, path } => write!(
+                f,
+                "project `{iri}` {version} is recorded at `{path}`, which does not exist"
+            ),
+            EnvProblem::BrokenProject {
+                iri,
+                version,
+                path,
+                reason,
+            } => write!(
+                f,
+                "project `{iri}` {version} at `{path}` is broken: {reason}"
+            ),
+            EnvProblem::Modified { iri, version, path } => write!(
+                f,
+                "project `{iri}` {version} at `{path}` was modified after it was installed"
+            ),
+            EnvProblem::DuplicateEntry { iri, version, path } => write!(
+                f,
+                "project `{iri}` {version} at `{path}` is recorded more than once"
+            ),
+            EnvProblem::Orphaned { path } => {
+                write!(f, "`{path}` does not belong to any installed project")
+            }
+        }
+    }
+}
+
+impl EnvProblem {
+    /// Whether `repair` removes the entry of the project from `env.toml`
+    fn removes_entry(&self) -> bool {
+        !matches!(self, EnvProblem::Orphaned { .. })
+    }
+}
+
+impl LocalDirectoryEnvironment {
+    /// Find inconsistencies between `env.toml` and the installed projects,
+    /// and installed projects modified after they were installed. Editable
+    /// projects are not owned by the environment and are not checked
+    pub fn check(&self) -> Result<Vec<EnvProblem>, Box<FsIoError>> {
+        Ok(self.find_problems()?.into_iter().map(|(_, p)| p).collect())
+    }
+
+    /// Repair the inconsistencies found by [`check`](Self::check): remove
+    /// entries of missing, broken and duplicate projects from `env.toml`,
+    /// then remove everything in `lib/` not referred to by the remaining
+    /// entries. Returns the problems that were repaired. Removed projects
+    /// are installed again by the next sync
+    pub fn repair(&mut self) -> Result<Vec<EnvProblem>, Box<FsIoError>> {
+        let problems = self.find_problems()?;
+        if problems.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let removed: HashSet<usize> = problems
+            .iter()
+            .filter(|(_, p)| p.removes_entry())
+            .filter_map(|(idx, _)| *idx)
+            .collect();
+        let mut idx = 0;
+        self.metadata.projects.retain(|_| {
+            idx += 1;
+            !removed.contains(&(idx - 1))
+        });
+        self.write()?;
+
+        let kept: HashSet<String> = self.installed_dirs();
+        for path in self.lib_entries()? {
+            if kept.contains(&path) {
+                continue;
+            }
+            let absolute = self.root_dir.join(&path);
+            log::debug!("removing `{absolute}`");
+            if wrapfs::metadata(&absolute)?.is_dir() {
+                wrapfs::remove_dir_all(&absolute)?;
+            } else {
+                wrapfs::remove_file(&absolute)?;
+            }
+        }
+
+        Ok(problems.into_iter().map(|(_, p)| p).collect())
+    }
+
+    /// Problems with the index of the entry they concern, if any
+    fn find_problems(&self) -> Result<Vec<(Option<usize>, EnvProblem)>, Box<FsIoError>> {
+        let mut problems = vec![];
+        let mut seen = HashSet::new();
+        for (idx, project) in self.metadata.projects.iter().enumerate() {
+            if !project.is_installed() {
+                continue;
+            }
+            let iri = project.identifiers.first().cloned().unwrap_or_default();
+            let version = project.version.clone();
+            let path = project.path.to_string();
+            if !project
+                .identifiers
+                .iter()
+                .all(|i| seen.insert((i.clone(), version.clone())))
+            {
+                problems.push((Some(idx), EnvProblem::DuplicateEntry { iri, version, path }));
+                continue;
+            }
+
+            let storage = self.get_project_storage(project);
+            if !storage.project_path.is_dir() {
+                problems.push((Some(idx), EnvProblem::MissingDir { iri, version, path }));
+                continue;
+            }
+            let reason = match (storage.get_info(), storage.get_meta()) {
+                (Err(e), _) | (_, Err(e)) => Some(e.to_string()),
+                (Ok(None), _) => Some("missing `.project.json`".to_owned()),
+                (_, Ok(None)) => Some("missing `.meta.json`".to_owned()),
+                (Ok(Some(info)), Ok(Some(_))) if info.version != version => {
+                    Some(format!("`.project.json` has version {}", info.version))
+                }
+                (Ok(Some(_)), Ok(Some(_))) => match self.install_status(project) {
+                    Ok(InstallStatus::Modified) => {
+                        problems.push((Some(idx), EnvProblem::Modified { iri, version, path }));
+                        continue;
+                    }
+                    Ok(InstallStatus::Unchanged | InstallStatus::Unknown) => None,
+                    Err(e) => Some(e.to_string()),
+                },
+            };
+            if let Some(reason) = reason {
+                problems.push((
+                    Some(idx),
+                    EnvProblem::BrokenProject {
+                        iri,
+                        version,
+                        path,
+                        reason,
+                    },
+                ));
+            }
+        }
+
+        let installed = self.installed_dirs();
+        for path in self.lib_entries()? {
+            if !installed.contains(&path) {
+                problems.push((None, EnvProblem::Orphaned { path }));
+            }
+        }
+        Ok(problems)
+    }
+
+    /// Paths of installed projects, relative to the env directory
+    fn installed_dirs(&self) -> HashSet<String> {
+        self.metadata
+            .projects
+            .iter()
+            .filter(|p| p.is_installed())
+            .map(|p| p.path.to_string())
+            .collect()
+    }
+
+    /// Paths of everything directly in `lib/`, relative to the env
+    /// directory and sorted
+    fn lib_entries(&self) -> Result<Vec<String>, Box<FsIoError>> {
+        let lib_dir: Utf8PathBuf = self.root_dir.join(PROJECT_PATH_PREFIX);
+        if !lib_dir.is_dir() {
+            return Ok(vec![]);
+        }
+        let mut entries = vec![];
+        for entry in wrapfs::read_dir(&lib_dir)? {
+            let entry = entry.map_err(|e| FsIoError::ReadDir(lib_dir.clone(), e))?;
+            entries.push(format!("{PROJECT_PATH_PREFIX}{}", entry.file_name()));
+        }
+        entries.sort();
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+#[path = "./check_tests.rs"]
+mod tests;
@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use camino_tempfile::{Utf8TempDir, tempdir};
+
+use super::EnvProblem;
+use crate::{
+    commands::env::do_env_install_project,
+    env::{
+        DEFAULT_ENV_NAME, OverwritePolicy, ReadEnvironment,
+        local_directory::LocalDirectoryEnvironment,
+    },
+    init::do_init_memory,
+};
+
+fn make_env(projects: &[(&str, &str)]) -> (Utf8TempDir, LocalDirectoryEnvironment) {
+    let cwd = tempdir().unwrap();
+    let env_dir = cwd.path().join(DEFAULT_ENV_NAME);
+    std::fs::create_dir(&env_dir).unwrap();
+    let mut env = LocalDirectoryEnvironment::create(&env_dir).unwrap();
+    for (name, version) in projects {
+        let project = do_init_memory(*name, None::<&str>, *version, None).unwrap();
+        do_env_install_project(
+            format!("urn:kpar:{name}"),
+            version,
+            &project,
+            None,
+            &mut env,
+            OverwritePolicy::Error,
+            false,
+            None,
+            None,
+        )
+        .unwrap();
+    }
+    (cwd, env)
+}
+
+fn project_dir(env: &LocalDirectoryEnvironment, name: &str) -> camino::Utf8PathBuf {
+    let project = env
+        .projects()
+        .iter()
+        .find(|p| p.name == name)
+        .expect("project is installed");
+    env.root_path().join(project.path.as_str())
+}
+
+#[test]
+fn healthy_env_has_no_problems() {
+    let (_cwd, env) = make_env(&[("a", "1.0.0"), ("b", "2.0.0")]);
+    assert_eq!(env.check().unwrap(), []);
+}
+
+#[test]
+fn check_finds_problems() {
+    let (_cwd, env) = make_env(&[("a", "1.0.0"), ("b", "2.0.0"), ("c", "3.0.0")]);
+    std::fs::remove_dir_all(project_dir(&env, "a")).unwrap();
+    std::fs::remove_file(project_dir(&env, "b").join(".meta.json")).unwrap();
+    std::fs::create_dir(env.root_path().join("lib/leftover")).unwrap();
+
+    let problems = env.check().unwrap();
+    assert_eq!(problems.len(), 3, "{problems:?}");
+    assert!(matches!(
+        &problems[0],
+        EnvProblem::MissingDir { iri, version, .. } if iri == "urn:kpar:a" && version == "1.0.0"
+    ));
+    assert!(matches!(
+        &problems[1],
+        EnvProblem::BrokenProject { iri, reason, .. }
+            if iri == "urn:kpar:b" && reason == "missing `.meta.json`"
+    ));
+    assert_eq!(
+        problems[2],
+        EnvProblem::Orphaned {
+            path: "lib/leftover".into()
+        }
+    );
+}
+
+#[test]
+fn repair_removes_broken_entries_and_orphans() {
+    let (_cwd, mut env) = make_env(&[("a", "1.0.0"), ("b", "2.0.0"), ("c", "3.0.0")]);
+    let b_dir = project_dir(&env, "b");
+    std::fs::remove_dir_all(project_dir(&env, "a")).unwrap();
+    std::fs::remove_file(b_dir.join(".meta.json")).unwrap();
+    std::fs::write(env.root_path().join("lib/leftover.tmp"), "").unwrap();
+
+    assert_eq!(env.repair().unwrap().len(), 3);
+    assert_eq!(env.check().unwrap(), []);
+    assert!(!b_dir.exists());
+    assert!(!env.root_path().join("lib/leftover.tmp").exists());
+
+    let reread = LocalDirectoryEnvironment::read(env.root_path()).unwrap();
+    let names: Vec<&str> = reread.projects().iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["c"]);
+    assert!(reread.get_project("urn:kpar:c", "3.0.0").is_ok());
+}
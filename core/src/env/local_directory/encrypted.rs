@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Installed projects stored encrypted, see
+//! [`EnvStorage::Encrypted`](crate::config::EnvStorage::Encrypted). Their
+//! source files are encrypted at rest (AES-256-GCM), while `.project.json`
+//! and `.meta.json` are kept in plain text, as they are needed for
+//! resolution without the key. Sources are decrypted when read through
+//! `ProjectRead`.
+
+#[cfg(feature = "encryption")]
+use std::fmt;
+
+#[cfg(feature = "encryption")]
+use aes_gcm::{
+    Aes256Gcm, Key, KeyInit, Nonce,
+    aead::{Aead, AeadCore, OsRng, Payload},
+};
+#[cfg(feature = "encryption")]
+use camino::Utf8Path;
+use thiserror::Error;
+#[cfg(feature = "encryption")]
+use walkdir::WalkDir;
+
+use crate::project::utils::FsIoError;
+#[cfg(feature = "encryption")]
+use crate::{env::local_directory::archive::EXTRACTED, project::utils::wrapfs};
+
+#[derive(Error, Debug)]
+pub enum EncryptionError {
+    #[error(
+        "projects are stored encrypted, but no encryption key is set;\n\
+        set `env.key_file` in the config or the `SYSAND_ENV_KEY` environment variable"
+    )]
+    MissingKey,
+    #[error("failed to decrypt source file `{0}`: wrong encryption key or the file is corrupted")]
+    Decrypt(Box<str>),
+    #[error("source file `{0}` is not encrypted")]
+    NotEncrypted(Box<str>),
+    #[error("failed to encrypt source file `{0}`")]
+    Encrypt(Box<str>),
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+}
+
+impl From<FsIoError> for EncryptionError {
+    fn from(v: FsIoError) -> Self {
+        Self::Io(Box::new(v))
+    }
+}
+
+#[cfg(feature = "encryption")]
+/// Prefix of every encrypted source file, followed by the nonce and the
+/// ciphertext.
+const MAGIC: &[u8; 8] = b"SYSENC1\0";
+#[cfg(feature = "encryption")]
+const NONCE_LEN: usize = 12;
+
+#[cfg(feature = "encryption")]
+/// 256-bit key used to encrypt environment sources.
+#[derive(Clone)]
+pub struct EnvKey([u8; 32]);
+
+#[cfg(feature = "encryption")]
+#[derive(Error, Debug)]
+pub enum EnvKeyParseError {
+    #[error("encryption key must be 64 hexadecimal characters (32 bytes), got {0} characters")]
+    Length(usize),
+    #[error("encryption key is not valid hexadecimal: {0}")]
+    Hex(#[from] hex::FromHexError),
+}
+
+#[cfg(feature = "encryption")]
+impl EnvKey {
+    pub fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Parse a key given as 64 hexadecimal characters.
+    pub fn from_hex<S: AsRef<str>>(key: S) -> Result<Self, EnvKeyParseError> {
+        let key = key.as_ref().trim();
+        if key.len() != 64 {
+            return Err(EnvKeyParseError::Length(key.len()));
+        }
+        let mut bytes = [0; 32];
+        hex::decode_to_slice(key, &mut bytes)?;
+        Ok(Self(bytes))
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.0))
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl fmt::Debug for EnvKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EnvKey(<redacted>)")
+    }
+}
+
+#[cfg(feature = "encryption")]
+/// Encrypt all files of the project in `dir` in place, except
+/// `.project.json` and `.meta.json`. Returns whether any file was
+/// encrypted, which none is if only the metadata of the project is
+/// installed
+pub(super) fn encrypt_project(dir: &Utf8Path, key: &EnvKey) -> Result<bool, EncryptionError> {
+    let mut encrypted = false;
+    for entry in WalkDir::new(dir).min_depth(1).sort_by_file_name() {
+        let entry = entry.map_err(|e| {
+            let path = e.path().unwrap_or(dir.as_std_path()).to_string_lossy();
+            FsIoError::ReadDir(path.as_ref().into(), e.into())
+        })?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let path =
+            Utf8Path::from_path(entry.path()).expect("BUG: path in project directory is not UTF-8");
+        let relative = path
+            .strip_prefix(dir)
+            .expect("BUG: file is not in project directory")
+            .components()
+            .map(|c| c.as_str())
+            .collect::<Vec<_>>()
+            .join("/");
+        if EXTRACTED.contains(&relative.as_str()) {
+            continue;
+        }
+        let plain = wrapfs::read(path)?;
+        wrapfs::write(path, encrypt_source(key, &relative, &plain)?)?;
+        encrypted = true;
+    }
+    Ok(encrypted)
+}
+
+#[cfg(feature = "encryption")]
+fn encrypt_source(key: &EnvKey, path: &str, plain: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    // The path is bound to the ciphertext so that files cannot be swapped
+    let ciphertext = key
+        .cipher()
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plain,
+                aad: path.as_bytes(),
+            },
+        )
+        .map_err(|_| EncryptionError::Encrypt(path.into()))?;
+    let mut out = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+#[cfg(feature = "encryption")]
+/// Decrypt the contents `data` of the source file at `path` of a project
+pub(super) fn decrypt_source(
+    key: &EnvKey,
+    path: &str,
+    data: &[u8],
+) -> Result<Vec<u8>, EncryptionError> {
+    let Some(rest) = data.strip_prefix(MAGIC.as_slice()) else {
+        return Err(EncryptionError::NotEncrypted(path.into()));
+    };
+    if rest.len() < NONCE_LEN {
+        return Err(EncryptionError::Decrypt(path.into()));
+    }
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    key.cipher()
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: path.as_bytes(),
+            },
+        )
+        .map_err(|_| EncryptionError::Decrypt(path.into()))
+}
+
+#[cfg(all(test, feature = "encryption"))]
+#[path = "./encrypted_tests.rs"]
+mod tests;
@@ -0,0 +1,169 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::io::{Cursor, Read as _};
+
+use camino_tempfile::{Utf8TempDir, tempdir};
+use indexmap::IndexMap;
+use typed_path::Utf8UnixPath;
+
+use super::{EncryptionError, EnvKey, EnvKeyParseError};
+use crate::{
+    config::EnvStorage,
+    env::{
+        DEFAULT_ENV_NAME, OverwritePolicy, ReadEnvironment, WriteEnvironment,
+        local_directory::{LocalDirectoryEnvironment, LocalWriteError, lazy::LazySrcError},
+        utils::clone_project,
+    },
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw, format_created_now},
+    project::{ProjectMut, ProjectRead, memory::InMemoryProject},
+};
+
+const URI: &str = "urn:sysand_test:secret";
+const SOURCE: &str = "package Secret;";
+
+fn key(byte: u8) -> EnvKey {
+    EnvKey::new([byte; 32])
+}
+
+fn source_project() -> InMemoryProject {
+    let info = InterchangeProjectInfoRaw {
+        name: "secret".to_string(),
+        publisher: None,
+        description: None,
+        version: "1.0.0".to_string(),
+        license: None,
+        maintainer: vec![],
+        website: None,
+        topic: vec![],
+        usage: vec![],
+    };
+    let meta = InterchangeProjectMetadataRaw {
+        index: IndexMap::from([("Secret".to_string(), "Secret.sysml".to_string())]),
+        created: format_created_now(),
+        metamodel: None,
+        includes_derived: None,
+        includes_implied: None,
+        checksum: None,
+        variants: IndexMap::new(),
+    };
+    let mut project = InMemoryProject::default();
+    project.put_project(&info, &meta, true).unwrap();
+    project
+        .write_source(
+            Utf8UnixPath::new("Secret.sysml"),
+            &mut Cursor::new(SOURCE),
+            true,
+        )
+        .unwrap();
+    project
+}
+
+fn make_env() -> (Utf8TempDir, LocalDirectoryEnvironment) {
+    let cwd = tempdir().unwrap();
+    let env_dir = cwd.path().join(DEFAULT_ENV_NAME);
+    std::fs::create_dir(&env_dir).unwrap();
+    let env = LocalDirectoryEnvironment::create(&env_dir)
+        .unwrap()
+        .with_storage(EnvStorage::Encrypted);
+    (cwd, env)
+}
+
+fn install(env: &mut LocalDirectoryEnvironment, source: &InMemoryProject) {
+    env.put_project(URI, "1.0.0", None, OverwritePolicy::Replace, |p| {
+        clone_project(source, p, true).map(|_| ())
+    })
+    .unwrap();
+}
+
+#[test]
+fn parse_key() {
+    assert!(EnvKey::from_hex("ab".repeat(32)).is_ok());
+    assert!(matches!(
+        EnvKey::from_hex("abcd"),
+        Err(EnvKeyParseError::Length(4))
+    ));
+    assert!(matches!(
+        EnvKey::from_hex("zz".repeat(32)),
+        Err(EnvKeyParseError::Hex(_))
+    ));
+}
+
+#[test]
+fn sources_are_encrypted_at_rest() {
+    let (_cwd, env) = make_env();
+    let mut env = env.with_key(key(1));
+    let source = source_project();
+    install(&mut env, &source);
+
+    let installed = env.projects()[0].clone();
+    assert!(installed.encrypted);
+    let reread = LocalDirectoryEnvironment::read(env.root_path()).unwrap();
+    assert!(reread.projects()[0].encrypted);
+    let dir = env.root_path().join(installed.path.as_str());
+    let on_disk = std::fs::read(dir.join("Secret.sysml")).unwrap();
+    assert!(
+        !on_disk
+            .windows(SOURCE.len())
+            .any(|w| w == SOURCE.as_bytes())
+    );
+    // Needed for resolution without the key
+    assert!(
+        std::fs::read_to_string(dir.join(".project.json"))
+            .unwrap()
+            .contains("secret")
+    );
+
+    let project = env.get_project(URI, "1.0.0").unwrap();
+    assert!(project.is_encrypted());
+    let mut content = String::new();
+    project
+        .read_source("Secret.sysml")
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!(content, SOURCE);
+    assert_eq!(
+        project.checksum_canonical_hex().unwrap(),
+        source.checksum_canonical_hex().unwrap()
+    );
+}
+
+#[test]
+fn wrong_or_missing_key_fails_to_decrypt() {
+    let (_cwd, env) = make_env();
+    let mut env = env.with_key(key(1));
+    install(&mut env, &source_project());
+
+    let other = LocalDirectoryEnvironment::read(env.root_path())
+        .unwrap()
+        .with_key(key(2));
+    let project = other.get_project(URI, "1.0.0").unwrap();
+    assert!(matches!(
+        project.read_source("Secret.sysml"),
+        Err(LazySrcError::Encryption(EncryptionError::Decrypt(_)))
+    ));
+
+    let without_key = LocalDirectoryEnvironment::read(env.root_path()).unwrap();
+    let project = without_key.get_project(URI, "1.0.0").unwrap();
+    assert!(matches!(
+        project.read_source("Secret.sysml"),
+        Err(LazySrcError::NoKey(_))
+    ));
+}
+
+#[test]
+fn install_without_key_fails() {
+    let (_cwd, mut env) = make_env();
+    let source = source_project();
+    let result = env.put_project(URI, "1.0.0", None, OverwritePolicy::Replace, |p| {
+        clone_project(&source, p, true).map(|_| ())
+    });
+    assert!(matches!(
+        result,
+        Err(crate::env::PutProjectError::Write(
+            LocalWriteError::Encryption(EncryptionError::MissingKey)
+        ))
+    ));
+    assert!(env.projects().is_empty());
+}
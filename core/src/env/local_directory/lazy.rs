@@ -0,0 +1,361 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::{
+    fmt::Debug,
+    fs::File,
+    io::{self, Cursor, Read},
+    ops::Deref,
+    sync::{Arc, OnceLock},
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use thiserror::Error;
+use typed_path::Utf8UnixPath;
+
+use crate::{
+    context::ProjectContext,
+    env::local_directory::{
+        archive::{ARCHIVE_PATH, unpack_project},
+        encrypted::EncryptionError,
+    },
+    lock::Source,
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
+    project::{
+        CanonicalizationError, ProjectChecksum, ProjectRead,
+        local_kpar::{LocalKParError, LocalKParProjectRaw},
+        local_src::{LocalSrcError, LocalSrcProject},
+        utils::FsIoError,
+    },
+};
+#[cfg(feature = "encryption")]
+use crate::{
+    env::local_directory::encrypted::{EnvKey, decrypt_source},
+    project::utils::wrapfs,
+};
+
+type FetchFn = dyn Fn(&[Source], &mut LocalSrcProject) -> Result<(), String> + Send + Sync;
+
+/// Fetches the files of a project installed with only its `.project.json`
+/// and `.meta.json` from one of its `sources` into the installed project
+#[derive(Clone)]
+pub struct SourceFetcher(Arc<FetchFn>);
+
+impl SourceFetcher {
+    pub fn new<F>(fetch: F) -> Self
+    where
+        F: Fn(&[Source], &mut LocalSrcProject) -> Result<(), String> + Send + Sync + 'static,
+    {
+        Self(Arc::new(fetch))
+    }
+}
+
+impl Debug for SourceFetcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SourceFetcher")
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum LazySrcError {
+    #[error(transparent)]
+    Project(#[from] LocalSrcError),
+    #[error(
+        "project at `{0}` is installed without its sources, and\n\
+        they cannot be fetched here"
+    )]
+    NoFetcher(Utf8PathBuf),
+    #[error("failed to fetch sources of project at `{0}`:\n{1}")]
+    Fetch(Utf8PathBuf, String),
+    #[error(transparent)]
+    Archive(#[from] LocalKParError),
+    #[error(
+        "project at `{0}` is stored encrypted, and no encryption key is set;\n\
+        set `env.key_file` in the config or the `SYSAND_ENV_KEY` environment variable"
+    )]
+    NoKey(Utf8PathBuf),
+    #[error(transparent)]
+    Encryption(#[from] EncryptionError),
+}
+
+/// Source file of a [`LazySrcProject`]
+#[derive(Debug)]
+pub enum LazySrcReader {
+    File(File),
+    /// Contents of a file stored encrypted
+    Decrypted(Cursor<Vec<u8>>),
+}
+
+impl Read for LazySrcReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::File(file) => file.read(buf),
+            Self::Decrypted(contents) => contents.read(buf),
+        }
+    }
+}
+
+/// Project installed in a `LocalDirectoryEnvironment`. If only its
+/// `.project.json` and `.meta.json` are installed, its remaining files are
+/// fetched the first time a missing source is read. Files of a project
+/// stored as a KPAR are read from the archive unless they were extracted,
+/// and those of a project stored encrypted are decrypted
+#[derive(Clone, Debug)]
+pub struct LazySrcProject {
+    pub project: LocalSrcProject,
+    lazy: Option<LazySources>,
+    /// Archive of a project stored as a KPAR, opened on first read
+    archive: Option<Arc<OnceLock<LocalKParProjectRaw>>>,
+    /// Whether the source files of the project are encrypted
+    encrypted: bool,
+    /// Key to decrypt the source files with
+    #[cfg(feature = "encryption")]
+    key: Option<EnvKey>,
+}
+
+#[derive(Clone, Debug)]
+struct LazySources {
+    sources: Vec<Source>,
+    fetcher: Option<SourceFetcher>,
+    fetched: Arc<OnceLock<Result<(), String>>>,
+}
+
+impl LazySrcProject {
+    /// Project with all its files installed
+    pub fn new(project: LocalSrcProject) -> Self {
+        Self {
+            project,
+            lazy: None,
+            archive: None,
+            encrypted: false,
+            #[cfg(feature = "encryption")]
+            key: None,
+        }
+    }
+
+    /// Project with only `.project.json` and `.meta.json` installed, whose
+    /// remaining files are fetched from `sources` by `fetcher`
+    pub fn metadata_only(
+        project: LocalSrcProject,
+        sources: Vec<Source>,
+        fetcher: Option<SourceFetcher>,
+    ) -> Self {
+        Self {
+            project,
+            lazy: Some(LazySources {
+                sources,
+                fetcher,
+                fetched: Arc::new(OnceLock::new()),
+            }),
+            archive: None,
+            encrypted: false,
+            #[cfg(feature = "encryption")]
+            key: None,
+        }
+    }
+
+    /// The files of the project are stored in `project.kpar` in its
+    /// directory
+    pub fn with_archive(mut self) -> Self {
+        self.archive = Some(Arc::new(OnceLock::new()));
+        self
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.archive.is_some()
+    }
+
+    /// The source files of the project are encrypted
+    pub fn with_encryption(mut self) -> Self {
+        self.encrypted = true;
+        self
+    }
+
+    /// Decrypt the source files of a project stored encrypted with `key`
+    #[cfg(feature = "encryption")]
+    pub fn with_key(mut self, key: Option<EnvKey>) -> Self {
+        self.key = key;
+        self
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.encrypted
+    }
+
+    /// Decrypted contents of the source file at `path`
+    #[cfg(feature = "encryption")]
+    fn read_decrypted(&self, path: &Utf8UnixPath) -> Result<Vec<u8>, LazySrcError> {
+        let Some(key) = &self.key else {
+            return Err(LazySrcError::NoKey(self.project.project_path.clone()));
+        };
+        let source_path = self
+            .project
+            .get_source_path(path)
+            .map_err(LocalSrcError::from)?;
+        let data = wrapfs::read(source_path).map_err(LocalSrcError::Io)?;
+        Ok(decrypt_source(key, path.as_str(), &data)?)
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    fn read_decrypted(&self, _path: &Utf8UnixPath) -> Result<Vec<u8>, LazySrcError> {
+        Err(LazySrcError::NoKey(self.project.project_path.clone()))
+    }
+
+    /// Whether the source files of the project are present in its
+    /// directory. Always true unless it is stored as a KPAR
+    pub fn is_extracted(&self) -> Result<bool, LazySrcError> {
+        Ok(!self.is_archived() || self.sources_present()?)
+    }
+
+    /// Extract the files of a project stored as a KPAR into its directory,
+    /// unless its source files are already present. Does nothing for other
+    /// projects
+    pub fn materialize(&self) -> Result<(), LazySrcError> {
+        if self.is_extracted()? {
+            return Ok(());
+        }
+        let path = &self.project.project_path;
+        let extracting = "Extracting";
+        let header = crate::style::get_style_config().header;
+        log::info!("{header}{extracting:>12}{header:#} sources of `{path}`");
+        Ok(unpack_project(path)?)
+    }
+
+    /// Archive of a project stored as a KPAR
+    fn open_archive(&self) -> Result<Option<&LocalKParProjectRaw>, LocalKParError> {
+        let Some(archive) = &self.archive else {
+            return Ok(None);
+        };
+        if let Some(raw) = archive.get() {
+            return Ok(Some(raw));
+        }
+        let raw =
+            LocalKParProjectRaw::new_project_at_root(self.project.project_path.join(ARCHIVE_PATH))?;
+        Ok(Some(archive.get_or_init(|| raw)))
+    }
+
+    pub fn is_metadata_only(&self) -> bool {
+        self.lazy.is_some()
+    }
+
+    /// Fetch the files of a metadata only project, unless its source files
+    /// are already present. Does nothing for other projects
+    pub fn fetch_sources(&self) -> Result<(), LazySrcError> {
+        let Some(lazy) = &self.lazy else {
+            return Ok(());
+        };
+        if self.sources_present()? {
+            return Ok(());
+        }
+        let path = &self.project.project_path;
+        let Some(fetcher) = &lazy.fetcher else {
+            return Err(LazySrcError::NoFetcher(path.clone()));
+        };
+        lazy.fetched
+            .get_or_init(|| {
+                let fetching = "Fetching";
+                let header = crate::style::get_style_config().header;
+                log::info!("{header}{fetching:>12}{header:#} sources of `{path}`");
+                (fetcher.0)(&lazy.sources, &mut self.project.clone())
+            })
+            .clone()
+            .map_err(|e| LazySrcError::Fetch(path.clone(), e))
+    }
+
+    fn sources_present(&self) -> Result<bool, LocalSrcError> {
+        let Some(meta) = self.project.get_meta()? else {
+            return Ok(false);
+        };
+        for path in meta.source_paths(true) {
+            if !self.project.get_source_path(&path)?.is_file() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl Deref for LazySrcProject {
+    type Target = LocalSrcProject;
+
+    fn deref(&self) -> &Self::Target {
+        &self.project
+    }
+}
+
+impl ProjectRead for LazySrcProject {
+    type Error = LazySrcError;
+
+    fn project_root(&self) -> Option<&Utf8Path> {
+        self.project.project_root()
+    }
+
+    fn get_project(
+        &self,
+    ) -> Result<
+        (
+            Option<InterchangeProjectInfoRaw>,
+            Option<InterchangeProjectMetadataRaw>,
+        ),
+        Self::Error,
+    > {
+        Ok(self.project.get_project()?)
+    }
+
+    type SourceReader<'a> = LazySrcReader;
+
+    fn read_source<P: AsRef<Utf8UnixPath>>(
+        &self,
+        path: P,
+    ) -> Result<Self::SourceReader<'_>, Self::Error> {
+        if self.encrypted {
+            let contents = self.read_decrypted(path.as_ref())?;
+            return Ok(LazySrcReader::Decrypted(Cursor::new(contents)));
+        }
+        let present = self
+            .project
+            .get_source_path(&path)
+            .map_err(LocalSrcError::from)?
+            .is_file();
+        if !present {
+            if let Some(archive) = self.open_archive()? {
+                return Ok(LazySrcReader::File(archive.read_source(path)?.into_inner()));
+            }
+            if self.is_metadata_only() {
+                self.fetch_sources()?;
+            }
+        }
+        Ok(LazySrcReader::File(self.project.read_source(path)?))
+    }
+
+    fn sources(&self, ctx: &ProjectContext) -> Result<Vec<Source>, Self::Error> {
+        if !self.encrypted {
+            return Ok(self.project.sources(ctx)?);
+        }
+        // Files on disk are encrypted, so the checksum is computed from
+        // their decrypted contents
+        let mut project = self.project.clone();
+        if project.expected_checksum.is_none() {
+            project.expected_checksum = Some(match self.checksum_canonical_variant()? {
+                ProjectChecksum::Project(c) | ProjectChecksum::Kpar(c) => c,
+            });
+        }
+        Ok(project.sources(ctx)?)
+    }
+
+    fn checksum_canonical_variant(&self) -> Result<ProjectChecksum, Self::Error> {
+        match self.checksum_canonical_hex() {
+            Ok(Some(c)) => Ok(ProjectChecksum::Project(c)),
+            Ok(None) => Err(LocalSrcError::MissingInfoMeta.into()),
+            Err(CanonicalizationError::ProjectRead(e)) => Err(e),
+            Err(CanonicalizationError::FileRead(path, error)) => Err(LocalSrcError::Io(
+                FsIoError::ReadFile(String::from(path).into(), error).into(),
+            )
+            .into()),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./lazy_tests.rs"]
+mod tests;
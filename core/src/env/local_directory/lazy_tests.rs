@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::{
+    io::{Cursor, Read},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use camino_tempfile::{Utf8TempDir, tempdir};
+use indexmap::IndexMap;
+use typed_path::Utf8UnixPath;
+
+use super::{LazySrcError, SourceFetcher};
+use crate::{
+    commands::env::do_env_install_project,
+    env::{
+        DEFAULT_ENV_NAME, OverwritePolicy, ReadEnvironment,
+        local_directory::LocalDirectoryEnvironment, utils::clone_project,
+    },
+    lock::Source,
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw, format_created_now},
+    project::{ProjectMut, ProjectRead, memory::InMemoryProject},
+    utils::format_err,
+};
+
+const URI: &str = "urn:kpar:lib";
+
+fn source_project() -> InMemoryProject {
+    let info = InterchangeProjectInfoRaw {
+        name: "lib".to_string(),
+        publisher: None,
+        description: None,
+        version: "1.0.0".to_string(),
+        license: None,
+        maintainer: vec![],
+        website: None,
+        topic: vec![],
+        usage: vec![],
+    };
+    let meta = InterchangeProjectMetadataRaw {
+        index: IndexMap::from([("Lib".to_string(), "Lib.sysml".to_string())]),
+        created: format_created_now(),
+        metamodel: None,
+        includes_derived: None,
+        includes_implied: None,
+        checksum: None,
+        variants: IndexMap::new(),
+    };
+    let mut project = InMemoryProject::default();
+    project.put_project(&info, &meta, true).unwrap();
+    project
+        .write_source(
+            Utf8UnixPath::new("Lib.sysml"),
+            &mut Cursor::new("package Lib;"),
+            true,
+        )
+        .unwrap();
+    project
+}
+
+fn sources() -> Vec<Source> {
+    vec![Source::RemoteSrc {
+        remote_src: "https://example.com/lib".to_string(),
+        checksum: "0".repeat(64),
+    }]
+}
+
+/// Environment with `source_project()` installed with metadata only
+fn make_env() -> (Utf8TempDir, LocalDirectoryEnvironment) {
+    let cwd = tempdir().unwrap();
+    let env_dir = cwd.path().join(DEFAULT_ENV_NAME);
+    std::fs::create_dir(&env_dir).unwrap();
+    let mut env = LocalDirectoryEnvironment::create(&env_dir).unwrap();
+    do_env_install_project(
+        URI,
+        "1.0.0",
+        &source_project(),
+        None,
+        &mut env,
+        OverwritePolicy::Error,
+        false,
+        Some(&sources()),
+        None,
+    )
+    .unwrap();
+    (cwd, env)
+}
+
+#[test]
+fn metadata_only_install_records_sources() {
+    let (_cwd, env) = make_env();
+    let project = env.get_project(URI, "1.0.0").unwrap();
+
+    assert!(project.is_metadata_only());
+    assert_eq!(project.name().unwrap().as_deref(), Some("lib"));
+    assert!(project.info_path().is_file());
+    assert!(!project.project_path.join("Lib.sysml").exists());
+
+    let reread = LocalDirectoryEnvironment::read(env.root_path()).unwrap();
+    assert_eq!(reread.projects()[0].sources, sources());
+    assert!(reread.projects()[0].metadata_only);
+}
+
+#[test]
+fn metadata_only_sources_without_fetcher() {
+    let (_cwd, env) = make_env();
+    let project = env.get_project(URI, "1.0.0").unwrap();
+
+    let Err(err) = project.read_source("Lib.sysml") else {
+        panic!("sources of a metadata only project read without fetching them");
+    };
+    assert!(matches!(err, LazySrcError::NoFetcher(_)), "{err}");
+}
+
+#[test]
+fn metadata_only_sources_fetched_once() {
+    let (_cwd, env) = make_env();
+    let fetches = Arc::new(AtomicUsize::new(0));
+    let counter = fetches.clone();
+    let env = env.with_source_fetcher(SourceFetcher::new(move |fetch_sources, target| {
+        assert_eq!(fetch_sources, sources().as_slice());
+        counter.fetch_add(1, Ordering::SeqCst);
+        clone_project(&source_project(), target, true)
+            .map(|_| ())
+            .map_err(format_err)
+    }));
+    let project = env.get_project(URI, "1.0.0").unwrap();
+
+    let mut source = String::new();
+    project
+        .read_source("Lib.sysml")
+        .unwrap()
+        .read_to_string(&mut source)
+        .unwrap();
+    assert_eq!(source, "package Lib;");
+    project.read_source("Lib.sysml").unwrap();
+    assert!(project.read_source("Missing.sysml").is_err());
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn full_install_replaces_metadata_only() {
+    let (_cwd, mut env) = make_env();
+    do_env_install_project(
+        URI,
+        "1.0.0",
+        &source_project(),
+        None,
+        &mut env,
+        OverwritePolicy::Replace,
+        false,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let project = env.get_project(URI, "1.0.0").unwrap();
+    assert!(!project.is_metadata_only());
+    assert!(project.project_path.join("Lib.sysml").is_file());
+}
@@ -1,17 +1,21 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
-use std::{fmt::Display, str::FromStr};
+use std::{collections::HashSet, fmt::Display, str::FromStr};
 
 use camino::{Utf8Path, Utf8PathBuf};
-use serde::Deserialize;
+use fluent_uri::Iri;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use toml_edit::{ArrayOfTables, DocumentMut, Item, Table, Value, value};
 use typed_path::{Utf8UnixComponent, Utf8UnixPathBuf};
 
 use crate::{
-    env::ProjectChecksum,
-    model::InterchangeProjectUsageRaw,
+    env::{ProjectChecksum, ProjectChecksumResult, local_directory::PROJECT_PATH_PREFIX},
+    iri_normalize::{IriVersionFilename, iris_equivalent},
+    lock::Source,
+    migration::{Format, Migration, VersionStatus},
+    model::{InterchangeProjectInfoRaw, InterchangeProjectUsageRaw},
     project::{
         local_src::{LocalSrcError, LocalSrcProject},
         utils::{FsIoError, deserialize_unix_path, wrapfs},
@@ -21,11 +25,34 @@ use crate::{
 
 const METADATA_PREFIX: &str = "# This file is automatically generated by Sysand and is not intended to be edited manually.\n\n";
 const CURRENT_METADATA_VERSION: &str = "0.1";
-const SUPPORTED_METADATA_VERSIONS: &[&str] = &[CURRENT_METADATA_VERSION];
+/// Version of env metadata without a version stamp
+const UNVERSIONED_METADATA_VERSION: &str = "0.0";
+
+/// Format of the env metadata file `env.toml`
+pub const ENV_FORMAT: Format = Format {
+    name: "env metadata",
+    version_key: "version",
+    current: CURRENT_METADATA_VERSION,
+    unversioned: Some(UNVERSIONED_METADATA_VERSION),
+    migrations: &[Migration {
+        from: UNVERSIONED_METADATA_VERSION,
+        to: CURRENT_METADATA_VERSION,
+        description: "add version stamp",
+        apply: |_| Ok(()),
+    }],
+};
+
+fn unversioned() -> String {
+    UNVERSIONED_METADATA_VERSION.to_owned()
+}
 
 #[derive(Debug, Deserialize, Clone)]
-pub(super) struct EnvMetadata {
+pub(crate) struct EnvMetadata {
+    #[serde(default = "unversioned")]
     pub version: String,
+    /// Write operations on the environment are refused
+    #[serde(default)]
+    pub read_only: bool,
     #[serde(rename = "project", default)]
     pub projects: Vec<EnvProject>,
 }
@@ -34,6 +61,7 @@ impl Default for EnvMetadata {
     fn default() -> Self {
         EnvMetadata {
             version: CURRENT_METADATA_VERSION.to_string(),
+            read_only: false,
             projects: vec![],
         }
     }
@@ -41,10 +69,17 @@ impl Default for EnvMetadata {
 
 #[derive(Debug, Error)]
 pub enum ParseError {
-    #[error("failed to parse env metadata file")]
+    #[error("failed to parse env metadata file: {0}")]
     Toml(#[from] toml::de::Error),
     #[error("env metadata version `{0}` is not supported")]
     UnsupportedVersion(String),
+    #[error("env metadata version `{0}` is outdated; run `sysand migrate` to update it")]
+    OutdatedVersion(String),
+    #[error(
+        "env metadata version `{0}` is newer than the supported version\n\
+        `{CURRENT_METADATA_VERSION}`; upgrade sysand to use it"
+    )]
+    NewerVersion(String),
     #[error(
         "non-editable project has path `{0}` that contains references to parent or current directory"
     )]
@@ -66,8 +101,14 @@ impl FromStr for EnvMetadata {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let metadata: EnvMetadata = toml::from_str(s)?;
 
-        if !SUPPORTED_METADATA_VERSIONS.contains(&metadata.version.as_str()) {
-            return Err(ParseError::UnsupportedVersion(metadata.version.clone()));
+        let version = &metadata.version;
+        match ENV_FORMAT.status(version) {
+            VersionStatus::Current => (),
+            VersionStatus::Outdated => return Err(ParseError::OutdatedVersion(version.clone())),
+            VersionStatus::Newer => return Err(ParseError::NewerVersion(version.clone())),
+            VersionStatus::Unsupported => {
+                return Err(ParseError::UnsupportedVersion(version.clone()));
+            }
         }
 
         for project in &metadata.projects {
@@ -106,6 +147,9 @@ impl EnvMetadata {
         let mut doc = DocumentMut::new();
         doc.decor_mut().set_prefix(METADATA_PREFIX);
         doc.insert("version", value(Value::from(&self.version)));
+        if self.read_only {
+            doc.insert("read_only", value(true));
+        }
 
         let mut projects = ArrayOfTables::new();
         for project in &self.projects {
@@ -127,20 +171,26 @@ impl EnvMetadata {
         let identifier = identifier.as_ref();
         let version = version.as_ref();
         self.projects.iter().enumerate().find(|(_idx, p)| {
-            p.version == version && p.identifiers.iter().any(|iri| iri == identifier)
+            p.version == version
+                && p.identifiers
+                    .iter()
+                    .any(|iri| iris_equivalent(iri, identifier))
         })
     }
 
-    pub(super) fn find_project_version<S: AsRef<str>, V: AsRef<str>>(
+    pub(crate) fn find_project_version<S: AsRef<str>, V: AsRef<str>>(
         &self,
         identifier: S,
         version: V,
     ) -> Option<&EnvProject> {
         let identifier = identifier.as_ref();
         let version = version.as_ref();
-        self.projects
-            .iter()
-            .find(|p| p.version == version && p.identifiers.iter().any(|iri| iri == identifier))
+        self.projects.iter().find(|p| {
+            p.version == version
+                && p.identifiers
+                    .iter()
+                    .any(|iri| iris_equivalent(iri, identifier))
+        })
     }
 
     pub(super) fn find_project_version_mut<S: AsRef<str>, V: AsRef<str>>(
@@ -150,9 +200,12 @@ impl EnvMetadata {
     ) -> Option<&mut EnvProject> {
         let identifier = identifier.as_ref();
         let version = version.as_ref();
-        self.projects
-            .iter_mut()
-            .find(|p| p.version == version && p.identifiers.iter().any(|iri| iri == identifier))
+        self.projects.iter_mut().find(|p| {
+            p.version == version
+                && p.identifiers
+                    .iter()
+                    .any(|iri| iris_equivalent(iri, identifier))
+        })
     }
 
     pub(super) fn find_project_version_any_mut<S: AsRef<str>, V: AsRef<str>>(
@@ -165,19 +218,21 @@ impl EnvMetadata {
             p.version == version
                 && p.identifiers
                     .iter()
-                    .any(|iri| identifiers.iter().any(|i| i.as_ref() == iri))
+                    .any(|iri| identifiers.iter().any(|i| iris_equivalent(i.as_ref(), iri)))
         })
     }
 
     /// Find all versions of all the projects that have `identifier` as one of their
     /// identifiers.
-    pub(super) fn find_project_versions(
+    pub(crate) fn find_project_versions(
         &self,
         identifier: &str,
     ) -> impl Iterator<Item = &EnvProject> {
-        self.projects
-            .iter()
-            .filter(move |p| p.identifiers.iter().any(|iri| iri == identifier))
+        self.projects.iter().filter(move |p| {
+            p.identifiers
+                .iter()
+                .any(|iri| iris_equivalent(iri, identifier))
+        })
     }
 
     /// Find all projects that have `identifier`, and return
@@ -187,10 +242,58 @@ impl EnvMetadata {
         &self,
         identifier: &str,
     ) -> impl Iterator<Item = (usize, &EnvProject)> {
+        self.projects.iter().enumerate().filter(move |(_idx, p)| {
+            p.identifiers
+                .iter()
+                .any(|iri| iris_equivalent(iri, identifier))
+        })
+    }
+
+    /// Projects whose usages include `identifier`
+    pub(super) fn dependents(&self, identifier: &str) -> Vec<&EnvProject> {
         self.projects
             .iter()
-            .enumerate()
-            .filter(move |(_idx, p)| p.identifiers.iter().any(|iri| iri == identifier))
+            .filter(|p| p.usages.iter().any(|u| iris_equivalent(u, identifier)))
+            .collect()
+    }
+
+    /// Indices of the installed projects that are left unused when those
+    /// at `removed` are uninstalled: the projects they use, directly or
+    /// through others, that no other project uses and that `keep` does not
+    /// retain. Sorted by index
+    pub(super) fn orphans(
+        &self,
+        removed: &[usize],
+        keep: impl Fn(&EnvProject) -> bool,
+    ) -> Vec<usize> {
+        let mut candidates: Vec<usize> = self
+            .closure(removed.iter().copied(), &[])
+            .into_iter()
+            .filter(|i| !removed.contains(i) && self.projects[*i].is_installed())
+            .collect();
+        let retained = (0..self.projects.len()).filter(|i| {
+            !removed.contains(i) && (!candidates.contains(i) || keep(&self.projects[*i]))
+        });
+        let needed = self.closure(retained, removed);
+        candidates.retain(|i| !needed.contains(i));
+        candidates.sort_unstable();
+        candidates
+    }
+
+    /// Indices of the projects at `roots` and of those they use, directly
+    /// or through others, not passing through those at `skip`
+    fn closure(&self, roots: impl IntoIterator<Item = usize>, skip: &[usize]) -> HashSet<usize> {
+        let mut seen = HashSet::new();
+        let mut stack: Vec<usize> = roots.into_iter().collect();
+        while let Some(i) = stack.pop() {
+            if skip.contains(&i) || !seen.insert(i) {
+                continue;
+            }
+            for usage in &self.projects[i].usages {
+                stack.extend(self.find_project_versions_idxs(usage).map(|(j, _)| j));
+            }
+        }
+        seen
     }
 
     pub(super) fn add_project(&mut self, project: EnvProject) {
@@ -215,30 +318,111 @@ impl EnvMetadata {
         let info = project
             .get_info()?
             .ok_or(AddProjectError::MissingInfo(project.project_path.clone()))?;
-        let project = EnvProject {
-            publisher: info.publisher,
-            name: info.name,
-            version: info.version,
-            path: project
+        self.add_project_info(
+            identifiers,
+            project
                 .nominal_path
                 .as_ref()
                 .expect("BUG: no nominal path for project")
                 .to_owned(),
+            info,
+            editable,
+            workspace,
+            checksum,
+        );
+
+        Ok(())
+    }
+
+    /// Add project described by `info` located at `path`
+    pub(super) fn add_project_info(
+        &mut self,
+        identifiers: Vec<String>,
+        path: Utf8UnixPathBuf,
+        info: InterchangeProjectInfoRaw,
+        editable: bool,
+        workspace: bool,
+        checksum: Option<ProjectChecksum>,
+    ) {
+        let mut project = EnvProject {
+            publisher: None,
+            name: String::new(),
+            version: String::new(),
+            path,
             identifiers,
-            usages: info
-                .usage
-                .into_iter()
-                .map(|u| match u {
-                    InterchangeProjectUsageRaw::Resource { resource, .. } => resource,
-                })
-                .collect(),
+            usages: vec![],
             editable,
             workspace,
-            checksum: checksum.map(Into::into),
+            checksum: None,
+            metadata_only: false,
+            sources: vec![],
+            archived: false,
+            encrypted: false,
         };
+        project.update_from_info(info, checksum);
         self.add_project(project);
+    }
 
-        Ok(())
+    /// Compare `checksum` with the recorded checksum of project `identifier`
+    /// version `version`
+    pub(crate) fn verify_checksum<S: AsRef<str>, V: AsRef<str>>(
+        &self,
+        identifier: S,
+        version: V,
+        checksum: &ProjectChecksum,
+    ) -> ProjectChecksumResult {
+        let Some(p) = self.find_project_version(identifier, version) else {
+            return ProjectChecksumResult::VersionNotFound;
+        };
+        let (expected, actual) = match (checksum, &p.checksum) {
+            (_, None) => return ProjectChecksumResult::ChecksumNotPresent,
+            (ProjectChecksum::Project(c), Some(EnvProjectChecksum::Project { src_cksum })) => {
+                (c, src_cksum)
+            }
+            (ProjectChecksum::Kpar(c), Some(EnvProjectChecksum::Kpar { kpar_cksum })) => {
+                (c, kpar_cksum)
+            }
+            _ => return ProjectChecksumResult::DifferentChecksumKinds,
+        };
+        if expected == actual {
+            ProjectChecksumResult::Match
+        } else {
+            ProjectChecksumResult::Mismatch
+        }
+    }
+
+    /// Result of comparing `checksum` with the checksum version `version`
+    /// of `identifier` was installed with, if any
+    pub(crate) fn compare_installed<S: AsRef<str>, V: AsRef<str>>(
+        &self,
+        identifier: S,
+        version: V,
+        checksum: Option<&ProjectChecksum>,
+    ) -> ProjectChecksumResult {
+        match checksum {
+            Some(checksum) => self.verify_checksum(identifier, version, checksum),
+            None if self.find_project_version(identifier, version).is_some() => {
+                ProjectChecksumResult::ChecksumNotPresent
+            }
+            None => ProjectChecksumResult::VersionNotFound,
+        }
+    }
+
+    /// Determine a path for a new installed project/version. Path will be
+    /// relative to the env directory
+    pub(super) fn new_project_path(&self, iri: Iri<&str>, version: &str) -> Utf8UnixPathBuf {
+        let mut path_iter = IriVersionFilename::new(iri, version);
+        let mut candidate = path_iter.next_candidate();
+        while self
+            .projects
+            .iter()
+            .any(|p| p.is_installed() && p.path.file_name().unwrap() == candidate)
+        {
+            candidate = path_iter.next_candidate();
+        }
+        let mut path = String::from(path_iter);
+        path.insert_str(0, PROJECT_PATH_PREFIX);
+        path.into()
     }
 }
 
@@ -277,6 +461,21 @@ pub struct EnvProject {
     pub workspace: bool,
     #[serde(flatten, skip_serializing_if = "Option::is_none")]
     pub checksum: Option<EnvProjectChecksum>,
+    /// Indicator of whether only `.project.json` and `.meta.json`
+    /// of the project are installed.
+    #[serde(default)]
+    pub metadata_only: bool,
+    /// Sources to fetch the rest of a `metadata_only` project from.
+    #[serde(default)]
+    pub sources: Vec<Source>,
+    /// Indicator of whether the files of the project are kept in
+    /// `project.kpar` in its directory instead of being extracted.
+    #[serde(default)]
+    pub archived: bool,
+    /// Indicator of whether the source files of the project are encrypted
+    /// with the key of the environment.
+    #[serde(default)]
+    pub encrypted: bool,
 }
 
 /// Checksum of the source this project was installed from:
@@ -284,7 +483,7 @@ pub struct EnvProject {
 /// - src_cksum for local/remote src
 // Serde by default will allow both variants to coexist in the file, this
 // is desirable to have forward compat.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum EnvProjectChecksum {
     Kpar { kpar_cksum: String },
@@ -334,10 +533,50 @@ impl EnvProject {
                 }
             }
         }
+        if self.metadata_only {
+            table.insert("metadata_only", value(true));
+            let sources = multiline_array(self.sources.iter().map(|s| s.to_toml()));
+            table.insert("sources", value(sources));
+        }
+        if self.archived {
+            table.insert("archived", value(true));
+        }
+        if self.encrypted {
+            table.insert("encrypted", value(true));
+        }
 
         table
     }
 
+    /// Determine whether the project is installed in the environment. This
+    /// is currently false only for editable projects.
+    pub(super) fn is_installed(&self) -> bool {
+        self.path.starts_with(PROJECT_PATH_PREFIX)
+    }
+
+    /// Update the project from its (new) `info` and `checksum`
+    pub(super) fn update_from_info(
+        &mut self,
+        info: InterchangeProjectInfoRaw,
+        checksum: Option<ProjectChecksum>,
+    ) {
+        self.publisher = info.publisher;
+        self.name = info.name;
+        self.version = info.version;
+        self.usages = info
+            .usage
+            .into_iter()
+            .map(|u| match u {
+                InterchangeProjectUsageRaw::Resource { resource, .. } => resource,
+            })
+            .collect();
+        self.checksum = checksum.map(Into::into);
+        self.metadata_only = false;
+        self.sources.clear();
+        self.archived = false;
+        self.encrypted = false;
+    }
+
     /// Adds identifiers from other project.
     /// Should only be done if the underlying projects are the same.
     /// In particular they must have the same version.
@@ -64,7 +64,7 @@ fn editable_project_with_parent_dir_is_accepted() {
 
 #[test]
 fn unsupported_version_is_rejected() {
-    let toml = r#"version = "99.0""#;
+    let toml = r#"version = "X""#;
     let err = EnvMetadata::from_str(toml).unwrap_err();
     assert_matches!(
         err,
@@ -72,3 +72,77 @@ fn unsupported_version_is_rejected() {
         "unexpected error: {err}"
     );
 }
+
+#[test]
+fn unversioned_metadata_is_outdated() {
+    let toml = minimal_toml("subdir/project", false).replace("version = \"0.1\"\n\n", "");
+    let err = EnvMetadata::from_str(&toml).unwrap_err();
+    assert_matches!(
+        err,
+        ParseError::OutdatedVersion(ref v) if v == "0.0",
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn newer_metadata_version_is_rejected() {
+    let toml = minimal_toml("subdir/project", false).replace("\"0.1\"", "\"0.2\"");
+    let err = EnvMetadata::from_str(&toml).unwrap_err();
+    assert_matches!(
+        err,
+        ParseError::NewerVersion(ref v) if v == "0.2",
+        "unexpected error: {err}"
+    );
+}
+
+fn project_toml(name: &str, path: &str, usages: &[&str]) -> String {
+    let usages: Vec<_> = usages.iter().map(|u| format!("\"urn:kpar:{u}\"")).collect();
+    format!(
+        r#"
+[[project]]
+name = "{name}"
+version = "1.0.0"
+path = "{path}"
+identifiers = ["urn:kpar:{name}"]
+usages = [{}]
+editable = {}
+"#,
+        usages.join(", "),
+        !path.starts_with(PROJECT_PATH_PREFIX),
+    )
+}
+
+#[test]
+fn orphans_are_only_used_by_removed_projects() {
+    let mut toml = "version = \"0.1\"\n".to_owned();
+    for (name, usages) in [
+        ("x", &["a", "b"][..]),
+        ("a", &["c"]),
+        ("b", &[]),
+        ("c", &[]),
+        ("y", &["b"]),
+    ] {
+        toml.push_str(&project_toml(name, &format!("lib/{name}"), usages));
+    }
+    toml.push_str(&project_toml("app", "app", &["y"]));
+    let metadata = EnvMetadata::from_str(&toml).unwrap();
+    let names = |indices: Vec<usize>| -> Vec<&str> {
+        indices
+            .into_iter()
+            .map(|i| metadata.projects[i].name.as_str())
+            .collect()
+    };
+
+    let dependents: Vec<_> = metadata
+        .dependents("urn:kpar:b")
+        .into_iter()
+        .map(|p| &p.name)
+        .collect();
+    assert_eq!(dependents, ["x", "y"]);
+    assert_eq!(names(metadata.orphans(&[0], |_| false)), ["a", "c"]);
+    assert_eq!(names(metadata.orphans(&[0], |p| p.name == "c")), ["a"]);
+    // `b` is still used by `x`
+    assert!(metadata.orphans(&[4], |_| false).is_empty());
+    // `app` uses `y`, but not through it `b`, once `y` is removed
+    assert_eq!(names(metadata.orphans(&[0, 4], |_| false)), ["a", "b", "c"]);
+}
@@ -10,23 +10,26 @@ use std::{
 use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
 use fluent_uri::Iri;
 use thiserror::Error;
-use typed_path::Utf8UnixPathBuf;
+use walkdir::WalkDir;
 
 use crate::{
+    config::EnvStorage,
     env::{
-        ProjectChecksum, ProjectChecksumResult, PutProjectError, ReadEnvironment, WriteEnvironment,
+        OverwritePolicy, ProjectChecksum, ProjectChecksumResult, PutProjectError, ReadEnvironment,
+        WriteEnvironment,
         local_directory::{
+            archive::pack_project,
             metadata::{
-                AddProjectError, EnvMetadata, EnvMetadataError, EnvProject, EnvProjectChecksum,
-                load_env_metadata, parse_env_metadata,
+                AddProjectError, EnvMetadata, EnvMetadataError, EnvProject, load_env_metadata,
+                parse_env_metadata,
             },
             utils::clean_dir,
         },
     },
-    iri_normalize::IriVersionFilename,
+    iri_normalize::iris_equivalent,
     lock::{Lock, Source},
-    model::InterchangeProjectUsageRaw,
     project::{
+        local_kpar::LocalKParError,
         local_src::{LocalSrcError, LocalSrcProject, PathError},
         utils::{
             FsIoError, ProjectDeserializationError, ProjectSerializationError, RelativizePathError,
@@ -36,9 +39,19 @@ use crate::{
     workspace::Workspace,
 };
 
+pub mod archive;
+pub mod check;
+pub mod encrypted;
+pub mod lazy;
 pub mod metadata;
+pub mod provenance;
 pub mod utils;
+pub mod vfs;
 
+use encrypted::EncryptionError;
+#[cfg(feature = "encryption")]
+use encrypted::{EnvKey, encrypt_project};
+use lazy::{LazySrcProject, SourceFetcher};
 use utils::{TryMoveError, try_move_files};
 
 // TODO: validate IRIs and paths
@@ -49,6 +62,14 @@ pub struct LocalDirectoryEnvironment {
     /// Path of the env, including `.sysand` part. Must be canonical
     root_dir: Utf8PathBuf,
     metadata: EnvMetadata,
+    /// Fetches the sources of projects installed without them
+    source_fetcher: Option<SourceFetcher>,
+    /// Storage of newly installed projects
+    storage: EnvStorage,
+    /// Key to encrypt newly installed projects with, if they are stored
+    /// encrypted, and to decrypt projects stored encrypted
+    #[cfg(feature = "encryption")]
+    key: Option<EnvKey>,
 }
 
 pub const METADATA_PATH: &str = "env.toml";
@@ -62,7 +83,14 @@ impl LocalDirectoryEnvironment {
         Self::warn_if_old_sysand_env_present(&root_dir);
 
         let metadata = load_env_metadata(root_dir.join(METADATA_PATH))?;
-        Ok(Self { root_dir, metadata })
+        Ok(Self {
+            root_dir,
+            metadata,
+            source_fetcher: None,
+            storage: EnvStorage::default(),
+            #[cfg(feature = "encryption")]
+            key: None,
+        })
     }
 
     /// `root_dir` can be any cwd-relative/absolute path. `env.toml` must not exist
@@ -77,7 +105,14 @@ impl LocalDirectoryEnvironment {
         file.write_all(metadata.to_string().as_bytes())
             .map_err(|e| FsIoError::WriteFile(path, e))?;
 
-        Ok(Self { root_dir, metadata })
+        Ok(Self {
+            root_dir,
+            metadata,
+            source_fetcher: None,
+            storage: EnvStorage::default(),
+            #[cfg(feature = "encryption")]
+            key: None,
+        })
     }
 
     /// Try reading the environment metadata. If it does not exist,
@@ -94,7 +129,14 @@ impl LocalDirectoryEnvironment {
                 let metadata = parse_env_metadata(meta_path, s)?;
 
                 let root_dir = wrapfs::canonicalize(root_dir)?;
-                Ok(Some(Self { root_dir, metadata }))
+                Ok(Some(Self {
+                    root_dir,
+                    metadata,
+                    source_fetcher: None,
+                    storage: EnvStorage::default(),
+                    #[cfg(feature = "encryption")]
+                    key: None,
+                }))
             }
             Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
             Err(e) => Err(EnvMetadataError::Io(
@@ -112,7 +154,7 @@ impl LocalDirectoryEnvironment {
     /// To install a project in the environment, use `put_project()`.
     /// Call is idempotent. Does not update metadata file
     pub fn merge_lock(&mut self, lock: &Lock, ws: Option<&Workspace>) {
-        self.metadata.projects.retain(Self::is_installed);
+        self.metadata.projects.retain(EnvProject::is_installed);
         for project in &lock.projects {
             // Projects that are installed in the environment are ignored, so only
             // editable (and workspace, which are a subset of editable) projects have to be added
@@ -137,11 +179,37 @@ impl LocalDirectoryEnvironment {
                     editable: true,
                     workspace: workspace_member,
                     checksum: None,
+                    metadata_only: false,
+                    sources: vec![],
+                    archived: false,
+                    encrypted: false,
                 });
             }
         }
     }
 
+    /// Use `fetcher` to fetch the sources of projects installed with
+    /// only their `.project.json` and `.meta.json` when they are first read
+    pub fn with_source_fetcher(mut self, fetcher: SourceFetcher) -> Self {
+        self.source_fetcher = Some(fetcher);
+        self
+    }
+
+    /// Store projects installed from now on as `storage`. Projects that
+    /// are already installed are left as they are
+    pub fn with_storage(mut self, storage: EnvStorage) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Encrypt projects installed from now on with `key`, if they are
+    /// stored encrypted, and decrypt projects stored encrypted with it
+    #[cfg(feature = "encryption")]
+    pub fn with_key(mut self, key: EnvKey) -> Self {
+        self.key = Some(key);
+        self
+    }
+
     pub fn root_path(&self) -> &Utf8Path {
         &self.root_dir
     }
@@ -158,6 +226,78 @@ impl LocalDirectoryEnvironment {
         &self.metadata.projects
     }
 
+    /// Projects of the environment that use `iri`, according to their
+    /// recorded usages
+    pub fn dependents(&self, iri: &str) -> Vec<&EnvProject> {
+        self.metadata.dependents(iri)
+    }
+
+    /// Installed projects that uninstalling `iri` (only `version` of it,
+    /// if given) leaves unused by the remaining projects. Projects locked
+    /// in `lock` are kept
+    pub fn orphans(
+        &self,
+        iri: &str,
+        version: Option<&str>,
+        lock: Option<&Lock>,
+    ) -> Vec<&EnvProject> {
+        let removed: Vec<usize> = self
+            .metadata
+            .find_project_versions_idxs(iri)
+            .filter(|(_, p)| version.is_none_or(|v| v == p.version))
+            .map(|(i, _)| i)
+            .collect();
+        let locked = |project: &EnvProject| lock.is_some_and(|lock| is_locked(lock, project));
+        self.metadata
+            .orphans(&removed, locked)
+            .into_iter()
+            .map(|i| &self.metadata.projects[i])
+            .collect()
+    }
+
+    /// Installed projects that none of `locks` lock at their version, e.g.
+    /// versions left behind by earlier syncs
+    pub fn unlocked(&self, locks: &[Lock]) -> Vec<&EnvProject> {
+        self.metadata
+            .projects
+            .iter()
+            .filter(|p| p.is_installed() && !locks.iter().any(|lock| is_locked(lock, p)))
+            .collect()
+    }
+
+    /// Total size in bytes of the files of the installed `project`
+    pub fn installed_size(&self, project: &EnvProject) -> Result<u64, Box<FsIoError>> {
+        let dir = self.root_dir.join(project.path.as_str());
+        let mut size = 0;
+        for entry in WalkDir::new(&dir) {
+            let entry = entry.map_err(|e| {
+                let path = e.path().unwrap_or(dir.as_std_path()).to_string_lossy();
+                FsIoError::ReadDir(path.as_ref().into(), e.into())
+            })?;
+            if entry.file_type().is_file() {
+                let metadata = entry.metadata().map_err(|e| {
+                    FsIoError::Metadata(entry.path().to_string_lossy().as_ref().into(), e.into())
+                })?;
+                size += metadata.len();
+            }
+        }
+        Ok(size)
+    }
+
+    /// Mark the environment as read-only, or writable again, and write
+    /// `env.toml`
+    pub fn set_read_only(&mut self, read_only: bool) -> Result<(), Box<FsIoError>> {
+        self.metadata.read_only = read_only;
+        self.write()
+    }
+
+    pub(crate) fn ensure_writable(&self) -> Result<(), LocalWriteError> {
+        if self.metadata.read_only {
+            return Err(LocalWriteError::ReadOnly(self.root_dir.clone()));
+        }
+        Ok(())
+    }
+
     /// Parent directory of the env, i.e. the directory in which `.sysand` resides.
     /// It is assumed to be the workspace (if present) or project root, which in turn is
     /// the root of relative paths of `editable`/`workspace` projects
@@ -220,28 +360,47 @@ impl LocalDirectoryEnvironment {
         }
     }
 
-    /// Determine whether `p` is installed in the environment. This is currently false
-    /// only for editable projects.
-    fn is_installed(p: &EnvProject) -> bool {
-        p.path.starts_with(PROJECT_PATH_PREFIX)
+    /// Installed `project` as it is read from the environment
+    fn lazy_project(&self, project: &EnvProject) -> LazySrcProject {
+        let storage = self.get_project_storage(project);
+        let lazy = if project.metadata_only {
+            LazySrcProject::metadata_only(
+                storage,
+                project.sources.clone(),
+                self.source_fetcher.clone(),
+            )
+        } else {
+            LazySrcProject::new(storage)
+        };
+        let lazy = if project.archived {
+            lazy.with_archive()
+        } else {
+            lazy
+        };
+        let lazy = if project.encrypted {
+            lazy.with_encryption()
+        } else {
+            lazy
+        };
+        #[cfg(feature = "encryption")]
+        let lazy = lazy.with_key(self.key.clone());
+        lazy
     }
 
-    /// Determine a path for a new project/version. Path will be relative to and under
-    /// `self.root_path()`
-    fn compute_project_path(&self, iri: Iri<&str>, version: impl AsRef<str>) -> Utf8UnixPathBuf {
-        let mut path_iter = IriVersionFilename::new(iri, version);
-        let mut candidate = path_iter.next_candidate();
-        while self
-            .metadata
-            .projects
-            .iter()
-            .any(|p| Self::is_installed(p) && p.path.file_name().unwrap() == candidate)
-        {
-            candidate = path_iter.next_candidate();
+    /// Encrypt the source files of the new project in `dir` if projects are
+    /// stored encrypted. Returns whether any file was encrypted
+    #[cfg(feature = "encryption")]
+    fn encrypt_new_project(&self, dir: &Utf8Path) -> Result<bool, LocalWriteError> {
+        if self.storage != EnvStorage::Encrypted {
+            return Ok(false);
         }
-        let mut path = String::from(path_iter);
-        path.insert_str(0, PROJECT_PATH_PREFIX);
-        path.into()
+        let key = self.key.as_ref().ok_or(EncryptionError::MissingKey)?;
+        Ok(encrypt_project(dir, key)?)
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    fn encrypt_new_project(&self, _dir: &Utf8Path) -> Result<bool, LocalWriteError> {
+        Ok(false)
     }
 
     fn warn_if_old_sysand_env_present(root_dir: &Utf8Path) {
@@ -269,6 +428,17 @@ impl LocalDirectoryEnvironment {
     // }
 }
 
+/// Whether `lock` locks `project` at its version, under any of its identifiers
+fn is_locked(lock: &Lock, project: &EnvProject) -> bool {
+    lock.projects.iter().any(|locked| {
+        locked.version == project.version
+            && locked
+                .identifiers
+                .iter()
+                .any(|i| project.identifiers.iter().any(|j| iris_equivalent(i, j)))
+    })
+}
+
 #[derive(Error, Debug)]
 pub enum LocalReadError {
     #[error("prioject {0} is not present in environment")]
@@ -312,7 +482,7 @@ impl ReadEnvironment for LocalDirectoryEnvironment {
             .collect())
     }
 
-    type InterchangeProjectRead = LocalSrcProject;
+    type InterchangeProjectRead = LazySrcProject;
 
     fn get_project<S: AsRef<str>, T: AsRef<str>>(
         &self,
@@ -320,7 +490,7 @@ impl ReadEnvironment for LocalDirectoryEnvironment {
         version: T,
     ) -> Result<Self::InterchangeProjectRead, Self::ReadError> {
         if let Some(project) = self.metadata.find_project_version(&uri, &version) {
-            Ok(self.get_project_storage(project))
+            Ok(self.lazy_project(project))
         } else {
             Err(LocalReadError::ProjectNotFound(uri.as_ref().into()))
         }
@@ -332,25 +502,7 @@ impl ReadEnvironment for LocalDirectoryEnvironment {
         version: V,
         checksum: &ProjectChecksum,
     ) -> Result<ProjectChecksumResult, Self::ReadError> {
-        if let Some(p) = self.metadata.find_project_version(uri, version) {
-            let (expected, actual) = match (checksum, &p.checksum) {
-                (_, None) => return Ok(ProjectChecksumResult::ChecksumNotPresent),
-                (ProjectChecksum::Project(c), Some(EnvProjectChecksum::Project { src_cksum })) => {
-                    (c, src_cksum)
-                }
-                (ProjectChecksum::Kpar(c), Some(EnvProjectChecksum::Kpar { kpar_cksum })) => {
-                    (c, kpar_cksum)
-                }
-                _ => return Ok(ProjectChecksumResult::DifferentChecksumKinds),
-            };
-            if expected == actual {
-                Ok(ProjectChecksumResult::Match)
-            } else {
-                Ok(ProjectChecksumResult::Mismatch)
-            }
-        } else {
-            Ok(ProjectChecksumResult::VersionNotFound)
-        }
+        Ok(self.metadata.verify_checksum(uri, version, checksum))
     }
 }
 
@@ -382,6 +534,12 @@ pub enum LocalWriteError {
     MissingMeta,
     #[error("project is missing `.project.json` and/or `.meta.json` files")]
     MissingInfoMeta,
+    #[error("environment `{0}` is read-only")]
+    ReadOnly(Utf8PathBuf),
+    #[error("failed to store project as a KPAR: {0}")]
+    Archive(#[from] LocalKParError),
+    #[error("failed to store project encrypted: {0}")]
+    Encryption(#[from] EncryptionError),
 }
 
 impl From<FsIoError> for LocalWriteError {
@@ -419,7 +577,6 @@ impl WriteEnvironment for LocalDirectoryEnvironment {
 
     type InterchangeProjectMut = LocalSrcProject;
 
-    /// Will overwrite the specified project version if it exists
     // TODO: support multiple identifiers. This will allow aliases and identifying
     // projects by their publisher/name, even if they were retrieved under a
     // different identifier.
@@ -428,14 +585,27 @@ impl WriteEnvironment for LocalDirectoryEnvironment {
         uri: S,
         version: T,
         checksum: Option<ProjectChecksum>,
+        overwrite: OverwritePolicy,
         write_project: F,
     ) -> Result<Self::InterchangeProjectMut, PutProjectError<Self::WriteError, CE>>
     where
         F: FnOnce(&mut Self::InterchangeProjectMut) -> Result<(), CE>,
     {
+        self.ensure_writable()?;
         let identifier = uri.as_ref();
         let version = version.as_ref();
 
+        let installed = self
+            .metadata
+            .compare_installed(identifier, version, checksum.as_ref());
+        if !overwrite.should_write(identifier, version, installed)? {
+            let existing = self
+                .metadata
+                .find_project_version(identifier, version)
+                .expect("BUG: version is installed");
+            return Ok(self.get_project_storage(existing));
+        }
+
         let project_temp = camino_tempfile::tempdir()
             .map_err(|e| LocalWriteError::from(FsIoError::MkTempDir(e)))?;
         let mut tentative_project = LocalSrcProject {
@@ -444,15 +614,18 @@ impl WriteEnvironment for LocalDirectoryEnvironment {
             expected_checksum: None,
         };
 
-        if let Some(existing) = self.metadata.find_project_version_mut(identifier, version) {
+        if let Some(existing) = self.metadata.find_project_version(identifier, version) {
             // Create a temp clone and change it to avoid modifying env in case of errors
             // TODO: how to handle editable projects here?
             assert!(!existing.editable);
             assert!(!existing.workspace);
-
-            write_project(&mut tentative_project).map_err(PutProjectError::Callback)?;
             // Project is not editable, so this is always correct
             let absolute_path = self.root_dir.join(existing.path.as_str());
+
+            write_project(&mut tentative_project).map_err(PutProjectError::Callback)?;
+            let archived = self.storage == EnvStorage::Kpar
+                && pack_project(project_temp.path()).map_err(LocalWriteError::from)?;
+            let encrypted = self.encrypt_new_project(project_temp.path())?;
             try_move_files(&[(project_temp.path(), &absolute_path)])
                 .map_err(LocalWriteError::from)?;
             tentative_project.project_path = absolute_path;
@@ -463,17 +636,13 @@ impl WriteEnvironment for LocalDirectoryEnvironment {
                 Err(e) => return Err(PutProjectError::Write(LocalWriteError::from(e))),
             };
 
-            existing.publisher = info.publisher;
-            existing.name = info.name;
-            existing.version = info.version;
-            existing.usages = info
-                .usage
-                .into_iter()
-                .map(|u| match u {
-                    InterchangeProjectUsageRaw::Resource { resource, .. } => resource,
-                })
-                .collect();
-            existing.checksum = checksum.map(Into::into);
+            let existing = self
+                .metadata
+                .find_project_version_mut(identifier, version)
+                .expect("BUG: version is installed");
+            existing.update_from_info(info, checksum);
+            existing.archived = archived;
+            existing.encrypted = encrypted;
 
             self.write().map_err(LocalWriteError::from)?;
 
@@ -481,13 +650,16 @@ impl WriteEnvironment for LocalDirectoryEnvironment {
         } else {
             // TODO: try writing to the target directly (we manage it exclusively) and on failure revert.
             write_project(&mut tentative_project).map_err(PutProjectError::Callback)?;
+            let archived = self.storage == EnvStorage::Kpar
+                && pack_project(project_temp.path()).map_err(LocalWriteError::from)?;
+            let encrypted = self.encrypt_new_project(project_temp.path())?;
 
             // Project write was successful
 
             // TODO: take iri as arg
             let iri = Iri::parse(identifier)
                 .map_err(|e| PutProjectError::IriParse(identifier.to_owned(), e))?;
-            let path = self.compute_project_path(iri, version);
+            let path = self.metadata.new_project_path(iri, version);
             let absolute_project_path = self.root_path().join(path.as_str());
 
             // Tolerate missing `lib/`
@@ -511,6 +683,10 @@ impl WriteEnvironment for LocalDirectoryEnvironment {
                     checksum,
                 )
                 .map_err(LocalWriteError::from)?;
+            if let Some(added) = self.metadata.find_project_version_mut(identifier, version) {
+                added.archived = archived;
+                added.encrypted = encrypted;
+            }
 
             self.write().map_err(LocalWriteError::from)?;
 
@@ -523,6 +699,7 @@ impl WriteEnvironment for LocalDirectoryEnvironment {
         uri: S,
         version: T,
     ) -> Result<(), Self::WriteError> {
+        self.ensure_writable()?;
         let identifier = uri.as_ref();
         let version = version.as_ref();
         if let Some((idx, project)) = self.metadata.find_project_version_idx(identifier, version) {
@@ -544,6 +721,7 @@ impl WriteEnvironment for LocalDirectoryEnvironment {
     }
 
     fn del_uri<S: AsRef<str>>(&mut self, uri: S) -> Result<(), Self::WriteError> {
+        self.ensure_writable()?;
         let project_versions = self.metadata.find_project_versions_idxs(uri.as_ref());
         let mut indices_to_remove = Vec::new();
         for (idx, p) in project_versions {
@@ -567,4 +745,40 @@ impl WriteEnvironment for LocalDirectoryEnvironment {
 
         Ok(())
     }
+
+    fn is_read_only(&self) -> bool {
+        self.metadata.read_only
+    }
+
+    fn supports_metadata_only<S: AsRef<str>>(&self, _uri: S) -> bool {
+        true
+    }
+
+    fn set_metadata_only<S: AsRef<str>, T: AsRef<str>>(
+        &mut self,
+        uri: S,
+        version: T,
+        sources: Vec<Source>,
+    ) -> Result<(), Self::WriteError> {
+        self.ensure_writable()?;
+        let Some(project) = self.metadata.find_project_version_mut(&uri, version) else {
+            return Err(LocalReadError::ProjectNotFound(uri.as_ref().into()).into());
+        };
+        project.metadata_only = true;
+        project.sources = sources;
+        self.write()?;
+
+        Ok(())
+    }
+
+    fn record_install<S: AsRef<str>, T: AsRef<str>>(
+        &mut self,
+        uri: S,
+        version: T,
+        source: Option<&Source>,
+        checksum: Option<&ProjectChecksum>,
+    ) -> Result<(), Self::WriteError> {
+        self.ensure_writable()?;
+        Ok(self.record_provenance(uri.as_ref(), version.as_ref(), source, checksum)?)
+    }
 }
@@ -0,0 +1,188 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Record of where each project installed in a [`LocalDirectoryEnvironment`]
+//! came from, kept in `.sysand-install.json` in the project directory.
+
+use camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    env::{
+        ProjectChecksum,
+        local_directory::{LocalDirectoryEnvironment, lazy::LazySrcError, metadata::EnvProject},
+    },
+    lock::Source,
+    model::{HashAlg, format_created_now, project_hash_hex},
+    project::{
+        CanonicalizationError, ProjectRead, hash_reader_hex,
+        utils::{FsIoError, wrapfs},
+    },
+};
+
+pub const PROVENANCE_PATH: &str = ".sysand-install.json";
+
+/// Contents of `.sysand-install.json`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallProvenance {
+    /// Source the project was installed from, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>,
+    /// Time of installation, in RFC 3339 format
+    pub installed_at: String,
+    /// Canonical hash of the project as installed, with the checksums of
+    /// its source files computed from their contents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_hash: Option<String>,
+    /// Hash of the KPAR the project was installed from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact_hash: Option<String>,
+}
+
+/// Whether an installed project was changed after it was installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallStatus {
+    Unchanged,
+    Modified,
+    /// The project has no recorded hash to compare with, or only its
+    /// metadata is installed
+    Unknown,
+}
+
+#[derive(Error, Debug)]
+pub enum ProvenanceError {
+    #[error("failed to parse `{0}`: {1}")]
+    Parse(Utf8PathBuf, serde_json::Error),
+    #[error(transparent)]
+    Hash(#[from] CanonicalizationError<LazySrcError>),
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+}
+
+/// Canonical hash of `project`, with the checksums of its source files
+/// computed from their contents instead of taken from `.meta.json`
+pub fn content_hash<P: ProjectRead>(
+    project: &P,
+) -> Result<Option<String>, CanonicalizationError<P::Error>> {
+    let (Some(info), Some(mut meta)) = project
+        .get_project()
+        .map_err(CanonicalizationError::ProjectRead)?
+    else {
+        return Ok(None);
+    };
+    for (path, checksum) in meta
+        .checksum
+        .as_mut()
+        .into_iter()
+        .flat_map(|index| index.iter_mut())
+    {
+        let mut src = project
+            .read_source(path)
+            .map_err(CanonicalizationError::ProjectRead)?;
+        checksum.algorithm = HashAlg::Sha256.to_string();
+        checksum.value = hash_reader_hex(HashAlg::Sha256, &mut src)
+            .map_err(|e| CanonicalizationError::FileRead(path.as_str().into(), e))?;
+    }
+    Ok(Some(project_hash_hex(&info, &meta)))
+}
+
+impl LocalDirectoryEnvironment {
+    fn provenance_path(&self, project: &EnvProject) -> Utf8PathBuf {
+        self.root_dir
+            .join(project.path.as_str())
+            .join(PROVENANCE_PATH)
+    }
+
+    /// Provenance of an installed project. `None` for editable projects and
+    /// projects installed without it
+    pub fn provenance(
+        &self,
+        project: &EnvProject,
+    ) -> Result<Option<InstallProvenance>, ProvenanceError> {
+        if !project.is_installed() {
+            return Ok(None);
+        }
+        let path = self.provenance_path(project);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let contents = wrapfs::read_to_string(&path)?;
+        serde_json::from_str(&contents)
+            .map(Some)
+            .map_err(|e| ProvenanceError::Parse(path, e))
+    }
+
+    /// Compare the contents of an installed project with its recorded hash.
+    /// Source files without a checksum in `.meta.json` are not covered
+    pub fn install_status(&self, project: &EnvProject) -> Result<InstallStatus, ProvenanceError> {
+        if project.metadata_only {
+            return Ok(InstallStatus::Unknown);
+        }
+        let Some(recorded) = self.provenance(project)?.and_then(|p| p.project_hash) else {
+            return Ok(InstallStatus::Unknown);
+        };
+        let current = content_hash(&self.lazy_project(project))?;
+        Ok(if current.as_deref() == Some(recorded.as_str()) {
+            InstallStatus::Unchanged
+        } else {
+            InstallStatus::Modified
+        })
+    }
+
+    /// Write the provenance of version `version` of `uri`, unless it is
+    /// already recorded. Provenance of a replaced project is removed
+    /// together with its directory
+    pub(super) fn record_provenance(
+        &self,
+        uri: &str,
+        version: &str,
+        source: Option<&Source>,
+        checksum: Option<&ProjectChecksum>,
+    ) -> Result<(), Box<FsIoError>> {
+        let Some(project) = self.metadata.find_project_version(uri, version) else {
+            return Ok(());
+        };
+        if !project.is_installed() || self.provenance_path(project).is_file() {
+            return Ok(());
+        }
+
+        let project_hash = if project.metadata_only {
+            self.get_project_storage(project)
+                .checksum_canonical_hex()
+                .map_err(|e| e.to_string())
+        } else {
+            content_hash(&self.lazy_project(project)).map_err(|e| e.to_string())
+        }
+        .unwrap_or_else(|e| {
+            log::debug!("failed to hash installed project `{uri}` {version}: {e}");
+            None
+        });
+        let artifact_hash = match (checksum, source) {
+            (Some(ProjectChecksum::Kpar(hash)), _) => Some(hash.clone()),
+            (
+                _,
+                Some(
+                    Source::LocalKpar { kpar_digest, .. }
+                    | Source::RemoteKpar { kpar_digest, .. }
+                    | Source::IndexKpar { kpar_digest, .. },
+                ),
+            ) => Some(kpar_digest.clone()),
+            _ => None,
+        };
+        let provenance = InstallProvenance {
+            source: source.cloned(),
+            installed_at: format_created_now(),
+            project_hash,
+            artifact_hash,
+        };
+        let contents =
+            serde_json::to_string_pretty(&provenance).expect("provenance is always serializable");
+        wrapfs::write(self.provenance_path(project), contents)
+    }
+}
+
+#[cfg(test)]
+#[path = "./provenance_tests.rs"]
+mod tests;
@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::io::Cursor;
+
+use camino_tempfile::{Utf8TempDir, tempdir};
+use indexmap::IndexMap;
+use typed_path::Utf8UnixPath;
+
+use super::{InstallStatus, PROVENANCE_PATH, content_hash};
+use crate::{
+    commands::env::do_env_install_project,
+    env::{
+        DEFAULT_ENV_NAME, OverwritePolicy,
+        local_directory::{LocalDirectoryEnvironment, check::EnvProblem, metadata::EnvProject},
+    },
+    lock::Source,
+    model::{
+        InterchangeProjectChecksumRaw, InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw,
+        format_created_now,
+    },
+    project::{ProjectChecksum, ProjectMut, ProjectRead, memory::InMemoryProject},
+};
+
+const URI: &str = "urn:kpar:lib";
+
+fn source_project(contents: &str) -> InMemoryProject {
+    let info = InterchangeProjectInfoRaw {
+        name: "lib".to_string(),
+        publisher: None,
+        description: None,
+        version: "1.0.0".to_string(),
+        license: None,
+        maintainer: vec![],
+        website: None,
+        topic: vec![],
+        usage: vec![],
+    };
+    let meta = InterchangeProjectMetadataRaw {
+        index: IndexMap::from([("Lib".to_string(), "Lib.sysml".to_string())]),
+        created: "2026-01-01T00:00:00.000Z".to_string(),
+        metamodel: None,
+        includes_derived: None,
+        includes_implied: None,
+        checksum: Some(IndexMap::from([(
+            "Lib.sysml".to_string(),
+            InterchangeProjectChecksumRaw {
+                value: "0".repeat(64),
+                algorithm: "SHA256".to_string(),
+            },
+        )])),
+        variants: IndexMap::new(),
+    };
+    let mut project = InMemoryProject::default();
+    project.put_project(&info, &meta, true).unwrap();
+    project
+        .write_source(
+            Utf8UnixPath::new("Lib.sysml"),
+            &mut Cursor::new(contents),
+            true,
+        )
+        .unwrap();
+    project
+}
+
+fn source() -> Source {
+    Source::RemoteSrc {
+        remote_src: "https://example.com/lib".to_string(),
+        checksum: "0".repeat(64),
+    }
+}
+
+fn install(
+    env: &mut LocalDirectoryEnvironment,
+    project: &InMemoryProject,
+    checksum: Option<ProjectChecksum>,
+    overwrite: OverwritePolicy,
+) {
+    do_env_install_project(
+        URI,
+        "1.0.0",
+        project,
+        checksum,
+        env,
+        overwrite,
+        false,
+        None,
+        Some(&source()),
+    )
+    .unwrap();
+}
+
+fn make_env() -> (Utf8TempDir, LocalDirectoryEnvironment) {
+    let cwd = tempdir().unwrap();
+    let env_dir = cwd.path().join(DEFAULT_ENV_NAME);
+    std::fs::create_dir(&env_dir).unwrap();
+    let mut env = LocalDirectoryEnvironment::create(&env_dir).unwrap();
+    install(
+        &mut env,
+        &source_project("package Lib;"),
+        Some(ProjectChecksum::Kpar("1".repeat(64))),
+        OverwritePolicy::Error,
+    );
+    (cwd, env)
+}
+
+fn installed(env: &LocalDirectoryEnvironment) -> EnvProject {
+    env.projects()[0].clone()
+}
+
+#[test]
+fn install_records_provenance() {
+    let (_cwd, env) = make_env();
+    let provenance = env.provenance(&installed(&env)).unwrap().unwrap();
+
+    assert_eq!(provenance.source, Some(source()));
+    assert_eq!(provenance.artifact_hash, Some("1".repeat(64)));
+    assert_eq!(
+        provenance.project_hash,
+        content_hash(&source_project("package Lib;")).unwrap()
+    );
+    assert!(provenance.installed_at <= format_created_now());
+    assert_eq!(
+        env.install_status(&installed(&env)).unwrap(),
+        InstallStatus::Unchanged
+    );
+}
+
+#[test]
+fn content_hash_ignores_recorded_checksums() {
+    let project = source_project("package Lib;");
+    assert_ne!(
+        content_hash(&project).unwrap(),
+        project.checksum_canonical_hex().unwrap()
+    );
+    assert_ne!(
+        content_hash(&project).unwrap(),
+        content_hash(&source_project("package Lib2;")).unwrap()
+    );
+}
+
+#[test]
+fn modified_install_is_detected() {
+    let (_cwd, env) = make_env();
+    let project = installed(&env);
+    let project_dir = env.root_path().join(project.path.as_str());
+    std::fs::write(project_dir.join("Lib.sysml"), "package Changed;").unwrap();
+
+    assert_eq!(
+        env.install_status(&project).unwrap(),
+        InstallStatus::Modified
+    );
+    assert_eq!(
+        env.check().unwrap(),
+        [EnvProblem::Modified {
+            iri: URI.into(),
+            version: "1.0.0".into(),
+            path: project.path.to_string(),
+        }]
+    );
+}
+
+#[test]
+fn kept_install_keeps_provenance() {
+    let (_cwd, mut env) = make_env();
+    let before = env.provenance(&installed(&env)).unwrap().unwrap();
+
+    install(
+        &mut env,
+        &source_project("package Lib2;"),
+        None,
+        OverwritePolicy::Skip,
+    );
+    assert_eq!(env.provenance(&installed(&env)).unwrap(), Some(before));
+
+    install(
+        &mut env,
+        &source_project("package Lib2;"),
+        None,
+        OverwritePolicy::Replace,
+    );
+    let after = env.provenance(&installed(&env)).unwrap().unwrap();
+    assert_eq!(after.artifact_hash, None);
+    assert_eq!(
+        after.project_hash,
+        content_hash(&source_project("package Lib2;")).unwrap()
+    );
+}
+
+#[test]
+fn missing_provenance_is_unknown() {
+    let (_cwd, env) = make_env();
+    let project = installed(&env);
+    std::fs::remove_file(
+        env.root_path()
+            .join(project.path.as_str())
+            .join(PROVENANCE_PATH),
+    )
+    .unwrap();
+
+    assert_eq!(env.provenance(&project).unwrap(), None);
+    assert_eq!(
+        env.install_status(&project).unwrap(),
+        InstallStatus::Unknown
+    );
+    assert_eq!(env.check().unwrap(), []);
+}
@@ -0,0 +1,375 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use fluent_uri::Iri;
+use thiserror::Error;
+use typed_path::{Utf8UnixPath, Utf8UnixPathBuf};
+
+use crate::{
+    env::{
+        OverwritePolicy, ProjectChecksum, ProjectChecksumResult, PutProjectError, ReadEnvironment,
+        WriteEnvironment,
+        local_directory::{
+            METADATA_PATH,
+            metadata::{EnvMetadata, EnvMetadataError, EnvProject, parse_env_metadata},
+        },
+        utils::ErrorBound,
+    },
+    project::{
+        ProjectRead,
+        vfs::{VfsProject, VfsProjectError},
+    },
+    vfs::{Vfs, normalize},
+};
+
+/// Directory where projects are written before being moved into place
+const STAGING_PATH: &str = ".staging";
+
+/// Environment with the same layout as
+/// [`LocalDirectoryEnvironment`](super::LocalDirectoryEnvironment), stored
+/// on a [`Vfs`]. Metadata changes have to be written to `env.toml`
+/// explicitly
+#[derive(Debug, Clone)]
+pub struct VfsDirectoryEnvironment<F: Vfs> {
+    fs: F,
+    /// Path of the env in `fs`, including `.sysand` part
+    root_path: Utf8UnixPathBuf,
+    metadata: EnvMetadata,
+}
+
+#[derive(Error, Debug)]
+pub enum VfsEnvironmentError<E: ErrorBound> {
+    #[error("project {0} is not present in environment")]
+    ProjectNotFound(Box<str>),
+    #[error("environment metadata file `{0}` not found")]
+    MissingMetadata(Utf8UnixPathBuf),
+    #[error("environment metadata file `{0}` already exists")]
+    MetadataExists(Utf8UnixPathBuf),
+    #[error(transparent)]
+    Metadata(#[from] EnvMetadataError),
+    #[error(transparent)]
+    Project(#[from] VfsProjectError<E>),
+    #[error(transparent)]
+    Vfs(E),
+    #[error("project is missing `.project.json` and/or `.meta.json` files")]
+    MissingInfoMeta,
+    #[error("environment `{0}` is read-only")]
+    ReadOnly(Utf8UnixPathBuf),
+}
+
+impl<F: Vfs> VfsDirectoryEnvironment<F> {
+    /// Create an empty environment at `root_path`. `env.toml` must not exist
+    pub fn create<P: Into<Utf8UnixPathBuf>>(
+        fs: F,
+        root_path: P,
+    ) -> Result<Self, VfsEnvironmentError<F::Error>> {
+        let root_path = root_path.into();
+        let metadata_path = root_path.join(METADATA_PATH);
+        if fs
+            .is_file(&metadata_path)
+            .map_err(VfsEnvironmentError::Vfs)?
+        {
+            return Err(VfsEnvironmentError::MetadataExists(metadata_path));
+        }
+
+        let env = Self {
+            fs,
+            root_path,
+            metadata: EnvMetadata::default(),
+        };
+        env.write()?;
+        Ok(env)
+    }
+
+    /// Read the environment at `root_path`
+    pub fn read<P: Into<Utf8UnixPathBuf>>(
+        fs: F,
+        root_path: P,
+    ) -> Result<Self, VfsEnvironmentError<F::Error>> {
+        let root_path = root_path.into();
+        Self::try_read(fs, root_path.clone())?
+            .ok_or_else(|| VfsEnvironmentError::MissingMetadata(root_path.join(METADATA_PATH)))
+    }
+
+    /// Try reading the environment at `root_path`. If its metadata does not
+    /// exist, returns `Ok(None)`
+    pub fn try_read<P: Into<Utf8UnixPathBuf>>(
+        fs: F,
+        root_path: P,
+    ) -> Result<Option<Self>, VfsEnvironmentError<F::Error>> {
+        let root_path = root_path.into();
+        let metadata_path = root_path.join(METADATA_PATH);
+        let Some(metadata) = fs
+            .read_to_string(&metadata_path)
+            .map_err(VfsEnvironmentError::Vfs)?
+        else {
+            return Ok(None);
+        };
+        let metadata = parse_env_metadata(metadata_path.as_str(), metadata)?;
+
+        Ok(Some(Self {
+            fs,
+            root_path,
+            metadata,
+        }))
+    }
+
+    pub fn fs(&self) -> &F {
+        &self.fs
+    }
+
+    pub fn root_path(&self) -> &Utf8UnixPath {
+        &self.root_path
+    }
+
+    pub fn metadata_path(&self) -> Utf8UnixPathBuf {
+        self.root_path.join(METADATA_PATH)
+    }
+
+    pub fn write(&self) -> Result<(), VfsEnvironmentError<F::Error>> {
+        self.fs
+            .write(self.metadata_path(), self.metadata.to_string())
+            .map_err(VfsEnvironmentError::Vfs)
+    }
+
+    pub fn projects(&self) -> &[EnvProject] {
+        &self.metadata.projects
+    }
+
+    /// Mark the environment as read-only, or writable again, and write
+    /// `env.toml`
+    pub fn set_read_only(&mut self, read_only: bool) -> Result<(), VfsEnvironmentError<F::Error>> {
+        self.metadata.read_only = read_only;
+        self.write()
+    }
+
+    fn ensure_writable(&self) -> Result<(), VfsEnvironmentError<F::Error>> {
+        if self.metadata.read_only {
+            return Err(VfsEnvironmentError::ReadOnly(self.root_path.clone()));
+        }
+        Ok(())
+    }
+
+    fn get_project_storage(&self, project: &EnvProject) -> VfsProject<F> {
+        let relative = project.path.as_str();
+        let (root_path, nominal_path) = if project.editable {
+            // Editable projects are relative to the parent of the env
+            let parent = self.root_path.parent().unwrap_or(Utf8UnixPath::new(""));
+            (normalize(&parent.join(relative)), relative.into())
+        } else {
+            let nominal_path = match self.root_path.file_name() {
+                Some(name) => format!("{name}/{relative}"),
+                None => relative.to_owned(),
+            };
+            (self.root_path.join(relative), nominal_path.into())
+        };
+        VfsProject {
+            fs: self.fs.clone(),
+            root_path,
+            nominal_path: Some(nominal_path),
+            expected_checksum: None,
+        }
+    }
+
+    /// Replace the contents of directory `to` with those of `from`
+    fn move_dir(&self, from: &Utf8UnixPath, to: &Utf8UnixPath) -> Result<(), F::Error> {
+        self.fs.remove_dir_all(to)?;
+        for file in self.fs.list_files(from)? {
+            if let Some(contents) = self.fs.read(from.join(&file))? {
+                self.fs.write(to.join(&file), contents)?;
+            }
+        }
+        self.fs.remove_dir_all(from)
+    }
+}
+
+impl<F: Vfs> ReadEnvironment for VfsDirectoryEnvironment<F> {
+    type ReadError = VfsEnvironmentError<F::Error>;
+
+    type UriIter = Vec<Result<String, Self::ReadError>>;
+
+    fn uris(&self) -> Result<Self::UriIter, Self::ReadError> {
+        Ok(self
+            .metadata
+            .projects
+            .iter()
+            .flat_map(|p| p.identifiers.iter())
+            .cloned()
+            .map(Ok)
+            .collect())
+    }
+
+    type VersionIter = Vec<Result<String, Self::ReadError>>;
+
+    fn versions<S: AsRef<str>>(&self, uri: S) -> Result<Self::VersionIter, Self::ReadError> {
+        Ok(self
+            .metadata
+            .find_project_versions(uri.as_ref())
+            .map(|p| Ok(p.version.to_owned()))
+            .collect())
+    }
+
+    type InterchangeProjectRead = VfsProject<F>;
+
+    fn get_project<S: AsRef<str>, T: AsRef<str>>(
+        &self,
+        uri: S,
+        version: T,
+    ) -> Result<Self::InterchangeProjectRead, Self::ReadError> {
+        match self.metadata.find_project_version(&uri, &version) {
+            Some(project) => Ok(self.get_project_storage(project)),
+            None => Err(VfsEnvironmentError::ProjectNotFound(uri.as_ref().into())),
+        }
+    }
+
+    fn has_version_verified<S: AsRef<str>, V: AsRef<str>>(
+        &self,
+        uri: S,
+        version: V,
+        checksum: &ProjectChecksum,
+    ) -> Result<ProjectChecksumResult, Self::ReadError> {
+        Ok(self.metadata.verify_checksum(uri, version, checksum))
+    }
+}
+
+impl<F: Vfs> WriteEnvironment for VfsDirectoryEnvironment<F> {
+    type WriteError = VfsEnvironmentError<F::Error>;
+
+    type InterchangeProjectMut = VfsProject<F>;
+
+    /// The project is written to a staging directory first, so the
+    /// environment is left unchanged if `write_project` fails
+    fn put_project<S: AsRef<str>, T: AsRef<str>, W, CE>(
+        &mut self,
+        uri: S,
+        version: T,
+        checksum: Option<ProjectChecksum>,
+        overwrite: OverwritePolicy,
+        write_project: W,
+    ) -> Result<Self::InterchangeProjectMut, PutProjectError<Self::WriteError, CE>>
+    where
+        W: FnOnce(&mut Self::InterchangeProjectMut) -> Result<(), CE>,
+    {
+        self.ensure_writable()?;
+        let identifier = uri.as_ref();
+        let version = version.as_ref();
+
+        let installed = self
+            .metadata
+            .compare_installed(identifier, version, checksum.as_ref());
+        if !overwrite.should_write(identifier, version, installed)? {
+            let existing = self
+                .metadata
+                .find_project_version(identifier, version)
+                .expect("BUG: version is installed");
+            return Ok(self.get_project_storage(existing));
+        }
+
+        let staging_path = self.root_path.join(STAGING_PATH);
+        self.fs
+            .remove_dir_all(&staging_path)
+            .map_err(VfsEnvironmentError::Vfs)?;
+        let mut tentative_project = VfsProject::new(self.fs.clone(), staging_path.clone());
+        if let Err(e) = write_project(&mut tentative_project) {
+            if let Err(e) = self.fs.remove_dir_all(&staging_path) {
+                log::debug!("cleanup: failed to remove staging directory `{staging_path}`: {e}");
+            }
+            return Err(PutProjectError::Callback(e));
+        }
+        let info = tentative_project
+            .get_info()
+            .map_err(VfsEnvironmentError::from)?
+            .ok_or(VfsEnvironmentError::MissingInfoMeta)?;
+
+        let path = match self.metadata.find_project_version_mut(identifier, version) {
+            Some(existing) => {
+                // TODO: how to handle editable projects here?
+                assert!(!existing.editable);
+                assert!(!existing.workspace);
+                existing.update_from_info(info, checksum);
+                existing.path.clone()
+            }
+            None => {
+                // TODO: take iri as arg
+                let iri = Iri::parse(identifier)
+                    .map_err(|e| PutProjectError::IriParse(identifier.to_owned(), e))?;
+                let path = self.metadata.new_project_path(iri, version);
+                self.metadata.add_project_info(
+                    vec![identifier.to_owned()],
+                    path.clone(),
+                    info,
+                    false,
+                    false,
+                    checksum,
+                );
+                path
+            }
+        };
+
+        self.move_dir(&staging_path, &self.root_path.join(&path))
+            .map_err(VfsEnvironmentError::Vfs)?;
+        self.write()?;
+
+        let project = self
+            .metadata
+            .find_project_version(identifier, version)
+            .expect("BUG: project was just added");
+        Ok(self.get_project_storage(project))
+    }
+
+    fn del_project_version<S: AsRef<str>, T: AsRef<str>>(
+        &mut self,
+        uri: S,
+        version: T,
+    ) -> Result<(), Self::WriteError> {
+        self.ensure_writable()?;
+        if let Some((idx, project)) = self
+            .metadata
+            .find_project_version_idx(uri.as_ref(), version.as_ref())
+        {
+            // Doesn't make sense to remove workspace projects
+            assert!(!project.workspace);
+            // Editable projects are not owned by the env
+            if !project.editable {
+                self.fs
+                    .remove_dir_all(self.root_path.join(&project.path))
+                    .map_err(VfsEnvironmentError::Vfs)?;
+            }
+            self.metadata.projects.swap_remove(idx);
+            self.write()?;
+        }
+
+        Ok(())
+    }
+
+    fn del_uri<S: AsRef<str>>(&mut self, uri: S) -> Result<(), Self::WriteError> {
+        self.ensure_writable()?;
+        let mut indices_to_remove = Vec::new();
+        for (idx, p) in self.metadata.find_project_versions_idxs(uri.as_ref()) {
+            // Doesn't make sense to remove workspace projects
+            assert!(!p.workspace);
+            if !p.editable {
+                self.fs
+                    .remove_dir_all(self.root_path.join(&p.path))
+                    .map_err(VfsEnvironmentError::Vfs)?;
+            }
+            indices_to_remove.push(idx);
+        }
+        // `swap_remove()` does not affect elements before the one being removed,
+        // so indices have to be removed from largest to smallest
+        for idx in indices_to_remove.into_iter().rev() {
+            self.metadata.projects.swap_remove(idx);
+        }
+        self.write()?;
+
+        Ok(())
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.metadata.read_only
+    }
+}
+
+#[cfg(test)]
+#[path = "./vfs_tests.rs"]
+mod tests;
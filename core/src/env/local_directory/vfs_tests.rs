@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::convert::Infallible;
+
+use super::{VfsDirectoryEnvironment, VfsEnvironmentError};
+use crate::{
+    env::{
+        DEFAULT_ENV_NAME, OverwritePolicy, ProjectChecksumResult, PutProjectError, ReadEnvironment,
+        WriteEnvironment,
+        local_directory::LocalDirectoryEnvironment,
+        utils::{CloneError, clone_project},
+    },
+    init::do_init_memory,
+    project::{ProjectRead, memory::InMemoryError, vfs::VfsProjectError},
+    vfs::{MemoryFs, StdFs, Vfs},
+};
+
+const URI: &str = "urn:kpar:test";
+
+fn put_test_project<F: Vfs>(env: &mut VfsDirectoryEnvironment<F>, version: &str) {
+    let project = do_init_memory("Test", None::<&str>, version, None).unwrap();
+    let checksum = project.checksum_canonical_variant().unwrap();
+    env.put_project(
+        URI,
+        version,
+        Some(checksum),
+        OverwritePolicy::Replace,
+        |p| {
+            clone_project(&project, p, true)?;
+            Ok::<(), CloneError<InMemoryError, VfsProjectError<F::Error>>>(())
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn put_get_delete() {
+    let fs = MemoryFs::new();
+    let mut env = VfsDirectoryEnvironment::create(fs.clone(), DEFAULT_ENV_NAME).unwrap();
+    put_test_project(&mut env, "1.0.0");
+    put_test_project(&mut env, "2.0.0");
+
+    let env = VfsDirectoryEnvironment::read(fs.clone(), DEFAULT_ENV_NAME).unwrap();
+    let mut env = env;
+    assert!(env.has(URI).unwrap());
+    assert_eq!(env.versions(URI).unwrap().len(), 2);
+    let project = env.get_project(URI, "1.0.0").unwrap();
+    assert_eq!(project.name().unwrap().as_deref(), Some("Test"));
+    assert_eq!(
+        env.has_version_verified(URI, "1.0.0", &project.checksum_canonical_variant().unwrap())
+            .unwrap(),
+        ProjectChecksumResult::Match
+    );
+
+    env.del_project_version(URI, "1.0.0").unwrap();
+    assert!(!fs.is_file(project.info_path()).unwrap());
+    assert!(env.get_project(URI, "1.0.0").is_err());
+
+    env.del_uri(URI).unwrap();
+    assert!(!env.has(URI).unwrap());
+    // Only `env.toml` is left
+    assert_eq!(fs.list_files(DEFAULT_ENV_NAME).unwrap().len(), 1);
+}
+
+#[test]
+fn failed_write_leaves_env_unchanged() {
+    let fs = MemoryFs::new();
+    let mut env = VfsDirectoryEnvironment::create(fs.clone(), DEFAULT_ENV_NAME).unwrap();
+
+    let result = env.put_project(URI, "1.0.0", None, OverwritePolicy::Replace, |_| {
+        Err::<(), _>("failed")
+    });
+    assert!(result.is_err());
+    assert!(!env.has(URI).unwrap());
+    assert_eq!(fs.list_files(DEFAULT_ENV_NAME).unwrap().len(), 1);
+}
+
+#[test]
+fn read_only_env_refuses_writes() {
+    let fs = MemoryFs::new();
+    let mut env = VfsDirectoryEnvironment::create(fs.clone(), DEFAULT_ENV_NAME).unwrap();
+    put_test_project(&mut env, "1.0.0");
+    env.set_read_only(true).unwrap();
+
+    let mut env = VfsDirectoryEnvironment::read(fs.clone(), DEFAULT_ENV_NAME).unwrap();
+    assert!(env.is_read_only());
+    assert!(matches!(
+        env.del_uri(URI),
+        Err(VfsEnvironmentError::ReadOnly(_))
+    ));
+    let result = env.put_project(URI, "2.0.0", None, OverwritePolicy::Replace, |_| {
+        Ok::<(), Infallible>(())
+    });
+    assert!(matches!(
+        result,
+        Err(PutProjectError::Write(VfsEnvironmentError::ReadOnly(_)))
+    ));
+    assert_eq!(env.versions(URI).unwrap().len(), 1);
+    assert!(env.get_project(URI, "1.0.0").is_ok());
+
+    env.set_read_only(false).unwrap();
+    env.del_uri(URI).unwrap();
+    assert!(!env.has(URI).unwrap());
+}
+
+#[test]
+fn create_existing_fails() {
+    let fs = MemoryFs::new();
+    VfsDirectoryEnvironment::create(fs.clone(), DEFAULT_ENV_NAME).unwrap();
+
+    assert!(matches!(
+        VfsDirectoryEnvironment::create(fs.clone(), DEFAULT_ENV_NAME),
+        Err(VfsEnvironmentError::<Infallible>::MetadataExists(_))
+    ));
+    assert!(
+        VfsDirectoryEnvironment::try_read(fs, "missing")
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn compatible_with_local_directory_environment() {
+    let tmp = camino_tempfile::tempdir().unwrap();
+    let fs = StdFs::new(tmp.path());
+    let mut env = VfsDirectoryEnvironment::create(fs, DEFAULT_ENV_NAME).unwrap();
+    put_test_project(&mut env, "1.0.0");
+
+    let local = LocalDirectoryEnvironment::read(tmp.path().join(DEFAULT_ENV_NAME)).unwrap();
+    let project = local.get_project(URI, "1.0.0").unwrap();
+    assert_eq!(project.name().unwrap().as_deref(), Some("Test"));
+    assert_eq!(
+        project.checksum_canonical_variant().unwrap(),
+        env.get_project(URI, "1.0.0")
+            .unwrap()
+            .checksum_canonical_variant()
+            .unwrap()
+    );
+}
@@ -2,7 +2,7 @@
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
 use crate::{
-    env::{PutProjectError, ReadEnvironment, WriteEnvironment, utils::ErrorBound},
+    env::{OverwritePolicy, PutProjectError, ReadEnvironment, WriteEnvironment, utils::ErrorBound},
     project::{ProjectChecksum, ProjectMut, ProjectRead},
 };
 use std::{
@@ -259,12 +259,28 @@ impl<Project: ProjectMut + Clone + Default> WriteEnvironment for MemoryStorageEn
         uri: S,
         version: T,
         // TODO: change env structure to store this somewhere
-        _checksum: Option<ProjectChecksum>,
+        checksum: Option<ProjectChecksum>,
+        overwrite: OverwritePolicy,
         write_project: F,
     ) -> Result<Self::InterchangeProjectMut, super::PutProjectError<Self::WriteError, E>>
     where
         F: FnOnce(&mut Self::InterchangeProjectMut) -> Result<(), E>,
     {
+        let existing = self
+            .projects
+            .get(uri.as_ref())
+            .and_then(|versions| versions.get(version.as_ref()));
+        if let Some(existing) = existing {
+            let installed = match (checksum, existing.checksum_canonical_variant()) {
+                (Some(new), Ok(old)) if new == old => ProjectChecksumResult::Match,
+                (Some(_), Ok(_)) => ProjectChecksumResult::Mismatch,
+                _ => ProjectChecksumResult::ChecksumNotPresent,
+            };
+            if !overwrite.should_write(uri.as_ref(), version.as_ref(), installed)? {
+                return Ok(existing.clone());
+            }
+        }
+
         let mut tentative_project = Project::default();
 
         write_project(&mut tentative_project).map_err(PutProjectError::Callback)?;
@@ -5,8 +5,8 @@ use std::collections::HashMap;
 
 use crate::{
     env::{
-        ReadEnvironment, WriteEnvironment,
-        memory::MemoryStorageEnvironment,
+        OverwritePolicy, PutProjectError, ReadEnvironment, WriteEnvironment,
+        memory::{MemoryStorageEnvironment, MemoryWriteError},
         utils::{CloneError, clone_project},
     },
     init::do_init_memory,
@@ -20,6 +20,55 @@ fn new_env() -> MemoryStorageEnvironment<InMemoryProject> {
     MemoryStorageEnvironment::<InMemoryProject>::new()
 }
 
+/// Installs `project` as version 0.0.1 of `urn:kpar:first` and returns the
+/// name of the installed project
+fn put(
+    env: &mut MemoryStorageEnvironment<InMemoryProject>,
+    project: &InMemoryProject,
+    overwrite: OverwritePolicy,
+) -> Result<String, PutProjectError<MemoryWriteError, CloneError<InMemoryError, InMemoryError>>> {
+    let checksum = project.checksum_canonical_variant().unwrap();
+    env.put_project("urn:kpar:first", "0.0.1", Some(checksum), overwrite, |p| {
+        clone_project(project, p, true).map(|_| ())
+    })
+    .map(|p| p.get_info().unwrap().unwrap().name)
+}
+
+#[test]
+fn overwrite_policy() {
+    let first = do_init_memory("First", None::<&str>, "0.0.1", None).unwrap();
+    let other = do_init_memory("Other", None::<&str>, "0.0.1", None).unwrap();
+    let mut env = new_env();
+    put(&mut env, &first, OverwritePolicy::Error).unwrap();
+
+    // Identical versions are kept unless replacing unconditionally
+    for policy in [
+        OverwritePolicy::Error,
+        OverwritePolicy::Skip,
+        OverwritePolicy::ReplaceIfDifferentHash,
+        OverwritePolicy::Replace,
+    ] {
+        assert_eq!(put(&mut env, &first, policy).unwrap(), "First");
+    }
+
+    assert!(matches!(
+        put(&mut env, &other, OverwritePolicy::Error),
+        Err(PutProjectError::AlreadyInstalled { .. })
+    ));
+    assert_eq!(
+        put(&mut env, &other, OverwritePolicy::Skip).unwrap(),
+        "First"
+    );
+    assert_eq!(
+        put(&mut env, &other, OverwritePolicy::ReplaceIfDifferentHash).unwrap(),
+        "Other"
+    );
+    assert_eq!(
+        put(&mut env, &first, OverwritePolicy::Replace).unwrap(),
+        "First"
+    );
+}
+
 #[test]
 fn write_environment() {
     let uri1 = "urn:kpar:first".to_string();
@@ -31,7 +80,7 @@ fn write_environment() {
     let c2 = project2.checksum_canonical_variant().unwrap();
     let mut env = new_env();
 
-    env.put_project(&uri1, &version, Some(c1), |p| {
+    env.put_project(&uri1, &version, Some(c1), OverwritePolicy::Replace, |p| {
         clone_project(&project1, p, true)?;
 
         Ok::<(), CloneError<InMemoryError, InMemoryError>>(())
@@ -44,7 +93,7 @@ fn write_environment() {
         env.projects.get(&uri1).unwrap().get(&version).unwrap()
     );
 
-    env.put_project(&uri2, &version, Some(c2), |p| {
+    env.put_project(&uri2, &version, Some(c2), OverwritePolicy::Replace, |p| {
         clone_project(&project2, p, true)?;
 
         Ok::<(), CloneError<InMemoryError, InMemoryError>>(())
@@ -6,8 +6,11 @@ use std::{fmt::Debug, marker::Unpin, sync::Arc};
 use futures::{Stream, StreamExt};
 use thiserror::Error;
 
+pub use sysand_macros::ReadEnvironment;
+
 use crate::{
     env::utils::ErrorBound,
+    lock::Source,
     project::{
         AsAsyncProject, AsSyncProjectTokio, ProjectChecksum, ProjectMut, ProjectRead,
         ProjectReadAsync,
@@ -15,14 +18,21 @@ use crate::{
 };
 
 // Implementations
+#[cfg(feature = "filesystem")]
+pub mod content_addressed;
 #[cfg(all(feature = "filesystem", feature = "networking"))]
 pub mod discovery;
+pub mod fallback;
 #[cfg(all(feature = "filesystem", feature = "networking"))]
 pub mod index;
 #[cfg(feature = "filesystem")]
+pub mod layered;
+#[cfg(feature = "filesystem")]
 pub mod local_directory;
 pub mod memory;
 pub mod null;
+#[cfg(all(feature = "filesystem", feature = "networking"))]
+pub mod remote;
 
 pub mod utils;
 
@@ -352,10 +362,57 @@ pub enum PutProjectError<WE, CE> {
     Callback(CE),
     #[error("failed to parse `{0}` as IRI: {1}")]
     IriParse(String, fluent_uri::ParseError),
+    #[error("version `{version}` of `{uri}` is already installed with different contents")]
+    AlreadyInstalled { uri: String, version: String },
     // #[error(transparent)]
     // AddProject(AE),
 }
 
+/// What [`WriteEnvironment::put_project`] does if the version of the
+/// project is already installed. The installed version is identical if it
+/// was installed with the same checksum
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    /// Fail, unless the installed version is identical, which is then kept
+    #[default]
+    Error,
+    /// Keep the installed version
+    Skip,
+    /// Replace the installed version
+    Replace,
+    /// Replace the installed version, unless it is identical
+    ReplaceIfDifferentHash,
+}
+
+impl OverwritePolicy {
+    /// Whether to write version `version` of `uri`, given `installed`, the
+    /// result of comparing the checksum of the installed version with the
+    /// new one. Logs the installed versions that are kept
+    pub fn should_write<WE, CE>(
+        self,
+        uri: &str,
+        version: &str,
+        installed: ProjectChecksumResult,
+    ) -> Result<bool, PutProjectError<WE, CE>> {
+        let keep = |reason: &str| {
+            let skipping = "Skipping";
+            let header = crate::style::get_style_config().header;
+            log::info!("{header}{skipping:>12}{header:#} `{uri}` {version}: {reason}");
+            Ok(false)
+        };
+        match (self, installed) {
+            (_, ProjectChecksumResult::VersionNotFound) | (OverwritePolicy::Replace, _) => Ok(true),
+            (_, ProjectChecksumResult::Match) => keep("identical version already installed"),
+            (OverwritePolicy::Skip, _) => keep("version already installed"),
+            (OverwritePolicy::ReplaceIfDifferentHash, _) => Ok(true),
+            (OverwritePolicy::Error, _) => Err(PutProjectError::AlreadyInstalled {
+                uri: uri.to_owned(),
+                version: version.to_owned(),
+            }),
+        }
+    }
+}
+
 pub trait WriteEnvironment {
     type WriteError: ErrorBound;
 
@@ -363,12 +420,16 @@ pub trait WriteEnvironment {
 
     // TODO: Should this be replaced by a transactional interface?
     /// Install a project in the environment. The project files will be copied into
-    /// the environment, so this is not suitable for editable projects
+    /// the environment, so this is not suitable for editable projects.
+    /// If the version is already installed, `overwrite` decides whether it
+    /// is replaced, see [`OverwritePolicy::should_write`]; a kept version is
+    /// returned without calling `write_project`
     fn put_project<S: AsRef<str>, T: AsRef<str>, F, CE>(
         &mut self,
         uri: S,
         version: T,
         checksum: Option<ProjectChecksum>,
+        overwrite: OverwritePolicy,
         // Callback allows the implementation to gracefully recover
         // in case of an error, to just "allocate"
         write_project: F,
@@ -383,4 +444,42 @@ pub trait WriteEnvironment {
     ) -> Result<(), Self::WriteError>;
 
     fn del_uri<S: AsRef<str>>(&mut self, uri: S) -> Result<(), Self::WriteError>;
+
+    /// Whether the environment refuses all writes, e.g. because it is
+    /// distributed with a tool installation or managed by an admin.
+    /// Reading from it is unaffected
+    fn is_read_only(&self) -> bool {
+        false
+    }
+
+    /// Whether `uri` can be installed with only its `.project.json` and
+    /// `.meta.json`, see [`set_metadata_only`](Self::set_metadata_only)
+    fn supports_metadata_only<S: AsRef<str>>(&self, _uri: S) -> bool {
+        false
+    }
+
+    /// Record that version `version` of `uri` was installed without its
+    /// source files, which are to be fetched from `sources` when first
+    /// read. Only called if [`supports_metadata_only`](Self::supports_metadata_only)
+    /// is true for `uri`
+    fn set_metadata_only<S: AsRef<str>, T: AsRef<str>>(
+        &mut self,
+        _uri: S,
+        _version: T,
+        _sources: Vec<Source>,
+    ) -> Result<(), Self::WriteError> {
+        Ok(())
+    }
+
+    /// Record that version `version` of `uri` was installed from `source`
+    /// with `checksum`. Environments that keep no such records ignore it
+    fn record_install<S: AsRef<str>, T: AsRef<str>>(
+        &mut self,
+        _uri: S,
+        _version: T,
+        _source: Option<&Source>,
+        _checksum: Option<&ProjectChecksum>,
+    ) -> Result<(), Self::WriteError> {
+        Ok(())
+    }
 }
@@ -0,0 +1,199 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Read-only access to a local environment directory served over HTTP,
+//! e.g. a `.sysand` directory shared by a team on a fast network. The
+//! environment is described by its `env.toml`, and its projects are read
+//! as remote source projects relative to the environment URL.
+//!
+//! A remote environment is meant to be used as a cache, so failing to
+//! fetch or parse its `env.toml` is logged and the environment is then
+//! treated as empty instead of failing resolution.
+
+use std::{str::FromStr, sync::Arc};
+
+use thiserror::Error;
+
+use crate::{
+    auth::HTTPAuthentication,
+    env::{
+        ProjectChecksumResult, ReadEnvironmentAsync,
+        discovery::with_trailing_slash,
+        local_directory::{
+            METADATA_PATH,
+            metadata::{EnvMetadata, EnvProjectChecksum},
+        },
+    },
+    project::{ProjectChecksum, reqwest_src::ReqwestSrcProjectAsync},
+    resolve::net_utils::text_get_request,
+    utils::format_err,
+};
+
+#[derive(Error, Debug)]
+pub enum RemoteEnvironmentError {
+    #[error("project `{0}` is not present in remote environment `{1}`")]
+    ProjectNotFound(Box<str>, Box<str>),
+}
+
+/// Environment at `url`, whose `env.toml` is fetched once, on first use.
+/// Editable projects are skipped, as their sources are not inside the
+/// environment directory
+#[derive(Debug)]
+pub struct RemoteEnvironmentAsync<Policy> {
+    client: reqwest_middleware::ClientWithMiddleware,
+    auth_policy: Arc<Policy>,
+    url: url::Url,
+    metadata: tokio::sync::OnceCell<EnvMetadata>,
+}
+
+impl<Policy> RemoteEnvironmentAsync<Policy> {
+    /// `url` is the URL of the environment directory, i.e. the directory
+    /// containing `env.toml`
+    pub fn new(
+        client: reqwest_middleware::ClientWithMiddleware,
+        auth_policy: Arc<Policy>,
+        url: url::Url,
+    ) -> Self {
+        let url = if url.cannot_be_a_base() {
+            url
+        } else {
+            with_trailing_slash(url)
+        };
+        Self {
+            client,
+            auth_policy,
+            url,
+            metadata: tokio::sync::OnceCell::new(),
+        }
+    }
+
+    pub fn url(&self) -> &url::Url {
+        &self.url
+    }
+}
+
+impl<Policy: HTTPAuthentication> RemoteEnvironmentAsync<Policy> {
+    async fn metadata(&self) -> &EnvMetadata {
+        self.metadata
+            .get_or_init(|| async {
+                match self.fetch_metadata().await {
+                    Ok(metadata) => metadata,
+                    Err(e) => {
+                        log::warn!("not using shared environment `{}`: {e}", self.url);
+                        EnvMetadata::default()
+                    }
+                }
+            })
+            .await
+    }
+
+    async fn fetch_metadata(&self) -> Result<EnvMetadata, String> {
+        let url = self.url.join(METADATA_PATH).map_err(|e| e.to_string())?;
+        let response = self
+            .auth_policy
+            .with_authentication(&self.client, &text_get_request(url.clone()))
+            .await
+            .map_err(format_err)?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "HTTP request to `{url}` returned status {}",
+                response.status()
+            ));
+        }
+        let text = response.text().await.map_err(format_err)?;
+        EnvMetadata::from_str(&text).map_err(|e| format!("invalid `{url}`: {e}"))
+    }
+
+    /// Base URL of the project installed at `path`, relative to the env
+    fn project_url(&self, path: &str) -> url::Url {
+        let mut url = self.url.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.pop_if_empty();
+            segments.extend(path.split('/').filter(|s| !s.is_empty()));
+            segments.push("");
+        }
+        url
+    }
+}
+
+type ResultStream<T> = futures::stream::Iter<std::vec::IntoIter<Result<T, RemoteEnvironmentError>>>;
+
+impl<Policy: HTTPAuthentication> ReadEnvironmentAsync for RemoteEnvironmentAsync<Policy> {
+    type ReadError = RemoteEnvironmentError;
+
+    type UriStream = ResultStream<String>;
+
+    async fn uris_async(&self) -> Result<Self::UriStream, Self::ReadError> {
+        let uris: Vec<_> = self
+            .metadata()
+            .await
+            .projects
+            .iter()
+            .filter(|p| !p.editable)
+            .flat_map(|p| p.identifiers.iter().cloned().map(Ok))
+            .collect();
+        Ok(futures::stream::iter(uris))
+    }
+
+    type VersionStream = ResultStream<String>;
+
+    async fn versions_async<S: AsRef<str>>(
+        &self,
+        uri: S,
+    ) -> Result<Self::VersionStream, Self::ReadError> {
+        let versions: Vec<_> = self
+            .metadata()
+            .await
+            .find_project_versions(uri.as_ref())
+            .filter(|p| !p.editable)
+            .map(|p| Ok(p.version.clone()))
+            .collect();
+        Ok(futures::stream::iter(versions))
+    }
+
+    type InterchangeProjectRead = ReqwestSrcProjectAsync<Policy>;
+
+    async fn get_project_async<S: AsRef<str>, T: AsRef<str>>(
+        &self,
+        uri: S,
+        version: T,
+    ) -> Result<Self::InterchangeProjectRead, Self::ReadError> {
+        let project = self
+            .metadata()
+            .await
+            .find_project_version(&uri, &version)
+            .filter(|p| !p.editable)
+            .ok_or_else(|| {
+                RemoteEnvironmentError::ProjectNotFound(
+                    uri.as_ref().into(),
+                    self.url.as_str().into(),
+                )
+            })?;
+        let expected_checksum = match &project.checksum {
+            Some(EnvProjectChecksum::Project { src_cksum }) => Some(src_cksum.clone()),
+            _ => None,
+        };
+        Ok(ReqwestSrcProjectAsync::new(
+            self.client.clone(),
+            self.project_url(project.path.as_str()),
+            self.auth_policy.clone(),
+            expected_checksum,
+        ))
+    }
+
+    async fn has_version_verified_async<S: AsRef<str>, V: AsRef<str>>(
+        &self,
+        uri: S,
+        version: V,
+        checksum: &ProjectChecksum,
+    ) -> Result<ProjectChecksumResult, Self::ReadError> {
+        Ok(self
+            .metadata()
+            .await
+            .verify_checksum(uri, version, checksum))
+    }
+}
+
+#[cfg(test)]
+#[path = "./remote_tests.rs"]
+mod tests;
@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::sync::Arc;
+
+use crate::{
+    auth::Unauthenticated,
+    env::{ProjectChecksumResult, ReadEnvironment, ReadEnvironmentAsync},
+    project::{ProjectChecksum, ProjectRead},
+    resolve::net_utils::create_reqwest_client,
+};
+
+use super::RemoteEnvironmentAsync;
+
+const ENV_TOML: &str = r#"version = "0.1"
+
+[[project]]
+name = "Shared"
+version = "1.0.0"
+path = "lib/shared_1.0.0"
+identifiers = ["urn:kpar:shared"]
+src_cksum = "abc"
+
+[[project]]
+name = "Shared"
+version = "2.0.0"
+path = "lib/shared_2.0.0"
+identifiers = ["urn:kpar:shared"]
+
+[[project]]
+name = "Editable"
+version = "1.0.0"
+path = "../editable"
+identifiers = ["urn:kpar:editable"]
+editable = true
+"#;
+
+fn remote_env(
+    url: &str,
+) -> Result<
+    crate::env::AsSyncEnvironmentTokio<RemoteEnvironmentAsync<Unauthenticated>>,
+    Box<dyn std::error::Error>,
+> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    Ok(RemoteEnvironmentAsync::new(
+        create_reqwest_client()?,
+        Arc::new(Unauthenticated {}),
+        url::Url::parse(url)?,
+    )
+    .to_tokio_sync(Arc::new(runtime)))
+}
+
+#[test]
+fn remote_env_reads_env_toml_once() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = mockito::Server::new();
+    let env_mock = server
+        .mock("GET", "/team/.sysand/env.toml")
+        .with_status(200)
+        .with_body(ENV_TOML)
+        .expect(1)
+        .create();
+    let info_mock = server
+        .mock("GET", "/team/.sysand/lib/shared_1.0.0/.project.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"name":"Shared","version":"1.0.0"}"#)
+        .expect(1)
+        .create();
+
+    // No trailing slash: the URL is that of the env directory
+    let env = remote_env(&format!("{}/team/.sysand", server.url()))?;
+
+    let uris: Vec<_> = env.uris()?.collect::<Result<_, _>>()?;
+    assert_eq!(uris, ["urn:kpar:shared", "urn:kpar:shared"]);
+    let versions: Vec<_> = env.versions("urn:kpar:shared")?.collect::<Result<_, _>>()?;
+    assert_eq!(versions, ["1.0.0", "2.0.0"]);
+    assert!(!env.has("urn:kpar:editable")?);
+    assert!(env.get_project("urn:kpar:editable", "1.0.0").is_err());
+
+    let project = env.get_project("urn:kpar:shared", "1.0.0")?;
+    assert_eq!(project.inner.expected_checksum.as_deref(), Some("abc"));
+    assert_eq!(project.get_info()?.unwrap().name, "Shared");
+
+    assert_eq!(
+        env.has_version_verified(
+            "urn:kpar:shared",
+            "1.0.0",
+            &ProjectChecksum::Project("abc".into())
+        )?,
+        ProjectChecksumResult::Match
+    );
+    assert_eq!(
+        env.has_version_verified(
+            "urn:kpar:shared",
+            "2.0.0",
+            &ProjectChecksum::Project("abc".into())
+        )?,
+        ProjectChecksumResult::ChecksumNotPresent
+    );
+
+    env_mock.assert();
+    info_mock.assert();
+
+    Ok(())
+}
+
+#[test]
+fn unavailable_remote_env_is_empty() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = mockito::Server::new();
+    let env_mock = server
+        .mock("GET", "/.sysand/env.toml")
+        .with_status(404)
+        .expect(1)
+        .create();
+
+    let env = remote_env(&format!("{}/.sysand/", server.url()))?;
+
+    assert_eq!(env.uris()?.count(), 0);
+    assert_eq!(env.versions("urn:kpar:shared")?.count(), 0);
+
+    env_mock.assert();
+
+    Ok(())
+}
@@ -44,44 +44,76 @@ pub fn clone_project<P: ProjectRead, Q: ProjectMut>(
     (InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw),
     CloneError<P::Error, Q::Error>,
 > {
-    match from.get_project().map_err(CloneError::ProjectRead)? {
+    let (info, meta) = require_info_meta(from.get_project().map_err(CloneError::ProjectRead)?)?;
+    to.put_project(&info, &meta, overwrite)
+        .map_err(CloneError::EnvWrite)?;
+
+    for source_path in &meta.source_paths(true) {
+        let mut source = from
+            .read_source(source_path)
+            .map_err(CloneError::ProjectRead)?;
+        to.write_source(source_path, &mut source, overwrite)
+            .map_err(CloneError::EnvWrite)?;
+    }
+    for path in optional_files(&info) {
+        copy_optional_file(from, to, overwrite, path)?;
+    }
+
+    Ok((info, meta))
+}
+
+/// Both `.project.json` and `.meta.json` of a project that is copied
+pub(crate) fn require_info_meta<PE: ErrorBound, WE: ErrorBound>(
+    project: (
+        Option<InterchangeProjectInfoRaw>,
+        Option<InterchangeProjectMetadataRaw>,
+    ),
+) -> Result<(InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw), CloneError<PE, WE>> {
+    match project {
         (None, None) => Err(CloneError::IncompleteSource(
             "missing `.project.json` and `.meta.json`",
         )),
         (None, _) => Err(CloneError::IncompleteSource("missing `.project.json`")),
         (_, None) => Err(CloneError::IncompleteSource("missing `.meta.json`")),
-        (Some(info), Some(meta)) => {
-            to.put_project(&info, &meta, overwrite)
-                .map_err(CloneError::EnvWrite)?;
+        (Some(info), Some(meta)) => Ok((info, meta)),
+    }
+}
 
-            for source_path in &meta.source_paths(true) {
-                let mut source = from
-                    .read_source(source_path)
-                    .map_err(CloneError::ProjectRead)?;
-                to.write_source(source_path, &mut source, overwrite)
-                    .map_err(CloneError::EnvWrite)?;
+/// Files that are copied together with the sources of a project if they
+/// exist: `README.md` and the texts of its licenses
+pub(crate) fn optional_files(info: &InterchangeProjectInfoRaw) -> Vec<String> {
+    let mut files = vec!["README.md".to_owned()];
+    if let Some(l) = info.license.as_deref() {
+        match spdx::Expression::parse(l) {
+            Ok(expr) => {
+                files.extend(
+                    license_file_stems(&expr)
+                        .into_iter()
+                        .map(|stem| format!("LICENSES/{stem}.txt")),
+                );
             }
-            copy_optional_file(from, to, overwrite, "README.md")?;
-
-            if let Some(l) = info.license.as_deref() {
-                match spdx::Expression::parse(l) {
-                    Ok(expr) => {
-                        for stem in license_file_stems(&expr) {
-                            let relative = format!("LICENSES/{stem}.txt");
-                            copy_optional_file(from, to, overwrite, relative)?;
-                        }
-                    }
-                    Err(e) => {
-                        log::debug!(
-                            "project's license `{l}` is not a valid SPDX license expression:\n{e}"
-                        );
-                    }
-                }
+            Err(e) => {
+                log::debug!("project's license `{l}` is not a valid SPDX license expression:\n{e}");
             }
-
-            Ok((info, meta))
         }
     }
+    files
+}
+
+/// Copies only `.project.json` and `.meta.json` of the project from
+/// `from` to `to`, without any of its source files. Returns project metadata
+pub fn clone_project_metadata<P: ProjectRead, Q: ProjectMut>(
+    from: &P,
+    to: &mut Q,
+    overwrite: bool,
+) -> Result<
+    (InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw),
+    CloneError<P::Error, Q::Error>,
+> {
+    let (info, meta) = require_info_meta(from.get_project().map_err(CloneError::ProjectRead)?)?;
+    to.put_project(&info, &meta, overwrite)
+        .map_err(CloneError::EnvWrite)?;
+    Ok((info, meta))
 }
 
 fn copy_optional_file<P: ProjectRead, Q: ProjectMut, S: AsRef<Utf8UnixPath>>(
@@ -0,0 +1,161 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Stable codes identifying errors, so that tools consuming sysand output
+//! can react to them without parsing messages.
+//!
+//! Every error variant of the command errors has a code of the form
+//! `SYSAND-E<number>`. A code is never reused for a different error and its
+//! [`ErrorClass`] never changes; messages may change at any time. Codes are
+//! allocated in blocks:
+//!
+//! | Codes         | Errors                                       |
+//! |---------------|----------------------------------------------|
+//! | `E0001`       | Errors without a more specific code          |
+//! | `E0002`       | Invalid command line                         |
+//! | `E0010-E0099` | Filesystem ([`FsIoError`])                   |
+//! | `E0100-E0199` | `init`                                       |
+//! | `E0200-E0299` | `add`, including index search                |
+//! | `E0300-E0399` | `remove`                                     |
+//! | `E0400-E0499` | `include`                                    |
+//! | `E0500-E0599` | `exclude`                                    |
+//! | `E0600-E0699` | `info`                                       |
+//! | `E0700-E0799` | `sources`                                    |
+//! | `E0800-E0899` | `lock`                                       |
+//! | `E0900-E0999` | `sync`                                       |
+//! | `E1000-E1099` | `build`                                      |
+//! | `E1100-E1199` | `env`                                        |
+//! | `E1200-E1299` | `publish`                                    |
+//! | `E1300-E1699` | `index` (`init`, `add`, `yank`, `remove`)    |
+//! | `E1700-E1799` | `export`                                     |
+//! | `E1800-E1899` | `migrate-iri`                                |
+//! | `E1900-E1999` | Command line interface                       |
+//! | `E2000-E2099` | `migrate`                                    |
+//! | `E2100-E2199` | `list`                                       |
+//! | `E2200-E2299` | `upgrade-check`                              |
+//! | `E2300-E2399` | `verify`                                     |
+//! | `E2400-E2499` | `files`                                      |
+//! | `E2500-E2599` | `import`                                     |
+//! | `E2600-E2699` | `bundle`                                     |
+//! | `E2700-E2799` | `owner`                                      |
+//! | `E2800-E2899` | Lifecycle hooks (`[hooks]`)                  |
+//! | `E2900-E2999` | `normalize`                                  |
+//! | `E3000-E3099` | `Session`                                    |
+//! | `E3100-E3199` | `config`                                     |
+//!
+//! [`FsIoError`]: crate::project::utils::FsIoError
+
+use std::fmt;
+
+/// Broad category of an error. Each class has its own process exit code,
+/// see [`ErrorClass::exit_code`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorClass {
+    /// Errors not covered by any other class
+    Other,
+    /// Invalid command line arguments or argument values
+    Usage,
+    /// Project is missing, invalid or cannot be modified as requested
+    Project,
+    /// Dependencies cannot be found or have no compatible versions
+    Resolution,
+    /// Failure reading or modifying an environment
+    Environment,
+    /// Failure building a KPAR
+    Build,
+    /// Failure reading or modifying a local index
+    Index,
+    /// Project cannot be published or the index rejected it
+    Publish,
+    /// Filesystem error
+    Io,
+    /// Network or HTTP error
+    Network,
+}
+
+impl ErrorClass {
+    /// Process exit code for errors of this class. `0` is success, `101`
+    /// is used for crashes
+    pub fn exit_code(self) -> u8 {
+        match self {
+            ErrorClass::Other => 1,
+            ErrorClass::Usage => 2,
+            ErrorClass::Project => 3,
+            ErrorClass::Resolution => 4,
+            ErrorClass::Environment => 5,
+            ErrorClass::Build => 6,
+            ErrorClass::Index => 7,
+            ErrorClass::Publish => 8,
+            ErrorClass::Io => 9,
+            ErrorClass::Network => 10,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorClass::Other => "other",
+            ErrorClass::Usage => "usage",
+            ErrorClass::Project => "project",
+            ErrorClass::Resolution => "resolution",
+            ErrorClass::Environment => "environment",
+            ErrorClass::Build => "build",
+            ErrorClass::Index => "index",
+            ErrorClass::Publish => "publish",
+            ErrorClass::Io => "io",
+            ErrorClass::Network => "network",
+        }
+    }
+}
+
+impl fmt::Display for ErrorClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Code of a specific error, displayed as e.g. `SYSAND-E0012`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ErrorCode {
+    number: u16,
+    class: ErrorClass,
+}
+
+impl ErrorCode {
+    /// Error without a more specific code
+    pub const OTHER: ErrorCode = ErrorCode::new(1, ErrorClass::Other);
+    /// Invalid command line
+    pub const USAGE: ErrorCode = ErrorCode::new(2, ErrorClass::Usage);
+
+    pub const fn new(number: u16, class: ErrorClass) -> Self {
+        Self { number, class }
+    }
+
+    pub fn number(self) -> u16 {
+        self.number
+    }
+
+    pub fn class(self) -> ErrorClass {
+        self.class
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SYSAND-E{:04}", self.number)
+    }
+}
+
+/// Errors with a stable [`ErrorCode`] for each variant
+pub trait HasErrorCode {
+    fn error_code(&self) -> ErrorCode;
+}
+
+impl<T: HasErrorCode + ?Sized> HasErrorCode for Box<T> {
+    fn error_code(&self) -> ErrorCode {
+        (**self).error_code()
+    }
+}
+
+#[cfg(test)]
+#[path = "./error_code_tests.rs"]
+mod tests;
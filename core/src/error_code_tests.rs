@@ -0,0 +1,53 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::collections::HashSet;
+
+use super::*;
+use crate::{
+    commands::init::InitError,
+    project::{memory::InMemoryError, utils::FsIoError},
+};
+
+const CLASSES: [ErrorClass; 10] = [
+    ErrorClass::Other,
+    ErrorClass::Usage,
+    ErrorClass::Project,
+    ErrorClass::Resolution,
+    ErrorClass::Environment,
+    ErrorClass::Build,
+    ErrorClass::Index,
+    ErrorClass::Publish,
+    ErrorClass::Io,
+    ErrorClass::Network,
+];
+
+#[test]
+fn display() {
+    assert_eq!(ErrorCode::OTHER.to_string(), "SYSAND-E0001");
+    assert_eq!(
+        ErrorCode::new(1264, ErrorClass::Publish).to_string(),
+        "SYSAND-E1264"
+    );
+}
+
+#[test]
+fn exit_codes_are_distinct() {
+    let exit_codes: HashSet<_> = CLASSES.iter().map(|c| c.exit_code()).collect();
+    assert_eq!(exit_codes.len(), CLASSES.len());
+    assert!(!exit_codes.contains(&0));
+    assert_eq!(ErrorClass::Usage.exit_code(), 2);
+}
+
+#[test]
+fn variant_codes() {
+    let err = FsIoError::CurrentDir(std::io::Error::other("test"));
+    assert_eq!(err.error_code().to_string(), "SYSAND-E0027");
+    assert_eq!(Box::new(err).error_code().class(), ErrorClass::Io);
+
+    let err = InitError::<InMemoryError>::SemVerParse(
+        "x".into(),
+        semver::Version::parse("x").unwrap_err(),
+    );
+    assert_eq!(err.error_code(), ErrorCode::new(101, ErrorClass::Usage));
+}
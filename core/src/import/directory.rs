@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Plain directory of dependencies, e.g. `libs/`, where every project
+//! directory (one with `.project.json`) and every KPAR directly inside is a
+//! dependency.
+
+use camino::Utf8Path;
+
+use crate::{
+    import::{ImportError, file_url_usage},
+    model::InterchangeProjectUsageRaw,
+    project::utils::{FsIoError, wrapfs},
+};
+
+/// Usages of the projects and KPARs directly in the directory `path`,
+/// sorted by file name. Other entries are ignored
+pub fn read_dependencies<E>(
+    path: &Utf8Path,
+) -> Result<Vec<InterchangeProjectUsageRaw>, ImportError<E>> {
+    let mut dependencies = vec![];
+    for entry in wrapfs::read_dir(path)? {
+        let entry = entry.map_err(|e| FsIoError::ReadDir(path.to_owned(), e))?;
+        let entry_path = entry.path();
+        let is_project =
+            wrapfs::is_dir(entry_path)? && wrapfs::is_file(entry_path.join(".project.json"))?;
+        let is_kpar = entry_path.extension() == Some("kpar") && wrapfs::is_file(entry_path)?;
+        if is_project || is_kpar {
+            dependencies.push(entry_path.to_owned());
+        } else {
+            log::debug!("`{entry_path}` is neither a project nor a KPAR, skipping");
+        }
+    }
+    dependencies.sort();
+    dependencies.iter().map(|p| file_url_usage(p)).collect()
+}
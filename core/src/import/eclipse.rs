@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Eclipse `.project` files, as used by e.g. SysIDE. Project references
+//! are listed by name in `<projects>`, and refer to projects of the same
+//! Eclipse workspace, which are expected to be next to the referencing
+//! project:
+//!
+//! ```xml
+//! <projectDescription>
+//!     <name>Vehicle</name>
+//!     <projects>
+//!         <project>Units</project>
+//!     </projects>
+//! </projectDescription>
+//! ```
+
+use camino::Utf8Path;
+
+use crate::{
+    import::{ImportError, ImportFormat, file_url_usage},
+    model::InterchangeProjectUsageRaw,
+    project::utils::wrapfs,
+};
+
+pub const PROJECT_FILE: &str = ".project";
+
+/// Usages of the projects referenced by the `.project` file at `path`, or
+/// in the directory `path`. References to projects that do not exist are
+/// skipped with a warning
+pub fn read_dependencies<E>(
+    path: &Utf8Path,
+) -> Result<Vec<InterchangeProjectUsageRaw>, ImportError<E>> {
+    let file = if wrapfs::is_dir(path)? {
+        path.join(PROJECT_FILE)
+    } else {
+        path.to_owned()
+    };
+    let contents = wrapfs::read_to_string(&file)?;
+    let names = referenced_projects(&contents).map_err(|message| ImportError::Parse {
+        format: ImportFormat::Eclipse,
+        path: file.as_str().into(),
+        message,
+    })?;
+
+    // The Eclipse workspace contains the project containing the file
+    let workspace = file
+        .parent()
+        .and_then(Utf8Path::parent)
+        .unwrap_or(Utf8Path::new(".."));
+    let mut usages = vec![];
+    for name in names {
+        let project = workspace.join(&name);
+        if !wrapfs::is_dir(&project)? {
+            log::warn!("skipping referenced project `{name}`: `{project}` does not exist");
+            continue;
+        }
+        usages.push(file_url_usage(&project)?);
+    }
+    Ok(usages)
+}
+
+/// Names in `<project>` elements of the `<projects>` element
+fn referenced_projects(contents: &str) -> Result<Vec<String>, String> {
+    let Some(start) = contents.find("<projects>") else {
+        // `<projects/>` or no element at all
+        return Ok(vec![]);
+    };
+    let rest = &contents[start + "<projects>".len()..];
+    let end = rest
+        .find("</projects>")
+        .ok_or("`<projects>` element is not closed")?;
+    let mut projects = &rest[..end];
+
+    let mut names = vec![];
+    while let Some(start) = projects.find("<project>") {
+        let rest = &projects[start + "<project>".len()..];
+        let end = rest
+            .find("</project>")
+            .ok_or("`<project>` element is not closed")?;
+        let name = unescape(rest[..end].trim());
+        if name.is_empty() {
+            return Err("`<project>` element is empty".into());
+        }
+        names.push(name);
+        projects = &rest[end + "</project>".len()..];
+    }
+    Ok(names)
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
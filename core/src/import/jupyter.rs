@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Jupyter notebooks (`.ipynb`) declaring dependencies with magics in code
+//! cells, one per line, optionally with a version constraint:
+//!
+//! ```text
+//! %dependencies urn:kpar:units
+//! %dependencies pkg:sysand/acme/vehicles >=1.2
+//! ```
+
+use camino::Utf8Path;
+use serde::Deserialize;
+
+use crate::{
+    import::{ImportError, ImportFormat},
+    model::InterchangeProjectUsageRaw,
+    project::utils::wrapfs,
+};
+
+pub const DEPENDENCIES_MAGIC: &str = "%dependencies";
+
+#[derive(Deserialize)]
+struct Notebook {
+    #[serde(default)]
+    cells: Vec<Cell>,
+}
+
+#[derive(Deserialize)]
+struct Cell {
+    cell_type: String,
+    #[serde(default)]
+    source: Source,
+}
+
+/// Cell source is either a single string or a list of lines
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Source {
+    Text(String),
+    Lines(Vec<String>),
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Source::Text(String::new())
+    }
+}
+
+/// Usages declared by `%dependencies` magics in the code cells of the
+/// notebook at `path`
+pub fn read_dependencies<E>(
+    path: &Utf8Path,
+) -> Result<Vec<InterchangeProjectUsageRaw>, ImportError<E>> {
+    let parse_error = |message: String| ImportError::Parse {
+        format: ImportFormat::Jupyter,
+        path: path.as_str().into(),
+        message,
+    };
+    let contents = wrapfs::read_to_string(path)?;
+    let notebook: Notebook =
+        serde_json::from_str(&contents).map_err(|e| parse_error(e.to_string()))?;
+
+    let mut usages = vec![];
+    for cell in notebook.cells.iter().filter(|c| c.cell_type == "code") {
+        let source = match &cell.source {
+            Source::Text(text) => text.clone(),
+            Source::Lines(lines) => lines.concat(),
+        };
+        for line in source.lines() {
+            let Some(args) = line.trim().strip_prefix(DEPENDENCIES_MAGIC) else {
+                continue;
+            };
+            if !args.is_empty() && !args.starts_with(char::is_whitespace) {
+                continue;
+            }
+            let mut args = args.split_whitespace();
+            let Some(resource) = args.next() else {
+                return Err(parse_error(format!("`{line}` does not name a dependency")));
+            };
+            let version_constraint = args.collect::<Vec<_>>().join(" ");
+            usages.push(InterchangeProjectUsageRaw::Resource {
+                resource: resource.to_owned(),
+                version_constraint: (!version_constraint.is_empty()).then_some(version_constraint),
+                when: None,
+                hash: None,
+            });
+        }
+    }
+    Ok(usages)
+}
@@ -0,0 +1,161 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Reading dependencies declared by other SysML tooling, to turn them into
+//! usages of a sysand project. Each supported format has its own adapter.
+
+use std::{fmt, str::FromStr};
+
+use camino::Utf8Path;
+use thiserror::Error;
+
+use crate::{
+    add::{AddError, do_add},
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    model::InterchangeProjectUsageRaw,
+    project::{
+        ProjectMut,
+        utils::{FsIoError, wrapfs},
+    },
+};
+
+pub mod directory;
+pub mod eclipse;
+pub mod jupyter;
+
+/// Format of the dependency declarations to import
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Eclipse (e.g. SysIDE) `.project` file. Referenced projects are
+    /// expected next to the project containing the file
+    Eclipse,
+    /// Jupyter notebook with `%dependencies <IRI> [<version>]` magics
+    Jupyter,
+    /// Directory whose subdirectories and KPARs are the dependencies
+    Directory,
+}
+
+impl ImportFormat {
+    pub const ALL: [ImportFormat; 3] = [
+        ImportFormat::Eclipse,
+        ImportFormat::Jupyter,
+        ImportFormat::Directory,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportFormat::Eclipse => "eclipse",
+            ImportFormat::Jupyter => "jupyter",
+            ImportFormat::Directory => "directory",
+        }
+    }
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("unknown import format `{0}`, expected one of `eclipse`, `jupyter`, `directory`")]
+pub struct ImportFormatParseError(String);
+
+impl FromStr for ImportFormat {
+    type Err = ImportFormatParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ImportFormat::ALL
+            .into_iter()
+            .find(|f| f.as_str() == s)
+            .ok_or_else(|| ImportFormatParseError(s.to_owned()))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ImportError<ProjectError> {
+    #[error(transparent)]
+    Add(AddError<ProjectError>),
+    #[error("failed to parse {format} dependencies in `{path}`: {message}")]
+    Parse {
+        format: ImportFormat,
+        path: Box<str>,
+        message: String,
+    },
+    #[error("unsupported path `{0}`: it cannot be turned into a `file://` URL")]
+    UnsupportedPath(Box<str>),
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+}
+
+impl<ProjectError> From<FsIoError> for ImportError<ProjectError> {
+    fn from(v: FsIoError) -> Self {
+        Self::Io(Box::new(v))
+    }
+}
+
+impl<ProjectError> HasErrorCode for ImportError<ProjectError> {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            ImportError::Add(e) => e.error_code(),
+            ImportError::Parse { .. } => ErrorCode::new(2501, ErrorClass::Usage),
+            ImportError::UnsupportedPath(_) => ErrorCode::new(2502, ErrorClass::Usage),
+            ImportError::Io(e) => e.error_code(),
+        }
+    }
+}
+
+/// Dependencies declared in `path` in the given `format`, in the order
+/// they are declared, without duplicates
+pub fn read_dependencies<E, P: AsRef<Utf8Path>>(
+    format: ImportFormat,
+    path: P,
+) -> Result<Vec<InterchangeProjectUsageRaw>, ImportError<E>> {
+    let path = path.as_ref();
+    let usages = match format {
+        ImportFormat::Eclipse => eclipse::read_dependencies(path)?,
+        ImportFormat::Jupyter => jupyter::read_dependencies(path)?,
+        ImportFormat::Directory => directory::read_dependencies(path)?,
+    };
+    let mut unique: Vec<InterchangeProjectUsageRaw> = Vec::with_capacity(usages.len());
+    for usage in usages {
+        if !unique.contains(&usage) {
+            unique.push(usage);
+        }
+    }
+    Ok(unique)
+}
+
+/// Add the dependencies declared in `path` in the given `format` as usages
+/// of `project`. Returns the usages that were added, i.e. not already
+/// present
+pub fn do_import<Pr: ProjectMut, P: AsRef<Utf8Path>>(
+    project: &mut Pr,
+    format: ImportFormat,
+    path: P,
+) -> Result<Vec<InterchangeProjectUsageRaw>, ImportError<Pr::Error>> {
+    let mut added = vec![];
+    for usage in read_dependencies(format, path)? {
+        if do_add(project, &usage).map_err(ImportError::Add)? {
+            added.push(usage);
+        }
+    }
+    Ok(added)
+}
+
+/// `file://` URL of the project at `path`, as used by `sysand add --path`
+fn file_url_usage<E>(path: &Utf8Path) -> Result<InterchangeProjectUsageRaw, ImportError<E>> {
+    let absolute = wrapfs::canonicalize(path)?;
+    let url = url::Url::from_file_path(&absolute)
+        .map_err(|()| ImportError::UnsupportedPath(path.as_str().into()))?;
+    Ok(InterchangeProjectUsageRaw::Resource {
+        resource: url.into(),
+        version_constraint: None,
+        when: None,
+        hash: None,
+    })
+}
+
+#[cfg(test)]
+#[path = "./mod_tests.rs"]
+mod tests;
@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use camino::Utf8Path;
+use camino_tempfile::tempdir;
+
+use crate::{
+    import::{ImportError, ImportFormat, do_import, read_dependencies},
+    init::do_init_memory,
+    model::InterchangeProjectUsageRaw,
+    project::{ProjectRead, memory::InMemoryError},
+};
+
+fn usage(resource: &str, version_constraint: Option<&str>) -> InterchangeProjectUsageRaw {
+    InterchangeProjectUsageRaw::Resource {
+        resource: resource.into(),
+        version_constraint: version_constraint.map(Into::into),
+        when: None,
+        hash: None,
+    }
+}
+
+fn file_url(path: &Utf8Path) -> String {
+    url::Url::from_file_path(path.canonicalize_utf8().unwrap())
+        .unwrap()
+        .into()
+}
+
+fn read(
+    format: ImportFormat,
+    path: &Utf8Path,
+) -> Result<Vec<InterchangeProjectUsageRaw>, ImportError<InMemoryError>> {
+    read_dependencies(format, path)
+}
+
+#[test]
+fn import_format_from_str() {
+    for format in ImportFormat::ALL {
+        assert_eq!(format.as_str().parse::<ImportFormat>().unwrap(), format);
+    }
+    assert!("maven".parse::<ImportFormat>().is_err());
+}
+
+#[test]
+fn import_eclipse() {
+    let tmp = tempdir().unwrap();
+    for dir in ["Vehicle", "Units", "Parts & Pieces"] {
+        std::fs::create_dir(tmp.path().join(dir)).unwrap();
+    }
+    std::fs::write(
+        tmp.path().join("Vehicle/.project"),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<projectDescription>
+    <name>Vehicle</name>
+    <comment></comment>
+    <projects>
+        <project>Units</project>
+        <project>Parts &amp; Pieces</project>
+        <project>Missing</project>
+    </projects>
+    <buildSpec>
+    </buildSpec>
+</projectDescription>
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read(ImportFormat::Eclipse, &tmp.path().join("Vehicle")).unwrap(),
+        [
+            usage(&file_url(&tmp.path().join("Units")), None),
+            usage(&file_url(&tmp.path().join("Parts & Pieces")), None),
+        ]
+    );
+
+    std::fs::write(
+        tmp.path().join("Vehicle/.project"),
+        "<projectDescription><projects><project>Units</projects></projectDescription>",
+    )
+    .unwrap();
+    assert!(matches!(
+        read(ImportFormat::Eclipse, &tmp.path().join("Vehicle/.project")),
+        Err(ImportError::Parse {
+            format: ImportFormat::Eclipse,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn import_jupyter() {
+    let tmp = tempdir().unwrap();
+    let notebook = tmp.path().join("model.ipynb");
+    std::fs::write(
+        &notebook,
+        r#"{
+  "cells": [
+    {"cell_type": "markdown", "source": "%dependencies urn:kpar:ignored"},
+    {"cell_type": "code", "source": ["%dependencies urn:kpar:units\n", "package P;\n"]},
+    {"cell_type": "code", "source": "  %dependencies pkg:sysand/acme/vehicles >=1.2, <2\n%dependenciesx urn:kpar:x\n%dependencies urn:kpar:units"}
+  ],
+  "metadata": {},
+  "nbformat": 4,
+  "nbformat_minor": 5
+}"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        read(ImportFormat::Jupyter, &notebook).unwrap(),
+        [
+            usage("urn:kpar:units", None),
+            usage("pkg:sysand/acme/vehicles", Some(">=1.2, <2")),
+        ]
+    );
+}
+
+#[test]
+fn import_directory() {
+    let tmp = tempdir().unwrap();
+    let libs = tmp.path().join("libs");
+    std::fs::create_dir_all(libs.join("b")).unwrap();
+    std::fs::create_dir_all(libs.join("not-a-project")).unwrap();
+    std::fs::write(libs.join("b/.project.json"), "{}").unwrap();
+    std::fs::write(libs.join("a.kpar"), "").unwrap();
+    std::fs::write(libs.join("notes.txt"), "").unwrap();
+
+    assert_eq!(
+        read(ImportFormat::Directory, &libs).unwrap(),
+        [
+            usage(&file_url(&libs.join("a.kpar")), None),
+            usage(&file_url(&libs.join("b")), None),
+        ]
+    );
+}
+
+#[test]
+fn import_adds_new_usages() {
+    let tmp = tempdir().unwrap();
+    let notebook = tmp.path().join("model.ipynb");
+    std::fs::write(
+        &notebook,
+        r#"{"cells": [{"cell_type": "code", "source": "%dependencies urn:kpar:a\n%dependencies urn:kpar:b 1.0.0"}]}"#,
+    )
+    .unwrap();
+    let mut project = do_init_memory("test", None::<&str>, "1.0.0", None).unwrap();
+    crate::add::do_add(&mut project, &usage("urn:kpar:a", None)).unwrap();
+
+    let added = do_import(&mut project, ImportFormat::Jupyter, &notebook).unwrap();
+    assert_eq!(added, [usage("urn:kpar:b", Some("1.0.0"))]);
+    let info = project.get_info().unwrap().unwrap();
+    assert_eq!(info.usage.len(), 2);
+}
@@ -0,0 +1,267 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Authenticated client of the API of a remote sysand index, used for the
+//! operations that modify the index, e.g. publishing and managing owners.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use reqwest::header;
+use reqwest_middleware::{ClientWithMiddleware, RequestBuilder};
+use serde::Deserialize;
+use thiserror::Error;
+use url::Url;
+
+use crate::{
+    auth::{ForceBearerAuth, HTTPAuthentication, PermissionDenied},
+    env::discovery::with_trailing_slash,
+};
+
+const OWNERS_ENDPOINT_PATH: &str = "v1/owners";
+const DEPRECATE_ENDPOINT_PATH: &str = "v1/deprecate";
+
+/// Response of the index API
+#[derive(Debug)]
+pub struct ApiResponse {
+    pub status: u16,
+    /// Final URL of the response, after redirects
+    pub url: String,
+    pub body: Bytes,
+}
+
+/// Client of the index API at `api_root`, as given by the discovery
+/// document of the index, see
+/// [`fetch_index_config`](crate::env::discovery::fetch_index_config).
+/// All requests carry the bearer token of the client
+#[derive(Debug, Clone)]
+pub struct IndexApiClient {
+    client: ClientWithMiddleware,
+    api_root: Url,
+    auth: ForceBearerAuth,
+    runtime: Arc<tokio::runtime::Runtime>,
+}
+
+impl IndexApiClient {
+    pub fn new(
+        client: ClientWithMiddleware,
+        api_root: Url,
+        auth: ForceBearerAuth,
+        runtime: Arc<tokio::runtime::Runtime>,
+    ) -> Self {
+        Self {
+            client,
+            api_root: with_trailing_slash(api_root),
+            auth,
+            runtime,
+        }
+    }
+
+    pub fn api_root(&self) -> &Url {
+        &self.api_root
+    }
+
+    /// URL of the endpoint at `path`, relative to the API root
+    pub fn endpoint(&self, path: &str) -> Url {
+        self.api_root.join(path).unwrap()
+    }
+
+    /// Send the request made by `build_request` with the credentials of the
+    /// client. `build_request` may be called several times, e.g. to retry
+    pub fn send<F>(&self, build_request: F) -> Result<ApiResponse, IndexApiError>
+    where
+        F: Fn(&ClientWithMiddleware) -> RequestBuilder + 'static,
+    {
+        self.runtime.block_on(async {
+            let response = self
+                .auth
+                .with_authentication(&self.client, &build_request)
+                .await?;
+            let status = response.status().as_u16();
+            let url = response.url().to_string();
+            let body = response
+                .bytes()
+                .await
+                .map_err(IndexApiError::ResponseBody)?;
+            Ok(ApiResponse { status, url, body })
+        })
+    }
+
+    /// Owners of the project `iri`
+    pub fn list_owners(&self, iri: &str) -> Result<Vec<String>, IndexApiError> {
+        #[derive(Deserialize)]
+        struct Owners {
+            owners: Vec<String>,
+        }
+
+        let mut url = self.endpoint(OWNERS_ENDPOINT_PATH);
+        url.query_pairs_mut().append_pair("iri", iri);
+        let response = self.send(move |c| c.get(url.clone()))?;
+        check_status(&response)?;
+        let owners: Owners = serde_json::from_slice(&response.body)
+            .map_err(|e| IndexApiError::MalformedResponse(response.url.into(), e))?;
+        Ok(owners.owners)
+    }
+
+    /// Make `owner` an owner of the project `iri`
+    pub fn add_owner(&self, iri: &str, owner: &str) -> Result<(), IndexApiError> {
+        self.modify_owner(reqwest::Method::PUT, iri, owner)
+    }
+
+    /// Make `owner` no longer an owner of the project `iri`
+    pub fn remove_owner(&self, iri: &str, owner: &str) -> Result<(), IndexApiError> {
+        self.modify_owner(reqwest::Method::DELETE, iri, owner)
+    }
+
+    /// Mark the project `iri` deprecated, suggesting `replaced_by` in its
+    /// place
+    pub fn deprecate(&self, iri: &str, replaced_by: Option<&str>) -> Result<(), IndexApiError> {
+        let url = self.endpoint(DEPRECATE_ENDPOINT_PATH);
+        let body = serde_json::json!({ "iri": iri, "replaced_by": replaced_by }).to_string();
+        let response = self.send(move |c| {
+            c.post(url.clone())
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(body.clone())
+        })?;
+        check_status(&response)
+    }
+
+    fn modify_owner(
+        &self,
+        method: reqwest::Method,
+        iri: &str,
+        owner: &str,
+    ) -> Result<(), IndexApiError> {
+        let url = self.endpoint(OWNERS_ENDPOINT_PATH);
+        let body = serde_json::json!({ "iri": iri, "owner": owner }).to_string();
+        let response = self.send(move |c| {
+            c.request(method.clone(), url.clone())
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(body.clone())
+        })?;
+        check_status(&response)
+    }
+}
+
+/// Server-declared reason for rejecting a modification of a project by
+/// credentials that do not own it, parsed from a 403 response body of the
+/// form `{"error": "...", "iri": "pkg:sysand/acme/lib", "owners": ["alice"]}`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotOwner {
+    pub iri: Option<String>,
+    /// Current owners of the project, as far as the server discloses them
+    pub owners: Vec<String>,
+}
+
+impl NotOwner {
+    /// Parse a 403 response body. Returns `None` if the body does not
+    /// list the owners of the project
+    pub fn from_response_body(body: &[u8]) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct Body {
+            iri: Option<String>,
+            owners: Vec<String>,
+        }
+
+        let body: Body = serde_json::from_slice(body).ok()?;
+        Some(Self {
+            iri: body.iri.filter(|iri| !iri.trim().is_empty()),
+            owners: body.owners,
+        })
+    }
+}
+
+impl std::fmt::Display for NotOwner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.iri {
+            Some(iri) => write!(f, "your credentials do not own `{iri}`")?,
+            None => write!(f, "your credentials do not own the project")?,
+        }
+        if self.owners.is_empty() {
+            Ok(())
+        } else {
+            write!(
+                f,
+                "; ask one of its owners (`{}`) to add you with `sysand owner add`",
+                self.owners.join("`, `")
+            )
+        }
+    }
+}
+
+impl std::error::Error for NotOwner {}
+
+/// Error for an unsuccessful `response`, if it is one
+pub fn check_status(response: &ApiResponse) -> Result<(), IndexApiError> {
+    let body = || error_body_to_string(&response.body);
+    match response.status {
+        200..=299 => Ok(()),
+        400 => Err(IndexApiError::BadRequest(body())),
+        401 => Err(IndexApiError::Unauthorized(body())),
+        403 => Err(
+            if let Some(not_owner) = NotOwner::from_response_body(&response.body) {
+                IndexApiError::NotOwner(not_owner)
+            } else if let Some(denied) = PermissionDenied::from_response_body(&response.body) {
+                IndexApiError::PermissionDenied(denied)
+            } else {
+                IndexApiError::Unauthorized(body())
+            },
+        ),
+        404 => Err(IndexApiError::NotFound(
+            response.url.as_str().into(),
+            body(),
+        )),
+        409 => Err(IndexApiError::Conflict(body())),
+        status => Err(IndexApiError::ServerError {
+            status,
+            body: body(),
+        }),
+    }
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// Message of a JSON `{"error": "..."}` body, or else the body itself
+pub(crate) fn error_body_to_string(body_bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(body_bytes);
+    let trimmed = text.trim();
+
+    if trimmed.is_empty() {
+        return "no error details provided".to_string();
+    }
+
+    serde_json::from_str::<ErrorResponse>(trimmed)
+        .map(|error| error.error)
+        .unwrap_or_else(|_| trimmed.to_string())
+}
+
+#[derive(Error, Debug)]
+pub enum IndexApiError {
+    #[error("HTTP request failed: {0:#?}")]
+    Http(#[from] reqwest_middleware::Error),
+    #[error("failed to read server response body: {0:#?}")]
+    ResponseBody(#[source] reqwest::Error),
+    #[error("malformed response from `{0}`")]
+    MalformedResponse(Box<str>, #[source] serde_json::Error),
+    #[error("server error ({status}): {body}")]
+    ServerError { status: u16, body: String },
+    #[error("authentication failed: {0}")]
+    Unauthorized(String),
+    #[error("not permitted: {0}")]
+    PermissionDenied(PermissionDenied),
+    #[error("not permitted: {0}")]
+    NotOwner(NotOwner),
+    #[error("conflict: {0}")]
+    Conflict(String),
+    #[error("bad request: {0}")]
+    BadRequest(String),
+    #[error("endpoint `{0}` not found: {1}")]
+    NotFound(Box<str>, String),
+}
+
+#[cfg(test)]
+#[path = "./api_tests.rs"]
+mod tests;
@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::{assert_matches, sync::Arc};
+
+use mockito::Matcher;
+use url::Url;
+
+use super::{IndexApiClient, IndexApiError, NotOwner};
+use crate::{
+    auth::{ForceBearerAuth, GlobMapBuilder},
+    commands::owner::{OwnerError, do_owner_add, do_owner_list, do_owner_remove, owner_bearer},
+    resolve::net_utils::create_reqwest_client,
+};
+
+fn runtime() -> Arc<tokio::runtime::Runtime> {
+    Arc::new(
+        tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .enable_time()
+            .build()
+            .unwrap(),
+    )
+}
+
+fn api_client(server: &mockito::Server) -> IndexApiClient {
+    IndexApiClient::new(
+        create_reqwest_client().unwrap(),
+        Url::parse(&format!("{}/api", server.url())).unwrap(),
+        ForceBearerAuth::new("test-token"),
+        runtime(),
+    )
+}
+
+#[test]
+fn endpoint_is_relative_to_api_root() {
+    let server = mockito::Server::new();
+    let api = api_client(&server);
+    assert_eq!(
+        api.endpoint("v1/owners").as_str(),
+        format!("{}/api/v1/owners", server.url())
+    );
+}
+
+#[test]
+fn list_owners() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/api/v1/owners")
+        .match_query(Matcher::UrlEncoded(
+            "iri".into(),
+            "pkg:sysand/acme/lib".into(),
+        ))
+        .match_header("authorization", "Bearer test-token")
+        .with_status(200)
+        .with_body(r#"{"owners":["bob","alice"]}"#)
+        .create();
+
+    let owners = do_owner_list(&api_client(&server), "pkg:sysand/acme/lib").unwrap();
+    assert_eq!(owners, ["alice", "bob"]);
+    mock.assert();
+}
+
+#[test]
+fn add_and_remove_owner() {
+    let mut server = mockito::Server::new();
+    let body = Matcher::Json(serde_json::json!({"iri": "pkg:sysand/acme/lib", "owner": "carol"}));
+    let add = server
+        .mock("PUT", "/api/v1/owners")
+        .match_body(body.clone())
+        .with_status(204)
+        .create();
+    let remove = server
+        .mock("DELETE", "/api/v1/owners")
+        .match_body(body)
+        .with_status(204)
+        .create();
+
+    let api = api_client(&server);
+    do_owner_add(&api, "pkg:sysand/acme/lib", "carol").unwrap();
+    do_owner_remove(&api, "pkg:sysand/acme/lib", "carol").unwrap();
+    add.assert();
+    remove.assert();
+}
+
+#[test]
+fn deprecate() {
+    let mut server = mockito::Server::new();
+    let with_replacement = server
+        .mock("POST", "/api/v1/deprecate")
+        .match_body(Matcher::Json(serde_json::json!(
+            {"iri": "pkg:sysand/acme/lib", "replaced_by": "pkg:sysand/acme/lib2"}
+        )))
+        .with_status(204)
+        .create();
+    let without_replacement = server
+        .mock("POST", "/api/v1/deprecate")
+        .match_body(Matcher::Json(serde_json::json!(
+            {"iri": "pkg:sysand/acme/old", "replaced_by": null}
+        )))
+        .with_status(204)
+        .create();
+
+    let api = api_client(&server);
+    api.deprecate("pkg:sysand/acme/lib", Some("pkg:sysand/acme/lib2"))
+        .unwrap();
+    api.deprecate("pkg:sysand/acme/old", None).unwrap();
+    with_replacement.assert();
+    without_replacement.assert();
+}
+
+#[test]
+fn not_owner() {
+    let mut server = mockito::Server::new();
+    server
+        .mock("PUT", "/api/v1/owners")
+        .with_status(403)
+        .with_body(r#"{"error":"not an owner","iri":"pkg:sysand/acme/lib","owners":["alice"]}"#)
+        .create();
+
+    let err = do_owner_add(&api_client(&server), "pkg:sysand/acme/lib", "carol").unwrap_err();
+    assert_matches!(
+        err,
+        OwnerError::Api(IndexApiError::NotOwner(NotOwner { owners, .. })) if owners == ["alice"]
+    );
+}
+
+#[test]
+fn non_sysand_iri_makes_no_request() {
+    let mut server = mockito::Server::new();
+    let mock = server.mock("GET", Matcher::Any).expect(0).create();
+
+    let err = do_owner_list(&api_client(&server), "urn:kpar:lib").unwrap_err();
+    assert_matches!(err, OwnerError::NotSysandIri(_));
+    mock.assert();
+}
+
+#[test]
+fn bearer_lookup() {
+    let api_root = Url::parse("https://index.example.com/api/").unwrap();
+    let mut builder = GlobMapBuilder::new();
+    builder.add(
+        "https://index.example.com/**",
+        ForceBearerAuth::new("token"),
+    );
+    owner_bearer(&builder.build().unwrap(), &api_root).unwrap();
+
+    let empty = GlobMapBuilder::new().build().unwrap();
+    assert_matches!(
+        owner_bearer(&empty, &api_root),
+        Err(OwnerError::NoBearer { .. })
+    );
+}
@@ -5,7 +5,7 @@ use thiserror::Error;
 
 use crate::{
     iri_normalize::{IriNormalizeError, canonicalize_iri},
-    purl::{PKG_SYSAND_PREFIX, SysandPurlError, parse_sysand_purl},
+    purl::{SysandPurlError, parse_sysand_purl},
     utils::sha256_lowercase_hex,
 };
 
@@ -23,28 +23,59 @@ pub enum ParseIriError {
         #[source]
         source: SysandPurlError,
     },
+    #[error(
+        "malformed `urn:kpar` IRI `{iri}`: name must be 1-100 ASCII letters, digits, \
+         `-`, `_` or `.`, starting and ending with a letter or digit"
+    )]
+    MalformedKparUrn { iri: Box<str> },
+}
+
+/// Prefix of IRIs identifying projects by a name, independent of where
+/// they are hosted
+pub const KPAR_URN_PREFIX: &str = "urn:kpar:";
+
+/// Parse a `urn:kpar:<name>` IRI into its name. Returns `Ok(None)` for IRIs
+/// that do not start with `urn:kpar:` (case-insensitive)
+pub fn parse_kpar_urn(iri: &str) -> Result<Option<&str>, ParseIriError> {
+    let Some(name) = iri
+        .get(..KPAR_URN_PREFIX.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(KPAR_URN_PREFIX))
+        .map(|_| &iri[KPAR_URN_PREFIX.len()..])
+    else {
+        return Ok(None);
+    };
+    let edge = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric());
+    let valid = name.len() <= 100
+        && edge(name.chars().next())
+        && edge(name.chars().next_back())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(ParseIriError::MalformedKparUrn { iri: iri.into() });
+    }
+    Ok(Some(name))
 }
 
 /// Parse an IRI to later construct the index path segments that locate its project directory.
 /// The detailed wire mapping is specified in `design/index-protocol.md`;
-/// this function keeps malformed `pkg:sysand/...` IRIs out of the generic
-/// `_iri/<hash>/` bucket so user typos fail loudly.
+/// this function keeps malformed `pkg:sysand/...` and `urn:kpar:...` IRIs
+/// out of the generic `_iri/<hash>/` bucket so user typos fail loudly.
 pub(crate) fn parse_iri(iri: &str) -> Result<ParsedIri, ParseIriError> {
+    if let Some(name) = parse_kpar_urn(iri)? {
+        return Ok(ParsedIri::Kpar {
+            name: name.to_owned(),
+            normalized_iri: normalize_iri(iri)?,
+        });
+    }
     match parse_sysand_purl(iri) {
         Ok(Some((publisher, name))) => Ok(ParsedIri::Sysand {
             publisher: publisher.to_string(),
             name: name.to_string(),
         }),
-        Ok(None) => {
-            let malformed = |source| ParseIriError::MalformedIri {
-                iri: iri.into(),
-                source,
-            };
-            let parsed =
-                fluent_uri::Iri::parse(iri).map_err(|e| malformed(IriNormalizeError::Parse(e)))?;
-            let normalized_iri = canonicalize_iri(parsed).map_err(malformed)?;
-            Ok(ParsedIri::Other { normalized_iri })
-        }
+        Ok(None) => Ok(ParsedIri::Other {
+            normalized_iri: normalize_iri(iri)?,
+        }),
         Err(source) => Err(ParseIriError::MalformedSysandPurl {
             iri: iri.into(),
             source,
@@ -52,33 +83,67 @@ pub(crate) fn parse_iri(iri: &str) -> Result<ParsedIri, ParseIriError> {
     }
 }
 
+/// Canonical form of `iri` hashed for the `_iri` bucket
+fn normalize_iri(iri: &str) -> Result<String, ParseIriError> {
+    let malformed = |source| ParseIriError::MalformedIri {
+        iri: iri.into(),
+        source,
+    };
+    let parsed = fluent_uri::Iri::parse(iri).map_err(|e| malformed(IriNormalizeError::Parse(e)))?;
+    canonicalize_iri(parsed).map_err(malformed)
+}
+
 pub(crate) const IRI_HASH_SEGMENT: &str = "_iri";
+pub(crate) const KPAR_URN_SEGMENT: &str = "_kpar";
 
 #[derive(Debug)]
 pub(crate) enum ParsedIri {
-    Sysand { publisher: String, name: String },
-    Other { normalized_iri: String },
+    Sysand {
+        publisher: String,
+        name: String,
+    },
+    Kpar {
+        name: String,
+        normalized_iri: String,
+    },
+    Other {
+        normalized_iri: String,
+    },
 }
 
 impl ParsedIri {
     pub(crate) fn get_path(&self) -> String {
         match self {
             ParsedIri::Sysand { publisher, name } => format!("{publisher}/{name}"),
-            ParsedIri::Other { normalized_iri } => {
-                format!(
-                    "{IRI_HASH_SEGMENT}/{}",
-                    sha256_lowercase_hex(normalized_iri)
-                )
-            }
+            ParsedIri::Kpar { name, .. } => format!("{KPAR_URN_SEGMENT}/{name}"),
+            ParsedIri::Other { normalized_iri } => hashed_path(normalized_iri),
         }
     }
 
+    /// Path of the project in indexes that predate `_kpar/`, if it differs
+    /// from [`Self::get_path`]
+    pub(crate) fn legacy_path(&self) -> Option<String> {
+        match self {
+            ParsedIri::Kpar { normalized_iri, .. } => Some(hashed_path(normalized_iri)),
+            ParsedIri::Sysand { .. } | ParsedIri::Other { .. } => None,
+        }
+    }
+
+    #[cfg(feature = "filesystem")]
     pub(crate) fn get_iri(&self) -> String {
         match self {
             ParsedIri::Sysand { publisher, name } => {
-                format!("{}{}/{}", PKG_SYSAND_PREFIX, publisher, name)
+                format!("{}{}/{}", crate::purl::PKG_SYSAND_PREFIX, publisher, name)
             }
+            ParsedIri::Kpar { name, .. } => format!("{KPAR_URN_PREFIX}{name}"),
             ParsedIri::Other { normalized_iri } => normalized_iri.clone(),
         }
     }
 }
+
+fn hashed_path(normalized_iri: &str) -> String {
+    format!(
+        "{IRI_HASH_SEGMENT}/{}",
+        sha256_lowercase_hex(normalized_iri)
+    )
+}
@@ -1,10 +1,29 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
 
+#[cfg(feature = "filesystem")]
 pub use crate::commands::index::{
     RemoveTarget, do_index_add, do_index_init, do_index_remove, do_index_yank,
 };
-pub use iri::ParseIriError;
+pub use iri::{KPAR_URN_PREFIX, ParseIriError, parse_kpar_urn};
 
+#[cfg(all(feature = "filesystem", feature = "networking"))]
+pub mod api;
 pub(crate) mod iri;
+#[cfg(feature = "filesystem")]
 pub(crate) mod model;
+
+/// Path of the directory holding `versions.json` and the per-version
+/// directories of `iri`, relative to the index root and without a trailing
+/// slash. Lets clients that do not use [`crate::env::index`] (e.g. the
+/// browser bindings) locate projects in a sysand index.
+pub fn project_path(iri: &str) -> Result<String, ParseIriError> {
+    Ok(iri::parse_iri(iri)?.get_path())
+}
+
+/// Path of the project directory of `iri` in indexes that predate the
+/// `_kpar` layout, if it differs from [`project_path`]. Clients try it when
+/// `versions.json` is not found at [`project_path`]
+pub fn legacy_project_path(iri: &str) -> Result<Option<String>, ParseIriError> {
+    Ok(iri::parse_iri(iri)?.legacy_path())
+}
@@ -51,6 +51,13 @@ pub(crate) enum VersionStatus {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub(crate) struct VersionsJson {
     pub(crate) versions: Vec<VersionEntry>,
+    /// Whether the project as a whole is deprecated (§8). Deprecated
+    /// projects stay resolvable; clients only warn when selecting them.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub(crate) deprecated: bool,
+    /// IRI of the project suggested in place of a deprecated one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) replaced_by: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,13 +18,10 @@ use icu_properties::{
 };
 use idna::punycode;
 
-// TODO: use IRI canonicalization to deduplicate usages:
-// - canonicalize each IRI before trying to resolve
-// - canonicalize each usage before adding
-// - use canonical versions in `sysand-lock.toml` and `env.toml` (for identifiers
-//   and usages)
+// TODO: use canonical versions in `sysand-lock.toml` and `env.toml` (for
+// identifiers and usages)
 
-/// Canonicalize IRI. Currently only used for the `_iri/<hash>` bucket.
+/// Canonicalize IRI. Used for the `_iri/<hash>` bucket and [`normalize_iri`].
 ///
 /// Non-`pkg:sysand` IRIs are located under `_iri/<sha256_hex(normalized_iri)>`,
 /// so two clients pointing at the same project must produce byte-identical
@@ -44,7 +41,6 @@ use idna::punycode;
 /// a `String`, or an error if the host fails IDN conversion; IDN conversion
 /// fails are likely, but not necessarily user error (they are not allowed
 /// by IDNA).
-#[cfg(feature = "filesystem")]
 pub(crate) fn canonicalize_iri(iri: Iri<&str>) -> Result<String, IriNormalizeError> {
     let normalized = iri.normalize();
     let with_idn = punycode_host(&normalized)?;
@@ -110,6 +106,60 @@ pub(crate) fn canonicalize_iri_tolerant(iri: Iri<&str>) -> String {
     final_string
 }
 
+/// Canonical form of `iri`, as produced by the `_iri/<hash>` bucket
+/// canonicalization: lowercase scheme and host, no default port, no dot
+/// segments, normalized percent-encoding, Punycode host and `/` as the
+/// empty `http`/`https` path.
+pub fn normalize_iri(iri: &str) -> Result<String, IriNormalizeError> {
+    canonicalize_iri(Iri::parse(iri).map_err(IriNormalizeError::Parse)?)
+}
+
+/// Like [`normalize_iri`], but returns `iri` as is if it is not a valid IRI,
+/// and leaves hosts that are not valid IDNs as they are
+pub fn normalize_iri_tolerant(iri: &str) -> String {
+    match Iri::parse(iri) {
+        Ok(parsed) => canonicalize_iri_tolerant(parsed),
+        Err(_) => iri.to_owned(),
+    }
+}
+
+/// Key that is equal for two IRIs if and only if they are equivalent, see
+/// [`iris_equivalent`]
+pub fn iri_equivalence_key(iri: &str) -> String {
+    let canonical = normalize_iri_tolerant(iri);
+    let Ok(parsed) = Iri::parse(canonical.as_str()) else {
+        return canonical;
+    };
+    let scheme = parsed.scheme();
+    if scheme != SCHEME_HTTP && scheme != SCHEME_HTTPS {
+        return canonical;
+    }
+    let path = parsed.path().as_str();
+    let path_start = path.as_ptr() as usize - canonical.as_ptr() as usize;
+    let path_end = path_start + path.len();
+    let path = if path.len() > 1 {
+        path.strip_suffix('/').unwrap_or(path)
+    } else {
+        path
+    };
+    // `http` and `https` are the same resource for the purpose of
+    // identifying projects
+    let after_scheme = canonical[..path_start]
+        .split_once(':')
+        .map_or("", |(_, r)| r);
+    format!(
+        "{SCHEME_HTTPS}:{after_scheme}{path}{}",
+        &canonical[path_end..]
+    )
+}
+
+/// Whether `a` and `b` identify the same project: they are equal after
+/// [`normalize_iri_tolerant`], except that `http` and `https` and a
+/// trailing `/` of the path are not distinguished
+pub fn iris_equivalent(a: &str, b: &str) -> bool {
+    a == b || iri_equivalence_key(a) == iri_equivalence_key(b)
+}
+
 /// Replace a non-ASCII RegName host with its `domainToASCII` (Punycode) form.
 /// IPv4, IPv6 literals, and already-ASCII RegNames pass through untouched.
 /// Returns the resulting serialization as an owned `String`; the rewrite is a
@@ -141,7 +191,6 @@ fn punycode_host(iri: &Iri<String>) -> Result<String, IriNormalizeError> {
 
 #[derive(Debug, thiserror::Error)]
 pub enum IriNormalizeError {
-    #[cfg(feature = "filesystem")]
     #[error("IRI is not a well-formed RFC 3987 IRI: {0}")]
     Parse(fluent_uri::ParseError),
     #[error("host `{host}` is not a valid IDN and cannot be converted to Punycode")]
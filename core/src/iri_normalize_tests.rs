@@ -96,6 +96,38 @@ fn userinfo_and_non_default_port_preserved() {
         "http://User@example.com:8080/path"
     );
 }
+#[test]
+fn equivalent_iris() {
+    for other in [
+        "https://example.com/x",
+        "https://EXAMPLE.com:443/x/",
+        "http://example.com/%78",
+        "http://example.com/a/../x",
+    ] {
+        assert!(iris_equivalent("http://example.com/x", other), "{other}");
+    }
+    assert!(iris_equivalent(
+        "http://example.com",
+        "https://example.com/"
+    ));
+    assert!(!iris_equivalent(
+        "http://example.com/x",
+        "http://example.com/y"
+    ));
+    assert!(!iris_equivalent("urn:kpar:a", "urn:kpar:a/"));
+    assert!(iris_equivalent("not an iri", "not an iri"));
+}
+
+#[test]
+fn normalize_iri_tolerant_keeps_relative_file_iris() {
+    assert_eq!(normalize_iri_tolerant("file:../lib"), "file:../lib");
+    assert_eq!(normalize_iri_tolerant("not an iri"), "not an iri");
+    assert_eq!(
+        normalize_iri("HTTP://Example.com:80/a/./b").unwrap(),
+        "http://example.com/a/b"
+    );
+}
+
 use std::{cmp::min, error::Error};
 
 /// Returns (IRI, expected_normalized)
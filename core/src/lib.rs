@@ -16,18 +16,34 @@ pub mod auth;
 pub mod config;
 pub mod context;
 pub mod env;
+pub mod error_code;
 #[cfg(feature = "filesystem")]
+pub mod import;
 pub mod index;
-mod iri_normalize;
+pub mod iri_normalize;
 pub mod lock;
+pub mod migration;
+pub mod output;
+pub mod parallel;
+pub mod progress;
 pub mod project;
 pub mod purl;
 pub mod resolve;
+#[cfg(feature = "json-schema")]
+pub mod schema;
+#[cfg(all(feature = "filesystem", feature = "networking"))]
+pub mod session;
 pub mod solve;
 pub mod stdlib;
+// Terminal styling shared with the CLI; not part of the stable API
+#[doc(hidden)]
 pub mod style;
 pub mod symbols;
+pub mod timings;
 pub mod utils;
+pub mod vfs;
+#[cfg(feature = "filesystem")]
+pub mod walk;
 
 #[cfg(feature = "filesystem")]
 pub mod workspace;
@@ -35,6 +51,15 @@ pub mod workspace;
 #[cfg(feature = "filesystem")]
 pub mod discover;
 
+/// Version of the public API of `sysand-core`, following semantic versioning
+/// independently of the package version.
+///
+/// The public API is recorded in `core/public-api.txt` and checked in CI by
+/// `core/scripts/public_api.sh`. Removing or changing an item there requires
+/// a breaking bump of this version, adding one a compatible bump. Items
+/// marked `#[doc(hidden)]` are excluded and carry no guarantees.
+pub const API_VERSION: &str = "0.28.0";
+
 #[cfg(not(feature = "std"))]
 compile_error!("`std` feature is currently required to build `sysand`");
 
@@ -7,3 +7,14 @@
 fn placeholder_test() {
     assert_eq!(1, 1);
 }
+
+#[test]
+fn api_version_matches_snapshot() {
+    let version = semver::Version::parse(crate::API_VERSION).unwrap();
+    let header = include_str!("../public-api.txt").lines().next().unwrap();
+    assert_eq!(
+        header,
+        format!("# sysand-core API_VERSION {version}"),
+        "`core/public-api.txt` is stale, run `core/scripts/public_api.sh`"
+    );
+}
@@ -12,6 +12,7 @@ use std::{
 };
 
 use fluent_uri::Iri;
+use indexmap::{IndexMap, IndexSet};
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -19,24 +20,37 @@ use toml_edit::{ArrayOfTables, DocumentMut, Formatted, InlineTable, Item, Table,
 use typed_path::Utf8UnixPathBuf;
 
 use crate::{
+    commands::lock::{DEFAULT_LOCKFILE_NAME, JSON_LOCKFILE_NAME},
     config::OverrideSource,
     env::ReadEnvironment,
+    iri_normalize::{iri_equivalence_key, iris_equivalent},
+    migration::{Format, Migration, MigrationError, VersionStatus},
     project::{
         ProjectChecksum,
         utils::{deserialize_unix_path, serialize_unix_path},
     },
-    utils::{RelativePathKind, RelativeUnixPathError, multiline_array, parse_relative_unix_path},
+    solve::markers::Markers,
+    utils::{
+        RelativePathKind, RelativeUnixPathError, multiline_array, parse_relative_unix_path,
+        sha256_lowercase_hex,
+    },
 };
 
 pub const LOCKFILE_PREFIX: &str = "# This file is automatically generated by Sysand and is not intended to be edited manually.\n\n";
-pub const CURRENT_LOCK_VERSION: &str = "0.5";
-pub const SUPPORTED_LOCK_VERSIONS: &[&str] = &[CURRENT_LOCK_VERSION];
+pub const CURRENT_LOCK_VERSION: &str = "0.6";
+/// Versions that can be read without migrating. Version 0.5 lacks only
+/// the optional `rev` of git sources
+pub const SUPPORTED_LOCK_VERSIONS: &[&str] = &["0.5", CURRENT_LOCK_VERSION];
 
-pub const LOCKFILE_ENTRIES: &[&str] = &["lock_version", "project"];
+pub const LOCKFILE_ENTRIES: &[&str] = &["lock_version", "markers", "project"];
 
 #[derive(Debug, Deserialize, PartialEq)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Lock {
     pub lock_version: String,
+    /// Markers that conditional usages were evaluated against
+    #[serde(skip_serializing_if = "Markers::is_empty", default)]
+    pub markers: Markers,
     #[serde(rename = "project", skip_serializing_if = "Vec::is_empty", default)]
     pub projects: Vec<Project>,
 }
@@ -45,97 +59,244 @@ impl Default for Lock {
     fn default() -> Self {
         Lock {
             lock_version: CURRENT_LOCK_VERSION.to_string(),
+            markers: Markers::new(),
             projects: vec![],
         }
     }
 }
 
+/// Lockfile format. Lockfiles older than version 0.5 cannot be migrated,
+/// since their checksums are computed differently and have to be
+/// recomputed from the locked projects
+pub const LOCK_FORMAT: Format = Format {
+    name: "lockfile",
+    version_key: "lock_version",
+    current: CURRENT_LOCK_VERSION,
+    unversioned: None,
+    migrations: &[Migration {
+        from: "0.5",
+        to: "0.6",
+        description: "git sources record the locked commit in `rev`",
+        apply: drop_lock_hash,
+    }],
+};
+
+/// Remove the hash from the header of a TOML lockfile, since it does not
+/// match the migrated contents. The next lock operation writes it again
+fn drop_lock_hash(document: &mut DocumentMut) -> Result<(), MigrationError> {
+    let hash_comment = format!("# {LOCK_HASH_KEY}: ");
+    let strip = |decor: &mut toml_edit::Decor| {
+        if let Some(prefix) = decor.prefix().and_then(|p| p.as_str()) {
+            let prefix: String = prefix
+                .split_inclusive('\n')
+                .filter(|l| !l.starts_with(&hash_comment))
+                .collect();
+            decor.set_prefix(prefix);
+        }
+    };
+    // The header is attached to the first key
+    strip(document.decor_mut());
+    if let Some(mut key) = document.key_mut("lock_version") {
+        strip(key.leaf_decor_mut());
+    }
+    Ok(())
+}
+
 #[derive(Debug, Error)]
 pub enum VersionError {
     #[error("lockfile version `{0}` is not supported; regenerate it with a lock operation")]
     Unsupported(String),
+    #[error("lockfile version `{0}` is outdated; run `sysand migrate` to update it")]
+    Outdated(String),
+    #[error(
+        "lockfile version `{0}` is newer than the supported version `{CURRENT_LOCK_VERSION}`;\n\
+        upgrade sysand to use it"
+    )]
+    Newer(String),
     #[error("lockfile version is missing")]
     Missing,
 }
 
 fn check_lock_version(document: &DocumentMut) -> Result<(), VersionError> {
-    if let Some(Item::Value(Value::String(lock_version))) = document.get("lock_version") {
-        let version = lock_version.value();
-        if SUPPORTED_LOCK_VERSIONS.contains(&version.as_str()) {
-            Ok(())
+    match document.get("lock_version") {
+        Some(Item::Value(Value::String(lock_version))) => check_version(lock_version.value()),
+        _ => Err(VersionError::Missing),
+    }
+}
+
+fn check_version(version: &str) -> Result<(), VersionError> {
+    if SUPPORTED_LOCK_VERSIONS.contains(&version) {
+        return Ok(());
+    }
+    match LOCK_FORMAT.status(version) {
+        VersionStatus::Current => Ok(()),
+        VersionStatus::Outdated => Err(VersionError::Outdated(version.to_owned())),
+        VersionStatus::Newer => Err(VersionError::Newer(version.to_owned())),
+        VersionStatus::Unsupported => Err(VersionError::Unsupported(version.to_owned())),
+    }
+}
+
+/// Serialization format of a lockfile. Both formats hold the same data and
+/// are read by all commands; the format of written lockfiles is set by
+/// `[lock] format` in the configuration
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum LockFormat {
+    #[default]
+    Toml,
+    Json,
+}
+
+impl LockFormat {
+    /// Name of lockfiles in this format
+    pub fn file_name(self) -> &'static str {
+        match self {
+            LockFormat::Toml => DEFAULT_LOCKFILE_NAME,
+            LockFormat::Json => JSON_LOCKFILE_NAME,
+        }
+    }
+
+    /// Format of the lockfile with `file_name`, if it is a lockfile
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        [LockFormat::Toml, LockFormat::Json]
+            .into_iter()
+            .find(|f| f.file_name() == file_name)
+    }
+
+    /// Format of lockfile contents `s`. JSON lockfiles start with `{`
+    pub fn of_contents(s: &str) -> Self {
+        if s.trim_start().starts_with('{') {
+            LockFormat::Json
         } else {
-            Err(VersionError::Unsupported(version.clone()))
+            LockFormat::Toml
         }
-    } else {
-        Err(VersionError::Missing)
     }
 }
 
+/// Key of the lockfile hash, a TOML comment in the header of TOML
+/// lockfiles and the first field of JSON lockfiles
+pub const LOCK_HASH_KEY: &str = "lock_hash";
+const LOCK_HASH_PREFIX: &str = "sha256:";
+
 #[derive(Debug, Error)]
 pub enum ParseError {
     #[error("failed to parse lockfile: {0}")]
     Toml(#[from] toml::de::Error),
     #[error("failed to parse lockfile: {0}")]
     TomlEdit(#[from] toml_edit::TomlError),
+    #[error("failed to parse lockfile: {0}")]
+    Json(#[from] serde_json::Error),
     #[error(transparent)]
     Validation(#[from] ValidationError),
     #[error(transparent)]
     Version(#[from] VersionError),
+    #[error(
+        "lockfile hash `{recorded}` does not match its contents (`{actual}`),\n\
+        it may have been edited manually; regenerate it with a lock operation"
+    )]
+    HashMismatch { recorded: String, actual: String },
 }
 
+/// Parses lockfiles in both [`LockFormat`]s. JSON lockfiles are recognized
+/// by starting with `{`. If the lockfile has a hash, it must match the
+/// contents
 impl FromStr for Lock {
     type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let document = DocumentMut::from_str(s)?;
-        check_lock_version(&document)?;
-
-        for (field, _) in document.iter() {
-            if !LOCKFILE_ENTRIES.contains(&field) {
-                log::warn!("unknown field `{field}` in lockfile");
+        let (lock, recorded_hash) = match LockFormat::of_contents(s) {
+            LockFormat::Json => parse_json(s)?,
+            LockFormat::Toml => parse_toml(s)?,
+        };
+        lock.validate()?;
+        if let Some(recorded) = recorded_hash {
+            let actual = format!("{LOCK_HASH_PREFIX}{}", lock.canonical_hash());
+            if recorded != actual {
+                return Err(ParseError::HashMismatch { recorded, actual });
             }
         }
-        for (field, value, name) in document
-            .get("project")
-            .and_then(Item::as_array_of_tables)
-            .into_iter()
-            .flat_map(|projects| projects.iter())
-            .flat_map(|project| {
-                project
-                    .into_iter()
-                    .map(|(field, value)| (field, value, project.get("name")))
-            })
-        {
-            if !PROJECT_ENTRIES.contains(&field) {
-                log::warn!(
-                    "unknown field `{}` in {} in lockfile",
-                    field,
-                    project_with(name)
-                );
-            } else if field == "sources" {
-                for (field, _) in value
-                    .as_array()
-                    .into_iter()
-                    .flat_map(|sources| sources.iter())
-                    .map(|source| source.as_inline_table())
-                    .flat_map(|s| s.into_iter())
-                    .flat_map(|s| s.into_iter())
-                {
-                    if !SOURCE_ENTRIES.contains(&field) {
-                        log::warn!(
-                            "unknown field `{}` in source for {} in lockfile",
-                            field,
-                            project_with(name)
-                        );
-                    }
+        Ok(lock)
+    }
+}
+
+fn parse_toml(s: &str) -> Result<(Lock, Option<String>), ParseError> {
+    let document = DocumentMut::from_str(s)?;
+    check_lock_version(&document)?;
+
+    for (field, _) in document.iter() {
+        if !LOCKFILE_ENTRIES.contains(&field) {
+            log::warn!("unknown field `{field}` in lockfile");
+        }
+    }
+    for (field, value, name) in document
+        .get("project")
+        .and_then(Item::as_array_of_tables)
+        .into_iter()
+        .flat_map(|projects| projects.iter())
+        .flat_map(|project| {
+            project
+                .into_iter()
+                .map(|(field, value)| (field, value, project.get("name")))
+        })
+    {
+        if !PROJECT_ENTRIES.contains(&field) {
+            log::warn!(
+                "unknown field `{}` in {} in lockfile",
+                field,
+                project_with(name)
+            );
+        } else if field == "sources" {
+            for (field, _) in value
+                .as_array()
+                .into_iter()
+                .flat_map(|sources| sources.iter())
+                .map(|source| source.as_inline_table())
+                .flat_map(|s| s.into_iter())
+                .flat_map(|s| s.into_iter())
+            {
+                if !SOURCE_ENTRIES.contains(&field) {
+                    log::warn!(
+                        "unknown field `{}` in source for {} in lockfile",
+                        field,
+                        project_with(name)
+                    );
                 }
             }
         }
+    }
 
-        // TODO: find a way to not reparse
-        let lock: Lock = toml::from_str(s)?;
-        lock.validate()?;
-        Ok(lock)
+    // The hash is a comment in the header, as it is not part of the data
+    let hash_comment = format!("# {LOCK_HASH_KEY}: ");
+    let recorded_hash = s
+        .lines()
+        .take_while(|l| l.starts_with('#') || l.trim().is_empty())
+        .find_map(|l| l.strip_prefix(&hash_comment))
+        .map(|h| h.trim().to_owned());
+
+    // TODO: find a way to not reparse
+    let lock: Lock = toml::from_str(s)?;
+    Ok((lock, recorded_hash))
+}
+
+fn parse_json(s: &str) -> Result<(Lock, Option<String>), ParseError> {
+    let mut object: serde_json::Map<String, serde_json::Value> = serde_json::from_str(s)?;
+    match object.get("lock_version") {
+        Some(serde_json::Value::String(version)) => check_version(version)?,
+        _ => return Err(VersionError::Missing.into()),
+    }
+    let recorded_hash = match object.shift_remove(LOCK_HASH_KEY) {
+        Some(serde_json::Value::String(hash)) => Some(hash),
+        // Reported as a mismatch
+        Some(other) => Some(other.to_string()),
+        None => None,
+    };
+    for field in object.keys() {
+        if !LOCKFILE_ENTRIES.contains(&field.as_str()) {
+            log::warn!("unknown field `{field}` in lockfile");
+        }
     }
+    let lock: Lock = serde_json::from_value(serde_json::Value::Object(object))?;
+    Ok((lock, recorded_hash))
 }
 
 fn project_with<D: Display>(name: Option<D>) -> String {
@@ -146,6 +307,8 @@ fn project_with<D: Display>(name: Option<D>) -> String {
     }
 }
 
+/// Canonical TOML form of the lock, without a hash. See
+/// [`Lock::serialize`] for the contents of lockfiles
 impl Display for Lock {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.to_toml())
@@ -189,6 +352,8 @@ pub enum ValidationError {
         name: String,
         source: fluent_uri::ParseError,
     },
+    #[error("invalid git revision `{rev}` for project `{name}` in lockfile, expected a commit id")]
+    InvalidGitRev { rev: String, name: String },
 }
 
 pub type ProjectResolution<Env> = (
@@ -205,10 +370,129 @@ impl Lock {
         Ok(())
     }
 
+    /// Projects that use the project identified by `iri`
+    pub fn dependents(&self, iri: &str) -> Vec<&Project> {
+        let Some(project) = self
+            .projects
+            .iter()
+            .find(|p| p.identifiers.iter().any(|i| iris_equivalent(i, iri)))
+        else {
+            return vec![];
+        };
+        self.projects
+            .iter()
+            .filter(|p| {
+                p.usages
+                    .iter()
+                    .any(|u| project.identifiers.iter().any(|i| iris_equivalent(i, u)))
+            })
+            .collect()
+    }
+
+    /// Three-way merge of locks `ours` and `theirs` changed from `base`.
+    /// Projects are matched by their first identifier, as in validation.
+    /// Projects changed on only one side take that change, while those
+    /// changed differently on both sides are conflicts, which must be
+    /// resolved with [`LockMerge::resolve`]
+    pub fn merge(base: Lock, ours: Lock, theirs: Lock) -> LockMerge {
+        let [base, ours, theirs] = [base, ours, theirs].map(|lock| {
+            let lock = lock.canonicalize();
+            let projects: IndexMap<_, _> = lock
+                .projects
+                .into_iter()
+                .map(|p| (merge_key(&p), p))
+                .collect();
+            (lock.markers, projects)
+        });
+        let keys: IndexSet<_> = ours.1.keys().chain(theirs.1.keys()).cloned().collect();
+
+        let mut projects = vec![];
+        let mut conflicts = vec![];
+        for key in keys {
+            let [b, o, t] = [&base, &ours, &theirs].map(|(_, projects)| projects.get(&key));
+            match merge_entry(b, o, t) {
+                Some(project) => projects.extend(project.cloned()),
+                None => conflicts.push(key),
+            }
+        }
+        let markers = merge_entry(Some(&base.0), Some(&ours.0), Some(&theirs.0)).flatten();
+        LockMerge {
+            conflicts: conflicts
+                .iter()
+                .map(|key| {
+                    ours.1
+                        .get(key)
+                        .or_else(|| theirs.1.get(key))
+                        .map(|p| p.name.clone())
+                        .unwrap_or_default()
+                })
+                .collect(),
+            markers_conflict: markers.is_none(),
+            lock: Lock {
+                lock_version: CURRENT_LOCK_VERSION.to_owned(),
+                markers: markers.cloned().unwrap_or_default(),
+                projects,
+            },
+            conflict_keys: conflicts,
+        }
+    }
+
+    /// SHA256 digest of the canonical TOML form of the lock. It is the same
+    /// for both [`LockFormat`]s
+    pub fn canonical_hash(&self) -> String {
+        sha256_lowercase_hex(self.to_string())
+    }
+
+    /// Contents of a lockfile in `format`, including the hash of the lock
+    pub fn serialize(&self, format: LockFormat) -> String {
+        let hash = format!("{LOCK_HASH_PREFIX}{}", self.canonical_hash());
+        match format {
+            LockFormat::Toml => {
+                let mut doc = self.to_toml();
+                doc.decor_mut()
+                    .set_prefix(format!("{LOCKFILE_PREFIX}# {LOCK_HASH_KEY}: {hash}\n\n"));
+                doc.to_string()
+            }
+            LockFormat::Json => {
+                let mut object = serde_json::Map::new();
+                object.insert(LOCK_HASH_KEY.to_owned(), hash.into());
+                object.insert("lock_version".to_owned(), self.lock_version.clone().into());
+                if !self.markers.is_empty() {
+                    object.insert(
+                        "markers".to_owned(),
+                        serde_json::to_value(&self.markers)
+                            .expect("lockfile markers are always serializable"),
+                    );
+                }
+                if !self.projects.is_empty() {
+                    object.insert(
+                        "project".to_owned(),
+                        serde_json::to_value(&self.projects)
+                            .expect("lockfile projects are always serializable"),
+                    );
+                }
+                let mut json =
+                    serde_json::to_string_pretty(&object).expect("lockfile is always serializable");
+                json.push('\n');
+                json
+            }
+        }
+    }
+
     fn to_toml(&self) -> DocumentMut {
         let mut doc = DocumentMut::new();
         doc.decor_mut().set_prefix(LOCKFILE_PREFIX);
         doc.insert("lock_version", value(Value::from(&self.lock_version)));
+        if !self.markers.is_empty() {
+            doc.insert(
+                "markers",
+                value(InlineTable::from_iter(
+                    self.markers
+                        .iter()
+                        .map(|(k, v)| (k.as_str(), Value::from(v))),
+                )),
+            );
+        }
 
         let mut projects = ArrayOfTables::new();
         for project in &self.projects {
@@ -233,7 +517,11 @@ impl Lock {
         let mut seen_projects = HashSet::new();
         let mut seen_exports = HashSet::new();
         for project in &self.projects {
-            if !seen_projects.insert(project.identifiers.first()) {
+            let key = project
+                .identifiers
+                .first()
+                .map(|id| iri_equivalence_key(id));
+            if !seen_projects.insert(key) {
                 let id = match project.identifiers.first() {
                     Some(id) => id,
                     None => {
@@ -265,12 +553,12 @@ impl Lock {
                 );
             });
             for iri in &project.identifiers {
-                iri_versions.insert(iri.clone());
+                iri_versions.insert(iri_equivalence_key(iri));
             }
         }
         for project in &self.projects {
             for usage in &project.usages {
-                if !iri_versions.contains(usage.inner()) {
+                if !iri_versions.contains(&iri_equivalence_key(usage.inner())) {
                     return Err(ValidationError::UnsatisfiedUsage {
                         usage: usage.inner().to_owned(),
                         name: project.name.clone(),
@@ -316,7 +604,9 @@ impl Lock {
                     | Source::RemoteSrc {
                         remote_src: url, ..
                     }
-                    | Source::RemoteGit { remote_git: url } => {
+                    | Source::RemoteGit {
+                        remote_git: url, ..
+                    } => {
                         if let Err(e) = Iri::parse(url.as_str()) {
                             return Err(ValidationError::InvalidSourceUrl {
                                 name: project.name.clone(),
@@ -325,6 +615,16 @@ impl Lock {
                         }
                     }
                 }
+                // Full SHA-1 or SHA-256 commit id
+                if let Source::RemoteGit { rev: Some(rev), .. } = source
+                    && (!matches!(rev.len(), 40 | 64)
+                        || !rev.bytes().all(|c| matches!(c, b'0'..=b'9' | b'a'..=b'f')))
+                {
+                    return Err(ValidationError::InvalidGitRev {
+                        rev: rev.clone(),
+                        name: project.name.clone(),
+                    });
+                }
 
                 // Digest composition and length
                 let (c, kind) = match source {
@@ -333,7 +633,7 @@ impl Lock {
                     Source::LocalKpar { kpar_digest: c, .. }
                     | Source::RemoteKpar { kpar_digest: c, .. }
                     | Source::IndexKpar { kpar_digest: c, .. } => (c, "kpar"),
-                    Source::RemoteGit { remote_git: _ } | Source::Editable { editable: _ } => {
+                    Source::RemoteGit { .. } | Source::Editable { editable: _ } => {
                         continue;
                     }
                 };
@@ -385,13 +685,75 @@ impl Lock {
                     | Source::RemoteKpar { kpar_digest: c, .. }
                     | Source::IndexKpar { kpar_digest: c, .. }
                     | Source::RemoteSrc { checksum: c, .. } => c.make_ascii_lowercase(),
-                    Source::RemoteGit { remote_git: _ } | Source::Editable { editable: _ } => (),
+                    Source::RemoteGit { .. } | Source::Editable { editable: _ } => (),
                 }
             }
         }
     }
 }
 
+/// Identity of a project when merging, its first identifier
+fn merge_key(project: &Project) -> Option<String> {
+    project
+        .identifiers
+        .first()
+        .map(|id| iri_equivalence_key(id))
+}
+
+/// Merged entry, or `None` if both sides changed it differently. The
+/// inner `None` is a removed entry
+fn merge_entry<'a, T: PartialEq>(
+    base: Option<&'a T>,
+    ours: Option<&'a T>,
+    theirs: Option<&'a T>,
+) -> Option<Option<&'a T>> {
+    if ours == theirs || theirs == base {
+        Some(ours)
+    } else if ours == base {
+        Some(theirs)
+    } else {
+        None
+    }
+}
+
+/// Result of [`Lock::merge`]
+#[derive(Debug)]
+pub struct LockMerge {
+    /// Merged lock without the conflicting projects
+    pub lock: Lock,
+    /// Names of the conflicting projects
+    pub conflicts: Vec<String>,
+    /// Whether the markers were changed differently on both sides
+    pub markers_conflict: bool,
+    conflict_keys: Vec<Option<String>>,
+}
+
+impl LockMerge {
+    /// Whether the merge needs no resolution
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty() && !self.markers_conflict
+    }
+
+    /// Take the conflicting entries from `resolved`, a lock of the merged
+    /// project, together with the projects it adds. Entries merged cleanly
+    /// are kept as they are
+    pub fn resolve(self, resolved: Lock) -> Result<Lock, ValidationError> {
+        let mut lock = self.lock;
+        if self.markers_conflict {
+            lock.markers = resolved.markers;
+        }
+        let merged: HashSet<_> = lock.projects.iter().map(merge_key).collect();
+        for project in resolved.projects {
+            let key = merge_key(&project);
+            if self.conflict_keys.contains(&key) || !merged.contains(&key) {
+                lock.projects.push(project);
+            }
+        }
+        lock.validate()?;
+        Ok(lock.canonicalize())
+    }
+}
+
 pub const PROJECT_ENTRIES: &[&str] = &[
     "name",
     "publisher",
@@ -399,11 +761,13 @@ pub const PROJECT_ENTRIES: &[&str] = &[
     "exports",
     "identifiers",
     "usages",
+    "variant",
     "sources",
 ];
 
 /// Fields that might not be set for every project are `Option`
-#[derive(Clone, Eq, Debug, Deserialize, PartialEq)]
+#[derive(Clone, Eq, Debug, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Project {
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub publisher: Option<String>,
@@ -415,6 +779,9 @@ pub struct Project {
     pub identifiers: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub usages: Vec<Usage>,
+    /// Variant of the project to install, see `variants` in `sysand.toml`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub variant: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub sources: Vec<Source>,
 }
@@ -437,9 +804,12 @@ impl PartialOrd for Project {
     }
 }
 
+// Only used to detect symbol collisions; not part of the stable API
+#[doc(hidden)]
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub struct StrHash(u64);
 
+#[doc(hidden)]
 pub fn hash_str(val: &str) -> StrHash {
     let mut hasher = DefaultHasher::new();
     val.hash(&mut hasher);
@@ -466,6 +836,9 @@ impl Project {
         if !usages.is_empty() {
             table.insert("usages", value(usages));
         }
+        if let Some(variant) = &self.variant {
+            table.insert("variant", value(variant));
+        }
         let sources = multiline_array(self.sources.iter().map(|s| s.to_toml()));
         if !sources.is_empty() {
             table.insert("sources", value(sources));
@@ -487,11 +860,13 @@ const SOURCE_ENTRIES: &[&str] = &[
     "kpar_size",
     "kpar_digest",
     "remote_git",
+    "rev",
 ];
 
 // TODO: turn this into a `SourceG` that specializes to `SourceRaw`
 // and `Source`; `SourceRaw` is used only for serde
 #[derive(Clone, Eq, Debug, Deserialize, Ord, PartialEq, PartialOrd, Serialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Source {
     // Unix path resolved relative to workspace root (`..` is allowed)
@@ -500,6 +875,7 @@ pub enum Source {
             deserialize_with = "deserialize_unix_path",
             serialize_with = "serialize_unix_path"
         )]
+        #[cfg_attr(feature = "json-schema", schemars(with = "String"))]
         editable: Utf8UnixPathBuf,
     },
     // Unix path resolved relative to workspace root (`..` is allowed)
@@ -508,6 +884,7 @@ pub enum Source {
             deserialize_with = "deserialize_unix_path",
             serialize_with = "serialize_unix_path"
         )]
+        #[cfg_attr(feature = "json-schema", schemars(with = "String"))]
         src_path: Utf8UnixPathBuf,
         checksum: String,
     },
@@ -517,6 +894,7 @@ pub enum Source {
             deserialize_with = "deserialize_unix_path",
             serialize_with = "serialize_unix_path"
         )]
+        #[cfg_attr(feature = "json-schema", schemars(with = "String"))]
         kpar_path: Utf8UnixPathBuf,
         kpar_size: NonZeroU64,
         kpar_digest: String,
@@ -537,6 +915,10 @@ pub enum Source {
     },
     RemoteGit {
         remote_git: String,
+        /// Commit the project was locked at. Without it, the default
+        /// branch is installed
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rev: Option<String>,
     },
 }
 
@@ -565,8 +947,11 @@ impl Source {
                 table.insert("src_path", Value::from(src_path.as_str()));
                 table.insert("checksum", Value::from(checksum));
             }
-            Source::RemoteGit { remote_git } => {
+            Source::RemoteGit { remote_git, rev } => {
                 table.insert("remote_git", Value::from(remote_git));
+                if let Some(rev) = rev {
+                    table.insert("rev", Value::from(rev));
+                }
             }
             Source::RemoteKpar {
                 remote_kpar,
@@ -641,7 +1026,7 @@ impl Source {
             } => OverrideSource::RemoteSrc {
                 remote_src: remote_src.to_owned(),
             },
-            Source::RemoteGit { remote_git } => OverrideSource::RemoteGit {
+            Source::RemoteGit { remote_git, .. } => OverrideSource::RemoteGit {
                 remote_git: remote_git.to_owned(),
             },
         }
@@ -649,7 +1034,7 @@ impl Source {
 
     pub fn to_checksum(&self) -> Option<ProjectChecksum> {
         match self {
-            Source::Editable { editable: _ } | Source::RemoteGit { remote_git: _ } => None,
+            Source::Editable { editable: _ } | Source::RemoteGit { .. } => None,
             Source::RemoteSrc {
                 remote_src: _,
                 checksum,
@@ -678,6 +1063,7 @@ impl Source {
 }
 
 #[derive(Clone, Eq, Debug, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 pub struct Usage(String);
 
 impl Deref for Usage {
@@ -724,6 +1110,15 @@ impl<'de> Deserialize<'de> for Usage {
     }
 }
 
+impl Serialize for Usage {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
 // impl From<InterchangeProjectUsageRaw> for Usage {
 //     fn from(value: InterchangeProjectUsageRaw) -> Usage {
 //         Usage {
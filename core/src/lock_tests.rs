@@ -10,10 +10,11 @@ use typed_path::Utf8UnixPathBuf;
 use crate::utils::format_err;
 use crate::{
     lock::{
-        CURRENT_LOCK_VERSION, LOCKFILE_PREFIX, Lock, Project, Source, Usage, ValidationError,
-        VersionError, check_lock_version,
+        CURRENT_LOCK_VERSION, LOCK_FORMAT, LOCKFILE_PREFIX, Lock, LockFormat, ParseError, Project,
+        Source, Usage, ValidationError, VersionError, check_lock_version,
     },
     project::ProjectChecksum,
+    solve::markers::Markers,
 };
 
 #[test]
@@ -42,6 +43,22 @@ fn check_unsupported_lock_version() {
     );
 }
 
+#[test]
+fn check_newer_lock_version() {
+    let document = DocumentMut::from_str(r#"lock_version = "1.0""#).unwrap();
+    let Err(err) = check_lock_version(&document) else {
+        panic!()
+    };
+    assert_matches!(err, VersionError::Newer(ref s) if s == "1.0");
+    assert_eq!(
+        format_err(err),
+        format!(
+            "lockfile version `1.0` is newer than the supported version `{CURRENT_LOCK_VERSION}`;\n\
+            upgrade sysand to use it"
+        )
+    );
+}
+
 #[test]
 fn old_registry_lockfile_is_rejected_by_version_gate() {
     let lockfile = format!(
@@ -99,6 +116,7 @@ fn check_missing_lock_version() {
 fn to_toml_matches_expected<D: Display>(projects: Vec<Project>, toml: D) {
     let lock = Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects,
     };
     let expected = format!(
@@ -119,6 +137,7 @@ fn minimal_to_toml() {
             identifiers: vec![],
             usages: vec![],
             sources: vec![],
+            variant: None,
         }],
         r#"
 [[project]]
@@ -140,6 +159,7 @@ fn many_projects_to_toml() {
                 identifiers: vec![],
                 usages: vec![],
                 sources: vec![],
+                variant: None,
             },
             Project {
                 name: "Two".to_string(),
@@ -149,6 +169,7 @@ fn many_projects_to_toml() {
                 identifiers: vec![],
                 usages: vec![],
                 sources: vec![],
+                variant: None,
             },
             Project {
                 name: "Three".to_string(),
@@ -158,6 +179,7 @@ fn many_projects_to_toml() {
                 identifiers: vec![],
                 usages: vec![],
                 sources: vec![],
+                variant: None,
             },
         ],
         r#"
@@ -189,6 +211,7 @@ fn one_export_to_toml() {
             identifiers: vec![],
             usages: vec![],
             sources: vec![],
+            variant: None,
         }],
         r#"
 [[project]]
@@ -216,6 +239,7 @@ fn many_exports_to_toml() {
             identifiers: vec![],
             usages: vec![],
             sources: vec![],
+            variant: None,
         }],
         r#"
 [[project]]
@@ -241,6 +265,7 @@ fn one_iri_to_toml() {
             identifiers: vec!["urn:kpar:example".to_string()],
             usages: vec![],
             sources: vec![],
+            variant: None,
         }],
         r#"
 [[project]]
@@ -268,6 +293,7 @@ fn many_identifiers_to_toml() {
             ],
             usages: vec![],
             sources: vec![],
+            variant: None,
         }],
         r#"
 [[project]]
@@ -295,6 +321,7 @@ fn one_source_to_toml() {
             sources: vec![Source::Editable {
                 editable: Utf8UnixPathBuf::from("."),
             }],
+            variant: None,
         }],
         r#"
 [[project]]
@@ -351,8 +378,10 @@ fn many_sources_to_toml() {
                 },
                 Source::RemoteGit {
                     remote_git: "github.com/example/remote.git".to_string(),
+                    rev: None,
                 },
             ],
+            variant: None,
         }],
         r#"
 [[project]]
@@ -382,6 +411,7 @@ fn one_usage_to_toml() {
             identifiers: vec![],
             usages: vec![Usage::from("urn:kpar:usage".to_string())],
             sources: vec![],
+            variant: None,
         }],
         r#"
 [[project]]
@@ -409,6 +439,7 @@ fn many_usage_to_toml() {
                 Usage::from("urn:kpar:third".to_string()),
             ],
             sources: vec![],
+            variant: None,
         }],
         r#"
 [[project]]
@@ -502,6 +533,7 @@ fn make_project<N: AsRef<str>, S: AsRef<str>>(
         identifiers: identifiers.iter().map(|s| String::from(*s)).collect(),
         usages: usages.to_vec(),
         sources: vec![],
+        variant: None,
     }
 }
 
@@ -509,6 +541,7 @@ fn make_project<N: AsRef<str>, S: AsRef<str>>(
 fn validate_empty() {
     Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![],
     }
     .validate()
@@ -519,6 +552,7 @@ fn validate_empty() {
 fn validate_minimal() {
     Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![make_project("a", None, "0.0.1", &[], &[], &[])],
     }
     .validate()
@@ -530,6 +564,7 @@ fn validate_single_usage() {
     let iri = "urn:kpar:test";
     Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![
             make_project(
                 "a",
@@ -552,6 +587,7 @@ fn validate_multiple_usage() {
     let iri2 = "urn:kpar:test2";
     Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![
             make_project(
                 "a",
@@ -575,6 +611,7 @@ fn validate_chained_usages() {
     let iri2 = "urn:kpar:test2";
     Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![
             make_project(
                 "a",
@@ -604,6 +641,7 @@ fn validate_unsupported_lock_version() {
     let version = "X";
     let Err(err) = Lock {
         lock_version: version.to_owned(),
+        markers: Markers::new(),
         projects: vec![],
     }
     .validate() else {
@@ -625,6 +663,7 @@ fn validate_single_name_collision() {
     let iri = "urn:kpar:test";
     let Err(err) = Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![
             make_project(
                 "a",
@@ -655,6 +694,7 @@ fn validate_multiple_name_collision() {
     let iri = "urn:kpar:test";
     let Err(err) = Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![
             make_project(
                 "a",
@@ -680,6 +720,7 @@ fn validate_unsatisfied_usage() {
     let usage_in = Usage::from("urn:kpar:test".to_string());
     let Err(err) = Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![make_project(
             "a",
             None,
@@ -699,6 +740,30 @@ fn validate_unsatisfied_usage() {
     assert_eq!(name, "a");
 }
 
+#[test]
+fn validate_equivalent_iris() {
+    let lock = |other_id: &'static str| Lock {
+        lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
+        projects: vec![
+            make_project(
+                "a",
+                None,
+                "0.0.1",
+                &[],
+                &["http://example.com/a"],
+                &[Usage::from("https://EXAMPLE.com/b/".to_string())],
+            ),
+            make_project("b", None, "0.0.1", &[], &[other_id], &[]),
+        ],
+    };
+    lock("http://example.com/b").validate().unwrap();
+    let Err(ValidationError::ProjectIdCollision(_)) = lock("https://example.com/a/").validate()
+    else {
+        panic!()
+    };
+}
+
 #[test]
 fn validate_checksum_invalid_digest_all_source_types() {
     // 64 chars but contains 'X' — right length, wrong character.
@@ -748,6 +813,7 @@ fn validate_checksum_invalid_digest_all_source_types() {
     for (label, source) in cases {
         let Err(err) = Lock {
             lock_version: CURRENT_LOCK_VERSION.to_string(),
+            markers: Markers::new(),
             projects: vec![Project {
                 name: "a".into(),
                 publisher: None,
@@ -756,6 +822,7 @@ fn validate_checksum_invalid_digest_all_source_types() {
                 identifiers: vec![],
                 usages: vec![],
                 sources: vec![source],
+                variant: None,
             }],
         }
         .validate() else {
@@ -775,6 +842,7 @@ fn validate_kpar_digest_rejects_uppercase() {
     let invalid_digest = "dA8747a6f27A32f10Ba393113bCe29f788181037a71f093f90e0ad5829d2b780";
     let err = Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![Project {
             name: "Indexed".to_string(),
             publisher: None,
@@ -787,6 +855,7 @@ fn validate_kpar_digest_rejects_uppercase() {
                 kpar_size: std::num::NonZeroU64::new(123).unwrap(),
                 kpar_digest: invalid_digest.to_string(),
             }],
+            variant: None,
         }],
     }
     .validate()
@@ -803,6 +872,7 @@ fn validate_kpar_digest_rejects_uppercase() {
 fn sort_empty() {
     let mut lock = Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![],
     };
     lock.sort();
@@ -815,6 +885,7 @@ fn sort_single_trivial() {
     let project = make_project("a", None, "0.0.1", &[], &[], &[]);
     let mut lock = Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![project.clone()],
     };
     lock.sort();
@@ -828,6 +899,7 @@ fn sort_exports() {
     let project2 = make_project("a", None, "0.0.1", &["A", "B"], &[], &[]);
     let mut lock = Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![project1],
     };
     lock.sort();
@@ -841,6 +913,7 @@ fn sort_identifiers() {
     let project2 = make_project("a", None, "0.0.1", &[], &["urn:kpar:a", "urn:kpar:b"], &[]);
     let mut lock = Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![project1],
     };
     lock.sort();
@@ -863,6 +936,7 @@ fn sort_sources() {
     let project2 = make_project("a", None, "0.0.1", &[], &[], &[usage1, usage2]);
     let mut lock = Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![project1],
     };
     lock.sort();
@@ -885,6 +959,7 @@ fn sort_sources_with_constraints() {
     let project2 = make_project("a", None, "0.0.1", &[], &[], &[usage1, usage2]);
     let mut lock = Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![project1],
     };
     lock.sort();
@@ -898,6 +973,7 @@ fn sort_projects_by_name() {
     let project2 = make_project("B", None, "0.0.1", &["A"], &["urn:kpar:a"], &[]);
     let mut lock = Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![project2.clone(), project1.clone()],
     };
     lock.sort();
@@ -911,6 +987,7 @@ fn sort_projects_by_exports() {
     let project2 = make_project("B", None, "0.0.1", &["B"], &["urn:kpar:a"], &[]);
     let mut lock = Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![project2.clone(), project1.clone()],
     };
     lock.sort();
@@ -924,6 +1001,7 @@ fn sort_projects_by_identifiers() {
     let project2 = make_project("B", None, "0.0.1", &["A"], &["urn:kpar:b"], &[]);
     let mut lock = Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![project2.clone(), project1.clone()],
     };
     lock.sort();
@@ -937,6 +1015,7 @@ fn sort_projects_by_version() {
     let project2 = make_project("B", None, "0.0.2", &["A"], &["urn:kpar:a"], &[]);
     let mut lock = Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![project2.clone(), project1.clone()],
     };
     lock.sort();
@@ -951,6 +1030,7 @@ fn canonicalize_checksums() {
 
     let mut lock = Lock {
         lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
         projects: vec![Project {
             name: "a".into(),
             publisher: None,
@@ -987,8 +1067,10 @@ fn canonicalize_checksums() {
                 },
                 Source::RemoteGit {
                     remote_git: "https://github.com/example/example.git".to_string(),
+                    rev: None,
                 },
             ],
+            variant: None,
         }],
     };
     lock.canonicalize_checksums();
@@ -1048,6 +1130,74 @@ version = "1.0.0"
     assert_eq!(s, "0.4");
 }
 
+#[test]
+fn lockfile_version_0_5_is_read() {
+    let lockfile = format!(
+        r#"{LOCKFILE_PREFIX}lock_version = "0.5"
+
+[[project]]
+name = "Git project"
+version = "1.0.0"
+sources = [{{ remote_git = "https://example.com/git.git" }}]
+"#
+    );
+
+    let lock = Lock::from_str(&lockfile).unwrap();
+    assert_eq!(
+        lock.projects[0].sources,
+        [Source::RemoteGit {
+            remote_git: "https://example.com/git.git".into(),
+            rev: None,
+        }]
+    );
+}
+
+#[test]
+fn migrating_lockfile_0_5_drops_hash() {
+    let mut lock = lock_with_all_sources();
+    lock.lock_version = "0.5".into();
+    let serialized = lock.serialize(LockFormat::Toml);
+    assert!(serialized.contains("\n# lock_hash: "));
+
+    let (migrated, migrated_str) = LOCK_FORMAT.migrate_str(&serialized).unwrap().unwrap();
+    assert_eq!(migrated.from, "0.5");
+    assert!(migrated_str.starts_with(LOCKFILE_PREFIX), "{migrated_str}");
+    assert!(!migrated_str.contains("lock_hash"));
+    let parsed = Lock::from_str(&migrated_str).unwrap();
+    assert_eq!(parsed.lock_version, CURRENT_LOCK_VERSION);
+    assert_eq!(parsed.projects, lock.projects);
+}
+
+#[test]
+fn validate_invalid_git_rev() {
+    for rev in [
+        "main",
+        "8A3B3E2C6EE1C4D2E5C2A1F3B0F2D7E1A9C4B6D0",
+        "8a3b3e2",
+    ] {
+        let err = Lock {
+            lock_version: CURRENT_LOCK_VERSION.to_string(),
+            markers: Markers::new(),
+            projects: vec![Project {
+                name: "a".into(),
+                publisher: None,
+                version: "0.0.1".to_string(),
+                exports: vec![],
+                identifiers: vec![],
+                usages: vec![],
+                sources: vec![Source::RemoteGit {
+                    remote_git: "https://example.com/a.git".into(),
+                    rev: Some(rev.into()),
+                }],
+                variant: None,
+            }],
+        }
+        .validate()
+        .unwrap_err();
+        assert_matches!(err, ValidationError::InvalidGitRev { rev: ref r, ref name } if r == rev && name == "a");
+    }
+}
+
 #[test]
 fn source_to_checksum_editable_is_none() {
     let source = Source::Editable {
@@ -1060,6 +1210,7 @@ fn source_to_checksum_editable_is_none() {
 fn source_to_checksum_remote_git_is_none() {
     let source = Source::RemoteGit {
         remote_git: "https://github.com/example/example.git".to_string(),
+        rev: None,
     };
     assert!(source.to_checksum().is_none());
 }
@@ -1122,3 +1273,269 @@ fn source_to_checksum_index_kpar_is_kpar_variant() {
     };
     assert_eq!(source.to_checksum(), Some(ProjectChecksum::Kpar(digest)));
 }
+
+fn lock_with_all_sources() -> Lock {
+    let digest = "a".repeat(64);
+    let mut dependency = make_project(
+        "Dependency",
+        Some("acme".into()),
+        "2.0.0",
+        &["Dep"],
+        &["urn:kpar:dependency"],
+        &[],
+    );
+    dependency.sources = vec![
+        Source::LocalSrc {
+            src_path: Utf8UnixPathBuf::from("../dep"),
+            checksum: digest.clone(),
+        },
+        Source::LocalKpar {
+            kpar_path: Utf8UnixPathBuf::from("dep.kpar"),
+            kpar_size: NonZeroU64::new(10).unwrap(),
+            kpar_digest: digest.clone(),
+        },
+        Source::RemoteKpar {
+            remote_kpar: "https://example.com/dep.kpar".into(),
+            kpar_size: NonZeroU64::new(10).unwrap(),
+            kpar_digest: digest.clone(),
+        },
+        Source::IndexKpar {
+            index_kpar: "https://example.com/index/dep.kpar".into(),
+            kpar_size: NonZeroU64::new(10).unwrap(),
+            kpar_digest: digest.clone(),
+        },
+        Source::RemoteSrc {
+            remote_src: "https://example.com/dep/".into(),
+            checksum: digest,
+        },
+        Source::RemoteGit {
+            remote_git: "https://example.com/dep.git".into(),
+            rev: Some("8a3b3e2c6ee1c4d2e5c2a1f3b0f2d7e1a9c4b6d0".into()),
+        },
+    ];
+    let mut main = make_project(
+        "Main",
+        None,
+        "1.0.0",
+        &["Main"],
+        &["urn:kpar:main"],
+        &[Usage::from("urn:kpar:dependency".to_string())],
+    );
+    main.sources = vec![Source::Editable {
+        editable: Utf8UnixPathBuf::from("."),
+    }];
+    Lock {
+        lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
+        projects: vec![main, dependency],
+    }
+    .canonicalize()
+}
+
+#[test]
+fn serialized_lock_roundtrips_in_both_formats() {
+    let lock = lock_with_all_sources();
+    for format in [LockFormat::Toml, LockFormat::Json] {
+        let serialized = lock.serialize(format);
+        let parsed = Lock::from_str(&serialized)
+            .unwrap_or_else(|e| panic!("{format:?}: {}\n{serialized}", format_err(e)));
+        assert_eq!(parsed, lock, "{format:?}");
+        assert_eq!(parsed.canonical_hash(), lock.canonical_hash());
+    }
+    let toml = lock.serialize(LockFormat::Toml);
+    assert!(toml.starts_with(LOCKFILE_PREFIX));
+    assert!(toml.contains(&format!(
+        "\n# lock_hash: sha256:{}\n",
+        lock.canonical_hash()
+    )));
+    let json: serde_json::Value = serde_json::from_str(&lock.serialize(LockFormat::Json)).unwrap();
+    assert_eq!(
+        json["lock_hash"],
+        format!("sha256:{}", lock.canonical_hash())
+    );
+}
+
+#[test]
+fn markers_roundtrip_in_both_formats() {
+    let mut lock = lock_with_all_sources();
+    lock.markers = [("tool", "syside"), ("variant", "full")]
+        .map(|(k, v)| (k.to_owned(), v.to_owned()))
+        .into();
+    for format in [LockFormat::Toml, LockFormat::Json] {
+        let parsed = Lock::from_str(&lock.serialize(format)).unwrap();
+        assert_eq!(parsed, lock, "{format:?}");
+    }
+    assert!(
+        lock.to_string()
+            .contains("\nmarkers = { tool = \"syside\", variant = \"full\" }\n")
+    );
+    assert_ne!(
+        lock.canonical_hash(),
+        lock_with_all_sources().canonical_hash()
+    );
+}
+
+#[test]
+fn lockfile_without_hash_is_accepted() {
+    let lock = lock_with_all_sources();
+    assert_eq!(Lock::from_str(&lock.to_string()).unwrap(), lock);
+
+    let mut json: serde_json::Value =
+        serde_json::from_str(&lock.serialize(LockFormat::Json)).unwrap();
+    json.as_object_mut().unwrap().shift_remove("lock_hash");
+    assert_eq!(Lock::from_str(&json.to_string()).unwrap(), lock);
+}
+
+#[test]
+fn edited_lockfile_is_rejected() {
+    let lock = lock_with_all_sources();
+    for format in [LockFormat::Toml, LockFormat::Json] {
+        let edited = lock
+            .serialize(format)
+            .replace("https://example.com/dep.git", "https://example.org/dep.git");
+        let err = Lock::from_str(&edited).unwrap_err();
+        assert_matches!(
+            &err,
+            ParseError::HashMismatch { recorded, actual }
+                if recorded == &format!("sha256:{}", lock.canonical_hash()) && actual != recorded,
+            "{format:?}"
+        );
+    }
+}
+
+#[test]
+fn json_lockfile_version_is_checked() {
+    let err = Lock::from_str(r#"{"lock_version": "0.3"}"#).unwrap_err();
+    assert_matches!(err, ParseError::Version(VersionError::Unsupported(ref s)) if s == "0.3");
+    let err = Lock::from_str(r#"{"project": []}"#).unwrap_err();
+    assert_matches!(err, ParseError::Version(VersionError::Missing));
+}
+
+#[test]
+fn lock_format_file_names() {
+    for format in [LockFormat::Toml, LockFormat::Json] {
+        assert_eq!(LockFormat::from_file_name(format.file_name()), Some(format));
+    }
+    assert_eq!(LockFormat::from_file_name("sysand.toml"), None);
+}
+
+#[test]
+fn dependents_of_project() {
+    let project = |name: &str, iri: &str, usages: &[&str]| Project {
+        name: name.to_owned(),
+        publisher: None,
+        version: "1.0.0".to_owned(),
+        exports: vec![],
+        identifiers: vec![iri.to_owned()],
+        usages: usages.iter().map(|u| Usage::from(u.to_string())).collect(),
+        sources: vec![],
+        variant: None,
+    };
+    let lock = Lock {
+        projects: vec![
+            project("app", "urn:kpar:app", &["urn:kpar:lib", "urn:kpar:util"]),
+            project("lib", "urn:kpar:lib", &["urn:kpar:util"]),
+            project("util", "urn:kpar:util", &[]),
+        ],
+        ..Default::default()
+    };
+
+    let names = |iri| -> Vec<&str> {
+        lock.dependents(iri)
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect()
+    };
+    assert_eq!(names("urn:kpar:util"), ["app", "lib"]);
+    assert_eq!(names("urn:kpar:lib"), ["app"]);
+    assert!(names("urn:kpar:app").is_empty());
+    assert!(names("urn:kpar:missing").is_empty());
+}
+
+/// Lock of project `main` using `deps`, given as (name, version)
+fn merge_lock(deps: &[(&'static str, &'static str)]) -> Lock {
+    let mut projects: Vec<_> = deps
+        .iter()
+        .map(|(name, version)| {
+            let iri: &'static str = format!("urn:kpar:{name}").leak();
+            make_project(name, None, version, &[], &[iri], &[])
+        })
+        .collect();
+    let usages: Vec<_> = deps
+        .iter()
+        .map(|(name, _)| Usage::from(format!("urn:kpar:{name}")))
+        .collect();
+    projects.push(make_project(
+        "main",
+        None,
+        "1.0.0",
+        &[],
+        &["urn:kpar:main"],
+        &usages,
+    ));
+    Lock {
+        lock_version: CURRENT_LOCK_VERSION.to_string(),
+        markers: Markers::new(),
+        projects,
+    }
+    .canonicalize()
+}
+
+#[test]
+fn merge_takes_changes_from_both_sides() {
+    let base = merge_lock(&[("a", "1.0.0"), ("b", "1.0.0"), ("c", "1.0.0")]);
+    let ours = merge_lock(&[("a", "1.1.0"), ("b", "1.0.0")]);
+    let theirs = merge_lock(&[("a", "1.0.0"), ("b", "2.0.0"), ("c", "1.0.0")]);
+
+    let merge = Lock::merge(base, ours, theirs);
+
+    assert!(merge.is_clean());
+    let versions: Vec<_> = merge
+        .lock
+        .projects
+        .iter()
+        .map(|p| (p.name.as_str(), p.version.as_str()))
+        .collect();
+    // `c` and its usage by `main` were removed only in `ours`
+    assert_eq!(
+        versions,
+        [("a", "1.1.0"), ("b", "2.0.0"), ("main", "1.0.0")]
+    );
+    assert_eq!(merge.lock.projects[2].usages.len(), 2);
+}
+
+#[test]
+fn merge_conflicts_are_resolved() {
+    let base = merge_lock(&[("a", "1.0.0"), ("b", "1.0.0")]);
+    let ours = merge_lock(&[("a", "1.1.0"), ("b", "1.0.0")]);
+    let theirs = merge_lock(&[("a", "1.2.0"), ("b", "1.0.0")]);
+
+    let merge = Lock::merge(base, ours, theirs);
+
+    assert!(!merge.is_clean());
+    assert_eq!(merge.conflicts, ["a"]);
+    assert!(!merge.markers_conflict);
+
+    let mut resolved = merge_lock(&[("a", "1.3.0"), ("b", "1.1.0"), ("c", "1.0.0")]);
+    resolved.markers = [("tool", "syside")]
+        .map(|(k, v)| (k.to_owned(), v.to_owned()))
+        .into();
+    let lock = merge.resolve(resolved).unwrap();
+
+    let versions: Vec<_> = lock
+        .projects
+        .iter()
+        .map(|p| (p.name.as_str(), p.version.as_str()))
+        .collect();
+    // Only the conflicting `a` and the new `c` are taken from the resolution
+    assert_eq!(
+        versions,
+        [
+            ("a", "1.3.0"),
+            ("b", "1.0.0"),
+            ("c", "1.0.0"),
+            ("main", "1.0.0")
+        ]
+    );
+    assert!(lock.markers.is_empty());
+}
@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Migrations of versioned on-disk formats, such as the lockfile and the
+//! metadata of local environments.
+//!
+//! Every format stamps its files with a version under a top-level key. When
+//! the format changes, its current version is bumped and a [`Migration`]
+//! from the previous version is added to its [`Format`]. Files in an older
+//! version are then rejected on read with a hint to migrate them, which
+//! applies the chain of migrations from their version to the current one.
+
+use std::cmp::Ordering;
+
+use thiserror::Error;
+use toml_edit::{DocumentMut, Item, Value, value};
+
+use crate::error_code::{ErrorClass, ErrorCode, HasErrorCode};
+
+/// Migration of a format from version `from` to version `to`
+#[derive(Debug)]
+pub struct Migration {
+    pub from: &'static str,
+    pub to: &'static str,
+    /// Short description of the changes, shown when migrating
+    pub description: &'static str,
+    /// Transform a document in version `from` to version `to`. The version
+    /// stamp is updated afterwards, so `apply` does not need to change it
+    pub apply: fn(&mut DocumentMut) -> Result<(), MigrationError>,
+}
+
+/// A versioned on-disk format
+#[derive(Debug)]
+pub struct Format {
+    /// Name of the format used in messages, e.g. `lockfile`
+    pub name: &'static str,
+    /// Top-level key of the version stamp
+    pub version_key: &'static str,
+    pub current: &'static str,
+    /// Version of files without a version stamp. If `None`, the stamp is
+    /// required
+    pub unversioned: Option<&'static str>,
+    /// Migrations between consecutive versions, oldest first
+    pub migrations: &'static [Migration],
+}
+
+/// How a version relates to the current version of a [`Format`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionStatus {
+    Current,
+    /// Older, and there are migrations to the current version
+    Outdated,
+    /// Newer, i.e. written by a newer version of sysand
+    Newer,
+    /// Older without migrations to the current version, or not a version
+    Unsupported,
+}
+
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error("failed to parse {0}: {1}")]
+    Toml(&'static str, Box<toml_edit::TomlError>),
+    #[error("{0} has no version stamp `{1}`")]
+    MissingVersion(&'static str, &'static str),
+    #[error(
+        "{0} version `{1}` is not supported and cannot be migrated;\n\
+        regenerate it with the current version of sysand"
+    )]
+    Unsupported(&'static str, String),
+    #[error(
+        "{0} version `{1}` is newer than the supported version `{2}`; upgrade sysand to use it"
+    )]
+    Newer(&'static str, String, &'static str),
+    #[error("failed to migrate {format} from version `{from}` to `{to}`: {reason}")]
+    Failed {
+        format: &'static str,
+        from: &'static str,
+        to: &'static str,
+        reason: String,
+    },
+}
+
+impl HasErrorCode for MigrationError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            MigrationError::Toml(..) => ErrorCode::new(2001, ErrorClass::Project),
+            MigrationError::MissingVersion(..) => ErrorCode::new(2002, ErrorClass::Project),
+            MigrationError::Unsupported(..) => ErrorCode::new(2003, ErrorClass::Project),
+            MigrationError::Newer(..) => ErrorCode::new(2004, ErrorClass::Project),
+            MigrationError::Failed { .. } => ErrorCode::new(2005, ErrorClass::Project),
+        }
+    }
+}
+
+/// Outcome of migrating a document
+#[derive(Debug)]
+pub struct Migrated {
+    /// Version of the document before migrating
+    pub from: String,
+    /// Migrations applied, in order
+    pub applied: Vec<&'static Migration>,
+}
+
+impl Format {
+    /// Version stamp of `document`
+    pub fn version_of(&self, document: &DocumentMut) -> Result<String, MigrationError> {
+        match document.get(self.version_key) {
+            Some(Item::Value(Value::String(version))) => Ok(version.value().to_owned()),
+            None => self
+                .unversioned
+                .map(str::to_owned)
+                .ok_or(MigrationError::MissingVersion(self.name, self.version_key)),
+            Some(_) => Err(MigrationError::MissingVersion(self.name, self.version_key)),
+        }
+    }
+
+    pub fn status(&self, version: &str) -> VersionStatus {
+        if version == self.current {
+            VersionStatus::Current
+        } else if self.chain(version).is_some() {
+            VersionStatus::Outdated
+        } else if compare_versions(version, self.current) == Some(Ordering::Greater) {
+            VersionStatus::Newer
+        } else {
+            VersionStatus::Unsupported
+        }
+    }
+
+    /// Error for reading a file in `version`, or `None` if it is current
+    pub fn check_version(&self, version: &str) -> Option<MigrationError> {
+        match self.status(version) {
+            VersionStatus::Current | VersionStatus::Outdated => None,
+            VersionStatus::Newer => Some(MigrationError::Newer(
+                self.name,
+                version.to_owned(),
+                self.current,
+            )),
+            VersionStatus::Unsupported => {
+                Some(MigrationError::Unsupported(self.name, version.to_owned()))
+            }
+        }
+    }
+
+    /// Migrations from `version` to the current version, `None` if there
+    /// is no such chain
+    fn chain(&self, version: &str) -> Option<Vec<&'static Migration>> {
+        let mut chain = vec![];
+        let mut version = version;
+        while version != self.current {
+            let migration = self.migrations.iter().find(|m| m.from == version)?;
+            chain.push(migration);
+            version = migration.to;
+        }
+        Some(chain)
+    }
+
+    /// Migrate `document` to the current version. Returns `None` if it is
+    /// already current
+    pub fn migrate(&self, document: &mut DocumentMut) -> Result<Option<Migrated>, MigrationError> {
+        let from = self.version_of(document)?;
+        if let Some(err) = self.check_version(&from) {
+            return Err(err);
+        }
+        // Cannot fail, since `check_version` succeeded
+        let applied = self.chain(&from).unwrap_or_default();
+        if applied.is_empty() {
+            return Ok(None);
+        }
+        for migration in &applied {
+            (migration.apply)(document)?;
+            // Replace only the value, keeping comments before the key
+            match document.get_mut(self.version_key) {
+                Some(version) => *version = value(migration.to),
+                None => {
+                    document.insert(self.version_key, value(migration.to));
+                }
+            }
+        }
+        Ok(Some(Migrated { from, applied }))
+    }
+
+    /// Migrate the contents `s` of a file to the current version. Returns
+    /// `None` if it is already current
+    pub fn migrate_str(&self, s: &str) -> Result<Option<(Migrated, String)>, MigrationError> {
+        let mut document: DocumentMut = s
+            .parse()
+            .map_err(|e| MigrationError::Toml(self.name, Box::new(e)))?;
+        Ok(self
+            .migrate(&mut document)?
+            .map(|migrated| (migrated, document.to_string())))
+    }
+}
+
+/// Compare dot-separated numeric versions, `None` if either is not one
+fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    let parse = |v: &str| {
+        v.split('.')
+            .map(str::parse::<u64>)
+            .collect::<Result<Vec<_>, _>>()
+            .ok()
+    };
+    Some(parse(a)?.cmp(&parse(b)?))
+}
+
+#[cfg(test)]
+#[path = "./migration_tests.rs"]
+mod tests;
@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::assert_matches;
+
+use super::*;
+
+const FORMAT: Format = Format {
+    name: "test file",
+    version_key: "format_version",
+    current: "0.3",
+    unversioned: None,
+    migrations: &[
+        Migration {
+            from: "0.1",
+            to: "0.2",
+            description: "rename `old` to `new`",
+            apply: |document| {
+                if let Some(old) = document.remove("old") {
+                    document.insert("new", old);
+                }
+                Ok(())
+            },
+        },
+        Migration {
+            from: "0.2",
+            to: "0.3",
+            description: "require `new` to be a string",
+            apply: |document| match document.get("new") {
+                Some(new) if !new.is_str() => Err(MigrationError::Failed {
+                    format: "test file",
+                    from: "0.2",
+                    to: "0.3",
+                    reason: "`new` is not a string".to_owned(),
+                }),
+                _ => Ok(()),
+            },
+        },
+    ],
+};
+
+#[test]
+fn version_status() {
+    assert_eq!(FORMAT.status("0.3"), VersionStatus::Current);
+    assert_eq!(FORMAT.status("0.1"), VersionStatus::Outdated);
+    assert_eq!(FORMAT.status("0.2"), VersionStatus::Outdated);
+    assert_eq!(FORMAT.status("0.10"), VersionStatus::Newer);
+    assert_eq!(FORMAT.status("1.0"), VersionStatus::Newer);
+    assert_eq!(FORMAT.status("0.0"), VersionStatus::Unsupported);
+    assert_eq!(FORMAT.status("X"), VersionStatus::Unsupported);
+}
+
+#[test]
+fn migrations_are_chained() {
+    let (migrated, contents) = FORMAT
+        .migrate_str("format_version = \"0.1\"\nold = \"value\"\n")
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(migrated.from, "0.1");
+    assert_eq!(
+        migrated.applied.iter().map(|m| m.to).collect::<Vec<_>>(),
+        ["0.2", "0.3"]
+    );
+    assert_eq!(contents, "format_version = \"0.3\"\nnew = \"value\"\n");
+}
+
+#[test]
+fn comments_before_version_are_kept() {
+    let (_, contents) = FORMAT
+        .migrate_str("# header\n\nformat_version = \"0.1\"\n")
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(contents, "# header\n\nformat_version = \"0.3\"\n");
+}
+
+#[test]
+fn current_version_is_not_migrated() {
+    assert!(
+        FORMAT
+            .migrate_str("format_version = \"0.3\"\nnew = 1\n")
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn failed_migration_is_reported() {
+    let err = FORMAT
+        .migrate_str("format_version = \"0.1\"\nold = 1\n")
+        .unwrap_err();
+
+    assert_matches!(err, MigrationError::Failed { from: "0.2", .. });
+}
+
+#[test]
+fn unmigratable_versions_are_rejected() {
+    assert_matches!(
+        FORMAT.migrate_str("format_version = \"0.4\"\n").unwrap_err(),
+        MigrationError::Newer(_, version, "0.3") if version == "0.4"
+    );
+    assert_matches!(
+        FORMAT.migrate_str("format_version = \"0.0\"\n").unwrap_err(),
+        MigrationError::Unsupported(_, version) if version == "0.0"
+    );
+    assert_matches!(
+        FORMAT.migrate_str("new = 1\n").unwrap_err(),
+        MigrationError::MissingVersion("test file", "format_version")
+    );
+}
@@ -11,8 +11,9 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use typed_path::{Utf8UnixPath, Utf8UnixPathBuf};
 
-use crate::utils::{
-    RelativePathKind, RelativeUnixPathError, lowercase_hex, parse_relative_unix_path,
+use crate::{
+    solve::markers::{Condition, ConditionError, Markers},
+    utils::{RelativePathKind, RelativeUnixPathError, lowercase_hex, parse_relative_unix_path},
 };
 
 // pub struct RawIri(String);
@@ -28,6 +29,7 @@ pub const SYSML_METAMODEL_PREFIX: &str = "https://www.omg.org/spec/SysML/";
 pub const KERML_METAMODEL_PREFIX: &str = "https://www.omg.org/spec/KerML/";
 
 #[derive(Eq, Clone, PartialEq, Serialize, Deserialize, Hash, Debug)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "python", derive(FromPyObject, IntoPyObject))]
 #[serde(untagged)]
 pub enum InterchangeProjectUsageG<Iri, VersionReq> {
@@ -38,6 +40,16 @@ pub enum InterchangeProjectUsageG<Iri, VersionReq> {
         resource: Iri, // TODO: We should have a fallback for invalid IRIs
         #[serde(skip_serializing_if = "Option::is_none")]
         version_constraint: Option<VersionReq>, // TODO: We should have a fallback for invalid semvers
+        /// Condition on the markers that the usage applies under, see
+        /// [`crate::solve::markers`]. Not part of the KerML spec
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        #[cfg_attr(feature = "python", pyo3(default))]
+        when: Option<String>,
+        /// Canonical project hash the used project must have, as
+        /// `sha256:<hex>`. Not part of the KerML spec
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        #[cfg_attr(feature = "python", pyo3(default))]
+        hash: Option<String>,
     },
 }
 
@@ -52,6 +64,8 @@ impl InterchangeProjectUsageRaw {
             InterchangeProjectUsageG::Resource {
                 resource,
                 version_constraint,
+                when,
+                hash,
             } => {
                 // `pkg:sysand/<publisher>/<name>` is the canonical sysand project
                 // identifier; the index protocol routes it directly under
@@ -83,21 +97,83 @@ impl InterchangeProjectUsageRaw {
                             })
                         })
                         .transpose()?,
+                    when: when
+                        .as_ref()
+                        .map(|when| {
+                            when.parse::<Condition>().map_err(|e| {
+                                InterchangeProjectValidationError::InvalidUsageCondition {
+                                    resource: resource.to_owned(),
+                                    condition: when.to_owned(),
+                                    source: Box::new(e),
+                                }
+                            })?;
+                            Ok(when.to_owned())
+                        })
+                        .transpose()?,
+                    hash: hash
+                        .as_ref()
+                        .map(|hash| {
+                            parse_hash_pin(hash).ok_or_else(|| {
+                                InterchangeProjectValidationError::InvalidUsageHash {
+                                    resource: resource.to_owned(),
+                                    hash: hash.to_owned(),
+                                }
+                            })?;
+                            Ok(hash.to_owned())
+                        })
+                        .transpose()?,
                 })
             }
         }
     }
 }
 
+impl<Iri, VersionReq> InterchangeProjectUsageG<Iri, VersionReq> {
+    /// Whether the usage applies under `markers`. Usages whose condition
+    /// does not parse apply, as validation reports the condition
+    pub fn applies(&self, markers: &Markers) -> bool {
+        match self {
+            InterchangeProjectUsageG::Resource { when, .. } => when
+                .as_deref()
+                .and_then(|when| when.parse::<Condition>().ok())
+                .is_none_or(|condition| condition.evaluate(markers)),
+        }
+    }
+
+    /// Canonical project hash (lowercase hex) the usage is pinned to.
+    /// Malformed pins are ignored, as validation reports them
+    pub fn hash_pin(&self) -> Option<&str> {
+        match self {
+            InterchangeProjectUsageG::Resource { hash, .. } => {
+                hash.as_deref().and_then(parse_hash_pin)
+            }
+        }
+    }
+}
+
+/// Prefix of usage hash pins, see [`InterchangeProjectUsageG::hash_pin`]
+pub const HASH_PIN_PREFIX: &str = "sha256:";
+
+/// Hex digest of a `sha256:<64 lowercase hex digits>` hash pin
+pub fn parse_hash_pin(pin: &str) -> Option<&str> {
+    pin.strip_prefix(HASH_PIN_PREFIX).filter(|hex| {
+        hex.len() == 64 && hex.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+    })
+}
+
 impl From<InterchangeProjectUsage> for InterchangeProjectUsageRaw {
     fn from(value: InterchangeProjectUsage) -> InterchangeProjectUsageRaw {
         match value {
             InterchangeProjectUsageG::Resource {
                 resource,
                 version_constraint,
+                when,
+                hash,
             } => InterchangeProjectUsageRaw::Resource {
                 resource: resource.into_string(),
                 version_constraint: version_constraint.map(|x| x.to_string()),
+                when,
+                hash,
             },
         }
     }
@@ -111,9 +187,13 @@ impl From<InterchangeProjectUsageG<fluent_uri::Iri<String>, semver::VersionReq>>
             InterchangeProjectUsageG::Resource {
                 resource,
                 version_constraint,
+                when,
+                hash,
             } => InterchangeProjectUsageG::Resource {
                 resource: resource.into_string(),
                 version_constraint,
+                when,
+                hash,
             },
         }
     }
@@ -125,11 +205,19 @@ impl<Iri: Display, VersionReq: Display> Display for InterchangeProjectUsageG<Iri
             InterchangeProjectUsageG::Resource {
                 resource,
                 version_constraint,
+                when,
+                hash,
             } => {
                 write!(f, "IRI `{resource}`")?;
                 if let Some(vc) = version_constraint {
                     write!(f, " ({vc})")?;
                 }
+                if let Some(when) = when {
+                    write!(f, " when `{when}`")?;
+                }
+                if let Some(hash) = hash {
+                    write!(f, " with hash `{hash}`")?;
+                }
             }
         }
         Ok(())
@@ -137,6 +225,7 @@ impl<Iri: Display, VersionReq: Display> Display for InterchangeProjectUsageG<Iri
 }
 
 #[derive(Eq, Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "python", derive(FromPyObject, IntoPyObject))]
 #[serde(rename_all = "camelCase")]
 pub struct InterchangeProjectInfoG<Iri, Version, VersionReq> {
@@ -266,7 +355,10 @@ impl InterchangeProjectInfoRaw {
 /// - BLAKE2b-256, BLAKE2b-384, BLAKE2b-512, BLAKE3
 /// - MD2, MD4, MD5, MD6
 /// - ADLER32
-// TODO: why is SHA512 missing? Also SHA256 vs SHA-384
+///
+/// SHA-512 is also accepted, as it is commonly required by policy (e.g.
+/// FIPS 180-4) and is the natural counterpart of SHA-384.
+// TODO: SHA256 vs SHA-384
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(try_from = "String", into = "&str")]
 #[cfg_attr(feature = "python", pyclass(eq, eq_int, from_py_object))]
@@ -281,6 +373,8 @@ pub enum KerMlChecksumAlg {
     Sha224,
     Sha256,
     Sha384,
+    /// Non-standard, not listed by KerML 1.0
+    Sha512,
     Sha3_256,
     Sha3_384,
     Sha3_512,
@@ -319,6 +413,7 @@ impl TryFrom<&str> for KerMlChecksumAlg {
             "SHA224" => Sha224,
             "SHA256" => Sha256,
             "SHA-384" => Sha384,
+            "SHA-512" => Sha512,
 
             "SHA3-256" => Sha3_256,
             "SHA3-384" => Sha3_384,
@@ -359,6 +454,7 @@ impl From<KerMlChecksumAlg> for &'static str {
             Sha224 => "SHA224",
             Sha256 => "SHA256",
             Sha384 => "SHA-384",
+            Sha512 => "SHA-512",
 
             Sha3_256 => "SHA3-256",
             Sha3_384 => "SHA3-384",
@@ -397,7 +493,7 @@ impl KerMlChecksumAlg {
             Sha224 => 56,
             Sha256 | Sha3_256 | Blake2b256 | Blake3 => 64,
             Sha384 | Sha3_384 | Blake2b384 => 96,
-            Sha3_512 | Blake2b512 => 128,
+            Sha512 | Sha3_512 | Blake2b512 => 128,
             Md2 | Md4 | Md5 => 32,
             // MD6 is variable length. TODO(spec): the
             // digest length must be somehow specified
@@ -409,6 +505,58 @@ impl KerMlChecksumAlg {
     }
 }
 
+/// Checksum algorithms that can be computed and verified. SHA256 is used
+/// for canonicalization unless configured otherwise.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HashAlg {
+    Sha224,
+    #[default]
+    Sha256,
+    Sha384,
+    Sha512,
+    Blake3,
+}
+
+#[derive(Debug, Error)]
+#[error(
+    "checksum algorithm `{0}` is not supported; supported algorithms are \
+    SHA224, SHA256, SHA-384, SHA-512 and BLAKE3"
+)]
+pub struct UnsupportedHashAlg(pub KerMlChecksumAlg);
+
+impl TryFrom<KerMlChecksumAlg> for HashAlg {
+    type Error = UnsupportedHashAlg;
+
+    fn try_from(value: KerMlChecksumAlg) -> Result<Self, Self::Error> {
+        match value {
+            KerMlChecksumAlg::Sha224 => Ok(HashAlg::Sha224),
+            KerMlChecksumAlg::Sha256 => Ok(HashAlg::Sha256),
+            KerMlChecksumAlg::Sha384 => Ok(HashAlg::Sha384),
+            KerMlChecksumAlg::Sha512 => Ok(HashAlg::Sha512),
+            KerMlChecksumAlg::Blake3 => Ok(HashAlg::Blake3),
+            other => Err(UnsupportedHashAlg(other)),
+        }
+    }
+}
+
+impl From<HashAlg> for KerMlChecksumAlg {
+    fn from(value: HashAlg) -> Self {
+        match value {
+            HashAlg::Sha224 => KerMlChecksumAlg::Sha224,
+            HashAlg::Sha256 => KerMlChecksumAlg::Sha256,
+            HashAlg::Sha384 => KerMlChecksumAlg::Sha384,
+            HashAlg::Sha512 => KerMlChecksumAlg::Sha512,
+            HashAlg::Blake3 => KerMlChecksumAlg::Blake3,
+        }
+    }
+}
+
+impl Display for HashAlg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        KerMlChecksumAlg::from(*self).fmt(f)
+    }
+}
+
 #[derive(Eq, Clone, PartialEq, Serialize, Deserialize, Debug)]
 #[cfg_attr(feature = "python", derive(FromPyObject, IntoPyObject))]
 #[serde(rename_all = "camelCase")]
@@ -419,6 +567,7 @@ pub struct InterchangeProjectChecksum {
 }
 
 #[derive(Eq, Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "python", derive(FromPyObject, IntoPyObject))]
 #[serde(rename_all = "camelCase")]
 pub struct InterchangeProjectChecksumRaw {
@@ -427,6 +576,7 @@ pub struct InterchangeProjectChecksumRaw {
 }
 
 #[derive(Eq, Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[cfg_attr(feature = "python", derive(FromPyObject, IntoPyObject))]
 #[serde(rename_all = "camelCase")]
 pub struct InterchangeProjectMetadataG<Iri, Path: Eq + Hash, DateTime, IPC> {
@@ -445,6 +595,13 @@ pub struct InterchangeProjectMetadataG<Iri, Path: Eq + Hash, DateTime, IPC> {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub checksum: Option<IndexMap<Path, IPC>>,
+
+    /// Named subsets of the source files, selected by dependents with
+    /// `variants` in `sysand.toml`. Paths may be files or directories.
+    /// Not part of the KerML spec
+    #[serde(skip_serializing_if = "IndexMap::is_empty", default)]
+    #[cfg_attr(feature = "python", pyo3(default))]
+    pub variants: IndexMap<String, Vec<Path>>,
 }
 
 pub type InterchangeProjectMetadataRaw =
@@ -496,6 +653,11 @@ impl From<InterchangeProjectMetadata> for InterchangeProjectMetadataRaw {
                     })
                     .collect()
             }),
+            variants: value
+                .variants
+                .into_iter()
+                .map(|(k, v)| (k, v.into_iter().map(|p| p.into_string()).collect()))
+                .collect(),
         }
     }
 }
@@ -519,10 +681,23 @@ pub enum InterchangeProjectValidationError {
         constraint: String,
         source: semver::Error,
     },
+    #[error("failed to parse condition `{condition}` of usage `{resource}`")]
+    InvalidUsageCondition {
+        resource: String,
+        condition: String,
+        source: Box<ConditionError>,
+    },
+    #[error(
+        "invalid hash `{hash}` of usage `{resource}`, expected\n\
+        `sha256:` followed by 64 lowercase hex digits"
+    )]
+    InvalidUsageHash { resource: String, hash: String },
     #[error("exported symbol index (`index` field in `.meta.json`) references an invalid path")]
     InvalidPathInIndex(#[source] RelativeUnixPathError),
     #[error("source file checksum (`checksum` field in `.meta.json`) references an invalid path")]
     InvalidPathInChecksum(#[source] RelativeUnixPathError),
+    #[error("variant `{0}` (`variants` field in `.meta.json`) references an invalid path")]
+    InvalidPathInVariant(String, #[source] RelativeUnixPathError),
     #[error("failed to parse `{0}` as RFC3339 datetime: {1}")]
     InvalidCreatedTime(Box<str>, chrono::ParseError),
     #[error(
@@ -559,6 +734,7 @@ impl Default for InterchangeProjectMetadataRaw {
             includes_derived: None,
             includes_implied: None,
             checksum: None,
+            variants: IndexMap::default(),
         }
     }
 }
@@ -615,6 +791,22 @@ impl InterchangeProjectMetadataRaw {
             None
         };
 
+        let mut variants = IndexMap::with_capacity(self.variants.len());
+        for (name, paths) in &self.variants {
+            let paths = paths
+                .iter()
+                .map(|p| {
+                    // Paths may be directories
+                    parse_relative_unix_path(p, RelativePathKind::SubDirectory)
+                        .map(|p| p.to_path_buf())
+                        .map_err(|e| {
+                            InterchangeProjectValidationError::InvalidPathInVariant(name.clone(), e)
+                        })
+                })
+                .collect::<Result<_, _>>()?;
+            variants.insert(name.clone(), paths);
+        }
+
         let metamodel = if let Some(m) = &self.metamodel {
             if !KNOWN_METAMODELS.contains(&m.as_str()) {
                 log::warn!("project uses an unknown metamodel `{}`", m);
@@ -644,9 +836,45 @@ impl InterchangeProjectMetadataRaw {
             includes_derived: self.includes_derived,
             includes_implied: self.includes_implied,
             checksum,
+            variants,
         })
     }
 
+    /// Whether `path` is one of the source files of variant `name`, i.e.
+    /// listed in it or in one of its directories. `None` if there is no
+    /// such variant
+    pub fn variant_contains(&self, name: &str, path: &str) -> Option<bool> {
+        let paths = self.variants.get(name)?;
+        Some(paths.iter().any(|p| variant_path_contains(p, path)))
+    }
+
+    /// Restrict `index` and `checksum` to the source files of variant
+    /// `name`, which is then the only variant. Returns `false` without
+    /// changing anything if there is no such variant
+    pub fn restrict_to_variant(&mut self, name: &str) -> bool {
+        if !self.variants.contains_key(name) {
+            return false;
+        }
+        let keep = |path: &str| self.variant_contains(name, path) == Some(true);
+        let index = self
+            .index
+            .iter()
+            .filter(|(_, path)| keep(path))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        let checksum = self.checksum.as_ref().map(|checksum| {
+            checksum
+                .iter()
+                .filter(|(path, _)| keep(path))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect()
+        });
+        self.index = index;
+        self.checksum = checksum;
+        self.variants.retain(|k, _| k == name);
+        true
+    }
+
     /// Get symbols recorded in `index` for file at `path`
     pub fn file_index_symbols<P: AsRef<str>>(&self, path: P) -> HashSet<String> {
         self.index
@@ -715,6 +943,35 @@ impl InterchangeProjectMetadataRaw {
             .map(|x| x.0)
             .collect()
     }
+
+    /// Refer to the source file at `from` as `to` in the checksums, the
+    /// index and the variants, keeping the order of the entries. Returns
+    /// whether any entry referred to `from`
+    pub fn rename_source<P: AsRef<Utf8UnixPath>, Q: AsRef<Utf8UnixPath>>(
+        &mut self,
+        from: P,
+        to: Q,
+    ) -> bool {
+        let (from, to) = (from.as_ref().as_str(), to.as_ref().as_str());
+        let mut renamed = false;
+        if let Some(checksum) = self.checksum.as_mut()
+            && let Some((idx, _, value)) = checksum.shift_remove_full(from)
+        {
+            checksum.shift_insert(idx, to.to_owned(), value);
+            renamed = true;
+        }
+        for path in self
+            .index
+            .values_mut()
+            .chain(self.variants.values_mut().flatten())
+        {
+            if path == from {
+                *path = to.to_owned();
+                renamed = true;
+            }
+        }
+        renamed
+    }
 }
 
 impl<Iri, Path: Eq + Hash + Clone, DateTime, IPC>
@@ -728,6 +985,7 @@ impl<Iri, Path: Eq + Hash + Clone, DateTime, IPC>
             includes_derived: None,
             includes_implied: None,
             checksum: None,
+            variants: IndexMap::new(),
         }
     }
 
@@ -751,6 +1009,16 @@ impl<Iri, Path: Eq + Hash + Clone, DateTime, IPC>
     }
 }
 
+/// Whether `path` is the file `variant_path` of a variant, or inside the
+/// directory `variant_path`
+pub(crate) fn variant_path_contains(variant_path: &str, path: &str) -> bool {
+    let variant_path = variant_path.trim_end_matches('/');
+    path == variant_path
+        || path
+            .strip_prefix(variant_path)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
 pub type ProjectHash = Array<u8, typenum::U32>;
 
 fn project_hash_str<S: AsRef<str>, T: AsRef<str>>(info: S, meta: T) -> ProjectHash {
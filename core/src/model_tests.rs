@@ -4,7 +4,8 @@
 use indexmap::IndexMap;
 
 use crate::{
-    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw, InterchangeProjectUsageRaw},
+    solve::markers::Markers,
     utils::lowercase_hex,
 };
 
@@ -40,6 +41,7 @@ fn json_hash_agrees_with_shell() {
         includes_derived: None,
         includes_implied: None,
         checksum: None,
+        variants: IndexMap::new(),
     };
 
     assert_eq!(
@@ -57,3 +59,90 @@ fn json_hash_agrees_with_shell() {
         "3b08c7119d89c406de6bdfbed29566077209d295736264229ad5d2e33991b3b4"
     );
 }
+
+#[test]
+fn variant_paths() {
+    let meta: InterchangeProjectMetadataRaw = serde_json::from_str(
+        r#"{
+            "index": {"A": "a.sysml", "B": "extra/b.sysml", "C": "extras.sysml"},
+            "created": "2025-01-01T00:00:00Z",
+            "variants": {"minimal": ["a.sysml"], "full": ["a.sysml", "extra/"]}
+        }"#,
+    )
+    .unwrap();
+    meta.validate().unwrap();
+
+    assert_eq!(meta.variant_contains("minimal", "a.sysml"), Some(true));
+    assert_eq!(
+        meta.variant_contains("minimal", "extra/b.sysml"),
+        Some(false)
+    );
+    assert_eq!(meta.variant_contains("full", "extra/b.sysml"), Some(true));
+    assert_eq!(meta.variant_contains("full", "extras.sysml"), Some(false));
+    assert_eq!(meta.variant_contains("unknown", "a.sysml"), None);
+
+    let mut full = meta.clone();
+    assert!(full.restrict_to_variant("full"));
+    assert_eq!(full.index.keys().collect::<Vec<_>>(), ["A", "B"]);
+    let mut unknown = meta.clone();
+    assert!(!unknown.restrict_to_variant("unknown"));
+    assert_eq!(unknown, meta);
+
+    let invalid = InterchangeProjectMetadataRaw {
+        variants: IndexMap::from([("up".to_string(), vec!["../a.sysml".to_string()])]),
+        ..meta
+    };
+    assert!(matches!(
+        invalid.validate(),
+        Err(super::InterchangeProjectValidationError::InvalidPathInVariant(name, _)) if name == "up"
+    ));
+}
+
+#[test]
+fn usage_conditions() {
+    let usage = |when: &str| -> InterchangeProjectUsageRaw {
+        serde_json::from_value(serde_json::json!({"resource": "urn:kpar:a", "when": when})).unwrap()
+    };
+    let markers = [("tool".to_owned(), "syside".to_owned())].into();
+
+    let syside = usage(r#"tool == "syside""#);
+    assert!(syside.validate().unwrap().applies(&markers));
+    assert!(!syside.applies(&Markers::new()));
+    assert!(!usage("tool != 'syside'").applies(&markers));
+    assert!(
+        serde_json::to_string(&syside)
+            .unwrap()
+            .contains(r#""when":"tool == \"syside\"""#)
+    );
+
+    assert!(matches!(
+        usage("platform == 'x'").validate(),
+        Err(super::InterchangeProjectValidationError::InvalidUsageCondition { condition, .. })
+            if condition == "platform == 'x'"
+    ));
+}
+
+#[test]
+fn usage_hash_pins() {
+    let usage = |hash: &str| -> InterchangeProjectUsageRaw {
+        serde_json::from_value(serde_json::json!({"resource": "urn:kpar:a", "hash": hash})).unwrap()
+    };
+    let hex = "0123456789abcdef".repeat(4);
+
+    let pinned = usage(&format!("sha256:{hex}"));
+    assert_eq!(pinned.validate().unwrap().hash_pin(), Some(hex.as_str()));
+    assert!(
+        serde_json::to_string(&pinned)
+            .unwrap()
+            .contains(&format!(r#""hash":"sha256:{hex}""#))
+    );
+
+    for invalid in [hex.clone(), format!("sha1:{hex}"), "sha256:ABCD".to_owned()] {
+        assert!(usage(&invalid).hash_pin().is_none());
+        assert!(matches!(
+            usage(&invalid).validate(),
+            Err(super::InterchangeProjectValidationError::InvalidUsageHash { hash, .. })
+                if hash == invalid
+        ));
+    }
+}
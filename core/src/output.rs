@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Where commands write their results. Logs and progress messages are
+//! not results and go through `log` instead
+
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex},
+};
+
+use serde_json::Value;
+
+/// Receiver of the results of commands. Each result comes both as text for
+/// people and as a JSON value, and the sink decides which to keep
+pub trait OutputSink {
+    /// Receive one result. `human` may span several lines and has no
+    /// trailing newline
+    fn emit(&mut self, human: &str, value: Value) -> io::Result<()>;
+}
+
+impl<S: OutputSink + ?Sized> OutputSink for &mut S {
+    fn emit(&mut self, human: &str, value: Value) -> io::Result<()> {
+        (**self).emit(human, value)
+    }
+}
+
+impl<S: OutputSink + ?Sized> OutputSink for Box<S> {
+    fn emit(&mut self, human: &str, value: Value) -> io::Result<()> {
+        (**self).emit(human, value)
+    }
+}
+
+/// Writes the text of each result on its own lines
+#[derive(Debug)]
+pub struct HumanSink<W>(pub W);
+
+impl<W: Write> OutputSink for HumanSink<W> {
+    fn emit(&mut self, human: &str, _value: Value) -> io::Result<()> {
+        writeln!(self.0, "{human}")
+    }
+}
+
+/// Writes the value of each result as a JSON object on its own line
+#[derive(Debug)]
+pub struct JsonSink<W>(pub W);
+
+impl<W: Write> OutputSink for JsonSink<W> {
+    fn emit(&mut self, _human: &str, value: Value) -> io::Result<()> {
+        writeln!(self.0, "{value}")
+    }
+}
+
+/// Discards all results
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SilentSink;
+
+impl OutputSink for SilentSink {
+    fn emit(&mut self, _human: &str, _value: Value) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Result kept by a [`CaptureSink`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedOutput {
+    pub human: String,
+    pub value: Value,
+}
+
+/// Keeps all results in memory. Clones share the results, so one clone can
+/// be handed to a command and another read afterwards
+#[derive(Debug, Default, Clone)]
+pub struct CaptureSink(Arc<Mutex<Vec<CapturedOutput>>>);
+
+impl CaptureSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Results received so far
+    pub fn outputs(&self) -> Vec<CapturedOutput> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Text of the results received so far, as [`HumanSink`] writes it
+    pub fn human(&self) -> String {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|o| format!("{}\n", o.human))
+            .collect()
+    }
+
+    /// Values of the results received so far
+    pub fn values(&self) -> Vec<Value> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|o| o.value.clone())
+            .collect()
+    }
+}
+
+impl OutputSink for CaptureSink {
+    fn emit(&mut self, human: &str, value: Value) -> io::Result<()> {
+        self.0.lock().unwrap().push(CapturedOutput {
+            human: human.to_owned(),
+            value,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "./output_tests.rs"]
+mod tests;
@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use serde_json::json;
+
+use super::{CaptureSink, HumanSink, JsonSink, OutputSink, SilentSink};
+
+fn emit_two(sink: &mut dyn OutputSink) {
+    sink.emit(
+        "a 1.0.0\n  used by: b",
+        json!({"name": "a", "used_by": ["b"]}),
+    )
+    .unwrap();
+    sink.emit("c", json!("c")).unwrap();
+}
+
+#[test]
+fn human_sink_writes_text_lines() {
+    let mut sink = HumanSink(Vec::new());
+    emit_two(&mut sink);
+    assert_eq!(
+        String::from_utf8(sink.0).unwrap(),
+        "a 1.0.0\n  used by: b\nc\n"
+    );
+}
+
+#[test]
+fn json_sink_writes_value_lines() {
+    let mut sink = JsonSink(Vec::new());
+    emit_two(&mut sink);
+    assert_eq!(
+        String::from_utf8(sink.0).unwrap(),
+        "{\"name\":\"a\",\"used_by\":[\"b\"]}\n\"c\"\n"
+    );
+}
+
+#[test]
+fn capture_sink_is_shared_by_clones() {
+    let capture = CaptureSink::new();
+    let mut boxed: Box<dyn OutputSink> = Box::new(capture.clone());
+    emit_two(&mut boxed);
+    assert_eq!(capture.outputs().len(), 2);
+    assert_eq!(capture.human(), "a 1.0.0\n  used by: b\nc\n");
+    assert_eq!(capture.values()[1], json!("c"));
+
+    emit_two(&mut SilentSink);
+}
@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Running independent work, such as checksumming files, on several threads.
+//! Results are always returned in the order of the inputs, so that output
+//! does not depend on the number of threads.
+
+use std::{fmt, num::NonZeroUsize, str::FromStr};
+
+/// Number of threads to use for work that can be done in parallel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Jobs(NonZeroUsize);
+
+impl Jobs {
+    /// Do all work on the calling thread
+    pub const ONE: Self = Self(NonZeroUsize::MIN);
+
+    pub fn new(jobs: NonZeroUsize) -> Self {
+        Self(jobs)
+    }
+
+    pub fn get(self) -> usize {
+        self.0.get()
+    }
+}
+
+impl Default for Jobs {
+    /// As many threads as the system can run in parallel. Threads are not
+    /// available on WebAssembly, so there it is always one
+    fn default() -> Self {
+        #[cfg(target_arch = "wasm32")]
+        {
+            Self::ONE
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            std::thread::available_parallelism()
+                .map(Self)
+                .unwrap_or(Self::ONE)
+        }
+    }
+}
+
+impl From<NonZeroUsize> for Jobs {
+    fn from(value: NonZeroUsize) -> Self {
+        Self(value)
+    }
+}
+
+impl FromStr for Jobs {
+    type Err = <NonZeroUsize as FromStr>::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self)
+    }
+}
+
+impl fmt::Display for Jobs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Apply `f` to all `items` using up to `jobs` threads. The results are in
+/// the same order as `items`. Threads take the next unprocessed item when
+/// they are done with the previous one, so that a few large items do not
+/// hold up the rest
+pub(crate) fn parallel_map<T, R, F>(items: &[T], jobs: Jobs, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = jobs.get().min(items.len());
+    if cfg!(target_arch = "wasm32") || threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<Option<R>> = std::iter::repeat_with(|| None).take(items.len()).collect();
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = vec![];
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(i) else {
+                            break done;
+                        };
+                        done.push((i, f(item)));
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            let done = match worker.join() {
+                Ok(done) => done,
+                Err(panic) => std::panic::resume_unwind(panic),
+            };
+            for (i, result) in done {
+                results[i] = Some(result);
+            }
+        }
+    });
+    results
+        .into_iter()
+        .map(|r| r.expect("all items are processed"))
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "./parallel_tests.rs"]
+mod tests;
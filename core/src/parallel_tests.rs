@@ -0,0 +1,36 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::num::NonZeroUsize;
+
+use crate::parallel::{Jobs, parallel_map};
+
+#[test]
+fn parallel_map_keeps_order() {
+    let items: Vec<u64> = (0..1000).collect();
+    let expected: Vec<u64> = items.iter().map(|i| i * i).collect();
+    for jobs in [1, 2, 3, 8, 2000] {
+        let jobs = Jobs::new(NonZeroUsize::new(jobs).unwrap());
+        // Make later items finish first
+        let squares = parallel_map(&items, jobs, |&i| {
+            if i < 8 {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+            i * i
+        });
+        assert_eq!(squares, expected, "jobs: {jobs}");
+    }
+}
+
+#[test]
+fn parallel_map_empty() {
+    let squares = parallel_map(&[] as &[u64], Jobs::default(), |i| i * i);
+    assert!(squares.is_empty());
+}
+
+#[test]
+fn jobs_from_str() {
+    assert_eq!("4".parse::<Jobs>().unwrap().get(), 4);
+    assert!("0".parse::<Jobs>().is_err());
+    assert!(Jobs::default() >= Jobs::ONE);
+}
@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Progress of long-running operations: building KPARs, installing
+//! projects during sync and downloading KPARs. Events are dropped unless a
+//! listener was installed with [`with_listener`].
+
+use std::sync::{Arc, RwLock};
+
+use serde::Serialize;
+
+type Listener = Arc<dyn ProgressListener + Send + Sync>;
+
+static LISTENER: RwLock<Option<Listener>> = RwLock::new(None);
+
+/// Operation a [`ProgressEvent`] belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProgressPhase {
+    /// Adding source files to a KPAR, counted in files
+    Build,
+    /// Applying sync actions, counted in actions
+    Install,
+    /// Downloading a KPAR, counted in bytes
+    Download,
+}
+
+impl ProgressPhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProgressPhase::Build => "build",
+            ProgressPhase::Install => "install",
+            ProgressPhase::Download => "download",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProgressEvent {
+    pub phase: ProgressPhase,
+    /// Units done so far, including the one named by `message`
+    pub current: u64,
+    /// Total units, if known in advance
+    pub total: Option<u64>,
+    /// What is being worked on, e.g. a file path or a project IRI
+    pub message: String,
+}
+
+pub trait ProgressListener {
+    fn on_progress(&self, event: &ProgressEvent);
+}
+
+impl<F: Fn(&ProgressEvent)> ProgressListener for F {
+    fn on_progress(&self, event: &ProgressEvent) {
+        self(event)
+    }
+}
+
+/// Run `f` with `listener` receiving all progress events, from any thread.
+/// The previous listener is restored afterwards, also on panic
+pub fn with_listener<L, R>(listener: L, f: impl FnOnce() -> R) -> R
+where
+    L: ProgressListener + Send + Sync + 'static,
+{
+    struct Restore(Option<Listener>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            *LISTENER.write().unwrap_or_else(|e| e.into_inner()) = self.0.take();
+        }
+    }
+
+    let previous = LISTENER
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .replace(Arc::new(listener));
+    let _restore = Restore(previous);
+    f()
+}
+
+/// Send an event to the installed listener, if any. `message` is only
+/// built when someone listens
+pub(crate) fn report<M: Into<String>>(
+    phase: ProgressPhase,
+    current: u64,
+    total: Option<u64>,
+    message: impl FnOnce() -> M,
+) {
+    // Clone so the listener may itself install listeners
+    let Some(listener) = LISTENER.read().ok().and_then(|l| l.clone()) else {
+        return;
+    };
+    listener.on_progress(&ProgressEvent {
+        phase,
+        current,
+        total,
+        message: message().into(),
+    });
+}
+
+#[cfg(test)]
+#[path = "./progress_tests.rs"]
+mod tests;
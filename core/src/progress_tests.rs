@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "filesystem")]
+use camino_tempfile::tempdir;
+
+use super::{ProgressEvent, ProgressPhase, report, with_listener};
+#[cfg(feature = "filesystem")]
+use crate::{
+    commands::build::{KparCompressionMethod, do_build_kpar},
+    include::do_include,
+    init::do_init,
+    model::HashAlg,
+    project::local_src::LocalSrcProject,
+};
+
+/// Tests installing listeners must not overlap, as they would replace
+/// each other's listeners
+static SERIAL: Mutex<()> = Mutex::new(());
+
+/// Listener collecting the events that satisfy `keep`. The listener is
+/// global, so tests running in parallel may report unrelated events
+fn collect(
+    keep: impl Fn(&ProgressEvent) -> bool + Send + Sync + 'static,
+) -> (
+    Arc<Mutex<Vec<ProgressEvent>>>,
+    impl Fn(&ProgressEvent) + Send + Sync + 'static,
+) {
+    let events = Arc::new(Mutex::new(vec![]));
+    let sink = events.clone();
+    let listener = move |e: &ProgressEvent| {
+        if keep(e) {
+            sink.lock().unwrap().push(e.clone());
+        }
+    };
+    (events, listener)
+}
+
+#[test]
+fn listener_is_scoped() {
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let is_test = |e: &ProgressEvent| e.message.starts_with("scoped ");
+    let (outer, outer_listener) = collect(is_test);
+    let (inner, inner_listener) = collect(is_test);
+
+    report(ProgressPhase::Download, 1, None, || "scoped before");
+    with_listener(outer_listener, || {
+        report(ProgressPhase::Download, 2, Some(4), || "scoped outer");
+        with_listener(inner_listener, || {
+            report(ProgressPhase::Download, 3, Some(4), || "scoped inner");
+        });
+        report(ProgressPhase::Download, 4, Some(4), || "scoped outer");
+    });
+
+    let currents = |events: &Mutex<Vec<ProgressEvent>>| {
+        events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| e.current)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(currents(&outer), [2, 4]);
+    assert_eq!(currents(&inner), [3]);
+}
+
+#[cfg(feature = "filesystem")]
+#[test]
+fn build_reports_each_file() {
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let tmp = tempdir().unwrap();
+    let mut project = LocalSrcProject {
+        nominal_path: None,
+        project_path: tmp.path().to_owned(),
+        expected_checksum: None,
+    };
+    do_init("test".into(), None, "1.0.0".into(), None, &mut project).unwrap();
+    for name in ["progress_a.sysml", "progress_b.sysml"] {
+        std::fs::write(tmp.path().join(name), "package P;\n").unwrap();
+    }
+    do_include(
+        &mut project,
+        ["progress_a.sysml".into(), "progress_b.sysml".into()].into_iter(),
+        Some(HashAlg::Sha256),
+        true,
+        None,
+    )
+    .unwrap();
+
+    let (events, listener) =
+        collect(|e| e.phase == ProgressPhase::Build && e.message.starts_with("progress_"));
+    with_listener(listener, || {
+        do_build_kpar(
+            &project,
+            tmp.path().join("test.kpar"),
+            KparCompressionMethod::default(),
+            true,
+            false,
+        )
+        .unwrap();
+    });
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        [
+            ProgressEvent {
+                phase: ProgressPhase::Build,
+                current: 1,
+                total: Some(2),
+                message: "progress_a.sysml".into(),
+            },
+            ProgressEvent {
+                phase: ProgressPhase::Build,
+                current: 2,
+                total: Some(2),
+                message: "progress_b.sysml".into(),
+            },
+        ]
+    );
+}
@@ -6,15 +6,11 @@ use std::{result::Result, sync::Arc};
 use camino::Utf8Path;
 use reqwest_middleware::ClientWithMiddleware;
 use thiserror::Error;
-use typed_path::Utf8UnixPath;
 
 use crate::{
     auth::HTTPAuthentication,
     config::OverrideSource,
-    context::ProjectContext,
     env::memory::MemoryStorageEnvironment,
-    lock::Source,
-    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
     project::{
         AsSyncProjectTokio, ProjectRead, ProjectReadAsync,
         editable::EditableProject,
@@ -109,13 +105,13 @@ impl<Policy: HTTPAuthentication> AnyProject<Policy> {
                 .to_tokio_sync(runtime),
             )),
             OverrideSource::RemoteSrc { remote_src } => Ok(AnyProject::RemoteSrc(
-                ReqwestSrcProjectAsync::<Policy> {
+                ReqwestSrcProjectAsync::<Policy>::new(
                     client,
-                    url: reqwest::Url::parse(&remote_src)
+                    reqwest::Url::parse(&remote_src)
                         .map_err(|e| TryFromSourceError::UrlParse(remote_src, e))?,
                     auth_policy,
-                    expected_checksum: None,
-                }
+                    None,
+                )
                 .to_tokio_sync(runtime),
             )),
             OverrideSource::RemoteGit { remote_git } => Ok(AnyProject::RemoteGit(
@@ -10,7 +10,7 @@ use crate::{
     project::ProjectRead,
 };
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 /// Treat a project type `P` as an "Editable" project. This simply adds
 /// a `source` pointing to the nominal path `nominal_path` when
 /// this project is in a lockfile.
@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
 
-use std::num::NonZero;
+use std::{num::NonZero, sync::Mutex};
 
 use camino::Utf8PathBuf;
 use gix::{prepare_clone, remote::fetch::Shallow};
@@ -16,6 +16,7 @@ use crate::{
         local_src::{LocalSrcError, LocalSrcProject, PathError},
         utils::{FileWithLifetime, RelativizePathError, ToPathBuf},
     },
+    resolve::git_cache::{GitCache, GitCacheError},
 };
 
 use super::utils::{FsIoError, ProjectDeserializationError, ProjectSerializationError, wrapfs};
@@ -25,6 +26,10 @@ pub struct GixDownloadedProject {
     pub url: gix::Url,
     tmp_dir: camino_tempfile::Utf8TempDir,
     inner: LocalSrcProject,
+    cache: Option<GitCache>,
+    rev: Option<String>,
+    /// Id of the checked out commit, once downloaded
+    downloaded: Mutex<Option<String>>,
 }
 
 #[derive(Error, Debug)]
@@ -45,6 +50,10 @@ pub enum GixDownloadedError {
     Fetch(String, Box<gix::clone::fetch::Error>),
     #[error("git checkout in temporary directory `{0}` failed: {1}")]
     Checkout(Utf8PathBuf, Box<gix::clone::checkout::main_worktree::Error>),
+    #[error("failed to resolve the commit checked out from `{0}`: {1}")]
+    Head(String, Box<gix::reference::head_id::Error>),
+    #[error(transparent)]
+    Cache(#[from] GitCacheError),
     #[error(
         "cannot construct a relative path from the workspace/project
         directory to one of its dependencies' directory:\n\
@@ -95,11 +104,44 @@ impl GixDownloadedProject {
                 expected_checksum: None,
             },
             tmp_dir,
+            cache: None,
+            rev: None,
+            downloaded: Mutex::new(None),
         })
     }
 
-    fn ensure_downloaded(&self) -> Result<(), GixDownloadedError> {
-        if !self.tmp_dir.path().join(".git").is_dir() {
+    /// Check out the project from a mirror in `cache` instead of cloning
+    /// it directly
+    pub fn with_cache(mut self, cache: Option<GitCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Check out commit `rev` instead of the default branch
+    pub fn with_rev(mut self, rev: Option<String>) -> Self {
+        self.rev = rev;
+        self
+    }
+
+    /// Download the project if needed, returning the checked out commit
+    fn ensure_downloaded(&self) -> Result<String, GixDownloadedError> {
+        let mut downloaded = self.downloaded.lock().unwrap();
+        if let Some(commit) = &*downloaded {
+            return Ok(commit.clone());
+        }
+
+        let commit = if let Some(cache) = &self.cache {
+            cache.checkout_rev(&self.url, self.tmp_dir.path(), self.rev.as_deref())?
+        } else if let Some(rev) = &self.rev {
+            // A shallow clone cannot reach arbitrary commits, so use a
+            // throwaway mirror
+            let mirror_dir = camino_tempfile::tempdir().map_err(FsIoError::MkTempDir)?;
+            GitCache::new(mirror_dir.path()).checkout_rev(
+                &self.url,
+                self.tmp_dir.path(),
+                Some(rev),
+            )?
+        } else {
             let prepared_clone = prepare_clone(self.url.clone(), self.tmp_dir.path())
                 .map_err(|e| GixDownloadedError::Clone(self.url.to_string(), Box::new(e)))?;
 
@@ -107,14 +149,18 @@ impl GixDownloadedProject {
                 .with_shallow(Shallow::DepthAtRemote(NonZero::new(1).unwrap()))
                 .fetch_then_checkout(gix::progress::Discard, &gix::interrupt::IS_INTERRUPTED)
                 .map_err(|e| GixDownloadedError::Fetch(self.url.to_string(), Box::new(e)))?;
-            let (_repo, _) = prepare_checkout
+            let (repo, _) = prepare_checkout
                 .main_worktree(gix::progress::Discard, &gix::interrupt::IS_INTERRUPTED)
                 .map_err(|e| {
                     GixDownloadedError::Checkout(self.tmp_dir.to_path_buf(), Box::new(e))
                 })?;
-        }
+            repo.head_id()
+                .map_err(|e| GixDownloadedError::Head(self.url.to_string(), Box::new(e)))?
+                .to_string()
+        };
+        *downloaded = Some(commit.clone());
 
-        Ok(())
+        Ok(commit)
     }
 }
 
@@ -150,8 +196,10 @@ impl ProjectRead for GixDownloadedProject {
     }
 
     fn sources(&self, _ctx: &ProjectContext) -> Result<Vec<Source>, Self::Error> {
+        let commit = self.ensure_downloaded()?;
         Ok(vec![Source::RemoteGit {
             remote_git: self.url.to_string(),
+            rev: Some(commit),
         }])
     }
 
@@ -128,3 +128,55 @@ pub fn basic_gix_access() -> Result<(), Box<dyn std::error::Error>> {
     // server.kill()?;
     Ok(())
 }
+
+#[cfg(feature = "alltests")]
+#[test]
+pub fn gix_checks_out_locked_rev() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::{context::ProjectContext, lock::Source, project::utils::wrapfs};
+
+    let repo_dir = tempdir()?;
+    git_init(repo_dir.path())?;
+    let commit = |version: &str| -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(
+            repo_dir.path().join(".project.json"),
+            format!(r#"{{"name":"locked","version":"{version}"}}"#),
+        )?;
+        Command::new("git")
+            .args(["add", ".project.json"])
+            .current_dir(repo_dir.path())
+            .output()?
+            .assert()
+            .success();
+        Command::new("git")
+            .args(["commit", "-m", version])
+            .current_dir(repo_dir.path())
+            .output()?
+            .assert()
+            .success();
+        Ok(())
+    };
+    commit("1.0.0")?;
+
+    let url = format!("file://{}", wrapfs::canonicalize(repo_dir.path())?);
+    let project = GixDownloadedProject::new(&url)?;
+    let [Source::RemoteGit { rev: Some(rev), .. }] =
+        &project.sources(&ProjectContext::default())?[..]
+    else {
+        panic!("expected a git source with a revision");
+    };
+
+    commit("2.0.0")?;
+
+    let (Some(info), _) = GixDownloadedProject::new(&url)?.get_project()? else {
+        panic!("expected info");
+    };
+    assert_eq!(info.version, "2.0.0");
+
+    let locked = GixDownloadedProject::new(&url)?.with_rev(Some(rev.clone()));
+    let (Some(info), _) = locked.get_project()? else {
+        panic!("expected info");
+    };
+    assert_eq!(info.version, "1.0.0");
+
+    Ok(())
+}
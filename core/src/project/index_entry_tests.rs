@@ -45,6 +45,8 @@ fn make_fixture() -> IndexEntryProject<Unauthenticated> {
         usage: vec![InterchangeProjectUsageRaw::Resource {
             resource: format!("{PKG_SYSAND_PREFIX}acme/widget"),
             version_constraint: Some("^1.0".to_string()),
+            when: None,
+            hash: None,
         }],
         kpar_size: std::num::NonZeroU64::new(42).unwrap(),
         kpar_digest,
@@ -98,7 +100,9 @@ fn usage_async_returns_advertised_without_fetch() {
         usage[0],
         InterchangeProjectUsageRaw::Resource {
             resource: format!("{PKG_SYSAND_PREFIX}acme/widget"),
-            version_constraint: Some("^1.0".to_string())
+            version_constraint: Some("^1.0".to_string()),
+            when: None,
+            hash: None,
         }
     );
     assert!(!project.archive.is_downloaded_and_verified());
@@ -18,10 +18,11 @@ use zip::{ZipArchive, read::ZipFile, result::ZipError};
 
 use crate::{
     context::ProjectContext,
+    env::utils::{CloneError, optional_files, require_info_meta},
     lock::Source,
     model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
     project::{
-        self, KparMeta, ProjectChecksum, ProjectRead, hash_reader,
+        self, KparMeta, ProjectChecksum, ProjectMut, ProjectRead, hash_reader,
         utils::{RelativizePathError, ZipArchiveError, relativize_path},
     },
     utils::{lowercase_hex, sha256_lowercase_hex},
@@ -36,15 +37,30 @@ pub enum KparInnerPath {
     Root,
     /// Project is at a given path within archive
     Known(Utf8UnixPathBuf),
-    /// Project path within archive is unknown and has to be guessed
+    /// Project path within archive is unknown and has to be guessed.
+    /// A project at the archive root is preferred; otherwise the archive
+    /// must contain exactly one project
     Guess,
+    /// Project is the one within archive whose `.project.json` has the
+    /// given name
+    Named(String),
+}
+
+/// Project found within a KPAR, see [`kpar_project_roots`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KparProjectRoot {
+    /// Directory containing `.project.json`; empty for the archive root
+    pub root: Utf8UnixPathBuf,
+    /// Name from `.project.json`, if it could be read
+    pub name: Option<String>,
 }
 
 /// Project stored in as a KPar (Zip) archive in the local filesystem.
 /// Source file paths are resolved relative to and under `root`. Both
 /// `.project.json` and `.meta.json` are searched for in `root`.
 /// Use `LocalKParProject::new_guess_root` to guess `root` based on the
-/// presence of a (presumed unique) `.project.json`.
+/// presence of `.project.json`, or [`kpar_project_roots`] to list the
+/// projects of an archive containing several.
 ///
 /// Paths used in the archive are expected to match those used in the metadata
 /// manifest (.meta.json)! Sysand *MAY* try to normalize paths in order
@@ -117,6 +133,14 @@ pub enum LocalKParError {
     },
     #[error("kpar at `{path}` is an empty file")]
     EmptyKpar { path: Box<str> },
+    #[error("kpar at `{path}` contains several projects ({candidates}), select one by name")]
+    MultipleProjects { path: Box<str>, candidates: String },
+    #[error("kpar at `{path}` contains no project named `{name}`, only {candidates}")]
+    NoProjectNamed {
+        path: Box<str>,
+        name: String,
+        candidates: String,
+    },
 }
 
 impl From<FsIoError> for LocalKParError {
@@ -167,6 +191,20 @@ impl LocalKParProject {
         &self.archive_path
     }
 
+    /// Copy the project into `to`, see [`LocalKParProjectRaw::extract_into`].
+    /// The archive is verified against the expected size and digest first
+    pub fn extract_into<Q: ProjectMut>(
+        &self,
+        to: &mut Q,
+        overwrite: bool,
+    ) -> Result<
+        (InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw),
+        CloneError<LocalKParError, Q::Error>,
+    > {
+        let (raw, _) = self.ensure_initialized().map_err(CloneError::ProjectRead)?;
+        raw.extract_into(to, overwrite)
+    }
+
     fn ensure_initialized(&self) -> Result<&(LocalKParProjectRaw, KparMeta), LocalKParError> {
         // TODO: use `OnceCell::get_or_try_init()` once it's stable;
         // using `get_or_init()` directly requires us to always put the error into an `Arc` to
@@ -299,7 +337,7 @@ impl LocalKParProjectRaw {
             }
         };
         let mut archive = wrapfs::File::open(path)?;
-        let sha256_hex = match hash_reader(&mut archive) {
+        let sha256_hex = match hash_reader::<Sha256, _>(&mut archive) {
             Ok(digest) => lowercase_hex(digest),
             Err(e) => {
                 return Err(LocalKParError::Io(
@@ -315,13 +353,13 @@ impl LocalKParProjectRaw {
         let root = match root {
             KparInnerPath::Root => None,
             KparInnerPath::Known(path) => Some(path),
-            KparInnerPath::Guess => {
+            root => {
                 archive
                     .rewind()
                     .map_err(|e| FsIoError::Seek(path.to_owned(), 0, e))?;
                 let mut zip = zip::ZipArchive::new(archive)
-                    .map_err(|e| ZipArchiveError::ReadArchive(path.into(), e))?;
-                Some(guess_root(&mut zip)?)
+                    .map_err(|e| ZipArchiveError::read_archive(path.into(), e))?;
+                Some(select_root(&mut zip, path, &root)?)
             }
         };
 
@@ -346,8 +384,8 @@ impl LocalKParProjectRaw {
         let archive = wrapfs::File::open(path)?;
 
         let mut zip = zip::ZipArchive::new(archive)
-            .map_err(|e| ZipArchiveError::ReadArchive(path.into(), e))?;
-        let root = Some(guess_root(&mut zip)?);
+            .map_err(|e| ZipArchiveError::read_archive(path.into(), e))?;
+        let root = Some(select_root(&mut zip, path, &KparInnerPath::Guess)?);
 
         Ok(LocalKParProjectRaw {
             tmp_dir: tempdir().map_err(FsIoError::MkTempDir)?,
@@ -374,12 +412,12 @@ impl LocalKParProjectRaw {
         let root = match root {
             KparInnerPath::Root => None,
             KparInnerPath::Known(path) => Some(path),
-            KparInnerPath::Guess => {
+            root => {
                 let archive = wrapfs::File::open(path)?;
 
                 let mut zip = zip::ZipArchive::new(archive)
-                    .map_err(|e| ZipArchiveError::ReadArchive(path.into(), e))?;
-                Some(guess_root(&mut zip)?)
+                    .map_err(|e| ZipArchiveError::read_archive(path.into(), e))?;
+                Some(select_root(&mut zip, path, &root)?)
             }
         };
         Ok(LocalKParProjectRaw {
@@ -429,7 +467,58 @@ impl LocalKParProjectRaw {
 
     pub(crate) fn open_archive(&self) -> Result<ZipArchive<fs::File>, LocalKParError> {
         Ok(zip::ZipArchive::new(self.open_archive_file()?)
-            .map_err(|e| ZipArchiveError::ReadArchive(self.archive_path.as_path().into(), e))?)
+            .map_err(|e| ZipArchiveError::read_archive(self.archive_path.as_path().into(), e))?)
+    }
+
+    /// Copy the project into `to`. Unlike [`clone_project`], the archive is
+    /// opened once and each file is decompressed straight into `to`, without
+    /// extracting it to a temporary file first. Memory use does not depend
+    /// on the size of the files.
+    ///
+    /// [`clone_project`]: crate::env::utils::clone_project
+    pub fn extract_into<Q: ProjectMut>(
+        &self,
+        to: &mut Q,
+        overwrite: bool,
+    ) -> Result<
+        (InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw),
+        CloneError<LocalKParError, Q::Error>,
+    > {
+        let mut archive = self.open_archive().map_err(CloneError::ProjectRead)?;
+        let info = self
+            .get_parsed(&mut archive, ".project.json")
+            .map_err(CloneError::ProjectRead)?;
+        let meta = self
+            .get_parsed(&mut archive, ".meta.json")
+            .map_err(CloneError::ProjectRead)?;
+        let (info, meta) = require_info_meta((info, meta))?;
+        to.put_project(&info, &meta, overwrite)
+            .map_err(CloneError::EnvWrite)?;
+
+        let missing = |(path, err): (Utf8UnixPathBuf, ZipError)| {
+            CloneError::ProjectRead(LocalKParError::Zip(ZipArchiveError::named_file_meta(
+                path.into_string().into(),
+                err,
+            )))
+        };
+        for source_path in &meta.source_paths(true) {
+            let mut file = self
+                .get_relative(&mut archive, source_path)
+                .map_err(missing)?;
+            to.write_source(source_path, &mut file, overwrite)
+                .map_err(CloneError::EnvWrite)?;
+        }
+        for path in optional_files(&info) {
+            match self.get_relative(&mut archive, &path) {
+                Ok(mut file) => to
+                    .write_source(&path, &mut file, overwrite)
+                    .map_err(CloneError::EnvWrite)?,
+                Err((_, ZipError::FileNotFound)) => {}
+                Err(e) => return Err(missing(e)),
+            }
+        }
+
+        Ok((info, meta))
     }
 
     /// `path` must be relative and use Unix separators
@@ -458,7 +547,7 @@ impl LocalKParProjectRaw {
                 ProjectDeserializationError::new(path.as_ref().as_str(), e)
             })?)),
             Err((_, ZipError::FileNotFound)) => Ok(None),
-            Err((path, err)) => Err(LocalKParError::Zip(ZipArchiveError::NamedFileMeta(
+            Err((path, err)) => Err(LocalKParError::Zip(ZipArchiveError::named_file_meta(
                 path.into_string().into(),
                 err,
             ))),
@@ -518,7 +607,7 @@ impl ProjectRead for LocalKParProjectRaw {
             let mut archive = self.open_archive()?;
             let mut zip_file = self
                 .get_relative(&mut archive, path)
-                .map_err(|(p, e)| ZipArchiveError::NamedFileMeta(p.into_string().into(), e))?;
+                .map_err(|(p, e)| ZipArchiveError::named_file_meta(p.into_string().into(), e))?;
 
             std::io::copy(&mut zip_file, &mut tmp_file)
                 .map_err(|e| FsIoError::WriteFile(tmp_file_path.clone(), e))?;
@@ -543,13 +632,94 @@ impl ProjectRead for LocalKParProjectRaw {
     }
 }
 
-/// Guess the directory of the project within the zip archive. Project
+/// List the projects in the KPAR at `path`, i.e. the directories that
+/// contain `.project.json`, sorted by path
+pub fn kpar_project_roots<P: AsRef<Utf8Path>>(
+    path: P,
+) -> Result<Vec<KparProjectRoot>, LocalKParError> {
+    let path = path.as_ref();
+    let mut zip = zip::ZipArchive::new(wrapfs::File::open(path)?)
+        .map_err(|e| ZipArchiveError::read_archive(path.into(), e))?;
+    project_roots(&mut zip)
+}
+
+fn project_roots(
+    archive: &mut ZipArchive<fs::File>,
+) -> Result<Vec<KparProjectRoot>, LocalKParError> {
+    let mut roots = find_roots(archive)?;
+    roots.sort();
+    Ok(roots
+        .into_iter()
+        .map(|root| {
+            let info = root.join(".project.json");
+            let name = archive
+                .by_path(info.as_str())
+                .ok()
+                .and_then(|f| serde_json::from_reader::<_, InterchangeProjectInfoRaw>(f).ok())
+                .map(|info| info.name);
+            KparProjectRoot { root, name }
+        })
+        .collect())
+}
+
+/// Pick the directory of the project within the zip archive at `path`
+/// according to `root`, which must be `Guess` or `Named`. Returned path
+/// will be empty if the directory is archive root
+fn select_root(
+    archive: &mut ZipArchive<fs::File>,
+    path: &Utf8Path,
+    root: &KparInnerPath,
+) -> Result<Utf8UnixPathBuf, LocalKParError> {
+    let mut roots = project_roots(archive)?;
+    if roots.is_empty() {
+        return Err(LocalKParError::NotFound(".project.json".into()));
+    }
+    let candidates = || {
+        roots
+            .iter()
+            .map(|r| match (&r.name, r.root.as_str()) {
+                (Some(name), "") => format!("`{name}`"),
+                (Some(name), dir) => format!("`{name}` in `{dir}`"),
+                (None, "") => "unnamed project".to_owned(),
+                (None, dir) => format!("unnamed project in `{dir}`"),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match root {
+        KparInnerPath::Named(name) => {
+            match roots
+                .iter()
+                .position(|r| r.name.as_deref() == Some(name.as_str()))
+            {
+                Some(i) => Ok(roots.swap_remove(i).root),
+                None => Err(LocalKParError::NoProjectNamed {
+                    path: path.as_str().into(),
+                    name: name.clone(),
+                    candidates: candidates(),
+                }),
+            }
+        }
+        _ => {
+            if roots.len() == 1 || roots[0].root.as_str().is_empty() {
+                Ok(roots.swap_remove(0).root)
+            } else {
+                Err(LocalKParError::MultipleProjects {
+                    path: path.as_str().into(),
+                    candidates: candidates(),
+                })
+            }
+        }
+    }
+}
+
+/// Find the directories of the projects within the zip archive. Project
 /// directory here means any directory that contains `.project.json`.
-/// Returned path will be empty if the directory is archive root
-fn guess_root(archive: &mut ZipArchive<fs::File>) -> Result<Utf8UnixPathBuf, LocalKParError> {
-    let mut maybe_root = None;
+/// Returned paths will be empty if the directory is archive root
+fn find_roots(archive: &mut ZipArchive<fs::File>) -> Result<Vec<Utf8UnixPathBuf>, LocalKParError> {
+    let mut roots = vec![];
     for i in 0..archive.len() {
-        let file = archive.by_index(i).map_err(ZipArchiveError::FileMeta)?;
+        let file = archive.by_index_raw(i).map_err(ZipArchiveError::FileMeta)?;
 
         // TODO: do more sanitization here; enclosed_name() does some checks, but
         // it also makes the path OS-native, so Utf8UnixPath won't work with it.
@@ -558,17 +728,11 @@ fn guess_root(archive: &mut ZipArchive<fs::File>) -> Result<Utf8UnixPathBuf, Loc
         if file.enclosed_name().is_some() {
             let p = Utf8UnixPath::new(file.name());
             if let Some(root) = project_root_from_zip_entry_path(p)? {
-                maybe_root = Some(root);
-                break;
+                roots.push(root);
             }
         }
     }
-
-    if let Some(root) = maybe_root {
-        Ok(root)
-    } else {
-        Err(LocalKParError::NotFound(".project.json".into()))
-    }
+    Ok(roots)
 }
 
 fn project_root_from_zip_entry_path(
@@ -6,9 +6,9 @@ use std::io::{Read as _, Write};
 use camino_tempfile::tempdir;
 use zip::write::SimpleFileOptions;
 
-use crate::project::local_kpar::KparInnerPath;
+use crate::project::{local_kpar::KparInnerPath, memory::InMemoryProject, utils::ZipArchiveError};
 
-use super::ProjectRead;
+use super::{LocalKParError, ProjectRead};
 
 #[test]
 fn basic_kpar_archive() -> Result<(), Box<dyn std::error::Error>> {
@@ -106,3 +106,197 @@ fn project_root_uses_zip_path_separators() {
 
     assert_eq!(root, typed_path::Utf8UnixPath::new("some_root_dir"));
 }
+
+#[test]
+fn extract_into_streams_files() -> Result<(), Box<dyn std::error::Error>> {
+    let cwd = tempdir()?;
+    let zip_path = cwd.path().join("test.kpar");
+
+    {
+        let file = std::fs::File::create(&zip_path).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        let options = SimpleFileOptions::default();
+
+        zip.start_file("root/.project.json", options)?;
+        zip.write_all(br#"{"name":"extract","version":"1.2.3","license":"MIT"}"#)?;
+        zip.start_file("root/.meta.json", options)?;
+        zip.write_all(br#"{"index":{"Test":"test.sysml"},"created":"123"}"#)?;
+        zip.start_file("root/test.sysml", options)?;
+        zip.write_all(br#"package Test;"#)?;
+        zip.start_file("root/README.md", options)?;
+        zip.write_all(b"# Extract")?;
+
+        zip.finish().unwrap();
+    }
+
+    let project = super::LocalKParProject::new(zip_path, KparInnerPath::Guess, None, None);
+    let mut target = InMemoryProject::new();
+    let (info, _) = project.extract_into(&mut target, false)?;
+
+    assert_eq!(info.name, "extract");
+    assert_eq!(target.get_info()?.unwrap().name, "extract");
+    let mut src = String::new();
+    target.read_source("test.sysml")?.read_to_string(&mut src)?;
+    assert_eq!(src, "package Test;");
+    assert!(target.read_source("README.md").is_ok());
+    // Missing license text is skipped
+    assert!(target.read_source("LICENSES/MIT.txt").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn kpar_archive_with_many_entries() -> Result<(), Box<dyn std::error::Error>> {
+    let cwd = tempdir()?;
+    let zip_path = cwd.path().join("test.kpar");
+
+    // More entries than fit in the classic end of central directory record
+    let count = u16::MAX as usize + 10;
+    {
+        let file = std::fs::File::create(&zip_path).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        let options = SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored)
+            .unix_permissions(0o755);
+
+        zip.start_file(".project.json", options)?;
+        zip.write_all(br#"{"name":"many_entries","version":"1.2.3"}"#)?;
+        zip.start_file(".meta.json", options)?;
+        zip.write_all(br#"{"index":{},"created":"123"}"#)?;
+        for i in 0..count {
+            zip.start_file(format!("src/{i}.sysml"), options)?;
+            zip.write_all(format!("package P{i};").as_bytes())?;
+        }
+
+        zip.finish().unwrap();
+    }
+
+    let archive = zip::ZipArchive::new(std::fs::File::open(&zip_path)?)?;
+    assert_eq!(archive.len(), count + 2);
+
+    let project = super::LocalKParProject::new(zip_path, KparInnerPath::Guess, None, None);
+    assert_eq!(project.get_info()?.unwrap().name, "many_entries");
+
+    let mut src = String::new();
+    project
+        .read_source(format!("src/{}.sysml", count - 1))?
+        .read_to_string(&mut src)?;
+    assert_eq!(src, format!("package P{};", count - 1));
+
+    Ok(())
+}
+
+#[test]
+fn unsupported_compression_method_is_reported() -> Result<(), Box<dyn std::error::Error>> {
+    let cwd = tempdir()?;
+    let zip_path = cwd.path().join("test.kpar");
+
+    {
+        let file = std::fs::File::create(&zip_path).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        let options = SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored)
+            .unix_permissions(0o755);
+
+        zip.start_file("test.sysml", options)?;
+        zip.write_all(br#"package Test;"#)?;
+        zip.start_file(".project.json", options)?;
+        zip.write_all(br#"{"name":"unsupported","version":"1.2.3"}"#)?;
+        zip.start_file(".meta.json", options)?;
+        zip.write_all(br#"{"index":{},"created":"123"}"#)?;
+
+        zip.finish().unwrap();
+    }
+
+    // Mark `test.sysml` as WavPack compressed, which `zip` cannot read
+    let mut bytes = std::fs::read(&zip_path)?;
+    bytes[8..10].copy_from_slice(&97u16.to_le_bytes());
+    let end = bytes.windows(4).rposition(|w| w == b"PK\x05\x06").unwrap();
+    let central = u32::from_le_bytes(bytes[end + 16..end + 20].try_into()?) as usize;
+    bytes[central + 10..central + 12].copy_from_slice(&97u16.to_le_bytes());
+    std::fs::write(&zip_path, bytes)?;
+
+    let project = super::LocalKParProject::new(zip_path, KparInnerPath::Root, None, None);
+    assert_eq!(project.get_info()?.unwrap().name, "unsupported");
+    let err = project.read_source("test.sysml").err().unwrap();
+    assert!(
+        matches!(
+            &err,
+            LocalKParError::Zip(ZipArchiveError::Unsupported(name, _)) if &**name == "test.sysml"
+        ),
+        "{err:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn kpar_archive_with_several_projects() -> Result<(), Box<dyn std::error::Error>> {
+    let cwd = tempdir()?;
+    let zip_path = cwd.path().join("test.kpar");
+
+    {
+        let file = std::fs::File::create(&zip_path).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        let options = SimpleFileOptions::default();
+
+        for name in ["b", "a"] {
+            zip.start_file(format!("{name}/.project.json"), options)?;
+            write!(zip, r#"{{"name":"{name}","version":"1.2.3"}}"#)?;
+            zip.start_file(format!("{name}/.meta.json"), options)?;
+            zip.write_all(br#"{"index":{},"created":"123"}"#)?;
+        }
+
+        zip.finish().unwrap();
+    }
+
+    let roots = super::kpar_project_roots(&zip_path)?;
+    let names: Vec<_> = roots.iter().map(|r| r.name.as_deref()).collect();
+    assert_eq!(names, [Some("a"), Some("b")]);
+    assert_eq!(roots[1].root.as_str(), "b");
+
+    let guessed = super::LocalKParProject::new(&zip_path, KparInnerPath::Guess, None, None);
+    assert!(matches!(
+        guessed.get_info(),
+        Err(LocalKParError::MultipleProjects { .. })
+    ));
+
+    let named =
+        super::LocalKParProject::new(&zip_path, KparInnerPath::Named("b".into()), None, None);
+    assert_eq!(named.get_info()?.unwrap().name, "b");
+
+    let missing =
+        super::LocalKParProject::new(&zip_path, KparInnerPath::Named("c".into()), None, None);
+    assert!(matches!(
+        missing.get_info(),
+        Err(LocalKParError::NoProjectNamed { .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn kpar_archive_prefers_project_at_root() -> Result<(), Box<dyn std::error::Error>> {
+    let cwd = tempdir()?;
+    let zip_path = cwd.path().join("test.kpar");
+
+    {
+        let file = std::fs::File::create(&zip_path).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        let options = SimpleFileOptions::default();
+
+        zip.start_file("vendored/.project.json", options)?;
+        zip.write_all(br#"{"name":"vendored","version":"1.2.3"}"#)?;
+        zip.start_file(".project.json", options)?;
+        zip.write_all(br#"{"name":"top","version":"1.2.3"}"#)?;
+        zip.start_file(".meta.json", options)?;
+        zip.write_all(br#"{"index":{},"created":"123"}"#)?;
+
+        zip.finish().unwrap();
+    }
+
+    let project = super::LocalKParProject::new(zip_path, KparInnerPath::Guess, None, None);
+    assert_eq!(project.get_info()?.unwrap().name, "top");
+
+    Ok(())
+}
@@ -302,6 +302,27 @@ impl ProjectMut for LocalSrcProject {
 
         Ok(())
     }
+
+    fn move_source<P: AsRef<Utf8UnixPath>, Q: AsRef<Utf8UnixPath>>(
+        &mut self,
+        from: P,
+        to: Q,
+    ) -> Result<(), LocalSrcError> {
+        let from_path = self.get_source_path(from)?;
+        let to_path = self.get_source_path(to)?;
+        if to_path.exists() {
+            return Err(LocalSrcError::AlreadyExists(format!(
+                "`{to_path}` already exists"
+            )));
+        }
+
+        if let Some(parents) = to_path.parent() {
+            wrapfs::create_dir_all(parents)?;
+        }
+        wrapfs::rename(from_path, to_path)?;
+
+        Ok(())
+    }
 }
 
 #[derive(Error, Debug)]
@@ -115,6 +115,26 @@ impl ProjectMut for InMemoryProject {
 
         Ok(())
     }
+
+    fn move_source<P: AsRef<Utf8UnixPath>, Q: AsRef<Utf8UnixPath>>(
+        &mut self,
+        from: P,
+        to: Q,
+    ) -> Result<(), InMemoryError> {
+        let (from, to) = (from.as_ref(), to.as_ref());
+        if self.files.contains_key(to) {
+            return Err(InMemoryError::AlreadyExists(format!(
+                "`{to}` already exists"
+            )));
+        }
+        let contents = self
+            .files
+            .remove(from)
+            .ok_or_else(|| InMemoryError::FileNotFound(from.to_owned()))?;
+        self.files.insert(to.to_owned(), contents);
+
+        Ok(())
+    }
 }
 
 #[derive(Error, Debug)]
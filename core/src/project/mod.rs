@@ -2,10 +2,10 @@
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
 use camino::Utf8Path;
-use digest::{array::Array, typenum};
-use futures::io::{AsyncBufReadExt as _, AsyncRead};
+use digest::Output;
+use futures::io::AsyncBufReadExt as _;
 use indexmap::IndexMap;
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
 use std::{
     fmt::{Debug, Display},
     io::{self, BufRead as _, BufReader, Read},
@@ -18,6 +18,11 @@ use utils::FsIoError;
 
 pub use sysand_macros::ProjectMut;
 pub use sysand_macros::ProjectRead;
+pub use sysand_macros::ProjectReadAsync;
+// Named by the expansions of the derives in `sysand_macros`
+#[doc(hidden)]
+pub use futures::io::AsyncRead;
+#[doc(hidden)]
 pub use typed_path::Utf8UnixPath;
 
 use crate::{
@@ -25,10 +30,10 @@ use crate::{
     env::utils::ErrorBound,
     lock::Source,
     model::{
-        InterchangeProjectChecksumRaw, InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw,
-        InterchangeProjectUsageRaw, KerMlChecksumAlg, ProjectHash, project_hash_hex,
+        HashAlg, InterchangeProjectChecksumRaw, InterchangeProjectInfoRaw,
+        InterchangeProjectMetadataRaw, InterchangeProjectUsageRaw, project_hash_hex,
     },
-    utils::lowercase_hex,
+    timings::{self, Phase},
 };
 
 // Implementations
@@ -52,6 +57,8 @@ pub mod reqwest_kpar_download;
 // pub mod reqwest_kpar_ranged;
 #[cfg(feature = "networking")]
 pub mod reqwest_src;
+pub mod variant;
+pub mod vfs;
 
 // Generic implementations
 pub mod cached;
@@ -65,9 +72,10 @@ pub struct KparMeta {
     pub sha256_hex: String,
 }
 
-/// Produce a SHA-256 digest by hashing all the contents of `reader`
-pub(crate) fn hash_reader<R: Read>(reader: &mut R) -> Result<Array<u8, typenum::U32>, io::Error> {
-    let mut hasher = Sha256::new();
+/// Produce a digest by hashing all the contents of `reader` with `D`
+pub(crate) fn hash_reader<D: Digest, R: Read>(reader: &mut R) -> Result<Output<D>, io::Error> {
+    let _timer = timings::time(Phase::Hashing);
+    let mut hasher = D::new();
     let mut buffered = BufReader::new(reader);
 
     loop {
@@ -86,8 +94,11 @@ pub(crate) fn hash_reader<R: Read>(reader: &mut R) -> Result<Array<u8, typenum::
     Ok(hasher.finalize())
 }
 
-async fn hash_reader_async<R: AsyncRead + Unpin>(reader: &mut R) -> Result<ProjectHash, io::Error> {
-    let mut hasher = Sha256::new();
+async fn hash_reader_async<D: Digest, R: AsyncRead + Unpin>(
+    reader: &mut R,
+) -> Result<Output<D>, io::Error> {
+    let _timer = timings::time(Phase::Hashing);
+    let mut hasher = D::new();
     let mut buffered = futures::io::BufReader::new(reader);
 
     loop {
@@ -106,6 +117,31 @@ async fn hash_reader_async<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Proje
     Ok(hasher.finalize())
 }
 
+/// Produce a lowercase hex digest by hashing all the contents of `reader`
+/// with `algorithm`
+pub fn hash_reader_hex<R: Read>(algorithm: HashAlg, reader: &mut R) -> Result<String, io::Error> {
+    Ok(match algorithm {
+        HashAlg::Sha224 => hex::encode(hash_reader::<Sha224, _>(reader)?),
+        HashAlg::Sha256 => hex::encode(hash_reader::<Sha256, _>(reader)?),
+        HashAlg::Sha384 => hex::encode(hash_reader::<Sha384, _>(reader)?),
+        HashAlg::Sha512 => hex::encode(hash_reader::<Sha512, _>(reader)?),
+        HashAlg::Blake3 => hex::encode(hash_reader::<blake3::Hasher, _>(reader)?),
+    })
+}
+
+async fn hash_reader_hex_async<R: AsyncRead + Unpin>(
+    algorithm: HashAlg,
+    reader: &mut R,
+) -> Result<String, io::Error> {
+    Ok(match algorithm {
+        HashAlg::Sha224 => hex::encode(hash_reader_async::<Sha224, _>(reader).await?),
+        HashAlg::Sha256 => hex::encode(hash_reader_async::<Sha256, _>(reader).await?),
+        HashAlg::Sha384 => hex::encode(hash_reader_async::<Sha384, _>(reader).await?),
+        HashAlg::Sha512 => hex::encode(hash_reader_async::<Sha512, _>(reader).await?),
+        HashAlg::Blake3 => hex::encode(hash_reader_async::<blake3::Hasher, _>(reader).await?),
+    })
+}
+
 #[derive(Error, Debug)]
 pub enum CanonicalizationError<ReadError: ErrorBound> {
     #[error(transparent)]
@@ -248,6 +284,16 @@ pub trait ProjectRead {
     /// Produces canonicalized project metadata, replacing all source file hashes by SHA256.
     fn canonical_meta(
         &self,
+    ) -> Result<Option<InterchangeProjectMetadataRaw>, CanonicalizationError<Self::Error>> {
+        self.canonical_meta_with(HashAlg::Sha256)
+    }
+
+    /// Produces project metadata with all source file hashes replaced by
+    /// `algorithm` hashes. Hashes already using `algorithm` are not
+    /// recomputed.
+    fn canonical_meta_with(
+        &self,
+        algorithm: HashAlg,
     ) -> Result<Option<InterchangeProjectMetadataRaw>, CanonicalizationError<Self::Error>> {
         let Some(mut meta) = self
             .get_meta()
@@ -256,23 +302,21 @@ pub trait ProjectRead {
             return Ok(None);
         };
 
+        let algorithm_name = algorithm.to_string();
         for (path, checksum) in meta
             .checksum
             .as_mut()
             .into_iter()
             .flat_map(|index| index.iter_mut())
         {
-            let sha256: &str = KerMlChecksumAlg::Sha256.into();
-            if checksum.algorithm != sha256 {
-                checksum.algorithm = sha256.to_owned();
+            if checksum.algorithm != algorithm_name {
+                checksum.algorithm = algorithm_name.clone();
 
                 let mut src = self
                     .read_source(path)
                     .map_err(CanonicalizationError::ProjectRead)?;
-                checksum.value = lowercase_hex(
-                    hash_reader(&mut src)
-                        .map_err(|e| CanonicalizationError::FileRead(path.as_str().into(), e))?,
-                );
+                checksum.value = hash_reader_hex(algorithm, &mut src)
+                    .map_err(|e| CanonicalizationError::FileRead(path.as_str().into(), e))?;
             } else {
                 checksum.value = checksum.value.to_lowercase();
             }
@@ -386,6 +430,13 @@ impl<T: ProjectRead> ProjectRead for &T {
         (*self).canonical_meta()
     }
 
+    fn canonical_meta_with(
+        &self,
+        algorithm: HashAlg,
+    ) -> Result<Option<InterchangeProjectMetadataRaw>, CanonicalizationError<Self::Error>> {
+        (*self).canonical_meta_with(algorithm)
+    }
+
     fn checksum_non_canonical_hex(&self) -> Result<Option<String>, Self::Error> {
         (*self).checksum_non_canonical_hex()
     }
@@ -466,6 +517,13 @@ impl<T: ProjectRead> ProjectRead for &mut T {
         (**self).canonical_meta()
     }
 
+    fn canonical_meta_with(
+        &self,
+        algorithm: HashAlg,
+    ) -> Result<Option<InterchangeProjectMetadataRaw>, CanonicalizationError<Self::Error>> {
+        (**self).canonical_meta_with(algorithm)
+    }
+
     fn checksum_non_canonical_hex(&self) -> Result<Option<String>, Self::Error> {
         (**self).checksum_non_canonical_hex()
     }
@@ -571,6 +629,18 @@ pub trait ProjectReadAsync {
         &self,
     ) -> impl Future<
         Output = Result<Option<InterchangeProjectMetadataRaw>, CanonicalizationError<Self::Error>>,
+    > {
+        self.canonical_meta_with_async(HashAlg::Sha256)
+    }
+
+    /// Produces project metadata with all source file hashes replaced by
+    /// `algorithm` hashes. Hashes already using `algorithm` are not
+    /// recomputed.
+    fn canonical_meta_with_async(
+        &self,
+        algorithm: HashAlg,
+    ) -> impl Future<
+        Output = Result<Option<InterchangeProjectMetadataRaw>, CanonicalizationError<Self::Error>>,
     > {
         async move {
             let Some(mut meta) = self
@@ -582,19 +652,19 @@ pub trait ProjectReadAsync {
             };
 
             if let Some(mut checksums) = meta.checksum {
+                let algorithm_name = algorithm.to_string();
+                let algorithm_name = &algorithm_name;
                 let future_checksums = checksums.drain(..).map(|(path, mut checksum)| async move {
-                    let sha256: &str = KerMlChecksumAlg::Sha256.into();
-                    if checksum.algorithm != sha256 {
-                        checksum.algorithm = sha256.to_owned();
+                    if &checksum.algorithm != algorithm_name {
+                        checksum.algorithm = algorithm_name.clone();
 
                         let mut src = self
                             .read_source_async(&path)
                             .await
                             .map_err(CanonicalizationError::ProjectRead)?;
-                        checksum.value =
-                            lowercase_hex(hash_reader_async(&mut src).await.map_err(|e| {
-                                CanonicalizationError::FileRead(path.clone().into(), e)
-                            })?);
+                        checksum.value = hash_reader_hex_async(algorithm, &mut src)
+                            .await
+                            .map_err(|e| CanonicalizationError::FileRead(path.clone().into(), e))?;
                     } else {
                         checksum.value = checksum.value.to_lowercase();
                     }
@@ -743,6 +813,15 @@ impl<T: ProjectReadAsync> ProjectReadAsync for &T {
         (**self).canonical_meta_async()
     }
 
+    fn canonical_meta_with_async(
+        &self,
+        algorithm: HashAlg,
+    ) -> impl Future<
+        Output = Result<Option<InterchangeProjectMetadataRaw>, CanonicalizationError<Self::Error>>,
+    > {
+        (**self).canonical_meta_with_async(algorithm)
+    }
+
     fn checksum_non_canonical_hex_async(
         &self,
     ) -> impl Future<Output = Result<Option<String>, Self::Error>> {
@@ -843,6 +922,124 @@ impl<T: ProjectReadAsync> ProjectReadAsync for &mut T {
         (**self).canonical_meta_async()
     }
 
+    fn canonical_meta_with_async(
+        &self,
+        algorithm: HashAlg,
+    ) -> impl Future<
+        Output = Result<Option<InterchangeProjectMetadataRaw>, CanonicalizationError<Self::Error>>,
+    > {
+        (**self).canonical_meta_with_async(algorithm)
+    }
+
+    fn checksum_non_canonical_hex_async(
+        &self,
+    ) -> impl Future<Output = Result<Option<String>, Self::Error>> {
+        (**self).checksum_non_canonical_hex_async()
+    }
+
+    fn checksum_canonical_hex_async(
+        &self,
+    ) -> impl Future<Output = Result<Option<String>, CanonicalizationError<Self::Error>>> {
+        (**self).checksum_canonical_hex_async()
+    }
+
+    fn checksum_canonical_variant_async(
+        &self,
+    ) -> impl Future<Output = Result<ProjectChecksum, Self::Error>> {
+        (**self).checksum_canonical_variant_async()
+    }
+}
+
+impl<T: ProjectReadAsync> ProjectReadAsync for Box<T> {
+    type Error = T::Error;
+
+    fn get_project_async(
+        &self,
+    ) -> impl Future<
+        Output = Result<
+            (
+                Option<InterchangeProjectInfoRaw>,
+                Option<InterchangeProjectMetadataRaw>,
+            ),
+            Self::Error,
+        >,
+    > {
+        (**self).get_project_async()
+    }
+
+    type SourceReader<'a>
+        = T::SourceReader<'a>
+    where
+        Self: 'a;
+
+    fn read_source_async<P: AsRef<Utf8UnixPath>>(
+        &self,
+        path: P,
+    ) -> impl Future<Output = Result<Self::SourceReader<'_>, Self::Error>> {
+        (**self).read_source_async(path)
+    }
+
+    fn sources_async(
+        &self,
+        ctx: &ProjectContext,
+    ) -> impl Future<Output = Result<Vec<Source>, Self::Error>> {
+        (**self).sources_async(ctx)
+    }
+
+    fn get_info_async(
+        &self,
+    ) -> impl Future<Output = Result<Option<InterchangeProjectInfoRaw>, Self::Error>> {
+        (**self).get_info_async()
+    }
+
+    fn get_meta_async(
+        &self,
+    ) -> impl Future<Output = Result<Option<InterchangeProjectMetadataRaw>, Self::Error>> {
+        (**self).get_meta_async()
+    }
+
+    fn name_async(&self) -> impl Future<Output = Result<Option<String>, Self::Error>> {
+        (**self).name_async()
+    }
+
+    fn is_definitely_invalid_async(&self) -> impl Future<Output = bool> {
+        (**self).is_definitely_invalid_async()
+    }
+
+    fn version_async(&self) -> impl Future<Output = Result<Option<String>, Self::Error>> {
+        (**self).version_async()
+    }
+
+    fn usage_async(
+        &self,
+    ) -> impl Future<Output = Result<Option<Vec<InterchangeProjectUsageRaw>>, Self::Error>> {
+        (**self).usage_async()
+    }
+
+    fn checksum_async(
+        &self,
+    ) -> impl Future<Output = Result<Option<IndexMap<String, InterchangeProjectChecksumRaw>>, Self::Error>>
+    {
+        (**self).checksum_async()
+    }
+
+    fn canonical_meta_async(
+        &self,
+    ) -> impl Future<
+        Output = Result<Option<InterchangeProjectMetadataRaw>, CanonicalizationError<Self::Error>>,
+    > {
+        (**self).canonical_meta_async()
+    }
+
+    fn canonical_meta_with_async(
+        &self,
+        algorithm: HashAlg,
+    ) -> impl Future<
+        Output = Result<Option<InterchangeProjectMetadataRaw>, CanonicalizationError<Self::Error>>,
+    > {
+        (**self).canonical_meta_with_async(algorithm)
+    }
+
     fn checksum_non_canonical_hex_async(
         &self,
     ) -> impl Future<Output = Result<Option<String>, Self::Error>> {
@@ -906,6 +1103,13 @@ pub trait ProjectMut: ProjectRead {
         source: &mut R,
         overwrite: bool,
     ) -> Result<(), Self::Error>;
+
+    /// Move the source file at `from` to `to`, which must not exist yet
+    fn move_source<P: AsRef<Utf8UnixPath>, Q: AsRef<Utf8UnixPath>>(
+        &mut self,
+        from: P,
+        to: Q,
+    ) -> Result<(), Self::Error>;
 }
 
 // ----- Blanket trait impls -----
@@ -936,6 +1140,14 @@ impl<T: ProjectMut> ProjectMut for &mut T {
         (**self).write_source(path, source, overwrite)
     }
 
+    fn move_source<P: AsRef<Utf8UnixPath>, Q: AsRef<Utf8UnixPath>>(
+        &mut self,
+        from: P,
+        to: Q,
+    ) -> Result<(), Self::Error> {
+        (**self).move_source(from, to)
+    }
+
     fn put_project(
         &mut self,
         info: &InterchangeProjectInfoRaw,
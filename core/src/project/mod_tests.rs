@@ -4,14 +4,15 @@
 use std::collections::HashMap;
 
 use indexmap::IndexMap;
+use sha2::Sha256;
 use typed_path::Utf8UnixPath;
 
 use crate::{
     model::{
-        InterchangeProjectChecksumRaw, InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw,
-        KerMlChecksumAlg,
+        HashAlg, InterchangeProjectChecksumRaw, InterchangeProjectInfoRaw,
+        InterchangeProjectMetadataRaw, KerMlChecksumAlg,
     },
-    project::{ProjectRead, hash_reader, memory::InMemoryProject},
+    project::{ProjectRead, hash_reader, hash_reader_hex, memory::InMemoryProject},
     utils::lowercase_hex,
 };
 
@@ -21,13 +22,49 @@ fn sanity_check_hasher() -> Result<(), Box<dyn std::error::Error>> {
 
     // echo -n "FooBarBaz" | sha256sum
     assert_eq!(
-        lowercase_hex(hash_reader(&mut std::io::Cursor::new(input))?),
+        lowercase_hex(hash_reader::<Sha256, _>(&mut std::io::Cursor::new(input))?),
         "4da8b89a905445e96dd0ab6c9be9a72c8b0ffc686a57a3cc6808a8952a3560ed"
     );
 
     Ok(())
 }
 
+#[test]
+fn hash_reader_hex_algorithms() -> Result<(), Box<dyn std::error::Error>> {
+    // Test vectors for "abc" from FIPS 180-4 and the BLAKE3 reference
+    for (algorithm, expected) in [
+        (
+            HashAlg::Sha224,
+            "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7",
+        ),
+        (
+            HashAlg::Sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        ),
+        (
+            HashAlg::Sha384,
+            "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed\
+            8086072ba1e7cc2358baeca134c825a7",
+        ),
+        (
+            HashAlg::Sha512,
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+            2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+        ),
+        (
+            HashAlg::Blake3,
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85",
+        ),
+    ] {
+        let actual = hash_reader_hex(algorithm, &mut std::io::Cursor::new("abc"))?;
+        assert_eq!(actual, expected, "{algorithm}");
+        let expected_len = KerMlChecksumAlg::from(algorithm).expected_hex_len();
+        assert_eq!(Some(actual.len()), expected_len.map(usize::from));
+    }
+
+    Ok(())
+}
+
 #[test]
 fn canonicalization_no_checksums() -> Result<(), Box<dyn std::error::Error>> {
     let project = InMemoryProject {
@@ -55,6 +92,7 @@ fn canonicalization_no_checksums() -> Result<(), Box<dyn std::error::Error>> {
                     value: "".to_string(),
                 },
             )])),
+            variants: IndexMap::new(),
         }),
         files: HashMap::from([(
             Utf8UnixPath::new("MyFile.txt").to_path_buf(),
@@ -2,7 +2,6 @@
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
 use std::{
-    error::Error,
     io::{self, Write as _},
     num::NonZeroU64,
     pin::Pin,
@@ -19,11 +18,13 @@ use crate::{
     context::ProjectContext,
     lock::Source,
     model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
+    progress::{self, ProgressPhase},
     project::{
         KparMeta, ProjectRead, ProjectReadAsync,
         local_kpar::{LocalKParError, LocalKParProject, LocalKParProjectRaw},
     },
-    resolve::net_utils::kpar_get_request,
+    resolve::net_utils::{HeadProbe, kpar_get_request, kpar_head_request, probe_head},
+    timings::{self, Phase},
     utils::lowercase_hex,
 };
 
@@ -101,7 +102,10 @@ pub struct ReqwestIndexKparDownloadedProject<Policy> {
 // TODO: reduce size of errors here and elsewhere
 #[derive(Error, Debug)]
 pub enum ReqwestKparDownloadedError {
-    #[error("HTTP request to `{url}` returned status {status}")]
+    #[error(
+        "HTTP request to `{url}` returned status {status}{}",
+        crate::auth::auth_status_hint(*status)
+    )]
     BadHttpStatus {
         url: Box<str>,
         status: reqwest::StatusCode,
@@ -140,6 +144,20 @@ impl From<FsIoError> for ReqwestKparDownloadedError {
     }
 }
 
+/// Whether downloading a KPAR would definitely fail, judging by a HEAD
+/// request for it. This avoids downloading KPARs only to find out that
+/// they are missing or are something else, e.g. an HTML page
+fn is_invalid_kpar_probe(probe: HeadProbe, expected_size: Option<u64>) -> bool {
+    match probe {
+        HeadProbe::Invalid => true,
+        HeadProbe::Present { size: Some(0) } => true,
+        HeadProbe::Present { size: Some(size) } => {
+            expected_size.is_some_and(|expected| expected != size)
+        }
+        HeadProbe::Present { size: None } | HeadProbe::Unknown => false,
+    }
+}
+
 impl<Policy: HTTPAuthentication> ReqwestRemoteKparDownloadedProject<Policy> {
     // TODO: indicate to `inner` that it should not guess root
     // Also decide whether to take URL or str in all constructors here, and
@@ -223,6 +241,7 @@ impl<Policy: HTTPAuthentication> ReqwestRemoteKparDownloadedProject<Policy> {
         let mut hasher = Sha256::new();
         let mut written = 0_u64;
 
+        let timer = timings::time(Phase::Download);
         while let Some(bytes) = bytes_stream.next().await {
             let bytes = bytes.map_err(ReqwestKparDownloadedError::Reqwest)?;
             written += bytes.len() as u64;
@@ -238,7 +257,14 @@ impl<Policy: HTTPAuthentication> ReqwestRemoteKparDownloadedProject<Policy> {
             hasher.update(&bytes);
             file.write_all(&bytes)
                 .map_err(|e| FsIoError::WriteFile(archive_path.clone(), e))?;
+            progress::report(
+                ProgressPhase::Download,
+                written,
+                self.expected.as_ref().map(|e| e.size_bytes.get()),
+                || self.url.as_str(),
+            );
         }
+        drop(timer);
         let written = if let Some(w) = NonZeroU64::new(written) {
             w
         } else {
@@ -371,21 +397,16 @@ impl<Policy: HTTPAuthentication> ProjectReadAsync for ReqwestRemoteKparDownloade
     }
 
     async fn is_definitely_invalid_async(&self) -> bool {
-        // FIXME: error should be returned
-        match self.ensure_downloaded_verified().await {
-            Ok((inner, _)) => inner.is_definitely_invalid(),
-            Err(e) => {
-                // TODO: generalize `format_sources()` to logging
-                log::debug!("error downloading/reading a kpar: {e}");
-                let mut error: &dyn Error = &e;
-                while let Some(source) = error.source() {
-                    log::debug!("  caused by: {source}");
-                    error = source;
-                }
-
-                false
-            }
+        if let Some((inner, _)) = self.downloaded_verified.get() {
+            return inner.is_definitely_invalid();
         }
+        let probe = probe_head(
+            &self.client,
+            self.auth_policy.as_ref(),
+            &kpar_head_request(self.url.clone()),
+        )
+        .await;
+        is_invalid_kpar_probe(probe, self.expected.as_ref().map(|e| e.size_bytes.get()))
     }
 
     async fn checksum_canonical_variant_async(&self) -> Result<ProjectChecksum, Self::Error> {
@@ -588,22 +609,16 @@ impl<Policy: HTTPAuthentication> ProjectReadAsync for ReqwestIndexKparDownloaded
     }
 
     async fn is_definitely_invalid_async(&self) -> bool {
-        // FIXME: error should be returned; does it make sense to download the project
-        // here, as this is supposed to be a quick check
-        match self.ensure_downloaded_verified().await {
-            Ok(inner) => inner.is_definitely_invalid(),
-            Err(e) => {
-                // TODO: generalize `format_sources()` to logging
-                log::debug!("error downloading/reading a kpar: {e}");
-                let mut error: &dyn Error = &e;
-                while let Some(source) = error.source() {
-                    log::debug!("  caused by: {source}");
-                    error = source;
-                }
-
-                false
-            }
+        if let Some(inner) = self.downloaded_verified.get() {
+            return inner.is_definitely_invalid();
         }
+        let probe = probe_head(
+            &self.client,
+            self.auth_policy.as_ref(),
+            &kpar_head_request(self.url.clone()),
+        )
+        .await;
+        is_invalid_kpar_probe(probe, Some(self.expected_size.get()))
     }
 
     async fn checksum_canonical_variant_async(&self) -> Result<ProjectChecksum, Self::Error> {
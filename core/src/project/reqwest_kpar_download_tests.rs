@@ -270,3 +270,91 @@ fn index_kpar_source_roundtrips_digest_and_size() -> Result<(), Box<dyn std::err
 
     Ok(())
 }
+
+#[test]
+fn head_probe_discards_kpars_without_downloading() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = mockito::Server::new();
+    let url = reqwest::Url::parse(&server.url())?;
+
+    let head_missing = server
+        .mock("HEAD", "/missing.kpar")
+        .with_status(404)
+        .expect(1)
+        .create();
+    let head_html = server
+        .mock("HEAD", "/login.kpar")
+        .with_status(200)
+        .with_header("content-type", "text/html; charset=utf-8")
+        .expect(1)
+        .create();
+    let head_unsupported = server
+        .mock("HEAD", "/no-head.kpar")
+        .with_status(405)
+        .expect(1)
+        .create();
+    let get_any = server.mock("GET", mockito::Matcher::Any).expect(0).create();
+
+    let runtime = Arc::new(
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?,
+    );
+    let project = |name: &str| {
+        ReqwestRemoteKparDownloadedProject::new_guess_root(
+            format!("{url}{name}"),
+            create_reqwest_client().unwrap(),
+            Arc::new(Unauthenticated {}),
+            None,
+        )
+        .unwrap()
+        .to_tokio_sync(runtime.clone())
+    };
+
+    assert!(project("missing.kpar").is_definitely_invalid());
+    assert!(project("login.kpar").is_definitely_invalid());
+    // Without HEAD support nothing definite can be said
+    assert!(!project("no-head.kpar").is_definitely_invalid());
+
+    head_missing.assert();
+    head_html.assert();
+    head_unsupported.assert();
+    get_any.assert();
+
+    Ok(())
+}
+
+#[test]
+fn head_probe_checks_index_kpar_size() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = mockito::Server::new();
+    let url = reqwest::Url::parse(&server.url())?;
+
+    let head_kpar = server
+        .mock("HEAD", "/project.kpar")
+        .with_status(200)
+        .with_header("content-type", "application/zip")
+        .with_header("content-length", "64")
+        .expect(2)
+        .create();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let project = |size: u64| {
+        ReqwestIndexKparDownloadedProject::new(
+            url.join("project.kpar").unwrap(),
+            create_reqwest_client().unwrap(),
+            Arc::new(Unauthenticated {}),
+            NonZeroU64::new(size).unwrap(),
+            "0".repeat(64),
+        )
+        .unwrap()
+    };
+
+    runtime.block_on(async {
+        assert!(!project(64).is_definitely_invalid_async().await);
+        assert!(project(65).is_definitely_invalid_async().await);
+    });
+    head_kpar.assert();
+
+    Ok(())
+}
@@ -7,15 +7,19 @@ use std::{io, marker::Send, pin::Pin, sync::Arc};
 
 use futures::{TryStreamExt, join};
 use thiserror::Error;
+use tokio::sync::OnceCell;
 use typed_path::Utf8UnixPath;
 
 use crate::{
     auth::HTTPAuthentication,
     context::ProjectContext,
     lock::Source,
-    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
+    model::{HashAlg, InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw, KerMlChecksumAlg},
     project::{CanonicalizationError, ProjectReadAsync, utils::FsIoError},
-    resolve::net_utils::{json_get_request, json_head_request, text_get_request},
+    resolve::net_utils::{
+        HeadProbe, json_get_request, json_head_request, probe_head, text_get_request,
+    },
+    utils::hash_lowercase_hex,
 };
 
 use super::ProjectChecksum;
@@ -30,6 +34,10 @@ use super::ProjectChecksum;
 /// /Mëkanïk/Kömmandöh.sysml
 /// is accessed by
 /// GET https://www.example.com/project/M%C3%ABkan%C3%AFk/K%C3%B6mmand%C3%B6h.sysml
+///
+/// Static file servers, e.g. nginx with `autoindex`, can serve projects this
+/// way. Source files with a checksum in `.meta.json` are verified against it
+/// when read.
 #[derive(Clone, Debug)]
 pub struct ReqwestSrcProjectAsync<Policy> {
     /// (reqwest) HTTP client to use for GET requests
@@ -38,9 +46,26 @@ pub struct ReqwestSrcProjectAsync<Policy> {
     pub url: reqwest::Url,
     pub auth_policy: Arc<Policy>,
     pub expected_checksum: Option<String>,
+    /// `.meta.json` of the project, fetched once
+    meta: Arc<OnceCell<Option<InterchangeProjectMetadataRaw>>>,
 }
 
 impl<Policy> ReqwestSrcProjectAsync<Policy> {
+    pub fn new(
+        client: reqwest_middleware::ClientWithMiddleware,
+        url: reqwest::Url,
+        auth_policy: Arc<Policy>,
+        expected_checksum: Option<String>,
+    ) -> Self {
+        Self {
+            client,
+            url,
+            auth_policy,
+            expected_checksum,
+            meta: Arc::default(),
+        }
+    }
+
     pub fn info_url(&self) -> reqwest::Url {
         self.url.join(".project.json").expect("internal URL error")
     }
@@ -56,6 +81,49 @@ impl<Policy> ReqwestSrcProjectAsync<Policy> {
     }
 }
 
+impl<Policy: HTTPAuthentication> ReqwestSrcProjectAsync<Policy> {
+    async fn fetch_meta(&self) -> Result<Option<InterchangeProjectMetadataRaw>, ReqwestSrcError> {
+        let meta_resp = self
+            .auth_policy
+            .with_authentication(&self.client, &json_get_request(self.meta_url()))
+            .await
+            .map_err(ReqwestSrcError::ReqwestMiddleware)?;
+
+        Ok(if meta_resp.status().is_success() {
+            let rep = meta_resp.text().await.map_err(ReqwestSrcError::Reqwest)?;
+            Some(serde_json::from_str(&rep).map_err(|e| ReqwestSrcError::Deserialize(rep, e))?)
+        } else {
+            None
+        })
+    }
+
+    /// Algorithm and value of the checksum of the source file `path` in
+    /// `.meta.json`, if it has one with a supported algorithm
+    async fn expected_source_checksum(
+        &self,
+        path: &Utf8UnixPath,
+    ) -> Result<Option<(HashAlg, String)>, ReqwestSrcError> {
+        let meta = self.get_meta_async().await?;
+        let Some(checksum) = meta
+            .as_ref()
+            .and_then(|m| m.checksum.as_ref())
+            .and_then(|c| c.get(path.as_str()))
+        else {
+            return Ok(None);
+        };
+        let algorithm = KerMlChecksumAlg::try_from(checksum.algorithm.as_str())
+            .ok()
+            .and_then(|a| HashAlg::try_from(a).ok());
+        if algorithm.is_none() {
+            log::debug!(
+                "not verifying `{path}`, checksum algorithm `{}` is not supported",
+                checksum.algorithm
+            );
+        }
+        Ok(algorithm.map(|a| (a, checksum.value.clone())))
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum ReqwestSrcError {
     #[error("error making an HTTP request:\n{0:#?}")]
@@ -68,6 +136,15 @@ pub enum ReqwestSrcError {
     BadStatus(Box<str>, reqwest::StatusCode),
     #[error("project is missing `.project.json` and/or `.meta.json` files")]
     MissingInfoMeta,
+    #[error(
+        "source file `{path}` does not match its checksum in `.meta.json`:\n\
+         expected {expected}, got {actual}"
+    )]
+    ChecksumMismatch {
+        path: Box<str>,
+        expected: String,
+        actual: String,
+    },
     // This is only needed because `checksum_canonical_hex` can return
     // `io:Error`, which in this case is actually a network/HTTP error
     #[error(transparent)]
@@ -107,18 +184,10 @@ impl<Policy: HTTPAuthentication> ProjectReadAsync for ReqwestSrcProjectAsync<Pol
     }
 
     async fn get_meta_async(&self) -> Result<Option<InterchangeProjectMetadataRaw>, Self::Error> {
-        let meta_resp = self
-            .auth_policy
-            .with_authentication(&self.client, &json_get_request(self.meta_url()))
+        self.meta
+            .get_or_try_init(|| self.fetch_meta())
             .await
-            .map_err(ReqwestSrcError::ReqwestMiddleware)?;
-
-        Ok(if meta_resp.status().is_success() {
-            let rep = meta_resp.text().await.map_err(ReqwestSrcError::Reqwest)?;
-            Some(serde_json::from_str(&rep).map_err(|e| ReqwestSrcError::Deserialize(rep, e))?)
-        } else {
-            None
-        })
+            .cloned()
     }
 
     type SourceReader<'a>
@@ -134,43 +203,50 @@ impl<Policy: HTTPAuthentication> ProjectReadAsync for ReqwestSrcProjectAsync<Pol
     ) -> Result<Self::SourceReader<'_>, Self::Error> {
         use futures::StreamExt as _;
 
+        let path = path.as_ref();
+        let expected = self.expected_source_checksum(path).await?;
         let resp = self
             .auth_policy
             .with_authentication(&self.client, &text_get_request(self.src_url(path)))
             .await
             .map_err(ReqwestSrcError::ReqwestMiddleware)?;
 
-        if resp.status().is_success() {
-            Ok(resp
+        if !resp.status().is_success() {
+            return Err(ReqwestSrcError::BadStatus(
+                resp.url().as_str().into(),
+                resp.status(),
+            ));
+        }
+        let Some((algorithm, expected)) = expected else {
+            return Ok(resp
                 .bytes_stream()
                 .map_err(io::Error::other)
                 .boxed()
-                .into_async_read())
-        } else {
-            Err(ReqwestSrcError::BadStatus(
-                resp.url().as_str().into(),
-                resp.status(),
-            ))
+                .into_async_read());
+        };
+        // Verified before any of it is read
+        let bytes = resp.bytes().await.map_err(ReqwestSrcError::Reqwest)?;
+        let actual = hash_lowercase_hex(algorithm, &bytes);
+        if !actual.eq_ignore_ascii_case(&expected) {
+            return Err(ReqwestSrcError::ChecksumMismatch {
+                path: path.as_str().into(),
+                expected,
+                actual,
+            });
         }
+        Ok(futures::stream::once(async { Ok(bytes) })
+            .boxed()
+            .into_async_read())
     }
 
     async fn is_definitely_invalid_async(&self) -> bool {
-        let info_request = &json_head_request(self.info_url());
-        let info_resp = self
-            .auth_policy
-            .with_authentication(&self.client, info_request);
-
-        let meta_request = &json_head_request(self.meta_url());
-        let meta_resp = self
-            .auth_policy
-            .with_authentication(&self.client, meta_request);
-
-        match join!(info_resp, meta_resp) {
-            (Ok(info_head), Ok(meta_head)) => {
-                !info_head.status().is_success() || !meta_head.status().is_success()
-            }
-            _ => true,
-        }
+        let info_request = json_head_request(self.info_url());
+        let meta_request = json_head_request(self.meta_url());
+        let (info, meta) = join!(
+            probe_head(&self.client, self.auth_policy.as_ref(), &info_request),
+            probe_head(&self.client, self.auth_policy.as_ref(), &meta_request)
+        );
+        info == HeadProbe::Invalid || meta == HeadProbe::Invalid
     }
 
     async fn sources_async(&self, _ctx: &ProjectContext) -> Result<Vec<Source>, Self::Error> {
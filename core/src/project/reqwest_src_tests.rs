@@ -20,17 +20,12 @@ fn empty_remote_definitely_invalid_http_src() -> Result<(), Box<dyn std::error::
 
     let client = create_reqwest_client()?;
 
-    let project = ReqwestSrcProjectAsync {
-        client,
-        url,
-        auth_policy: Arc::new(Unauthenticated {}),
-        expected_checksum: None,
-    }
-    .to_tokio_sync(Arc::new(
-        tokio::runtime::Builder::new_current_thread()
-            .enable_all()
-            .build()?,
-    ));
+    let project = ReqwestSrcProjectAsync::new(client, url, Arc::new(Unauthenticated {}), None)
+        .to_tokio_sync(Arc::new(
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?,
+        ));
 
     assert!(project.is_definitely_invalid());
 
@@ -75,17 +70,12 @@ fn basic_project_urls_http_src() -> Result<(), Box<dyn std::error::Error>> {
 
     let client = create_reqwest_client()?;
 
-    let project = ReqwestSrcProjectAsync {
-        client,
-        url,
-        auth_policy: Arc::new(Unauthenticated {}),
-        expected_checksum: None,
-    }
-    .to_tokio_sync(Arc::new(
-        tokio::runtime::Builder::new_current_thread()
-            .enable_all()
-            .build()?,
-    ));
+    let project = ReqwestSrcProjectAsync::new(client, url, Arc::new(Unauthenticated {}), None)
+        .to_tokio_sync(Arc::new(
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?,
+        ));
 
     let (Some(info), Some(meta)) = project.get_project()? else {
         panic!()
@@ -113,3 +103,106 @@ fn basic_project_urls_http_src() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn head_probe_http_src() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = mockito::Server::new();
+    let url = reqwest::Url::parse(&server.url())?;
+
+    let runtime = Arc::new(
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?,
+    );
+    let project = |path: &str| {
+        ReqwestSrcProjectAsync::new(
+            create_reqwest_client().unwrap(),
+            url.join(path).unwrap(),
+            Arc::new(Unauthenticated {}),
+            None,
+        )
+        .to_tokio_sync(runtime.clone())
+    };
+
+    // A catch-all HTML page is not a project
+    server
+        .mock("HEAD", mockito::Matcher::Regex("^/html/".to_string()))
+        .with_status(200)
+        .with_header("content-type", "text/html")
+        .create();
+    assert!(project("html/").is_definitely_invalid());
+
+    server
+        .mock("HEAD", mockito::Matcher::Regex("^/json/".to_string()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .create();
+    assert!(!project("json/").is_definitely_invalid());
+
+    // The server does not support HEAD requests
+    server
+        .mock("HEAD", mockito::Matcher::Regex("^/no-head/".to_string()))
+        .with_status(405)
+        .create();
+    assert!(!project("no-head/").is_definitely_invalid());
+
+    Ok(())
+}
+
+#[test]
+fn sources_verified_against_meta_checksums() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = mockito::Server::new();
+    let url = reqwest::Url::parse(&server.url())?;
+
+    let src = "package P;";
+    let meta = format!(
+        r#"{{"index":{{"P":"p.sysml"}},"created":"0000-00-00T00:00:00.123456789Z","checksum":{{"p.sysml":{{"value":"{}","algorithm":"SHA256"}},"q.sysml":{{"value":"{}","algorithm":"SHA256"}}}}}}"#,
+        crate::utils::sha256_lowercase_hex(src),
+        crate::utils::sha256_lowercase_hex("package Q;"),
+    );
+    let meta_mock = server
+        .mock("GET", "/.meta.json")
+        .with_status(200)
+        .with_body(meta)
+        .expect(1)
+        .create();
+    server
+        .mock("GET", "/p.sysml")
+        .with_status(200)
+        .with_body(src)
+        .create();
+    server
+        .mock("GET", "/q.sysml")
+        .with_status(200)
+        .with_body("package Tampered;")
+        .create();
+
+    let project = ReqwestSrcProjectAsync::new(
+        create_reqwest_client()?,
+        url,
+        Arc::new(Unauthenticated {}),
+        None,
+    )
+    .to_tokio_sync(Arc::new(
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?,
+    ));
+
+    let mut src_buf = String::new();
+    project
+        .read_source(Utf8UnixPath::new("p.sysml"))?
+        .read_to_string(&mut src_buf)?;
+    assert_eq!(src, src_buf);
+
+    let Err(super::ReqwestSrcError::ChecksumMismatch { path, .. }) =
+        project.read_source(Utf8UnixPath::new("q.sysml"))
+    else {
+        panic!("expected a checksum mismatch");
+    };
+    assert_eq!(&*path, "q.sysml");
+
+    // `.meta.json` is fetched once for all sources
+    meta_mock.assert();
+    Ok(())
+}
@@ -10,6 +10,8 @@ use typed_path::Utf8UnixPathBuf;
 #[cfg(feature = "filesystem")]
 use zip::{self, result::ZipError};
 
+use crate::error_code::{ErrorClass, ErrorCode, HasErrorCode};
+
 /// A file that is guaranteed to exist as long as the lifetime.
 /// Intended to be used with temporary files that are automatically
 /// deleted; in this case, the lifetime `'a` is the lifetime of the
@@ -26,6 +28,10 @@ impl FileWithLifetime<'_> {
             phantom: std::marker::PhantomData,
         }
     }
+
+    pub fn into_inner(self) -> std::fs::File {
+        self.internal
+    }
 }
 
 impl Read for FileWithLifetime<'_> {
@@ -129,6 +135,35 @@ pub enum FsIoError {
     Seek(Utf8PathBuf, u64, io::Error),
 }
 
+impl HasErrorCode for FsIoError {
+    fn error_code(&self) -> ErrorCode {
+        let number = match self {
+            FsIoError::Canonicalize(..) => 10,
+            FsIoError::Absolute(..) => 11,
+            FsIoError::MkDir(..) => 12,
+            FsIoError::OpenFile(..) => 13,
+            FsIoError::Metadata(..) => 14,
+            FsIoError::MetadataHandle(..) => 15,
+            FsIoError::CreateTempFile(..) => 16,
+            FsIoError::MkTempDir(..) => 17,
+            FsIoError::WriteFile(..) => 18,
+            FsIoError::ReadDir(..) => 19,
+            FsIoError::ReadFile(..) => 20,
+            FsIoError::ReadFileHandle(..) => 21,
+            FsIoError::Move(..) => 22,
+            FsIoError::CreateFile(..) => 23,
+            FsIoError::CopyFile(..) => 24,
+            FsIoError::RmFile(..) => 25,
+            FsIoError::RmDir(..) => 26,
+            FsIoError::CurrentDir(..) => 27,
+            FsIoError::IsFile(..) => 28,
+            FsIoError::IsDir(..) => 29,
+            FsIoError::Seek(..) => 30,
+        };
+        ErrorCode::new(number, ErrorClass::Io)
+    }
+}
+
 /// Wrappers for filesystem I/O functions to return `FsIoError`.
 /// Copies the `std` interface 1 to 1, except for the error type.
 pub mod wrapfs {
@@ -211,6 +246,14 @@ pub mod wrapfs {
         })
     }
 
+    pub fn rename<P: AsRef<Utf8Path>, Q: AsRef<Utf8Path>>(
+        from: P,
+        to: Q,
+    ) -> Result<(), Box<FsIoError>> {
+        fs::rename(from.as_ref(), to.as_ref())
+            .map_err(|e| Box::new(FsIoError::Move(from.as_ref().into(), to.as_ref().into(), e)))
+    }
+
     pub fn read_to_string<P: AsRef<Utf8Path>>(path: P) -> Result<String, Box<FsIoError>> {
         fs::read_to_string(path.as_ref())
             .map_err(|e| Box::new(FsIoError::ReadFile(path.as_ref().into(), e)))
@@ -362,6 +405,45 @@ pub enum ZipArchiveError {
     Write(Box<Utf8Path>, ZipError),
     #[error("failed to finish creating zip archive at `{0}`: {1}")]
     Finish(Box<Utf8Path>, ZipError),
+    #[error(
+        "`{0}` uses a zip feature that is not supported (encryption, split archives \
+        or a compression method sysand was built without): {1}"
+    )]
+    Unsupported(Box<str>, ZipError),
+    #[error(
+        "file `{0}` is larger than 4 GiB, but its size was not known before \
+        adding it to the zip archive"
+    )]
+    SizeUnknown(Box<str>),
+}
+
+#[cfg(feature = "filesystem")]
+impl ZipArchiveError {
+    /// Error for failing to open the zip archive at `path`
+    pub fn read_archive(path: Box<Utf8Path>, err: ZipError) -> Self {
+        if is_unsupported(&err) {
+            Self::Unsupported(path.as_str().into(), err)
+        } else {
+            Self::ReadArchive(path, err)
+        }
+    }
+
+    /// Error for failing to read the entry `name` of a zip archive
+    pub fn named_file_meta(name: Box<str>, err: ZipError) -> Self {
+        if is_unsupported(&err) {
+            Self::Unsupported(name, err)
+        } else {
+            Self::NamedFileMeta(name, err)
+        }
+    }
+}
+
+#[cfg(feature = "filesystem")]
+fn is_unsupported(err: &ZipError) -> bool {
+    matches!(
+        err,
+        ZipError::UnsupportedArchive(_) | ZipError::CompressionMethodNotSupported(_)
+    )
 }
 
 #[derive(Debug, Error, Clone)]
@@ -0,0 +1,84 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use typed_path::Utf8UnixPath;
+
+use crate::{
+    context::ProjectContext,
+    lock::Source,
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
+    project::{ProjectChecksum, ProjectRead},
+};
+
+/// Treat a project type `P` as only one of its variants, i.e. with the
+/// metadata restricted to the source files of the variant, see
+/// [`InterchangeProjectMetadataRaw::restrict_to_variant`]. Sources and
+/// checksum are those of the whole project, so that it can be checked
+/// against the lockfile. Metadata is left unchanged if the project does
+/// not declare the variant
+#[derive(Clone, Debug)]
+pub struct VariantProject<P: ProjectRead> {
+    inner: P,
+    variant: String,
+}
+
+impl<P: ProjectRead> VariantProject<P> {
+    pub fn new<S: Into<String>>(project: P, variant: S) -> VariantProject<P> {
+        VariantProject {
+            inner: project,
+            variant: variant.into(),
+        }
+    }
+
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    pub fn variant(&self) -> &str {
+        &self.variant
+    }
+}
+
+impl<P: ProjectRead> ProjectRead for VariantProject<P> {
+    type Error = P::Error;
+
+    fn get_project(
+        &self,
+    ) -> Result<
+        (
+            Option<InterchangeProjectInfoRaw>,
+            Option<InterchangeProjectMetadataRaw>,
+        ),
+        Self::Error,
+    > {
+        let (info, mut meta) = self.inner.get_project()?;
+        if let Some(meta) = &mut meta {
+            meta.restrict_to_variant(&self.variant);
+        }
+        Ok((info, meta))
+    }
+
+    type SourceReader<'a>
+        = P::SourceReader<'a>
+    where
+        Self: 'a;
+
+    fn read_source<Q: AsRef<Utf8UnixPath>>(
+        &self,
+        path: Q,
+    ) -> Result<Self::SourceReader<'_>, Self::Error> {
+        self.inner.read_source(path)
+    }
+
+    fn sources(&self, ctx: &ProjectContext) -> Result<Vec<Source>, Self::Error> {
+        self.inner.sources(ctx)
+    }
+
+    fn checksum_canonical_variant(&self) -> Result<ProjectChecksum, Self::Error> {
+        self.inner.checksum_canonical_variant()
+    }
+}
+
+#[cfg(test)]
+#[path = "./variant_tests.rs"]
+mod tests;
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use indexmap::IndexMap;
+use typed_path::Utf8UnixPath;
+
+use super::VariantProject;
+use crate::{
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
+    project::{ProjectRead, memory::InMemoryProject},
+};
+
+fn project() -> InMemoryProject {
+    let info = InterchangeProjectInfoRaw {
+        name: "variants".to_string(),
+        publisher: None,
+        description: None,
+        version: "1.0.0".to_string(),
+        license: None,
+        maintainer: vec![],
+        website: None,
+        topic: vec![],
+        usage: vec![],
+    };
+    let meta = InterchangeProjectMetadataRaw {
+        index: IndexMap::from([
+            ("A".to_string(), "a.sysml".to_string()),
+            ("B".to_string(), "extra/b.sysml".to_string()),
+        ]),
+        variants: IndexMap::from([
+            ("minimal".to_string(), vec!["a.sysml".to_string()]),
+            (
+                "full".to_string(),
+                vec!["a.sysml".to_string(), "extra".to_string()],
+            ),
+        ]),
+        ..Default::default()
+    };
+    let mut project = InMemoryProject::from_info_meta(info, meta);
+    project
+        .files
+        .insert("a.sysml".into(), "package A;".to_string());
+    project
+        .files
+        .insert("extra/b.sysml".into(), "package B;".to_string());
+    project
+}
+
+#[test]
+fn restricts_metadata_to_variant() {
+    let project = project();
+    let minimal = VariantProject::new(&project, "minimal");
+
+    let meta = minimal.get_meta().unwrap().unwrap();
+    assert_eq!(
+        meta.index,
+        IndexMap::from([("A".to_string(), "a.sysml".to_string())])
+    );
+    assert_eq!(meta.variants.keys().collect::<Vec<_>>(), ["minimal"]);
+    assert_eq!(
+        minimal.checksum_canonical_variant().unwrap(),
+        project.checksum_canonical_variant().unwrap()
+    );
+
+    let installed = InMemoryProject::from_project(&minimal).unwrap();
+    assert_eq!(installed.files.len(), 1);
+    assert!(installed.files.contains_key(Utf8UnixPath::new("a.sysml")));
+
+    let full = VariantProject::new(&project, "full");
+    assert_eq!(full.get_meta().unwrap().unwrap().index.len(), 2);
+}
+
+#[test]
+fn unknown_variant_keeps_metadata() {
+    let project = project();
+    let unknown = VariantProject::new(&project, "unknown");
+    assert_eq!(unknown.get_meta().unwrap(), project.get_meta().unwrap());
+}
@@ -0,0 +1,269 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::io::{Cursor, Read};
+
+use thiserror::Error;
+use typed_path::{Utf8UnixComponent, Utf8UnixPath, Utf8UnixPathBuf};
+
+use crate::{
+    context::ProjectContext,
+    env::utils::ErrorBound,
+    lock::Source,
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
+    project::{
+        CanonicalizationError, ProjectChecksum, ProjectMut, ProjectRead,
+        utils::{FsIoError, ProjectDeserializationError, ProjectSerializationError},
+    },
+    vfs::Vfs,
+};
+
+/// Project stored in a directory of a virtual filesystem, laid out like an
+/// extracted KPAR archive. The [`Vfs`] counterpart of
+/// [`LocalSrcProject`](crate::project::local_src::LocalSrcProject).
+#[derive(Clone, Debug)]
+pub struct VfsProject<F: Vfs> {
+    pub fs: F,
+    /// Path of the project directory in `fs`
+    pub root_path: Utf8UnixPathBuf,
+    /// Path used in `Source::LocalSrc` returned by `.sources()`.
+    /// If `None`, `root_path` is used.
+    pub nominal_path: Option<Utf8UnixPathBuf>,
+    // TODO: enforce that the project matches the checksum if provided
+    // before reading; see LocalKparProject for example
+    pub expected_checksum: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum VfsProjectError<E: ErrorBound> {
+    #[error("{0}")]
+    AlreadyExists(String),
+    #[error("project read error: file `{0}` not found")]
+    FileNotFound(Utf8UnixPathBuf),
+    #[error("path `{0}` is unsafe, it must be relative and stay within the project")]
+    UnsafePath(Utf8UnixPathBuf),
+    #[error(transparent)]
+    Vfs(E),
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+    #[error(transparent)]
+    Deserialize(#[from] ProjectDeserializationError),
+    #[error(transparent)]
+    Serialize(#[from] ProjectSerializationError),
+    #[error("project is missing `.project.json` and/or `.meta.json` files")]
+    MissingInfoMeta,
+}
+
+impl<E: ErrorBound> From<FsIoError> for VfsProjectError<E> {
+    fn from(v: FsIoError) -> Self {
+        Self::Io(Box::new(v))
+    }
+}
+
+impl<F: Vfs> VfsProject<F> {
+    pub fn new<P: Into<Utf8UnixPathBuf>>(fs: F, root_path: P) -> Self {
+        Self {
+            fs,
+            root_path: root_path.into(),
+            nominal_path: None,
+            expected_checksum: None,
+        }
+    }
+
+    pub fn info_path(&self) -> Utf8UnixPathBuf {
+        self.root_path.join(".project.json")
+    }
+
+    pub fn meta_path(&self) -> Utf8UnixPathBuf {
+        self.root_path.join(".meta.json")
+    }
+
+    /// Path of source file `path` in `fs`. Fails if `path` is absolute or
+    /// would leave the project directory
+    pub fn get_source_path<P: AsRef<Utf8UnixPath>>(
+        &self,
+        path: P,
+    ) -> Result<Utf8UnixPathBuf, VfsProjectError<F::Error>> {
+        let path = path.as_ref();
+        let mut depth = 0usize;
+        for component in path.components() {
+            match component {
+                Utf8UnixComponent::Normal(_) => depth += 1,
+                Utf8UnixComponent::CurDir => {}
+                Utf8UnixComponent::ParentDir if depth > 0 => depth -= 1,
+                Utf8UnixComponent::ParentDir | Utf8UnixComponent::RootDir => {
+                    return Err(VfsProjectError::UnsafePath(path.to_owned()));
+                }
+            }
+        }
+        Ok(self.root_path.join(path))
+    }
+
+    fn read_json<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &Utf8UnixPath,
+        name: &str,
+    ) -> Result<Option<T>, VfsProjectError<F::Error>> {
+        match self.fs.read(path).map_err(VfsProjectError::Vfs)? {
+            Some(contents) => Ok(Some(
+                serde_json::from_slice(&contents)
+                    .map_err(|e| ProjectDeserializationError::new(name, e))?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    fn write_json<T: serde::Serialize>(
+        &self,
+        path: &Utf8UnixPath,
+        value: &T,
+        overwrite: bool,
+    ) -> Result<(), VfsProjectError<F::Error>> {
+        if !overwrite && self.fs.is_file(path).map_err(VfsProjectError::Vfs)? {
+            return Err(VfsProjectError::AlreadyExists(format!(
+                "`{path}` already exists"
+            )));
+        }
+
+        let mut contents = serde_json::to_vec_pretty(value).map_err(|e| {
+            ProjectSerializationError::new(format!("failed to serialize `{path}`"), e)
+        })?;
+        contents.push(b'\n');
+        self.fs.write(path, contents).map_err(VfsProjectError::Vfs)
+    }
+}
+
+impl<F: Vfs> ProjectRead for VfsProject<F> {
+    type Error = VfsProjectError<F::Error>;
+
+    fn get_project(
+        &self,
+    ) -> Result<
+        (
+            Option<InterchangeProjectInfoRaw>,
+            Option<InterchangeProjectMetadataRaw>,
+        ),
+        Self::Error,
+    > {
+        Ok((
+            self.read_json(&self.info_path(), ".project.json")?,
+            self.read_json(&self.meta_path(), ".meta.json")?,
+        ))
+    }
+
+    type SourceReader<'a>
+        = Cursor<Vec<u8>>
+    where
+        Self: 'a;
+
+    fn read_source<P: AsRef<Utf8UnixPath>>(
+        &self,
+        path: P,
+    ) -> Result<Self::SourceReader<'_>, Self::Error> {
+        let source_path = self.get_source_path(&path)?;
+        match self.fs.read(&source_path).map_err(VfsProjectError::Vfs)? {
+            Some(contents) => Ok(Cursor::new(contents)),
+            None => Err(VfsProjectError::FileNotFound(path.as_ref().to_owned())),
+        }
+    }
+
+    fn sources(&self, _ctx: &ProjectContext) -> Result<Vec<Source>, Self::Error> {
+        let checksum = match &self.expected_checksum {
+            Some(c) => c.clone(),
+            None => match self.checksum_canonical_variant()? {
+                ProjectChecksum::Project(c) | ProjectChecksum::Kpar(c) => c,
+            },
+        };
+        let src_path = self.nominal_path.as_ref().unwrap_or(&self.root_path);
+        Ok(vec![Source::LocalSrc {
+            src_path: src_path.as_str().into(),
+            checksum,
+        }])
+    }
+
+    fn checksum_canonical_variant(&self) -> Result<ProjectChecksum, Self::Error> {
+        match self.checksum_canonical_hex() {
+            Ok(Some(c)) => Ok(ProjectChecksum::Project(c)),
+            Ok(None) => Err(VfsProjectError::MissingInfoMeta),
+            Err(CanonicalizationError::ProjectRead(e)) => Err(e),
+            Err(CanonicalizationError::FileRead(path, error)) => {
+                Err(FsIoError::ReadFile(String::from(path).into(), error).into())
+            }
+        }
+    }
+}
+
+impl<F: Vfs> ProjectMut for VfsProject<F> {
+    fn put_info(
+        &mut self,
+        info: &InterchangeProjectInfoRaw,
+        overwrite: bool,
+    ) -> Result<(), Self::Error> {
+        self.write_json(&self.info_path(), info, overwrite)
+    }
+
+    fn put_meta(
+        &mut self,
+        meta: &InterchangeProjectMetadataRaw,
+        overwrite: bool,
+    ) -> Result<(), Self::Error> {
+        self.write_json(&self.meta_path(), meta, overwrite)
+    }
+
+    fn write_source<P: AsRef<Utf8UnixPath>, R: Read>(
+        &mut self,
+        path: P,
+        source: &mut R,
+        overwrite: bool,
+    ) -> Result<(), Self::Error> {
+        let source_path = self.get_source_path(path)?;
+
+        if !overwrite
+            && self
+                .fs
+                .is_file(&source_path)
+                .map_err(VfsProjectError::Vfs)?
+        {
+            return Err(VfsProjectError::AlreadyExists(format!(
+                "`{source_path}` already exists"
+            )));
+        }
+
+        let mut contents = vec![];
+        source
+            .read_to_end(&mut contents)
+            .map_err(FsIoError::ReadFileHandle)?;
+        self.fs
+            .write(&source_path, contents)
+            .map_err(VfsProjectError::Vfs)
+    }
+
+    fn move_source<P: AsRef<Utf8UnixPath>, Q: AsRef<Utf8UnixPath>>(
+        &mut self,
+        from: P,
+        to: Q,
+    ) -> Result<(), Self::Error> {
+        let from_path = self.get_source_path(&from)?;
+        let to_path = self.get_source_path(to)?;
+        if self.fs.is_file(&to_path).map_err(VfsProjectError::Vfs)? {
+            return Err(VfsProjectError::AlreadyExists(format!(
+                "`{to_path}` already exists"
+            )));
+        }
+
+        let contents = self.fs.read(&from_path).map_err(VfsProjectError::Vfs)?;
+        let Some(contents) = contents else {
+            return Err(VfsProjectError::FileNotFound(from.as_ref().to_owned()));
+        };
+        self.fs
+            .write(&to_path, contents)
+            .map_err(VfsProjectError::Vfs)?;
+        self.fs
+            .remove_file(&from_path)
+            .map_err(VfsProjectError::Vfs)
+    }
+}
+
+#[cfg(test)]
+#[path = "./vfs_tests.rs"]
+mod tests;
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::io::Read as _;
+
+use typed_path::Utf8UnixPathBuf;
+
+use super::{VfsProject, VfsProjectError};
+use crate::{
+    include::do_include,
+    init::do_init,
+    model::HashAlg,
+    project::{ProjectMut, ProjectRead},
+    vfs::{MemoryFs, Vfs},
+};
+
+#[test]
+fn init_and_include() {
+    let fs = MemoryFs::new();
+    fs.write("project/A.sysml", "package A;").unwrap();
+    let mut project = VfsProject::new(fs.clone(), "project");
+
+    do_init(
+        "test".to_string(),
+        None,
+        "1.0.0".to_string(),
+        None,
+        &mut project,
+    )
+    .unwrap();
+    do_include(
+        &mut project,
+        [Utf8UnixPathBuf::from("A.sysml")].into_iter(),
+        Some(HashAlg::Sha256),
+        true,
+        None,
+    )
+    .unwrap();
+
+    assert!(fs.is_file("project/.project.json").unwrap());
+    let meta = project.get_meta().unwrap().unwrap();
+    assert_eq!(meta.index.get("A").map(String::as_str), Some("A.sysml"));
+    assert!(project.checksum_canonical_hex().unwrap().is_some());
+
+    let mut source = String::new();
+    project
+        .read_source("A.sysml")
+        .unwrap()
+        .read_to_string(&mut source)
+        .unwrap();
+    assert_eq!(source, "package A;");
+}
+
+#[test]
+fn refuses_unsafe_and_existing_paths() {
+    let mut project = VfsProject::new(MemoryFs::new(), "project");
+
+    assert!(matches!(
+        project.write_source("../escape.sysml", &mut "".as_bytes(), true),
+        Err(VfsProjectError::UnsafePath(_))
+    ));
+    assert!(matches!(
+        project.read_source("/abs.sysml"),
+        Err(VfsProjectError::UnsafePath(_))
+    ));
+
+    project
+        .write_source("a/../A.sysml", &mut "package A;".as_bytes(), false)
+        .unwrap();
+    assert!(matches!(
+        project.write_source("A.sysml", &mut "".as_bytes(), false),
+        Err(VfsProjectError::AlreadyExists(_))
+    ));
+    assert!(matches!(
+        project.read_source("B.sysml"),
+        Err(VfsProjectError::FileNotFound(_))
+    ));
+}
@@ -109,6 +109,19 @@ pub fn normalize_field(s: &str) -> String {
     s.to_ascii_lowercase().replace(' ', "-")
 }
 
+/// The `pkg:sysand/<publisher>/<name>` IRI identifying a project with the
+/// given (unnormalized) publisher and name, or `None` if either of them is
+/// not valid.
+pub fn sysand_purl(publisher: &str, name: &str) -> Option<String> {
+    (is_valid_unnormalized_publisher(publisher) && is_valid_unnormalized_name(name)).then(|| {
+        format!(
+            "{PKG_SYSAND_PREFIX}{}/{}",
+            normalize_field(publisher),
+            normalize_field(name)
+        )
+    })
+}
+
 /// Reason a `pkg:sysand/...` IRI failed [`parse_sysand_purl`]. Used to
 /// build human-readable validation errors that explain the rejection
 /// without leaking parser internals.
@@ -1,18 +1,14 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
-use std::{fmt::Debug, iter::Peekable};
+use std::{collections::HashMap, fmt::Debug, iter::Peekable, num::NonZeroUsize};
 
 use indexmap::IndexMap;
 use thiserror::Error;
-use typed_path::Utf8UnixPath;
 
 use crate::{
-    context::ProjectContext,
-    lock::Source,
-    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
     project::{ProjectRead, cached::CachedProject},
-    resolve::{ResolutionOutcome, ResolveRead, null::NullResolver},
+    resolve::{IriOutcome, ResolutionOutcome, ResolveRead, dedup_iris, null::NullResolver},
     utils::format_err,
 };
 
@@ -235,36 +231,209 @@ impl<
         &self,
         uri: &fluent_uri::Iri<String>,
     ) -> Result<ResolutionOutcome<Self::ResolvedStorages>, Self::Error> {
-        let mut at_least_one_supports = false;
+        if let Some(outcome) = self.resolve_file(uri)? {
+            return Ok(outcome);
+        }
+        let mut partial = self.resolve_local(uri)?;
+        if let Some(remote_resolver) = &self.remote_resolver
+            && let Some(found) =
+                Self::take_remote(uri, remote_resolver.resolve_read(uri), &mut partial)?
+        {
+            return Ok(ResolutionOutcome::Resolved(found));
+        }
+        if let Some(index_resolver) = &self.index_resolver
+            && let Some(found) =
+                Self::take_index(uri, index_resolver.resolve_read(uri), &mut partial)?
+        {
+            return Ok(ResolutionOutcome::Resolved(found));
+        }
+        Ok(partial.finish())
+    }
+
+    /// Same as resolving each IRI with [`resolve_read`](Self::resolve_read),
+    /// but the remote and index resolvers each get all IRIs that reach them
+    /// at once, so that they can resolve them concurrently
+    fn resolve_many<I: IntoIterator<Item = fluent_uri::Iri<String>>>(
+        &self,
+        uris: I,
+        concurrency: NonZeroUsize,
+    ) -> Vec<IriOutcome<Self::ResolvedStorages, Self::Error>> {
+        let uris = dedup_iris(uris);
+        let mut outcomes: Vec<_> = uris.iter().map(|_| None).collect();
+        let mut pending = vec![];
+        for (i, uri) in uris.iter().enumerate() {
+            match self.resolve_file(uri) {
+                Ok(None) => match self.resolve_local(uri) {
+                    Ok(partial) => pending.push((i, partial)),
+                    Err(err) => outcomes[i] = Some(Err(err)),
+                },
+                Ok(Some(outcome)) => outcomes[i] = Some(Ok(outcome)),
+                Err(err) => outcomes[i] = Some(Err(err)),
+            }
+        }
+        if let Some(remote_resolver) = &self.remote_resolver {
+            pending = resolve_pending(
+                remote_resolver,
+                &uris,
+                pending,
+                &mut outcomes,
+                concurrency,
+                Self::take_remote,
+            );
+        }
+        if let Some(index_resolver) = &self.index_resolver {
+            pending = resolve_pending(
+                index_resolver,
+                &uris,
+                pending,
+                &mut outcomes,
+                concurrency,
+                Self::take_index,
+            );
+        }
+        for (i, partial) in pending {
+            outcomes[i] = Some(Ok(partial.finish()));
+        }
+        uris.into_iter()
+            .zip(outcomes)
+            .map(|(uri, outcome)| (uri, outcome.expect("every IRI has an outcome")))
+            .collect()
+    }
+}
+
+type CombinedOutcome<F, L, R, I> = ResolutionOutcome<CombinedIterator<F, L, R, I>>;
+
+type CombinedError<F, L, R, I> = CombinedResolverError<
+    <F as ResolveRead>::Error,
+    <L as ResolveRead>::Error,
+    <R as ResolveRead>::Error,
+    <I as ResolveRead>::Error,
+>;
+
+type CombinedResult<F, L, R, I> = Result<CombinedOutcome<F, L, R, I>, CombinedError<F, L, R, I>>;
+
+/// Resolution of an IRI that was not settled by the file resolver
+struct Partial<LocalProjectStorage> {
+    /// Locally cached projects by checksum, in reverse order for `pop`-ing
+    locals: IndexMap<String, LocalProjectStorage>,
+    at_least_one_supports: bool,
+}
+
+impl<LocalProjectStorage> Partial<LocalProjectStorage> {
+    /// As a last resort, use only locally cached projects, if any were found
+    fn finish<F: ResolveRead, L, R: ResolveRead, I: ResolveRead>(
+        self,
+    ) -> CombinedOutcome<F, L, R, I>
+    where
+        L: ResolveRead<ProjectStorage = LocalProjectStorage>,
+    {
+        if !self.at_least_one_supports {
+            ResolutionOutcome::UnsupportedIRIType("no resolver accepted the IRI".to_owned())
+        } else if self.locals.is_empty() {
+            ResolutionOutcome::Unresolvable("no resolver was able to resolve the IRI".to_owned())
+        } else {
+            ResolutionOutcome::Resolved(CombinedIterator {
+                state: CombinedIteratorState::Done,
+                locals: self.locals,
+            })
+        }
+    }
+}
 
-        // If the file resolver does not outright reject the IRI type,
-        // use it.
+/// Resolve the IRIs of `pending` at once with `resolver`, recording the
+/// outcomes that `take` settles. Returns the still pending IRIs
+fn resolve_pending<Res, F, L, R, I, Take>(
+    resolver: &Res,
+    uris: &[fluent_uri::Iri<String>],
+    pending: Vec<(usize, Partial<L::ProjectStorage>)>,
+    outcomes: &mut [Option<CombinedResult<F, L, R, I>>],
+    concurrency: NonZeroUsize,
+    take: Take,
+) -> Vec<(usize, Partial<L::ProjectStorage>)>
+where
+    Res: ResolveRead,
+    F: ResolveRead,
+    L: ResolveRead,
+    R: ResolveRead,
+    I: ResolveRead,
+    Take: Fn(
+        &fluent_uri::Iri<String>,
+        Result<ResolutionOutcome<Res::ResolvedStorages>, Res::Error>,
+        &mut Partial<L::ProjectStorage>,
+    ) -> Result<Option<CombinedIterator<F, L, R, I>>, CombinedError<F, L, R, I>>,
+{
+    let mut resolved: HashMap<_, _> = resolver
+        .resolve_many(pending.iter().map(|(i, _)| uris[*i].clone()), concurrency)
+        .into_iter()
+        .collect();
+    let mut still_pending = vec![];
+    for (i, mut partial) in pending {
+        let uri = &uris[i];
+        let outcome = resolved
+            .remove(uri)
+            .unwrap_or_else(|| resolver.resolve_read(uri));
+        match take(uri, outcome, &mut partial) {
+            Ok(None) => still_pending.push((i, partial)),
+            Ok(Some(found)) => outcomes[i] = Some(Ok(ResolutionOutcome::Resolved(found))),
+            Err(err) => outcomes[i] = Some(Err(err)),
+        }
+    }
+    still_pending
+}
+
+impl<
+    FileResolver: ResolveRead,
+    LocalResolver: ResolveRead,
+    RemoteResolver: ResolveRead,
+    IndexResolver: ResolveRead,
+> CombinedResolver<FileResolver, LocalResolver, RemoteResolver, IndexResolver>
+{
+    /// If the file resolver does not outright reject the IRI type, its
+    /// outcome is final
+    #[expect(clippy::type_complexity)]
+    fn resolve_file(
+        &self,
+        uri: &fluent_uri::Iri<String>,
+    ) -> Result<
+        Option<CombinedOutcome<FileResolver, LocalResolver, RemoteResolver, IndexResolver>>,
+        CombinedError<FileResolver, LocalResolver, RemoteResolver, IndexResolver>,
+    > {
         // TODO: autodetect git (and possibly other VCSs), and use appropriate (e.g. git) resolver for them.
-        if let Some(file_resolver) = &self.file_resolver {
-            match file_resolver
-                .resolve_read(uri)
-                .map_err(CombinedResolverError::File)?
-            {
-                ResolutionOutcome::UnsupportedIRIType(msg) => {
-                    log::debug!("file resolver rejected IRI `{uri}`: {msg}");
-                } // Just continue
-                ResolutionOutcome::Resolved(r) => {
-                    //at_least_one_supports = true;
-                    return Ok(ResolutionOutcome::Resolved(CombinedIterator {
-                        state: CombinedIteratorState::ResolvedFile(r.into_iter()),
-                        locals: IndexMap::new(),
-                    }));
-                }
-                ResolutionOutcome::Unresolvable(msg) => {
-                    return Ok(ResolutionOutcome::Unresolvable(format!(
-                        "failed to resolve as file: {msg}"
-                    )));
-                }
+        let Some(file_resolver) = &self.file_resolver else {
+            return Ok(None);
+        };
+        match file_resolver
+            .resolve_read(uri)
+            .map_err(CombinedResolverError::File)?
+        {
+            ResolutionOutcome::UnsupportedIRIType(msg) => {
+                log::debug!("file resolver rejected IRI `{uri}`: {msg}");
+                Ok(None)
+            }
+            ResolutionOutcome::Resolved(r) => {
+                Ok(Some(ResolutionOutcome::Resolved(CombinedIterator {
+                    state: CombinedIteratorState::ResolvedFile(r.into_iter()),
+                    locals: IndexMap::new(),
+                })))
             }
+            ResolutionOutcome::Unresolvable(msg) => Ok(Some(ResolutionOutcome::Unresolvable(
+                format!("failed to resolve as file: {msg}"),
+            ))),
         }
+    }
 
-        // Collect local cached projects
-        let mut locals: IndexMap<String, LocalResolver::ProjectStorage> = IndexMap::new();
+    /// Collect local cached projects
+    fn resolve_local(
+        &self,
+        uri: &fluent_uri::Iri<String>,
+    ) -> Result<
+        Partial<LocalResolver::ProjectStorage>,
+        CombinedError<FileResolver, LocalResolver, RemoteResolver, IndexResolver>,
+    > {
+        let mut partial = Partial {
+            locals: IndexMap::new(),
+            at_least_one_supports: false,
+        };
 
         if let Some(local_resolver) = &self.local_resolver {
             match local_resolver
@@ -272,7 +441,7 @@ impl<
                 .map_err(CombinedResolverError::Local)?
             {
                 ResolutionOutcome::Resolved(projects) => {
-                    at_least_one_supports = true;
+                    partial.at_least_one_supports = true;
                     for res in projects {
                         match res {
                             Err(err) => {
@@ -283,7 +452,7 @@ impl<
                             }
                             Ok(project) => match project.checksum_canonical_hex() {
                                 Ok(Some(checksum)) => {
-                                    locals.insert(checksum, project);
+                                    partial.locals.insert(checksum, project);
                                 }
                                 Ok(None) => {
                                     log::debug!(
@@ -304,122 +473,121 @@ impl<
                     log::debug!("local resolver rejected IRI `{uri}`: {msg}");
                 }
                 ResolutionOutcome::Unresolvable(msg) => {
-                    at_least_one_supports = true;
+                    partial.at_least_one_supports = true;
                     log::debug!("local resolver unable to resolve IRI `{uri}`: {msg}");
                 }
             };
         }
 
         // Need in reverse order for pop-ing
-        locals.reverse();
+        partial.locals.reverse();
 
-        if let Some(remote_resolver) = &self.remote_resolver {
-            // Skip over remote resolution if unresolvable or if only invalid projects are produced.
-            match remote_resolver
-                .resolve_read(uri)
-                .map_err(CombinedResolverError::Remote)?
-            {
-                ResolutionOutcome::UnsupportedIRIType(msg) => {
-                    log::debug!("remote resolver rejected IRI `{uri}`: {msg}");
-                }
-                ResolutionOutcome::Unresolvable(msg) => {
-                    at_least_one_supports = true;
-                    log::debug!("remote resolver unable to resolve IRI `{uri}`: {msg}");
-                }
-                ResolutionOutcome::Resolved(remote_projects) => {
-                    at_least_one_supports = true;
-                    // See if at least one project is valid
-                    let mut remote_projects = remote_projects.into_iter().peekable();
-
-                    loop {
-                        match remote_projects.peek() {
-                            Some(Err(err)) => {
-                                log::debug!(
-                                    "remote resolver skipping project for IRI `{uri}` due to: {}",
-                                    format_err(err)
-                                );
+        Ok(partial)
+    }
+
+    /// Skip over remote resolution if unresolvable or if only invalid
+    /// projects are produced
+    #[expect(clippy::type_complexity)]
+    fn take_remote(
+        uri: &fluent_uri::Iri<String>,
+        outcome: Result<ResolutionOutcome<RemoteResolver::ResolvedStorages>, RemoteResolver::Error>,
+        partial: &mut Partial<LocalResolver::ProjectStorage>,
+    ) -> Result<
+        Option<CombinedIterator<FileResolver, LocalResolver, RemoteResolver, IndexResolver>>,
+        CombinedError<FileResolver, LocalResolver, RemoteResolver, IndexResolver>,
+    > {
+        match outcome.map_err(CombinedResolverError::Remote)? {
+            ResolutionOutcome::UnsupportedIRIType(msg) => {
+                log::debug!("remote resolver rejected IRI `{uri}`: {msg}");
+            }
+            ResolutionOutcome::Unresolvable(msg) => {
+                partial.at_least_one_supports = true;
+                log::debug!("remote resolver unable to resolve IRI `{uri}`: {msg}");
+            }
+            ResolutionOutcome::Resolved(remote_projects) => {
+                partial.at_least_one_supports = true;
+                // See if at least one project is valid
+                let mut remote_projects = remote_projects.into_iter().peekable();
+
+                loop {
+                    match remote_projects.peek() {
+                        Some(Err(err)) => {
+                            log::debug!(
+                                "remote resolver skipping project for IRI `{uri}` due to: {}",
+                                format_err(err)
+                            );
+                            remote_projects.next();
+                        }
+                        Some(Ok(project)) => {
+                            if project.is_definitely_invalid() {
                                 remote_projects.next();
+                                continue;
                             }
-                            Some(Ok(project)) => {
-                                if project.is_definitely_invalid() {
+
+                            match project.get_project() {
+                                Ok((Some(_), Some(_))) => {
+                                    // Found at least one nominally valid project
+                                    return Ok(Some(CombinedIterator {
+                                        state: CombinedIteratorState::ResolvedRemote(
+                                            remote_projects,
+                                        ),
+                                        locals: std::mem::take(&mut partial.locals),
+                                    }));
+                                }
+                                Ok(_) => {
+                                    log::debug!(
+                                        "remote resolver skipping project for IRI `{uri}` due to missing info/meta"
+                                    );
                                     remote_projects.next();
-                                    continue;
                                 }
-
-                                match project.get_project() {
-                                    Ok((Some(_), Some(_))) => {
-                                        // Found at least one nominally valid project
-                                        return Ok(ResolutionOutcome::Resolved(CombinedIterator {
-                                            state: CombinedIteratorState::ResolvedRemote(
-                                                remote_projects,
-                                            ),
-                                            locals,
-                                        }));
-                                    }
-                                    Ok(_) => {
-                                        log::debug!(
-                                            "remote resolver skipping project for IRI `{uri}` due to missing info/meta"
-                                        );
-                                        remote_projects.next();
-                                    }
-                                    Err(err) => {
-                                        log::debug!(
-                                            "remote resolver skipping project for IRI `{uri}`: {}",
-                                            format_err(err)
-                                        );
-                                        remote_projects.next();
-                                    }
+                                Err(err) => {
+                                    log::debug!(
+                                        "remote resolver skipping project for IRI `{uri}`: {}",
+                                        format_err(err)
+                                    );
+                                    remote_projects.next();
                                 }
                             }
-                            None => {
-                                log::debug!(
-                                    "remote resolver unable to find valid project for IRI `{uri}`"
-                                );
-                                break;
-                            }
+                        }
+                        None => {
+                            log::debug!(
+                                "remote resolver unable to find valid project for IRI `{uri}`"
+                            );
+                            break;
                         }
                     }
                 }
             }
         }
+        Ok(None)
+    }
 
-        // Finally try the sysand index if neither file/remote gave anything useful
-        if let Some(index_resolver) = &self.index_resolver {
-            match index_resolver
-                .resolve_read(uri)
-                .map_err(CombinedResolverError::Index)?
-            {
-                ResolutionOutcome::Resolved(x) => {
-                    return Ok(ResolutionOutcome::Resolved(CombinedIterator {
-                        state: CombinedIteratorState::ResolvedIndex(x.into_iter()),
-                        locals,
-                    }));
-                }
-                ResolutionOutcome::UnsupportedIRIType(msg) => {
-                    log::debug!("index resolver rejected IRI `{uri}` due to: {msg}");
-                }
-                ResolutionOutcome::Unresolvable(msg) => {
-                    at_least_one_supports = true;
-                    log::debug!("index resolver unable to resolve IRI `{uri}`: {msg}");
-                }
-            };
-        }
-
-        // As a last resort, use only locally cached projects, if any were found
-        if !at_least_one_supports {
-            Ok(ResolutionOutcome::UnsupportedIRIType(
-                "no resolver accepted the IRI".to_owned(),
-            ))
-        } else if locals.is_empty() {
-            Ok(ResolutionOutcome::Unresolvable(
-                "no resolver was able to resolve the IRI".to_owned(),
-            ))
-        } else {
-            Ok(ResolutionOutcome::Resolved(CombinedIterator {
-                state: CombinedIteratorState::Done,
-                locals,
-            }))
-        }
+    /// Try the sysand index if neither file/remote gave anything useful
+    #[expect(clippy::type_complexity)]
+    fn take_index(
+        uri: &fluent_uri::Iri<String>,
+        outcome: Result<ResolutionOutcome<IndexResolver::ResolvedStorages>, IndexResolver::Error>,
+        partial: &mut Partial<LocalResolver::ProjectStorage>,
+    ) -> Result<
+        Option<CombinedIterator<FileResolver, LocalResolver, RemoteResolver, IndexResolver>>,
+        CombinedError<FileResolver, LocalResolver, RemoteResolver, IndexResolver>,
+    > {
+        match outcome.map_err(CombinedResolverError::Index)? {
+            ResolutionOutcome::Resolved(x) => {
+                return Ok(Some(CombinedIterator {
+                    state: CombinedIteratorState::ResolvedIndex(x.into_iter()),
+                    locals: std::mem::take(&mut partial.locals),
+                }));
+            }
+            ResolutionOutcome::UnsupportedIRIType(msg) => {
+                log::debug!("index resolver rejected IRI `{uri}` due to: {msg}");
+            }
+            ResolutionOutcome::Unresolvable(msg) => {
+                partial.at_least_one_supports = true;
+                log::debug!("index resolver unable to resolve IRI `{uri}`: {msg}");
+            }
+        };
+        Ok(None)
     }
 }
 
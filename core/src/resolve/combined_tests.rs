@@ -2,7 +2,7 @@
 // SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
 
 use std::assert_matches;
-use std::collections::HashMap;
+use std::{collections::HashMap, num::NonZeroUsize};
 
 use fluent_uri::Iri;
 use indexmap::IndexMap;
@@ -10,11 +10,12 @@ use indexmap::IndexMap;
 use crate::{
     info::{InfoError, do_info},
     model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
-    project::memory::InMemoryProject,
+    project::{ProjectRead, memory::InMemoryProject},
     resolve::{
-        ResolveRead,
+        ResolutionOutcome, ResolveRead,
         combined::{CombinedResolver, NO_RESOLVER},
         memory::{AcceptAll, MemoryResolver},
+        null::NullResolver,
     },
 };
 
@@ -38,6 +39,7 @@ fn minimal_project<S: AsRef<str>, T: AsRef<str>>(name: S, version: T) -> InMemor
             includes_derived: None,
             includes_implied: None,
             checksum: None,
+            variants: IndexMap::new(),
         }),
         files: HashMap::new(),
         nominal_sources: vec![],
@@ -331,3 +333,60 @@ fn no_semantic_versions_error_test() {
 
     assert_matches!(info_meta, Err(InfoError::NoSemanticVersionsFound(_)));
 }
+
+fn name<R: ResolveRead>(outcome: ResolutionOutcome<R::ResolvedStorages>) -> String {
+    match outcome {
+        ResolutionOutcome::Resolved(projects) => {
+            let project = projects.into_iter().next().unwrap().unwrap();
+            project.name().unwrap().unwrap()
+        }
+        ResolutionOutcome::Unresolvable(_) => "unresolvable".to_string(),
+        ResolutionOutcome::UnsupportedIRIType(_) => "unsupported".to_string(),
+    }
+}
+
+#[test]
+fn resolve_many_matches_resolve_read() {
+    let [a, b, c, missing] = ["urn:kpar:a", "urn:kpar:b", "urn:kpar:c", "urn:kpar:missing"]
+        .map(|uri| Iri::parse(uri.to_string()).unwrap());
+
+    type Memory = MemoryResolver<AcceptAll, InMemoryProject>;
+    type Resolver = CombinedResolver<NullResolver, Memory, Memory, Memory>;
+    let resolver: Resolver = CombinedResolver {
+        file_resolver: NO_RESOLVER,
+        remote_resolver: Some(MemoryResolver::from([(
+            a.clone(),
+            vec![minimal_project("a", "1.0.0")],
+        )])),
+        local_resolver: Some(MemoryResolver::from([(
+            c.clone(),
+            vec![minimal_project("c", "1.0.0")],
+        )])),
+        index_resolver: Some(MemoryResolver::from([(
+            b.clone(),
+            vec![minimal_project("b", "1.0.0")],
+        )])),
+    };
+
+    let outcomes = resolver.resolve_many(
+        [a.clone(), b.clone(), a.clone(), c.clone(), missing.clone()],
+        NonZeroUsize::MIN,
+    );
+    let names: Vec<_> = outcomes
+        .into_iter()
+        .map(|(uri, outcome)| (uri, name::<Resolver>(outcome.unwrap())))
+        .collect();
+    let expected: Vec<_> = [a, b, c, missing]
+        .into_iter()
+        .map(|uri| {
+            let name = name::<Resolver>(resolver.resolve_read(&uri).unwrap());
+            (uri, name)
+        })
+        .collect();
+
+    assert_eq!(names, expected);
+    assert_eq!(
+        names.iter().map(|(_, n)| n.as_str()).collect::<Vec<_>>(),
+        ["a", "b", "c", "unresolvable"]
+    );
+}
@@ -225,6 +225,8 @@ impl From<LocalKParError> for FileResolverProjectError {
                 actual,
             },
             LocalKParError::EmptyKpar { path } => Self::EmptyKpar { path },
+            e @ (LocalKParError::MultipleProjects { .. }
+            | LocalKParError::NoProjectNamed { .. }) => Self::Other(e.to_string()),
         }
     }
 }
@@ -0,0 +1,196 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! On-disk cache of git repositories used by the git resolver.
+//!
+//! Each remote is mirrored in a bare repository keyed by its URL. A mirror
+//! is cloned when it's first needed and fetched the first time it's used in
+//! each process, so later checkouts only download new commits. If fetching
+//! fails, e.g. when offline, the mirror is used as is.
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use gix::remote::Direction;
+use thiserror::Error;
+
+use crate::{
+    config::local_fs::user_cache_dir,
+    project::utils::{FsIoError, wrapfs},
+    resolve::remove_entries,
+    timings,
+    utils::sha256_lowercase_hex,
+};
+
+/// Refspecs keeping the mirror's branches and tags identical to the remote's
+const MIRROR_REFSPECS: [&str; 2] = ["+refs/heads/*:refs/heads/*", "+refs/tags/*:refs/tags/*"];
+
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug, Clone)]
+pub struct GitCache {
+    root: Utf8PathBuf,
+    /// Mirrors already fetched by this process
+    fetched: Arc<Mutex<HashSet<Utf8PathBuf>>>,
+}
+
+#[derive(Error, Debug)]
+pub enum GitCacheError {
+    #[error("git clone from `{0}` failed: {1}")]
+    Clone(String, Box<gix::clone::Error>),
+    #[error("git fetch from `{0}` failed: {1}")]
+    Fetch(String, Box<gix::clone::fetch::Error>),
+    #[error("failed to check out cached git repository `{0}` into `{1}`")]
+    Checkout(String, Utf8PathBuf, #[source] BoxedError),
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+}
+
+impl From<FsIoError> for GitCacheError {
+    fn from(v: FsIoError) -> Self {
+        Self::Io(Box::new(v))
+    }
+}
+
+impl GitCache {
+    /// Cache storing mirrors in directory `root`, which is created when
+    /// needed
+    pub fn new<P: Into<Utf8PathBuf>>(root: P) -> Self {
+        Self {
+            root: root.into(),
+            fetched: Arc::default(),
+        }
+    }
+
+    /// `sysand/git` in the platform cache directory, if there is one
+    pub fn default_root() -> Option<Utf8PathBuf> {
+        Some(user_cache_dir()?.join("git"))
+    }
+
+    fn mirror_path(&self, url: &gix::Url) -> Utf8PathBuf {
+        self.root
+            .join(format!("{}.git", sha256_lowercase_hex(url.to_bstring())))
+    }
+
+    /// Check out the default branch of `url` into the empty directory
+    /// `dest`, updating the mirror of `url` first if needed
+    pub fn checkout(&self, url: &gix::Url, dest: &Utf8Path) -> Result<(), GitCacheError> {
+        self.checkout_rev(url, dest, None).map(|_| ())
+    }
+
+    /// Check out the commit with full id `rev` of `url`, or its default
+    /// branch if `rev` is `None`, into the empty directory `dest`. Returns the id of the
+    /// checked out commit
+    pub fn checkout_rev(
+        &self,
+        url: &gix::Url,
+        dest: &Utf8Path,
+        rev: Option<&str>,
+    ) -> Result<String, GitCacheError> {
+        let mirror = self.update(url)?;
+        checkout_commit(&mirror, dest, rev)
+            .map_err(|e| GitCacheError::Checkout(url.to_string(), dest.to_owned(), e))
+    }
+
+    /// Path to an up-to-date mirror of `url`
+    fn update(&self, url: &gix::Url) -> Result<Utf8PathBuf, GitCacheError> {
+        let mirror = self.mirror_path(url);
+        if !self.fetched.lock().unwrap().insert(mirror.clone()) {
+            return Ok(mirror);
+        }
+
+        let cached = wrapfs::is_dir(&mirror)?;
+        timings::record_cache("git", cached);
+        if cached {
+            log::debug!("fetching `{url}` into cached repository `{mirror}`");
+            if let Err(e) = fetch(&mirror, url) {
+                log::warn!(
+                    "failed to update cached git repository for `{url}`, using it as is: {e}"
+                );
+            }
+        } else {
+            log::debug!("cloning `{url}` into cache `{mirror}`");
+            self.clone_mirror(url, &mirror)?;
+        }
+        Ok(mirror)
+    }
+
+    fn clone_mirror(&self, url: &gix::Url, mirror: &Utf8Path) -> Result<(), GitCacheError> {
+        wrapfs::create_dir_all(&self.root)?;
+        // Clone next to the final location and move it there once complete,
+        // so that interrupted clones are never mistaken for mirrors
+        let tmp_dir = camino_tempfile::Builder::new()
+            .prefix(".tmp")
+            .tempdir_in(&self.root)
+            .map_err(FsIoError::MkTempDir)?;
+        let mut prepared_clone = gix::prepare_clone_bare(url.clone(), tmp_dir.path())
+            .map_err(|e| GitCacheError::Clone(url.to_string(), Box::new(e)))?
+            .configure_remote(
+                |remote| Ok(remote.with_refspecs(MIRROR_REFSPECS, Direction::Fetch)?),
+            );
+        prepared_clone
+            .fetch_only(gix::progress::Discard, &gix::interrupt::IS_INTERRUPTED)
+            .map_err(|e| GitCacheError::Fetch(url.to_string(), Box::new(e)))?;
+
+        if let Err(e) = std::fs::rename(tmp_dir.path(), mirror) {
+            // Another process may have cloned it concurrently
+            if !wrapfs::is_dir(mirror)? {
+                return Err(
+                    FsIoError::Move(tmp_dir.path().to_owned(), mirror.to_owned(), e).into(),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove all cached repositories, returning how many were removed
+    pub fn prune(&self) -> Result<usize, Box<FsIoError>> {
+        let removed = remove_entries(&self.root)?;
+        self.fetched.lock().unwrap().clear();
+        Ok(removed)
+    }
+}
+
+fn fetch(mirror: &Utf8Path, url: &gix::Url) -> Result<(), BoxedError> {
+    let repo = gix::open(mirror)?;
+    repo.remote_at(url.clone())?
+        .with_refspecs(MIRROR_REFSPECS, Direction::Fetch)?
+        .connect(Direction::Fetch)?
+        .prepare_fetch(gix::progress::Discard, Default::default())?
+        .receive(gix::progress::Discard, &gix::interrupt::IS_INTERRUPTED)?;
+    Ok(())
+}
+
+fn checkout_commit(
+    mirror: &Utf8Path,
+    dest: &Utf8Path,
+    rev: Option<&str>,
+) -> Result<String, BoxedError> {
+    let repo = gix::open(mirror)?;
+    let commit = match rev {
+        Some(rev) => repo.find_commit(gix::ObjectId::from_hex(rev.as_bytes())?)?,
+        None => repo.head_commit()?,
+    };
+    let tree = commit.tree_id()?;
+    let mut index = repo.index_from_tree(&tree)?;
+    let mut options =
+        repo.checkout_options(gix::worktree::stack::state::attributes::Source::IdMapping)?;
+    options.destination_is_initially_empty = true;
+    gix::worktree::state::checkout(
+        &mut index,
+        dest,
+        repo.objects.clone().into_arc()?,
+        &gix::progress::Discard,
+        &gix::progress::Discard,
+        &gix::interrupt::IS_INTERRUPTED,
+        options,
+    )?;
+    Ok(commit.id.to_string())
+}
+
+#[cfg(test)]
+#[path = "./git_cache_tests.rs"]
+mod tests;
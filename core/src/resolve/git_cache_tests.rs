@@ -0,0 +1,149 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+#![allow(unused_imports)]
+
+use std::process::Command;
+
+use assert_cmd::prelude::*;
+use camino::Utf8Path;
+use camino_tempfile::tempdir;
+
+use super::*;
+
+/// Run `git` with `args` in `path`
+#[cfg(feature = "alltests")]
+fn git(path: &Utf8Path, args: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+    Command::new("git")
+        .args(args)
+        .current_dir(path)
+        .output()?
+        .assert()
+        .success();
+    Ok(())
+}
+
+/// Commit `.project.json` with `version` to the repository at `path`
+#[cfg(feature = "alltests")]
+fn commit_version(path: &Utf8Path, version: &str) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::write(
+        path.join(".project.json"),
+        format!(r#"{{"name":"cached","version":"{version}"}}"#),
+    )?;
+    git(path, &["add", ".project.json"])?;
+    git(path, &["commit", "-m", version])
+}
+
+#[cfg(feature = "alltests")]
+fn init_repo(path: &Utf8Path) -> Result<gix::Url, Box<dyn std::error::Error>> {
+    git(path, &["init"])?;
+    git(path, &["config", "user.email", "user@sysand.com"])?;
+    git(path, &["config", "user.name", "Test User"])?;
+    commit_version(path, "1.0.0")?;
+    let path = wrapfs::canonicalize(path)?;
+    Ok(gix::url::parse(format!("file://{path}").as_str().into())?)
+}
+
+#[cfg(feature = "alltests")]
+fn checked_out_info(
+    cache: &GitCache,
+    url: &gix::Url,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let dest = tempdir()?;
+    cache.checkout(url, dest.path())?;
+    Ok(std::fs::read_to_string(dest.path().join(".project.json"))?)
+}
+
+#[cfg(feature = "alltests")]
+#[test]
+fn mirror_is_fetched_once_per_cache() -> Result<(), Box<dyn std::error::Error>> {
+    let repo_dir = tempdir()?;
+    let cache_dir = tempdir()?;
+    let url = init_repo(repo_dir.path())?;
+
+    let cache = GitCache::new(cache_dir.path());
+    assert!(checked_out_info(&cache, &url)?.contains("1.0.0"));
+
+    commit_version(repo_dir.path(), "2.0.0")?;
+    // Already fetched by this cache
+    assert!(checked_out_info(&cache, &url)?.contains("1.0.0"));
+    // A new process fetches the update
+    let cache = GitCache::new(cache_dir.path());
+    assert!(checked_out_info(&cache, &url)?.contains("2.0.0"));
+
+    Ok(())
+}
+
+#[cfg(feature = "alltests")]
+#[test]
+fn mirror_is_used_when_remote_is_unavailable() -> Result<(), Box<dyn std::error::Error>> {
+    let repo_dir = tempdir()?;
+    let cache_dir = tempdir()?;
+    let url = init_repo(repo_dir.path())?;
+
+    checked_out_info(&GitCache::new(cache_dir.path()), &url)?;
+    repo_dir.close()?;
+
+    let info = checked_out_info(&GitCache::new(cache_dir.path()), &url)?;
+    assert!(info.contains("1.0.0"));
+
+    Ok(())
+}
+
+#[cfg(feature = "alltests")]
+#[test]
+fn checkout_rev_checks_out_older_commit() -> Result<(), Box<dyn std::error::Error>> {
+    let repo_dir = tempdir()?;
+    let cache_dir = tempdir()?;
+    let url = init_repo(repo_dir.path())?;
+
+    let dest = tempdir()?;
+    let first = GitCache::new(cache_dir.path()).checkout_rev(&url, dest.path(), None)?;
+    commit_version(repo_dir.path(), "2.0.0")?;
+
+    let cache = GitCache::new(cache_dir.path());
+    assert!(checked_out_info(&cache, &url)?.contains("2.0.0"));
+    let dest = tempdir()?;
+    assert_eq!(cache.checkout_rev(&url, dest.path(), Some(&first))?, first);
+    let info = std::fs::read_to_string(dest.path().join(".project.json"))?;
+    assert!(info.contains("1.0.0"));
+
+    let dest = tempdir()?;
+    let missing = "0".repeat(40);
+    assert!(
+        cache
+            .checkout_rev(&url, dest.path(), Some(&missing))
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "alltests")]
+#[test]
+fn prune_removes_mirrors() -> Result<(), Box<dyn std::error::Error>> {
+    let repo_dir = tempdir()?;
+    let cache_dir = tempdir()?;
+    let url = init_repo(repo_dir.path())?;
+
+    let cache = GitCache::new(cache_dir.path());
+    checked_out_info(&cache, &url)?;
+
+    assert_eq!(cache.prune()?, 1);
+    assert_eq!(std::fs::read_dir(cache_dir.path())?.count(), 0);
+    // Removed mirrors are cloned again
+    assert!(checked_out_info(&cache, &url)?.contains("1.0.0"));
+
+    Ok(())
+}
+
+#[test]
+fn prune_missing_cache() -> Result<(), Box<dyn std::error::Error>> {
+    let cache_dir = tempdir()?;
+
+    let cache = GitCache::new(cache_dir.path().join("git"));
+
+    assert_eq!(cache.prune()?, 0);
+
+    Ok(())
+}
@@ -5,15 +5,19 @@ use thiserror::Error;
 
 use crate::{
     project::gix_git_download::{GixDownloadedError, GixDownloadedProject},
-    resolve::{ResolutionOutcome, ResolveRead},
+    resolve::{ResolutionOutcome, ResolveRead, git_cache::GitCache},
     utils::scheme::{
         SCHEME_FILE, SCHEME_GIT_FILE, SCHEME_GIT_HTTP, SCHEME_GIT_HTTPS, SCHEME_GIT_SSH,
         SCHEME_HTTP, SCHEME_HTTPS, SCHEME_SSH,
     },
 };
 
-#[derive(Debug)]
-pub struct GitResolver {}
+#[derive(Debug, Default)]
+pub struct GitResolver {
+    /// Cache of cloned repositories. Without it, each resolved project is
+    /// cloned anew
+    pub cache: Option<GitCache>,
+}
 
 #[derive(Error, Debug)]
 pub enum GitResolverError {
@@ -56,6 +60,7 @@ impl ResolveRead for GitResolver {
         Ok(ResolutionOutcome::Resolved(std::iter::once(
             // TODO: use trim_prefix() once it's stable
             GixDownloadedProject::new(uri.as_str().strip_prefix("git+").unwrap_or(uri.as_str()))
+                .map(|p| p.with_cache(self.cache.clone()))
                 .map_err(|e| e.into()),
         )))
     }
@@ -9,7 +9,7 @@ fn un_once<T>(x: &mut std::iter::Once<T>) -> T {
 
 #[test]
 fn basic_url_examples() -> Result<(), Box<dyn std::error::Error>> {
-    let resolver = GitResolver {};
+    let resolver = GitResolver::default();
 
     let ResolutionOutcome::Resolved(mut one_http_proj) =
         resolver.resolve_read_raw("http://www.example.com/proj")?
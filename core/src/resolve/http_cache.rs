@@ -0,0 +1,233 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! On-disk cache of project metadata (`.project.json` and `.meta.json`)
+//! fetched over HTTP.
+//!
+//! [`HttpCache`] is a middleware for the reqwest client, so every resolver
+//! using the client benefits from it. Cached metadata is used without
+//! contacting the server for the cache's TTL. Afterwards it is revalidated
+//! with a conditional request using the `ETag` and `Last-Modified` headers
+//! of the cached response, and only downloaded again if it has changed.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use camino::Utf8PathBuf;
+use http::Extensions;
+use reqwest::{
+    Method, Request, Response, ResponseBuilderExt, StatusCode,
+    header::{self, HeaderValue},
+};
+use reqwest_middleware::{Middleware, Next};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    config::local_fs::user_cache_dir, project::utils::FsIoError, resolve::remove_entries, timings,
+    utils::sha256_lowercase_hex,
+};
+
+/// How long cached metadata is used without revalidating it, unless
+/// configured otherwise
+pub const DEFAULT_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Files whose responses are cached
+const CACHED_FILES: &[&str] = &[".project.json", ".meta.json"];
+
+#[derive(Debug, Clone)]
+pub struct HttpCache {
+    root: Utf8PathBuf,
+    ttl: Duration,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    /// Seconds since Unix epoch when the response was fetched or last
+    /// revalidated
+    fetched_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content_type: Option<String>,
+    body: String,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+impl CacheEntry {
+    fn is_fresh(&self, ttl: Duration) -> bool {
+        now().saturating_sub(self.fetched_at) < ttl.as_secs()
+    }
+
+    fn to_response(&self, url: Url, method: &Method) -> Response {
+        let mut builder = http::Response::builder().status(StatusCode::OK).url(url);
+        if let Some(content_type) = &self.content_type {
+            builder = builder.header(header::CONTENT_TYPE, content_type);
+        }
+        let body = if method == Method::HEAD {
+            String::new()
+        } else {
+            self.body.clone()
+        };
+        builder
+            .body(body)
+            .expect("cached response headers were valid when stored")
+            .into()
+    }
+}
+
+fn header_string(response: &http::HeaderMap, name: header::HeaderName) -> Option<String> {
+    response
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_owned)
+}
+
+impl HttpCache {
+    /// Cache storing entries in directory `root`, which is created when
+    /// needed
+    pub fn new<P: Into<Utf8PathBuf>>(root: P, ttl: Duration) -> Self {
+        Self {
+            root: root.into(),
+            ttl,
+        }
+    }
+
+    /// `sysand/http` in the platform cache directory, if there is one
+    pub fn default_root() -> Option<Utf8PathBuf> {
+        Some(user_cache_dir()?.join("http"))
+    }
+
+    /// Remove all cached responses, returning how many were removed
+    pub fn prune(&self) -> Result<usize, Box<FsIoError>> {
+        remove_entries(&self.root)
+    }
+
+    fn is_cached(url: &Url) -> bool {
+        CACHED_FILES.iter().any(|f| url.path().ends_with(f))
+    }
+
+    fn entry_path(&self, url: &Url) -> Utf8PathBuf {
+        self.root
+            .join(format!("{}.json", sha256_lowercase_hex(url.as_str())))
+    }
+
+    fn read(&self, url: &Url) -> Option<CacheEntry> {
+        let contents = std::fs::read(self.entry_path(url)).ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&contents).ok()?;
+        // Guard against hash collisions
+        (entry.url == url.as_str()).then_some(entry)
+    }
+
+    /// Store `entry`. Failures are logged and otherwise ignored, since the
+    /// cache is only an optimization
+    fn write(&self, url: &Url, entry: &CacheEntry) {
+        let write = || -> std::io::Result<()> {
+            std::fs::create_dir_all(&self.root)?;
+            let mut file = camino_tempfile::NamedUtf8TempFile::new_in(&self.root)?;
+            serde_json::to_writer(&mut file, entry)?;
+            // Replace atomically, concurrent readers see either version
+            file.persist(self.entry_path(url))?;
+            Ok(())
+        };
+        if let Err(e) = write() {
+            log::debug!("failed to cache response from `{url}`: {e}");
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for HttpCache {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let method = req.method().clone();
+        if !(method == Method::GET || method == Method::HEAD) || !Self::is_cached(req.url()) {
+            return next.run(req, extensions).await;
+        }
+
+        let url = req.url().clone();
+        let cached = self.read(&url);
+        if let Some(entry) = &cached
+            && entry.is_fresh(self.ttl)
+        {
+            log::debug!("using cached response for {method} `{url}`");
+            timings::record_cache("http", true);
+            return Ok(entry.to_response(url, &method));
+        }
+        if method == Method::HEAD {
+            return next.run(req, extensions).await;
+        }
+
+        if let Some(entry) = &cached {
+            let headers = req.headers_mut();
+            if let Some(etag) = entry.etag.as_deref().and_then(|v| v.parse().ok()) {
+                headers.insert(header::IF_NONE_MATCH, etag);
+            }
+            if let Some(modified) = entry
+                .last_modified
+                .as_deref()
+                .and_then(|v| HeaderValue::from_str(v).ok())
+            {
+                headers.insert(header::IF_MODIFIED_SINCE, modified);
+            }
+        }
+
+        let resp = next.run(req, extensions).await?;
+        match (resp.status(), cached) {
+            (StatusCode::NOT_MODIFIED, Some(mut entry)) => {
+                log::debug!("cached response for `{url}` is up to date");
+                timings::record_cache("http", true);
+                entry.fetched_at = now();
+                self.write(&url, &entry);
+                Ok(entry.to_response(url, &method))
+            }
+            (StatusCode::OK, _) => {
+                timings::record_cache("http", false);
+                let final_url = resp.url().clone();
+                let version = resp.version();
+                let headers = resp.headers().clone();
+                let body = resp.bytes().await?;
+                if let Ok(text) = std::str::from_utf8(&body) {
+                    let entry = CacheEntry {
+                        url: url.to_string(),
+                        fetched_at: now(),
+                        etag: header_string(&headers, header::ETAG),
+                        last_modified: header_string(&headers, header::LAST_MODIFIED),
+                        content_type: header_string(&headers, header::CONTENT_TYPE),
+                        body: text.to_owned(),
+                    };
+                    self.write(&url, &entry);
+                }
+
+                // The body was consumed, so build an equivalent response
+                let mut builder = http::Response::builder()
+                    .status(StatusCode::OK)
+                    .version(version)
+                    .url(final_url);
+                if let Some(h) = builder.headers_mut() {
+                    *h = headers;
+                }
+                Ok(builder
+                    .body(body)
+                    .expect("headers were valid in original response")
+                    .into())
+            }
+            (_, _) => Ok(resp),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./http_cache_tests.rs"]
+mod tests;
@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::{sync::Arc, time::Duration};
+
+use camino_tempfile::tempdir;
+use reqwest::header;
+
+use super::*;
+use crate::{
+    auth::Unauthenticated,
+    config::NetworkConfig,
+    project::{ProjectRead, ProjectReadAsync, reqwest_src::ReqwestSrcProjectAsync},
+    resolve::net_utils::create_cached_reqwest_client,
+};
+
+const INFO: &str = r#"{"name":"cached","version":"1.2.3"}"#;
+
+fn project(
+    url: &str,
+    cache: HttpCache,
+) -> Result<impl ProjectRead<Error: std::fmt::Debug> + use<>, Box<dyn std::error::Error>> {
+    Ok(ReqwestSrcProjectAsync::new(
+        create_cached_reqwest_client(cache, &NetworkConfig::default(), &[])?,
+        reqwest::Url::parse(url)?,
+        Arc::new(Unauthenticated {}),
+        None,
+    )
+    .to_tokio_sync(Arc::new(
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?,
+    )))
+}
+
+#[test]
+fn fresh_entry_is_used() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = mockito::Server::new();
+    let cache_dir = tempdir()?;
+
+    let info_mock = server
+        .mock("GET", "/.project.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(INFO)
+        .expect(1)
+        .create();
+
+    for _ in 0..2 {
+        let project = project(
+            &server.url(),
+            HttpCache::new(cache_dir.path(), Duration::from_secs(3600)),
+        )?;
+        let info = project.get_info().unwrap().unwrap();
+        assert_eq!(info.name, "cached");
+    }
+
+    info_mock.assert();
+
+    Ok(())
+}
+
+#[test]
+fn stale_entry_is_revalidated() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = mockito::Server::new();
+    let cache_dir = tempdir()?;
+
+    let info_mock = server
+        .mock("GET", "/.project.json")
+        .match_header(header::IF_NONE_MATCH.as_str(), mockito::Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("etag", "\"v1\"")
+        .with_body(INFO)
+        .expect(1)
+        .create();
+    let not_modified_mock = server
+        .mock("GET", "/.project.json")
+        .match_header(header::IF_NONE_MATCH.as_str(), "\"v1\"")
+        .with_status(304)
+        .expect(2)
+        .create();
+
+    for _ in 0..3 {
+        let project = project(
+            &server.url(),
+            HttpCache::new(cache_dir.path(), Duration::ZERO),
+        )?;
+        let info = project.get_info().unwrap().unwrap();
+        assert_eq!(info.name, "cached");
+        assert_eq!(info.version, "1.2.3");
+    }
+
+    info_mock.assert();
+    not_modified_mock.assert();
+
+    Ok(())
+}
+
+#[test]
+fn errors_and_sources_are_not_cached() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = mockito::Server::new();
+    let cache_dir = tempdir()?;
+
+    let meta_mock = server
+        .mock("GET", "/.meta.json")
+        .with_status(404)
+        .expect(2)
+        .create();
+    let src_mock = server
+        .mock("GET", "/test.sysml")
+        .with_status(200)
+        .with_body("package Test;")
+        .expect(2)
+        .create();
+
+    for _ in 0..2 {
+        let project = project(
+            &server.url(),
+            HttpCache::new(cache_dir.path(), Duration::from_secs(3600)),
+        )?;
+        assert!(project.get_meta().unwrap().is_none());
+        let mut src = String::new();
+        std::io::Read::read_to_string(&mut project.read_source("test.sysml").unwrap(), &mut src)?;
+        assert_eq!(src, "package Test;");
+    }
+
+    meta_mock.assert();
+    src_mock.assert();
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
-use std::{fmt::Debug, sync::Arc};
+use std::{collections::HashSet, fmt::Debug, num::NonZeroUsize, sync::Arc};
 
 use crate::{
     env::{SyncStreamIter, utils::ErrorBound},
@@ -10,12 +10,21 @@ use crate::{
 
 use futures::stream::StreamExt as _;
 
+pub use sysand_macros::ResolveRead;
+// Named by the expansion of `#[derive(ResolveRead)]`
+#[doc(hidden)]
+pub use fluent_uri::Iri;
+
 pub mod combined;
 pub mod env;
 #[cfg(feature = "filesystem")]
 pub mod file;
 #[cfg(all(feature = "filesystem", feature = "networking"))]
+pub mod git_cache;
+#[cfg(all(feature = "filesystem", feature = "networking"))]
 pub mod gix_git;
+#[cfg(all(feature = "filesystem", feature = "networking"))]
+pub mod http_cache;
 pub mod memory;
 #[cfg(feature = "networking")]
 pub mod net_utils;
@@ -24,6 +33,7 @@ pub mod priority;
 pub mod remote;
 #[cfg(all(feature = "filesystem", feature = "networking"))]
 pub mod reqwest_http;
+pub mod scoped;
 pub mod sequential;
 #[cfg(all(feature = "filesystem", feature = "networking"))]
 pub mod standard;
@@ -39,6 +49,22 @@ pub enum ResolutionOutcome<T> {
     Unresolvable(String),
 }
 
+/// An IRI and the outcome of resolving it
+pub type IriOutcome<T, E> = (fluent_uri::Iri<String>, Result<ResolutionOutcome<T>, E>);
+
+/// Default number of IRIs resolved concurrently by `resolve_many`
+pub const DEFAULT_RESOLVE_CONCURRENCY: NonZeroUsize = NonZeroUsize::new(8).unwrap();
+
+/// `uris` without repetitions, in order of first occurrence
+pub(crate) fn dedup_iris<I: IntoIterator<Item = fluent_uri::Iri<String>>>(
+    uris: I,
+) -> Vec<fluent_uri::Iri<String>> {
+    let mut seen = HashSet::new();
+    uris.into_iter()
+        .filter(|uri| seen.insert(uri.clone()))
+        .collect()
+}
+
 impl<T> ResolutionOutcome<T> {
     pub fn map<U, F: FnOnce(T) -> U>(self, op: F) -> ResolutionOutcome<U> {
         match self {
@@ -80,6 +106,25 @@ pub trait ResolveRead {
         uri: &fluent_uri::Iri<String>,
     ) -> Result<ResolutionOutcome<Self::ResolvedStorages>, Self::Error>;
 
+    /// Resolve each distinct IRI of `uris`, with up to `concurrency`
+    /// resolutions in progress at a time if the resolver can do them
+    /// concurrently. Outcomes are in order of first occurrence of the IRI.
+    /// Resolvers may leave out IRIs they consider equivalent to an earlier one
+    fn resolve_many<I: IntoIterator<Item = fluent_uri::Iri<String>>>(
+        &self,
+        uris: I,
+        concurrency: NonZeroUsize,
+    ) -> Vec<IriOutcome<Self::ResolvedStorages, Self::Error>> {
+        let _ = concurrency;
+        dedup_iris(uris)
+            .into_iter()
+            .map(|uri| {
+                let outcome = self.resolve_read(&uri);
+                (uri, outcome)
+            })
+            .collect()
+    }
+
     /// Treat this `ResolveRead` as a (trivial) `ResolveReadAsync`
     fn to_async(self) -> AsAsyncResolve<Self>
     where
@@ -122,6 +167,22 @@ pub trait ResolveReadAsync {
         uri: &fluent_uri::Iri<String>,
     ) -> impl Future<Output = Result<ResolutionOutcome<Self::ResolvedStorages>, Self::Error>>;
 
+    /// Resolve each distinct IRI of `uris`, with up to `concurrency`
+    /// resolutions in progress at a time. Outcomes are yielded in order of
+    /// first occurrence of the IRI
+    fn resolve_many_async<I: IntoIterator<Item = fluent_uri::Iri<String>>>(
+        &self,
+        uris: I,
+        concurrency: NonZeroUsize,
+    ) -> impl futures::Stream<Item = IriOutcome<Self::ResolvedStorages, Self::Error>> {
+        futures::stream::iter(dedup_iris(uris))
+            .map(move |uri| async move {
+                let outcome = self.resolve_read_async(&uri).await;
+                (uri, outcome)
+            })
+            .buffered(concurrency.get())
+    }
+
     // Maybe make this return an associated type instead? Would, for example, allow
     // .as_async.as_tokio_sync == .as_tokio_sync.as_async == id
     /// Treat this `ResolveReadAsync` as a `ResolveRead` using the provided tokio runtime.
@@ -222,29 +283,77 @@ where
         &self,
         uri: &fluent_uri::Iri<String>,
     ) -> Result<ResolutionOutcome<Self::ResolvedStorages>, Self::Error> {
-        Ok(
-            match self.runtime.block_on(self.inner.resolve_read_async(uri))? {
-                ResolutionOutcome::Resolved(storages) => {
-                    let runtime_clone = self.runtime.clone();
-
-                    let inner: futures::stream::Map<_, Box<dyn Fn(_) -> _>> =
-                        storages.map(Box::new(move |project| {
-                            Ok(AsSyncProjectTokio {
-                                runtime: runtime_clone.clone(),
-                                inner: project?,
-                            })
-                        }));
-
-                    ResolutionOutcome::Resolved(SyncStreamIter {
-                        runtime: self.runtime.clone(),
-                        inner,
-                    })
-                }
-                ResolutionOutcome::UnsupportedIRIType(msg) => {
-                    ResolutionOutcome::UnsupportedIRIType(msg)
-                }
-                ResolutionOutcome::Unresolvable(msg) => ResolutionOutcome::Unresolvable(msg),
-            },
-        )
+        Ok(self.to_sync_outcome(self.runtime.block_on(self.inner.resolve_read_async(uri))?))
+    }
+
+    fn resolve_many<I: IntoIterator<Item = fluent_uri::Iri<String>>>(
+        &self,
+        uris: I,
+        concurrency: NonZeroUsize,
+    ) -> Vec<IriOutcome<Self::ResolvedStorages, Self::Error>> {
+        let outcomes: Vec<_> = self
+            .runtime
+            .block_on(self.inner.resolve_many_async(uris, concurrency).collect());
+        outcomes
+            .into_iter()
+            .map(|(uri, outcome)| (uri, outcome.map(|o| self.to_sync_outcome(o))))
+            .collect()
+    }
+}
+
+impl<T: ResolveReadAsync> AsSyncResolveTokio<T>
+where
+    <T as ResolveReadAsync>::ResolvedStorages: Unpin,
+{
+    fn to_sync_outcome(
+        &self,
+        outcome: ResolutionOutcome<<T as ResolveReadAsync>::ResolvedStorages>,
+    ) -> ResolutionOutcome<<Self as ResolveRead>::ResolvedStorages> {
+        match outcome {
+            ResolutionOutcome::Resolved(storages) => {
+                let runtime_clone = self.runtime.clone();
+
+                let inner: futures::stream::Map<_, Box<dyn Fn(_) -> _>> =
+                    storages.map(Box::new(move |project| {
+                        Ok(AsSyncProjectTokio {
+                            runtime: runtime_clone.clone(),
+                            inner: project?,
+                        })
+                    }));
+
+                ResolutionOutcome::Resolved(SyncStreamIter {
+                    runtime: self.runtime.clone(),
+                    inner,
+                })
+            }
+            ResolutionOutcome::UnsupportedIRIType(msg) => {
+                ResolutionOutcome::UnsupportedIRIType(msg)
+            }
+            ResolutionOutcome::Unresolvable(msg) => ResolutionOutcome::Unresolvable(msg),
+        }
+    }
+}
+
+/// Remove everything in directory `root`, if it exists, returning the
+/// number of removed entries
+#[cfg(all(feature = "filesystem", feature = "networking"))]
+pub(crate) fn remove_entries(
+    root: &camino::Utf8Path,
+) -> Result<usize, Box<crate::project::utils::FsIoError>> {
+    use crate::project::utils::{FsIoError, wrapfs};
+
+    if !wrapfs::is_dir(root)? {
+        return Ok(0);
+    }
+    let mut removed = 0;
+    for entry in wrapfs::read_dir(root)? {
+        let entry = entry.map_err(|e| FsIoError::ReadDir(root.to_owned(), e))?;
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            wrapfs::remove_dir_all(entry.path())?;
+        } else {
+            wrapfs::remove_file(entry.path())?;
+        }
+        removed += 1;
     }
+    Ok(removed)
 }
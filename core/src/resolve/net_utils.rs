@@ -1,11 +1,31 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
 
-use std::{error::Error, fmt::Display};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::Display,
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+    time::{Duration, SystemTime},
+};
 
-use reqwest::header;
-use reqwest_middleware::{ClientWithMiddleware, RequestBuilder};
-use url::Url;
+use http::Extensions;
+use reqwest::{Request, Response, StatusCode, header};
+use reqwest_middleware::{ClientWithMiddleware, Middleware, Next, RequestBuilder};
+use tokio::{
+    sync::{OwnedSemaphorePermit, Semaphore},
+    time::Instant,
+};
+use url::{Origin, Url};
+
+use crate::{
+    auth::HTTPAuthentication,
+    config::{Config, Index, NetworkConfig},
+    timings::{self, Phase},
+};
+
+#[cfg(feature = "filesystem")]
+use crate::resolve::http_cache::HttpCache;
 
 // application/vnd.github.raw is required for GitHub API to return raw
 // file contents
@@ -36,6 +56,16 @@ pub fn json_get_request(url: impl Into<Url>) -> impl Fn(&ClientWithMiddleware) -
     }
 }
 
+/// For probing KPARs without downloading them
+pub fn kpar_head_request(url: impl Into<Url>) -> impl Fn(&ClientWithMiddleware) -> RequestBuilder {
+    let this_url = url.into();
+    move |client: &ClientWithMiddleware| -> RequestBuilder {
+        client
+            .head(this_url.clone())
+            .header(header::ACCEPT, KPAR_ACCEPT)
+    }
+}
+
 pub fn json_head_request(url: impl Into<Url>) -> impl Fn(&ClientWithMiddleware) -> RequestBuilder {
     let this_url = url.into();
     move |client: &ClientWithMiddleware| -> RequestBuilder {
@@ -45,6 +75,72 @@ pub fn json_head_request(url: impl Into<Url>) -> impl Fn(&ClientWithMiddleware)
     }
 }
 
+/// What a HEAD request tells about a remote file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeadProbe {
+    /// A GET request for the file would definitely fail or return
+    /// something else than the file, e.g. an HTML page
+    Invalid,
+    /// The file seems to exist. `size` is its size in bytes, if the
+    /// server reported it
+    Present { size: Option<u64> },
+    /// Nothing definite can be said, e.g. the server does not support
+    /// HEAD requests
+    Unknown,
+}
+
+impl HeadProbe {
+    pub fn from_response(response: &reqwest::Response) -> Self {
+        let status = response.status();
+        if status == StatusCode::METHOD_NOT_ALLOWED {
+            return HeadProbe::Unknown;
+        }
+        if !status.is_success() {
+            return HeadProbe::Invalid;
+        }
+        // Servers commonly answer requests for any path with an HTML
+        // page (e.g. a login page or a single page app), which is never
+        // a project file
+        let is_html = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(';').next())
+            .is_some_and(|v| v.trim().eq_ignore_ascii_case("text/html"));
+        if is_html {
+            return HeadProbe::Invalid;
+        }
+        // `Response::content_length()` is the length of the (empty) body
+        // of the HEAD response, so the header is read directly
+        let size = response
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok());
+        HeadProbe::Present { size }
+    }
+}
+
+/// Send the HEAD request made by `request` and classify the response.
+/// Failing to send the request means that a GET request would fail too
+pub async fn probe_head<Policy, F>(
+    client: &ClientWithMiddleware,
+    auth_policy: &Policy,
+    request: &F,
+) -> HeadProbe
+where
+    Policy: HTTPAuthentication,
+    F: Fn(&ClientWithMiddleware) -> RequestBuilder + 'static,
+{
+    match auth_policy.with_authentication(client, request).await {
+        Ok(response) => HeadProbe::from_response(&response),
+        Err(e) => {
+            log::debug!("HEAD request failed: {e}");
+            HeadProbe::Invalid
+        }
+    }
+}
+
 /// For all text files that are not JSON
 pub fn text_get_request(url: impl Into<Url>) -> impl Fn(&ClientWithMiddleware) -> RequestBuilder {
     let this_url = url.into();
@@ -57,19 +153,77 @@ pub fn text_get_request(url: impl Into<Url>) -> impl Fn(&ClientWithMiddleware) -
 
 #[derive(Debug)]
 pub struct ReqwestClientBuildError {
-    inner: reqwest::Error,
+    inner: ClientBuildErrorKind,
+}
+
+#[derive(Debug)]
+enum ClientBuildErrorKind {
+    Reqwest(reqwest::Error),
+    InvalidProxy(String, reqwest::Error),
+    ReadCaBundle(String, std::io::Error),
+    InvalidCaBundle(String, reqwest::Error),
+    EmptyCaBundle(String),
+    InvalidIndexUrl(String, url::ParseError),
+    ReadClientCert(String, std::io::Error),
+    InvalidClientCert(String, reqwest::Error),
 }
 
 impl From<reqwest::Error> for ReqwestClientBuildError {
     fn from(value: reqwest::Error) -> Self {
+        Self {
+            inner: ClientBuildErrorKind::Reqwest(value),
+        }
+    }
+}
+
+impl From<ClientBuildErrorKind> for ReqwestClientBuildError {
+    fn from(value: ClientBuildErrorKind) -> Self {
         Self { inner: value }
     }
 }
 
 impl Display for ReqwestClientBuildError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "failed to build reqwest HTTP client: {}", self.inner)?;
-        match self.inner.source() {
+        let source: &dyn Error = match &self.inner {
+            ClientBuildErrorKind::Reqwest(e) => {
+                write!(f, "failed to build reqwest HTTP client: {e}")?;
+                e
+            }
+            ClientBuildErrorKind::InvalidProxy(url, e) => {
+                write!(f, "invalid proxy `{url}` in `[network] proxy`: {e}")?;
+                e
+            }
+            ClientBuildErrorKind::ReadCaBundle(path, e) => {
+                return write!(f, "failed to read CA bundle `{path}`: {e}");
+            }
+            ClientBuildErrorKind::EmptyCaBundle(path) => {
+                return write!(f, "CA bundle `{path}` contains no PEM certificates");
+            }
+            ClientBuildErrorKind::InvalidCaBundle(path, e) => {
+                write!(
+                    f,
+                    "invalid CA bundle `{path}`, expected PEM certificates: {e}"
+                )?;
+                e
+            }
+            ClientBuildErrorKind::InvalidIndexUrl(url, e) => {
+                return write!(
+                    f,
+                    "invalid URL `{url}` of index with client certificate: {e}"
+                );
+            }
+            ClientBuildErrorKind::ReadClientCert(path, e) => {
+                return write!(f, "failed to read client certificate or key `{path}`: {e}");
+            }
+            ClientBuildErrorKind::InvalidClientCert(path, e) => {
+                write!(
+                    f,
+                    "invalid client certificate `{path}`, expected PEM certificate and private key: {e}"
+                )?;
+                e
+            }
+        };
+        match source.source() {
             Some(source) => write!(f, "\ncaused by: {}", source),
             None => Ok(()),
         }
@@ -81,14 +235,487 @@ impl Error for ReqwestClientBuildError {}
 /// Note that gix manages its own HTTP client, so logs may indicate
 /// duplicate initialization.
 ///
+/// Proxies are taken from the `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY`
+/// and `NO_PROXY` environment variables.
+///
 /// Relies on `reqwest`'s default redirect behaviour (up to 10
 /// automatic redirects); index clients MUST follow HTTP redirects
 /// on the index URL discovery fetch and on every index resource.
 pub fn create_reqwest_client()
 -> Result<reqwest_middleware::ClientWithMiddleware, ReqwestClientBuildError> {
+    create_reqwest_client_with_network(&NetworkConfig::default(), &[])
+}
+
+/// Create a reqwest client like [`create_reqwest_client`], using the proxy,
+/// CA certificates and request limits configured in `network`. Requests to
+/// `indexes` with a client certificate present it, for indexes requiring
+/// mutual TLS.
+pub fn create_reqwest_client_with_network(
+    network: &NetworkConfig,
+    indexes: &[Index],
+) -> Result<reqwest_middleware::ClientWithMiddleware, ReqwestClientBuildError> {
+    Ok(
+        reqwest_middleware::ClientBuilder::new(create_inner_client(network)?)
+            .with(HostLimiter::new(network))
+            .with(RequestLogger)
+            .with(ClientCertRouter::new(network, indexes)?)
+            .build(),
+    )
+}
+
+/// Create a reqwest client like [`create_reqwest_client_with_network`]
+/// that caches project metadata in `cache`.
+#[cfg(feature = "filesystem")]
+pub fn create_cached_reqwest_client(
+    cache: HttpCache,
+    network: &NetworkConfig,
+    indexes: &[Index],
+) -> Result<reqwest_middleware::ClientWithMiddleware, ReqwestClientBuildError> {
+    Ok(
+        reqwest_middleware::ClientBuilder::new(create_inner_client(network)?)
+            .with(cache)
+            .with(HostLimiter::new(network))
+            .with(RequestLogger)
+            .with(ClientCertRouter::new(network, indexes)?)
+            .build(),
+    )
+}
+
+/// Creates the HTTP client of a command run from its configuration, once,
+/// and hands out handles to it. Resolvers, KPAR downloads and the publish
+/// client given these handles share the client's connection pool, so
+/// connections, TLS sessions and HTTP/2 connections are reused across them.
+///
+/// Clones of the factory share the client. It is created when first asked
+/// for, so runs that make no requests do not read e.g. the CA bundle.
+#[derive(Debug, Clone, Default)]
+pub struct HttpClientFactory {
+    network: NetworkConfig,
+    indexes: Vec<Index>,
+    #[cfg(feature = "filesystem")]
+    cache: Option<HttpCache>,
+    client: Arc<OnceLock<ClientWithMiddleware>>,
+}
+
+impl HttpClientFactory {
+    /// Factory of clients using the `[network]` settings and index client
+    /// certificates of `config`
+    pub fn new(config: &Config) -> Self {
+        Self {
+            network: config.network.clone(),
+            indexes: config.indexes.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Factory like [`Self::new`], shared by every caller in the process
+    /// with the same `[network]` settings and index client certificates, so
+    /// that embedding tools resolving projects repeatedly reuse connections.
+    /// Connections belong to the runtime that opened them, so the clients
+    /// must only be used on [`shared_runtime`](crate::session::shared_runtime)
+    pub fn shared(config: &Config) -> Self {
+        static SHARED: Mutex<Vec<HttpClientFactory>> = Mutex::new(Vec::new());
+        let mut shared = SHARED.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(factory) = shared
+            .iter()
+            .find(|f| f.network == config.network && f.indexes == config.indexes)
+        {
+            return factory.clone();
+        }
+        let factory = Self::new(config);
+        shared.push(factory.clone());
+        factory
+    }
+
+    /// Cache project metadata in `cache`
+    #[cfg(feature = "filesystem")]
+    pub fn with_cache(self, cache: HttpCache) -> Self {
+        Self {
+            cache: Some(cache),
+            client: Arc::default(),
+            ..self
+        }
+    }
+
+    /// Handle to the shared client, creating it if this is the first
+    pub fn client(&self) -> Result<ClientWithMiddleware, ReqwestClientBuildError> {
+        if let Some(client) = self.client.get() {
+            return Ok(client.clone());
+        }
+        #[cfg(feature = "filesystem")]
+        let client = match &self.cache {
+            Some(cache) => {
+                create_cached_reqwest_client(cache.clone(), &self.network, &self.indexes)?
+            }
+            None => create_reqwest_client_with_network(&self.network, &self.indexes)?,
+        };
+        #[cfg(not(feature = "filesystem"))]
+        let client = create_reqwest_client_with_network(&self.network, &self.indexes)?;
+        Ok(self.client.get_or_init(|| client).clone())
+    }
+}
+
+/// Logs every request sent over the network with its status and the time
+/// until the response headers arrived, in the `method`, `url`, `status`
+/// and `elapsed_ms` fields. Requests answered from the cache are not sent
+#[derive(Debug)]
+struct RequestLogger;
+
+#[async_trait::async_trait]
+impl Middleware for RequestLogger {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let method = req.method().clone();
+        let url = req.url().clone();
+        log::trace!(method = method.as_str(), url = url.as_str(); "{method} {url}");
+        let start = std::time::Instant::now();
+        let timer = timings::time(Phase::Download);
+        let result = next.run(req, extensions).await;
+        drop(timer);
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+        match &result {
+            Ok(response) => {
+                timings::record_download(
+                    url.host_str().unwrap_or_default(),
+                    response.content_length(),
+                );
+                let status = response.status();
+                log::debug!(
+                    method = method.as_str(), url = url.as_str(), status = status.as_u16(), elapsed_ms;
+                    "{method} {url}: {status} in {elapsed_ms} ms"
+                );
+            }
+            Err(e) => log::debug!(
+                method = method.as_str(), url = url.as_str(), elapsed_ms;
+                "{method} {url} failed after {elapsed_ms} ms: {e}"
+            ),
+        }
+        result
+    }
+}
+
+/// TLS connection to an index with a client certificate failed, most
+/// likely because the server did not accept the certificate
+#[derive(Debug)]
+pub struct ClientCertHandshakeError {
+    pub origin: String,
+    pub client_cert: String,
+    source: reqwest::Error,
+}
+
+impl Display for ClientCertHandshakeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TLS connection to `{}` with client certificate `{}` failed",
+            self.origin, self.client_cert
+        )?;
+        // The reqwest error itself only names the URL
+        let mut cause: &dyn Error = &self.source;
+        while let Some(source) = cause.source() {
+            cause = source;
+        }
+        write!(f, ": {cause}")
+    }
+}
+
+impl Error for ClientCertHandshakeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Sends requests to the origins of indexes with a client certificate
+/// through a client presenting that certificate. Other requests are sent
+/// by the default client.
+#[derive(Debug)]
+struct ClientCertRouter {
+    clients: Vec<(Origin, String, reqwest::Client)>,
+}
+
+impl ClientCertRouter {
+    fn new(network: &NetworkConfig, indexes: &[Index]) -> Result<Self, ReqwestClientBuildError> {
+        let mut clients = vec![];
+        for index in indexes {
+            let Some(cert) = &index.client_cert else {
+                continue;
+            };
+            let url = Url::parse(&index.url)
+                .map_err(|e| ClientBuildErrorKind::InvalidIndexUrl(index.url.clone(), e))?;
+            let read = |path: &String| {
+                std::fs::read(path)
+                    .map_err(|e| ClientBuildErrorKind::ReadClientCert(path.clone(), e))
+            };
+            let mut pem = read(cert)?;
+            if let Some(key) = &index.client_key {
+                pem.push(b'\n');
+                pem.extend(read(key)?);
+            }
+            let identity = reqwest::Identity::from_pem(&pem)
+                .map_err(|e| ClientBuildErrorKind::InvalidClientCert(cert.clone(), e))?;
+            let client = client_builder(network)?.identity(identity).build()?;
+            clients.push((url.origin(), cert.clone(), client));
+        }
+        Ok(Self { clients })
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for ClientCertRouter {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let origin = req.url().origin();
+        let Some((_, cert, client)) = self.clients.iter().find(|(o, _, _)| *o == origin) else {
+            return next.run(req, extensions).await;
+        };
+        client.execute(req).await.map_err(|e| {
+            if e.is_connect() {
+                reqwest_middleware::Error::middleware(ClientCertHandshakeError {
+                    origin: origin.ascii_serialization(),
+                    client_cert: cert.clone(),
+                    source: e,
+                })
+            } else {
+                e.into()
+            }
+        })
+    }
+}
+
+/// Default of `[network] max_concurrent_requests`
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 8;
+/// Default of `[network] max_retry_after`, in seconds
+pub const DEFAULT_MAX_RETRY_AFTER: u64 = 60;
+/// Number of times a request answered with 429 Too Many Requests is retried
+const MAX_RATE_LIMITED_RETRIES: usize = 3;
+
+/// Limits the requests sent to each host at once and per second, as
+/// configured in `[network]`. Requests answered with 429 Too Many Requests
+/// are retried after the time asked for by `Retry-After`, during which no
+/// other requests are sent to the host.
+#[derive(Debug)]
+struct HostLimiter {
+    network: NetworkConfig,
+    max_retry_after: Duration,
+    hosts: Mutex<HashMap<String, Arc<HostState>>>,
+}
+
+#[derive(Debug)]
+struct HostState {
+    permits: Arc<Semaphore>,
+    /// Minimum time between the starts of requests
+    interval: Duration,
+    /// Earliest start of the next request
+    next_start: Mutex<Instant>,
+}
+
+impl HostLimiter {
+    fn new(network: &NetworkConfig) -> Self {
+        Self {
+            network: network.clone(),
+            max_retry_after: Duration::from_secs(
+                network.max_retry_after.unwrap_or(DEFAULT_MAX_RETRY_AFTER),
+            ),
+            hosts: Mutex::default(),
+        }
+    }
+
+    fn host(&self, host: &str) -> Arc<HostState> {
+        let mut hosts = self.hosts.lock().unwrap_or_else(PoisonError::into_inner);
+        hosts
+            .entry(host.to_owned())
+            .or_insert_with(|| {
+                let limits = self.network.hosts.get(host);
+                let max_concurrent = limits
+                    .and_then(|l| l.max_concurrent_requests)
+                    .or(self.network.max_concurrent_requests)
+                    .unwrap_or(DEFAULT_MAX_CONCURRENT_REQUESTS);
+                let interval = limits
+                    .and_then(|l| l.requests_per_second)
+                    .or(self.network.requests_per_second)
+                    .filter(|rate| *rate > 0.0)
+                    .map_or(Duration::ZERO, |rate| Duration::from_secs_f64(1.0 / rate));
+                Arc::new(HostState {
+                    permits: Arc::new(Semaphore::new(max_concurrent.max(1))),
+                    interval,
+                    next_start: Mutex::new(Instant::now()),
+                })
+            })
+            .clone()
+    }
+}
+
+impl HostState {
+    /// Wait until a request may be sent to the host, returning the permit
+    /// to hold while it is
+    async fn acquire(&self) -> OwnedSemaphorePermit {
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("host semaphore is never closed");
+        let start = {
+            let mut next_start = self
+                .next_start
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let start = (*next_start).max(Instant::now());
+            *next_start = start + self.interval;
+            start
+        };
+        tokio::time::sleep_until(start).await;
+        permit
+    }
+
+    /// Send no requests to the host for `duration`
+    fn pause(&self, duration: Duration) {
+        let mut next_start = self
+            .next_start
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        *next_start = (*next_start).max(Instant::now() + duration);
+    }
+}
+
+/// Time to wait before retrying, given by the `Retry-After` header of
+/// `response` either in seconds or as a date
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response
+        .headers()
+        .get(header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(date.duration_since(SystemTime::now()).unwrap_or_default())
+}
+
+#[async_trait::async_trait]
+impl Middleware for HostLimiter {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let Some(host) = req.url().host_str().map(str::to_owned) else {
+            return next.run(req, extensions).await;
+        };
+        let state = self.host(&host);
+        let mut retries = 0;
+        loop {
+            // Requests with streamed bodies cannot be retried
+            let retry = (retries < MAX_RATE_LIMITED_RETRIES)
+                .then(|| req.try_clone())
+                .flatten();
+            let permit = state.acquire().await;
+            let response = next.clone().run(req, extensions).await?;
+            drop(permit);
+            if response.status() != StatusCode::TOO_MANY_REQUESTS {
+                return Ok(response);
+            }
+            let wait = retry_after(&response);
+            let (Some(retry), Some(wait)) = (retry, wait.filter(|w| *w <= self.max_retry_after))
+            else {
+                return Ok(response);
+            };
+            log::warn!(
+                "`{host}` is limiting the rate of requests, retrying in {} s",
+                wait.as_secs()
+            );
+            state.pause(wait);
+            req = retry;
+            retries += 1;
+        }
+    }
+}
+
+/// Environment variables naming the proxy for each scheme, in order of
+/// precedence
+const PROXY_ENV_VARS: [(&str, [&str; 4]); 2] = [
+    (
+        "https",
+        ["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"],
+    ),
+    (
+        "http",
+        ["HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"],
+    ),
+];
+
+fn create_inner_client(
+    network: &NetworkConfig,
+) -> Result<reqwest::Client, ReqwestClientBuildError> {
+    Ok(client_builder(network)?.build()?)
+}
+
+fn client_builder(
+    network: &NetworkConfig,
+) -> Result<reqwest::ClientBuilder, ReqwestClientBuildError> {
     const UA: &str = concat!("sysand/", env!("CARGO_PKG_VERSION"));
 
-    let client = reqwest::Client::builder().user_agent(UA).build()?;
+    let mut builder = reqwest::Client::builder().user_agent(UA);
 
-    Ok(reqwest_middleware::ClientBuilder::new(client).build())
+    // Hosts in `NO_PROXY` are bypassed in addition to the configured ones
+    let no_proxy = || {
+        let mut hosts = network.no_proxy.clone();
+        hosts.extend(
+            ["NO_PROXY", "no_proxy"]
+                .iter()
+                .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty())),
+        );
+        reqwest::NoProxy::from_string(&hosts.join(","))
+    };
+    if let Some(url) = &network.proxy {
+        let proxy = reqwest::Proxy::all(url)
+            .map_err(|e| ClientBuildErrorKind::InvalidProxy(url.clone(), e))?;
+        builder = builder.proxy(proxy.no_proxy(no_proxy()));
+    } else if !network.no_proxy.is_empty() {
+        // Any explicitly set proxy disables the proxies from the
+        // environment, so set those again with the additional exclusions
+        for (scheme, vars) in PROXY_ENV_VARS {
+            let Some(url) = vars
+                .iter()
+                .find_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+            else {
+                continue;
+            };
+            let proxy = match scheme {
+                "https" => reqwest::Proxy::https(&url),
+                _ => reqwest::Proxy::http(&url),
+            }
+            .map_err(|e| ClientBuildErrorKind::InvalidProxy(url.clone(), e))?;
+            builder = builder.proxy(proxy.no_proxy(no_proxy()));
+        }
+    }
+
+    if let Some(path) = &network.ca_bundle {
+        let pem =
+            std::fs::read(path).map_err(|e| ClientBuildErrorKind::ReadCaBundle(path.clone(), e))?;
+        let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+            .map_err(|e| ClientBuildErrorKind::InvalidCaBundle(path.clone(), e))?;
+        if certificates.is_empty() {
+            return Err(ClientBuildErrorKind::EmptyCaBundle(path.clone()).into());
+        }
+        // In addition to the system trust store
+        builder = builder.tls_certs_merge(certificates);
+    }
+
+    Ok(builder)
 }
+
+#[cfg(test)]
+#[path = "./net_utils_tests.rs"]
+mod tests;
@@ -41,6 +41,7 @@ fn mock_project<S: AsRef<str>, T: AsRef<str>, V: AsRef<str>>(
                 includes_derived: None,
                 includes_implied: None,
                 checksum: Some(IndexMap::default()),
+                variants: IndexMap::new(),
             }),
             files: HashMap::default(),
             nominal_sources: vec![],
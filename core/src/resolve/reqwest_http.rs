@@ -1,19 +1,12 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
-use std::{convert::Infallible, io, pin::Pin, sync::Arc};
-
-use futures::AsyncRead;
-use thiserror::Error;
+use std::{convert::Infallible, sync::Arc};
 
 use crate::{
     auth::HTTPAuthentication,
-    context::ProjectContext,
-    lock::Source,
-    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
     project::{
-        CanonicalizationError, ProjectReadAsync,
-        reqwest_kpar_download::ReqwestRemoteKparDownloadedProject,
+        ProjectReadAsync, reqwest_kpar_download::ReqwestRemoteKparDownloadedProject,
         reqwest_src::ReqwestSrcProjectAsync,
     },
     resolve::ResolveReadAsync,
@@ -31,208 +24,13 @@ pub struct HTTPResolverAsync<Policy> {
     //pub prefer_ranged: bool,
 }
 
-#[derive(Debug)]
-pub enum HTTPProjectAsync<Policy> {
+#[derive(Debug, ProjectReadAsync)]
+pub enum HTTPProjectAsync<Policy: HTTPAuthentication> {
     HTTPSrcProject(ReqwestSrcProjectAsync<Policy>),
     // HTTPKParProjectRanged(ReqwestKparRangedProject),
     HTTPKParProjectDownloaded(Box<ReqwestRemoteKparDownloadedProject<Policy>>),
 }
 
-#[derive(Error, Debug)]
-pub enum HTTPProjectError<Policy: HTTPAuthentication> {
-    #[error(transparent)]
-    SrcProject(<ReqwestSrcProjectAsync<Policy> as ProjectReadAsync>::Error),
-    // #[error(transparent)]
-    // KParRanged(<ReqwestKparRangedProject as ProjectRead>::Error),
-    #[error(transparent)]
-    KparDownloaded(<ReqwestRemoteKparDownloadedProject<Policy> as ProjectReadAsync>::Error),
-}
-
-pub enum HTTPProjectAsyncReader<'a, Policy: HTTPAuthentication> {
-    SrcProjectReader(<ReqwestSrcProjectAsync<Policy> as ProjectReadAsync>::SourceReader<'a>),
-    //KParRangedReader(<ReqwestKparRangedProject as ProjectRead>::SourceReader<'a>),
-    KparDownloadedReader(
-        <ReqwestRemoteKparDownloadedProject<Policy> as ProjectReadAsync>::SourceReader<'a>,
-    ),
-}
-
-impl<Policy: HTTPAuthentication> AsyncRead for HTTPProjectAsyncReader<'_, Policy> {
-    fn poll_read(
-        self: Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
-        buf: &mut [u8],
-    ) -> std::task::Poll<io::Result<usize>> {
-        match self.get_mut() {
-            HTTPProjectAsyncReader::SrcProjectReader(proj) => Pin::new(proj).poll_read(cx, buf),
-            //HTTPProjectAsyncReader::KParRangedReader(proj) => todo!(),
-            HTTPProjectAsyncReader::KparDownloadedReader(proj) => Pin::new(proj).poll_read(cx, buf),
-        }
-    }
-}
-
-impl<Policy: HTTPAuthentication> ProjectReadAsync for HTTPProjectAsync<Policy> {
-    type Error = HTTPProjectError<Policy>;
-
-    async fn get_project_async(
-        &self,
-    ) -> Result<
-        (
-            Option<InterchangeProjectInfoRaw>,
-            Option<InterchangeProjectMetadataRaw>,
-        ),
-        Self::Error,
-    > {
-        match self {
-            HTTPProjectAsync::HTTPSrcProject(proj) => proj
-                .get_project_async()
-                .await
-                .map_err(HTTPProjectError::SrcProject),
-            // HTTPProjectAsync::HTTPKParProjectRanged(proj) => proj
-            //     .get_project()
-            //     .map_err(HTTPProjectError::KParRanged),
-            HTTPProjectAsync::HTTPKParProjectDownloaded(proj) => proj
-                .get_project_async()
-                .await
-                .map_err(HTTPProjectError::KparDownloaded),
-        }
-    }
-
-    type SourceReader<'a>
-        = HTTPProjectAsyncReader<'a, Policy>
-    where
-        Self: 'a;
-
-    async fn read_source_async<P: AsRef<typed_path::Utf8UnixPath>>(
-        &self,
-        path: P,
-    ) -> Result<Self::SourceReader<'_>, Self::Error> {
-        match self {
-            HTTPProjectAsync::HTTPSrcProject(proj) => proj
-                .read_source_async(path)
-                .await
-                .map_err(HTTPProjectError::SrcProject)
-                .map(HTTPProjectAsyncReader::SrcProjectReader),
-            // HTTPProjectAsync::HTTPKParProjectRanged(proj) => proj
-            //     .read_source_async(path)
-            //     .map_err(HTTPProjectError::KParRanged)
-            //     .map(HTTPProjectReader::SrcProjectReader),
-            HTTPProjectAsync::HTTPKParProjectDownloaded(proj) => proj
-                .read_source_async(path)
-                .await
-                .map_err(HTTPProjectError::KparDownloaded)
-                .map(HTTPProjectAsyncReader::KparDownloadedReader),
-        }
-    }
-
-    async fn is_definitely_invalid_async(&self) -> bool {
-        match self {
-            HTTPProjectAsync::HTTPSrcProject(proj) => proj.is_definitely_invalid_async().await,
-            //HTTPProjectAsync::HTTPKParProjectRanged(proj) => proj.is_definitely_invalid(),
-            HTTPProjectAsync::HTTPKParProjectDownloaded(proj) => {
-                proj.is_definitely_invalid_async().await
-            }
-        }
-    }
-
-    async fn sources_async(&self, ctx: &ProjectContext) -> Result<Vec<Source>, Self::Error> {
-        match self {
-            HTTPProjectAsync::HTTPSrcProject(proj) => proj
-                .sources_async(ctx)
-                .await
-                .map_err(HTTPProjectError::SrcProject),
-            //HTTPProjectAsync::HTTPKParProjectRanged(proj) => proj.sources(),
-            HTTPProjectAsync::HTTPKParProjectDownloaded(proj) => proj
-                .sources_async(ctx)
-                .await
-                .map_err(HTTPProjectError::KparDownloaded),
-        }
-    }
-
-    async fn get_info_async(&self) -> Result<Option<InterchangeProjectInfoRaw>, Self::Error> {
-        match self {
-            HTTPProjectAsync::HTTPSrcProject(proj) => proj
-                .get_info_async()
-                .await
-                .map_err(HTTPProjectError::SrcProject),
-            HTTPProjectAsync::HTTPKParProjectDownloaded(proj) => proj
-                .get_info_async()
-                .await
-                .map_err(HTTPProjectError::KparDownloaded),
-        }
-    }
-
-    async fn get_meta_async(&self) -> Result<Option<InterchangeProjectMetadataRaw>, Self::Error> {
-        match self {
-            HTTPProjectAsync::HTTPSrcProject(proj) => proj
-                .get_meta_async()
-                .await
-                .map_err(HTTPProjectError::SrcProject),
-            HTTPProjectAsync::HTTPKParProjectDownloaded(proj) => proj
-                .get_meta_async()
-                .await
-                .map_err(HTTPProjectError::KparDownloaded),
-        }
-    }
-
-    async fn version_async(&self) -> Result<Option<String>, Self::Error> {
-        match self {
-            HTTPProjectAsync::HTTPSrcProject(proj) => proj
-                .version_async()
-                .await
-                .map_err(HTTPProjectError::SrcProject),
-            HTTPProjectAsync::HTTPKParProjectDownloaded(proj) => proj
-                .version_async()
-                .await
-                .map_err(HTTPProjectError::KparDownloaded),
-        }
-    }
-
-    async fn usage_async(
-        &self,
-    ) -> Result<Option<Vec<crate::model::InterchangeProjectUsageRaw>>, Self::Error> {
-        match self {
-            HTTPProjectAsync::HTTPSrcProject(proj) => proj
-                .usage_async()
-                .await
-                .map_err(HTTPProjectError::SrcProject),
-            HTTPProjectAsync::HTTPKParProjectDownloaded(proj) => proj
-                .usage_async()
-                .await
-                .map_err(HTTPProjectError::KparDownloaded),
-        }
-    }
-
-    async fn checksum_canonical_hex_async(
-        &self,
-    ) -> Result<Option<String>, CanonicalizationError<Self::Error>> {
-        match self {
-            HTTPProjectAsync::HTTPSrcProject(proj) => proj
-                .checksum_canonical_hex_async()
-                .await
-                .map_err(|e| e.map_project_read(HTTPProjectError::SrcProject)),
-            HTTPProjectAsync::HTTPKParProjectDownloaded(proj) => proj
-                .checksum_canonical_hex_async()
-                .await
-                .map_err(|e| e.map_project_read(HTTPProjectError::KparDownloaded)),
-        }
-    }
-
-    async fn checksum_canonical_variant_async(
-        &self,
-    ) -> Result<crate::project::ProjectChecksum, Self::Error> {
-        match self {
-            HTTPProjectAsync::HTTPSrcProject(proj) => proj
-                .checksum_canonical_variant_async()
-                .await
-                .map_err(HTTPProjectError::SrcProject),
-            HTTPProjectAsync::HTTPKParProjectDownloaded(proj) => proj
-                .checksum_canonical_variant_async()
-                .await
-                .map_err(HTTPProjectError::KparDownloaded),
-        }
-    }
-}
-
 pub struct HTTPProjects<Policy> {
     client: reqwest_middleware::ClientWithMiddleware,
     url: reqwest::Url,
@@ -287,12 +85,14 @@ impl<Policy: HTTPAuthentication> HTTPProjects<Policy> {
         // resolving `bar` relative to `http://www.example.com/foo` gives `http://www.example.com/bar`
         // while resolving `bar` relative to `http://www.example.com/foo/` gives `http://www.example.com/foo/bar`
         if self.url.path() == "" || self.url.path().ends_with("/") {
-            Some(HTTPProjectAsync::HTTPSrcProject(ReqwestSrcProjectAsync {
-                client: self.client.clone(), // Already internally an Rc
-                url: self.url.clone(),
-                auth_policy: auth_policy.clone(),
-                expected_checksum: None,
-            }))
+            Some(HTTPProjectAsync::HTTPSrcProject(
+                ReqwestSrcProjectAsync::new(
+                    self.client.clone(),
+                    self.url.clone(),
+                    auth_policy.clone(),
+                    None,
+                ),
+            ))
         // If the resolver is set to be lax, try forcing the terminal slash
         } else if self.lax {
             let mut lax_url = self.url.clone();
@@ -300,12 +100,9 @@ impl<Policy: HTTPAuthentication> HTTPProjects<Policy> {
             lax_path.push('/');
             lax_url.set_path(&lax_path);
 
-            Some(HTTPProjectAsync::HTTPSrcProject(ReqwestSrcProjectAsync {
-                client: self.client.clone(), // Already internally an Rc
-                url: lax_url,
-                auth_policy,
-                expected_checksum: None,
-            }))
+            Some(HTTPProjectAsync::HTTPSrcProject(
+                ReqwestSrcProjectAsync::new(self.client.clone(), lax_url, auth_policy, None),
+            ))
         } else {
             None
         }
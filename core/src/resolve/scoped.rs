@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::iter::Flatten;
+
+use fluent_uri::Iri;
+use futures::StreamExt as _;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+use crate::resolve::{
+    ResolutionOutcome, ResolveRead, ResolveReadAsync, memory::IRIPredicate,
+    sequential::combine_outcomes,
+};
+
+/// IRI patterns such as `urn:mycorp:*` or `https://models.mycorp.com/*`,
+/// in which `*` matches any characters, including `/`
+#[derive(Debug, Clone, Default)]
+pub struct IriScope {
+    patterns: Vec<String>,
+    globset: GlobSet,
+}
+
+impl IriScope {
+    pub fn new<I: IntoIterator<Item = S>, S: AsRef<str>>(
+        patterns: I,
+    ) -> Result<Self, globset::Error> {
+        let patterns: Vec<String> = patterns
+            .into_iter()
+            .map(|p| p.as_ref().to_owned())
+            .collect();
+        let mut builder = GlobSetBuilder::new();
+        for pattern in &patterns {
+            builder.add(GlobBuilder::new(pattern).literal_separator(false).build()?);
+        }
+        Ok(Self {
+            patterns,
+            globset: builder.build()?,
+        })
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+}
+
+impl IRIPredicate for IriScope {
+    fn accept_iri(&self, iri: &Iri<String>) -> bool {
+        self.globset.is_match(iri.as_str())
+    }
+}
+
+/// Routes IRIs to resolvers by their scopes. IRIs in the scope of some
+/// resolvers are resolved only by those, so that they cannot be resolved
+/// to projects of the same name elsewhere. Other IRIs are resolved by all
+/// resolvers. In both cases the resolvers are tried in sequence, like
+/// [`SequentialResolver`](crate::resolve::sequential::SequentialResolver).
+#[derive(Debug)]
+pub struct ScopedResolver<R> {
+    inner: Vec<(IriScope, R)>,
+    /// Scopes routed to none of the resolvers
+    reserved: Vec<IriScope>,
+}
+
+impl<R> ScopedResolver<R> {
+    pub fn new<I: IntoIterator<Item = (IriScope, R)>>(resolvers: I) -> Self {
+        ScopedResolver {
+            inner: resolvers.into_iter().collect(),
+            reserved: vec![],
+        }
+    }
+
+    /// Resolve IRIs in `scope` by none of the resolvers, e.g. because
+    /// the resolver they belong to is not in use
+    pub fn reserve(&mut self, scope: IriScope) {
+        self.reserved.push(scope);
+    }
+
+    #[cfg(all(feature = "filesystem", feature = "networking"))]
+    pub(crate) fn resolvers_mut(&mut self) -> impl Iterator<Item = (&mut IriScope, &mut R)> {
+        self.inner.iter_mut().map(|(scope, r)| (scope, r))
+    }
+
+    /// Resolvers to try for `uri`, or `None` if `uri` is reserved
+    fn route(&self, uri: &Iri<String>) -> Option<Vec<&R>> {
+        let scoped: Vec<&R> = self
+            .inner
+            .iter()
+            .filter(|(scope, _)| scope.accept_iri(uri))
+            .map(|(_, r)| r)
+            .collect();
+        if !scoped.is_empty() {
+            Some(scoped)
+        } else if self.reserved.iter().any(|scope| scope.accept_iri(uri)) {
+            None
+        } else {
+            Some(self.inner.iter().map(|(_, r)| r).collect())
+        }
+    }
+}
+
+fn reserved_outcome<T>(uri: &Iri<String>) -> ResolutionOutcome<T> {
+    ResolutionOutcome::Unresolvable(format!(
+        "IRI `{uri}` is in the scope of an index that is not in use"
+    ))
+}
+
+impl<R: ResolveRead> ResolveRead for ScopedResolver<R> {
+    type Error = R::Error;
+
+    type ProjectStorage = R::ProjectStorage;
+
+    type ResolvedStorages = Flatten<std::vec::IntoIter<<R as ResolveRead>::ResolvedStorages>>;
+
+    fn resolve_read(
+        &self,
+        uri: &Iri<String>,
+    ) -> Result<ResolutionOutcome<Self::ResolvedStorages>, Self::Error> {
+        let Some(resolvers) = self.route(uri) else {
+            return Ok(reserved_outcome(uri));
+        };
+        Ok(
+            combine_outcomes(resolvers.into_iter().map(|r| r.resolve_read(uri)))?
+                .map(|iters| iters.into_iter().flatten()),
+        )
+    }
+}
+
+impl<R: ResolveReadAsync> ResolveReadAsync for ScopedResolver<R> {
+    type Error = R::Error;
+
+    type ProjectStorage = R::ProjectStorage;
+
+    type ResolvedStorages = futures::stream::Flatten<
+        futures::stream::Iter<std::vec::IntoIter<<R as ResolveReadAsync>::ResolvedStorages>>,
+    >;
+
+    async fn resolve_read_async(
+        &self,
+        uri: &Iri<String>,
+    ) -> Result<ResolutionOutcome<Self::ResolvedStorages>, Self::Error> {
+        let Some(resolvers) = self.route(uri) else {
+            return Ok(reserved_outcome(uri));
+        };
+        let outcomes =
+            futures::future::join_all(resolvers.into_iter().map(|r| r.resolve_read_async(uri)))
+                .await;
+
+        Ok(combine_outcomes(outcomes)?.map(|streams| futures::stream::iter(streams).flatten()))
+    }
+}
+
+#[cfg(test)]
+#[path = "./scoped_tests.rs"]
+mod tests;
@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::collections::HashMap;
+
+use fluent_uri::Iri;
+use indexmap::IndexMap;
+
+use crate::{
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
+    project::{ProjectRead, memory::InMemoryProject},
+    resolve::{
+        ResolutionOutcome, ResolveRead,
+        memory::{AcceptAll, IRIPredicate, MemoryResolver},
+        scoped::{IriScope, ScopedResolver},
+    },
+};
+
+fn mock_project<S: AsRef<str>, T: AsRef<str>>(
+    uri: S,
+    version: T,
+) -> (Iri<String>, InMemoryProject) {
+    (
+        Iri::parse(uri.as_ref().to_string()).unwrap(),
+        InMemoryProject {
+            info: Some(InterchangeProjectInfoRaw {
+                name: "mock".to_string(),
+                publisher: None,
+                description: None,
+                version: version.as_ref().to_string(),
+                license: None,
+                maintainer: vec![],
+                website: None,
+                topic: vec![],
+                usage: vec![],
+            }),
+            meta: Some(InterchangeProjectMetadataRaw {
+                index: IndexMap::default(),
+                created: crate::model::format_created_now(),
+                metamodel: None,
+                includes_derived: None,
+                includes_implied: None,
+                checksum: Some(IndexMap::default()),
+                variants: IndexMap::new(),
+            }),
+            files: HashMap::default(),
+            nominal_sources: vec![],
+        },
+    )
+}
+
+fn mock_resolver<I: IntoIterator<Item = (Iri<String>, InMemoryProject)>>(
+    projects: I,
+) -> MemoryResolver<AcceptAll, InMemoryProject> {
+    MemoryResolver {
+        iri_predicate: AcceptAll {},
+        projects: HashMap::from_iter(projects.into_iter().map(|(k, v)| (k, vec![v]))),
+    }
+}
+
+fn resolved_versions<R: ResolveRead>(resolver: &R, uri: &str) -> Option<Vec<String>> {
+    match resolver.resolve_read_raw(uri).unwrap() {
+        ResolutionOutcome::Resolved(projects) => Some(
+            projects
+                .into_iter()
+                .map(|p| p.unwrap().version().unwrap().unwrap())
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+#[test]
+fn iri_scope_matching() {
+    let scope = IriScope::new(["urn:mycorp:*", "https://models.mycorp.com/*"]).unwrap();
+    let accepts = |iri: &str| scope.accept_iri(&Iri::parse(iri.to_string()).unwrap());
+
+    assert!(accepts("urn:mycorp:lib"));
+    assert!(accepts("https://models.mycorp.com/a/b/c"));
+    assert!(!accepts("urn:kpar:lib"));
+    assert!(!accepts("https://models.mycorp.com.evil.org/lib"));
+    assert!(!IriScope::default().accept_iri(&Iri::parse("urn:mycorp:lib".to_string()).unwrap()));
+}
+
+#[test]
+fn scoped_iris_only_resolve_in_scope() {
+    let internal = mock_resolver([mock_project("urn:mycorp:lib", "1.0.0")]);
+    let public = mock_resolver([
+        mock_project("urn:mycorp:lib", "6.6.6"),
+        mock_project("urn:kpar:lib", "2.0.0"),
+    ]);
+
+    let resolver = ScopedResolver::new([
+        (IriScope::default(), public),
+        (IriScope::new(["urn:mycorp:*"]).unwrap(), internal),
+    ]);
+
+    assert_eq!(
+        resolved_versions(&resolver, "urn:mycorp:lib"),
+        Some(vec!["1.0.0".to_string()])
+    );
+    assert_eq!(
+        resolved_versions(&resolver, "urn:kpar:lib"),
+        Some(vec!["2.0.0".to_string()])
+    );
+}
+
+#[test]
+fn reserved_iris_resolve_nowhere() {
+    let public = mock_resolver([mock_project("urn:mycorp:lib", "6.6.6")]);
+
+    let mut resolver = ScopedResolver::new([(IriScope::default(), public)]);
+    resolver.reserve(IriScope::new(["urn:mycorp:*"]).unwrap());
+
+    assert!(matches!(
+        resolver.resolve_read_raw("urn:mycorp:lib").unwrap(),
+        ResolutionOutcome::Unresolvable(_)
+    ));
+}
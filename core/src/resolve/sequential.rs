@@ -21,6 +21,47 @@ impl<R> SequentialResolver<R> {
     }
 }
 
+/// Combine the outcomes of resolvers tried in sequence. Resolved if any
+/// resolver resolved the IRI, otherwise unresolvable if any resolver
+/// supports it
+pub(crate) fn combine_outcomes<S, E, I: IntoIterator<Item = Result<ResolutionOutcome<S>, E>>>(
+    outcomes: I,
+) -> Result<ResolutionOutcome<Vec<S>>, E> {
+    let mut resolved = vec![];
+    let mut any_supported = false;
+    let mut msgs = vec![];
+
+    for outcome in outcomes {
+        match outcome? {
+            ResolutionOutcome::Resolved(storages) => {
+                any_supported = true;
+                resolved.push(storages)
+            }
+            ResolutionOutcome::UnsupportedIRIType(msg) => {
+                msgs.push(msg);
+            }
+            ResolutionOutcome::Unresolvable(msg) => {
+                any_supported = true;
+                msgs.push(msg);
+            }
+        }
+    }
+
+    if !resolved.is_empty() {
+        Ok(ResolutionOutcome::Resolved(resolved))
+    } else if any_supported {
+        Ok(ResolutionOutcome::Unresolvable(format!(
+            "unresolvable: {:?}",
+            msgs
+        )))
+    } else {
+        Ok(ResolutionOutcome::UnsupportedIRIType(format!(
+            "unsupported IRI: {:?}",
+            msgs
+        )))
+    }
+}
+
 impl<R: ResolveRead> ResolveRead for SequentialResolver<R> {
     type Error = R::Error;
 
@@ -32,39 +73,10 @@ impl<R: ResolveRead> ResolveRead for SequentialResolver<R> {
         &self,
         uri: &fluent_uri::Iri<String>,
     ) -> Result<ResolutionOutcome<Self::ResolvedStorages>, Self::Error> {
-        let mut iters = vec![];
-        let mut any_supported = false;
-        let mut msgs = vec![];
-
-        for resolver in &self.inner {
-            match resolver.resolve_read(uri)? {
-                ResolutionOutcome::Resolved(storages) => {
-                    any_supported = true;
-                    iters.push(storages)
-                }
-                ResolutionOutcome::UnsupportedIRIType(msg) => {
-                    msgs.push(msg);
-                }
-                ResolutionOutcome::Unresolvable(msg) => {
-                    any_supported = true;
-                    msgs.push(msg);
-                }
-            }
-        }
-
-        if !iters.is_empty() {
-            Ok(ResolutionOutcome::Resolved(iters.into_iter().flatten()))
-        } else if any_supported {
-            Ok(ResolutionOutcome::Unresolvable(format!(
-                "unresolvable: {:?}",
-                msgs
-            )))
-        } else {
-            Ok(ResolutionOutcome::UnsupportedIRIType(format!(
-                "unsupported IRI: {:?}",
-                msgs
-            )))
-        }
+        Ok(
+            combine_outcomes(self.inner.iter().map(|resolver| resolver.resolve_read(uri)))?
+                .map(|iters| iters.into_iter().flatten()),
+        )
     }
 }
 
@@ -88,41 +100,7 @@ impl<R: ResolveReadAsync> ResolveReadAsync for SequentialResolver<R> {
         )
         .await;
 
-        let mut streams = vec![];
-        let mut any_supported = false;
-        let mut msgs = vec![];
-
-        for outcome in outcomes {
-            match outcome? {
-                ResolutionOutcome::Resolved(storages) => {
-                    any_supported = true;
-                    streams.push(storages)
-                }
-                ResolutionOutcome::UnsupportedIRIType(msg) => {
-                    msgs.push(msg);
-                }
-                ResolutionOutcome::Unresolvable(msg) => {
-                    any_supported = true;
-                    msgs.push(msg);
-                }
-            }
-        }
-
-        if !streams.is_empty() {
-            Ok(ResolutionOutcome::Resolved(
-                futures::stream::iter(streams).flatten(),
-            ))
-        } else if any_supported {
-            Ok(ResolutionOutcome::Unresolvable(format!(
-                "unresolvable: {:?}",
-                msgs
-            )))
-        } else {
-            Ok(ResolutionOutcome::UnsupportedIRIType(format!(
-                "unsupported IRI: {:?}",
-                msgs
-            )))
-        }
+        Ok(combine_outcomes(outcomes)?.map(|streams| futures::stream::iter(streams).flatten()))
     }
 }
 
@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
 
-use std::collections::HashMap;
+use std::{collections::HashMap, num::NonZeroUsize};
 
 use fluent_uri::Iri;
 use indexmap::IndexMap;
@@ -10,7 +10,7 @@ use crate::{
     model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
     project::{ProjectRead, memory::InMemoryProject},
     resolve::{
-        ResolutionOutcome, ResolveRead,
+        ResolutionOutcome, ResolveRead, ResolveReadAsync,
         memory::{AcceptAll, MemoryResolver},
         sequential::SequentialResolver,
     },
@@ -42,6 +42,7 @@ fn mock_project<S: AsRef<str>, T: AsRef<str>, V: AsRef<str>>(
                 includes_derived: None,
                 includes_implied: None,
                 checksum: Some(IndexMap::default()),
+                variants: IndexMap::new(),
             }),
             files: HashMap::default(),
             nominal_sources: vec![],
@@ -106,3 +107,50 @@ fn resolution_preference() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn resolve_many_deduplicates() -> Result<(), Box<dyn std::error::Error>> {
+    let resolver = SequentialResolver::new([mock_resolver([
+        mock_project("urn:kpar:foo", "foo", "1.2.3"),
+        mock_project("urn:kpar:bar", "bar", "3.2.1"),
+    ])]);
+    let runtime = std::sync::Arc::new(
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?,
+    );
+    let resolver = resolver.to_async().to_tokio_sync(runtime);
+
+    let uris = [
+        "urn:kpar:bar",
+        "urn:kpar:foo",
+        "urn:kpar:bar",
+        "urn:kpar:baz",
+    ]
+    .map(|uri| Iri::parse(uri.to_string()).unwrap());
+    let outcomes = resolver.resolve_many(uris, NonZeroUsize::new(2).unwrap());
+
+    let versions: Vec<_> = outcomes
+        .into_iter()
+        .map(|(uri, outcome)| {
+            let version = match outcome.unwrap() {
+                ResolutionOutcome::Resolved(projects) => {
+                    let projects: Vec<_> = projects.into_iter().collect::<Result<_, _>>().unwrap();
+                    projects[0].version().unwrap()
+                }
+                _ => None,
+            };
+            (uri.into_string(), version)
+        })
+        .collect();
+    assert_eq!(
+        versions,
+        [
+            ("urn:kpar:bar".to_string(), Some("3.2.1".to_string())),
+            ("urn:kpar:foo".to_string(), Some("1.2.3".to_string())),
+            ("urn:kpar:baz".to_string(), None),
+        ]
+    );
+
+    Ok(())
+}
@@ -1,37 +1,55 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
-use std::{fmt, result::Result, sync::Arc};
+use std::{collections::HashMap, fmt, num::NonZeroUsize, result::Result, sync::Arc};
 
 use camino::Utf8PathBuf;
 use reqwest_middleware::ClientWithMiddleware;
+use thiserror::Error;
 
 use crate::{
     auth::HTTPAuthentication,
+    config::Index,
     env::{
         discovery::DiscoveryError, index::IndexEnvironmentAsync,
-        local_directory::LocalDirectoryEnvironment,
+        local_directory::LocalDirectoryEnvironment, remote::RemoteEnvironmentAsync,
     },
+    iri_normalize::normalize_iri_tolerant,
     resolve::{
-        AsSyncResolveTokio, ResolveRead, ResolveReadAsync,
+        AsSyncResolveTokio, IriOutcome, ResolveRead, ResolveReadAsync,
         combined::CombinedResolver,
+        dedup_iris,
         env::EnvResolver,
         file::FileResolver,
+        git_cache::GitCache,
         gix_git::GitResolver,
+        priority::PriorityResolver,
         remote::{RemotePriority, RemoteResolver},
         reqwest_http::HTTPResolverAsync,
+        scoped::{IriScope, ScopedResolver},
         sequential::SequentialResolver,
     },
 };
 
 pub type LocalEnvResolver = EnvResolver<LocalDirectoryEnvironment>;
 
-pub type RemoteIndexResolver<Policy> =
-    SequentialResolver<EnvResolver<IndexEnvironmentAsync<Policy>>>;
+pub type SharedEnvResolver<Policy> =
+    SequentialResolver<EnvResolver<RemoteEnvironmentAsync<Policy>>>;
+
+/// The local environment, falling back to the shared environments for
+/// projects it has no versions of
+pub type StandardLocalResolver<Policy> = PriorityResolver<
+    SequentialResolver<LocalEnvResolver>,
+    AsSyncResolveTokio<SharedEnvResolver<Policy>>,
+>;
+
+/// The indexes, in order. IRIs in the scope of an index are only looked
+/// up in that index
+pub type RemoteIndexResolver<Policy> = ScopedResolver<EnvResolver<IndexEnvironmentAsync<Policy>>>;
 
 type StandardResolverInner<Policy> = CombinedResolver<
     FileResolver,
-    LocalEnvResolver,
+    StandardLocalResolver<Policy>,
     RemoteResolver<AsSyncResolveTokio<HTTPResolverAsync<Policy>>, GitResolver>,
     AsSyncResolveTokio<RemoteIndexResolver<Policy>>,
 >;
@@ -44,6 +62,55 @@ impl<Policy: HTTPAuthentication> fmt::Debug for StandardResolver<Policy> {
     }
 }
 
+impl<Policy: HTTPAuthentication> StandardResolver<Policy> {
+    /// Clone git repositories through `cache`, if any
+    pub fn with_git_cache(mut self, cache: Option<GitCache>) -> Self {
+        if let Some(git_resolver) = self
+            .0
+            .remote_resolver
+            .as_mut()
+            .and_then(|r| r.git_resolver.as_mut())
+        {
+            git_resolver.cache = cache;
+        }
+        self
+    }
+
+    /// Restrict the IRIs in the `scope` of `indexes` to be resolved only
+    /// against the index they are in the scope of. IRIs in the scope of an
+    /// index that is not in use are not resolved against any index
+    pub fn with_index_scopes(mut self, indexes: &[Index]) -> Result<Self, IndexScopeError> {
+        let Some(index_resolver) = self.0.index_resolver.as_mut() else {
+            return Ok(self);
+        };
+        for index in indexes.iter().filter(|i| !i.scope.is_empty()) {
+            let url = url::Url::parse(&index.url)
+                .map_err(|e| IndexScopeError::InvalidUrl(index.url.clone(), e))?;
+            let scope = IriScope::new(&index.scope)
+                .map_err(|e| IndexScopeError::InvalidPattern(index.url.clone(), e))?;
+            let mut scope = Some(scope);
+            for (resolver_scope, resolver) in index_resolver.inner.resolvers_mut() {
+                if resolver.env.discovery_root() == Some(&url) {
+                    *resolver_scope = scope.take().unwrap_or_else(|| resolver_scope.clone());
+                    break;
+                }
+            }
+            if let Some(scope) = scope {
+                index_resolver.inner.reserve(scope);
+            }
+        }
+        Ok(self)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum IndexScopeError {
+    #[error("invalid URL `{0}` of index with `scope`: {1}")]
+    InvalidUrl(String, url::ParseError),
+    #[error("invalid `scope` of index `{0}`: {1}")]
+    InvalidPattern(String, globset::Error),
+}
+
 impl<Policy: HTTPAuthentication> ResolveRead for StandardResolver<Policy> {
     type Error = <StandardResolverInner<Policy> as ResolveRead>::Error;
 
@@ -55,7 +122,36 @@ impl<Policy: HTTPAuthentication> ResolveRead for StandardResolver<Policy> {
         &self,
         uri: &fluent_uri::Iri<String>,
     ) -> Result<crate::resolve::ResolutionOutcome<Self::ResolvedStorages>, Self::Error> {
-        self.0.resolve_read(uri)
+        // Resolve equivalent IRIs (see `iri_normalize`) the same way
+        match fluent_uri::Iri::parse(normalize_iri_tolerant(uri.as_str())) {
+            Ok(normalized) => self.0.resolve_read(&normalized),
+            Err(_) => self.0.resolve_read(uri),
+        }
+    }
+
+    fn resolve_many<I: IntoIterator<Item = fluent_uri::Iri<String>>>(
+        &self,
+        uris: I,
+        concurrency: NonZeroUsize,
+    ) -> Vec<IriOutcome<Self::ResolvedStorages, Self::Error>> {
+        let uris = dedup_iris(uris);
+        let normalized: Vec<_> = uris
+            .iter()
+            .map(|uri| {
+                fluent_uri::Iri::parse(normalize_iri_tolerant(uri.as_str()))
+                    .unwrap_or_else(|_| uri.clone())
+            })
+            .collect();
+        let mut outcomes: HashMap<_, _> = self
+            .0
+            .resolve_many(normalized.iter().cloned(), concurrency)
+            .into_iter()
+            .collect();
+        // IRIs equivalent to an earlier one are left out
+        uris.into_iter()
+            .zip(normalized)
+            .filter_map(|(uri, normalized)| outcomes.remove(&normalized).map(|o| (uri, o)))
+            .collect()
     }
 }
 
@@ -80,7 +176,7 @@ pub fn standard_remote_resolver<Policy: HTTPAuthentication>(
             }
             .to_tokio_sync(runtime),
         ),
-        git_resolver: Some(GitResolver {}),
+        git_resolver: Some(GitResolver::default()),
         priority: RemotePriority::PreferHTTP,
     }
 }
@@ -89,6 +185,21 @@ pub fn standard_local_resolver(local_env: LocalDirectoryEnvironment) -> LocalEnv
     EnvResolver { env: local_env }
 }
 
+/// Resolver reading the shared environments at `urls`, in order. A shared
+/// environment is a local environment directory served over HTTP, e.g. by
+/// a team, and is used as a read-only cache of installed projects
+pub fn standard_shared_resolver<Policy: HTTPAuthentication>(
+    client: ClientWithMiddleware,
+    urls: Vec<url::Url>,
+    runtime: Arc<tokio::runtime::Runtime>,
+    auth_policy: Arc<Policy>,
+) -> AsSyncResolveTokio<SharedEnvResolver<Policy>> {
+    let envs = urls.into_iter().map(|url| EnvResolver {
+        env: RemoteEnvironmentAsync::new(client.clone(), auth_policy.clone(), url),
+    });
+    SequentialResolver::new(envs).to_tokio_sync(runtime)
+}
+
 pub fn standard_index_resolver<Policy: HTTPAuthentication>(
     client: ClientWithMiddleware,
     urls: Vec<url::Url>,
@@ -99,7 +210,7 @@ pub fn standard_index_resolver<Policy: HTTPAuthentication>(
     // `sysand-index-config.json` here: resolver construction happens for
     // commands and bindings before we know whether an index dependency is
     // needed at all. The env resolves discovery lazily on first index use.
-    let envs: Vec<EnvResolver<IndexEnvironmentAsync<Policy>>> = urls
+    let envs: Vec<(IriScope, EnvResolver<IndexEnvironmentAsync<Policy>>)> = urls
         .into_iter()
         .map(|discovery_root| {
             let env = IndexEnvironmentAsync::from_discovery_root(
@@ -107,23 +218,43 @@ pub fn standard_index_resolver<Policy: HTTPAuthentication>(
                 auth_policy.clone(),
                 discovery_root,
             );
-            EnvResolver { env }
+            (IriScope::default(), EnvResolver { env })
         })
         .collect();
-    Ok(SequentialResolver::new(envs).to_tokio_sync(runtime))
+    Ok(ScopedResolver::new(envs).to_tokio_sync(runtime))
 }
 
 // TODO: Replace most of these arguments by some general CLIOptions object
+/// Projects are looked up in `local_env` first, then in the shared
+/// environments at `shared_env_urls` and finally in the indexes at
+/// `index_urls`. The environments only serve as caches of projects
+/// resolved otherwise, see [`CombinedResolver`]
 pub fn standard_resolver<Policy: HTTPAuthentication>(
     cwd: Option<Utf8PathBuf>,
     local_env: Option<LocalDirectoryEnvironment>,
     client: Option<ClientWithMiddleware>,
     index_urls: Option<Vec<url::Url>>,
+    shared_env_urls: Vec<url::Url>,
     runtime: Arc<tokio::runtime::Runtime>,
     auth_policy: Arc<Policy>,
 ) -> Result<StandardResolver<Policy>, DiscoveryError> {
     let file_resolver = standard_file_resolver(cwd);
-    let local_resolver = local_env.map(standard_local_resolver);
+    let shared_resolver = match &client {
+        Some(client) if !shared_env_urls.is_empty() => Some(standard_shared_resolver(
+            client.clone(),
+            shared_env_urls,
+            runtime.clone(),
+            auth_policy.clone(),
+        )),
+        _ => None,
+    };
+    let local_resolver = (local_env.is_some() || shared_resolver.is_some()).then(|| {
+        PriorityResolver::new(
+            SequentialResolver::new(local_env.map(standard_local_resolver)),
+            shared_resolver
+                .unwrap_or_else(|| SequentialResolver::new(vec![]).to_tokio_sync(runtime.clone())),
+        )
+    });
     let remote_resolver = client
         .clone()
         .map(|x| standard_remote_resolver(x, runtime.clone(), auth_policy.clone()));
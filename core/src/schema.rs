@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! JSON Schemas of the files sysand reads, generated from the types they
+//! are deserialized into, so that other tools validate them exactly as
+//! sysand does.
+
+use std::{fmt, str::FromStr};
+
+use schemars::{JsonSchema, Schema, generate::SchemaSettings};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    config::Config,
+    lock::{Lock, SUPPORTED_LOCK_VERSIONS},
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
+};
+
+/// Version of sysand the schemas were generated by, recorded in every
+/// schema as `x-sysand-version`
+pub const SCHEMA_SYSAND_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// File format to generate the schema of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaKind {
+    /// Project information, `.project.json`
+    Project,
+    /// Project metadata, `.meta.json`
+    Meta,
+    /// Lockfile, `sysand-lock.toml` or `sysand-lock.json`
+    Lock,
+    /// Configuration, `sysand.toml`
+    Config,
+}
+
+impl SchemaKind {
+    pub const ALL: [SchemaKind; 4] = [
+        SchemaKind::Project,
+        SchemaKind::Meta,
+        SchemaKind::Lock,
+        SchemaKind::Config,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SchemaKind::Project => "project",
+            SchemaKind::Meta => "meta",
+            SchemaKind::Lock => "lock",
+            SchemaKind::Config => "config",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            SchemaKind::Project => ".project.json",
+            SchemaKind::Meta => ".meta.json",
+            SchemaKind::Lock => "sysand lockfile",
+            SchemaKind::Config => "sysand.toml",
+        }
+    }
+}
+
+impl fmt::Display for SchemaKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("unknown schema `{0}`, expected one of `project`, `meta`, `lock`, `config`")]
+pub struct SchemaKindParseError(String);
+
+impl FromStr for SchemaKind {
+    type Err = SchemaKindParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SchemaKind::ALL
+            .into_iter()
+            .find(|k| k.as_str() == s)
+            .ok_or_else(|| SchemaKindParseError(s.to_owned()))
+    }
+}
+
+/// JSON Schema (draft 2020-12) of the file format `kind`
+pub fn json_schema(kind: SchemaKind) -> Schema {
+    let mut schema = match kind {
+        SchemaKind::Project => generate::<InterchangeProjectInfoRaw>(),
+        SchemaKind::Meta => generate::<InterchangeProjectMetadataRaw>(),
+        SchemaKind::Lock => {
+            let mut schema = generate::<Lock>();
+            // Only lockfiles of supported versions are read
+            if let Some(lock_version) = schema
+                .get_mut("properties")
+                .and_then(|p| p.get_mut("lock_version"))
+                .and_then(Value::as_object_mut)
+            {
+                lock_version.insert("enum".into(), SUPPORTED_LOCK_VERSIONS.into());
+            }
+            schema
+        }
+        SchemaKind::Config => generate::<Config>(),
+    };
+    schema.insert("title".into(), kind.title().into());
+    schema.insert("x-sysand-version".into(), SCHEMA_SYSAND_VERSION.into());
+    schema
+}
+
+fn generate<T: JsonSchema>() -> Schema {
+    SchemaSettings::draft2020_12()
+        .into_generator()
+        .into_root_schema_for::<T>()
+}
+
+#[cfg(test)]
+#[path = "./schema_tests.rs"]
+mod tests;
@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use serde_json::{Value, json};
+
+use super::{SCHEMA_SYSAND_VERSION, SchemaKind, json_schema};
+use crate::lock::SUPPORTED_LOCK_VERSIONS;
+
+fn schema(kind: SchemaKind) -> Value {
+    json_schema(kind).to_value()
+}
+
+#[test]
+fn schema_kind_roundtrip() {
+    for kind in SchemaKind::ALL {
+        assert_eq!(kind.as_str().parse::<SchemaKind>().unwrap(), kind);
+    }
+    assert!("manifest".parse::<SchemaKind>().is_err());
+}
+
+#[test]
+fn schemas_are_versioned() {
+    for kind in SchemaKind::ALL {
+        let schema = schema(kind);
+        assert_eq!(
+            schema["$schema"],
+            "https://json-schema.org/draft/2020-12/schema"
+        );
+        assert_eq!(schema["x-sysand-version"], SCHEMA_SYSAND_VERSION);
+    }
+}
+
+#[test]
+fn project_schema_follows_serde_names() {
+    let schema = schema(SchemaKind::Project);
+    assert_eq!(schema["title"], ".project.json");
+    assert_eq!(schema["required"], json!(["name", "version"]));
+    let properties = schema["properties"].as_object().unwrap();
+    assert!(properties.contains_key("usage"));
+    assert!(!properties.contains_key("version_constraint"));
+}
+
+#[test]
+fn meta_schema_requires_index_and_created() {
+    let schema = schema(SchemaKind::Meta);
+    assert_eq!(schema["required"], json!(["index", "created"]));
+    assert!(schema["properties"]["includesDerived"].is_object());
+}
+
+#[test]
+fn lock_schema_restricts_lock_version() {
+    let schema = schema(SchemaKind::Lock);
+    assert_eq!(
+        schema["properties"]["lock_version"]["enum"],
+        json!(SUPPORTED_LOCK_VERSIONS)
+    );
+    assert!(schema["properties"]["project"].is_object());
+}
+
+#[test]
+fn config_schema_uses_file_names() {
+    let schema = schema(SchemaKind::Config);
+    let properties = schema["properties"].as_object().unwrap();
+    for key in ["index", "project", "post_build", "install_location", "lock"] {
+        assert!(properties.contains_key(key), "missing `{key}`");
+    }
+    assert!(!properties.contains_key("indexes"));
+}
@@ -0,0 +1,546 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Ready-made setup of configuration, resolvers and environment for tools
+//! embedding sysand, so that they do not have to wire them up like the CLI
+//! does. A [`Session`] is opened on a directory and runs the common commands
+//! on the project found there.
+
+use std::{
+    error::Error,
+    fmt,
+    num::NonZeroU64,
+    sync::{Arc, OnceLock},
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use reqwest_middleware::ClientWithMiddleware;
+use thiserror::Error;
+use typed_path::Utf8UnixPathBuf;
+use url::Url;
+
+use crate::{
+    add::do_add_guess,
+    auth::{HTTPAuthentication, Unauthenticated},
+    build::{KparCompressionMethod, do_build_kpar},
+    commands::{
+        env::do_env_local_dir,
+        lock::{LockOutcome, do_lock_local_editable},
+        sources::{
+            LocalSourcesError, do_sources_local_src_project_no_deps, find_project_dependencies,
+        },
+        sync::do_sync,
+    },
+    config::{
+        Config,
+        local_fs::{
+            CONFIG_FILE, ConfigReadError, get_config, load_env_settings, load_system_config,
+            load_user_config,
+        },
+    },
+    context::ProjectContext,
+    discover::{discover_project_within, discover_workspace},
+    env::{
+        DEFAULT_ENV_NAME,
+        discovery::DiscoveryError,
+        local_directory::{LocalDirectoryEnvironment, metadata::EnvMetadataError},
+    },
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    info::do_info,
+    lock::{Lock, LockFormat},
+    model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
+    project::{
+        AsSyncProjectTokio, KparMeta, ProjectReadAsync,
+        gix_git_download::{GixDownloadedError, GixDownloadedProject},
+        local_kpar::{KparInnerPath, LocalKParProject, LocalKParProjectRaw},
+        local_src::LocalSrcProject,
+        memory::InMemoryProject,
+        reqwest_kpar_download::{
+            ReqwestIndexKparDownloadedProject, ReqwestRemoteKparDownloadedProject,
+        },
+        reqwest_src::ReqwestSrcProjectAsync,
+        utils::{FsIoError, wrapfs},
+    },
+    resolve::{
+        memory::{AcceptAll, MemoryResolver},
+        net_utils::{HttpClientFactory, ReqwestClientBuildError},
+        priority::PriorityResolver,
+        standard::{IndexScopeError, StandardResolver, standard_resolver},
+    },
+    solve::{markers::Markers, pubgrub::VersionStrategy},
+    stdlib::known_std_libs,
+    workspace::WorkspaceReadError,
+};
+
+/// Index used when the configuration does not set any
+pub const DEFAULT_INDEX_URL: &str = "https://sysand.com";
+
+/// Resolver used by [`Session`]: standard libraries are provided by the
+/// tools using the project, everything else is resolved as usual
+pub type SessionResolver<Policy> =
+    PriorityResolver<MemoryResolver<AcceptAll, InMemoryProject>, StandardResolver<Policy>>;
+
+/// Configuration, project and networking of an embedding tool. Resolution
+/// follows the configuration, except that `[[project]]` overrides are not
+/// applied, and standard libraries are never installed
+pub struct Session<Policy = Unauthenticated> {
+    pub config: Config,
+    pub project: ProjectContext,
+    pub client: ClientWithMiddleware,
+    pub runtime: Arc<tokio::runtime::Runtime>,
+    pub auth_policy: Arc<Policy>,
+    /// Indexes to resolve from, `None` to use no index
+    pub index_urls: Option<Vec<Url>>,
+}
+
+impl Session {
+    /// Discover the project and workspace containing `directory` and read
+    /// the configuration like the CLI does, see [`load_configs`]
+    ///
+    /// [`load_configs`]: crate::config::local_fs::load_configs
+    pub fn open<P: AsRef<Utf8Path>>(directory: P) -> Result<Self, SessionError> {
+        let directory = directory.as_ref();
+        let mut config = load_env_settings()?;
+        config.merge(load_user_config()?);
+        let system_config = load_system_config()?;
+        let max_depth = config
+            .discovery
+            .max_depth
+            .or(system_config.discovery.max_depth);
+        let current_project = discover_project_within(directory, max_depth)?;
+        let current_workspace = discover_workspace(directory)?;
+        if let Some(workspace) = &current_workspace {
+            config.merge(Config {
+                workspace: get_config(workspace.root_path().join(CONFIG_FILE))?.workspace,
+                ..Default::default()
+            });
+        }
+        if let Some(project) = &current_project {
+            config.merge(get_config(project.root_path().join(CONFIG_FILE))?);
+        }
+        config.merge(system_config);
+        let env_root = match (&current_workspace, &current_project) {
+            (Some(w), _) => w.root_path(),
+            (None, Some(p)) => p.root_path(),
+            (None, None) => directory,
+        };
+        let env = LocalDirectoryEnvironment::try_read(env_root.join(DEFAULT_ENV_NAME))?;
+        Self::new(
+            config,
+            ProjectContext {
+                env,
+                current_workspace,
+                current_project,
+                current_directory: directory.to_owned(),
+                markers: Markers::new(),
+                version_strategy: VersionStrategy::default(),
+            },
+        )
+    }
+
+    /// Session for `project` with `config` and no credentials. Sessions with
+    /// the same network settings share their runtime and HTTP connections
+    pub fn new(config: Config, project: ProjectContext) -> Result<Self, SessionError> {
+        let client = HttpClientFactory::shared(&config).client()?;
+        let index_urls =
+            Some(config.index_urls(vec![], vec![DEFAULT_INDEX_URL.to_string()], vec![])?);
+        Ok(Self {
+            config,
+            project,
+            client,
+            runtime: shared_runtime(),
+            auth_policy: Arc::new(Unauthenticated {}),
+            index_urls,
+        })
+    }
+}
+
+impl<Policy: HTTPAuthentication> Session<Policy> {
+    /// Authenticate requests with `auth_policy`
+    pub fn with_auth_policy<P: HTTPAuthentication>(self, auth_policy: Arc<P>) -> Session<P> {
+        Session {
+            config: self.config,
+            project: self.project,
+            client: self.client,
+            runtime: self.runtime,
+            auth_policy,
+            index_urls: self.index_urls,
+        }
+    }
+
+    /// Resolve from the indexes at `index_urls` instead, or from no index
+    /// if `None`
+    pub fn with_index_urls(mut self, index_urls: Option<Vec<Url>>) -> Self {
+        self.index_urls = index_urls;
+        self
+    }
+
+    /// Root directory of the current project
+    pub fn project_root(&self) -> Result<&Utf8Path, SessionError> {
+        self.project
+            .current_project
+            .as_ref()
+            .map(|p| p.root_path())
+            .ok_or_else(|| SessionError::NoProject(self.project.current_directory.clone()))
+    }
+
+    fn current_project(&self) -> Result<&LocalSrcProject, SessionError> {
+        self.project
+            .current_project
+            .as_ref()
+            .ok_or_else(|| SessionError::NoProject(self.project.current_directory.clone()))
+    }
+
+    /// Resolver for the usages of the current project
+    pub fn resolver(&self) -> Result<SessionResolver<Policy>, SessionError> {
+        let std_libs = known_std_libs()
+            .into_iter()
+            .map(|(iri, projects)| (fluent_uri::Iri::parse(iri).unwrap(), projects))
+            .collect();
+        Ok(PriorityResolver::new(
+            MemoryResolver {
+                iri_predicate: AcceptAll {},
+                projects: std_libs,
+            },
+            standard_resolver(
+                Some(self.project.current_directory.clone()),
+                self.project.env.clone(),
+                Some(self.client.clone()),
+                self.index_urls.clone(),
+                self.config.shared_env_urls()?,
+                self.runtime.clone(),
+                self.auth_policy.clone(),
+            )?
+            .with_index_scopes(&self.config.indexes)?,
+        ))
+    }
+
+    /// Information and metadata of the project at `iri`
+    pub fn info<S: AsRef<str>>(
+        &self,
+        iri: S,
+    ) -> Result<(InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw), SessionError> {
+        do_info(iri, &self.resolver()?).map_err(SessionError::coded)
+    }
+
+    /// Add a usage of `iri` to the current project. Returns whether the
+    /// project changed; the lockfile is not updated
+    pub fn add<S: Into<String>>(
+        &mut self,
+        iri: S,
+        version_constraint: Option<String>,
+    ) -> Result<bool, SessionError> {
+        let directory = self.project.current_directory.clone();
+        let project = self
+            .project
+            .current_project
+            .as_mut()
+            .ok_or(SessionError::NoProject(directory))?;
+        do_add_guess(project, iri.into(), version_constraint).map_err(SessionError::coded)
+    }
+
+    /// Lock the usages of the current project and write the lockfile next
+    /// to it, in the format set by `lock.format`
+    pub fn lock(&self) -> Result<Lock, SessionError> {
+        let project_root = self.project_root()?;
+        let mut outcome = do_lock_local_editable(
+            ".",
+            project_root,
+            None,
+            &known_std_libs(),
+            self.resolver()?,
+            &self.project,
+        )
+        .map_err(SessionError::coded)?;
+        outcome
+            .select_variants(&self.config.variants)
+            .map_err(SessionError::coded)?;
+        let LockOutcome { lock, .. } = outcome;
+        let lock = lock.canonicalize();
+        let format = self.config.lock.format.unwrap_or_default();
+        wrapfs::write(
+            project_root.join(format.file_name()),
+            lock.serialize(format),
+        )?;
+        Ok(lock)
+    }
+
+    /// Lockfile of the current project
+    pub fn read_lock(&self) -> Result<Lock, SessionError> {
+        let project_root = self.project_root()?;
+        let Some(path) = [LockFormat::Toml, LockFormat::Json]
+            .iter()
+            .map(|f| project_root.join(f.file_name()))
+            .find(|p| p.is_file())
+        else {
+            return Err(SessionError::NoLockfile(project_root.to_owned()));
+        };
+        wrapfs::read_to_string(&path)?
+            .parse()
+            .map_err(|e| SessionError::InvalidLockfile(path, e))
+    }
+
+    /// Install the projects of the lockfile in the environment of the
+    /// current project, creating the environment if needed
+    pub fn sync(&mut self) -> Result<(), SessionError> {
+        let lock = self.read_lock()?;
+        let project_root = self.project_root()?.to_owned();
+        let mut env = match self.project.env.take() {
+            Some(env) => env,
+            None => {
+                let env_root = match &self.project.current_workspace {
+                    Some(w) => w.root_path(),
+                    None => &project_root,
+                };
+                do_env_local_dir(env_root.join(DEFAULT_ENV_NAME)).map_err(SessionError::coded)?
+            }
+        }
+        .with_storage(self.config.env.storage.unwrap_or_default());
+
+        let (client, runtime, auth_policy) = (&self.client, &self.runtime, &self.auth_policy);
+        let result =
+            do_sync(
+                &lock,
+                &mut env,
+                Some(
+                    |src_path: Utf8UnixPathBuf, checksum: String| -> LocalSrcProject {
+                        LocalSrcProject {
+                            project_path: project_root.join(src_path.as_str()),
+                            nominal_path: Some(src_path),
+                            expected_checksum: Some(checksum),
+                        }
+                    },
+                ),
+                Some(
+                    |remote_src: String,
+                     checksum: String|
+                     -> Result<
+                        AsSyncProjectTokio<ReqwestSrcProjectAsync<Policy>>,
+                        url::ParseError,
+                    > {
+                        Ok(ReqwestSrcProjectAsync::new(
+                            client.clone(),
+                            Url::parse(&remote_src)?,
+                            auth_policy.clone(),
+                            Some(checksum),
+                        )
+                        .to_tokio_sync(runtime.clone()))
+                    },
+                ),
+                Some(
+                    |kpar_path: String, kpar_size: NonZeroU64, kpar_digest: String| {
+                        LocalKParProject::new(
+                            project_root.join(&kpar_path),
+                            KparInnerPath::Guess,
+                            Some(kpar_path.into()),
+                            Some(KparMeta {
+                                size_bytes: kpar_size,
+                                sha256_hex: kpar_digest,
+                            }),
+                        )
+                    },
+                ),
+                Some(
+                    |remote_kpar: String,
+                     size: NonZeroU64,
+                     digest: String|
+                     -> Result<
+                        AsSyncProjectTokio<ReqwestRemoteKparDownloadedProject<Policy>>,
+                        url::ParseError,
+                    > {
+                        let project = ReqwestRemoteKparDownloadedProject::new_guess_root(
+                            Url::parse(&remote_kpar)?,
+                            client.clone(),
+                            auth_policy.clone(),
+                            Some(KparMeta {
+                                size_bytes: size,
+                                sha256_hex: digest,
+                            }),
+                        )
+                        .expect("BUG: failed to create temporary directory");
+                        Ok(project.to_tokio_sync(runtime.clone()))
+                    },
+                ),
+                Some(
+                    |index_kpar: String,
+                     size: NonZeroU64,
+                     digest: String|
+                     -> Result<
+                        AsSyncProjectTokio<ReqwestIndexKparDownloadedProject<Policy>>,
+                        url::ParseError,
+                    > {
+                        let project = ReqwestIndexKparDownloadedProject::new(
+                            Url::parse(&index_kpar)?,
+                            client.clone(),
+                            auth_policy.clone(),
+                            size,
+                            digest,
+                        )
+                        .expect("BUG: failed to create temporary directory");
+                        Ok(project.to_tokio_sync(runtime.clone()))
+                    },
+                ),
+                Some(
+                    |remote_git: String,
+                     rev: Option<String>|
+                     -> Result<GixDownloadedProject, GixDownloadedError> {
+                        Ok(GixDownloadedProject::new(remote_git)?.with_rev(rev))
+                    },
+                ),
+                &known_std_libs(),
+            )
+            .map_err(SessionError::coded);
+        let written = env.write();
+        self.project.env = Some(env);
+        result?;
+        Ok(written?)
+    }
+
+    /// Source files of the current project and, with `include_deps`, of its
+    /// usages installed in the environment
+    pub fn sources(&self, include_deps: bool) -> Result<Vec<Utf8PathBuf>, SessionError> {
+        let project = self.current_project()?;
+        let mut sources: Vec<Utf8PathBuf> =
+            do_sources_local_src_project_no_deps(project, true).map_err(SessionError::coded)?;
+        if !include_deps {
+            return Ok(sources);
+        }
+        let Some(env) = &self.project.env else {
+            return Err(SessionError::NoEnvironment);
+        };
+        let usages = match project
+            .get_info()
+            .map_err(|e| SessionError::coded(LocalSourcesError::Project(e)))?
+        {
+            Some(info) => {
+                info.validate()
+                    .map_err(|e| SessionError::Dependencies(e.into()))?
+                    .usage
+            }
+            None => vec![],
+        };
+        let deps = find_project_dependencies(usages, env.clone(), &known_std_libs())
+            .map_err(|e| SessionError::Dependencies(e.to_string().into()))?;
+        for dep in deps {
+            sources.extend(
+                do_sources_local_src_project_no_deps(&dep, true).map_err(SessionError::coded)?,
+            );
+        }
+        Ok(sources)
+    }
+
+    /// Build the current project into a KPAR at `path`
+    pub fn build<P: AsRef<Utf8Path>>(
+        &self,
+        path: P,
+        compression: KparCompressionMethod,
+    ) -> Result<LocalKParProjectRaw, SessionError> {
+        do_build_kpar(self.current_project()?, path, compression, true, false)
+            .map_err(SessionError::coded)
+    }
+}
+
+/// Runtime for the asynchronous parts of commands, on the current thread
+/// Runtime shared by every [`Session`] created with [`Session::new`], so
+/// that they can share HTTP connections
+pub fn shared_runtime() -> Arc<tokio::runtime::Runtime> {
+    static RUNTIME: OnceLock<Arc<tokio::runtime::Runtime>> = OnceLock::new();
+    RUNTIME.get_or_init(new_runtime).clone()
+}
+
+pub fn new_runtime() -> Arc<tokio::runtime::Runtime> {
+    Arc::new(
+        tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .enable_time()
+            .build()
+            .expect("failed to create async runtime"),
+    )
+}
+
+#[derive(Error, Debug)]
+pub enum SessionError {
+    #[error("no project found in `{0}`")]
+    NoProject(Utf8PathBuf),
+    #[error("no environment found, run `sync` first")]
+    NoEnvironment,
+    #[error("project `{0}` has no lockfile, run `lock` first")]
+    NoLockfile(Utf8PathBuf),
+    #[error("invalid lockfile `{0}`")]
+    InvalidLockfile(Utf8PathBuf, #[source] crate::lock::ParseError),
+    #[error("failed to find usages in the environment: {0}")]
+    Dependencies(Box<dyn Error + Send + Sync>),
+    #[error(transparent)]
+    Config(#[from] ConfigReadError),
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceReadError),
+    #[error(transparent)]
+    Env(#[from] EnvMetadataError),
+    #[error(transparent)]
+    Client(#[from] ReqwestClientBuildError),
+    #[error(transparent)]
+    Resolver(#[from] DiscoveryError),
+    #[error(transparent)]
+    IndexScope(#[from] IndexScopeError),
+    #[error("invalid URL in configuration: {0}")]
+    Url(#[from] url::ParseError),
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+    /// Error of a command, keeping its code
+    #[error(transparent)]
+    Command(CodedError),
+}
+
+impl SessionError {
+    fn coded<E: HasErrorCode + Error + Send + Sync + 'static>(error: E) -> Self {
+        SessionError::Command(CodedError {
+            code: error.error_code(),
+            error: Box::new(error),
+        })
+    }
+}
+
+impl HasErrorCode for SessionError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            SessionError::NoProject(_) => ErrorCode::new(3000, ErrorClass::Project),
+            SessionError::NoEnvironment => ErrorCode::new(3001, ErrorClass::Environment),
+            SessionError::NoLockfile(_) => ErrorCode::new(3002, ErrorClass::Project),
+            SessionError::InvalidLockfile(..) => ErrorCode::new(3003, ErrorClass::Project),
+            SessionError::Dependencies(_) => ErrorCode::new(3004, ErrorClass::Resolution),
+            SessionError::Config(_) => ErrorCode::new(3005, ErrorClass::Usage),
+            SessionError::Workspace(_) => ErrorCode::new(3006, ErrorClass::Project),
+            SessionError::Env(_) => ErrorCode::new(3007, ErrorClass::Environment),
+            SessionError::Client(_) => ErrorCode::new(3008, ErrorClass::Network),
+            SessionError::Resolver(_) => ErrorCode::new(3009, ErrorClass::Resolution),
+            SessionError::IndexScope(_) => ErrorCode::new(3010, ErrorClass::Usage),
+            SessionError::Url(_) => ErrorCode::new(3011, ErrorClass::Usage),
+            SessionError::Io(e) => e.error_code(),
+            SessionError::Command(e) => e.code,
+        }
+    }
+}
+
+/// Error of a command together with its [`ErrorCode`], as commands have
+/// error types generic over the projects and resolvers they use
+#[derive(Debug)]
+pub struct CodedError {
+    pub code: ErrorCode,
+    pub error: Box<dyn Error + Send + Sync>,
+}
+
+impl fmt::Display for CodedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl Error for CodedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.source()
+    }
+}
+
+#[cfg(test)]
+#[path = "./session_tests.rs"]
+mod tests;
@@ -0,0 +1,71 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use camino_tempfile::tempdir;
+
+use super::{Session, SessionError};
+use crate::{
+    build::KparCompressionMethod,
+    env::{DEFAULT_ENV_NAME, ReadEnvironment},
+    error_code::HasErrorCode,
+    include::do_include,
+    init::do_init_local_file,
+    model::HashAlg,
+};
+
+const QUANTITIES: &str =
+    "https://www.omg.org/spec/SysML/20250201/Quantities-and-Units-Domain-Library.kpar";
+
+#[test]
+fn session_runs_project_commands() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp = tempdir()?;
+    let mut project =
+        do_init_local_file("app".into(), None, "1.0.0".into(), None, tmp.path().into())?;
+    std::fs::write(tmp.path().join("app.sysml"), "package App;\n")?;
+    do_include(
+        &mut project,
+        ["app.sysml".into()].into_iter(),
+        Some(HashAlg::Sha256),
+        true,
+        None,
+    )?;
+    std::fs::create_dir(tmp.path().join("nested"))?;
+
+    let mut session = Session::open(tmp.path().join("nested"))?.with_index_urls(None);
+    assert_eq!(session.project_root()?, tmp.path());
+
+    assert!(session.add(QUANTITIES, None)?);
+    let lock = session.lock()?;
+    assert!(
+        lock.projects
+            .iter()
+            .any(|p| p.identifiers.iter().any(|i| i == QUANTITIES))
+    );
+    assert!(tmp.path().join("sysand-lock.toml").is_file());
+
+    session.sync()?;
+    let env = session.project.env.as_ref().unwrap();
+    assert_eq!(env.root_path(), tmp.path().join(DEFAULT_ENV_NAME));
+    // Standard libraries are provided by tools, not installed
+    assert!(!env.has(QUANTITIES)?);
+
+    assert_eq!(session.sources(true)?, [tmp.path().join("app.sysml")]);
+
+    let kpar = tmp.path().join("app.kpar");
+    session.build(&kpar, KparCompressionMethod::default())?;
+    assert!(kpar.is_file());
+
+    Ok(())
+}
+
+#[test]
+fn session_without_project() -> Result<(), Box<dyn std::error::Error>> {
+    let tmp = tempdir()?;
+    let session = Session::open(tmp.path())?;
+
+    let err = session.lock().unwrap_err();
+    assert!(matches!(err, SessionError::NoProject(_)));
+    assert_eq!(err.error_code().to_string(), "SYSAND-E3000");
+
+    Ok(())
+}
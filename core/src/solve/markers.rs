@@ -0,0 +1,331 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Conditions that make a usage apply only when locking for some tool,
+//! metamodel or variant, such as `tool == "syside" and not variant == "lite"`.
+//!
+//! ```text
+//! condition := and ("or" and)*
+//! and       := unary ("and" unary)*
+//! unary     := "not" unary | "(" condition ")" | ATTRIBUTE ("==" | "!=") STRING
+//! ```
+//!
+//! Strings are quoted with `"` or `'` and have no escapes. A comparison with
+//! an attribute that has no value is `false` for `==` and `true` for `!=`.
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use thiserror::Error;
+
+/// Attributes that conditions can compare
+pub const MARKER_ATTRIBUTES: &[&str] = &["tool", "metamodel", "variant"];
+
+/// Values of the attributes that conditions are evaluated against
+pub type Markers = BTreeMap<String, String>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+}
+
+impl fmt::Display for CompareOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompareOp::Eq => f.write_str("=="),
+            CompareOp::Ne => f.write_str("!="),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    Compare {
+        attribute: String,
+        op: CompareOp,
+        value: String,
+    },
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+impl Condition {
+    pub fn evaluate(&self, markers: &Markers) -> bool {
+        match self {
+            Condition::Compare {
+                attribute,
+                op,
+                value,
+            } => {
+                let equal = markers.get(attribute) == Some(value);
+                match op {
+                    CompareOp::Eq => equal,
+                    CompareOp::Ne => !equal,
+                }
+            }
+            Condition::Not(inner) => !inner.evaluate(markers),
+            Condition::And(a, b) => a.evaluate(markers) && b.evaluate(markers),
+            Condition::Or(a, b) => a.evaluate(markers) || b.evaluate(markers),
+        }
+    }
+
+    /// Writes an operand of `and`
+    fn fmt_conjunct(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Or(..) => write!(f, "({self})"),
+            _ => write!(f, "{self}"),
+        }
+    }
+}
+
+/// Canonical form of the condition, with `"` quotes and only the
+/// parentheses that are needed
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Compare {
+                attribute,
+                op,
+                value,
+            } if value.contains('"') => write!(f, "{attribute} {op} '{value}'"),
+            Condition::Compare {
+                attribute,
+                op,
+                value,
+            } => write!(f, "{attribute} {op} \"{value}\""),
+            Condition::Not(inner) => match inner.as_ref() {
+                Condition::And(..) | Condition::Or(..) => write!(f, "not ({inner})"),
+                _ => write!(f, "not {inner}"),
+            },
+            Condition::And(a, b) => {
+                a.fmt_conjunct(f)?;
+                f.write_str(" and ")?;
+                b.fmt_conjunct(f)
+            }
+            Condition::Or(a, b) => {
+                write!(f, "{a}")?;
+                f.write_str(" or ")?;
+                match b.as_ref() {
+                    Condition::Or(..) => write!(f, "({b})"),
+                    _ => write!(f, "{b}"),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ConditionError {
+    #[error(
+        "unknown attribute `{0}`, expected one of `{attributes}`",
+        attributes = MARKER_ATTRIBUTES.join("`, `")
+    )]
+    UnknownAttribute(String),
+    #[error("unterminated string starting at position {0}")]
+    UnterminatedString(usize),
+    #[error("unexpected `{found}` at position {position}, expected {expected}")]
+    Unexpected {
+        found: String,
+        position: usize,
+        expected: &'static str,
+    },
+    #[error("unexpected end of condition, expected {0}")]
+    UnexpectedEnd(&'static str),
+    #[error("invalid marker `{0}`, expected `ATTRIBUTE=VALUE`")]
+    InvalidMarker(String),
+}
+
+impl FromStr for Condition {
+    type Err = ConditionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            next: 0,
+        };
+        let condition = parser.or()?;
+        match parser.tokens.get(parser.next) {
+            None => Ok(condition),
+            Some((position, token)) => Err(ConditionError::Unexpected {
+                found: token.to_string(),
+                position: *position,
+                expected: "`and`, `or` or the end of the condition",
+            }),
+        }
+    }
+}
+
+/// Parses an `ATTRIBUTE=VALUE` marker
+pub fn parse_marker(s: &str) -> Result<(String, String), ConditionError> {
+    let (attribute, value) = s
+        .split_once('=')
+        .ok_or_else(|| ConditionError::InvalidMarker(s.to_owned()))?;
+    let attribute = attribute.trim();
+    if !MARKER_ATTRIBUTES.contains(&attribute) {
+        return Err(ConditionError::UnknownAttribute(attribute.to_owned()));
+    }
+    Ok((attribute.to_owned(), value.trim().to_owned()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Op(CompareOp),
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(word) => f.write_str(word),
+            Token::Str(s) => write!(f, "\"{s}\""),
+            Token::Op(op) => write!(f, "{op}"),
+            Token::Open => f.write_str("("),
+            Token::Close => f.write_str(")"),
+        }
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<(usize, Token)>, ConditionError> {
+    let mut tokens = vec![];
+    let mut chars = s.char_indices().peekable();
+    while let Some((position, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '"' | '\'' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, end)) if end == c => break,
+                        Some((_, other)) => value.push(other),
+                        None => return Err(ConditionError::UnterminatedString(position)),
+                    }
+                }
+                Token::Str(value)
+            }
+            '=' | '!' if chars.next_if(|(_, next)| *next == '=').is_some() => {
+                Token::Op(if c == '=' {
+                    CompareOp::Eq
+                } else {
+                    CompareOp::Ne
+                })
+            }
+            c if c.is_ascii_alphanumeric() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some((_, next)) =
+                    chars.next_if(|(_, next)| next.is_ascii_alphanumeric() || *next == '_')
+                {
+                    word.push(next);
+                }
+                Token::Word(word)
+            }
+            other => {
+                return Err(ConditionError::Unexpected {
+                    found: other.to_string(),
+                    position,
+                    expected: "an attribute, `not` or `(`",
+                });
+            }
+        };
+        tokens.push((position, token));
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+}
+
+impl Parser {
+    fn peek_word(&self, word: &str) -> bool {
+        matches!(self.tokens.get(self.next), Some((_, Token::Word(w))) if w == word)
+    }
+
+    fn take(&mut self, expected: &'static str) -> Result<(usize, Token), ConditionError> {
+        let token = self
+            .tokens
+            .get(self.next)
+            .cloned()
+            .ok_or(ConditionError::UnexpectedEnd(expected))?;
+        self.next += 1;
+        Ok(token)
+    }
+
+    fn or(&mut self) -> Result<Condition, ConditionError> {
+        let mut condition = self.and()?;
+        while self.peek_word("or") {
+            self.next += 1;
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition, ConditionError> {
+        let mut condition = self.unary()?;
+        while self.peek_word("and") {
+            self.next += 1;
+            condition = Condition::And(Box::new(condition), Box::new(self.unary()?));
+        }
+        Ok(condition)
+    }
+
+    fn unary(&mut self) -> Result<Condition, ConditionError> {
+        const OPERAND: &str = "an attribute, `not` or `(`";
+        match self.take(OPERAND)? {
+            (_, Token::Word(word)) if word == "not" => Ok(Condition::Not(Box::new(self.unary()?))),
+            (_, Token::Open) => {
+                let condition = self.or()?;
+                match self.take("`)`")? {
+                    (_, Token::Close) => Ok(condition),
+                    (position, token) => Err(ConditionError::Unexpected {
+                        found: token.to_string(),
+                        position,
+                        expected: "`)`",
+                    }),
+                }
+            }
+            (_, Token::Word(attribute)) => {
+                if !MARKER_ATTRIBUTES.contains(&attribute.as_str()) {
+                    return Err(ConditionError::UnknownAttribute(attribute));
+                }
+                let op = match self.take("`==` or `!=`")? {
+                    (_, Token::Op(op)) => op,
+                    (position, token) => {
+                        return Err(ConditionError::Unexpected {
+                            found: token.to_string(),
+                            position,
+                            expected: "`==` or `!=`",
+                        });
+                    }
+                };
+                match self.take("a quoted string")? {
+                    (_, Token::Str(value)) => Ok(Condition::Compare {
+                        attribute,
+                        op,
+                        value,
+                    }),
+                    (position, token) => Err(ConditionError::Unexpected {
+                        found: token.to_string(),
+                        position,
+                        expected: "a quoted string",
+                    }),
+                }
+            }
+            (position, token) => Err(ConditionError::Unexpected {
+                found: token.to_string(),
+                position,
+                expected: OPERAND,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./markers_tests.rs"]
+mod tests;
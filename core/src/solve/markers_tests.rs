@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use crate::solve::markers::{Condition, ConditionError, Markers, parse_marker};
+
+fn markers(pairs: &[(&str, &str)]) -> Markers {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn eval(condition: &str, pairs: &[(&str, &str)]) -> bool {
+    condition
+        .parse::<Condition>()
+        .unwrap()
+        .evaluate(&markers(pairs))
+}
+
+#[test]
+fn evaluate_conditions() {
+    let syside = [("tool", "syside"), ("variant", "full")];
+    assert!(eval("tool == \"syside\"", &syside));
+    assert!(!eval("tool != 'syside'", &syside));
+    assert!(eval("tool == 'other' or variant == 'full'", &syside));
+    assert!(!eval("tool == 'syside' and not variant == 'full'", &syside));
+    assert!(eval(
+        "not (tool == 'other' or variant == 'lite') and tool == 'syside'",
+        &syside
+    ));
+    // Unset attributes are not equal to any value
+    assert!(!eval("metamodel == ''", &syside));
+    assert!(eval("metamodel != 'x'", &syside));
+}
+
+#[test]
+fn and_binds_tighter_than_or() {
+    let condition: Condition = "tool == 'a' or tool == 'b' and variant == 'c'"
+        .parse()
+        .unwrap();
+    assert!(condition.evaluate(&markers(&[("tool", "a")])));
+    assert_eq!(
+        condition.to_string(),
+        "tool == \"a\" or tool == \"b\" and variant == \"c\""
+    );
+}
+
+#[test]
+fn display_round_trips() {
+    for condition in [
+        "(tool == \"a\" or tool == \"b\") and variant == \"c\"",
+        "not (tool == \"a\" and variant == \"b\")",
+        "not tool == \"a\"",
+        "tool == \"a\" or (tool == \"b\" or tool == \"c\")",
+        "tool == 'say \"hi\"'",
+    ] {
+        let parsed: Condition = condition.parse().unwrap();
+        assert_eq!(parsed.to_string(), condition);
+        assert_eq!(parsed.to_string().parse::<Condition>().unwrap(), parsed);
+    }
+}
+
+#[test]
+fn parse_errors() {
+    let err = |s: &str| s.parse::<Condition>().unwrap_err();
+    assert_eq!(
+        err("platform == 'x'"),
+        ConditionError::UnknownAttribute("platform".into())
+    );
+    assert_eq!(err("tool == 'x"), ConditionError::UnterminatedString(8));
+    assert_eq!(
+        err("tool = 'x'"),
+        ConditionError::Unexpected {
+            found: "=".into(),
+            position: 5,
+            expected: "an attribute, `not` or `(`"
+        }
+    );
+    assert_eq!(err("(tool == 'x'"), ConditionError::UnexpectedEnd("`)`"));
+    assert!(matches!(
+        err("tool == 'x' tool"),
+        ConditionError::Unexpected { position: 12, .. }
+    ));
+    assert_eq!(
+        err(""),
+        ConditionError::UnexpectedEnd("an attribute, `not` or `(`")
+    );
+}
+
+#[test]
+fn parse_markers() {
+    assert_eq!(
+        parse_marker("tool=syside").unwrap(),
+        ("tool".to_string(), "syside".to_string())
+    );
+    assert_eq!(
+        parse_marker("tool").unwrap_err(),
+        ConditionError::InvalidMarker("tool".into())
+    );
+    assert_eq!(
+        parse_marker("os=linux").unwrap_err(),
+        ConditionError::UnknownAttribute("os".into())
+    );
+}
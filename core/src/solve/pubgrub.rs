@@ -11,11 +11,17 @@ use std::{
     collections::{HashMap, HashSet, hash_map::Entry},
     fmt::Write as _,
     fmt::{self, Display},
+    num::NonZeroUsize,
 };
 
 use thiserror::Error;
 
-use crate::{model::InterchangeProjectUsage, project::ProjectRead, resolve::ResolveRead};
+use crate::{
+    model::InterchangeProjectUsage,
+    project::ProjectRead,
+    resolve::{DEFAULT_RESOLVE_CONCURRENCY, ResolutionOutcome, ResolveRead},
+    solve::markers::Markers,
+};
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum DependencyIdentifier {
@@ -200,11 +206,26 @@ struct CandidateSummary {
     usage: Vec<InterchangeProjectUsage>,
 }
 
+/// Which of the versions allowed by all constraints the solver prefers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VersionStrategy {
+    #[default]
+    Newest,
+    /// Lowest versions, to check that lower bounds of constraints are
+    /// correct
+    Minimal,
+}
+
 pub struct ProjectSolver<R: ResolveRead> {
     // Internal RefCell, used in order to lazily populate the cache during resolution
     resolved_candidates: RefCell<CandidateMap<R::ProjectStorage>>,
     // dependency_provider: OfflineDependencyProvider<DependencyIdentifier, DiscreteHashSet>,
     resolver: R,
+    /// Number of IRIs resolved concurrently
+    concurrency: NonZeroUsize,
+    /// Markers that conditional usages are evaluated against
+    markers: Markers,
+    strategy: VersionStrategy,
 }
 
 /// Returned Vec will have `len >= 1`
@@ -222,115 +243,164 @@ fn resolve_candidates<R: ResolveRead>(
             .map(|c| c.summary.clone())
             .collect()),
         Entry::Vacant(vacant_entry) => {
-            let mut found = vec![];
-
-            match resolver
-                .resolve_read(uri)
-                .map_err(InternalSolverError::Resolution)?
-            {
-                crate::resolve::ResolutionOutcome::UnsupportedIRIType(msg) => {
-                    return Err(InternalSolverError::UnsupportedIriType(format!(
-                        "unsupported IRI type of `{uri}`: {msg}"
-                    )));
-                }
-                crate::resolve::ResolutionOutcome::Unresolvable(msg) => {
-                    return Err(InternalSolverError::NotFound(uri.as_str().into(), msg));
-                }
-                crate::resolve::ResolutionOutcome::Resolved(alternatives) => {
-                    for alternative in alternatives {
-                        let project = match alternative {
-                            Ok(project) => project,
-                            Err(e) => {
-                                log::debug!("candidate project for `{uri}` is error: {e}");
-                                continue;
-                            }
-                        };
-
-                        let version = match project.version() {
-                            Ok(Some(version)) => match semver::Version::parse(&version) {
-                                Ok(version) => version,
-                                Err(e) => {
-                                    log::debug!(
-                                        "candidate project for `{uri}` has invalid version `{version}`: {e}"
-                                    );
-                                    continue;
-                                }
-                            },
-                            Ok(None) => {
-                                log::debug!(
-                                    "candidate project for `{uri}` did not expose a version"
-                                );
-                                continue;
-                            }
-                            Err(e) => {
-                                log::debug!(
-                                    "candidate project for `{uri}` failed to get version: {e}"
-                                );
-                                continue;
-                            }
-                        };
-
-                        let usage = match project.usage() {
-                            Ok(Some(usage)) => {
-                                let validated: Result<Vec<InterchangeProjectUsage>, _> =
-                                    usage.into_iter().map(|usage| usage.validate()).collect();
-                                match validated {
-                                    Ok(usage) => usage,
-                                    Err(e) => {
-                                        log::debug!(
-                                            "candidate project for `{uri}` has invalid usage: {e}"
-                                        );
-                                        continue;
-                                    }
-                                }
-                            }
-                            Ok(None) => {
-                                log::debug!("candidate project for `{uri}` did not expose usages");
-                                continue;
-                            }
+            let found = candidates_from_outcome::<R>(uri, resolver.resolve_read(uri))?;
+            let result: Vec<CandidateSummary> = found.iter().map(|c| c.summary.clone()).collect();
+
+            vacant_entry.insert(found);
+
+            Ok(result)
+        }
+    }
+}
+
+/// Candidates of a resolved IRI, `len >= 1`
+fn candidates_from_outcome<R: ResolveRead>(
+    uri: &fluent_uri::Iri<String>,
+    outcome: Result<ResolutionOutcome<R::ResolvedStorages>, R::Error>,
+) -> Result<Vec<Candidate<R::ProjectStorage>>, InternalSolverError<R>> {
+    let mut found = vec![];
+
+    match outcome.map_err(InternalSolverError::Resolution)? {
+        ResolutionOutcome::UnsupportedIRIType(msg) => {
+            return Err(InternalSolverError::UnsupportedIriType(format!(
+                "unsupported IRI type of `{uri}`: {msg}"
+            )));
+        }
+        ResolutionOutcome::Unresolvable(msg) => {
+            return Err(InternalSolverError::NotFound(uri.as_str().into(), msg));
+        }
+        ResolutionOutcome::Resolved(alternatives) => {
+            for alternative in alternatives {
+                let project = match alternative {
+                    Ok(project) => project,
+                    Err(e) => {
+                        log::debug!("candidate project for `{uri}` is error: {e}");
+                        continue;
+                    }
+                };
+
+                let version = match project.version() {
+                    Ok(Some(version)) => match semver::Version::parse(&version) {
+                        Ok(version) => version,
+                        Err(e) => {
+                            log::debug!(
+                                "candidate project for `{uri}` has invalid version `{version}`: {e}"
+                            );
+                            continue;
+                        }
+                    },
+                    Ok(None) => {
+                        log::debug!("candidate project for `{uri}` did not expose a version");
+                        continue;
+                    }
+                    Err(e) => {
+                        log::debug!("candidate project for `{uri}` failed to get version: {e}");
+                        continue;
+                    }
+                };
+
+                let usage = match project.usage() {
+                    Ok(Some(usage)) => {
+                        let validated: Result<Vec<InterchangeProjectUsage>, _> =
+                            usage.into_iter().map(|usage| usage.validate()).collect();
+                        match validated {
+                            Ok(usage) => usage,
                             Err(e) => {
-                                log::debug!(
-                                    "candidate project for `{uri}` failed to get usages: {e}"
-                                );
+                                log::debug!("candidate project for `{uri}` has invalid usage: {e}");
                                 continue;
                             }
-                        };
-
-                        found.push(Candidate {
-                            summary: CandidateSummary { version, usage },
-                            project,
-                        });
+                        }
                     }
-                    if found.is_empty() {
-                        return Err(InternalSolverError::NoValidCandidates(uri.as_str().into()));
+                    Ok(None) => {
+                        log::debug!("candidate project for `{uri}` did not expose usages");
+                        continue;
                     }
-                }
-            }
+                    Err(e) => {
+                        log::debug!("candidate project for `{uri}` failed to get usages: {e}");
+                        continue;
+                    }
+                };
 
-            let result: Vec<CandidateSummary> = found.iter().map(|c| c.summary.clone()).collect();
+                found.push(Candidate {
+                    summary: CandidateSummary { version, usage },
+                    project,
+                });
+            }
+            if found.is_empty() {
+                return Err(InternalSolverError::NoValidCandidates(uri.as_str().into()));
+            }
+        }
+    }
 
-            vacant_entry.insert(found);
+    Ok(found)
+}
 
-            Ok(result)
+/// Resolve the not yet cached IRIs of `usages` at once, so that the
+/// resolver can resolve them concurrently. Then resolve those used by the
+/// candidate `strategy` prefers for each of them, and so on, so that deep
+/// graphs are resolved in one batch per level instead of one IRI at a time.
+/// Failures are not cached, and are reported when [`resolve_candidates`]
+/// resolves the IRI again
+fn prefetch_candidates<R: ResolveRead>(
+    resolver: &R,
+    usages: &[InterchangeProjectUsage],
+    cache: &mut CandidateMap<R::ProjectStorage>,
+    concurrency: NonZeroUsize,
+    markers: &Markers,
+    strategy: VersionStrategy,
+) {
+    let uncached = |usages: &mut dyn Iterator<Item = &InterchangeProjectUsage>,
+                    cache: &CandidateMap<R::ProjectStorage>| {
+        usages
+            .filter(|usage| usage.applies(markers))
+            .map(|InterchangeProjectUsage::Resource { resource, .. }| resource)
+            .filter(|resource| !cache.contains_key(*resource))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    let mut missing = uncached(&mut usages.iter(), cache);
+    while !missing.is_empty() {
+        let mut next = vec![];
+        for (uri, outcome) in resolver.resolve_many(missing, concurrency) {
+            if let Ok(found) = candidates_from_outcome::<R>(&uri, outcome) {
+                let preferred = match strategy {
+                    VersionStrategy::Newest => found.iter().max_by_key(|c| &c.summary.version),
+                    VersionStrategy::Minimal => found.iter().min_by_key(|c| &c.summary.version),
+                };
+                next.extend(preferred.into_iter().flat_map(|c| c.summary.usage.clone()));
+                cache.insert(uri, found);
+            }
         }
+        missing = uncached(&mut next.iter(), cache);
     }
 }
 
 fn compute_deps<R: ResolveRead + fmt::Debug>(
     resolver: &R,
-    usages: &Vec<InterchangeProjectUsage>,
+    usages: &[InterchangeProjectUsage],
     cache: &mut CandidateMap<R::ProjectStorage>,
+    concurrency: NonZeroUsize,
+    markers: &Markers,
+    strategy: VersionStrategy,
 ) -> Result<
     pubgrub::Dependencies<DependencyIdentifier, DiscreteHashSet, String>,
     InternalSolverError<R>,
 > {
+    let usages: Vec<_> = usages
+        .iter()
+        .filter(|usage| usage.applies(markers))
+        .cloned()
+        .collect();
+    prefetch_candidates(resolver, &usages, cache, concurrency, markers, strategy);
+
     let mut deps: Vec<(DependencyIdentifier, DiscreteHashSet)> = Vec::new();
 
-    for usage in usages {
+    for usage in &usages {
         match usage {
             InterchangeProjectUsage::Resource {
                 resource,
                 version_constraint,
+                ..
             } => {
                 if let Some(constraint) = version_constraint {
                     let mut valid_candidates = HashSet::new();
@@ -481,9 +551,29 @@ impl<R: ResolveRead> ProjectSolver<R> {
             resolved_candidates: RefCell::new(HashMap::new()),
             //dependency_provider: OfflineDependencyProvider::<DependencyIdentifier, DiscreteHashSet>::new(),
             resolver,
+            concurrency: DEFAULT_RESOLVE_CONCURRENCY,
+            markers: Markers::new(),
+            strategy: VersionStrategy::default(),
         }
     }
 
+    /// Resolve up to `concurrency` IRIs at a time
+    pub fn with_concurrency(mut self, concurrency: NonZeroUsize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Skip usages whose condition does not hold under `markers`
+    pub fn with_markers(mut self, markers: Markers) -> Self {
+        self.markers = markers;
+        self
+    }
+
+    pub fn with_strategy(mut self, strategy: VersionStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
     //let mut map: RefMut<'_, _> = self.resolved_candidates.borrow_mut();
 }
 
@@ -519,7 +609,21 @@ impl<R: ResolveRead + fmt::Debug + 'static> DependencyProvider for ProjectSolver
     ) -> Result<Option<Self::V>, Self::Err> {
         match range {
             DiscreteHashSet::Finite(hash_set) => {
-                let res = hash_set.iter().min().cloned();
+                let res = match (package, self.strategy) {
+                    (DependencyIdentifier::Remote(iri), VersionStrategy::Minimal) => {
+                        let candidates = resolve_candidates(
+                            &self.resolver,
+                            iri,
+                            &mut self.resolved_candidates.borrow_mut(),
+                        )?;
+                        hash_set
+                            .iter()
+                            .filter(|i| **i < candidates.len())
+                            .min_by(|i, j| candidates[**i].version.cmp(&candidates[**j].version))
+                            .cloned()
+                    }
+                    _ => hash_set.iter().min().cloned(),
+                };
                 log::debug!("choosing version for request ({:?})", res);
                 Ok(res)
             }
@@ -541,10 +645,17 @@ impl<R: ResolveRead + fmt::Debug + 'static> DependencyProvider for ProjectSolver
                                 .enumerate()
                                 .map(|(idx, el)| (idx, el.version))
                                 .collect();
-                        // Choose the highest version. We'll assume that version
-                        // order is stable across multiple `resolve_candidates()`
+                        // Choose the highest (or lowest) version. We'll assume that
+                        // version order is stable across multiple `resolve_candidates()`
                         // calls, as DiscreteHashSet does not save actual versions
-                        versions_indexes.sort_unstable_by(|el1, el2| el2.1.cmp(&el1.1));
+                        match self.strategy {
+                            VersionStrategy::Newest => {
+                                versions_indexes.sort_unstable_by(|el1, el2| el2.1.cmp(&el1.1))
+                            }
+                            VersionStrategy::Minimal => {
+                                versions_indexes.sort_unstable_by(|el1, el2| el1.1.cmp(&el2.1))
+                            }
+                        }
                         let mut found = None;
                         for (i, v) in versions_indexes.iter() {
                             if !hash_set.contains(i) {
@@ -578,6 +689,9 @@ impl<R: ResolveRead + fmt::Debug + 'static> DependencyProvider for ProjectSolver
                 &self.resolver,
                 usages,
                 &mut self.resolved_candidates.borrow_mut(),
+                self.concurrency,
+                &self.markers,
+                self.strategy,
             ),
             DependencyIdentifier::Remote(iri) => {
                 let info = {
@@ -601,6 +715,9 @@ impl<R: ResolveRead + fmt::Debug + 'static> DependencyProvider for ProjectSolver
                     &self.resolver,
                     &info.usage,
                     &mut self.resolved_candidates.borrow_mut(),
+                    self.concurrency,
+                    &self.markers,
+                    self.strategy,
                 )
             }
         }
@@ -613,7 +730,28 @@ pub fn solve<R: ResolveRead + fmt::Debug + 'static>(
     requested: Vec<InterchangeProjectUsage>,
     resolver: R,
 ) -> Result<Solution<R::ProjectStorage>, SolverError<R>> {
-    let solver = ProjectSolver::new(resolver);
+    solve_with_markers(requested, resolver, Markers::new())
+}
+
+/// Like [`solve`], but only with the usages that apply under `markers`
+pub fn solve_with_markers<R: ResolveRead + fmt::Debug + 'static>(
+    requested: Vec<InterchangeProjectUsage>,
+    resolver: R,
+    markers: Markers,
+) -> Result<Solution<R::ProjectStorage>, SolverError<R>> {
+    solve_with_strategy(requested, resolver, markers, VersionStrategy::default())
+}
+
+/// Like [`solve_with_markers`], preferring versions by `strategy`
+pub fn solve_with_strategy<R: ResolveRead + fmt::Debug + 'static>(
+    requested: Vec<InterchangeProjectUsage>,
+    resolver: R,
+    markers: Markers,
+    strategy: VersionStrategy,
+) -> Result<Solution<R::ProjectStorage>, SolverError<R>> {
+    let solver = ProjectSolver::new(resolver)
+        .with_markers(markers)
+        .with_strategy(strategy);
 
     let package = DependencyIdentifier::Requested(requested);
 
@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
 
-use std::collections::HashMap;
+use std::{cell::RefCell, collections::HashMap, num::NonZeroUsize, rc::Rc};
 
 use fluent_uri::Iri;
 use indexmap::IndexMap;
@@ -13,7 +13,7 @@ use crate::{
         InterchangeProjectUsageRaw,
     },
     project::{ProjectRead, memory::InMemoryProject},
-    resolve::env::EnvResolver,
+    resolve::{IriOutcome, ResolutionOutcome, ResolveRead, env::EnvResolver},
 };
 
 fn trivial_memory_project(
@@ -36,6 +36,8 @@ fn trivial_memory_project(
                 .map(|(d, dv)| InterchangeProjectUsageRaw::Resource {
                     resource: d.to_string(),
                     version_constraint: dv.map(|x| x.to_string()),
+                    when: None,
+                    hash: None,
                 })
                 .collect(),
         }),
@@ -46,6 +48,7 @@ fn trivial_memory_project(
             includes_derived: None,
             includes_implied: None,
             checksum: Some(IndexMap::default()),
+            variants: IndexMap::new(),
         }),
         files: HashMap::default(),
         nominal_sources: vec![],
@@ -96,6 +99,8 @@ fn version_selection() -> Result<(), Box<dyn std::error::Error>> {
         vec![InterchangeProjectUsage::Resource {
             resource: fluent_uri::Iri::parse("urn:kpar:version_selection")?.into(),
             version_constraint: Some(semver::VersionReq::parse(">=2.0.0")?),
+            when: None,
+            hash: None,
         }],
         resolver,
     )?;
@@ -154,10 +159,14 @@ fn diamond_selection() -> Result<(), Box<dyn std::error::Error>> {
             InterchangeProjectUsage::Resource {
                 resource: fluent_uri::Iri::parse("urn:kpar:diamond_selection_a")?.into(),
                 version_constraint: Some(semver::VersionReq::parse(">=0.1.0")?),
+                when: None,
+                hash: None,
             },
             InterchangeProjectUsage::Resource {
                 resource: fluent_uri::Iri::parse("urn:kpar:diamond_selection_b")?.into(),
                 version_constraint: None,
+                when: None,
+                hash: None,
             },
         ],
         resolver,
@@ -182,3 +191,202 @@ fn diamond_selection() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn minimal_version_selection() -> Result<(), Box<dyn std::error::Error>> {
+    let project_a = trivial_memory_project(
+        "minimal_a",
+        "1.0.0",
+        vec![("urn:kpar:minimal_b", Some(">=1.1.0"))],
+    );
+    let project_b: Vec<_> = ["1.0.0", "1.1.0", "1.2.0"]
+        .map(|v| trivial_memory_project("minimal_b", v, vec![]))
+        .into();
+    let structure: &[(&str, &[InMemoryProject])] = &[
+        ("urn:kpar:minimal_a", &[project_a]),
+        ("urn:kpar:minimal_b", &project_b),
+    ];
+    let requested = || -> Result<_, Box<dyn std::error::Error>> {
+        Ok(vec![InterchangeProjectUsage::Resource {
+            resource: Iri::parse("urn:kpar:minimal_a")?.into(),
+            version_constraint: None,
+            when: None,
+            hash: None,
+        }])
+    };
+    let version_b = |strategy| -> Result<_, Box<dyn std::error::Error>> {
+        let solution = super::solve_with_strategy(
+            requested()?,
+            simple_resolver_environment(structure),
+            Default::default(),
+            strategy,
+        )?;
+        Ok(solution
+            .get(Iri::parse("urn:kpar:minimal_b")?.into())
+            .unwrap()
+            .version()?
+            .unwrap())
+    };
+
+    assert_eq!(version_b(super::VersionStrategy::Minimal)?, "1.1.0");
+
+    Ok(())
+}
+
+#[test]
+fn conditional_usages_follow_markers() -> Result<(), Box<dyn std::error::Error>> {
+    let mut project_a = trivial_memory_project(
+        "conditional_a",
+        "1.0.0",
+        vec![
+            ("urn:kpar:conditional_b", None),
+            ("urn:kpar:conditional_c", None),
+        ],
+    );
+    for (usage, when) in project_a
+        .info
+        .as_mut()
+        .unwrap()
+        .usage
+        .iter_mut()
+        .zip([r#"tool == "syside""#, r#"tool != "syside""#])
+    {
+        let InterchangeProjectUsageRaw::Resource { when: w, .. } = usage;
+        *w = Some(when.to_owned());
+    }
+    let project_b = trivial_memory_project("conditional_b", "1.0.0", vec![]);
+    let project_c = trivial_memory_project("conditional_c", "1.0.0", vec![]);
+    let structure: &[(&str, &[InMemoryProject])] = &[
+        ("urn:kpar:conditional_a", &[project_a]),
+        ("urn:kpar:conditional_b", &[project_b]),
+        ("urn:kpar:conditional_c", &[project_c]),
+    ];
+    let requested = || -> Result<_, Box<dyn std::error::Error>> {
+        Ok(vec![InterchangeProjectUsage::Resource {
+            resource: Iri::parse("urn:kpar:conditional_a")?.into(),
+            version_constraint: None,
+            when: None,
+            hash: None,
+        }])
+    };
+
+    let markers = [("tool".to_owned(), "syside".to_owned())].into();
+    let solution = super::solve_with_markers(
+        requested()?,
+        simple_resolver_environment(structure),
+        markers,
+    )?;
+    let mut names: Vec<_> = solution.keys().map(|iri| iri.as_str()).collect();
+    names.sort();
+    assert_eq!(names, ["urn:kpar:conditional_a", "urn:kpar:conditional_b"]);
+
+    let solution = super::solve(requested()?, simple_resolver_environment(structure))?;
+    let mut names: Vec<_> = solution.keys().map(|iri| iri.as_str()).collect();
+    names.sort();
+    assert_eq!(names, ["urn:kpar:conditional_a", "urn:kpar:conditional_c"]);
+
+    Ok(())
+}
+
+/// Resolver recording the IRIs resolved by each call, separately for
+/// `resolve_many` and `resolve_read`
+#[derive(Debug)]
+struct RecordingResolver<R> {
+    inner: R,
+    batches: Rc<RefCell<Vec<Vec<String>>>>,
+    single: Rc<RefCell<Vec<String>>>,
+}
+
+impl<R: ResolveRead> ResolveRead for RecordingResolver<R> {
+    type Error = R::Error;
+    type ProjectStorage = R::ProjectStorage;
+    type ResolvedStorages = R::ResolvedStorages;
+
+    fn resolve_read(
+        &self,
+        uri: &Iri<String>,
+    ) -> Result<ResolutionOutcome<Self::ResolvedStorages>, Self::Error> {
+        self.single.borrow_mut().push(uri.to_string());
+        self.inner.resolve_read(uri)
+    }
+
+    fn resolve_many<I: IntoIterator<Item = Iri<String>>>(
+        &self,
+        uris: I,
+        _concurrency: NonZeroUsize,
+    ) -> Vec<IriOutcome<Self::ResolvedStorages, Self::Error>> {
+        let uris: Vec<_> = uris.into_iter().collect();
+        self.batches
+            .borrow_mut()
+            .push(uris.iter().map(|uri| uri.to_string()).collect());
+        uris.into_iter()
+            .map(|uri| {
+                let outcome = self.inner.resolve_read(&uri);
+                (uri, outcome)
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn deep_graph_is_prefetched_by_level() -> Result<(), Box<dyn std::error::Error>> {
+    let structure: &[(&str, &[InMemoryProject])] = &[
+        (
+            "urn:kpar:a",
+            &[trivial_memory_project(
+                "a",
+                "1.0.0",
+                vec![("urn:kpar:b", None)],
+            )],
+        ),
+        (
+            "urn:kpar:b",
+            &[trivial_memory_project(
+                "b",
+                "1.0.0",
+                vec![("urn:kpar:c", None)],
+            )],
+        ),
+        (
+            "urn:kpar:c",
+            &[trivial_memory_project("c", "1.0.0", vec![])],
+        ),
+        (
+            "urn:kpar:x",
+            &[trivial_memory_project("x", "1.0.0", vec![])],
+        ),
+    ];
+    let resolver = RecordingResolver {
+        inner: simple_resolver_environment(structure),
+        batches: Rc::default(),
+        single: Rc::default(),
+    };
+    let (batches, single) = (resolver.batches.clone(), resolver.single.clone());
+
+    let requested = ["urn:kpar:a", "urn:kpar:x"]
+        .map(|iri| -> Result<_, Box<dyn std::error::Error>> {
+            Ok(InterchangeProjectUsage::Resource {
+                resource: Iri::parse(iri)?.into(),
+                version_constraint: None,
+                when: None,
+                hash: None,
+            })
+        })
+        .into_iter()
+        .collect::<Result<_, _>>()?;
+    let solution = super::solve(requested, resolver)?;
+
+    assert_eq!(solution.len(), 4);
+    assert_eq!(
+        *batches.borrow(),
+        [
+            vec!["urn:kpar:a", "urn:kpar:x"],
+            vec!["urn:kpar:b"],
+            vec!["urn:kpar:c"]
+        ]
+    );
+    // Every IRI is resolved once
+    assert!(single.borrow().is_empty());
+
+    Ok(())
+}
@@ -1,9 +1,14 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use crate::project::memory::InMemoryProject;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    model::{InterchangeProjectUsageRaw, KERML_METAMODEL_PREFIX, SYSML_METAMODEL_PREFIX},
+    project::memory::InMemoryProject,
+};
 
 const QUANTITIES_AND_UNITS_LIBRARY_INFO_20250201: &str =
     include_str!("stdlib_assets/20250201/quantities-and-units-library.project.json");
@@ -121,3 +126,129 @@ pub fn known_std_libs() -> HashMap<String, Vec<InMemoryProject>> {
         ),
     ])
 }
+
+/// Whether dependencies on the known standard libraries are processed
+/// (locked, installed, listed in sources) instead of assumed to be provided
+/// by the tool. Set by `include_std = true | false | "auto"` in the
+/// configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(from = "IncludeStdRaw", into = "IncludeStdRaw")]
+pub enum IncludeStd {
+    Always,
+    Never,
+    /// Only the standard libraries of the metamodel declared by the project
+    Auto,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+enum IncludeStdRaw {
+    Bool(bool),
+    Mode(IncludeStdMode),
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+enum IncludeStdMode {
+    Auto,
+}
+
+impl From<IncludeStdRaw> for IncludeStd {
+    fn from(raw: IncludeStdRaw) -> Self {
+        match raw {
+            IncludeStdRaw::Bool(true) => IncludeStd::Always,
+            IncludeStdRaw::Bool(false) => IncludeStd::Never,
+            IncludeStdRaw::Mode(IncludeStdMode::Auto) => IncludeStd::Auto,
+        }
+    }
+}
+
+impl From<IncludeStd> for IncludeStdRaw {
+    fn from(include: IncludeStd) -> Self {
+        match include {
+            IncludeStd::Always => IncludeStdRaw::Bool(true),
+            IncludeStd::Never => IncludeStdRaw::Bool(false),
+            IncludeStd::Auto => IncludeStdRaw::Mode(IncludeStdMode::Auto),
+        }
+    }
+}
+
+impl IncludeStd {
+    /// Known standard libraries to treat as provided by the tool for a
+    /// project with `metamodel`
+    pub fn ignored_std_libs(
+        self,
+        metamodel: Option<&str>,
+    ) -> HashMap<String, Vec<InMemoryProject>> {
+        let mut libs = known_std_libs();
+        match self {
+            IncludeStd::Always => libs.clear(),
+            IncludeStd::Never => {}
+            IncludeStd::Auto => {
+                libs.retain(|iri, _| !metamodel.is_some_and(|m| std_lib_matches_metamodel(iri, m)))
+            }
+        }
+        libs
+    }
+}
+
+/// Whether the standard library `iri` is part of the release of
+/// `metamodel`, e.g. `https://www.omg.org/spec/SysML/20250201`. A SysML
+/// metamodel also matches the KerML libraries of its release
+pub fn std_lib_matches_metamodel(iri: &str, metamodel: &str) -> bool {
+    /// Whether `iri` is KerML, and its release
+    fn release(iri: &str) -> Option<(bool, &str)> {
+        let (kerml, rest) = match iri.strip_prefix(KERML_METAMODEL_PREFIX) {
+            Some(rest) => (true, rest),
+            None => (false, iri.strip_prefix(SYSML_METAMODEL_PREFIX)?),
+        };
+        Some((kerml, rest.split('/').next()?))
+    }
+    match (release(iri), release(metamodel)) {
+        (Some((lib_kerml, lib_release)), Some((kerml, release))) => {
+            lib_release == release && (lib_kerml || !kerml)
+        }
+        _ => false,
+    }
+}
+
+/// Usages of the known standard libraries that define any of `names` as
+/// top-level symbols, e.g. the Quantities and Units Domain Library for
+/// `ISQ` or `SI`. The version constraint is the version of the library.
+/// Sorted by IRI
+pub fn std_usages_for_names<'a>(
+    names: impl IntoIterator<Item = &'a str>,
+) -> Vec<InterchangeProjectUsageRaw> {
+    let mut providers: HashMap<String, (String, String)> = HashMap::new();
+    for (iri, projects) in known_std_libs() {
+        for project in projects {
+            let (Some(info), Some(meta)) = (project.info, project.meta) else {
+                continue;
+            };
+            for symbol in meta.index.into_keys() {
+                providers.insert(symbol, (iri.clone(), info.version.clone()));
+            }
+        }
+    }
+
+    let usages: BTreeMap<String, String> = names
+        .into_iter()
+        .filter_map(|name| providers.get(name).cloned())
+        .collect();
+    usages
+        .into_iter()
+        .map(|(resource, version)| InterchangeProjectUsageRaw::Resource {
+            resource,
+            version_constraint: Some(version),
+            when: None,
+            hash: None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "./stdlib_tests.rs"]
+mod tests;
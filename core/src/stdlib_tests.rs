@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use crate::{
+    model::InterchangeProjectUsageRaw,
+    stdlib::{IncludeStd, known_std_libs, std_lib_matches_metamodel, std_usages_for_names},
+};
+
+#[test]
+fn std_usages_for_std_symbols() {
+    let usages = std_usages_for_names(["SI", "ISQ", "ScalarValues", "Vehicles"]);
+    assert_eq!(
+        usages,
+        [
+            InterchangeProjectUsageRaw::Resource {
+                resource: "https://www.omg.org/spec/KerML/20250201/Data-Type-Library.kpar"
+                    .into(),
+                version_constraint: Some("1.0.0".into()),
+                when: None,
+                hash: None,
+            },
+            InterchangeProjectUsageRaw::Resource {
+                resource:
+                    "https://www.omg.org/spec/SysML/20250201/Quantities-and-Units-Domain-Library.kpar"
+                        .into(),
+                version_constraint: Some("2.0.0".into()),
+                when: None,
+                hash: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn std_usages_for_unknown_symbols() {
+    assert!(std_usages_for_names(["Vehicles", "isq"]).is_empty());
+}
+
+#[test]
+fn std_libs_match_metamodel_release() {
+    let kerml = "https://www.omg.org/spec/KerML/20250201/Data-Type-Library.kpar";
+    let sysml = "https://www.omg.org/spec/SysML/20250201/Systems-Library.kpar";
+    let sysml_metamodel = "https://www.omg.org/spec/SysML/20250201";
+    let kerml_metamodel = "https://www.omg.org/spec/KerML/20250201";
+
+    assert!(std_lib_matches_metamodel(kerml, sysml_metamodel));
+    assert!(std_lib_matches_metamodel(sysml, sysml_metamodel));
+    assert!(std_lib_matches_metamodel(kerml, kerml_metamodel));
+    assert!(!std_lib_matches_metamodel(sysml, kerml_metamodel));
+    assert!(!std_lib_matches_metamodel(
+        sysml,
+        "https://www.omg.org/spec/SysML/20240201"
+    ));
+    assert!(!std_lib_matches_metamodel(
+        sysml,
+        "https://example.com/SysML"
+    ));
+}
+
+#[test]
+fn ignored_std_libs_by_mode() {
+    let kerml_metamodel = Some("https://www.omg.org/spec/KerML/20250201");
+    assert!(IncludeStd::Always.ignored_std_libs(None).is_empty());
+    assert_eq!(
+        IncludeStd::Never.ignored_std_libs(kerml_metamodel).len(),
+        known_std_libs().len()
+    );
+    assert_eq!(
+        IncludeStd::Auto.ignored_std_libs(None).len(),
+        known_std_libs().len()
+    );
+
+    let ignored = IncludeStd::Auto.ignored_std_libs(kerml_metamodel);
+    assert!(!ignored.is_empty());
+    assert!(ignored.keys().all(|iri| iri.contains("/SysML/")));
+}
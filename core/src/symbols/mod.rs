@@ -8,8 +8,13 @@
 //! field `index` of `.meta.json` files.
 
 mod lex;
+#[cfg(feature = "filesystem")]
+pub(crate) mod top_level;
 
-use std::{collections::HashMap, iter::Peekable};
+use std::{
+    collections::{BTreeSet, HashMap},
+    iter::Peekable,
+};
 
 use logos::{Logos, Source};
 use thiserror::Error;
@@ -597,6 +602,57 @@ pub fn top_level_kerml<S: AsRef<str>>(source: S) -> Result<Vec<String>, ExtractE
     collect_symbols(source, all, &keywords)
 }
 
+/// Names that `source` refers to as the first segment of a qualified name,
+/// e.g. `Vehicles` in `import Vehicles::*;` or `part car : Vehicles::Car;`,
+/// or imports directly, e.g. `Vehicles` in `import Vehicles;`. These include
+/// the top-level symbols of other projects that `source` depends on.
+pub fn referenced_names<S: AsRef<str>>(source: S) -> Result<BTreeSet<String>, ExtractError> {
+    let source = source.as_ref();
+    let mut lexer = Token::lexer(source);
+
+    let mut names = BTreeSet::new();
+    // Name not preceded by `::` or `.`, which is the first segment of a
+    // qualified name if followed by `::`
+    let mut first_segment: Option<String> = None;
+    let mut after_separator = false;
+    let mut in_import = false;
+    while let Some(token) = lexer.next() {
+        let token = match token {
+            Ok(token) => token,
+            Err(e) => {
+                let (line, byte) = line_byte(source, lexer.span());
+                return Err(ExtractError::Syntax(line, byte, e));
+            }
+        };
+        match token {
+            Token::Space | Token::BlockComment | Token::LineComment => continue,
+            Token::DoubleColon => {
+                if let Some(name) = first_segment.take() {
+                    names.insert(name);
+                }
+            }
+            Token::OtherIdentifier | Token::Quoted => {
+                let original = lexer.slice();
+                if token == Token::OtherIdentifier && original == "import" {
+                    in_import = true;
+                }
+                first_segment = (!after_separator).then(|| original.trim_matches('\'').to_string());
+            }
+            Token::Semicolon | Token::BraceOpen | Token::BraceClose => {
+                if in_import && let Some(name) = first_segment.take() {
+                    names.insert(name);
+                }
+                in_import = false;
+                first_segment = None;
+            }
+            _ => first_segment = None,
+        }
+        after_separator = matches!(token, Token::DoubleColon | Token::Period);
+    }
+
+    Ok(names)
+}
+
 // Returns: (line, byte), both 1-indexed
 fn line_byte(source: &str, span: logos::Span) -> (u32, u32) {
     let range = &source.as_bytes()[..span.start];
@@ -83,3 +83,18 @@ fn lex_line_comment() -> Result<(), Box<dyn std::error::Error>> {
     }
     Ok(())
 }
+
+#[test]
+fn referenced_names_of_qualified_names_and_imports() {
+    let src = "package P {\n\
+        private import Vehicles::*;\n\
+        import all 'Quoted Lib'::Parts::**;\n\
+        import Units;\n\
+        // import Commented::*;\n\
+        part car : Vehicles::Car :> Base::Part;\n\
+        attribute x = a.b::c;\n\
+        part def Local;\n\
+    }";
+    let names: Vec<String> = referenced_names(src).unwrap().into_iter().collect();
+    assert_eq!(names, ["Base", "Quoted Lib", "Units", "Vehicles"]);
+}
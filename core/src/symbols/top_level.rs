@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Streaming reduction of a source file to the text that can contain its
+//! top-level symbols, so that symbols of very large files can be extracted
+//! without reading the whole file into memory.
+//!
+//! The bodies of top-level elements (everything between the outermost
+//! braces) are dropped, which does not change the symbols found by
+//! [`top_level_sysml`](super::top_level_sysml) and
+//! [`top_level_kerml`](super::top_level_kerml). Comments, strings and
+//! quoted names are recognized the same way as the lexer does, so that
+//! braces inside them are not counted.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum State {
+    Code,
+    /// After `/`
+    Slash,
+    /// After `//`
+    SlashSlash,
+    LineComment,
+    /// `prev` and `prev2` are the last two bytes of the comment body
+    BlockComment {
+        prev: u8,
+        prev2: u8,
+    },
+    /// `prev` is the last byte of the string body
+    String {
+        prev: u8,
+    },
+    /// `prev` is the last byte of the quoted name body
+    Quoted {
+        prev: u8,
+    },
+}
+
+/// Top-level text of a source file fed to it in chunks
+#[derive(Debug)]
+pub(crate) struct TopLevelText {
+    text: Vec<u8>,
+    state: State,
+    depth: u32,
+    /// Braces are not balanced, so the reduced text may not have the same
+    /// symbols as the file
+    unbalanced: bool,
+    /// Last bytes of an incomplete UTF-8 sequence at the end of a chunk
+    utf8_tail: Vec<u8>,
+    invalid_utf8: bool,
+}
+
+impl Default for TopLevelText {
+    fn default() -> Self {
+        Self {
+            text: vec![],
+            state: State::Code,
+            depth: 0,
+            unbalanced: false,
+            utf8_tail: vec![],
+            invalid_utf8: false,
+        }
+    }
+}
+
+impl TopLevelText {
+    pub fn feed(&mut self, chunk: &[u8]) {
+        self.check_utf8(chunk);
+        for &byte in chunk {
+            self.push(byte);
+        }
+    }
+
+    /// Top-level text of the file, or `None` if it cannot be reduced
+    /// reliably, i.e. it is not valid UTF-8, braces are unbalanced or a
+    /// comment, string or quoted name is unterminated. The whole file has to
+    /// be read in that case, also to report the error
+    pub fn finish(self) -> Option<String> {
+        let terminated = matches!(
+            self.state,
+            State::Code | State::Slash | State::SlashSlash | State::LineComment
+        );
+        if self.invalid_utf8
+            || !self.utf8_tail.is_empty()
+            || self.unbalanced
+            || self.depth != 0
+            || !terminated
+        {
+            return None;
+        }
+        String::from_utf8(self.text).ok()
+    }
+
+    fn check_utf8(&mut self, chunk: &[u8]) {
+        if self.invalid_utf8 {
+            return;
+        }
+        let joined;
+        let bytes = if self.utf8_tail.is_empty() {
+            chunk
+        } else {
+            let mut tail = std::mem::take(&mut self.utf8_tail);
+            tail.extend_from_slice(chunk);
+            joined = tail;
+            &joined
+        };
+        if let Err(e) = std::str::from_utf8(bytes) {
+            match e.error_len() {
+                // Sequence may continue in the next chunk
+                None => self.utf8_tail = bytes[e.valid_up_to()..].to_vec(),
+                Some(_) => self.invalid_utf8 = true,
+            }
+        }
+    }
+
+    fn push(&mut self, byte: u8) {
+        let top_level = self.depth == 0;
+        self.state = match self.state {
+            State::Code => self.code(byte),
+            State::Slash => match byte {
+                b'*' => State::BlockComment { prev: 0, prev2: 0 },
+                b'/' => State::SlashSlash,
+                _ => self.code(byte),
+            },
+            State::SlashSlash => match byte {
+                b'*' => State::BlockComment { prev: 0, prev2: 0 },
+                b'\n' => State::Code,
+                _ => State::LineComment,
+            },
+            State::LineComment => match byte {
+                b'\n' => State::Code,
+                _ => State::LineComment,
+            },
+            State::BlockComment { prev, prev2 } => {
+                if byte == b'/' && prev == b'*' && prev2 != b'\\' {
+                    State::Code
+                } else {
+                    State::BlockComment {
+                        prev: byte,
+                        prev2: prev,
+                    }
+                }
+            }
+            State::String { prev } => {
+                if byte == b'"' && prev != b'\\' {
+                    State::Code
+                } else {
+                    State::String { prev: byte }
+                }
+            }
+            State::Quoted { prev } => {
+                if byte == b'\'' && prev != b'\\' {
+                    State::Code
+                } else {
+                    State::Quoted { prev: byte }
+                }
+            }
+        };
+        // Keep the braces enclosing top-level bodies
+        if top_level || self.depth == 0 {
+            self.text.push(byte);
+        }
+    }
+
+    fn code(&mut self, byte: u8) -> State {
+        match byte {
+            b'/' => return State::Slash,
+            b'"' => return State::String { prev: 0 },
+            b'\'' => return State::Quoted { prev: 0 },
+            b'{' => self.depth += 1,
+            b'}' => match self.depth.checked_sub(1) {
+                Some(depth) => self.depth = depth,
+                None => self.unbalanced = true,
+            },
+            _ => (),
+        }
+        State::Code
+    }
+}
+
+#[cfg(test)]
+#[path = "./top_level_tests.rs"]
+mod tests;
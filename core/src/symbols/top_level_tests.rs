@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use super::TopLevelText;
+use crate::symbols::{top_level_kerml, top_level_sysml};
+
+fn reduce(source: &str, chunk_size: usize) -> Option<String> {
+    let mut text = TopLevelText::default();
+    for chunk in source.as_bytes().chunks(chunk_size) {
+        text.feed(chunk);
+    }
+    text.finish()
+}
+
+const SOURCES: &[&str] = &[
+    "package P;",
+    "package P { part def A; package Inner { part def B; } }",
+    "package 'Quoted {' { part def '}'; }\npackage Q;",
+    "/* { */ package P { doc /* } \\*/ } */ part x; }\nlibrary package L;",
+    "// {\npackage P { // }\n}\nstandard library package S { }",
+    "//* { */ package P { attribute s = \"}\\\"{\"; }",
+    "package <'ß'> 'Ünïcödé' { part def 'Ω' { } }",
+    "public package P { }\nprivate package Q { part p { } }\n",
+];
+
+#[test]
+fn reduced_text_has_the_same_symbols() {
+    for source in SOURCES {
+        for chunk_size in [1, 2, 3, 7, 4096] {
+            let reduced =
+                reduce(source, chunk_size).unwrap_or_else(|| panic!("failed to reduce {source:?}"));
+            assert_eq!(
+                top_level_sysml(&reduced).unwrap(),
+                top_level_sysml(source).unwrap(),
+                "source: {source:?}, reduced: {reduced:?}"
+            );
+            assert_eq!(
+                top_level_kerml(&reduced).unwrap(),
+                top_level_kerml(source).unwrap(),
+                "source: {source:?}, reduced: {reduced:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn bodies_are_dropped() {
+    assert_eq!(
+        reduce("package P { part def A { } }\npackage Q;", 5).unwrap(),
+        "package P {}\npackage Q;"
+    );
+}
+
+#[test]
+fn unreliable_text_is_not_reduced() {
+    for source in [
+        "package P {",
+        "package P; }",
+        "} package P {",
+        "package P { /* }",
+        "package P { attribute s = \"; }",
+        "package 'P { }",
+    ] {
+        assert_eq!(reduce(source, 3), None, "source: {source:?}");
+    }
+
+    let mut text = TopLevelText::default();
+    text.feed(b"package P { \xff }");
+    assert_eq!(text.finish(), None);
+
+    // Incomplete UTF-8 sequence at the end of the file
+    let mut text = TopLevelText::default();
+    text.feed("package P; // ß".as_bytes().split_last().unwrap().1);
+    assert_eq!(text.finish(), None);
+}
@@ -0,0 +1,169 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Opt-in statistics of where commands spend their time and what they
+//! download, summarised by `sysand --timings`. Nothing is recorded unless
+//! [`enable`] was called.
+
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use serde::{Serialize, Serializer};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static STATS: Mutex<Option<(Instant, Timings)>> = Mutex::new(None);
+
+/// Step of a command pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    Resolution,
+    /// Time until response headers arrive, and the download of KPAR bodies
+    Download,
+    Hashing,
+    Install,
+    /// Writing KPAR archives
+    Zip,
+}
+
+impl Phase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Phase::Resolution => "resolution",
+            Phase::Download => "download",
+            Phase::Hashing => "hashing",
+            Phase::Install => "install",
+            Phase::Zip => "zip",
+        }
+    }
+}
+
+/// Statistics collected since [`enable`] was called
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct Timings {
+    #[serde(rename = "total_seconds", serialize_with = "as_secs")]
+    pub total: Duration,
+    /// Wall time spent in each phase. Phases overlap, e.g. downloads
+    /// happen during resolution
+    pub phases: BTreeMap<Phase, PhaseTimings>,
+    /// Requests sent and bytes received per host. Bodies without a known
+    /// length are not counted
+    pub downloads: BTreeMap<String, HostDownloads>,
+    /// Hits and misses of each cache, e.g. `http` and `git`
+    pub caches: BTreeMap<&'static str, CacheStats>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct PhaseTimings {
+    pub count: u64,
+    #[serde(rename = "seconds", serialize_with = "as_secs")]
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct HostDownloads {
+    pub requests: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// Fraction of lookups that were hits, if there were any
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+}
+
+fn as_secs<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+/// Start recording statistics
+pub fn enable() {
+    *STATS.lock().unwrap() = Some((Instant::now(), Timings::default()));
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Statistics recorded so far, if recording is enabled
+pub fn snapshot() -> Option<Timings> {
+    STATS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|(start, timings)| Timings {
+            total: start.elapsed(),
+            ..timings.clone()
+        })
+}
+
+fn update(f: impl FnOnce(&mut Timings)) {
+    if !is_enabled() {
+        return;
+    }
+    if let Some((_, timings)) = STATS.lock().unwrap().as_mut() {
+        f(timings);
+    }
+}
+
+/// Records the time until it is dropped in its phase
+#[must_use]
+pub struct PhaseTimer(Option<(Phase, Instant)>);
+
+/// Time `phase` until the returned timer is dropped
+pub fn time(phase: Phase) -> PhaseTimer {
+    PhaseTimer(is_enabled().then(|| (phase, Instant::now())))
+}
+
+impl Drop for PhaseTimer {
+    fn drop(&mut self) {
+        if let Some((phase, start)) = self.0.take() {
+            let elapsed = start.elapsed();
+            update(|timings| {
+                let entry = timings.phases.entry(phase).or_default();
+                entry.count += 1;
+                entry.elapsed += elapsed;
+            });
+        }
+    }
+}
+
+/// Record a request to `host` with a response body of `bytes`
+pub fn record_download(host: &str, bytes: Option<u64>) {
+    update(|timings| {
+        let entry = timings.downloads.entry(host.to_owned()).or_default();
+        entry.requests += 1;
+        entry.bytes += bytes.unwrap_or(0);
+    });
+}
+
+/// Record a lookup in `cache`
+pub fn record_cache(cache: &'static str, hit: bool) {
+    update(|timings| {
+        let entry = timings.caches.entry(cache).or_default();
+        if hit {
+            entry.hits += 1;
+        } else {
+            entry.misses += 1;
+        }
+    });
+}
+
+#[cfg(test)]
+#[path = "./timings_tests.rs"]
+mod tests;
@@ -0,0 +1,39 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use crate::timings::{self, CacheStats, HostDownloads, Phase};
+
+#[test]
+fn records_only_when_enabled() {
+    // Other tests run in the same process, so only look at entries
+    // recorded here
+    timings::record_download("before.timings.test", Some(1));
+    timings::enable();
+    {
+        let _timer = timings::time(Phase::Zip);
+    }
+    timings::record_download("timings.test", Some(10));
+    timings::record_download("timings.test", None);
+    timings::record_cache("timings-test", true);
+    timings::record_cache("timings-test", false);
+    timings::record_cache("timings-test", true);
+
+    let timings = timings::snapshot().unwrap();
+    assert!(!timings.downloads.contains_key("before.timings.test"));
+    assert_eq!(
+        timings.downloads["timings.test"],
+        HostDownloads {
+            requests: 2,
+            bytes: 10
+        }
+    );
+    let cache = &timings.caches["timings-test"];
+    assert_eq!(cache, &CacheStats { hits: 2, misses: 1 });
+    assert_eq!(cache.hit_rate(), Some(2.0 / 3.0));
+    assert!(timings.phases[&Phase::Zip].count >= 1);
+
+    let json = serde_json::to_value(&timings).unwrap();
+    assert!(json["total_seconds"].is_f64());
+    assert!(json["phases"]["zip"]["seconds"].is_f64());
+    assert_eq!(json["downloads"]["timings.test"]["bytes"], 10);
+}
@@ -5,10 +5,12 @@ use std::{error::Error, fmt::Write as _};
 
 use digest::{array::Array, typenum};
 use indexmap::IndexSet;
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
 use thiserror::Error;
 use typed_path::{Utf8UnixPath, Utf8WindowsPath};
 
+use crate::model::HashAlg;
+
 pub(crate) mod scheme {
     use fluent_uri::component::Scheme;
     #[cfg(feature = "filesystem")]
@@ -59,6 +61,64 @@ pub fn sha256_lowercase_hex(data: impl AsRef<[u8]>) -> String {
     lowercase_hex(Sha256::digest(data))
 }
 
+/// Hash `data` with `algorithm`, producing a lowercase hex digest
+pub fn hash_lowercase_hex(algorithm: HashAlg, data: impl AsRef<[u8]>) -> String {
+    match algorithm {
+        HashAlg::Sha224 => hex::encode(Sha224::digest(data)),
+        HashAlg::Sha256 => sha256_lowercase_hex(data),
+        HashAlg::Sha384 => hex::encode(Sha384::digest(data)),
+        HashAlg::Sha512 => hex::encode(Sha512::digest(data)),
+        HashAlg::Blake3 => hex::encode(blake3::Hasher::digest(data)),
+    }
+}
+
+/// Incremental version of [`hash_lowercase_hex`], for data that is not
+/// available all at once
+#[cfg(feature = "filesystem")]
+pub(crate) enum StreamHasher {
+    Sha224(Sha224),
+    Sha256(Sha256),
+    Sha384(Sha384),
+    Sha512(Sha512),
+    Blake3(Box<blake3::Hasher>),
+}
+
+#[cfg(feature = "filesystem")]
+impl StreamHasher {
+    pub fn new(algorithm: HashAlg) -> Self {
+        match algorithm {
+            HashAlg::Sha224 => Self::Sha224(Sha224::new()),
+            HashAlg::Sha256 => Self::Sha256(Sha256::new()),
+            HashAlg::Sha384 => Self::Sha384(Sha384::new()),
+            HashAlg::Sha512 => Self::Sha512(Sha512::new()),
+            HashAlg::Blake3 => Self::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Sha224(h) => Digest::update(h, data),
+            Self::Sha256(h) => Digest::update(h, data),
+            Self::Sha384(h) => Digest::update(h, data),
+            Self::Sha512(h) => Digest::update(h, data),
+            Self::Blake3(h) => {
+                h.update(data);
+            }
+        }
+    }
+
+    /// Lowercase hex digest of all the data passed to [`Self::update`]
+    pub fn finalize_hex(self) -> String {
+        match self {
+            Self::Sha224(h) => hex::encode(h.finalize()),
+            Self::Sha256(h) => lowercase_hex(h.finalize()),
+            Self::Sha384(h) => hex::encode(h.finalize()),
+            Self::Sha512(h) => hex::encode(h.finalize()),
+            Self::Blake3(h) => hex::encode(Digest::finalize(*h)),
+        }
+    }
+}
+
 /// Encode `bytes` as lowercase hex string
 pub fn lowercase_hex(bytes: Array<u8, typenum::U32>) -> String {
     hex::encode(bytes)
@@ -0,0 +1,269 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Virtual filesystem abstraction.
+//!
+//! [`Vfs`] is the minimal set of file operations needed to store projects
+//! and environments. It allows [`VfsProject`](crate::project::vfs::VfsProject)
+//! and (with the `filesystem` feature) `VfsDirectoryEnvironment` to operate
+//! on the local filesystem ([`StdFs`]), in memory ([`MemoryFs`]), or on any
+//! other storage, e.g. browser local storage.
+//!
+//! Paths are Unix-style and interpreted relative to the root of the
+//! filesystem; a leading `/` is ignored. Directories are implicit: writing a
+//! file creates its parent directories.
+
+use std::{
+    collections::BTreeMap,
+    convert::Infallible,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use typed_path::{Utf8UnixComponent, Utf8UnixPath, Utf8UnixPathBuf};
+
+use crate::env::utils::ErrorBound;
+
+pub trait Vfs: Clone + Debug {
+    type Error: ErrorBound;
+
+    /// Whether a file exists at `path`
+    fn is_file<P: AsRef<Utf8UnixPath>>(&self, path: P) -> Result<bool, Self::Error>;
+
+    /// Read the file at `path`. Returns `Ok(None)` if it does not exist.
+    fn read<P: AsRef<Utf8UnixPath>>(&self, path: P) -> Result<Option<Vec<u8>>, Self::Error>;
+
+    /// Write `contents` to the file at `path`, replacing it if it exists
+    fn write<P: AsRef<Utf8UnixPath>, C: AsRef<[u8]>>(
+        &self,
+        path: P,
+        contents: C,
+    ) -> Result<(), Self::Error>;
+
+    /// Remove the file at `path`. Does nothing if it does not exist.
+    fn remove_file<P: AsRef<Utf8UnixPath>>(&self, path: P) -> Result<(), Self::Error>;
+
+    /// Remove the directory at `path` with all its contents. Does nothing
+    /// if it does not exist.
+    fn remove_dir_all<P: AsRef<Utf8UnixPath>>(&self, path: P) -> Result<(), Self::Error>;
+
+    /// Paths of all files under the directory `path` (recursively),
+    /// relative to it and sorted
+    fn list_files<P: AsRef<Utf8UnixPath>>(
+        &self,
+        path: P,
+    ) -> Result<Vec<Utf8UnixPathBuf>, Self::Error>;
+
+    /// Read the file at `path` as UTF-8. Returns `Ok(None)` if it does not
+    /// exist. Invalid UTF-8 is replaced.
+    fn read_to_string<P: AsRef<Utf8UnixPath>>(
+        &self,
+        path: P,
+    ) -> Result<Option<String>, Self::Error> {
+        Ok(self
+            .read(path)?
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned()))
+    }
+}
+
+/// Normalize `path` to a sequence of normal components. `..` removes the
+/// previous component and cannot go above the root.
+pub(crate) fn normalize(path: &Utf8UnixPath) -> Utf8UnixPathBuf {
+    let mut result = Utf8UnixPathBuf::new();
+    for component in path.components() {
+        match component {
+            Utf8UnixComponent::RootDir | Utf8UnixComponent::CurDir => {}
+            Utf8UnixComponent::ParentDir => {
+                result.pop();
+            }
+            Utf8UnixComponent::Normal(c) => result.push(c),
+        }
+    }
+    result
+}
+
+/// Filesystem held in memory. Clones share the same contents, so a
+/// `MemoryFs` can be handed to several projects and environments.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryFs {
+    files: Arc<Mutex<BTreeMap<Utf8UnixPathBuf, Vec<u8>>>>,
+}
+
+impl MemoryFs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn files(&self) -> std::sync::MutexGuard<'_, BTreeMap<Utf8UnixPathBuf, Vec<u8>>> {
+        // Poisoning cannot leave the map in an inconsistent state
+        self.files.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Vfs for MemoryFs {
+    type Error = Infallible;
+
+    fn is_file<P: AsRef<Utf8UnixPath>>(&self, path: P) -> Result<bool, Self::Error> {
+        Ok(self.files().contains_key(&normalize(path.as_ref())))
+    }
+
+    fn read<P: AsRef<Utf8UnixPath>>(&self, path: P) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self.files().get(&normalize(path.as_ref())).cloned())
+    }
+
+    fn write<P: AsRef<Utf8UnixPath>, C: AsRef<[u8]>>(
+        &self,
+        path: P,
+        contents: C,
+    ) -> Result<(), Self::Error> {
+        self.files()
+            .insert(normalize(path.as_ref()), contents.as_ref().to_vec());
+        Ok(())
+    }
+
+    fn remove_file<P: AsRef<Utf8UnixPath>>(&self, path: P) -> Result<(), Self::Error> {
+        self.files().remove(&normalize(path.as_ref()));
+        Ok(())
+    }
+
+    fn remove_dir_all<P: AsRef<Utf8UnixPath>>(&self, path: P) -> Result<(), Self::Error> {
+        let dir = normalize(path.as_ref());
+        self.files().retain(|p, _| !p.starts_with(&dir));
+        Ok(())
+    }
+
+    fn list_files<P: AsRef<Utf8UnixPath>>(
+        &self,
+        path: P,
+    ) -> Result<Vec<Utf8UnixPathBuf>, Self::Error> {
+        let dir = normalize(path.as_ref());
+        Ok(self
+            .files()
+            .keys()
+            .filter_map(|p| p.strip_prefix(&dir).ok())
+            .filter(|p| !p.as_str().is_empty())
+            .map(Utf8UnixPath::to_path_buf)
+            .collect())
+    }
+}
+
+#[cfg(feature = "filesystem")]
+pub use std_fs::StdFs;
+
+#[cfg(feature = "filesystem")]
+mod std_fs {
+    use std::io::ErrorKind;
+
+    use camino::{Utf8Path, Utf8PathBuf};
+    use typed_path::{Utf8UnixPath, Utf8UnixPathBuf};
+
+    use super::{Vfs, normalize};
+    use crate::project::utils::{FsIoError, wrapfs};
+
+    /// Local filesystem, rooted at a directory
+    #[derive(Clone, Debug)]
+    pub struct StdFs {
+        root: Utf8PathBuf,
+    }
+
+    impl StdFs {
+        pub fn new<P: Into<Utf8PathBuf>>(root: P) -> Self {
+            Self { root: root.into() }
+        }
+
+        pub fn root(&self) -> &Utf8Path {
+            &self.root
+        }
+
+        /// Native path of `path`
+        pub fn native_path<P: AsRef<Utf8UnixPath>>(&self, path: P) -> Utf8PathBuf {
+            let mut result = self.root.clone();
+            for component in normalize(path.as_ref()).iter() {
+                result.push(component);
+            }
+            result
+        }
+    }
+
+    impl Vfs for StdFs {
+        type Error = Box<FsIoError>;
+
+        fn is_file<P: AsRef<Utf8UnixPath>>(&self, path: P) -> Result<bool, Self::Error> {
+            wrapfs::is_file(self.native_path(path))
+        }
+
+        fn read<P: AsRef<Utf8UnixPath>>(&self, path: P) -> Result<Option<Vec<u8>>, Self::Error> {
+            let path = self.native_path(path);
+            match std::fs::read(&path) {
+                Ok(contents) => Ok(Some(contents)),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(FsIoError::ReadFile(path, e).into()),
+            }
+        }
+
+        fn write<P: AsRef<Utf8UnixPath>, C: AsRef<[u8]>>(
+            &self,
+            path: P,
+            contents: C,
+        ) -> Result<(), Self::Error> {
+            let path = self.native_path(path);
+            if let Some(parent) = path.parent() {
+                wrapfs::create_dir_all(parent)?;
+            }
+            wrapfs::write(path, contents)
+        }
+
+        fn remove_file<P: AsRef<Utf8UnixPath>>(&self, path: P) -> Result<(), Self::Error> {
+            let path = self.native_path(path);
+            match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != ErrorKind::NotFound => Err(FsIoError::RmFile(path, e).into()),
+                _ => Ok(()),
+            }
+        }
+
+        fn remove_dir_all<P: AsRef<Utf8UnixPath>>(&self, path: P) -> Result<(), Self::Error> {
+            let path = self.native_path(path);
+            match std::fs::remove_dir_all(&path) {
+                Err(e) if e.kind() != ErrorKind::NotFound => Err(FsIoError::RmDir(path, e).into()),
+                _ => Ok(()),
+            }
+        }
+
+        fn list_files<P: AsRef<Utf8UnixPath>>(
+            &self,
+            path: P,
+        ) -> Result<Vec<Utf8UnixPathBuf>, Self::Error> {
+            fn walk(
+                dir: &Utf8Path,
+                prefix: &Utf8UnixPath,
+                result: &mut Vec<Utf8UnixPathBuf>,
+            ) -> Result<(), Box<FsIoError>> {
+                for entry in wrapfs::read_dir(dir)? {
+                    let entry = entry.map_err(|e| FsIoError::ReadDir(dir.to_owned(), e))?;
+                    let file_type = entry
+                        .file_type()
+                        .map_err(|e| FsIoError::Metadata(entry.path().to_owned(), e))?;
+                    let relative = prefix.join(entry.file_name());
+                    if file_type.is_dir() {
+                        walk(entry.path(), &relative, result)?;
+                    } else {
+                        result.push(relative);
+                    }
+                }
+                Ok(())
+            }
+
+            let dir = self.native_path(path);
+            let mut result = vec![];
+            if wrapfs::is_dir(&dir)? {
+                walk(&dir, Utf8UnixPath::new(""), &mut result)?;
+            }
+            result.sort();
+            Ok(result)
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./vfs_tests.rs"]
+mod tests;
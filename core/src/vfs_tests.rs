@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use typed_path::{Utf8UnixPath, Utf8UnixPathBuf};
+
+use super::{MemoryFs, Vfs, normalize};
+
+#[test]
+fn normalize_paths() {
+    assert_eq!(normalize(Utf8UnixPath::new("/a/./b/../c")), "a/c");
+    assert_eq!(normalize(Utf8UnixPath::new("../a")), "a");
+}
+
+#[test]
+fn memory_fs_roundtrip() {
+    let fs = MemoryFs::new();
+    fs.write("dir/a.sysml", "package A;").unwrap();
+    fs.write("/dir/sub/b.sysml", "package B;").unwrap();
+    fs.write("other.sysml", "package C;").unwrap();
+
+    assert!(fs.is_file("dir/a.sysml").unwrap());
+    assert!(!fs.is_file("dir").unwrap());
+    assert_eq!(
+        fs.read_to_string("./dir/a.sysml").unwrap().as_deref(),
+        Some("package A;")
+    );
+    assert_eq!(fs.read("missing").unwrap(), None);
+    assert_eq!(
+        fs.list_files("dir").unwrap(),
+        vec![
+            Utf8UnixPathBuf::from("a.sysml"),
+            Utf8UnixPathBuf::from("sub/b.sysml")
+        ]
+    );
+
+    // Clones share contents
+    let clone = fs.clone();
+    clone.remove_dir_all("dir").unwrap();
+    assert!(fs.list_files("dir").unwrap().is_empty());
+    assert!(fs.is_file("other.sysml").unwrap());
+
+    fs.remove_file("other.sysml").unwrap();
+    fs.remove_file("other.sysml").unwrap();
+    assert!(fs.list_files("").unwrap().is_empty());
+}
+
+#[cfg(feature = "filesystem")]
+#[test]
+fn std_fs_roundtrip() {
+    use super::StdFs;
+
+    let tmp = camino_tempfile::tempdir().unwrap();
+    let fs = StdFs::new(tmp.path());
+    fs.write("dir/sub/b.sysml", "package B;").unwrap();
+    fs.write("dir/a.sysml", "package A;").unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(tmp.path().join("dir").join("a.sysml")).unwrap(),
+        "package A;"
+    );
+    assert_eq!(fs.read("missing").unwrap(), None);
+    assert_eq!(
+        fs.list_files("dir").unwrap(),
+        vec![
+            Utf8UnixPathBuf::from("a.sysml"),
+            Utf8UnixPathBuf::from("sub/b.sysml")
+        ]
+    );
+
+    fs.remove_dir_all("dir").unwrap();
+    fs.remove_dir_all("dir").unwrap();
+    assert!(!tmp.path().join("dir").exists());
+}
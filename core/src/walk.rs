@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Directory walking shared by commands that scan project directories
+
+use std::{io, path::Path};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use ignore::{DirEntry, Walk, WalkBuilder};
+
+use crate::{env::DEFAULT_ENV_NAME, project::utils::FsIoError};
+
+/// Name of the file with `.gitignore`-style patterns of paths that sysand
+/// does not scan
+pub const SYSANDIGNORE_FILE: &str = ".sysandignore";
+
+/// Name of the directory that `build` writes KPARs to by default
+pub const OUTPUT_DIR: &str = "output";
+
+/// Builder of a walk over `root`, recursive and sorted by file name, skipping:
+/// - paths ignored by `.gitignore`, `.git/info/exclude` and
+///   `.sysandignore` in `root` and its parents, even outside a git
+///   repository
+/// - local environments
+/// - the build output directory directly in `root`
+/// - entries (other than `root`) for which `filter` returns `false`
+///
+/// Hidden files are not skipped. Symbolic links are not followed
+pub fn walk_builder<F>(root: &Utf8Path, filter: F) -> WalkBuilder
+where
+    F: Fn(&DirEntry) -> bool + Send + Sync + 'static,
+{
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .standard_filters(false)
+        .git_ignore(true)
+        .git_exclude(true)
+        .require_git(false)
+        .parents(true)
+        .add_custom_ignore_filename(SYSANDIGNORE_FILE)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(move |e| e.depth() == 0 || (!is_skipped_dir(e) && filter(e)));
+    builder
+}
+
+/// Walk built by [`walk_builder`]
+pub fn walker<F>(root: &Utf8Path, filter: F) -> Walk
+where
+    F: Fn(&DirEntry) -> bool + Send + Sync + 'static,
+{
+    walk_builder(root, filter).build()
+}
+
+fn is_skipped_dir(entry: &DirEntry) -> bool {
+    if !entry.file_type().is_some_and(|t| t.is_dir()) {
+        return false;
+    }
+    let name = entry.file_name();
+    name == DEFAULT_ENV_NAME || (entry.depth() == 1 && name == OUTPUT_DIR)
+}
+
+/// `path` relative to `root`, with `/` separators. `None` (after logging a
+/// warning) if the path is not UTF-8
+pub fn relative_path(root: &Utf8Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let Some(relative) = relative.to_str() else {
+        log::warn!("skipping non-UTF-8 path `{}`", relative.display());
+        return None;
+    };
+    Some(relative.replace(std::path::MAIN_SEPARATOR, "/"))
+}
+
+/// Path (`root` if unknown) and I/O error of a [`walker`] error
+pub fn split_error(root: &Utf8Path, err: ignore::Error) -> (Utf8PathBuf, io::Error) {
+    let path = error_path(&err)
+        .and_then(|p| Utf8PathBuf::from_path_buf(p.to_owned()).ok())
+        .unwrap_or_else(|| root.to_owned());
+    let message = err.to_string();
+    let err = err
+        .into_io_error()
+        .unwrap_or_else(|| io::Error::other(message));
+    (path, err)
+}
+
+/// Converts a [`walker`] error to [`FsIoError::ReadDir`]
+pub fn walk_error(root: &Utf8Path, err: ignore::Error) -> Box<FsIoError> {
+    let (path, err) = split_error(root, err);
+    Box::new(FsIoError::ReadDir(path, err))
+}
+
+fn error_path(err: &ignore::Error) -> Option<&Path> {
+    match err {
+        ignore::Error::WithPath { path, .. } => Some(path),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            error_path(err)
+        }
+        ignore::Error::Partial(errs) => errs.first().and_then(error_path),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+#[path = "./walk_tests.rs"]
+mod tests;
@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use camino_tempfile::tempdir;
+
+use crate::walk::{relative_path, walker};
+
+#[test]
+fn walker_skips_ignored() {
+    let tmp = tempdir().unwrap();
+    let root = tmp.path().join("project");
+    for dir in ["drafts", "gen", ".sysand/lib", "output", "models/output"] {
+        std::fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    std::fs::write(tmp.path().join(".gitignore"), "*.bak\n").unwrap();
+    std::fs::write(root.join(".gitignore"), "gen/\n").unwrap();
+    std::fs::write(root.join(".sysandignore"), "drafts/\n!keep.bak\n").unwrap();
+    for file in [
+        "a.sysml",
+        "a.bak",
+        "keep.bak",
+        "drafts/d.sysml",
+        "gen/g.sysml",
+        ".sysand/lib/x.sysml",
+        "output/p.kpar",
+        "models/output/o.sysml",
+    ] {
+        std::fs::write(root.join(file), "").unwrap();
+    }
+
+    let paths: Vec<String> = walker(&root, |_| true)
+        .map(|e| relative_path(&root, e.unwrap().path()).unwrap())
+        .collect();
+    assert_eq!(
+        paths,
+        [
+            "",
+            ".gitignore",
+            ".sysandignore",
+            "a.sysml",
+            "keep.bak",
+            "models",
+            "models/output",
+            "models/output/o.sysml",
+        ]
+    );
+}
@@ -113,6 +113,18 @@ impl WorkspaceDeserializationError {
     }
 }
 
+#[derive(Error, Debug)]
+pub enum WorkspaceWriteError {
+    #[error("workspace already exists in `{0}`")]
+    AlreadyExists(Utf8PathBuf),
+    #[error(transparent)]
+    Io(#[from] Box<FsIoError>),
+    #[error("failed to serialize `.workspace.json`: {0}")]
+    Serialize(serde_json::Error),
+    #[error("invalid workspace configuration: {0}")]
+    Validation(#[from] WorkspaceValidationError),
+}
+
 #[derive(Debug)]
 pub struct Workspace {
     root_dir: Utf8PathBuf,
@@ -133,6 +145,28 @@ impl Workspace {
         }
     }
 
+    /// Create workspace info file `.workspace.json` in `root_dir`, which
+    /// must not contain one yet
+    pub fn create(
+        root_dir: Utf8PathBuf,
+        info: WorkspaceInfoRaw,
+    ) -> Result<Self, WorkspaceWriteError> {
+        let info_path = root_dir.join(".workspace.json");
+        if wrapfs::is_file(&info_path)? {
+            return Err(WorkspaceWriteError::AlreadyExists(root_dir));
+        }
+        let validated = WorkspaceInfo::try_from(info.clone())?;
+        let mut contents =
+            serde_json::to_string_pretty(&info).map_err(WorkspaceWriteError::Serialize)?;
+        contents.push('\n');
+        wrapfs::create_dir_all(&root_dir)?;
+        wrapfs::write(&info_path, contents)?;
+        Ok(Self {
+            root_dir,
+            info: validated,
+        })
+    }
+
     pub fn root_path(&self) -> &Utf8Path {
         &self.root_dir
     }
@@ -49,3 +49,29 @@ fn deserialize_invalid_metamodel_iri() {
     let err = result.unwrap_err();
     assert_matches!(err, WorkspaceValidationError::InvalidIri(..));
 }
+
+#[test]
+fn create_writes_info_once() {
+    let root = camino_tempfile::tempdir().unwrap();
+    let root_dir = root.path().join("ws");
+    let info = WorkspaceInfoRaw {
+        projects: vec![WorkspaceProjectInfoRaw {
+            path: "core".into(),
+            iris: vec!["pkg:sysand/acme/core".into()],
+        }],
+        meta: None,
+    };
+
+    let created = Workspace::create(root_dir.clone(), info.clone()).unwrap();
+    assert_eq!(
+        created.projects()[0].iris[0].as_str(),
+        "pkg:sysand/acme/core"
+    );
+    let read = Workspace::new(root_dir.clone()).unwrap();
+    assert_eq!(read.projects(), created.projects());
+
+    assert_matches!(
+        Workspace::create(root_dir, info),
+        Err(WorkspaceWriteError::AlreadyExists(_))
+    );
+}
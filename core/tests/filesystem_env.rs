@@ -15,7 +15,7 @@ mod filesystem_tests {
     use sysand_core::{
         commands::env::do_env_local_dir,
         env::{
-            DEFAULT_ENV_NAME, ReadEnvironment, WriteEnvironment,
+            DEFAULT_ENV_NAME, OverwritePolicy, ReadEnvironment, WriteEnvironment,
             local_directory::LocalDirectoryEnvironment, utils::clone_project,
         },
         info::do_info,
@@ -118,6 +118,7 @@ version = \"0.1\"
                 includes_derived: None,
                 includes_implied: None,
                 checksum: None,
+                variants: Default::default(),
             };
 
             let mut source_project = InMemoryProject::default();
@@ -129,9 +130,13 @@ version = \"0.1\"
             )?;
             let checksum = source_project.checksum_canonical_variant()?;
 
-            env.put_project(uri, version_str, Some(checksum), |p| {
-                clone_project(&source_project, p, true).map(|_| ())
-            })?;
+            env.put_project(
+                uri,
+                version_str,
+                Some(checksum),
+                OverwritePolicy::Replace,
+                |p| clone_project(&source_project, p, true).map(|_| ()),
+            )?;
         }
 
         Ok(env)
@@ -206,6 +211,7 @@ version = \"0.1\"
                 includes_derived: None,
                 includes_implied: None,
                 checksum: None,
+                variants: Default::default(),
             };
             let mut other_project = InMemoryProject::default();
             other_project.put_project(&info, &meta, true)?;
@@ -215,9 +221,13 @@ version = \"0.1\"
                 true,
             )?;
             let checksum = other_project.checksum_canonical_variant()?;
-            env.put_project(other_uri, "1.0.0", Some(checksum), |p| {
-                clone_project(&other_project, p, true).map(|_| ())
-            })?;
+            env.put_project(
+                other_uri,
+                "1.0.0",
+                Some(checksum),
+                OverwritePolicy::Replace,
+                |p| clone_project(&other_project, p, true).map(|_| ()),
+            )?;
         }
 
         env.del_uri(uri)?;
@@ -275,6 +285,7 @@ version = \"0.1\"
             includes_derived: None,
             includes_implied: None,
             checksum: None,
+            variants: Default::default(),
         };
 
         let mut source_project = InMemoryProject::default();
@@ -287,9 +298,13 @@ version = \"0.1\"
         source_project.write_source(source_path, &mut Cursor::new(source_code), true)?;
         let checksum = source_project.checksum_canonical_variant()?;
 
-        directory_environment.put_project("urn:sysand_test:1", "1.2.3", Some(checksum), |p| {
-            clone_project(&source_project, p, true).map(|_| ())
-        })?;
+        directory_environment.put_project(
+            "urn:sysand_test:1",
+            "1.2.3",
+            Some(checksum),
+            OverwritePolicy::Replace,
+            |p| clone_project(&source_project, p, true).map(|_| ()),
+        )?;
 
         let target_project = directory_environment.get_project("urn:sysand_test:1", "1.2.3")?;
         let (read_info, read_meta) = target_project.get_project()?;
@@ -12,7 +12,7 @@ use semver::Version;
 use sysand_core::{
     commands::env::do_env_memory,
     env::{
-        ReadEnvironment, WriteEnvironment,
+        OverwritePolicy, ReadEnvironment, WriteEnvironment,
         utils::{CloneError, clone_project},
     },
     info::do_info,
@@ -64,6 +64,7 @@ fn env_manual_install() -> Result<(), Box<dyn std::error::Error>> {
         includes_derived: None,
         includes_implied: None,
         checksum: None,
+        variants: IndexMap::new(),
     }
     .into();
 
@@ -76,11 +77,17 @@ fn env_manual_install() -> Result<(), Box<dyn std::error::Error>> {
 
     source_project.write_source(source_path, &mut Cursor::new(source_code), true)?;
 
-    memory_environment.put_project("urn:sysand_test:1", "1.2.3", None, |p| {
-        clone_project(&source_project, p, true)?;
-
-        Ok::<(), CloneError<InMemoryError, InMemoryError>>(())
-    })?;
+    memory_environment.put_project(
+        "urn:sysand_test:1",
+        "1.2.3",
+        None,
+        OverwritePolicy::Replace,
+        |p| {
+            clone_project(&source_project, p, true)?;
+
+            Ok::<(), CloneError<InMemoryError, InMemoryError>>(())
+        },
+    )?;
 
     let target_project = memory_environment.get_project("urn:sysand_test:1", "1.2.3")?;
 
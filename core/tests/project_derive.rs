@@ -6,16 +6,16 @@ use std::{
     io::{Cursor, Read},
 };
 
+use futures::AsyncReadExt as _;
 use sysand_core::{
+    context::ProjectContext,
+    lock::Source,
     model::{InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw},
     project::{
-        CanonicalizationError, ProjectChecksum, ProjectMut, ProjectRead, memory::InMemoryProject,
+        AsAsyncProject, CanonicalizationError, ProjectChecksum, ProjectMut, ProjectRead,
+        ProjectReadAsync, memory::InMemoryProject,
     },
 };
-
-// Have to have these in scope for ProjectRead
-// TODO: Find a better solution (that works both inside and outside sysand_core)
-use sysand_core::{context::ProjectContext, lock::Source};
 use typed_path::Utf8UnixPath;
 
 #[derive(ProjectRead)]
@@ -73,6 +73,7 @@ fn macro_get_project() {
         includes_derived: None,
         includes_implied: None,
         checksum: None,
+        variants: indexmap::IndexMap::new(),
     };
     let test_double = OneVariantProjectRead::Variant(InMemoryProject {
         info: Some(info.clone()),
@@ -147,6 +148,7 @@ fn macro_put_meta() {
         includes_derived: None,
         includes_implied: None,
         checksum: None,
+        variants: indexmap::IndexMap::new(),
     };
     let mut project = OneVariantProjectMut::Variant(InMemoryProject::new());
 
@@ -276,3 +278,61 @@ enum GenericProjectMut<SomeProject: ProjectRead + ProjectMut> {
 fn macro_generic_mut() {
     let _project = GenericProjectMut::<InMemoryProject>::Variant(InMemoryProject::new());
 }
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[derive(ProjectReadAsync)]
+enum TwoVariantProjectReadAsync {
+    First(Box<AsAsyncProject<InMemoryProject>>),
+    Second(AsAsyncProject<FixedDigestProject>),
+}
+
+#[test]
+fn macro_async_read_source() {
+    let mut files = HashMap::new();
+    files.insert("path".into(), "file content".to_string());
+    let project = TwoVariantProjectReadAsync::First(Box::new(
+        InMemoryProject {
+            info: None,
+            meta: None,
+            files,
+            nominal_sources: vec![],
+        }
+        .to_async(),
+    ));
+
+    let mut buffer = String::new();
+    block_on(async {
+        project
+            .read_source_async("path")
+            .await
+            .unwrap()
+            .read_to_string(&mut buffer)
+            .await
+            .unwrap();
+    });
+
+    assert_eq!(buffer, "file content");
+    assert!(matches!(
+        block_on(project.read_source_async("missing")),
+        Err(TwoVariantProjectReadAsyncError::First(_))
+    ));
+}
+
+#[test]
+fn derive_async_forwards_checksum_canonical_hex() {
+    let project = TwoVariantProjectReadAsync::Second(
+        FixedDigestProject {
+            digest: "deadbeef".to_string(),
+        }
+        .to_async(),
+    );
+
+    let got = block_on(project.checksum_canonical_hex_async()).unwrap();
+    assert_eq!(got.as_deref(), Some("deadbeef"));
+}
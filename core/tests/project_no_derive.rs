@@ -200,6 +200,23 @@ where
                 .map_err(GenericProjectError::Variant3),
         }
     }
+    fn move_source<P: AsRef<typed_path::Utf8UnixPath>, Q: AsRef<typed_path::Utf8UnixPath>>(
+        &mut self,
+        from: P,
+        to: Q,
+    ) -> Result<(), Self::Error> {
+        match self {
+            GenericProject::Variant1(project) => project
+                .move_source(from, to)
+                .map_err(GenericProjectError::Variant1),
+            GenericProject::Variant2(project) => project
+                .move_source(from, to)
+                .map_err(GenericProjectError::Variant2),
+            GenericProject::Variant3(project) => project
+                .move_source(from, to)
+                .map_err(GenericProjectError::Variant3),
+        }
+    }
 }
 
 #[test]
@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use fluent_uri::Iri;
+use sysand_core::{
+    env::{ReadEnvironment, memory::MemoryStorageEnvironment},
+    init::do_init_memory,
+    project::{ProjectRead, memory::InMemoryProject},
+    resolve::{
+        ResolutionOutcome, ResolveRead,
+        memory::{AcceptAll, MemoryResolver},
+        null::NullResolver,
+    },
+};
+
+#[derive(ResolveRead)]
+enum EitherResolver {
+    Null(NullResolver),
+    Memory(MemoryResolver<AcceptAll, InMemoryProject>),
+}
+
+#[derive(ReadEnvironment)]
+enum EitherEnvironment {
+    First(MemoryStorageEnvironment<InMemoryProject>),
+    Second(MemoryStorageEnvironment<InMemoryProject>),
+}
+
+fn project(name: &str, version: &str) -> InMemoryProject {
+    do_init_memory(name, None::<&str>, version, None).unwrap()
+}
+
+#[test]
+fn derive_resolve_read() {
+    let iri = Iri::parse("urn:kpar:a".to_string()).unwrap();
+    let memory = EitherResolver::Memory(MemoryResolver::from([(
+        iri.clone(),
+        vec![project("a", "1.0.0")],
+    )]));
+
+    let ResolutionOutcome::Resolved(storages) = memory.resolve_read(&iri).unwrap() else {
+        panic!("expected `{iri}` to resolve");
+    };
+    let projects: Vec<_> = storages.collect::<Result<_, _>>().unwrap();
+    assert_eq!(projects.len(), 1);
+    assert!(matches!(projects[0], EitherResolverProject::Memory(_)));
+    assert_eq!(projects[0].name().unwrap().as_deref(), Some("a"));
+
+    let null = EitherResolver::Null(NullResolver {});
+    assert!(!matches!(
+        null.resolve_read(&iri).unwrap(),
+        ResolutionOutcome::Resolved(_)
+    ));
+}
+
+#[test]
+fn derive_read_environment() {
+    let env = EitherEnvironment::Second(MemoryStorageEnvironment::from([
+        (
+            "urn:kpar:a".to_string(),
+            "1.0.0".to_string(),
+            project("a", "1.0.0"),
+        ),
+        (
+            "urn:kpar:a".to_string(),
+            "2.0.0".to_string(),
+            project("a", "2.0.0"),
+        ),
+    ]));
+
+    let uris: Vec<_> = env.uris().unwrap().collect::<Result<_, _>>().unwrap();
+    assert_eq!(uris, ["urn:kpar:a"]);
+    let mut versions: Vec<_> = env
+        .versions("urn:kpar:a")
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    versions.sort();
+    assert_eq!(versions, ["1.0.0", "2.0.0"]);
+    assert!(env.has_version("urn:kpar:a", "2.0.0").unwrap());
+    assert!(!env.has("urn:kpar:b").unwrap());
+
+    let project = env.get_project("urn:kpar:a", "2.0.0").unwrap();
+    assert!(matches!(project, EitherEnvironmentProject::Second(_)));
+    assert_eq!(project.version().unwrap().as_deref(), Some("2.0.0"));
+    assert_eq!(env.candidate_projects("urn:kpar:a").unwrap().len(), 2);
+
+    let empty = EitherEnvironment::First(MemoryStorageEnvironment::new());
+    assert!(!empty.has("urn:kpar:a").unwrap());
+}
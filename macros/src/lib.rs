@@ -3,7 +3,7 @@
 
 use itertools::Itertools;
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{Data, DataEnum, DeriveInput, parse_macro_input};
 
 /// Derives `ProjectRead` for an enum by delegating to its variants and
@@ -79,11 +79,15 @@ use syn::{Data, DataEnum, DeriveInput, parse_macro_input};
 #[proc_macro_derive(ProjectRead)]
 pub fn project_read_derive(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
+    TokenStream::from(project_read_impl(&ast))
+}
 
+/// Expansion of [`ProjectRead`](project_read_derive) for the enum `ast`.
+/// Also used for the project enums synthesized by other derives
+fn project_read_impl(ast: &DeriveInput) -> proc_macro2::TokenStream {
     let Data::Enum(DataEnum { variants, .. }) = &ast.data else {
         return syn::Error::new_spanned(&ast.ident, "ProjectRead can only be derived on an enum")
-            .to_compile_error()
-            .into();
+            .to_compile_error();
     };
 
     let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
@@ -125,7 +129,7 @@ pub fn project_read_derive(input: TokenStream) -> TokenStream {
                 },
                 // error_args
                 quote! {
-                    <#variant_type as ProjectRead>::Error
+                    <#variant_type as ::sysand_core::project::ProjectRead>::Error
                 },
                 // source_reader_variants
                 quote! {
@@ -141,7 +145,7 @@ pub fn project_read_derive(input: TokenStream) -> TokenStream {
                 },
                 // source_reader_args
                 quote! {
-                    <#variant_type as ProjectRead>::SourceReader<'a>
+                    <#variant_type as ::sysand_core::project::ProjectRead>::SourceReader<'a>
                 },
                 // get_project_match
                 quote! {
@@ -206,7 +210,7 @@ pub fn project_read_derive(input: TokenStream) -> TokenStream {
     let variant_parts = match variant_parts {
         Ok(var) => var,
         Err(err) => {
-            return err.to_compile_error().into();
+            return err.to_compile_error();
         }
     };
 
@@ -292,7 +296,7 @@ pub fn project_read_derive(input: TokenStream) -> TokenStream {
             }
         }
 
-        impl #impl_generics ProjectRead for #enum_ident #type_generics #where_clause {
+        impl #impl_generics ::sysand_core::project::ProjectRead for #enum_ident #type_generics #where_clause {
             type Error = #error_ident<
                 #( #error_args ),*
             >;
@@ -301,8 +305,8 @@ pub fn project_read_derive(input: TokenStream) -> TokenStream {
                 &self,
             ) -> ::std::result::Result<
                 (
-                    ::std::option::Option<InterchangeProjectInfoRaw>,
-                    ::std::option::Option<InterchangeProjectMetadataRaw>,
+                    ::std::option::Option<::sysand_core::model::InterchangeProjectInfoRaw>,
+                    ::std::option::Option<::sysand_core::model::InterchangeProjectMetadataRaw>,
                 ),
                 Self::Error,
             > {
@@ -318,7 +322,7 @@ pub fn project_read_derive(input: TokenStream) -> TokenStream {
             where
                 Self: 'a;
 
-            fn read_source<P: ::std::convert::AsRef<Utf8UnixPath>>(
+            fn read_source<P: ::std::convert::AsRef<::sysand_core::project::Utf8UnixPath>>(
                 &self,
                 path: P,
             ) -> ::std::result::Result<Self::SourceReader<'_>, Self::Error> {
@@ -327,7 +331,7 @@ pub fn project_read_derive(input: TokenStream) -> TokenStream {
                 }
             }
 
-            fn sources(&self, ctx: &ProjectContext) -> ::std::result::Result<::std::vec::Vec<Source>, Self::Error> {
+            fn sources(&self, ctx: &::sysand_core::context::ProjectContext) -> ::std::result::Result<::std::vec::Vec<::sysand_core::lock::Source>, Self::Error> {
                 match self {
                     #( #sources_match ),*
                 }
@@ -335,7 +339,7 @@ pub fn project_read_derive(input: TokenStream) -> TokenStream {
 
             fn get_info(
                 &self,
-            ) -> ::std::result::Result<::std::option::Option<InterchangeProjectInfoRaw>, Self::Error> {
+            ) -> ::std::result::Result<::std::option::Option<::sysand_core::model::InterchangeProjectInfoRaw>, Self::Error> {
                 match self {
                     #( #get_info_match ),*
                 }
@@ -343,7 +347,7 @@ pub fn project_read_derive(input: TokenStream) -> TokenStream {
 
             fn get_meta(
                 &self,
-            ) -> ::std::result::Result<::std::option::Option<InterchangeProjectMetadataRaw>, Self::Error> {
+            ) -> ::std::result::Result<::std::option::Option<::sysand_core::model::InterchangeProjectMetadataRaw>, Self::Error> {
                 match self {
                     #( #get_meta_match ),*
                 }
@@ -394,7 +398,7 @@ pub fn project_read_derive(input: TokenStream) -> TokenStream {
         }
     };
 
-    TokenStream::from(expanded)
+    expanded
 }
 
 /// Derives `ProjectMut` for an enum by delegating to its variants.
@@ -422,6 +426,7 @@ pub fn project_read_derive(input: TokenStream) -> TokenStream {
 /// - [`ProjectMut::put_info`] delegates to the active variant.
 /// - [`ProjectMut::put_meta`] delegates to the active variant.
 /// - [`ProjectMut::write_source`] delegates to the active variant.
+/// - [`ProjectMut::move_source`] delegates to the active variant.
 ///
 /// All other methods are handled by the default implementation of the
 /// `ProjectMut` trait.
@@ -497,6 +502,12 @@ pub fn project_mut_derive(input: TokenStream) -> TokenStream {
                         .write_source(path, source, overwrite)
                         .map_err(#error_ident::#variant_ident)
                 },
+                // move_source_match
+                quote! {
+                    #enum_ident::#variant_ident(project) => project
+                        .move_source(from, to)
+                        .map_err(#error_ident::#variant_ident)
+                },
             ))
         })
         .collect();
@@ -508,14 +519,18 @@ pub fn project_mut_derive(input: TokenStream) -> TokenStream {
         }
     };
 
-    let (put_info_match, put_meta_match, write_source_match): (Vec<_>, Vec<_>, Vec<_>) =
-        variant_parts.iter().cloned().multiunzip();
+    let (put_info_match, put_meta_match, write_source_match, move_source_match): (
+        Vec<_>,
+        Vec<_>,
+        Vec<_>,
+        Vec<_>,
+    ) = variant_parts.iter().cloned().multiunzip();
 
     let expanded = quote! {
-        impl #impl_generics ProjectMut for #enum_ident #type_generics #where_clause {
+        impl #impl_generics ::sysand_core::project::ProjectMut for #enum_ident #type_generics #where_clause {
             fn put_info(
                 &mut self,
-                info: &InterchangeProjectInfoRaw,
+                info: &::sysand_core::model::InterchangeProjectInfoRaw,
                 overwrite: bool,
             ) -> ::std::result::Result<(), Self::Error> {
                 match self {
@@ -525,7 +540,7 @@ pub fn project_mut_derive(input: TokenStream) -> TokenStream {
 
             fn put_meta(
                 &mut self,
-                meta: &InterchangeProjectMetadataRaw,
+                meta: &::sysand_core::model::InterchangeProjectMetadataRaw,
                 overwrite: bool,
             ) -> ::std::result::Result<(), Self::Error> {
                 match self {
@@ -533,7 +548,7 @@ pub fn project_mut_derive(input: TokenStream) -> TokenStream {
                 }
             }
 
-            fn write_source<P: ::std::convert::AsRef<Utf8UnixPath>, R: ::std::io::Read>(
+            fn write_source<P: ::std::convert::AsRef<::sysand_core::project::Utf8UnixPath>, R: ::std::io::Read>(
                 &mut self,
                 path: P,
                 source: &mut R,
@@ -543,8 +558,726 @@ pub fn project_mut_derive(input: TokenStream) -> TokenStream {
                     #( #write_source_match ),*
                 }
             }
+
+            fn move_source<
+                P: ::std::convert::AsRef<::sysand_core::project::Utf8UnixPath>,
+                Q: ::std::convert::AsRef<::sysand_core::project::Utf8UnixPath>,
+            >(
+                &mut self,
+                from: P,
+                to: Q,
+            ) -> ::std::result::Result<(), Self::Error> {
+                match self {
+                    #( #move_source_match ),*
+                }
+            }
         }
     };
 
     TokenStream::from(expanded)
 }
+
+/// Derives `ProjectReadAsync` for an enum by delegating to its variants and
+/// synthesizing unified associated types.
+///
+/// This is the async counterpart of [`ProjectRead`](project_read_derive),
+/// and generates the same two enums:
+///
+/// - `<EnumName>Error`, wrapping the `ProjectReadAsync::Error` of each
+///   variant.
+/// - `<EnumName>SourceReader<'a>`, wrapping the
+///   `ProjectReadAsync::SourceReader<'a>` of each variant. It implements
+///   `AsyncRead` if all the wrapped readers are `AsyncRead + Unpin`.
+///
+/// An enum can therefore not derive both `ProjectRead` and
+/// `ProjectReadAsync`.
+///
+/// # Method Delegation
+///
+/// - [`ProjectReadAsync::get_project_async`],
+///   [`ProjectReadAsync::read_source_async`] and
+///   [`ProjectReadAsync::sources_async`] delegate to the active variant.
+/// - [`ProjectReadAsync::get_info_async`],
+///   [`ProjectReadAsync::get_meta_async`],
+///   [`ProjectReadAsync::is_definitely_invalid_async`],
+///   [`ProjectReadAsync::version_async`], [`ProjectReadAsync::usage_async`],
+///   [`ProjectReadAsync::checksum_canonical_hex_async`] and
+///   [`ProjectReadAsync::checksum_canonical_variant_async`] delegate to the
+///   active variant so leaf overrides survive wrapper enums.
+///
+/// All other methods are handled by the default implementation of the
+/// `ProjectReadAsync` trait.
+///
+/// # Requirements
+///
+/// - Each variant must contain exactly one value whose type implements
+///   `ProjectReadAsync`.
+/// - No additional fields are permitted in a variant.
+#[proc_macro_derive(ProjectReadAsync)]
+pub fn project_read_async_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let variants = match single_field_variants(&ast, "ProjectReadAsync") {
+        Ok(variants) => variants,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let (variant_idents, variant_types): (Vec<_>, Vec<_>) = variants.into_iter().unzip();
+
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+    let enum_ident = &ast.ident;
+    let error_ident = format_ident!("{}Error", enum_ident);
+    let source_reader_ident = format_ident!("{}SourceReader", enum_ident);
+    let error_enum = error_enum(&error_ident, &variant_idents);
+
+    // Delegation of `method(args)` to the active variant, mapping its error
+    let delegate = |method: proc_macro2::TokenStream| {
+        quote! {
+            match self {
+                #( #enum_ident::#variant_idents(project) => project
+                    .#method
+                    .await
+                    .map_err(#error_ident::#variant_idents) ),*
+            }
+        }
+    };
+    let get_project = delegate(quote! { get_project_async() });
+    let sources = delegate(quote! { sources_async(ctx) });
+    let get_info = delegate(quote! { get_info_async() });
+    let get_meta = delegate(quote! { get_meta_async() });
+    let version = delegate(quote! { version_async() });
+    let usage = delegate(quote! { usage_async() });
+    let checksum_canonical_variant = delegate(quote! { checksum_canonical_variant_async() });
+
+    let expanded = quote! {
+        #error_enum
+
+        pub enum #source_reader_ident<
+            #( #variant_idents ),*
+        > {
+            #( #variant_idents(#variant_idents) ),*
+        }
+
+        impl<
+            #( #variant_idents: ::sysand_core::project::AsyncRead + ::std::marker::Unpin ),*
+        > ::sysand_core::project::AsyncRead
+        for #source_reader_ident<
+            #( #variant_idents ),*
+        > {
+            fn poll_read(
+                self: ::std::pin::Pin<&mut Self>,
+                cx: &mut ::std::task::Context<'_>,
+                buf: &mut [u8],
+            ) -> ::std::task::Poll<::std::io::Result<usize>> {
+                match self.get_mut() {
+                    #( #source_reader_ident::#variant_idents(reader) =>
+                        ::std::pin::Pin::new(reader).poll_read(cx, buf) ),*
+                }
+            }
+        }
+
+        impl #impl_generics ::sysand_core::project::ProjectReadAsync
+        for #enum_ident #type_generics #where_clause {
+            type Error = #error_ident<
+                #( <#variant_types as ::sysand_core::project::ProjectReadAsync>::Error ),*
+            >;
+
+            fn get_project_async(
+                &self,
+            ) -> impl ::std::future::Future<
+                Output = ::std::result::Result<
+                    (
+                        ::std::option::Option<::sysand_core::model::InterchangeProjectInfoRaw>,
+                        ::std::option::Option<::sysand_core::model::InterchangeProjectMetadataRaw>,
+                    ),
+                    Self::Error,
+                >,
+            > {
+                async move { #get_project }
+            }
+
+            type SourceReader<'a>
+                = #source_reader_ident<
+                    #( <#variant_types as ::sysand_core::project::ProjectReadAsync>::SourceReader<'a> ),*
+                >
+            where
+                Self: 'a;
+
+            fn read_source_async<P: ::std::convert::AsRef<::sysand_core::project::Utf8UnixPath>>(
+                &self,
+                path: P,
+            ) -> impl ::std::future::Future<
+                Output = ::std::result::Result<Self::SourceReader<'_>, Self::Error>,
+            > {
+                async move {
+                    match self {
+                        #( #enum_ident::#variant_idents(project) => project
+                            .read_source_async(path)
+                            .await
+                            .map(#source_reader_ident::#variant_idents)
+                            .map_err(#error_ident::#variant_idents) ),*
+                    }
+                }
+            }
+
+            fn sources_async(
+                &self,
+                ctx: &::sysand_core::context::ProjectContext,
+            ) -> impl ::std::future::Future<
+                Output = ::std::result::Result<
+                    ::std::vec::Vec<::sysand_core::lock::Source>,
+                    Self::Error,
+                >,
+            > {
+                async move { #sources }
+            }
+
+            fn get_info_async(
+                &self,
+            ) -> impl ::std::future::Future<
+                Output = ::std::result::Result<
+                    ::std::option::Option<::sysand_core::model::InterchangeProjectInfoRaw>,
+                    Self::Error,
+                >,
+            > {
+                async move { #get_info }
+            }
+
+            fn get_meta_async(
+                &self,
+            ) -> impl ::std::future::Future<
+                Output = ::std::result::Result<
+                    ::std::option::Option<::sysand_core::model::InterchangeProjectMetadataRaw>,
+                    Self::Error,
+                >,
+            > {
+                async move { #get_meta }
+            }
+
+            fn is_definitely_invalid_async(&self) -> impl ::std::future::Future<Output = bool> {
+                async move {
+                    match self {
+                        #( #enum_ident::#variant_idents(project) =>
+                            project.is_definitely_invalid_async().await ),*
+                    }
+                }
+            }
+
+            fn version_async(
+                &self,
+            ) -> impl ::std::future::Future<
+                Output = ::std::result::Result<
+                    ::std::option::Option<::std::string::String>,
+                    Self::Error,
+                >,
+            > {
+                async move { #version }
+            }
+
+            fn usage_async(
+                &self,
+            ) -> impl ::std::future::Future<
+                Output = ::std::result::Result<
+                    ::std::option::Option<
+                        ::std::vec::Vec<::sysand_core::model::InterchangeProjectUsageRaw>,
+                    >,
+                    Self::Error,
+                >,
+            > {
+                async move { #usage }
+            }
+
+            fn checksum_canonical_hex_async(
+                &self,
+            ) -> impl ::std::future::Future<
+                Output = ::std::result::Result<
+                    ::std::option::Option<::std::string::String>,
+                    ::sysand_core::project::CanonicalizationError<Self::Error>,
+                >,
+            > {
+                async move {
+                    match self {
+                        #( #enum_ident::#variant_idents(project) => project
+                            .checksum_canonical_hex_async()
+                            .await
+                            .map_err(|e| e.map_project_read(#error_ident::#variant_idents)) ),*
+                    }
+                }
+            }
+
+            fn checksum_canonical_variant_async(
+                &self,
+            ) -> impl ::std::future::Future<
+                Output = ::std::result::Result<
+                    ::sysand_core::project::ProjectChecksum,
+                    Self::Error,
+                >,
+            > {
+                async move { #checksum_canonical_variant }
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Derives `ResolveRead` for an enum of resolvers by delegating to its
+/// variants and synthesizing unified associated types.
+///
+/// The macro generates three new types, each with one variant per
+/// original enum variant:
+///
+/// - `<EnumName>Error`, wrapping the `ResolveRead::Error` of each variant.
+/// - `<EnumName>Project`, wrapping the `ResolveRead::ProjectStorage` of
+///   each variant. It implements `ProjectRead` (and `Debug`) as if it was
+///   derived with [`ProjectRead`](project_read_derive), so it also comes
+///   with `<EnumName>ProjectError` and `<EnumName>ProjectSourceReader`.
+/// - `<EnumName>Storages`, an iterator over the projects resolved by the
+///   active variant, wrapped in `<EnumName>Project`.
+///
+/// # Method Delegation
+///
+/// - [`ResolveRead::resolve_read`] delegates to the active variant.
+/// - [`ResolveRead::resolve_read_raw`] and [`ResolveRead::resolve_many`]
+///   delegate to the active variant so leaf overrides, such as concurrent
+///   resolution, survive wrapper enums.
+///
+/// All other methods are handled by the default implementation of the
+/// `ResolveRead` trait.
+///
+/// # Requirements
+///
+/// - Each variant must contain exactly one value whose type implements
+///   `ResolveRead`.
+/// - No additional fields are permitted in a variant.
+#[proc_macro_derive(ResolveRead)]
+pub fn resolve_read_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let variants = match single_field_variants(&ast, "ResolveRead") {
+        Ok(variants) => variants,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let (variant_idents, variant_types): (Vec<_>, Vec<_>) = variants.into_iter().unzip();
+
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+    let enum_ident = &ast.ident;
+    let error_ident = format_ident!("{}Error", enum_ident);
+    let project_ident = format_ident!("{}Project", enum_ident);
+    let storages_ident = format_ident!("{}Storages", enum_ident);
+    let error_enum = error_enum(&error_ident, &variant_idents);
+    let project_enum = project_enum(&project_ident, &variant_idents);
+
+    let project_params: Vec<_> = variant_idents
+        .iter()
+        .map(|v| format_ident!("{}Project", v))
+        .collect();
+    let error_params: Vec<_> = variant_idents
+        .iter()
+        .map(|v| format_ident!("{}Error", v))
+        .collect();
+
+    let expanded = quote! {
+        #error_enum
+
+        #project_enum
+
+        pub enum #storages_ident<
+            #( #variant_idents ),*
+        > {
+            #( #variant_idents(#variant_idents) ),*
+        }
+
+        impl<
+            #( #variant_idents, #project_params, #error_params ),*
+        > ::std::iter::Iterator
+        for #storages_ident<
+            #( #variant_idents ),*
+        >
+        where
+            #(
+                #variant_idents: ::std::iter::Iterator<
+                    Item = ::std::result::Result<#project_params, #error_params>,
+                >,
+                #project_params: ::sysand_core::project::ProjectRead,
+            )*
+        {
+            type Item = ::std::result::Result<
+                #project_ident<#( #project_params ),*>,
+                #error_ident<#( #error_params ),*>,
+            >;
+
+            fn next(&mut self) -> ::std::option::Option<Self::Item> {
+                match self {
+                    #( #storages_ident::#variant_idents(storages) => storages.next().map(|storage| {
+                        storage
+                            .map(#project_ident::#variant_idents)
+                            .map_err(#error_ident::#variant_idents)
+                    }) ),*
+                }
+            }
+        }
+
+        impl #impl_generics ::sysand_core::resolve::ResolveRead
+        for #enum_ident #type_generics #where_clause {
+            type Error = #error_ident<
+                #( <#variant_types as ::sysand_core::resolve::ResolveRead>::Error ),*
+            >;
+
+            type ProjectStorage = #project_ident<
+                #( <#variant_types as ::sysand_core::resolve::ResolveRead>::ProjectStorage ),*
+            >;
+
+            type ResolvedStorages = #storages_ident<
+                #(
+                    <<#variant_types as ::sysand_core::resolve::ResolveRead>::ResolvedStorages
+                        as ::std::iter::IntoIterator>::IntoIter
+                ),*
+            >;
+
+            fn resolve_read_raw<S: ::std::convert::AsRef<str>>(
+                &self,
+                uri: S,
+            ) -> ::std::result::Result<
+                ::sysand_core::resolve::ResolutionOutcome<Self::ResolvedStorages>,
+                Self::Error,
+            > {
+                match self {
+                    #( #enum_ident::#variant_idents(resolver) => resolver
+                        .resolve_read_raw(uri)
+                        .map(|outcome| outcome.map(|storages| {
+                            #storages_ident::#variant_idents(storages.into_iter())
+                        }))
+                        .map_err(#error_ident::#variant_idents) ),*
+                }
+            }
+
+            fn resolve_read(
+                &self,
+                uri: &::sysand_core::resolve::Iri<::std::string::String>,
+            ) -> ::std::result::Result<
+                ::sysand_core::resolve::ResolutionOutcome<Self::ResolvedStorages>,
+                Self::Error,
+            > {
+                match self {
+                    #( #enum_ident::#variant_idents(resolver) => resolver
+                        .resolve_read(uri)
+                        .map(|outcome| outcome.map(|storages| {
+                            #storages_ident::#variant_idents(storages.into_iter())
+                        }))
+                        .map_err(#error_ident::#variant_idents) ),*
+                }
+            }
+
+            fn resolve_many<
+                I: ::std::iter::IntoIterator<
+                    Item = ::sysand_core::resolve::Iri<::std::string::String>,
+                >,
+            >(
+                &self,
+                uris: I,
+                concurrency: ::std::num::NonZeroUsize,
+            ) -> ::std::vec::Vec<
+                ::sysand_core::resolve::IriOutcome<Self::ResolvedStorages, Self::Error>,
+            > {
+                match self {
+                    #( #enum_ident::#variant_idents(resolver) => resolver
+                        .resolve_many(uris, concurrency)
+                        .into_iter()
+                        .map(|(uri, outcome)| {
+                            let outcome = outcome
+                                .map(|outcome| outcome.map(|storages| {
+                                    #storages_ident::#variant_idents(storages.into_iter())
+                                }))
+                                .map_err(#error_ident::#variant_idents);
+                            (uri, outcome)
+                        })
+                        .collect() ),*
+                }
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Derives `ReadEnvironment` for an enum of environments by delegating to
+/// its variants and synthesizing unified associated types.
+///
+/// The macro generates three new types, each with one variant per
+/// original enum variant:
+///
+/// - `<EnumName>Error`, wrapping the `ReadEnvironment::ReadError` of each
+///   variant.
+/// - `<EnumName>Project`, wrapping the
+///   `ReadEnvironment::InterchangeProjectRead` of each variant. It
+///   implements `ProjectRead` (and `Debug`) as if it was derived with
+///   [`ProjectRead`](project_read_derive), so it also comes with
+///   `<EnumName>ProjectError` and `<EnumName>ProjectSourceReader`.
+/// - `<EnumName>Iter`, an iterator over the IRIs or versions listed by the
+///   active variant.
+///
+/// # Method Delegation
+///
+/// - [`ReadEnvironment::uris`], [`ReadEnvironment::versions`],
+///   [`ReadEnvironment::get_project`] and
+///   [`ReadEnvironment::has_version_verified`] delegate to the active
+///   variant.
+/// - [`ReadEnvironment::has`], [`ReadEnvironment::has_version`] and
+///   [`ReadEnvironment::candidate_projects`] delegate to the active variant
+///   so leaf overrides survive wrapper enums.
+///
+/// All other methods are handled by the default implementation of the
+/// `ReadEnvironment` trait.
+///
+/// # Requirements
+///
+/// - Each variant must contain exactly one value whose type implements
+///   `ReadEnvironment`.
+/// - No additional fields are permitted in a variant.
+#[proc_macro_derive(ReadEnvironment)]
+pub fn read_environment_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let variants = match single_field_variants(&ast, "ReadEnvironment") {
+        Ok(variants) => variants,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let (variant_idents, variant_types): (Vec<_>, Vec<_>) = variants.into_iter().unzip();
+
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+    let enum_ident = &ast.ident;
+    let error_ident = format_ident!("{}Error", enum_ident);
+    let project_ident = format_ident!("{}Project", enum_ident);
+    let iter_ident = format_ident!("{}Iter", enum_ident);
+    let error_enum = error_enum(&error_ident, &variant_idents);
+    let project_enum = project_enum(&project_ident, &variant_idents);
+
+    let error_params: Vec<_> = variant_idents
+        .iter()
+        .map(|v| format_ident!("{}Error", v))
+        .collect();
+
+    // Delegation of `method(args)` to the active variant, mapping its error
+    let delegate = |method: proc_macro2::TokenStream| {
+        quote! {
+            match self {
+                #( #enum_ident::#variant_idents(env) => env
+                    .#method
+                    .map_err(#error_ident::#variant_idents) ),*
+            }
+        }
+    };
+    let has = delegate(quote! { has(uri) });
+    let has_version = delegate(quote! { has_version(uri, version) });
+    let has_version_verified = delegate(quote! { has_version_verified(uri, version, checksum) });
+
+    let expanded = quote! {
+        #error_enum
+
+        #project_enum
+
+        pub enum #iter_ident<
+            #( #variant_idents ),*
+        > {
+            #( #variant_idents(#variant_idents) ),*
+        }
+
+        impl<
+            #( #variant_idents, #error_params ),*
+        > ::std::iter::Iterator
+        for #iter_ident<
+            #( #variant_idents ),*
+        >
+        where
+            #(
+                #variant_idents: ::std::iter::Iterator<
+                    Item = ::std::result::Result<::std::string::String, #error_params>,
+                >,
+            )*
+        {
+            type Item = ::std::result::Result<
+                ::std::string::String,
+                #error_ident<#( #error_params ),*>,
+            >;
+
+            fn next(&mut self) -> ::std::option::Option<Self::Item> {
+                match self {
+                    #( #iter_ident::#variant_idents(iter) => iter
+                        .next()
+                        .map(|item| item.map_err(#error_ident::#variant_idents)) ),*
+                }
+            }
+        }
+
+        impl #impl_generics ::sysand_core::env::ReadEnvironment
+        for #enum_ident #type_generics #where_clause {
+            type ReadError = #error_ident<
+                #( <#variant_types as ::sysand_core::env::ReadEnvironment>::ReadError ),*
+            >;
+
+            type UriIter = #iter_ident<
+                #(
+                    <<#variant_types as ::sysand_core::env::ReadEnvironment>::UriIter
+                        as ::std::iter::IntoIterator>::IntoIter
+                ),*
+            >;
+
+            fn uris(&self) -> ::std::result::Result<Self::UriIter, Self::ReadError> {
+                match self {
+                    #( #enum_ident::#variant_idents(env) => env
+                        .uris()
+                        .map(|uris| #iter_ident::#variant_idents(uris.into_iter()))
+                        .map_err(#error_ident::#variant_idents) ),*
+                }
+            }
+
+            type VersionIter = #iter_ident<
+                #(
+                    <<#variant_types as ::sysand_core::env::ReadEnvironment>::VersionIter
+                        as ::std::iter::IntoIterator>::IntoIter
+                ),*
+            >;
+
+            fn versions<S: ::std::convert::AsRef<str>>(
+                &self,
+                uri: S,
+            ) -> ::std::result::Result<Self::VersionIter, Self::ReadError> {
+                match self {
+                    #( #enum_ident::#variant_idents(env) => env
+                        .versions(uri)
+                        .map(|versions| #iter_ident::#variant_idents(versions.into_iter()))
+                        .map_err(#error_ident::#variant_idents) ),*
+                }
+            }
+
+            type InterchangeProjectRead = #project_ident<
+                #( <#variant_types as ::sysand_core::env::ReadEnvironment>::InterchangeProjectRead ),*
+            >;
+
+            fn get_project<S: ::std::convert::AsRef<str>, T: ::std::convert::AsRef<str>>(
+                &self,
+                uri: S,
+                version: T,
+            ) -> ::std::result::Result<Self::InterchangeProjectRead, Self::ReadError> {
+                match self {
+                    #( #enum_ident::#variant_idents(env) => env
+                        .get_project(uri, version)
+                        .map(#project_ident::#variant_idents)
+                        .map_err(#error_ident::#variant_idents) ),*
+                }
+            }
+
+            fn has<S: ::std::convert::AsRef<str>>(
+                &self,
+                uri: S,
+            ) -> ::std::result::Result<bool, Self::ReadError> {
+                #has
+            }
+
+            fn has_version<S: ::std::convert::AsRef<str>, V: ::std::convert::AsRef<str>>(
+                &self,
+                uri: S,
+                version: V,
+            ) -> ::std::result::Result<bool, Self::ReadError> {
+                #has_version
+            }
+
+            fn has_version_verified<
+                S: ::std::convert::AsRef<str>,
+                V: ::std::convert::AsRef<str>,
+            >(
+                &self,
+                uri: S,
+                version: V,
+                checksum: &::sysand_core::project::ProjectChecksum,
+            ) -> ::std::result::Result<::sysand_core::env::ProjectChecksumResult, Self::ReadError> {
+                #has_version_verified
+            }
+
+            fn candidate_projects<S: ::std::convert::AsRef<str>>(
+                &self,
+                uri: S,
+            ) -> ::std::result::Result<
+                ::std::vec::Vec<Self::InterchangeProjectRead>,
+                Self::ReadError,
+            > {
+                match self {
+                    #( #enum_ident::#variant_idents(env) => env
+                        .candidate_projects(uri)
+                        .map(|projects| {
+                            projects
+                                .into_iter()
+                                .map(#project_ident::#variant_idents)
+                                .collect()
+                        })
+                        .map_err(#error_ident::#variant_idents) ),*
+                }
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Identifier and type of each variant of the enum `ast`, which must all be
+/// tuple variants with a single field
+fn single_field_variants(
+    ast: &DeriveInput,
+    derive: &str,
+) -> syn::Result<Vec<(syn::Ident, syn::Type)>> {
+    let Data::Enum(DataEnum { variants, .. }) = &ast.data else {
+        return Err(syn::Error::new_spanned(
+            &ast.ident,
+            format!("{derive} can only be derived on an enum"),
+        ));
+    };
+    variants
+        .iter()
+        .map(|variant| match &variant.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                Ok((variant.ident.clone(), fields.unnamed[0].ty.clone()))
+            }
+            syn::Fields::Unnamed(_) => Err(syn::Error::new_spanned(
+                &variant.ident,
+                "each variant must contain exactly one field",
+            )),
+            _ => Err(syn::Error::new_spanned(
+                &variant.ident,
+                "only tuple variants supported",
+            )),
+        })
+        .collect()
+}
+
+/// Error enum `error_ident` with a transparent variant for the error of each
+/// of `variants`, given as generic parameters of the same name
+fn error_enum(error_ident: &syn::Ident, variants: &[syn::Ident]) -> proc_macro2::TokenStream {
+    quote! {
+        #[derive(::std::fmt::Debug, ::thiserror::Error)]
+        pub enum #error_ident<
+            #( #variants ),*
+        > {
+            #(
+                #[error(transparent)]
+                #variants(#variants)
+            ),*
+        }
+    }
+}
+
+/// Project enum `project_ident` with a variant for the project of each of
+/// `variants`, given as generic parameters of the same name, together with
+/// its `ProjectRead` implementation
+fn project_enum(project_ident: &syn::Ident, variants: &[syn::Ident]) -> proc_macro2::TokenStream {
+    let project: DeriveInput = syn::parse_quote! {
+        #[derive(::std::fmt::Debug)]
+        pub enum #project_ident<
+            #( #variants: ::sysand_core::project::ProjectRead ),*
+        > {
+            #( #variants(#variants) ),*
+        }
+    };
+    let project_read = project_read_impl(&project);
+    quote! {
+        #project
+
+        #project_read
+    }
+}
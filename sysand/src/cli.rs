@@ -3,7 +3,7 @@
 
 use std::{
     convert::Infallible,
-    ffi::OsStr,
+    ffi::{OsStr, OsString},
     fmt::{Display, Write},
 };
 
@@ -14,7 +14,16 @@ use semver::VersionReq;
 use sysand_core::{
     add::expand_sysand_purl_shorthand,
     build::KparCompressionMethod,
-    model::{KERML_METAMODEL_PREFIX, SYSML_METAMODEL_PREFIX},
+    bundle::BundleFormat,
+    config::{Config, local_fs::ConfigKey},
+    env::OverwritePolicy,
+    export::ExportFormat,
+    graph::GraphFormat,
+    import::ImportFormat,
+    model::{HashAlg, KERML_METAMODEL_PREFIX, SYSML_METAMODEL_PREFIX},
+    parallel::Jobs,
+    schema::SchemaKind,
+    stdlib::IncludeStd,
 };
 use url::Url;
 
@@ -58,6 +67,10 @@ pub struct Args {
     /// Display the sysand version.
     #[arg(short = 'V', long, action = clap::ArgAction::Version)]
     version: Option<bool>,
+
+    /// Deprecated names used on the command line, see [`rewrite_deprecated`]
+    #[arg(skip)]
+    pub deprecated: Vec<&'static Deprecation>,
 }
 
 #[derive(clap::Subcommand, Debug, Clone)]
@@ -85,6 +98,25 @@ pub enum Command {
         /// Don't require license to be an SPDX expression
         #[arg(long, requires = "license")]
         no_spdx: bool,
+        /// Canonical IRI of the project, recorded in `sysand.toml` and
+        /// checked when publishing. When running in a terminal, it is
+        /// asked for, suggesting `pkg:sysand/<publisher>/<name>`
+        #[arg(long, verbatim_doc_comment, conflicts_with = "workspace")]
+        iri: Option<String>,
+        /// Create a workspace instead, whose projects are locked and
+        /// installed together. Publisher, version and license apply to
+        /// its members
+        #[arg(long, conflicts_with = "name", verbatim_doc_comment)]
+        workspace: bool,
+        /// Path of a project to create in the new workspace, relative to
+        /// it. Its name is the last component of the path. Can be repeated
+        #[arg(
+            long,
+            value_name = "PATH",
+            requires = "workspace",
+            verbatim_doc_comment
+        )]
+        member: Vec<String>,
     },
     // Only for better error messages
     #[command(hide = true)]
@@ -103,6 +135,24 @@ pub enum Command {
         /// guide's `Project information and metadata` section
         #[clap(verbatim_doc_comment)]
         version_constraint: Option<String>,
+        /// Variant of the project to use, one of the `variants` in its
+        /// `.meta.json`. Only the source files of the variant are installed
+        #[arg(long, value_name = "NAME", verbatim_doc_comment)]
+        variant: Option<String>,
+        /// Why the project is used, recorded in `sysand.toml` and shown by
+        /// `sysand list` and `sysand why`
+        #[arg(long, value_name = "TEXT", verbatim_doc_comment)]
+        reason: Option<String>,
+        /// Use the project only when the condition holds for the markers
+        /// given when locking, e.g. `tool == "syside"`. Recorded in the
+        /// `when` field of the usage in `.project.json`
+        #[arg(long, value_name = "CONDITION", verbatim_doc_comment)]
+        when: Option<String>,
+        /// Require the project to have this canonical project hash, as
+        /// `sha256:<hex>`. Recorded in the `hash` field of the usage in
+        /// `.project.json` and checked on `sysand lock` and `sysand sync`
+        #[arg(long, value_name = "HASH", verbatim_doc_comment)]
+        hash: Option<String>,
         /// Do not automatically resolve dependencies (and generate lockfile)
         #[arg(long, default_value_t = false)]
         no_lock: bool,
@@ -121,6 +171,63 @@ pub enum Command {
         #[clap(flatten)]
         locator: RemoveProjectLocatorArgs,
     },
+    /// Add the dependencies declared by other SysML tooling as usages
+    /// of the current project. Dependencies given by path are added as
+    /// `file://` URLs, like with `sysand add --path`
+    #[clap(verbatim_doc_comment)]
+    Import {
+        /// Format of the dependency declarations
+        #[arg(value_enum)]
+        format: ImportFormatCli,
+        /// File or directory declaring the dependencies
+        path: Utf8PathBuf,
+    },
+    /// Replace usages of a project IRI with a new IRI, e.g. after the
+    /// project was renamed. Updates the current project, or all projects
+    /// of the current workspace, and their lockfiles
+    #[clap(verbatim_doc_comment)]
+    MigrateIri {
+        /// Previous IRI of the project, or <publisher>/<name> shorthand
+        /// for pkg:sysand/<publisher>/<name>
+        #[arg(value_parser = parse_usage_iri, verbatim_doc_comment)]
+        old: Iri<String>,
+        /// New IRI of the project, or <publisher>/<name> shorthand
+        /// for pkg:sysand/<publisher>/<name>
+        #[arg(value_parser = parse_usage_iri, verbatim_doc_comment)]
+        new: Iri<String>,
+        /// Only show what would be changed
+        #[arg(long)]
+        dry_run: bool,
+        /// Create a redirect stub in this directory: a project with the
+        /// name of the project previously identified by `OLD`, whose only
+        /// usage is `NEW`. Publishing it as a new version of that project
+        /// points its users to the new IRI
+        #[arg(
+            long,
+            value_name = "PATH",
+            requires = "redirect_version",
+            verbatim_doc_comment
+        )]
+        redirect_stub: Option<Utf8PathBuf>,
+        /// Version of the redirect stub. Should be newer than every
+        /// published version of the project previously identified by `OLD`
+        #[arg(
+            long,
+            value_name = "VERSION",
+            requires = "redirect_stub",
+            verbatim_doc_comment
+        )]
+        redirect_version: Option<String>,
+    },
+    /// Migrate the lockfile and env of the current project or workspace
+    /// to the file format versions of this version of sysand. The original
+    /// files are kept next to the migrated ones as `<file>.<version>.bak`
+    #[clap(verbatim_doc_comment)]
+    Migrate {
+        /// Only show what would be migrated
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// Clone a project to a specified directory.
     /// Equivalent to manually downloading, extracting the
     /// project to the directory and running `sysand sync`
@@ -140,6 +247,13 @@ pub enum Command {
         /// Don't resolve or install dependencies
         #[arg(long)]
         no_deps: bool,
+        /// Clone the project and each of its locked dependencies into
+        /// sibling directories of the target directory, so that all of
+        /// them can be edited. Each project uses the cloned dependencies
+        /// as editable projects through source overrides in its
+        /// `sysand.toml`
+        #[arg(long, conflicts_with = "no_deps", verbatim_doc_comment)]
+        workspace: bool,
         #[command(flatten)]
         resolution_opts: ResolutionOptions,
     },
@@ -156,9 +270,27 @@ pub enum Command {
         //       Maybe only when building a kpar?
         #[arg(long, default_value_t = false)]
         compute_checksum: bool,
+        /// Compute and add each file's (current) checksum using the given
+        /// algorithm. Building a KPAR keeps the algorithm of each file
+        #[arg(long, value_enum, value_name = "ALGORITHM", verbatim_doc_comment)]
+        checksum_algorithm: Option<HashAlgCli>,
         /// Do not detect and add top level symbols to index
         #[arg(long, default_value_t = false)]
         no_index_symbols: bool,
+        /// Add usages of the standard libraries that the files refer
+        /// to, e.g. `ISQ::` or `SI::`, but the project does not declare.
+        /// Without this, missing standard library usages are only reported
+        #[arg(
+            long,
+            default_value_t = false,
+            conflicts_with = "no_index_symbols",
+            verbatim_doc_comment
+        )]
+        add_std_usages: bool,
+        /// Number of files to read and checksum in parallel
+        /// (default: the number of available CPUs)
+        #[arg(short, long, value_name = "N", verbatim_doc_comment)]
+        jobs: Option<Jobs>,
     },
     /// Exclude model interchange file from project metadata
     Exclude {
@@ -166,6 +298,16 @@ pub enum Command {
         #[arg(num_args = 1..)]
         paths: Vec<Utf8PathBuf>,
     },
+    /// Move or rename a model interchange file of the project, updating
+    /// its checksum, index and variant entries in project metadata.
+    /// If interrupted, run it again to finish updating the metadata
+    #[clap(verbatim_doc_comment)]
+    Mv {
+        /// File to move
+        old: Utf8PathBuf,
+        /// New path of the file
+        new: Utf8PathBuf,
+    },
     /// Build a KerML Project Archive (KPAR). If executed in a workspace
     /// outside of a project, builds all projects in the workspace.
     #[clap(verbatim_doc_comment)]
@@ -180,8 +322,16 @@ pub enum Command {
         #[clap(verbatim_doc_comment)]
         path: Option<Utf8PathBuf>,
         /// Method to compress the files in the KPAR
-        #[arg(short = 'c', long, default_value_t, value_enum)]
-        compression: KparCompressionMethodCli,
+        /// (default: `[build] compression` from config, or deflated).
+        /// Methods other than stored and deflated may not be supported
+        /// by other tools reading KPARs
+        #[arg(short = 'c', long, value_enum, verbatim_doc_comment)]
+        compression: Option<KparCompressionMethodCli>,
+        /// Compression level, e.g. 1-9 for deflated and 1-22 for zstd
+        /// (default: `[build] compression_level` from config, or
+        /// the default level of the compression method)
+        #[arg(long, allow_negative_numbers = true, verbatim_doc_comment)]
+        compression_level: Option<i64>,
         /// Allow usages of local paths (`file://`).
         /// Warning: using this makes the project not portable between different
         /// computers, as `file://` URL always contains an absolute path.
@@ -196,6 +346,53 @@ pub enum Command {
         /// Don't update exported symbols index in the built KPAR metadata
         #[arg(long, conflicts_with = "update_meta")]
         keep_index: bool,
+        /// Don't run post-build hooks configured in `[[post_build]]`
+        #[arg(long, default_value_t = false)]
+        no_post_build: bool,
+        /// Build even if the files matching `[build] include` differ from
+        /// the files in project metadata. The matching files are packaged
+        #[arg(long, default_value_t = false, verbatim_doc_comment)]
+        allow_dirty: bool,
+        /// Build each KPAR a second time and fail if the archives differ.
+        /// File times in the KPAR are taken from `SOURCE_DATE_EPOCH`
+        /// if it is set
+        #[arg(long, default_value_t = false, verbatim_doc_comment)]
+        check_reproducible: bool,
+        /// Number of files to checksum after post-build hooks in parallel
+        /// (default: the number of available CPUs)
+        #[arg(short, long, value_name = "N", verbatim_doc_comment)]
+        jobs: Option<Jobs>,
+    },
+    /// Check that the source files of the current project match their
+    /// checksums in project metadata
+    #[clap(verbatim_doc_comment)]
+    Verify {
+        /// Number of files to checksum in parallel
+        /// (default: the number of available CPUs)
+        #[arg(short, long, value_name = "N", verbatim_doc_comment)]
+        jobs: Option<Jobs>,
+        /// Instead check that the projects installed in `.sysand` were
+        /// not modified after they were installed
+        #[arg(long, conflicts_with = "jobs", verbatim_doc_comment)]
+        env: bool,
+    },
+    /// Rewrite `.project.json` and `.meta.json` of the current project in
+    /// canonical form, so that diffs stay minimal: metadata index and
+    /// checksums sorted by path, checksums as lowercase SHA256 and
+    /// consistent indentation
+    #[clap(verbatim_doc_comment)]
+    Normalize {
+        /// Don't write anything, fail if the files are not in canonical form
+        #[arg(long)]
+        check: bool,
+    },
+    /// List the files of the current project and whether `sysand build`
+    /// would package them, with the reason why
+    #[clap(verbatim_doc_comment)]
+    Files {
+        /// Classify files as `sysand build --allow-dirty` would
+        #[arg(long, default_value_t = false)]
+        allow_dirty: bool,
     },
     /// Publish a KPAR to a sysand package index
     Publish {
@@ -208,8 +405,13 @@ pub enum Command {
         /// Configured index URL to publish to (e.g. https://sysand.com)
         /// May point to a path containing sysand-index-config.json, or directly
         /// to the API root (e.g. https://sysand.com/api)
-        #[arg(long, value_name = "URL", verbatim_doc_comment)]
-        index: Url,
+        #[arg(
+            long,
+            value_name = "URL",
+            required_unless_present = "dry_run",
+            verbatim_doc_comment
+        )]
+        index: Option<Url>,
 
         /// How to use CI trusted publishing for acquiring publish credentials
         #[arg(
@@ -220,9 +422,92 @@ pub enum Command {
             verbatim_doc_comment
         )]
         trusted_publishing: TrustedPublishingMode,
+
+        /// Only check that the KPAR can be published, including the
+        /// `[publish]` policy checks of the configuration, without
+        /// uploading it
+        #[arg(long, verbatim_doc_comment)]
+        dry_run: bool,
+
+        /// Instead of uploading the KPAR, mark its project deprecated in
+        /// the index. Deprecated projects stay installable, but selecting
+        /// them warns and `sysand audit` lists them
+        #[arg(long, conflicts_with = "dry_run", verbatim_doc_comment)]
+        deprecate: bool,
+
+        /// IRI of the project to suggest in place of the deprecated one
+        #[arg(long, value_name = "IRI", requires = "deprecate")]
+        replaced_by: Option<fluent_uri::Iri<String>>,
     },
     /// Create or update lockfile
     Lock {
+        /// If no project is found, create a minimal project in the current
+        /// directory that includes all SysML v2 and KerML files under it
+        #[arg(long, verbatim_doc_comment)]
+        here: bool,
+        /// Merge lockfiles BASE, OURS and THEIRS, writing the result to
+        /// OURS. Entries changed differently in OURS and THEIRS are
+        /// resolved again for the current project. For use as a git merge
+        /// driver with `sysand lock --merge %O %A %B`
+        #[arg(
+            long,
+            num_args = 3,
+            value_names = ["BASE", "OURS", "THEIRS"],
+            conflicts_with = "here",
+            verbatim_doc_comment
+        )]
+        merge: Option<Vec<Utf8PathBuf>>,
+        /// Select the lowest versions satisfying all constraints instead
+        /// of the newest, to check that lower bounds are correct
+        #[arg(long, verbatim_doc_comment)]
+        minimal_versions: bool,
+        /// Fail if the lockfile would change instead of writing it
+        #[arg(long, conflicts_with = "merge")]
+        locked: bool,
+        #[command(flatten)]
+        resolution_opts: ResolutionOptions,
+    },
+    /// List the usages of the current project with their version
+    /// constraint, the version locked in the lockfile, whether it is
+    /// installed in `.sysand` and the kind of source it is locked to
+    #[clap(verbatim_doc_comment)]
+    List,
+    /// Show why a project is used: the reason recorded by `sysand add
+    /// --reason` and the projects in the lockfile that use it
+    #[clap(verbatim_doc_comment)]
+    Why {
+        /// IRI of the used project
+        iri: String,
+    },
+    /// Print the dependency graph of the lockfile, with nodes labeled
+    /// IRI@version and edges labeled with version constraints
+    #[clap(verbatim_doc_comment)]
+    Graph {
+        /// Syntax of the graph
+        #[arg(long, value_enum, default_value_t)]
+        format: GraphFormatCli,
+        /// Cluster each workspace member with the projects used only by it
+        #[arg(long)]
+        cluster: bool,
+    },
+    /// Check whether upgrading a dependency to a new version may break
+    /// the current project. Lists the top-level symbols of the locked
+    /// version that the new version no longer has, with the files of the
+    /// current project referring to them, and changes to the usages of
+    /// the dependency
+    #[clap(verbatim_doc_comment)]
+    UpgradeCheck {
+        /// IRI of the dependency
+        iri: fluent_uri::Iri<String>,
+        /// Version to upgrade to
+        new_version: String,
+        #[command(flatten)]
+        resolution_opts: ResolutionOptions,
+    },
+    /// List the projects of the lockfile that their index declares
+    /// deprecated, with the projects suggested in their place
+    #[clap(verbatim_doc_comment)]
+    Audit {
         #[command(flatten)]
         resolution_opts: ResolutionOptions,
     },
@@ -236,11 +521,108 @@ pub enum Command {
         #[command(subcommand)]
         command: IndexCommand,
     },
+    /// Manage the owners of projects in a sysand package index
+    Owner {
+        #[command(subcommand)]
+        command: OwnerCommand,
+        /// Index URL (e.g. https://sysand.com). May point to a path containing
+        /// sysand-index-config.json, or directly to the API root.
+        /// Default is the default index of the configuration, or https://sysand.com
+        #[arg(long, value_name = "URL", global = true, verbatim_doc_comment)]
+        index: Option<Url>,
+    },
+    /// Manage the caches of project metadata and git repositories
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+    /// Inspect the configuration files
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
     /// Sync `.sysand` to lockfile, creating a lockfile and `.sysand` if needed
     Sync {
+        /// If no project is found, create a minimal project in the current
+        /// directory that includes all SysML v2 and KerML files under it
+        #[arg(long, verbatim_doc_comment)]
+        here: bool,
+        /// Print the actions needed to sync `.sysand` as JSON to standard
+        /// output instead of carrying them out. Requires a lockfile
+        #[arg(long, conflicts_with_all = ["here", "apply"], verbatim_doc_comment)]
+        plan: bool,
+        /// Carry out the actions of a plan created with `--plan`. Fails if
+        /// the lockfile has changed since the plan was created. A plan
+        /// that failed part way through can be applied again to finish it
+        #[arg(
+            long,
+            value_name = "PLAN",
+            conflicts_with = "here",
+            verbatim_doc_comment
+        )]
+        apply: Option<Utf8PathBuf>,
+        /// Install only the projects with these IRIs and their
+        /// dependencies. The whole lockfile is still checked
+        #[arg(
+            long,
+            value_name = "IRI",
+            num_args = 1..,
+            conflicts_with = "apply",
+            verbatim_doc_comment
+        )]
+        only: Vec<String>,
+        /// Do not install the projects with these IRIs, nor the
+        /// dependencies used only by them
+        #[arg(
+            long,
+            value_name = "IRI",
+            num_args = 1..,
+            conflicts_with = "apply",
+            verbatim_doc_comment
+        )]
+        skip: Vec<String>,
         #[command(flatten)]
         resolution_opts: ResolutionOptions,
     },
+    /// Export the dependencies pinned in the lockfile for use by other
+    /// build systems, such as Bazel, Buck2 or Nix. Each dependency is
+    /// listed with its name, version, URL and SHA256 digest of its KPAR
+    #[clap(verbatim_doc_comment)]
+    Export {
+        /// Format to export to
+        #[arg(long, value_enum)]
+        format: ExportFormatCli,
+        /// File to write to. Defaults to standard output
+        #[arg(long, short)]
+        output: Option<Utf8PathBuf>,
+    },
+    /// Print the JSON Schema of a file format read by sysand, for
+    /// validating files with other tools, e.g. editors
+    #[clap(verbatim_doc_comment)]
+    Schema {
+        /// File format
+        #[arg(value_enum)]
+        kind: SchemaKindCli,
+        /// File to write to. Defaults to standard output
+        #[arg(long, short)]
+        output: Option<Utf8PathBuf>,
+    },
+    /// Pack the current project and every project in its lockfile
+    /// into a single bundle of KPARs, to be installed offline with
+    /// `sysand bundle install`. Dependencies are packed from the
+    /// environment, so run `sysand sync` first
+    #[command(args_conflicts_with_subcommands = true, verbatim_doc_comment)]
+    Bundle {
+        #[command(subcommand)]
+        command: Option<BundleCommand>,
+        /// Path of the bundle to create. Defaults to `sysand-bundle.zip`,
+        /// or `sysand-bundle` with `--format directory`
+        #[arg(verbatim_doc_comment)]
+        output: Option<Utf8PathBuf>,
+        /// Layout of the bundle
+        #[arg(long, value_enum, default_value_t)]
+        format: BundleFormatCli,
+    },
     /// Describe or modify a local project (either the current one
     /// or one at a given path) or resolve and describe a project
     /// at a specified path or IRI/URL
@@ -249,14 +631,12 @@ pub enum Command {
         /// Use the project at the given path instead of the current project
         #[arg(short = 'p', long, group = "location")]
         path: Option<Utf8PathBuf>,
+        /// Name of the project to use when the KPAR at `--path`
+        /// contains several projects
+        #[arg(long, value_name = "NAME", requires = "path", verbatim_doc_comment)]
+        project: Option<String>,
         /// Use the project with the given IRI/URI/URL instead of the current project
-        #[arg(
-            short = 'i',
-            long,
-            visible_alias = "uri",
-            visible_alias = "url",
-            group = "location"
-        )]
+        #[arg(short = 'i', long, group = "location")]
         iri: Option<fluent_uri::Iri<String>>,
         /// Use the project with the given locator, trying to parse it as
         /// an IRI/URI/URL and otherwise falling back to using it as a path
@@ -271,6 +651,25 @@ pub enum Command {
         /// Do not try to normalise the IRI/URI when resolving
         #[arg(long, default_value_t = false, visible_alias = "no-normalize")]
         no_normalise: bool,
+        /// Print the info of every project installed in `.sysand`,
+        /// one JSON object per line
+        #[arg(
+            long,
+            default_value_t = false,
+            conflicts_with = "location",
+            verbatim_doc_comment
+        )]
+        all_installed: bool,
+        /// Print the info of every project in the indexes given
+        /// with `--index`, one JSON object per line
+        #[arg(
+            long,
+            default_value_t = false,
+            requires = "index",
+            conflicts_with_all = ["location", "all_installed"],
+            verbatim_doc_comment
+        )]
+        all: bool,
         // TODO: Add various options, such as whether to take local environment
         //       into consideration
         #[command(flatten)]
@@ -288,7 +687,12 @@ pub enum Command {
         sources_opts: SourcesOptions,
     },
     /// Prints the root directory of the current project
-    PrintRoot,
+    PrintRoot {
+        /// Print the paths of all projects in the current directory and
+        /// its subdirectories instead, including nested projects
+        #[arg(long, default_value_t = false)]
+        all: bool,
+    },
 }
 
 #[derive(clap::Args, Debug, Clone)]
@@ -296,9 +700,10 @@ pub enum Command {
 pub struct AddProjectLocatorArgs {
     /// IRI/URI/URL identifying the project to be used, or
     /// <publisher>/<name> shorthand for pkg:sysand/<publisher>/<name>.
-    /// Paths must use `--path`
-    #[clap(default_value = None, value_parser = parse_usage_locator_suggest_path)]
-    pub iri: Option<Iri<String>>,
+    /// A bare name, such as `vehicle`, searches the indexes for
+    /// matching projects to choose from. Paths must use `--path`
+    #[clap(default_value = None, value_parser = parse_add_locator, value_name = "IRI")]
+    pub iri: Option<AddLocator>,
     /// Path to the project to be added. Since every usage is identified
     /// by an IRI, `file://` URL will be used to refer to the project.
     /// Warning: using this makes the project not portable between different
@@ -344,7 +749,7 @@ pub struct CloneProjectLocatorArgs {
     )]
     pub auto_location: Option<String>,
     /// IRI/URI/URL identifying the project to be cloned
-    #[arg(short = 'i', long, visible_alias = "uri", visible_alias = "url")]
+    #[arg(short = 'i', long)]
     pub iri: Option<fluent_uri::Iri<String>>,
     /// Path to clone the project from. If version is also
     /// given, verifies that the project has the given version
@@ -397,6 +802,176 @@ impl From<KparCompressionMethodCli> for KparCompressionMethod {
     }
 }
 
+#[derive(clap::ValueEnum, Copy, Clone, Debug)]
+#[clap(rename_all = "lowercase")]
+pub enum ExportFormatCli {
+    /// Starlark file defining `SYSAND_DEPENDENCIES`, loadable from
+    /// Bazel and Buck2
+    Bazel,
+    /// Nix expression with fixed-output derivations of the dependencies
+    /// and a `sysand_env` derivation installing them
+    Nix,
+}
+
+impl From<ExportFormatCli> for ExportFormat {
+    fn from(value: ExportFormatCli) -> Self {
+        match value {
+            ExportFormatCli::Bazel => ExportFormat::Bazel,
+            ExportFormatCli::Nix => ExportFormat::Nix,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Copy, Clone, Debug)]
+#[clap(rename_all = "lowercase")]
+pub enum SchemaKindCli {
+    /// Project information, `.project.json`
+    Project,
+    /// Project metadata, `.meta.json`
+    Meta,
+    /// Lockfile, `sysand-lock.toml` or `sysand-lock.json`
+    Lock,
+    /// Configuration, `sysand.toml`
+    Config,
+}
+
+impl From<SchemaKindCli> for SchemaKind {
+    fn from(value: SchemaKindCli) -> Self {
+        match value {
+            SchemaKindCli::Project => SchemaKind::Project,
+            SchemaKindCli::Meta => SchemaKind::Meta,
+            SchemaKindCli::Lock => SchemaKind::Lock,
+            SchemaKindCli::Config => SchemaKind::Config,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Default, Copy, Clone, Debug)]
+#[clap(rename_all = "lowercase")]
+pub enum BundleFormatCli {
+    /// Single zip archive
+    #[default]
+    Zip,
+    /// Directory with the same contents as the zip archive
+    Directory,
+}
+
+impl From<BundleFormatCli> for BundleFormat {
+    fn from(value: BundleFormatCli) -> Self {
+        match value {
+            BundleFormatCli::Zip => BundleFormat::Zip,
+            BundleFormatCli::Directory => BundleFormat::Directory,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Copy, Clone, Debug)]
+#[clap(rename_all = "lowercase")]
+pub enum HashAlgCli {
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+    Blake3,
+}
+
+impl From<HashAlgCli> for HashAlg {
+    fn from(value: HashAlgCli) -> Self {
+        match value {
+            HashAlgCli::Sha224 => HashAlg::Sha224,
+            HashAlgCli::Sha256 => HashAlg::Sha256,
+            HashAlgCli::Sha384 => HashAlg::Sha384,
+            HashAlgCli::Sha512 => HashAlg::Sha512,
+            HashAlgCli::Blake3 => HashAlg::Blake3,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Default, Copy, Clone, Debug)]
+#[clap(rename_all = "lowercase")]
+pub enum GraphFormatCli {
+    /// Graphviz DOT
+    #[default]
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+impl From<GraphFormatCli> for GraphFormat {
+    fn from(value: GraphFormatCli) -> Self {
+        match value {
+            GraphFormatCli::Dot => GraphFormat::Dot,
+            GraphFormatCli::Mermaid => GraphFormat::Mermaid,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Copy, Clone, Debug)]
+#[clap(rename_all = "lowercase")]
+pub enum ImportFormatCli {
+    /// Eclipse (e.g. SysIDE) `.project` file, or a directory containing
+    /// it. Referenced projects must be next to the project
+    #[clap(verbatim_doc_comment)]
+    Eclipse,
+    /// Jupyter notebook with `%dependencies <IRI> [<version>]` lines
+    Jupyter,
+    /// Directory whose project directories and KPARs are dependencies
+    Directory,
+}
+
+impl From<ImportFormatCli> for ImportFormat {
+    fn from(value: ImportFormatCli) -> Self {
+        match value {
+            ImportFormatCli::Eclipse => ImportFormat::Eclipse,
+            ImportFormatCli::Jupyter => ImportFormat::Jupyter,
+            ImportFormatCli::Directory => ImportFormat::Directory,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Copy, Clone, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub enum OverwritePolicyCli {
+    /// Fail, unless the installed version is identical
+    Error,
+    /// Keep the installed version
+    Skip,
+    /// Replace the installed version
+    Replace,
+    /// Replace the installed version, unless it is identical
+    ReplaceIfDifferentHash,
+}
+
+impl From<OverwritePolicyCli> for OverwritePolicy {
+    fn from(value: OverwritePolicyCli) -> Self {
+        match value {
+            OverwritePolicyCli::Error => OverwritePolicy::Error,
+            OverwritePolicyCli::Skip => OverwritePolicy::Skip,
+            OverwritePolicyCli::Replace => OverwritePolicy::Replace,
+            OverwritePolicyCli::ReplaceIfDifferentHash => OverwritePolicy::ReplaceIfDifferentHash,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Default, Copy, Clone, Debug, PartialEq, Eq)]
+#[clap(rename_all = "lowercase")]
+pub enum ErrorFormat {
+    /// Human-readable text
+    #[default]
+    Human,
+    /// JSON object per line
+    Json,
+}
+
+#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+#[clap(rename_all = "lowercase")]
+pub enum TimingsFormat {
+    /// Summary printed to stderr
+    Human,
+    /// JSON file
+    Json,
+}
+
 #[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
 #[clap(rename_all = "lowercase")]
 pub enum TrustedPublishingMode {
@@ -1392,15 +1967,41 @@ impl InfoCommand {
 pub enum EnvCommand {
     /// Install project in `.sysand`
     Install {
-        /// IRI identifying the project to be installed
-        iri: fluent_uri::Iri<String>,
-        /// Version to be installed. Defaults to the latest
-        /// version according to SemVer 2.0, ignoring pre-releases
-        #[clap(verbatim_doc_comment)]
-        version: Option<String>,
-        /// Path to interchange project
-        #[arg(long, default_value = None)]
+        /// IRIs identifying the projects to be installed. A single
+        /// IRI may be followed by the version to be installed,
+        /// which defaults to the latest version according to
+        /// SemVer 2.0, ignoring pre-releases. All projects are
+        /// installed together with their dependencies, so
+        /// shared dependencies are only installed once
+        #[arg(
+            value_name = "IRI",
+            required_unless_present = "from_file",
+            verbatim_doc_comment
+        )]
+        iris: Vec<String>,
+        /// Install the projects listed in a file, one per line
+        /// as `<IRI> [<version>]`. Empty lines and lines starting
+        /// with `#` are ignored
+        #[arg(long, conflicts_with = "path", verbatim_doc_comment)]
+        from_file: Option<Utf8PathBuf>,
+        /// Path to interchange project. Only a single
+        /// project can be installed from a path
+        #[arg(long, default_value = None, verbatim_doc_comment)]
         path: Option<Utf8PathBuf>,
+        /// Name of the project to install when the KPAR at
+        /// `--path` contains several projects
+        #[arg(long, value_name = "NAME", requires = "path", verbatim_doc_comment)]
+        project: Option<String>,
+        /// Install the projects with the given IRIs exactly as they are
+        /// locked in the lockfile, from their locked sources, without
+        /// resolving them again. Their locked dependencies are installed
+        /// too, unless `--no-deps` is given
+        #[arg(
+            long,
+            conflicts_with_all = ["from_file", "path", "allow_overwrite", "overwrite", "allow_multiple"],
+            verbatim_doc_comment
+        )]
+        from_lock: bool,
 
         #[command(flatten)]
         install_opts: InstallOptions,
@@ -1413,8 +2014,14 @@ pub enum EnvCommand {
         iri: fluent_uri::Iri<String>,
         /// Version to be uninstalled
         version: Option<String>,
+        /// Also uninstall the projects it used that no remaining
+        /// project uses and that the lockfile does not lock
+        #[arg(long, verbatim_doc_comment)]
+        with_orphans: bool,
     },
-    /// List projects installed in `.sysand`
+    /// List projects installed in `.sysand`. With `--verbose`, also
+    /// show where each project was installed from and its hashes
+    #[clap(verbatim_doc_comment)]
     List,
     /// List source files for an installed project and
     /// (optionally) its dependencies
@@ -1430,6 +2037,143 @@ pub enum EnvCommand {
         #[command(flatten)]
         sources_opts: SourcesOptions,
     },
+    /// Print the projects installed in `.sysand`, with the sources
+    /// and checksums to install them from, as JSON to standard output.
+    /// Sources not recorded in `.sysand` are taken from the lockfile,
+    /// or resolved again
+    #[clap(verbatim_doc_comment)]
+    Export {
+        #[command(flatten)]
+        resolution_opts: ResolutionOptions,
+    },
+    /// Install exactly the projects of a file created by `sysand env export`
+    /// into `.sysand`, from their recorded sources. The lockfile is
+    /// neither read nor changed
+    #[clap(verbatim_doc_comment)]
+    Import {
+        /// Environment export file
+        path: Utf8PathBuf,
+    },
+    /// Check that the projects recorded in `.sysand/env.toml` are
+    /// installed intact, and that `.sysand/lib` has no leftovers
+    /// of interrupted installs
+    #[clap(verbatim_doc_comment)]
+    Doctor {
+        /// Remove broken projects from the environment and delete
+        /// leftovers. Removed projects are installed again by `sysand sync`
+        #[arg(long, default_value_t = false, verbatim_doc_comment)]
+        fix: bool,
+    },
+    /// Remove the projects installed in `.sysand` that the lockfile
+    /// does not lock, e.g. versions left behind as dependencies were
+    /// updated, and print the disk space reclaimed. Editable projects
+    /// are kept. In a workspace, projects locked by the lockfile of any
+    /// member, or by the shared lockfile if enabled, are kept
+    #[clap(verbatim_doc_comment)]
+    Gc {
+        /// Only print what would be removed
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+    },
+    /// Mark `.sysand` as read-only, e.g. before distributing it with a
+    /// tool installation. Projects are still read from a read-only
+    /// environment, but none can be installed or removed
+    #[clap(verbatim_doc_comment)]
+    ReadOnly {
+        /// Make the environment writable again
+        #[arg(long, default_value_t = false)]
+        off: bool,
+    },
+}
+
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum BundleCommand {
+    /// Install the projects of a bundle created by `sysand bundle`
+    /// into `.sysand`, without network access. Projects that are
+    /// already installed are skipped
+    #[clap(verbatim_doc_comment)]
+    Install {
+        /// Bundle zip archive or directory
+        bundle: Utf8PathBuf,
+    },
+}
+
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum CacheCommand {
+    /// Remove cached data. Without options, all caches are cleared
+    Prune {
+        /// Remove cached git repositories
+        #[arg(long)]
+        git: bool,
+        /// Remove cached project metadata fetched over HTTP
+        #[arg(long)]
+        http: bool,
+    },
+}
+
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum ConfigCommand {
+    /// Check the configuration files for errors and unknown keys, and
+    /// print the effective configuration with the files it comes from
+    Check,
+    /// Print the effective value of a setting
+    Get {
+        /// Key of the setting, e.g. `cache.ttl` or `index[0].url`
+        key: ConfigKey,
+    },
+    /// Set a setting in the project (or user) configuration file,
+    /// keeping its comments and formatting.
+    /// Add an `[[index]]` entry by setting a key of the entry after the
+    /// last one, e.g. `index[0].url` if there are none
+    #[clap(verbatim_doc_comment)]
+    Set {
+        /// Key of the setting, e.g. `cache.ttl` or `index[0].url`
+        key: ConfigKey,
+        /// TOML value, e.g. `60`, `true` or `["a", "b"]`. Taken as a string
+        /// if it is not valid TOML or the setting is a string
+        value: String,
+        /// Edit the user configuration file instead
+        #[arg(long)]
+        user: bool,
+    },
+    /// Remove a setting, or an entry of a list such as `index[1]`, from the
+    /// project (or user) configuration file
+    Unset {
+        /// Key of the setting, e.g. `cache.ttl` or `index[1]`
+        key: ConfigKey,
+        /// Edit the user configuration file instead
+        #[arg(long)]
+        user: bool,
+    },
+    /// List the effective settings
+    List {
+        /// Show the files each setting comes from
+        #[arg(long)]
+        origin: bool,
+    },
+}
+
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum OwnerCommand {
+    /// List the owners of a project
+    List {
+        /// IRI of the project, pkg:sysand/<publisher>/<name>
+        iri: String,
+    },
+    /// Allow a user or team to publish new versions of a project
+    Add {
+        /// IRI of the project, pkg:sysand/<publisher>/<name>
+        iri: String,
+        /// User or team to add, as known to the index
+        owner: String,
+    },
+    /// Stop a user or team from publishing new versions of a project
+    Remove {
+        /// IRI of the project, pkg:sysand/<publisher>/<name>
+        iri: String,
+        /// User or team to remove, as known to the index
+        owner: String,
+    },
 }
 
 #[derive(clap::Subcommand, Debug, Clone)]
@@ -1447,7 +2191,8 @@ pub enum IndexCommand {
     #[clap(verbatim_doc_comment)]
     Add {
         /// Project identifier. Default is pkg:sysand/<publisher>/<name>, if publisher is
-        /// specified in .project.json. Omitting both publisher and IRI is an error
+        /// specified in .project.json. Omitting both publisher and IRI is an error.
+        /// A urn:kpar:<name> IRI can only be used by projects of a single publisher
         #[clap(verbatim_doc_comment)]
         iri: Option<String>,
         // The type is str, not Iri so that a better error can be reported in some cases
@@ -1507,15 +2252,38 @@ pub struct IndexRemoveTarget {
 
 #[derive(clap::Args, Debug, Clone)]
 pub struct InstallOptions {
-    /// Allow overwriting existing installation
+    /// Allow overwriting existing installation. Same as `--overwrite replace`
     #[arg(long)]
     pub allow_overwrite: bool,
+    /// What to do if the version is already installed. Identical
+    /// versions (same checksum) are kept unless the policy is `replace`
+    #[arg(
+        long,
+        value_name = "POLICY",
+        conflicts_with = "allow_overwrite",
+        verbatim_doc_comment
+    )]
+    pub overwrite: Option<OverwritePolicyCli>,
     /// Install even if another version is already installed
     #[arg(long)]
     pub allow_multiple: bool,
     /// Don't install any dependencies
     #[arg(long)]
     pub no_deps: bool,
+    /// Install only `.project.json` and `.meta.json` of the projects.
+    /// Their other files are fetched when they are first read
+    #[arg(long, conflicts_with = "path", verbatim_doc_comment)]
+    pub metadata_only: bool,
+}
+
+impl InstallOptions {
+    pub fn overwrite_policy(&self) -> OverwritePolicy {
+        match self.overwrite {
+            Some(policy) => policy.into(),
+            None if self.allow_overwrite => OverwritePolicy::Replace,
+            None => OverwritePolicy::Error,
+        }
+    }
 }
 
 /// Control how packages and their dependencies are resolved.
@@ -1564,13 +2332,38 @@ pub struct ResolutionOptions {
     )]
     pub no_index: bool,
     /// Don't ignore KerML/SysML v2 standard libraries if specified as dependencies
+    /// (default: `include_std` from config, or false)
     #[arg(
         long,
         default_value_t = false,
         global = true,
-        help_heading = "Resolution options"
+        help_heading = "Resolution options",
+        verbatim_doc_comment
     )]
     pub include_std: bool,
+    /// Ignore KerML/SysML v2 standard libraries even if `include_std`
+    /// is set in config
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with = "include_std",
+        global = true,
+        help_heading = "Resolution options",
+        verbatim_doc_comment
+    )]
+    pub no_include_std: bool,
+}
+
+impl ResolutionOptions {
+    pub fn include_std_mode(&self, config: &Config) -> IncludeStd {
+        include_std_mode(self.include_std, self.no_include_std, config)
+    }
+
+    /// Whether standard libraries are ignored only by default, in which
+    /// case a warning tells how to include them
+    pub fn ignores_std_by_default(&self, config: &Config) -> bool {
+        !self.include_std && !self.no_include_std && config.include_std.is_none()
+    }
 }
 
 #[derive(clap::Args, Debug, Clone)]
@@ -1617,21 +2410,62 @@ pub struct SourcesOptions {
     #[arg(long, default_value_t = false, conflicts_with = "include_std")]
     pub no_deps: bool,
     /// Include (installed) KerML/SysML v2 standard libraries
-    #[arg(long, default_value_t = false)]
+    /// (default: `include_std` from config, or false)
+    #[arg(long, default_value_t = false, verbatim_doc_comment)]
     pub include_std: bool,
+    /// Omit KerML/SysML v2 standard libraries even if `include_std`
+    /// is set in config
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with = "include_std",
+        verbatim_doc_comment
+    )]
+    pub no_include_std: bool,
+    /// Extract the files of installed projects stored as KPARs
+    /// (`env.storage = "kpar"` in config), so that the listed paths exist
+    #[arg(long, default_value_t = false, verbatim_doc_comment)]
+    pub materialize: bool,
+}
+
+impl SourcesOptions {
+    pub fn include_std_mode(&self, config: &Config) -> IncludeStd {
+        include_std_mode(self.include_std, self.no_include_std, config)
+    }
+
+    /// Whether standard libraries are ignored only by default, in which
+    /// case a warning tells how to include them
+    pub fn ignores_std_by_default(&self, config: &Config) -> bool {
+        !self.include_std && !self.no_include_std && config.include_std.is_none()
+    }
+}
+
+/// `--include-std` or `--no-include-std` if given, otherwise `include_std`
+/// from `config`
+fn include_std_mode(include_std: bool, no_include_std: bool, config: &Config) -> IncludeStd {
+    if include_std {
+        IncludeStd::Always
+    } else if no_include_std {
+        IncludeStd::Never
+    } else {
+        config.include_std.unwrap_or(IncludeStd::Never)
+    }
 }
 
 #[derive(clap::Args, Debug)]
 pub struct GlobalOptions {
-    /// Use verbose output
+    /// Use verbose output. Repeat for more detail:
+    /// `-v` for debug and `-vv` for trace messages
     #[arg(
         long,
         short,
+        action = clap::ArgAction::Count,
         group = "log-level",
         global = true,
-        help_heading = "Global options"
+        help_heading = "Global options",
+        verbatim_doc_comment
     )]
-    pub verbose: bool,
+    pub verbose: u8,
     /// Do not output log messages
     #[arg(
         long,
@@ -1644,14 +2478,310 @@ pub struct GlobalOptions {
     /// Disable discovery of configuration files
     #[arg(long, global = true, help_heading = "Global options", env = env_vars::SYSAND_NO_CONFIG)]
     pub no_config: bool,
+    /// Do not use or update the caches of project metadata fetched over
+    /// HTTP and of git repositories
+    #[arg(long, global = true, help_heading = "Global options", env = env_vars::SYSAND_NO_CACHE)]
+    pub no_cache: bool,
+    /// Do not run lifecycle hooks configured under `[hooks]` in
+    /// `sysand.toml`, e.g. in CI
+    #[arg(long, global = true, help_heading = "Global options", env = env_vars::SYSAND_NO_HOOKS)]
+    pub no_hooks: bool,
     /// Give path to `sysand.toml` to use for configuration
     #[arg(long, global = true, help_heading = "Global options", env = env_vars::SYSAND_CONFIG_FILE)]
     pub config_file: Option<String>,
+    /// Marker that conditional usages are evaluated against when locking,
+    /// e.g. `tool=syside`. Takes precedence over `[lock] markers` in
+    /// `sysand.toml`. `metamodel` defaults to the one of the current project
+    #[arg(
+        long = "marker",
+        value_name = "ATTRIBUTE=VALUE",
+        value_parser = parse_marker,
+        global = true,
+        help_heading = "Global options",
+        verbatim_doc_comment
+    )]
+    pub markers: Vec<(String, String)>,
+    /// Format of errors and log messages printed to stderr. With `json`,
+    /// each message is a JSON object on its own line. Errors include a
+    /// stable code (e.g. `SYSAND-E0101`) and the exit code is determined by
+    /// the class of the error:
+    /// 1 other, 2 usage, 3 project, 4 resolution, 5 environment, 6 build,
+    /// 7 index, 8 publish, 9 io, 10 network
+    #[arg(
+        long,
+        global = true,
+        default_value_t = ErrorFormat::Human,
+        value_enum,
+        help_heading = "Global options",
+        verbatim_doc_comment
+    )]
+    pub error_format: ErrorFormat,
+    /// Format of log messages printed to stderr (default: `--error-format`).
+    /// With `json`, messages about resolution, downloads and installation
+    /// carry their details, e.g. request URLs and timings, in a `fields`
+    /// object
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "FORMAT",
+        help_heading = "Global options",
+        verbatim_doc_comment
+    )]
+    pub log_format: Option<ErrorFormat>,
+    /// Format of command results printed to stdout. With `json`, each
+    /// result is a JSON value on its own line, e.g. one per listed project
+    #[arg(
+        long,
+        global = true,
+        default_value_t = ErrorFormat::Human,
+        value_enum,
+        value_name = "FORMAT",
+        help_heading = "Global options",
+        verbatim_doc_comment
+    )]
+    pub output_format: ErrorFormat,
+    /// Summarise where time was spent, how much was downloaded from each
+    /// host and how often caches were hit. With `json`, the summary is
+    /// written to `sysand-timings.json` in the current directory
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        default_missing_value = "human",
+        help_heading = "Global options"
+    )]
+    pub timings: Option<TimingsFormat>,
     /// Print help
     #[arg(long, short, global = true, action = clap::ArgAction::HelpLong, help_heading = "Global options")]
     pub help: Option<bool>,
 }
 
+/// Flags and commands that were renamed. The old names keep working for
+/// a release cycle (see [`rewrite_deprecated`]); remove an entry once the
+/// release given in `removed_in` is out. Codes are never reused.
+pub const DEPRECATIONS: &[Deprecation] = &[
+    Deprecation {
+        code: "D0001",
+        kind: DeprecatedName::Flag,
+        command: &["info"],
+        old: "uri",
+        new: "iri",
+        removed_in: "0.2.0",
+    },
+    Deprecation {
+        code: "D0002",
+        kind: DeprecatedName::Flag,
+        command: &["info"],
+        old: "url",
+        new: "iri",
+        removed_in: "0.2.0",
+    },
+    Deprecation {
+        code: "D0003",
+        kind: DeprecatedName::Flag,
+        command: &["clone"],
+        old: "uri",
+        new: "iri",
+        removed_in: "0.2.0",
+    },
+    Deprecation {
+        code: "D0004",
+        kind: DeprecatedName::Flag,
+        command: &["clone"],
+        old: "url",
+        new: "iri",
+        removed_in: "0.2.0",
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeprecatedName {
+    /// Long flag, without leading `--`
+    Flag,
+    /// (Sub)command
+    Command,
+}
+
+/// A renamed flag or command.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Deprecation {
+    /// Stable identifier of the warning, e.g. `D0001`, so that scripts can
+    /// match on it
+    pub code: &'static str,
+    pub kind: DeprecatedName,
+    /// Names of the (sub)commands leading to the flag or command, e.g.
+    /// `["index"]` for `sysand index <old>`. A flag applies to these
+    /// commands and all their subcommands
+    pub command: &'static [&'static str],
+    pub old: &'static str,
+    pub new: &'static str,
+    /// First release in which the old name no longer works
+    pub removed_in: &'static str,
+}
+
+impl Deprecation {
+    fn display_name(&self, name: &str) -> String {
+        match self.kind {
+            DeprecatedName::Flag => format!("--{name}"),
+            DeprecatedName::Command => {
+                let mut full = String::from("sysand");
+                for command in self.command {
+                    full.push(' ');
+                    full.push_str(command);
+                }
+                full.push(' ');
+                full.push_str(name);
+                full
+            }
+        }
+    }
+
+    /// Emit the deprecation warning. JSON logs carry the code in `code` and
+    /// the names in the `old`, `new` and `removed_in` fields
+    pub fn warn(&self) {
+        let (old, new) = (self.display_name(self.old), self.display_name(self.new));
+        log::warn!(
+            code = self.code, old = old.as_str(), new = new.as_str(), removed_in = self.removed_in;
+            "`{old}` is deprecated and will be removed in sysand {},\n\
+            {:>8} use `{new}` instead",
+            self.removed_in,
+            ' ',
+        );
+    }
+}
+
+/// Whether `args` ask for JSON diagnostics. Used to format errors in
+/// arguments themselves, so it must not rely on them being valid.
+pub fn requests_json_errors(args: &[OsString]) -> bool {
+    let mut args = args.iter().skip(1).map(|a| a.to_str());
+    while let Some(arg) = args.next() {
+        match arg {
+            Some("--") => break,
+            Some("--error-format=json") => return true,
+            Some("--error-format") if args.next() == Some(Some("json")) => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Replace deprecated flag and command names in `args` by their new names.
+/// Returns the rewritten arguments and the deprecations that were used.
+///
+/// Arguments are matched against the clap command tree, so option values
+/// and positional arguments are never rewritten, even if they happen to
+/// equal a deprecated name. Nothing after `--` is rewritten.
+pub fn rewrite_deprecated<I, T>(
+    args: I,
+    deprecations: &[Deprecation],
+) -> (Vec<OsString>, Vec<&Deprecation>)
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString>,
+{
+    let mut args = args.into_iter().map(Into::into);
+    let mut rewritten: Vec<OsString> = args.next().into_iter().collect();
+    let mut used = vec![];
+    if deprecations.is_empty() {
+        rewritten.extend(args);
+        return (rewritten, used);
+    }
+
+    let mut root = <Args as clap::CommandFactory>::command();
+    root.build();
+    let mut current = &root;
+    let mut path: Vec<&str> = vec![];
+
+    while let Some(arg) = args.next() {
+        let Some(arg_str) = arg.to_str() else {
+            rewritten.push(arg);
+            continue;
+        };
+
+        if arg_str == "--" {
+            rewritten.push(arg);
+            break;
+        }
+
+        let takes_value = if let Some(flag) = arg_str.strip_prefix("--") {
+            let (name, value) = match flag.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (flag, None),
+            };
+            let name = match deprecations.iter().find(|d| {
+                d.kind == DeprecatedName::Flag && d.old == name && path.starts_with(d.command)
+            }) {
+                Some(deprecation) => {
+                    used.push(deprecation);
+                    rewritten.push(match value {
+                        Some(value) => format!("--{}={value}", deprecation.new).into(),
+                        None => format!("--{}", deprecation.new).into(),
+                    });
+                    deprecation.new
+                }
+                None => {
+                    rewritten.push(arg.clone());
+                    name
+                }
+            };
+            value.is_none()
+                && current
+                    .get_arguments()
+                    .find(|a| {
+                        a.get_long_and_visible_aliases()
+                            .is_some_and(|longs| longs.contains(&name))
+                    })
+                    .is_some_and(|a| a.get_action().takes_values())
+        } else if let Some(shorts) = arg_str.strip_prefix('-')
+            && !shorts.is_empty()
+        {
+            rewritten.push(arg.clone());
+            // In a group like `-qi`, the first flag taking a value consumes
+            // the rest of the group or, if it is last, the next argument
+            let takes_value = |c: char| {
+                current.get_arguments().any(|a| {
+                    a.get_short_and_visible_aliases()
+                        .is_some_and(|shorts| shorts.contains(&c))
+                        && a.get_action().takes_values()
+                })
+            };
+            shorts
+                .char_indices()
+                .find(|&(_, c)| takes_value(c))
+                .is_some_and(|(i, c)| i + c.len_utf8() == shorts.len())
+        } else {
+            let name = match deprecations.iter().find(|d| {
+                d.kind == DeprecatedName::Command && d.old == arg_str && d.command == path
+            }) {
+                Some(deprecation) => {
+                    used.push(deprecation);
+                    rewritten.push(deprecation.new.into());
+                    deprecation.new
+                }
+                None => {
+                    rewritten.push(arg.clone());
+                    arg_str
+                }
+            };
+            if let Some(subcommand) = current.find_subcommand(name) {
+                current = subcommand;
+                path.push(subcommand.get_name());
+            }
+            false
+        };
+
+        if takes_value && let Some(value) = args.next() {
+            rewritten.push(value);
+        }
+    }
+
+    rewritten.extend(args);
+    (rewritten, used)
+}
+
 /// Parse an IRI. Tolerates missing IRI scheme, uses
 /// `https://` scheme in that case.
 fn parse_https_iri(s: &str) -> Result<fluent_uri::Iri<String>, fluent_uri::ParseError> {
@@ -1764,18 +2894,45 @@ impl ValueEnum for MetamodelVersion {
     }
 }
 
-fn parse_usage_locator_suggest_path(s: &str) -> Result<Iri<String>, String> {
-    use crate::style::USAGE;
+fn parse_marker(s: &str) -> Result<(String, String), String> {
+    sysand_core::solve::markers::parse_marker(s).map_err(|e| e.to_string())
+}
+
+fn parse_usage_iri(s: &str) -> Result<Iri<String>, String> {
     match Iri::parse(s) {
         Ok(i) => Ok(i.to_owned()),
         Err(err) => match expand_sysand_purl_shorthand(s) {
             Ok(Some(purl)) => Ok(Iri::parse(purl).expect("BUG: Sysand PURL is invalid IRI")),
-            Ok(None) => Err(format!(
-                "{err}\n{USAGE}hint:{USAGE:#} if you wanted to use a path, use `--path` instead"
-            )),
-            Err(e) => Err(format!(
-                "{e}\n{USAGE}hint:{USAGE:#} if you wanted to use a path, use `--path` instead"
-            )),
+            Ok(None) => Err(err.to_string()),
+            Err(e) => Err(e.to_string()),
         },
     }
 }
+
+/// Project to add, given either exactly or as a name to search for
+#[derive(Clone, Debug)]
+pub enum AddLocator {
+    Iri(Iri<String>),
+    Search(String),
+}
+
+fn parse_add_locator(s: &str) -> Result<AddLocator, String> {
+    match parse_usage_locator_suggest_path(s) {
+        Ok(iri) => Ok(AddLocator::Iri(iri)),
+        Err(_)
+            if !s.is_empty()
+                && s.chars()
+                    .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.')) =>
+        {
+            Ok(AddLocator::Search(s.to_owned()))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn parse_usage_locator_suggest_path(s: &str) -> Result<Iri<String>, String> {
+    use crate::style::USAGE;
+    parse_usage_iri(s).map_err(|e| {
+        format!("{e}\n{USAGE}hint:{USAGE:#} if you wanted to use a path, use `--path` instead")
+    })
+}
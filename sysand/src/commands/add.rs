@@ -3,39 +3,60 @@
 
 use std::{collections::HashMap, path::Path, sync::Arc};
 
-use anyhow::{Result, bail};
+use anyhow::{Result, anyhow, bail};
 use camino::{Utf8Path, Utf8PathBuf};
 
 use fluent_uri::Iri;
 use sysand_core::{
     add::do_add,
     auth::HTTPAuthentication,
-    commands::lock::{DEFAULT_LOCKFILE_NAME, LockOutcome, do_lock_local_editable},
+    commands::{
+        lock::{do_lock_local_editable, do_lock_workspace},
+        sync::SyncFilter,
+    },
     config::{
         Config, ConfigProject, OverrideSource,
-        local_fs::{CONFIG_FILE, add_project_source_to_config},
+        local_fs::{
+            CONFIG_FILE, add_project_source_to_config, set_project_variant_in_config,
+            set_usage_reason_in_config,
+        },
     },
     context::ProjectContext,
+    env::{ReadEnvironment, ReadEnvironmentAsync, index::IndexEnvironmentAsync},
+    iri_normalize::normalize_iri_tolerant,
     model::InterchangeProjectUsageRaw,
     project::{
         ProjectRead,
         utils::{relativize_path, wrapfs},
     },
-    resolve::{ResolutionOutcome, ResolveRead, standard::standard_resolver},
+    resolve::{ResolutionOutcome, ResolveRead, git_cache::GitCache, standard::standard_resolver},
+    search::do_search,
     utils::format_err,
 };
 
 use crate::{
     CliError, DEFAULT_INDEX_URL,
     cli::{ProjectSourceOptions, ResolutionOptions},
-    commands::{lock::create_resolver, sync::command_sync},
+    commands::{
+        lock::{create_resolver, write_lockfile},
+        sync::command_sync,
+    },
+    error::CodedResultExt,
+    prompt_select, shared_lock_workspace,
 };
 
+/// How many matching projects are offered when searching for a project
+const MAX_SEARCH_MATCHES: usize = 10;
+
 // TODO: Collect common arguments
 #[allow(clippy::too_many_arguments)]
 pub fn command_add<Policy: HTTPAuthentication>(
     iri: Iri<String>,
     version_constraint: Option<String>,
+    variant: Option<String>,
+    reason: Option<String>,
+    when: Option<String>,
+    hash: Option<String>,
     no_lock: bool,
     no_sync: bool,
     resolution_opts: ResolutionOptions,
@@ -45,10 +66,13 @@ pub fn command_add<Policy: HTTPAuthentication>(
     no_config: bool,
     ctx: ProjectContext,
     client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
     runtime: Arc<tokio::runtime::Runtime>,
     auth_policy: Arc<Policy>,
 ) -> Result<()> {
-    let iri = iri.as_ref();
+    // Equivalent spellings of an IRI are recorded the same everywhere
+    let iri = normalize_iri_tolerant(iri.as_str());
+    let iri = iri.as_str();
     let mut current_project = ctx
         .current_project
         .clone()
@@ -78,6 +102,7 @@ pub fn command_add<Policy: HTTPAuthentication>(
             default_index,
             no_index,
             include_std: _,
+            no_include_std: _,
         } = resolution_opts.clone();
 
         let index_urls = if no_index {
@@ -91,9 +116,12 @@ pub fn command_add<Policy: HTTPAuthentication>(
             None,
             Some(client.clone()),
             index_urls,
+            vec![],
             runtime.clone(),
             auth_policy.clone(),
-        )?;
+        )?
+        .with_git_cache(git_cache.clone())
+        .with_index_scopes(&config.indexes)?;
         let outcome = std_resolver.resolve_read(&url)?;
         let mut source = None;
         match outcome {
@@ -166,13 +194,13 @@ pub fn command_add<Policy: HTTPAuthentication>(
         None
     };
 
-    if let Some(source) = source {
-        let config_path = config_file
-            .map(Utf8PathBuf::from)
-            .or((!no_config).then(|| current_project.root_path().join(CONFIG_FILE)));
+    let config_path = config_file
+        .map(Utf8PathBuf::from)
+        .or((!no_config).then(|| current_project.root_path().join(CONFIG_FILE)));
 
-        if let Some(path) = config_path {
-            add_project_source_to_config(&path, iri, &source)?;
+    if let Some(source) = source {
+        if let Some(path) = &config_path {
+            add_project_source_to_config(path, iri, &source)?;
         } else {
             log::warn!("project source for `{iri}` not added to any config file");
         }
@@ -183,26 +211,46 @@ pub fn command_add<Policy: HTTPAuthentication>(
         });
     }
 
-    let provided_iris = if !resolution_opts.include_std {
-        let sysml_std = crate::known_std_libs();
-        if sysml_std.contains_key(iri) {
-            crate::logger::warn_std(iri);
-            return Ok(());
+    if let Some(variant) = variant {
+        if let Some(path) = &config_path {
+            set_project_variant_in_config(path, iri, &variant)?;
+        } else {
+            log::warn!("variant of `{iri}` not added to any config file");
         }
-        sysml_std
-    } else {
-        HashMap::default()
-    };
+
+        config.variants.insert(iri.to_owned(), variant);
+    }
+
+    if let Some(reason) = reason {
+        if let Some(path) = &config_path {
+            set_usage_reason_in_config(path, iri, &reason)?;
+        } else {
+            log::warn!("reason for `{iri}` not added to any config file");
+        }
+
+        config.reasons.insert(iri.to_owned(), reason);
+    }
+
+    let provided_iris = crate::ignored_std_libs(
+        resolution_opts.include_std_mode(&config),
+        Some(&current_project),
+    );
+    if provided_iris.contains_key(iri) {
+        crate::logger::warn_std(iri);
+        return Ok(());
+    }
 
     let usage_raw = InterchangeProjectUsageRaw::Resource {
         resource: iri.to_owned(),
         version_constraint,
+        when,
+        hash,
     };
 
     if !no_lock {
         let info_path = current_project.info_path();
         let info_backup = wrapfs::read_to_string(&info_path)?;
-        let added = do_add(&mut current_project, &usage_raw)?;
+        let added = do_add(&mut current_project, &usage_raw).coded()?;
         if !added {
             return Ok(());
         }
@@ -221,6 +269,7 @@ pub fn command_add<Policy: HTTPAuthentication>(
             resolution_opts,
             &config,
             client,
+            git_cache,
             runtime,
             auth_policy,
             current_project.root_path(),
@@ -236,17 +285,97 @@ pub fn command_add<Policy: HTTPAuthentication>(
             }
         }
     } else {
-        do_add(&mut current_project, &usage_raw)?;
+        do_add(&mut current_project, &usage_raw).coded()?;
         Ok(())
     }
 }
 
+/// Search the indexes for projects matching `query` and ask which one to
+/// add. Without a `version_constraint`, the chosen project is added with a
+/// caret constraint on its latest version. When not running in a
+/// terminal, fails listing the matching projects instead
+pub fn select_index_project<Policy: HTTPAuthentication>(
+    query: &str,
+    version_constraint: Option<String>,
+    resolution_opts: ResolutionOptions,
+    config: &Config,
+    client: reqwest_middleware::ClientWithMiddleware,
+    runtime: Arc<tokio::runtime::Runtime>,
+    auth_policy: Arc<Policy>,
+) -> Result<(Iri<String>, Option<String>)> {
+    use std::io::IsTerminal;
+
+    let ResolutionOptions {
+        index,
+        default_index,
+        no_index,
+        include_std: _,
+        no_include_std: _,
+    } = resolution_opts;
+    if no_index {
+        bail!("`{query}` is not an IRI, and indexes cannot be searched for it with `--no-index`");
+    }
+    let envs: Vec<_> = config
+        .index_urls(index, vec![DEFAULT_INDEX_URL.to_string()], default_index)?
+        .into_iter()
+        .map(|url| {
+            IndexEnvironmentAsync::from_discovery_root(client.clone(), auth_policy.clone(), url)
+                .to_tokio_sync(runtime.clone())
+        })
+        .collect();
+
+    let mut matches = do_search(query, &envs).coded()?;
+    matches.truncate(MAX_SEARCH_MATCHES);
+    let hint = crate::style::USAGE;
+    if matches.is_empty() {
+        bail!(
+            "`{query}` is not an IRI, and no projects matching it were found in the indexes\n\
+            {hint}hint:{hint:#} if you wanted to use a path, use `--path` instead"
+        );
+    }
+    let iri = if std::io::stdin().is_terminal() && std::io::stderr().is_terminal() {
+        match prompt_select("Select the project to add", &matches)? {
+            Some(i) => matches.swap_remove(i),
+            None => bail!("no project selected"),
+        }
+    } else {
+        bail!(
+            "`{query}` is not an IRI; projects matching it:\n{}\n\
+            {hint}hint:{hint:#} pass the IRI of the project to add",
+            matches
+                .iter()
+                .map(|m| format!("{:>4}{m}", ' '))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    };
+
+    let version_constraint = match version_constraint {
+        Some(version_constraint) => Some(version_constraint),
+        None => {
+            let mut latest = None;
+            for env in &envs {
+                if let Some(version) = env.versions(&iri)?.next() {
+                    latest = Some(version?);
+                    break;
+                }
+            }
+            latest.map(|version| format!("^{version}"))
+        }
+    };
+    let iri =
+        Iri::parse(iri).map_err(|(e, iri)| anyhow!("index lists invalid IRI `{iri}`: {e}"))?;
+
+    Ok((iri, version_constraint))
+}
+
 #[expect(clippy::too_many_arguments)]
 fn resolve_deps<P: AsRef<Utf8Path>, Policy: HTTPAuthentication>(
     no_sync: bool,
     resolution_opts: ResolutionOptions,
     config: &Config,
     client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
     runtime: Arc<tokio::runtime::Runtime>,
     auth_policy: Arc<Policy>,
     project_root: P,
@@ -261,39 +390,51 @@ fn resolve_deps<P: AsRef<Utf8Path>, Policy: HTTPAuthentication>(
         &ctx,
         provided_iris.clone(),
         client.clone(),
+        git_cache.clone(),
         runtime.clone(),
         auth_policy.clone(),
     )?;
-    // FIXME: use project path relative to and under the workspace root.
-    let LockOutcome { lock, .. } = do_lock_local_editable(
-        ".",
-        &project_root,
-        project_identifiers,
-        &provided_iris,
-        resolver,
-        &ctx,
-    )?;
+    let (lock, lock_root) = if let Some(workspace) = shared_lock_workspace(config, &ctx) {
+        let mut outcome = do_lock_workspace(workspace, &provided_iris, resolver, &ctx).coded()?;
+        outcome.select_variants(&config.variants).coded()?;
+        (outcome.lock, workspace.root_path().to_owned())
+    } else {
+        // FIXME: use project path relative to and under the workspace root.
+        let mut outcome = do_lock_local_editable(
+            ".",
+            &project_root,
+            project_identifiers,
+            &provided_iris,
+            resolver,
+            &ctx,
+        )
+        .coded()?;
+        outcome.select_variants(&config.variants).coded()?;
+        (outcome.lock, project_root.as_ref().to_owned())
+    };
     let lock = lock.canonicalize();
-    wrapfs::write(
-        project_root.as_ref().join(DEFAULT_LOCKFILE_NAME),
-        lock.to_string(),
-    )?;
+    write_lockfile(&lock_root, &lock, config.lock.format.unwrap_or_default())?;
     if !no_sync {
         let mut env = crate::get_or_create_env(
             ctx.env,
             ctx.current_workspace.as_ref(),
             ctx.current_project.as_ref(),
             ctx.current_directory,
+            config,
         )?;
         command_sync(
             &lock,
-            project_root,
+            lock_root,
             &mut env,
+            config,
             client,
+            git_cache,
             &provided_iris,
             runtime,
             auth_policy,
             ctx.current_workspace.as_ref(),
+            false,
+            &SyncFilter::default(),
         )?;
     }
     Ok(())
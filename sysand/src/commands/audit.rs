@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use camino::Utf8Path;
+use serde_json::json;
+use sysand_core::output::OutputSink;
+use sysand_core::{
+    auth::HTTPAuthentication, commands::audit::do_audit_async, env::index::IndexEnvironmentAsync,
+};
+use url::Url;
+
+use crate::{
+    commands::lock::{lockfile_path, read_lockfile},
+    error::CodedResultExt,
+};
+
+/// List the projects of the lockfile in `lock_root` that any of the
+/// indexes at `index_urls` declares deprecated
+pub fn command_audit<P: AsRef<Utf8Path>, Policy: HTTPAuthentication>(
+    lock_root: P,
+    index_urls: Vec<Url>,
+    client: reqwest_middleware::ClientWithMiddleware,
+    runtime: Arc<tokio::runtime::Runtime>,
+    auth_policy: Arc<Policy>,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    let lockfile = lockfile_path(lock_root)?;
+    if !lockfile.is_file() {
+        bail!("lockfile `{lockfile}` not found, run `sysand lock` to create it");
+    }
+    let lock = read_lockfile(&lockfile)?;
+    if index_urls.is_empty() {
+        log::warn!("no indexes to check the lockfile against");
+    }
+    let indexes: Vec<_> = index_urls
+        .into_iter()
+        .map(|url| {
+            IndexEnvironmentAsync::from_discovery_root(client.clone(), auth_policy.clone(), url)
+        })
+        .collect();
+
+    let deprecated = runtime.block_on(do_audit_async(&lock, &indexes)).coded()?;
+    let header = sysand_core::style::get_style_config().header;
+    if deprecated.is_empty() {
+        log::info!(
+            "{header}{:>12}{header:#} {} projects, none deprecated",
+            "Audited",
+            lock.projects.len()
+        );
+        return Ok(());
+    }
+    for project in deprecated {
+        let replacement = match &project.replaced_by {
+            Some(replaced_by) => format!(", use `{replaced_by}` instead"),
+            None => String::new(),
+        };
+        out.emit(
+            &format!(
+                "{} {} (`{}`) is deprecated{replacement}",
+                project.name, project.version, project.iri
+            ),
+            json!({
+                "name": project.name,
+                "version": project.version,
+                "iri": project.iri,
+                "replaced_by": project.replaced_by,
+            }),
+        )?;
+    }
+    Ok(())
+}
@@ -1,45 +1,98 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
-use anyhow::{Result, bail};
+use anyhow::Result;
 use camino::Utf8Path;
 use sysand_core::{
-    build::{KParBuildError, KparCompressionMethod, do_build_kpar, do_build_workspace_kpars},
+    build::{
+        CommandPostProcessor, KParBuildError, KparCompression, KparManifest, KparPostProcessor,
+        do_build_kpar_with_manifest, do_build_workspace_kpars, do_check_reproducible_kpar,
+        post_process_kpar,
+    },
+    config::PostBuildHook,
+    error_code::HasErrorCode,
+    parallel::Jobs,
     project::local_src::LocalSrcProject,
     workspace::Workspace,
 };
 
+use crate::error::{Coded, CodedResultExt};
+
+#[allow(clippy::too_many_arguments)]
 pub fn command_build_for_project<P: AsRef<Utf8Path>>(
     path: P,
-    compression: KparCompressionMethod,
+    compression: KparCompression,
     current_project: LocalSrcProject,
     update_index: bool,
     allow_path_usage: bool,
+    manifest: &KparManifest,
+    check_reproducible: bool,
+    post_build: &[PostBuildHook],
+    jobs: Jobs,
 ) -> Result<()> {
-    match do_build_kpar(
+    match do_build_kpar_with_manifest(
         &current_project,
         &path,
         compression,
         update_index,
         allow_path_usage,
+        manifest,
     ) {
-        Ok(_) => Ok(()),
+        Ok(kpar) => {
+            if check_reproducible {
+                do_check_reproducible_kpar(
+                    &current_project,
+                    &path,
+                    compression,
+                    update_index,
+                    allow_path_usage,
+                    manifest,
+                    None,
+                )
+                .coded()?;
+            }
+            let processors: Vec<_> = post_build.iter().map(CommandPostProcessor::from).collect();
+            let processors: Vec<_> = processors
+                .iter()
+                .map(|p| p as &dyn KparPostProcessor)
+                .collect();
+            post_process_kpar(kpar, &processors, Some(&current_project.project_path), jobs)?;
+            Ok(())
+        }
         Err(err) => match err {
-            KParBuildError::PathUsage(_) => bail!(
-                "{err}\n\
-                to build anyway, pass `--allow-path-usage`"
-            ),
-            _ => bail!(err),
+            KParBuildError::PathUsage(_) => Err(Coded::new(
+                err.error_code(),
+                format!(
+                    "{err}\n\
+                    to build anyway, pass `--allow-path-usage`"
+                ),
+            )
+            .into()),
+            KParBuildError::Dirty { .. } => Err(Coded::new(
+                err.error_code(),
+                format!(
+                    "{err}\n\
+                    update the metadata with `sysand include`/`sysand exclude`,\n\
+                    or pass `--allow-dirty` to package the selected files"
+                ),
+            )
+            .into()),
+            _ => Err(Coded::new(err.error_code(), err).into()),
         },
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn command_build_for_workspace<P: AsRef<Utf8Path>>(
     path: P,
-    compression: KparCompressionMethod,
+    compression: KparCompression,
     workspace: Workspace,
     update_index: bool,
     allow_path_usage: bool,
+    manifest: &KparManifest,
+    check_reproducible: bool,
+    post_build: &[PostBuildHook],
+    jobs: Jobs,
 ) -> Result<()> {
     log::warn!(
         "Workspaces are an experimental feature\n\
@@ -47,13 +100,45 @@ pub fn command_build_for_workspace<P: AsRef<Utf8Path>>(
         releases. For the status of this feature, see\n\
         https://github.com/sensmetry/sysand/issues/101."
     );
-    do_build_workspace_kpars(
+    let kpars = do_build_workspace_kpars(
         &workspace,
         &path,
         compression,
         update_index,
         allow_path_usage,
-    )?;
+        manifest,
+    )
+    .coded()?;
+
+    if check_reproducible {
+        for (kpar, project) in kpars.iter().zip(workspace.projects()) {
+            let project = LocalSrcProject {
+                nominal_path: None,
+                project_path: workspace.root_path().join(&project.path),
+                expected_checksum: None,
+            };
+            do_check_reproducible_kpar(
+                &project,
+                kpar.archive_path(),
+                compression,
+                update_index,
+                allow_path_usage,
+                manifest,
+                Some(&workspace),
+            )
+            .coded()?;
+        }
+    }
+
+    let processors: Vec<_> = post_build.iter().map(CommandPostProcessor::from).collect();
+    let processors: Vec<_> = processors
+        .iter()
+        .map(|p| p as &dyn KparPostProcessor)
+        .collect();
+    for (kpar, project) in kpars.into_iter().zip(workspace.projects()) {
+        let project_root = workspace.root_path().join(&project.path);
+        post_process_kpar(kpar, &processors, Some(&project_root), jobs)?;
+    }
 
     Ok(())
 }
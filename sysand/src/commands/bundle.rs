@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use anyhow::{Result, bail};
+use camino::{Utf8Path, Utf8PathBuf};
+use sysand_core::{
+    bundle::{BundleFormat, do_bundle, do_bundle_install},
+    env::local_directory::LocalDirectoryEnvironment,
+};
+
+use crate::{
+    commands::lock::{lockfile_path, read_lockfile},
+    error::CodedResultExt,
+};
+
+/// Bundle the projects in the lockfile in `lock_root` into `output`
+pub fn command_bundle<P: AsRef<Utf8Path>>(
+    lock_root: P,
+    env: Option<LocalDirectoryEnvironment>,
+    output: Option<Utf8PathBuf>,
+    format: BundleFormat,
+) -> Result<()> {
+    let lock_root = lock_root.as_ref();
+    let lockfile = lockfile_path(lock_root)?;
+    if !lockfile.is_file() {
+        bail!("lockfile `{lockfile}` not found, run `sysand sync` to create it");
+    }
+    let lock = read_lockfile(&lockfile)?;
+    let Some(env) = env else {
+        bail!("unable to identify environment to bundle from, run `sysand sync` first");
+    };
+    let output = output.unwrap_or_else(|| match format {
+        BundleFormat::Zip => "sysand-bundle.zip".into(),
+        BundleFormat::Directory => "sysand-bundle".into(),
+    });
+
+    let manifest = do_bundle(&lock, lock_root, &env, &output, format).coded()?;
+
+    let bundled = "Bundled";
+    let header = sysand_core::style::get_style_config().header;
+    log::info!(
+        "{header}{bundled:>12}{header:#} {} project(s) into `{output}`",
+        manifest.projects.len()
+    );
+    Ok(())
+}
+
+/// Install the projects of the bundle at `bundle` into `env`
+pub fn command_bundle_install<P: AsRef<Utf8Path>>(
+    bundle: P,
+    mut env: LocalDirectoryEnvironment,
+) -> Result<()> {
+    let installed = do_bundle_install(bundle.as_ref(), &mut env).coded()?;
+
+    let finished = "Installed";
+    let header = sysand_core::style::get_style_config().header;
+    log::info!(
+        "{header}{finished:>12}{header:#} {} project(s) from `{}`",
+        installed.len(),
+        bundle.as_ref()
+    );
+    Ok(())
+}
@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use anyhow::{Result, bail};
+use camino::Utf8PathBuf;
+
+use sysand_core::resolve::{git_cache::GitCache, http_cache::HttpCache};
+
+/// Clear the caches in `cache_dir`. If neither `git` nor `http` is set,
+/// all of them are cleared
+pub fn command_cache_prune(cache_dir: Option<Utf8PathBuf>, git: bool, http: bool) -> Result<()> {
+    let Some(cache_dir) = cache_dir else {
+        bail!("unable to determine the cache directory, set `SYSAND_CACHE_DIR`");
+    };
+    let all = !git && !http;
+    let header = sysand_core::style::get_style_config().header;
+    let pruned = "Pruned";
+
+    if git || all {
+        let removed = GitCache::new(cache_dir.join("git")).prune()?;
+        log::info!("{header}{pruned:>12}{header:#} {removed} cached git repositories");
+    }
+    if http || all {
+        // TTL is irrelevant for pruning
+        let removed = HttpCache::new(cache_dir.join("http"), Default::default()).prune()?;
+        log::info!("{header}{pruned:>12}{header:#} {removed} cached HTTP responses");
+    }
+
+    Ok(())
+}
@@ -2,24 +2,37 @@
 // SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
 
 use anyhow::{Result, anyhow, bail};
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use fluent_uri::Iri;
 use semver::Version;
 
-use std::{collections::HashMap, fs, io::ErrorKind, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::ErrorKind,
+    sync::Arc,
+};
 
 use sysand_core::{
     auth::HTTPAuthentication,
-    commands::lock::{DEFAULT_LOCKFILE_NAME, LockOutcome},
-    config::Config,
+    commands::{lock::LockOutcome, sync::SyncFilter},
+    config::{
+        Config, ConfigProject, OverrideSource,
+        local_fs::{CONFIG_FILE, add_project_source_to_config, set_project_iri_in_config},
+    },
     context::ProjectContext,
     env::{local_directory::utils::clean_dir, utils::clone_project},
+    model::{InterchangeProjectInfoRaw, InterchangeProjectUsageRaw},
     project::{
-        ProjectRead, editable::EditableProject, local_kpar::LocalKParProjectRaw,
-        local_src::LocalSrcProject, utils::wrapfs,
+        ProjectRead,
+        editable::EditableProject,
+        local_kpar::LocalKParProjectRaw,
+        local_src::LocalSrcProject,
+        utils::{FsIoError, wrapfs},
     },
     resolve::{
         ResolutionOutcome, ResolveRead,
+        git_cache::GitCache,
         memory::{AcceptAll, MemoryResolver},
         priority::PriorityResolver,
         standard::{StandardResolver, standard_resolver},
@@ -29,7 +42,11 @@ use sysand_core::{
 use crate::{
     CliError, DEFAULT_INDEX_URL,
     cli::{CloneProjectLocatorArgs, ResolutionOptions},
-    commands::sync::command_sync,
+    commands::{
+        lock::{create_resolver, write_lockfile},
+        sync::command_sync,
+    },
+    error::CodedResultExt,
     get_or_create_env,
 };
 
@@ -38,7 +55,9 @@ pub enum ProjectLocator {
     Path(Utf8PathBuf),
 }
 
-/// Clones project from `locator` to `target` directory.
+/// Clones project from `locator` to `target` directory. With `workspace`,
+/// the project is cloned into a subdirectory of `target` and its locked
+/// dependencies into sibling directories.
 #[allow(clippy::too_many_arguments)]
 pub fn command_clone<Policy: HTTPAuthentication>(
     locator: CloneProjectLocatorArgs,
@@ -46,9 +65,11 @@ pub fn command_clone<Policy: HTTPAuthentication>(
     target: Option<Utf8PathBuf>,
     ctx: ProjectContext,
     no_deps: bool,
+    workspace: bool,
     resolution_opts: ResolutionOptions,
     config: &Config,
     client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
     runtime: Arc<tokio::runtime::Runtime>,
     auth_policy: Arc<Policy>,
 ) -> Result<()> {
@@ -77,17 +98,47 @@ pub fn command_clone<Policy: HTTPAuthentication>(
         canonical
     };
 
+    // The name of the project's directory in the workspace is only known
+    // once the project is cloned
+    let staging_dir = if workspace {
+        Some(
+            camino_tempfile::Builder::new()
+                .prefix(".clone")
+                .tempdir_in(&project_path)?,
+        )
+    } else {
+        None
+    };
+    let workspace_path = project_path.clone();
+
     let (include_std, locator, local_project, std_resolver) = match obtain_project(
         locator,
         version,
-        resolution_opts,
+        resolution_opts.clone(),
         config,
         &client,
+        git_cache.clone(),
         &runtime,
         auth_policy.clone(),
-        project_path,
+        staging_dir
+            .as_ref()
+            .map_or(project_path.clone(), |d| d.path().to_owned()),
+        &project_path,
         &ctx,
-    ) {
+    )
+    .and_then(|mut ret| {
+        if workspace {
+            let info = ret
+                .2
+                .get_info()?
+                .ok_or_else(|| anyhow!("missing project info"))?;
+            let path = workspace_path.join(directory_name(&info.name, &HashSet::new()));
+            fs::rename(&ret.2.project_path, &path)
+                .map_err(|e| FsIoError::Move(ret.2.project_path.clone(), path.clone(), e))?;
+            ret.2.project_path = path;
+        }
+        Ok(ret)
+    }) {
         Ok(ret) => ret,
         Err(e) => {
             // Clean up the target dir. This is safe, since we ensured
@@ -97,6 +148,13 @@ pub fn command_clone<Policy: HTTPAuthentication>(
             return Err(e);
         }
     };
+    // Clones keep the IRI they were cloned from as their canonical IRI,
+    // unless it only locates the project on this machine
+    if let ProjectLocator::Iri(iri) = &locator
+        && !matches!(iri.scheme().as_str(), "file" | "git+file")
+    {
+        set_project_iri_in_config(local_project.project_path.join(CONFIG_FILE), iri.as_str())?;
+    }
 
     // Update project context with the new cloned project
     // TODO: Consider under which circumstances (if any)
@@ -106,6 +164,8 @@ pub fn command_clone<Policy: HTTPAuthentication>(
         current_project: Some(local_project.clone()),
         current_directory: ctx.current_directory,
         env: None,
+        markers: ctx.markers,
+        version_strategy: ctx.version_strategy,
     };
 
     if !no_deps {
@@ -132,14 +192,44 @@ pub fn command_clone<Policy: HTTPAuthentication>(
             _ => None,
         };
         let LockOutcome {
-            lock,
-            dependencies: _dependencies,
+            mut lock,
+            dependencies,
         } = sysand_core::commands::lock::do_lock_projects(
-            [(identifiers, &project)],
+            [(identifiers.clone(), &project)],
             resolver,
             &provided_iris,
             &ctx,
-        )?;
+        )
+        .coded()?;
+        if workspace {
+            let mut overrides =
+                clone_workspace(&workspace_path, &project.inner().project_path, dependencies)?;
+            // Lock again, so that the lockfile refers to the cloned projects
+            overrides.extend(config.projects.iter().cloned());
+            let config = Config {
+                projects: overrides,
+                ..config.clone()
+            };
+            let resolver = create_resolver(
+                resolution_opts,
+                &config,
+                &project.inner().project_path,
+                &ctx,
+                provided_iris.clone(),
+                client.clone(),
+                git_cache.clone(),
+                runtime.clone(),
+                auth_policy.clone(),
+            )?;
+            lock = sysand_core::commands::lock::do_lock_projects(
+                [(identifiers, &project)],
+                resolver,
+                &provided_iris,
+                &ctx,
+            )
+            .coded()?
+            .lock;
+        }
         // Warn if we have any std lib dependencies
         if !provided_iris.is_empty()
             && lock
@@ -150,9 +240,10 @@ pub fn command_clone<Policy: HTTPAuthentication>(
             crate::logger::warn_std_deps();
         }
         let lock = lock.canonicalize();
-        wrapfs::write(
-            project.inner().project_path.join(DEFAULT_LOCKFILE_NAME),
-            lock.to_string(),
+        write_lockfile(
+            &project.inner().project_path,
+            &lock,
+            config.lock.format.unwrap_or_default(),
         )?;
 
         let mut env = get_or_create_env(
@@ -160,22 +251,145 @@ pub fn command_clone<Policy: HTTPAuthentication>(
             ctx.current_workspace.as_ref(),
             ctx.current_project.as_ref(),
             ctx.current_directory,
+            config,
         )?;
         command_sync(
             &lock,
             &project.inner().project_path,
             &mut env,
+            config,
             client,
+            git_cache,
             &provided_iris,
             runtime,
             auth_policy,
             ctx.current_workspace.as_ref(),
+            false,
+            &SyncFilter::default(),
         )?;
     }
 
     Ok(())
 }
 
+/// Name of the workspace directory for project `name` that is not in
+/// `taken`
+fn directory_name(name: &str, taken: &HashSet<String>) -> String {
+    let base: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let base = match base.trim_matches('.') {
+        "" => "project",
+        base => base,
+    };
+    let mut dir = base.to_owned();
+    let mut n = 1;
+    while taken.contains(&dir) {
+        n += 1;
+        dir = format!("{base}-{n}");
+    }
+    dir
+}
+
+/// Clone `dependencies` into sibling directories of the project at
+/// `root_path` in `workspace_path`, and add editable source overrides for
+/// the dependencies that each cloned project (transitively) uses to its
+/// `sysand.toml`. Returns the overrides of the project at `root_path`.
+fn clone_workspace<P: ProjectRead>(
+    workspace_path: &Utf8Path,
+    root_path: &Utf8Path,
+    dependencies: Vec<(Iri<String>, P)>,
+) -> Result<Vec<ConfigProject>> {
+    let cloned = "Cloned";
+    let header = sysand_core::style::get_style_config().header;
+
+    let root_dir = root_path
+        .file_name()
+        .expect("BUG: workspace project has no directory name")
+        .to_owned();
+    let root_info = LocalSrcProject {
+        nominal_path: None,
+        project_path: root_path.to_owned(),
+        expected_checksum: None,
+    }
+    .get_info()?
+    .ok_or_else(|| anyhow!("missing project info"))?;
+
+    let mut taken = HashSet::from([root_dir.clone()]);
+    // Directory and usages of each project
+    let mut projects = vec![(root_dir, usage_iris(&root_info))];
+    // IRI -> index in `projects`
+    let mut providers = HashMap::new();
+    for (iri, storage) in dependencies {
+        let info = storage
+            .get_info()?
+            .ok_or_else(|| anyhow!("missing project info for `{iri}`"))?;
+        let dir = directory_name(&info.name, &taken);
+        let mut local_project = LocalSrcProject {
+            nominal_path: None,
+            project_path: workspace_path.join(&dir),
+            expected_checksum: None,
+        };
+        wrapfs::create_dir_all(&local_project.project_path)?;
+        clone_project(&storage, &mut local_project, true)?;
+        log::info!(
+            "{header}{cloned:>12}{header:#} `{}` {} to `{}`",
+            info.name,
+            info.version,
+            local_project.project_path
+        );
+        taken.insert(dir.clone());
+        providers.insert(iri.into_string(), projects.len());
+        projects.push((dir, usage_iris(&info)));
+    }
+
+    let mut root_overrides = vec![];
+    for (index, (dir, _)) in projects.iter().enumerate() {
+        let config_path = workspace_path.join(dir).join(CONFIG_FILE);
+        let mut pending = vec![index];
+        let mut seen = HashSet::from([index]);
+        let mut used = vec![];
+        while let Some(current) = pending.pop() {
+            for iri in &projects[current].1 {
+                if let Some(&provider) = providers.get(iri)
+                    && seen.insert(provider)
+                {
+                    used.push((iri, provider));
+                    pending.push(provider);
+                }
+            }
+        }
+        used.sort_unstable();
+        for (iri, provider) in used {
+            let source = OverrideSource::Editable {
+                editable: format!("../{}", projects[provider].0).into(),
+            };
+            add_project_source_to_config(&config_path, iri, &source)?;
+            if index == 0 {
+                root_overrides.push(ConfigProject {
+                    identifiers: vec![iri.clone()],
+                    sources: vec![source],
+                });
+            }
+        }
+    }
+    Ok(root_overrides)
+}
+
+fn usage_iris(info: &InterchangeProjectInfoRaw) -> Vec<String> {
+    info.usage
+        .iter()
+        .map(|InterchangeProjectUsageRaw::Resource { resource, .. }| resource.clone())
+        .collect()
+}
+
 #[expect(clippy::too_many_arguments)]
 fn obtain_project<Policy: HTTPAuthentication>(
     locator: CloneProjectLocatorArgs,
@@ -183,9 +397,12 @@ fn obtain_project<Policy: HTTPAuthentication>(
     resolution_opts: ResolutionOptions,
     config: &Config,
     client: &reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
     runtime: &Arc<tokio::runtime::Runtime>,
     auth_policy: Arc<Policy>,
     project_path: Utf8PathBuf,
+    // Shown as the target of cloning
+    target: &Utf8Path,
     ctx: &ProjectContext,
 ) -> Result<
     (
@@ -201,6 +418,7 @@ fn obtain_project<Policy: HTTPAuthentication>(
         default_index,
         no_index,
         include_std,
+        no_include_std: _,
     } = resolution_opts;
     if let Some(existing_project) = &ctx.current_project {
         log::warn!(
@@ -255,9 +473,12 @@ fn obtain_project<Policy: HTTPAuthentication>(
         None,
         Some(client.clone()),
         index_urls,
+        config.shared_env_urls()?,
         runtime.clone(),
         auth_policy.clone(),
-    )?;
+    )?
+    .with_git_cache(git_cache)
+    .with_index_scopes(&config.indexes)?;
     match &locator {
         ProjectLocator::Iri(iri) => {
             log::info!(
@@ -265,7 +486,7 @@ fn obtain_project<Policy: HTTPAuthentication>(
                 {:>12} `{}`",
                 iri,
                 ' ',
-                local_project.project_path,
+                target,
             );
             let (_version, storage) = get_project_version(iri, version, &std_resolver)?;
             let (info, _meta) = clone_project(&storage, &mut local_project, true)?;
@@ -284,6 +505,7 @@ fn obtain_project<Policy: HTTPAuthentication>(
                     cloned,
                     header,
                     &mut local_project,
+                    target,
                     path,
                     remote_project,
                 )?;
@@ -299,6 +521,7 @@ fn obtain_project<Policy: HTTPAuthentication>(
                     cloned,
                     header,
                     &mut local_project,
+                    target,
                     path,
                     remote_project,
                 )?;
@@ -309,12 +532,14 @@ fn obtain_project<Policy: HTTPAuthentication>(
     Ok((include_std, locator, local_project, std_resolver))
 }
 
+#[expect(clippy::too_many_arguments)]
 fn clone_local<P: ProjectRead>(
     version: Option<String>,
     cloning: &str,
     cloned: &str,
     header: clap::builder::styling::Style,
     local_project: &mut LocalSrcProject,
+    target: &Utf8Path,
     path: &Utf8PathBuf,
     remote_project: P,
 ) -> Result<(), anyhow::Error> {
@@ -332,7 +557,7 @@ fn clone_local<P: ProjectRead>(
                 {:>12} `{}`",
         wrapfs::canonicalize(path)?,
         ' ',
-        local_project.project_path,
+        target,
     );
     let (info, _meta) = clone_project(&remote_project, local_project, true)?;
     log::info!(
@@ -0,0 +1,362 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use anyhow::{Result, anyhow};
+use camino::{Utf8Path, Utf8PathBuf};
+use serde_json::json;
+use sysand_core::{
+    config::{
+        Config,
+        local_fs::{
+            CONFIG_FILE, ConfigKey, ConfigReadError, KeySegment, SYSAND_SETTINGS, UnknownKey,
+            check_config, check_env_settings, set_config_value, system_config_path,
+            unset_config_value, user_config_path, warn_unknown_keys,
+        },
+    },
+    context::ProjectContext,
+    output::OutputSink,
+    project::utils::wrapfs,
+};
+use toml_edit::{DocumentMut, Item};
+
+use crate::{CliError, error::CodedResultExt};
+
+/// Configuration read for `project`, with what each part is and where it
+/// is read from, in the order their settings take precedence
+fn config_sources(
+    config_file: Option<&str>,
+    no_config: bool,
+    project: &ProjectContext,
+) -> Vec<(&'static str, String)> {
+    let mut sources = vec![];
+    if std::env::var_os(SYSAND_SETTINGS).is_some() {
+        sources.push(("environment", SYSAND_SETTINGS.to_string()));
+    }
+    if let Some(config_file) = config_file {
+        sources.push(("--config-file", config_file.to_string()));
+    }
+    if !no_config {
+        if let Some(path) = user_config_path() {
+            sources.push(("user", path.into_string()));
+        }
+        let project_file = project_config_file(project);
+        let workspace_file = project
+            .current_workspace
+            .as_ref()
+            .map(|w| w.root_path().join(CONFIG_FILE))
+            .filter(|w| wrapfs::canonicalize(w).ok() != wrapfs::canonicalize(&project_file).ok());
+        sources.push(("project", project_file.into_string()));
+        if let Some(workspace_file) = workspace_file {
+            sources.push(("workspace", workspace_file.into_string()));
+        }
+        if let Some(path) = system_config_path() {
+            sources.push(("system", path.into_string()));
+        }
+    }
+    sources
+}
+
+/// Read the configuration of the given origin at `location`, if there is
+/// any, with the keys in it that are not settings
+fn read_source(
+    origin: &str,
+    location: &str,
+) -> Result<Option<(Config, Vec<UnknownKey>)>, ConfigReadError> {
+    if origin == "environment" {
+        check_env_settings()
+    } else if wrapfs::is_file(location)? {
+        check_config(location).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+fn project_config_file(project: &ProjectContext) -> Utf8PathBuf {
+    project
+        .current_project
+        .as_ref()
+        .map_or(Utf8Path::new("."), |p| p.root_path())
+        .join(CONFIG_FILE)
+}
+
+/// Settings of `config`, read from a file of the given origin, that are
+/// used. Only workspace settings are shared with workspace projects
+fn shared_settings(origin: &str, config: Config) -> Config {
+    if origin == "workspace" {
+        Config {
+            workspace: config.workspace,
+            ..Default::default()
+        }
+    } else {
+        config
+    }
+}
+
+/// Read the configuration at `sources`, returning the merged configuration
+/// and the settings read from each source
+fn read_config_sources(
+    sources: Vec<(&'static str, String)>,
+) -> Result<(toml::Table, Vec<(String, toml::Table)>)> {
+    let mut config = Config::default();
+    let mut read = vec![];
+    for (origin, location) in sources {
+        if let Some((source_config, unknown_keys)) = read_source(origin, &location)? {
+            warn_unknown_keys(&location, &unknown_keys);
+            let source_config = shared_settings(origin, source_config);
+            read.push((location, toml::Table::try_from(&source_config)?));
+            config.merge(source_config);
+        }
+    }
+    Ok((toml::Table::try_from(&config)?, read))
+}
+
+/// Sources among `read` that the setting `key` of `merged` comes from
+fn origins<'a>(
+    key: &ConfigKey,
+    merged: &toml::Table,
+    read: &'a [(String, toml::Table)],
+) -> Vec<&'a str> {
+    // Each source appends its own entries to lists of tables
+    if let [KeySegment::Key(first), KeySegment::Index(index), ..] = &key.0[..]
+        && let Some(toml::Value::Array(entries)) = merged.get(first)
+        && entries.iter().all(toml::Value::is_table)
+    {
+        return read
+            .iter()
+            .flat_map(|(location, table)| {
+                let entries = table.get(first).and_then(toml::Value::as_array);
+                std::iter::repeat_n(location.as_str(), entries.map_or(0, Vec::len))
+            })
+            .nth(*index)
+            .into_iter()
+            .collect();
+    }
+    let sources = read
+        .iter()
+        .filter(|(_, table)| key.get(table).is_some())
+        .map(|(location, _)| location.as_str());
+    // Single values are taken from the first source setting them, lists and
+    // tables are merged
+    if key
+        .get(merged)
+        .is_some_and(|v| v.is_array() || v.is_table())
+    {
+        sources.collect()
+    } else {
+        sources.take(1).collect()
+    }
+}
+
+/// Check the configuration read for `project` for errors and unknown keys,
+/// in the order their settings take precedence, and emit the merged
+/// configuration with where each setting comes from
+pub fn command_config_check(
+    config_file: Option<&str>,
+    no_config: bool,
+    project: &ProjectContext,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    let header = sysand_core::style::get_style_config().header;
+    let checked = "Checked";
+    let mut problems = 0;
+    let mut config = Config::default();
+    // Configuration of each file as read, for the origins of the settings
+    let mut read = vec![];
+    let mut reports = vec![];
+    for (origin, location) in config_sources(config_file, no_config, project) {
+        match read_source(origin, &location) {
+            Ok(None) => {}
+            Ok(Some((source_config, unknown_keys))) => {
+                warn_unknown_keys(&location, &unknown_keys);
+                log::info!("{header}{checked:>12}{header:#} {origin} configuration `{location}`");
+                problems += unknown_keys.len();
+                reports.push(json!({
+                    "path": location,
+                    "origin": origin,
+                    "unknown_keys": unknown_keys,
+                }));
+                let source_config = shared_settings(origin, source_config);
+                read.push((location, toml::Table::try_from(&source_config)?));
+                config.merge(source_config);
+            }
+            Err(err) => {
+                log::error!("{err}");
+                problems += 1;
+                reports.push(json!({
+                    "path": location,
+                    "origin": origin,
+                    "error": err.to_string(),
+                }));
+            }
+        }
+    }
+
+    let merged = toml::Table::try_from(&config)?;
+    let mut document: DocumentMut = toml::to_string(&config)?.parse()?;
+    let mut origins_json = serde_json::Map::new();
+    for (mut key, item) in document.as_table_mut().iter_mut() {
+        let config_key = ConfigKey(vec![KeySegment::Key(key.get().to_string())]);
+        let sources = origins(&config_key, &merged, &read);
+        match item {
+            Item::Table(table) => table
+                .decor_mut()
+                .set_prefix(format!("\n# from {}\n", sources.join(", "))),
+            Item::ArrayOfTables(tables) => {
+                for (index, table) in tables.iter_mut().enumerate() {
+                    let mut entry_key = config_key.clone();
+                    entry_key.0.push(KeySegment::Index(index));
+                    let entry_sources = origins(&entry_key, &merged, &read);
+                    table
+                        .decor_mut()
+                        .set_prefix(format!("\n# from {}\n", entry_sources.join(", ")));
+                }
+            }
+            _ => key
+                .leaf_decor_mut()
+                .set_prefix(format!("# from {}\n", sources.join(", "))),
+        }
+        origins_json.insert(key.get().to_string(), json!(sources));
+    }
+
+    out.emit(
+        document.to_string().trim(),
+        json!({
+            "files": reports,
+            "config": config,
+            "origins": origins_json,
+        }),
+    )?;
+
+    if problems > 0 {
+        return Err(CliError::InvalidConfig(problems).into());
+    }
+    Ok(())
+}
+
+/// Emit the effective value of the setting `key`
+pub fn command_config_get(
+    key: ConfigKey,
+    config_file: Option<&str>,
+    no_config: bool,
+    project: &ProjectContext,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    let (merged, read) = read_config_sources(config_sources(config_file, no_config, project))?;
+    let value = key
+        .get(&merged)
+        .ok_or_else(|| CliError::ConfigKeyNotSet(key.to_string()))?;
+    let human = match value {
+        toml::Value::String(s) => s.clone(),
+        toml::Value::Table(table) => toml::to_string(table)?.trim().to_string(),
+        value => value.to_string(),
+    };
+    out.emit(
+        &human,
+        json!({
+            "key": key.to_string(),
+            "value": value,
+            "origin": origins(&key, &merged, &read),
+        }),
+    )?;
+    Ok(())
+}
+
+/// Configuration file edited by `config set` and `config unset`
+fn edited_config_file(user: bool, project: &ProjectContext) -> Result<Utf8PathBuf> {
+    if user {
+        user_config_path()
+            .ok_or_else(|| anyhow!("unable to determine the user configuration directory"))
+    } else {
+        Ok(project_config_file(project))
+    }
+}
+
+/// Set `key` to `value` in the project configuration file, or the user one
+/// if `user` is set
+pub fn command_config_set(
+    key: ConfigKey,
+    value: String,
+    user: bool,
+    project: &ProjectContext,
+) -> Result<()> {
+    let path = edited_config_file(user, project)?;
+    if let Some(parent) = path.parent() {
+        wrapfs::create_dir_all(parent)?;
+    }
+    set_config_value(path, &key, &value).coded()?;
+    Ok(())
+}
+
+/// Remove `key` from the project configuration file, or the user one if
+/// `user` is set
+pub fn command_config_unset(key: ConfigKey, user: bool, project: &ProjectContext) -> Result<()> {
+    let path = edited_config_file(user, project)?;
+    if !unset_config_value(&path, &key).coded()? {
+        log::warn!("`{key}` is not set in `{path}`");
+    }
+    Ok(())
+}
+
+/// Emit each effective setting, with the files it comes from if `origin`
+/// is set
+pub fn command_config_list(
+    origin: bool,
+    config_file: Option<&str>,
+    no_config: bool,
+    project: &ProjectContext,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    let (merged, read) = read_config_sources(config_sources(config_file, no_config, project))?;
+    let mut settings = vec![];
+    for (key, value) in &merged {
+        flatten(
+            &mut vec![KeySegment::Key(key.clone())],
+            value,
+            &mut settings,
+        );
+    }
+
+    let mut lines = vec![];
+    let mut values = vec![];
+    for (key, value) in settings {
+        let mut line = format!("{key} = {value}");
+        let mut setting = json!({ "key": key.to_string(), "value": value });
+        if origin {
+            let sources = origins(&key, &merged, &read);
+            line.push_str(&format!("  # {}", sources.join(", ")));
+            setting["origin"] = json!(sources);
+        }
+        lines.push(line);
+        values.push(setting);
+    }
+    out.emit(&lines.join("\n"), json!(values))?;
+    Ok(())
+}
+
+/// Collect the settings in `value`, at `prefix`. Lists of values are single
+/// settings, lists of tables are split into their entries
+fn flatten<'a>(
+    prefix: &mut Vec<KeySegment>,
+    value: &'a toml::Value,
+    settings: &mut Vec<(ConfigKey, &'a toml::Value)>,
+) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                prefix.push(KeySegment::Key(key.clone()));
+                flatten(prefix, value, settings);
+                prefix.pop();
+            }
+        }
+        toml::Value::Array(entries)
+            if !entries.is_empty() && entries.iter().all(toml::Value::is_table) =>
+        {
+            for (index, entry) in entries.iter().enumerate() {
+                prefix.push(KeySegment::Index(index));
+                flatten(prefix, entry, settings);
+                prefix.pop();
+            }
+        }
+        value => settings.push((ConfigKey(prefix.clone()), value)),
+    }
+}
@@ -8,51 +8,206 @@ use anyhow::{Result, anyhow, bail};
 use camino::{Utf8Path, Utf8PathBuf};
 use fluent_uri::Iri;
 
+use serde_json::json;
+use sysand_core::output::OutputSink;
 use sysand_core::{
     auth::HTTPAuthentication,
-    commands::{env::do_env_local_dir, lock::LockOutcome},
+    build::format_size,
+    commands::{
+        env::{EnvExport, do_env_export, do_env_local_dir},
+        lock::LockOutcome,
+        sync::SyncFilter,
+    },
     config::Config,
     context::ProjectContext,
-    env::local_directory::LocalDirectoryEnvironment,
-    lock::Lock,
+    env::{
+        WriteEnvironment,
+        local_directory::{LocalDirectoryEnvironment, lazy::SourceFetcher},
+        utils::clone_project,
+    },
+    lock::{Lock, Source},
     model::InterchangeProjectUsage,
     project::{
         ProjectRead,
+        any::AnyProject,
         local_kpar::{KparInnerPath, LocalKParProject},
         local_src::LocalSrcProject,
+        memory::InMemoryProject,
         utils::wrapfs,
     },
     resolve::{
+        ResolveRead,
         file::FileResolverProject,
+        git_cache::GitCache,
         memory::{AcceptAll, MemoryResolver},
         priority::PriorityResolver,
         standard::standard_resolver,
     },
+    utils::format_err,
 };
 use typed_path::Utf8UnixPathBuf;
 
 use crate::{
     DEFAULT_INDEX_URL,
     cli::{InstallOptions, ResolutionOptions},
-    commands::sync::command_sync,
+    commands::{
+        lock::{has_lockfile, lockfile_path, read_lockfile},
+        sync::command_sync,
+    },
+    error::{CodedResultExt, ENV_READ, ENV_WRITE, ResultExt},
     get_overrides,
 };
 
 pub fn command_env<P: AsRef<Utf8Path>>(path: P) -> Result<LocalDirectoryEnvironment> {
-    let env = do_env_local_dir(path)?;
+    let env = do_env_local_dir(path).coded()?;
     Ok(env)
 }
 
+/// Project to install, as given to `env install`
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstallRequest {
+    pub iri: Iri<String>,
+    /// Version constraint, latest version if `None`
+    pub version: Option<String>,
+}
+
+/// Projects to install, given either as IRIs or as a single IRI followed
+/// by its version in `args`, and as lines `<IRI> [<version>]` in
+/// `from_file`. The same IRI may only be requested with one version
+pub fn install_requests(
+    args: Vec<String>,
+    from_file: Option<&Utf8Path>,
+) -> Result<Vec<InstallRequest>> {
+    let mut requests: Vec<InstallRequest> = vec![];
+    let mut push = |request: InstallRequest| -> Result<()> {
+        match requests.iter().find(|r| r.iri == request.iri) {
+            Some(r) if r.version == request.version => Ok(()),
+            Some(r) => bail!(
+                "`{}` is requested with different versions: {} and {}",
+                request.iri,
+                r.version.as_deref().unwrap_or("latest"),
+                request.version.as_deref().unwrap_or("latest"),
+            ),
+            None => {
+                requests.push(request);
+                Ok(())
+            }
+        }
+    };
+
+    // For compatibility, `<IRI> <version>` installs a single project
+    if let [iri, version] = &args[..]
+        && Iri::parse(version.as_str()).is_err()
+    {
+        push(InstallRequest {
+            iri: Iri::parse(iri.clone()).map_err(|(e, _)| anyhow!("invalid IRI `{iri}`: {e}"))?,
+            version: Some(version.clone()),
+        })?;
+    } else {
+        for iri in args {
+            let iri = Iri::parse(iri).map_err(|(e, iri)| {
+                anyhow!("invalid IRI `{iri}`: {e}; a version may only be given for a single IRI")
+            })?;
+            push(InstallRequest { iri, version: None })?;
+        }
+    }
+
+    if let Some(path) = from_file {
+        let contents = wrapfs::read_to_string(path)?;
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let (Some(iri), version, None) = (parts.next(), parts.next(), parts.next()) else {
+                bail!(
+                    "`{path}` line {}: expected `<IRI> [<version>]`, found `{line}`",
+                    i + 1
+                );
+            };
+            let iri = Iri::parse(iri.to_owned())
+                .map_err(|(e, iri)| anyhow!("`{path}` line {}: invalid IRI `{iri}`: {e}", i + 1))?;
+            push(InstallRequest {
+                iri,
+                version: version.map(str::to_owned),
+            })?;
+        }
+    }
+
+    if requests.is_empty() {
+        bail!("no projects to install");
+    }
+    Ok(requests)
+}
+
+/// Resolver used to install projects into the environment: config
+/// overrides, then `provided_iris`, then the standard resolver
+#[allow(clippy::too_many_arguments)]
+fn env_resolver<Policy: HTTPAuthentication>(
+    config: &Config,
+    index: Vec<String>,
+    default_index: Vec<String>,
+    no_index: bool,
+    provided_iris: &HashMap<String, Vec<InMemoryProject>>,
+    project_root: &Utf8Path,
+    client: &reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
+    runtime: Arc<tokio::runtime::Runtime>,
+    auth_policy: Arc<Policy>,
+) -> Result<impl ResolveRead<ProjectStorage: std::fmt::Debug> + std::fmt::Debug + use<Policy>> {
+    let index_urls = if no_index {
+        None
+    } else {
+        Some(config.index_urls(index, vec![DEFAULT_INDEX_URL.to_string()], default_index)?)
+    };
+
+    let overrides = get_overrides(
+        config,
+        project_root,
+        client,
+        runtime.clone(),
+        auth_policy.clone(),
+    )?;
+
+    let mut memory_projects = HashMap::default();
+    for (k, v) in provided_iris {
+        memory_projects.insert(fluent_uri::Iri::parse(k.clone()).unwrap(), v.to_vec());
+    }
+    let override_resolver = PriorityResolver::new(
+        MemoryResolver::from(overrides),
+        MemoryResolver {
+            iri_predicate: AcceptAll {},
+            projects: memory_projects,
+        },
+    );
+    // TODO: Move out the runtime
+    Ok(PriorityResolver::new(
+        override_resolver,
+        standard_resolver(
+            None,
+            None,
+            Some(client.clone()),
+            index_urls,
+            config.shared_env_urls()?,
+            runtime,
+            auth_policy,
+        )?
+        .with_git_cache(git_cache)
+        .with_index_scopes(&config.indexes)?,
+    ))
+}
+
 // TODO: Factor out provided_iris logic
 #[allow(clippy::too_many_arguments)]
 pub fn command_env_install<Policy: HTTPAuthentication>(
-    iri: Iri<String>,
-    version: Option<String>,
+    requests: Vec<InstallRequest>,
     install_opts: InstallOptions,
     resolution_opts: ResolutionOptions,
     config: &Config,
     project_root: Option<Utf8PathBuf>,
     client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
     runtime: Arc<tokio::runtime::Runtime>,
     auth_policy: Arc<Policy>,
     mut ctx: ProjectContext,
@@ -63,91 +218,102 @@ pub fn command_env_install<Policy: HTTPAuthentication>(
         ctx.current_workspace.as_ref(),
         ctx.current_project.as_ref(),
         &ctx.current_directory,
+        config,
     )?;
     ctx.env = Some(env);
 
+    let overwrite = install_opts.overwrite_policy();
     let InstallOptions {
-        allow_overwrite,
+        allow_overwrite: _,
+        overwrite: _,
         allow_multiple,
         no_deps,
+        metadata_only,
     } = install_opts;
     let ResolutionOptions {
         index,
         default_index,
         no_index,
         include_std,
+        no_include_std: _,
     } = resolution_opts;
 
     // TODO: should probably first check that current project exists
-    let provided_iris = if !include_std {
+    let (provided_iris, requests) = if !include_std {
         let sysml_std = crate::known_std_libs();
-        if sysml_std.contains_key(iri.as_ref()) {
-            crate::logger::warn_std(iri.as_ref());
+        let requests: Vec<_> = requests
+            .into_iter()
+            .filter(|request| {
+                let is_std = sysml_std.contains_key(request.iri.as_str());
+                if is_std {
+                    crate::logger::warn_std(request.iri.as_str());
+                }
+                !is_std
+            })
+            .collect();
+        if requests.is_empty() {
             return Ok(());
         }
-        sysml_std
+        (sysml_std, requests)
     } else {
-        HashMap::default()
-    };
-
-    let index_urls = if no_index {
-        None
-    } else {
-        Some(config.index_urls(index, vec![DEFAULT_INDEX_URL.to_string()], default_index)?)
+        (HashMap::default(), requests)
     };
 
-    let overrides = get_overrides(
+    let resolver = env_resolver(
         config,
+        index,
+        default_index,
+        no_index,
+        &provided_iris,
         &project_root,
         &client,
+        git_cache.clone(),
         runtime.clone(),
         auth_policy.clone(),
     )?;
 
-    let mut memory_projects = HashMap::default();
-    for (k, v) in &provided_iris {
-        memory_projects.insert(fluent_uri::Iri::parse(k.clone()).unwrap(), v.to_vec());
-    }
-    let override_resolver = PriorityResolver::new(
-        MemoryResolver::from(overrides),
-        MemoryResolver {
-            iri_predicate: AcceptAll {},
-            projects: memory_projects,
-        },
-    );
-    // TODO: Move out the runtime
-    let resolver = PriorityResolver::new(
-        override_resolver,
-        standard_resolver(
-            None,
-            None,
-            Some(client.clone()),
-            index_urls,
-            runtime.clone(),
-            auth_policy.clone(),
-        )?,
-    );
-
     // TODO: don't use different root project resolution
     //       mechanisms depending on no_deps
     if no_deps {
-        let (version, storage) =
-            crate::commands::clone::get_project_version(&iri, version, &resolver)?;
-        sysand_core::commands::env::do_env_install_project(
-            &iri,
-            &version.to_string(),
-            &storage,
-            Some(storage.checksum_canonical_variant()?),
-            // Initialized above
-            &mut ctx.env.unwrap(),
-            allow_overwrite,
-            allow_multiple,
-        )?;
+        // Resolve all projects before installing any of them
+        let mut resolved = Vec::with_capacity(requests.len());
+        for InstallRequest { iri, version } in requests {
+            let (version, storage) =
+                crate::commands::clone::get_project_version(&iri, version, &resolver)?;
+            let sources = storage.sources(&ctx)?;
+            resolved.push((iri, version, storage, sources));
+        }
+        let env = ctx.env.as_mut().unwrap();
+        for (iri, version, storage, sources) in resolved {
+            sysand_core::commands::env::do_env_install_project(
+                &iri,
+                &version.to_string(),
+                &storage,
+                Some(storage.checksum_canonical_variant()?),
+                env,
+                overwrite,
+                allow_multiple,
+                metadata_only.then_some(sources.as_slice()),
+                sources.first(),
+            )
+            .coded()?;
+        }
     } else {
-        let usages = vec![InterchangeProjectUsage::Resource {
-            resource: fluent_uri::Iri::from_str(iri.as_ref())?,
-            version_constraint: version.map(|v| semver::VersionReq::parse(&v)).transpose()?,
-        }];
+        // All projects are locked together, so that shared dependencies
+        // are resolved and installed once
+        let usages = requests
+            .into_iter()
+            .map(|InstallRequest { iri, version }| {
+                Ok(InterchangeProjectUsage::Resource {
+                    resource: fluent_uri::Iri::from_str(iri.as_ref())?,
+                    version_constraint: version
+                        .map(|v| semver::VersionReq::parse(&v))
+                        .transpose()?,
+                    when: None,
+                    hash: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         let LockOutcome {
             lock,
@@ -158,10 +324,11 @@ pub fn command_env_install<Policy: HTTPAuthentication>(
             resolver,
             &provided_iris,
             &ctx,
-        )?;
+        )
+        .coded()?;
         // Find if we added any std lib dependencies. This relies on `Lock::default()`
         // and `do_lock_extend()` to not read the existing lockfile, i.e. `lock` contains
-        // only `iri` and `iri`'s dependencies.
+        // only the requested projects and their dependencies.
         if !provided_iris.is_empty()
             && lock
                 .projects
@@ -174,28 +341,88 @@ pub fn command_env_install<Policy: HTTPAuthentication>(
             &lock,
             project_root,
             &mut ctx.env.unwrap(),
+            config,
             client,
+            git_cache,
             &provided_iris,
             runtime,
             auth_policy,
             ctx.current_workspace.as_ref(),
+            metadata_only,
+            &SyncFilter::default(),
         )?;
     }
 
     Ok(())
 }
 
+/// Install the projects of the lockfile in `lock_root` with the given
+/// IRIs, at their locked versions and from their locked sources, without
+/// resolving them again
+#[allow(clippy::too_many_arguments)]
+pub fn command_env_install_locked<Policy: HTTPAuthentication>(
+    iris: Vec<String>,
+    install_opts: InstallOptions,
+    resolution_opts: ResolutionOptions,
+    config: &Config,
+    lock_root: Utf8PathBuf,
+    client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
+    runtime: Arc<tokio::runtime::Runtime>,
+    auth_policy: Arc<Policy>,
+    ctx: ProjectContext,
+) -> Result<()> {
+    if !has_lockfile(&lock_root) {
+        bail!(
+            "lockfile `{}` not found; run `sysand lock` first",
+            lockfile_path(&lock_root)?
+        );
+    }
+    let lock = read_lockfile(lockfile_path(&lock_root)?)?;
+    let provided_iris = crate::ignored_std_libs(
+        resolution_opts.include_std_mode(config),
+        ctx.current_project.as_ref(),
+    );
+    let mut env = crate::get_or_create_env(
+        ctx.env,
+        ctx.current_workspace.as_ref(),
+        ctx.current_project.as_ref(),
+        &ctx.current_directory,
+        config,
+    )?;
+    command_sync(
+        &lock,
+        lock_root,
+        &mut env,
+        config,
+        client,
+        git_cache,
+        &provided_iris,
+        runtime,
+        auth_policy,
+        ctx.current_workspace.as_ref(),
+        install_opts.metadata_only,
+        &SyncFilter {
+            only: iris,
+            skip: vec![],
+            no_deps: install_opts.no_deps,
+        },
+    )
+}
+
 // TODO: Collect common arguments
 #[allow(clippy::too_many_arguments)]
 pub fn command_env_install_path<Policy: HTTPAuthentication>(
     iri: Iri<String>,
     version: Option<String>,
     path: Utf8PathBuf,
+    project: Option<String>,
     install_opts: InstallOptions,
     resolution_opts: ResolutionOptions,
     config: &Config,
     project_root: Option<Utf8PathBuf>,
     client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
     runtime: Arc<tokio::runtime::Runtime>,
     auth_policy: Arc<Policy>,
     mut ctx: ProjectContext,
@@ -206,34 +433,43 @@ pub fn command_env_install_path<Policy: HTTPAuthentication>(
         ctx.current_workspace.as_ref(),
         ctx.current_project.as_ref(),
         &ctx.current_directory,
+        config,
     )?;
     ctx.env = Some(env);
 
+    let overwrite = install_opts.overwrite_policy();
     let InstallOptions {
-        allow_overwrite,
+        allow_overwrite: _,
+        overwrite: _,
         allow_multiple,
         no_deps,
+        // Conflicts with `--path`
+        metadata_only: _,
     } = install_opts;
     let ResolutionOptions {
         index,
         default_index,
         no_index,
         include_std,
+        no_include_std: _,
     } = resolution_opts;
 
     let metadata = wrapfs::metadata(&path)?;
     let project = if metadata.is_dir() {
+        if project.is_some() {
+            bail!("`--project` can only be used with KPAR archives");
+        }
         FileResolverProject::LocalSrcProject(LocalSrcProject {
-            // Provide an empty nominal path to satisfy lock. It won't be used for actual
-            // syncing, as the project is installed manually
-            nominal_path: Some(Utf8UnixPathBuf::new()),
+            // Nominal path is recorded as the source of the installed project. It won't be
+            // used for actual syncing, as the project is installed manually
+            nominal_path: Some(path.as_str().into()),
             project_path: path.as_str().into(),
             expected_checksum: None,
         })
     } else if metadata.is_file() {
         FileResolverProject::LocalKParProject(LocalKParProject::new(
             &path,
-            KparInnerPath::Guess,
+            project.map_or(KparInnerPath::Guess, KparInnerPath::Named),
             Some(path.as_str().into()),
             None,
         ))
@@ -268,18 +504,37 @@ pub fn command_env_install_path<Policy: HTTPAuthentication>(
         bail!("given version {version} does not match project version {project_version}")
     }
 
-    // TODO: Fix this hack. Manual installation needed to respect `allow_overwrite`/`allow_multiple`.
-    // Lockfile generation should be configurable. How to handle allow_overwrite/allow_multiple
+    // TODO: Fix this hack. Manual installation needed to respect `overwrite`/`allow_multiple`.
+    // Lockfile generation should be configurable. How to handle overwrite/allow_multiple
     // for dependencies? How about when syncing (e.g. after `add`)?
-    sysand_core::commands::env::do_env_install_project(
-        iri.as_str(),
-        &project_version,
-        &project,
-        Some(project.checksum_canonical_variant()?),
-        ctx.env.as_mut().unwrap(),
-        allow_overwrite,
-        allow_multiple,
-    )?;
+    let sources = project.sources(&ctx)?;
+    let env = ctx.env.as_mut().unwrap();
+    if let FileResolverProject::LocalKParProject(kpar) = &project {
+        // Avoid extracting each file to a temporary directory first
+        sysand_core::commands::env::do_env_install_kpar(
+            iri.as_str(),
+            &project_version,
+            kpar,
+            env,
+            overwrite,
+            allow_multiple,
+            sources.first(),
+        )
+        .coded()?;
+    } else {
+        sysand_core::commands::env::do_env_install_project(
+            iri.as_str(),
+            &project_version,
+            &project,
+            Some(project.checksum_canonical_variant()?),
+            env,
+            overwrite,
+            allow_multiple,
+            None,
+            sources.first(),
+        )
+        .coded()?;
+    }
     if !no_deps {
         let overrides = get_overrides(
             config,
@@ -308,9 +563,12 @@ pub fn command_env_install_path<Policy: HTTPAuthentication>(
                 None,
                 Some(client.clone()),
                 index_urls,
+                config.shared_env_urls()?,
                 runtime.clone(),
                 auth_policy.clone(),
-            )?,
+            )?
+            .with_git_cache(git_cache.clone())
+            .with_index_scopes(&config.indexes)?,
         );
         let LockOutcome {
             mut lock,
@@ -320,7 +578,8 @@ pub fn command_env_install_path<Policy: HTTPAuthentication>(
             resolver,
             &provided_iris,
             &ctx,
-        )?;
+        )
+        .coded()?;
         // FIXME: part of hack above, the project is already installed
         // If it's not removed from lock here, sync will try to install it again,
         // and fail because of missing sources path
@@ -329,35 +588,414 @@ pub fn command_env_install_path<Policy: HTTPAuthentication>(
             &lock,
             project_root,
             &mut ctx.env.unwrap(),
+            config,
             client,
+            git_cache,
             &provided_iris,
             runtime,
             auth_policy,
             ctx.current_workspace.as_ref(),
+            false,
+            &SyncFilter::default(),
         )?;
     }
 
     Ok(())
 }
 
+/// Fetches the sources of projects installed with `--metadata-only` from
+/// the first of their recorded sources that has the expected checksum.
+/// Relative source paths are resolved against `project_root`
+pub fn source_fetcher<Policy: HTTPAuthentication + Send + Sync + 'static>(
+    project_root: &Utf8Path,
+    client: reqwest_middleware::ClientWithMiddleware,
+    runtime: Arc<tokio::runtime::Runtime>,
+    auth_policy: Arc<Policy>,
+) -> SourceFetcher {
+    let project_root = project_root.to_owned();
+    SourceFetcher::new(move |sources, target| {
+        let mut errors = vec![];
+        for source in sources {
+            let fetched = AnyProject::try_from_override_source(
+                source.to_override(),
+                &project_root,
+                auth_policy.clone(),
+                client.clone(),
+                runtime.clone(),
+            )
+            .map_err(format_err)
+            .and_then(|project| {
+                if let Some(expected) = source.to_checksum() {
+                    let actual = project.checksum_canonical_variant().map_err(format_err)?;
+                    if actual != expected {
+                        return Err(format!(
+                            "incorrect checksum: expected `{expected}`, but the actual is `{actual}`"
+                        ));
+                    }
+                }
+                clone_project(&project, target, true).map_err(format_err)
+            });
+            match fetched {
+                Ok(_) => return Ok(()),
+                Err(e) => errors.push(e),
+            }
+        }
+        Err(errors.join("\n"))
+    })
+}
+
+/// Uninstall `version` of `iri`, or all its versions. With `orphans_lock_root`,
+/// also uninstall the projects left unused, keeping those locked in the
+/// lockfile in that directory
 pub fn command_env_uninstall<S: AsRef<str>, V: AsRef<str>>(
     iri: S,
     version: Option<V>,
+    orphans_lock_root: Option<&Utf8Path>,
     mut env: LocalDirectoryEnvironment,
 ) -> Result<()> {
-    sysand_core::commands::env::do_env_uninstall(&iri, version.as_ref(), &mut env)?;
+    let iri = iri.as_ref();
+    let version = version.as_ref().map(AsRef::as_ref);
+    let orphans: Vec<(String, String)> = match orphans_lock_root {
+        Some(lock_root) => {
+            let lock = if has_lockfile(lock_root) {
+                Some(read_lockfile(lockfile_path(lock_root)?)?)
+            } else {
+                None
+            };
+            env.orphans(iri, version, lock.as_ref())
+                .into_iter()
+                .filter_map(|p| Some((p.identifiers.first()?.clone(), p.version.clone())))
+                .collect()
+        }
+        None => vec![],
+    };
+    let dependents: Vec<String> = env
+        .dependents(iri)
+        .into_iter()
+        .filter(|p| !p.identifiers.iter().any(|i| i == iri))
+        .map(|p| format!("`{}` {}", p.name, p.version))
+        .collect();
+    if !dependents.is_empty() {
+        log::warn!("`{iri}` is still used by {}", dependents.join(", "));
+    }
+
+    sysand_core::commands::env::do_env_uninstall(iri, version, &mut env).with_code(ENV_WRITE)?;
+    for (orphan, orphan_version) in orphans {
+        sysand_core::commands::env::do_env_uninstall(orphan, Some(orphan_version), &mut env)
+            .with_code(ENV_WRITE)?;
+    }
     env.write()?;
 
     Ok(())
 }
 
-pub fn command_env_list(env: Option<LocalDirectoryEnvironment>) -> Result<()> {
+/// List the installed projects. If `verbose`, also print where each
+/// project was installed from
+pub fn command_env_list(
+    env: Option<LocalDirectoryEnvironment>,
+    verbose: bool,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
     let Some(env) = env else {
         bail!("unable to identify environment to list");
     };
 
-    for (uri, version) in sysand_core::commands::env::do_env_list(env)? {
-        println!("`{uri}` {}", version.unwrap_or("".to_string()));
+    let listed = sysand_core::commands::env::do_env_list(env.clone()).with_code(ENV_READ)?;
+    for (uri, version) in listed {
+        let mut human = format!("`{uri}` {}", version.as_deref().unwrap_or(""));
+        let mut value = json!({"iri": uri, "version": version});
+        let provenance = if verbose
+            && let Some(project) = env
+                .projects()
+                .iter()
+                .find(|p| Some(&p.version) == version.as_ref() && p.identifiers.contains(&uri))
+        {
+            env.provenance(project).with_code(ENV_READ)?
+        } else {
+            None
+        };
+        if let Some(provenance) = provenance {
+            let source = provenance.source.as_ref().map(|s| s.to_toml().to_string());
+            if let Some(source) = &source {
+                human.push_str(&format!("\n    source:       {source}"));
+            }
+            human.push_str(&format!("\n    installed at: {}", provenance.installed_at));
+            if let Some(hash) = &provenance.project_hash {
+                human.push_str(&format!("\n    project hash: {hash}"));
+            }
+            if let Some(hash) = &provenance.artifact_hash {
+                human.push_str(&format!("\n    KPAR hash:    {hash}"));
+            }
+            value["source"] = json!(source);
+            value["installed_at"] = json!(provenance.installed_at.to_string());
+            value["project_hash"] = json!(provenance.project_hash);
+            value["kpar_hash"] = json!(provenance.artifact_hash);
+        }
+        out.emit(&human, value)?;
+    }
+    Ok(())
+}
+
+/// Mark `env` as read-only, or writable again
+pub fn command_env_read_only(
+    env: Option<LocalDirectoryEnvironment>,
+    read_only: bool,
+) -> Result<()> {
+    let Some(mut env) = env else {
+        bail!("unable to identify environment");
+    };
+    env.set_read_only(read_only).with_code(ENV_WRITE)?;
+
+    let header = sysand_core::style::get_style_config().header;
+    let marked = "Marked";
+    let state = if read_only { "read-only" } else { "writable" };
+    log::info!(
+        "{header}{marked:>12}{header:#} environment `{}` {state}",
+        env.root_path()
+    );
+    Ok(())
+}
+
+pub fn command_env_doctor(env: Option<LocalDirectoryEnvironment>, fix: bool) -> Result<()> {
+    let Some(mut env) = env else {
+        bail!("unable to identify environment to check");
+    };
+
+    let header = sysand_core::style::get_style_config().header;
+    if fix {
+        if env.is_read_only() {
+            bail!(
+                "environment `{}` is read-only; it cannot be repaired",
+                env.root_path()
+            );
+        }
+        let repaired = env.repair().with_code(ENV_WRITE)?;
+        for problem in &repaired {
+            log::warn!("{problem}");
+        }
+        let repaired_label = "Repaired";
+        log::info!(
+            "{header}{repaired_label:>12}{header:#} {} problem(s) in environment",
+            repaired.len()
+        );
+        return Ok(());
+    }
+
+    let problems = env.check().with_code(ENV_READ)?;
+    for problem in &problems {
+        log::error!("{problem}");
+    }
+    if !problems.is_empty() {
+        bail!(
+            "found {} problem(s) in environment; run `sysand env doctor --fix` to repair them",
+            problems.len()
+        );
+    }
+    let checked = "Checked";
+    log::info!(
+        "{header}{checked:>12}{header:#} {} project(s), no problems found",
+        env.projects().len()
+    );
+    Ok(())
+}
+
+/// Remove the installed projects that none of the lockfiles in `lock_roots`
+/// lock, printing the disk space reclaimed. With `dry_run`, only print them
+pub fn command_env_gc(
+    env: Option<LocalDirectoryEnvironment>,
+    lock_roots: &[Utf8PathBuf],
+    dry_run: bool,
+) -> Result<()> {
+    let Some(mut env) = env else {
+        bail!("unable to identify environment to collect garbage in");
+    };
+    let mut locks = Vec::with_capacity(lock_roots.len());
+    for lock_root in lock_roots {
+        if !has_lockfile(lock_root) {
+            bail!(
+                "lockfile `{}` not found; run `sysand lock` first",
+                lockfile_path(lock_root)?
+            );
+        }
+        locks.push(read_lockfile(lockfile_path(lock_root)?)?);
+    }
+    if !dry_run && env.is_read_only() {
+        bail!(
+            "environment `{}` is read-only; no projects can be removed from it",
+            env.root_path()
+        );
+    }
+
+    let header = sysand_core::style::get_style_config().header;
+    let removing = if dry_run { "Would remove" } else { "Removing" };
+    let mut unlocked = vec![];
+    let mut reclaimed = 0;
+    for project in env.unlocked(&locks) {
+        let Some(iri) = project.identifiers.first() else {
+            continue;
+        };
+        let size = env.installed_size(project)?;
+        log::info!(
+            "{header}{removing:>12}{header:#} `{iri}` {} ({})",
+            project.version,
+            format_size(size)
+        );
+        unlocked.push((iri.clone(), project.version.clone()));
+        reclaimed += size;
+    }
+
+    if !dry_run {
+        for (iri, version) in &unlocked {
+            env.del_project_version(iri, version).with_code(ENV_WRITE)?;
+        }
+        env.write()?;
     }
+    let removed = if dry_run { "Would remove" } else { "Removed" };
+    log::info!(
+        "{header}{removed:>12}{header:#} {} project(s), {} in total",
+        unlocked.len(),
+        format_size(reclaimed)
+    );
+    Ok(())
+}
+
+/// Print the projects installed in the environment, with the sources they
+/// can be installed from, as JSON to standard output. Sources not recorded
+/// in the environment are taken from the lockfile or resolved again
+#[allow(clippy::too_many_arguments)]
+pub fn command_env_export<Policy: HTTPAuthentication>(
+    resolution_opts: ResolutionOptions,
+    config: &Config,
+    project_root: Option<Utf8PathBuf>,
+    client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
+    runtime: Arc<tokio::runtime::Runtime>,
+    auth_policy: Arc<Policy>,
+    ctx: &ProjectContext,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    let Some(env) = &ctx.env else {
+        bail!("unable to identify environment to export");
+    };
+    let project_root = project_root.unwrap_or(ctx.current_directory.clone());
+    let lock = if has_lockfile(&project_root) {
+        Some(read_lockfile(lockfile_path(&project_root)?)?)
+    } else {
+        None
+    };
+    let ResolutionOptions {
+        index,
+        default_index,
+        no_index,
+        include_std: _,
+        no_include_std: _,
+    } = resolution_opts;
+    let resolver = env_resolver(
+        config,
+        index,
+        default_index,
+        no_index,
+        &HashMap::default(),
+        &project_root,
+        &client,
+        git_cache,
+        runtime,
+        auth_policy,
+    )?;
+
+    let export = do_env_export(env, &project_root, |project| -> Result<_, String> {
+        let locked = lock.iter().flat_map(|l| &l.projects).find(|p| {
+            p.version == project.version
+                && p.identifiers
+                    .iter()
+                    .any(|iri| project.identifiers.contains(iri))
+        });
+        if let Some(locked) = locked {
+            return Ok(locked.sources.clone());
+        }
+        let Some(iri) = project.identifiers.first() else {
+            return Ok(vec![]);
+        };
+        // Local sources are resolved relative to the workspace or project root
+        let Some(sources_root) = ctx
+            .current_workspace
+            .as_ref()
+            .map(|w| w.root_path())
+            .or(ctx.current_project.as_ref().map(|p| p.root_path()))
+        else {
+            return Err("sources can only be resolved in a project or workspace".to_owned());
+        };
+        let resolve = || -> Result<_> {
+            let iri = Iri::parse(iri.clone()).map_err(|(e, _)| e)?;
+            let (_, storage) = crate::commands::clone::get_project_version(
+                &iri,
+                Some(project.version.clone()),
+                &resolver,
+            )?;
+            let mut sources = storage.sources(ctx)?;
+            for source in &mut sources {
+                if let Source::LocalSrc { src_path: path, .. }
+                | Source::LocalKpar {
+                    kpar_path: path, ..
+                } = source
+                {
+                    *path = Utf8UnixPathBuf::from(sources_root.as_str()).join(&*path);
+                }
+            }
+            Ok(sources)
+        };
+        resolve().map_err(|e| format!("{e:#}"))
+    })
+    .coded()?;
+    out.emit(
+        &serde_json::to_string_pretty(&export)?,
+        serde_json::to_value(&export)?,
+    )?;
+
+    Ok(())
+}
+
+/// Install exactly the projects of an environment export created with
+/// `command_env_export`, from their recorded sources
+#[allow(clippy::too_many_arguments)]
+pub fn command_env_import<Policy: HTTPAuthentication>(
+    path: &Utf8Path,
+    config: &Config,
+    project_root: Option<Utf8PathBuf>,
+    client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
+    runtime: Arc<tokio::runtime::Runtime>,
+    auth_policy: Arc<Policy>,
+    ctx: ProjectContext,
+) -> Result<()> {
+    let export: EnvExport = match serde_json::from_str(&wrapfs::read_to_string(path)?) {
+        Ok(export) => export,
+        Err(e) => bail!("invalid environment export `{path}`:\n{e}"),
+    };
+    let lock = export.to_lock().coded()?;
+    let project_root = project_root.unwrap_or(ctx.current_directory.clone());
+    let mut env = crate::get_or_create_env(
+        ctx.env,
+        ctx.current_workspace.as_ref(),
+        ctx.current_project.as_ref(),
+        &ctx.current_directory,
+        config,
+    )?;
+    command_sync(
+        &lock,
+        project_root,
+        &mut env,
+        config,
+        client,
+        git_cache,
+        &HashMap::default(),
+        runtime,
+        auth_policy,
+        ctx.current_workspace.as_ref(),
+        false,
+        &SyncFilter::default(),
+    )?;
+    export.verify(&env).coded()?;
+
     Ok(())
 }
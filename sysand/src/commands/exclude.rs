@@ -5,7 +5,7 @@ use anyhow::Result;
 use camino::Utf8PathBuf;
 use sysand_core::{context::ProjectContext, exclude::do_exclude};
 
-use crate::CliError;
+use crate::{CliError, error::CodedResultExt};
 
 pub fn command_exclude(paths: Vec<Utf8PathBuf>, ctx: ProjectContext) -> Result<()> {
     let mut current_project = ctx
@@ -17,7 +17,7 @@ pub fn command_exclude(paths: Vec<Utf8PathBuf>, ctx: ProjectContext) -> Result<(
         unix_paths.push(current_project.get_unix_path(f)?);
     }
 
-    do_exclude(&mut current_project, unix_paths.into_iter())?;
+    do_exclude(&mut current_project, unix_paths.into_iter()).coded()?;
 
     Ok(())
 }
@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use anyhow::{Result, bail};
+use camino::Utf8Path;
+use serde_json::json;
+use sysand_core::output::OutputSink;
+use sysand_core::{
+    export::{ExportFormat, do_export},
+    project::utils::wrapfs,
+};
+
+use crate::{
+    commands::lock::{lockfile_path, read_lockfile},
+    error::CodedResultExt,
+};
+
+/// Export the lockfile of the project at `project_root` to `output`,
+/// or standard output if `None`
+pub fn command_export<P: AsRef<Utf8Path>, O: AsRef<Utf8Path>>(
+    project_root: P,
+    format: ExportFormat,
+    output: Option<O>,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    let lockfile = lockfile_path(project_root)?;
+    if !lockfile.is_file() {
+        bail!("lockfile `{lockfile}` not found, run `sysand lock` to create it");
+    }
+    let lock = read_lockfile(&lockfile)?;
+
+    let exported = do_export(&lock, format).coded()?;
+    match output {
+        Some(output) => wrapfs::write(output.as_ref(), exported)?,
+        None => out.emit(exported.trim_end_matches('\n'), json!(exported))?,
+    }
+    Ok(())
+}
@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use anyhow::Result;
+use serde_json::json;
+use sysand_core::output::OutputSink;
+use sysand_core::{
+    commands::{build::KparManifest, files::do_files},
+    context::ProjectContext,
+};
+
+use crate::{CliError, error::CodedResultExt};
+
+/// Print the files of the current project, with whether and why building
+/// a KPAR with `manifest` would package them
+pub fn command_files(
+    manifest: &KparManifest,
+    ctx: ProjectContext,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    let current_project = ctx
+        .current_project
+        .ok_or(CliError::MissingProjectCurrentDir)?;
+
+    let rows: Vec<[String; 3]> = do_files(&current_project, manifest)
+        .coded()?
+        .into_iter()
+        .map(|f| [f.path, f.class.to_string(), f.reason.to_string()])
+        .collect();
+    let header = ["PATH", "CLASS", "REASON"].map(String::from);
+    let mut widths = header.each_ref().map(String::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let table = std::iter::once(&header)
+        .chain(&rows)
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            line.trim_end().to_owned()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let value = rows
+        .iter()
+        .map(|[path, class, reason]| json!({"path": path, "class": class, "reason": reason}))
+        .collect();
+    out.emit(&table, serde_json::Value::Array(value))?;
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use anyhow::{Result, bail};
+use camino::Utf8Path;
+use serde_json::json;
+use sysand_core::output::OutputSink;
+use sysand_core::{
+    commands::graph::{DependencyGraph, GraphFormat},
+    env::{ReadEnvironment, local_directory::LocalDirectoryEnvironment},
+    lock::{Project, Source},
+    model::InterchangeProjectUsageRaw,
+    project::{
+        local_src::LocalSrcProject,
+        utils::{relativize_path, wrapfs},
+    },
+    workspace::Workspace,
+};
+
+use crate::commands::lock::{lockfile_path, read_lockfile};
+
+/// Print the dependency graph of the lockfile in `lock_root`. If
+/// `cluster_workspace` is given, each of its members is clustered with the
+/// projects used only by it
+pub fn command_graph<P: AsRef<Utf8Path>>(
+    lock_root: P,
+    format: GraphFormat,
+    cluster_workspace: Option<&Workspace>,
+    env: Option<&LocalDirectoryEnvironment>,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    let lock_root = wrapfs::canonicalize(lock_root.as_ref())?;
+    let lockfile = lockfile_path(&lock_root)?;
+    if !lockfile.is_file() {
+        bail!("lockfile `{lockfile}` not found, run `sysand lock` to create it");
+    }
+    let lock = read_lockfile(&lockfile)?;
+
+    let mut graph =
+        DependencyGraph::from_lock(&lock, |project| declared_usages(project, &lock_root, env));
+    if let Some(workspace) = cluster_workspace {
+        let mut members = vec![];
+        for path in workspace.absolute_project_paths() {
+            members.push(relativize_path(wrapfs::canonicalize(path)?, &lock_root)?.to_string());
+        }
+        graph.cluster_editable(&members);
+    }
+
+    let rendered = graph.render(format);
+    out.emit(rendered.trim_end_matches('\n'), json!(rendered))?;
+    Ok(())
+}
+
+/// Usages declared in `.project.json` of the locked `project`, read from
+/// its directory if it is editable and otherwise from `env`. Version
+/// constraints are only shown for projects whose information is found
+fn declared_usages(
+    project: &Project,
+    lock_root: &Utf8Path,
+    env: Option<&LocalDirectoryEnvironment>,
+) -> Vec<InterchangeProjectUsageRaw> {
+    let info = project.sources.iter().find_map(|s| match s {
+        Source::Editable { editable } => LocalSrcProject {
+            nominal_path: None,
+            project_path: lock_root.join(editable.as_str()),
+            expected_checksum: None,
+        }
+        .get_info()
+        .ok()
+        .flatten(),
+        _ => None,
+    });
+    let info = info.or_else(|| {
+        let env = env?;
+        project.identifiers.iter().find_map(|iri| {
+            env.get_project(iri, &project.version)
+                .ok()?
+                .get_info()
+                .ok()
+                .flatten()
+        })
+    });
+    match info {
+        Some(info) => info.usage,
+        None => {
+            log::debug!(
+                "information of `{}` {} not found, not showing its version constraints",
+                project.name,
+                project.version
+            );
+            vec![]
+        }
+    }
+}
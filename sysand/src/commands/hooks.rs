@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use anyhow::{Result, bail};
+use camino::Utf8Path;
+use indexmap::IndexSet;
+use sysand_core::{
+    commands::hooks::{HookStage, run_hooks},
+    config::Hook,
+    context::ProjectContext,
+    env::local_directory::LocalDirectoryEnvironment,
+    project::local_src::LocalSrcProject,
+    sources::{do_sources_local_src_project_no_deps, find_project_dependencies},
+    stdlib::known_std_libs,
+};
+
+use crate::error::CodedResultExt;
+
+/// Projects whose sources are passed to hooks: the current project, or
+/// else all projects of the current workspace
+pub fn hook_projects(ctx: &ProjectContext) -> Vec<LocalSrcProject> {
+    match (&ctx.current_project, &ctx.current_workspace) {
+        (Some(project), _) => vec![project.clone()],
+        (None, Some(workspace)) => workspace
+            .absolute_project_paths()
+            .into_iter()
+            .map(|project_path| LocalSrcProject {
+                nominal_path: None,
+                project_path,
+                expected_checksum: None,
+            })
+            .collect(),
+        (None, None) => vec![],
+    }
+}
+
+/// Run `hooks` of `stage` in `root`, passing them the source files of
+/// `projects` and of their (non-standard library) dependencies installed
+/// in `env`
+pub fn command_hooks(
+    stage: HookStage,
+    hooks: &[Hook],
+    root: &Utf8Path,
+    projects: &[LocalSrcProject],
+    env: Option<&LocalDirectoryEnvironment>,
+) -> Result<()> {
+    if hooks.is_empty() {
+        return Ok(());
+    }
+    let provided_iris = known_std_libs();
+    let mut sources = IndexSet::new();
+    for project in projects {
+        sources.extend(do_sources_local_src_project_no_deps(project, true).coded()?);
+        let Some(env) = env else {
+            continue;
+        };
+        let Some(info) = project.get_info()? else {
+            bail!("project is missing project information")
+        };
+        for dep in find_project_dependencies(info.validate()?.usage, env.clone(), &provided_iris)? {
+            dep.fetch_sources()?;
+            sources.extend(do_sources_local_src_project_no_deps(&dep, true).coded()?);
+        }
+    }
+    run_hooks(stage, hooks, root, &Vec::from_iter(sources)).coded()?;
+    Ok(())
+}
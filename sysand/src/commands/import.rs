@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use anyhow::Result;
+use camino::Utf8PathBuf;
+use sysand_core::{
+    context::ProjectContext,
+    import::{ImportFormat, do_import},
+};
+
+use crate::{CliError, error::CodedResultExt};
+
+/// Add the dependencies declared at `path` in `format` as usages of the
+/// current project
+pub fn command_import(format: ImportFormat, path: Utf8PathBuf, ctx: ProjectContext) -> Result<()> {
+    let mut current_project = ctx
+        .current_project
+        .ok_or(CliError::MissingProjectCurrentDir)?;
+
+    let added = do_import(&mut current_project, format, &path).coded()?;
+
+    let imported = "Imported";
+    let header = sysand_core::style::get_style_config().header;
+    log::info!(
+        "{header}{imported:>12}{header:#} {} usage(s) from {format} `{path}`",
+        added.len()
+    );
+    if !added.is_empty() {
+        log::info!("run `sysand lock` or `sysand sync` to resolve the new usages");
+    }
+    Ok(())
+}
@@ -3,14 +3,23 @@
 
 use anyhow::{Result, bail};
 use camino::Utf8PathBuf;
-use sysand_core::{context::ProjectContext, include::do_include, project::utils::wrapfs};
+use sysand_core::{
+    add::do_add,
+    context::ProjectContext,
+    include::{do_include_parallel, find_missing_std_usages},
+    model::HashAlg,
+    parallel::Jobs,
+    project::utils::wrapfs,
+};
 
-use crate::CliError;
+use crate::{CliError, error::CodedResultExt};
 
 pub fn command_include(
     paths: Vec<Utf8PathBuf>,
-    compute_checksum: bool,
+    checksum_algorithm: Option<HashAlg>,
     index_symbols: bool,
+    add_std_usages: bool,
+    jobs: Jobs,
     ctx: ProjectContext,
 ) -> Result<()> {
     let mut current_project = ctx
@@ -24,13 +33,30 @@ pub fn command_include(
         }
         unix_paths.push(current_project.get_unix_path(p)?);
     }
-    do_include(
+    do_include_parallel(
         &mut current_project,
-        unix_paths.into_iter(),
-        compute_checksum,
+        unix_paths.iter().cloned(),
+        checksum_algorithm,
         index_symbols,
         None,
-    )?;
+        jobs,
+    )
+    .coded()?;
+
+    if index_symbols {
+        let missing = find_missing_std_usages(&current_project, &unix_paths).coded()?;
+        for usage in &missing {
+            if add_std_usages {
+                do_add(&mut current_project, usage).coded()?;
+            } else {
+                log::warn!(
+                    "included files refer to standard library {usage},\n\
+                     which is not a usage of the project;\n\
+                     add it with `sysand include --add-std-usages` or `sysand add`"
+                );
+            }
+        }
+    }
 
     Ok(())
 }
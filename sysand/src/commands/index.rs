@@ -4,12 +4,14 @@
 use anyhow::Result;
 use camino::Utf8Path;
 
+use crate::error::CodedResultExt;
+
 use sysand_core::index::{
     RemoveTarget, do_index_add, do_index_init, do_index_remove, do_index_yank,
 };
 
 pub fn command_index_init<R: AsRef<Utf8Path>>(index_root: R) -> Result<()> {
-    do_index_init(index_root)?;
+    do_index_init(index_root).coded()?;
     Ok(())
 }
 
@@ -18,7 +20,7 @@ pub fn command_index_add<I: AsRef<str>, P: AsRef<Utf8Path>, R: AsRef<Utf8Path>>(
     kpar_path: P,
     index_root: R,
 ) -> Result<()> {
-    do_index_add(iri, kpar_path, index_root)?;
+    do_index_add(iri, kpar_path, index_root).coded()?;
     Ok(())
 }
 
@@ -27,7 +29,7 @@ pub fn command_index_yank<I: AsRef<str>, V: AsRef<str>, R: AsRef<Utf8Path>>(
     version: V,
     index_root: R,
 ) -> Result<()> {
-    do_index_yank(iri, version, index_root)?;
+    do_index_yank(iri, version, index_root).coded()?;
     Ok(())
 }
 
@@ -36,6 +38,6 @@ pub fn command_index_remove<I: AsRef<str>, R: AsRef<Utf8Path>>(
     target: RemoveTarget,
     index_root: R,
 ) -> Result<()> {
-    do_index_remove(iri, target, index_root)?;
+    do_index_remove(iri, target, index_root).coded()?;
     Ok(())
 }
@@ -7,10 +7,14 @@ use crate::{
         GetMetaVerb, InfoCommandVerb, RemoveInfoVerb, RemoveMetaVerb, RemoveVerb, SetInfoVerb,
         SetMetaVerb, SetVerb,
     },
+    error::CodedResultExt,
 };
 use camino::Utf8Path;
+use serde_json::json;
+use sysand_core::output::OutputSink;
 use sysand_core::{
     auth::HTTPAuthentication,
+    config::Index,
     context::ProjectContext,
     model::{
         InterchangeProjectChecksumRaw, InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw,
@@ -18,18 +22,21 @@ use sysand_core::{
     },
     project::{ProjectMut, ProjectRead, any::OverrideProject, local_kpar::KparInnerPath},
     resolve::{
-        file::FileResolverProject, memory::MemoryResolver, priority::PriorityResolver,
-        standard::standard_resolver,
+        file::FileResolverProject, git_cache::GitCache, memory::MemoryResolver,
+        priority::PriorityResolver, standard::standard_resolver,
     },
     style,
 };
 
-use anstream::{print, println};
 use anyhow::{Result, bail};
 use fluent_uri::Iri;
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, fmt::Write, sync::Arc};
 use sysand_core::{
-    info::{do_info, do_info_project},
+    env::{
+        ReadEnvironment, ReadEnvironmentAsync, index::IndexEnvironmentAsync,
+        local_directory::LocalDirectoryEnvironment,
+    },
+    info::{do_info, do_info_env, do_info_project},
     project::utils::wrapfs,
     project::{local_kpar::LocalKParProject, local_src::LocalSrcProject},
 };
@@ -38,34 +45,37 @@ use url::Url;
 pub fn pprint_interchange_project(
     info: &InterchangeProjectInfoRaw,
     excluded_iris: &HashSet<String>,
-) {
+    out: &mut dyn OutputSink,
+) -> Result<()> {
     let header = style::get_style_config().header;
-    println!("{header}Name:{header:#} {}", info.name);
+    let mut human = String::new();
+    writeln!(human, "{header}Name:{header:#} {}", info.name)?;
     if let Some(publisher) = &info.publisher {
-        println!("{header}Publisher:{header:#} {}", publisher);
+        writeln!(human, "{header}Publisher:{header:#} {}", publisher)?;
     }
     if let Some(ref description) = info.description {
-        println!("{header}Description:{header:#} {}", description);
+        writeln!(human, "{header}Description:{header:#} {}", description)?;
     }
-    println!("{header}Version:{header:#} {}", info.version);
+    writeln!(human, "{header}Version:{header:#} {}", info.version)?;
     if let Some(ref license) = info.license {
-        println!("{header}License:{header:#} {}", license);
+        writeln!(human, "{header}License:{header:#} {}", license)?;
     }
     if let Some(ref website) = info.website {
-        println!("{header}Website:{header:#} {}", website);
+        writeln!(human, "{header}Website:{header:#} {}", website)?;
     }
     if !info.maintainer.is_empty() {
-        println!(
+        writeln!(
+            human,
             "{header}Maintainer(s):{header:#} {}",
             info.maintainer.join(", ")
-        );
+        )?;
     }
     if !info.topic.is_empty() {
-        println!("{header}Topics:{header:#} {}", info.topic.join(", "));
+        writeln!(human, "{header}Topics:{header:#} {}", info.topic.join(", "))?;
     }
 
     if info.usage.is_empty() {
-        println!("No usages.");
+        writeln!(human, "No usages.")?;
     } else {
         let has_ignored_usages = info.usage.iter().any(|u| match u {
             InterchangeProjectUsageRaw::Resource { resource, .. } => {
@@ -82,41 +92,55 @@ pub fn pprint_interchange_project(
             })
             .collect();
         if has_ignored_usages && usages_to_print.is_empty() {
-            println!("All usages are ignored");
+            writeln!(human, "All usages are ignored")?;
         } else {
-            println!("{header}Usages:{header:#}");
+            writeln!(human, "{header}Usages:{header:#}")?;
             for usage in usages_to_print.iter() {
                 match usage {
                     InterchangeProjectUsageRaw::Resource {
                         resource,
                         version_constraint,
+                        when,
+                        hash,
                     } => {
-                        print!("    {resource}");
+                        write!(human, "    {resource}")?;
                         if let Some(v) = version_constraint {
-                            println!(" ({v})");
-                        } else {
-                            println!();
+                            write!(human, " ({v})")?;
+                        }
+                        if let Some(when) = when {
+                            write!(human, " when `{when}`")?;
                         }
+                        if let Some(hash) = hash {
+                            write!(human, " with hash `{hash}`")?;
+                        }
+                        writeln!(human)?;
                     }
                 }
             }
             if has_ignored_usages {
-                println!("Some usages are ignored");
+                writeln!(human, "Some usages are ignored")?;
             }
         }
     }
+    out.emit(human.trim_end(), serde_json::to_value(info)?)?;
+    Ok(())
 }
 
-fn interpret_project_path<P: AsRef<Utf8Path>>(path: P) -> Result<FileResolverProject> {
+/// `project` selects the project of a KPAR containing several
+fn interpret_project_path<P: AsRef<Utf8Path>>(
+    path: P,
+    project: Option<&str>,
+) -> Result<FileResolverProject> {
     let metadata = wrapfs::metadata(&path)?;
     Ok(if metadata.is_file() {
-        FileResolverProject::LocalKParProject(LocalKParProject::new(
-            path,
-            KparInnerPath::Guess,
-            None,
-            None,
-        ))
+        let root = project.map_or(KparInnerPath::Guess, |name| {
+            KparInnerPath::Named(name.to_owned())
+        });
+        FileResolverProject::LocalKParProject(LocalKParProject::new(path, root, None, None))
     } else if metadata.is_dir() {
+        if project.is_some() {
+            bail!("`--project` can only be used with KPAR archives");
+        }
         FileResolverProject::LocalSrcProject(LocalSrcProject {
             nominal_path: None,
             project_path: path.as_ref().as_str().into(),
@@ -128,17 +152,65 @@ fn interpret_project_path<P: AsRef<Utf8Path>>(path: P) -> Result<FileResolverPro
     })
 }
 
+/// Print the info of every project installed in `env` as JSON lines
+pub fn command_info_all_installed(
+    env: Option<LocalDirectoryEnvironment>,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    let Some(env) = env else {
+        bail!("unable to identify environment to list");
+    };
+    print_info_entries(&env, out)
+}
+
+/// Print the info of every project in the indexes at `index_urls` as JSON
+/// lines, as they are fetched
+pub fn command_info_all_indexes<Policy: HTTPAuthentication>(
+    index_urls: Vec<Url>,
+    client: reqwest_middleware::ClientWithMiddleware,
+    runtime: Arc<tokio::runtime::Runtime>,
+    auth_policy: Arc<Policy>,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    for url in index_urls {
+        let env =
+            IndexEnvironmentAsync::from_discovery_root(client.clone(), auth_policy.clone(), url)
+                .to_tokio_sync(runtime.clone());
+        print_info_entries(&env, out)?;
+    }
+    Ok(())
+}
+
+/// Projects that cannot be read are reported after printing the others
+fn print_info_entries<Env: ReadEnvironment>(env: &Env, out: &mut dyn OutputSink) -> Result<()> {
+    let mut failed = 0;
+    for entry in do_info_env(env).coded()? {
+        match entry {
+            Ok(entry) => out.emit(
+                &serde_json::to_string(&entry)?,
+                serde_json::to_value(&entry)?,
+            )?,
+            Err(e) => {
+                log::error!("{e}");
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("failed to read {failed} project(s)");
+    }
+    Ok(())
+}
+
 pub fn command_info_path<P: AsRef<Utf8Path>>(
     path: P,
+    project: Option<&str>,
     excluded_iris: &HashSet<String>,
+    out: &mut dyn OutputSink,
 ) -> Result<()> {
-    let project = interpret_project_path(&path)?;
+    let project = interpret_project_path(&path, project)?;
     match do_info_project(&project) {
-        Ok((info, _)) => {
-            pprint_interchange_project(&info, excluded_iris);
-
-            Ok(())
-        }
+        Ok((info, _)) => pprint_interchange_project(&info, excluded_iris, out),
         Err(err) => bail!(CliError::InvalidProject {
             iri: path.as_ref().to_string(),
             source: err
@@ -151,12 +223,15 @@ pub fn command_info_uri<Policy: HTTPAuthentication>(
     uri: Iri<String>,
     _normalise: bool,
     client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
     index_urls: Option<Vec<Url>>,
+    indexes: &[Index],
     excluded_iris: &HashSet<String>,
     overrides: Vec<(Iri<String>, Vec<OverrideProject<Policy>>)>,
     runtime: Arc<tokio::runtime::Runtime>,
     auth_policy: Arc<Policy>,
     ctx: ProjectContext,
+    out: &mut dyn OutputSink,
 ) -> Result<()> {
     // FIXME: The more precise error messages are ignored here. For example,
     // if a user provides a relative file URI (this is invalid since file
@@ -171,40 +246,53 @@ pub fn command_info_uri<Policy: HTTPAuthentication>(
             ctx.env,
             Some(client),
             index_urls,
+            vec![],
             runtime,
             auth_policy,
-        )?,
+        )?
+        .with_git_cache(git_cache)
+        .with_index_scopes(indexes)?,
     );
 
-    let (info, _) = do_info(&uri, &combined_resolver)?;
-    pprint_interchange_project(&info, excluded_iris);
-    Ok(())
+    let (info, _) = do_info(&uri, &combined_resolver).coded()?;
+    pprint_interchange_project(&info, excluded_iris, out)
 }
 
-fn print_output(output: Option<Vec<String>>, numbered: bool) {
-    if let Some(lines) = output {
-        if numbered {
-            for (line_number, line) in lines.iter().enumerate() {
-                println!("{}: {}", line_number + 1, line);
-            }
+fn print_output(
+    output: Option<Vec<String>>,
+    numbered: bool,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    if let Some(lines) = output.filter(|lines| !lines.is_empty()) {
+        let human = if numbered {
+            lines
+                .iter()
+                .enumerate()
+                .map(|(line_number, line)| format!("{}: {}", line_number + 1, line))
+                .collect::<Vec<_>>()
+                .join("\n")
         } else {
-            for line in lines {
-                println!("{}", line);
-            }
-        }
+            lines.join("\n")
+        };
+        out.emit(&human, json!(lines))?;
     }
+    Ok(())
 }
 
 pub fn command_info_verb_path<P: AsRef<Utf8Path>>(
     path: P,
+    project: Option<&str>,
     verb: InfoCommandVerb,
     numbered: bool,
+    out: &mut dyn OutputSink,
 ) -> Result<()> {
-    let project = interpret_project_path(&path)?;
+    let project = interpret_project_path(&path, project)?;
 
     match project {
         FileResolverProject::LocalSrcProject(mut local_src_project) => match verb {
-            InfoCommandVerb::Get(get_verb) => apply_get(&get_verb, &local_src_project, numbered),
+            InfoCommandVerb::Get(get_verb) => {
+                apply_get(&get_verb, &local_src_project, numbered, out)
+            }
             InfoCommandVerb::Set(set_verb) => apply_set(&set_verb, &mut local_src_project),
             InfoCommandVerb::Clear(clear_verb) => apply_clear(&clear_verb, &mut local_src_project),
             InfoCommandVerb::Add(add_verb) => apply_add(&add_verb, &mut local_src_project),
@@ -213,7 +301,9 @@ pub fn command_info_verb_path<P: AsRef<Utf8Path>>(
             }
         },
         FileResolverProject::LocalKParProject(local_kpar_project) => match verb {
-            InfoCommandVerb::Get(get_verb) => apply_get(&get_verb, &local_kpar_project, numbered),
+            InfoCommandVerb::Get(get_verb) => {
+                apply_get(&get_verb, &local_kpar_project, numbered, out)
+            }
             InfoCommandVerb::Set(_) => bail!("`set` cannot be used with kpar archives"),
             InfoCommandVerb::Clear(_) => bail!("`clear` cannot be used with kpar archives"),
             InfoCommandVerb::Add(_) => bail!("`add` cannot be used with kpar archives"),
@@ -228,11 +318,14 @@ pub fn command_info_verb_uri<Policy: HTTPAuthentication>(
     verb: InfoCommandVerb,
     numbered: bool,
     client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
     index_urls: Option<Vec<Url>>,
+    indexes: &[Index],
     overrides: Vec<(Iri<String>, Vec<OverrideProject<Policy>>)>,
     runtime: Arc<tokio::runtime::Runtime>,
     auth_policy: Arc<Policy>,
     ctx: ProjectContext,
+    out: &mut dyn OutputSink,
 ) -> Result<()> {
     match verb {
         InfoCommandVerb::Get(get_verb) => {
@@ -243,19 +336,22 @@ pub fn command_info_verb_uri<Policy: HTTPAuthentication>(
                     ctx.env,
                     Some(client),
                     index_urls,
+                    vec![],
                     runtime,
                     auth_policy,
-                )?,
+                )?
+                .with_git_cache(git_cache)
+                .with_index_scopes(indexes)?,
             );
 
             match get_verb {
                 crate::cli::GetVerb::GetInfoVerb(get_info_verb) => {
-                    let (info, _meta) = do_info(&uri, &combined_resolver)?;
-                    apply_get_info(&get_info_verb, info, numbered)?;
+                    let (info, _meta) = do_info(&uri, &combined_resolver).coded()?;
+                    apply_get_info(&get_info_verb, info, numbered, out)?;
                 }
                 crate::cli::GetVerb::GetMetaVerb(get_meta_verb) => {
-                    let (_info, meta) = do_info(&uri, &combined_resolver)?;
-                    apply_get_meta(&get_meta_verb, meta, numbered)?;
+                    let (_info, meta) = do_info(&uri, &combined_resolver).coded()?;
+                    apply_get_meta(&get_meta_verb, meta, numbered, out)?;
                 }
             }
         }
@@ -272,9 +368,10 @@ pub fn command_info_current_project(
     mut current_project: LocalSrcProject,
     verb: InfoCommandVerb,
     numbered: bool,
+    out: &mut dyn OutputSink,
 ) -> Result<()> {
     match verb {
-        InfoCommandVerb::Get(get_verb) => apply_get(&get_verb, &current_project, numbered),
+        InfoCommandVerb::Get(get_verb) => apply_get(&get_verb, &current_project, numbered, out),
         InfoCommandVerb::Set(set_verb) => apply_set(&set_verb, &mut current_project),
         InfoCommandVerb::Clear(clear_verb) => apply_clear(&clear_verb, &mut current_project),
         InfoCommandVerb::Add(add_verb) => apply_add(&add_verb, &mut current_project),
@@ -330,13 +427,14 @@ fn apply_get<Project: ProjectRead>(
     get_verb: &crate::cli::GetVerb,
     project: &Project,
     numbered: bool,
+    out: &mut dyn OutputSink,
 ) -> Result<()> {
     match get_verb {
         crate::cli::GetVerb::GetInfoVerb(get_info_verb) => {
-            apply_get_info(get_info_verb, get_info_or_bail(project)?, numbered)
+            apply_get_info(get_info_verb, get_info_or_bail(project)?, numbered, out)
         }
         crate::cli::GetVerb::GetMetaVerb(get_meta_verb) => {
-            apply_get_meta(get_meta_verb, get_meta_or_bail(project)?, numbered)
+            apply_get_meta(get_meta_verb, get_meta_or_bail(project)?, numbered, out)
         }
     }
 }
@@ -345,16 +443,19 @@ fn apply_get_info(
     get_info_verb: &GetInfoVerb,
     info: InterchangeProjectInfoRaw,
     numbered: bool,
+    out: &mut dyn OutputSink,
 ) -> Result<()> {
     match get_info_verb {
-        GetInfoVerb::GetName => print_output(Some(vec![info.name]), numbered),
-        GetInfoVerb::GetPublisher => print_output(info.publisher.map(|x| vec![x]), numbered),
-        GetInfoVerb::GetDescription => print_output(info.description.map(|x| vec![x]), numbered),
-        GetInfoVerb::GetVersion => print_output(Some(vec![info.version]), numbered),
-        GetInfoVerb::GetLicense => print_output(info.license.map(|x| vec![x]), numbered),
-        GetInfoVerb::GetMaintainer => print_output(Some(info.maintainer), numbered),
-        GetInfoVerb::GetWebsite => print_output(info.website.map(|x| vec![x]), numbered),
-        GetInfoVerb::GetTopic => print_output(Some(info.topic), numbered),
+        GetInfoVerb::GetName => print_output(Some(vec![info.name]), numbered, out),
+        GetInfoVerb::GetPublisher => print_output(info.publisher.map(|x| vec![x]), numbered, out),
+        GetInfoVerb::GetDescription => {
+            print_output(info.description.map(|x| vec![x]), numbered, out)
+        }
+        GetInfoVerb::GetVersion => print_output(Some(vec![info.version]), numbered, out),
+        GetInfoVerb::GetLicense => print_output(info.license.map(|x| vec![x]), numbered, out),
+        GetInfoVerb::GetMaintainer => print_output(Some(info.maintainer), numbered, out),
+        GetInfoVerb::GetWebsite => print_output(info.website.map(|x| vec![x]), numbered, out),
+        GetInfoVerb::GetTopic => print_output(Some(info.topic), numbered, out),
         GetInfoVerb::GetUsage => print_output(
             Some(
                 info.usage
@@ -363,27 +464,35 @@ fn apply_get_info(
                         InterchangeProjectUsageRaw::Resource {
                             resource,
                             version_constraint,
+                            when,
+                            hash,
                         } => {
+                            let mut usage = resource;
                             if let Some(version_constraint) = version_constraint {
-                                format!("{resource} ({version_constraint})")
-                            } else {
-                                resource.clone()
+                                usage.push_str(&format!(" ({version_constraint})"));
+                            }
+                            if let Some(when) = when {
+                                usage.push_str(&format!(" when `{when}`"));
                             }
+                            if let Some(hash) = hash {
+                                usage.push_str(&format!(" with hash `{hash}`"));
+                            }
+                            usage
                         }
                     })
                     .collect(),
             ),
             numbered,
+            out,
         ),
     }
-
-    Ok(())
 }
 
 fn apply_get_meta(
     get_meta_verb: &GetMetaVerb,
     meta: InterchangeProjectMetadataRaw,
     numbered: bool,
+    out: &mut dyn OutputSink,
 ) -> Result<()> {
     match get_meta_verb {
         GetMetaVerb::GetIndex => print_output(
@@ -394,16 +503,19 @@ fn apply_get_meta(
                     .collect(),
             ),
             numbered,
+            out,
         ),
-        GetMetaVerb::GetCreated => print_output(Some(vec![meta.created]), numbered),
-        GetMetaVerb::GetMetamodel => print_output(meta.metamodel.map(|x| vec![x]), numbered),
+        GetMetaVerb::GetCreated => print_output(Some(vec![meta.created]), numbered, out),
+        GetMetaVerb::GetMetamodel => print_output(meta.metamodel.map(|x| vec![x]), numbered, out),
         GetMetaVerb::GetIncludesDerived => print_output(
             meta.includes_derived.map(|x| vec![format!("{}", x)]),
             numbered,
+            out,
         ),
         GetMetaVerb::GetIncludesImplied => print_output(
             meta.includes_implied.map(|x| vec![format!("{}", x)]),
             numbered,
+            out,
         ),
         GetMetaVerb::GetChecksum => print_output(
             meta.checksum.map(|xs| {
@@ -416,10 +528,9 @@ fn apply_get_meta(
                     .collect()
             }),
             numbered,
+            out,
         ),
     }
-
-    Ok(())
 }
 
 fn apply_set<Project: ProjectRead + ProjectMut>(
@@ -1,13 +1,25 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
-use crate::CliError;
-use anyhow::Result;
-use camino::{Utf8Path, Utf8PathBuf};
-use sysand_core::project::{local_src::LocalSrcProject, utils::wrapfs};
+use crate::{CliError, error::CodedResultExt, style::HEADER};
+use anyhow::{Result, bail};
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use sysand_core::{
+    commands::init::{suggest_project_iri, validate_project_iri},
+    config::local_fs::{
+        CONFIG_FILE, set_project_iri_in_config, set_workspace_shared_lock_in_config,
+    },
+    include::do_include,
+    model::HashAlg,
+    project::{local_src::LocalSrcProject, utils::wrapfs},
+    symbols::Language,
+    workspace::{Workspace, WorkspaceInfoRaw, WorkspaceProjectInfoRaw},
+};
+use typed_path::Utf8UnixPathBuf;
 
 const DEFAULT_VERSION: &str = "0.0.1";
 
+#[expect(clippy::too_many_arguments)]
 pub fn command_init(
     name: Option<String>,
     publisher: Option<String>,
@@ -15,6 +27,7 @@ pub fn command_init(
     no_semver: bool,
     license: Option<String>,
     no_spdx: bool,
+    iri: Option<String>,
     path: Option<String>,
 ) -> Result<()> {
     let path = match path {
@@ -30,6 +43,13 @@ pub fn command_init(
         Some(n) => n,
         None => default_name_from_path(&path)?,
     };
+    let iri = match iri {
+        Some(iri) => Some(iri),
+        None => prompt_project_iri(suggest_project_iri(&name, publisher.as_deref()).as_deref())?,
+    };
+    if let Some(iri) = &iri {
+        validate_project_iri(iri).coded()?;
+    }
 
     sysand_core::init::do_init_ext(
         name,
@@ -40,13 +60,163 @@ pub fn command_init(
         no_spdx,
         &mut LocalSrcProject {
             nominal_path: None,
-            project_path: path,
+            project_path: path.clone(),
             expected_checksum: None,
         },
+    )
+    .coded()?;
+    if let Some(iri) = iri {
+        set_project_iri_in_config(path.join(CONFIG_FILE), iri)?;
+    }
+    Ok(())
+}
+
+/// Create a workspace in `path` with a project in each of the `members`
+/// paths, and enable `workspace.shared_lock` in its configuration file so
+/// that its projects are locked and installed together
+pub fn command_init_workspace(
+    path: Option<String>,
+    members: Vec<String>,
+    publisher: Option<String>,
+    version: Option<String>,
+    no_semver: bool,
+    license: Option<String>,
+    no_spdx: bool,
+) -> Result<()> {
+    let root = path.map(Utf8PathBuf::from).unwrap_or_else(|| ".".into());
+    let version = version.unwrap_or_else(|| DEFAULT_VERSION.to_owned());
+
+    let mut projects = vec![];
+    for member in &members {
+        let mut components = vec![];
+        for component in Utf8Path::new(member).components() {
+            match component {
+                Utf8Component::Normal(c) => components.push(c),
+                Utf8Component::CurDir => {}
+                _ => bail!("workspace member path `{member}` must be inside the workspace"),
+            }
+        }
+        let Some(name) = components.last() else {
+            bail!("workspace member path `{member}` must not be the workspace root");
+        };
+        projects.push(WorkspaceProjectInfoRaw {
+            path: components.join("/"),
+            iris: suggest_project_iri(name, publisher.as_deref())
+                .into_iter()
+                .collect(),
+        });
+    }
+
+    let header = HEADER;
+    log::info!("{header}{:>12}{header:#} workspace in `{root}`", "Creating");
+    Workspace::create(
+        root.clone(),
+        WorkspaceInfoRaw {
+            projects: projects.clone(),
+            meta: None,
+        },
     )?;
+    set_workspace_shared_lock_in_config(root.join(CONFIG_FILE), true)?;
+
+    for member in projects {
+        let project_path = root.join(&member.path);
+        wrapfs::create_dir_all(&project_path)?;
+        sysand_core::init::do_init_ext(
+            default_name_from_path(&project_path)?,
+            publisher.clone(),
+            version.clone(),
+            no_semver,
+            license.clone(),
+            no_spdx,
+            &mut LocalSrcProject {
+                nominal_path: None,
+                project_path: project_path.clone(),
+                expected_checksum: None,
+            },
+        )
+        .coded()?;
+        if let Some(iri) = member.iris.first() {
+            set_project_iri_in_config(project_path.join(CONFIG_FILE), iri)?;
+        }
+    }
     Ok(())
 }
 
+/// Ask for the IRI of the new project, suggesting `suggestion`. Returns
+/// `None` when not running in a terminal or if none is wanted
+fn prompt_project_iri(suggestion: Option<&str>) -> Result<Option<String>> {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    if !(std::io::stdin().is_terminal() && std::io::stderr().is_terminal()) {
+        return Ok(None);
+    }
+    let mut stderr = std::io::stderr();
+    match suggestion {
+        Some(suggestion) => write!(stderr, "IRI of the project, `-` for none [{suggestion}]: ")?,
+        None => write!(stderr, "IRI of the project, empty for none: ")?,
+    }
+    stderr.flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(match answer.trim() {
+        "-" => None,
+        "" => suggestion.map(str::to_owned),
+        iri => Some(iri.to_owned()),
+    })
+}
+
+/// Create a minimal project in `path` that includes all SysML v2 and KerML
+/// files under it. Hidden directories and paths skipped by
+/// [`sysand_core::walk::walker`] are skipped
+pub fn command_init_here(path: &Utf8Path) -> Result<LocalSrcProject> {
+    let root = wrapfs::canonicalize(path)?;
+    let mut project = LocalSrcProject {
+        nominal_path: Some(Utf8UnixPathBuf::from(".")),
+        project_path: root.clone(),
+        expected_checksum: None,
+    };
+
+    sysand_core::init::do_init_ext(
+        default_name_from_path(&root)?,
+        None,
+        DEFAULT_VERSION.to_owned(),
+        false,
+        None,
+        false,
+        &mut project,
+    )
+    .coded()?;
+
+    let mut sources = vec![];
+    let walker = sysand_core::walk::walker(&root, |e| {
+        !(e.file_type().is_some_and(|t| t.is_dir())
+            && e.file_name().to_string_lossy().starts_with('.'))
+    });
+    for entry in walker {
+        let entry = entry.map_err(|e| sysand_core::walk::walk_error(&root, e))?;
+        let Some(path) = Utf8Path::from_path(entry.path()) else {
+            log::warn!("skipping non-UTF-8 path `{}`", entry.path().display());
+            continue;
+        };
+        if entry.file_type().is_some_and(|t| t.is_file())
+            && path.extension().and_then(Language::from_suffix).is_some()
+        {
+            sources.push(project.get_unix_path(path)?);
+        }
+    }
+
+    do_include(
+        &mut project,
+        sources.into_iter(),
+        Some(HashAlg::Sha256),
+        true,
+        None,
+    )
+    .coded()?;
+
+    Ok(project)
+}
+
 fn default_name_from_path<P: AsRef<Utf8Path>>(path: P) -> Result<String> {
     Ok(wrapfs::canonicalize(&path)?
         .file_name()
@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use anyhow::{Result, bail};
+use camino::Utf8Path;
+use indexmap::IndexMap;
+use serde_json::json;
+use sysand_core::output::OutputSink;
+use sysand_core::{
+    commands::list::do_list, context::ProjectContext,
+    env::local_directory::LocalDirectoryEnvironment,
+};
+
+use crate::{
+    CliError,
+    commands::lock::{lockfile_path, read_lockfile},
+    error::CodedResultExt,
+};
+
+/// Print the usages of the current project with their version constraint,
+/// the version locked in the lockfile in `lock_root`, whether that version
+/// is installed and the kind of source it is locked to. If any usage has
+/// one of the `reasons`, they are shown too
+pub fn command_list<P: AsRef<Utf8Path>>(
+    lock_root: P,
+    ctx: ProjectContext,
+    reasons: &IndexMap<String, String>,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    let current_project = ctx
+        .current_project
+        .ok_or(CliError::MissingProjectCurrentDir)?;
+    let Some(info) = current_project.get_info()? else {
+        bail!("project is missing project information")
+    };
+
+    let lockfile = lockfile_path(lock_root)?;
+    let lock = if lockfile.is_file() {
+        Some(read_lockfile(&lockfile)?)
+    } else {
+        log::warn!("lockfile `{lockfile}` not found, run `sysand lock` to create it");
+        None
+    };
+
+    let statuses =
+        do_list::<LocalDirectoryEnvironment>(&info.usage, lock.as_ref(), ctx.env.as_ref())
+            .coded()?;
+
+    let show_reasons = statuses
+        .iter()
+        .any(|status| reasons.contains_key(&status.iri));
+    let value = statuses
+        .iter()
+        .map(|status| {
+            json!({
+                "iri": status.iri,
+                "version_constraint": status.version_constraint,
+                "locked_version": status.locked_version,
+                "installed": status.installed,
+                "source": status.source.as_ref().map(|s| s.to_string()),
+                "reason": reasons.get(&status.iri),
+            })
+        })
+        .collect();
+    let rows: Vec<Vec<String>> = statuses
+        .into_iter()
+        .map(|status| {
+            let reason = show_reasons.then(|| {
+                reasons
+                    .get(&status.iri)
+                    .cloned()
+                    .unwrap_or_else(|| "-".into())
+            });
+            [
+                status.iri,
+                status.version_constraint.unwrap_or_else(|| "*".into()),
+                status.locked_version.unwrap_or_else(|| "-".into()),
+                if status.installed { "yes" } else { "no" }.into(),
+                status
+                    .source
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "-".into()),
+            ]
+            .into_iter()
+            .chain(reason)
+            .collect()
+        })
+        .collect();
+    let header: Vec<String> = ["IRI", "CONSTRAINT", "LOCKED", "INSTALLED", "SOURCE"]
+        .into_iter()
+        .chain(show_reasons.then_some("REASON"))
+        .map(String::from)
+        .collect();
+    let mut widths: Vec<usize> = header.iter().map(String::len).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let table = std::iter::once(&header)
+        .chain(&rows)
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            line.trim_end().to_owned()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    out.emit(&table, serde_json::Value::Array(value))?;
+
+    Ok(())
+}
@@ -1,28 +1,130 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use anyhow::Result;
-use camino::Utf8Path;
+use anyhow::{Result, bail};
+use camino::{Utf8Path, Utf8PathBuf};
 
 use sysand_core::{
     auth::HTTPAuthentication,
-    commands::lock::{DEFAULT_LOCKFILE_NAME, LockOutcome, do_lock_local_editable},
+    commands::lock::{
+        DEFAULT_LOCKFILE_NAME, JSON_LOCKFILE_NAME, LockOutcome, do_lock_local_editable,
+        do_lock_workspace,
+    },
     config::Config,
     context::ProjectContext,
+    lock::{Lock, LockFormat, Project},
     project::{memory::InMemoryProject, utils::wrapfs},
     resolve::{
+        git_cache::GitCache,
         memory::{AcceptAll, MemoryResolver},
         priority::PriorityResolver,
         standard::{StandardResolver, standard_resolver},
     },
-    stdlib::known_std_libs,
+    workspace::Workspace,
 };
 use typed_path::Utf8UnixPath;
 
-use crate::{DEFAULT_INDEX_URL, cli::ResolutionOptions, get_overrides};
+use crate::{
+    CliError, DEFAULT_INDEX_URL, cli::ResolutionOptions, error::CodedResultExt, get_overrides,
+    logger::Span,
+};
+
+/// Path of the lockfile in `dir`, in whichever [`LockFormat`] it is. If
+/// there is no lockfile, this is the path of a TOML lockfile
+pub fn lockfile_path<P: AsRef<Utf8Path>>(dir: P) -> Result<Utf8PathBuf> {
+    let toml = dir.as_ref().join(DEFAULT_LOCKFILE_NAME);
+    let json = dir.as_ref().join(JSON_LOCKFILE_NAME);
+    match (wrapfs::is_file(&toml)?, wrapfs::is_file(&json)?) {
+        (true, true) => bail!("found both `{toml}` and `{json}`, remove the one not in use"),
+        (false, true) => Ok(json),
+        _ => Ok(toml),
+    }
+}
+
+/// Whether `dir` has a lockfile in either [`LockFormat`]
+pub fn has_lockfile<P: AsRef<Utf8Path>>(dir: P) -> bool {
+    [LockFormat::Toml, LockFormat::Json]
+        .iter()
+        .any(|f| dir.as_ref().join(f.file_name()).is_file())
+}
+
+/// Read and parse `lockfile`, which is in either [`LockFormat`]
+pub fn read_lockfile<P: AsRef<Utf8Path>>(lockfile: P) -> Result<Lock> {
+    let lockfile = lockfile.as_ref();
+    match wrapfs::read_to_string(lockfile)?.parse() {
+        Ok(lock) => Ok(lock),
+        // Include file path in errors
+        Err(e) => bail!("invalid lockfile `{lockfile}`:\n{e}"),
+    }
+}
+
+/// Write `lock` to the lockfile in `dir` in `format`, removing a lockfile
+/// in the other format, so that there is only one
+pub fn write_lockfile<P: AsRef<Utf8Path>>(dir: P, lock: &Lock, format: LockFormat) -> Result<()> {
+    let dir = dir.as_ref();
+    wrapfs::write(dir.join(format.file_name()), lock.serialize(format))?;
+    for other in [LockFormat::Toml, LockFormat::Json] {
+        let path = dir.join(other.file_name());
+        if other != format && wrapfs::is_file(&path)? {
+            log::debug!("removing lockfile `{path}` in the previous format");
+            wrapfs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Check that the lockfile in `dir` is `lock`, as `sysand lock --locked`
+/// does instead of writing it
+pub fn check_lockfile<P: AsRef<Utf8Path>>(dir: P, lock: &Lock) -> Result<()> {
+    let path = lockfile_path(dir)?;
+    let changes = if wrapfs::is_file(&path)? {
+        lock_changes(&read_lockfile(&path)?.canonicalize(), lock)
+    } else {
+        vec!["lockfile does not exist".to_owned()]
+    };
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let changes = changes
+        .iter()
+        .map(|c| format!("  {c}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Err(CliError::OutdatedLockfile(path.into_string(), changes).into())
+}
+
+/// Differences from lock `old` to `new`, one per line
+fn lock_changes(old: &Lock, new: &Lock) -> Vec<String> {
+    let key = |p: &Project| p.identifiers.first().unwrap_or(&p.name).clone();
+    let old_projects: HashMap<_, _> = old.projects.iter().map(|p| (key(p), p)).collect();
+    let new_keys: HashSet<_> = new.projects.iter().map(key).collect();
+    let mut changes = vec![];
+    if old.markers != new.markers {
+        changes.push("markers changed".to_owned());
+    }
+    for project in &new.projects {
+        match old_projects.get(&key(project)) {
+            None => changes.push(format!("`{}` {} added", project.name, project.version)),
+            Some(old) if old.version != project.version => changes.push(format!(
+                "`{}` {} -> {}",
+                project.name, old.version, project.version
+            )),
+            Some(old) if *old != project => {
+                changes.push(format!("`{}` {} changed", project.name, project.version))
+            }
+            Some(_) => (),
+        }
+    }
+    for project in &old.projects {
+        if !new_keys.contains(&key(project)) {
+            changes.push(format!("`{}` {} removed", project.name, project.version));
+        }
+    }
+    changes
+}
 
 /// Generate a lockfile for `current_project`.
 #[expect(clippy::too_many_arguments)]
@@ -32,15 +134,49 @@ pub fn command_lock<P: AsRef<Utf8UnixPath>, Policy: HTTPAuthentication, R: AsRef
     config: &Config,
     project_root: R,
     client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
     runtime: Arc<tokio::runtime::Runtime>,
     auth_policy: Arc<Policy>,
     ctx: &ProjectContext,
 ) -> Result<sysand_core::lock::Lock> {
-    let provided_iris = if !resolution_opts.include_std {
-        known_std_libs()
-    } else {
-        HashMap::default()
-    };
+    let canonical = lock_project(
+        &path,
+        resolution_opts,
+        config,
+        project_root,
+        client,
+        git_cache,
+        runtime,
+        auth_policy,
+        ctx,
+    )?;
+    write_lockfile(
+        Utf8Path::new(path.as_ref().as_str()),
+        &canonical,
+        config.lock.format.unwrap_or_default(),
+    )?;
+
+    Ok(canonical)
+}
+
+/// Resolve the lock of `current_project` without writing it
+#[expect(clippy::too_many_arguments)]
+pub fn lock_project<P: AsRef<Utf8UnixPath>, Policy: HTTPAuthentication, R: AsRef<Utf8Path>>(
+    path: P,
+    resolution_opts: ResolutionOptions,
+    config: &Config,
+    project_root: R,
+    client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
+    runtime: Arc<tokio::runtime::Runtime>,
+    auth_policy: Arc<Policy>,
+    ctx: &ProjectContext,
+) -> Result<sysand_core::lock::Lock> {
+    let _span = Span::enter("resolution");
+    let provided_iris = crate::ignored_std_libs(
+        resolution_opts.include_std_mode(config),
+        ctx.current_project.as_ref(),
+    );
     let wrapped_resolver = create_resolver(
         resolution_opts,
         config,
@@ -49,6 +185,7 @@ pub fn command_lock<P: AsRef<Utf8UnixPath>, Policy: HTTPAuthentication, R: AsRef
         // TODO: avoid expensive clone here
         provided_iris.clone(),
         client,
+        git_cache,
         runtime,
         auth_policy,
     )?;
@@ -63,27 +200,135 @@ pub fn command_lock<P: AsRef<Utf8UnixPath>, Policy: HTTPAuthentication, R: AsRef
     } else {
         None
     };
-    let LockOutcome {
-        lock,
-        dependencies: _dependencies,
-    } = do_lock_local_editable(
+    let mut outcome = do_lock_local_editable(
         &path,
         &project_root,
         alias_iris,
         &provided_iris,
         wrapped_resolver,
         ctx,
-    )?;
+    )
+    .coded()?;
+    outcome.select_variants(&config.variants).coded()?;
+    let LockOutcome {
+        lock,
+        dependencies: _dependencies,
+    } = outcome;
 
-    let canonical = lock.canonicalize();
-    wrapfs::write(
-        Utf8Path::new(path.as_ref().as_str()).join(DEFAULT_LOCKFILE_NAME),
-        canonical.to_string(),
+    Ok(lock.canonicalize())
+}
+
+/// Generate a single lockfile in the root of `workspace` for all of its
+/// projects, used when `workspace.shared_lock` is set.
+#[expect(clippy::too_many_arguments)]
+pub fn command_lock_workspace<Policy: HTTPAuthentication>(
+    workspace: &Workspace,
+    resolution_opts: ResolutionOptions,
+    config: &Config,
+    client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
+    runtime: Arc<tokio::runtime::Runtime>,
+    auth_policy: Arc<Policy>,
+    ctx: &ProjectContext,
+) -> Result<sysand_core::lock::Lock> {
+    let canonical = lock_workspace(
+        workspace,
+        resolution_opts,
+        config,
+        client,
+        git_cache,
+        runtime,
+        auth_policy,
+        ctx,
+    )?;
+    write_lockfile(
+        workspace.root_path(),
+        &canonical,
+        config.lock.format.unwrap_or_default(),
     )?;
 
     Ok(canonical)
 }
 
+/// Resolve the shared lock of `workspace` without writing it
+#[expect(clippy::too_many_arguments)]
+pub fn lock_workspace<Policy: HTTPAuthentication>(
+    workspace: &Workspace,
+    resolution_opts: ResolutionOptions,
+    config: &Config,
+    client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
+    runtime: Arc<tokio::runtime::Runtime>,
+    auth_policy: Arc<Policy>,
+    ctx: &ProjectContext,
+) -> Result<sysand_core::lock::Lock> {
+    let _span = Span::enter("resolution");
+    let provided_iris = crate::ignored_std_libs(
+        resolution_opts.include_std_mode(config),
+        ctx.current_project.as_ref(),
+    );
+    let wrapped_resolver = create_resolver(
+        resolution_opts,
+        config,
+        workspace.root_path(),
+        ctx,
+        provided_iris.clone(),
+        client,
+        git_cache,
+        runtime,
+        auth_policy,
+    )?;
+
+    let mut outcome =
+        do_lock_workspace(workspace, &provided_iris, wrapped_resolver, ctx).coded()?;
+    outcome.select_variants(&config.variants).coded()?;
+    let LockOutcome {
+        lock,
+        dependencies: _dependencies,
+    } = outcome;
+
+    Ok(lock.canonicalize())
+}
+
+/// Merge lockfiles `ours` and `theirs` changed from `base`, writing the
+/// result to `ours` in its format, as a git merge driver does. Projects
+/// changed differently on both sides are taken from a new lock of the
+/// current project, created by `resolve` only if there are any
+pub fn command_lock_merge<F: FnOnce() -> Result<Lock>>(
+    base: &Utf8Path,
+    ours: &Utf8Path,
+    theirs: &Utf8Path,
+    resolve: F,
+) -> Result<()> {
+    let format = LockFormat::of_contents(&wrapfs::read_to_string(ours)?);
+    // Git passes an empty base if both sides added the lockfile
+    let base = if wrapfs::read_to_string(base)?.trim().is_empty() {
+        Lock::default()
+    } else {
+        read_lockfile(base)?
+    };
+    let merge = Lock::merge(base, read_lockfile(ours)?, read_lockfile(theirs)?);
+    let lock = if merge.is_clean() {
+        merge.lock
+    } else {
+        let mut conflicts: Vec<_> = merge.conflicts.iter().map(|n| format!("`{n}`")).collect();
+        if merge.markers_conflict {
+            conflicts.push("markers".to_owned());
+        }
+        log::info!(
+            "re-solving conflicting lockfile entries: {}",
+            conflicts.join(", ")
+        );
+        let resolved = resolve()?;
+        match merge.resolve(resolved) {
+            Ok(lock) => lock,
+            Err(e) => bail!("merged lockfile `{ours}` is invalid, run `sysand lock`:\n{e}"),
+        }
+    };
+    wrapfs::write(ours, lock.serialize(format))?;
+    Ok(())
+}
+
 #[expect(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn create_resolver<R: AsRef<Utf8Path>, Policy: HTTPAuthentication>(
     resolution_opts: ResolutionOptions,
@@ -92,6 +337,7 @@ pub fn create_resolver<R: AsRef<Utf8Path>, Policy: HTTPAuthentication>(
     ctx: &ProjectContext,
     provided_iris: HashMap<String, Vec<InMemoryProject>>,
     client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
     runtime: Arc<tokio::runtime::Runtime>,
     auth_policy: Arc<Policy>,
 ) -> Result<
@@ -114,6 +360,7 @@ pub fn create_resolver<R: AsRef<Utf8Path>, Policy: HTTPAuthentication>(
         default_index,
         no_index,
         include_std: _,
+        no_include_std: _,
     } = resolution_opts;
 
     let index_urls = if no_index {
@@ -152,9 +399,12 @@ pub fn create_resolver<R: AsRef<Utf8Path>, Policy: HTTPAuthentication>(
             ctx.env.to_owned(),
             Some(client),
             index_urls,
+            config.shared_env_urls()?,
             runtime,
             auth_policy,
-        )?,
+        )?
+        .with_git_cache(git_cache)
+        .with_index_scopes(&config.indexes)?,
     );
 
     Ok(wrapped_resolver)
@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use anyhow::Result;
+use camino::Utf8Path;
+
+use sysand_core::{
+    commands::{lock::DEFAULT_LOCKFILE_NAME, migrate::do_migrate_file},
+    context::ProjectContext,
+    env::{
+        DEFAULT_ENV_NAME,
+        local_directory::{METADATA_PATH, metadata::ENV_FORMAT},
+    },
+    lock::LOCK_FORMAT,
+    migration::Format,
+    project::utils::wrapfs,
+};
+
+use crate::error::CodedResultExt;
+
+/// Migrate the lockfiles and the env of the current workspace or project
+/// (or of the current directory, if there is neither) to the current
+/// format versions
+pub fn command_migrate(dry_run: bool, ctx: ProjectContext) -> Result<()> {
+    let root = match (&ctx.current_workspace, &ctx.current_project) {
+        (Some(workspace), _) => workspace.root_path().to_owned(),
+        (None, Some(project)) => project.root_path().to_owned(),
+        (None, None) => ctx.current_directory.clone(),
+    };
+    let mut lock_dirs = vec![root.clone()];
+    if let Some(workspace) = &ctx.current_workspace {
+        for path in workspace.absolute_project_paths() {
+            if !lock_dirs.contains(&path) {
+                lock_dirs.push(path);
+            }
+        }
+    }
+
+    let mut migrated = false;
+    for dir in lock_dirs {
+        migrated |= migrate_file(&dir.join(DEFAULT_LOCKFILE_NAME), &LOCK_FORMAT, dry_run)?;
+    }
+    migrated |= migrate_file(
+        &root.join(DEFAULT_ENV_NAME).join(METADATA_PATH),
+        &ENV_FORMAT,
+        dry_run,
+    )?;
+
+    if !migrated {
+        log::info!("{:>12} nothing to do: all files are up to date", ' ');
+    }
+    Ok(())
+}
+
+/// Migrate the file at `path` if it exists. Returns whether it was (or
+/// would be) migrated
+fn migrate_file(path: &Utf8Path, format: &Format, dry_run: bool) -> Result<bool> {
+    if !wrapfs::is_file(path)? {
+        return Ok(false);
+    }
+    Ok(do_migrate_file(path, format, dry_run).coded()?.is_some())
+}
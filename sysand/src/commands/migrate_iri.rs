@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use anyhow::{Result, bail};
+use camino::Utf8PathBuf;
+use fluent_uri::Iri;
+
+use sysand_core::{
+    add::do_add,
+    context::ProjectContext,
+    init::do_init,
+    lock::LockFormat,
+    migrate_iri::{do_migrate_iri_project, migrate_iri_lock},
+    model::InterchangeProjectUsageRaw,
+    project::{local_src::LocalSrcProject, utils::wrapfs},
+    purl::parse_sysand_purl,
+};
+
+use crate::{
+    CliError,
+    commands::lock::{lockfile_path, read_lockfile},
+    error::CodedResultExt,
+};
+
+/// Replace usages of `old` with `new` in the current project, or in all
+/// projects of the current workspace, and in their lockfiles. If
+/// `redirect_stub` is given as `(path, version)`, create a project at `path`
+/// that uses only `new`, to be published as `version` of the project
+/// previously identified by `old`.
+pub fn command_migrate_iri(
+    old: Iri<String>,
+    new: Iri<String>,
+    dry_run: bool,
+    redirect_stub: Option<(Utf8PathBuf, String)>,
+    ctx: ProjectContext,
+) -> Result<()> {
+    let project_paths = match (&ctx.current_workspace, &ctx.current_project) {
+        (Some(workspace), _) => workspace.absolute_project_paths(),
+        (None, Some(project)) => vec![project.root_path().to_owned()],
+        (None, None) => bail!(CliError::MissingProjectCurrentDir),
+    };
+    let (old, new) = (old.as_str(), new.as_str());
+
+    let header = sysand_core::style::get_style_config().header;
+    let migrating = "Migrating";
+    let updated = if dry_run { "Would update" } else { "Updated" };
+    log::info!("{header}{migrating:>12}{header:#} `{old}` to `{new}`");
+
+    // Name and publisher of the project identified by `old`
+    let mut old_project = None;
+    let mut total = 0;
+    for path in project_paths {
+        let mut project = LocalSrcProject {
+            nominal_path: None,
+            project_path: path.clone(),
+            expected_checksum: None,
+        };
+        let usages = do_migrate_iri_project(&mut project, old, new, dry_run).coded()?;
+        if usages > 0 {
+            log::info!(
+                "{header}{updated:>12}{header:#} {usages} usage(s) in `{}`",
+                path.join(".project.json")
+            );
+        }
+        total += usages;
+
+        let lockfile = lockfile_path(&path)?;
+        if !wrapfs::is_file(&lockfile)? {
+            continue;
+        }
+        let mut lock = read_lockfile(&lockfile)?;
+        if old_project.is_none() {
+            old_project = lock
+                .projects
+                .iter()
+                .find(|p| p.identifiers.iter().any(|i| i == old))
+                .map(|p| (p.name.clone(), p.publisher.clone()));
+        }
+        let entries = migrate_iri_lock(&mut lock, old, new);
+        if entries > 0 {
+            if !dry_run {
+                let format = lockfile
+                    .file_name()
+                    .and_then(LockFormat::from_file_name)
+                    .unwrap_or_default();
+                wrapfs::write(&lockfile, lock.canonicalize().serialize(format))?;
+            }
+            log::info!("{header}{updated:>12}{header:#} {entries} entries in `{lockfile}`");
+        }
+        total += entries;
+    }
+    if total == 0 {
+        log::warn!("no usages of `{old}` found");
+    }
+
+    if let Some((path, version)) = redirect_stub {
+        let (name, publisher) = match old_project {
+            Some(project) => project,
+            None => match parse_sysand_purl(old) {
+                Ok(Some((publisher, name))) => (name.to_owned(), Some(publisher.to_owned())),
+                _ => bail!(
+                    "cannot determine the name of the project identified by `{old}` for the\n\
+                    redirect stub, since it is neither in a lockfile nor a Sysand PURL"
+                ),
+            },
+        };
+        if dry_run {
+            let creating = "Would create";
+            log::info!(
+                "{header}{creating:>12}{header:#} redirect stub `{name}` {version} in `{path}`"
+            );
+        } else {
+            wrapfs::create_dir_all(&path)?;
+            let mut stub = LocalSrcProject {
+                nominal_path: None,
+                project_path: path,
+                expected_checksum: None,
+            };
+            do_init(name, publisher, version, None, &mut stub).coded()?;
+            do_add(
+                &mut stub,
+                &InterchangeProjectUsageRaw::Resource {
+                    resource: new.to_owned(),
+                    version_constraint: None,
+                    when: None,
+                    hash: None,
+                },
+            )
+            .coded()?;
+        }
+    }
+
+    Ok(())
+}
@@ -2,17 +2,36 @@
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
 pub mod add;
+pub mod audit;
 pub mod build;
+pub mod bundle;
+pub mod cache;
 pub mod clone;
+pub mod config;
 pub mod env;
 pub mod exclude;
+pub mod export;
+pub mod files;
+pub mod graph;
+pub mod hooks;
+pub mod import;
 pub mod include;
 pub mod index;
 pub mod info;
 pub mod init;
+pub mod list;
 pub mod lock;
+pub mod migrate;
+pub mod migrate_iri;
+pub mod mv;
+pub mod normalize;
+pub mod owner;
 pub mod print_root;
 pub mod publish;
 pub mod remove;
+pub mod schema;
 pub mod sources;
 pub mod sync;
+pub mod upgrade_check;
+pub mod verify;
+pub mod why;
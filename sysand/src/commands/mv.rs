@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use anyhow::Result;
+use camino::Utf8PathBuf;
+use sysand_core::{context::ProjectContext, mv::do_mv};
+
+use crate::{CliError, error::CodedResultExt};
+
+pub fn command_mv(old: Utf8PathBuf, new: Utf8PathBuf, ctx: ProjectContext) -> Result<()> {
+    let mut current_project = ctx
+        .current_project
+        .ok_or(CliError::MissingProjectCurrentDir)?;
+
+    let old = current_project.get_unix_path(old)?;
+    let new = current_project.get_unix_path(new)?;
+    do_mv(&mut current_project, old, new).coded()?;
+
+    Ok(())
+}
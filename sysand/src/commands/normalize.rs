@@ -0,0 +1,22 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use anyhow::Result;
+use sysand_core::{commands::normalize::do_normalize, context::ProjectContext};
+
+use crate::{CliError, error::CodedResultExt};
+
+/// Rewrite the project information and metadata of the current project in
+/// canonical form, or with `check` only fail if they are not
+pub fn command_normalize(check: bool, ctx: ProjectContext) -> Result<()> {
+    let current_project = ctx
+        .current_project
+        .ok_or(CliError::MissingProjectCurrentDir)?;
+
+    let normalized = "Normalized";
+    let header = sysand_core::style::get_style_config().header;
+    for path in do_normalize(&current_project, check).coded()? {
+        log::info!("{header}{normalized:>12}{header:#} `{path}`");
+    }
+    Ok(())
+}
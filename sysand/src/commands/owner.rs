@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use serde_json::json;
+use sysand_core::output::OutputSink;
+use sysand_core::{
+    auth::StandardHTTPAuthentication,
+    commands::{
+        owner::{do_owner_add, do_owner_list, do_owner_remove, owner_bearer},
+        publish::{EndpointKind, validate_endpoint_url_shape},
+    },
+    env::discovery::fetch_index_config,
+    index::api::IndexApiClient,
+};
+use url::Url;
+
+use crate::{cli::OwnerCommand, error::CodedResultExt};
+
+pub fn command_owner(
+    command: OwnerCommand,
+    index: Url,
+    auth_policy: Arc<StandardHTTPAuthentication>,
+    client: reqwest_middleware::ClientWithMiddleware,
+    runtime: Arc<tokio::runtime::Runtime>,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    validate_endpoint_url_shape(&index, EndpointKind::DiscoveryRoot)?;
+    // As for publish, discovery may use any credentials, the API only
+    // bearer tokens
+    let endpoints = runtime.block_on(fetch_index_config(&client, &*auth_policy, &index))?;
+    let bearer_map = Arc::unwrap_or_clone(auth_policy).try_into_publish_bearer_auth_map()?;
+    let bearer = owner_bearer(&bearer_map, &endpoints.api_root).coded()?;
+    let api = IndexApiClient::new(client, endpoints.api_root, bearer, runtime);
+
+    let header = sysand_core::style::get_style_config().header;
+    match command {
+        OwnerCommand::List { iri } => {
+            for owner in do_owner_list(&api, &iri).coded()? {
+                out.emit(&owner, json!(owner))?;
+            }
+        }
+        OwnerCommand::Add { iri, owner } => {
+            do_owner_add(&api, &iri, &owner).coded()?;
+            log::info!(
+                "{header}{:>12}{header:#} `{owner}` as owner of `{iri}`",
+                "Added"
+            );
+        }
+        OwnerCommand::Remove { iri, owner } => {
+            do_owner_remove(&api, &iri, &owner).coded()?;
+            log::info!(
+                "{header}{:>12}{header:#} `{owner}` as owner of `{iri}`",
+                "Removed"
+            );
+        }
+    }
+    Ok(())
+}
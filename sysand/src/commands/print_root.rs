@@ -4,18 +4,44 @@
 use anyhow::{Result, anyhow};
 
 use camino::Utf8Path;
-use sysand_core::{project::utils::wrapfs, root::do_root};
+use serde_json::json;
+use sysand_core::output::OutputSink;
+use sysand_core::{
+    discover::{Discovery, WalkOptions, trace_project, walk_projects},
+    project::utils::wrapfs,
+};
 
 use crate::CliError;
 
-pub fn command_print_root<P: AsRef<Utf8Path>>(path: P) -> Result<()> {
-    match do_root(path)? {
-        Some(root) => {
-            println!("{}", wrapfs::canonicalize(root)?);
+pub fn command_print_root<P: AsRef<Utf8Path>>(
+    path: P,
+    max_depth: Option<usize>,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    match trace_project(path, max_depth) {
+        Discovery::Found(root) => {
+            let root = wrapfs::canonicalize(root)?;
+            out.emit(root.as_str(), json!(root))?;
             Ok(())
         }
-        None => Err(anyhow!(CliError::InvalidDirectory(
-            "not inside a project".to_string(),
-        ))),
+        Discovery::NotFound(reason) => Err(anyhow!(CliError::InvalidDirectory(format!(
+            "not inside a project ({reason})"
+        )))),
     }
 }
+
+/// Print the paths of all projects in `path` and its subdirectories,
+/// relative to it
+pub fn command_print_all_roots<P: AsRef<Utf8Path>>(
+    path: P,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    let report = walk_projects(path, &WalkOptions::default())?;
+    for stop in &report.stops {
+        log::warn!("did not search `{}`: {}", stop.path, stop.reason);
+    }
+    for project in &report.projects {
+        out.emit(project.path.as_str(), json!(project.path.as_str()))?;
+    }
+    Ok(())
+}
@@ -8,27 +8,43 @@ use camino::Utf8PathBuf;
 use sysand_core::{
     auth::StandardHTTPAuthentication,
     build::default_kpar_path,
-    commands::publish::{
-        EndpointKind, TrustedPublishingEnvironment, do_publish, prepare_publish_payload,
-        resolve_publish_bearer, validate_endpoint_url_shape,
+    commands::{
+        policy::{PolicySubject, PublishPolicy},
+        publish::{
+            EndpointKind, TrustedPublishingEnvironment, do_deprecate, do_publish,
+            prepare_publish_payload, resolve_publish_bearer, validate_endpoint_url_shape,
+        },
     },
+    config::PublishConfig,
     context::ProjectContext,
-    env::discovery::{ResolvedEndpoints, fetch_index_config},
+    env::{
+        discovery::{ResolvedEndpoints, fetch_index_config},
+        index::Deprecation,
+    },
+    index::api::IndexApiClient,
     project::utils::wrapfs,
 };
 use url::Url;
 
-use crate::{CliError, cli::TrustedPublishingMode};
+use crate::{CliError, cli::TrustedPublishingMode, error::CodedResultExt};
 
+#[expect(clippy::too_many_arguments)]
 pub fn command_publish(
     path: Option<Utf8PathBuf>,
-    index: Url,
+    index: Option<Url>,
     trusted_publishing: TrustedPublishingMode,
+    dry_run: bool,
+    deprecate: Option<Deprecation>,
+    project_iri: Option<&str>,
+    policy_config: &PublishConfig,
     ctx: &ProjectContext,
     auth_policy: Arc<StandardHTTPAuthentication>,
     client: reqwest_middleware::ClientWithMiddleware,
     runtime: Arc<tokio::runtime::Runtime>,
 ) -> Result<()> {
+    // The configured IRI is that of the current project, so it only
+    // applies to its default KPAR
+    let project_iri = project_iri.filter(|_| path.is_none());
     let kpar_path = resolve_publish_kpar_path(path, ctx)?;
     if !wrapfs::is_file(&kpar_path)? {
         bail!("KPAR file not found at `{kpar_path}`, run `sysand build` first");
@@ -36,12 +52,32 @@ pub fn command_publish(
     // Reject obviously-malformed discovery-root URLs (bad scheme,
     // query/fragment components) before issuing any network request —
     // a config typo should not cost a DNS lookup + connect attempt.
-    validate_endpoint_url_shape(&index, EndpointKind::DiscoveryRoot)?;
+    if let Some(index) = &index {
+        validate_endpoint_url_shape(index, EndpointKind::DiscoveryRoot)?;
+    }
     // Validate and prepare the kpar payload before any network work,
     // so that kpar-content errors (bad semver, invalid publisher/name,
     // oversized archive) surface before discovery or credential
     // matching does.
     let prepared = prepare_publish_payload(&kpar_path)?;
+    if let Some(iri) = project_iri {
+        prepared.check_project_iri(iri).coded()?;
+    }
+    let policy = PublishPolicy::from_config(policy_config).coded()?;
+    // Deprecating uploads nothing for the policy to check
+    if !policy.is_empty() && deprecate.is_none() {
+        let subject = PolicySubject::from_kpar(&kpar_path).coded()?;
+        policy.enforce(&subject).coded()?;
+    }
+
+    let header = sysand_core::style::get_style_config().header;
+    let Some(index) = index.filter(|_| !dry_run) else {
+        log::info!(
+            "{header}{:>12}{header:#} `{kpar_path}` can be published",
+            "Checked"
+        );
+        return Ok(());
+    };
 
     // Resolve `api_root` before credential matching so publish credentials
     // are matched against the actual upload URL. Discovery uses the full auth
@@ -63,9 +99,23 @@ pub fn command_publish(
         &runtime,
     )?;
 
-    let response = do_publish(prepared, index, api_root, bearer, client, runtime)?;
+    if let Some(deprecation) = deprecate {
+        let api = IndexApiClient::new(client, api_root, bearer, runtime);
+        do_deprecate(&prepared, &deprecation, &api).coded()?;
+        let replacement = match &deprecation.replaced_by {
+            Some(replaced_by) => format!(", replaced by `{replaced_by}`"),
+            None => String::new(),
+        };
+        log::info!(
+            "{header}{:>12}{header:#} `{}`{replacement}",
+            "Deprecated",
+            prepared.iri()
+        );
+        return Ok(());
+    }
+
+    let response = do_publish(prepared, index, api_root, bearer, client, runtime).coded()?;
 
-    let header = sysand_core::style::get_style_config().header;
     if response.is_new_project {
         log::info!(
             "{header}{:>12}{header:#} new project successfully",
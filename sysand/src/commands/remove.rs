@@ -12,7 +12,7 @@ use sysand_core::{
     remove::do_remove,
 };
 
-use crate::CliError;
+use crate::{CliError, error::CodedResultExt};
 
 pub fn command_remove(
     iri: Iri<String>,
@@ -32,7 +32,7 @@ pub fn command_remove(
         remove_project_source_from_config(path, &iri)?;
     }
 
-    let usages = do_remove(&mut current_project, iri.into_string())?;
+    let usages = do_remove(&mut current_project, iri.into_string()).coded()?;
 
     let removed = "Removed";
     let header = sysand_core::style::get_style_config().header;
@@ -41,6 +41,7 @@ pub fn command_remove(
             InterchangeProjectUsageRaw::Resource {
                 resource,
                 version_constraint,
+                ..
             } => match version_constraint {
                 Some(vc) => {
                     log::info!(
@@ -59,6 +60,7 @@ pub fn command_remove(
                 InterchangeProjectUsageRaw::Resource {
                     resource,
                     version_constraint,
+                    ..
                 } => match version_constraint {
                     Some(vc) => {
                         log::info!("{:>13} `{resource}` with version constraints `{vc}`", ' ');
@@ -0,0 +1,25 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use anyhow::Result;
+use camino::Utf8Path;
+use sysand_core::output::OutputSink;
+use sysand_core::{
+    project::utils::wrapfs,
+    schema::{SchemaKind, json_schema},
+};
+
+/// Write the JSON Schema of `kind` to `output`, or standard output if `None`
+pub fn command_schema<O: AsRef<Utf8Path>>(
+    kind: SchemaKind,
+    output: Option<O>,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    let schema = json_schema(kind);
+    let pretty = serde_json::to_string_pretty(&schema)?;
+    match output {
+        Some(output) => wrapfs::write(output.as_ref(), pretty + "\n")?,
+        None => out.emit(&pretty, serde_json::to_value(schema)?)?,
+    }
+    Ok(())
+}
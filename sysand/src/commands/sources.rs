@@ -3,27 +3,36 @@
 
 use std::collections::HashMap;
 
-use crate::CliError;
+use crate::{CliError, error::CodedResultExt};
 
-use anstream::println;
 use anyhow::{Result, bail};
 use semver::VersionReq;
+use serde_json::json;
+use sysand_core::output::OutputSink;
 use sysand_core::{
     context::ProjectContext,
-    env::{local_directory::LocalDirectoryEnvironment, null::NullEnvironment},
+    env::{
+        fallback::FallbackEnvironment,
+        local_directory::{LocalDirectoryEnvironment, lazy::LazySrcProject},
+        null::NullEnvironment,
+    },
     project::{ProjectRead, memory::InMemoryProject},
     sources::{do_sources_local_src_project_no_deps, find_project_dependencies},
+    stdlib::IncludeStd,
 };
 
 use sysand_core::env::ReadEnvironment;
 
+#[expect(clippy::too_many_arguments)]
 pub fn command_sources_env<S: AsRef<str>>(
     iri: S,
     version: Option<VersionReq>,
     include_deps: bool,
     env: Option<LocalDirectoryEnvironment>,
-    provided_iris: &HashMap<String, Vec<InMemoryProject>>,
-    include_std: bool,
+    include_std: IncludeStd,
+    warn_std: bool,
+    materialize: bool,
+    out: &mut dyn OutputSink,
 ) -> Result<()> {
     let Some(env) = env else {
         bail!("unable to identify local environment");
@@ -61,8 +70,9 @@ pub fn command_sources_env<S: AsRef<str>>(
         }
     };
 
-    for src_path in do_sources_local_src_project_no_deps(&project, true)? {
-        println!("{}", src_path);
+    ensure_source_files(&project, materialize)?;
+    for src_path in do_sources_local_src_project_no_deps(&project, true).coded()? {
+        out.emit(src_path.as_str(), json!(src_path))?;
     }
 
     if include_deps {
@@ -70,12 +80,14 @@ pub fn command_sources_env<S: AsRef<str>>(
             bail!("project is missing project information")
         };
 
-        if !include_std {
+        let provided_iris = crate::ignored_std_libs(include_std, Some(&project));
+        if warn_std {
             crate::logger::warn_std_deps();
         }
-        for dep in find_project_dependencies(info.validate()?.usage, env, provided_iris)? {
-            for src_path in do_sources_local_src_project_no_deps(&dep, true)? {
-                println!("{}", src_path);
+        for dep in find_project_dependencies(info.validate()?.usage, env, &provided_iris)? {
+            ensure_source_files(&dep, materialize)?;
+            for src_path in do_sources_local_src_project_no_deps(&dep, true).coded()? {
+                out.emit(src_path.as_str(), json!(src_path))?;
             }
         }
     }
@@ -83,10 +95,15 @@ pub fn command_sources_env<S: AsRef<str>>(
     Ok(())
 }
 
+/// Dependencies not in the local environment are looked up in
+/// `system_envs`
 pub fn command_sources_project(
     include_deps: bool,
     ctx: ProjectContext,
+    system_envs: Vec<LocalDirectoryEnvironment>,
     provided_iris: &HashMap<String, Vec<InMemoryProject>>,
+    materialize: bool,
+    out: &mut dyn OutputSink,
 ) -> Result<()> {
     let current_project = ctx
         .current_project
@@ -97,25 +114,50 @@ pub fn command_sources_project(
     };
     let info = info.validate()?;
 
-    for src_path in do_sources_local_src_project_no_deps(&current_project, true)? {
-        println!("{}", src_path);
+    for src_path in do_sources_local_src_project_no_deps(&current_project, true).coded()? {
+        out.emit(src_path.as_str(), json!(src_path))?;
     }
 
     if include_deps {
-        let deps = match ctx.env {
-            Some(env) => find_project_dependencies(info.usage, env, provided_iris)?,
-            None => {
-                let env = NullEnvironment::new();
-                find_project_dependencies(info.usage, env, provided_iris)?
-            }
+        let layers: Vec<_> = ctx.env.into_iter().chain(system_envs).collect();
+        let deps = if layers.is_empty() {
+            let env = NullEnvironment::new();
+            find_project_dependencies(info.usage, env, provided_iris)?
+        } else {
+            let env = FallbackEnvironment::new(layers);
+            find_project_dependencies(info.usage, env, provided_iris)?
         };
 
         for dep in deps {
-            for src_path in do_sources_local_src_project_no_deps(&dep, true)? {
-                println!("{}", src_path);
+            ensure_source_files(&dep, materialize)?;
+            for src_path in do_sources_local_src_project_no_deps(&dep, true).coded()? {
+                out.emit(src_path.as_str(), json!(src_path))?;
             }
         }
     }
 
     Ok(())
 }
+
+/// Listed paths must exist, so sources of metadata only projects are
+/// fetched, and projects stored as KPARs must be extracted. Files of
+/// projects stored encrypted cannot be used from disk
+fn ensure_source_files(project: &LazySrcProject, materialize: bool) -> Result<()> {
+    if project.is_encrypted() {
+        bail!(
+            "project at `{}` is stored encrypted, so its files cannot be listed",
+            project.project_path
+        );
+    }
+    project.fetch_sources()?;
+    if materialize {
+        project.materialize()?;
+    } else if !project.is_extracted()? {
+        bail!(
+            "project at `{}` is stored as a KPAR and its files are not extracted;\n\
+            use `--materialize` to extract them",
+            project.project_path
+        );
+    }
+    Ok(())
+}
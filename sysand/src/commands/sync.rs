@@ -1,17 +1,25 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
-use std::{collections::HashMap, num::NonZeroU64, sync::Arc};
+use std::{collections::HashMap, convert::Infallible, num::NonZeroU64, sync::Arc};
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use camino::Utf8Path;
 use typed_path::Utf8UnixPathBuf;
 use url::ParseError;
 
+use sysand_core::output::OutputSink;
 use sysand_core::{
     auth::HTTPAuthentication,
-    env::local_directory::LocalDirectoryEnvironment,
-    lock::Lock,
+    commands::sync::{
+        SyncAction, SyncFilter, SyncPlan, do_sync_apply, do_sync_plan, do_sync_verify_pins,
+    },
+    config::Config,
+    env::{
+        WriteEnvironment, fallback::FallbackEnvironment, layered::LayeredEnvironment,
+        local_directory::LocalDirectoryEnvironment, memory::MemoryStorageEnvironment,
+    },
+    lock::{Lock, Source},
     project::{
         AsSyncProjectTokio, KparMeta, ProjectReadAsync,
         gix_git_download::{GixDownloadedError, GixDownloadedProject},
@@ -22,23 +30,257 @@ use sysand_core::{
             ReqwestIndexKparDownloadedProject, ReqwestRemoteKparDownloadedProject,
         },
         reqwest_src::ReqwestSrcProjectAsync,
+        utils::wrapfs,
     },
+    resolve::git_cache::GitCache,
     workspace::Workspace,
 };
 
+use crate::{error::CodedResultExt, get_system_envs, logger::Span};
+
+/// Sync `env` to `lock`. Projects with an install location in `config`
+/// are also installed there, and projects installed in the system
+/// environments of `config` are used from there. If `metadata_only` is
+/// set, only `.project.json` and `.meta.json` of other projects are
+/// installed. The whole `lock` is checked, but only projects selected by
+/// `filter` are installed
 #[allow(clippy::too_many_arguments)]
 pub fn command_sync<P: AsRef<Utf8Path>, Policy: HTTPAuthentication>(
     lock: &Lock,
     project_root: P,
     env: &mut LocalDirectoryEnvironment,
+    config: &Config,
     client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
     provided_iris: &HashMap<String, Vec<InMemoryProject>>,
     runtime: Arc<tokio::runtime::Runtime>,
     auth_policy: Arc<Policy>,
     ws: Option<&Workspace>,
+    metadata_only: bool,
+    filter: &SyncFilter,
 ) -> Result<()> {
-    sysand_core::commands::sync::do_sync(
-        lock,
+    env.merge_lock(lock, ws);
+    let mut layered =
+        LayeredEnvironment::new(env.clone(), config.install_locations(project_root.as_ref()));
+    let mut plan =
+        do_sync_plan::<_, Infallible, Infallible>(lock, &layered, provided_iris).coded()?;
+    use_system_envs(&mut plan, config, project_root.as_ref())?;
+    filter
+        .apply::<Infallible, Infallible>(&mut plan, lock)
+        .coded()?;
+    if env.is_read_only() {
+        // Nothing can be written, not even the editable projects of `lock`
+        return ensure_nothing_to_do(&plan, env);
+    }
+    plan.metadata_only = metadata_only;
+    let lock_root = project_root.as_ref().to_owned();
+    let result = apply_plan(
+        &plan,
+        project_root,
+        &mut layered,
+        client,
+        git_cache,
+        runtime,
+        auth_policy,
+    )
+    .and_then(|()| verify_pins(lock, &lock_root, &layered));
+    *env = layered.into_inner();
+    result
+}
+
+/// Leave out of `plan` the projects installed in the system environments
+/// of `config`
+fn use_system_envs(plan: &mut SyncPlan, config: &Config, project_root: &Utf8Path) -> Result<()> {
+    let system_envs = get_system_envs(config, project_root)?;
+    if !system_envs.is_empty() {
+        plan.use_installed::<_, Infallible, Infallible>(&FallbackEnvironment::new(system_envs))
+            .coded()?;
+    }
+    Ok(())
+}
+
+/// Fail unless `plan` leaves the read-only `env` unchanged
+fn ensure_nothing_to_do(plan: &SyncPlan, env: &LocalDirectoryEnvironment) -> Result<()> {
+    let Some(action) = plan.actions.first() else {
+        return Ok(());
+    };
+    let (iri, version) = match action {
+        SyncAction::Install { project } | SyncAction::Update { project, .. } => {
+            (display_name(project), &project.version)
+        }
+        SyncAction::Remove { iri, version } => (format!("`{iri}`"), version),
+    };
+    let hint = crate::style::USAGE;
+    bail!(
+        "environment `{}` is read-only, but {iri} {version} is not installed as locked\n\
+        {hint}hint:{hint:#} install it in a system environment, or make the environment\n\
+        {:>6}writable with `sysand env read-only --off`",
+        env.root_path(),
+        ' '
+    );
+}
+
+/// Print the plan for syncing `env` (or an empty env, if there is none) to
+/// `lock` as JSON to `out`
+#[expect(clippy::too_many_arguments)]
+pub fn command_sync_plan<P: AsRef<Utf8Path>>(
+    lock: &Lock,
+    project_root: P,
+    env: Option<LocalDirectoryEnvironment>,
+    config: &Config,
+    provided_iris: &HashMap<String, Vec<InMemoryProject>>,
+    ws: Option<&Workspace>,
+    filter: &SyncFilter,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    let mut plan = match env {
+        Some(mut env) => {
+            // Only in memory, `env` is not written
+            env.merge_lock(lock, ws);
+            let env = LayeredEnvironment::new(env, config.install_locations(project_root.as_ref()));
+            do_sync_plan::<_, Infallible, Infallible>(lock, &env, provided_iris)
+        }
+        None => do_sync_plan::<_, Infallible, Infallible>(
+            lock,
+            &MemoryStorageEnvironment::<InMemoryProject>::new(),
+            provided_iris,
+        ),
+    }
+    .coded()?;
+    use_system_envs(&mut plan, config, project_root.as_ref())?;
+    filter
+        .apply::<Infallible, Infallible>(&mut plan, lock)
+        .coded()?;
+
+    let header = sysand_core::style::get_style_config().header;
+    if plan.actions.is_empty() {
+        log::info!("{:>12} nothing to do: env is already up to date", ' ');
+    }
+    for action in &plan.actions {
+        match action {
+            SyncAction::Install { project } => {
+                let install = "Install";
+                log::info!(
+                    "{header}{install:>12}{header:#} {} {}",
+                    display_name(project),
+                    project.version
+                );
+            }
+            SyncAction::Update { project, replaces } => {
+                let update = "Update";
+                let replacing = if replaces.is_empty() {
+                    String::new()
+                } else {
+                    format!(" (replacing {})", replaces.join(", "))
+                };
+                log::info!(
+                    "{header}{update:>12}{header:#} {} {}{replacing}",
+                    display_name(project),
+                    project.version
+                );
+            }
+            SyncAction::Remove { iri, version } => {
+                let remove = "Remove";
+                log::info!("{header}{remove:>12}{header:#} `{iri}` {version}");
+            }
+        }
+    }
+    out.emit(
+        &serde_json::to_string_pretty(&plan)?,
+        serde_json::to_value(&plan)?,
+    )?;
+
+    Ok(())
+}
+
+/// Carry out the plan at `plan_path`, which must have been created from
+/// `lock` by `sysand sync --plan`
+#[allow(clippy::too_many_arguments)]
+pub fn command_sync_apply<P: AsRef<Utf8Path>, Policy: HTTPAuthentication>(
+    plan_path: &Utf8Path,
+    lock: &Lock,
+    project_root: P,
+    env: &mut LocalDirectoryEnvironment,
+    config: &Config,
+    client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
+    runtime: Arc<tokio::runtime::Runtime>,
+    auth_policy: Arc<Policy>,
+    ws: Option<&Workspace>,
+) -> Result<()> {
+    let plan: SyncPlan = match serde_json::from_str(&wrapfs::read_to_string(plan_path)?) {
+        Ok(plan) => plan,
+        Err(e) => bail!("invalid sync plan `{plan_path}`:\n{e}"),
+    };
+    if !plan.is_for(lock) {
+        bail!(
+            "sync plan `{plan_path}` was not created from the current lockfile;\n\
+            create a new one with `sysand sync --plan`"
+        );
+    }
+    if env.is_read_only() {
+        return ensure_nothing_to_do(&plan, env);
+    }
+
+    env.merge_lock(lock, ws);
+    let mut layered =
+        LayeredEnvironment::new(env.clone(), config.install_locations(project_root.as_ref()));
+    let lock_root = project_root.as_ref().to_owned();
+    let result = apply_plan(
+        &plan,
+        project_root,
+        &mut layered,
+        client,
+        git_cache,
+        runtime,
+        auth_policy,
+    )
+    .and_then(|()| verify_pins(lock, &lock_root, &layered));
+    *env = layered.into_inner();
+    result
+}
+
+/// Check the hash pins of the usages of the editable projects of `lock`
+/// against the projects installed in `env`
+fn verify_pins(lock: &Lock, lock_root: &Utf8Path, env: &LayeredEnvironment) -> Result<()> {
+    for project in &lock.projects {
+        for source in &project.sources {
+            let Source::Editable { editable } = source else {
+                continue;
+            };
+            let info = LocalSrcProject {
+                nominal_path: None,
+                project_path: lock_root.join(editable.as_str()),
+                expected_checksum: None,
+            }
+            .get_info()?;
+            if let Some(info) = info {
+                do_sync_verify_pins::<_, Infallible, Infallible>(&info.usage, lock, env).coded()?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn display_name(project: &sysand_core::lock::Project) -> String {
+    match project.identifiers.first() {
+        Some(iri) => format!("`{iri}`"),
+        None => format!("`{}`", project.name),
+    }
+}
+
+fn apply_plan<P: AsRef<Utf8Path>, Policy: HTTPAuthentication>(
+    plan: &SyncPlan,
+    project_root: P,
+    env: &mut LayeredEnvironment,
+    client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
+    runtime: Arc<tokio::runtime::Runtime>,
+    auth_policy: Arc<Policy>,
+) -> Result<()> {
+    let _span = Span::enter("install");
+    do_sync_apply(
+        plan,
         env,
         Some(
             |src_path: Utf8UnixPathBuf, checksum: String| -> LocalSrcProject {
@@ -53,12 +295,12 @@ pub fn command_sync<P: AsRef<Utf8Path>, Policy: HTTPAuthentication>(
             |remote_src: String,
              checksum: String|
              -> Result<AsSyncProjectTokio<ReqwestSrcProjectAsync<Policy>>, ParseError> {
-                Ok(ReqwestSrcProjectAsync {
-                    client: client.clone(),
-                    url: reqwest::Url::parse(&remote_src)?,
-                    auth_policy: auth_policy.clone(),
-                    expected_checksum: Some(checksum),
-                }
+                Ok(ReqwestSrcProjectAsync::new(
+                    client.clone(),
+                    reqwest::Url::parse(&remote_src)?,
+                    auth_policy.clone(),
+                    Some(checksum),
+                )
                 .to_tokio_sync(runtime.clone()))
             },
         ),
@@ -117,15 +359,18 @@ pub fn command_sync<P: AsRef<Utf8Path>, Policy: HTTPAuthentication>(
             },
         ),
         Some(
-            |remote_git: String| -> Result<GixDownloadedProject, GixDownloadedError> {
-                GixDownloadedProject::new(remote_git)
+            |remote_git: String,
+             rev: Option<String>|
+             -> Result<GixDownloadedProject, GixDownloadedError> {
+                Ok(GixDownloadedProject::new(remote_git)?
+                    .with_cache(git_cache.clone())
+                    .with_rev(rev))
             },
         ),
-        provided_iris,
-    )?;
+    )
+    .coded()?;
 
-    env.merge_lock(lock, ws);
-    env.write()?;
+    env.inner.write()?;
 
     Ok(())
 }
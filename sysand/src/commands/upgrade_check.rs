@@ -0,0 +1,168 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::{fmt::Write, sync::Arc};
+
+use anyhow::{Result, bail};
+use camino::Utf8Path;
+use fluent_uri::Iri;
+use sysand_core::output::OutputSink;
+use sysand_core::{
+    auth::HTTPAuthentication,
+    commands::upgrade_check::{UpgradeReport, UsageChange, do_upgrade_check},
+    config::Index,
+    context::ProjectContext,
+    project::any::OverrideProject,
+    resolve::{
+        git_cache::GitCache, memory::MemoryResolver, priority::PriorityResolver,
+        standard::standard_resolver,
+    },
+    style,
+};
+use url::Url;
+
+use crate::{
+    CliError,
+    commands::{
+        clone::get_project_version,
+        lock::{lockfile_path, read_lockfile},
+    },
+    error::CodedResultExt,
+};
+
+/// Compare the version of `iri` locked in the lockfile in `lock_root` with
+/// `new_version`, and print the top-level symbols the current project refers
+/// to that `new_version` no longer has
+#[expect(clippy::too_many_arguments)]
+pub fn command_upgrade_check<P: AsRef<Utf8Path>, Policy: HTTPAuthentication>(
+    iri: Iri<String>,
+    new_version: String,
+    lock_root: P,
+    client: reqwest_middleware::ClientWithMiddleware,
+    git_cache: Option<GitCache>,
+    index_urls: Option<Vec<Url>>,
+    indexes: &[Index],
+    shared_env_urls: Vec<Url>,
+    overrides: Vec<(Iri<String>, Vec<OverrideProject<Policy>>)>,
+    runtime: Arc<tokio::runtime::Runtime>,
+    auth_policy: Arc<Policy>,
+    ctx: ProjectContext,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    let current_project = ctx
+        .current_project
+        .ok_or(CliError::MissingProjectCurrentDir)?;
+
+    let lockfile = lockfile_path(lock_root)?;
+    if !lockfile.is_file() {
+        bail!("lockfile `{lockfile}` not found, run `sysand lock` to create it");
+    }
+    let lock = read_lockfile(&lockfile)?;
+    let Some(locked) = lock
+        .projects
+        .iter()
+        .find(|p| p.identifiers.iter().any(|i| i == iri.as_str()))
+    else {
+        bail!("`{iri}` is not in lockfile `{lockfile}`");
+    };
+
+    let resolver = PriorityResolver::new(
+        MemoryResolver::from(overrides),
+        standard_resolver(
+            Some(ctx.current_directory),
+            ctx.env,
+            Some(client),
+            index_urls,
+            shared_env_urls,
+            runtime,
+            auth_policy,
+        )?
+        .with_git_cache(git_cache)
+        .with_index_scopes(indexes)?,
+    );
+    let (_, old) = get_project_version(&iri, Some(locked.version.clone()), &resolver)?;
+    let (_, new) = get_project_version(&iri, Some(new_version), &resolver)?;
+
+    let report = do_upgrade_check(&current_project, iri.as_str(), &old, &new).coded()?;
+    print_report(&report, out)
+}
+
+fn print_report(report: &UpgradeReport, out: &mut dyn OutputSink) -> Result<()> {
+    let header = style::get_style_config().header;
+    let mut human = String::new();
+    writeln!(
+        human,
+        "{header}Upgrading{header:#} `{}` {} -> {}",
+        report.iri, report.old_version, report.new_version
+    )?;
+
+    let breaking: Vec<_> = report.breaking().collect();
+    if breaking.is_empty() {
+        writeln!(
+            human,
+            "No removed top-level symbols are used by the current project"
+        )?;
+    } else {
+        writeln!(
+            human,
+            "{header}Removed symbols used by the current project:{header:#}"
+        )?;
+        for removed in breaking {
+            let renamed = match &removed.renamed_to {
+                Some(new_name) => format!(" (renamed to `{new_name}`?)"),
+                None => String::new(),
+            };
+            writeln!(
+                human,
+                "    {}{renamed} used in {}",
+                removed.symbol,
+                removed.used_in.join(", ")
+            )?;
+        }
+    }
+
+    let unused: Vec<_> = report
+        .removed_symbols
+        .iter()
+        .filter(|s| s.used_in.is_empty())
+        .map(|s| s.symbol.as_str())
+        .collect();
+    if !unused.is_empty() {
+        writeln!(
+            human,
+            "{header}Other removed symbols:{header:#} {}",
+            unused.join(", ")
+        )?;
+    }
+    if !report.added_symbols.is_empty() {
+        writeln!(
+            human,
+            "{header}Added symbols:{header:#} {}",
+            report.added_symbols.join(", ")
+        )?;
+    }
+    if !report.usage_changes.is_empty() {
+        writeln!(human, "{header}Usage changes:{header:#}")?;
+        let constraint = |c: &Option<String>| c.clone().unwrap_or_else(|| "*".into());
+        for change in &report.usage_changes {
+            match change {
+                UsageChange::Added {
+                    iri,
+                    version_constraint,
+                } => writeln!(human, "    + {iri} {}", constraint(version_constraint))?,
+                UsageChange::Removed {
+                    iri,
+                    version_constraint,
+                } => writeln!(human, "    - {iri} {}", constraint(version_constraint))?,
+                UsageChange::Constraint { iri, old, new } => writeln!(
+                    human,
+                    "    ~ {iri} {} -> {}",
+                    constraint(old),
+                    constraint(new)
+                )?,
+            }
+        }
+    }
+    out.emit(human.trim_end(), serde_json::to_value(report)?)?;
+    Ok(())
+}
@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use anyhow::{Result, bail};
+use sysand_core::{
+    commands::verify::{FileStatus, do_verify},
+    context::ProjectContext,
+    env::local_directory::{LocalDirectoryEnvironment, provenance::InstallStatus},
+    parallel::Jobs,
+    project::local_src::LocalSrcError,
+};
+
+use crate::{
+    CliError,
+    error::{CodedResultExt, ENV_READ, ResultExt},
+};
+
+/// Check the source files of the current project against the checksums in
+/// its `.meta.json`
+pub fn command_verify(jobs: Jobs, ctx: ProjectContext) -> Result<()> {
+    let current_project = ctx
+        .current_project
+        .ok_or(CliError::MissingProjectCurrentDir)?;
+
+    let report = do_verify(&current_project, jobs).coded()?;
+    for file in &report.files {
+        match &file.status {
+            FileStatus::Match => log::debug!("`{}` matches its checksum", file.path),
+            FileStatus::Mismatch { expected, actual } => log::error!(
+                "`{}` does not match its checksum\n\
+                expected: {expected}\n\
+                actual:   {actual}",
+                file.path
+            ),
+            FileStatus::NoChecksum => log::warn!("`{}` has no checksum", file.path),
+            FileStatus::UnsupportedAlgorithm(algorithm) => log::warn!(
+                "`{}` has a {algorithm} checksum, which cannot be verified",
+                file.path
+            ),
+        }
+    }
+    report.check::<LocalSrcError>().coded()?;
+
+    let verified = "Verified";
+    let header = sysand_core::style::get_style_config().header;
+    let unchecked = report.unchecked().count();
+    let checked = report.files.len() - unchecked;
+    if unchecked == 0 {
+        log::info!("{header}{verified:>12}{header:#} {checked} file(s)");
+    } else {
+        log::info!(
+            "{header}{verified:>12}{header:#} {checked} file(s), {unchecked} without a verifiable checksum"
+        );
+    }
+    Ok(())
+}
+
+/// Check the projects installed in the environment against the hashes
+/// recorded when they were installed
+pub fn command_verify_env(env: Option<LocalDirectoryEnvironment>) -> Result<()> {
+    let Some(env) = env else {
+        bail!("unable to identify environment to verify");
+    };
+
+    let mut checked = 0;
+    let mut unknown = 0;
+    let mut modified = 0;
+    for project in env
+        .projects()
+        .iter()
+        .filter(|p| !p.editable && !p.workspace)
+    {
+        let iri = project.identifiers.first().map_or("", String::as_str);
+        let version = &project.version;
+        match env.install_status(project).with_code(ENV_READ)? {
+            InstallStatus::Unchanged => {
+                log::debug!("`{iri}` {version} is unchanged");
+                checked += 1;
+            }
+            InstallStatus::Modified => {
+                log::error!("`{iri}` {version} was modified after it was installed");
+                modified += 1;
+            }
+            InstallStatus::Unknown => {
+                log::warn!("`{iri}` {version} has no recorded hash to verify");
+                unknown += 1;
+            }
+        }
+    }
+    if modified > 0 {
+        bail!(
+            "{modified} installed project(s) were modified; run `sysand env doctor --fix` \
+            and `sysand sync` to install them again"
+        );
+    }
+
+    let verified = "Verified";
+    let header = sysand_core::style::get_style_config().header;
+    if unknown == 0 {
+        log::info!("{header}{verified:>12}{header:#} {checked} project(s)");
+    } else {
+        log::info!(
+            "{header}{verified:>12}{header:#} {checked} project(s), {unknown} without a recorded hash"
+        );
+    }
+    Ok(())
+}
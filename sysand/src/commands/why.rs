@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use anyhow::{Result, bail};
+use camino::Utf8Path;
+use indexmap::IndexMap;
+use serde_json::json;
+use sysand_core::output::OutputSink;
+
+use crate::commands::lock::{lockfile_path, read_lockfile};
+
+/// Print the reason recorded for the usage `iri` and the projects in the
+/// lockfile in `lock_root` that use it
+pub fn command_why<P: AsRef<Utf8Path>>(
+    iri: &str,
+    lock_root: P,
+    reasons: &IndexMap<String, String>,
+    out: &mut dyn OutputSink,
+) -> Result<()> {
+    let reason = reasons.get(iri);
+    let lockfile = lockfile_path(lock_root)?;
+    let dependents = if lockfile.is_file() {
+        let lock = read_lockfile(&lockfile)?;
+        lock.dependents(iri)
+            .into_iter()
+            .map(|p| format!("{} {}", p.name, p.version))
+            .collect()
+    } else {
+        log::warn!("lockfile `{lockfile}` not found, run `sysand lock` to create it");
+        vec![]
+    };
+    if reason.is_none() && dependents.is_empty() {
+        bail!("`{iri}` is not used by any locked project and has no recorded reason");
+    }
+
+    let mut human = format!("`{iri}`");
+    if let Some(reason) = reason {
+        human.push_str(&format!("\n  reason: {reason}"));
+    }
+    for dependent in &dependents {
+        human.push_str(&format!("\n  used by: {dependent}"));
+    }
+    out.emit(
+        &human,
+        json!({"iri": iri, "reason": reason, "used_by": dependents}),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,380 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! State shared by all commands, set up once before running one
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use anyhow::{Result, bail};
+use camino::{Utf8Path, Utf8PathBuf};
+use reqwest_middleware::ClientWithMiddleware;
+use sysand_core::{
+    auth::{CredentialHelper, StandardHTTPAuthentication, StandardHTTPAuthenticationBuilder},
+    config::{
+        Config, HooksConfig,
+        local_fs::{
+            CONFIG_FILE, get_config, load_env_settings, load_system_config, load_user_config,
+        },
+    },
+    context::ProjectContext,
+    discover::{discover_project_within, discover_workspace},
+    env::local_directory::LocalDirectoryEnvironment,
+    output::{HumanSink, JsonSink, OutputSink},
+    project::utils::wrapfs,
+    resolve::git_cache::GitCache,
+    session::{Session, new_runtime},
+    solve::{markers::Markers, pubgrub::VersionStrategy},
+};
+use url::Url;
+
+use crate::{
+    DEFAULT_INDEX_URL, Overrides, add_env_source_roots,
+    cli::{Command, ErrorFormat, GlobalOptions},
+    commands::env::source_fetcher,
+    create_client, get_cache_dir, get_env, get_env_key, get_markers, get_or_create_env,
+    get_overrides, render_manifest, shared_lock_workspace,
+};
+
+/// Everything a command needs besides its own arguments: the
+/// configuration, the current project and environment, networking and
+/// where to write output to
+pub struct CommandContext {
+    pub config: Config,
+    pub project: ProjectContext,
+    /// `--config-file`
+    pub config_file: Option<String>,
+    /// `--no-config`
+    pub no_config: bool,
+    /// `--verbose` count
+    pub verbose: u8,
+    pub client: ClientWithMiddleware,
+    pub git_cache: Option<GitCache>,
+    pub runtime: Arc<tokio::runtime::Runtime>,
+    pub auth_policy: Arc<StandardHTTPAuthentication>,
+    /// Receiver of the results of commands, printed to stdout by default.
+    /// Logs and prompts always go to stderr
+    pub out: Box<dyn OutputSink>,
+}
+
+impl CommandContext {
+    /// Context for `project` with `config`, no credentials and no caches.
+    /// Lets other binaries and tests run commands without going through
+    /// the discovery and configuration of [`Self::from_options`]
+    pub fn new(config: Config, project: ProjectContext) -> Result<Self> {
+        let auth_policy = Arc::new(StandardHTTPAuthenticationBuilder::new().build()?);
+        Ok(Self::from_session(
+            Session::new(config, project)?.with_auth_policy(auth_policy),
+        ))
+    }
+
+    /// Context running commands with the configuration, project and
+    /// networking of `session`
+    pub fn from_session(session: Session<StandardHTTPAuthentication>) -> Self {
+        Self {
+            config: session.config,
+            project: session.project,
+            config_file: None,
+            no_config: true,
+            verbose: 0,
+            client: session.client,
+            git_cache: None,
+            runtime: session.runtime,
+            auth_policy: session.auth_policy,
+            out: Box::new(HumanSink(anstream::stdout())),
+        }
+    }
+
+    /// Discover the project in the current directory and read the
+    /// configuration and credentials as `global_opts` say, to run `command`
+    pub fn from_options(global_opts: &GlobalOptions, command: &Command) -> Result<Self> {
+        let cwd = wrapfs::current_dir()?;
+        // `config` commands read the configuration themselves, e.g. to
+        // report all problems in it
+        let checks_config = matches!(command, Command::Config { .. });
+        let no_config = global_opts.no_config || checks_config;
+        // Settings are taken from, in order of precedence: `SYSAND_SETTINGS`,
+        // `--config-file`, the user, project, workspace and system files
+        let mut config = Config::default();
+        let mut system_config = Config::default();
+        if !checks_config {
+            config.merge(load_env_settings()?);
+            if let Some(config_file) = &global_opts.config_file {
+                config.merge(get_config(config_file)?);
+            }
+        }
+        if !no_config {
+            config.merge(load_user_config()?);
+            system_config = load_system_config()?;
+        }
+
+        // The project-local configuration file can only be read once the
+        // project is found, so discovery settings come from the others
+        let max_depth = config
+            .discovery
+            .max_depth
+            .or(system_config.discovery.max_depth);
+        let current_project = discover_project_within(&cwd, max_depth)?;
+        let current_workspace = discover_workspace(&cwd)?;
+        let env = match (&current_workspace, &current_project) {
+            // Outdated envs cannot be read, only migrated
+            _ if matches!(command, Command::Migrate { .. }) => None,
+            // TODO: does it make sense to support env unassociated with a project
+            // when index and env are different?
+            (None, None) => get_env(&cwd)?,
+            (None, Some(pr)) => get_env(pr.root_path())?,
+            (Some(w), _) => get_env(w.root_path())?,
+        };
+        let mut ctx = ProjectContext {
+            env,
+            current_workspace,
+            current_project,
+            current_directory: cwd,
+            markers: Markers::new(),
+            version_strategy: VersionStrategy::default(),
+        };
+
+        if !no_config {
+            let config_file = ctx
+                .current_project
+                .as_ref()
+                .map_or(Utf8Path::new("."), |p| p.root_path())
+                .join(CONFIG_FILE);
+            config.merge(get_config(&config_file)?);
+            // Workspace settings are shared by all workspace projects
+            if let Some(workspace) = &ctx.current_workspace {
+                let workspace_config_file = workspace.root_path().join(CONFIG_FILE);
+                if wrapfs::canonicalize(&workspace_config_file).ok()
+                    != wrapfs::canonicalize(&config_file).ok()
+                {
+                    config.merge(Config {
+                        workspace: get_config(workspace_config_file)?.workspace,
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+        config.merge(system_config);
+        add_env_source_roots(&mut config, &ctx.current_directory)?;
+        ctx.markers = get_markers(&global_opts.markers, &config, &ctx)?;
+        let check = match command {
+            Command::Build { .. } | Command::Lock { .. } => Some(false),
+            Command::Normalize { check } => Some(*check),
+            _ => None,
+        };
+        if let (Some(check), Some(manifest)) = (check, &config.manifest) {
+            render_manifest(manifest, &mut ctx, check)?;
+        }
+        if global_opts.no_hooks {
+            config.hooks = HooksConfig::default();
+        }
+
+        let cache_dir = get_cache_dir(global_opts.no_cache);
+        let client = create_client(cache_dir.as_deref(), &config)?;
+        let git_cache = cache_dir.map(|d| GitCache::new(d.join("git")));
+        let runtime = new_runtime();
+        let auth_policy = Arc::new(auth_policy(&config)?);
+
+        // Projects installed with `--metadata-only` fetch their sources on
+        // first read, and those stored encrypted are decrypted with the key
+        let env_key = get_env_key(&config)?;
+        ctx.env = ctx.env.map(|env| {
+            let fetcher = source_fetcher(
+                env.root_path().parent().unwrap_or(env.root_path()),
+                client.clone(),
+                runtime.clone(),
+                auth_policy.clone(),
+            );
+            let env = env.with_source_fetcher(fetcher);
+            match &env_key {
+                Some(key) => env.with_key(key.clone()),
+                None => env,
+            }
+        });
+
+        Ok(Self {
+            config,
+            project: ctx,
+            config_file: global_opts.config_file.clone(),
+            no_config: global_opts.no_config,
+            verbose: global_opts.verbose,
+            client,
+            git_cache,
+            runtime,
+            auth_policy,
+            out: match global_opts.output_format {
+                ErrorFormat::Human => Box::new(HumanSink(anstream::stdout())),
+                ErrorFormat::Json => Box::new(JsonSink(anstream::stdout())),
+            },
+        })
+    }
+
+    /// Send the results of commands to `out` instead
+    pub fn with_output(mut self, out: impl OutputSink + 'static) -> Self {
+        self.out = Box::new(out);
+        self
+    }
+
+    /// Root directory of the current project, if any
+    pub fn project_root(&self) -> Option<Utf8PathBuf> {
+        self.project
+            .current_project
+            .as_ref()
+            .map(|p| p.root_path().to_owned())
+    }
+
+    /// Directory of the lockfile: the workspace root if the workspace is
+    /// locked as a whole, otherwise the project root or the current
+    /// directory
+    pub fn lock_root(&self) -> Utf8PathBuf {
+        match shared_lock_workspace(&self.config, &self.project) {
+            Some(workspace) => workspace.root_path().to_owned(),
+            None => self
+                .project_root()
+                .unwrap_or_else(|| self.project.current_directory.clone()),
+        }
+    }
+
+    /// URLs of the indexes that resolvers should use, `None` with
+    /// `no_index`
+    pub fn index_urls(
+        &self,
+        index: Vec<String>,
+        default_index: Vec<String>,
+        no_index: bool,
+    ) -> Result<Option<Vec<Url>>> {
+        if no_index {
+            return Ok(None);
+        }
+        Ok(Some(self.config.index_urls(
+            index,
+            vec![DEFAULT_INDEX_URL.to_string()],
+            default_index,
+        )?))
+    }
+
+    /// Projects that resolvers should use in place of the ones they
+    /// would find, from the configuration and its source roots
+    pub fn overrides(&self) -> Result<Overrides<StandardHTTPAuthentication>> {
+        get_overrides(
+            &self.config,
+            self.project_root()
+                .as_ref()
+                .unwrap_or(&self.project.current_directory),
+            &self.client,
+            self.runtime.clone(),
+            self.auth_policy.clone(),
+        )
+    }
+
+    /// Take the environment of the current project or workspace, creating
+    /// it if it does not exist yet
+    pub fn take_or_create_env(&mut self) -> Result<LocalDirectoryEnvironment> {
+        get_or_create_env(
+            self.project.env.take(),
+            self.project.current_workspace.as_ref(),
+            self.project.current_project.as_ref(),
+            &self.project.current_directory,
+            &self.config,
+        )
+    }
+}
+
+/// Credentials given by `SYSAND_CRED_<X>` environment variables and the
+/// credential helpers of the indexes in `config`
+fn auth_policy(config: &Config) -> Result<StandardHTTPAuthentication> {
+    // FIXME: This is a temporary implementation to provide credentials until
+    //        https://github.com/sensmetry/sysand/pull/157
+    //        gets merged.
+    let mut auth_patterns = HashMap::new();
+    let mut basic_auth_users = HashMap::new();
+    let mut basic_auth_passwords = HashMap::new();
+    let mut bearer_auth_tokens = HashMap::new();
+
+    for (key, value) in std::env::vars() {
+        if let Some(key_rest) = key.strip_prefix("SYSAND_CRED_") {
+            if let Some(key_name) = key_rest.strip_suffix("_BASIC_USER") {
+                basic_auth_users.insert(key_name.to_owned(), value);
+            } else if let Some(key_name) = key_rest.strip_suffix("_BASIC_PASS") {
+                basic_auth_passwords.insert(key_name.to_owned(), value);
+            } else if let Some(key_name) = key_rest.strip_suffix("_BEARER_TOKEN") {
+                bearer_auth_tokens.insert(key_name.to_owned(), value);
+            } else {
+                auth_patterns.insert(key_rest.to_owned(), value);
+            }
+        }
+    }
+
+    let mut basic_auth_pattern_names = HashSet::new();
+    for x in [
+        &auth_patterns,
+        &basic_auth_users,
+        &basic_auth_passwords,
+        &bearer_auth_tokens,
+    ] {
+        for k in x.keys() {
+            basic_auth_pattern_names.insert(k);
+        }
+    }
+
+    let mut auths_builder: StandardHTTPAuthenticationBuilder =
+        StandardHTTPAuthenticationBuilder::new();
+    for k in basic_auth_pattern_names {
+        match (
+            auth_patterns.get(k),
+            basic_auth_users.get(k),
+            basic_auth_passwords.get(k),
+            bearer_auth_tokens.get(k),
+        ) {
+            (Some(pattern), None, None, None) => {
+                bail!(
+                    "SYSAND_CRED_{k} (`{pattern}`) has no matching authentication scheme, please specify SYSAND_CRED_{k}_BASIC_USER/SYSAND_CRED_{k}_BASIC_PASS or SYSAND_CRED_{k}_BEARER_TOKEN"
+                );
+            }
+            (Some(pattern), maybe_username, maybe_password, maybe_token) => {
+                let mut matched_schemes = 0;
+
+                match (maybe_username, maybe_password) {
+                    (Some(username), Some(password)) => {
+                        matched_schemes += 1;
+                        log::debug!("auth: env vars specify HTTP basic for URL glob `{pattern}`");
+                        auths_builder.add_basic_auth(pattern, username, password)
+                    }
+                    (None, None) => {}
+                    (_, _) => {
+                        bail!(
+                            "please specify both (or neither) of SYSAND_CRED_{k}_BASIC_USER and SYSAND_CRED_{k}_BASIC_PASS"
+                        );
+                    }
+                }
+
+                if let Some(token) = maybe_token {
+                    matched_schemes += 1;
+                    log::debug!("auth: env vars specify bearer token for URL glob `{pattern}`");
+                    auths_builder.add_bearer_auth(pattern, token);
+                }
+
+                if matched_schemes > 1 {
+                    log::warn!(
+                        "SYSAND_CRED_{k} (`{pattern}`) has multiple authentication schemes!"
+                    );
+                }
+            }
+            (None, _, _, _) => {
+                bail!("please specify URL pattern SYSAND_CRED_{k} for credential");
+            }
+        }
+    }
+
+    for index in &config.indexes {
+        if let Some(command) = &index.credential_helper {
+            let pattern = format!("{}/**", index.url.trim_end_matches('/'));
+            log::debug!("auth: config specifies credential helper for URL glob `{pattern}`");
+            auths_builder
+                .add_credential_helper(pattern, CredentialHelper::new(command, &index.url));
+        }
+    }
+    Ok(auths_builder.build()?)
+}
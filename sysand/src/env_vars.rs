@@ -18,3 +18,38 @@ pub const SYSAND_CONFIG_FILE: &str = "SYSAND_CONFIG_FILE";
 /// Corresponds to the `--no-config` command line argument. If set prevents reading of any
 /// Sysand config files.
 pub const SYSAND_NO_CONFIG: &str = "SYSAND_NO_CONFIG";
+
+/// Corresponds to the `source_roots` configuration option. List of directories,
+/// separated like `PATH`, whose projects are used when resolving dependencies
+/// without having to be installed. Takes precedence over configured source roots.
+pub const SYSAND_EXTRA_SOURCE_ROOTS: &str = "SYSAND_EXTRA_SOURCE_ROOTS";
+
+/// Corresponds to the `system_envs` configuration option. List of read-only
+/// environment directories, separated like `PATH`, e.g. set by a tool installation
+/// that ships its libraries. Takes precedence over configured system environments.
+pub const SYSAND_SYSTEM_ENVS: &str = "SYSAND_SYSTEM_ENVS";
+
+/// Corresponds to the `--no-cache` command line argument. If set, neither project
+/// metadata fetched over HTTP nor git repositories are cached.
+pub const SYSAND_NO_CACHE: &str = "SYSAND_NO_CACHE";
+
+/// Corresponds to the `--no-hooks` command line argument. If set, lifecycle hooks
+/// configured under `[hooks]` are not run.
+pub const SYSAND_NO_HOOKS: &str = "SYSAND_NO_HOOKS";
+
+/// Directory of the caches of project metadata fetched over HTTP (`http`) and of
+/// git repositories (`git`). Defaults to `sysand` in the platform cache directory.
+pub const SYSAND_CACHE_DIR: &str = "SYSAND_CACHE_DIR";
+
+/// Key of the projects stored encrypted in environments (`env.storage =
+/// "encrypted"`), as 64 hexadecimal characters. Takes precedence over the
+/// `env.key_file` configuration option.
+pub const SYSAND_ENV_KEY: &str = "SYSAND_ENV_KEY";
+
+/// Settings in TOML that take precedence over all configuration files, e.g.
+/// `network.proxy = "http://proxy:3128"`. Also read with `--no-config`.
+pub use sysand_core::config::local_fs::SYSAND_SETTINGS;
+
+/// Path of the system configuration file, which has the lowest precedence.
+/// Defaults to `/etc/sysand/sysand.toml`, or a platform equivalent location.
+pub use sysand_core::config::local_fs::SYSAND_SYSTEM_CONFIG;
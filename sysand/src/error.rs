@@ -1,9 +1,20 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
-use sysand_core::{info::InfoProjectError, resolve::file::FileResolverProjectError};
+use std::{error::Error as StdError, fmt, process::ExitCode};
+
+use anstream::eprintln;
+
+use sysand_core::{
+    error_code::{ErrorClass, ErrorCode, HasErrorCode},
+    info::InfoProjectError,
+    project::utils::FsIoError,
+    resolve::file::FileResolverProjectError,
+};
 use thiserror::Error;
 
+use crate::{cli::ErrorFormat, style};
+
 #[derive(Error, Debug)]
 pub enum CliError {
     #[error("invalid directory: {0}")]
@@ -24,4 +35,158 @@ pub enum CliError {
     MissingProjectVersion(String, String),
     #[error("unable to find interchange project in current directory")]
     MissingProjectCurrentDir,
+    #[error("found {0} problem(s) in the configuration files")]
+    InvalidConfig(usize),
+    #[error("`{0}` is not set")]
+    ConfigKeyNotSet(String),
+    #[error("lockfile `{0}` is not up to date, run `sysand lock`:\n{1}")]
+    OutdatedLockfile(String, String),
+}
+
+impl HasErrorCode for CliError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            CliError::InvalidDirectory(_) => ErrorCode::new(1901, ErrorClass::Usage),
+            CliError::NoResolve(_) => ErrorCode::new(1902, ErrorClass::Resolution),
+            CliError::InvalidProject { .. } => ErrorCode::new(1903, ErrorClass::Project),
+            CliError::InvalidIri(..) => ErrorCode::new(1904, ErrorClass::Usage),
+            CliError::MissingProject(_) => ErrorCode::new(1905, ErrorClass::Environment),
+            CliError::MissingProjectVersion(..) => ErrorCode::new(1906, ErrorClass::Environment),
+            CliError::MissingProjectCurrentDir => ErrorCode::new(1907, ErrorClass::Project),
+            CliError::InvalidConfig(_) => ErrorCode::new(1908, ErrorClass::Usage),
+            CliError::ConfigKeyNotSet(_) => ErrorCode::new(1909, ErrorClass::Usage),
+            CliError::OutdatedLockfile(..) => ErrorCode::new(1912, ErrorClass::Project),
+        }
+    }
+}
+
+/// Failure reading the environment
+pub const ENV_READ: ErrorCode = ErrorCode::new(1910, ErrorClass::Environment);
+/// Failure modifying the environment
+pub const ENV_WRITE: ErrorCode = ErrorCode::new(1911, ErrorClass::Environment);
+
+/// Error together with its [`ErrorCode`]. `anyhow::Error` can only be
+/// downcast to concrete types, so errors of generic types have to be
+/// wrapped in this to make their code available to [`error_code`].
+/// Displays as, and has the same sources as, the wrapped error.
+#[derive(Debug)]
+pub struct Coded {
+    code: ErrorCode,
+    error: Box<dyn StdError + Send + Sync>,
+}
+
+impl Coded {
+    pub fn new<E: Into<Box<dyn StdError + Send + Sync>>>(code: ErrorCode, error: E) -> Self {
+        Self {
+            code,
+            error: error.into(),
+        }
+    }
+}
+
+impl fmt::Display for Coded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl StdError for Coded {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.error.source()
+    }
+}
+
+pub trait ResultExt<T> {
+    /// Attach `code` to the error
+    fn with_code(self, code: ErrorCode) -> Result<T, Coded>;
+}
+
+impl<T, E: StdError + Send + Sync + 'static> ResultExt<T> for Result<T, E> {
+    fn with_code(self, code: ErrorCode) -> Result<T, Coded> {
+        self.map_err(|error| Coded::new(code, error))
+    }
+}
+
+pub trait CodedResultExt<T> {
+    /// Attach the error's own code to it
+    fn coded(self) -> Result<T, Coded>;
+}
+
+impl<T, E: HasErrorCode + StdError + Send + Sync + 'static> CodedResultExt<T> for Result<T, E> {
+    fn coded(self) -> Result<T, Coded> {
+        self.map_err(|error| Coded::new(error.error_code(), error))
+    }
+}
+
+/// Code of `err`. This is the code of the outermost error in its chain that
+/// has one, or else of the first filesystem error, or else
+/// [`ErrorCode::OTHER`].
+pub fn error_code(err: &anyhow::Error) -> ErrorCode {
+    err.chain()
+        .find_map(|cause| {
+            if let Some(coded) = cause.downcast_ref::<Coded>() {
+                Some(coded.code)
+            } else {
+                cause.downcast_ref::<CliError>().map(|e| e.error_code())
+            }
+        })
+        .or_else(|| {
+            err.chain().find_map(|cause| {
+                cause
+                    .downcast_ref::<FsIoError>()
+                    .or_else(|| cause.downcast_ref::<Box<FsIoError>>().map(|e| &**e))
+                    .map(|e| e.error_code())
+            })
+        })
+        .unwrap_or(ErrorCode::OTHER)
+}
+
+/// Print `err` to stderr in the given format and return the exit code
+/// for it
+pub fn report(err: &anyhow::Error, format: ErrorFormat) -> ExitCode {
+    let code = error_code(err);
+    match format {
+        ErrorFormat::Human => {
+            let style = style::ERROR;
+            eprintln!("{style}error[{code}]{style:#}: {err}");
+            let mut causes = err.chain();
+            // The first cause is the error itself which is printed already
+            _ = causes.next();
+            for cause in causes {
+                eprintln!("{style}  caused by:{style:#} {cause}");
+            }
+            let note_style = style::GOOD;
+            if log::max_level() < log::Level::Debug {
+                eprintln!(
+                    "\n{note_style}note{note_style:#}: pass `-v`/`--verbose` to output additional logs"
+                );
+            }
+        }
+        ErrorFormat::Json => {
+            let causes: Vec<_> = err.chain().skip(1).map(|c| c.to_string()).collect();
+            print_json_error(code, &err.to_string(), &causes);
+        }
+    }
+    ExitCode::from(code.class().exit_code())
+}
+
+/// Print a command line parsing error in JSON format
+pub fn report_clap_json(err: &clap::Error) -> ExitCode {
+    let rendered = err.render().to_string();
+    let message = rendered.trim();
+    let message = message.strip_prefix("error: ").unwrap_or(message);
+    print_json_error(ErrorCode::USAGE, message, &[]);
+    ExitCode::from(ErrorCode::USAGE.class().exit_code())
+}
+
+fn print_json_error(code: ErrorCode, message: &str, causes: &[String]) {
+    let diagnostic = serde_json::json!({
+        "level": "error",
+        "code": code.to_string(),
+        "class": code.class().as_str(),
+        "exit_code": code.class().exit_code(),
+        "message": message,
+        "causes": causes,
+    });
+    std::eprintln!("{diagnostic}");
 }
@@ -5,14 +5,7 @@
 compile_error!("`std` feature is currently required to build `sysand`");
 
 use std::{
-    collections::{HashMap, HashSet},
-    ffi::OsString,
-    fs,
-    io::ErrorKind,
-    panic,
-    process::ExitCode,
-    str::FromStr,
-    sync::Arc,
+    collections::HashMap, ffi::OsString, panic, process::ExitCode, sync::Arc, time::Duration,
 };
 
 use anstream::eprintln;
@@ -21,64 +14,65 @@ use fluent_uri::Iri;
 
 use camino::{Utf8Path, Utf8PathBuf};
 use clap::Parser;
+use reqwest_middleware::ClientWithMiddleware;
 use sysand_core::{
-    auth::{HTTPAuthentication, StandardHTTPAuthenticationBuilder},
-    commands::lock::DEFAULT_LOCKFILE_NAME,
+    auth::HTTPAuthentication,
+    commands::manifest::do_render_manifest,
     config::{
-        Config,
-        local_fs::{get_config, load_configs},
+        Config, EnvStorage, ProjectManifest, WhenMissing,
+        local_fs::{CONFIG_FILE, user_cache_dir},
     },
     context::ProjectContext,
-    discover::{discover_project, discover_workspace},
-    env::{DEFAULT_ENV_NAME, local_directory::LocalDirectoryEnvironment},
-    index::RemoveTarget,
-    init::InitError,
-    lock::Lock,
+    discover::{SourceRootProjectKind, discover_source_root},
+    env::{
+        DEFAULT_ENV_NAME,
+        local_directory::{
+            LocalDirectoryEnvironment,
+            encrypted::{EncryptionError, EnvKey},
+        },
+    },
     project::{
+        ProjectRead,
         any::{AnyProject, OverrideProject},
+        local_kpar::{KparInnerPath, LocalKParProject},
         local_src::LocalSrcProject,
+        memory::InMemoryProject,
         reference::ProjectReference,
-        utils::wrapfs,
+        utils::{relativize_path, wrapfs},
+    },
+    resolve::{
+        http_cache::{DEFAULT_TTL, HttpCache},
+        net_utils::HttpClientFactory,
     },
-    resolve::net_utils::create_reqwest_client,
-    stdlib::known_std_libs,
+    solve::markers::{ConditionError, MARKER_ATTRIBUTES, Markers},
+    stdlib::{IncludeStd, known_std_libs},
     workspace::Workspace,
 };
+use typed_path::Utf8UnixPathBuf;
 use url::Url;
 
 use crate::{
-    cli::{Args, Command, InfoCommand},
-    commands::{
-        add::command_add,
-        build::{command_build_for_project, command_build_for_workspace},
-        env::{
-            command_env, command_env_install, command_env_install_path, command_env_list,
-            command_env_uninstall,
-        },
-        exclude::command_exclude,
-        include::command_include,
-        index::{command_index_add, command_index_init, command_index_remove, command_index_yank},
-        info::{command_info_current_project, command_info_path, command_info_verb_path},
-        init::command_init,
-        lock::command_lock,
-        print_root::command_print_root,
-        publish::command_publish,
-        remove::command_remove,
-        sources::{command_sources_env, command_sources_project},
-        sync::command_sync,
-    },
+    cli::Args,
+    commands::{env::command_env, init::command_init_here},
+    context::CommandContext,
+    error::CodedResultExt,
 };
 
-pub const DEFAULT_INDEX_URL: &str = "https://sysand.com";
+pub use sysand_core::session::DEFAULT_INDEX_URL;
 
 pub mod cli;
 pub mod commands;
+pub mod context;
 pub mod env_vars;
 pub mod logger;
 pub mod style;
 
 mod error;
+mod progress;
+mod run;
+mod timings;
 pub use error::CliError;
+pub use run::run_command;
 
 pub fn lib_main<I, T>(args: I) -> ExitCode
 where
@@ -87,26 +81,29 @@ where
 {
     set_panic_hook();
 
+    let (args, deprecated) = cli::rewrite_deprecated(args, cli::DEPRECATIONS);
+    let json_requested = cli::requests_json_errors(&args);
     match Args::try_parse_from(args) {
-        Ok(args) => {
-            if let Err(err) = run_cli(args) {
-                let style = style::ERROR;
-                eprintln!("{style}error{style:#}: {err}");
-                let mut causes = err.chain();
-                // The first cause is the error itself which is printed already
-                _ = causes.next();
-                for cause in causes {
-                    eprintln!("{style}  caused by:{style:#} {cause}");
-                }
-                let note_style = style::GOOD;
-                if log::max_level() < log::Level::Debug {
-                    eprintln!(
-                        "\n{note_style}note{note_style:#}: pass `-v`/`--verbose` to output additional logs"
-                    );
-                }
-                return ExitCode::FAILURE;
+        Ok(mut args) => {
+            args.deprecated = deprecated;
+            let error_format = args.global_opts.error_format;
+            let timings = args.global_opts.timings;
+            if timings.is_some() {
+                sysand_core::timings::enable();
+            }
+            let result = run_cli(args);
+            if let Some(format) = timings
+                && let Err(err) = timings::report(format)
+            {
+                log::warn!("failed to report timings: {err}");
+            }
+            if let Err(err) = result {
+                return error::report(&err, error_format);
             }
         }
+        Err(err) if json_requested && err.use_stderr() => {
+            return error::report_clap_json(&err);
+        }
         Err(err) => {
             err.print().expect("failed to write Clap error");
             return ExitCode::from(err.exit_code() as u8);
@@ -135,12 +132,39 @@ fn set_panic_hook() {
     }));
 }
 
+/// Directory containing the caches, if caching is enabled
+fn get_cache_dir(no_cache: bool) -> Option<Utf8PathBuf> {
+    if no_cache {
+        return None;
+    }
+    std::env::var(env_vars::SYSAND_CACHE_DIR)
+        .ok()
+        .map(Utf8PathBuf::from)
+        .or_else(user_cache_dir)
+}
+
+/// Create the HTTP client shared by the command, caching project metadata
+/// in `cache_dir`
+fn create_client(cache_dir: Option<&Utf8Path>, config: &Config) -> Result<ClientWithMiddleware> {
+    let mut factory = HttpClientFactory::new(config);
+    if let Some(cache_dir) = cache_dir {
+        let root = cache_dir.join("http");
+        let ttl = config.cache.ttl.map_or(DEFAULT_TTL, Duration::from_secs);
+        log::debug!("caching HTTP metadata in `{root}` for {}s", ttl.as_secs());
+        factory = factory.with_cache(HttpCache::new(root, ttl));
+    }
+    Ok(factory.client()?)
+}
+
 pub fn run_cli(args: cli::Args) -> Result<()> {
     sysand_core::style::set_style_config(crate::style::CONFIG);
 
-    let cwd = wrapfs::current_dir()?;
     let log_level = get_log_level(args.global_opts.verbose, args.global_opts.quiet);
-    if logger::init(log_level).is_err() {
+    let log_format = args
+        .global_opts
+        .log_format
+        .unwrap_or(args.global_opts.error_format);
+    if logger::init(log_level, log_format).is_err() {
         let warn = style::WARN;
         eprintln!(
             "{warn}warning{warn:#}: failed to set up logger because it has already been set up;\n\
@@ -150,623 +174,15 @@ pub fn run_cli(args: cli::Args) -> Result<()> {
         log::set_max_level(log_level);
     }
     log::debug!("sysand v{}", env!("CARGO_PKG_VERSION"));
-
-    let current_project = discover_project(&cwd)?;
-    let current_workspace = discover_workspace(&cwd)?;
-    let env = match (&current_workspace, &current_project) {
-        // TODO: does it make sense to support env unassociated with a project
-        // when index and env are different?
-        (None, None) => get_env(&cwd)?,
-        (None, Some(pr)) => get_env(pr.root_path())?,
-        (Some(w), _) => get_env(w.root_path())?,
-    };
-    let ctx = ProjectContext {
-        env,
-        current_workspace,
-        current_project,
-        current_directory: cwd,
-    };
-    let project_root = ctx
-        .current_project
-        .as_ref()
-        .map(|p| p.root_path().to_owned());
-
-    let auto_config = if args.global_opts.no_config {
-        Config::default()
-    } else {
-        load_configs(project_root.as_deref().unwrap_or(Utf8Path::new(".")))?
-    };
-
-    let mut config = if let Some(config_file) = &args.global_opts.config_file {
-        get_config(config_file)?
-    } else {
-        Config::default()
-    };
-
-    config.merge(auto_config);
-
-    let client = create_reqwest_client()?;
-
-    let runtime = Arc::new(
-        tokio::runtime::Builder::new_current_thread()
-            .enable_io()
-            .enable_time()
-            .build()
-            .unwrap(),
-    );
-
-    let _runtime_keep_alive = runtime.clone();
-
-    // FIXME: This is a temporary implementation to provide credentials until
-    //        https://github.com/sensmetry/sysand/pull/157
-    //        gets merged.
-    let mut auth_patterns = HashMap::new();
-    let mut basic_auth_users = HashMap::new();
-    let mut basic_auth_passwords = HashMap::new();
-    let mut bearer_auth_tokens = HashMap::new();
-
-    for (key, value) in std::env::vars() {
-        if let Some(key_rest) = key.strip_prefix("SYSAND_CRED_") {
-            if let Some(key_name) = key_rest.strip_suffix("_BASIC_USER") {
-                basic_auth_users.insert(key_name.to_owned(), value);
-            } else if let Some(key_name) = key_rest.strip_suffix("_BASIC_PASS") {
-                basic_auth_passwords.insert(key_name.to_owned(), value);
-            } else if let Some(key_name) = key_rest.strip_suffix("_BEARER_TOKEN") {
-                bearer_auth_tokens.insert(key_name.to_owned(), value);
-            } else {
-                auth_patterns.insert(key_rest.to_owned(), value);
-            }
-        }
+    for deprecation in &args.deprecated {
+        deprecation.warn();
     }
 
-    let mut basic_auth_pattern_names = HashSet::new();
-    for x in [
-        &auth_patterns,
-        &basic_auth_users,
-        &basic_auth_passwords,
-        &bearer_auth_tokens,
-    ] {
-        for k in x.keys() {
-            basic_auth_pattern_names.insert(k);
-        }
-    }
-
-    let mut auths_builder: StandardHTTPAuthenticationBuilder =
-        StandardHTTPAuthenticationBuilder::new();
-    for k in basic_auth_pattern_names {
-        match (
-            auth_patterns.get(k),
-            basic_auth_users.get(k),
-            basic_auth_passwords.get(k),
-            bearer_auth_tokens.get(k),
-        ) {
-            (Some(pattern), None, None, None) => {
-                anyhow::bail!(
-                    "SYSAND_CRED_{k} (`{pattern}`) has no matching authentication scheme, please specify SYSAND_CRED_{k}_BASIC_USER/SYSAND_CRED_{k}_BASIC_PASS or SYSAND_CRED_{k}_BEARER_TOKEN"
-                );
-            }
-            (Some(pattern), maybe_username, maybe_password, maybe_token) => {
-                let mut matched_schemes = 0;
-
-                match (maybe_username, maybe_password) {
-                    (Some(username), Some(password)) => {
-                        matched_schemes += 1;
-                        log::debug!("auth: env vars specify HTTP basic for URL glob `{pattern}`");
-                        auths_builder.add_basic_auth(pattern, username, password)
-                    }
-                    (None, None) => {}
-                    (_, _) => {
-                        anyhow::bail!(
-                            "please specify both (or neither) of SYSAND_CRED_{k}_BASIC_USER and SYSAND_CRED_{k}_BASIC_PASS"
-                        );
-                    }
-                }
-
-                if let Some(token) = maybe_token {
-                    matched_schemes += 1;
-                    log::debug!("auth: env vars specify bearer token for URL glob `{pattern}`");
-                    auths_builder.add_bearer_auth(pattern, token);
-                }
-
-                if matched_schemes > 1 {
-                    log::warn!(
-                        "SYSAND_CRED_{k} (`{pattern}`) has multiple authentication schemes!"
-                    );
-                }
-            }
-            (None, _, _, _) => {
-                anyhow::bail!("please specify URL pattern SYSAND_CRED_{k} for credential");
-            }
-        }
-    }
-    let auth_policy = Arc::new(auths_builder.build()?);
-
-    match args.command {
-        Command::Init {
-            path,
-            name,
-            publisher,
-            version,
-            no_semver,
-            license,
-            no_spdx,
-        } => command_init(name, publisher, version, no_semver, license, no_spdx, path),
-        Command::New { .. } => bail!("use `init` instead of `new`"),
-        Command::Env { command } => match command {
-            None => {
-                let env_dir = {
-                    let mut p = project_root.unwrap_or(ctx.current_directory);
-                    p.push(DEFAULT_ENV_NAME);
-                    p
-                };
-                command_env(env_dir)?;
-
-                Ok(())
-            }
-            Some(cli::EnvCommand::Install {
-                iri,
-                version,
-                path,
-                install_opts,
-                resolution_opts,
-            }) => {
-                if let Some(path) = path {
-                    command_env_install_path(
-                        iri,
-                        version,
-                        path,
-                        install_opts,
-                        resolution_opts,
-                        &config,
-                        project_root,
-                        client,
-                        runtime,
-                        auth_policy,
-                        ctx,
-                    )
-                } else {
-                    command_env_install(
-                        iri,
-                        version,
-                        install_opts,
-                        resolution_opts,
-                        &config,
-                        project_root,
-                        client,
-                        runtime,
-                        auth_policy,
-                        ctx,
-                    )
-                }
-            }
-            Some(cli::EnvCommand::Uninstall { iri, version }) => match ctx.env {
-                Some(local_environment) => command_env_uninstall(iri, version, local_environment),
-                None => {
-                    log::warn!("no environment to uninstall from");
-                    Ok(())
-                }
-            },
-            Some(cli::EnvCommand::List) => command_env_list(ctx.env),
-            Some(cli::EnvCommand::Sources {
-                iri,
-                version,
-                sources_opts,
-            }) => {
-                let cli::SourcesOptions {
-                    no_deps,
-                    include_std,
-                } = sources_opts;
-                let provided_iris = if !include_std {
-                    known_std_libs()
-                } else {
-                    HashMap::default()
-                };
-
-                command_sources_env(iri, version, !no_deps, ctx.env, &provided_iris, include_std)
-            }
-        },
-        Command::Index { command } => {
-            let root =
-                |index_root: Option<Utf8PathBuf>| index_root.unwrap_or(ctx.current_directory);
-            match command {
-                cli::IndexCommand::Init { index_root } => command_index_init(root(index_root)),
-                cli::IndexCommand::Add {
-                    iri,
-                    kpar_path,
-                    index_root,
-                } => command_index_add(iri, kpar_path, root(index_root)),
-                cli::IndexCommand::Yank {
-                    iri,
-                    version,
-                    index_root,
-                } => command_index_yank(iri, version, root(index_root)),
-                cli::IndexCommand::Remove {
-                    iri,
-                    target,
-                    index_root,
-                } => {
-                    let target = match (target.version, target.project) {
-                        (Some(version), false) => RemoveTarget::Version(version),
-                        (None, true) => RemoveTarget::Project,
-                        _ => unreachable!(),
-                    };
-                    command_index_remove(iri, target, root(index_root))
-                }
-            }
-        }
-        Command::Lock { resolution_opts } => {
-            if let Some(project_root) = project_root {
-                crate::commands::lock::command_lock(
-                    ".",
-                    resolution_opts,
-                    &config,
-                    project_root,
-                    client,
-                    runtime,
-                    auth_policy,
-                    &ctx,
-                )
-                .map(|_| ())
-            } else {
-                bail!(
-                    "not inside a project - neither current nor any of the parent directories contain a SysML v2 or KerML project"
-                )
-            }
-        }
-        Command::Sync { resolution_opts } => {
-            // TODO: only print this if we actually skip install of any std libs
-            let provided_iris = if !resolution_opts.include_std {
-                crate::logger::warn_std_deps();
-                known_std_libs()
-            } else {
-                HashMap::default()
-            };
-
-            let project_root = project_root.unwrap_or(ctx.current_directory.clone());
-            let lockfile = project_root.join(DEFAULT_LOCKFILE_NAME);
-            let lock = match fs::read_to_string(&lockfile) {
-                Ok(l) => match Lock::from_str(&l) {
-                    Ok(l) => l,
-                    // Include file path in errors
-                    Err(e) => bail!("invalid lockfile `{lockfile}`:\n{e}"),
-                },
-                Err(e) => {
-                    if e.kind() == ErrorKind::NotFound {
-                        command_lock(
-                            ".",
-                            resolution_opts,
-                            &config,
-                            &project_root,
-                            client.clone(),
-                            runtime.clone(),
-                            auth_policy.clone(),
-                            &ctx,
-                        )?
-                    } else {
-                        bail!("failed to read lockfile `{lockfile}`: {e}")
-                    }
-                }
-            };
-            let mut local_environment = get_or_create_env(
-                ctx.env,
-                ctx.current_workspace.as_ref(),
-                ctx.current_project.as_ref(),
-                &ctx.current_directory,
-            )?;
-            command_sync(
-                &lock,
-                project_root,
-                &mut local_environment,
-                client,
-                &provided_iris,
-                runtime,
-                auth_policy,
-                ctx.current_workspace.as_ref(),
-            )
-        }
-        Command::PrintRoot => command_print_root(ctx.current_directory),
-        Command::Info {
-            path,
-            iri,
-            auto_location,
-            no_normalise,
-            resolution_opts,
-            subcommand,
-        } => {
-            let cli::ResolutionOptions {
-                index,
-                default_index,
-                no_index,
-                include_std,
-            } = resolution_opts;
-            let index_urls = if no_index {
-                None
-            } else {
-                Some(config.index_urls(
-                    index,
-                    vec![DEFAULT_INDEX_URL.to_string()],
-                    default_index,
-                )?)
-            };
-            let excluded_iris: HashSet<_> = if !include_std {
-                // Only print std warning when command is to print all info
-                // or just usages.
-                // These are the only cases where stdlib usages affect output
-                // TODO: be more precise, this warning is annoying
-                match subcommand {
-                    None
-                    | Some(InfoCommand::Usage {
-                        clear: None,
-                        add: None,
-                        set: None,
-                        remove: None,
-                        numbered: _,
-                    }) => crate::logger::warn_std_deps(),
-                    _ => (),
-                }
-                known_std_libs().into_keys().collect()
-            } else {
-                HashSet::default()
-            };
-
-            let project_root = project_root.as_ref().unwrap_or(&ctx.current_directory);
-            let overrides = get_overrides(
-                &config,
-                project_root,
-                &client,
-                runtime.clone(),
-                auth_policy.clone(),
-            )?;
-
-            enum Location {
-                WorkDir,
-                Iri(fluent_uri::Iri<String>),
-                Path(Utf8PathBuf),
-            }
-
-            let location = if let Some(auto_location) = auto_location {
-                debug_assert!(path.is_none());
-                debug_assert!(iri.is_none());
-
-                if let Ok(iri) = fluent_uri::Iri::parse(auto_location.clone()) {
-                    Location::Iri(iri)
-                } else {
-                    Location::Path(auto_location.into())
-                }
-            } else if let Some(path) = path {
-                debug_assert!(auto_location.is_none());
-                debug_assert!(iri.is_none());
-
-                Location::Path(path)
-            } else if let Some(iri) = iri {
-                debug_assert!(path.is_none());
-                debug_assert!(auto_location.is_none());
-
-                Location::Iri(iri)
-            } else {
-                Location::WorkDir
-            };
-
-            match (location, subcommand) {
-                (Location::WorkDir, subcommand) => {
-                    if let Some(current_project) = ctx.current_project {
-                        match subcommand {
-                            Some(subcommand) => {
-                                match subcommand {
-                                    cli::InfoCommand::Version {
-                                        ref set, no_semver, ..
-                                    } => {
-                                        if !no_semver && let Some(v) = set {
-                                            semver::Version::parse(v).map_err(|e| {
-                                                InitError::<std::convert::Infallible>::SemVerParse(
-                                                    v.as_str().into(),
-                                                    e,
-                                                )
-                                            })?;
-                                        }
-                                    }
-                                    cli::InfoCommand::License {
-                                        ref set, no_spdx, ..
-                                    } => {
-                                        if !no_spdx && let Some(l) = set {
-                                            spdx::Expression::parse(l).map_err(|e| {
-                                                InitError::<std::convert::Infallible>::SPDXLicenseParse(l.as_str().into(), e)
-                                            })?;
-                                        }
-                                    }
-                                    _ => (),
-                                }
-
-                                let numbered = subcommand.numbered();
-                                command_info_current_project(
-                                    current_project,
-                                    subcommand.as_verb(),
-                                    numbered,
-                                )
-                            }
-                            None => command_info_path(current_project.root_path(), &excluded_iris),
-                        }
-                    } else {
-                        bail!(
-                            "run outside of an active project, did you mean to use `--path` or `--iri`?"
-                        )
-                    }
-                }
-                (Location::Iri(iri), None) => crate::commands::info::command_info_uri(
-                    iri,
-                    !no_normalise,
-                    client,
-                    index_urls,
-                    &excluded_iris,
-                    overrides,
-                    runtime,
-                    auth_policy,
-                    ctx,
-                ),
-                (Location::Iri(iri), Some(subcommand)) => {
-                    let numbered = subcommand.numbered();
-
-                    crate::commands::info::command_info_verb_uri(
-                        iri,
-                        subcommand.as_verb(),
-                        numbered,
-                        client,
-                        index_urls,
-                        overrides,
-                        runtime,
-                        auth_policy,
-                        ctx,
-                    )
-                }
-                (Location::Path(path), None) => command_info_path(&path, &excluded_iris),
-                (Location::Path(path), Some(subcommand)) => {
-                    let numbered = subcommand.numbered();
-
-                    command_info_verb_path(&path, subcommand.as_verb(), numbered)
-                }
-            }
-        }
-        Command::Add {
-            locator,
-            version_constraint,
-            no_lock,
-            no_sync,
-            resolution_opts,
-            source_opts,
-        } => {
-            let iri = iri_or_path_to_iri(locator.iri, locator.path)?;
-            command_add(
-                iri,
-                version_constraint,
-                no_lock,
-                no_sync,
-                resolution_opts,
-                source_opts,
-                config,
-                args.global_opts.config_file,
-                args.global_opts.no_config,
-                ctx,
-                client,
-                runtime,
-                auth_policy,
-            )
-        }
-        Command::Remove { locator } => {
-            let iri = iri_or_path_to_iri(locator.iri, locator.path)?;
-            command_remove(
-                iri,
-                ctx,
-                args.global_opts.config_file,
-                args.global_opts.no_config,
-            )
-        }
-        Command::Include {
-            paths,
-            compute_checksum: add_checksum,
-            no_index_symbols,
-        } => command_include(paths, add_checksum, !no_index_symbols, ctx),
-        Command::Exclude { paths } => command_exclude(paths, ctx),
-        Command::Build {
-            path,
-            compression,
-            update_meta,
-            allow_path_usage,
-            keep_index,
-        } => {
-            if update_meta {
-                log::warn!("`--update-meta` is now the default behavior and is no longer needed")
-            }
-            if let Some(current_project) = ctx.current_project {
-                // Even if we are in a workspace, the project takes precedence.
-                let path = if let Some(path) = path {
-                    path
-                } else {
-                    let path = sysand_core::build::default_kpar_path(
-                        &current_project,
-                        ctx.current_workspace.as_ref(),
-                        &current_project.project_path,
-                    )?;
-                    if let Some(output_dir) = path.parent()
-                        && !wrapfs::is_dir(output_dir)?
-                    {
-                        wrapfs::create_dir(output_dir)?;
-                    }
-                    path
-                };
-                command_build_for_project(
-                    path,
-                    compression.into(),
-                    current_project,
-                    !keep_index,
-                    allow_path_usage,
-                )
-            } else {
-                // If the workspace is also missing, report an error about
-                // missing project because that is what the user is more likely
-                // to be looking for.
-                let current_workspace = ctx
-                    .current_workspace
-                    .ok_or(CliError::MissingProjectCurrentDir)?;
-                let output_dir =
-                    path.unwrap_or_else(|| current_workspace.root_path().join("output"));
-                if !wrapfs::is_dir(&output_dir)? {
-                    wrapfs::create_dir(&output_dir)?;
-                }
-                command_build_for_workspace(
-                    output_dir,
-                    compression.into(),
-                    current_workspace,
-                    !keep_index,
-                    allow_path_usage,
-                )
-            }
-        }
-        cli::Command::Publish {
-            path,
-            index,
-            trusted_publishing,
-        } => command_publish(
-            path,
-            index,
-            trusted_publishing,
-            &ctx,
-            auth_policy,
-            client,
-            runtime,
-        ),
-        Command::Sources { sources_opts } => {
-            let cli::SourcesOptions {
-                no_deps,
-                include_std,
-            } = sources_opts;
-            let provided_iris = if !include_std {
-                crate::logger::warn_std_omit();
-                known_std_libs()
-            } else {
-                HashMap::default()
-            };
-
-            command_sources_project(!no_deps, ctx, &provided_iris)
-        }
-        Command::Clone {
-            locator,
-            version,
-            target,
-            resolution_opts,
-            no_deps,
-        } => commands::clone::command_clone(
-            locator,
-            version,
-            target,
-            ctx,
-            no_deps,
-            resolution_opts,
-            &config,
-            client,
-            runtime,
-            auth_policy,
-        ),
-    }
+    let cx = CommandContext::from_options(&args.global_opts, &args.command)?;
+    let _runtime_keep_alive = cx.runtime.clone();
+    progress::with_stderr_progress(log_level >= log::LevelFilter::Info, || {
+        run_command(args.command, cx)
+    })
 }
 
 fn iri_or_path_to_iri(
@@ -785,36 +201,177 @@ fn iri_or_path_to_iri(
     })
 }
 
+/// Workspace to lock as a whole, if `workspace.shared_lock` is set and
+/// there is a current workspace
+pub fn shared_lock_workspace<'a>(
+    config: &Config,
+    ctx: &'a ProjectContext,
+) -> Option<&'a Workspace> {
+    if config.workspace.shared_lock == Some(true) {
+        ctx.current_workspace.as_ref()
+    } else {
+        None
+    }
+}
+
+/// Render `.project.json` and `.meta.json` of the current project from
+/// `manifest`, creating the project in the current directory if there is
+/// none yet. With `check`, fails instead if they are out of date
+fn render_manifest(
+    manifest: &ProjectManifest,
+    ctx: &mut ProjectContext,
+    check: bool,
+) -> Result<()> {
+    let project = ctx.current_project.get_or_insert_with(|| LocalSrcProject {
+        nominal_path: Some(Utf8UnixPathBuf::from(".")),
+        project_path: ctx.current_directory.clone(),
+        expected_checksum: None,
+    });
+    let config_path = project.root_path().join(CONFIG_FILE);
+    if do_render_manifest(manifest, &config_path, project, check).coded()? && check {
+        bail!(
+            "`.project.json` or `.meta.json` is out of date with `[manifest]` in \
+            `{config_path}`; run `sysand normalize`"
+        );
+    }
+    Ok(())
+}
+
 /// Read `root/.sysand/` metadata
 pub fn get_env(root: impl AsRef<Utf8Path>) -> Result<Option<LocalDirectoryEnvironment>> {
     let environment_path = root.as_ref().join(DEFAULT_ENV_NAME);
     LocalDirectoryEnvironment::try_read(environment_path).map_err(anyhow::Error::from)
 }
 
+/// Key of the projects stored encrypted in environments, given by
+/// [`env_vars::SYSAND_ENV_KEY`] or the file set by `env.key_file` in `config`
+pub fn get_env_key(config: &Config) -> Result<Option<EnvKey>> {
+    let (key, origin) = match std::env::var(env_vars::SYSAND_ENV_KEY) {
+        Ok(key) => (key, env_vars::SYSAND_ENV_KEY.to_owned()),
+        Err(_) => match &config.env.key_file {
+            Some(path) => (wrapfs::read_to_string(path)?, format!("`{path}`")),
+            None => return Ok(None),
+        },
+    };
+    EnvKey::from_hex(key)
+        .map(Some)
+        .map_err(|e| anyhow!("invalid key in {origin}: {e}"))
+}
+
+/// Called when a command needs a project but none was found. Creates a
+/// minimal project in the current directory if `here` is set or
+/// `when_missing` allows it, otherwise fails. Returns the project root
+fn project_fallback(
+    ctx: &mut ProjectContext,
+    here: bool,
+    when_missing: Option<WhenMissing>,
+) -> Result<Utf8PathBuf> {
+    let create = here
+        || match when_missing.unwrap_or_default() {
+            WhenMissing::Error => false,
+            WhenMissing::Here => true,
+            WhenMissing::Prompt => prompt_create_project(&ctx.current_directory)?,
+        };
+    if !create {
+        let hint = style::USAGE;
+        bail!(
+            "not inside a project - neither current nor any of the parent directories contain a SysML v2 or KerML project\n\
+            {hint}hint:{hint:#} run `sysand init` to create one, or pass `--here` to create a minimal\n\
+            {:>6}project including all SysML v2/KerML files in the current directory",
+            ' '
+        );
+    }
+
+    let project = command_init_here(&ctx.current_directory)?;
+    let root = project.root_path().to_owned();
+    ctx.current_project = Some(project);
+    Ok(root)
+}
+
+/// Ask whether to create a project in `path`. Always declines when not
+/// running in a terminal
+fn prompt_create_project(path: &Utf8Path) -> Result<bool> {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    if !(std::io::stdin().is_terminal() && std::io::stderr().is_terminal()) {
+        return Ok(false);
+    }
+    let mut stderr = std::io::stderr();
+    write!(
+        stderr,
+        "No SysML v2 or KerML project found. Create one in `{path}`? [y/N] "
+    )?;
+    stderr.flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes" | "Yes"))
+}
+
+/// Ask to choose one of `items`, returning its index, or `None` if the
+/// answer is empty. Fails when not running in a terminal
+pub fn prompt_select<S: AsRef<str>>(prompt: &str, items: &[S]) -> Result<Option<usize>> {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    if !(std::io::stdin().is_terminal() && std::io::stderr().is_terminal()) {
+        bail!("cannot prompt for a choice when not running in a terminal");
+    }
+    let mut stderr = std::io::stderr();
+    for (i, item) in items.iter().enumerate() {
+        writeln!(stderr, "{:>4}) {}", i + 1, item.as_ref())?;
+    }
+    loop {
+        write!(stderr, "{prompt} [1-{}]: ", items.len())?;
+        stderr.flush()?;
+        let mut answer = String::new();
+        std::io::stdin().lock().read_line(&mut answer)?;
+        let answer = answer.trim();
+        if answer.is_empty() {
+            return Ok(None);
+        }
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=items.len()).contains(&n) => return Ok(Some(n - 1)),
+            _ => writeln!(stderr, "Enter a number from 1 to {}", items.len())?,
+        }
+    }
+}
+
 /// Unpack `env`, or create an empty environment otherwise
+/// Projects are installed in the returned environment as set by
+/// `env.storage` in `config`, encrypted with the key given by [`get_env_key`]
 pub fn get_or_create_env(
     env: Option<LocalDirectoryEnvironment>,
     workspace: Option<&Workspace>,
     project: Option<&LocalSrcProject>,
     cwd: impl AsRef<Utf8Path>,
+    config: &Config,
 ) -> Result<LocalDirectoryEnvironment> {
-    if let Some(env) = env {
-        return Ok(env);
-    }
-    let base_path = match (workspace, project) {
-        (None, None) => cwd.as_ref(),
-        (None, Some(pr)) => pr.root_path(),
-        (Some(w), _) => w.root_path(),
+    let env = match env {
+        Some(env) => env,
+        None => {
+            let base_path = match (workspace, project) {
+                (None, None) => cwd.as_ref(),
+                (None, Some(pr)) => pr.root_path(),
+                (Some(w), _) => w.root_path(),
+            };
+            command_env(base_path.join(DEFAULT_ENV_NAME))?
+        }
     };
-    command_env(base_path.join(DEFAULT_ENV_NAME))
+    let storage = config.env.storage.unwrap_or_default();
+    let env = env.with_storage(storage);
+    match get_env_key(config)? {
+        Some(key) => Ok(env.with_key(key)),
+        None if storage == EnvStorage::Encrypted => Err(EncryptionError::MissingKey.into()),
+        None => Ok(env),
+    }
 }
 
-fn get_log_level(verbose: bool, quiet: bool) -> log::LevelFilter {
+fn get_log_level(verbose: u8, quiet: bool) -> log::LevelFilter {
     match (verbose, quiet) {
-        (true, true) => unreachable!(),
-        (true, false) => log::LevelFilter::Debug,
-        (false, true) => log::LevelFilter::Error,
-        (false, false) => log::LevelFilter::Info,
+        (1.., true) => unreachable!(),
+        (0, true) => log::LevelFilter::Error,
+        (0, false) => log::LevelFilter::Info,
+        (1, false) => log::LevelFilter::Debug,
+        (2.., false) => log::LevelFilter::Trace,
     }
 }
 
@@ -843,5 +400,159 @@ pub fn get_overrides<P: AsRef<Utf8Path>, Policy: HTTPAuthentication>(
             overrides.push((Iri::parse(identifier.as_str())?.into(), projects));
         }
     }
+
+    // Projects in source roots come after configured overrides and never
+    // replace them
+    let configured = overrides.len();
+    for root in &config.source_roots {
+        let native_root = project_root.as_ref().join(root);
+        // Lockfiles only allow paths relative to the project
+        let nominal_root = if Utf8Path::new(root).is_absolute() {
+            relativize_path(
+                wrapfs::canonicalize(&native_root)?,
+                wrapfs::canonicalize(&project_root)?,
+            )?
+        } else {
+            Utf8UnixPathBuf::from(root.replace('\\', "/"))
+        };
+        for found in discover_source_root(&native_root)? {
+            let native_path = native_root.join(found.path.as_str());
+            let nominal_path = if found.path == "." {
+                nominal_root.clone()
+            } else if nominal_root == "." {
+                found.path
+            } else {
+                nominal_root.join(&found.path)
+            };
+            let project = ProjectReference::new(match found.kind {
+                SourceRootProjectKind::Src => AnyProject::LocalSrc(LocalSrcProject {
+                    nominal_path: Some(nominal_path),
+                    project_path: native_path,
+                    expected_checksum: None,
+                }),
+                SourceRootProjectKind::Kpar => AnyProject::LocalKpar(LocalKParProject::new(
+                    native_path,
+                    KparInnerPath::Guess,
+                    Some(nominal_path),
+                    None,
+                )),
+            });
+            for iri in found.iris {
+                if overrides[..configured].iter().any(|(i, _)| i == &iri) {
+                    log::debug!("`{iri}` from source root `{root}` is overridden in config");
+                    continue;
+                }
+                match overrides[configured..].iter_mut().find(|(i, _)| i == &iri) {
+                    Some((_, projects)) => projects.push(project.clone()),
+                    None => overrides.push((iri, vec![project.clone()])),
+                }
+            }
+        }
+    }
     Ok(overrides)
 }
+
+/// Add the directories listed in [`env_vars::SYSAND_EXTRA_SOURCE_ROOTS`] to
+/// the source roots of `config`, and those in [`env_vars::SYSAND_SYSTEM_ENVS`]
+/// to its system environments, ahead of configured ones. Relative paths
+/// are relative to `cwd`
+/// Markers from `--marker`, then `[lock] markers`, with `metamodel`
+/// defaulting to the one of the current project
+fn get_markers(
+    cli_markers: &[(String, String)],
+    config: &Config,
+    ctx: &ProjectContext,
+) -> Result<Markers> {
+    let mut markers: Markers = cli_markers.iter().cloned().collect();
+    for (attribute, value) in &config.lock.markers {
+        if !MARKER_ATTRIBUTES.contains(&attribute.as_str()) {
+            bail!(
+                "invalid `[lock] markers` in config: {}",
+                ConditionError::UnknownAttribute(attribute.to_owned())
+            );
+        }
+        markers
+            .entry(attribute.to_owned())
+            .or_insert_with(|| value.to_owned());
+    }
+    if !markers.contains_key("metamodel")
+        && let Some(metamodel) = ctx
+            .current_project
+            .as_ref()
+            .and_then(|project| project.get_meta().ok().flatten())
+            .and_then(|meta| meta.metamodel)
+    {
+        markers.insert("metamodel".to_owned(), metamodel);
+    }
+    Ok(markers)
+}
+
+fn add_env_source_roots(config: &mut Config, cwd: &Utf8Path) -> Result<()> {
+    config.source_roots.splice(
+        0..0,
+        env_var_paths(env_vars::SYSAND_EXTRA_SOURCE_ROOTS, cwd)?,
+    );
+    config
+        .system_envs
+        .splice(0..0, env_var_paths(env_vars::SYSAND_SYSTEM_ENVS, cwd)?);
+    Ok(())
+}
+
+/// Paths listed in the environment variable `var`, separated like `PATH`
+fn env_var_paths(var: &str, cwd: &Utf8Path) -> Result<Vec<String>> {
+    let Some(paths) = std::env::var_os(var) else {
+        return Ok(vec![]);
+    };
+    let mut env_paths = vec![];
+    for path in std::env::split_paths(&paths) {
+        if path.as_os_str().is_empty() {
+            continue;
+        }
+        let path = Utf8PathBuf::from_path_buf(path).map_err(|path| {
+            anyhow!(
+                "{var} contains a path that is not valid UTF-8: `{}`",
+                path.display()
+            )
+        })?;
+        env_paths.push(cwd.join(path).into_string());
+    }
+    Ok(env_paths)
+}
+
+/// Read the system environments in `config`. They are only read from,
+/// whether or not they are marked read-only
+pub fn get_system_envs(
+    config: &Config,
+    project_root: impl AsRef<Utf8Path>,
+) -> Result<Vec<LocalDirectoryEnvironment>> {
+    let key = get_env_key(config)?;
+    config
+        .system_env_paths(project_root)
+        .into_iter()
+        .map(|path| {
+            let env = LocalDirectoryEnvironment::read(&path)
+                .map_err(|e| anyhow!("failed to read system environment `{path}`: {e}"))?;
+            Ok(match &key {
+                Some(key) => env.with_key(key.clone()),
+                None => env,
+            })
+        })
+        .collect()
+}
+
+/// Known standard libraries to treat as provided with `include`. In
+/// `auto` mode, those of the metamodel declared by `project` are processed
+pub fn ignored_std_libs<P: ProjectRead>(
+    include: IncludeStd,
+    project: Option<&P>,
+) -> HashMap<String, Vec<InMemoryProject>> {
+    let metamodel = match (include, project) {
+        (IncludeStd::Auto, Some(project)) => project
+            .get_meta()
+            .ok()
+            .flatten()
+            .and_then(|meta| meta.metamodel),
+        _ => None,
+    };
+    include.ignored_std_libs(metamodel.as_deref())
+}
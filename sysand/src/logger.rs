@@ -2,30 +2,51 @@
 // SPDX-FileCopyrightText: © 2025 Sysand contributors <opensource@sensmetry.com>
 
 use env_logger::{Builder, Target, fmt::Formatter};
-use log::{LevelFilter, Record, SetLoggerError};
-use std::io::{self, Write};
+use log::{
+    LevelFilter, Record, SetLoggerError,
+    kv::{self, Key, Value, VisitSource},
+};
+use std::{
+    io::{self, Write},
+    time::Instant,
+};
 
-use crate::style;
+use crate::{cli::ErrorFormat, style};
 
-pub fn init(level: LevelFilter) -> Result<(), SetLoggerError> {
-    Builder::new()
+pub fn init(level: LevelFilter, log_format: ErrorFormat) -> Result<(), SetLoggerError> {
+    let mut builder = Builder::new();
+    builder
         .filter_module("pubgrub", LevelFilter::Warn)
-        .filter_level(level)
-        .format(format)
+        .filter_level(level);
+    match log_format {
+        ErrorFormat::Human => builder.format(format),
+        ErrorFormat::Json => builder.format(format_json),
+    };
+    builder
         .target(Target::Stderr)
         .parse_default_env()
         .try_init()
 }
 
+/// Stable code of a diagnostic, given in the `code` field of its record
+fn record_code(record: &Record<'_>) -> Option<String> {
+    record
+        .key_values()
+        .get(Key::from_str("code"))
+        .map(|code| code.to_string())
+}
+
 fn format(buf: &mut Formatter, record: &Record<'_>) -> Result<(), io::Error> {
+    let code = record_code(record).map(|code| format!("[{code}]"));
+    let code = code.as_deref().unwrap_or_default();
     match record.level() {
         log::Level::Error => {
             let style = style::ERROR;
-            writeln!(buf, "{style}error{style:#}: {}", record.args())
+            writeln!(buf, "{style}error{code}{style:#}: {}", record.args())
         }
         log::Level::Warn => {
             let style = style::WARN;
-            writeln!(buf, "{style}warning{style:#}: {}", record.args())
+            writeln!(buf, "{style}warning{code}{style:#}: {}", record.args())
         }
         log::Level::Debug => {
             let style = style::NOTE;
@@ -41,6 +62,77 @@ fn format(buf: &mut Formatter, record: &Record<'_>) -> Result<(), io::Error> {
     }
 }
 
+/// Format a log record as a single-line JSON object
+fn format_json(buf: &mut Formatter, record: &Record<'_>) -> Result<(), io::Error> {
+    let level = match record.level() {
+        log::Level::Error => "error",
+        log::Level::Warn => "warning",
+        log::Level::Info => "info",
+        log::Level::Debug => "debug",
+        log::Level::Trace => "trace",
+    };
+    let mut message = serde_json::json!({
+        "level": level,
+        "message": record.args().to_string(),
+    });
+    let mut fields = FieldsVisitor(serde_json::Map::new());
+    // Visiting only fails if the visitor does
+    let _ = record.key_values().visit(&mut fields);
+    // Like the code of an error diagnostic
+    if let Some(code) = fields.0.remove("code") {
+        message["code"] = code;
+    }
+    if !fields.0.is_empty() {
+        message["fields"] = fields.0.into();
+    }
+    writeln!(buf, "{message}")
+}
+
+/// Collects the structured fields of a log record
+struct FieldsVisitor(serde_json::Map<String, serde_json::Value>);
+
+impl<'kvs> VisitSource<'kvs> for FieldsVisitor {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+        let value = if let Some(n) = value.to_u64() {
+            n.into()
+        } else if let Some(n) = value.to_i64() {
+            n.into()
+        } else if let Some(b) = value.to_bool() {
+            b.into()
+        } else {
+            value.to_string().into()
+        };
+        self.0.insert(key.to_string(), value);
+        Ok(())
+    }
+}
+
+/// Logs the start of a step, e.g. resolution, at trace level and, when
+/// dropped, its duration at debug level. JSON logs carry the name and the
+/// duration in the `step` and `elapsed_ms` fields
+pub struct Span {
+    step: &'static str,
+    start: Instant,
+}
+
+impl Span {
+    pub fn enter(step: &'static str) -> Self {
+        log::trace!(step; "{step} started");
+        Self {
+            step,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let step = self.step;
+        let elapsed_ms = self.start.elapsed().as_millis() as u64;
+        log::debug!(step, elapsed_ms; "{step} took {elapsed_ms} ms");
+    }
+}
+
 const SP: char = ' ';
 
 /// Print a warning that standard library package `iri` is ignored
@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Progress line drawn on stderr while building KPARs and downloading them
+
+use std::io::{IsTerminal, Write};
+
+use sysand_core::{
+    build::format_size,
+    progress::{self, ProgressEvent, ProgressPhase},
+};
+
+use crate::style::HEADER;
+
+/// Run `f`, drawing its progress events on stderr if it is a terminal and
+/// `enabled` is set
+pub fn with_stderr_progress<R>(enabled: bool, f: impl FnOnce() -> R) -> R {
+    if !(enabled && std::io::stderr().is_terminal()) {
+        return f();
+    }
+    let result = progress::with_listener(draw, f);
+    clear(&mut std::io::stderr().lock());
+    result
+}
+
+/// Redraw the line in place with `\r`, which `log` does not allow as it
+/// always appends a newline. The line is erased once the operation is done
+fn draw(event: &ProgressEvent) {
+    let label = match event.phase {
+        ProgressPhase::Build => "Building",
+        ProgressPhase::Download => "Downloading",
+        // Installs are logged one by one already
+        ProgressPhase::Install => return,
+    };
+    let count = match (event.phase, event.total) {
+        (ProgressPhase::Download, Some(total)) => {
+            format!("{}/{}", format_size(event.current), format_size(total))
+        }
+        (ProgressPhase::Download, None) => format_size(event.current),
+        (_, Some(total)) => format!("{}/{total}", event.current),
+        (_, None) => event.current.to_string(),
+    };
+
+    let mut stderr = anstream::stderr().lock();
+    clear(&mut stderr);
+    let header = HEADER;
+    let _ = write!(
+        stderr,
+        "{header}{label:>12}{header:#} ({count}) {}",
+        event.message
+    );
+    if event.total == Some(event.current) {
+        clear(&mut stderr);
+    }
+    let _ = stderr.flush();
+}
+
+fn clear(stderr: &mut impl Write) {
+    // Erase to the end of the line
+    let _ = write!(stderr, "\r\x1b[K");
+}
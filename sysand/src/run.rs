@@ -0,0 +1,1261 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Running of each command in a [`CommandContext`]
+
+use std::{collections::HashSet, fs, io::ErrorKind, str::FromStr};
+
+use anyhow::{Result, bail};
+use camino::Utf8PathBuf;
+use sysand_core::{
+    build::{KparCompression, KparCompressionMethod, KparManifest},
+    commands::{hooks::HookStage, sync::SyncFilter},
+    env::DEFAULT_ENV_NAME,
+    index::RemoveTarget,
+    init::InitError,
+    lock::Lock,
+    model::HashAlg,
+    project::utils::wrapfs,
+    solve::pubgrub::VersionStrategy,
+};
+use url::Url;
+
+use crate::{
+    CliError, DEFAULT_INDEX_URL,
+    cli::{self, Command, InfoCommand},
+    commands::{
+        add::command_add,
+        build::{command_build_for_project, command_build_for_workspace},
+        config::{
+            command_config_check, command_config_get, command_config_list, command_config_set,
+            command_config_unset,
+        },
+        env::{
+            InstallRequest, command_env, command_env_doctor, command_env_export, command_env_gc,
+            command_env_import, command_env_install, command_env_install_locked,
+            command_env_install_path, command_env_list, command_env_read_only,
+            command_env_uninstall, install_requests,
+        },
+        exclude::command_exclude,
+        files::command_files,
+        hooks::{command_hooks, hook_projects},
+        import::command_import,
+        include::command_include,
+        index::{command_index_add, command_index_init, command_index_remove, command_index_yank},
+        info::{command_info_current_project, command_info_path, command_info_verb_path},
+        init::{command_init, command_init_workspace},
+        lock::{
+            check_lockfile, command_lock, command_lock_merge, command_lock_workspace, has_lockfile,
+            lock_project, lock_workspace, lockfile_path,
+        },
+        mv::command_mv,
+        normalize::command_normalize,
+        print_root::{command_print_all_roots, command_print_root},
+        publish::command_publish,
+        remove::command_remove,
+        sources::{command_sources_env, command_sources_project},
+        sync::{command_sync, command_sync_apply, command_sync_plan},
+        verify::{command_verify, command_verify_env},
+    },
+    context::CommandContext,
+    error::CodedResultExt,
+    get_cache_dir, get_system_envs, ignored_std_libs, iri_or_path_to_iri, project_fallback,
+    shared_lock_workspace,
+};
+
+/// Run `command` in `cx`
+pub fn run_command(command: Command, mut cx: CommandContext) -> Result<()> {
+    match command {
+        Command::Init {
+            path,
+            name,
+            publisher,
+            version,
+            no_semver,
+            license,
+            no_spdx,
+            iri,
+            workspace: false,
+            ..
+        } => command_init(
+            name, publisher, version, no_semver, license, no_spdx, iri, path,
+        ),
+        Command::Init {
+            path,
+            publisher,
+            version,
+            no_semver,
+            license,
+            no_spdx,
+            workspace: true,
+            member,
+            ..
+        } => command_init_workspace(
+            path, member, publisher, version, no_semver, license, no_spdx,
+        ),
+        Command::New { .. } => bail!("use `init` instead of `new`"),
+        Command::Env { command } => run_env(command, cx),
+        Command::Index { command } => run_index(command, cx),
+        Command::Lock {
+            here,
+            merge: None,
+            minimal_versions,
+            locked,
+            resolution_opts,
+        } => {
+            if minimal_versions {
+                cx.project.version_strategy = VersionStrategy::Minimal;
+            }
+            run_lock(here, locked, resolution_opts, cx)
+        }
+        Command::Lock {
+            merge: Some(files),
+            minimal_versions,
+            resolution_opts,
+            ..
+        } => {
+            if minimal_versions {
+                cx.project.version_strategy = VersionStrategy::Minimal;
+            }
+            run_lock_merge(files, resolution_opts, cx)
+        }
+        Command::Owner { command, index } => {
+            let index = match index {
+                Some(index) => index,
+                None => cx
+                    .config
+                    .index_urls(vec![], vec![DEFAULT_INDEX_URL.to_string()], vec![])?
+                    .pop()
+                    .expect("default index URL is always present"),
+            };
+            crate::commands::owner::command_owner(
+                command,
+                index,
+                cx.auth_policy,
+                cx.client,
+                cx.runtime,
+                &mut cx.out,
+            )
+        }
+        Command::Cache { command } => match command {
+            cli::CacheCommand::Prune { git, http } => {
+                crate::commands::cache::command_cache_prune(get_cache_dir(false), git, http)
+            }
+        },
+        Command::Config { command } => run_config(command, cx),
+        Command::Sync {
+            here,
+            plan,
+            apply,
+            only,
+            skip,
+            resolution_opts,
+        } => run_sync(
+            here,
+            plan,
+            apply,
+            SyncFilter {
+                only,
+                skip,
+                no_deps: false,
+            },
+            resolution_opts,
+            cx,
+        ),
+        Command::Export { format, output } => crate::commands::export::command_export(
+            cx.project_root().unwrap_or(cx.project.current_directory),
+            format.into(),
+            output,
+            &mut cx.out,
+        ),
+        Command::Bundle {
+            command: Some(cli::BundleCommand::Install { bundle }),
+            ..
+        } => {
+            let env = cx.take_or_create_env()?;
+            crate::commands::bundle::command_bundle_install(bundle, env)
+        }
+        Command::Bundle {
+            command: None,
+            output,
+            format,
+        } => crate::commands::bundle::command_bundle(
+            cx.lock_root(),
+            cx.project.env,
+            output,
+            format.into(),
+        ),
+        Command::Schema { kind, output } => {
+            crate::commands::schema::command_schema(kind.into(), output, &mut cx.out)
+        }
+        Command::PrintRoot { all: false } => command_print_root(
+            cx.project.current_directory,
+            cx.config.discovery.max_depth,
+            &mut cx.out,
+        ),
+        Command::PrintRoot { all: true } => {
+            command_print_all_roots(cx.project.current_directory, &mut cx.out)
+        }
+        Command::List => crate::commands::list::command_list(
+            cx.lock_root(),
+            cx.project,
+            &cx.config.reasons,
+            &mut cx.out,
+        ),
+        Command::Why { iri } => {
+            crate::commands::why::command_why(&iri, cx.lock_root(), &cx.config.reasons, &mut cx.out)
+        }
+        Command::Graph { format, cluster } => run_graph(format, cluster, cx),
+        Command::UpgradeCheck {
+            iri,
+            new_version,
+            resolution_opts,
+        } => run_upgrade_check(iri, new_version, resolution_opts, cx),
+        Command::Audit { resolution_opts } => {
+            let cli::ResolutionOptions {
+                index,
+                default_index,
+                no_index,
+                include_std: _,
+                no_include_std: _,
+            } = resolution_opts;
+            let index_urls = cx
+                .index_urls(index, default_index, no_index)?
+                .unwrap_or_default();
+            crate::commands::audit::command_audit(
+                cx.lock_root(),
+                index_urls,
+                cx.client,
+                cx.runtime,
+                cx.auth_policy,
+                &mut cx.out,
+            )
+        }
+        Command::Info {
+            path,
+            project,
+            iri,
+            auto_location,
+            no_normalise,
+            all_installed,
+            all,
+            resolution_opts,
+            subcommand,
+        } => run_info(
+            path,
+            project,
+            iri,
+            auto_location,
+            no_normalise,
+            all_installed,
+            all,
+            resolution_opts,
+            subcommand,
+            cx,
+        ),
+        Command::Add {
+            locator,
+            version_constraint,
+            variant,
+            reason,
+            when,
+            hash,
+            no_lock,
+            no_sync,
+            resolution_opts,
+            source_opts,
+        } => {
+            let (iri, version_constraint) = match locator.iri {
+                Some(cli::AddLocator::Search(query)) => crate::commands::add::select_index_project(
+                    &query,
+                    version_constraint,
+                    resolution_opts.clone(),
+                    &cx.config,
+                    cx.client.clone(),
+                    cx.runtime.clone(),
+                    cx.auth_policy.clone(),
+                )?,
+                Some(cli::AddLocator::Iri(iri)) => (iri, version_constraint),
+                None => (iri_or_path_to_iri(None, locator.path)?, version_constraint),
+            };
+            command_add(
+                iri,
+                version_constraint,
+                variant,
+                reason,
+                when,
+                hash,
+                no_lock,
+                no_sync,
+                resolution_opts,
+                source_opts,
+                cx.config,
+                cx.config_file,
+                cx.no_config,
+                cx.project,
+                cx.client,
+                cx.git_cache,
+                cx.runtime,
+                cx.auth_policy,
+            )
+        }
+        Command::Remove { locator } => {
+            let iri = iri_or_path_to_iri(locator.iri, locator.path)?;
+            command_remove(iri, cx.project, cx.config_file, cx.no_config)
+        }
+        Command::Import { format, path } => command_import(format.into(), path, cx.project),
+        Command::MigrateIri {
+            old,
+            new,
+            dry_run,
+            redirect_stub,
+            redirect_version,
+        } => crate::commands::migrate_iri::command_migrate_iri(
+            old,
+            new,
+            dry_run,
+            redirect_stub.zip(redirect_version),
+            cx.project,
+        ),
+        Command::Migrate { dry_run } => {
+            crate::commands::migrate::command_migrate(dry_run, cx.project)
+        }
+        Command::Include {
+            paths,
+            compute_checksum,
+            checksum_algorithm,
+            no_index_symbols,
+            add_std_usages,
+            jobs,
+        } => {
+            let checksum_algorithm = checksum_algorithm
+                .map(HashAlg::from)
+                .or(compute_checksum.then_some(HashAlg::Sha256));
+            command_include(
+                paths,
+                checksum_algorithm,
+                !no_index_symbols,
+                add_std_usages,
+                jobs.unwrap_or_default(),
+                cx.project,
+            )
+        }
+        Command::Exclude { paths } => command_exclude(paths, cx.project),
+        Command::Mv { old, new } => command_mv(old, new, cx.project),
+        Command::Build {
+            path,
+            compression,
+            compression_level,
+            update_meta,
+            allow_path_usage,
+            keep_index,
+            no_post_build,
+            allow_dirty,
+            check_reproducible,
+            jobs,
+        } => run_build(
+            path,
+            compression,
+            compression_level,
+            update_meta,
+            allow_path_usage,
+            keep_index,
+            no_post_build,
+            allow_dirty,
+            check_reproducible,
+            jobs,
+            cx,
+        ),
+        Command::Verify { jobs, env: false } => {
+            command_verify(jobs.unwrap_or_default(), cx.project)
+        }
+        Command::Verify { env: true, .. } => command_verify_env(cx.project.env),
+        Command::Normalize { check } => command_normalize(check, cx.project),
+        Command::Files { allow_dirty } => command_files(
+            &KparManifest::new(&cx.config.build, allow_dirty),
+            cx.project,
+            &mut cx.out,
+        ),
+        Command::Publish {
+            path,
+            index,
+            trusted_publishing,
+            dry_run,
+            deprecate,
+            replaced_by,
+        } => command_publish(
+            path,
+            index,
+            trusted_publishing,
+            dry_run,
+            deprecate.then(|| sysand_core::env::index::Deprecation {
+                replaced_by: replaced_by.map(|iri| iri.into_string()),
+            }),
+            cx.config.iri.as_deref(),
+            &cx.config.publish,
+            &cx.project,
+            cx.auth_policy,
+            cx.client,
+            cx.runtime,
+        ),
+        Command::Sources { sources_opts } => {
+            let provided_iris = ignored_std_libs(
+                sources_opts.include_std_mode(&cx.config),
+                cx.project.current_project.as_ref(),
+            );
+            if sources_opts.ignores_std_by_default(&cx.config) {
+                crate::logger::warn_std_omit();
+            }
+
+            let system_envs = match cx.project_root() {
+                Some(root) => get_system_envs(&cx.config, root)?,
+                None => vec![],
+            };
+            command_sources_project(
+                !sources_opts.no_deps,
+                cx.project,
+                system_envs,
+                &provided_iris,
+                sources_opts.materialize,
+                &mut cx.out,
+            )
+        }
+        Command::Clone {
+            locator,
+            version,
+            target,
+            resolution_opts,
+            no_deps,
+            workspace,
+        } => crate::commands::clone::command_clone(
+            locator,
+            version,
+            target,
+            cx.project,
+            no_deps,
+            workspace,
+            resolution_opts,
+            &cx.config,
+            cx.client,
+            cx.git_cache,
+            cx.runtime,
+            cx.auth_policy,
+        ),
+    }
+}
+
+fn run_env(command: Option<cli::EnvCommand>, mut cx: CommandContext) -> Result<()> {
+    let project_root = cx.project_root();
+    let lock_root = cx.lock_root();
+    let CommandContext {
+        config,
+        project: ctx,
+        client,
+        git_cache,
+        runtime,
+        auth_policy,
+        ..
+    } = cx;
+    match command {
+        None => {
+            let mut env_dir = project_root.unwrap_or(ctx.current_directory);
+            env_dir.push(DEFAULT_ENV_NAME);
+            command_env(env_dir)?;
+
+            Ok(())
+        }
+        Some(cli::EnvCommand::Install {
+            iris,
+            from_lock: true,
+            install_opts,
+            resolution_opts,
+            ..
+        }) => command_env_install_locked(
+            iris,
+            install_opts,
+            resolution_opts,
+            &config,
+            lock_root,
+            client,
+            git_cache,
+            runtime,
+            auth_policy,
+            ctx,
+        ),
+        Some(cli::EnvCommand::Install {
+            iris,
+            from_file,
+            path,
+            project,
+            from_lock: false,
+            install_opts,
+            resolution_opts,
+        }) => {
+            let mut requests = install_requests(iris, from_file.as_deref())?;
+            if let Some(path) = path {
+                let (Some(InstallRequest { iri, version }), None) =
+                    (requests.pop(), requests.pop())
+                else {
+                    bail!("only a single project can be installed with `--path`");
+                };
+                command_env_install_path(
+                    iri,
+                    version,
+                    path,
+                    project,
+                    install_opts,
+                    resolution_opts,
+                    &config,
+                    project_root,
+                    client,
+                    git_cache,
+                    runtime,
+                    auth_policy,
+                    ctx,
+                )
+            } else {
+                command_env_install(
+                    requests,
+                    install_opts,
+                    resolution_opts,
+                    &config,
+                    project_root,
+                    client,
+                    git_cache,
+                    runtime,
+                    auth_policy,
+                    ctx,
+                )
+            }
+        }
+        Some(cli::EnvCommand::Uninstall {
+            iri,
+            version,
+            with_orphans,
+        }) => match ctx.env {
+            Some(local_environment) => command_env_uninstall(
+                iri,
+                version,
+                with_orphans.then_some(lock_root.as_path()),
+                local_environment,
+            ),
+            None => {
+                log::warn!("no environment to uninstall from");
+                Ok(())
+            }
+        },
+        Some(cli::EnvCommand::List) => command_env_list(ctx.env, cx.verbose > 0, &mut cx.out),
+        Some(cli::EnvCommand::Doctor { fix }) => command_env_doctor(ctx.env, fix),
+        Some(cli::EnvCommand::Gc { dry_run }) => {
+            // The environment of a workspace is shared by its members, so keep
+            // what any of them locks
+            let lock_roots = match &ctx.current_workspace {
+                Some(workspace) if shared_lock_workspace(&config, &ctx).is_none() => workspace
+                    .projects()
+                    .iter()
+                    .map(|project| workspace.root_path().join(&project.path))
+                    .collect(),
+                _ => vec![lock_root],
+            };
+            command_env_gc(ctx.env, &lock_roots, dry_run)
+        }
+        Some(cli::EnvCommand::ReadOnly { off }) => command_env_read_only(ctx.env, !off),
+        Some(cli::EnvCommand::Export { resolution_opts }) => command_env_export(
+            resolution_opts,
+            &config,
+            project_root,
+            client,
+            git_cache,
+            runtime,
+            auth_policy,
+            &ctx,
+            &mut cx.out,
+        ),
+        Some(cli::EnvCommand::Import { path }) => command_env_import(
+            &path,
+            &config,
+            project_root,
+            client,
+            git_cache,
+            runtime,
+            auth_policy,
+            ctx,
+        ),
+        Some(cli::EnvCommand::Sources {
+            iri,
+            version,
+            sources_opts,
+        }) => command_sources_env(
+            iri,
+            version,
+            !sources_opts.no_deps,
+            ctx.env,
+            sources_opts.include_std_mode(&config),
+            sources_opts.ignores_std_by_default(&config),
+            sources_opts.materialize,
+            &mut cx.out,
+        ),
+    }
+}
+
+fn run_index(command: cli::IndexCommand, cx: CommandContext) -> Result<()> {
+    let root = |index_root: Option<Utf8PathBuf>| {
+        index_root.unwrap_or(cx.project.current_directory.clone())
+    };
+    match command {
+        cli::IndexCommand::Init { index_root } => command_index_init(root(index_root)),
+        cli::IndexCommand::Add {
+            iri,
+            kpar_path,
+            index_root,
+        } => command_index_add(iri, kpar_path, root(index_root)),
+        cli::IndexCommand::Yank {
+            iri,
+            version,
+            index_root,
+        } => command_index_yank(iri, version, root(index_root)),
+        cli::IndexCommand::Remove {
+            iri,
+            target,
+            index_root,
+        } => {
+            let target = match (target.version, target.project) {
+                (Some(version), false) => RemoveTarget::Version(version),
+                (None, true) => RemoveTarget::Project,
+                _ => unreachable!(),
+            };
+            command_index_remove(iri, target, root(index_root))
+        }
+    }
+}
+
+fn run_lock(
+    here: bool,
+    locked: bool,
+    resolution_opts: cli::ResolutionOptions,
+    cx: CommandContext,
+) -> Result<()> {
+    let project_root = cx.project_root();
+    let CommandContext {
+        config,
+        project: mut ctx,
+        client,
+        git_cache,
+        runtime,
+        auth_policy,
+        ..
+    } = cx;
+    if let Some(workspace) = shared_lock_workspace(&config, &ctx) {
+        if locked {
+            let lock = lock_workspace(
+                workspace,
+                resolution_opts,
+                &config,
+                client,
+                git_cache,
+                runtime,
+                auth_policy,
+                &ctx,
+            )?;
+            return check_lockfile(workspace.root_path(), &lock);
+        }
+        return command_lock_workspace(
+            workspace,
+            resolution_opts,
+            &config,
+            client,
+            git_cache,
+            runtime,
+            auth_policy,
+            &ctx,
+        )
+        .map(|_| ());
+    }
+    let project_root = match project_root {
+        Some(project_root) => project_root,
+        None => project_fallback(&mut ctx, here, config.discovery.when_missing)?,
+    };
+    if locked {
+        let lock = lock_project(
+            ".",
+            resolution_opts,
+            &config,
+            project_root,
+            client,
+            git_cache,
+            runtime,
+            auth_policy,
+            &ctx,
+        )?;
+        return check_lockfile(".", &lock);
+    }
+    command_lock(
+        ".",
+        resolution_opts,
+        &config,
+        project_root,
+        client,
+        git_cache,
+        runtime,
+        auth_policy,
+        &ctx,
+    )
+    .map(|_| ())
+}
+
+fn run_lock_merge(
+    files: Vec<Utf8PathBuf>,
+    resolution_opts: cli::ResolutionOptions,
+    cx: CommandContext,
+) -> Result<()> {
+    let [base, ours, theirs] =
+        <[Utf8PathBuf; 3]>::try_from(files).expect("`--merge` takes three files");
+    let project_root = cx.project_root();
+    let CommandContext {
+        config,
+        project: ctx,
+        client,
+        git_cache,
+        runtime,
+        auth_policy,
+        ..
+    } = cx;
+    command_lock_merge(&base, &ours, &theirs, || {
+        if let Some(workspace) = shared_lock_workspace(&config, &ctx) {
+            return lock_workspace(
+                workspace,
+                resolution_opts,
+                &config,
+                client,
+                git_cache,
+                runtime,
+                auth_policy,
+                &ctx,
+            );
+        }
+        let Some(project_root) = project_root else {
+            bail!("conflicting lockfile entries can only be resolved in a project");
+        };
+        lock_project(
+            ".",
+            resolution_opts,
+            &config,
+            project_root,
+            client,
+            git_cache,
+            runtime,
+            auth_policy,
+            &ctx,
+        )
+    })
+}
+
+fn run_sync(
+    here: bool,
+    plan: bool,
+    apply: Option<Utf8PathBuf>,
+    filter: SyncFilter,
+    resolution_opts: cli::ResolutionOptions,
+    mut cx: CommandContext,
+) -> Result<()> {
+    let project_root = cx.project_root();
+    let CommandContext {
+        config,
+        project: mut ctx,
+        client,
+        git_cache,
+        runtime,
+        auth_policy,
+        ..
+    } = cx;
+    // TODO: only print this if we actually skip install of any std libs
+    let provided_iris = ignored_std_libs(
+        resolution_opts.include_std_mode(&config),
+        ctx.current_project.as_ref(),
+    );
+    if resolution_opts.ignores_std_by_default(&config) {
+        crate::logger::warn_std_deps();
+    }
+
+    let shared_root = shared_lock_workspace(&config, &ctx).map(|w| w.root_path().to_owned());
+    let project_root = match (shared_root, project_root) {
+        (Some(workspace_root), _) => workspace_root,
+        (None, Some(project_root)) => project_root,
+        // A bare lockfile can still be synced without a project
+        (None, None)
+            if ctx.current_workspace.is_none() && !has_lockfile(&ctx.current_directory) =>
+        {
+            project_fallback(&mut ctx, here, config.discovery.when_missing)?
+        }
+        (None, None) => ctx.current_directory.clone(),
+    };
+    let lockfile = lockfile_path(&project_root)?;
+    let lock = match fs::read_to_string(&lockfile) {
+        Ok(l) => match Lock::from_str(&l) {
+            Ok(l) => l,
+            // Include file path in errors
+            Err(e) => bail!("invalid lockfile `{lockfile}`:\n{e}"),
+        },
+        Err(e) => {
+            if e.kind() == ErrorKind::NotFound && (plan || apply.is_some()) {
+                bail!("lockfile `{lockfile}` not found; run `sysand lock` first")
+            } else if e.kind() == ErrorKind::NotFound
+                && let Some(workspace) = shared_lock_workspace(&config, &ctx)
+            {
+                command_lock_workspace(
+                    workspace,
+                    resolution_opts,
+                    &config,
+                    client.clone(),
+                    git_cache.clone(),
+                    runtime.clone(),
+                    auth_policy.clone(),
+                    &ctx,
+                )?
+            } else if e.kind() == ErrorKind::NotFound {
+                command_lock(
+                    ".",
+                    resolution_opts,
+                    &config,
+                    &project_root,
+                    client.clone(),
+                    git_cache.clone(),
+                    runtime.clone(),
+                    auth_policy.clone(),
+                    &ctx,
+                )?
+            } else {
+                bail!("failed to read lockfile `{lockfile}`: {e}")
+            }
+        }
+    };
+    if plan {
+        return command_sync_plan(
+            &lock,
+            &project_root,
+            ctx.env,
+            &config,
+            &provided_iris,
+            ctx.current_workspace.as_ref(),
+            &filter,
+            &mut cx.out,
+        );
+    }
+    let projects = hook_projects(&ctx);
+    let mut local_environment = crate::get_or_create_env(
+        ctx.env,
+        ctx.current_workspace.as_ref(),
+        ctx.current_project.as_ref(),
+        &ctx.current_directory,
+        &config,
+    )?;
+    if let Some(plan_path) = apply {
+        return command_sync_apply(
+            &plan_path,
+            &lock,
+            project_root,
+            &mut local_environment,
+            &config,
+            client,
+            git_cache,
+            runtime,
+            auth_policy,
+            ctx.current_workspace.as_ref(),
+        );
+    }
+    command_sync(
+        &lock,
+        &project_root,
+        &mut local_environment,
+        &config,
+        client,
+        git_cache,
+        &provided_iris,
+        runtime,
+        auth_policy,
+        ctx.current_workspace.as_ref(),
+        false,
+        &filter,
+    )?;
+    command_hooks(
+        HookStage::PostSync,
+        &config.hooks.post_sync,
+        &project_root,
+        &projects,
+        Some(&local_environment),
+    )
+}
+
+fn run_graph(format: cli::GraphFormatCli, cluster: bool, mut cx: CommandContext) -> Result<()> {
+    let lock_root = cx.lock_root();
+    let cluster_workspace = cx.project.current_workspace.as_ref().filter(|_| cluster);
+    if cluster && cluster_workspace.is_none() {
+        log::warn!("not in a workspace, `--cluster` has no effect");
+    }
+    crate::commands::graph::command_graph(
+        lock_root,
+        format.into(),
+        cluster_workspace,
+        cx.project.env.as_ref(),
+        &mut cx.out,
+    )
+}
+
+fn run_upgrade_check(
+    iri: fluent_uri::Iri<String>,
+    new_version: String,
+    resolution_opts: cli::ResolutionOptions,
+    mut cx: CommandContext,
+) -> Result<()> {
+    let cli::ResolutionOptions {
+        index,
+        default_index,
+        no_index,
+        include_std: _,
+        no_include_std: _,
+    } = resolution_opts;
+    let index_urls = cx.index_urls(index, default_index, no_index)?;
+    let lock_root = cx.lock_root();
+    let overrides = cx.overrides()?;
+    crate::commands::upgrade_check::command_upgrade_check(
+        iri,
+        new_version,
+        lock_root,
+        cx.client,
+        cx.git_cache,
+        index_urls,
+        &cx.config.indexes,
+        cx.config.shared_env_urls()?,
+        overrides,
+        cx.runtime,
+        cx.auth_policy,
+        cx.project,
+        &mut cx.out,
+    )
+}
+
+#[expect(clippy::too_many_arguments)]
+fn run_info(
+    path: Option<Utf8PathBuf>,
+    project: Option<String>,
+    iri: Option<fluent_uri::Iri<String>>,
+    auto_location: Option<String>,
+    no_normalise: bool,
+    all_installed: bool,
+    all: bool,
+    resolution_opts: cli::ResolutionOptions,
+    subcommand: Option<InfoCommand>,
+    mut cx: CommandContext,
+) -> Result<()> {
+    if (all_installed || all) && subcommand.is_some() {
+        bail!("`--all-installed` and `--all` cannot be used with a subcommand");
+    }
+    if all_installed {
+        return crate::commands::info::command_info_all_installed(cx.project.env, &mut cx.out);
+    }
+    let include_std = resolution_opts.include_std_mode(&cx.config);
+    let warn_std = resolution_opts.ignores_std_by_default(&cx.config);
+    let cli::ResolutionOptions {
+        index,
+        default_index,
+        no_index,
+        include_std: _,
+        no_include_std: _,
+    } = resolution_opts;
+    if all {
+        let index_urls = index
+            .iter()
+            .map(|url| Url::parse(url))
+            .collect::<Result<_, _>>()?;
+        return crate::commands::info::command_info_all_indexes(
+            index_urls,
+            cx.client,
+            cx.runtime,
+            cx.auth_policy,
+            &mut cx.out,
+        );
+    }
+    let index_urls = cx.index_urls(index, default_index, no_index)?;
+    let excluded_iris: HashSet<_> =
+        ignored_std_libs(include_std, cx.project.current_project.as_ref())
+            .into_keys()
+            .collect();
+    if warn_std {
+        // Only print std warning when command is to print all info
+        // or just usages.
+        // These are the only cases where stdlib usages affect output
+        // TODO: be more precise, this warning is annoying
+        match subcommand {
+            None
+            | Some(InfoCommand::Usage {
+                clear: None,
+                add: None,
+                set: None,
+                remove: None,
+                numbered: _,
+            }) => crate::logger::warn_std_deps(),
+            _ => (),
+        }
+    }
+
+    let overrides = cx.overrides()?;
+
+    enum Location {
+        WorkDir,
+        Iri(fluent_uri::Iri<String>),
+        Path(Utf8PathBuf),
+    }
+
+    let location = if let Some(auto_location) = auto_location {
+        debug_assert!(path.is_none());
+        debug_assert!(iri.is_none());
+
+        if let Ok(iri) = fluent_uri::Iri::parse(auto_location.clone()) {
+            Location::Iri(iri)
+        } else {
+            Location::Path(auto_location.into())
+        }
+    } else if let Some(path) = path {
+        debug_assert!(auto_location.is_none());
+        debug_assert!(iri.is_none());
+
+        Location::Path(path)
+    } else if let Some(iri) = iri {
+        debug_assert!(path.is_none());
+        debug_assert!(auto_location.is_none());
+
+        Location::Iri(iri)
+    } else {
+        Location::WorkDir
+    };
+
+    let out = &mut cx.out;
+    match (location, subcommand) {
+        (Location::WorkDir, subcommand) => {
+            let Some(current_project) = cx.project.current_project else {
+                bail!("run outside of an active project, did you mean to use `--path` or `--iri`?")
+            };
+            match subcommand {
+                Some(subcommand) => {
+                    match subcommand {
+                        cli::InfoCommand::Version {
+                            ref set, no_semver, ..
+                        } => {
+                            if !no_semver && let Some(v) = set {
+                                semver::Version::parse(v).map_err(|e| {
+                                    InitError::<std::convert::Infallible>::SemVerParse(
+                                        v.as_str().into(),
+                                        e,
+                                    )
+                                })?;
+                            }
+                        }
+                        cli::InfoCommand::License {
+                            ref set, no_spdx, ..
+                        } => {
+                            if !no_spdx && let Some(l) = set {
+                                spdx::Expression::parse(l).map_err(|e| {
+                                    InitError::<std::convert::Infallible>::SPDXLicenseParse(
+                                        l.as_str().into(),
+                                        e,
+                                    )
+                                })?;
+                            }
+                        }
+                        _ => (),
+                    }
+
+                    let numbered = subcommand.numbered();
+                    command_info_current_project(
+                        current_project,
+                        subcommand.as_verb(),
+                        numbered,
+                        out,
+                    )
+                }
+                None => command_info_path(current_project.root_path(), None, &excluded_iris, out),
+            }
+        }
+        (Location::Iri(iri), None) => crate::commands::info::command_info_uri(
+            iri,
+            !no_normalise,
+            cx.client,
+            cx.git_cache,
+            index_urls,
+            &cx.config.indexes,
+            &excluded_iris,
+            overrides,
+            cx.runtime,
+            cx.auth_policy,
+            cx.project,
+            out,
+        ),
+        (Location::Iri(iri), Some(subcommand)) => {
+            let numbered = subcommand.numbered();
+
+            crate::commands::info::command_info_verb_uri(
+                iri,
+                subcommand.as_verb(),
+                numbered,
+                cx.client,
+                cx.git_cache,
+                index_urls,
+                &cx.config.indexes,
+                overrides,
+                cx.runtime,
+                cx.auth_policy,
+                cx.project,
+                out,
+            )
+        }
+        (Location::Path(path), None) => {
+            command_info_path(&path, project.as_deref(), &excluded_iris, out)
+        }
+        (Location::Path(path), Some(subcommand)) => {
+            let numbered = subcommand.numbered();
+
+            command_info_verb_path(
+                &path,
+                project.as_deref(),
+                subcommand.as_verb(),
+                numbered,
+                out,
+            )
+        }
+    }
+}
+
+#[expect(clippy::too_many_arguments)]
+fn run_build(
+    path: Option<Utf8PathBuf>,
+    compression: Option<cli::KparCompressionMethodCli>,
+    compression_level: Option<i64>,
+    update_meta: bool,
+    allow_path_usage: bool,
+    keep_index: bool,
+    no_post_build: bool,
+    allow_dirty: bool,
+    check_reproducible: bool,
+    jobs: Option<sysand_core::parallel::Jobs>,
+    cx: CommandContext,
+) -> Result<()> {
+    let CommandContext {
+        config,
+        project: ctx,
+        ..
+    } = cx;
+    let jobs = jobs.unwrap_or_default();
+    let manifest = KparManifest::new(&config.build, allow_dirty);
+    let method = match compression {
+        Some(method) => method.into(),
+        None => config
+            .build
+            .compression_method()
+            .coded()?
+            .unwrap_or_default(),
+    };
+    let compression =
+        KparCompression::new(method, compression_level.or(config.build.compression_level))
+            .coded()?;
+    if !matches!(
+        method,
+        KparCompressionMethod::Stored | KparCompressionMethod::Deflated
+    ) {
+        log::warn!(
+            "{method:?} compression is not supported by all tools reading KPARs,\n\
+            use `stored` or `deflated` for KPARs that are shared"
+        );
+    }
+    let post_build = if no_post_build {
+        &[][..]
+    } else {
+        &config.post_build[..]
+    };
+    if update_meta {
+        log::warn!("`--update-meta` is now the default behavior and is no longer needed")
+    }
+    let hook_root = match (&ctx.current_project, &ctx.current_workspace) {
+        (Some(project), _) => Some(project.root_path().to_owned()),
+        (None, Some(workspace)) => Some(workspace.root_path().to_owned()),
+        (None, None) => None,
+    };
+    if let Some(root) = hook_root {
+        command_hooks(
+            HookStage::PreBuild,
+            &config.hooks.pre_build,
+            &root,
+            &hook_projects(&ctx),
+            ctx.env.as_ref(),
+        )?;
+    }
+    if let Some(current_project) = ctx.current_project {
+        // Even if we are in a workspace, the project takes precedence.
+        let path = if let Some(path) = path {
+            path
+        } else {
+            let path = sysand_core::build::default_kpar_path(
+                &current_project,
+                ctx.current_workspace.as_ref(),
+                &current_project.project_path,
+            )?;
+            if let Some(output_dir) = path.parent()
+                && !wrapfs::is_dir(output_dir)?
+            {
+                wrapfs::create_dir(output_dir)?;
+            }
+            path
+        };
+        command_build_for_project(
+            path,
+            compression,
+            current_project,
+            !keep_index,
+            allow_path_usage,
+            &manifest,
+            check_reproducible,
+            post_build,
+            jobs,
+        )
+    } else {
+        // If the workspace is also missing, report an error about
+        // missing project because that is what the user is more likely
+        // to be looking for.
+        let current_workspace = ctx
+            .current_workspace
+            .ok_or(CliError::MissingProjectCurrentDir)?;
+        let output_dir = path.unwrap_or_else(|| current_workspace.root_path().join("output"));
+        if !wrapfs::is_dir(&output_dir)? {
+            wrapfs::create_dir(&output_dir)?;
+        }
+        command_build_for_workspace(
+            output_dir,
+            compression,
+            current_workspace,
+            !keep_index,
+            allow_path_usage,
+            &manifest,
+            check_reproducible,
+            post_build,
+            jobs,
+        )
+    }
+}
+
+fn run_config(command: cli::ConfigCommand, mut cx: CommandContext) -> Result<()> {
+    let config_file = cx.config_file.as_deref();
+    match command {
+        cli::ConfigCommand::Check => {
+            command_config_check(config_file, cx.no_config, &cx.project, &mut cx.out)
+        }
+        cli::ConfigCommand::Get { key } => {
+            command_config_get(key, config_file, cx.no_config, &cx.project, &mut cx.out)
+        }
+        cli::ConfigCommand::Set { key, value, user } => {
+            command_config_set(key, value, user, &cx.project)
+        }
+        cli::ConfigCommand::Unset { key, user } => command_config_unset(key, user, &cx.project),
+        cli::ConfigCommand::List { origin } => {
+            command_config_list(origin, config_file, cx.no_config, &cx.project, &mut cx.out)
+        }
+    }
+}
@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+//! Summary printed by `--timings`
+
+use anstream::eprintln;
+use anyhow::Result;
+use sysand_core::{build::format_size, project::utils::wrapfs, timings::Timings};
+
+use crate::{cli::TimingsFormat, style::HEADER};
+
+pub const TIMINGS_FILE: &str = "sysand-timings.json";
+
+/// Print the statistics recorded during the command to stderr, or write
+/// them to [`TIMINGS_FILE`]
+pub fn report(format: TimingsFormat) -> Result<()> {
+    let Some(timings) = sysand_core::timings::snapshot() else {
+        return Ok(());
+    };
+    match format {
+        TimingsFormat::Human => print_summary(&timings),
+        TimingsFormat::Json => {
+            let contents = serde_json::to_string_pretty(&timings)?;
+            wrapfs::write(TIMINGS_FILE, contents)?;
+            let header = HEADER;
+            log::info!(
+                "{header}{:>12}{header:#} timings to `{TIMINGS_FILE}`",
+                "Wrote"
+            );
+        }
+    }
+    Ok(())
+}
+
+fn print_summary(timings: &Timings) {
+    let header = HEADER;
+    eprintln!(
+        "{header}{:>12}{header:#} in {:.3}s",
+        "Timings",
+        timings.total.as_secs_f64()
+    );
+    for (phase, stats) in &timings.phases {
+        eprintln!(
+            "{:>12} {:.3}s ({} {})",
+            phase.as_str(),
+            stats.elapsed.as_secs_f64(),
+            stats.count,
+            if stats.count == 1 { "time" } else { "times" }
+        );
+    }
+    if !timings.downloads.is_empty() {
+        eprintln!("{header}{:>12}{header:#}", "Downloads");
+        for (host, stats) in &timings.downloads {
+            eprintln!(
+                "{:>12} {} in {} {}",
+                host,
+                format_size(stats.bytes),
+                stats.requests,
+                if stats.requests == 1 {
+                    "request"
+                } else {
+                    "requests"
+                }
+            );
+        }
+    }
+    if !timings.caches.is_empty() {
+        eprintln!("{header}{:>12}{header:#}", "Caches");
+        for (cache, stats) in &timings.caches {
+            let rate = stats
+                .hit_rate()
+                .map(|rate| format!(" ({:.0}% hit rate)", rate * 100.0))
+                .unwrap_or_default();
+            eprintln!(
+                "{:>12} {} hits, {} misses{rate}",
+                cache, stats.hits, stats.misses
+            );
+        }
+    }
+}
@@ -1462,3 +1462,140 @@ fn remove_nonexistent() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn add_search_non_interactive() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = mockito::Server::new();
+    let _config_mock = server
+        .mock("GET", "/sysand-index-config.json")
+        .with_status(404)
+        .create();
+    let index_mock = server
+        .mock("GET", "/index.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"projects":[
+                {"iri":"pkg:sysand/acme/my-vehicle"},
+                {"iri":"pkg:sysand/acme/engine"},
+                {"iri":"pkg:sysand/acme/vehicle"}
+            ]}"#,
+        )
+        .expect(2)
+        .create();
+
+    let (_temp_dir, cwd, out) =
+        run_sysand(["init", "--version", "1.2.3", "--name", "add_search"], None)?;
+    out.assert().success();
+
+    let url = server.url();
+    let out = run_sysand_in(
+        &cwd,
+        ["add", "vehicle", "--no-lock", "--default-index", &url],
+        None,
+    )?;
+    out.assert().failure().stderr(predicate::str::contains(
+        "`vehicle` is not an IRI; projects matching it:\n    \
+        pkg:sysand/acme/vehicle\n    \
+        pkg:sysand/acme/my-vehicle\n",
+    ));
+
+    let out = run_sysand_in(
+        &cwd,
+        ["add", "lorry", "--no-lock", "--default-index", &url],
+        None,
+    )?;
+    out.assert().failure().stderr(predicate::str::contains(
+        "`lorry` is not an IRI, and no projects matching it were found in the indexes",
+    ));
+    index_mock.assert();
+
+    let out = run_sysand_in(&cwd, ["add", "vehicle", "--no-lock", "--no-index"], None)?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("with `--no-index`"));
+
+    let out = run_sysand_in(&cwd, ["add", "./vehicle", "--no-lock"], None)?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("use `--path` instead"));
+
+    let info = std::fs::read_to_string(cwd.join(".project.json"))?;
+    assert!(!info.contains("usage"));
+
+    Ok(())
+}
+
+#[test]
+fn add_variant() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        ["init", "--version", "1.2.3", "--name", "add_variant"],
+        None,
+    )?;
+    out.assert().success();
+    let config_path = cwd.join("sysand.toml");
+
+    let dep = cwd.join("lib/dep");
+    std::fs::create_dir_all(dep.join("extra"))?;
+    std::fs::write(
+        dep.join(".project.json"),
+        r#"{"name": "dep", "version": "1.0.0"}"#,
+    )?;
+    std::fs::write(
+        dep.join(".meta.json"),
+        r#"{
+  "index": {"A": "a.sysml", "B": "extra/b.sysml"},
+  "created": "2025-01-01T00:00:00Z",
+  "variants": {"minimal": ["a.sysml"], "full": ["a.sysml", "extra"]}
+}"#,
+    )?;
+    std::fs::write(dep.join("a.sysml"), "package A;")?;
+    std::fs::write(dep.join("extra/b.sysml"), "package B;")?;
+
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "add",
+            "urn:kpar:dep",
+            "--from-path",
+            "lib/dep",
+            "--variant",
+            "tiny",
+        ],
+        Some(config_path.as_str()),
+    )?;
+    out.assert().failure().stderr(predicate::str::contains(
+        "project `urn:kpar:dep` has no variant `tiny`, available variants: `minimal`, `full`",
+    ));
+
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "add",
+            "urn:kpar:dep",
+            "--from-path",
+            "lib/dep",
+            "--variant",
+            "minimal",
+        ],
+        Some(config_path.as_str()),
+    )?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains(format!(
+            "Setting variant `minimal` of project `urn:kpar:dep` in configuration file at `{config_path}`"
+        )));
+
+    let config = std::fs::read_to_string(&config_path)?;
+    assert!(config.contains("[variants]\n\"urn:kpar:dep\" = \"minimal\"\n"));
+    let lock = std::fs::read_to_string(cwd.join("sysand-lock.toml"))?;
+    assert!(lock.contains("variant = \"minimal\""));
+
+    let out = run_sysand_in(&cwd, ["sources"], Some(config_path.as_str()))?;
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains("a.sysml"))
+        .stdout(predicate::str::contains("b.sysml").not());
+
+    Ok(())
+}
@@ -18,3 +18,23 @@ fn basic_execution() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn network_config_is_applied() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    let cfg_path = cwd.join("network.toml");
+    std::fs::write(&cfg_path, "[network]\nca_bundle = \"missing.pem\"\n")?;
+
+    let out = run_sysand_in(&cwd, ["info"], Some(cfg_path.as_str()))?;
+    out.assert().failure().stderr(predicate::str::contains(
+        "failed to read CA bundle `missing.pem`",
+    ));
+
+    std::fs::write(&cfg_path, "[network]\nproxy = \"http://[::1\"\n")?;
+    let out = run_sysand_in(&cwd, ["info"], Some(cfg_path.as_str()))?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid proxy `http://[::1`"));
+
+    Ok(())
+}
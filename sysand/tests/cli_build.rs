@@ -168,6 +168,55 @@ fn build_errors_when_index_symbol_is_missing_from_file() -> Result<(), Box<dyn s
     Ok(())
 }
 
+#[test]
+fn build_checks_variant_paths() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        [
+            "init",
+            "--version",
+            "1.2.3",
+            "--name",
+            "test_build_variants",
+        ],
+        None,
+    )?;
+    out.assert().success();
+
+    std::fs::write(cwd.join("test.sysml"), b"package P;\n")?;
+    let out = run_sysand_in(&cwd, ["include", "test.sysml"], None)?;
+    out.assert().success();
+
+    let meta_path = cwd.join(".meta.json");
+    let mut meta: serde_json::Value = serde_json::from_str(&fs::read_to_string(&meta_path)?)?;
+    meta["variants"] = json!({
+        "minimal": ["test.sysml"],
+    });
+    std::fs::write(&meta_path, serde_json::to_string_pretty(&meta)?)?;
+
+    let out = run_sysand_in(&cwd, ["build", "./test_build_good.kpar"], None)?;
+    out.assert().success();
+    let kpar_project = LocalKParProjectRaw::new_guess_root(cwd.join("test_build_good.kpar"))?;
+    let Some(meta) = kpar_project.get_meta()? else {
+        panic!("failed to get built project meta");
+    };
+    assert_eq!(meta.variants["minimal"], ["test.sysml"]);
+
+    let mut meta: serde_json::Value = serde_json::from_str(&fs::read_to_string(&meta_path)?)?;
+    meta["variants"] = json!({
+        "minimal": ["test.sysml"],
+        "full": ["test.sysml", "extra"],
+    });
+    std::fs::write(&meta_path, serde_json::to_string_pretty(&meta)?)?;
+
+    let out = run_sysand_in(&cwd, ["build", "./test_build_bad.kpar"], None)?;
+    out.assert().failure().stderr(predicate::str::contains(
+        "path `extra` of variant `full` does not match any packaged file",
+    ));
+    assert!(!cwd.join("test_build_bad.kpar").exists());
+
+    Ok(())
+}
+
 #[test]
 fn project_build_warns_when_file_symbol_is_missing_from_index()
 -> Result<(), Box<dyn std::error::Error>> {
@@ -262,6 +311,159 @@ fn project_build_path_usage() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn project_build_include_manifest() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        ["init", "--version", "1.2.3", "--name", "test_manifest"],
+        None,
+    )?;
+    out.assert().success();
+
+    fs::write(cwd.join("test.sysml"), b"package P;\n")?;
+    fs::write(cwd.join("draft.sysml"), b"package D;\n")?;
+    fs::write(
+        cwd.join("sysand.toml"),
+        "[build]\ninclude = [\"*.sysml\"]\n",
+    )?;
+
+    let out = run_sysand_in(&cwd, ["include", "test.sysml"], None)?;
+    out.assert().success();
+    let cfg = Some(cwd.join("sysand.toml").into_string());
+
+    let out = run_sysand_in(&cwd, ["build", "./test_build.kpar"], cfg.as_deref())?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "selected, but not included in metadata: `draft.sysml`",
+        ))
+        .stderr(predicate::str::contains("--allow-dirty"));
+    assert!(!cwd.join("test_build.kpar").exists());
+
+    let out = run_sysand_in(
+        &cwd,
+        ["build", "./test_build.kpar", "--allow-dirty"],
+        cfg.as_deref(),
+    )?;
+    out.assert().success().stderr(predicate::str::contains(
+        "packaging file `draft.sysml` not included in metadata",
+    ));
+    assert_kpar_file(&cwd.join("test_build.kpar"), "draft.sysml", "package D;\n");
+
+    fs::write(
+        cwd.join("sysand.toml"),
+        "[build]\ninclude = [\"*.sysml\"]\nexclude = [\"draft.sysml\"]\n",
+    )?;
+    let out = run_sysand_in(&cwd, ["build", "./test_build.kpar"], cfg.as_deref())?;
+    out.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn project_build_check_reproducible() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        ["init", "--version", "1.2.3", "--name", "test_reproducible"],
+        None,
+    )?;
+    out.assert().success();
+
+    fs::write(cwd.join("test.sysml"), b"package P;\n")?;
+    let out = run_sysand_in(&cwd, ["include", "test.sysml"], None)?;
+    out.assert().success();
+
+    let env = IndexMap::from([("SOURCE_DATE_EPOCH", "1700000000")]);
+    let out = run_sysand_in_with(
+        &cwd,
+        ["build", "./first.kpar", "--check-reproducible"],
+        None,
+        &env,
+    )?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("is reproducible"));
+    let out = run_sysand_in_with(&cwd, ["build", "./second.kpar"], None, &env)?;
+    out.assert().success();
+
+    assert_eq!(
+        fs::read(cwd.join("first.kpar"))?,
+        fs::read(cwd.join("second.kpar"))?
+    );
+
+    let mut archive = zip::ZipArchive::new(fs::File::open(cwd.join("first.kpar"))?)?;
+    let file = archive.by_name("test.sysml")?;
+    let modified = file.last_modified().unwrap();
+    assert_eq!(
+        (modified.year(), modified.month(), modified.day()),
+        (2023, 11, 14)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn project_build_compression() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        ["init", "--version", "1.2.3", "--name", "test_compression"],
+        None,
+    )?;
+    out.assert().success();
+
+    fs::write(cwd.join("test.sysml"), "package P;\n".repeat(100))?;
+    let out = run_sysand_in(&cwd, ["include", "test.sysml"], None)?;
+    out.assert().success();
+
+    fs::write(
+        cwd.join("sysand.toml"),
+        "[build]\ncompression = \"stored\"\n",
+    )?;
+    let cfg = cwd.join("sysand.toml");
+    let out = run_sysand_in(&cwd, ["build", "./stored.kpar"], Some(cfg.as_str()))?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("uncompressed)"));
+    let mut archive = zip::ZipArchive::new(fs::File::open(cwd.join("stored.kpar"))?)?;
+    assert_eq!(
+        archive.by_name("test.sysml")?.compression(),
+        zip::CompressionMethod::Stored
+    );
+
+    // The command line takes precedence over config
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "build",
+            "./deflated.kpar",
+            "--compression",
+            "deflated",
+            "--compression-level",
+            "9",
+        ],
+        Some(cfg.as_str()),
+    )?;
+    out.assert().success();
+    let mut archive = zip::ZipArchive::new(fs::File::open(cwd.join("deflated.kpar"))?)?;
+    assert_eq!(
+        archive.by_name("test.sysml")?.compression(),
+        zip::CompressionMethod::Deflated
+    );
+    assert!(
+        fs::metadata(cwd.join("deflated.kpar"))?.len()
+            < fs::metadata(cwd.join("stored.kpar"))?.len()
+    );
+
+    let out = run_sysand_in(
+        &cwd,
+        ["build", "./invalid.kpar", "--compression-level", "5"],
+        Some(cfg.as_str()),
+    )?;
+    out.assert().failure().stderr(predicate::str::contains(
+        "does not support compression levels",
+    ));
+    assert!(!cwd.join("invalid.kpar").exists());
+
+    Ok(())
+}
+
 struct WProject {
     name: String,
     info_path: Utf8PathBuf,
@@ -1284,3 +1486,92 @@ fn compression_method(compression: Option<&str>) -> Result<(), Box<dyn std::erro
     assert_eq!(src, "package P;\n");
     Ok(())
 }
+
+#[cfg(unix)]
+#[test]
+fn project_build_post_build_hook() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) =
+        run_sysand(["init", "--version", "1.2.3", "--name", "test_build"], None)?;
+    out.assert().success();
+    std::fs::write(cwd.join("test.sysml"), b"package P;\n")?;
+    let out = run_sysand_in(&cwd, ["include", "test.sysml"], None)?;
+    out.assert().success();
+
+    let config_path = cwd.join("hooks.toml");
+    std::fs::write(
+        &config_path,
+        "[[post_build]]\nname = \"always-fails\"\ncommand = \"false\"\n",
+    )?;
+
+    let out = run_sysand_in(
+        &cwd,
+        ["build", "./test_build.kpar"],
+        Some(config_path.as_str()),
+    )?;
+    out.assert().failure().stderr(predicate::str::contains(
+        "post-processor `always-fails` failed",
+    ));
+
+    let out = run_sysand_in(
+        &cwd,
+        ["build", "./test_build.kpar", "--no-post-build"],
+        Some(config_path.as_str()),
+    )?;
+    out.assert().success();
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn project_build_pre_build_hook() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) =
+        run_sysand(["init", "--version", "1.2.3", "--name", "test_build"], None)?;
+    out.assert().success();
+    std::fs::write(cwd.join("test.sysml"), b"package P;\n")?;
+    let out = run_sysand_in(&cwd, ["include", "test.sysml"], None)?;
+    out.assert().success();
+
+    let config_path = cwd.join("hooks.toml");
+    std::fs::write(
+        &config_path,
+        "[[hooks.pre_build]]\nname = \"record\"\ncommand = \"sh\"\n\
+         args = [\"-c\", \"echo \\\"$SYSAND_HOOK $SYSAND_SOURCES\\\" > hook.txt\"]\n",
+    )?;
+
+    let out = run_sysand_in(
+        &cwd,
+        ["build", "./test_build.kpar", "--no-hooks"],
+        Some(config_path.as_str()),
+    )?;
+    out.assert().success();
+    assert!(!cwd.join("hook.txt").exists());
+
+    let out = run_sysand_in(
+        &cwd,
+        ["build", "./test_build.kpar"],
+        Some(config_path.as_str()),
+    )?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("not\nsandboxed"))
+        .stderr(predicate::str::contains("`pre_build` hook `record`"));
+    let recorded = std::fs::read_to_string(cwd.join("hook.txt"))?;
+    assert!(recorded.starts_with("pre_build "));
+    assert!(recorded.trim_end().ends_with("test.sysml"));
+
+    std::fs::write(
+        &config_path,
+        "[[hooks.pre_build]]\nname = \"always-fails\"\ncommand = \"false\"\n",
+    )?;
+    let out = run_sysand_in(
+        &cwd,
+        ["build", "./test_build.kpar"],
+        Some(config_path.as_str()),
+    )?;
+    out.assert().failure().stderr(predicate::str::contains(
+        "`pre_build` hook `always-fails` exited with",
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::io::Read as _;
+
+use assert_cmd::prelude::*;
+use indexmap::IndexMap;
+use predicates::str::contains;
+use sysand_core::config::{self, ConfigProject, OverrideSource};
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+#[test]
+fn bundle_and_install() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        ["init", "--name", "bundle_and_install", "--version", "1.2.3"],
+        None,
+    )?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["init", "--version", "1.0.0", "local_dep"], None)?;
+    out.assert().success();
+
+    let cfg = toml::to_string(&config::Config {
+        projects: vec![ConfigProject {
+            identifiers: vec!["urn:kpar:local_dep".to_string()],
+            sources: vec![OverrideSource::LocalSrc {
+                src_path: "local_dep".into(),
+            }],
+        }],
+        ..Default::default()
+    })?;
+    let cfg_path = cwd.join(config::local_fs::CONFIG_FILE);
+    std::fs::write(&cfg_path, cfg)?;
+
+    let out = run_sysand_in(
+        &cwd,
+        ["add", "urn:kpar:local_dep", "^1"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["bundle"], None)?;
+    out.assert()
+        .success()
+        .stderr(contains("Bundled 2 project(s) into `sysand-bundle.zip`"));
+    assert!(cwd.join("sysand-bundle.zip").is_file());
+
+    // Install into a fresh environment, without the dependency sources
+    std::fs::remove_dir_all(cwd.join(".sysand"))?;
+    std::fs::remove_dir_all(cwd.join("local_dep"))?;
+    let out = run_sysand_in(&cwd, ["bundle", "install", "sysand-bundle.zip"], None)?;
+    out.assert()
+        .success()
+        .stderr(contains("Installed 1 project(s) from `sysand-bundle.zip`"));
+
+    let out = run_sysand_in(&cwd, ["env", "list"], None)?;
+    out.assert()
+        .success()
+        .stdout(contains("`urn:kpar:local_dep` 1.0.0"));
+
+    Ok(())
+}
+
+/// Projects stored encrypted in the environment are decrypted when packed
+#[test]
+fn bundle_from_encrypted_env() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "bundle_from_encrypted_env"], None)?;
+    out.assert().success();
+    let out = run_sysand_in(&cwd, ["init", "--version", "1.0.0", "local_dep"], None)?;
+    out.assert().success();
+    std::fs::write(cwd.join("local_dep/local_dep.sysml"), "package LocalDep;")?;
+    let out = run_sysand_in(
+        &cwd.join("local_dep"),
+        ["include", "--no-index-symbols", "local_dep.sysml"],
+        None,
+    )?;
+    out.assert().success();
+
+    let mut cfg = toml::to_string(&config::Config {
+        projects: vec![ConfigProject {
+            identifiers: vec!["urn:kpar:local_dep".to_string()],
+            sources: vec![OverrideSource::LocalSrc {
+                src_path: "local_dep".into(),
+            }],
+        }],
+        ..Default::default()
+    })?;
+    cfg.push_str("\n[env]\nstorage = \"encrypted\"\n");
+    let cfg_path = cwd.join(config::local_fs::CONFIG_FILE);
+    std::fs::write(&cfg_path, cfg)?;
+    let cfg = cfg_path.as_str();
+    let key = IndexMap::from([("SYSAND_ENV_KEY", "cd".repeat(32))]);
+
+    let out = run_sysand_in_with(&cwd, ["add", "urn:kpar:local_dep", "^1"], Some(cfg), &key)?;
+    out.assert().success();
+    let stored = std::fs::read(cwd.join(".sysand/lib/kpar.local_dep_1.0.0/local_dep.sysml"))?;
+    assert!(stored.starts_with(b"SYSENC1\0"));
+
+    let out = run_sysand_in(&cwd, ["bundle"], Some(cfg))?;
+    out.assert()
+        .failure()
+        .stderr(contains("no encryption key is set"));
+
+    let out = run_sysand_in_with(&cwd, ["bundle", "--format", "directory"], Some(cfg), &key)?;
+    out.assert().success();
+    let kpar = std::fs::File::open(cwd.join("sysand-bundle/kpars/local_dep-1.0.0.kpar"))?;
+    let mut archive = zip::ZipArchive::new(kpar)?;
+    let mut source = String::new();
+    archive
+        .by_name("local_dep.sysml")?
+        .read_to_string(&mut source)?;
+    assert_eq!(source, "package LocalDep;");
+
+    Ok(())
+}
+
+#[test]
+fn bundle_requires_lockfile() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        [
+            "init",
+            "--name",
+            "bundle_requires_lockfile",
+            "--version",
+            "1.2.3",
+        ],
+        None,
+    )?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["bundle", "--format", "directory"], None)?;
+    out.assert().failure().stderr(contains("run `sysand sync`"));
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use assert_cmd::prelude::*;
+use indexmap::IndexMap;
+use predicates::prelude::*;
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+#[test]
+fn cache_prune() -> Result<(), Box<dyn std::error::Error>> {
+    let cache_dir = camino_tempfile::tempdir()?;
+    let git_dir = cache_dir.path().join("git");
+    let http_dir = cache_dir.path().join("http");
+    std::fs::create_dir_all(git_dir.join("0123.git"))?;
+    std::fs::create_dir_all(&http_dir)?;
+    std::fs::write(http_dir.join("0123.json"), "{}")?;
+    let env = IndexMap::from([("SYSAND_CACHE_DIR", cache_dir.path().as_str())]);
+
+    let (_temp_dir, cwd, out) = run_sysand_with(["cache", "prune", "--git"], None, &env)?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("Pruned 1 cached git repositories"))
+        .stderr(predicate::str::contains("HTTP").not());
+    assert_eq!(std::fs::read_dir(&git_dir)?.count(), 0);
+    assert!(http_dir.join("0123.json").is_file());
+
+    let out = run_sysand_in_with(&cwd, ["cache", "prune"], None, &env)?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("Pruned 0 cached git repositories"))
+        .stderr(predicate::str::contains("Pruned 1 cached HTTP responses"));
+    assert_eq!(std::fs::read_dir(&http_dir)?.count(), 0);
+
+    Ok(())
+}
@@ -295,3 +295,37 @@ fn clone_nonexsitent_nested_target() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+// `--workspace` clones dependencies into sibling directories and uses them
+// as editable projects
+#[test]
+fn clone_workspace() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "b", "--name", "Lib B"], None)?;
+    out.assert().success();
+    let out = run_sysand_in(&cwd, ["init", "a", "--name", "app"], None)?;
+    out.assert().success();
+    let b_url = file_url_from_path(wrapfs::canonicalize(cwd.join("b"))?);
+    wrapfs::write(
+        cwd.join("a").join(".project.json"),
+        format!(r#"{{"name":"app","version":"0.0.1","usage":[{{"resource":"{b_url}"}}]}}"#),
+    )?;
+
+    let out = run_sysand_in(&cwd, ["clone", "a", "--workspace", "--target", "ws"], None)?;
+
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("Cloned `app` 0.0.1"))
+        .stderr(predicate::str::contains("Cloned `Lib B` 0.0.1"));
+    let ws = cwd.join("ws");
+    assert!(ws.join("app").join(".project.json").is_file());
+    assert!(ws.join("Lib-B").join(".project.json").is_file());
+    assert_eq!(wrapfs::read_dir(&ws)?.count(), 2);
+    let config = wrapfs::read_to_string(ws.join("app").join("sysand.toml"))?;
+    assert!(config.contains(&format!(r#""{b_url}""#)));
+    assert!(config.contains(r#"editable = "../Lib-B""#));
+    let lock = wrapfs::read_to_string(ws.join("app").join("sysand-lock.toml"))?;
+    assert!(lock.contains(r#"editable = "../Lib-B""#));
+    assert!(ws.join("app").join(DEFAULT_ENV_NAME).is_dir());
+
+    Ok(())
+}
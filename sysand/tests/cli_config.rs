@@ -0,0 +1,230 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use assert_cmd::prelude::*;
+use indexmap::IndexMap;
+use predicates::str::contains;
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+#[test]
+fn config_check_valid() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    let config = cwd.join("config.toml");
+    std::fs::write(
+        &config,
+        "[[index]]\nurl = \"https://example.com\"\n\n[cache]\nttl = 60\n",
+    )?;
+
+    let out = run_sysand_in(&cwd, ["config", "check"], Some(config.as_str()))?;
+    out.assert()
+        .success()
+        .stderr(contains("Checked --config-file configuration"))
+        .stdout(contains(format!("# from {config}\n[[index]]")))
+        .stdout(contains("ttl = 60"));
+
+    Ok(())
+}
+
+#[test]
+fn config_check_unknown_keys() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    let config = cwd.join("config.toml");
+    std::fs::write(
+        &config,
+        "[[index]]\nurl = \"https://example.com\"\ndefualt = true\n",
+    )?;
+
+    let out = run_sysand_in(
+        &cwd,
+        ["--output-format", "json", "config", "check"],
+        Some(config.as_str()),
+    )?;
+    let output = out
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(contains(format!(
+            "unknown key `index[0].defualt` in `{config}:3` is ignored"
+        )))
+        .stderr(contains("found 1 problem(s) in the configuration files"))
+        .get_output()
+        .stdout
+        .clone();
+    let report: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(
+        report["files"][0]["unknown_keys"][0],
+        serde_json::json!({"key": "index[0].defualt", "line": 3})
+    );
+    assert_eq!(report["config"]["index"][0]["url"], "https://example.com");
+
+    // Other commands warn, but still run
+    let out = run_sysand_in(
+        &cwd,
+        ["init", "--name", "p", "--version", "1.0.0"],
+        Some(config.as_str()),
+    )?;
+    out.assert()
+        .success()
+        .stderr(contains("unknown key `index[0].defualt`"));
+
+    Ok(())
+}
+
+#[test]
+fn config_check_invalid() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    let config = cwd.join("config.toml");
+    std::fs::write(&config, "[cache]\nttl = \"soon\"\n")?;
+
+    let out = run_sysand_in(&cwd, ["config", "check"], Some(config.as_str()))?;
+    out.assert()
+        .failure()
+        .stderr(contains("failed to deserialize TOML file"))
+        .stderr(contains("line 2"));
+
+    Ok(())
+}
+
+#[test]
+fn config_set_get_unset() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    let config = cwd.join("sysand.toml");
+    std::fs::write(&config, "[cache]\n# Refresh daily\nttl = 86400\n")?;
+
+    for (key, value) in [
+        ("index[0].url", "https://a.example.com"),
+        ("index[1].url", "https://b.example.com"),
+        ("index[1].default", "true"),
+        ("iri", "pkg:sysand/acme/lib"),
+    ] {
+        let out = run_sysand_in(&cwd, ["config", "set", key, value], None)?;
+        out.assert()
+            .success()
+            .stderr(contains(format!("Setting `{key}`")));
+    }
+    let out = run_sysand_in(
+        &cwd,
+        ["config", "set", "index[5].url", "https://c.com"],
+        None,
+    )?;
+    out.assert()
+        .failure()
+        .stderr(contains("entries can only be added at the end of a list"));
+    let out = run_sysand_in(&cwd, ["config", "set", "cache.tll", "1"], None)?;
+    out.assert()
+        .failure()
+        .stderr(contains("`cache.tll` is not a setting"));
+
+    let contents = std::fs::read_to_string(&config)?;
+    assert!(
+        contents.contains("# Refresh daily\nttl = 86400\n"),
+        "{contents}"
+    );
+
+    // `config get` and `config list` read the files like other commands
+    let config_arg = Some(config.as_str());
+    let out = run_sysand_in(&cwd, ["config", "get", "index[1].url"], config_arg)?;
+    out.assert().success().stdout("https://b.example.com\n");
+    let out = run_sysand_in(&cwd, ["config", "get", "cache.ttl"], config_arg)?;
+    out.assert().success().stdout("86400\n");
+    let out = run_sysand_in(&cwd, ["config", "get", "network.proxy"], config_arg)?;
+    out.assert()
+        .failure()
+        .stderr(contains("`network.proxy` is not set"));
+    let out = run_sysand_in(&cwd, ["config", "list", "--origin"], config_arg)?;
+    out.assert()
+        .success()
+        .stdout(contains(format!("index[1].default = true  # {config}\n")));
+
+    let out = run_sysand_in(&cwd, ["config", "unset", "index[0]"], None)?;
+    out.assert().success();
+    let out = run_sysand_in(&cwd, ["config", "unset", "cache.ttl"], None)?;
+    out.assert().success();
+    let out = run_sysand_in(&cwd, ["config", "unset", "cache.ttl"], None)?;
+    out.assert()
+        .success()
+        .stderr(contains("`cache.ttl` is not set"));
+
+    let out = run_sysand_in(
+        &cwd,
+        ["--output-format", "json", "config", "list"],
+        config_arg,
+    )?;
+    let output = out.assert().success().get_output().stdout.clone();
+    let settings: serde_json::Value = serde_json::from_slice(&output)?;
+    assert_eq!(
+        settings,
+        serde_json::json!([
+            {"key": "iri", "value": "pkg:sysand/acme/lib"},
+            {"key": "index[0].url", "value": "https://b.example.com"},
+            {"key": "index[0].default", "value": true},
+        ])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn config_env_settings() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    let config = cwd.join("config.toml");
+    std::fs::write(
+        &config,
+        "[cache]\nttl = 60\n\n[[index]]\nurl = \"https://a.com\"\n",
+    )?;
+    let env = IndexMap::from([(
+        "SYSAND_SETTINGS",
+        "cache.ttl = 5\nindex = [{ url = \"https://b.com\" }]",
+    )]);
+
+    let out = run_sysand_in_with(
+        &cwd,
+        ["config", "list", "--origin"],
+        Some(config.as_str()),
+        &env,
+    )?;
+    out.assert().success().stdout(format!(
+        "index[0].url = \"https://b.com\"  # SYSAND_SETTINGS\n\
+        index[1].url = \"https://a.com\"  # {config}\n\
+        cache.ttl = 5  # SYSAND_SETTINGS\n"
+    ));
+
+    let env = IndexMap::from([("SYSAND_SETTINGS", "cache.ttl = ")]);
+    let out = run_sysand_in_with(&cwd, ["env"], None, &env)?;
+    out.assert()
+        .failure()
+        .stderr(contains("invalid settings in `SYSAND_SETTINGS`"));
+
+    Ok(())
+}
+
+#[test]
+fn config_system_file() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    let system = cwd.join("system.toml");
+    std::fs::write(
+        &system,
+        "[cache]\nttl = 1\n\n[network]\nproxy = \"http://corp:3128\"\n",
+    )?;
+    std::fs::write(cwd.join("sysand.toml"), "[cache]\nttl = 60\n")?;
+
+    // Without `--no-config`, isolated from the user configuration
+    let out = std::process::Command::new(assert_cmd::cargo::cargo_bin!("sysand"))
+        .args(["config", "list", "--origin"])
+        .current_dir(&cwd)
+        .env("NO_COLOR", "1")
+        .env("XDG_CONFIG_HOME", cwd.join("user"))
+        .env("SYSAND_SYSTEM_CONFIG", &system)
+        .output()?;
+    out.assert()
+        .success()
+        .stdout(contains("cache.ttl = 60  # ./sysand.toml\n"))
+        .stdout(contains(format!(
+            "network.proxy = \"http://corp:3128\"  # {system}\n"
+        )));
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::collections::HashSet;
+
+use assert_cmd::prelude::*;
+use clap::Parser;
+use predicates::prelude::*;
+use sysand::cli::{Args, DEPRECATIONS, DeprecatedName, Deprecation, rewrite_deprecated};
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+const TEST_DEPRECATIONS: &[Deprecation] = &[
+    Deprecation {
+        code: "D9001",
+        kind: DeprecatedName::Flag,
+        command: &[],
+        old: "registry",
+        new: "index",
+        removed_in: "99.0.0",
+    },
+    Deprecation {
+        code: "D9002",
+        kind: DeprecatedName::Command,
+        command: &["index"],
+        old: "create",
+        new: "init",
+        removed_in: "99.0.0",
+    },
+];
+
+fn rewrite(args: &[&str]) -> (Vec<String>, Vec<&'static str>) {
+    let (args, used) = rewrite_deprecated(args.iter().copied(), TEST_DEPRECATIONS);
+    (
+        args.into_iter().map(|a| a.into_string().unwrap()).collect(),
+        used.into_iter().map(|d| d.code).collect(),
+    )
+}
+
+#[test]
+fn deprecated_flag_is_renamed() {
+    let (args, used) = rewrite(&["sysand", "sync", "--registry", "https://example.com"]);
+    assert_eq!(args, ["sysand", "sync", "--index", "https://example.com"]);
+    assert_eq!(used, ["D9001"]);
+    assert!(Args::try_parse_from(args).is_ok());
+
+    let (args, used) = rewrite(&["sysand", "--registry=https://example.com", "sync"]);
+    assert_eq!(args, ["sysand", "--index=https://example.com", "sync"]);
+    assert_eq!(used, ["D9001"]);
+}
+
+#[test]
+fn deprecated_command_is_renamed() {
+    let (args, used) = rewrite(&["sysand", "index", "create"]);
+    assert_eq!(args, ["sysand", "index", "init"]);
+    assert_eq!(used, ["D9002"]);
+    assert!(Args::try_parse_from(args).is_ok());
+}
+
+#[test]
+fn values_and_positionals_are_kept() {
+    // `create` as option value, as command outside `index` and after `--`
+    let (args, used) = rewrite(&["sysand", "index", "--config-file", "create", "create"]);
+    assert_eq!(args, ["sysand", "index", "--config-file", "create", "init"]);
+    assert_eq!(used, ["D9002"]);
+
+    let (args, used) = rewrite(&["sysand", "init", "create", "--", "--registry"]);
+    assert_eq!(args, ["sysand", "init", "create", "--", "--registry"]);
+    assert!(used.is_empty());
+}
+
+#[test]
+fn no_deprecations_keeps_args() {
+    let args = ["sysand", "index", "create", "--registry"];
+    let (rewritten, used) = rewrite_deprecated(args, &[]);
+    assert_eq!(rewritten, args);
+    assert!(used.is_empty());
+}
+
+#[test]
+fn deprecation_codes_are_unique() {
+    let mut codes = HashSet::new();
+    for deprecation in DEPRECATIONS {
+        assert!(codes.insert(deprecation.code), "{}", deprecation.code);
+    }
+}
+
+#[test]
+fn deprecated_flag_warns() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "deprecated_flag_warns"], None)?;
+    out.assert().success();
+    let uri = url::Url::from_directory_path(&cwd).unwrap();
+
+    let out = run_sysand_in(&cwd, ["info", "--uri", uri.as_str()], None)?;
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains("Name: deprecated_flag_warns"))
+        .stderr(predicate::str::contains(
+            "warning[D0001]: `--uri` is deprecated",
+        ))
+        .stderr(predicate::str::contains("use `--iri` instead"));
+
+    let out = run_sysand_in(&cwd, ["info", "--iri", uri.as_str()], None)?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("deprecated").not());
+
+    Ok(())
+}
+
+#[test]
+fn deprecated_flag_warns_json() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "deprecated_flag_warns_json"], None)?;
+    out.assert().success();
+    let uri = url::Url::from_directory_path(&cwd).unwrap();
+
+    let out = run_sysand_in(
+        &cwd,
+        ["--log-format", "json", "info", "--url", uri.as_str()],
+        None,
+    )?;
+    let stderr = String::from_utf8_lossy(&out.stderr).into_owned();
+    out.assert().success();
+    let warning: serde_json::Value = stderr
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("stderr line is not JSON"))
+        .find(|record: &serde_json::Value| record["code"] == "D0002")
+        .expect("no deprecation warning");
+    assert_eq!(warning["level"], "warning");
+    assert_eq!(warning["fields"]["old"], "--url");
+    assert_eq!(warning["fields"]["new"], "--iri");
+    assert_eq!(warning["fields"]["removed_in"], "0.2.0");
+    assert!(!warning["message"].as_str().unwrap().contains("D0002"));
+
+    Ok(())
+}
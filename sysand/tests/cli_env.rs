@@ -172,7 +172,7 @@ fn env_install_from_http_kpar() -> Result<(), Box<dyn std::error::Error>> {
         .with_status(200)
         .with_header("content-type", "application/octet-stream")
         .with_body(&test_body)
-        .expect(0)
+        .expect(1)
         .create();
 
     let get_mock = server
@@ -220,8 +220,74 @@ kpar_cksum = "1838ad10a9c1fa46c74a92c68212e6fdcf6f6011a94ca5f16e343ea18a8e203b"
 
 /// `sysand env install <IRI> --location <LOCATION>` should install
 /// the interchange project att <LOCATION> as <IRI> in local env.
-/// If the same command is run again it should give an error,
-/// and if run again with flag `--allow-overwrite` it should succeed
+/// If the same command is run again the identical version is skipped,
+/// and if run again with flag `--allow-overwrite` it is reinstalled
+/// Serve a project directory like a static file server, with `lib.sysml`
+/// having the body `served`
+fn mock_http_src_project(server: &mut Server, served: &str) {
+    let src = "package Lib;";
+    let meta = format!(
+        r#"{{"index":{{"Lib":"lib.sysml"}},"created":"2025-01-01T00:00:00.000000000Z","checksum":{{"lib.sysml":{{"value":"{}","algorithm":"SHA256"}}}}}}"#,
+        sysand_core::utils::sha256_lowercase_hex(src)
+    );
+    server
+        .mock("GET", mockito::Matcher::Regex("^/lib/info/refs".into()))
+        .with_status(404)
+        .create();
+    for (path, body) in [
+        (
+            "/lib/.project.json",
+            r#"{"name":"lib","version":"1.0.0","usage":[]}"#,
+        ),
+        ("/lib/.meta.json", meta.as_str()),
+        ("/lib/lib.sysml", served),
+    ] {
+        server.mock("HEAD", path).with_status(200).create();
+        server
+            .mock("GET", path)
+            .with_status(200)
+            .with_body(body)
+            .create();
+    }
+}
+
+#[test]
+fn env_install_from_http_src_dir() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, _) = run_sysand(["env"], None)?;
+    let mut server = Server::new();
+    mock_http_src_project(&mut server, "package Lib;");
+
+    let project_url = format!("{}/lib/", server.url());
+    let out = run_sysand_in(&cwd, ["env", "install", &project_url], None)?;
+    out.assert().success();
+
+    let installed = std::fs::read_to_string(cwd.join(DEFAULT_ENV_NAME).join(METADATA_PATH))?;
+    let path = installed
+        .lines()
+        .find_map(|l| l.strip_prefix("path = \""))
+        .and_then(|l| l.strip_suffix('"'))
+        .expect("installed project path");
+    assert_eq!(
+        std::fs::read_to_string(cwd.join(DEFAULT_ENV_NAME).join(path).join("lib.sysml"))?,
+        "package Lib;"
+    );
+    Ok(())
+}
+
+#[test]
+fn env_install_from_http_src_dir_checksum_mismatch() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, _) = run_sysand(["env"], None)?;
+    let mut server = Server::new();
+    mock_http_src_project(&mut server, "package Tampered;");
+
+    let project_url = format!("{}/lib/", server.url());
+    let out = run_sysand_in(&cwd, ["env", "install", &project_url], None)?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("does not match its checksum"));
+    Ok(())
+}
+
 #[test]
 fn env_install_from_local_dir_allow_overwrite() -> Result<(), Box<dyn std::error::Error>> {
     let (_temp_dir, cwd, _) = run_sysand(["env"], None)?;
@@ -256,9 +322,9 @@ fn env_install_from_local_dir_allow_overwrite() -> Result<(), Box<dyn std::error
         None,
     )?;
 
-    out.assert()
-        .failure()
-        .stderr(predicate::str::contains("error"));
+    out.assert().success().stderr(predicate::str::contains(
+        "`urn:kpar:test` 0.0.1: identical version already installed",
+    ));
 
     let out = run_sysand_in(
         &cwd,
@@ -388,8 +454,9 @@ fn env_install_allow_multiple() -> Result<(), Box<dyn std::error::Error>> {
         "`urn:kpar:allow-multiple-lib` 2.0.0",
     ));
 
-    // --allow-multiple still rejects reinstalling the same version that is
-    // already present (that requires --allow-overwrite instead).
+    // --allow-multiple still rejects reinstalling the same version with
+    // different contents (that requires --overwrite instead).
+    std::fs::write(cwd_v1.join("TestLib.sysml"), "package TestLib2;")?;
     run_sysand_in(
         &cwd,
         [
@@ -408,6 +475,59 @@ fn env_install_allow_multiple() -> Result<(), Box<dyn std::error::Error>> {
         "project with IRI `urn:kpar:allow-multiple-lib` already has version `1.0.0` installed",
     ));
 
+    // Keep the installed version
+    run_sysand_in(
+        &cwd,
+        [
+            "env",
+            "install",
+            "urn:kpar:allow-multiple-lib",
+            "--path",
+            cwd_v1.as_str(),
+            "--allow-multiple",
+            "--overwrite",
+            "skip",
+        ],
+        None,
+    )?
+    .assert()
+    .success()
+    .stderr(predicate::str::contains("version already installed"));
+    assert_eq!(
+        std::fs::read_to_string(
+            cwd.join(DEFAULT_ENV_NAME)
+                .join("lib/kpar.allow-multiple-lib_1.0.0")
+                .join("TestLib.sysml")
+        )?,
+        "package TestLib;"
+    );
+
+    // Replace it, as its contents differ
+    run_sysand_in(
+        &cwd,
+        [
+            "env",
+            "install",
+            "urn:kpar:allow-multiple-lib",
+            "--path",
+            cwd_v1.as_str(),
+            "--allow-multiple",
+            "--overwrite",
+            "replace-if-different-hash",
+        ],
+        None,
+    )?
+    .assert()
+    .success();
+    assert_eq!(
+        std::fs::read_to_string(
+            cwd.join(DEFAULT_ENV_NAME)
+                .join("lib/kpar.allow-multiple-lib_1.0.0")
+                .join("TestLib.sysml")
+        )?,
+        "package TestLib2;"
+    );
+
     Ok(())
 }
 
@@ -514,3 +634,838 @@ fn env_install_multiple_projects_env_toml() -> Result<(), Box<dyn std::error::Er
 
     Ok(())
 }
+
+/// `sysand env install <IRI>...` resolves all projects together, so a
+/// dependency shared between them is installed once
+#[test]
+fn env_install_batch_shared_dependency() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["env"], None)?;
+    out.assert().success();
+
+    for (name, deps) in [
+        ("shared", &[][..]),
+        ("a", &["urn:kpar:shared"][..]),
+        ("b", &["urn:kpar:shared"][..]),
+        ("c", &[][..]),
+    ] {
+        let dir = cwd.join("libs").join(name);
+        std::fs::create_dir_all(&dir)?;
+        run_sysand_in(&dir, ["init", "--version", "1.0.0", "--name", name], None)?
+            .assert()
+            .success();
+        for dep in deps {
+            run_sysand_in(&dir, ["add", dep, "--no-lock"], None)?
+                .assert()
+                .success();
+        }
+    }
+    let config_path = cwd.join("sysand.toml");
+    std::fs::write(
+        &config_path,
+        ["shared", "a", "b", "c"]
+            .map(|name| {
+                format!(
+                    "[[project]]\nidentifiers = [\"urn:kpar:{name}\"]\n\
+                    sources = [{{ src_path = \"libs/{name}\" }}]\n"
+                )
+            })
+            .join("\n"),
+    )?;
+    std::fs::write(
+        cwd.join("projects.txt"),
+        "# Projects for the tool\n\nurn:kpar:c 1.0.0\n",
+    )?;
+
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "env",
+            "install",
+            "urn:kpar:a",
+            "urn:kpar:b",
+            "--from-file",
+            "projects.txt",
+            "--no-index",
+        ],
+        Some(config_path.as_str()),
+    )?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("`urn:kpar:shared` 1.0.0").count(1));
+
+    let out = run_sysand_in(&cwd, ["env", "list"], None)?;
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains("`urn:kpar:a` 1.0.0"))
+        .stdout(predicate::str::contains("`urn:kpar:b` 1.0.0"))
+        .stdout(predicate::str::contains("`urn:kpar:c` 1.0.0"))
+        .stdout(predicate::str::contains("`urn:kpar:shared` 1.0.0").count(1));
+
+    Ok(())
+}
+
+#[test]
+fn env_install_batch_invalid_requests() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["env"], None)?;
+    out.assert().success();
+
+    // A version is only accepted after a single IRI
+    let out = run_sysand_in(
+        &cwd,
+        ["env", "install", "urn:kpar:a", "urn:kpar:b", "1.0.0"],
+        None,
+    )?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid IRI `1.0.0`"));
+
+    std::fs::write(cwd.join("projects.txt"), "urn:kpar:a 2.0.0\n")?;
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "env",
+            "install",
+            "urn:kpar:a",
+            "1.0.0",
+            "--from-file",
+            "projects.txt",
+        ],
+        None,
+    )?;
+    out.assert().failure().stderr(predicate::str::contains(
+        "`urn:kpar:a` is requested with different versions: 1.0.0 and 2.0.0",
+    ));
+
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "env",
+            "install",
+            "urn:kpar:a",
+            "urn:kpar:b",
+            "--path",
+            "some/project",
+        ],
+        None,
+    )?;
+    out.assert().failure().stderr(predicate::str::contains(
+        "only a single project can be installed with `--path`",
+    ));
+
+    Ok(())
+}
+
+/// `sysand env install` should read projects missing from the local
+/// environment from the shared environments in `cache.shared_envs`
+#[test]
+fn env_install_from_shared_env() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["env"], None)?;
+    out.assert().success();
+
+    let mut server = Server::new();
+    let env_mock = server
+        .mock("GET", "/team/.sysand/env.toml")
+        .with_status(200)
+        .with_body(
+            "version = \"0.1\"\n\n[[project]]\nname = \"shared\"\nversion = \"1.0.0\"\n\
+            path = \"lib/shared_1.0.0\"\nidentifiers = [\"urn:kpar:shared\"]\n",
+        )
+        .expect(1)
+        .create();
+    let info_mock = server
+        .mock("GET", "/team/.sysand/lib/shared_1.0.0/.project.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"name":"shared","version":"1.0.0","usage":[]}"#)
+        .expect_at_least(1)
+        .create();
+    let meta_mock = server
+        .mock("GET", "/team/.sysand/lib/shared_1.0.0/.meta.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"index":{"Shared":"shared.sysml"},"created":"2026-01-01T00:00:00.000000000Z"}"#,
+        )
+        .expect_at_least(1)
+        .create();
+    let src_mock = server
+        .mock("GET", "/team/.sysand/lib/shared_1.0.0/shared.sysml")
+        .with_status(200)
+        .with_body("package Shared;\n")
+        .expect_at_least(1)
+        .create();
+
+    let config_path = cwd.join("sysand.toml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "[cache]\nshared_envs = [\"{}/team/.sysand\"]\n",
+            server.url()
+        ),
+    )?;
+
+    let out = run_sysand_in(
+        &cwd,
+        ["env", "install", "urn:kpar:shared", "--no-index"],
+        Some(config_path.as_str()),
+    )?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("`urn:kpar:shared` 1.0.0"));
+
+    env_mock.assert();
+    info_mock.assert();
+    meta_mock.assert();
+    src_mock.assert();
+    assert_eq!(
+        std::fs::read_to_string(
+            cwd.join(DEFAULT_ENV_NAME)
+                .join("lib")
+                .read_dir()?
+                .next()
+                .unwrap()?
+                .path()
+                .join("shared.sysml")
+        )?,
+        "package Shared;\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn env_install_metadata_only() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["env"], None)?;
+    out.assert().success();
+
+    let lib_dir = cwd.join("libs").join("a");
+    std::fs::create_dir_all(&lib_dir)?;
+    run_sysand_in(
+        &lib_dir,
+        ["init", "--version", "1.0.0", "--name", "a"],
+        None,
+    )?
+    .assert()
+    .success();
+    std::fs::write(lib_dir.join("A.sysml"), "package A;\n")?;
+    run_sysand_in(&lib_dir, ["include", "A.sysml"], None)?
+        .assert()
+        .success();
+    let config_path = cwd.join("sysand.toml");
+    std::fs::write(
+        &config_path,
+        "[[project]]\nidentifiers = [\"urn:kpar:a\"]\n\
+        sources = [{ src_path = \"libs/a\" }]\n",
+    )?;
+
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "env",
+            "install",
+            "urn:kpar:a",
+            "--metadata-only",
+            "--no-index",
+        ],
+        Some(config_path.as_str()),
+    )?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("(metadata only)"));
+
+    let env_toml = std::fs::read_to_string(cwd.join(DEFAULT_ENV_NAME).join(METADATA_PATH))?;
+    assert!(env_toml.contains("metadata_only = true"), "{env_toml}");
+    assert!(env_toml.contains("src_path = \"libs/a\""), "{env_toml}");
+    let path = env_toml
+        .lines()
+        .find_map(|l| l.strip_prefix("path = "))
+        .unwrap()
+        .trim_matches('"');
+    let installed = cwd.join(DEFAULT_ENV_NAME).join(path);
+    assert!(installed.join(".project.json").is_file());
+    assert!(!installed.join("A.sysml").exists());
+
+    // Sources are fetched when they are needed
+    let out = run_sysand_in(&cwd, ["env", "sources", "urn:kpar:a"], None)?;
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains("A.sysml"));
+    assert_eq!(
+        std::fs::read_to_string(installed.join("A.sysml"))?,
+        "package A;\n"
+    );
+
+    Ok(())
+}
+
+/// `sysand env doctor` should report broken projects and leftovers, and
+/// `--fix` should remove them
+#[test]
+fn env_doctor_repairs_env() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, _) = run_sysand(["env"], None)?;
+    let test_path = fixture_path("test_lib");
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "env",
+            "install",
+            "urn:kpar:test",
+            "--path",
+            test_path.as_str(),
+        ],
+        None,
+    )?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["env", "doctor"], None)?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("no problems found"));
+
+    let lib_path = cwd.join(DEFAULT_ENV_NAME).join("lib");
+    std::fs::remove_file(lib_path.join("kpar.test_0.0.1").join(".meta.json"))?;
+    std::fs::create_dir(lib_path.join("kpar.leftover_1.0.0"))?;
+
+    let out = run_sysand_in(&cwd, ["env", "doctor"], None)?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "project `urn:kpar:test` 0.0.1 at `lib/kpar.test_0.0.1` is broken: missing `.meta.json`",
+        ))
+        .stderr(predicate::str::contains(
+            "`lib/kpar.leftover_1.0.0` does not belong to any installed project",
+        ))
+        .stderr(predicate::str::contains("found 2 problem(s)"));
+
+    let out = run_sysand_in(&cwd, ["env", "doctor", "--fix"], None)?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("2 problem(s)"));
+    assert!(!lib_path.join("kpar.test_0.0.1").exists());
+    assert!(!lib_path.join("kpar.leftover_1.0.0").exists());
+    assert_eq!(
+        std::fs::read_to_string(cwd.join(DEFAULT_ENV_NAME).join(METADATA_PATH))?,
+        DEFAULT_ENV_FILE_CONTENTS
+    );
+
+    let out = run_sysand_in(&cwd, ["env", "doctor"], None)?;
+    out.assert().success();
+
+    Ok(())
+}
+
+/// Installed projects record where they came from, shown by
+/// `env list --verbose`, and changes to them are found by `verify --env`
+/// and `env doctor`
+#[test]
+fn env_install_records_provenance() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, _) = run_sysand(["env"], None)?;
+    let (_temp_lib, cwd_lib, out) =
+        run_sysand(["init", "--version", "1.0.0", "--name", "lib"], None)?;
+    out.assert().success();
+    std::fs::write(cwd_lib.join("Lib.sysml"), "package Lib;")?;
+    run_sysand_in(&cwd_lib, ["include", "Lib.sysml"], None)?
+        .assert()
+        .success();
+    run_sysand_in(
+        &cwd,
+        ["env", "install", "urn:kpar:lib", "--path", cwd_lib.as_str()],
+        None,
+    )?
+    .assert()
+    .success();
+
+    let out = run_sysand_in(&cwd, ["env", "list"], None)?;
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains("`urn:kpar:lib` 1.0.0"))
+        .stdout(predicate::str::contains("source:").not());
+    let out = run_sysand_in(&cwd, ["env", "list", "--verbose"], None)?;
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "source:       {{ src_path = \"{cwd_lib}\""
+        )))
+        .stdout(predicate::str::contains("installed at:"))
+        .stdout(predicate::str::contains("project hash:"));
+
+    run_sysand_in(&cwd, ["verify", "--env"], None)?
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Verified 1 project(s)"));
+
+    std::fs::write(
+        cwd.join(DEFAULT_ENV_NAME)
+            .join("lib/kpar.lib_1.0.0")
+            .join("Lib.sysml"),
+        "package Changed;",
+    )?;
+    run_sysand_in(&cwd, ["verify", "--env"], None)?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "`urn:kpar:lib` 1.0.0 was modified after it was installed",
+        ));
+    run_sysand_in(&cwd, ["env", "doctor"], None)?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "project `urn:kpar:lib` 1.0.0 at `lib/kpar.lib_1.0.0` was modified after it was installed",
+        ));
+
+    Ok(())
+}
+
+/// `sysand env export` should record the installed projects with their
+/// sources, and `sysand env import` should install exactly them elsewhere
+#[test]
+fn env_export_import() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, _) = run_sysand(["init", "--version", "1.0.0", "--name", "app"], None)?;
+    let iri = url::Url::from_file_path(fixture_path("test_lib"))
+        .unwrap()
+        .to_string();
+
+    let out = run_sysand_in(
+        &cwd,
+        ["env", "install", &iri, "--no-deps", "--no-index"],
+        None,
+    )?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["env", "export", "--no-index"], None)?;
+    let out = out.assert().success();
+    let export = String::from_utf8(out.get_output().stdout.clone())?;
+    let json: serde_json::Value = serde_json::from_str(&export)?;
+    let project = &json["projects"][0];
+    assert_eq!(project["identifiers"][0], iri.as_str());
+    assert_eq!(project["version"], "0.0.1");
+    assert_eq!(
+        project["src_cksum"],
+        "c83ef78e3b8d52d622dea6db2e7c6c326801500ae1e99f6a54c39a1f473367b3"
+    );
+    assert!(project["sources"][0]["src_path"].is_string());
+    std::fs::write(cwd.join("env.json"), &export)?;
+
+    let (_other_dir, other, _) = run_sysand(["env"], None)?;
+    let out = run_sysand_in(
+        &other,
+        ["env", "import", cwd.join("env.json").as_str()],
+        None,
+    )?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("Install"));
+
+    let out = run_sysand_in(&other, ["env", "list"], None)?;
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains(format!("`{iri}` 0.0.1")));
+
+    std::fs::write(
+        cwd.join("bad.json"),
+        export.replace("c83ef78e3b8d52d622", "000000000000000000"),
+    )?;
+    let (_bad_dir, bad, _) = run_sysand(["env"], None)?;
+    let out = run_sysand_in(&bad, ["env", "import", cwd.join("bad.json").as_str()], None)?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("incorrect checksum"));
+
+    Ok(())
+}
+
+#[test]
+fn env_install_kpar_storage() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, _) = run_sysand(["env"], None)?;
+    let cfg_path = cwd.join("storage.toml");
+    std::fs::write(&cfg_path, "[env]\nstorage = \"kpar\"\n")?;
+    let cfg = cfg_path.as_str();
+    let test_path = fixture_path("test_lib");
+
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "env",
+            "install",
+            "urn:kpar:test",
+            "--path",
+            test_path.as_str(),
+        ],
+        Some(cfg),
+    )?;
+    out.assert().success();
+
+    let project_dir = cwd.join(DEFAULT_ENV_NAME).join("lib/kpar.test_0.0.1");
+    assert!(project_dir.join("project.kpar").is_file());
+    assert!(project_dir.join(".project.json").is_file());
+    assert!(!project_dir.join("libtest.sysml").exists());
+    assert!(
+        std::fs::read_to_string(cwd.join(DEFAULT_ENV_NAME).join(METADATA_PATH))?
+            .contains("archived = true")
+    );
+
+    let out = run_sysand_in(&cwd, ["env", "sources", "urn:kpar:test"], Some(cfg))?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("--materialize"));
+
+    let out = run_sysand_in(
+        &cwd,
+        ["env", "sources", "urn:kpar:test", "--materialize"],
+        Some(cfg),
+    )?;
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains("libtest.sysml"));
+    assert!(project_dir.join("libtest.sysml").is_file());
+
+    Ok(())
+}
+
+#[test]
+fn env_install_encrypted_storage() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, _) = run_sysand(["env"], None)?;
+    let cfg_path = cwd.join("storage.toml");
+    std::fs::write(&cfg_path, "[env]\nstorage = \"encrypted\"\n")?;
+    let cfg = cfg_path.as_str();
+    let test_path = fixture_path("test_lib");
+    let install = [
+        "env",
+        "install",
+        "urn:kpar:test",
+        "--path",
+        test_path.as_str(),
+    ];
+
+    let out = run_sysand_in(&cwd, install, Some(cfg))?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("no encryption key is set"));
+
+    std::fs::write(
+        &cfg_path,
+        "[env]\nstorage = \"encrypted\"\nkey_file = \"env.key\"\n",
+    )?;
+    std::fs::write(cwd.join("env.key"), "ab".repeat(32))?;
+    let out = run_sysand_in(&cwd, install, Some(cfg))?;
+    out.assert().success();
+
+    let project_dir = cwd.join(DEFAULT_ENV_NAME).join("lib/kpar.test_0.0.1");
+    let plain = std::fs::read(test_path.join("libtest.sysml"))?;
+    let stored = std::fs::read(project_dir.join("libtest.sysml"))?;
+    assert!(stored.starts_with(b"SYSENC1\0"));
+    assert!(!stored.windows(plain.len()).any(|w| w == plain));
+    assert!(project_dir.join(".project.json").is_file());
+    assert!(
+        std::fs::read_to_string(cwd.join(DEFAULT_ENV_NAME).join(METADATA_PATH))?
+            .contains("encrypted = true")
+    );
+
+    let out = run_sysand_in(&cwd, ["env", "sources", "urn:kpar:test"], Some(cfg))?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("stored encrypted"));
+
+    Ok(())
+}
+
+#[test]
+fn env_install_from_kpar_with_several_projects() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    let kpar = cwd.join("several.kpar");
+    write_multi_project_kpar(&kpar, &["first", "second"])?;
+
+    run_sysand_in(
+        &cwd,
+        ["env", "install", "urn:kpar:second", "--path", kpar.as_str()],
+        None,
+    )?
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("contains several projects"));
+
+    run_sysand_in(
+        &cwd,
+        [
+            "env",
+            "install",
+            "urn:kpar:second",
+            "--path",
+            kpar.as_str(),
+            "--project",
+            "second",
+        ],
+        None,
+    )?
+    .assert()
+    .success();
+
+    run_sysand_in(&cwd, ["env", "list"], None)?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("urn:kpar:second"));
+    run_sysand_in(&cwd, ["env", "sources", "urn:kpar:second"], None)?
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("second.sysml"));
+
+    Ok(())
+}
+
+/// `env install --from-lock` installs locked projects from their locked
+/// sources, with or without their dependencies
+#[test]
+fn env_install_from_lock() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "main", "--version", "1.0.0"], None)?;
+    out.assert().success();
+    for name in ["dep", "lib"] {
+        let out = run_sysand_in(
+            &cwd,
+            ["init", "--name", name, "--version", "1.0.0", name],
+            None,
+        )?;
+        out.assert().success();
+    }
+    let out = run_sysand_in(&cwd.join("dep"), ["add", "urn:kpar:lib", "--no-lock"], None)?;
+    out.assert().success();
+    let out = run_sysand_in(&cwd, ["add", "urn:kpar:dep", "--no-lock"], None)?;
+    out.assert().success();
+    let cfg_path = cwd.join("sysand.toml");
+    std::fs::write(
+        &cfg_path,
+        "[[project]]\n\
+        identifiers = [\"urn:kpar:dep\"]\n\
+        sources = [{ src_path = \"dep\" }]\n\n\
+        [[project]]\n\
+        identifiers = [\"urn:kpar:lib\"]\n\
+        sources = [{ src_path = \"lib\" }]\n",
+    )?;
+
+    // Nothing to install from yet
+    let out = run_sysand_in(
+        &cwd,
+        ["env", "install", "--from-lock", "urn:kpar:dep"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("run `sysand lock` first"));
+
+    let out = run_sysand_in(&cwd, ["lock"], Some(cfg_path.as_str()))?;
+    out.assert().success();
+    let lib_path = cwd.join(DEFAULT_ENV_NAME).join("lib");
+
+    let out = run_sysand_in(
+        &cwd,
+        ["env", "install", "--from-lock", "urn:kpar:dep", "--no-deps"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().success();
+    assert!(lib_path.join("kpar.dep_1.0.0").is_dir());
+    assert!(!lib_path.join("kpar.lib_1.0.0").exists());
+
+    let out = run_sysand_in(
+        &cwd,
+        ["env", "install", "--from-lock", "urn:kpar:dep"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().success();
+    assert!(lib_path.join("kpar.lib_1.0.0").is_dir());
+
+    let out = run_sysand_in(
+        &cwd,
+        ["env", "install", "--from-lock", "urn:kpar:other"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().failure().stderr(predicate::str::contains(
+        "no project with IRI `urn:kpar:other` in lockfile",
+    ));
+
+    Ok(())
+}
+
+/// `env uninstall --with-orphans` also removes the dependencies that no
+/// remaining project uses, unless the lockfile locks them
+#[test]
+fn env_uninstall_with_orphans() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "main", "--version", "1.0.0"], None)?;
+    out.assert().success();
+    for name in ["dep", "lib"] {
+        let out = run_sysand_in(
+            &cwd,
+            ["init", "--name", name, "--version", "1.0.0", name],
+            None,
+        )?;
+        out.assert().success();
+    }
+    let out = run_sysand_in(&cwd.join("dep"), ["add", "urn:kpar:lib", "--no-lock"], None)?;
+    out.assert().success();
+    let out = run_sysand_in(&cwd, ["add", "urn:kpar:dep", "--no-lock"], None)?;
+    out.assert().success();
+    let cfg_path = cwd.join("sysand.toml");
+    std::fs::write(
+        &cfg_path,
+        "[[project]]\n\
+        identifiers = [\"urn:kpar:dep\"]\n\
+        sources = [{ src_path = \"dep\" }]\n\n\
+        [[project]]\n\
+        identifiers = [\"urn:kpar:lib\"]\n\
+        sources = [{ src_path = \"lib\" }]\n",
+    )?;
+    let lib_path = cwd.join(DEFAULT_ENV_NAME).join("lib");
+
+    let out = run_sysand_in(&cwd, ["sync"], Some(cfg_path.as_str()))?;
+    out.assert().success();
+    let out = run_sysand_in(
+        &cwd,
+        ["env", "uninstall", "urn:kpar:dep", "--with-orphans"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().success().stderr(predicate::str::contains(
+        "`urn:kpar:dep` is still used by `main` 1.0.0",
+    ));
+    assert!(!lib_path.join("kpar.dep_1.0.0").exists());
+    // Locked in the lockfile
+    assert!(lib_path.join("kpar.lib_1.0.0").is_dir());
+
+    let out = run_sysand_in(&cwd, ["sync"], Some(cfg_path.as_str()))?;
+    out.assert().success();
+    std::fs::remove_file(cwd.join("sysand-lock.toml"))?;
+    let out = run_sysand_in(
+        &cwd,
+        ["env", "uninstall", "urn:kpar:dep", "--with-orphans"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().success();
+    assert!(!lib_path.join("kpar.dep_1.0.0").exists());
+    assert!(!lib_path.join("kpar.lib_1.0.0").exists());
+
+    Ok(())
+}
+
+/// `env gc` removes the installed projects that the lockfile does not lock
+#[test]
+fn env_gc_removes_unlocked_projects() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "main", "--version", "1.0.0"], None)?;
+    out.assert().success();
+    for name in ["dep", "extra"] {
+        let out = run_sysand_in(
+            &cwd,
+            ["init", "--name", name, "--version", "1.0.0", name],
+            None,
+        )?;
+        out.assert().success();
+    }
+    let out = run_sysand_in(&cwd, ["add", "urn:kpar:dep", "--no-lock"], None)?;
+    out.assert().success();
+    let cfg_path = cwd.join("sysand.toml");
+    std::fs::write(
+        &cfg_path,
+        "[[project]]\n\
+        identifiers = [\"urn:kpar:dep\"]\n\
+        sources = [{ src_path = \"dep\" }]\n\n\
+        [[project]]\n\
+        identifiers = [\"urn:kpar:extra\"]\n\
+        sources = [{ src_path = \"extra\" }]\n",
+    )?;
+    let lib_path = cwd.join(DEFAULT_ENV_NAME).join("lib");
+
+    let out = run_sysand_in(&cwd, ["sync"], Some(cfg_path.as_str()))?;
+    out.assert().success();
+    let out = run_sysand_in(
+        &cwd,
+        ["env", "install", "urn:kpar:extra"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().success();
+    assert!(lib_path.join("kpar.extra_1.0.0").is_dir());
+
+    let out = run_sysand_in(&cwd, ["env", "gc", "--dry-run"], Some(cfg_path.as_str()))?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Would remove `urn:kpar:extra` 1.0.0",
+        ))
+        .stderr(predicate::str::contains("Would remove 1 project(s)"));
+    assert!(lib_path.join("kpar.extra_1.0.0").is_dir());
+
+    let out = run_sysand_in(&cwd, ["env", "gc"], Some(cfg_path.as_str()))?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("Removed 1 project(s)"));
+    assert!(!lib_path.join("kpar.extra_1.0.0").exists());
+    assert!(lib_path.join("kpar.dep_1.0.0").is_dir());
+
+    let out = run_sysand_in(&cwd, ["env", "list"], Some(cfg_path.as_str()))?;
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains("extra").not());
+
+    Ok(())
+}
+
+#[test]
+fn env_gc_keeps_projects_locked_by_other_workspace_members()
+-> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) =
+        run_sysand(["init", "--name", "a", "--version", "1.0.0", "a"], None)?;
+    out.assert().success();
+    for name in ["b", "extra"] {
+        let out = run_sysand_in(
+            &cwd,
+            ["init", "--name", name, "--version", "1.0.0", name],
+            None,
+        )?;
+        out.assert().success();
+    }
+    std::fs::write(
+        cwd.join(".workspace.json"),
+        r#"{"projects": [
+            {"path": "one", "iris": ["urn:kpar:one"]},
+            {"path": "two", "iris": ["urn:kpar:two"]}
+        ]}"#,
+    )?;
+    let cfg_path = cwd.join("sysand.toml");
+    let mut cfg = String::new();
+    for name in ["a", "b", "extra"] {
+        cfg += &format!(
+            "[[project]]\n\
+            identifiers = [\"urn:kpar:{name}\"]\n\
+            sources = [{{ src_path = \"../{name}\" }}]\n\n"
+        );
+    }
+    std::fs::write(&cfg_path, cfg)?;
+    for (member, usage) in [("one", "urn:kpar:a"), ("two", "urn:kpar:b")] {
+        let out = run_sysand_in(&cwd, ["init", "--version", "1.0.0", member], None)?;
+        out.assert().success();
+        let out = run_sysand_in(
+            &cwd.join(member),
+            ["add", usage, "--no-lock"],
+            Some(cfg_path.as_str()),
+        )?;
+        out.assert().success();
+        let out = run_sysand_in(&cwd.join(member), ["sync"], Some(cfg_path.as_str()))?;
+        out.assert().success();
+    }
+    let out = run_sysand_in(
+        &cwd.join("one"),
+        ["env", "install", "urn:kpar:extra"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().success();
+    let lib_path = cwd.join(DEFAULT_ENV_NAME).join("lib");
+    assert!(lib_path.join("kpar.b_1.0.0").is_dir());
+    assert!(lib_path.join("kpar.extra_1.0.0").is_dir());
+
+    // `one` does not lock `b`, but `two` does
+    let out = run_sysand_in(&cwd.join("one"), ["env", "gc"], Some(cfg_path.as_str()))?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("Removing `urn:kpar:extra` 1.0.0"))
+        .stderr(predicate::str::contains("Removed 1 project(s)"));
+    assert!(!lib_path.join("kpar.extra_1.0.0").exists());
+    assert!(lib_path.join("kpar.a_1.0.0").is_dir());
+    assert!(lib_path.join("kpar.b_1.0.0").is_dir());
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+fn json_lines(stderr: &[u8]) -> Vec<serde_json::Value> {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("stderr line is not JSON"))
+        .collect()
+}
+
+#[test]
+fn human_error_has_code() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, _cwd, out) = run_sysand(["include", "test.sysml"], None)?;
+
+    out.assert().code(3).stderr(predicate::str::contains(
+        "error[SYSAND-E1907]: unable to find interchange project in current directory",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn json_error() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, _cwd, out) =
+        run_sysand(["include", "test.sysml", "--error-format", "json"], None)?;
+
+    assert_eq!(out.status.code(), Some(3));
+    let diagnostics = json_lines(&out.stderr);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["level"], "error");
+    assert_eq!(diagnostics[0]["code"], "SYSAND-E1907");
+    assert_eq!(diagnostics[0]["class"], "project");
+    assert_eq!(diagnostics[0]["exit_code"], 3);
+    assert_eq!(
+        diagnostics[0]["message"],
+        "unable to find interchange project in current directory"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn json_error_from_core() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "json_errors"], None)?;
+    out.assert().success();
+
+    let out = run_sysand_in(
+        &cwd,
+        ["init", "--name", "json_errors", "--error-format=json"],
+        None,
+    )?;
+
+    assert!(!out.status.success());
+    let diagnostics = json_lines(&out.stderr);
+    let error = diagnostics.last().unwrap();
+    assert_eq!(error["level"], "error");
+    assert_eq!(error["code"], "SYSAND-E0102");
+    assert_eq!(
+        out.status.code(),
+        error["exit_code"].as_i64().map(|c| c as i32)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn json_usage_error() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, _cwd, out) = run_sysand(["--bogus", "--error-format", "json"], None)?;
+
+    assert_eq!(out.status.code(), Some(2));
+    let diagnostics = json_lines(&out.stderr);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["code"], "SYSAND-E0002");
+    assert_eq!(diagnostics[0]["class"], "usage");
+
+    Ok(())
+}
+
+#[test]
+fn json_help_is_not_error() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, _cwd, out) = run_sysand(["--help", "--error-format", "json"], None)?;
+
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains("Usage"));
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use sysand_core::{commands::lock::DEFAULT_LOCKFILE_NAME, lock::CURRENT_LOCK_VERSION};
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+const DIGEST: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+#[test]
+fn export_bazel() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "export_bazel"], None)?;
+    out.assert().success();
+    std::fs::write(
+        cwd.join(DEFAULT_LOCKFILE_NAME),
+        format!(
+            r#"lock_version = "{CURRENT_LOCK_VERSION}"
+
+[[project]]
+name = "export_bazel"
+version = "0.0.1"
+usages = ["urn:kpar:lib"]
+sources = [{{ editable = "." }}]
+
+[[project]]
+name = "lib"
+version = "1.2.3"
+identifiers = ["urn:kpar:lib"]
+sources = [{{ remote_kpar = "https://example.com/lib.kpar", kpar_size = 10, kpar_digest = "{DIGEST}" }}]
+"#
+        ),
+    )?;
+
+    let out = run_sysand_in(&cwd, ["export", "--format", "bazel"], None)?;
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains("SYSAND_DEPENDENCIES = ["))
+        .stdout(predicate::str::contains(
+            r#""url": "https://example.com/lib.kpar","#,
+        ))
+        .stdout(predicate::str::contains(format!(
+            r#""sha256": "{DIGEST}","#
+        )))
+        .stdout(predicate::str::contains("export_bazel").not());
+
+    let out = run_sysand_in(
+        &cwd,
+        ["export", "--format", "bazel", "--output", "deps.bzl"],
+        None,
+    )?;
+    out.assert().success().stdout(predicate::str::is_empty());
+    let manifest = std::fs::read_to_string(cwd.join("deps.bzl"))?;
+    assert!(manifest.contains(r#""repository": "sysand_lib","#));
+
+    Ok(())
+}
+
+#[test]
+fn export_nix() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "export_nix"], None)?;
+    out.assert().success();
+    std::fs::write(
+        cwd.join(DEFAULT_LOCKFILE_NAME),
+        format!(
+            r#"lock_version = "{CURRENT_LOCK_VERSION}"
+
+[[project]]
+name = "export_nix"
+version = "0.0.1"
+usages = ["urn:kpar:lib"]
+sources = [{{ editable = "." }}]
+
+[[project]]
+name = "lib"
+version = "1.2.3"
+identifiers = ["urn:kpar:lib"]
+sources = [{{ remote_kpar = "https://example.com/lib.kpar", kpar_size = 10, kpar_digest = "{DIGEST}" }}]
+"#
+        ),
+    )?;
+
+    let out = run_sysand_in(&cwd, ["export", "--format", "nix"], None)?;
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains(r#""sysand_lib" = {"#))
+        .stdout(predicate::str::contains(
+            r#"url = "https://example.com/lib.kpar";"#,
+        ))
+        .stdout(predicate::str::contains(format!(r#"sha256 = "{DIGEST}";"#)))
+        .stdout(predicate::str::contains("sysand_env = "))
+        .stdout(predicate::str::contains("export_nix").not());
+
+    Ok(())
+}
+
+#[test]
+fn export_without_lockfile() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "export_no_lock"], None)?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["export", "--format", "bazel"], None)?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("run `sysand lock`"));
+
+    Ok(())
+}
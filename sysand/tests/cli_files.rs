@@ -0,0 +1,53 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use assert_cmd::prelude::*;
+use predicates::str::contains;
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+#[test]
+fn files_classifies_project_files() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) =
+        run_sysand(["init", "--name", "files", "--version", "1.0.0"], None)?;
+    out.assert().success();
+    std::fs::write(cwd.join("main.sysml"), "package Main;\n")?;
+    std::fs::write(cwd.join("draft.sysml"), "package Draft;\n")?;
+    std::fs::write(cwd.join("notes.txt"), "notes\n")?;
+    let out = run_sysand_in(&cwd, ["include", "main.sysml"], None)?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["files"], None)?;
+    out.assert()
+        .success()
+        .stdout(contains("PATH"))
+        .stdout(contains(
+            ".project.json  metadata  project information/metadata",
+        ))
+        .stdout(contains(
+            "draft.sysml    unknown   not listed in `.meta.json`",
+        ))
+        .stdout(contains("main.sysml     source    listed in `.meta.json`"))
+        .stdout(contains(
+            "notes.txt      ignored   not a SysML v2 (`.sysml`) or KerML (`.kerml`) file",
+        ));
+
+    std::fs::write(
+        cwd.join("sysand.toml"),
+        "[build]\ninclude = [\"*.sysml\"]\nexclude = [\"draft.sysml\"]\n",
+    )?;
+    let cfg = Some(cwd.join("sysand.toml").into_string());
+    let out = run_sysand_in(&cwd, ["files"], cfg.as_deref())?;
+    out.assert()
+        .success()
+        .stdout(contains(
+            "draft.sysml    ignored   matches exclude glob `draft.sysml`",
+        ))
+        .stdout(contains(
+            "main.sysml     source    matches include glob `*.sysml`",
+        ));
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use assert_cmd::prelude::*;
+use predicates::str::contains;
+use serde_json::json;
+use sysand_core::config::{self, ConfigProject, OverrideSource};
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+/// Write a configuration file overriding `urn:kpar:lib` with the project
+/// in `lib`, optionally enabling `workspace.shared_lock`
+fn write_config(
+    cwd: &camino::Utf8Path,
+    shared_lock: bool,
+) -> Result<camino::Utf8PathBuf, Box<dyn std::error::Error>> {
+    let cfg = toml::to_string(&config::Config {
+        projects: vec![ConfigProject {
+            identifiers: vec!["urn:kpar:lib".to_string()],
+            sources: vec![OverrideSource::LocalSrc {
+                src_path: "lib".into(),
+            }],
+        }],
+        workspace: config::WorkspaceConfig {
+            shared_lock: shared_lock.then_some(true),
+        },
+        ..Default::default()
+    })?;
+    let cfg_path = cwd.join(config::local_fs::CONFIG_FILE);
+    std::fs::write(&cfg_path, cfg)?;
+    Ok(cfg_path)
+}
+
+#[test]
+fn graph_dot_and_mermaid() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "app", "--version", "1.2.3"], None)?;
+    out.assert().success();
+    let out = run_sysand_in(&cwd, ["init", "--version", "1.0.0", "lib"], None)?;
+    out.assert().success();
+    let cfg_path = write_config(&cwd, false)?;
+
+    let out = run_sysand_in(&cwd, ["graph"], None)?;
+    out.assert()
+        .failure()
+        .stderr(contains("run `sysand lock` to create it"));
+
+    let out = run_sysand_in(&cwd, ["add", "urn:kpar:lib", "^1"], Some(cfg_path.as_str()))?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["graph"], None)?;
+    out.assert().success().stdout(
+        "digraph dependencies {\n    \
+             rankdir=LR;\n    \
+             n0 [label=\"app@1.2.3\"];\n    \
+             n1 [label=\"urn:kpar:lib@1.0.0\"];\n    \
+             n0 -> n1 [label=\"^1\"];\n\
+         }\n",
+    );
+
+    let out = run_sysand_in(&cwd, ["graph", "--format", "mermaid"], None)?;
+    out.assert().success().stdout(
+        "flowchart LR\n    \
+             n0[\"app@1.2.3\"]\n    \
+             n1[\"urn:kpar:lib@1.0.0\"]\n    \
+             n0 -->|\"^1\"| n1\n",
+    );
+
+    Ok(())
+}
+
+#[test]
+fn graph_clustered_by_workspace_member() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    std::fs::write(
+        cwd.join(".workspace.json"),
+        json!({"projects": [
+            {"path": "project1", "iris": ["urn:kpar:project1"]},
+            {"path": "project2", "iris": ["urn:kpar:project2"]}
+        ]})
+        .to_string(),
+    )?;
+    for name in ["lib", "project1", "project2"] {
+        let out = run_sysand_in(&cwd, ["init", "--version", "1.0.0", name], None)?;
+        out.assert().success();
+    }
+    let out = run_sysand_in(
+        &cwd.join("project1"),
+        ["add", "urn:kpar:lib", "^1", "--no-lock"],
+        None,
+    )?;
+    out.assert().success();
+    let cfg_path = write_config(&cwd, true)?;
+
+    let out = run_sysand_in(&cwd.join("project1"), ["lock"], Some(cfg_path.as_str()))?;
+    out.assert().success();
+
+    let out = run_sysand_in(
+        &cwd.join("project2"),
+        ["graph", "--cluster"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().success().stdout(
+        "digraph dependencies {\n    \
+             rankdir=LR;\n    \
+             subgraph cluster_0 {\n        \
+                 label=\"urn:kpar:project1@1.0.0\";\n        \
+                 n0 [label=\"urn:kpar:lib@1.0.0\"];\n        \
+                 n1 [label=\"urn:kpar:project1@1.0.0\"];\n    \
+             }\n    \
+             subgraph cluster_1 {\n        \
+                 label=\"urn:kpar:project2@1.0.0\";\n        \
+                 n2 [label=\"urn:kpar:project2@1.0.0\"];\n    \
+             }\n    \
+             n1 -> n0 [label=\"^1\"];\n\
+         }\n",
+    );
+
+    Ok(())
+}
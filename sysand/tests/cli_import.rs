@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use assert_cmd::prelude::*;
+use predicates::str::contains;
+use sysand_core::model::{InterchangeProjectInfoRaw, InterchangeProjectUsageRaw};
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+#[test]
+fn import_jupyter_dependencies() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) =
+        run_sysand(["init", "--name", "import", "--version", "1.0.0"], None)?;
+    out.assert().success();
+    std::fs::write(
+        cwd.join("model.ipynb"),
+        r#"{"cells": [{"cell_type": "code", "source": ["%dependencies urn:kpar:a\n", "%dependencies urn:kpar:b 1.2.0\n"]}]}"#,
+    )?;
+
+    let out = run_sysand_in(&cwd, ["import", "jupyter", "model.ipynb"], None)?;
+    out.assert()
+        .success()
+        .stderr(contains("2 usage(s) from jupyter `model.ipynb`"));
+    let info: InterchangeProjectInfoRaw =
+        serde_json::from_str(&std::fs::read_to_string(cwd.join(".project.json"))?)?;
+    assert_eq!(
+        info.usage,
+        [
+            InterchangeProjectUsageRaw::Resource {
+                resource: "urn:kpar:a".into(),
+                version_constraint: None,
+                when: None,
+                hash: None,
+            },
+            InterchangeProjectUsageRaw::Resource {
+                resource: "urn:kpar:b".into(),
+                version_constraint: Some("^1.2.0".into()),
+                when: None,
+                hash: None,
+            },
+        ]
+    );
+
+    let out = run_sysand_in(&cwd, ["import", "jupyter", "model.ipynb"], None)?;
+    out.assert().success().stderr(contains("0 usage(s)"));
+
+    Ok(())
+}
+
+#[test]
+fn import_invalid_notebook() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) =
+        run_sysand(["init", "--name", "import", "--version", "1.0.0"], None)?;
+    out.assert().success();
+    std::fs::write(cwd.join("model.ipynb"), "not json")?;
+
+    let out = run_sysand_in(&cwd, ["import", "jupyter", "model.ipynb"], None)?;
+    out.assert()
+        .failure()
+        .stderr(contains("SYSAND-E2501"))
+        .stderr(contains(
+            "failed to parse jupyter dependencies in `model.ipynb`",
+        ));
+
+    Ok(())
+}
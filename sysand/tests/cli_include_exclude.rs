@@ -5,8 +5,10 @@ use std::{fs, io::Write};
 
 use assert_cmd::prelude::*;
 use indexmap::IndexMap;
+use predicates::prelude::PredicateBooleanExt;
 use sysand_core::model::{
-    InterchangeProjectChecksumRaw, InterchangeProjectMetadataRaw, KerMlChecksumAlg,
+    InterchangeProjectChecksumRaw, InterchangeProjectInfoRaw, InterchangeProjectMetadataRaw,
+    InterchangeProjectUsageRaw, KerMlChecksumAlg,
 };
 // use predicates::prelude::*;
 
@@ -524,3 +526,151 @@ fn exclude_nonexistent() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn include_checksum_algorithm() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        [
+            "init",
+            "--version",
+            "1.2.3",
+            "--name",
+            "include_and_exclude",
+        ],
+        None,
+    )?;
+
+    fs::write(cwd.join("test.sysml"), b"package P;\n")?;
+
+    out.assert().success();
+
+    let out = run_sysand_in(
+        &cwd,
+        ["include", "test.sysml", "--checksum-algorithm", "sha512"],
+        None,
+    )?;
+
+    out.assert().success();
+
+    let meta: InterchangeProjectMetadataRaw =
+        serde_json::from_reader(fs::File::open(cwd.join(".meta.json"))?)?;
+
+    assert_eq!(
+        meta.checksum.unwrap(),
+        IndexMap::from([(
+            "test.sysml".to_string(),
+            InterchangeProjectChecksumRaw {
+                value: "9baefaaf63ca3b97e63b54183b92af0b9929947e8fb2778181ea877bbedb388e\
+                        8ff946730b19f69097674a8c12208e4796b217a57bb0a21eebd60d289c668537"
+                    .to_string(),
+                algorithm: KerMlChecksumAlg::Sha512.into(),
+            }
+        ),])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn include_std_usages() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        ["init", "--version", "1.0.0", "--name", "include_std_usages"],
+        None,
+    )?;
+    out.assert().success();
+    fs::write(
+        cwd.join("test.sysml"),
+        b"package P {\n    private import ISQ::*;\n}\n",
+    )?;
+
+    let out = run_sysand_in(&cwd, ["include", "test.sysml"], None)?;
+    out.assert()
+        .success()
+        .stderr(predicates::str::contains(
+            "refer to standard library IRI `https://www.omg.org/spec/SysML/20250201/Quantities-and-Units-Domain-Library.kpar` (2.0.0)",
+        ));
+    let info: InterchangeProjectInfoRaw =
+        serde_json::from_reader(fs::File::open(cwd.join(".project.json"))?)?;
+    assert!(info.usage.is_empty());
+
+    let out = run_sysand_in(&cwd, ["include", "test.sysml", "--add-std-usages"], None)?;
+    out.assert().success();
+    let info: InterchangeProjectInfoRaw =
+        serde_json::from_reader(fs::File::open(cwd.join(".project.json"))?)?;
+    assert_eq!(
+        info.usage,
+        [InterchangeProjectUsageRaw::Resource {
+            resource:
+                "https://www.omg.org/spec/SysML/20250201/Quantities-and-Units-Domain-Library.kpar"
+                    .into(),
+            version_constraint: Some("^2.0.0".into()),
+            when: None,
+            hash: None,
+        }]
+    );
+
+    let out = run_sysand_in(&cwd, ["include", "test.sysml"], None)?;
+    out.assert()
+        .success()
+        .stderr(predicates::str::contains("standard library").not());
+
+    Ok(())
+}
+
+#[test]
+fn mv_included_file() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--version", "1.2.3", "--name", "mv"], None)?;
+    out.assert().success();
+    fs::write(cwd.join("test.sysml"), b"package P;\n")?;
+    let out = run_sysand_in(&cwd, ["include", "test.sysml", "--compute-checksum"], None)?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["mv", "test.sysml", "models/test.sysml"], None)?;
+    out.assert().success();
+
+    assert!(!cwd.join("test.sysml").exists());
+    assert_eq!(fs::read(cwd.join("models/test.sysml"))?, b"package P;\n");
+    let meta: InterchangeProjectMetadataRaw =
+        serde_json::from_reader(fs::File::open(cwd.join(".meta.json"))?)?;
+    assert_eq!(
+        meta.index,
+        IndexMap::from([("P".to_string(), "models/test.sysml".to_string())])
+    );
+    let checksum = meta.checksum.unwrap();
+    assert_eq!(checksum.keys().collect::<Vec<_>>(), ["models/test.sysml"]);
+
+    fs::write(cwd.join("other.sysml"), b"package Q;\n")?;
+    let out = run_sysand_in(&cwd, ["mv", "other.sysml", "models/other.sysml"], None)?;
+    out.assert().failure().stderr(predicates::str::contains(
+        "could not find file `other.sysml` in project metadata",
+    ));
+    assert!(cwd.join("other.sysml").exists());
+
+    Ok(())
+}
+
+#[test]
+fn mv_completes_interrupted_move() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--version", "1.2.3", "--name", "mv"], None)?;
+    out.assert().success();
+    fs::write(cwd.join("test.sysml"), b"package P;\n")?;
+    let out = run_sysand_in(&cwd, ["include", "test.sysml"], None)?;
+    out.assert().success();
+
+    // Interrupted after moving the file, before writing `.meta.json`
+    fs::create_dir(cwd.join("models"))?;
+    fs::rename(cwd.join("test.sysml"), cwd.join("models/test.sysml"))?;
+
+    let out = run_sysand_in(&cwd, ["mv", "test.sysml", "models/test.sysml"], None)?;
+    out.assert().success();
+
+    assert_eq!(fs::read(cwd.join("models/test.sysml"))?, b"package P;\n");
+    let meta: InterchangeProjectMetadataRaw =
+        serde_json::from_reader(fs::File::open(cwd.join(".meta.json"))?)?;
+    assert_eq!(
+        meta.index,
+        IndexMap::from([("P".to_string(), "models/test.sysml".to_string())])
+    );
+
+    Ok(())
+}
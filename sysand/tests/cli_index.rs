@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use assert_cmd::prelude::*;
+use camino::Utf8Path;
+use predicates::prelude::*;
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+fn build_kpar(
+    cwd: &Utf8Path,
+    dir: &str,
+    publisher: &str,
+    version: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    run_sysand_in(
+        cwd,
+        [
+            "init",
+            dir,
+            "--name",
+            "lib",
+            "--publisher",
+            publisher,
+            "--version",
+            version,
+        ],
+        None,
+    )?
+    .assert()
+    .success();
+    let kpar = cwd.join(format!("{dir}.kpar"));
+    run_sysand_in(&cwd.join(dir), ["build", kpar.as_str()], None)?
+        .assert()
+        .success();
+    Ok(kpar.into_string())
+}
+
+#[test]
+fn index_add_kpar_urn() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    let first = build_kpar(&cwd, "first", "acme", "1.0.0")?;
+    let second = build_kpar(&cwd, "second", "acme", "1.1.0")?;
+    let other = build_kpar(&cwd, "other", "globex", "2.0.0")?;
+    run_sysand_in(&cwd, ["index", "init", "--index-root", "index"], None)?
+        .assert()
+        .success();
+    let add = |iri: &str, kpar: &str| {
+        run_sysand_in(
+            &cwd,
+            [
+                "index",
+                "add",
+                iri,
+                "--kpar-path",
+                kpar,
+                "--index-root",
+                "index",
+            ],
+            None,
+        )
+    };
+
+    add("urn:kpar:lib", &first)?.assert().success();
+    add("URN:KPAR:lib", &second)?.assert().success();
+    assert!(cwd.join("index/_kpar/lib/1.1.0/project.kpar").is_file());
+    let index = std::fs::read_to_string(cwd.join("index/index.json"))?;
+    assert_eq!(index.matches("urn:kpar:lib").count(), 1);
+
+    add("urn:kpar:lib", &other)?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "already used by a project of publisher `acme`",
+        ));
+    add("urn:kpar:Lib", &other)?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("differs only in case"));
+    add("urn:kpar:-lib", &other)?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("malformed `urn:kpar` IRI"));
+
+    Ok(())
+}
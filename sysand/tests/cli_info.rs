@@ -137,6 +137,40 @@ fn info_basic_path_auto() -> Result<(), Box<dyn Error>> {
     info_basic(false, true)
 }
 
+#[test]
+fn info_kpar_with_several_projects() -> Result<(), Box<dyn Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    let kpar = cwd.join("several.kpar");
+    write_multi_project_kpar(&kpar, &["first", "second"])?;
+
+    run_sysand_in(&cwd, ["info", "--path", kpar.as_str()], None)?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("contains several projects"))
+        .stderr(predicate::str::contains("`first` in `first`"))
+        .stderr(predicate::str::contains("`second` in `second`"));
+
+    run_sysand_in(
+        &cwd,
+        ["info", "--path", kpar.as_str(), "--project", "second"],
+        None,
+    )?
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Name: second"));
+
+    run_sysand_in(
+        &cwd,
+        ["info", "--path", kpar.as_str(), "--project", "third"],
+        None,
+    )?
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains("no project named `third`"));
+
+    Ok(())
+}
+
 #[test]
 fn info_basic_iri_explicit() -> Result<(), Box<dyn Error>> {
     info_basic(true, false)
@@ -194,7 +228,7 @@ fn info_basic_http_url_noauth() -> Result<(), Box<dyn Error>> {
         .with_status(200)
         .with_header("content-type", "application/json")
         .with_body(r#"{"index":{},"created":"0000-00-00T00:00:00.123456789Z"}"#)
-        .expect(3) // TODO: Reduce this to 1
+        .expect(1)
         .create();
 
     let (_, _, out) = run_sysand(["info", "--iri", &server.url()], None)?;
@@ -266,7 +300,7 @@ fn info_basic_http_url_irrelevant_auth() -> Result<(), Box<dyn Error>> {
         .with_status(200)
         .with_header("content-type", "application/json")
         .with_body(r#"{"index":{},"created":"0000-00-00T00:00:00.123456789Z"}"#)
-        .expect(3) // TODO: Reduce this to 1
+        .expect(1)
         .create();
 
     let (_, _, out) = run_sysand_with(
@@ -403,7 +437,7 @@ fn info_basic_http_url_auth() -> Result<(), Box<dyn Error>> {
         .match_header("authorization", Matcher::Missing)
         .with_header("content-type", "application/json")
         .with_body(r#"{"index":{},"created":"0000-00-00T00:00:00.123456789Z"}"#)
-        .expect(3) // TODO: Reduce this to 1
+        .expect(1)
         .create();
 
     let meta_mock_auth = server
@@ -415,7 +449,7 @@ fn info_basic_http_url_auth() -> Result<(), Box<dyn Error>> {
         )
         .with_header("content-type", "application/json")
         .with_body(r#"{"index":{},"created":"0000-00-00T00:00:00.123456789Z"}"#)
-        .expect(3) // TODO: Reduce this to 1
+        .expect(1)
         .create();
 
     let (_, _, out) = run_sysand_with(
@@ -558,7 +592,7 @@ fn info_bearer_http_url_auth() -> Result<(), Box<dyn Error>> {
         .match_header("authorization", Matcher::Missing)
         .with_header("content-type", "application/json")
         .with_body(r#"{"index":{},"created":"0000-00-00T00:00:00.123456789Z"}"#)
-        .expect(3) // TODO: Reduce this to 1
+        .expect(1)
         .create();
 
     let meta_mock_auth = server
@@ -570,7 +604,7 @@ fn info_bearer_http_url_auth() -> Result<(), Box<dyn Error>> {
         )
         .with_header("content-type", "application/json")
         .with_body(r#"{"index":{},"created":"0000-00-00T00:00:00.123456789Z"}"#)
-        .expect(3) // TODO: Reduce this to 1
+        .expect(1)
         .create();
 
     let (_, _, out) = run_sysand_with(
@@ -744,7 +778,7 @@ fn info_basic_file_git() -> Result<(), Box<dyn Error>> {
     let (_, _, out) = run_sysand(
         [
             "info",
-            "--uri",
+            "--iri",
             url::Url::from_file_path(cwd.path()).unwrap().as_str(),
         ],
         None,
@@ -782,7 +816,7 @@ fn info_basic_index_url() -> Result<(), Box<dyn Error>> {
     let mut server = mockito::Server::new();
     let config_mock = mock_index_config_absent(&mut server, 2);
 
-    let iri_dir = "/_iri/e837859ce90bb1917c2698a6d62caa5786f67662fd1e35eb320f6e9da96939fe";
+    let iri_dir = "/_kpar/info_basic_index_url";
 
     let project_body = project_json_for("info_basic_index_url", "1.2.3");
     let versions_body = versions_json_for("1.2.3");
@@ -838,15 +872,16 @@ fn info_basic_index_url() -> Result<(), Box<dyn Error>> {
     meta_json_mock.assert();
     kpar_mock.assert();
 
-    // Catch-all for any _iri/<hash>/versions.json we didn't explicitly mock —
-    // return 404 so the resolver treats the IRI as absent from this index.
+    // Catch-all for any versions.json we didn't explicitly mock, in the
+    // `_kpar` layout and the `_iri` layout it falls back to — return 404 so
+    // the resolver treats the IRI as absent from this index.
     let missing_versions_mock = server
         .mock(
             "GET",
-            Matcher::Regex(r"^/_iri/[a-f0-9]+/versions\.json$".to_string()),
+            Matcher::Regex(r"^/(_kpar/[^/]+|_iri/[0-9a-f]{64})/versions\.json$".to_string()),
         )
         .with_status(404)
-        .expect(1)
+        .expect(2)
         .create();
 
     let (_, _, out) = run_sysand(
@@ -876,8 +911,8 @@ fn info_multi_index_url_noauth() -> Result<(), Box<dyn Error>> {
     let config_mock = mock_index_config_absent(&mut server, 3);
     let config_mock_alt = mock_index_config_absent(&mut server_alt, 2);
 
-    let iri_dir = "/_iri/f38ace6666fe279c9e856b2a25b14bf0a03b8c23ff1db524acf1afd78f66b042";
-    let iri_dir_alt = "/_iri/f0f4203b967855590901dc5c90f525d732015ca10598e333815cc30600874565";
+    let iri_dir = "/_kpar/info_multi_index_url";
+    let iri_dir_alt = "/_kpar/info_multi_index_url_alt";
 
     let project_body = project_json_for("info_multi_index_url", "1.2.3");
     let project_alt_body = project_json_for("info_multi_index_url_alt", "1.2.3");
@@ -949,18 +984,18 @@ fn info_multi_index_url_noauth() -> Result<(), Box<dyn Error>> {
     let server_missing_mock = server
         .mock(
             "GET",
-            Matcher::Regex(r"^/_iri/[a-f0-9]+/versions\.json$".to_string()),
+            Matcher::Regex(r"^/(_kpar/[^/]+|_iri/[0-9a-f]{64})/versions\.json$".to_string()),
         )
         .with_status(404)
-        .expect(2)
+        .expect(4)
         .create();
     let server_alt_missing_mock = server_alt
         .mock(
             "GET",
-            Matcher::Regex(r"^/_iri/[a-f0-9]+/versions\.json$".to_string()),
+            Matcher::Regex(r"^/(_kpar/[^/]+|_iri/[0-9a-f]{64})/versions\.json$".to_string()),
         )
         .with_status(404)
-        .expect(1)
+        .expect(2)
         .create();
 
     let (_, _, out) = run_sysand(
@@ -1037,8 +1072,8 @@ fn info_multi_index_url_auth() -> Result<(), Box<dyn Error>> {
     let config_mock = mock_index_config_absent(&mut server, 6);
     let config_mock_alt = mock_index_config_absent(&mut server_alt, 2);
 
-    let iri_dir = "/_iri/f38ace6666fe279c9e856b2a25b14bf0a03b8c23ff1db524acf1afd78f66b042";
-    let iri_dir_alt = "/_iri/f0f4203b967855590901dc5c90f525d732015ca10598e333815cc30600874565";
+    let iri_dir = "/_kpar/info_multi_index_url";
+    let iri_dir_alt = "/_kpar/info_multi_index_url_alt";
     let basic_auth = "Basic dXNlcl8xMjM0OnBhc3NfNDMyMQ==";
 
     let project_body = project_json_for("info_multi_index_url", "1.2.3");
@@ -1139,18 +1174,18 @@ fn info_multi_index_url_auth() -> Result<(), Box<dyn Error>> {
     let server_missing_mock = server
         .mock(
             "GET",
-            Matcher::Regex(r"^/_iri/[a-f0-9]+/versions\.json$".to_string()),
+            Matcher::Regex(r"^/(_kpar/[^/]+|_iri/[0-9a-f]{64})/versions\.json$".to_string()),
         )
         .with_status(404)
-        .expect(4)
+        .expect(8)
         .create();
     let server_alt_missing_mock = server_alt
         .mock(
             "GET",
-            Matcher::Regex(r"^/_iri/[a-f0-9]+/versions\.json$".to_string()),
+            Matcher::Regex(r"^/(_kpar/[^/]+|_iri/[0-9a-f]{64})/versions\.json$".to_string()),
         )
         .with_status(404)
-        .expect(1)
+        .expect(2)
         .create();
 
     let server_pattern = format!("http://{}/**", server.host_with_port());
@@ -1241,8 +1276,8 @@ fn info_multi_index_url_config() -> Result<(), Box<dyn Error>> {
     let config_mock = mock_index_config_absent(&mut server, 2);
     let config_mock_alt = mock_index_config_absent(&mut server_alt, 2);
 
-    let iri_dir = "/_iri/1206faf209922d2c3c3ce220d5b78b6001b1ec42ab1304d65590d1749453c5b5";
-    let iri_dir_alt = "/_iri/bf1998eaeb56282e6dd62686b1ea51dfeffded6964aa53cc89cf70a9a2627c97";
+    let iri_dir = "/_kpar/info_multi_index_url_config";
+    let iri_dir_alt = "/_kpar/info_multi_index_url_config_alt";
 
     let project_body = project_json_for("info_multi_index_url_config", "1.2.3");
     let project_alt_body = project_json_for("info_multi_index_url_config_alt", "1.2.3");
@@ -1312,18 +1347,18 @@ fn info_multi_index_url_config() -> Result<(), Box<dyn Error>> {
     let server_missing_mock = server
         .mock(
             "GET",
-            Matcher::Regex(r"^/_iri/[a-f0-9]+/versions\.json$".to_string()),
+            Matcher::Regex(r"^/(_kpar/[^/]+|_iri/[0-9a-f]{64})/versions\.json$".to_string()),
         )
         .with_status(404)
-        .expect(1)
+        .expect(2)
         .create();
     let server_alt_missing_mock = server_alt
         .mock(
             "GET",
-            Matcher::Regex(r"^/_iri/[a-f0-9]+/versions\.json$".to_string()),
+            Matcher::Regex(r"^/(_kpar/[^/]+|_iri/[0-9a-f]{64})/versions\.json$".to_string()),
         )
         .with_status(404)
-        .expect(1)
+        .expect(2)
         .create();
 
     let (_temp_dir, cwd) = new_temp_cwd()?;
@@ -1708,3 +1743,188 @@ fn info_set_metamodel() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn info_http_url_cached() -> Result<(), Box<dyn Error>> {
+    let mut server = mockito::Server::new();
+    let cache_dir = camino_tempfile::tempdir()?;
+
+    server
+        .mock("GET", "/info/refs?service=git-upload-pack")
+        .with_status(404)
+        .create();
+    server.mock("GET", "/").with_status(404).create();
+
+    let info = r#"{"name":"info_http_url_cached","version":"1.2.3"}"#;
+    let meta = r#"{"index":{},"created":"0000-00-00T00:00:00.123456789Z"}"#;
+    for (path, body) in [("/.project.json", info), ("/.meta.json", meta)] {
+        server
+            .mock("HEAD", path)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .create();
+    }
+
+    // Both runs together fetch each file only once
+    let info_mock = server
+        .mock("GET", "/.project.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(info)
+        .expect(1)
+        .create();
+    let meta_mock = server
+        .mock("GET", "/.meta.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(meta)
+        .expect(1)
+        .create();
+
+    let env = IndexMap::from([
+        ("SYSAND_NO_CACHE", "false"),
+        ("SYSAND_CACHE_DIR", cache_dir.path().as_str()),
+    ]);
+    for _ in 0..2 {
+        let (_, _, out) = run_sysand_with(["info", "--iri", &server.url()], None, &env)?;
+        out.assert()
+            .success()
+            .stdout(predicate::str::contains("Name: info_http_url_cached"));
+    }
+
+    info_mock.assert();
+    meta_mock.assert();
+
+    Ok(())
+}
+
+#[test]
+fn info_all_installed() -> Result<(), Box<dyn Error>> {
+    let (_temp_dir, cwd, _) = run_sysand(["env"], None)?;
+
+    let test_path = fixture_path("test_lib");
+    run_sysand_in(
+        &cwd,
+        [
+            "env",
+            "install",
+            "urn:kpar:test",
+            "--path",
+            test_path.as_str(),
+        ],
+        None,
+    )?
+    .assert()
+    .success();
+
+    let out = run_sysand_in(&cwd, ["info", "--all-installed"], None)?;
+    let stdout = String::from_utf8(out.assert().success().get_output().stdout.clone())?;
+    let lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["iri"], "urn:kpar:test");
+    assert_eq!(lines[0]["version"], "0.0.1");
+    assert_eq!(lines[0]["info"]["version"], "0.0.1");
+
+    Ok(())
+}
+
+#[test]
+fn info_all_index() -> Result<(), Box<dyn Error>> {
+    let mut server = mockito::Server::new();
+    let _config_mock = mock_index_config_absent(&mut server, 1);
+
+    let iri = "urn:kpar:info_basic_index_url";
+    let iri_dir = "/_kpar/info_basic_index_url";
+    let index_mock = server
+        .mock("GET", "/index.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(r#"{{"projects":[{{"iri":"{iri}"}}]}}"#))
+        .create();
+    let _versions_mock = server
+        .mock("GET", format!("{iri_dir}/versions.json").as_str())
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(versions_json_for("1.2.3"))
+        .create();
+    let _project_mock = server
+        .mock("GET", format!("{iri_dir}/1.2.3/.project.json").as_str())
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(project_json_for("info_basic_index_url", "1.2.3"))
+        .create();
+    let _meta_mock = server
+        .mock("GET", format!("{iri_dir}/1.2.3/.meta.json").as_str())
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(TEST_META_JSON_BODY)
+        .create();
+
+    let (_temp_dir, _cwd, out) = run_sysand(["info", "--all", "--index", &server.url()], None)?;
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            r#""iri":"{iri}","version":"1.2.3""#
+        )));
+    index_mock.assert();
+
+    Ok(())
+}
+
+#[test]
+fn info_include_std_config() -> Result<(), Box<dyn Error>> {
+    let std_iri = "https://www.omg.org/spec/KerML/20250201/Function-Library.kpar";
+    let (_temp_dir, cwd, out) = run_sysand(
+        [
+            "init",
+            "--version",
+            "1.0.0",
+            "--name",
+            "info_include_std_config",
+        ],
+        None,
+    )?;
+    out.assert().success();
+    run_sysand_in(&cwd, ["info", "metamodel", "--set", "kerml"], None)?
+        .assert()
+        .success();
+    run_sysand_in(&cwd, ["add", std_iri, "--no-lock", "--include-std"], None)?
+        .assert()
+        .success();
+
+    let cfg_path = cwd.join(sysand_core::config::local_fs::CONFIG_FILE);
+    for (include_std, included) in [("true", true), ("false", false), ("\"auto\"", true)] {
+        std::fs::write(&cfg_path, format!("include_std = {include_std}\n"))?;
+        let out = run_sysand_in(&cwd, ["info"], Some(cfg_path.as_str()))?;
+        let out = out
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("--include-std").not());
+        if included {
+            out.stdout(predicate::str::contains(std_iri));
+        } else {
+            out.stdout(predicate::str::contains("All usages are ignored"));
+        }
+    }
+
+    // Flags override the configuration
+    let out = run_sysand_in(&cwd, ["info", "--no-include-std"], Some(cfg_path.as_str()))?;
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains("All usages are ignored"));
+
+    // The declared metamodel selects the standard libraries in `auto` mode
+    let meta_path = cwd.join(".meta.json");
+    let meta = std::fs::read_to_string(&meta_path)?;
+    std::fs::write(&meta_path, meta.replace("KerML/20250201", "KerML/20240201"))?;
+    let out = run_sysand_in(&cwd, ["info"], Some(cfg_path.as_str()))?;
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains("All usages are ignored"));
+
+    Ok(())
+}
@@ -208,3 +208,97 @@ fn init_fail_on_double_init_cwd() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// `sysand init --iri` should record the IRI in `sysand.toml`, and reject
+/// invalid IRIs without creating the project
+#[test]
+fn init_iri() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        [
+            "init",
+            "--name",
+            "lib",
+            "--iri",
+            "pkg:sysand/acme/lib",
+            "lib",
+        ],
+        None,
+    )?;
+    out.assert().success();
+    let config = std::fs::read_to_string(cwd.join("lib").join("sysand.toml"))?;
+    assert_eq!(config, "iri = \"pkg:sysand/acme/lib\"\n");
+
+    let out = run_sysand_in(&cwd, ["init", "--iri", "pkg:sysand/acme", "other"], None)?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("not a valid `pkg:sysand` PURL"));
+    assert!(!cwd.join("other").join(".project.json").exists());
+
+    let out = run_sysand_in(&cwd, ["init", "--iri", "foo:bar", "unresolvable"], None)?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("not supported by any resolver"));
+
+    Ok(())
+}
+
+#[test]
+fn init_workspace_with_members() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        [
+            "init",
+            "--workspace",
+            "--publisher",
+            "acme",
+            "--member",
+            "core",
+            "--member",
+            "libs/payloads",
+            "ws",
+        ],
+        None,
+    )?;
+    out.assert().success();
+    let ws = cwd.join("ws");
+    let info: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(ws.join(".workspace.json"))?)?;
+    assert_eq!(
+        info,
+        serde_json::json!({"projects": [
+            {"path": "core", "iris": ["pkg:sysand/acme/core"]},
+            {"path": "libs/payloads", "iris": ["pkg:sysand/acme/payloads"]}
+        ]})
+    );
+    assert_eq!(
+        std::fs::read_to_string(ws.join("sysand.toml"))?,
+        "[workspace]\nshared_lock = true\n"
+    );
+    let payloads: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+        ws.join("libs/payloads/.project.json"),
+    )?)?;
+    assert_eq!(payloads["name"], "payloads");
+    assert_eq!(payloads["publisher"], "acme");
+
+    // Members can use each other and are locked together
+    run_sysand_in(
+        &ws.join("core"),
+        ["add", "pkg:sysand/acme/payloads", "--no-lock"],
+        None,
+    )?
+    .assert()
+    .success();
+    let cfg = ws.join("sysand.toml");
+    run_sysand_in(&ws, ["lock"], Some(cfg.as_str()))?
+        .assert()
+        .success();
+    let lock = std::fs::read_to_string(ws.join("sysand-lock.toml"))?;
+    assert!(lock.contains(r#"editable = "libs/payloads""#));
+    assert!(!ws.join("core/sysand-lock.toml").exists());
+
+    let out = run_sysand_in(&cwd, ["init", "--workspace", "ws"], None)?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("workspace already exists"));
+
+    Ok(())
+}
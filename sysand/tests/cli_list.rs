@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use assert_cmd::prelude::*;
+use predicates::{prelude::*, str::contains};
+use sysand_core::config::{self, ConfigProject, OverrideSource};
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+#[test]
+fn list_usage_status() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        ["init", "--name", "list_usage_status", "--version", "1.2.3"],
+        None,
+    )?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["init", "--version", "1.0.0", "local_dep"], None)?;
+    out.assert().success();
+
+    let cfg = toml::to_string(&config::Config {
+        projects: vec![ConfigProject {
+            identifiers: vec!["urn:kpar:local_dep".to_string()],
+            sources: vec![OverrideSource::LocalSrc {
+                src_path: "local_dep".into(),
+            }],
+        }],
+        ..Default::default()
+    })?;
+    let cfg_path = cwd.join(config::local_fs::CONFIG_FILE);
+    std::fs::write(&cfg_path, cfg)?;
+
+    let out = run_sysand_in(
+        &cwd,
+        ["add", "urn:kpar:local_dep", "^1"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().success();
+    let out = run_sysand_in(&cwd, ["add", "urn:kpar:unlocked", "--no-lock"], None)?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["list"], None)?;
+    out.assert().success().stdout(
+        "IRI                 CONSTRAINT  LOCKED  INSTALLED  SOURCE\n\
+         urn:kpar:local_dep  ^1          1.0.0   yes        path\n\
+         urn:kpar:unlocked   *           -       no         -\n",
+    );
+
+    std::fs::remove_dir_all(cwd.join(".sysand"))?;
+    let out = run_sysand_in(&cwd, ["list"], None)?;
+    out.assert().success().stdout(contains(
+        "urn:kpar:local_dep  ^1          1.0.0   no         path",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn list_without_lockfile() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        [
+            "init",
+            "--name",
+            "list_without_lockfile",
+            "--version",
+            "1.2.3",
+        ],
+        None,
+    )?;
+    out.assert().success();
+    let out = run_sysand_in(&cwd, ["add", "urn:kpar:dep", "2", "--no-lock"], None)?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["list"], None)?;
+    out.assert()
+        .success()
+        .stdout(contains("urn:kpar:dep  ^2          -       no         -"))
+        .stderr(contains("run `sysand lock` to create it"));
+
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    let out = run_sysand_in(&cwd, ["list"], None)?;
+    out.assert().failure().stdout(predicate::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn list_and_why_show_reasons() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "app", "--version", "1.2.3"], None)?;
+    out.assert().success();
+    let out = run_sysand_in(&cwd, ["init", "--version", "1.0.0", "thermal"], None)?;
+    out.assert().success();
+
+    let cfg = toml::to_string(&config::Config {
+        projects: vec![ConfigProject {
+            identifiers: vec!["urn:kpar:thermal".to_string()],
+            sources: vec![OverrideSource::LocalSrc {
+                src_path: "thermal".into(),
+            }],
+        }],
+        ..Default::default()
+    })?;
+    let cfg_path = cwd.join(config::local_fs::CONFIG_FILE);
+    std::fs::write(&cfg_path, cfg)?;
+
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "add",
+            "urn:kpar:thermal",
+            "--reason",
+            "needed for thermal analysis",
+        ],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().success();
+    let out = run_sysand_in(&cwd, ["add", "urn:kpar:other", "--no-lock"], None)?;
+    out.assert().success();
+    let config: config::Config = toml::from_str(&std::fs::read_to_string(&cfg_path)?)?;
+    assert_eq!(
+        config.reasons["urn:kpar:thermal"],
+        "needed for thermal analysis"
+    );
+
+    let out = run_sysand_in(&cwd, ["list"], Some(cfg_path.as_str()))?;
+    out.assert().success().stdout(
+        "IRI               CONSTRAINT  LOCKED  INSTALLED  SOURCE  REASON\n\
+         urn:kpar:thermal  *           1.0.0   yes        path    needed for thermal analysis\n\
+         urn:kpar:other    *           -       no         -       -\n",
+    );
+
+    let out = run_sysand_in(&cwd, ["why", "urn:kpar:thermal"], Some(cfg_path.as_str()))?;
+    out.assert().success().stdout(
+        "`urn:kpar:thermal`\n  \
+         reason: needed for thermal analysis\n  \
+         used by: app 1.2.3\n",
+    );
+
+    let out = run_sysand_in(
+        &cwd,
+        ["why", "urn:kpar:thermal", "--output-format", "json"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().success().stdout(
+        "{\"iri\":\"urn:kpar:thermal\",\"reason\":\"needed for thermal analysis\",\
+         \"used_by\":[\"app 1.2.3\"]}\n",
+    );
+
+    let out = run_sysand_in(&cwd, ["why", "urn:kpar:unused"], Some(cfg_path.as_str()))?;
+    out.assert()
+        .failure()
+        .stderr(contains("is not used by any locked project"));
+
+    Ok(())
+}
@@ -4,10 +4,11 @@
 use std::path::Path;
 
 use assert_cmd::prelude::*;
+use indexmap::IndexMap;
 use mockito::{Mock, Server, ServerGuard};
 use predicates::{prelude::*, str::contains};
 use sysand_core::{
-    commands::lock::DEFAULT_LOCKFILE_NAME,
+    commands::lock::{DEFAULT_LOCKFILE_NAME, JSON_LOCKFILE_NAME},
     config::{self, ConfigProject, OverrideSource},
     env::{DEFAULT_ENV_NAME, local_directory::LocalDirectoryEnvironment},
     lock::{Lock, Source},
@@ -80,6 +81,7 @@ fn lock_local_source() -> Result<(), Box<dyn std::error::Error>> {
                 src_path: "local_dep".into(),
             }],
         }],
+        ..Default::default()
     })?;
 
     let cfg_path = cwd.join(config::local_fs::CONFIG_FILE);
@@ -130,6 +132,7 @@ fn lock_std_lib() -> Result<(), Box<dyn std::error::Error>> {
                 src_path: cwd.join("local_dep").as_str().into(),
             }],
         }],
+        ..Default::default()
     })?;
 
     let cfg_path = cwd.join(config::local_fs::CONFIG_FILE);
@@ -246,6 +249,8 @@ fn inject_usages_versions<
         info.usage.push(InterchangeProjectUsageRaw::Resource {
             resource: usage.as_ref().to_string(),
             version_constraint: version_req.map(|x| x.as_ref().to_string()),
+            when: None,
+            hash: None,
         });
     }
 
@@ -262,7 +267,7 @@ fn lock_basic_http_deps() -> Result<(), Box<dyn std::error::Error>> {
     let c_url = mock_project(
         &mut server,
         &mut project_mocks,
-        [1, 8, 1, 6],
+        [1, 8, 1, 2],
         "c",
         "lock_basic_http_deps_c",
         "1.0.0",
@@ -272,7 +277,7 @@ fn lock_basic_http_deps() -> Result<(), Box<dyn std::error::Error>> {
     let a_url = mock_project(
         &mut server,
         &mut project_mocks,
-        [1, 8, 1, 6],
+        [1, 8, 1, 2],
         "a",
         "lock_basic_http_deps_a",
         "1.0.0",
@@ -281,7 +286,7 @@ fn lock_basic_http_deps() -> Result<(), Box<dyn std::error::Error>> {
     let b_url = mock_project(
         &mut server,
         &mut project_mocks,
-        [1, 8, 1, 6],
+        [1, 8, 1, 2],
         "b",
         "lock_basic_http_deps_b",
         "1.0.0",
@@ -524,6 +529,67 @@ fn lock_and_sync_against_mock_index() -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
+#[test]
+fn lock_and_audit_deprecated_index_project() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new();
+    let (kpar_bytes, info, meta) = build_index_kpar_bytes("dep", "0.1.0");
+
+    server
+        .mock("GET", "/sysand-index-config.json")
+        .with_status(404)
+        .create();
+    let versions = format!(
+        r#"{{"versions":[{}],"deprecated":true,"replaced_by":"{PKG_SYSAND_PREFIX}mock/new-dep"}}"#,
+        versions_json_entry_body(
+            "0.1.0",
+            kpar_bytes.len(),
+            &sha256_lowercase_hex(&kpar_bytes)
+        )
+    );
+    let versions_mock = server
+        .mock("GET", "/mock/dep/versions.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(versions)
+        .expect(2)
+        .create();
+    server
+        .mock("GET", "/mock/dep/0.1.0/.project.json")
+        .with_status(200)
+        .with_body(serde_json::to_string(&info)?)
+        .create();
+    server
+        .mock("GET", "/mock/dep/0.1.0/.meta.json")
+        .with_status(200)
+        .with_body(serde_json::to_string(&meta)?)
+        .create();
+
+    let (_temp_dir, cwd, out) = run_sysand(
+        ["init", "--name", "audit_deprecated", "--version", "1.0.0"],
+        None,
+    )?;
+    out.assert().success();
+    inject_usages(
+        cwd.join(".project.json"),
+        [format!("{PKG_SYSAND_PREFIX}mock/dep")],
+    )?;
+
+    let server_url = server.url();
+    let out = run_sysand_in(&cwd, ["lock", "--default-index", &server_url], None)?;
+    out.assert().success().stderr(predicate::str::contains(format!(
+        "project `{PKG_SYSAND_PREFIX}mock/dep` is deprecated; use `{PKG_SYSAND_PREFIX}mock/new-dep` instead"
+    )));
+
+    let out = run_sysand_in(&cwd, ["audit", "--default-index", &server_url], None)?;
+    out.assert().success().stdout(format!(
+        "dep 0.1.0 (`{PKG_SYSAND_PREFIX}mock/dep`) is deprecated, use `{PKG_SYSAND_PREFIX}mock/new-dep` instead\n"
+    ));
+
+    versions_mock.assert();
+
+    Ok(())
+}
+
 #[test]
 fn sync_hard_fails_on_kpar_digest_drift_from_lockfile() -> Result<(), Box<dyn std::error::Error>> {
     // Server immutability + lockfile digest tripwire: `lock` records both the
@@ -695,7 +761,7 @@ fn lock_fail_unsatisfiable() -> Result<(), Box<dyn std::error::Error>> {
     let a_url = mock_project(
         &mut server,
         &mut project_mocks,
-        [1, 4, 1, 2],
+        [1, 4, 1, 1],
         "a",
         "lock_basic_http_deps_a",
         "1.0.0",
@@ -729,3 +795,623 @@ fn lock_fail_unsatisfiable() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn lock_no_project() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+
+    let out = run_sysand_in(&cwd, ["lock"], None)?;
+
+    out.assert()
+        .failure()
+        .stderr(contains("not inside a project"))
+        .stderr(contains("--here"));
+    assert!(!cwd.join(".project.json").exists());
+
+    Ok(())
+}
+
+#[test]
+fn lock_here() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    std::fs::create_dir_all(cwd.join("sub"))?;
+    std::fs::create_dir_all(cwd.join(".hidden"))?;
+    std::fs::write(cwd.join("A.sysml"), "package A;")?;
+    std::fs::write(cwd.join("sub").join("B.kerml"), "package B;")?;
+    std::fs::write(cwd.join(".hidden").join("C.sysml"), "package C;")?;
+    std::fs::write(cwd.join("notes.txt"), "not a model")?;
+
+    let out = run_sysand_in(&cwd, ["lock", "--here"], None)?;
+
+    out.assert().success();
+
+    let meta: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(cwd.join(".meta.json"))?)?;
+    assert_eq!(meta["index"], json!({ "A": "A.sysml", "B": "sub/B.kerml" }));
+    assert!(cwd.join(".project.json").is_file());
+    assert!(cwd.join(DEFAULT_LOCKFILE_NAME).is_file());
+
+    Ok(())
+}
+
+#[test]
+fn lock_here_respects_ignore_files() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    std::fs::create_dir_all(cwd.join("gen"))?;
+    std::fs::create_dir_all(cwd.join("output"))?;
+    std::fs::write(cwd.join("A.sysml"), "package A;")?;
+    std::fs::write(cwd.join("Scratch.sysml"), "package Scratch;")?;
+    std::fs::write(cwd.join("gen").join("G.sysml"), "package G;")?;
+    std::fs::write(cwd.join("output").join("O.sysml"), "package O;")?;
+    std::fs::write(cwd.join(".gitignore"), "gen/\n")?;
+    std::fs::write(cwd.join(".sysandignore"), "Scratch.sysml\n")?;
+
+    let out = run_sysand_in(&cwd, ["lock", "--here"], None)?;
+
+    out.assert().success();
+
+    let meta: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(cwd.join(".meta.json"))?)?;
+    assert_eq!(meta["index"], json!({ "A": "A.sysml" }));
+
+    Ok(())
+}
+
+#[test]
+fn lock_when_missing_here() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    std::fs::write(cwd.join("A.sysml"), "package A;")?;
+    let cfg_path = cwd.join("config.toml");
+    std::fs::write(&cfg_path, "[discovery]\nwhen_missing = \"here\"\n")?;
+
+    let out = run_sysand_in(&cwd, ["lock"], Some(cfg_path.as_str()))?;
+
+    out.assert().success();
+    assert!(cwd.join(".project.json").is_file());
+    assert!(cwd.join(DEFAULT_LOCKFILE_NAME).is_file());
+
+    Ok(())
+}
+
+#[test]
+fn lock_discovery_max_depth() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        [
+            "init",
+            "--name",
+            "lock_discovery_max_depth",
+            "--version",
+            "1.2.3",
+        ],
+        None,
+    )?;
+
+    out.assert().success();
+
+    let sub = cwd.join("sub");
+    std::fs::create_dir_all(&sub)?;
+    let cfg_path = cwd.join("config.toml");
+    std::fs::write(&cfg_path, "[discovery]\nmax_depth = 0\n")?;
+
+    let out = run_sysand_in(&sub, ["lock"], Some(cfg_path.as_str()))?;
+
+    out.assert()
+        .failure()
+        .stderr(contains("not inside a project"));
+
+    let out = run_sysand_in(&sub, ["lock"], None)?;
+
+    out.assert().success();
+
+    Ok(())
+}
+
+fn init_source_root_projects(cwd: &camino::Utf8Path) -> Result<(), Box<dyn std::error::Error>> {
+    let out = run_sysand_in(
+        cwd,
+        ["init", "--name", "lock_source_roots", "--version", "1.0.0"],
+        None,
+    )?;
+    out.assert().success();
+    let out = run_sysand_in(
+        cwd,
+        [
+            "init",
+            "--name",
+            "Dep Lib",
+            "--publisher",
+            "acme",
+            "--version",
+            "2.0.0",
+            "deps/dep-lib",
+        ],
+        None,
+    )?;
+    out.assert().success();
+    let out = run_sysand_in(cwd, ["add", "pkg:sysand/acme/dep-lib", "--no-lock"], None)?;
+    out.assert().success();
+    Ok(())
+}
+
+fn locked_dep_source(cwd: &camino::Utf8Path) -> Result<Source, Box<dyn std::error::Error>> {
+    let lock: Lock = toml::from_str(&std::fs::read_to_string(cwd.join(DEFAULT_LOCKFILE_NAME))?)?;
+    let dep = lock
+        .projects
+        .iter()
+        .find(|p| p.name == "Dep Lib")
+        .expect("dependency should be locked");
+    assert_eq!(dep.version, "2.0.0");
+    Ok(dep.sources[0].clone())
+}
+
+#[test]
+fn lock_extra_source_roots_env() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    init_source_root_projects(&cwd)?;
+    let deps = cwd.join("deps");
+
+    let out = run_sysand_in_with(
+        &cwd,
+        ["lock", "--no-index"],
+        None,
+        &IndexMap::from([("SYSAND_EXTRA_SOURCE_ROOTS", deps.as_str())]),
+    )?;
+
+    out.assert().success();
+    let Source::LocalSrc { src_path, .. } = locked_dep_source(&cwd)? else {
+        panic!("expected a local source");
+    };
+    assert_eq!(src_path.as_str(), "deps/dep-lib");
+
+    Ok(())
+}
+
+#[test]
+fn lock_source_roots_config() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    init_source_root_projects(&cwd)?;
+    let cfg_path = cwd.join("config.toml");
+    std::fs::write(&cfg_path, "source_roots = [\"deps\"]\n")?;
+
+    let out = run_sysand_in(&cwd, ["lock", "--no-index"], Some(cfg_path.as_str()))?;
+
+    out.assert().success();
+    let Source::LocalSrc { src_path, .. } = locked_dep_source(&cwd)? else {
+        panic!("expected a local source");
+    };
+    assert_eq!(src_path.as_str(), "deps/dep-lib");
+
+    // Without a source root the dependency cannot be resolved
+    let out = run_sysand_in(&cwd, ["lock", "--no-index"], None)?;
+    out.assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn lock_conditional_usage() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    init_source_root_projects(&cwd)?;
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "add",
+            "pkg:sysand/acme/dep-lib",
+            "--no-lock",
+            "--when",
+            "tool == 'syside'",
+        ],
+        None,
+    )?;
+    out.assert().success();
+    let cfg_path = cwd.join("config.toml");
+    std::fs::write(&cfg_path, "source_roots = [\"deps\"]\n")?;
+
+    // Without the marker the usage does not apply
+    let out = run_sysand_in(&cwd, ["lock", "--no-index"], Some(cfg_path.as_str()))?;
+    out.assert().success();
+    let lock: Lock = toml::from_str(&std::fs::read_to_string(cwd.join(DEFAULT_LOCKFILE_NAME))?)?;
+    assert_eq!(lock.projects.len(), 1);
+    assert!(lock.projects[0].usages.is_empty());
+
+    let out = run_sysand_in(
+        &cwd,
+        ["lock", "--no-index", "--marker", "tool=syside"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().success();
+    locked_dep_source(&cwd)?;
+    let lock: Lock = toml::from_str(&std::fs::read_to_string(cwd.join(DEFAULT_LOCKFILE_NAME))?)?;
+    assert_eq!(lock.markers.get("tool").map(String::as_str), Some("syside"));
+
+    let out = run_sysand_in(&cwd, ["lock", "--marker", "platform=linux"], None)?;
+    out.assert()
+        .failure()
+        .stderr(contains("unknown attribute `platform`"));
+
+    Ok(())
+}
+
+/// Set up a workspace with `project1` and `project2`, which use `lib` (not a
+/// workspace project) with the given version constraints. `project1` also
+/// uses `project2`. Returns the path of a configuration file enabling
+/// `workspace.shared_lock`
+fn shared_lock_workspace(
+    cwd: &camino::Utf8Path,
+    constraints: [&str; 2],
+) -> Result<camino::Utf8PathBuf, Box<dyn std::error::Error>> {
+    std::fs::write(
+        cwd.join(".workspace.json"),
+        json!({"projects": [
+            {"path": "project1", "iris": ["urn:kpar:project1"]},
+            {"path": "project2", "iris": ["urn:kpar:project2"]}
+        ]})
+        .to_string(),
+    )?;
+    let out = run_sysand_in(cwd, ["init", "--version", "1.0.0", "lib"], None)?;
+    out.assert().success();
+
+    for (name, constraint) in ["project1", "project2"].into_iter().zip(constraints) {
+        let out = run_sysand_in(cwd, ["init", "--version", "1.2.3", name], None)?;
+        out.assert().success();
+        let out = run_sysand_in(
+            &cwd.join(name),
+            ["add", "urn:kpar:lib", constraint, "--no-lock"],
+            None,
+        )?;
+        out.assert().success();
+    }
+    let out = run_sysand_in(
+        &cwd.join("project1"),
+        ["add", "urn:kpar:project2", "--no-lock"],
+        None,
+    )?;
+    out.assert().success();
+
+    let cfg = toml::to_string(&config::Config {
+        projects: vec![ConfigProject {
+            identifiers: vec!["urn:kpar:lib".to_string()],
+            sources: vec![OverrideSource::LocalSrc {
+                src_path: "lib".into(),
+            }],
+        }],
+        workspace: config::WorkspaceConfig {
+            shared_lock: Some(true),
+        },
+        ..Default::default()
+    })?;
+    let cfg_path = cwd.join(config::local_fs::CONFIG_FILE);
+    std::fs::write(&cfg_path, cfg)?;
+
+    Ok(cfg_path)
+}
+
+#[test]
+fn lock_workspace_shared() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    let cfg_path = shared_lock_workspace(&cwd, ["^1.0", ">=1"])?;
+    let project1_cwd = cwd.join("project1");
+
+    let out = run_sysand_in(&project1_cwd, ["lock"], Some(cfg_path.as_str()))?;
+    out.assert().success();
+
+    assert!(!project1_cwd.join(DEFAULT_LOCKFILE_NAME).exists());
+    let lock: Lock = toml::from_str(&std::fs::read_to_string(cwd.join(DEFAULT_LOCKFILE_NAME))?)?;
+    let mut names: Vec<_> = lock.projects.iter().map(|p| p.name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, ["lib", "project1", "project2"]);
+    for (name, path) in [("project1", "project1"), ("project2", "project2")] {
+        let project = lock.projects.iter().find(|p| p.name == name).unwrap();
+        assert_eq!(
+            project.sources,
+            vec![Source::Editable {
+                editable: path.into()
+            }]
+        );
+    }
+
+    let out = run_sysand_in(&project1_cwd, ["sync"], Some(cfg_path.as_str()))?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd.join("project2"), ["env", "list"], None)?;
+    out.assert()
+        .success()
+        .stdout(contains("`urn:kpar:lib` 1.0.0"));
+    assert!(!project1_cwd.join(DEFAULT_ENV_NAME).exists());
+
+    Ok(())
+}
+
+#[test]
+fn lock_workspace_shared_conflict() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    let cfg_path = shared_lock_workspace(&cwd, ["^1.0", "^2.0"])?;
+
+    let out = run_sysand_in(&cwd.join("project2"), ["lock"], Some(cfg_path.as_str()))?;
+    out.assert()
+        .failure()
+        .stderr(contains("no versions satisfy all workspace projects"))
+        .stderr(contains("`urn:kpar:lib` is used with different versions"))
+        .stderr(contains("^1.0 by `project1`"))
+        .stderr(contains("^2.0 by `project2`"));
+    assert!(!cwd.join(DEFAULT_LOCKFILE_NAME).exists());
+
+    Ok(())
+}
+
+#[test]
+fn lock_json_format() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) =
+        run_sysand(["init", "--name", "lock_json", "--version", "1.2.3"], None)?;
+    out.assert().success();
+    let cfg_path = cwd.join(config::local_fs::CONFIG_FILE);
+    std::fs::write(&cfg_path, "[lock]\nformat = \"json\"\n")?;
+
+    let out = run_sysand_in(&cwd, ["lock"], Some(cfg_path.as_str()))?;
+    out.assert().success();
+    assert!(!cwd.join(DEFAULT_LOCKFILE_NAME).exists());
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(cwd.join(JSON_LOCKFILE_NAME))?)?;
+    assert!(json["lock_hash"].as_str().unwrap().starts_with("sha256:"));
+    assert_eq!(json["project"][0]["name"], "lock_json");
+
+    // Commands read JSON lockfiles regardless of the configured format
+    let out = run_sysand_in(&cwd, ["sync"], None)?;
+    out.assert().success();
+
+    // Locking in the default format replaces the JSON lockfile
+    let out = run_sysand_in(&cwd, ["lock"], None)?;
+    out.assert().success();
+    assert!(!cwd.join(JSON_LOCKFILE_NAME).exists());
+    let lockfile = std::fs::read_to_string(cwd.join(DEFAULT_LOCKFILE_NAME))?;
+    assert!(lockfile.contains("# lock_hash: sha256:"), "{lockfile}");
+
+    std::fs::write(
+        cwd.join(DEFAULT_LOCKFILE_NAME),
+        lockfile.replace("version = \"1.2.3\"", "version = \"1.2.4\""),
+    )?;
+    let out = run_sysand_in(&cwd, ["sync"], None)?;
+    out.assert()
+        .failure()
+        .stderr(contains("does not match its contents"));
+
+    Ok(())
+}
+
+#[test]
+fn lock_merge() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) =
+        run_sysand(["init", "--name", "lock_merge", "--version", "1.2.3"], None)?;
+    out.assert().success();
+    for dep in ["dep_a", "dep_b"] {
+        let out = run_sysand_in(&cwd, ["init", "--version", "1.0.0", dep], None)?;
+        out.assert().success();
+        let out = run_sysand_in(&cwd, ["add", &format!("urn:kpar:{dep}"), "--no-lock"], None)?;
+        out.assert().success();
+    }
+    let cfg = toml::to_string(&config::Config {
+        projects: ["dep_a", "dep_b"]
+            .map(|dep| ConfigProject {
+                identifiers: vec![format!("urn:kpar:{dep}")],
+                sources: vec![OverrideSource::LocalSrc {
+                    src_path: dep.into(),
+                }],
+            })
+            .into(),
+        ..Default::default()
+    })?;
+    let cfg_path = cwd.join(config::local_fs::CONFIG_FILE);
+    std::fs::write(&cfg_path, cfg)?;
+    let out = run_sysand_in(&cwd, ["lock"], Some(cfg_path.as_str()))?;
+    out.assert().success();
+    let locked = std::fs::read_to_string(cwd.join(DEFAULT_LOCKFILE_NAME))?;
+
+    // Lockfiles with the versions of `dep_a` and `dep_b` replaced
+    let write_lock = |name: &str, a: &str, b: &str| -> Result<String, Box<dyn std::error::Error>> {
+        let mut lock: Lock = locked.parse()?;
+        for project in &mut lock.projects {
+            match project.name.as_str() {
+                "dep_a" => project.version = a.into(),
+                "dep_b" => project.version = b.into(),
+                _ => (),
+            }
+        }
+        std::fs::write(cwd.join(name), lock.serialize(Default::default()))?;
+        Ok(name.to_owned())
+    };
+    let versions = |name: &str| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let lock: Lock = std::fs::read_to_string(cwd.join(name))?.parse()?;
+        Ok(lock
+            .projects
+            .into_iter()
+            .filter(|p| p.name.starts_with("dep_"))
+            .map(|p| p.version)
+            .collect())
+    };
+
+    // Changes on different sides merge without resolving
+    let base = write_lock("base.toml", "0.1.0", "0.1.0")?;
+    let ours = write_lock("ours.toml", "0.2.0", "0.1.0")?;
+    let theirs = write_lock("theirs.toml", "0.1.0", "0.3.0")?;
+    let out = run_sysand_in(&cwd, ["lock", "--merge", &base, &ours, &theirs], None)?;
+    out.assert().success();
+    assert_eq!(versions(&ours)?, ["0.2.0", "0.3.0"]);
+
+    // `dep_a` is resolved again, keeping the merged `dep_b`
+    let ours = write_lock("ours.toml", "0.2.0", "0.1.0")?;
+    let theirs = write_lock("theirs.toml", "0.4.0", "0.3.0")?;
+    let out = run_sysand_in(
+        &cwd,
+        ["lock", "--merge", &base, &ours, &theirs],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert()
+        .success()
+        .stderr(contains("conflicting lockfile entries: `dep_a`"));
+    assert_eq!(versions(&ours)?, ["1.0.0", "0.3.0"]);
+
+    Ok(())
+}
+
+#[test]
+fn lock_minimal_versions_and_locked() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        ["init", "--name", "lock_minimal", "--version", "1.2.3"],
+        None,
+    )?;
+    out.assert().success();
+    for (dir, version) in [("dep_old", "1.0.0"), ("dep_new", "1.1.0")] {
+        let out = run_sysand_in(
+            &cwd,
+            ["init", "--name", "dep", "--version", version, dir],
+            None,
+        )?;
+        out.assert().success();
+    }
+    let out = run_sysand_in(&cwd, ["add", "urn:kpar:dep", ">=1.0.0", "--no-lock"], None)?;
+    out.assert().success();
+    let cfg = toml::to_string(&config::Config {
+        projects: vec![ConfigProject {
+            identifiers: vec!["urn:kpar:dep".to_string()],
+            sources: ["dep_new", "dep_old"]
+                .map(|dir| OverrideSource::LocalSrc {
+                    src_path: dir.into(),
+                })
+                .into(),
+        }],
+        ..Default::default()
+    })?;
+    let cfg_path = cwd.join(config::local_fs::CONFIG_FILE);
+    std::fs::write(&cfg_path, cfg)?;
+    let dep_version = || -> Result<String, Box<dyn std::error::Error>> {
+        let lock: Lock = std::fs::read_to_string(cwd.join(DEFAULT_LOCKFILE_NAME))?.parse()?;
+        Ok(lock
+            .projects
+            .into_iter()
+            .find(|p| p.name == "dep")
+            .unwrap()
+            .version)
+    };
+
+    // Nothing to compare against yet
+    let out = run_sysand_in(&cwd, ["lock", "--locked"], Some(cfg_path.as_str()))?;
+    out.assert()
+        .failure()
+        .stderr(contains("lockfile does not exist"));
+
+    let out = run_sysand_in(&cwd, ["lock"], Some(cfg_path.as_str()))?;
+    out.assert().success();
+    assert_eq!(dep_version()?, "1.1.0");
+
+    let out = run_sysand_in(&cwd, ["lock", "--locked"], Some(cfg_path.as_str()))?;
+    out.assert().success();
+    let out = run_sysand_in(
+        &cwd,
+        ["lock", "--minimal-versions", "--locked"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert()
+        .failure()
+        .stderr(contains("is not up to date"))
+        .stderr(contains("`dep` 1.1.0 -> 1.0.0"));
+    assert_eq!(dep_version()?, "1.1.0");
+
+    let out = run_sysand_in(
+        &cwd,
+        ["lock", "--minimal-versions"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().success();
+    assert_eq!(dep_version()?, "1.0.0");
+
+    Ok(())
+}
+
+#[test]
+fn lock_and_sync_check_hash_pins() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        ["init", "--name", "lock_hash_pins", "--version", "1.2.3"],
+        None,
+    )?;
+    out.assert().success();
+    let out = run_sysand_in(
+        &cwd,
+        ["init", "--name", "dep", "--version", "1.0.0", "dep"],
+        None,
+    )?;
+    out.assert().success();
+    let cfg = toml::to_string(&config::Config {
+        projects: vec![ConfigProject {
+            identifiers: vec!["urn:kpar:dep".to_string()],
+            sources: vec![OverrideSource::LocalSrc {
+                src_path: "dep".into(),
+            }],
+        }],
+        ..Default::default()
+    })?;
+    let cfg_path = cwd.join(config::local_fs::CONFIG_FILE);
+    std::fs::write(&cfg_path, cfg)?;
+
+    let out = run_sysand_in(
+        &cwd,
+        ["add", "urn:kpar:dep", "1.0.0"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().success();
+    let lock: Lock = std::fs::read_to_string(cwd.join(DEFAULT_LOCKFILE_NAME))?.parse()?;
+    let hash = lock
+        .projects
+        .iter()
+        .find(|p| p.name == "dep")
+        .and_then(|p| match &p.sources[..] {
+            [Source::LocalSrc { checksum, .. }] => Some(format!("sha256:{checksum}")),
+            _ => None,
+        })
+        .unwrap();
+
+    let out = run_sysand_in(
+        &cwd,
+        ["add", "urn:kpar:dep", "1.0.0", "--hash", &hash],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().success();
+    let info: InterchangeProjectInfoRaw =
+        serde_json::from_str(&std::fs::read_to_string(cwd.join(".project.json"))?)?;
+    assert_eq!(info.usage[0].hash_pin(), hash.strip_prefix("sha256:"));
+
+    let wrong = format!("sha256:{}", "0".repeat(64));
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "add",
+            "urn:kpar:dep",
+            "1.0.0",
+            "--hash",
+            &wrong,
+            "--no-lock",
+        ],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().success();
+    let out = run_sysand_in(&cwd, ["sync"], Some(cfg_path.as_str()))?;
+    out.assert()
+        .failure()
+        .stderr(contains("installed project `urn:kpar:dep` has hash"))
+        .stderr(contains(&wrong));
+    let out = run_sysand_in(&cwd, ["lock"], Some(cfg_path.as_str()))?;
+    out.assert()
+        .failure()
+        .stderr(contains("but its usage is pinned to"))
+        .stderr(contains(&wrong));
+
+    let out = run_sysand_in(
+        &cwd,
+        ["add", "urn:kpar:dep", "--hash", "sha256:00", "--no-lock"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert()
+        .failure()
+        .stderr(contains("invalid hash `sha256:00`"));
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+fn json_lines(stderr: &[u8]) -> Vec<serde_json::Value> {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("stderr line is not JSON"))
+        .collect()
+}
+
+#[test]
+fn verbosity_levels() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "verbosity_levels"], None)?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["lock", "--no-index", "-v"], None)?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("debug: resolution took"))
+        .stderr(predicate::str::contains("resolution started").not());
+
+    let out = run_sysand_in(&cwd, ["lock", "--no-index", "-vv"], None)?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("trace: resolution started"));
+
+    let out = run_sysand_in(&cwd, ["info", "-v", "--quiet"], None)?;
+    out.assert().failure().code(2);
+
+    Ok(())
+}
+
+#[test]
+fn json_log_has_request_fields() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = mockito::Server::new();
+    server
+        .mock("GET", "/info/refs?service=git-upload-pack")
+        .with_status(404)
+        .create();
+    server.mock("GET", "/").with_status(404).create();
+    let info = r#"{"name":"json_log_has_request_fields","version":"1.2.3"}"#;
+    let meta = r#"{"index":{},"created":"0000-00-00T00:00:00.123456789Z"}"#;
+    for method in ["HEAD", "GET"] {
+        for (path, body) in [("/.project.json", info), ("/.meta.json", meta)] {
+            server
+                .mock(method, path)
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(body)
+                .create();
+        }
+    }
+
+    let url = server.url();
+    let (_, _, out) = run_sysand(["info", "--iri", &url, "-v", "--log-format", "json"], None)?;
+    assert!(out.status.success());
+
+    let records = json_lines(&out.stderr);
+    let request = records
+        .iter()
+        .find(|r| r["fields"]["url"] == format!("{url}/.project.json"))
+        .expect("request to `.project.json` is logged");
+    assert_eq!(request["level"], "debug");
+    assert_eq!(request["fields"]["status"], 200);
+    assert!(request["fields"]["elapsed_ms"].is_u64());
+
+    Ok(())
+}
+
+#[test]
+fn timings_summary() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "timings_summary"], None)?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["lock", "--no-index", "--timings"], None)?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("Timings"))
+        .stderr(predicate::str::contains("resolution"));
+    assert!(!cwd.join("sysand-timings.json").exists());
+
+    let out = run_sysand_in(&cwd, ["lock", "--no-index", "--timings", "json"], None)?;
+    out.assert().success();
+    let timings: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(cwd.join("sysand-timings.json"))?)?;
+    assert!(timings["total_seconds"].is_f64());
+    assert_eq!(timings["phases"]["resolution"]["count"], 1);
+    assert!(timings["phases"]["resolution"]["seconds"].is_f64());
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use std::fs;
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use sysand_core::{
+    commands::lock::DEFAULT_LOCKFILE_NAME,
+    env::{DEFAULT_ENV_NAME, local_directory::METADATA_PATH},
+};
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+#[test]
+fn migrate_unversioned_env() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "migrate_env"], None)?;
+    out.assert().success();
+    run_sysand_in(&cwd, ["sync"], None)?.assert().success();
+
+    let env_metadata = cwd.join(DEFAULT_ENV_NAME).join(METADATA_PATH);
+    let unversioned = fs::read_to_string(&env_metadata)?.replace("version = \"0.1\"\n", "");
+    fs::write(&env_metadata, &unversioned)?;
+
+    let out = run_sysand_in(&cwd, ["env", "list"], None)?;
+
+    out.assert().failure().stderr(predicate::str::contains(
+        "env metadata version `0.0` is outdated; run `sysand migrate` to update it",
+    ));
+
+    let out = run_sysand_in(&cwd, ["migrate", "--dry-run"], None)?;
+
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("Would migrate"))
+        .stderr(predicate::str::contains("from version 0.0 to 0.1"));
+    assert_eq!(fs::read_to_string(&env_metadata)?, unversioned);
+
+    let out = run_sysand_in(&cwd, ["migrate"], None)?;
+
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("Migrating"))
+        .stderr(predicate::str::contains("env.toml.0.0.bak"));
+    assert_eq!(
+        fs::read_to_string(cwd.join(DEFAULT_ENV_NAME).join("env.toml.0.0.bak"))?,
+        unversioned
+    );
+    run_sysand_in(&cwd, ["env", "list"], None)?
+        .assert()
+        .success();
+
+    let out = run_sysand_in(&cwd, ["migrate"], None)?;
+
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("all files are up to date"));
+
+    Ok(())
+}
+
+#[test]
+fn newer_lockfile_is_rejected() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "migrate_newer"], None)?;
+    out.assert().success();
+    fs::write(cwd.join(DEFAULT_LOCKFILE_NAME), "lock_version = \"99.0\"\n")?;
+
+    let out = run_sysand_in(&cwd, ["sync"], None)?;
+
+    out.assert().failure().stderr(predicate::str::contains(
+        "lockfile version `99.0` is newer than the supported version",
+    ));
+
+    let out = run_sysand_in(&cwd, ["migrate"], None)?;
+
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("upgrade sysand to use it"));
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use sysand_core::{commands::lock::DEFAULT_LOCKFILE_NAME, lock::CURRENT_LOCK_VERSION};
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+fn write_lock(cwd: &camino::Utf8Path, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::write(
+        cwd.join(DEFAULT_LOCKFILE_NAME),
+        format!(
+            r#"lock_version = "{CURRENT_LOCK_VERSION}"
+
+[[project]]
+name = "{name}"
+version = "0.0.1"
+usages = ["pkg:sysand/acme/old"]
+sources = [{{ editable = "." }}]
+
+[[project]]
+publisher = "Acme"
+name = "Old"
+version = "1.0.0"
+identifiers = ["pkg:sysand/acme/old"]
+sources = [{{ remote_git = "https://example.com/old.git" }}]
+"#
+        ),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn migrate_iri() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "migrate"], None)?;
+    out.assert().success();
+    let out = run_sysand_in(&cwd, ["add", "--no-lock", "acme/old", "^1.0"], None)?;
+    out.assert().success();
+    write_lock(&cwd, "migrate")?;
+
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "migrate-iri",
+            "acme/old",
+            "pkg:sysand/acme/new",
+            "--redirect-stub",
+            "stub",
+            "--redirect-version",
+            "1.1.0",
+        ],
+        None,
+    )?;
+
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Migrating `pkg:sysand/acme/old` to `pkg:sysand/acme/new`",
+        ))
+        .stderr(predicate::str::contains("Updated 1 usage(s)"))
+        .stderr(predicate::str::contains("Updated 2 entries"));
+
+    let info_json = std::fs::read_to_string(cwd.join(".project.json"))?;
+    assert!(info_json.contains(r#""resource": "pkg:sysand/acme/new""#));
+    assert!(info_json.contains(r#""versionConstraint": "^1.0""#));
+    assert!(!info_json.contains("pkg:sysand/acme/old"));
+    let lock = std::fs::read_to_string(cwd.join(DEFAULT_LOCKFILE_NAME))?;
+    assert!(lock.contains("pkg:sysand/acme/new"));
+    assert!(!lock.contains("pkg:sysand/acme/old"));
+
+    let stub_json = std::fs::read_to_string(cwd.join("stub").join(".project.json"))?;
+    assert!(stub_json.contains(r#""name": "Old""#));
+    assert!(stub_json.contains(r#""publisher": "Acme""#));
+    assert!(stub_json.contains(r#""version": "1.1.0""#));
+    assert!(stub_json.contains(r#""resource": "pkg:sysand/acme/new""#));
+
+    Ok(())
+}
+
+#[test]
+fn migrate_iri_dry_run() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "migrate_dry_run"], None)?;
+    out.assert().success();
+    let out = run_sysand_in(&cwd, ["add", "--no-lock", "acme/old"], None)?;
+    out.assert().success();
+    write_lock(&cwd, "migrate_dry_run")?;
+    let info_json = std::fs::read_to_string(cwd.join(".project.json"))?;
+    let lock = std::fs::read_to_string(cwd.join(DEFAULT_LOCKFILE_NAME))?;
+
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "migrate-iri",
+            "acme/old",
+            "acme/new",
+            "--dry-run",
+            "--redirect-stub",
+            "stub",
+            "--redirect-version",
+            "1.1.0",
+        ],
+        None,
+    )?;
+
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("Would update 1 usage(s)"))
+        .stderr(predicate::str::contains("Would update 2 entries"))
+        .stderr(predicate::str::contains(
+            "Would create redirect stub `Old` 1.1.0",
+        ));
+    assert_eq!(
+        std::fs::read_to_string(cwd.join(".project.json"))?,
+        info_json
+    );
+    assert_eq!(
+        std::fs::read_to_string(cwd.join(DEFAULT_LOCKFILE_NAME))?,
+        lock
+    );
+    assert!(!cwd.join("stub").exists());
+
+    Ok(())
+}
+
+#[test]
+fn migrate_iri_without_usages() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["init", "--name", "migrate_unused"], None)?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["migrate-iri", "urn:kpar:old", "urn:kpar:new"], None)?;
+
+    out.assert().success().stderr(predicate::str::contains(
+        "no usages of `urn:kpar:old` found",
+    ));
+
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "migrate-iri",
+            "urn:kpar:old",
+            "urn:kpar:new",
+            "--redirect-stub",
+            "stub",
+            "--redirect-version",
+            "1.1.0",
+        ],
+        None,
+    )?;
+
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot determine the name"));
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use assert_cmd::prelude::*;
+use predicates::str::contains;
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+#[test]
+fn normalize_check_and_rewrite() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) =
+        run_sysand(["init", "--name", "normalize", "--version", "1.0.0"], None)?;
+    out.assert().success();
+
+    // Freshly created metadata is already normalized
+    let out = run_sysand_in(&cwd, ["normalize", "--check"], None)?;
+    out.assert().success();
+
+    std::fs::write(
+        cwd.join(".project.json"),
+        r#"{"version": "1.0.0", "name": "normalize"}"#,
+    )?;
+    let out = run_sysand_in(&cwd, ["normalize", "--check"], None)?;
+    out.assert()
+        .failure()
+        .stderr(contains("SYSAND-E2903"))
+        .stderr(contains("1 file(s) are not normalized"))
+        .stderr(contains(".project.json"));
+
+    let out = run_sysand_in(&cwd, ["normalize"], None)?;
+    out.assert().success().stderr(contains("Normalized"));
+    assert_eq!(
+        std::fs::read_to_string(cwd.join(".project.json"))?,
+        "{\n  \"name\": \"normalize\",\n  \"version\": \"1.0.0\"\n}\n"
+    );
+
+    let out = run_sysand_in(&cwd, ["normalize", "--check"], None)?;
+    out.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn manifest_renders_project_json() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    let cfg_path = cwd.join("sysand.toml");
+    std::fs::write(
+        &cfg_path,
+        r#"
+[manifest]
+name = "lib"
+publisher = "acme"
+version = "1.0.0"
+usage = [{ resource = "urn:kpar:dep", version_constraint = "^2" }]
+"#,
+    )?;
+
+    let out = run_sysand_in(&cwd, ["normalize", "--check"], Some(cfg_path.as_str()))?;
+    out.assert()
+        .failure()
+        .stderr(contains("out of date with `[manifest]`"));
+    assert!(!cwd.join(".project.json").exists());
+
+    let out = run_sysand_in(&cwd, ["normalize"], Some(cfg_path.as_str()))?;
+    out.assert().success().stderr(contains("Rendering"));
+    let info: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(cwd.join(".project.json"))?)?;
+    assert_eq!(info["name"], "lib");
+    assert_eq!(info["usage"][0]["versionConstraint"], "^2");
+    let out = run_sysand_in(&cwd, ["normalize", "--check"], Some(cfg_path.as_str()))?;
+    out.assert().success();
+
+    // Editing `.project.json` by hand after rendering is reported
+    std::fs::write(
+        cwd.join(".project.json"),
+        r#"{"name": "lib", "publisher": "acme", "version": "1.0.1", "usage": []}"#,
+    )?;
+    std::fs::File::options()
+        .write(true)
+        .open(&cfg_path)?
+        .set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(10))?;
+    let out = run_sysand_in(&cwd, ["normalize"], Some(cfg_path.as_str()))?;
+    out.assert()
+        .failure()
+        .stderr(contains("was edited after the `[manifest]`"))
+        .stderr(contains("`usage`, `version`"));
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use assert_cmd::prelude::*;
+use indexmap::IndexMap;
+use mockito::{Matcher, Server};
+use predicates::prelude::*;
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+fn bearer_env_for_url(url: &str) -> IndexMap<String, String> {
+    IndexMap::from([
+        ("SYSAND_CRED_TEST".to_string(), format!("{url}/**")),
+        (
+            "SYSAND_CRED_TEST_BEARER_TOKEN".to_string(),
+            "test-token".to_string(),
+        ),
+    ])
+}
+
+fn mock_index_config(server: &mut Server) -> mockito::Mock {
+    let body = format!(r#"{{"api_root":"{}/api/"}}"#, server.url());
+    server
+        .mock("GET", "/sysand-index-config.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(body)
+        .create()
+}
+
+#[test]
+fn owner_list() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new();
+    mock_index_config(&mut server);
+    let list = server
+        .mock("GET", "/api/v1/owners")
+        .match_query(Matcher::UrlEncoded(
+            "iri".into(),
+            "pkg:sysand/acme/lib".into(),
+        ))
+        .match_header("authorization", "Bearer test-token")
+        .with_status(200)
+        .with_body(r#"{"owners":["bob","alice"]}"#)
+        .create();
+
+    let env = bearer_env_for_url(&server.url());
+    let (_temp_dir, _cwd, out) = run_sysand_with(
+        [
+            "owner",
+            "list",
+            "pkg:sysand/acme/lib",
+            "--index",
+            &server.url(),
+        ],
+        None,
+        &env,
+    )?;
+    out.assert().success().stdout("alice\nbob\n");
+    list.assert();
+
+    Ok(())
+}
+
+#[test]
+fn owner_add_not_owner() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new();
+    mock_index_config(&mut server);
+    server
+        .mock("PUT", "/api/v1/owners")
+        .with_status(403)
+        .with_body(r#"{"error":"not an owner","iri":"pkg:sysand/acme/lib","owners":["alice"]}"#)
+        .create();
+
+    let env = bearer_env_for_url(&server.url());
+    let (_temp_dir, _cwd, out) = run_sysand_with(
+        [
+            "owner",
+            "add",
+            "pkg:sysand/acme/lib",
+            "carol",
+            "--index",
+            &server.url(),
+        ],
+        None,
+        &env,
+    )?;
+    out.assert().failure().stderr(predicate::str::contains(
+        "your credentials do not own `pkg:sysand/acme/lib`; ask one of its owners (`alice`)",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn owner_requires_credentials() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new();
+    mock_index_config(&mut server);
+
+    let (_temp_dir, _cwd, out) = run_sysand(
+        [
+            "owner",
+            "remove",
+            "pkg:sysand/acme/lib",
+            "carol",
+            "--index",
+            &server.url(),
+        ],
+        None,
+    )?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("no bearer token credentials"));
+
+    Ok(())
+}
+
+#[test]
+fn owner_list_with_credential_helper() -> Result<(), Box<dyn std::error::Error>> {
+    let mut server = Server::new();
+    mock_index_config(&mut server);
+    let list = server
+        .mock("GET", "/api/v1/owners")
+        .match_query(Matcher::UrlEncoded(
+            "iri".into(),
+            "pkg:sysand/acme/lib".into(),
+        ))
+        .match_header("authorization", "Bearer helper-token")
+        .with_status(200)
+        .with_body(r#"{"owners":["alice"]}"#)
+        .create();
+
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+    let config_path = cwd.join("sysand.toml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "[[index]]\nurl = \"{}\"\ncredential_helper = \"echo helper-token\"\n",
+            server.url()
+        ),
+    )?;
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "owner",
+            "list",
+            "pkg:sysand/acme/lib",
+            "--index",
+            &server.url(),
+        ],
+        Some(config_path.as_str()),
+    )?;
+    out.assert().success().stdout("alice\n");
+    list.assert();
+
+    Ok(())
+}
@@ -418,6 +418,68 @@ fn publish_with_explicit_index_succeeds() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn publish_deprecate_marks_project_without_upload() -> TestResult {
+    let (_temp_dir, cwd) = setup_built_project("test-deprecate")?;
+    let mut server = Server::new();
+    let config_mock = mock_index_config_api_at_api(&mut server);
+    let upload_mock = server.mock("POST", "/api/v1/upload").expect(0).create();
+    let deprecate_mock = server
+        .mock("POST", "/api/v1/deprecate")
+        .match_header("authorization", "Bearer test-token")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(r#""iri":"pkg:sysand/[^"]*/test-deprecate""#.to_string()),
+            Matcher::Regex(r#""replaced_by":"pkg:sysand/acme/next""#.to_string()),
+        ]))
+        .with_status(204)
+        .expect(1)
+        .create();
+
+    let env = bearer_env_for_url(server.url().as_str());
+    let out = run_sysand_in_with(
+        &cwd,
+        [
+            "publish",
+            "--index",
+            server.url().as_str(),
+            "--deprecate",
+            "--replaced-by",
+            "pkg:sysand/acme/next",
+        ],
+        None,
+        &env,
+    )?;
+    out.assert().success().stderr(predicate::str::contains(
+        "replaced by `pkg:sysand/acme/next`",
+    ));
+    deprecate_mock.assert();
+    upload_mock.assert();
+    config_mock.assert();
+
+    Ok(())
+}
+
+#[test]
+fn publish_replaced_by_requires_deprecate() -> TestResult {
+    let (_temp_dir, cwd) = init_project("test-replaced-by")?;
+    let out = run_sysand_in(
+        &cwd,
+        [
+            "publish",
+            "--index",
+            "http://localhost:1",
+            "--replaced-by",
+            "pkg:sysand/acme/next",
+        ],
+        None,
+    )?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("--deprecate"));
+
+    Ok(())
+}
+
 #[test]
 fn publish_explicit_path_outside_project_dir() -> TestResult {
     let (_temp_dir, cwd) = setup_built_project_at("outside-publish", "artifact.kpar")?;
@@ -798,6 +860,21 @@ fn publish_403_maps_to_auth_error() -> TestResult {
     )
 }
 
+#[test]
+fn publish_403_with_permission_names_missing_permission() -> TestResult {
+    assert_publish_error_status(
+        "publish-permission-403",
+        403,
+        r#"{"error":"token is read-only","permission":"publish","scope":"urn:corp:*"}"#,
+        Some("application/json"),
+        &[
+            "publish not permitted",
+            "your credentials lack `publish` permission on `urn:corp:*`",
+            "token is read-only",
+        ],
+    )
+}
+
 #[test]
 fn publish_404_maps_to_not_found_error() -> TestResult {
     assert_publish_error_status(
@@ -830,3 +907,66 @@ fn publish_500_json_error_body_extracts_error_message() -> TestResult {
         &["server error (500)", "Invalid token"],
     )
 }
+
+#[test]
+fn publish_dry_run_checks_without_index() -> TestResult {
+    let (_temp_dir, cwd) = setup_built_project("test-publish")?;
+    let out = run_sysand_in(&cwd, ["publish", "--dry-run"], None)?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("can be published"));
+
+    Ok(())
+}
+
+#[test]
+fn publish_checks_configured_project_iri() -> TestResult {
+    let (_temp_dir, cwd) = setup_built_project("test-publish")?;
+
+    let config_path = cwd.join("sysand.toml");
+    fs::write(&config_path, "iri = \"pkg:sysand/acme/test-publish\"\n")?;
+    let out = run_sysand_in(&cwd, ["publish", "--dry-run"], Some(config_path.as_str()))?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "project IRI `pkg:sysand/acme/test-publish`",
+        ))
+        .stderr(predicate::str::contains("pkg:sysand/untitled/test-publish"));
+
+    fs::write(&config_path, "iri = \"pkg:sysand/untitled/test-publish\"\n")?;
+    let out = run_sysand_in(&cwd, ["publish", "--dry-run"], Some(config_path.as_str()))?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("can be published"));
+
+    Ok(())
+}
+
+#[test]
+fn publish_policy_violations_abort_before_network() -> TestResult {
+    let (_temp_dir, cwd) = setup_built_project("test-publish")?;
+
+    let config_path = cwd.join("publish-test.toml");
+    std::fs::write(
+        &config_path,
+        "[publish]\nrequire_readme = true\nmax_archive_size = 1\n\n\
+         [[publish.metadata_check]]\nfield = \"name\"\npattern = \"mycorp-.*\"\n",
+    )?;
+
+    // Unreachable index: the policy must fail before any request is made
+    let out = run_sysand_in(
+        &cwd,
+        ["publish", "--index", "http://127.0.0.1:1"],
+        Some(config_path.as_str()),
+    )?;
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("violates the publish policy"))
+        .stderr(predicate::str::contains("require_readme"))
+        .stderr(predicate::str::contains("max_archive_size"))
+        .stderr(predicate::str::contains(
+            "`name` value `test-publish` does not match",
+        ));
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use assert_cmd::prelude::*;
+use predicates::str::contains;
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+#[test]
+fn schema_of_each_format() -> Result<(), Box<dyn std::error::Error>> {
+    for (kind, title) in [
+        ("project", ".project.json"),
+        ("meta", ".meta.json"),
+        ("lock", "sysand lockfile"),
+        ("config", "sysand.toml"),
+    ] {
+        let (_temp_dir, _cwd, out) = run_sysand(["schema", kind], None)?;
+        let output = out.assert().success().get_output().stdout.clone();
+        let schema: serde_json::Value = serde_json::from_slice(&output)?;
+        assert_eq!(schema["title"], title);
+        assert_eq!(
+            schema["$schema"],
+            "https://json-schema.org/draft/2020-12/schema"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn schema_to_file() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(["schema", "config", "--output", "config.json"], None)?;
+    out.assert().success().stdout("");
+    let schema = std::fs::read_to_string(cwd.join("config.json"))?;
+    assert!(schema.contains("\"post_build\""));
+
+    let out = run_sysand_in(&cwd, ["schema", "manifest"], None)?;
+    out.assert()
+        .failure()
+        .stderr(contains("invalid value 'manifest'"));
+    Ok(())
+}
@@ -74,6 +74,32 @@ editable = true
     Ok(())
 }
 
+#[cfg(unix)]
+#[test]
+fn sync_runs_post_sync_hooks() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) =
+        run_sysand(["init", "--version", "1.2.3", "--name", "sync_hooks"], None)?;
+    out.assert().success();
+    fs::write(cwd.join("test.sysml"), b"package P;\n")?;
+    let out = run_sysand_in(&cwd, ["include", "test.sysml"], None)?;
+    out.assert().success();
+
+    let config_path = cwd.join("hooks.toml");
+    fs::write(
+        &config_path,
+        "[[hooks.post_sync]]\ncommand = \"sh\"\n\
+         args = [\"-c\", \"echo \\\"$SYSAND_SOURCES\\\" > hook.txt\"]\n",
+    )?;
+    let out = run_sysand_in(&cwd, ["sync"], Some(config_path.as_str()))?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("`post_sync` hook `sh`"));
+    let recorded = fs::read_to_string(cwd.join("hook.txt"))?;
+    assert!(recorded.trim_end().ends_with("test.sysml"));
+
+    Ok(())
+}
+
 #[test]
 fn repeated_sync_keeps_lockfile_and_env_toml_stable() -> Result<(), Box<dyn std::error::Error>> {
     let (_temp_dir, cwd, out) = run_sysand(
@@ -244,7 +270,7 @@ fn sync_to_remote() -> Result<(), Box<dyn std::error::Error>> {
         .with_status(200)
         .with_header("content-type", "application/json")
         .with_body(r#"{"index":{},"created":"0000-00-00T00:00:00.123456789Z"}"#)
-        .expect(2) // TODO: Reduce this to 1 after caching
+        .expect(1)
         .match_request(|r| r.has_header(header::USER_AGENT))
         .create();
 
@@ -348,7 +374,7 @@ fn sync_to_remote_auth() -> Result<(), Box<dyn std::error::Error>> {
         .with_status(404)
         .with_header("content-type", "application/json")
         .with_body(r#"{"index":{},"created":"0000-00-00T00:00:00.123456789Z"}"#)
-        .expect(2) // TODO: Reduce this to 1
+        .expect(1)
         .create();
 
     let meta_mock_auth = server
@@ -360,7 +386,7 @@ fn sync_to_remote_auth() -> Result<(), Box<dyn std::error::Error>> {
         .with_status(200)
         .with_header("content-type", "application/json")
         .with_body(r#"{"index":{},"created":"0000-00-00T00:00:00.123456789Z"}"#)
-        .expect(2) // TODO: Reduce this to 1
+        .expect(1)
         .create();
 
     fs::write(
@@ -635,3 +661,297 @@ fn sync_env_toml_with_editable_and_non_editable() -> Result<(), Box<dyn std::err
 
     Ok(())
 }
+
+#[test]
+fn sync_plan_and_apply() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+
+    let proj_dir = cwd.join("lib").join("sync_plan");
+    fs::create_dir_all(&proj_dir)?;
+    fs::write(
+        proj_dir.join(".project.json"),
+        r#"{
+  "name": "sync_plan",
+  "version": "1.2.3"
+}
+"#,
+    )?;
+    fs::write(
+        proj_dir.join(".meta.json"),
+        r#"{
+  "index": {},
+  "created": "2025-06-12T10:48:55.597880Z"
+}
+"#,
+    )?;
+    let lock = r#"lock_version = "0.5"
+
+[[project]]
+name = "sync_plan"
+version = "1.2.3"
+identifiers = ["urn:kpar:sync_plan"]
+sources = [
+    { src_path = "lib/sync_plan", checksum = "ca5c759031a5d8cfb7f8218710b766ecad99f13a317ccd89daff8b6dd2cc847e" },
+]
+"#;
+    fs::write(cwd.join(DEFAULT_LOCKFILE_NAME), lock)?;
+
+    let out = run_sysand_in(&cwd, ["sync", "--plan"], None)?;
+    let plan_path = cwd.join("plan.json");
+    fs::write(&plan_path, &out.stdout)?;
+
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains(r#""action": "install""#))
+        .stdout(predicate::str::contains(r#""urn:kpar:sync_plan""#))
+        .stderr(predicate::str::contains(
+            "Install `urn:kpar:sync_plan` 1.2.3",
+        ));
+    assert!(!cwd.join(DEFAULT_ENV_NAME).exists());
+
+    let out = run_sysand_in(&cwd, ["sync", "--apply", plan_path.as_str()], None)?;
+
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("Installing"));
+
+    let out = run_sysand_in(&cwd, ["env", "list"], None)?;
+
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains("`urn:kpar:sync_plan` 1.2.3"));
+
+    // Applying the plan again does not reinstall anything
+    let out = run_sysand_in(&cwd, ["sync", "--apply", plan_path.as_str()], None)?;
+
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("Installing").not());
+
+    let out = run_sysand_in(&cwd, ["sync", "--plan"], None)?;
+
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains(r#""actions": []"#))
+        .stderr(predicate::str::contains("env is already up to date"));
+
+    fs::write(
+        cwd.join(DEFAULT_LOCKFILE_NAME),
+        lock.replace("1.2.3", "1.2.4"),
+    )?;
+
+    let out = run_sysand_in(&cwd, ["sync", "--apply", plan_path.as_str()], None)?;
+
+    out.assert()
+        .failure()
+        .stderr(predicate::str::contains("was not created from the current"));
+
+    Ok(())
+}
+
+#[test]
+fn sync_to_install_location() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+
+    let proj_dir = cwd.join("lib").join("sync_plan");
+    fs::create_dir_all(&proj_dir)?;
+    fs::write(
+        proj_dir.join(".project.json"),
+        r#"{
+  "name": "sync_plan",
+  "version": "1.2.3"
+}
+"#,
+    )?;
+    fs::write(
+        proj_dir.join(".meta.json"),
+        r#"{
+  "index": {},
+  "created": "2025-06-12T10:48:55.597880Z"
+}
+"#,
+    )?;
+    fs::write(
+        cwd.join(DEFAULT_LOCKFILE_NAME),
+        r#"lock_version = "0.5"
+
+[[project]]
+name = "sync_plan"
+version = "1.2.3"
+identifiers = ["urn:kpar:sync_plan"]
+sources = [
+    { src_path = "lib/sync_plan", checksum = "ca5c759031a5d8cfb7f8218710b766ecad99f13a317ccd89daff8b6dd2cc847e" },
+]
+"#,
+    )?;
+    let config_path = cwd.join("sysand.toml");
+    fs::write(
+        &config_path,
+        r#"[[install_location]]
+iri = "urn:kpar:sync_plan"
+path = "tool/libs/sync_plan"
+"#,
+    )?;
+    let location = cwd.join("tool/libs/sync_plan");
+
+    let out = run_sysand_in(&cwd, ["sync"], Some(config_path.as_str()))?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("also at"));
+    assert!(location.join(".project.json").is_file());
+
+    // A missing copy is restored by the next sync
+    fs::remove_dir_all(&location)?;
+    let out = run_sysand_in(&cwd, ["sync"], Some(config_path.as_str()))?;
+    out.assert().success();
+    assert!(location.join(".project.json").is_file());
+
+    let out = run_sysand_in(&cwd, ["sync"], Some(config_path.as_str()))?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("env is already up to date"));
+
+    Ok(())
+}
+
+#[test]
+fn sync_only_and_skip() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+
+    let mut lock = r#"lock_version = "0.5"
+"#
+    .to_string();
+    for name in ["geometry", "other"] {
+        let proj_dir = cwd.join("lib").join(name);
+        fs::create_dir_all(&proj_dir)?;
+        fs::write(
+            proj_dir.join(".project.json"),
+            "{\n  \"name\": \"sync_to_local\",\n  \"version\": \"1.2.3\"\n}\n",
+        )?;
+        fs::write(
+            proj_dir.join(".meta.json"),
+            "{\n  \"index\": {},\n  \"created\": \"2025-06-12T10:48:55.597880Z\"\n}\n",
+        )?;
+        lock.push_str(&format!(
+            r#"
+[[project]]
+name = "{name}"
+version = "1.2.3"
+identifiers = ["urn:kpar:{name}"]
+sources = [
+    {{ src_path = "lib/{name}", checksum = "fd4566669f12f969e04aa970d27659057357d078d00530d442de21d517959f92" }},
+]
+"#
+        ));
+    }
+    fs::write(cwd.join(DEFAULT_LOCKFILE_NAME), lock)?;
+
+    let out = run_sysand_in(&cwd, ["sync", "--only", "urn:kpar:missing"], None)?;
+    out.assert().failure().stderr(predicate::str::contains(
+        "no project with IRI `urn:kpar:missing`",
+    ));
+
+    let out = run_sysand_in(&cwd, ["sync", "--only", "urn:kpar:geometry"], None)?;
+    out.assert().success();
+    let out = run_sysand_in(&cwd, ["env", "list"], None)?;
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains("urn:kpar:geometry"))
+        .stdout(predicate::str::contains("urn:kpar:other").not());
+
+    let out = run_sysand_in(&cwd, ["sync", "--skip", "urn:kpar:geometry"], None)?;
+    out.assert()
+        .success()
+        .stderr(predicate::str::contains("env is already up to date").not());
+    let out = run_sysand_in(&cwd, ["env", "list"], None)?;
+    out.assert()
+        .success()
+        .stdout(predicate::str::contains("urn:kpar:geometry"))
+        .stdout(predicate::str::contains("urn:kpar:other"));
+
+    Ok(())
+}
+
+#[test]
+fn sync_uses_read_only_system_env() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd) = new_temp_cwd()?;
+
+    let proj_dir = cwd.join("lib").join("sync_plan");
+    fs::create_dir_all(&proj_dir)?;
+    fs::write(
+        proj_dir.join(".project.json"),
+        r#"{
+  "name": "sync_plan",
+  "version": "1.2.3"
+}
+"#,
+    )?;
+    fs::write(
+        proj_dir.join(".meta.json"),
+        r#"{
+  "index": {},
+  "created": "2025-06-12T10:48:55.597880Z"
+}
+"#,
+    )?;
+    fs::write(
+        cwd.join(DEFAULT_LOCKFILE_NAME),
+        r#"lock_version = "0.5"
+
+[[project]]
+name = "sync_plan"
+version = "1.2.3"
+identifiers = ["urn:kpar:sync_plan"]
+sources = [
+    { src_path = "lib/sync_plan", checksum = "ca5c759031a5d8cfb7f8218710b766ecad99f13a317ccd89daff8b6dd2cc847e" },
+]
+"#,
+    )?;
+
+    run_sysand_in(&cwd, ["sync"], None)?.assert().success();
+    run_sysand_in(&cwd, ["env", "read-only"], None)?
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("read-only"));
+    let env_toml = fs::read_to_string(cwd.join(DEFAULT_ENV_NAME).join(METADATA_PATH))?;
+    assert!(env_toml.contains("read_only = true"));
+
+    run_sysand_in(&cwd, ["env", "uninstall", "urn:kpar:sync_plan"], None)?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is read-only"));
+    // Nothing to write, so syncing a read-only env that is up to date works
+    run_sysand_in(&cwd, ["sync"], None)?.assert().success();
+
+    // The read-only env becomes a system env, and the local env is
+    // created empty
+    fs::rename(cwd.join(DEFAULT_ENV_NAME), cwd.join("system"))?;
+    let config_path = cwd.join("sysand.toml");
+    fs::write(&config_path, "system_envs = [\"system\"]\n")?;
+    run_sysand_in(&cwd, ["sync"], Some(config_path.as_str()))?
+        .assert()
+        .success();
+    let env_toml = fs::read_to_string(cwd.join(DEFAULT_ENV_NAME).join(METADATA_PATH))?;
+    assert!(!env_toml.contains("sync_plan"));
+
+    // Without the system env, the project is missing from the local env,
+    // which cannot be written once marked read-only
+    run_sysand_in(&cwd, ["env", "read-only"], None)?
+        .assert()
+        .success();
+    run_sysand_in(&cwd, ["sync"], None)?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "is read-only, but `urn:kpar:sync_plan` 1.2.3 is not installed as locked",
+        ));
+    run_sysand_in(&cwd, ["env", "read-only", "--off"], None)?
+        .assert()
+        .success();
+    run_sysand_in(&cwd, ["sync"], None)?.assert().success();
+    let env_toml = fs::read_to_string(cwd.join(DEFAULT_ENV_NAME).join(METADATA_PATH))?;
+    assert!(env_toml.contains("sync_plan"));
+    assert!(!env_toml.contains("read_only"));
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use assert_cmd::prelude::*;
+use predicates::str::contains;
+use sysand_core::config::{self, ConfigProject, OverrideSource};
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+#[test]
+fn upgrade_check_reports_used_removed_symbols() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) = run_sysand(
+        ["init", "--name", "upgrade_check", "--version", "1.0.0"],
+        None,
+    )?;
+    out.assert().success();
+    std::fs::write(
+        cwd.join("main.sysml"),
+        "package Main {\n    private import Old::*;\n}\n",
+    )?;
+    let out = run_sysand_in(&cwd, ["include", "main.sysml"], None)?;
+    out.assert().success();
+
+    for (dir, version, source) in [
+        ("dep1", "1.0.0", "package Lib;\npackage Old;\n"),
+        ("dep2", "2.0.0", "package Lib;\npackage New;\n"),
+    ] {
+        let out = run_sysand_in(
+            &cwd,
+            ["init", "--name", "dep", "--version", version, dir],
+            None,
+        )?;
+        out.assert().success();
+        std::fs::write(cwd.join(dir).join("lib.sysml"), source)?;
+        let out = run_sysand_in(&cwd.join(dir), ["include", "lib.sysml"], None)?;
+        out.assert().success();
+    }
+    std::fs::write(cwd.join("dep1").join("gone.sysml"), "package Gone;\n")?;
+    let out = run_sysand_in(&cwd.join("dep1"), ["include", "gone.sysml"], None)?;
+    out.assert().success();
+
+    let cfg = toml::to_string(&config::Config {
+        projects: vec![ConfigProject {
+            identifiers: vec!["urn:kpar:dep".to_string()],
+            sources: vec![
+                OverrideSource::LocalSrc {
+                    src_path: "dep1".into(),
+                },
+                OverrideSource::LocalSrc {
+                    src_path: "dep2".into(),
+                },
+            ],
+        }],
+        ..Default::default()
+    })?;
+    let cfg_path = cwd.join(config::local_fs::CONFIG_FILE);
+    std::fs::write(&cfg_path, cfg)?;
+
+    let out = run_sysand_in(&cwd, ["add", "urn:kpar:dep", "^1"], Some(cfg_path.as_str()))?;
+    out.assert().success();
+
+    let out = run_sysand_in(
+        &cwd,
+        ["upgrade-check", "urn:kpar:dep", "2.0.0"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert()
+        .success()
+        .stdout(contains("`urn:kpar:dep` 1.0.0 -> 2.0.0"))
+        .stdout(contains("Old (renamed to `New`?) used in main.sysml"))
+        .stdout(contains("Other removed symbols: Gone"))
+        .stdout(contains("Added symbols: New"));
+
+    let out = run_sysand_in(
+        &cwd,
+        ["upgrade-check", "urn:kpar:dep", "3.0.0"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert().failure().stderr(contains("3.0.0"));
+
+    let out = run_sysand_in(
+        &cwd,
+        ["upgrade-check", "urn:kpar:unlocked", "2.0.0"],
+        Some(cfg_path.as_str()),
+    )?;
+    out.assert()
+        .failure()
+        .stderr(contains("`urn:kpar:unlocked` is not in lockfile"));
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use assert_cmd::prelude::*;
+use predicates::str::contains;
+
+// pub due to https://github.com/rust-lang/rust/issues/46379
+mod common;
+pub use common::*;
+
+#[test]
+fn verify_checks_included_files() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) =
+        run_sysand(["init", "--name", "verify", "--version", "1.0.0"], None)?;
+    out.assert().success();
+    let files: Vec<String> = (0..20).map(|i| format!("p{i}.sysml")).collect();
+    for (i, file) in files.iter().enumerate() {
+        std::fs::write(cwd.join(file), format!("package P{i};\n"))?;
+    }
+    let mut include = vec!["include", "--compute-checksum", "--jobs", "4"];
+    include.extend(files.iter().map(String::as_str));
+    let out = run_sysand_in(&cwd, include, None)?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["verify", "--jobs", "3"], None)?;
+    out.assert()
+        .success()
+        .stderr(contains("Verified"))
+        .stderr(contains("20 file(s)"));
+
+    std::fs::write(cwd.join("p3.sysml"), "package Changed;\n")?;
+    std::fs::write(cwd.join("p12.sysml"), "package Changed;\n")?;
+    let out = run_sysand_in(&cwd, ["verify"], None)?;
+    out.assert()
+        .failure()
+        .stderr(contains("SYSAND-E2303"))
+        .stderr(contains(
+            "contents of 2 file(s) do not match their checksums in `.meta.json`: `p12.sysml`, `p3.sysml`",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn verify_reports_files_without_checksum() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, out) =
+        run_sysand(["init", "--name", "verify", "--version", "1.0.0"], None)?;
+    out.assert().success();
+    std::fs::write(cwd.join("main.sysml"), "package Main;\n")?;
+    let out = run_sysand_in(&cwd, ["include", "main.sysml"], None)?;
+    out.assert().success();
+
+    let out = run_sysand_in(&cwd, ["verify", "--jobs", "1"], None)?;
+    out.assert()
+        .success()
+        .stderr(contains("`main.sysml` has no checksum"))
+        .stderr(contains("0 file(s), 1 without a verifiable checksum"));
+
+    let out = run_sysand_in(&cwd, ["verify", "--jobs", "0"], None)?;
+    out.assert().failure();
+
+    Ok(())
+}
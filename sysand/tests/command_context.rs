@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: © 2026 Sysand contributors <opensource@sensmetry.com>
+
+use camino_tempfile::tempdir;
+use serde_json::json;
+use sysand::{cli::Command, context::CommandContext, run_command};
+use sysand_core::{config::Config, context::ProjectContext, output::CaptureSink};
+
+#[test]
+fn command_runs_with_injected_config_and_output() -> Result<(), Box<dyn std::error::Error>> {
+    let cwd = tempdir()?;
+    let mut config = Config::default();
+    config
+        .reasons
+        .insert("urn:kpar:thermal".into(), "thermal analysis".into());
+    let project = ProjectContext {
+        current_directory: cwd.path().to_owned(),
+        ..Default::default()
+    };
+    let out = CaptureSink::new();
+    let cx = CommandContext::new(config, project)?.with_output(out.clone());
+
+    run_command(
+        Command::Why {
+            iri: "urn:kpar:thermal".into(),
+        },
+        cx,
+    )?;
+
+    assert_eq!(
+        out.human(),
+        "`urn:kpar:thermal`\n  reason: thermal analysis\n"
+    );
+    assert_eq!(
+        out.values(),
+        [json!({
+            "iri": "urn:kpar:thermal",
+            "reason": "thermal analysis",
+            "used_by": [],
+        })]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn print_all_roots_emits_each_project() -> Result<(), Box<dyn std::error::Error>> {
+    let cwd = tempdir()?;
+    for name in ["a", "b"] {
+        std::fs::create_dir(cwd.path().join(name))?;
+        std::fs::write(
+            cwd.path().join(name).join(".project.json"),
+            format!(r#"{{"name":"{name}","version":"1.0.0","usage":[]}}"#),
+        )?;
+    }
+    let project = ProjectContext {
+        current_directory: cwd.path().to_owned(),
+        ..Default::default()
+    };
+    let out = CaptureSink::new();
+    let cx = CommandContext::new(Config::default(), project)?.with_output(out.clone());
+
+    run_command(Command::PrintRoot { all: true }, cx)?;
+
+    assert_eq!(out.values(), [json!("a"), json!("b")]);
+
+    Ok(())
+}
@@ -60,6 +60,28 @@ pub fn fixture_path(name: &str) -> Utf8PathBuf {
     path
 }
 
+/// Write a KPAR at `path` containing one project per name in `names`,
+/// each in a directory of the same name
+pub fn write_multi_project_kpar(path: &Utf8Path, names: &[&str]) -> Result<(), Box<dyn Error>> {
+    use std::io::Write as _;
+
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path)?);
+    let options = zip::write::SimpleFileOptions::default();
+    for name in names {
+        zip.start_file(format!("{name}/.project.json"), options)?;
+        write!(zip, r#"{{"name":"{name}","version":"1.0.0"}}"#)?;
+        zip.start_file(format!("{name}/.meta.json"), options)?;
+        write!(
+            zip,
+            r#"{{"index":{{"{name}":"{name}.sysml"}},"created":"2025-01-01T00:00:00.000000000Z"}}"#
+        )?;
+        zip.start_file(format!("{name}/{name}.sysml"), options)?;
+        write!(zip, "package {name};")?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
 /// Build a single `versions.json` entry fixture.
 pub fn versions_json_entry_body(version: &str, kpar_size: usize, kpar_digest_hex: &str) -> String {
     format!(
@@ -88,6 +110,8 @@ pub fn sysand_cmd_in_with<'a, I: IntoIterator<Item = &'a str>>(
     let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sysand"));
 
     cmd.env("NO_COLOR", "1");
+    // Tests must not share cached HTTP responses, as mock servers reuse URLs
+    cmd.env("SYSAND_NO_CACHE", "true");
     cmd.envs(env);
 
     cmd.args(args);
@@ -28,3 +28,30 @@ fn discover_basic() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn print_all_roots() -> Result<(), Box<dyn std::error::Error>> {
+    let (_temp_dir, cwd, _) = run_sysand(["init", "--version", "1.2.3", "outer"], None)?;
+    std::fs::create_dir_all(cwd.join("outer/models"))?;
+    run_sysand_in(
+        &cwd,
+        ["init", "--version", "1.2.3", "outer/models/inner"],
+        None,
+    )?
+    .assert()
+    .success();
+
+    run_sysand_in(&cwd, ["print-root", "--all"], None)?
+        .assert()
+        .success()
+        .stdout("outer\nouter/models/inner\n");
+
+    run_sysand_in(&cwd, ["print-root"], None)?
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "not inside a project (reached the filesystem root)",
+        ));
+
+    Ok(())
+}